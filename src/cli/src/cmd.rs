@@ -6,12 +6,21 @@ use async_trait::async_trait;
 pub mod add;
 pub use add::AddCmd;
 
+pub mod admin;
+pub use admin::AdminCmd;
+
 pub mod branch;
 pub use branch::BranchCmd;
 
+pub mod bundle;
+pub use bundle::BundleCmd;
+
 pub mod checkout;
 pub use checkout::CheckoutCmd;
 
+pub mod clean;
+pub use clean::CleanCmd;
+
 pub mod clone;
 pub use clone::CloneCmd;
 
@@ -21,15 +30,24 @@ pub use commit_cache::CommitCacheCmd;
 pub mod commit;
 pub use commit::CommitCmd;
 
+pub mod commit_state;
+pub use commit_state::CommitStateCmd;
+
 pub mod config;
 pub use config::ConfigCmd;
 
+pub mod cp;
+pub use cp::CpCmd;
+
 pub mod create_remote;
 pub use create_remote::CreateRemoteCmd;
 
 pub mod db;
 pub use db::DbCmd;
 
+pub mod dedup;
+pub use dedup::DedupCmd;
+
 pub mod delete_remote;
 pub use delete_remote::DeleteRemoteCmd;
 
@@ -42,9 +60,21 @@ pub use diff::DiffCmd;
 pub mod download;
 pub use download::DownloadCmd;
 
+pub mod encrypt;
+pub use encrypt::EncryptCmd;
+
+pub mod export;
+pub use export::ExportCmd;
+
 pub mod fetch;
 pub use fetch::FetchCmd;
 
+pub mod freeze;
+pub use freeze::FreezeCmd;
+
+pub mod import;
+pub use import::ImportCmd;
+
 pub mod info;
 pub use info::InfoCmd;
 
@@ -57,30 +87,59 @@ pub use load::LoadCmd;
 pub mod log;
 pub use log::LogCmd;
 
+pub mod ls;
+pub use ls::LsCmd;
+
 pub mod migrate;
 pub use migrate::MigrateCmd;
 
 pub mod moo;
 pub use moo::MooCmd;
 
+#[cfg(feature = "mount")]
+pub mod mount;
+#[cfg(feature = "mount")]
+pub use mount::MountCmd;
+
 pub mod merge;
 pub use merge::MergeCmd;
 
+pub mod merge_base;
+pub use merge_base::MergeBaseCmd;
+
+pub mod merge_queue;
+pub use merge_queue::MergeQueueCmd;
+
+pub mod mv;
+pub use mv::MvCmd;
+
 pub mod node;
 pub use node::NodeCmd;
 
 pub mod pack;
 pub use pack::PackCmd;
 
+pub mod pin;
+pub use pin::PinCmd;
+
+pub mod pr;
+pub use pr::PrCmd;
+
 pub mod pull;
 pub use pull::PullCmd;
 
 pub mod push;
 pub use push::PushCmd;
 
+pub mod release;
+pub use release::ReleaseCmd;
+
 pub mod remote;
 pub use remote::RemoteCmd;
 
+pub mod repack;
+pub use repack::RepackCmd;
+
 pub mod restore;
 pub use restore::RestoreCmd;
 
@@ -93,9 +152,18 @@ pub use rm::RmCmd;
 pub mod save;
 pub use save::SaveCmd;
 
+pub mod scan;
+pub use scan::ScanCmd;
+
 pub mod schemas;
 pub use schemas::SchemasCmd;
 
+pub mod show;
+pub use show::ShowCmd;
+
+pub mod thaw;
+pub use thaw::ThawCmd;
+
 pub mod tree;
 pub use tree::TreeCmd;
 
@@ -2,6 +2,7 @@ use std::path::PathBuf;
 
 use async_trait::async_trait;
 use clap::{Arg, Command};
+use dialoguer::Confirm;
 use liboxen::error::OxenError;
 
 use liboxen::model::LocalRepository;
@@ -13,6 +14,9 @@ use crate::helpers::check_repo_migration_needed;
 
 pub const ADD: &str = "add";
 
+/// Ask before adding more paths than this from globs
+const MAX_PATHS_WITHOUT_CONFIRM: usize = 10_000;
+
 pub struct AddCmd;
 
 pub fn add_args() -> Command {
@@ -22,8 +26,16 @@ pub fn add_args() -> Command {
         .arg(
             Arg::new("files")
                 .required(true)
+                .help("Files, directories or quoted globs such as 'images/**/*.png'. Globs skip anything in .oxenignore.")
                 .action(clap::ArgAction::Append),
         )
+        .arg(
+            Arg::new("yes")
+                .long("yes")
+                .short('y')
+                .help("Add without prompting when globs match a large number of files.")
+                .action(clap::ArgAction::SetTrue),
+        )
 }
 
 #[async_trait]
@@ -35,13 +47,36 @@ impl RunCmd for AddCmd {
     fn args(&self) -> Command {
         // Setups the CLI args for the command
         add_args()
+            .mut_arg("files", |arg| arg.required_unless_present("update"))
+            .arg(
+                Arg::new("update")
+                    .long("update")
+                    .short('u')
+                    .help("Only stage modified and removed files that are already tracked, optionally limited to the given paths or globs.")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("rows")
+                    .long("rows")
+                    .visible_alias("filter")
+                    .value_name("FILTER")
+                    .conflicts_with("update")
+                    .help("Only stage the rows of a tabular file that match the filter, such as 'split == train'.")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("allow-nested")
+                    .long("allow-nested")
+                    .help("Vendor the files of nested oxen repositories into this one instead of skipping them.")
+                    .action(clap::ArgAction::SetTrue),
+            )
     }
 
     async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
         // Parse Args
         let paths: Vec<PathBuf> = args
             .get_many::<String>("files")
-            .expect("Must supply files")
+            .unwrap_or_default()
             .map(PathBuf::from)
             .collect();
 
@@ -55,8 +90,56 @@ impl RunCmd for AddCmd {
         let repository = LocalRepository::from_current_dir()?;
         check_repo_migration_needed(&repository)?;
 
-        for path in &opts.paths {
-            repositories::add(&repository, path)?;
+        if let Some(filter) = args.get_one::<String>("rows") {
+            let [path] = opts.paths.as_slice() else {
+                return Err(OxenError::basic_str(
+                    "Can only stage rows of one file at a time",
+                ));
+            };
+            let path = std::env::current_dir()?.join(path);
+            repositories::add::add_rows(&repository, &path, filter)?;
+            return Ok(());
+        }
+
+        if args.get_flag("update") {
+            // Paths on the command line are relative to where it is run from
+            let current_dir = std::env::current_dir()?;
+            let paths: Vec<PathBuf> = opts.paths.iter().map(|p| current_dir.join(p)).collect();
+            let staged = repositories::add::add_update(&repository, &paths)?;
+            if staged.is_empty() {
+                println!("No changes to tracked files");
+            }
+            return Ok(());
+        }
+
+        // Expand globs here rather than relying on the shell, then add everything in one pass
+        let paths = repositories::add::expand_paths(&repository, &opts.paths)?;
+        if paths.is_empty() {
+            return Err(OxenError::basic_str(format!(
+                "No files matched {:?}",
+                opts.paths
+            )));
+        }
+
+        if paths.len() > MAX_PATHS_WITHOUT_CONFIRM && !args.get_flag("yes") {
+            match Confirm::new()
+                .with_prompt(format!("Add {} matching paths?", paths.len()))
+                .interact()
+            {
+                Ok(true) => {}
+                Ok(false) => return Ok(()),
+                Err(e) => {
+                    return Err(OxenError::basic_str(format!(
+                        "Error confirming add, pass --yes to skip the prompt: {e}"
+                    )));
+                }
+            }
+        }
+
+        if args.get_flag("allow-nested") {
+            repositories::add::add_all_allow_nested(&repository, &paths)?;
+        } else {
+            repositories::add::add_all(&repository, &paths)?;
         }
 
         Ok(())
@@ -0,0 +1,125 @@
+use std::path::Path;
+
+use async_trait::async_trait;
+use clap::{Arg, Command};
+
+use liboxen::command::migrate::{self, DEFAULT_MIGRATION_WORKERS};
+use liboxen::error::OxenError;
+
+use crate::cmd::RunCmd;
+pub const NAME: &str = "verify-migrations";
+
+pub struct AdminVerifyMigrationsCmd;
+
+#[async_trait]
+impl RunCmd for AdminVerifyMigrationsCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME)
+            .about("Report which migrations each repository still needs, and optionally run them")
+            .arg(
+                Arg::new("PATH")
+                    .help("Directory holding the repositories, or a single repository")
+                    .required(true),
+            )
+            .arg(
+                Arg::new("all")
+                    .long("all")
+                    .short('a')
+                    .help("Check all oxen repositories in this directory")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("run")
+                    .long("run")
+                    .help("Run the needed migrations, oldest first")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("workers")
+                    .long("workers")
+                    .short('w')
+                    .help("Number of repositories to migrate at the same time with --run")
+                    .default_value(DEFAULT_MIGRATION_WORKERS.to_string())
+                    .value_parser(clap::value_parser!(usize))
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("json")
+                    .long("json")
+                    .help("Print the report as JSON")
+                    .conflicts_with("run")
+                    .action(clap::ArgAction::SetTrue),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        // Parse Args
+        let path = Path::new(args.get_one::<String>("PATH").expect("required"));
+        let all = args.get_flag("all");
+        let num_workers = *args.get_one::<usize>("workers").unwrap();
+
+        let statuses = migrate::verify(path, all)?;
+        if args.get_flag("json") {
+            println!("{}", serde_json::to_string_pretty(&statuses)?);
+            return Ok(());
+        }
+
+        let mut num_behind = 0;
+        for status in statuses.iter() {
+            if let Some(error) = &status.error {
+                println!("{}: could not check, {}", status.path.display(), error);
+            } else if status.needed.is_empty() {
+                println!("{}: up to date", status.path.display());
+            } else {
+                let needed: Vec<String> = status
+                    .needed
+                    .iter()
+                    .map(|name| {
+                        if status.in_progress.contains(name) {
+                            format!("{name} (interrupted)")
+                        } else {
+                            name.to_string()
+                        }
+                    })
+                    .collect();
+                println!("{}: needs {}", status.path.display(), needed.join(", "));
+            }
+            if !status.is_up_to_date() {
+                num_behind += 1;
+            }
+        }
+        println!(
+            "{num_behind} of {} repositories need migrations or could not be checked",
+            statuses.len()
+        );
+
+        if !args.get_flag("run") || num_behind == 0 {
+            return Ok(());
+        }
+
+        let mut num_failed = 0;
+        for (name, summary) in migrate::run_needed(&statuses, num_workers)? {
+            println!(
+                "{} migrated {} of {} repositories",
+                name,
+                summary.num_succeeded(),
+                summary.num_repos
+            );
+            for (path, error) in summary.failed.iter() {
+                println!("  failed {}: {}", path.display(), error);
+            }
+            num_failed += summary.failed.len();
+        }
+        if num_failed > 0 {
+            return Err(OxenError::basic_str(format!(
+                "{num_failed} migrations failed"
+            )));
+        }
+        Ok(())
+    }
+}
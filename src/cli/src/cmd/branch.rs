@@ -4,8 +4,9 @@ use colored::Colorize;
 
 use liboxen::api;
 use liboxen::error::OxenError;
-use liboxen::model::LocalRepository;
+use liboxen::model::{CommitState, LocalRepository};
 use liboxen::repositories;
+use std::str::FromStr;
 
 use crate::cmd::RunCmd;
 use crate::helpers::{check_remote_version, check_remote_version_blocking, get_host_from_repo};
@@ -71,6 +72,13 @@ impl RunCmd for BranchCmd {
                     .exclusive(true)
                     .action(clap::ArgAction::SetTrue),
             )
+            .arg(
+                Arg::new("state")
+                    .long("state")
+                    .help("Only list branches whose head commit is in this data quality state")
+                    .value_parser(["approved", "quarantined"])
+                    .action(clap::ArgAction::Set),
+            )
     }
 
     async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
@@ -102,6 +110,9 @@ impl RunCmd for BranchCmd {
             self.rename_current_branch(&repo, name)
         } else if args.get_flag("show-current") {
             self.show_current_branch(&repo)
+        } else if let Some(state) = args.get_one::<String>("state") {
+            let state = CommitState::from_str(state)?;
+            self.list_branches_in_state(&repo, state)
         } else {
             self.list_branches(&repo)
         }
@@ -135,6 +146,19 @@ impl BranchCmd {
         Ok(())
     }
 
+    pub fn list_branches_in_state(
+        &self,
+        repo: &LocalRepository,
+        state: CommitState,
+    ) -> Result<(), OxenError> {
+        let branches = repositories::branches::list(repo)?;
+        let branches = repositories::commits::states::filter_branches(repo, branches, state)?;
+        for branch in branches.iter() {
+            println!("  {}", branch.name)
+        }
+        Ok(())
+    }
+
     pub fn show_current_branch(&self, repo: &LocalRepository) -> Result<(), OxenError> {
         if let Some(current_branch) = repositories::branches::current_branch(repo)? {
             println!("{}", current_branch.name);
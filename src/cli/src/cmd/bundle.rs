@@ -0,0 +1,74 @@
+pub mod create;
+pub use create::BundleCreateCmd;
+
+pub mod pull;
+pub use pull::BundlePullCmd;
+
+pub mod unbundle;
+pub use unbundle::BundleUnbundleCmd;
+
+use async_trait::async_trait;
+use clap::Command;
+
+use liboxen::error::OxenError;
+use std::collections::HashMap;
+
+use crate::cmd::RunCmd;
+pub const NAME: &str = "bundle";
+pub struct BundleCmd;
+
+#[async_trait]
+impl RunCmd for BundleCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        let mut command = Command::new(NAME)
+            .about("Move branches between repositories without a network connection, through a single archive file")
+            .subcommand_required(true)
+            .arg_required_else_help(true);
+
+        // These are all the subcommands for the bundle command
+        // including `create`, `unbundle` and `pull`
+        let sub_commands = self.get_subcommands();
+        for cmd in sub_commands.values() {
+            command = command.subcommand(cmd.args());
+        }
+        command
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let sub_commands = self.get_subcommands();
+        if let Some((name, sub_matches)) = args.subcommand() {
+            let Some(cmd) = sub_commands.get(name) else {
+                eprintln!("Unknown bundle subcommand {name}");
+                return Err(OxenError::basic_str(format!(
+                    "Unknown bundle subcommand {name}"
+                )));
+            };
+
+            // Calling await within an await is making it complain?
+            tokio::task::block_in_place(|| {
+                tokio::runtime::Handle::current().block_on(cmd.run(sub_matches))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+impl BundleCmd {
+    fn get_subcommands(&self) -> HashMap<String, Box<dyn RunCmd>> {
+        let commands: Vec<Box<dyn RunCmd>> = vec![
+            Box::new(BundleCreateCmd),
+            Box::new(BundlePullCmd),
+            Box::new(BundleUnbundleCmd),
+        ];
+        let mut runners: HashMap<String, Box<dyn RunCmd>> = HashMap::new();
+        for cmd in commands {
+            runners.insert(cmd.name().to_string(), cmd);
+        }
+        runners
+    }
+}
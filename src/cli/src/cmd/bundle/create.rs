@@ -0,0 +1,63 @@
+use async_trait::async_trait;
+use clap::{Arg, Command};
+
+use liboxen::constants::DEFAULT_BRANCH_NAME;
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+use crate::helpers::check_repo_migration_needed;
+
+pub const NAME: &str = "create";
+pub struct BundleCreateCmd;
+
+#[async_trait]
+impl RunCmd for BundleCreateCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Pack branches with their history and data files into a bundle file")
+            .arg(
+                Arg::new("file")
+                    .required(true)
+                    .help("Where to write the bundle, ex: dataset.oxenbundle"),
+            )
+            .arg(
+                Arg::new("revisions")
+                    .long("revisions")
+                    .short('r')
+                    .num_args(1..)
+                    .default_value(DEFAULT_BRANCH_NAME)
+                    .help("The branches to bundle"),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let file = args.get_one::<String>("file").expect("Must supply a file");
+        let branches: Vec<String> = args
+            .get_many::<String>("revisions")
+            .unwrap_or_default()
+            .cloned()
+            .collect();
+
+        let repo = LocalRepository::from_current_dir()?;
+        check_repo_migration_needed(&repo)?;
+
+        let manifest = repositories::bundle::create(&repo, file, &branches)?;
+        println!(
+            "Bundled {} commits and {} files ({}) to {}",
+            manifest.commit_ids.len(),
+            manifest.num_files,
+            bytesize::ByteSize::b(manifest.num_bytes),
+            file
+        );
+        for bundle_ref in &manifest.refs {
+            println!("  {} -> {}", bundle_ref.name, bundle_ref.commit_id);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+use async_trait::async_trait;
+use clap::{Arg, Command};
+
+use liboxen::constants::DEFAULT_BRANCH_NAME;
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::cmd::bundle::unbundle::print_result;
+use crate::cmd::RunCmd;
+use crate::helpers::check_repo_migration_needed;
+
+pub const NAME: &str = "pull";
+pub struct BundlePullCmd;
+
+#[async_trait]
+impl RunCmd for BundlePullCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Unpack a bundle, then update and check out one of its branches")
+            .arg(
+                Arg::new("file")
+                    .required(true)
+                    .help("The bundle to pull from"),
+            )
+            .arg(
+                Arg::new("BRANCH")
+                    .help("Branch to check out")
+                    .default_value(DEFAULT_BRANCH_NAME),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let file = args.get_one::<String>("file").expect("Must supply a file");
+        let branch = args
+            .get_one::<String>("BRANCH")
+            .expect("Must supply a branch");
+
+        let repo = LocalRepository::from_current_dir()?;
+        check_repo_migration_needed(&repo)?;
+
+        let result = repositories::bundle::pull(&repo, file, branch).await?;
+        print_result(&result);
+        println!("Checked out {branch}");
+        Ok(())
+    }
+}
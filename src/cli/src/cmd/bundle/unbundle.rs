@@ -0,0 +1,57 @@
+use async_trait::async_trait;
+use clap::{Arg, Command};
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+use liboxen::repositories::bundle::{BundleRefStatus, UnbundleResult};
+
+use crate::cmd::RunCmd;
+use crate::helpers::check_repo_migration_needed;
+
+pub const NAME: &str = "unbundle";
+pub struct BundleUnbundleCmd;
+
+#[async_trait]
+impl RunCmd for BundleUnbundleCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Unpack a bundle and create or fast-forward its branches, without changing the working directory")
+            .arg(Arg::new("file").required(true).help("The bundle to unpack"))
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let file = args.get_one::<String>("file").expect("Must supply a file");
+
+        let repo = LocalRepository::from_current_dir()?;
+        check_repo_migration_needed(&repo)?;
+
+        let result = repositories::bundle::unbundle(&repo, file)?;
+        print_result(&result);
+        Ok(())
+    }
+}
+
+pub fn print_result(result: &UnbundleResult) {
+    println!(
+        "Unbundled {} commits and {} files",
+        result.manifest.commit_ids.len(),
+        result.manifest.num_files
+    );
+    for (bundle_ref, status) in &result.refs {
+        let status = match status {
+            BundleRefStatus::Created => "created",
+            BundleRefStatus::FastForwarded => "fast-forwarded",
+            BundleRefStatus::UpToDate => "up to date",
+            BundleRefStatus::SkippedCheckedOut => "checked out, use `oxen bundle pull` to update",
+        };
+        println!(
+            "  {} -> {} ({})",
+            bundle_ref.name, bundle_ref.commit_id, status
+        );
+    }
+}
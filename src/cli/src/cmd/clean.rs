@@ -0,0 +1,116 @@
+use async_trait::async_trait;
+use clap::{Arg, ArgMatches, Command};
+use dialoguer::Confirm;
+
+use crate::helpers::check_repo_migration_needed;
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+use liboxen::util;
+use std::path::PathBuf;
+
+use crate::cmd::RunCmd;
+pub const NAME: &str = "clean";
+pub struct CleanCmd;
+
+#[async_trait]
+impl RunCmd for CleanCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Removes untracked files and directories from the working directory. Paths in .oxenignore are kept.")
+            .arg(
+                Arg::new("PATH")
+                    .help("Only clean below this path. Defaults to the current directory.")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("dry-run")
+                    .long("dry-run")
+                    .short('n')
+                    .help("List what would be removed without removing anything.")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("force")
+                    .long("force")
+                    .short('f')
+                    .help("Remove without asking for confirmation.")
+                    .conflicts_with("dry-run")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("interactive")
+                    .long("interactive")
+                    .short('i')
+                    .help("Confirm each path before removing it.")
+                    .conflicts_with_all(["dry-run", "force"])
+                    .action(clap::ArgAction::SetTrue),
+            )
+    }
+
+    async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        let repository = LocalRepository::from_current_dir()?;
+        check_repo_migration_needed(&repository)?;
+
+        // The path is relative to where the command is run from
+        let current_dir = std::env::current_dir()?;
+        let path = args
+            .get_one::<String>("PATH")
+            .map(|p| current_dir.join(p))
+            .unwrap_or(current_dir);
+        let path = util::fs::path_relative_to_dir(path, &repository.path)?;
+
+        let untracked = repositories::clean::list_untracked(&repository, &path)?;
+        if untracked.is_empty() {
+            println!("Nothing to clean");
+            return Ok(());
+        }
+
+        if args.get_flag("dry-run") {
+            for path in untracked.iter() {
+                println!("Would remove {}", path.display());
+            }
+            return Ok(());
+        }
+
+        let to_remove: Vec<PathBuf> = if args.get_flag("force") {
+            untracked
+        } else if args.get_flag("interactive") {
+            let mut to_remove = vec![];
+            for path in untracked {
+                if confirm(format!("Remove {}?", path.display()))? {
+                    to_remove.push(path);
+                }
+            }
+            to_remove
+        } else {
+            for path in untracked.iter() {
+                println!("Would remove {}", path.display());
+            }
+            if !confirm(format!("Remove {} untracked paths?", untracked.len()))? {
+                return Ok(());
+            }
+            untracked
+        };
+
+        repositories::clean::remove(&repository, &to_remove)?;
+        for path in to_remove.iter() {
+            println!("Removed {}", path.display());
+        }
+
+        Ok(())
+    }
+}
+
+fn confirm(prompt: String) -> Result<bool, OxenError> {
+    Confirm::new().with_prompt(prompt).interact().map_err(|e| {
+        OxenError::basic_str(format!(
+            "Error confirming clean, pass --force to skip the prompt: {e}"
+        ))
+    })
+}
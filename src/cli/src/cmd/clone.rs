@@ -8,7 +8,9 @@ use liboxen::opts::CloneOpts;
 use liboxen::repositories;
 
 use crate::cmd::RunCmd;
-use crate::helpers::{check_remote_version, check_remote_version_blocking};
+use crate::helpers::{
+    apply_limit_rate, check_remote_version, check_remote_version_blocking, limit_rate_arg,
+};
 
 pub const NAME: &str = "clone";
 pub struct CloneCmd;
@@ -46,6 +48,7 @@ impl RunCmd for CloneCmd {
                     .default_missing_value(DEFAULT_BRANCH_NAME)
                     .action(clap::ArgAction::Set),
             )
+            .arg(limit_rate_arg())
     }
 
     async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
@@ -57,6 +60,7 @@ impl RunCmd for CloneCmd {
             .get_one::<String>("branch")
             .expect("Must supply a branch");
 
+        apply_limit_rate(args)?;
         let dst = std::env::current_dir().expect("Could not get current working directory");
         // Get the name of the repo from the url
         let name = url.split('/').last().unwrap();
@@ -1,8 +1,10 @@
+use std::str::FromStr;
+
 use async_trait::async_trait;
 use clap::{Arg, Command};
 
 use liboxen::error::OxenError;
-use liboxen::model::LocalRepository;
+use liboxen::model::{Commit, LocalRepository, User};
 use liboxen::repositories;
 
 use crate::cmd::RunCmd;
@@ -26,24 +28,76 @@ impl RunCmd for CommitCmd {
                     .help("The message for the commit. Should be descriptive about what changed.")
                     .long("message")
                     .short('m')
-                    .required(true)
+                    .required_unless_present("amend")
                     .action(clap::ArgAction::Set),
             )
+            .arg(
+                Arg::new("co-author")
+                    .long("co-author")
+                    .value_name("NAME <EMAIL>")
+                    .help("Credit another author with a Co-authored-by trailer. Can be given more than once.")
+                    .action(clap::ArgAction::Append),
+            )
+            .arg(
+                Arg::new("allow-empty")
+                    .long("allow-empty")
+                    .help("Create the commit even if nothing is staged, keeping the files of HEAD.")
+                    .conflicts_with("amend")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("amend")
+                    .long("amend")
+                    .help("Replace the HEAD commit with one that has this message and any staged changes. Keeps the message of HEAD if none is given.")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("force")
+                    .long("force")
+                    .short('f')
+                    .help("Amend even if the HEAD commit has already been pushed.")
+                    .requires("amend")
+                    .action(clap::ArgAction::SetTrue),
+            )
     }
 
     async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
         // Parse Args
-        let Some(message) = args.get_one::<String>("message") else {
+        let message = args.get_one::<String>("message");
+        let co_authors = args
+            .get_many::<String>("co-author")
+            .unwrap_or_default()
+            .map(|co_author| User::from_str(co_author))
+            .collect::<Result<Vec<User>, OxenError>>()?;
+
+        let repo = LocalRepository::from_current_dir()?;
+        check_repo_migration_needed(&repo)?;
+
+        if args.get_flag("amend") {
+            let message = match message {
+                Some(message) => message.to_owned(),
+                None => repositories::commits::head_commit(&repo)?.message,
+            };
+            let message = Commit::message_with_co_authors(&message, &co_authors);
+            println!("Amending HEAD with message: {message}");
+            repositories::commits::amend(&repo, &message, args.get_flag("force"))?;
+            return Ok(());
+        }
+
+        let Some(message) = message else {
             return Err(OxenError::basic_str(
                 "Err: Usage `oxen commit -m <message>`",
             ));
         };
 
-        let repo = LocalRepository::from_current_dir()?;
-        check_repo_migration_needed(&repo)?;
+        let message = Commit::message_with_co_authors(message, &co_authors);
 
         println!("Committing with message: {message}");
-        repositories::commit(&repo, message)?;
+        if args.get_flag("allow-empty") {
+            repositories::commits::commit_allow_empty(&repo, &message)?;
+        } else {
+            repositories::commit(&repo, &message)?;
+        }
 
         Ok(())
     }
@@ -0,0 +1,132 @@
+use async_trait::async_trait;
+use clap::{Arg, Command};
+use std::str::FromStr;
+
+use liboxen::api;
+use liboxen::config::UserConfig;
+use liboxen::error::OxenError;
+use liboxen::model::{CommitState, CommitStateEntry, LocalRepository, RemoteRepository};
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+use crate::helpers::{check_remote_version, get_host_from_repo};
+
+pub const NAME: &str = "commit-state";
+
+pub struct CommitStateCmd;
+
+#[async_trait]
+impl RunCmd for CommitStateCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Get or set the data quality state (approved / quarantined) of a commit")
+            .arg(
+                Arg::new("revision")
+                    .help("The commit or branch to annotate. Defaults to HEAD.")
+                    .required(false),
+            )
+            .arg(
+                Arg::new("state")
+                    .long("set")
+                    .short('s')
+                    .help("The state to set on the commit: approved or quarantined")
+                    .value_parser(["approved", "quarantined"])
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("clear")
+                    .long("clear")
+                    .help("Remove the state from the commit")
+                    .conflicts_with("state")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("remote")
+                    .long("remote")
+                    .short('r')
+                    .help("Get or set the state on the remote instead of the local repository")
+                    .action(clap::ArgAction::Set),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+
+        let revision = match args.get_one::<String>("revision") {
+            Some(revision) => revision.to_owned(),
+            None => repositories::commits::head_commit(&repo)?.id,
+        };
+        let state = args
+            .get_one::<String>("state")
+            .map(|s| CommitState::from_str(s))
+            .transpose()?;
+        let clear = args.get_flag("clear");
+
+        let entry = if let Some(remote_name) = args.get_one::<String>("remote") {
+            let remote_repo = self.get_remote_repo(&repo, remote_name).await?;
+            match state {
+                Some(state) => {
+                    let user = UserConfig::get()?.to_user();
+                    Some(
+                        api::client::commits::set_state(&remote_repo, &revision, state, &user)
+                            .await?,
+                    )
+                }
+                None if clear => {
+                    api::client::commits::clear_state(&remote_repo, &revision).await?;
+                    None
+                }
+                None => api::client::commits::get_state(&remote_repo, &revision).await?,
+            }
+        } else {
+            match state {
+                Some(state) => {
+                    let user = UserConfig::get()?.to_user();
+                    Some(repositories::commits::states::set(
+                        &repo, &revision, state, &user,
+                    )?)
+                }
+                None if clear => {
+                    repositories::commits::states::clear(&repo, &revision)?;
+                    None
+                }
+                None => repositories::commits::states::get(&repo, &revision)?,
+            }
+        };
+
+        self.print_entry(&revision, entry.as_ref());
+        Ok(())
+    }
+}
+
+impl CommitStateCmd {
+    async fn get_remote_repo(
+        &self,
+        repo: &LocalRepository,
+        remote_name: &str,
+    ) -> Result<RemoteRepository, OxenError> {
+        let host = get_host_from_repo(repo)?;
+        check_remote_version(host).await?;
+
+        let remote = repo
+            .get_remote(remote_name)
+            .ok_or(OxenError::remote_not_set(remote_name))?;
+        api::client::repositories::get_by_remote(&remote)
+            .await?
+            .ok_or(OxenError::remote_not_found(remote.clone()))
+    }
+
+    fn print_entry(&self, revision: &str, entry: Option<&CommitStateEntry>) {
+        match entry {
+            Some(entry) => println!(
+                "{}\t{}\t{} <{}>\t{}",
+                entry.commit_id, entry.state, entry.author, entry.email, entry.timestamp
+            ),
+            None => println!("{revision}\tnone"),
+        }
+    }
+}
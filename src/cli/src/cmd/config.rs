@@ -1,10 +1,13 @@
 use async_trait::async_trait;
 use clap::{Arg, Command};
 
+use liboxen::api;
 use liboxen::command;
 use liboxen::config::{AuthConfig, UserConfig};
 use liboxen::error::OxenError;
-use liboxen::model::LocalRepository;
+use liboxen::model::{CheckoutMode, LocalRepository};
+use liboxen::view::token::NewToken;
+use std::str::FromStr;
 
 use crate::cmd::RunCmd;
 pub const NAME: &str = "config";
@@ -60,12 +63,105 @@ impl RunCmd for ConfigCmd {
                     .help("Set the authentication token for a specific oxen-server host.")
                     .action(clap::ArgAction::Set),
             )
+            .arg(
+                Arg::new("connect-timeout")
+                    .long("connect-timeout")
+                    .number_of_values(2)
+                    .value_names(["HOST", "SECONDS"])
+                    .help("How long to wait to connect to an oxen-server host, 0 uses the default.")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("read-timeout")
+                    .long("read-timeout")
+                    .number_of_values(2)
+                    .value_names(["HOST", "SECONDS"])
+                    .help("How long to wait on a response from an oxen-server host before giving up, 0 waits as long as it takes.")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("proxy")
+                    .long("proxy")
+                    .number_of_values(2)
+                    .value_names(["HOST", "URL"])
+                    .help("Send requests to an oxen-server host through this proxy, an empty url goes back to the HTTP_PROXY and HTTPS_PROXY env vars.")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("ca-cert")
+                    .long("ca-cert")
+                    .number_of_values(2)
+                    .value_names(["HOST", "PATH"])
+                    .help("Also trust the certificates in this PEM file when connecting to an oxen-server host, for self-signed certificates or TLS intercepting proxies. An empty path removes it.")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("mint-token")
+                    .long("mint-token")
+                    .value_name("HOST")
+                    .help("Create a new token for a host from the one you are authenticated with, ex: for CI jobs. It can only do less than your token.")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("scope")
+                    .long("scope")
+                    .requires("mint-token")
+                    .help("Limit the minted token: read-only, workspace-only, repo:<namespace>/<name>, namespace:<namespace>, or a scope your token has. Can be repeated.")
+                    .action(clap::ArgAction::Append),
+            )
+            .arg(
+                Arg::new("expires-in")
+                    .long("expires-in")
+                    .requires("mint-token")
+                    .help("How long until the minted token expires, ex: 2h or 7days")
+                    .action(clap::ArgAction::Set),
+            )
             .arg(
                 Arg::new("default-host")
                     .long("default-host")
                     .help("Sets the default host used to check version numbers. If empty, the CLI will not do a version check.")
                     .action(clap::ArgAction::Set),
             )
+            .arg(
+                Arg::new("offline-mode")
+                    .long("offline-mode")
+                    .value_name("ENABLED")
+                    .help("Never connect to a remote from this machine, like passing --offline to every command. Useful in network restricted clusters.")
+                    .value_parser(clap::value_parser!(bool))
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("perceptual-hash")
+                    .long("perceptual-hash")
+                    .value_name("ENABLED")
+                    .help("Compute perceptual hashes of images as they are added to the current repository, used by `oxen dedup --fuzzy`.")
+                    .value_parser(clap::value_parser!(bool))
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("content-addressed-tree")
+                    .long("content-addressed-tree")
+                    .value_name("ENABLED")
+                    .help("Hash the dirs of new commits in the current repository from their paths and contents only, so identical trees get identical hashes across commits and forks.")
+                    .value_parser(clap::value_parser!(bool))
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("checkout-mode")
+                    .long("checkout-mode")
+                    .value_name("MODE")
+                    .help("How checkout puts version files into the working dir of the current repository: copy, reflink or hardlink. Hard linked version files are made read only.")
+                    .value_parser(["copy", "reflink", "hardlink"])
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("compression-level")
+                    .long("compression-level")
+                    .value_name("LEVEL")
+                    .help("Compress version files added or pulled into the current repository with zstd at this level, 0 stores them as is. Helps most with text heavy datasets.")
+                    .value_parser(clap::value_parser!(i32))
+                    .action(clap::ArgAction::Set),
+            )
             .arg_required_else_help(true)
     }
 
@@ -102,6 +198,32 @@ impl RunCmd for ConfigCmd {
             }
         }
 
+        for setting in ["connect-timeout", "read-timeout", "proxy", "ca-cert"] {
+            if let Some(values) = args.get_many::<String>(setting) {
+                if let [host, value] = values.collect::<Vec<_>>()[..] {
+                    match self.set_host_setting(host, setting, value) {
+                        Ok(_) => {}
+                        Err(err) => {
+                            eprintln!("{err}")
+                        }
+                    }
+                } else {
+                    eprintln!("invalid arguments for --{setting}");
+                }
+            }
+        }
+
+        if let Some(host) = args.get_one::<String>("mint-token") {
+            let scopes: Vec<String> = args
+                .get_many::<String>("scope")
+                .unwrap_or_default()
+                .cloned()
+                .collect();
+            let expires_in = args.get_one::<String>("expires-in");
+            self.mint_token(host, &scopes, expires_in.map(String::as_str))
+                .await?;
+        }
+
         if let Some(default_host) = args.get_one::<String>("default-host") {
             match self.set_default_host(default_host) {
                 Ok(_) => {}
@@ -111,6 +233,15 @@ impl RunCmd for ConfigCmd {
             }
         }
 
+        if let Some(offline) = args.get_one::<bool>("offline-mode") {
+            match self.set_offline_mode(*offline) {
+                Ok(_) => {}
+                Err(err) => {
+                    eprintln!("{err}")
+                }
+            }
+        }
+
         // Repo Dependent
         if let Some(remote) = args.get_many::<String>("set-remote") {
             let mut repo = LocalRepository::from_current_dir()?;
@@ -136,6 +267,48 @@ impl RunCmd for ConfigCmd {
             }
         }
 
+        if let Some(enabled) = args.get_one::<bool>("perceptual-hash") {
+            let mut repo = LocalRepository::from_current_dir()?;
+            match command::config::set_perceptual_hash(&mut repo, *enabled) {
+                Ok(_) => {}
+                Err(err) => {
+                    eprintln!("{err}")
+                }
+            }
+        }
+
+        if let Some(enabled) = args.get_one::<bool>("content-addressed-tree") {
+            let mut repo = LocalRepository::from_current_dir()?;
+            match command::config::set_content_addressed_tree(&mut repo, *enabled) {
+                Ok(_) => {}
+                Err(err) => {
+                    eprintln!("{err}")
+                }
+            }
+        }
+
+        if let Some(mode) = args.get_one::<String>("checkout-mode") {
+            let mut repo = LocalRepository::from_current_dir()?;
+            let mode = CheckoutMode::from_str(mode)?;
+            match command::config::set_checkout_mode(&mut repo, mode) {
+                Ok(_) => {}
+                Err(err) => {
+                    eprintln!("{err}")
+                }
+            }
+        }
+
+        if let Some(level) = args.get_one::<i32>("compression-level") {
+            let mut repo = LocalRepository::from_current_dir()?;
+            let level = (*level != 0).then_some(*level);
+            match command::config::set_compression_level(&mut repo, level) {
+                Ok(_) => {}
+                Err(err) => {
+                    eprintln!("{err}")
+                }
+            }
+        }
+
         Ok(())
     }
 }
@@ -174,6 +347,112 @@ impl ConfigCmd {
         Ok(())
     }
 
+    /// Set one of the connection settings of a host, an empty value or 0 unsets it
+    pub fn set_host_setting(
+        &self,
+        host: &str,
+        setting: &str,
+        value: &str,
+    ) -> Result<(), OxenError> {
+        let host = Self::strip_host(host)?;
+        let value = value.trim();
+        let parse_secs = |value: &str| -> Result<Option<u64>, OxenError> {
+            let secs = if value.is_empty() {
+                0
+            } else {
+                value.parse::<u64>()?
+            };
+            Ok((secs != 0).then_some(secs))
+        };
+        let text = (!value.is_empty()).then(|| value.to_string());
+
+        let mut config = AuthConfig::get_or_create()?;
+        match setting {
+            "connect-timeout" => {
+                let secs = parse_secs(value)?;
+                config.update_host_config(&host, |c| c.connect_timeout_secs = secs);
+            }
+            "read-timeout" => {
+                let secs = parse_secs(value)?;
+                config.update_host_config(&host, |c| c.read_timeout_secs = secs);
+            }
+            "proxy" => {
+                if let Some(proxy) = &text {
+                    url::Url::parse(proxy)?;
+                }
+                config.update_host_config(&host, |c| c.proxy = text);
+            }
+            "ca-cert" => {
+                let path = match text {
+                    Some(path) => Some(std::fs::canonicalize(&path).map_err(|err| {
+                        OxenError::basic_str(format!("Could not find {path:?}: {err}"))
+                    })?),
+                    None => None,
+                };
+                config.update_host_config(&host, |c| c.ca_cert = path);
+            }
+            _ => {
+                return Err(OxenError::basic_str(format!(
+                    "Unknown host setting {setting:?}"
+                )))
+            }
+        }
+        config.save_default()?;
+        println!("Set {setting} for host: {host}");
+        Ok(())
+    }
+
+    /// Mint a token from the one set for the host and print it, so it can be handed to a
+    /// CI job without touching the local config
+    pub async fn mint_token(
+        &self,
+        host: &str,
+        scopes: &[String],
+        expires_in: Option<&str>,
+    ) -> Result<(), OxenError> {
+        let new_token = Self::parse_new_token(scopes, expires_in)?;
+        let response = api::client::tokens::create(host, &new_token).await?;
+        println!("{}", response.token);
+
+        let mut limits = response.scopes.clone();
+        limits.extend(response.resources.iter().map(|r| format!("on {r}")));
+        if let Some(expires_at) = response.expires_at {
+            let expires_at = time::OffsetDateTime::from_unix_timestamp(expires_at)
+                .map_err(|err| OxenError::basic_str(err.to_string()))?;
+            limits.push(format!("expires {expires_at}"));
+        }
+        if !limits.is_empty() {
+            eprintln!("Token is limited to: {}", limits.join(", "));
+        }
+        Ok(())
+    }
+
+    fn parse_new_token(scopes: &[String], expires_in: Option<&str>) -> Result<NewToken, OxenError> {
+        let mut new_token = NewToken::default();
+        for scope in scopes {
+            if let Some(repo) = scope.strip_prefix("repo:") {
+                if repo.split('/').count() != 2 {
+                    return Err(OxenError::basic_str(format!(
+                        "Invalid scope {scope}, expected repo:<namespace>/<name>"
+                    )));
+                }
+                new_token.resources.push(repo.to_string());
+            } else if let Some(namespace) = scope.strip_prefix("namespace:") {
+                new_token.resources.push(namespace.to_string());
+            } else {
+                new_token.scopes.push(scope.to_string());
+            }
+        }
+        if let Some(expires_in) = expires_in {
+            let duration = humantime::parse_duration(expires_in).map_err(|err| {
+                OxenError::basic_str(format!("Invalid --expires-in {expires_in:?}: {err}"))
+            })?;
+            let now = time::OffsetDateTime::now_utc().unix_timestamp();
+            new_token.expires_at = Some(now + duration.as_secs() as i64);
+        }
+        Ok(new_token)
+    }
+
     pub fn set_default_host(&self, host: &str) -> Result<(), OxenError> {
         let host = Self::strip_host(host)?;
         let mut config = AuthConfig::get_or_create()?;
@@ -187,6 +466,18 @@ impl ConfigCmd {
         Ok(())
     }
 
+    pub fn set_offline_mode(&self, offline: bool) -> Result<(), OxenError> {
+        let mut config = AuthConfig::get_or_create()?;
+        config.offline = offline;
+        config.save_default()?;
+        if offline {
+            println!("Offline mode on, oxen will not connect to any remote");
+        } else {
+            println!("Offline mode off");
+        }
+        Ok(())
+    }
+
     pub fn set_user_name(&self, name: &str) -> Result<(), OxenError> {
         let mut config = UserConfig::get_or_create()?;
         config.name = String::from(name);
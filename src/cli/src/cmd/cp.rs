@@ -0,0 +1,121 @@
+use async_trait::async_trait;
+use clap::{Arg, Command};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use liboxen::api;
+use liboxen::config::UserConfig;
+use liboxen::constants::DEFAULT_BRANCH_NAME;
+use liboxen::error::OxenError;
+use liboxen::model::{OxenUri, RemoteRepository};
+use liboxen::repositories;
+use liboxen::view::copy::CopyRequest;
+
+use crate::cmd::RunCmd;
+use crate::helpers::{check_remote_version, get_host_or_default};
+
+pub const NAME: &str = "cp";
+pub struct CpCmd;
+
+#[async_trait]
+impl RunCmd for CpCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Copy a file or directory out of a remote repository, recording where it came from")
+            .arg(
+                Arg::new("source")
+                    .required(true)
+                    .help("The file or directory to copy, ex: oxen://ox/CatDog@main:images/cat.jpg"),
+            )
+            .arg(
+                Arg::new("destination")
+                    .required(true)
+                    .help("A local path, or a branch and path of another repository on the same host, ex: oxen://ox/Pets@main:cats/cat.jpg"),
+            )
+            .arg(
+                Arg::new("host")
+                    .long("host")
+                    .help("Host the repositories live on, for example: 'hub.oxen.ai'")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("message")
+                    .long("message")
+                    .short('m')
+                    .help("Commit message when copying into another repository")
+                    .action(clap::ArgAction::Set),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let source = args.get_one::<String>("source").unwrap();
+        let destination = args.get_one::<String>("destination").unwrap();
+        if !OxenUri::is_oxen_uri(source) {
+            return Err(OxenError::basic_str(format!(
+                "Source must be an oxen:// uri, got '{source}'"
+            )));
+        }
+        let src = OxenUri::from_str(source)?;
+
+        let host = match args.get_one::<String>("host") {
+            Some(host) => host.to_owned(),
+            None => get_host_or_default()?,
+        };
+        check_remote_version(&host).await?;
+
+        if OxenUri::is_oxen_uri(destination) {
+            let dst = OxenUri::from_str(destination)?;
+            let dst_repo = self.get_remote_repo(&dst, &host).await?;
+            let message = match args.get_one::<String>("message") {
+                Some(message) => message.to_owned(),
+                None => format!("Copy {src}"),
+            };
+            let user = UserConfig::get()?.to_user();
+            let request = CopyRequest {
+                source: src,
+                branch: dst
+                    .revision
+                    .clone()
+                    .unwrap_or(DEFAULT_BRANCH_NAME.to_string()),
+                path: dst.path.to_owned(),
+                message,
+                author: user.name,
+                email: user.email,
+            };
+            let commit = api::client::copy::copy(&dst_repo, &request).await?;
+            println!("Copied into {} in commit {}", dst, commit.id);
+        } else {
+            let src_repo = self.get_remote_repo(&src, &host).await?;
+            let provenance =
+                repositories::copy::copy_to_local(&src_repo, &src, PathBuf::from(destination))
+                    .await?;
+            println!(
+                "Copied {} from commit {} to {}",
+                src,
+                provenance.commit_id,
+                provenance.dest_path.to_string_lossy()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl CpCmd {
+    async fn get_remote_repo(
+        &self,
+        uri: &OxenUri,
+        host: &str,
+    ) -> Result<RemoteRepository, OxenError> {
+        let name = uri.repo_name_with_namespace();
+        api::client::repositories::get_by_name_and_host(&name, host)
+            .await?
+            .ok_or(OxenError::basic_str(format!(
+                "Repository does not exist {name}"
+            )))
+    }
+}
@@ -0,0 +1,132 @@
+use async_trait::async_trait;
+use bytesize::ByteSize;
+use clap::{Arg, Command};
+
+use liboxen::error::OxenError;
+use liboxen::model::{DuplicateGroup, LocalRepository, NearDuplicateGroup};
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+pub const NAME: &str = "dedup";
+pub struct DedupCmd;
+
+#[async_trait]
+impl RunCmd for DedupCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Report groups of files with identical content, or near-identical images with --fuzzy")
+            .arg(
+                Arg::new("revision")
+                    .long("revision")
+                    .help("The commit or branch to search. Defaults to HEAD.")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("all-commits")
+                    .long("all-commits")
+                    .help("Also group identical files across all the commits in the history of the revision.")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("fuzzy")
+                    .long("fuzzy")
+                    .help("Cluster images that look alike using perceptual hashes. Enable hashing at add time with `oxen config --perceptual-hash true`.")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("threshold")
+                    .long("threshold")
+                    .help("Max number of differing bits between perceptual hashes for --fuzzy to link two images.")
+                    .default_value("5")
+                    .value_parser(clap::value_parser!(u32).range(0..=64))
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("json")
+                    .long("json")
+                    .help("If present, will print the duplicate groups as json.")
+                    .action(clap::ArgAction::SetTrue),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+
+        let commit = match args.get_one::<String>("revision") {
+            Some(revision) => repositories::revisions::get(&repo, revision)?
+                .ok_or(OxenError::revision_not_found(revision.as_str().into()))?,
+            None => repositories::commits::head_commit(&repo)?,
+        };
+
+        let commits = if args.get_flag("all-commits") {
+            repositories::commits::list_from(&repo, &commit.id)?
+        } else {
+            vec![commit]
+        };
+
+        if args.get_flag("fuzzy") {
+            let threshold = *args.get_one::<u32>("threshold").unwrap();
+            let groups =
+                repositories::dedup::find_near_duplicate_images(&repo, &commits, threshold)?;
+            if args.get_flag("json") {
+                println!("{}", serde_json::to_string(&groups)?);
+            } else {
+                self.print_near_groups(&groups);
+            }
+            return Ok(());
+        }
+
+        let groups = repositories::dedup::find_duplicates_in_commits(&repo, &commits)?;
+
+        if args.get_flag("json") {
+            println!("{}", serde_json::to_string(&groups)?);
+        } else {
+            self.print_groups(&groups);
+        }
+
+        Ok(())
+    }
+}
+
+impl DedupCmd {
+    fn print_groups(&self, groups: &[DuplicateGroup]) {
+        let mut total_wasted = 0;
+        for group in groups {
+            println!(
+                "{} ({} x {})",
+                group.hash,
+                group.entries.len(),
+                ByteSize::b(group.num_bytes)
+            );
+            for entry in group.entries.iter() {
+                println!("  {}\t{}", entry.commit_id, entry.path.to_string_lossy());
+            }
+            total_wasted += group.wasted_bytes();
+        }
+        println!(
+            "Found {} duplicate groups, {} could be saved",
+            groups.len(),
+            ByteSize::b(total_wasted)
+        );
+    }
+
+    fn print_near_groups(&self, groups: &[NearDuplicateGroup]) {
+        for (i, group) in groups.iter().enumerate() {
+            println!("Group {} ({} images)", i + 1, group.entries.len());
+            for entry in group.entries.iter() {
+                println!(
+                    "  {}\t{}\t{}\t{}",
+                    entry.phash,
+                    entry.distance,
+                    entry.commit_id,
+                    entry.path.to_string_lossy()
+                );
+            }
+        }
+        println!("Found {} groups of near-duplicate images", groups.len());
+    }
+}
@@ -1,11 +1,14 @@
 use std::path::PathBuf;
+use std::str::FromStr;
 
 use async_trait::async_trait;
 use clap::{arg, Arg, ArgMatches, Command};
 
 use liboxen::command;
+use liboxen::core::df::sql_export::{ExportTarget, IfExists};
 use liboxen::error::OxenError;
 use liboxen::model::LocalRepository;
+use liboxen::opts::DFExportOpts;
 use liboxen::util::fs;
 
 use crate::cmd::RunCmd;
@@ -21,7 +24,7 @@ impl RunCmd for DFCmd {
     fn args(&self) -> Command {
         // Setups the CLI args for the command
         Command::new(NAME)
-        .about("View and transform data frames. Supported types: csv, tsv, ndjson, jsonl, parquet.")
+        .about("View and transform data frames. Supported types: csv, tsv, ndjson, jsonl, parquet, sqlite, geojson.")
         .arg(arg!(<PATH> ... "The DataFrame you want to process. If in the schema subcommand the schema ref."))
         .arg_required_else_help(true)
         .arg(
@@ -38,6 +41,37 @@ impl RunCmd for DFCmd {
                 .help("Output file to store the transformed data")
                 .action(clap::ArgAction::Set),
         )
+        .arg(
+            Arg::new("export")
+                .long("export")
+                .help("Write the data frame into a table of a database instead of printing it. Takes a postgres:// url or a .sqlite or .duckdb file.")
+                .conflicts_with_all(["write", "output"])
+                .action(clap::ArgAction::Set),
+        )
+        .arg(
+            Arg::new("export-table")
+                .long("export-table")
+                .help("Table to export into. Defaults to the file name without its extension.")
+                .requires("export")
+                .action(clap::ArgAction::Set),
+        )
+        .arg(
+            Arg::new("if-exists")
+                .long("if-exists")
+                .help("What to do if the export table already exists")
+                .value_parser(["fail", "replace", "append"])
+                .default_value("fail")
+                .requires("export")
+                .action(clap::ArgAction::Set),
+        )
+        .arg(
+            Arg::new("batch-size")
+                .long("batch-size")
+                .help("Number of rows inserted per transaction when exporting")
+                .value_parser(clap::value_parser!(usize))
+                .requires("export")
+                .action(clap::ArgAction::Set),
+        )
         .arg(
             Arg::new("full")
                 .long("full")
@@ -52,6 +86,18 @@ impl RunCmd for DFCmd {
                 .help("The delimiter to use when reading the file. Default is ','")
                 .action(clap::ArgAction::Set),
         )
+        .arg(
+            Arg::new("bbox")
+                .long("bbox")
+                .help("Keep rows whose geometry intersects a bounding box, for GeoJSON and GeoParquet. Format: 'min_x,min_y,max_x,max_y'")
+                .action(clap::ArgAction::Set),
+        )
+        .arg(
+            Arg::new("table")
+                .long("table")
+                .help("The table to read from a SQLite database. Required if the database has more than one table.")
+                .action(clap::ArgAction::Set),
+        )
         .arg(
             Arg::new("filter")
                 .long("filter")
@@ -216,7 +262,17 @@ impl RunCmd for DFCmd {
             return Err(OxenError::basic_str("Must supply a DataFrame to process."));
         };
 
-        if let Some(revision) = args.get_one::<String>("revision") {
+        if let Some(target) = args.get_one::<String>("export") {
+            let export_opts = DFCmd::parse_export_args(target, args)?;
+            let num_rows = match args.get_one::<String>("revision") {
+                Some(revision) => {
+                    let repo = LocalRepository::from_current_dir()?;
+                    command::df::export_revision(&repo, path, revision, &export_opts, opts)?
+                }
+                None => command::df::export(path, &export_opts, opts)?,
+            };
+            println!("Exported {num_rows} rows");
+        } else if let Some(revision) = args.get_one::<String>("revision") {
             let repo = LocalRepository::from_current_dir()?;
             command::df::df_revision(&repo, path, revision, opts)?;
         } else if args.get_flag("schema") || args.get_flag("schema-flat") {
@@ -232,6 +288,16 @@ impl RunCmd for DFCmd {
 }
 
 impl DFCmd {
+    fn parse_export_args(target: &str, args: &ArgMatches) -> Result<DFExportOpts, OxenError> {
+        let mut export_opts = DFExportOpts::new(ExportTarget::from_str(target)?);
+        export_opts.table = args.get_one::<String>("export-table").cloned();
+        export_opts.if_exists = IfExists::from_str(args.get_one::<String>("if-exists").unwrap())?;
+        if let Some(batch_size) = args.get_one::<usize>("batch-size") {
+            export_opts.batch_size = *batch_size;
+        }
+        Ok(export_opts)
+    }
+
     pub fn parse_df_args(args: &ArgMatches) -> liboxen::opts::DFOpts {
         let vstack: Option<Vec<PathBuf>> = if let Some(vstack) = args.get_many::<String>("vstack") {
             let values: Vec<PathBuf> = vstack.map(std::path::PathBuf::from).collect();
@@ -290,9 +356,11 @@ impl DFCmd {
             at: args
                 .get_one::<String>("at")
                 .map(|x| x.parse::<usize>().expect("at must be valid int")),
+            bbox: args.get_one::<String>("bbox").map(String::from),
             delete_row: args.get_one::<String>("delete-row").map(String::from),
             sort_by: args.get_one::<String>("sort").map(String::from),
             sql: args.get_one::<String>("sql").map(String::from),
+            table: args.get_one::<String>("table").map(String::from),
             text2sql: args.get_one::<String>("text2sql").map(String::from),
             host: args.get_one::<String>("host").map(String::from),
             unique: args.get_one::<String>("unique").map(String::from),
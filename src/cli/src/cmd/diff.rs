@@ -1,14 +1,15 @@
 use async_trait::async_trait;
-use clap::{Arg, Command};
+use clap::{Arg, ArgGroup, Command};
 use colored::ColoredString;
 use colored::Colorize;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use liboxen::core::df::pretty_print;
 use liboxen::core::df::tabular;
 use liboxen::error::OxenError;
-use liboxen::model::diff::tabular_diff::TabularDiffMods;
-use liboxen::model::diff::{ChangeType, DiffResult, TextDiff};
+use liboxen::model::diff::tabular_diff::{TabularCellChange, TabularDiffMods};
+use liboxen::model::diff::{ChangeType, DiffResult, DriftReport, ImageDiff, TextDiff};
+use liboxen::model::LocalRepository;
 use liboxen::opts::DiffOpts;
 use liboxen::repositories;
 use liboxen::util;
@@ -28,8 +29,8 @@ impl RunCmd for DiffCmd {
         Command::new(NAME)
             .about("Compare two files against each other or against versions. The two resource paramaters can be specified by filepath or `file:revision` syntax.")
             .arg(Arg::new("RESOURCE1")
-                .required(true)
-                .help("First resource, in format `file` or `file:revision`")
+                .required_unless_present("summary")
+                .help("First resource, in format `file` or `file:revision`. With --summary, the directory to summarize, defaults to the repository root.")
                 .index(1)
             )
             .arg(Arg::new("RESOURCE2")
@@ -56,9 +57,41 @@ impl RunCmd for DiffCmd {
                 .short('o')
                 .help("Output directory path to write the results of the comparison. Will write both match.csv (rows with same keys and compares) and diff.csv (rows with different compares between files.")
                 .action(clap::ArgAction::Set))
+            .arg(Arg::new("stats")
+                .long("stats")
+                .help("Compare the column distributions of RESOURCE1 between two revisions instead of its rows, reporting mean/std shifts, null rate changes, category frequency deltas and PSI/KL drift metrics.")
+                .requires("revisions")
+                .conflicts_with_all(["RESOURCE2", "keys", "compares", "output"])
+                .action(clap::ArgAction::SetTrue))
+            .arg(Arg::new("summary")
+                .long("summary")
+                .help("Summarize the changes between two revisions per directory, reporting added/removed/modified file counts and byte deltas without diffing any files.")
+                .requires("revisions")
+                .conflicts_with_all(["RESOURCE2", "keys", "compares", "output"])
+                .action(clap::ArgAction::SetTrue))
+            .group(ArgGroup::new("report").args(["stats", "summary"]))
+            .arg(Arg::new("revisions")
+                .long("revisions")
+                .short('r')
+                .help("Revisions to compare with --stats or --summary, in format `rev1..rev2`. A single revision is compared with HEAD.")
+                .requires("report")
+                .action(clap::ArgAction::Set))
+            .arg(Arg::new("json")
+                .long("json")
+                .help("If present, will print the --stats or --summary report as json.")
+                .requires("report")
+                .action(clap::ArgAction::SetTrue))
     }
 
     async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        if args.get_flag("stats") {
+            return DiffCmd::run_drift_report(args);
+        }
+
+        if args.get_flag("summary") {
+            return DiffCmd::run_dir_summary(args);
+        }
+
         // Parse Args
         let opts = DiffCmd::parse_args(args);
 
@@ -98,6 +131,136 @@ impl RunCmd for DiffCmd {
 }
 
 impl DiffCmd {
+    fn run_drift_report(args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+        let path = PathBuf::from(args.get_one::<String>("RESOURCE1").expect("required"));
+        let (base, head) = DiffCmd::parse_revisions(&repo, args)?;
+
+        let report = repositories::diffs::drift::drift_report(&repo, &path, &base, &head)?;
+        if args.get_flag("json") {
+            println!("{}", serde_json::to_string(&report)?);
+        } else {
+            DiffCmd::print_drift_report(&report)?;
+        }
+        Ok(())
+    }
+
+    fn run_dir_summary(args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+        let dir = args
+            .get_one::<String>("RESOURCE1")
+            .map(PathBuf::from)
+            .unwrap_or_default();
+        let (base, head) = DiffCmd::parse_revisions(&repo, args)?;
+        let base_commit = repositories::revisions::get(&repo, &base)?
+            .ok_or(OxenError::revision_not_found(base.as_str().into()))?;
+        let head_commit = repositories::revisions::get(&repo, &head)?
+            .ok_or(OxenError::revision_not_found(head.as_str().into()))?;
+
+        let diffs =
+            repositories::diffs::list_dir_size_diffs(&repo, &dir, &base_commit, &head_commit)?;
+        if args.get_flag("json") {
+            println!("{}", serde_json::to_string(&diffs)?);
+            return Ok(());
+        }
+
+        if diffs.is_empty() {
+            println!("No changes between {} and {}", base, head);
+            return Ok(());
+        }
+
+        println!("Changes from {} to {}\n", base, head);
+        for diff in &diffs {
+            let path = if diff.path == Path::new("") {
+                ".".to_string()
+            } else {
+                diff.path.to_string_lossy().to_string()
+            };
+            let counts = &diff.file_counts;
+            println!(
+                "{} ({}) {} {} {} {}",
+                path.bold(),
+                diff.status,
+                format!("+{}", counts.added).green(),
+                format!("-{}", counts.removed).red(),
+                format!("Δ{}", counts.modified).yellow(),
+                DiffCmd::format_byte_delta(diff.byte_delta)
+            );
+            for (data_type, delta) in &diff.data_type_byte_deltas {
+                println!("   {data_type}: {}", DiffCmd::format_byte_delta(*delta));
+            }
+        }
+        Ok(())
+    }
+
+    fn format_byte_delta(delta: i64) -> ColoredString {
+        let size = bytesize::ByteSize::b(delta.unsigned_abs());
+        if delta > 0 {
+            format!("+{size}").green()
+        } else if delta < 0 {
+            format!("-{size}").red()
+        } else {
+            format!("{size}").normal()
+        }
+    }
+
+    // `rev1..rev2`, or a single revision compared with HEAD
+    fn parse_revisions(
+        repo: &LocalRepository,
+        args: &clap::ArgMatches,
+    ) -> Result<(String, String), OxenError> {
+        let revisions = args.get_one::<String>("revisions").expect("required");
+        match revisions.split_once("..") {
+            Some((base, head)) => Ok((base.to_string(), head.to_string())),
+            None => Ok((
+                revisions.to_string(),
+                repositories::commits::head_commit(repo)?.id,
+            )),
+        }
+    }
+
+    fn print_drift_report(report: &DriftReport) -> Result<(), OxenError> {
+        println!(
+            "Drift of {} from {} ({} rows) to {} ({} rows)\n",
+            report.path,
+            report.base_revision,
+            report.base_num_rows,
+            report.head_revision,
+            report.head_num_rows
+        );
+
+        for col in &report.added_columns {
+            println!("{}", format!("   + {col} (added)").green());
+        }
+        for col in &report.removed_columns {
+            println!("{}", format!("   - {col} (removed)").red());
+        }
+
+        println!("{}", pretty_print::df_to_str(&report.to_df()?));
+
+        for column in report
+            .columns
+            .iter()
+            .filter(|c| !c.category_deltas.is_empty())
+        {
+            println!("\nCategory changes in {}:", column.name);
+            for delta in &column.category_deltas {
+                let line = format!(
+                    "   {} {:.4} -> {:.4} ({:+.4})",
+                    delta.value, delta.base_frequency, delta.head_frequency, delta.delta
+                );
+                if delta.delta > 0.0 {
+                    println!("{}", line.green());
+                } else if delta.delta < 0.0 {
+                    println!("{}", line.red());
+                } else {
+                    println!("{line}");
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn parse_args(args: &clap::ArgMatches) -> DiffOpts {
         let resource1 = args.get_one::<String>("RESOURCE1").expect("required");
         let resource2 = args.get_one::<String>("RESOURCE2");
@@ -156,11 +319,15 @@ impl DiffCmd {
                 // println!("{:?}", ct.summary);
                 DiffCmd::print_column_changes(&result.summary.modifications)?;
                 DiffCmd::print_row_changes(&result.summary.modifications)?;
+                DiffCmd::print_cell_changes(&result.parameters.keys, &result.cell_changes()?);
                 println!("{}", pretty_print::df_to_str(&result.contents));
             }
             DiffResult::Text(diff) => {
                 DiffCmd::print_text_diff(diff);
             }
+            DiffResult::Image(diff) => {
+                DiffCmd::print_image_diff(diff);
+            }
         }
 
         Ok(())
@@ -196,6 +363,29 @@ impl DiffCmd {
         Ok(())
     }
 
+    fn print_cell_changes(keys: &[String], changes: &[TabularCellChange]) {
+        if changes.is_empty() {
+            return;
+        }
+
+        println!("Cell changes:");
+        for change in changes {
+            let key = keys
+                .iter()
+                .zip(&change.keys)
+                .map(|(name, value)| format!("{name}={value}"))
+                .collect::<Vec<String>>()
+                .join(",");
+            let old = change.old.as_deref().unwrap_or("null");
+            let new = change.new.as_deref().unwrap_or("null");
+            println!(
+                "{}",
+                format!("   Δ [{key}] {}: {old} -> {new}", change.column).yellow()
+            );
+        }
+        println!();
+    }
+
     // TODO: Truncate to "and x more"
     fn print_column_changes(mods: &TabularDiffMods) -> Result<(), OxenError> {
         let mut outputs: Vec<ColoredString> = vec![];
@@ -230,6 +420,40 @@ impl DiffCmd {
         }
     }
 
+    fn print_image_diff(diff: &ImageDiff) {
+        let stats = &diff.image;
+        if diff.dimensions_changed() {
+            println!(
+                "{}",
+                format!(
+                    "Dimensions: {}x{} -> {}x{}",
+                    stats.base_width, stats.base_height, stats.head_width, stats.head_height
+                )
+                .yellow()
+            );
+        } else {
+            println!("Dimensions: {}x{}", stats.base_width, stats.base_height);
+        }
+
+        if !diff.has_changes() {
+            println!("Images are identical");
+            return;
+        }
+
+        println!(
+            "{}",
+            format!(
+                "   Δ {} of {} pixels ({:.2}%)",
+                stats.num_changed_pixels, stats.num_pixels, stats.percent_changed
+            )
+            .yellow()
+        );
+        println!(
+            "Mean channel delta: {:.2}, max: {}",
+            stats.mean_abs_delta, stats.max_abs_delta
+        );
+    }
+
     pub fn maybe_save_diff_output(
         result: &mut DiffResult,
         output: Option<PathBuf>,
@@ -245,6 +469,18 @@ impl DiffCmd {
             DiffResult::Text(_) => {
                 println!("Saving to disk not supported for text output");
             }
+            DiffResult::Image(diff) => {
+                let Some(composite_path) = &diff.image.composite_path else {
+                    return Ok(());
+                };
+                match output {
+                    Some(file_path) => {
+                        util::fs::copy(composite_path, &file_path)?;
+                        println!("Visual diff saved to {}", file_path.display());
+                    }
+                    None => println!("Visual diff saved to {}", composite_path.display()),
+                }
+            }
         }
 
         Ok(())
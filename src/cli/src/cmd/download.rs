@@ -3,9 +3,12 @@ use clap::{Arg, Command};
 use liboxen::api;
 use liboxen::error::OxenError;
 use std::path::PathBuf;
+use std::str::FromStr;
 
+use liboxen::model::OxenUri;
 use liboxen::opts::DownloadOpts;
 use liboxen::repositories;
+use liboxen::util;
 
 use crate::helpers::check_remote_version_blocking;
 use liboxen::constants::{DEFAULT_HOST, DEFAULT_REMOTE_NAME};
@@ -22,10 +25,11 @@ impl RunCmd for DownloadCmd {
 
     fn args(&self) -> Command {
         Command::new(NAME)
-        .about("Download a specific file from the remote repository")
+        .about("Download files or directories from a remote repository without cloning it")
         .arg(
             Arg::new("paths")
                 .required(true)
+                .help("The repository followed by the paths to download, ex: 'ox/CatDog images/cat_1.jpg'. Can also be a single oxen:// uri, ex: oxen://ox/CatDog@main:images")
                 .action(clap::ArgAction::Append),
         )
         .arg(
@@ -57,12 +61,27 @@ impl RunCmd for DownloadCmd {
 
     async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
         // Parse args
+        let mut paths: Vec<String> = args
+            .get_many::<String>("paths")
+            .expect("Must supply paths")
+            .cloned()
+            .collect();
+        let mut revision = args.get_one::<String>("revision").map(String::from);
+
+        // oxen://namespace/repo@revision:path expands to the repo name and the path
+        if OxenUri::is_oxen_uri(&paths[0]) {
+            let uri = OxenUri::from_str(&paths[0])?;
+            revision = revision.or(uri.revision.clone());
+            let mut expanded = vec![
+                uri.repo_name_with_namespace(),
+                uri.path.to_string_lossy().to_string(),
+            ];
+            expanded.extend(paths.drain(1..));
+            paths = expanded;
+        }
+
         let opts = DownloadOpts {
-            paths: args
-                .get_many::<String>("paths")
-                .expect("Must supply paths")
-                .map(PathBuf::from)
-                .collect(),
+            paths: paths.iter().map(PathBuf::from).collect(),
             dst: args
                 .get_one::<String>("output")
                 .map(PathBuf::from)
@@ -75,30 +94,40 @@ impl RunCmd for DownloadCmd {
                 .get_one::<String>("host")
                 .map(String::from)
                 .unwrap_or(DEFAULT_HOST.to_string()),
-            revision: args.get_one::<String>("revision").map(String::from),
+            revision,
         };
 
         let paths = &opts.paths;
-        if paths.is_empty() {
-            return Err(OxenError::basic_str("Must supply a path to download."));
+        if paths.len() < 2 {
+            return Err(OxenError::basic_str(
+                "Must supply a repository and a path to download, ex: oxen download ox/CatDog images/cat_1.jpg",
+            ));
         }
 
         check_remote_version_blocking(opts.clone().host).await?;
 
         // Check if the first path is a valid remote repo
         let name = paths[0].to_string_lossy();
-        if let Some(remote_repo) =
+        let Some(remote_repo) =
             api::client::repositories::get_by_name_host_and_remote(&name, &opts.host, &opts.remote)
                 .await?
-        {
-            // Download from the remote without having to have a local repo directory
-            let remote_paths = paths[1..].to_vec();
-            let commit_id = opts.remote_commit_id(&remote_repo).await?;
-            for path in remote_paths {
-                repositories::download(&remote_repo, &path, &opts.dst, &commit_id).await?;
-            }
-        } else {
-            eprintln!("Repository does not exist {}", name);
+        else {
+            return Err(OxenError::basic_str(format!(
+                "Repository does not exist {name}"
+            )));
+        };
+
+        // Several paths always land inside the output directory, so make sure it exists
+        let remote_paths = paths[1..].to_vec();
+        if remote_paths.len() > 1 && !opts.dst.exists() {
+            util::fs::create_dir_all(&opts.dst)?;
+        }
+
+        // Download from the remote without having to have a local repo directory
+        let commit_id = opts.remote_commit_id(&remote_repo).await?;
+        for path in remote_paths {
+            repositories::download(&remote_repo, &path, &opts.dst, &commit_id).await?;
+            println!("Downloaded {} to {}", path.display(), opts.dst.display());
         }
 
         Ok(())
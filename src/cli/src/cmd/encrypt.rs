@@ -0,0 +1,64 @@
+use async_trait::async_trait;
+use clap::{Arg, ArgMatches, Command};
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+use liboxen::util::encryption::ENCRYPTION_KEY_ENV;
+
+use crate::cmd::RunCmd;
+
+pub const NAME: &str = "encrypt";
+
+pub struct EncryptCmd;
+
+#[async_trait]
+impl RunCmd for EncryptCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Encrypt the version files of the repository at rest with a new key")
+            .arg(
+                Arg::new("disable")
+                    .long("disable")
+                    .help("Decrypt the version files and stop encrypting new ones")
+                    .conflicts_with("status")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("status")
+                    .long("status")
+                    .help("Show whether the repository is encrypted instead of encrypting it")
+                    .action(clap::ArgAction::SetTrue),
+            )
+    }
+
+    async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        let mut repo = LocalRepository::from_current_dir()?;
+
+        if args.get_flag("status") {
+            let status = repositories::encryption::status(&repo)?;
+            match &status.key_id {
+                Some(key_id) => println!("Encrypted with key {key_id}"),
+                None => println!("Not encrypted"),
+            }
+            println!(
+                "{} encrypted files, {} plain files",
+                status.num_encrypted, status.num_plain
+            );
+        } else if args.get_flag("disable") {
+            let num_decrypted = repositories::encryption::disable(&mut repo)?;
+            println!("Decrypted {num_decrypted} files");
+        } else {
+            let (key_id, key_path) = repositories::encryption::enable(&mut repo)?;
+            println!("Encrypted repository with key {key_id}");
+            println!(
+                "The key was saved to {key_path:?}. Back it up, without it the data cannot be read. Other machines can use it by copying the file or setting {ENCRYPTION_KEY_ENV}."
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,146 @@
+use async_trait::async_trait;
+use bytesize::ByteSize;
+use clap::{Arg, Command};
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+use liboxen::repositories::export::ExportFormat;
+use liboxen::repositories::git::FastExportOpts;
+use liboxen::util;
+
+use crate::cmd::RunCmd;
+use crate::helpers::check_repo_migration_needed;
+
+pub const NAME: &str = "export";
+pub struct ExportCmd;
+
+#[async_trait]
+impl RunCmd for ExportCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Write the files at a revision to a tar, tar.gz or zip archive without checking them out, or its history as a git fast-import stream")
+            .arg(
+                Arg::new("revision")
+                    .required(true)
+                    .help("The branch or commit id to export"),
+            )
+            .arg(
+                Arg::new("output")
+                    .long("output")
+                    .short('o')
+                    .required_unless_present("git-fast-export")
+                    .help("The archive to write, ex: dataset.tar.gz. With --git-fast-export, defaults to stdout.")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("path")
+                    .long("path")
+                    .short('p')
+                    .help("Only export the files under this path")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("format")
+                    .long("format")
+                    .value_parser(["tar", "tar.gz", "tgz", "zip"])
+                    .help("Archive format. Defaults to the extension of the output file.")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("git-fast-export")
+                    .long("git-fast-export")
+                    .help("Write the history of the revision as a stream for `git fast-import`")
+                    .conflicts_with_all(["path", "format"])
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("lfs-threshold")
+                    .long("lfs-threshold")
+                    .requires("git-fast-export")
+                    .help("Write files larger than this as git-LFS pointers, ex: 10MB")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("lfs-objects")
+                    .long("lfs-objects")
+                    .requires("lfs-threshold")
+                    .help("Directory to write the LFS objects to, ex: path/to/git/repo/.git/lfs/objects")
+                    .action(clap::ArgAction::Set),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let revision = args
+            .get_one::<String>("revision")
+            .expect("Must supply a revision");
+        if args.get_flag("git-fast-export") {
+            return git_fast_export(revision, args);
+        }
+        let output = args
+            .get_one::<String>("output")
+            .map(PathBuf::from)
+            .expect("Must supply an output file");
+        let path = args.get_one::<String>("path").map(PathBuf::from);
+        let format = match args.get_one::<String>("format") {
+            Some(format) => ExportFormat::from_str(format)?,
+            None => ExportFormat::from_path(&output)?,
+        };
+
+        let repo = LocalRepository::from_current_dir()?;
+        check_repo_migration_needed(&repo)?;
+
+        let summary =
+            repositories::export::export(&repo, revision, &output, path.as_deref(), format)?;
+        println!(
+            "Exported {} files ({}) at commit {} to {}",
+            summary.num_files,
+            bytesize::ByteSize::b(summary.num_bytes),
+            summary.commit.id,
+            output.display()
+        );
+        Ok(())
+    }
+}
+
+fn git_fast_export(revision: &str, args: &clap::ArgMatches) -> Result<(), OxenError> {
+    let lfs_threshold = args
+        .get_one::<String>("lfs-threshold")
+        .map(|size| {
+            ByteSize::from_str(size)
+                .map(|size| size.as_u64())
+                .map_err(|err| OxenError::basic_str(format!("Invalid --lfs-threshold: {err}")))
+        })
+        .transpose()?;
+    let opts = FastExportOpts {
+        lfs_threshold,
+        lfs_objects_dir: args.get_one::<String>("lfs-objects").map(PathBuf::from),
+    };
+
+    let repo = LocalRepository::from_current_dir()?;
+    check_repo_migration_needed(&repo)?;
+
+    // The stream goes to stdout by default, so the summary goes to stderr
+    let summary = match args.get_one::<String>("output") {
+        Some(output) => {
+            let mut writer = BufWriter::new(util::fs::file_create(output)?);
+            repositories::git::fast_export(&repo, revision, &mut writer, &opts)?
+        }
+        None => {
+            let mut writer = BufWriter::new(std::io::stdout().lock());
+            repositories::git::fast_export(&repo, revision, &mut writer, &opts)?
+        }
+    };
+    eprintln!(
+        "Exported {} commits and {} files ({} as LFS pointers)",
+        summary.num_commits, summary.num_blobs, summary.num_lfs_files
+    );
+    Ok(())
+}
@@ -1,12 +1,12 @@
 use async_trait::async_trait;
-use clap::Command;
+use clap::{Arg, Command};
+use liboxen::command;
 use liboxen::error::OxenError;
 use liboxen::model::LocalRepository;
 
-use liboxen::repositories;
-
 use crate::helpers::{
-    check_remote_version_blocking, check_repo_migration_needed, get_host_from_repo,
+    check_remote_version_blocking, check_repo_migration_needed, get_host_from_remote,
+    get_host_from_repo,
 };
 
 use crate::cmd::RunCmd;
@@ -20,16 +20,39 @@ impl RunCmd for FetchCmd {
     }
 
     fn args(&self) -> Command {
-        Command::new(NAME).about("Download objects and refs from the remote repository")
+        Command::new(NAME)
+            .about("Download objects and refs from the remote repository. Updates the remote-tracking branches like origin/main without changing local branches or the working directory.")
+            .arg(Arg::new("REMOTE").help("Remote to fetch from, defaults to every remote"))
+            .arg(
+                Arg::new("all")
+                    .long("all")
+                    .help("Download the data files of the full history, not just the branch heads")
+                    .action(clap::ArgAction::SetTrue),
+            )
     }
 
-    async fn run(&self, _args: &clap::ArgMatches) -> Result<(), OxenError> {
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
         let repository = LocalRepository::from_current_dir()?;
-        let host = get_host_from_repo(&repository)?;
+        let remote = args.get_one::<String>("REMOTE");
+        let all = args.get_flag("all");
+        let host = match remote {
+            Some(remote) => get_host_from_remote(&repository, remote)?,
+            None => get_host_from_repo(&repository)?,
+        };
 
         check_repo_migration_needed(&repository)?;
         check_remote_version_blocking(host.clone()).await?;
-        repositories::fetch(&repository, false).await?;
+        let fetched = match remote {
+            Some(remote) => command::fetch::fetch_remote(&repository, remote, all).await?,
+            None => command::fetch(&repository, all).await?,
+        };
+
+        if fetched.is_empty() {
+            println!("Remote-tracking branches are up to date");
+        }
+        for branch in fetched {
+            println!("{}\t{}", branch.name, branch.commit_id);
+        }
         Ok(())
     }
 }
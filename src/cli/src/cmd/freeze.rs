@@ -0,0 +1,110 @@
+use async_trait::async_trait;
+use clap::{Arg, ArgMatches, Command};
+
+use liboxen::api;
+use liboxen::error::OxenError;
+use liboxen::model::{FreezeOpts, LocalRepository, RemoteRepository, RepoFreeze};
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+use crate::helpers::{check_remote_version, get_host_from_repo};
+
+pub const NAME: &str = "freeze";
+
+pub struct FreezeCmd;
+
+#[async_trait]
+impl RunCmd for FreezeCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Freeze the repository so it is read only and cheaper to store. Undo with `oxen thaw`.")
+            .arg(
+                Arg::new("recompress")
+                    .long("recompress")
+                    .help("Recompress the version files with zstd at the max level")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("status")
+                    .long("status")
+                    .help("Show whether the repository is frozen instead of freezing it")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("remote")
+                    .long("remote")
+                    .help("Freeze the repository on the remote instead of the local one")
+                    .num_args(0..=1)
+                    .default_missing_value(liboxen::constants::DEFAULT_REMOTE_NAME)
+                    .action(clap::ArgAction::Set),
+            )
+    }
+
+    async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+        let opts = FreezeOpts {
+            recompress: args.get_flag("recompress"),
+        };
+
+        if let Some(remote_name) = args.get_one::<String>("remote") {
+            let remote_repo = get_remote_repo(&repo, remote_name).await?;
+            if args.get_flag("status") {
+                let freeze = api::client::freeze::get(&remote_repo).await?;
+                print_status(freeze.as_ref());
+            } else {
+                let freeze = api::client::freeze::freeze(&remote_repo, &opts).await?;
+                print_freeze(&freeze);
+            }
+            return Ok(());
+        }
+
+        if args.get_flag("status") {
+            let freeze = repositories::freeze::get(&repo)?;
+            print_status(freeze.as_ref());
+        } else {
+            let freeze = repositories::freeze::freeze(&repo, &opts)?;
+            print_freeze(&freeze);
+        }
+        Ok(())
+    }
+}
+
+pub async fn get_remote_repo(
+    repo: &LocalRepository,
+    remote_name: &str,
+) -> Result<RemoteRepository, OxenError> {
+    let host = get_host_from_repo(repo)?;
+    check_remote_version(host).await?;
+
+    let remote = repo
+        .get_remote(remote_name)
+        .ok_or(OxenError::remote_not_set(remote_name))?;
+    api::client::repositories::get_by_remote(&remote)
+        .await?
+        .ok_or(OxenError::remote_not_found(remote.clone()))
+}
+
+fn print_status(freeze: Option<&RepoFreeze>) {
+    match freeze {
+        Some(freeze) => {
+            println!("Repository is frozen since {}", freeze.frozen_at);
+            if freeze.recompressed {
+                println!("Version files are recompressed");
+            }
+        }
+        None => println!("Repository is not frozen"),
+    }
+}
+
+fn print_freeze(freeze: &RepoFreeze) {
+    println!("Repository frozen, run `oxen thaw` to make changes again");
+    println!(
+        "Size: {} -> {}",
+        bytesize::ByteSize::b(freeze.num_bytes_before),
+        bytesize::ByteSize::b(freeze.num_bytes_after)
+    );
+}
@@ -0,0 +1,69 @@
+use async_trait::async_trait;
+use clap::{Arg, Command};
+use std::path::PathBuf;
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::opts::ImportOpts;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+use crate::helpers::check_repo_migration_needed;
+
+pub const NAME: &str = "import";
+pub struct ImportCmd;
+
+#[async_trait]
+impl RunCmd for ImportCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Download a dataset from a url, HuggingFace or Kaggle and commit it")
+            .arg(
+                Arg::new("source")
+                    .required(true)
+                    .help("An http(s) url, hf://datasets/<owner>/<name>[@revision] or kaggle://<owner>/<dataset>"),
+            )
+            .arg(
+                Arg::new("path")
+                    .long("path")
+                    .short('p')
+                    .help("Directory to import into. Defaults to the name of the dataset.")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("message")
+                    .long("message")
+                    .short('m')
+                    .help("The commit message. Defaults to \"Import <source>\".")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("no-extract")
+                    .long("no-extract")
+                    .help("Commit downloaded archives as is instead of extracting them")
+                    .action(clap::ArgAction::SetTrue),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let source = args
+            .get_one::<String>("source")
+            .expect("Must supply a source");
+        let opts = ImportOpts {
+            path: args.get_one::<String>("path").map(PathBuf::from),
+            message: args.get_one::<String>("message").cloned(),
+            no_extract: args.get_flag("no-extract"),
+        };
+
+        let repo = LocalRepository::from_current_dir()?;
+        check_repo_migration_needed(&repo)?;
+
+        let commit = repositories::import::import(&repo, source, &opts).await?;
+        println!("Imported {} in commit {}", source, commit.id);
+        Ok(())
+    }
+}
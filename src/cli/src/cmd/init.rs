@@ -31,6 +31,12 @@ impl RunCmd for InitCmd {
                     .help("The oxen version to use, if you want to test older CLI versions (default: latest)")
                     .action(clap::ArgAction::Set),
             )
+            .arg(
+                Arg::new("allow-nested")
+                    .long("allow-nested")
+                    .help("Initialize the repository even if it is inside of, or contains, another oxen repository")
+                    .action(clap::ArgAction::SetTrue),
+            )
     }
 
     async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
@@ -42,6 +48,7 @@ impl RunCmd for InitCmd {
             .get_one::<String>("oxen-version")
             .map(|s| s.to_string());
         let oxen_version = MinOxenVersion::or_latest(version_str)?;
+        let allow_nested = args.get_flag("allow-nested");
 
         // Make sure the remote version is compatible
         let host = get_host_or_default()?;
@@ -49,7 +56,11 @@ impl RunCmd for InitCmd {
 
         // Initialize the repository
         let directory = dunce::canonicalize(PathBuf::from(&path))?;
-        repositories::init::init_with_version(&directory, oxen_version)?;
+        if allow_nested {
+            repositories::init::init_nested(&directory, oxen_version)?;
+        } else {
+            repositories::init::init_with_version(&directory, oxen_version)?;
+        }
         println!("🐂 repository initialized at: {directory:?}");
         Ok(())
     }
@@ -2,12 +2,18 @@ use async_trait::async_trait;
 use clap::{Arg, ArgMatches, Command};
 use colored::Colorize;
 use minus::Pager;
+use std::collections::HashMap;
 use std::fmt::Write;
-use time::format_description;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use time::{format_description, OffsetDateTime};
 
 use liboxen::error::OxenError;
-use liboxen::model::LocalRepository;
+use liboxen::model::{Commit, CommitState, LocalRepository};
+use liboxen::opts::LogOpts;
 use liboxen::repositories;
+use liboxen::util;
+use std::str::FromStr;
 
 use crate::cmd::RunCmd;
 pub const NAME: &str = "log";
@@ -42,6 +48,48 @@ impl RunCmd for LogCmd {
                     .help("Number of commits to show")
                     .default_value("20"),
             )
+            .arg(
+                Arg::new("path")
+                    .help("Only show commits that changed this file or directory")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("author")
+                    .long("author")
+                    .help("Only show commits whose author or co-author name or email contains this")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("grep")
+                    .long("grep")
+                    .help("Only show commits whose message contains this, ignoring case")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("since")
+                    .long("since")
+                    .help("Only show commits after a date like 2024-06-01 or 2024-06-01T12:00:00Z, or a duration ago like '2weeks'")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("until")
+                    .long("until")
+                    .help("Only show commits before a date or a duration ago, see --since")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("graph")
+                    .long("graph")
+                    .help("Draw the branches and merges of the history next to the commits")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("state")
+                    .long("state")
+                    .help("Only show commits in this data quality state")
+                    .value_parser(["approved", "quarantined"])
+                    .action(clap::ArgAction::Set),
+            )
     }
 
     async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
@@ -53,8 +101,30 @@ impl RunCmd for LogCmd {
             .expect("Must supply number")
             .parse::<usize>()
             .expect("number must be a valid integer.");
-        let revision = args.get_one::<String>("revision").map(String::from);
-        self.log_commits(&repo, revision, num_commits).await?;
+        let opts = LogOpts {
+            revision: args.get_one::<String>("revision").map(String::from),
+            author: args.get_one::<String>("author").map(String::from),
+            since: args
+                .get_one::<String>("since")
+                .map(|s| parse_date(s))
+                .transpose()?,
+            until: args
+                .get_one::<String>("until")
+                .map(|s| parse_date(s))
+                .transpose()?,
+            path: args.get_one::<String>("path").map(PathBuf::from),
+            grep: args.get_one::<String>("grep").map(String::from),
+            max_count: Some(num_commits),
+        };
+        let state = args
+            .get_one::<String>("state")
+            .map(|s| CommitState::from_str(s))
+            .transpose()?;
+        if args.get_flag("graph") {
+            self.log_graph(&repo, opts, state)?;
+        } else {
+            self.log_commits(&repo, opts, state).await?;
+        }
 
         Ok(())
     }
@@ -64,15 +134,10 @@ impl LogCmd {
     pub async fn log_commits(
         &self,
         repo: &LocalRepository,
-        revision: Option<String>,
-        num_commits: usize,
+        opts: LogOpts,
+        state: Option<CommitState>,
     ) -> Result<(), OxenError> {
-        let revision = match revision {
-            Some(revision) => revision,
-            None => repositories::commits::head_commit(repo)?.id,
-        };
-        let commits = repositories::commits::list_from(repo, &revision)?;
-        let commits = commits.iter().take(num_commits);
+        let commits = self.list_commits(repo, opts, state)?;
 
         // Fri, 21 Oct 2022 16:08:39 -0700
         let format = format_description::parse(
@@ -85,11 +150,17 @@ impl LogCmd {
             let commit_id_str = format!("commit {}", commit.id).yellow();
             write_to_pager(&mut output, &format!("{}\n", commit_id_str))?;
             write_to_pager(&mut output, &format!("Author: {}", commit.author))?;
+            for co_author in Commit::parse_co_authors(&commit.message) {
+                write_to_pager(&mut output, &format!("Co-author: {}", co_author.name))?;
+            }
             write_to_pager(
                 &mut output,
                 &format!("Date:   {}\n", commit.timestamp.format(&format).unwrap()),
             )?;
-            write_to_pager(&mut output, &format!("    {}\n", commit.message))?;
+            write_to_pager(
+                &mut output,
+                &format!("    {}\n", commit.message_without_co_authors()),
+            )?;
         }
 
         match minus::page_all(output) {
@@ -100,4 +171,69 @@ impl LogCmd {
         }
         Ok(())
     }
+
+    pub fn log_graph(
+        &self,
+        repo: &LocalRepository,
+        opts: LogOpts,
+        state: Option<CommitState>,
+    ) -> Result<(), OxenError> {
+        let commits = self.list_commits(repo, opts, state)?;
+
+        // Label the commits that branches point at, marking the one checked out
+        let current = repositories::branches::current_branch(repo)?.map(|b| b.name);
+        let mut refs: HashMap<String, Vec<String>> = HashMap::new();
+        for branch in repositories::branches::list(repo)? {
+            let name = if current.as_ref() == Some(&branch.name) {
+                format!("HEAD -> {}", branch.name)
+            } else {
+                branch.name
+            };
+            refs.entry(branch.commit_id).or_default().push(name);
+        }
+
+        let mut output = Pager::new();
+        for line in util::commit_graph::render(&commits, &refs) {
+            write_to_pager(&mut output, &line)?;
+        }
+        match minus::page_all(output) {
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("Error while paging: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    fn list_commits(
+        &self,
+        repo: &LocalRepository,
+        mut opts: LogOpts,
+        state: Option<CommitState>,
+    ) -> Result<Vec<Commit>, OxenError> {
+        // The state lives outside the commits, so filter on it before counting
+        let num_commits = opts.max_count.take().unwrap_or(usize::MAX);
+        let mut commits = repositories::commits::list_with_opts(repo, &opts)?;
+        if let Some(state) = state {
+            commits = repositories::commits::states::filter_commits(repo, commits, state)?;
+        }
+        commits.truncate(num_commits);
+        Ok(commits)
+    }
+}
+
+/// A date, a date and time, or a duration before now
+fn parse_date(value: &str) -> Result<OffsetDateTime, OxenError> {
+    if let Ok(timestamp) = humantime::parse_rfc3339_weak(value) {
+        return Ok(OffsetDateTime::from(timestamp));
+    }
+    if let Ok(timestamp) = humantime::parse_rfc3339_weak(&format!("{value}T00:00:00")) {
+        return Ok(OffsetDateTime::from(timestamp));
+    }
+    match humantime::parse_duration(value.trim_end_matches(" ago")) {
+        Ok(duration) => Ok(OffsetDateTime::from(SystemTime::now() - duration)),
+        Err(_) => Err(OxenError::basic_str(format!(
+            "Invalid date {value:?}, expected a date like 2024-06-01 or a duration like 2weeks"
+        ))),
+    }
 }
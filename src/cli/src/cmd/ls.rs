@@ -0,0 +1,187 @@
+use async_trait::async_trait;
+use bytesize::ByteSize;
+use clap::{Arg, Command};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use liboxen::api;
+use liboxen::constants::{DEFAULT_BRANCH_NAME, DEFAULT_PAGE_NUM, DEFAULT_PAGE_SIZE};
+use liboxen::error::OxenError;
+use liboxen::model::{LocalRepository, MetadataEntry, OxenUri};
+use liboxen::opts::{ListOpts, PaginateOpts};
+use liboxen::repositories;
+use liboxen::view::PaginatedDirEntries;
+
+use crate::cmd::RunCmd;
+use crate::helpers::{check_remote_version, get_host_from_repo, get_host_or_default};
+
+pub const NAME: &str = "ls";
+pub struct LsCmd;
+
+#[async_trait]
+impl RunCmd for LsCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("List the files and directories in a revision, locally or on a remote")
+            .arg(
+                Arg::new("path")
+                    .help("Directory to list. Can also be an oxen:// uri to list a remote repository without cloning it, ex: oxen://ox/CatDog@main:images")
+                    .default_value(""),
+            )
+            .arg(
+                Arg::new("revision")
+                    .long("revision")
+                    .help("The branch or commit id to list. Defaults to the current branch, or main on a remote.")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("remote")
+                    .long("remote")
+                    .help("List the directory on this remote of the current repository instead of the local tree")
+                    .num_args(0..=1)
+                    .default_missing_value("origin")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("host")
+                    .long("host")
+                    .help("Host to list an oxen:// uri from, for example: 'hub.oxen.ai'")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("page")
+                    .long("page")
+                    .short('p')
+                    .help("Page number of entries to show")
+                    .default_value(DEFAULT_PAGE_NUM.to_string())
+                    .value_parser(clap::value_parser!(usize)),
+            )
+            .arg(
+                Arg::new("page-size")
+                    .long("page-size")
+                    .help("Number of entries per page")
+                    .default_value(DEFAULT_PAGE_SIZE.to_string())
+                    .value_parser(clap::value_parser!(usize)),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let path = args.get_one::<String>("path").unwrap();
+        let page_num = *args.get_one::<usize>("page").unwrap();
+        let page_size = *args.get_one::<usize>("page-size").unwrap();
+        let revision = args.get_one::<String>("revision").cloned();
+
+        // Remote repository by uri, does not need a local repository
+        if OxenUri::is_oxen_uri(path) {
+            let uri = OxenUri::from_str(path)?;
+            let host = match args.get_one::<String>("host") {
+                Some(host) => host.to_owned(),
+                None => get_host_or_default()?,
+            };
+            let opts = ListOpts {
+                paths: vec![uri.path.to_owned()],
+                host,
+                remote: String::from("origin"),
+                revision: revision
+                    .or(uri.revision.clone())
+                    .unwrap_or(DEFAULT_BRANCH_NAME.to_string()),
+                page_num,
+                page_size,
+            };
+            check_remote_version(&opts.host).await?;
+            let name = uri.repo_name_with_namespace();
+            let remote_repo = api::client::repositories::get_by_name_and_host(&name, &opts.host)
+                .await?
+                .ok_or(OxenError::basic_str(format!(
+                    "Repository does not exist {name}"
+                )))?;
+            let entries = api::client::dir::list(
+                &remote_repo,
+                &opts.revision,
+                &opts.paths[0],
+                opts.page_num,
+                opts.page_size,
+            )
+            .await?;
+            self.print_entries(&entries);
+            return Ok(());
+        }
+
+        let repo = LocalRepository::from_current_dir()?;
+        let revision = match revision {
+            Some(revision) => revision,
+            None => match repositories::branches::current_branch(&repo)? {
+                Some(branch) => branch.name,
+                None => repositories::commits::head_commit(&repo)?.id,
+            },
+        };
+
+        let entries = if let Some(remote_name) = args.get_one::<String>("remote") {
+            let host = get_host_from_repo(&repo)?;
+            check_remote_version(&host).await?;
+            let remote = repo
+                .get_remote(remote_name)
+                .ok_or(OxenError::remote_not_set(remote_name))?;
+            let remote_repo = api::client::repositories::get_by_remote(&remote)
+                .await?
+                .ok_or(OxenError::remote_not_found(remote.clone()))?;
+            api::client::dir::list(&remote_repo, &revision, path, page_num, page_size).await?
+        } else {
+            let paginate_opts = PaginateOpts {
+                page_num,
+                page_size,
+            };
+            repositories::entries::list_directory(
+                &repo,
+                PathBuf::from(path),
+                &revision,
+                &paginate_opts,
+            )?
+        };
+        self.print_entries(&entries);
+
+        Ok(())
+    }
+}
+
+impl LsCmd {
+    fn print_entries(&self, paginated: &PaginatedDirEntries) {
+        for entry in paginated.entries.iter() {
+            println!("{}", self.format_entry(entry));
+        }
+        if paginated.total_pages > 1 {
+            println!(
+                "\nPage {} of {} ({} entries), see more with --page",
+                paginated.page_number, paginated.total_pages, paginated.total_entries
+            );
+        }
+    }
+
+    fn format_entry(&self, entry: &MetadataEntry) -> String {
+        let last_commit = entry
+            .latest_commit
+            .as_ref()
+            .map(|commit| {
+                let short_id: String = commit.id.chars().take(8).collect();
+                let message = commit.message.lines().next().unwrap_or_default();
+                format!("{short_id} {message}")
+            })
+            .unwrap_or_default();
+        let name = if entry.is_dir {
+            format!("{}/", entry.filename)
+        } else {
+            entry.filename.to_owned()
+        };
+        format!(
+            "{:<10}\t{:>10}\t{}\t{}",
+            entry.data_type.to_string(),
+            ByteSize::b(entry.size).to_string(),
+            name,
+            last_commit
+        )
+    }
+}
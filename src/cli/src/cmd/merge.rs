@@ -33,7 +33,17 @@ impl RunCmd for MergeCmd {
         let repository = LocalRepository::from_current_dir()?;
         check_repo_migration_needed(&repository)?;
 
-        repositories::merge::merge(&repository, branch)?;
+        let commit = repositories::merge::merge(&repository, branch)?;
+        if commit.is_none() {
+            let conflicts = repositories::merge::list_conflicts(&repository)?;
+            if !conflicts.is_empty() {
+                return Err(OxenError::merge_conflict(format!(
+                    "Merge of '{}' has {} conflict(s), resolve them and commit the result",
+                    branch,
+                    conflicts.len()
+                )));
+            }
+        }
         Ok(())
     }
 }
@@ -0,0 +1,57 @@
+use async_trait::async_trait;
+use clap::{Arg, ArgMatches, Command};
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+
+pub const NAME: &str = "merge-base";
+
+pub struct MergeBaseCmd;
+
+#[async_trait]
+impl RunCmd for MergeBaseCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Print the best common ancestor of two revisions, the base a merge between them would use")
+            .arg(
+                Arg::new("REV1")
+                    .help("The first commit or branch")
+                    .required(true)
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("REV2")
+                    .help("The second commit or branch")
+                    .required(true)
+                    .action(clap::ArgAction::Set),
+            )
+    }
+
+    async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+        let rev1 = args.get_one::<String>("REV1").expect("required");
+        let rev2 = args.get_one::<String>("REV2").expect("required");
+
+        let commit_1 = repositories::revisions::get(&repo, rev1)?
+            .ok_or(OxenError::revision_not_found(rev1.as_str().into()))?;
+        let commit_2 = repositories::revisions::get(&repo, rev2)?
+            .ok_or(OxenError::revision_not_found(rev2.as_str().into()))?;
+
+        match repositories::commits::merge_base(&repo, &commit_1, &commit_2)? {
+            Some(base) => {
+                println!("{}", base.id);
+                Ok(())
+            }
+            None => Err(OxenError::basic_str(format!(
+                "No common ancestor between {rev1} and {rev2}"
+            ))),
+        }
+    }
+}
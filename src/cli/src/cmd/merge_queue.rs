@@ -0,0 +1,161 @@
+use async_trait::async_trait;
+use clap::{Arg, ArgMatches, Command};
+
+use liboxen::api;
+use liboxen::config::UserConfig;
+use liboxen::constants::{DEFAULT_BRANCH_NAME, DEFAULT_REMOTE_NAME};
+use liboxen::error::OxenError;
+use liboxen::model::{LocalRepository, MergeQueueEntry, NewMergeQueueEntry, RemoteRepository};
+
+use crate::cmd::RunCmd;
+use crate::helpers::{check_remote_version, get_host_from_repo};
+
+pub const NAME: &str = "merge-queue";
+
+pub struct MergeQueueCmd;
+
+fn remote_arg() -> Arg {
+    Arg::new("remote")
+        .long("remote")
+        .short('r')
+        .help("Remote that hosts the merge queue")
+        .default_value(DEFAULT_REMOTE_NAME)
+        .action(clap::ArgAction::Set)
+}
+
+#[async_trait]
+impl RunCmd for MergeQueueCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Queue branches to be merged one at a time on the remote")
+            .subcommand_required(true)
+            .arg_required_else_help(true)
+            .subcommand(
+                Command::new("add")
+                    .about("Queue a branch to be merged into a base branch")
+                    .arg(Arg::new("head").required(true).help("Branch to merge"))
+                    .arg(
+                        Arg::new("base")
+                            .long("base")
+                            .short('b')
+                            .help("Branch to merge into")
+                            .default_value(DEFAULT_BRANCH_NAME),
+                    )
+                    .arg(
+                        Arg::new("notify-url").long("notify-url").help(
+                            "Url the server will POST the entry to once it is merged or fails",
+                        ),
+                    )
+                    .arg(remote_arg()),
+            )
+            .subcommand(
+                Command::new("list")
+                    .about("List the queued merges")
+                    .arg(
+                        Arg::new("base")
+                            .long("base")
+                            .short('b')
+                            .help("Only list merges into this branch"),
+                    )
+                    .arg(remote_arg()),
+            )
+            .subcommand(
+                Command::new("status")
+                    .about("Show the status of a queued merge")
+                    .arg(Arg::new("id").required(true))
+                    .arg(remote_arg()),
+            )
+            .subcommand(
+                Command::new("cancel")
+                    .about("Remove a merge from the queue if it has not started")
+                    .arg(Arg::new("id").required(true))
+                    .arg(remote_arg()),
+            )
+    }
+
+    async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+        let Some((name, sub_args)) = args.subcommand() else {
+            return Err(OxenError::basic_str("Must supply a merge-queue subcommand"));
+        };
+        let remote_repo = self.get_remote_repo(&repo, sub_args).await?;
+
+        match name {
+            "add" => {
+                let user = UserConfig::get()?.to_user();
+                let new_entry = NewMergeQueueEntry {
+                    base: sub_args.get_one::<String>("base").unwrap().to_owned(),
+                    head: sub_args.get_one::<String>("head").unwrap().to_owned(),
+                    author: user.name,
+                    email: user.email,
+                    notify_url: sub_args.get_one::<String>("notify-url").cloned(),
+                };
+                let entry = api::client::merge_queue::enqueue(&remote_repo, &new_entry).await?;
+                self.print_entry(&entry);
+            }
+            "list" => {
+                let base = sub_args.get_one::<String>("base").map(|s| s.as_str());
+                let entries = api::client::merge_queue::list(&remote_repo, base).await?;
+                for entry in entries.iter() {
+                    self.print_entry(entry);
+                }
+            }
+            "status" => {
+                let id = sub_args.get_one::<String>("id").unwrap();
+                let entry = api::client::merge_queue::get(&remote_repo, id).await?;
+                self.print_entry(&entry);
+                if !entry.conflicts.is_empty() {
+                    println!("\nConflicts:");
+                    for path in entry.conflicts.iter() {
+                        println!("  {}", path.to_string_lossy());
+                    }
+                }
+                if let Some(error) = &entry.error {
+                    println!("\nError: {error}");
+                }
+            }
+            "cancel" => {
+                let id = sub_args.get_one::<String>("id").unwrap();
+                let entry = api::client::merge_queue::cancel(&remote_repo, id).await?;
+                self.print_entry(&entry);
+            }
+            cmd => {
+                return Err(OxenError::basic_str(format!(
+                    "Unknown merge-queue subcommand {cmd}"
+                )))
+            }
+        }
+        Ok(())
+    }
+}
+
+impl MergeQueueCmd {
+    async fn get_remote_repo(
+        &self,
+        repo: &LocalRepository,
+        args: &ArgMatches,
+    ) -> Result<RemoteRepository, OxenError> {
+        let host = get_host_from_repo(repo)?;
+        check_remote_version(host).await?;
+
+        let remote_name = args.get_one::<String>("remote").unwrap();
+        let remote = repo
+            .get_remote(remote_name)
+            .ok_or(OxenError::remote_not_set(remote_name))?;
+        api::client::repositories::get_by_remote(&remote)
+            .await?
+            .ok_or(OxenError::remote_not_found(remote.clone()))
+    }
+
+    fn print_entry(&self, entry: &MergeQueueEntry) {
+        let merge_commit = entry.merge_commit_id.as_deref().unwrap_or("-");
+        println!(
+            "{}\t{} -> {}\t{}\t{}",
+            entry.id, entry.head, entry.base, entry.status, merge_commit
+        );
+    }
+}
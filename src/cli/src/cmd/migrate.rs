@@ -1,48 +1,21 @@
-use std::{collections::HashMap, path::Path};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 use async_trait::async_trait;
 use clap::{Arg, Command};
-use liboxen::{
-    command::migrate::{
-        AddDirectoriesToCacheMigration, CacheDataFrameSizeMigration, CreateMerkleTreesMigration,
-        OptimizeMerkleTreesMigration, PropagateSchemasMigration, UpdateVersionFilesMigration,
-    },
-    error::OxenError,
-    model::LocalRepository,
-};
+use liboxen::{error::OxenError, model::LocalRepository, repositories};
 
 use crate::cmd::RunCmd;
-use liboxen::command::migrate::Migrate;
+use liboxen::command::migrate::{self, Migrate, MigrationSummary, DEFAULT_MIGRATION_WORKERS};
 
 pub const NAME: &str = "migrate";
+const FROM_GIT: &str = "from-git";
 
 fn migrations() -> HashMap<String, Box<dyn Migrate>> {
-    let mut map: HashMap<String, Box<dyn Migrate>> = HashMap::new();
-    map.insert(
-        UpdateVersionFilesMigration.name().to_string(),
-        Box::new(UpdateVersionFilesMigration),
-    );
-    map.insert(
-        PropagateSchemasMigration.name().to_string(),
-        Box::new(PropagateSchemasMigration),
-    );
-    map.insert(
-        CacheDataFrameSizeMigration.name().to_string(),
-        Box::new(CacheDataFrameSizeMigration),
-    );
-    map.insert(
-        CreateMerkleTreesMigration.name().to_string(),
-        Box::new(CreateMerkleTreesMigration),
-    );
-    map.insert(
-        AddDirectoriesToCacheMigration.name().to_string(),
-        Box::new(AddDirectoriesToCacheMigration),
-    );
-    map.insert(
-        OptimizeMerkleTreesMigration.name().to_string(),
-        Box::new(OptimizeMerkleTreesMigration),
-    );
-    map
+    migrate::all_migrations()
+        .into_iter()
+        .map(|migration| (migration.name().to_string(), migration))
+        .collect()
 }
 
 pub fn migrate_args(name: &'static str, desc: &'static str) -> Command {
@@ -60,6 +33,15 @@ pub fn migrate_args(name: &'static str, desc: &'static str) -> Command {
                 .help("Run the migration for all oxen repositories in this directory")
                 .action(clap::ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("workers")
+                .long("workers")
+                .short('w')
+                .help("Number of repositories to migrate at the same time with --all")
+                .default_value(DEFAULT_MIGRATION_WORKERS.to_string())
+                .value_parser(clap::value_parser!(usize))
+                .action(clap::ArgAction::Set),
+        )
 }
 
 pub fn subcommands(name: &'static str, desc: &'static str) -> Command {
@@ -68,7 +50,24 @@ pub fn subcommands(name: &'static str, desc: &'static str) -> Command {
     let mut cmd = Command::new(name).about(desc).subcommand_required(true);
 
     for (_, migration) in migrations {
-        cmd = cmd.subcommand(migrate_args(migration.name(), migration.description()))
+        let mut args = migrate_args(migration.name(), migration.description());
+        if name == "up" {
+            args = args
+                .arg(
+                    Arg::new("resume")
+                        .long("resume")
+                        .help("Continue an interrupted run from its checkpoint instead of starting over")
+                        .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("dry-run")
+                        .long("dry-run")
+                        .help("Report which repositories need the migration and how far interrupted runs got, without changing anything")
+                        .conflicts_with("resume")
+                        .action(clap::ArgAction::SetTrue),
+                );
+        }
+        cmd = cmd.subcommand(args)
     }
 
     cmd
@@ -89,12 +88,32 @@ impl RunCmd for MigrateCmd {
             .subcommand_required(true)
             .subcommand(subcommands("up", "Apply a named migration forward."))
             .subcommand(subcommands("down", "Apply a named migration backward."))
+            .subcommand(
+                Command::new(FROM_GIT)
+                    .about("Replay the history of a git repository into a new oxen repository, resolving git-LFS files")
+                    .arg(
+                        Arg::new("PATH")
+                            .help("Path to the git repository")
+                            .required(true),
+                    )
+                    .arg(
+                        Arg::new("output")
+                            .long("output")
+                            .short('o')
+                            .help("Directory to create the oxen repository in. Defaults to <name>-oxen next to the git repository.")
+                            .action(clap::ArgAction::Set),
+                    ),
+            )
     }
 
     async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
         // Parse Args
         let migrations = migrations();
 
+        if let Some((FROM_GIT, sub_matches)) = args.subcommand() {
+            return from_git(sub_matches);
+        }
+
         if let Some((direction, sub_matches)) = args.subcommand() {
             if let Some((migration, sub_matches)) = sub_matches.subcommand() {
                 let migration = migrations
@@ -107,16 +126,23 @@ impl RunCmd for MigrateCmd {
                 let path = Path::new(path_str);
 
                 let all = sub_matches.get_flag("all");
+                let num_workers = *sub_matches.get_one::<usize>("workers").unwrap();
 
-                if direction == "up" {
-                    let repo = LocalRepository::new(path)?;
-                    if migration.is_needed(&repo)? {
-                        migration.up(path, all)?;
+                if direction == "up" && sub_matches.get_flag("dry-run") {
+                    print_dry_run(migration.as_ref(), path, all)?;
+                } else if direction == "up" {
+                    let resume = sub_matches.get_flag("resume");
+                    // With --all every repository checks for itself
+                    if all || migration.is_needed(&LocalRepository::new(path)?)? {
+                        let summary =
+                            migrate::run_up(migration.as_ref(), path, all, resume, num_workers)?;
+                        report_summary(migration.as_ref(), &summary, all)?;
                     } else {
                         println!("Migration already applied: {}", migration.name());
                     }
                 } else if direction == "down" {
-                    migration.down(path, all)?;
+                    let summary = migrate::run_down(migration.as_ref(), path, all, num_workers)?;
+                    report_summary(migration.as_ref(), &summary, all)?;
                 } else {
                     return Err(OxenError::basic_str(format!(
                         "Unknown direction: {}",
@@ -129,3 +155,107 @@ impl RunCmd for MigrateCmd {
         Ok(())
     }
 }
+
+fn report_summary(
+    migration: &dyn Migrate,
+    summary: &MigrationSummary,
+    all: bool,
+) -> Result<(), OxenError> {
+    if !all {
+        return Ok(());
+    }
+    println!(
+        "{} migrated {} of {} repositories",
+        migration.name(),
+        summary.num_succeeded(),
+        summary.num_repos
+    );
+    for (path, error) in summary.failed.iter() {
+        println!("  failed {}: {}", path.display(), error);
+    }
+    if !summary.failed.is_empty() {
+        return Err(OxenError::basic_str(format!(
+            "{} repositories failed to migrate",
+            summary.failed.len()
+        )));
+    }
+    Ok(())
+}
+
+fn print_dry_run(migration: &dyn Migrate, path: &Path, all: bool) -> Result<(), OxenError> {
+    let reports = migrate::dry_run(migration, path, all)?;
+    let mut num_needed = 0;
+    for report in reports.iter() {
+        let status = if report.in_progress {
+            let total = report
+                .num_steps
+                .map(|n| n.to_string())
+                .unwrap_or("?".to_string());
+            format!(
+                "interrupted after {}/{} steps, continue with --resume",
+                report.num_steps_done, total
+            )
+        } else if report.is_needed {
+            match report.num_steps {
+                Some(num_steps) => format!("needed, {num_steps} steps"),
+                None => "needed".to_string(),
+            }
+        } else {
+            "up to date".to_string()
+        };
+        if report.in_progress || report.is_needed {
+            num_needed += 1;
+        }
+        println!("{}: {}", report.path.display(), status);
+    }
+    println!(
+        "{} would migrate {num_needed} of {} repositories",
+        migration.name(),
+        reports.len()
+    );
+    Ok(())
+}
+
+fn from_git(args: &clap::ArgMatches) -> Result<(), OxenError> {
+    let git_path = Path::new(args.get_one::<String>("PATH").expect("required"));
+    let git_path = dunce::canonicalize(git_path)?;
+    let output = match args.get_one::<String>("output") {
+        Some(output) => PathBuf::from(output),
+        None => {
+            let name = git_path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or("repo".to_string());
+            git_path.with_file_name(format!("{name}-oxen"))
+        }
+    };
+
+    let summary = repositories::git::import(&git_path, &output)?;
+    println!(
+        "Imported {} commits on {} branches into {}",
+        summary.num_commits,
+        summary.branches.len(),
+        output.display()
+    );
+    if summary.num_empty_commits > 0 {
+        println!(
+            "Folded {} git commits without changes into their parents",
+            summary.num_empty_commits
+        );
+    }
+    for branch in &summary.skipped_branches {
+        println!(
+            "Skipped branch {branch}, it has no history in common with the checked out branch"
+        );
+    }
+    if !summary.missing_lfs_files.is_empty() {
+        println!(
+            "{} LFS files were missing their objects and were imported as pointers, run `git lfs fetch --all` first to include them:",
+            summary.missing_lfs_files.len()
+        );
+        for path in &summary.missing_lfs_files {
+            println!("  {}", path.display());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use async_trait::async_trait;
+use clap::{Arg, Command};
+use std::path::PathBuf;
+
+use liboxen::api;
+use liboxen::constants::DEFAULT_REMOTE_NAME;
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+use crate::helpers::{check_remote_version, get_host_from_repo};
+
+pub const NAME: &str = "mount";
+pub struct MountCmd;
+
+#[async_trait]
+impl RunCmd for MountCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Mount a revision as a read only filesystem. Files are read from the local versions or fetched from the remote on demand. Unmount with `umount <mountpoint>`.")
+            .arg(
+                Arg::new("revision")
+                    .required(true)
+                    .help("Branch or commit id to mount"),
+            )
+            .arg(
+                Arg::new("mountpoint")
+                    .required(true)
+                    .help("Empty directory to mount the revision at"),
+            )
+            .arg(
+                Arg::new("remote")
+                    .long("remote")
+                    .help("Remote to fetch files from when they are not downloaded locally")
+                    .default_value(DEFAULT_REMOTE_NAME)
+                    .action(clap::ArgAction::Set),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let revision = args.get_one::<String>("revision").unwrap().to_owned();
+        let mountpoint = PathBuf::from(args.get_one::<String>("mountpoint").unwrap());
+        let remote_name = args.get_one::<String>("remote").unwrap();
+
+        let repo = LocalRepository::from_current_dir()?;
+
+        // Without a remote we can still serve everything in the local version store
+        let remote_repo = match repo.get_remote(remote_name) {
+            Some(remote) => {
+                let host = get_host_from_repo(&repo)?;
+                check_remote_version(host).await?;
+                api::client::repositories::get_by_remote(&remote).await?
+            }
+            None => None,
+        };
+
+        println!(
+            "Mounting {} at {}, press Ctrl+C or run `umount {}` to stop",
+            revision,
+            mountpoint.display(),
+            mountpoint.display()
+        );
+        // FUSE blocks the thread it runs on until the filesystem is unmounted
+        tokio::task::spawn_blocking(move || {
+            repositories::mount::mount(&repo, &revision, &mountpoint, remote_repo)
+        })
+        .await
+        .map_err(|err| OxenError::basic_str(format!("oxen mount failed: {err}")))?
+    }
+}
@@ -0,0 +1,56 @@
+use async_trait::async_trait;
+use clap::{Arg, ArgMatches, Command};
+
+use crate::helpers::check_repo_migration_needed;
+
+use liboxen::command;
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use std::path::PathBuf;
+
+use crate::cmd::RunCmd;
+pub const NAME: &str = "mv";
+pub struct MvCmd;
+
+#[async_trait]
+impl RunCmd for MvCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Moves or renames a file or directory and stages it as a rename")
+            .arg(
+                Arg::new("source")
+                    .required(true)
+                    .help("The file or directory to move"),
+            )
+            .arg(
+                Arg::new("destination")
+                    .required(true)
+                    .help("The new path, or a directory to move the source into"),
+            )
+    }
+
+    async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        let src = args
+            .get_one::<String>("source")
+            .expect("Must supply source");
+        let dst = args
+            .get_one::<String>("destination")
+            .expect("Must supply destination");
+
+        let repository = LocalRepository::from_current_dir()?;
+        check_repo_migration_needed(&repository)?;
+
+        // Paths on the command line are relative to where it is run from
+        let current_dir = std::env::current_dir()?;
+        let src = current_dir.join(PathBuf::from(src));
+        let dst = current_dir.join(PathBuf::from(dst));
+        let moved_to = command::mv(&repository, &src, &dst)?;
+        println!("Moved to {}", moved_to.display());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+pub mod create;
+pub use create::PinCreateCmd;
+
+pub mod verify;
+pub use verify::PinVerifyCmd;
+
+use async_trait::async_trait;
+use clap::Command;
+
+use liboxen::error::OxenError;
+use std::collections::HashMap;
+
+use crate::cmd::RunCmd;
+pub const NAME: &str = "pin";
+pub struct PinCmd;
+
+#[async_trait]
+impl RunCmd for PinCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        let mut command = Command::new(NAME)
+            .about("Pin the exact version of data used, and verify a copy still matches it")
+            .subcommand_required(true)
+            .arg_required_else_help(true);
+
+        // These are all the subcommands for the pin command
+        // including `create` and `verify`
+        let sub_commands = self.get_subcommands();
+        for cmd in sub_commands.values() {
+            command = command.subcommand(cmd.args());
+        }
+        command
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let sub_commands = self.get_subcommands();
+        if let Some((name, sub_matches)) = args.subcommand() {
+            let Some(cmd) = sub_commands.get(name) else {
+                eprintln!("Unknown pin subcommand {name}");
+                return Err(OxenError::basic_str(format!(
+                    "Unknown pin subcommand {name}"
+                )));
+            };
+
+            // Calling await within an await is making it complain?
+            tokio::task::block_in_place(|| {
+                tokio::runtime::Handle::current().block_on(cmd.run(sub_matches))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+impl PinCmd {
+    fn get_subcommands(&self) -> HashMap<String, Box<dyn RunCmd>> {
+        let commands: Vec<Box<dyn RunCmd>> = vec![Box::new(PinCreateCmd), Box::new(PinVerifyCmd)];
+        let mut runners: HashMap<String, Box<dyn RunCmd>> = HashMap::new();
+        for cmd in commands {
+            runners.insert(cmd.name().to_string(), cmd);
+        }
+        runners
+    }
+}
@@ -0,0 +1,96 @@
+use async_trait::async_trait;
+use clap::{Arg, Command};
+use std::path::PathBuf;
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+pub const NAME: &str = "create";
+pub struct PinCreateCmd;
+
+#[async_trait]
+impl RunCmd for PinCreateCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Write a pin file with the hashes of the files at a revision")
+            .arg(Arg::new("name").required(true).help("Name of the pin, ex: my-model-v1"))
+            .arg(
+                Arg::new("spec")
+                    .required(true)
+                    .num_args(1..)
+                    .help("<revision>:<path> to pin, ex: main:train/ main:test/. A bare <revision> pins every file."),
+            )
+            .arg(
+                Arg::new("output")
+                    .long("output")
+                    .short('o')
+                    .help("Where to write the pin file. Defaults to <name>.pin.json")
+                    .action(clap::ArgAction::Set),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let Some(name) = args.get_one::<String>("name") else {
+            return Err(OxenError::basic_str("Must supply a pin name"));
+        };
+        let specs: Vec<&String> = args
+            .get_many::<String>("spec")
+            .unwrap_or_default()
+            .collect();
+        let (revision, paths) = self.parse_specs(&specs)?;
+        let output = args
+            .get_one::<String>("output")
+            .map(PathBuf::from)
+            .unwrap_or(PathBuf::from(format!("{name}.pin.json")));
+
+        let repo = LocalRepository::from_current_dir()?;
+        let pin = repositories::pins::create(&repo, name, &revision, &paths)?;
+        repositories::pins::save(&pin, &output)?;
+
+        println!(
+            "Pinned {} files at commit {} to {}",
+            pin.entries.len(),
+            pin.commit_id,
+            output.to_string_lossy()
+        );
+        Ok(())
+    }
+}
+
+impl PinCreateCmd {
+    /// All specs must share a revision, since a pin captures a single version
+    fn parse_specs(&self, specs: &[&String]) -> Result<(String, Vec<PathBuf>), OxenError> {
+        let mut revision: Option<&str> = None;
+        let mut paths = vec![];
+        for spec in specs {
+            let (rev, path) = match spec.split_once(':') {
+                Some((rev, path)) => (rev, Some(path)),
+                None => (spec.as_str(), None),
+            };
+
+            if revision.is_some_and(|r| r != rev) {
+                return Err(OxenError::basic_str(format!(
+                    "All paths in a pin must be from the same revision, got '{}' and '{}'",
+                    revision.unwrap(),
+                    rev
+                )));
+            }
+            revision = Some(rev);
+
+            if let Some(path) = path.filter(|p| !p.is_empty()) {
+                paths.push(PathBuf::from(path.trim_end_matches('/')));
+            }
+        }
+
+        match revision {
+            Some(revision) => Ok((revision.to_string(), paths)),
+            None => Err(OxenError::basic_str("Must supply <revision>:<path> to pin")),
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use async_trait::async_trait;
+use clap::{Arg, Command};
+use colored::Colorize;
+use std::path::PathBuf;
+
+use liboxen::api;
+use liboxen::error::OxenError;
+use liboxen::model::{PinMismatch, Remote};
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+pub const NAME: &str = "verify";
+pub struct PinVerifyCmd;
+
+#[async_trait]
+impl RunCmd for PinVerifyCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Verify that a local copy or the remote still matches a pin file")
+            .arg(Arg::new("pin").required(true).help("Path to the pin file"))
+            .arg(
+                Arg::new("dir")
+                    .long("dir")
+                    .short('d')
+                    .help("Local directory that holds the copy of the data. Defaults to the current directory.")
+                    .conflicts_with("remote")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("remote")
+                    .long("remote")
+                    .help("Verify against the remote repository recorded in the pin instead of a local copy")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("revision")
+                    .long("revision")
+                    .requires("remote")
+                    .help("Revision to check on the remote. Defaults to the pinned commit.")
+                    .action(clap::ArgAction::Set),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let Some(pin_path) = args.get_one::<String>("pin") else {
+            return Err(OxenError::basic_str("Must supply a pin file"));
+        };
+        let pin = repositories::pins::load(pin_path)?;
+
+        let mismatches = if args.get_flag("remote") {
+            let Some(url) = &pin.remote else {
+                return Err(OxenError::basic_str(
+                    "Pin was created in a repository without a remote",
+                ));
+            };
+            let remote = Remote {
+                name: String::from("pin"),
+                url: url.to_owned(),
+            };
+            let remote_repo = api::client::repositories::get_by_remote(&remote)
+                .await?
+                .ok_or(OxenError::remote_not_found(remote.clone()))?;
+            let revision = args.get_one::<String>("revision").unwrap_or(&pin.commit_id);
+            repositories::pins::verify_remote(&remote_repo, &pin, revision).await?
+        } else {
+            let dir = args
+                .get_one::<String>("dir")
+                .map(PathBuf::from)
+                .unwrap_or(PathBuf::from("."));
+            repositories::pins::verify_local(&pin, dir)?
+        };
+
+        self.report(&pin.name, pin.entries.len(), &mismatches)
+    }
+}
+
+impl PinVerifyCmd {
+    fn report(
+        &self,
+        name: &str,
+        num_entries: usize,
+        mismatches: &[PinMismatch],
+    ) -> Result<(), OxenError> {
+        if mismatches.is_empty() {
+            println!(
+                "{} all {} files match pin '{}'",
+                "OK".green(),
+                num_entries,
+                name
+            );
+            return Ok(());
+        }
+
+        for mismatch in mismatches {
+            let actual = mismatch.actual_hash.as_deref().unwrap_or("missing");
+            println!(
+                "{}\t{}\texpected {} got {}",
+                "MISMATCH".red(),
+                mismatch.path.to_string_lossy(),
+                mismatch.expected_hash,
+                actual
+            );
+        }
+        Err(OxenError::basic_str(format!(
+            "{} of {} files do not match pin '{}'",
+            mismatches.len(),
+            num_entries,
+            name
+        )))
+    }
+}
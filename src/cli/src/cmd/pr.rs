@@ -0,0 +1,224 @@
+use async_trait::async_trait;
+use clap::{Arg, ArgMatches, Command};
+use colored::Colorize;
+
+use liboxen::api;
+use liboxen::config::UserConfig;
+use liboxen::constants::{DEFAULT_BRANCH_NAME, DEFAULT_PAGE_NUM, DEFAULT_REMOTE_NAME};
+use liboxen::error::OxenError;
+use liboxen::model::diff::diff_entry_status::DiffEntryStatus;
+use liboxen::model::{
+    LocalRepository, MergeRequest, MergeRequestStatus, NewMergeRequest, RemoteRepository,
+};
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+use crate::helpers::{check_remote_version, get_host_from_repo};
+
+use std::str::FromStr;
+
+pub const NAME: &str = "pr";
+
+pub struct PrCmd;
+
+// Number of changed files printed by `oxen pr show`
+const DIFF_PAGE_SIZE: usize = 100;
+
+fn remote_arg() -> Arg {
+    Arg::new("remote")
+        .long("remote")
+        .short('r')
+        .help("Remote that hosts the merge requests")
+        .default_value(DEFAULT_REMOTE_NAME)
+        .action(clap::ArgAction::Set)
+}
+
+#[async_trait]
+impl RunCmd for PrCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Open merge requests on the remote so changes can be reviewed before they land")
+            .subcommand_required(true)
+            .arg_required_else_help(true)
+            .subcommand(
+                Command::new("create")
+                    .about("Open a merge request of a branch into a base branch")
+                    .arg(Arg::new("head").help("Branch to merge. Defaults to the current branch."))
+                    .arg(
+                        Arg::new("base")
+                            .long("base")
+                            .short('b')
+                            .help("Branch to merge into")
+                            .default_value(DEFAULT_BRANCH_NAME),
+                    )
+                    .arg(
+                        Arg::new("title")
+                            .long("title")
+                            .short('t')
+                            .help("Title of the merge request")
+                            .required(true),
+                    )
+                    .arg(
+                        Arg::new("message")
+                            .long("message")
+                            .short('m')
+                            .help("Description of the changes"),
+                    )
+                    .arg(remote_arg()),
+            )
+            .subcommand(
+                Command::new("list")
+                    .about("List the merge requests")
+                    .arg(
+                        Arg::new("status")
+                            .long("status")
+                            .short('s')
+                            .help("Only list merge requests with this status")
+                            .value_parser(["open", "merged", "closed"]),
+                    )
+                    .arg(remote_arg()),
+            )
+            .subcommand(
+                Command::new("show")
+                    .about("Show a merge request and the files it changes")
+                    .arg(Arg::new("id").required(true).help("Id or number"))
+                    .arg(remote_arg()),
+            )
+            .subcommand(
+                Command::new("merge")
+                    .about("Merge a merge request into its base branch")
+                    .arg(Arg::new("id").required(true).help("Id or number"))
+                    .arg(remote_arg()),
+            )
+            .subcommand(
+                Command::new("close")
+                    .about("Close a merge request without merging it")
+                    .arg(Arg::new("id").required(true).help("Id or number"))
+                    .arg(remote_arg()),
+            )
+    }
+
+    async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+        let Some((name, sub_args)) = args.subcommand() else {
+            return Err(OxenError::basic_str("Must supply a pr subcommand"));
+        };
+        let remote_repo = self.get_remote_repo(&repo, sub_args).await?;
+
+        match name {
+            "create" => {
+                let head = match sub_args.get_one::<String>("head") {
+                    Some(head) => head.to_owned(),
+                    None => {
+                        repositories::branches::current_branch(&repo)?
+                            .ok_or(OxenError::must_be_on_valid_branch())?
+                            .name
+                    }
+                };
+                let user = UserConfig::get()?.to_user();
+                let new_request = NewMergeRequest {
+                    title: sub_args.get_one::<String>("title").unwrap().to_owned(),
+                    description: sub_args
+                        .get_one::<String>("message")
+                        .cloned()
+                        .unwrap_or_default(),
+                    base: sub_args.get_one::<String>("base").unwrap().to_owned(),
+                    head,
+                    author: user.name,
+                    email: user.email,
+                };
+                let merge_request =
+                    api::client::merge_requests::create(&remote_repo, &new_request).await?;
+                self.print_merge_request(&merge_request);
+            }
+            "list" => {
+                let status = sub_args
+                    .get_one::<String>("status")
+                    .map(|s| MergeRequestStatus::from_str(s))
+                    .transpose()
+                    .map_err(OxenError::basic_str)?;
+                let merge_requests =
+                    api::client::merge_requests::list(&remote_repo, status).await?;
+                for merge_request in merge_requests.iter() {
+                    self.print_merge_request(merge_request);
+                }
+            }
+            "show" => {
+                let id = sub_args.get_one::<String>("id").unwrap();
+                let merge_request = api::client::merge_requests::get(&remote_repo, id).await?;
+                self.print_merge_request(&merge_request);
+                if !merge_request.description.is_empty() {
+                    println!("\n{}", merge_request.description);
+                }
+
+                let compare = api::client::merge_requests::diff(
+                    &remote_repo,
+                    id,
+                    DEFAULT_PAGE_NUM,
+                    DIFF_PAGE_SIZE,
+                )
+                .await?;
+                let counts = &compare.counts;
+                println!(
+                    "\n{} added, {} removed, {} modified",
+                    counts.added, counts.removed, counts.modified
+                );
+                for entry in compare.entries.iter().filter(|e| !e.is_dir) {
+                    let line = format!("   {}", entry.filename);
+                    match DiffEntryStatus::from_str(&entry.status) {
+                        Ok(DiffEntryStatus::Added) => println!("{}", line.green()),
+                        Ok(DiffEntryStatus::Removed) => println!("{}", line.red()),
+                        _ => println!("{}", line.yellow()),
+                    }
+                }
+            }
+            "merge" => {
+                let id = sub_args.get_one::<String>("id").unwrap();
+                let merge_request = api::client::merge_requests::merge(&remote_repo, id).await?;
+                self.print_merge_request(&merge_request);
+            }
+            "close" => {
+                let id = sub_args.get_one::<String>("id").unwrap();
+                let merge_request = api::client::merge_requests::close(&remote_repo, id).await?;
+                self.print_merge_request(&merge_request);
+            }
+            cmd => return Err(OxenError::basic_str(format!("Unknown pr subcommand {cmd}"))),
+        }
+        Ok(())
+    }
+}
+
+impl PrCmd {
+    async fn get_remote_repo(
+        &self,
+        repo: &LocalRepository,
+        args: &ArgMatches,
+    ) -> Result<RemoteRepository, OxenError> {
+        let host = get_host_from_repo(repo)?;
+        check_remote_version(host).await?;
+
+        let remote_name = args.get_one::<String>("remote").unwrap();
+        let remote = repo
+            .get_remote(remote_name)
+            .ok_or(OxenError::remote_not_set(remote_name))?;
+        api::client::repositories::get_by_remote(&remote)
+            .await?
+            .ok_or(OxenError::remote_not_found(remote.clone()))
+    }
+
+    fn print_merge_request(&self, merge_request: &MergeRequest) {
+        println!(
+            "#{}\t{}\t{} -> {}\t{}\t{}",
+            merge_request.number,
+            merge_request.title,
+            merge_request.head,
+            merge_request.base,
+            merge_request.status,
+            merge_request.author
+        );
+    }
+}
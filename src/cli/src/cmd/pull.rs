@@ -6,10 +6,9 @@ use liboxen::model::LocalRepository;
 use liboxen::repositories;
 
 use crate::helpers::{
-    check_remote_version, check_remote_version_blocking, check_repo_migration_needed,
-    get_host_from_repo,
+    apply_limit_rate, check_remote_version, check_remote_version_blocking,
+    check_repo_migration_needed, get_host_from_remote, limit_rate_arg, resolve_upstream,
 };
-use liboxen::constants::{DEFAULT_BRANCH_NAME, DEFAULT_REMOTE_NAME};
 
 use crate::cmd::RunCmd;
 pub const NAME: &str = "pull";
@@ -26,15 +25,11 @@ impl RunCmd for PullCmd {
             .about("Pull the files up from a remote branch")
             .arg(
                 Arg::new("REMOTE")
-                    .help("Remote you want to pull from")
-                    .default_value(DEFAULT_REMOTE_NAME)
-                    .default_missing_value(DEFAULT_REMOTE_NAME),
+                    .help("Remote you want to pull from, defaults to the upstream of the current branch or the current remote"),
             )
             .arg(
                 Arg::new("BRANCH")
-                    .help("Branch name to pull")
-                    .default_value(DEFAULT_BRANCH_NAME)
-                    .default_missing_value(DEFAULT_BRANCH_NAME),
+                    .help("Branch name to pull, defaults to the upstream of the current branch"),
             )
             .arg(
                 Arg::new("all")
@@ -42,28 +37,30 @@ impl RunCmd for PullCmd {
                     .help("This pulls the full commit history, all the data files, and all the commit databases. Useful if you want to have the entire history locally or push to a new remote.")
                     .action(clap::ArgAction::SetTrue),
             )
+            .arg(limit_rate_arg())
     }
 
     async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
-        // Parse args
-        let remote = args
-            .get_one::<String>("REMOTE")
-            .expect("Must supply a remote");
-        let branch = args
-            .get_one::<String>("BRANCH")
-            .expect("Must supply a branch");
-
-        let all = args.get_flag("all");
-
         // Get the repo
         let repository = LocalRepository::from_current_dir()?;
 
-        let host = get_host_from_repo(&repository)?;
+        // Parse args, anything left off comes from the tracked upstream
+        let (_, upstream) = resolve_upstream(
+            &repository,
+            args.get_one::<String>("REMOTE"),
+            args.get_one::<String>("BRANCH"),
+        )?;
+
+        let all = args.get_flag("all");
+        apply_limit_rate(args)?;
+
+        let host = get_host_from_remote(&repository, &upstream.remote)?;
         check_repo_migration_needed(&repository)?;
         check_remote_version_blocking(host.clone()).await?;
         check_remote_version(host).await?;
 
-        repositories::pull_remote_branch(&repository, remote, branch, all).await?;
+        repositories::pull_remote_branch(&repository, &upstream.remote, &upstream.branch, all)
+            .await?;
         Ok(())
     }
 }
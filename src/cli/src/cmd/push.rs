@@ -1,16 +1,16 @@
 use async_trait::async_trait;
 use clap::{Arg, Command};
 use liboxen::api;
+use liboxen::command;
 use liboxen::error::OxenError;
 use liboxen::model::LocalRepository;
 
 use liboxen::repositories;
 
 use crate::helpers::{
-    check_remote_version, check_remote_version_blocking, check_repo_migration_needed,
-    get_host_from_repo,
+    apply_limit_rate, check_remote_version, check_remote_version_blocking,
+    check_repo_migration_needed, get_host_from_remote, limit_rate_arg, resolve_upstream,
 };
-use liboxen::constants::{DEFAULT_BRANCH_NAME, DEFAULT_REMOTE_NAME};
 
 use crate::cmd::RunCmd;
 pub const NAME: &str = "push";
@@ -27,15 +27,11 @@ impl RunCmd for PushCmd {
             .about("Push the the files to the remote branch")
             .arg(
                 Arg::new("REMOTE")
-                    .help("Remote you want to push to")
-                    .default_value(DEFAULT_REMOTE_NAME)
-                    .default_missing_value(DEFAULT_REMOTE_NAME),
+                    .help("Remote you want to push to, defaults to the upstream of the branch or the current remote"),
             )
             .arg(
                 Arg::new("BRANCH")
-                    .help("Branch name to push to")
-                    .default_value(DEFAULT_BRANCH_NAME)
-                    .default_missing_value(DEFAULT_BRANCH_NAME),
+                    .help("Branch name to push, defaults to the current branch"),
             )
             .arg(
                 Arg::new("delete")
@@ -44,37 +40,79 @@ impl RunCmd for PushCmd {
                     .help("Remove the remote branch")
                     .action(clap::ArgAction::SetTrue),
             )
+            .arg(
+                Arg::new("set-upstream")
+                    .long("set-upstream")
+                    .short('u')
+                    .help("Track the remote branch, so later pushes and pulls on the branch go to it")
+                    .conflicts_with("delete")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("force-with-lease")
+                    .long("force-with-lease")
+                    .value_name("COMMIT")
+                    .num_args(0..=1)
+                    .require_equals(true)
+                    .default_missing_value("")
+                    .help("Overwrite the remote branch even if it is not an ancestor of the local branch, as long as the remote branch is still at COMMIT. Defaults to the remote-tracking branch from the last fetch.")
+                    .conflicts_with("delete"),
+            )
+            .arg(limit_rate_arg())
     }
 
     async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
-        // Parse args
-        let remote = args
-            .get_one::<String>("REMOTE")
-            .expect("Must supply a remote");
+        let mut repository = LocalRepository::from_current_dir()?;
 
-        let branch = args
-            .get_one::<String>("BRANCH")
-            .expect("Must supply a branch");
+        // Parse args, anything left off comes from the branch's upstream
+        let (branch, upstream) = resolve_upstream(
+            &repository,
+            args.get_one::<String>("REMOTE"),
+            args.get_one::<String>("BRANCH"),
+        )?;
+        let remote = &upstream.remote;
 
+        apply_limit_rate(args)?;
+        let host = get_host_from_remote(&repository, remote)?;
         // Call into liboxen to push or delete
         if args.get_flag("delete") {
-            let repository = LocalRepository::from_current_dir()?;
-
-            let host = get_host_from_repo(&repository)?;
             check_remote_version(host).await?;
 
-            api::client::branches::delete_remote(&repository, remote, branch).await?;
-            println!("Deleted remote branch: {remote}/{branch}");
+            api::client::branches::delete_remote(&repository, remote, &upstream.branch).await?;
+            println!("Deleted remote branch: {remote}/{}", upstream.branch);
             Ok(())
         } else {
-            let repository = LocalRepository::from_current_dir()?;
-            let host = get_host_from_repo(&repository)?;
+            if upstream.branch != branch {
+                return Err(OxenError::basic_str(format!(
+                    "Branch '{branch}' tracks '{remote}/{}', pushing to a remote branch with a different name is not supported.\n\nPush it by name with: oxen push {remote} {branch}",
+                    upstream.branch
+                )));
+            }
 
             check_repo_migration_needed(&repository)?;
             check_remote_version_blocking(host.clone()).await?;
             check_remote_version(host).await?;
 
-            repositories::push::push_remote_branch(&repository, remote, branch).await?;
+            match args.get_one::<String>("force-with-lease") {
+                Some(lease) => {
+                    let expected = Some(lease.as_str()).filter(|lease| !lease.is_empty());
+                    repositories::push::force_push_remote_branch(
+                        &repository,
+                        remote,
+                        &branch,
+                        expected,
+                    )
+                    .await?;
+                }
+                None => {
+                    repositories::push::push_remote_branch(&repository, remote, &branch).await?;
+                }
+            }
+
+            if args.get_flag("set-upstream") {
+                command::config::set_upstream(&mut repository, &branch, remote, &branch)?;
+                println!("Branch '{branch}' set up to track '{remote}/{branch}'");
+            }
             Ok(())
         }
     }
@@ -0,0 +1,203 @@
+use async_trait::async_trait;
+use clap::{Arg, ArgMatches, Command};
+
+use liboxen::api;
+use liboxen::config::UserConfig;
+use liboxen::error::OxenError;
+use liboxen::model::{LocalRepository, NewRelease, Release, RemoteRepository};
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+use crate::helpers::{check_remote_version, check_repo_migration_needed, get_host_from_repo};
+
+pub const NAME: &str = "release";
+
+pub struct ReleaseCmd;
+
+fn remote_arg() -> Arg {
+    Arg::new("remote")
+        .long("remote")
+        .short('r')
+        .help("Manage the releases on this remote instead of the local repository")
+        .action(clap::ArgAction::Set)
+}
+
+fn name_arg() -> Arg {
+    Arg::new("name").required(true).help("Name of the release")
+}
+
+#[async_trait]
+impl RunCmd for ReleaseCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Name dataset versions like train-v3. A release points at a commit and never moves.")
+            .subcommand_required(true)
+            .arg_required_else_help(true)
+            .subcommand(
+                Command::new("create")
+                    .about("Create a release")
+                    .arg(name_arg())
+                    .arg(
+                        Arg::new("revision")
+                            .help("Branch or commit id to release. Defaults to HEAD."),
+                    )
+                    .arg(
+                        Arg::new("message")
+                            .long("message")
+                            .short('m')
+                            .help("Description of the release"),
+                    )
+                    .arg(remote_arg()),
+            )
+            .subcommand(
+                Command::new("list")
+                    .about("List the releases, newest first")
+                    .arg(remote_arg()),
+            )
+            .subcommand(
+                Command::new("show")
+                    .about("Show a release")
+                    .arg(name_arg())
+                    .arg(remote_arg()),
+            )
+            .subcommand(
+                Command::new("edit")
+                    .about("Replace the description of a release")
+                    .arg(name_arg())
+                    .arg(
+                        Arg::new("message")
+                            .long("message")
+                            .short('m')
+                            .help("New description. Clears it if omitted."),
+                    )
+                    .arg(remote_arg()),
+            )
+            .subcommand(
+                Command::new("delete")
+                    .about("Delete a release. The commit it points at is kept.")
+                    .arg(name_arg())
+                    .arg(remote_arg()),
+            )
+    }
+
+    async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+        check_repo_migration_needed(&repo)?;
+        let Some((subcommand, sub_args)) = args.subcommand() else {
+            return Err(OxenError::basic_str("Must supply a release subcommand"));
+        };
+        let remote_repo = match sub_args.get_one::<String>("remote") {
+            Some(remote_name) => Some(self.get_remote_repo(&repo, remote_name).await?),
+            None => None,
+        };
+        let name = sub_args.get_one::<String>("name");
+        let description = sub_args.get_one::<String>("message").cloned();
+
+        match subcommand {
+            "create" => {
+                let revision = match sub_args.get_one::<String>("revision") {
+                    Some(revision) => revision.to_owned(),
+                    None if remote_repo.is_some() => {
+                        repositories::branches::current_branch(&repo)?
+                            .ok_or(OxenError::must_be_on_valid_branch())?
+                            .name
+                    }
+                    None => repositories::commits::head_commit(&repo)?.id,
+                };
+                let user = UserConfig::get()?.to_user();
+                let new_release = NewRelease {
+                    name: name.unwrap().to_owned(),
+                    revision,
+                    description,
+                    author: user.name,
+                    email: user.email,
+                };
+                let release = match &remote_repo {
+                    Some(remote_repo) => {
+                        api::client::releases::create(remote_repo, &new_release).await?
+                    }
+                    None => repositories::releases::create(&repo, new_release)?,
+                };
+                print_release(&release);
+            }
+            "list" => {
+                let releases = match &remote_repo {
+                    Some(remote_repo) => api::client::releases::list(remote_repo).await?,
+                    None => repositories::releases::list(&repo)?,
+                };
+                for release in releases.iter() {
+                    print_release(release);
+                }
+            }
+            "show" => {
+                let name = name.unwrap();
+                let release = match &remote_repo {
+                    Some(remote_repo) => api::client::releases::get(remote_repo, name).await?,
+                    None => repositories::releases::get(&repo, name)?
+                        .ok_or(OxenError::resource_not_found(name))?,
+                };
+                print_release(&release);
+                println!("Author: {} <{}>", release.author, release.email);
+                if let Some(description) = &release.description {
+                    println!("\n{description}");
+                }
+            }
+            "edit" => {
+                let name = name.unwrap();
+                let release = match &remote_repo {
+                    Some(remote_repo) => {
+                        api::client::releases::update_description(remote_repo, name, description)
+                            .await?
+                    }
+                    None => repositories::releases::update_description(&repo, name, description)?,
+                };
+                print_release(&release);
+            }
+            "delete" => {
+                let name = name.unwrap();
+                let release = match &remote_repo {
+                    Some(remote_repo) => api::client::releases::delete(remote_repo, name).await?,
+                    None => repositories::releases::delete(&repo, name)?,
+                };
+                println!("Deleted release {} ({})", release.name, release.commit_id);
+            }
+            cmd => {
+                return Err(OxenError::basic_str(format!(
+                    "Unknown release subcommand {cmd}"
+                )))
+            }
+        }
+        Ok(())
+    }
+}
+
+impl ReleaseCmd {
+    async fn get_remote_repo(
+        &self,
+        repo: &LocalRepository,
+        remote_name: &str,
+    ) -> Result<RemoteRepository, OxenError> {
+        let host = get_host_from_repo(repo)?;
+        check_remote_version(host).await?;
+
+        let remote = repo
+            .get_remote(remote_name)
+            .ok_or(OxenError::remote_not_set(remote_name))?;
+        api::client::repositories::get_by_remote(&remote)
+            .await?
+            .ok_or(OxenError::remote_not_found(remote.clone()))
+    }
+}
+
+fn print_release(release: &Release) {
+    println!(
+        "{}\t{}\t{}",
+        release.name,
+        release.commit_id,
+        release.created_at.date()
+    );
+}
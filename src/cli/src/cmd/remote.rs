@@ -1,10 +1,14 @@
 use async_trait::async_trait;
 use clap::{Arg, ArgMatches, Command};
 
+use liboxen::api;
+use liboxen::command;
+use liboxen::constants::DEFAULT_REMOTE_NAME;
 use liboxen::error::OxenError;
-use liboxen::model::LocalRepository;
+use liboxen::model::{LocalRepository, Mirror, NewMirror, RemoteRepository};
 
 use crate::cmd::RunCmd;
+use crate::helpers::{check_remote_version, get_host_from_repo};
 pub const NAME: &str = "remote";
 pub struct RemoteCmd;
 
@@ -15,16 +19,78 @@ impl RunCmd for RemoteCmd {
     }
 
     fn args(&self) -> Command {
-        Command::new(NAME).about("List oxen remotes.").arg(
-            Arg::new("verbose")
-                .long("verbose")
-                .short('v')
-                .help("Verbose output")
-                .action(clap::ArgAction::SetTrue),
-        )
+        Command::new(NAME)
+            .about("List, add, remove and rename oxen remotes.")
+            .arg(
+                Arg::new("verbose")
+                    .long("verbose")
+                    .short('v')
+                    .help("Verbose output")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .subcommand(
+                Command::new("add")
+                    .about("Add a remote, ex: oxen remote add backup https://hub.oxen.ai/ox/CatsVsDogs")
+                    .arg(Arg::new("NAME").help("Name of the remote").required(true))
+                    .arg(Arg::new("URL").help("Url of the remote repository").required(true)),
+            )
+            .subcommand(
+                Command::new("remove")
+                    .about("Remove a remote, branches tracking it stop tracking it")
+                    .arg(Arg::new("NAME").help("Name of the remote").required(true)),
+            )
+            .subcommand(
+                Command::new("rename")
+                    .about("Rename a remote, branches tracking it follow the rename")
+                    .arg(Arg::new("OLD").help("Current name of the remote").required(true))
+                    .arg(Arg::new("NEW").help("New name of the remote").required(true)),
+            )
+            .subcommand(
+                Command::new("mirror")
+                    .about("Replicate the remote repository to another oxen-server. Lists the mirrors if no url is given.")
+                    .arg(
+                        Arg::new("url")
+                            .help("Url of the repository to mirror to, ex: https://hub.oxen.ai/ox/CatsVsDogs"),
+                    )
+                    .arg(
+                        Arg::new("interval")
+                            .long("interval")
+                            .short('i')
+                            .help("Sync every this many seconds instead of after every push")
+                            .value_parser(clap::value_parser!(u64))
+                            .requires("url"),
+                    )
+                    .arg(
+                        Arg::new("remove")
+                            .long("remove")
+                            .help("Id of a mirror to stop replicating to")
+                            .conflicts_with_all(["url", "sync"]),
+                    )
+                    .arg(
+                        Arg::new("sync")
+                            .long("sync")
+                            .help("Id of a mirror to sync now")
+                            .conflicts_with("url"),
+                    )
+                    .arg(
+                        Arg::new("remote")
+                            .long("remote")
+                            .short('r')
+                            .help("Remote whose server replicates the repository")
+                            .default_value(DEFAULT_REMOTE_NAME),
+                    ),
+            )
     }
 
     async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        match args.subcommand() {
+            Some(("add", sub_args)) => return self.add(sub_args),
+            Some(("remove", sub_args)) => return self.remove(sub_args),
+            Some(("rename", sub_args)) => return self.rename(sub_args),
+            Some(("mirror", sub_args)) => return self.mirror(sub_args).await,
+            _ => {}
+        }
+
         let verbose = args.get_flag("verbose");
         if verbose {
             self.list_remotes_verbose()?;
@@ -56,4 +122,82 @@ impl RemoteCmd {
 
         Ok(())
     }
+
+    fn add(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        let mut repo = LocalRepository::from_current_dir()?;
+        let name = args.get_one::<String>("NAME").unwrap();
+        let url = args.get_one::<String>("URL").unwrap();
+        let remote = command::config::add_remote(&mut repo, name, url)?;
+        println!("Added remote {}\t{}", remote.name, remote.url);
+        Ok(())
+    }
+
+    fn remove(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        let mut repo = LocalRepository::from_current_dir()?;
+        let name = args.get_one::<String>("NAME").unwrap();
+        if !repo.has_remote(name) {
+            return Err(OxenError::remote_not_set(name));
+        }
+        command::config::delete_remote(&mut repo, name)?;
+        println!("Removed remote {name}");
+        Ok(())
+    }
+
+    fn rename(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        let mut repo = LocalRepository::from_current_dir()?;
+        let old_name = args.get_one::<String>("OLD").unwrap();
+        let new_name = args.get_one::<String>("NEW").unwrap();
+        command::config::rename_remote(&mut repo, old_name, new_name)?;
+        println!("Renamed remote {old_name} to {new_name}");
+        Ok(())
+    }
+
+    async fn mirror(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+        check_remote_version(get_host_from_repo(&repo)?).await?;
+        let remote_name = args.get_one::<String>("remote").unwrap();
+        let remote = repo
+            .get_remote(remote_name)
+            .ok_or(OxenError::remote_not_set(remote_name))?;
+        let remote_repo: RemoteRepository = api::client::repositories::get_by_remote(&remote)
+            .await?
+            .ok_or(OxenError::remote_not_found(remote.clone()))?;
+
+        if let Some(url) = args.get_one::<String>("url") {
+            let new_mirror = NewMirror {
+                url: url.to_owned(),
+                interval_seconds: args.get_one::<u64>("interval").copied(),
+            };
+            let mirror = api::client::mirrors::create(&remote_repo, &new_mirror).await?;
+            println!("Mirroring {} to {}", remote.url, mirror.url);
+            self.print_mirror(&mirror);
+        } else if let Some(id) = args.get_one::<String>("remove") {
+            let mirror = api::client::mirrors::delete(&remote_repo, id).await?;
+            println!("Stopped mirroring to {}", mirror.url);
+        } else if let Some(id) = args.get_one::<String>("sync") {
+            let mirror = api::client::mirrors::sync(&remote_repo, id).await?;
+            println!("Syncing to {}", mirror.url);
+        } else {
+            for mirror in api::client::mirrors::list(&remote_repo).await? {
+                self.print_mirror(&mirror);
+            }
+        }
+        Ok(())
+    }
+
+    fn print_mirror(&self, mirror: &Mirror) {
+        let schedule = match mirror.interval_seconds {
+            Some(seconds) => format!("every {seconds}s"),
+            None => String::from("on push"),
+        };
+        let last_synced = match (&mirror.last_error, mirror.last_synced_at) {
+            (Some(err), _) => format!("failed: {err}"),
+            (None, Some(at)) => format!("synced {at}"),
+            (None, None) => String::from("not synced yet"),
+        };
+        println!(
+            "{}\t{}\t{}\t{}",
+            mirror.id, mirror.url, schedule, last_synced
+        );
+    }
 }
@@ -0,0 +1,42 @@
+use async_trait::async_trait;
+use clap::{ArgMatches, Command};
+
+use crate::helpers::check_repo_migration_needed;
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+pub const NAME: &str = "repack";
+pub struct RepackCmd;
+
+#[async_trait]
+impl RunCmd for RepackCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME).about(
+            "Moves the merkle tree nodes, stored one directory per node, into a single pack file. Safe to run at any time, new commits keep writing loose nodes until the next repack.",
+        )
+    }
+
+    async fn run(&self, _args: &ArgMatches) -> Result<(), OxenError> {
+        let repository = LocalRepository::from_current_dir()?;
+        check_repo_migration_needed(&repository)?;
+
+        let summary = repositories::tree::repack(&repository)?;
+        if summary.num_nodes == 0 {
+            println!("Nothing to repack");
+        } else {
+            println!(
+                "Packed {} nodes, {} loose nodes and {} old packs removed",
+                summary.num_nodes, summary.num_loose, summary.num_packs
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -6,6 +6,7 @@ use liboxen::error::OxenError;
 use liboxen::model::LocalRepository;
 use liboxen::opts::RestoreOpts;
 use liboxen::repositories;
+use liboxen::util;
 use std::path::PathBuf;
 
 use crate::cmd::RunCmd;
@@ -22,13 +23,14 @@ impl RunCmd for RestoreCmd {
         Command::new(NAME)
         .about("Restore specified paths in the working tree with some contents from a restore source.")
         .arg(Arg::new("PATH")
-            .help("The files or directory to restore")
+            .help("The files or directories to restore")
+            .action(clap::ArgAction::Append)
         )
         .arg_required_else_help(true)
         .arg(
             Arg::new("source")
                 .long("source")
-                .help("Restores a specific revision of the file. Can supply commit id or branch name")
+                .help("Restores a specific revision of the file. Can supply a commit id, branch name or remote-tracking branch such as origin/main")
                 .action(clap::ArgAction::Set)
                 .requires("PATH"),
         )
@@ -42,28 +44,28 @@ impl RunCmd for RestoreCmd {
     }
 
     async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
-        let path = args.get_one::<String>("PATH").expect("required");
-
-        let opts = if let Some(source) = args.get_one::<String>("source") {
-            RestoreOpts {
-                path: PathBuf::from(path),
-                staged: args.get_flag("staged"),
-                is_remote: false,
-                source_ref: Some(String::from(source)),
-            }
-        } else {
-            RestoreOpts {
-                path: PathBuf::from(path),
-                staged: args.get_flag("staged"),
-                is_remote: false,
-                source_ref: None,
-            }
-        };
+        let paths: Vec<PathBuf> = args
+            .get_many::<String>("PATH")
+            .expect("required")
+            .map(PathBuf::from)
+            .collect();
+        let source_ref = args.get_one::<String>("source").map(String::from);
 
         let repository = LocalRepository::from_current_dir()?;
 
         check_repo_migration_needed(&repository)?;
-        repositories::restore::restore(&repository, opts)?;
+        let current_dir = std::env::current_dir()?;
+        for path in paths {
+            // Paths are relative to where the command was run, the index is relative to the repo root
+            let path = util::fs::path_relative_to_dir(current_dir.join(&path), &repository.path)?;
+            let opts = RestoreOpts {
+                path,
+                staged: args.get_flag("staged"),
+                is_remote: false,
+                source_ref: source_ref.clone(),
+            };
+            repositories::restore::restore(&repository, opts)?;
+        }
 
         Ok(())
     }
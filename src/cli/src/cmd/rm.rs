@@ -33,6 +33,13 @@ impl RunCmd for RmCmd {
                     .help("Removes the file from the staging area.")
                     .action(clap::ArgAction::SetTrue),
             )
+            .arg(
+                Arg::new("cached")
+                    .long("cached")
+                    .help("Stages the removal but keeps the files in the working directory.")
+                    .conflicts_with("staged")
+                    .action(clap::ArgAction::SetTrue),
+            )
             .arg(
                 Arg::new("recursive")
                     .long("recursive")
@@ -54,6 +61,7 @@ impl RunCmd for RmCmd {
             path: paths.first().unwrap().to_path_buf(),
             staged: args.get_flag("staged"),
             recursive: args.get_flag("recursive"),
+            cached: args.get_flag("cached"),
         };
 
         let repository = LocalRepository::from_current_dir()?;
@@ -0,0 +1,121 @@
+use async_trait::async_trait;
+use clap::{Arg, ArgMatches, Command};
+
+use liboxen::error::OxenError;
+use liboxen::model::{LocalRepository, PiiRule, PiiScanMode};
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+
+pub const NAME: &str = "scan";
+
+pub struct ScanCmd;
+
+#[async_trait]
+impl RunCmd for ScanCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Scan staged files for secrets and personal data like emails, SSNs and API keys")
+            .arg(
+                Arg::new("revision")
+                    .long("revision")
+                    .help("Scan every file in the commit or branch instead of the staged files")
+                    .conflicts_with("unpushed")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("unpushed")
+                    .long("unpushed")
+                    .help("Scan the files that commits not on a remote yet would push")
+                    .action(clap::ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("mode")
+                    .long("mode")
+                    .help("Set whether commits and pushes run the scan: off, warn or block")
+                    .value_parser(["off", "warn", "block"])
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("rule")
+                    .long("rule")
+                    .help("Add a rule as name=regex, ex: --rule 'employee_id=EMP-\\d{6}'")
+                    .action(clap::ArgAction::Append),
+            )
+            .arg(
+                Arg::new("disable-rule")
+                    .long("disable-rule")
+                    .help("Skip a built in rule, one of email, ssn, aws_access_key, github_token, slack_token, private_key or api_key")
+                    .action(clap::ArgAction::Append),
+            )
+            .arg(
+                Arg::new("ignore")
+                    .long("ignore")
+                    .help("Never scan paths matching the glob")
+                    .action(clap::ArgAction::Append),
+            )
+            .arg(
+                Arg::new("json")
+                    .long("json")
+                    .help("If present, will print the report as json.")
+                    .action(clap::ArgAction::SetTrue),
+            )
+    }
+
+    async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+
+        let is_config_change = ["mode", "rule", "disable-rule", "ignore"]
+            .iter()
+            .any(|name| args.contains_id(name));
+        if is_config_change {
+            let mut config = repositories::pii_scan::get_config(&repo)?;
+            if let Some(mode) = args.get_one::<String>("mode") {
+                config.mode = mode.parse::<PiiScanMode>()?;
+            }
+            for rule in args.get_many::<String>("rule").unwrap_or_default() {
+                let Some((name, pattern)) = rule.split_once('=') else {
+                    return Err(OxenError::basic_str(format!(
+                        "Invalid rule {rule:?}, expected name=regex"
+                    )));
+                };
+                config.rules.retain(|r| r.name != name);
+                config.rules.push(PiiRule::new(name, pattern));
+            }
+            for name in args.get_many::<String>("disable-rule").unwrap_or_default() {
+                if !config.disabled_rules.contains(name) {
+                    config.disabled_rules.push(name.to_owned());
+                }
+            }
+            for pattern in args.get_many::<String>("ignore").unwrap_or_default() {
+                if !config.ignore_paths.contains(pattern) {
+                    config.ignore_paths.push(pattern.to_owned());
+                }
+            }
+            repositories::pii_scan::set_config(&repo, &config)?;
+            println!("Scan mode is {}", config.mode);
+            return Ok(());
+        }
+
+        let report = if let Some(revision) = args.get_one::<String>("revision") {
+            let commit = repositories::revisions::get(&repo, revision)?
+                .ok_or(OxenError::revision_not_found(revision.as_str().into()))?;
+            repositories::pii_scan::scan_commit(&repo, &commit)?
+        } else if args.get_flag("unpushed") {
+            repositories::pii_scan::scan_unpushed(&repo)?
+        } else {
+            repositories::pii_scan::scan_staged(&repo)?
+        };
+
+        if args.get_flag("json") {
+            println!("{}", serde_json::to_string(&report)?);
+        } else {
+            println!("{report}");
+        }
+        Ok(())
+    }
+}
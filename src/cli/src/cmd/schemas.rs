@@ -12,12 +12,18 @@ pub const NAME: &str = "schemas";
 pub mod add;
 pub use add::SchemasAddCmd;
 
+pub mod apply;
+pub use apply::SchemasApplyCmd;
+
 pub mod list;
 pub use list::SchemasListCmd;
 
 pub mod rm;
 pub use rm::SchemasRmCmd;
 
+pub mod set_key;
+pub use set_key::SchemasSetKeyCmd;
+
 pub mod show;
 pub use show::SchemasShowCmd;
 
@@ -49,7 +55,7 @@ impl RunCmd for SchemasCmd {
         ;
 
         // These are all the subcommands for the schemas command
-        // including `add`, `name`, `list`, and `rm`
+        // including `add`, `apply`, `name`, `list`, `rm` and `set-key`
         let sub_commands = self.get_subcommands();
         for cmd in sub_commands.values() {
             command = command.subcommand(cmd.args());
@@ -96,8 +102,10 @@ impl SchemasCmd {
     fn get_subcommands(&self) -> HashMap<String, Box<dyn RunCmd>> {
         let commands: Vec<Box<dyn RunCmd>> = vec![
             Box::new(SchemasAddCmd),
+            Box::new(SchemasApplyCmd),
             Box::new(SchemasListCmd),
             Box::new(SchemasRmCmd),
+            Box::new(SchemasSetKeyCmd),
             Box::new(SchemasShowCmd),
         ];
         let mut runners: HashMap<String, Box<dyn RunCmd>> = HashMap::new();
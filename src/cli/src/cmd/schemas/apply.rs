@@ -0,0 +1,87 @@
+use async_trait::async_trait;
+use clap::{Arg, Command};
+use std::path::Path;
+
+use liboxen::error::OxenError;
+use liboxen::model::data_frame::schema::{Field, Schema};
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+use liboxen::util;
+
+use crate::cmd::RunCmd;
+pub const NAME: &str = "apply";
+
+pub struct SchemasApplyCmd;
+
+#[async_trait]
+impl RunCmd for SchemasApplyCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME)
+            .about("Validate all data frames matching a glob against a schema and stage the schema metadata on them.")
+            .arg(
+                Arg::new("glob")
+                    .long("glob")
+                    .short('g')
+                    .required(true)
+                    .help("Glob pattern relative to the repository root, ex: 'annotations/**/*.csv'"),
+            )
+            .arg(
+                Arg::new("schema")
+                    .long("schema")
+                    .short('s')
+                    .required(true)
+                    .help("Path to a JSON file with the schema `fields` (name, dtype, metadata) and optional schema `metadata`."),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        // Parse Args
+        let Some(pattern) = args.get_one::<String>("glob") else {
+            return Err(OxenError::basic_str("Must supply a --glob pattern"));
+        };
+        let Some(schema_path) = args.get_one::<String>("schema") else {
+            return Err(OxenError::basic_str("Must supply a --schema file"));
+        };
+
+        let schema = self.read_schema(Path::new(schema_path))?;
+
+        // Find the repo
+        let repository = LocalRepository::from_current_dir()?;
+
+        let results = repositories::data_frames::schemas::apply(&repository, pattern, &schema)?;
+        let mut paths: Vec<_> = results.keys().collect();
+        paths.sort();
+        for path in paths {
+            println!("{}", path.to_string_lossy());
+        }
+        println!("Applied schema to {} files", results.len());
+
+        Ok(())
+    }
+}
+
+impl SchemasApplyCmd {
+    fn read_schema(&self, path: &Path) -> Result<Schema, OxenError> {
+        let contents = util::fs::read_from_path(path)?;
+        let json: serde_json::Value = serde_json::from_str(&contents).map_err(|e| {
+            OxenError::basic_str(format!("Schema must be valid JSON: {:?}\n{}", path, e))
+        })?;
+
+        let Some(fields) = json.get("fields") else {
+            return Err(OxenError::basic_str(format!(
+                "Schema file {:?} must contain a list of `fields`",
+                path
+            )));
+        };
+        let fields: Vec<Field> = serde_json::from_value(fields.to_owned())?;
+
+        let mut schema = Schema::new(fields);
+        schema.metadata = json.get("metadata").cloned();
+        Ok(schema)
+    }
+}
@@ -0,0 +1,54 @@
+use async_trait::async_trait;
+use clap::{Arg, Command};
+use std::path::Path;
+
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::cmd::RunCmd;
+pub const NAME: &str = "set-key";
+
+pub struct SchemasSetKeyCmd;
+
+#[async_trait]
+impl RunCmd for SchemasSetKeyCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        // Setups the CLI args for the command
+        Command::new(NAME)
+            .about("Stage the columns that identify a row of a data frame. Tabular diffs match rows on them to report which values changed.")
+            .arg(
+                Arg::new("PATH")
+                    .required(true)
+                    .help("The path of the data frame file."),
+            )
+            .arg(
+                Arg::new("COLUMNS")
+                    .required(true)
+                    .num_args(1..)
+                    .help("One or more columns that together are unique for each row."),
+            )
+    }
+
+    async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
+        let path = Path::new(args.get_one::<String>("PATH").expect("required"));
+        let columns: Vec<String> = args
+            .get_many::<String>("COLUMNS")
+            .expect("required")
+            .cloned()
+            .collect();
+
+        let repository = LocalRepository::from_current_dir()?;
+        for (path, schema) in
+            repositories::data_frames::schemas::set_key(&repository, path, &columns)?
+        {
+            println!("{:?}\n{}", path, schema.verbose_str());
+        }
+
+        Ok(())
+    }
+}
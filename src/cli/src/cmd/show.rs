@@ -0,0 +1,116 @@
+use async_trait::async_trait;
+use clap::{Arg, ArgMatches, Command};
+use colored::Colorize;
+use time::format_description;
+
+use liboxen::error::OxenError;
+use liboxen::model::diff::diff_entry_status::DiffEntryStatus;
+use liboxen::model::entry::commit_entry::CommitPath;
+use liboxen::model::{Commit, LocalRepository};
+use liboxen::repositories;
+use liboxen::util;
+
+use crate::cmd::{DiffCmd, RunCmd};
+
+pub const NAME: &str = "show";
+
+pub struct ShowCmd;
+
+#[async_trait]
+impl RunCmd for ShowCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Show a commit and a summary of what it changed")
+            .arg(
+                Arg::new("revision")
+                    .help("The commit or branch to show. Defaults to HEAD.")
+                    .action(clap::ArgAction::Set),
+            )
+            .arg(
+                Arg::new("rows")
+                    .long("rows")
+                    .help("Also print the row changes of the modified data frames")
+                    .action(clap::ArgAction::SetTrue),
+            )
+    }
+
+    async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+        let commit = match args.get_one::<String>("revision") {
+            Some(revision) => repositories::revisions::get(&repo, revision)?
+                .ok_or(OxenError::revision_not_found(revision.as_str().into()))?,
+            None => repositories::commits::head_commit(&repo)?,
+        };
+
+        // Fri, 21 Oct 2022 16:08:39 -0700
+        let format = format_description::parse(
+            "[weekday], [day] [month repr:long] [year] [hour]:[minute]:[second] [offset_hour sign:mandatory]",
+        ).unwrap();
+        println!("{}", format!("commit {}", commit.id).yellow());
+        if commit.parent_ids.len() > 1 {
+            println!("Merge:  {}", commit.parent_ids.join(" "));
+        }
+        println!("Author: {} <{}>", commit.author, commit.email);
+        for co_author in Commit::parse_co_authors(&commit.message) {
+            println!("Co-author: {co_author}");
+        }
+        println!("Date:   {}\n", commit.timestamp.format(&format).unwrap());
+        for line in commit.message_without_co_authors().lines() {
+            println!("    {line}");
+        }
+        println!();
+
+        let changes = repositories::diffs::list_commit_changes(&repo, &commit)?;
+        if changes.is_empty() {
+            println!("No changes");
+            return Ok(());
+        }
+        for (dir, counts) in repositories::diffs::counts_by_dir(&changes) {
+            let dir = if dir.as_os_str().is_empty() {
+                String::from("/")
+            } else {
+                format!("{}/", dir.to_string_lossy())
+            };
+            println!(
+                "{dir}\t{}\t{}\t{}",
+                format!("+{}", counts.added).green(),
+                format!("-{}", counts.removed).red(),
+                format!("~{}", counts.modified).yellow()
+            );
+        }
+
+        if !args.get_flag("rows") {
+            return Ok(());
+        }
+        let Some(parent_id) = commit.parent_ids.first() else {
+            return Ok(());
+        };
+        let parent = repositories::commits::get_by_id(&repo, parent_id)?;
+        for (path, status) in changes.iter() {
+            if *status != DiffEntryStatus::Modified || !util::fs::is_tabular(path) {
+                continue;
+            }
+            println!("\n{}", path.to_string_lossy().bold());
+            let result = repositories::diffs::diff_commits(
+                &repo,
+                CommitPath {
+                    commit: parent.clone(),
+                    path: path.to_owned(),
+                },
+                CommitPath {
+                    commit: Some(commit.clone()),
+                    path: path.to_owned(),
+                },
+                vec![],
+                vec![],
+                vec![],
+            )?;
+            DiffCmd::print_diff_result(&result)?;
+        }
+        Ok(())
+    }
+}
@@ -54,6 +54,12 @@ impl RunCmd for StatusCmd {
                     .help("If present, does not truncate the output of status at all.")
                     .action(clap::ArgAction::SetTrue),
             )
+            .arg(
+                Arg::new("preview")
+                    .long("preview")
+                    .help("Show how many rows were added or removed and which columns changed in modified tabular files.")
+                    .action(clap::ArgAction::SetTrue),
+            )
             .arg(
                 Arg::new("paths")
                     .num_args(0..)
@@ -108,6 +114,17 @@ impl RunCmd for StatusCmd {
 
         repo_status.print_with_params(&opts);
 
+        if args.get_flag("preview") {
+            let previews =
+                repositories::status::preview_tabular_changes(&repository, &repo_status)?;
+            if !previews.is_empty() {
+                println!("Tabular changes against HEAD:");
+                for preview in previews {
+                    println!("  {}: {}", preview.path.display(), preview);
+                }
+            }
+        }
+
         Ok(())
     }
 }
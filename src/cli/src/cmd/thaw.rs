@@ -0,0 +1,47 @@
+use async_trait::async_trait;
+use clap::{Arg, ArgMatches, Command};
+
+use liboxen::api;
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::cmd::freeze::get_remote_repo;
+use crate::cmd::RunCmd;
+
+pub const NAME: &str = "thaw";
+
+pub struct ThawCmd;
+
+#[async_trait]
+impl RunCmd for ThawCmd {
+    fn name(&self) -> &str {
+        NAME
+    }
+
+    fn args(&self) -> Command {
+        Command::new(NAME)
+            .about("Thaw a frozen repository so it accepts changes again")
+            .arg(
+                Arg::new("remote")
+                    .long("remote")
+                    .help("Thaw the repository on the remote instead of the local one")
+                    .num_args(0..=1)
+                    .default_missing_value(liboxen::constants::DEFAULT_REMOTE_NAME)
+                    .action(clap::ArgAction::Set),
+            )
+    }
+
+    async fn run(&self, args: &ArgMatches) -> Result<(), OxenError> {
+        let repo = LocalRepository::from_current_dir()?;
+
+        if let Some(remote_name) = args.get_one::<String>("remote") {
+            let remote_repo = get_remote_repo(&repo, remote_name).await?;
+            api::client::freeze::thaw(&remote_repo).await?;
+        } else {
+            repositories::freeze::thaw(&repo)?;
+        }
+        println!("Repository thawed");
+        Ok(())
+    }
+}
@@ -1,12 +1,11 @@
 use async_trait::async_trait;
 use clap::Arg;
 use clap::Command;
+use colored::Colorize;
 
 use liboxen::api;
-use liboxen::constants::DEFAULT_PAGE_NUM;
-use liboxen::constants::DEFAULT_PAGE_SIZE;
 use liboxen::error::OxenError;
-use liboxen::model::LocalRepository;
+use liboxen::model::{LocalRepository, StagedEntryStatus};
 
 use crate::cmd::DiffCmd;
 use crate::cmd::RunCmd;
@@ -23,13 +22,16 @@ impl RunCmd for WorkspaceDiffCmd {
 
     fn args(&self) -> Command {
         // Setups the CLI args for the command
-        DiffCmd.args().arg(
-            Arg::new("workspace_id")
-                .long("workspace_id")
-                .short('w')
-                .help("The workspace to compare against.")
-                .action(clap::ArgAction::Set),
-        )
+        DiffCmd
+            .args()
+            .about("Preview the rows or files a workspace commit would change")
+            .arg(
+                Arg::new("workspace_id")
+                    .long("workspace_id")
+                    .short('w')
+                    .help("The workspace to compare against.")
+                    .action(clap::ArgAction::Set),
+            )
     }
 
     async fn run(&self, args: &clap::ArgMatches) -> Result<(), OxenError> {
@@ -44,16 +46,31 @@ impl RunCmd for WorkspaceDiffCmd {
 
         let remote_repo = api::client::repositories::get_default_remote(&repo).await?;
 
-        let diff = api::client::workspaces::data_frames::diff(
-            &remote_repo,
-            workspace_id,
-            &opts.path_1,
-            DEFAULT_PAGE_NUM,
-            DEFAULT_PAGE_SIZE,
-        )
-        .await?;
-        let remote_df = diff.view.to_df();
-        println!("{:?}", remote_df);
+        let diff = api::client::workspaces::diff(&remote_repo, workspace_id, &opts.path_1).await?;
+        if diff.files.is_empty() {
+            println!("No changes in workspace {workspace_id} for {:?}", diff.path);
+            return Ok(());
+        }
+
+        for file in diff.files.iter() {
+            let line = match file.status {
+                StagedEntryStatus::Added => format!("  added:    {}", file.path.display()).green(),
+                StagedEntryStatus::Modified => {
+                    format!("  modified: {}", file.path.display()).yellow()
+                }
+                StagedEntryStatus::Removed => format!("  removed:  {}", file.path.display()).red(),
+                StagedEntryStatus::Unmodified => continue,
+            };
+            println!("{line}");
+        }
+
+        if let Some(df_diff) = diff.data_frame {
+            println!(
+                "\nRows added: {} modified: {} removed: {}",
+                df_diff.counts.added, df_diff.counts.modified, df_diff.counts.removed
+            );
+            println!("{:?}", df_diff.rows.view.to_df());
+        }
 
         // TODO: Allow them to save a remote diff to disk
 
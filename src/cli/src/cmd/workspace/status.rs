@@ -2,7 +2,6 @@ use async_trait::async_trait;
 use clap::{Arg, ArgMatches, Command};
 
 use liboxen::api;
-use liboxen::error;
 use liboxen::error::OxenError;
 use liboxen::model::staged_data::StagedDataOpts;
 use liboxen::model::LocalRepository;
@@ -91,8 +90,8 @@ impl RunCmd for WorkspaceStatusCmd {
             ignore: None,
         };
 
-        let repo_dir = util::fs::get_repo_root_from_current_dir()
-            .ok_or(OxenError::basic_str(error::NO_REPO_FOUND))?;
+        let repo_dir =
+            util::fs::get_repo_root_from_current_dir().ok_or(OxenError::local_repo_not_found())?;
 
         let repository = LocalRepository::from_dir(&repo_dir)?;
 
@@ -0,0 +1,348 @@
+//! Exit codes of the oxen CLI, so scripts and CI can tell failures apart.
+//!
+//! | Code | Meaning                                                        |
+//! |------|----------------------------------------------------------------|
+//! | 0    | Success                                                        |
+//! | 1    | Any error not covered below                                    |
+//! | 2    | Invalid arguments or unknown command                           |
+//! | 3    | Not inside an oxen repository, or the repository was not found |
+//! | 4    | Branch, revision, workspace, path or other resource not found  |
+//! | 5    | Nothing to commit                                              |
+//! | 6    | Merge conflict                                                 |
+//! | 7    | Authentication failed or no auth token configured              |
+//! | 8    | Network or remote error                                        |
+//! | 9    | Repository migration or oxen update required                   |
+//! | 10   | Repository is frozen, or the branch is locked or protected     |
+//! | 11   | Invalid data, schema, file type or query                       |
+//! | 12   | Operation cancelled by the user                                |
+//! | 13   | Local filesystem or database error                             |
+//!
+//! Commands run with `--json` print failures as json on stdout instead, with the stable
+//! error code from `OxenError::code`:
+//!
+//! ```text
+//! {"error":{"code":"revision_not_found","message":"..."},"exit_code":4}
+//! ```
+//!
+
+use clap::ArgMatches;
+use serde_json::json;
+use std::process::ExitCode;
+
+use liboxen::error::OxenError;
+
+pub const SUCCESS: u8 = 0;
+pub const GENERAL_ERROR: u8 = 1;
+pub const USAGE_ERROR: u8 = 2;
+pub const REPO_NOT_FOUND: u8 = 3;
+pub const NOT_FOUND: u8 = 4;
+pub const NOTHING_TO_COMMIT: u8 = 5;
+pub const MERGE_CONFLICT: u8 = 6;
+pub const AUTHENTICATION: u8 = 7;
+pub const REMOTE_ERROR: u8 = 8;
+pub const UPGRADE_REQUIRED: u8 = 9;
+pub const LOCKED: u8 = 10;
+pub const INVALID_DATA: u8 = 11;
+pub const CANCELLED: u8 = 12;
+pub const LOCAL_IO_ERROR: u8 = 13;
+
+/// Map an error to the exit code documented above
+pub fn from_error(err: &OxenError) -> ExitCode {
+    ExitCode::from(code_for_error(err))
+}
+
+pub fn code_for_error(err: &OxenError) -> u8 {
+    match err {
+        OxenError::LocalRepoNotFound(_) | OxenError::RepoNotFound(_) => REPO_NOT_FOUND,
+
+        OxenError::BranchNotFound(_)
+        | OxenError::RevisionNotFound(_)
+        | OxenError::HeadNotFound(_)
+        | OxenError::NoCommitsFound(_)
+        | OxenError::WorkspaceNotFound(_)
+        | OxenError::QueryableWorkspaceNotFound()
+        | OxenError::ResourceNotFound(_)
+        | OxenError::PathDoesNotExist(_)
+        | OxenError::ParsedResourceNotFound(_)
+        | OxenError::CommitEntryNotFound(_)
+        | OxenError::UserConfigNotFound(_) => NOT_FOUND,
+
+        OxenError::NothingToCommit(_) => NOTHING_TO_COMMIT,
+
+        OxenError::MergeConflict(_) | OxenError::UpstreamMergeConflict(_) => MERGE_CONFLICT,
+
+        OxenError::Authentication(_) => AUTHENTICATION,
+
+        OxenError::RemoteRepoNotFound(_)
+        | OxenError::RemoteAheadOfLocal(_)
+        | OxenError::IncompleteLocalHistory(_)
+        | OxenError::RootCommitDoesNotMatch(_)
+        | OxenError::WorkspaceBehind(_)
+        | OxenError::NetworkError(_)
+        | OxenError::Offline(_)
+        | OxenError::RemoteRejected(_)
+        | OxenError::QuotaExceeded(_)
+        | OxenError::PushRejected(_)
+        | OxenError::ChecksumMismatch(_)
+        | OxenError::HTTP(_)
+        | OxenError::URI(_)
+        | OxenError::URL(_) => REMOTE_ERROR,
+
+        OxenError::MigrationRequired(_)
+        | OxenError::OxenUpdateRequired(_)
+        | OxenError::InvalidVersion(_) => UPGRADE_REQUIRED,
+
+        OxenError::RepoFrozen(_)
+        | OxenError::RemoteBranchLocked(_)
+        | OxenError::BranchProtected(_) => LOCKED,
+
+        OxenError::InvalidSchema(_)
+        | OxenError::IncompatibleSchemas(_)
+        | OxenError::SchemaMismatch(_)
+        | OxenError::ParseError(_)
+        | OxenError::InvalidFileType(_)
+        | OxenError::ColumnNameAlreadyExists(_)
+        | OxenError::ColumnNameNotFound(_)
+        | OxenError::UnsupportedOperation(_)
+        | OxenError::SQLParseError(_)
+        | OxenError::DataFrameError(_)
+        | OxenError::PolarsError(_)
+        | OxenError::ImageMetadataParseError(_) => INVALID_DATA,
+
+        OxenError::OperationCancelled(_) => CANCELLED,
+
+        OxenError::IO(_)
+        | OxenError::StripPrefixError(_)
+        | OxenError::DB(_)
+        | OxenError::JwalkError(_) => LOCAL_IO_ERROR,
+
+        _ => GENERAL_ERROR,
+    }
+}
+
+/// Whether the command, or any of its subcommands, was run with `--json`
+pub fn wants_json(args: &ArgMatches) -> bool {
+    let mut args = args;
+    loop {
+        if let Ok(Some(true)) = args.try_get_one::<bool>("json") {
+            return true;
+        }
+        match args.subcommand() {
+            Some((_, sub_args)) => args = sub_args,
+            None => return false,
+        }
+    }
+}
+
+/// The error as json, for commands run with `--json`
+pub fn error_json(err: &OxenError) -> serde_json::Value {
+    json!({
+        "error": {
+            "code": err.code(),
+            "message": err.to_string().trim(),
+        },
+        "exit_code": code_for_error(err),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use liboxen::error::{OxenError, StringError};
+    use liboxen::model::{Branch, Commit, Remote, RepoNew, Schema};
+    use time::OffsetDateTime;
+
+    use crate::exit_codes;
+
+    fn message() -> StringError {
+        StringError::from("failed")
+    }
+
+    #[test]
+    fn test_code_for_error() {
+        let commit = Commit {
+            id: String::from("abc"),
+            parent_ids: vec![],
+            message: String::from("root"),
+            author: String::from("ox"),
+            email: String::from("ox@oxen.ai"),
+            root_hash: None,
+            timestamp: OffsetDateTime::now_utc(),
+            co_authors: vec![],
+        };
+        let branch = Branch {
+            name: String::from("main"),
+            commit_id: String::from("abc"),
+        };
+        let http_error = reqwest::Client::new().get("not a url").build().unwrap_err();
+        let uri_error = "\n".parse::<http::Uri>().unwrap_err();
+        let Err(db_error) = rocksdb::DB::open_for_read_only(
+            &rocksdb::Options::default(),
+            "/oxen/exit_codes/does/not/exist",
+            false,
+        ) else {
+            panic!("opened a db that does not exist");
+        };
+        let jwalk_error = jwalk::WalkDir::new("/oxen/exit_codes/does/not/exist")
+            .into_iter()
+            .find_map(|entry| entry.err())
+            .unwrap();
+
+        let cases = vec![
+            (
+                OxenError::local_repo_not_found(),
+                exit_codes::REPO_NOT_FOUND,
+            ),
+            (
+                OxenError::repo_not_found(RepoNew::from_namespace_name("ox", "data")),
+                exit_codes::REPO_NOT_FOUND,
+            ),
+            (
+                OxenError::BranchNotFound(Box::new(message())),
+                exit_codes::NOT_FOUND,
+            ),
+            (
+                OxenError::revision_not_found(message()),
+                exit_codes::NOT_FOUND,
+            ),
+            (OxenError::head_not_found(), exit_codes::NOT_FOUND),
+            (OxenError::no_commits_found(), exit_codes::NOT_FOUND),
+            (
+                OxenError::workspace_not_found(message()),
+                exit_codes::NOT_FOUND,
+            ),
+            (
+                OxenError::QueryableWorkspaceNotFound(),
+                exit_codes::NOT_FOUND,
+            ),
+            (OxenError::resource_not_found("x"), exit_codes::NOT_FOUND),
+            (OxenError::path_does_not_exist("x"), exit_codes::NOT_FOUND),
+            (
+                OxenError::ParsedResourceNotFound(Box::new(std::path::Path::new("x").into())),
+                exit_codes::NOT_FOUND,
+            ),
+            (
+                OxenError::CommitEntryNotFound(message()),
+                exit_codes::NOT_FOUND,
+            ),
+            (OxenError::email_and_name_not_set(), exit_codes::NOT_FOUND),
+            (
+                OxenError::nothing_to_commit(),
+                exit_codes::NOTHING_TO_COMMIT,
+            ),
+            (OxenError::merge_conflict("x"), exit_codes::MERGE_CONFLICT),
+            (
+                OxenError::upstream_merge_conflict(),
+                exit_codes::MERGE_CONFLICT,
+            ),
+            (OxenError::auth_token_not_set(), exit_codes::AUTHENTICATION),
+            (
+                OxenError::RemoteRepoNotFound(Box::new(Remote {
+                    name: String::from("origin"),
+                    url: String::from("http://localhost:3000/ox/data"),
+                })),
+                exit_codes::REMOTE_ERROR,
+            ),
+            (OxenError::remote_ahead_of_local(), exit_codes::REMOTE_ERROR),
+            (
+                OxenError::incomplete_local_history(),
+                exit_codes::REMOTE_ERROR,
+            ),
+            (
+                OxenError::root_commit_does_not_match(commit),
+                exit_codes::REMOTE_ERROR,
+            ),
+            (
+                OxenError::workspace_behind(branch),
+                exit_codes::REMOTE_ERROR,
+            ),
+            (OxenError::network_error("x"), exit_codes::REMOTE_ERROR),
+            (OxenError::offline("x"), exit_codes::REMOTE_ERROR),
+            (
+                OxenError::remote_rejected(400, "bad_request", "x"),
+                exit_codes::REMOTE_ERROR,
+            ),
+            (OxenError::quota_exceeded("x"), exit_codes::REMOTE_ERROR),
+            (OxenError::push_rejected("x"), exit_codes::REMOTE_ERROR),
+            (
+                OxenError::checksum_mismatch(1, "report.jsonl"),
+                exit_codes::REMOTE_ERROR,
+            ),
+            (OxenError::HTTP(http_error), exit_codes::REMOTE_ERROR),
+            (OxenError::URI(uri_error), exit_codes::REMOTE_ERROR),
+            (
+                OxenError::URL(url::ParseError::EmptyHost),
+                exit_codes::REMOTE_ERROR,
+            ),
+            (
+                OxenError::migration_required("x"),
+                exit_codes::UPGRADE_REQUIRED,
+            ),
+            (
+                OxenError::oxen_update_required("x"),
+                exit_codes::UPGRADE_REQUIRED,
+            ),
+            (
+                OxenError::invalid_version("x"),
+                exit_codes::UPGRADE_REQUIRED,
+            ),
+            (OxenError::repo_is_frozen(), exit_codes::LOCKED),
+            (OxenError::remote_branch_locked(), exit_codes::LOCKED),
+            (OxenError::branch_protected("x"), exit_codes::LOCKED),
+            (
+                OxenError::InvalidSchema(Box::new(Schema::new(vec![]))),
+                exit_codes::INVALID_DATA,
+            ),
+            (
+                OxenError::incompatible_schemas(Schema::new(vec![])),
+                exit_codes::INVALID_DATA,
+            ),
+            (OxenError::schema_mismatch("x"), exit_codes::INVALID_DATA),
+            (OxenError::parse_error("x"), exit_codes::INVALID_DATA),
+            (OxenError::invalid_file_type("x"), exit_codes::INVALID_DATA),
+            (
+                OxenError::column_name_already_exists("x"),
+                exit_codes::INVALID_DATA,
+            ),
+            (
+                OxenError::column_name_not_found("x"),
+                exit_codes::INVALID_DATA,
+            ),
+            (
+                OxenError::UnsupportedOperation(message()),
+                exit_codes::INVALID_DATA,
+            ),
+            (OxenError::sql_parse_error("x"), exit_codes::INVALID_DATA),
+            (
+                OxenError::DataFrameError(message()),
+                exit_codes::INVALID_DATA,
+            ),
+            (
+                OxenError::PolarsError(polars::prelude::PolarsError::NoData("x".into())),
+                exit_codes::INVALID_DATA,
+            ),
+            (
+                OxenError::image_metadata_error("x"),
+                exit_codes::INVALID_DATA,
+            ),
+            (OxenError::operation_cancelled(), exit_codes::CANCELLED),
+            (
+                OxenError::IO(std::io::Error::other("x")),
+                exit_codes::LOCAL_IO_ERROR,
+            ),
+            (
+                OxenError::StripPrefixError(message()),
+                exit_codes::LOCAL_IO_ERROR,
+            ),
+            (OxenError::DB(db_error), exit_codes::LOCAL_IO_ERROR),
+            (
+                OxenError::JwalkError(jwalk_error),
+                exit_codes::LOCAL_IO_ERROR,
+            ),
+            (OxenError::basic_str("x"), exit_codes::GENERAL_ERROR),
+        ];
+
+        for (err, code) in cases {
+            assert_eq!(exit_codes::code_for_error(&err), code, "{err:?}");
+        }
+    }
+}
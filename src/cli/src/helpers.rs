@@ -4,10 +4,14 @@ use liboxen::command::migrate::Migrate;
 use liboxen::command::migrate::UpdateVersionFilesMigration;
 use liboxen::config::AuthConfig;
 use liboxen::constants;
+use liboxen::core::transfer;
 use liboxen::error::OxenError;
-use liboxen::model::LocalRepository;
+use liboxen::model::{LocalRepository, RemoteBranch};
+use liboxen::repositories;
+use liboxen::util;
 use liboxen::util::oxen_version::OxenVersion;
 
+use clap::{Arg, ArgMatches};
 use colored::Colorize;
 
 use std::str::FromStr;
@@ -31,7 +35,57 @@ pub fn get_host_from_repo(repo: &LocalRepository) -> Result<String, OxenError> {
     get_host_or_default()
 }
 
+/// Host of the named remote, falls back to the current remote if it is not set
+pub fn get_host_from_remote(repo: &LocalRepository, name: &str) -> Result<String, OxenError> {
+    if let Some(remote) = repo.get_remote(name) {
+        return api::client::get_host_from_url(remote.url);
+    }
+    get_host_from_repo(repo)
+}
+
+/// Where `oxen push` and `oxen pull` go when the remote or branch is left off.
+/// Returns the local branch and the remote branch: the upstream the local branch
+/// tracks if any, else the current remote and a branch of the same name.
+pub fn resolve_upstream(
+    repo: &LocalRepository,
+    remote: Option<&String>,
+    branch: Option<&String>,
+) -> Result<(String, RemoteBranch), OxenError> {
+    let local_branch = match branch {
+        Some(branch) => branch.to_owned(),
+        None => repositories::branches::current_branch(repo)?
+            .map(|b| b.name)
+            .unwrap_or(String::from(constants::DEFAULT_BRANCH_NAME)),
+    };
+    let upstream = repo.upstream(&local_branch);
+
+    let remote = match (remote, &upstream) {
+        (Some(remote), _) => remote.to_owned(),
+        (None, Some(upstream)) => upstream.remote.clone(),
+        (None, None) => repo
+            .remote()
+            .map(|r| r.name)
+            .unwrap_or(String::from(constants::DEFAULT_REMOTE_NAME)),
+    };
+    let remote_branch = match upstream {
+        Some(upstream) if branch.is_none() && upstream.remote == remote => upstream.branch,
+        _ => local_branch.clone(),
+    };
+
+    Ok((
+        local_branch,
+        RemoteBranch {
+            remote,
+            branch: remote_branch,
+        },
+    ))
+}
+
 pub async fn check_remote_version(host: impl AsRef<str>) -> Result<(), OxenError> {
+    // Only a warning, nothing to do without a network
+    if util::offline::is_offline() {
+        return Ok(());
+    }
     // Do the version check in the dispatch because it's only really the CLI that needs to do it
     match api::client::version::get_remote_version(host.as_ref()).await {
         Ok(remote_version) => {
@@ -51,6 +105,7 @@ pub async fn check_remote_version(host: impl AsRef<str>) -> Result<(), OxenError
 }
 
 pub async fn check_remote_version_blocking(host: impl AsRef<str>) -> Result<(), OxenError> {
+    util::offline::ensure_online(host.as_ref())?;
     match api::client::version::get_min_oxen_version(host.as_ref()).await {
         Ok(remote_version) => {
             let local_version: &str = constants::OXEN_VERSION;
@@ -104,3 +159,21 @@ pub fn check_repo_migration_needed(repo: &LocalRepository) -> Result<(), OxenErr
         "Error: Migration required".to_string().into(),
     ))
 }
+
+pub const LIMIT_RATE_ARG: &str = "limit-rate";
+
+/// Shared `--limit-rate` arg for the commands that transfer data
+pub fn limit_rate_arg() -> Arg {
+    Arg::new(LIMIT_RATE_ARG)
+        .long(LIMIT_RATE_ARG)
+        .value_name("RATE")
+        .help("Limit the transfer rate in bytes per second. Use K, M or G for KiB, MiB or GiB, like 10M.")
+        .action(clap::ArgAction::Set)
+}
+
+pub fn apply_limit_rate(args: &ArgMatches) -> Result<(), OxenError> {
+    if let Some(rate) = args.get_one::<String>(LIMIT_RATE_ARG) {
+        transfer::set_limit_rate(Some(transfer::parse_rate(rate)?));
+    }
+    Ok(())
+}
@@ -1,11 +1,12 @@
 use std::collections::HashMap;
 use std::process::ExitCode;
 
-use clap::Command;
+use clap::{Arg, ArgAction, Command};
 use liboxen::util;
 // use env_logger::Env;
 
 pub mod cmd;
+pub mod exit_codes;
 pub mod helpers;
 
 #[tokio::main]
@@ -14,37 +15,60 @@ async fn main() -> ExitCode {
 
     let cmds: Vec<Box<dyn cmd::RunCmd>> = vec![
         Box::new(cmd::AddCmd),
+        Box::new(cmd::AdminCmd),
         Box::new(cmd::BranchCmd),
+        Box::new(cmd::BundleCmd),
         Box::new(cmd::CheckoutCmd),
+        Box::new(cmd::CleanCmd),
         Box::new(cmd::CloneCmd),
         Box::new(cmd::CommitCacheCmd),
         Box::new(cmd::CommitCmd),
+        Box::new(cmd::CommitStateCmd),
         Box::new(cmd::ConfigCmd),
+        Box::new(cmd::CpCmd),
         Box::new(cmd::CreateRemoteCmd),
         Box::new(cmd::DbCmd),
+        Box::new(cmd::DedupCmd),
         Box::new(cmd::DeleteRemoteCmd),
         Box::new(cmd::DFCmd),
         Box::new(cmd::DiffCmd),
         Box::new(cmd::DownloadCmd),
+        Box::new(cmd::EncryptCmd),
+        Box::new(cmd::ExportCmd),
         Box::new(cmd::FetchCmd),
+        Box::new(cmd::FreezeCmd),
+        Box::new(cmd::ImportCmd),
         Box::new(cmd::InfoCmd),
         Box::new(cmd::InitCmd),
         Box::new(cmd::LoadCmd),
         Box::new(cmd::LogCmd),
+        Box::new(cmd::LsCmd),
         Box::new(cmd::MergeCmd),
+        Box::new(cmd::MergeBaseCmd),
+        Box::new(cmd::MergeQueueCmd),
         Box::new(cmd::MigrateCmd),
         Box::new(cmd::MooCmd),
+        #[cfg(feature = "mount")]
+        Box::new(cmd::MountCmd),
+        Box::new(cmd::MvCmd),
         Box::new(cmd::NodeCmd),
         Box::new(cmd::PackCmd),
+        Box::new(cmd::PinCmd),
+        Box::new(cmd::PrCmd),
         Box::new(cmd::PullCmd),
         Box::new(cmd::PushCmd),
         Box::new(cmd::RestoreCmd),
         Box::new(cmd::ReadLinesCmd),
+        Box::new(cmd::ReleaseCmd),
         Box::new(cmd::RemoteCmd),
+        Box::new(cmd::RepackCmd),
         Box::new(cmd::RmCmd),
         Box::new(cmd::SaveCmd),
+        Box::new(cmd::ScanCmd),
         Box::new(cmd::SchemasCmd),
+        Box::new(cmd::ShowCmd),
         Box::new(cmd::StatusCmd),
+        Box::new(cmd::ThawCmd),
         Box::new(cmd::TreeCmd),
         Box::new(cmd::UploadCmd),
         Box::new(cmd::UnpackCmd),
@@ -56,7 +80,14 @@ async fn main() -> ExitCode {
         .about("🐂 is a machine learning dataset management toolchain")
         .subcommand_required(true)
         .arg_required_else_help(true)
-        .allow_external_subcommands(true);
+        .allow_external_subcommands(true)
+        .arg(
+            Arg::new("offline")
+                .long("offline")
+                .global(true)
+                .help("Never connect to a remote. Commands that need one fail right away.")
+                .action(ArgAction::SetTrue),
+        );
 
     // Add all the commands to the command line
     let mut runners: HashMap<String, Box<dyn cmd::RunCmd>> = HashMap::new();
@@ -67,6 +98,9 @@ async fn main() -> ExitCode {
 
     // Parse the command line args and run the appropriate command
     let matches = command.get_matches();
+    if matches.get_flag("offline") {
+        util::offline::set_offline(true);
+    }
     match matches.subcommand() {
         // TODO: Get these in the help command instead of just falling back
         Some((command, args)) => {
@@ -75,17 +109,21 @@ async fn main() -> ExitCode {
                 match runner.run(args).await {
                     Ok(_) => {}
                     Err(err) => {
-                        eprintln!("{err}");
-                        return ExitCode::FAILURE;
+                        if exit_codes::wants_json(args) {
+                            println!("{}", exit_codes::error_json(&err));
+                        } else {
+                            eprintln!("{err}");
+                        }
+                        return exit_codes::from_error(&err);
                     }
                 }
             } else {
                 eprintln!("Unknown command `oxen {command}`");
-                return ExitCode::FAILURE;
+                return ExitCode::from(exit_codes::USAGE_ERROR);
             }
         }
         _ => unreachable!(), // If all subcommands are defined above, anything else is unreachable!()
     }
 
-    ExitCode::SUCCESS
+    ExitCode::from(exit_codes::SUCCESS)
 }
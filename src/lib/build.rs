@@ -0,0 +1,33 @@
+// Generates the gRPC sync service from proto/sync.proto when the `grpc` feature is on.
+// The root package builds liboxen from src/lib as well, so look for the protos in both places.
+
+fn main() {
+    #[cfg(feature = "grpc")]
+    compile_protos();
+}
+
+#[cfg(feature = "grpc")]
+fn compile_protos() {
+    use std::path::PathBuf;
+
+    let manifest_dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap());
+    let proto_dir = [
+        manifest_dir.join("proto"),
+        manifest_dir.join("src").join("lib").join("proto"),
+    ]
+    .into_iter()
+    .find(|dir| dir.exists())
+    .expect("Could not find the proto dir");
+    let proto = proto_dir.join("sync.proto");
+    println!("cargo:rerun-if-changed={}", proto.display());
+
+    // Don't require protoc on the build machine
+    if std::env::var_os("PROTOC").is_none() {
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("Could not find protoc");
+        std::env::set_var("PROTOC", protoc);
+    }
+
+    tonic_build::configure()
+        .compile_protos(&[proto], &[proto_dir])
+        .expect("Could not compile the gRPC protos");
+}
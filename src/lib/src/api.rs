@@ -5,3 +5,5 @@
 
 pub mod client;
 pub mod endpoint;
+#[cfg(feature = "grpc")]
+pub mod grpc;
@@ -1,28 +1,46 @@
 //! # API Client - For interacting with repositories on a remote machine
 //!
 
-use crate::config::AuthConfig;
+use crate::config::{AuthConfig, HostConfig};
 use crate::error::OxenError;
+use crate::util;
 use crate::view::http;
 use crate::view::OxenResponse;
 
 pub use reqwest::Url;
 use reqwest::{header, Client, ClientBuilder, IntoUrl};
+pub use retry::SendWithRetry;
+use std::time::Duration;
 
 pub mod branches;
+pub mod comments;
 pub mod commits;
 pub mod compare;
+pub mod copy;
 pub mod data_frames;
+pub mod dataset_cards;
 pub mod diff;
 pub mod dir;
 pub mod entries;
+#[cfg(feature = "faults")]
+pub mod faults;
+pub mod freeze;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod merge_queue;
+pub mod merge_requests;
 pub mod merger;
 pub mod metadata;
+pub mod mirrors;
+pub mod releases;
 pub mod repositories;
+pub mod retry;
 pub mod schemas;
 pub mod stats;
+pub mod tokens;
 pub mod tree;
 pub mod version;
+pub mod versions;
 pub mod workspaces;
 
 const VERSION: &str = crate::constants::OXEN_VERSION;
@@ -61,10 +79,33 @@ pub fn builder_for_url<U: IntoUrl>(url: U) -> Result<ClientBuilder, OxenError> {
     builder_for_host(host, true)
 }
 
+/// Same as [builder_for_url] without the oxen auth token, for urls that are not oxen remotes
+/// (ex: notifications). The offline mode and the timeouts, proxy and certificates configured
+/// for the host still apply.
+pub fn builder_for_url_without_auth<U: IntoUrl>(url: U) -> Result<ClientBuilder, OxenError> {
+    let host = get_host_from_url(url)?;
+    util::offline::ensure_online(&host)?;
+
+    let builder = builder();
+    let config = match AuthConfig::get() {
+        Ok(config) => config,
+        Err(err) => {
+            log::debug!("remote::client::builder_for_url_without_auth error getting config: {err}");
+            return Ok(builder);
+        }
+    };
+    match config.host_config(&host) {
+        Some(host_config) => with_host_config(builder, host_config),
+        None => Ok(builder),
+    }
+}
+
 fn builder_for_host<S: AsRef<str>>(
     host: S,
     should_add_user_agent: bool,
 ) -> Result<ClientBuilder, OxenError> {
+    util::offline::ensure_online(host.as_ref())?;
+
     let builder = if should_add_user_agent {
         builder()
     } else {
@@ -79,7 +120,15 @@ fn builder_for_host<S: AsRef<str>>(
             return Ok(builder);
         }
     };
-    if let Some(auth_token) = config.auth_token_for_host(host.as_ref()) {
+    let auth_host = host.as_ref().to_string();
+    // Requests through a fault injection proxy use the auth token of the real host
+    #[cfg(feature = "faults")]
+    let auth_host = faults::upstream_host(&auth_host);
+    let builder = match config.host_config(&auth_host) {
+        Some(host_config) => with_host_config(builder, host_config)?,
+        None => builder,
+    };
+    if let Some(auth_token) = config.auth_token_for_host(&auth_host) {
         log::debug!("Setting auth token for host: {}", host.as_ref());
         let auth_header = format!("Bearer {auth_token}");
         let mut auth_value = match header::HeaderValue::from_str(auth_header.as_str()) {
@@ -101,6 +150,46 @@ fn builder_for_host<S: AsRef<str>>(
     }
 }
 
+/// Apply the timeouts, proxy and extra root certificates configured for the host
+fn with_host_config(
+    mut builder: ClientBuilder,
+    config: &HostConfig,
+) -> Result<ClientBuilder, OxenError> {
+    if let Some(secs) = config.connect_timeout_secs {
+        builder = builder.connect_timeout(Duration::from_secs(secs));
+    }
+    if let Some(secs) = config.read_timeout_secs {
+        builder = builder.read_timeout(Duration::from_secs(secs));
+    }
+    if let Some(proxy) = &config.proxy {
+        let proxy = reqwest::Proxy::all(proxy).map_err(|err| {
+            OxenError::basic_str(format!(
+                "Invalid proxy {proxy:?} for host {}: {err}",
+                config.host
+            ))
+        })?;
+        builder = builder.proxy(proxy);
+    }
+    if let Some(path) = &config.ca_cert {
+        let pem = std::fs::read(path).map_err(|err| {
+            OxenError::basic_str(format!(
+                "Could not read CA certificates {path:?} for host {}: {err}",
+                config.host
+            ))
+        })?;
+        let certs = reqwest::Certificate::from_pem_bundle(&pem).map_err(|err| {
+            OxenError::basic_str(format!(
+                "Invalid CA certificates {path:?} for host {}: {err}",
+                config.host
+            ))
+        })?;
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+    Ok(builder)
+}
+
 fn builder() -> ClientBuilder {
     Client::builder().user_agent(format!("{USER_AGENT}/{VERSION}"))
 }
@@ -190,15 +279,35 @@ fn parse_status_and_message(
         }
         http::STATUS_ERROR => {
             log::debug!("Status error: {status}");
+            if response.status_message == http::MSG_REPO_FROZEN {
+                return Err(OxenError::repo_is_frozen());
+            }
+            if response.status_message == http::MSG_BRANCH_PROTECTED {
+                // The detail is the full message from the server
+                let msg = response.error_detail().unwrap_or(response.desc_or_msg());
+                return Err(OxenError::BranchProtected(format!("\n{msg}\n").into()));
+            }
+            if response.status_message == http::MSG_QUOTA_EXCEEDED {
+                let msg = response.error_detail().unwrap_or(response.desc_or_msg());
+                return Err(OxenError::QuotaExceeded(format!("\n{msg}\n").into()));
+            }
+            if response.status_message == http::MSG_PUSH_REJECTED {
+                let msg = response.error_detail().unwrap_or(response.desc_or_msg());
+                return Err(OxenError::PushRejected(format!("\n{msg}\n").into()));
+            }
             if let Some(msg) = response_msg_override {
                 if let Some(response_type) = response_type {
                     if response.desc_or_msg() == response_type {
-                        return Err(OxenError::basic_str(msg));
+                        return Err(OxenError::authentication(msg));
                     }
                 }
             }
 
-            Err(OxenError::basic_str(response.full_err_msg()))
+            Err(OxenError::remote_rejected(
+                status.as_u16(),
+                response.error_code(),
+                response.full_err_msg(),
+            ))
         }
         status => Err(OxenError::basic_str(format!("Unknown status [{status}]"))),
     }
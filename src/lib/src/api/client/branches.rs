@@ -1,12 +1,12 @@
 use crate::api;
 use crate::api::client;
+use crate::api::client::SendWithRetry;
 use crate::error::OxenError;
 use crate::model::{Branch, Commit, LocalRepository, RemoteRepository};
 use crate::view::{
     BranchLockResponse, BranchNewFromBranchName, BranchNewFromCommitId, BranchRemoteMerge,
-    BranchResponse, CommitResponse, ListBranchesResponse, StatusMessage,
+    BranchResponse, BranchUpdate, CommitResponse, ListBranchesResponse, StatusMessage,
 };
-use serde_json::json;
 
 pub async fn get_by_name(
     repository: &RemoteRepository,
@@ -16,7 +16,7 @@ pub async fn get_by_name(
     let url = api::endpoint::url_from_repo(repository, &uri)?;
 
     let client = client::new_for_url(&url)?;
-    if let Ok(res) = client.get(&url).send().await {
+    if let Ok(res) = client.get(&url).send_with_retry().await {
         let status = res.status();
         if 404 == status {
             return Ok(None);
@@ -60,7 +60,7 @@ pub async fn create_from_branch(
     })?;
 
     let client = client::new_for_url(&url)?;
-    let res = client.post(&url).body(params).send().await?;
+    let res = client.post(&url).body(params).send_with_retry().await?;
     let body = client::parse_json_body(&url, res).await?;
     let response: Result<BranchResponse, serde_json::Error> = serde_json::from_str(&body);
     match response {
@@ -93,7 +93,7 @@ pub async fn create_from_commit(
     })?;
 
     let client = client::new_for_url(&url)?;
-    let res = client.post(&url).body(params).send().await?;
+    let res = client.post(&url).body(params).send_with_retry().await?;
     let body = client::parse_json_body(&url, res).await?;
     let response: Result<BranchResponse, serde_json::Error> = serde_json::from_str(&body);
     match response {
@@ -113,7 +113,7 @@ pub async fn list(repository: &RemoteRepository) -> Result<Vec<Branch>, OxenErro
     let url = api::endpoint::url_from_repo(repository, "/branches")?;
 
     let client = client::new_for_url(&url)?;
-    if let Ok(res) = client.get(&url).send().await {
+    if let Ok(res) = client.get(&url).send_with_retry().await {
         let body = client::parse_json_body(&url, res).await?;
         let response: Result<ListBranchesResponse, serde_json::Error> = serde_json::from_str(&body);
         match response {
@@ -140,15 +140,43 @@ pub async fn update(
     branch_name: impl AsRef<str>,
     commit: &Commit,
 ) -> Result<Branch, OxenError> {
-    let branch_name = branch_name.as_ref();
+    put_update(repository, branch_name.as_ref(), commit, None).await
+}
+
+/// Point a remote branch at a commit that does not have to descend from its head, as
+/// long as the branch is still at `expected_commit_id`
+pub async fn update_with_lease(
+    repository: &RemoteRepository,
+    branch_name: impl AsRef<str>,
+    commit: &Commit,
+    expected_commit_id: impl AsRef<str>,
+) -> Result<Branch, OxenError> {
+    put_update(
+        repository,
+        branch_name.as_ref(),
+        commit,
+        Some(expected_commit_id.as_ref()),
+    )
+    .await
+}
+
+async fn put_update(
+    repository: &RemoteRepository,
+    branch_name: &str,
+    commit: &Commit,
+    expected_commit_id: Option<&str>,
+) -> Result<Branch, OxenError> {
     let uri = format!("/branches/{branch_name}");
     let url = api::endpoint::url_from_repo(repository, &uri)?;
     log::debug!("remote::branches::update url: {}", url);
 
-    let params = serde_json::to_string(&json!({ "commit_id": commit.id }))?;
+    let params = serde_json::to_string(&BranchUpdate {
+        commit_id: commit.id.to_owned(),
+        expected_commit_id: expected_commit_id.map(String::from),
+    })?;
 
     let client = client::new_for_url(&url)?;
-    if let Ok(res) = client.put(&url).body(params).send().await {
+    if let Ok(res) = client.put(&url).body(params).send_with_retry().await {
         let body = client::parse_json_body(&url, res).await?;
         let response: Result<BranchResponse, serde_json::Error> = serde_json::from_str(&body);
         match response {
@@ -186,7 +214,7 @@ pub async fn maybe_create_merge(
     let params = serde_json::to_string(&commits)?;
 
     let client = client::new_for_url(&url)?;
-    if let Ok(res) = client.put(&url).body(params).send().await {
+    if let Ok(res) = client.put(&url).body(params).send_with_retry().await {
         let body = client::parse_json_body(&url, res).await?;
         let response: Result<CommitResponse, serde_json::Error> = serde_json::from_str(&body);
         match response {
@@ -239,7 +267,7 @@ pub async fn delete(
     log::debug!("Deleting branch: {}", url);
 
     let client = client::new_for_url(&url)?;
-    if let Ok(res) = client.delete(&url).send().await {
+    if let Ok(res) = client.delete(&url).send_with_retry().await {
         let body = client::parse_json_body(&url, res).await?;
         let response: Result<StatusMessage, serde_json::Error> = serde_json::from_str(&body);
         match response {
@@ -264,7 +292,7 @@ pub async fn lock(
     log::debug!("Locking branch: {}", url);
 
     let client = client::new_for_url(&url)?;
-    if let Ok(res) = client.post(&url).send().await {
+    if let Ok(res) = client.post(&url).send_with_retry().await {
         let body = client::parse_json_body(&url, res).await?;
         let response: Result<StatusMessage, serde_json::Error> = serde_json::from_str(&body);
         match response {
@@ -285,7 +313,7 @@ pub async fn unlock(
     log::debug!("Unlocking branch: {}", url);
 
     let client = client::new_for_url(&url)?;
-    if let Ok(res) = client.post(&url).send().await {
+    if let Ok(res) = client.post(&url).send_with_retry().await {
         let body = client::parse_json_body(&url, res).await?;
         let response: Result<StatusMessage, serde_json::Error> = serde_json::from_str(&body);
         match response {
@@ -307,7 +335,7 @@ pub async fn is_locked(
     let url = api::endpoint::url_from_repo(repository, &uri)?;
     log::debug!("Checking if branch is locked: {}", url);
     let client = client::new_for_url(&url)?;
-    if let Ok(res) = client.get(&url).send().await {
+    if let Ok(res) = client.get(&url).send_with_retry().await {
         let body = client::parse_json_body(&url, res).await?;
         let response: Result<BranchLockResponse, serde_json::Error> = serde_json::from_str(&body);
         match response {
@@ -331,7 +359,7 @@ pub async fn latest_synced_commit(
     let url = api::endpoint::url_from_repo(repository, &uri)?;
     log::debug!("Retrieving latest synced commit for branch...");
     let client = client::new_for_url(&url)?;
-    if let Ok(res) = client.get(&url).send().await {
+    if let Ok(res) = client.get(&url).send_with_retry().await {
         let body = client::parse_json_body(&url, res).await?;
         let response: Result<CommitResponse, serde_json::Error> = serde_json::from_str(&body);
         match response {
@@ -0,0 +1,132 @@
+//! Discuss commits, files and rows with comments on the remote
+//!
+
+use crate::api;
+use crate::api::client;
+use crate::api::client::SendWithRetry;
+use crate::error::OxenError;
+use crate::model::{Comment, CommentFilter, CommentThread, NewComment, RemoteRepository};
+use crate::view::comment::{
+    CommentResponse, ListCommentThreadsResponse, ListCommentsResponse, UpdateCommentBody,
+};
+
+/// Add a comment or a reply on the remote
+pub async fn create(
+    remote_repo: &RemoteRepository,
+    new_comment: &NewComment,
+) -> Result<Comment, OxenError> {
+    let url = api::endpoint::url_from_repo(remote_repo, "/comments")?;
+    log::debug!("api::client::comments::create url: {url}");
+
+    let params = serde_json::to_string(new_comment)?;
+    let client = client::new_for_url(&url)?;
+    let res = client.post(&url).body(params).send_with_retry().await?;
+    parse_comment_response(&url, res, "create").await
+}
+
+/// List the comment threads on the remote that match the filter
+pub async fn list(
+    remote_repo: &RemoteRepository,
+    filter: &CommentFilter,
+) -> Result<Vec<CommentThread>, OxenError> {
+    let mut query: Vec<String> = vec![];
+    if let Some(commit_id) = &filter.commit_id {
+        query.push(format!("commit_id={}", urlencoding::encode(commit_id)));
+    }
+    if let Some(path) = &filter.path {
+        query.push(format!(
+            "path={}",
+            urlencoding::encode(&path.to_string_lossy())
+        ));
+    }
+    if let Some(row_hash) = &filter.row_hash {
+        query.push(format!("row_hash={}", urlencoding::encode(row_hash)));
+    }
+    let uri = if query.is_empty() {
+        String::from("/comments")
+    } else {
+        format!("/comments?{}", query.join("&"))
+    };
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!("api::client::comments::list url: {url}");
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send_with_retry().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<ListCommentThreadsResponse, serde_json::Error> =
+        serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val.threads),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::client::comments::list error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
+
+/// Get a comment on the remote by id
+pub async fn get(
+    remote_repo: &RemoteRepository,
+    id: impl AsRef<str>,
+) -> Result<Comment, OxenError> {
+    let uri = format!("/comments/{}", id.as_ref());
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!("api::client::comments::get url: {url}");
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send_with_retry().await?;
+    parse_comment_response(&url, res, "get").await
+}
+
+/// Replace the body of a comment on the remote
+pub async fn update(
+    remote_repo: &RemoteRepository,
+    id: impl AsRef<str>,
+    body: impl AsRef<str>,
+) -> Result<Comment, OxenError> {
+    let uri = format!("/comments/{}", id.as_ref());
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!("api::client::comments::update url: {url}");
+
+    let params = serde_json::to_string(&UpdateCommentBody {
+        body: body.as_ref().to_string(),
+    })?;
+    let client = client::new_for_url(&url)?;
+    let res = client.put(&url).body(params).send_with_retry().await?;
+    parse_comment_response(&url, res, "update").await
+}
+
+/// Delete a comment and its replies on the remote, returning the deleted comments
+pub async fn delete(
+    remote_repo: &RemoteRepository,
+    id: impl AsRef<str>,
+) -> Result<Vec<Comment>, OxenError> {
+    let uri = format!("/comments/{}", id.as_ref());
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!("api::client::comments::delete url: {url}");
+
+    let client = client::new_for_url(&url)?;
+    let res = client.delete(&url).send_with_retry().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<ListCommentsResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val.comments),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::client::comments::delete error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
+
+async fn parse_comment_response(
+    url: &str,
+    res: reqwest::Response,
+    action: &str,
+) -> Result<Comment, OxenError> {
+    let body = client::parse_json_body(url, res).await?;
+    let response: Result<CommentResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val.comment),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::client::comments::{action} error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
@@ -1,9 +1,11 @@
 use crate::api::client;
+use crate::api::client::SendWithRetry;
 use crate::constants::{
     COMMITS_DIR, DEFAULT_PAGE_NUM, DIRS_DIR, DIR_HASHES_DIR, HISTORY_DIR, OBJECTS_DIR, TREE_DIR,
 };
 
 use crate::core::db::{self};
+use crate::core::transfer;
 use crate::core::v0_10_0::commits::merge_objects_dbs;
 use crate::core::v0_10_0::index::{
     CommitDBReader, CommitEntryWriter, CommitReader, CommitWriter, Merger,
@@ -11,12 +13,17 @@ use crate::core::v0_10_0::index::{
 use crate::error::OxenError;
 use crate::model::commit::CommitWithBranchName;
 use crate::model::entry::unsynced_commit_entry::UnsyncedCommitEntries;
-use crate::model::{Branch, Commit, LocalRepository, MerkleHash, RemoteRepository};
+use crate::model::{
+    Branch, Commit, CommitState, CommitStateEntry, LocalRepository, MerkleHash, NewCommitState,
+    RemoteRepository, User,
+};
 use crate::opts::PaginateOpts;
 use crate::util::fs::oxen_hidden_dir;
 use crate::util::hasher::hash_buffer;
 use crate::util::progress_bar::{oxify_bar, ProgressBarType};
-use crate::view::commit::{CommitSyncStatusResponse, CommitTreeValidationResponse};
+use crate::view::commit::{
+    CommitStateResponse, CommitSyncStatusResponse, CommitTreeValidationResponse,
+};
 use crate::view::tree::merkle_hashes::MerkleHashes;
 use crate::{api, constants, repositories};
 use crate::{current_function, util};
@@ -57,7 +64,7 @@ pub async fn get_by_id(
     log::debug!("remote::commits::get_by_id {}", url);
 
     let client = client::new_for_url(&url)?;
-    if let Ok(res) = client.get(&url).send().await {
+    if let Ok(res) = client.get(&url).send_with_retry().await {
         if res.status() == 404 {
             return Ok(None);
         }
@@ -76,6 +83,80 @@ pub async fn get_by_id(
     }
 }
 
+/// Get the data quality state of a commit on the remote, if one has been set
+pub async fn get_state(
+    repository: &RemoteRepository,
+    revision: impl AsRef<str>,
+) -> Result<Option<CommitStateEntry>, OxenError> {
+    let revision = revision.as_ref();
+    let uri = format!("/commits/{revision}/state");
+    let url = api::endpoint::url_from_repo(repository, &uri)?;
+    log::debug!("remote::commits::get_state {}", url);
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send_with_retry().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<CommitStateResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(j_res) => Ok(j_res.state),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "get_state() Could not deserialize response [{err}]\n{body}"
+        ))),
+    }
+}
+
+/// Set the data quality state of a commit on the remote
+pub async fn set_state(
+    repository: &RemoteRepository,
+    revision: impl AsRef<str>,
+    state: CommitState,
+    user: &User,
+) -> Result<CommitStateEntry, OxenError> {
+    let revision = revision.as_ref();
+    let uri = format!("/commits/{revision}/state");
+    let url = api::endpoint::url_from_repo(repository, &uri)?;
+    log::debug!("remote::commits::set_state {}", url);
+
+    let body = NewCommitState {
+        state,
+        author: user.name.to_owned(),
+        email: user.email.to_owned(),
+    };
+    let params = serde_json::to_string(&body)?;
+
+    let client = client::new_for_url(&url)?;
+    let res = client.put(&url).body(params).send_with_retry().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<CommitStateResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(CommitStateResponse {
+            state: Some(state), ..
+        }) => Ok(state),
+        Ok(_) => Err(OxenError::basic_str(format!(
+            "set_state() No state returned for revision {revision}"
+        ))),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "set_state() Could not deserialize response [{err}]\n{body}"
+        ))),
+    }
+}
+
+/// Remove the data quality state from a commit on the remote
+pub async fn clear_state(
+    repository: &RemoteRepository,
+    revision: impl AsRef<str>,
+) -> Result<(), OxenError> {
+    let revision = revision.as_ref();
+    let uri = format!("/commits/{revision}/state");
+    let url = api::endpoint::url_from_repo(repository, &uri)?;
+    log::debug!("remote::commits::clear_state {}", url);
+
+    let client = client::new_for_url(&url)?;
+    let res = client.delete(&url).send_with_retry().await?;
+    client::parse_json_body(&url, res).await?;
+    Ok(())
+}
+
 /// List commits for a file
 pub async fn list_commits_for_path(
     remote_repo: &RemoteRepository,
@@ -92,7 +173,7 @@ pub async fn list_commits_for_path(
     );
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
     let client = client::new_for_url(&url)?;
-    let res = client.get(&url).send().await?;
+    let res = client.get(&url).send_with_retry().await?;
     let body = client::parse_json_body(&url, res).await?;
     let response: Result<PaginatedCommits, serde_json::Error> = serde_json::from_str(&body);
     match response {
@@ -153,7 +234,7 @@ pub async fn list_missing_hashes(
         .json(&MerkleHashes {
             hashes: commit_hashes,
         })
-        .send()
+        .send_idempotent()
         .await?;
     let body = client::parse_json_body(&url, res).await?;
     let response: Result<MerkleHashesResponse, serde_json::Error> = serde_json::from_str(&body);
@@ -217,7 +298,7 @@ async fn list_commit_history_paginated(
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
 
     let client = client::new_for_url(&url)?;
-    match client.get(&url).send().await {
+    match client.get(&url).send_with_retry().await {
         Ok(res) => {
             let body = client::parse_json_body(&url, res).await?;
             let response: Result<PaginatedCommits, serde_json::Error> = serde_json::from_str(&body);
@@ -244,7 +325,7 @@ async fn list_all_commits_paginated(
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
 
     let client = client::new_for_url(&url)?;
-    match client.get(&url).send().await {
+    match client.get(&url).send_with_retry().await {
         Ok(res) => {
             let body = client::parse_json_body(&url, res).await?;
             let response: Result<PaginatedCommits, serde_json::Error> = serde_json::from_str(&body);
@@ -270,7 +351,7 @@ pub async fn commit_is_synced(
     log::debug!("commit_is_synced checking URL: {}", url);
 
     let client = client::new_for_url(&url)?;
-    if let Ok(res) = client.get(&url).send().await {
+    if let Ok(res) = client.get(&url).send_with_retry().await {
         log::debug!("commit_is_synced Got response [{}]", res.status());
         if res.status() == 404 {
             return Ok(None);
@@ -302,7 +383,7 @@ pub async fn latest_commit_synced(
     log::debug!("latest_commit_synced checking URL: {}", url);
 
     let client = client::new_for_url(&url)?;
-    if let Ok(res) = client.get(&url).send().await {
+    if let Ok(res) = client.get(&url).send_with_retry().await {
         log::debug!("latest_commit_synced Got response [{}]", res.status());
         if res.status() == 404 {
             return Err(OxenError::basic_str("No synced commits found"));
@@ -379,7 +460,7 @@ pub async fn root_commit_maybe(
     log::debug!("remote::commits::root_commit {}", url);
 
     let client = client::new_for_url(&url)?;
-    if let Ok(res) = client.get(&url).send().await {
+    if let Ok(res) = client.get(&url).send_with_retry().await {
         let body = client::parse_json_body(&url, res).await?;
         log::debug!("api::client::commits::root_commit Got response {}", body);
         let response: Result<RootCommitResponse, serde_json::Error> = serde_json::from_str(&body);
@@ -475,7 +556,7 @@ pub async fn can_push(
 
     let client = client::new_for_url(&url)?;
 
-    if let Ok(res) = client.get(&url).send().await {
+    if let Ok(res) = client.get(&url).send_with_retry().await {
         log::debug!("can_push() request successful");
         let body = client::parse_json_body(&url, res).await?;
         let response: CommitTreeValidationResponse = serde_json::from_str(&body)?;
@@ -499,7 +580,7 @@ pub async fn download_commits_db_to_path(
     log::debug!("{} downloading from {}", current_function!(), url);
 
     let client = client::new_for_url(&url)?;
-    let res = client.get(url).send().await?;
+    let res = client.get(url).send_with_retry().await?;
 
     let dst = dst.as_ref();
     let reader = res
@@ -552,7 +633,7 @@ pub async fn download_objects_db_to_path(
     log::debug!("{} downloading from {}", current_function!(), url);
 
     let client = client::new_for_url(&url)?;
-    let res = client.get(url).send().await?;
+    let res = client.get(url).send_with_retry().await?;
 
     let dst = dst.as_ref();
 
@@ -638,7 +719,7 @@ pub async fn download_dir_hashes_from_url(
     let url = url.as_ref();
     log::debug!("{} downloading from {}", current_function!(), url);
     let client = client::new_for_url(url)?;
-    match client.get(url).send().await {
+    match client.get(url).send_with_retry().await {
         Ok(res) => {
             let path = path.as_ref();
             let reader = res
@@ -723,7 +804,7 @@ pub async fn download_dir_hashes_db_to_path(
     );
     log::debug!("{} downloading from {}", current_function!(), url);
     let client = client::new_for_url(&url)?;
-    match client.get(url).send().await {
+    match client.get(url).send_with_retry().await {
         Ok(res) => {
             let path = path.as_ref();
             let reader = res
@@ -801,7 +882,7 @@ pub async fn get_remote_parent(
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
 
     let client = client::new_for_url(&url)?;
-    if let Ok(res) = client.get(&url).send().await {
+    if let Ok(res) = client.get(&url).send_with_retry().await {
         let body = client::parse_json_body(&url, res).await?;
         let response: Result<ListCommitResponse, serde_json::Error> = serde_json::from_str(&body);
         match response {
@@ -835,7 +916,7 @@ pub async fn post_push_complete(
     .unwrap();
 
     let client = client::new_for_url(&url)?;
-    if let Ok(res) = client.post(&url).body(body).send().await {
+    if let Ok(res) = client.post(&url).body(body).send_with_retry().await {
         let body = client::parse_json_body(&url, res).await?;
         let response: Result<StatusMessage, serde_json::Error> = serde_json::from_str(&body);
         match response {
@@ -862,7 +943,7 @@ pub async fn bulk_post_push_complete(
     let body = serde_json::to_string(&json!(commits)).unwrap();
 
     let client = client::new_for_url(&url)?;
-    if let Ok(res) = client.post(&url).body(body).send().await {
+    if let Ok(res) = client.post(&url).body(body).send_with_retry().await {
         let body = client::parse_json_body(&url, res).await?;
         let response: Result<StatusMessage, serde_json::Error> = serde_json::from_str(&body);
         match response {
@@ -888,7 +969,7 @@ pub async fn get_commits_with_unsynced_dbs(
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
 
     let client = client::new_for_url(&url)?;
-    if let Ok(res) = client.get(&url).send().await {
+    if let Ok(res) = client.get(&url).send_with_retry().await {
         let body = client::parse_json_body(&url, res).await?;
         let response: Result<ListCommitResponse, serde_json::Error> = serde_json::from_str(&body);
         match response {
@@ -914,7 +995,7 @@ pub async fn get_commits_with_unsynced_entries(
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
 
     let client = client::new_for_url(&url)?;
-    if let Ok(res) = client.get(&url).send().await {
+    if let Ok(res) = client.get(&url).send_with_retry().await {
         let body = client::parse_json_body(&url, res).await?;
         let response: Result<ListCommitResponse, serde_json::Error> = serde_json::from_str(&body);
         match response {
@@ -1082,7 +1163,7 @@ pub async fn bulk_create_commit_obj_on_server(
     log::debug!("bulk_create_commit_obj_on_server {}\n{:?}", url, commits);
 
     let client = client::new_for_url(&url)?;
-    if let Ok(res) = client.post(&url).json(commits).send().await {
+    if let Ok(res) = client.post(&url).json(commits).send_with_retry().await {
         let body = client::parse_json_body(&url, res).await?;
         log::debug!("bulk_create_commit_obj_on_server got response {}", body);
         let response: Result<ListCommitResponse, serde_json::Error> = serde_json::from_str(&body);
@@ -1158,7 +1239,13 @@ async fn upload_single_tarball_to_server(
         .build()?;
 
     let size = buffer.len() as u64;
-    match client.post(&url).body(buffer.to_owned()).send().await {
+    transfer::throttle(size).await;
+    match client
+        .post(&url)
+        .body(buffer.to_owned())
+        .send_with_retry()
+        .await
+    {
         Ok(res) => {
             let body = client::parse_json_body(&url, res).await?;
 
@@ -1271,7 +1358,7 @@ pub async fn upload_data_chunk_to_server_with_retry(
     )))
 }
 
-async fn upload_data_chunk_to_server(
+pub async fn upload_data_chunk_to_server(
     remote_repo: &RemoteRepository,
     chunk: &[u8],
     hash: &str,
@@ -1307,7 +1394,14 @@ async fn upload_data_chunk_to_server(
         .timeout(time::Duration::from_secs(120))
         .build()?;
 
-    match client.post(&url).body(chunk.to_owned()).send().await {
+    transfer::throttle(chunk.len() as u64).await;
+    // The server stores chunks by number, so sending one again is safe
+    match client
+        .post(&url)
+        .body(chunk.to_owned())
+        .send_idempotent()
+        .await
+    {
         Ok(res) => {
             let body = client::parse_json_body(&url, res).await?;
 
@@ -1412,7 +1506,7 @@ mod tests {
             // Push it
             repositories::push(&local_repo).await?;
 
-            let is_synced = api::client::commits::commit_is_synced(&remote_repo, &commit.id)
+            let is_synced = test::wait_for_commit_synced(&remote_repo, &commit.id)
                 .await?
                 .unwrap();
             assert!(is_synced.is_valid);
@@ -1,5 +1,6 @@
 use crate::api;
 use crate::api::client;
+use crate::api::client::SendWithRetry;
 use crate::error::OxenError;
 use crate::model::{Commit, MerkleHash, RemoteRepository};
 use crate::view::compare::{CompareCommitsResponse, CompareEntries, CompareTabularResponse};
@@ -46,7 +47,11 @@ pub async fn create_compare(
 
     let client = client::new_for_url(&url)?;
 
-    let res = client.post(&url).json(&json!(req_body)).send().await?;
+    let res = client
+        .post(&url)
+        .json(&json!(req_body))
+        .send_with_retry()
+        .await?;
     let body = client::parse_json_body(&url, res).await?;
     let response: Result<CompareTabularResponse, serde_json::Error> = serde_json::from_str(&body);
     match response {
@@ -91,7 +96,12 @@ pub async fn update_compare(
 
     // let params =
 
-    if let Ok(res) = client.put(&url).json(&json!(req_body)).send().await {
+    if let Ok(res) = client
+        .put(&url)
+        .json(&json!(req_body))
+        .send_with_retry()
+        .await
+    {
         let body = client::parse_json_body(&url, res).await?;
         let response: Result<CompareTabularResponse, serde_json::Error> =
             serde_json::from_str(&body);
@@ -116,7 +126,7 @@ pub async fn get_derived_compare_df(
 
     let client = client::new_for_url(&url)?;
 
-    let res = client.get(&url).send().await?;
+    let res = client.get(&url).send_with_retry().await?;
     let body = client::parse_json_body(&url, res).await?;
     let response: Result<JsonDataFrameViewResponse, serde_json::Error> =
         serde_json::from_str(&body);
@@ -139,7 +149,7 @@ pub async fn commits(
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
 
     let client = client::new_for_url(&url)?;
-    let res = client.get(&url).send().await?;
+    let res = client.get(&url).send_with_retry().await?;
     let body = client::parse_json_body(&url, res).await?;
     let response: Result<CompareCommitsResponse, serde_json::Error> = serde_json::from_str(&body);
     match response {
@@ -161,7 +171,7 @@ pub async fn dir_tree(
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
 
     let client = client::new_for_url(&url)?;
-    let res = client.get(&url).send().await?;
+    let res = client.get(&url).send_with_retry().await?;
     let body = client::parse_json_body(&url, res).await?;
     let response: Result<DirTreeDiffResponse, serde_json::Error> = serde_json::from_str(&body);
     match response {
@@ -183,7 +193,7 @@ pub async fn entries(
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
 
     let client = client::new_for_url(&url)?;
-    let res = client.get(&url).send().await?;
+    let res = client.get(&url).send_with_retry().await?;
     let body = client::parse_json_body(&url, res).await?;
     let response: Result<CompareEntriesResponse, serde_json::Error> = serde_json::from_str(&body);
     match response {
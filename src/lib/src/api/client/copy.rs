@@ -0,0 +1,32 @@
+//! Copy files and directories between repositories on the same remote
+//!
+
+use crate::api;
+use crate::api::client;
+use crate::api::client::SendWithRetry;
+use crate::error::OxenError;
+use crate::model::{Commit, RemoteRepository};
+use crate::view::copy::CopyRequest;
+use crate::view::CommitResponse;
+
+/// Copy a file or directory from another repository on the same host into this one.
+/// The copy is done server side and committed to the requested branch.
+pub async fn copy(
+    remote_repo: &RemoteRepository,
+    request: &CopyRequest,
+) -> Result<Commit, OxenError> {
+    let url = api::endpoint::url_from_repo(remote_repo, "/copy")?;
+    log::debug!("api::client::copy::copy url: {url}");
+
+    let params = serde_json::to_string(request)?;
+    let client = client::new_for_url(&url)?;
+    let res = client.post(&url).body(params).send_with_retry().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<CommitResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val.commit),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::client::copy::copy error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
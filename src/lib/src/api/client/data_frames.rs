@@ -1,7 +1,11 @@
+use polars::prelude::DataFrame;
 use std::path::Path;
 
 use crate::api;
 use crate::api::client;
+use crate::api::client::SendWithRetry;
+use crate::constants;
+use crate::core::df::tabular;
 use crate::error::OxenError;
 use crate::model::RemoteRepository;
 use crate::opts::DFOpts;
@@ -20,7 +24,7 @@ pub async fn get(
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
 
     let client = client::new_for_url(&url)?;
-    match client.get(&url).send().await {
+    match client.get(&url).send_with_retry().await {
         Ok(res) => {
             let body = client::parse_json_body(&url, res).await?;
             log::debug!("got body: {}", body);
@@ -43,6 +47,39 @@ pub async fn get(
     }
 }
 
+/// Get the result of a query on a data frame as an Arrow IPC stream, so the column types
+/// survive the trip. Everything that matches is returned unless a page or slice is set.
+pub async fn get_arrow(
+    remote_repo: &RemoteRepository,
+    commit_or_branch: &str,
+    path: impl AsRef<Path>,
+    opts: DFOpts,
+) -> Result<DataFrame, OxenError> {
+    let path_str = util::fs::to_unix_str(path);
+    let mut query_str = opts.to_http_query_params();
+    if !query_str.is_empty() && !query_str.ends_with('&') {
+        query_str.push('&');
+    }
+    let uri = format!(
+        "/data_frames/{commit_or_branch}/{path_str}?{query_str}format={}",
+        constants::ARROW_FORMAT
+    );
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!("api::client::data_frames::get_arrow url: {url}");
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send_with_retry().await?;
+    // Errors still come back as json
+    if !res.status().is_success() {
+        let body = client::parse_json_body(&url, res).await?;
+        return Err(OxenError::basic_str(format!(
+            "api::client::data_frames::get_arrow unexpected response from {url}\n\n{body}"
+        )));
+    }
+    let bytes = res.bytes().await?;
+    tabular::df_from_arrow_stream(&bytes)
+}
+
 pub async fn index(
     remote_repo: &RemoteRepository,
     commit_or_branch: &str,
@@ -54,7 +91,7 @@ pub async fn index(
 
     let client = client::new_for_url(&url)?;
 
-    if let Ok(res) = client.post(&url).send().await {
+    if let Ok(res) = client.post(&url).send_with_retry().await {
         let body = client::parse_json_body(&url, res).await?;
         let response: Result<StatusMessage, serde_json::Error> = serde_json::from_str(&body);
 
@@ -83,7 +120,7 @@ pub async fn is_editable(
 
     let client = client::new_for_url(&url)?;
 
-    if let Ok(res) = client.post(&url).send().await {
+    if let Ok(res) = client.post(&url).send_with_retry().await {
         let body = client::parse_json_body(&url, res).await?;
         let response: Result<StatusMessage, serde_json::Error> = serde_json::from_str(&body);
 
@@ -235,6 +272,63 @@ mod tests {
         .await
     }
 
+    #[tokio::test]
+    async fn test_get_arrow_keeps_column_types() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|mut local_repo| async move {
+            let repo_dir = &local_repo.path;
+            let large_dir = repo_dir.join("large_files");
+            std::fs::create_dir_all(&large_dir)?;
+            let csv_file = large_dir.join("test.csv");
+            let from_file = test::test_200k_csv();
+            util::fs::copy(from_file, &csv_file)?;
+
+            repositories::add(&local_repo, &csv_file)?;
+            repositories::commit(&local_repo, "add test.csv")?;
+
+            // Set the proper remote
+            let remote = test::repo_remote_url_from(&local_repo.dirname());
+            command::config::set_remote(&mut local_repo, DEFAULT_REMOTE_NAME, &remote)?;
+
+            // Create the repo
+            let remote_repo = test::create_remote_repo(&local_repo).await?;
+
+            // Push the repo
+            repositories::push(&local_repo).await?;
+
+            // Not paginated by default, only the rows that match come back
+            let mut opts = DFOpts::empty();
+            opts.filter = Some(String::from("lefteye_x == 69"));
+            let df = api::client::data_frames::get_arrow(
+                &remote_repo,
+                DEFAULT_BRANCH_NAME,
+                "large_files/test.csv",
+                opts,
+            )
+            .await?;
+            assert!(df.height() > constants::DEFAULT_PAGE_SIZE);
+            assert_eq!(df.width(), 11);
+            assert_eq!(
+                df.column("lefteye_x")?.dtype(),
+                &polars::prelude::DataType::Int64
+            );
+
+            let mut opts = DFOpts::empty();
+            opts.page = Some(2);
+            opts.page_size = Some(20);
+            let df = api::client::data_frames::get_arrow(
+                &remote_repo,
+                DEFAULT_BRANCH_NAME,
+                "large_files/test.csv",
+                opts,
+            )
+            .await?;
+            assert_eq!(df.height(), 20);
+
+            Ok(())
+        })
+        .await
+    }
+
     #[tokio::test]
     async fn test_paginate_df_page_one() -> Result<(), OxenError> {
         test::run_empty_local_repo_test_async(|mut local_repo| async move {
@@ -428,6 +522,54 @@ mod tests {
         .await
     }
 
+    #[tokio::test]
+    async fn test_paginate_filtered_sorted_df() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|mut local_repo| async move {
+            let repo_dir = &local_repo.path;
+            let large_dir = repo_dir.join("large_files");
+            std::fs::create_dir_all(&large_dir)?;
+            let csv_file = large_dir.join("test.csv");
+            let from_file = test::test_200k_csv();
+            util::fs::copy(from_file, &csv_file)?;
+
+            repositories::add(&local_repo, &csv_file)?;
+            repositories::commit(&local_repo, "add test.csv")?;
+
+            // Set the proper remote
+            let remote = test::repo_remote_url_from(&local_repo.dirname());
+            command::config::set_remote(&mut local_repo, DEFAULT_REMOTE_NAME, &remote)?;
+
+            // Create the repo
+            let remote_repo = test::create_remote_repo(&local_repo).await?;
+
+            // Push the repo
+            repositories::push(&local_repo).await?;
+
+            // Page 2 of the rows that match, without indexing the df
+            let mut opts = DFOpts::empty();
+            opts.page = Some(2);
+            opts.page_size = Some(100);
+            opts.filter = Some("lefteye_x > 70".to_string());
+            opts.sort_by = Some("lefteye_x".to_string());
+            let df = api::client::data_frames::get(
+                &remote_repo,
+                DEFAULT_BRANCH_NAME,
+                PathBuf::from("large_files").join("test.csv"),
+                opts,
+            )
+            .await?;
+
+            assert_eq!(df.data_frame.source.size.height, 200_000);
+            assert_eq!(df.data_frame.view.data.as_array().unwrap().len(), 100);
+            assert_eq!(df.data_frame.view.pagination.page_number, 2);
+            assert_eq!(df.data_frame.view.pagination.total_entries, 37_291);
+            assert_eq!(df.data_frame.view.pagination.total_pages, 373);
+
+            Ok(())
+        })
+        .await
+    }
+
     #[tokio::test]
     async fn test_remote_get_schema_df_on_branch() -> Result<(), OxenError> {
         test::run_empty_local_repo_test_async(|mut local_repo| async move {
@@ -0,0 +1,33 @@
+//! Fetch the dataset card of a remote repository
+//!
+
+use crate::api;
+use crate::api::client;
+use crate::api::client::SendWithRetry;
+use crate::error::OxenError;
+use crate::model::{DatasetCard, RemoteRepository};
+use crate::view::dataset_card::DatasetCardResponse;
+
+/// The dataset card at a branch or commit on the remote, the default branch if None
+pub async fn get(
+    remote_repo: &RemoteRepository,
+    revision: Option<&str>,
+) -> Result<DatasetCard, OxenError> {
+    let uri = match revision {
+        Some(revision) => format!("/dataset_card/{revision}"),
+        None => String::from("/dataset_card"),
+    };
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!("api::client::dataset_cards::get url: {url}");
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send_with_retry().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<DatasetCardResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val.dataset_card),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::client::dataset_cards::get error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
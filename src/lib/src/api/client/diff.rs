@@ -2,6 +2,7 @@ use std::path::Path;
 
 use crate::api;
 use crate::api::client;
+use crate::api::client::SendWithRetry;
 use crate::error::OxenError;
 use crate::model::{DiffEntry, RemoteRepository};
 use crate::view::compare::{CompareEntries, CompareEntryResponse};
@@ -20,7 +21,7 @@ pub async fn list_diff_entries(
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
 
     let client = client::new_for_url(&url)?;
-    let res = client.get(&url).send().await?;
+    let res = client.get(&url).send_with_retry().await?;
     let body = client::parse_json_body(&url, res).await?;
     let response: Result<CompareEntriesResponse, serde_json::Error> = serde_json::from_str(&body);
     match response {
@@ -44,7 +45,7 @@ pub async fn diff_entries(
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
 
     let client = client::new_for_url(&url)?;
-    let res = client.get(&url).send().await?;
+    let res = client.get(&url).send_with_retry().await?;
     let body = client::parse_json_body(&url, res).await?;
     let response: Result<CompareEntryResponse, serde_json::Error> = serde_json::from_str(&body);
     match response {
@@ -2,6 +2,7 @@ use std::path::Path;
 
 use crate::api;
 use crate::api::client;
+use crate::api::client::SendWithRetry;
 use crate::constants;
 use crate::error::OxenError;
 use crate::model::metadata::generic_metadata::GenericMetadata;
@@ -33,7 +34,7 @@ pub async fn list(
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
 
     let client = client::new_for_url(&url)?;
-    let res = client.get(&url).send().await?;
+    let res = client.get(&url).send_with_retry().await?;
     let body = client::parse_json_body(&url, res).await?;
     let response: Result<PaginatedDirEntries, serde_json::Error> = serde_json::from_str(&body);
     match response {
@@ -1,6 +1,8 @@
 use crate::api::client;
+use crate::api::client::SendWithRetry;
 use crate::config::UserConfig;
 use crate::constants::{AVG_CHUNK_SIZE, DEFAULT_BRANCH_NAME, OBJECTS_DIR, OXEN_HIDDEN_DIR};
+use crate::core::transfer;
 use crate::core::v0_10_0::commits::merge_objects_dbs;
 use crate::core::v0_10_0::index::{puller, CommitEntryReader, ObjectDBReader};
 use crate::core::v0_19_0::structs::PullProgress;
@@ -54,7 +56,7 @@ pub async fn list_entries_with_type(
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
 
     let client = client::new_for_url(&url)?;
-    let response = client.get(&url).send().await?;
+    let response = client.get(&url).send_with_retry().await?;
     let body = client::parse_json_body(&url, response).await?;
     let paginated_response: PaginatedMetadataEntriesResponse = serde_json::from_str(&body)?;
     Ok(paginated_response.entries.entries)
@@ -94,7 +96,7 @@ pub async fn upload_entries(
         api::client::workspaces::create(remote_repo, &branch_name, &workspace_id).await?;
     assert_eq!(workspace.id, workspace_id);
 
-    api::client::workspaces::files::add_many(
+    let result = api::client::workspaces::files::add_many(
         remote_repo,
         &workspace_id,
         &opts.dst.to_string_lossy(),
@@ -102,6 +104,22 @@ pub async fn upload_entries(
     )
     .await?;
 
+    // Do not commit a partial upload
+    if !result.is_complete() {
+        let failed: Vec<String> = result
+            .failed
+            .iter()
+            .map(|f| format!("  {:?}: {}", f.path, f.reason))
+            .collect();
+        api::client::workspaces::delete(remote_repo, &workspace_id).await?;
+        return Err(OxenError::basic_str(format!(
+            "Could not upload {} of {} files:\n{}",
+            result.failed.len(),
+            result.failed.len() + result.added.len(),
+            failed.join("\n")
+        )));
+    }
+
     log::debug!("Committing on {}", branch_name);
 
     // Commit the data
@@ -246,7 +264,7 @@ pub async fn download_small_entry(
     let client = client::new_for_url(&url)?;
     let response = client
         .get(&url)
-        .send()
+        .send_with_retry()
         .await
         .map_err(|_| OxenError::resource_not_found(&url))?;
 
@@ -263,6 +281,7 @@ pub async fn download_small_entry(
 
         let mut dest_file = { util::fs::file_create(dest)? };
         let mut content = Cursor::new(response.bytes().await?);
+        transfer::throttle(content.get_ref().len() as u64).await;
 
         std::io::copy(&mut content, &mut dest_file)?;
         Ok(())
@@ -476,36 +495,52 @@ async fn download_entry_chunk(
         local_path
     );
 
+    let bytes =
+        download_chunk_bytes(remote_repo, remote_path, revision, chunk_start, chunk_size).await?;
+
+    if let Some(parent) = local_path.parent() {
+        if !parent.exists() {
+            log::debug!("Create parent dir {:?}", parent);
+            std::fs::create_dir_all(parent)?;
+        }
+    }
+
+    // TODO: replace these with util::fs:: file functions for better error messages
+    // Copy to file
+    let mut dest = { fs::File::create(local_path)? };
+    let mut content = Cursor::new(bytes);
+    std::io::copy(&mut content, &mut dest)?;
+    Ok(())
+}
+
+/// Downloads a byte range of a file into memory
+pub async fn download_chunk_bytes(
+    remote_repo: &RemoteRepository,
+    remote_path: impl AsRef<Path>,
+    revision: impl AsRef<str>,
+    chunk_start: u64,
+    chunk_size: u64,
+) -> Result<Vec<u8>, OxenError> {
     let uri = format!(
         "/chunk/{}/{}?chunk_start={}&chunk_size={}",
         revision.as_ref(),
-        remote_path.to_string_lossy(),
+        remote_path.as_ref().to_string_lossy(),
         chunk_start,
         chunk_size
     );
 
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
 
-    log::debug!("download_entry_chunk {}", url);
+    log::debug!("download_chunk_bytes {}", url);
 
     let client = client::new_for_url(&url)?;
-    let response = client.get(&url).send().await?;
-
-    if let Some(parent) = local_path.parent() {
-        if !parent.exists() {
-            log::debug!("Create parent dir {:?}", parent);
-            std::fs::create_dir_all(parent)?;
-        }
-    }
+    let response = client.get(&url).send_with_retry().await?;
 
     let status = response.status();
     if reqwest::StatusCode::OK == status {
-        // TODO: replace these with util::fs:: file functions for better error messages
-        // Copy to file
-        let mut dest = { fs::File::create(local_path)? };
-        let mut content = Cursor::new(response.bytes().await?);
-        std::io::copy(&mut content, &mut dest)?;
-        Ok(())
+        let bytes = response.bytes().await?.to_vec();
+        transfer::throttle(bytes.len() as u64).await;
+        Ok(bytes)
     } else {
         let err = format!("Could not download entry status: {status}");
         Err(OxenError::basic_str(err))
@@ -565,7 +600,7 @@ pub async fn try_download_data_from_version_paths(
     let url = api::endpoint::url_from_repo(remote_repo, "/versions")?;
 
     let client = client::new_for_url(&url)?;
-    if let Ok(res) = client.get(&url).body(body).send().await {
+    if let Ok(res) = client.get(&url).body(body).send_with_retry().await {
         if reqwest::StatusCode::UNAUTHORIZED == res.status() {
             let err = "Err: unauthorized request to download data".to_string();
             log::error!("{}", err);
@@ -623,6 +658,7 @@ pub async fn try_download_data_from_version_paths(
             size += metadata.len();
             idx += 1;
             log::debug!("Unpacked {} bytes {:?}", metadata.len(), entry_path);
+            transfer::throttle(metadata.len()).await;
         }
 
         Ok(size)
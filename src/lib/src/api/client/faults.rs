@@ -0,0 +1,297 @@
+//! # Fault Injection
+//!
+//! A local proxy that sits between `api::client` and an oxen server and injects latency,
+//! dropped connections and 5xx responses, so the retry and resume logic for push, pull
+//! and workspace uploads can be exercised in integration tests.
+//!
+//! Only built with the `faults` feature. Faults are planned per connection, in the order
+//! connections are accepted. The client opens a new connection for each request, so
+//! the plan is deterministic for a sequence of requests. Retries from `retry` also
+//! open new connections, so they use up the planned faults.
+//!
+//! ```ignore
+//! let plan = FaultPlan::default()
+//!     .with_latency(Duration::from_millis(50))
+//!     .on_connection(0, Fault::Drop)
+//!     .on_connection(1, Fault::Status(503));
+//! let proxy = FaultProxy::start(test::test_host(), plan).await?;
+//! let remote_repo = proxy.remote_repo(&remote_repo)?;
+//! ```
+//!
+
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+use crate::api::client::Url;
+use crate::constants;
+use crate::error::OxenError;
+use crate::model::RemoteRepository;
+
+// Largest request head we read before deciding how to fail it
+const MAX_HEAD_BYTES: usize = 64 * 1024;
+
+lazy_static! {
+    // Proxy host -> upstream host, so auth tokens for the upstream are still sent
+    static ref UPSTREAMS: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+}
+
+/// What happens to a single connection
+#[derive(Debug, Clone, PartialEq)]
+pub enum Fault {
+    /// Wait before forwarding the request
+    Delay(Duration),
+    /// Close the connection after reading the request, without a response
+    Drop,
+    /// Respond with this status code instead of forwarding the request
+    Status(u16),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FaultPlan {
+    latency: Duration,
+    connections: HashMap<usize, Fault>,
+    every: Option<(usize, Fault)>,
+}
+
+impl FaultPlan {
+    /// Latency added to every connection, before any other fault
+    pub fn with_latency(mut self, latency: Duration) -> FaultPlan {
+        self.latency = latency;
+        self
+    }
+
+    /// Inject a fault into the nth connection, starting at 0
+    pub fn on_connection(mut self, n: usize, fault: Fault) -> FaultPlan {
+        self.connections.insert(n, fault);
+        self
+    }
+
+    /// Inject a fault into every nth connection. Faults for a specific connection win.
+    pub fn every(mut self, n: usize, fault: Fault) -> FaultPlan {
+        self.every = Some((n.max(1), fault));
+        self
+    }
+
+    pub fn fault_for(&self, connection: usize) -> Option<Fault> {
+        if let Some(fault) = self.connections.get(&connection) {
+            return Some(fault.clone());
+        }
+        match &self.every {
+            Some((n, fault)) if (connection + 1) % n == 0 => Some(fault.clone()),
+            _ => None,
+        }
+    }
+}
+
+pub struct FaultProxy {
+    addr: SocketAddr,
+    num_connections: Arc<AtomicUsize>,
+    handle: JoinHandle<()>,
+}
+
+impl FaultProxy {
+    /// Start a proxy on a random local port that forwards to the upstream host
+    pub async fn start(
+        upstream: impl AsRef<str>,
+        plan: FaultPlan,
+    ) -> Result<FaultProxy, OxenError> {
+        let upstream = upstream.as_ref().to_string();
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let num_connections = Arc::new(AtomicUsize::new(0));
+        UPSTREAMS
+            .lock()
+            .unwrap()
+            .insert(addr.to_string(), upstream.clone());
+
+        let counter = Arc::clone(&num_connections);
+        let handle = tokio::spawn(async move {
+            loop {
+                let (socket, _) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(err) => {
+                        log::error!("FaultProxy could not accept connection: {}", err);
+                        continue;
+                    }
+                };
+                let n = counter.fetch_add(1, Ordering::SeqCst);
+                let fault = plan.fault_for(n);
+                log::debug!("FaultProxy connection {} fault {:?}", n, fault);
+                let upstream = upstream.clone();
+                let latency = plan.latency;
+                tokio::spawn(async move {
+                    if let Err(err) = handle_connection(socket, &upstream, latency, fault).await {
+                        log::debug!("FaultProxy connection {} closed: {}", n, err);
+                    }
+                });
+            }
+        });
+
+        Ok(FaultProxy {
+            addr,
+            num_connections,
+            handle,
+        })
+    }
+
+    /// The host to use in place of the upstream host
+    pub fn host(&self) -> String {
+        self.addr.to_string()
+    }
+
+    /// How many connections the proxy has accepted so far
+    pub fn num_connections(&self) -> usize {
+        self.num_connections.load(Ordering::SeqCst)
+    }
+
+    /// Point a url at the proxy instead of its host
+    pub fn url(&self, url: impl AsRef<str>) -> Result<String, OxenError> {
+        let mut url = Url::parse(url.as_ref())?;
+        url.set_host(Some(&self.addr.ip().to_string()))?;
+        url.set_port(Some(self.addr.port()))
+            .map_err(|_| OxenError::basic_str(format!("Cannot set port on {url}")))?;
+        Ok(url.to_string())
+    }
+
+    /// The same remote repository, reached through the proxy
+    pub fn remote_repo(
+        &self,
+        remote_repo: &RemoteRepository,
+    ) -> Result<RemoteRepository, OxenError> {
+        let mut remote_repo = remote_repo.clone();
+        remote_repo.remote.url = self.url(&remote_repo.remote.url)?;
+        Ok(remote_repo)
+    }
+}
+
+impl Drop for FaultProxy {
+    fn drop(&mut self) {
+        self.handle.abort();
+        UPSTREAMS.lock().unwrap().remove(&self.addr.to_string());
+    }
+}
+
+/// The host a proxy forwards to, or the host itself if it is not a proxy
+pub fn upstream_host(host: &str) -> String {
+    UPSTREAMS
+        .lock()
+        .unwrap()
+        .get(host)
+        .cloned()
+        .unwrap_or_else(|| host.to_string())
+}
+
+async fn handle_connection(
+    mut socket: TcpStream,
+    upstream: &str,
+    latency: Duration,
+    fault: Option<Fault>,
+) -> Result<(), OxenError> {
+    tokio::time::sleep(latency).await;
+    match fault {
+        Some(Fault::Drop) => {
+            read_head(&mut socket).await?;
+            Ok(())
+        }
+        Some(Fault::Status(code)) => {
+            read_head(&mut socket).await?;
+            let body = serde_json::json!({
+                "status": "error",
+                "status_message": "fault_injected",
+                "status_description": format!("Injected {code} response"),
+                "oxen_version": constants::OXEN_VERSION,
+            })
+            .to_string();
+            let response = format!(
+                "HTTP/1.1 {code} Injected Fault\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            socket.write_all(response.as_bytes()).await?;
+            socket.shutdown().await?;
+            Ok(())
+        }
+        Some(Fault::Delay(delay)) => {
+            tokio::time::sleep(delay).await;
+            forward(socket, upstream).await
+        }
+        None => forward(socket, upstream).await,
+    }
+}
+
+async fn forward(mut socket: TcpStream, upstream: &str) -> Result<(), OxenError> {
+    let mut upstream = TcpStream::connect(upstream).await?;
+    tokio::io::copy_bidirectional(&mut socket, &mut upstream).await?;
+    Ok(())
+}
+
+async fn read_head(socket: &mut TcpStream) -> Result<(), OxenError> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 4096];
+    while head.len() < MAX_HEAD_BYTES {
+        let n = socket.read(&mut buf).await?;
+        if n == 0 {
+            break;
+        }
+        head.extend_from_slice(&buf[..n]);
+        if head.windows(4).any(|w| w == b"\r\n\r\n") {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::api;
+    use crate::api::client::faults::{Fault, FaultPlan, FaultProxy};
+    use crate::error::OxenError;
+    use crate::test;
+
+    #[test]
+    fn test_fault_plan_order() {
+        let plan = FaultPlan::default()
+            .every(3, Fault::Status(500))
+            .on_connection(0, Fault::Drop)
+            .on_connection(2, Fault::Delay(Duration::from_millis(10)));
+        assert_eq!(plan.fault_for(0), Some(Fault::Drop));
+        assert_eq!(plan.fault_for(1), None);
+        assert_eq!(
+            plan.fault_for(2),
+            Some(Fault::Delay(Duration::from_millis(10)))
+        );
+        assert_eq!(plan.fault_for(5), Some(Fault::Status(500)));
+    }
+
+    #[tokio::test]
+    async fn test_fault_proxy_fails_then_forwards() -> Result<(), OxenError> {
+        test::run_empty_remote_repo_test(|_local_repo, remote_repo| async move {
+            let plan = FaultPlan::default()
+                .with_latency(Duration::from_millis(20))
+                .on_connection(0, Fault::Drop)
+                .on_connection(1, Fault::Status(503));
+            let proxy = FaultProxy::start(test::test_host(), plan).await?;
+            let proxied_repo = proxy.remote_repo(&remote_repo)?;
+
+            // The dropped connection is retried, and the retries run out on the 503
+            assert!(api::client::repositories::get_by_remote_repo(&proxied_repo)
+                .await
+                .is_err());
+            assert_eq!(proxy.num_connections(), 2);
+            let found = api::client::repositories::get_by_remote_repo(&proxied_repo).await?;
+            assert!(found.is_some());
+            assert_eq!(proxy.num_connections(), 3);
+
+            Ok(remote_repo)
+        })
+        .await
+    }
+}
@@ -0,0 +1,60 @@
+//! Freeze a remote repository so it is read only, or thaw it again
+//!
+
+use crate::api;
+use crate::api::client;
+use crate::api::client::SendWithRetry;
+use crate::error::OxenError;
+use crate::model::{FreezeOpts, RemoteRepository, RepoFreeze};
+use crate::view::repo_freeze::RepoFreezeResponse;
+
+/// Get how the remote repository was frozen, None if it is not frozen
+pub async fn get(remote_repo: &RemoteRepository) -> Result<Option<RepoFreeze>, OxenError> {
+    let url = api::endpoint::url_from_repo(remote_repo, "/freeze")?;
+    log::debug!("api::client::freeze::get url: {url}");
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send_with_retry().await?;
+    parse_response(&url, res).await
+}
+
+/// Freeze the remote repository, rejecting writes until it is thawed
+pub async fn freeze(
+    remote_repo: &RemoteRepository,
+    opts: &FreezeOpts,
+) -> Result<RepoFreeze, OxenError> {
+    let url = api::endpoint::url_from_repo(remote_repo, "/freeze")?;
+    log::debug!("api::client::freeze::freeze url: {url}");
+
+    let params = serde_json::to_string(opts)?;
+    let client = client::new_for_url(&url)?;
+    let res = client.put(&url).body(params).send_with_retry().await?;
+    parse_response(&url, res)
+        .await?
+        .ok_or(OxenError::basic_str("Remote repository was not frozen"))
+}
+
+/// Thaw the remote repository so it accepts writes again
+pub async fn thaw(remote_repo: &RemoteRepository) -> Result<(), OxenError> {
+    let url = api::endpoint::url_from_repo(remote_repo, "/freeze")?;
+    log::debug!("api::client::freeze::thaw url: {url}");
+
+    let client = client::new_for_url(&url)?;
+    let res = client.delete(&url).send_with_retry().await?;
+    parse_response(&url, res).await?;
+    Ok(())
+}
+
+async fn parse_response(
+    url: &str,
+    res: reqwest::Response,
+) -> Result<Option<RepoFreeze>, OxenError> {
+    let body = client::parse_json_body(url, res).await?;
+    let response: Result<RepoFreezeResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val.freeze),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::client::freeze error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
@@ -0,0 +1,340 @@
+//! # gRPC sync client
+//!
+//! Push and pull tree nodes and file versions over a single gRPC connection, streaming in
+//! both directions instead of sending a request per batch. Talks to the service oxen-server
+//! starts with `--grpc-port`, see `proto/sync.proto`. Only built with the `grpc` feature.
+//!
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::str::FromStr;
+use tokio::io::AsyncWriteExt;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
+use tonic::metadata::AsciiMetadataValue;
+use tonic::transport::Channel;
+use tonic::{Code, Request, Status};
+
+use crate::api::client;
+use crate::api::grpc;
+use crate::api::grpc::proto::{self, sync_service_client};
+use crate::api::grpc::{CHUNK_SIZE, NAMESPACE_METADATA_KEY, REPO_NAME_METADATA_KEY};
+use crate::config::AuthConfig;
+use crate::constants::{NODES_DIR, OXEN_HIDDEN_DIR, TREE_DIR};
+use crate::core::v0_19_0::index::merkle_node_db::node_db_path;
+use crate::core::v0_19_0::index::MerkleNodeDB;
+use crate::core::v0_19_0::quarantine;
+use crate::error::OxenError;
+use crate::model::merkle_tree::node::MerkleTreeNode;
+use crate::model::{LocalRepository, MerkleHash, RemoteRepository};
+use crate::util;
+
+// Hashes sent per message, keeps messages well under the size limit
+const HASHES_PER_REQUEST: usize = 10_000;
+// Largest message we accept, hash lists for big repositories don't fit the 4MB default
+const MAX_MESSAGE_SIZE: usize = 256 * 1024 * 1024;
+
+/// Client for the sync service of one remote repository
+pub struct SyncClient {
+    client: sync_service_client::SyncServiceClient<Channel>,
+    namespace: AsciiMetadataValue,
+    repo_name: AsciiMetadataValue,
+    auth: Option<AsciiMetadataValue>,
+}
+
+fn metadata_value(value: impl AsRef<str>) -> Result<AsciiMetadataValue, OxenError> {
+    AsciiMetadataValue::from_str(value.as_ref())
+        .map_err(|_| OxenError::basic_str(format!("Invalid gRPC metadata {:?}", value.as_ref())))
+}
+
+fn status_error(status: Status) -> OxenError {
+    match status.code() {
+        Code::Unauthenticated | Code::PermissionDenied => {
+            OxenError::authentication(status.message())
+        }
+        Code::NotFound => OxenError::resource_not_found(status.message()),
+        _ => OxenError::basic_str(format!(
+            "gRPC error {}: {}",
+            status.code(),
+            status.message()
+        )),
+    }
+}
+
+fn parse_hashes(hashes: Vec<String>) -> Result<HashSet<MerkleHash>, OxenError> {
+    hashes.iter().map(|h| MerkleHash::from_str(h)).collect()
+}
+
+impl SyncClient {
+    /// Connect to the service at `url`, ex: `http://localhost:50051`, to sync `remote_repo`.
+    /// Uses the auth token configured for the host of the remote repository.
+    pub async fn connect(
+        url: impl AsRef<str>,
+        remote_repo: &RemoteRepository,
+    ) -> Result<SyncClient, OxenError> {
+        let url = url.as_ref().to_string();
+        util::offline::ensure_online(&url)?;
+        let client = sync_service_client::SyncServiceClient::connect(url.clone())
+            .await
+            .map_err(|err| {
+                OxenError::basic_str(format!("Could not connect to gRPC service {url}: {err}"))
+            })?
+            .max_decoding_message_size(MAX_MESSAGE_SIZE)
+            .max_encoding_message_size(MAX_MESSAGE_SIZE);
+
+        let host = client::get_host_from_url(remote_repo.url())?;
+        let auth = match AuthConfig::get() {
+            Ok(config) => config.auth_token_for_host(&host),
+            Err(err) => {
+                log::debug!("SyncClient::connect error getting auth config: {}", err);
+                None
+            }
+        };
+        let auth = auth
+            .map(|token| {
+                let mut value = metadata_value(format!("Bearer {token}"))?;
+                value.set_sensitive(true);
+                Ok::<AsciiMetadataValue, OxenError>(value)
+            })
+            .transpose()?;
+
+        Ok(SyncClient {
+            client,
+            namespace: metadata_value(&remote_repo.namespace)?,
+            repo_name: metadata_value(&remote_repo.name)?,
+            auth,
+        })
+    }
+
+    /// Wrap a message with the repository and auth metadata
+    fn request<T>(&self, message: T) -> Request<T> {
+        let mut request = Request::new(message);
+        let metadata = request.metadata_mut();
+        metadata.insert(NAMESPACE_METADATA_KEY, self.namespace.clone());
+        metadata.insert(REPO_NAME_METADATA_KEY, self.repo_name.clone());
+        if let Some(auth) = &self.auth {
+            metadata.insert("authorization", auth.clone());
+        }
+        request
+    }
+
+    /// Node hashes the remote does not have yet
+    pub async fn list_missing_node_hashes(
+        &mut self,
+        node_ids: HashSet<MerkleHash>,
+    ) -> Result<HashSet<MerkleHash>, OxenError> {
+        let hashes = node_ids.iter().map(|h| h.to_string()).collect();
+        let request = self.request(proto::MerkleHashes { hashes });
+        let response = self
+            .client
+            .list_missing_nodes(request)
+            .await
+            .map_err(status_error)?;
+        parse_hashes(response.into_inner().hashes)
+    }
+
+    /// File hashes in the given commits the remote does not have yet
+    pub async fn list_missing_file_hashes_from_commits(
+        &mut self,
+        commit_ids: HashSet<MerkleHash>,
+    ) -> Result<HashSet<MerkleHash>, OxenError> {
+        let hashes = commit_ids.iter().map(|h| h.to_string()).collect();
+        let request = self.request(proto::MerkleHashes { hashes });
+        let response = self
+            .client
+            .list_missing_files(request)
+            .await
+            .map_err(status_error)?;
+        parse_hashes(response.into_inner().hashes)
+    }
+
+    /// Upload tree nodes, returns the hashes of the nodes the remote wrote
+    pub async fn create_nodes(
+        &mut self,
+        local_repo: &LocalRepository,
+        nodes: HashSet<MerkleTreeNode>,
+    ) -> Result<HashSet<MerkleHash>, OxenError> {
+        let enc = GzEncoder::new(Vec::new(), Compression::default());
+        let mut tar = tar::Builder::new(enc);
+        let tree_dir = local_repo
+            .path
+            .join(OXEN_HIDDEN_DIR)
+            .join(TREE_DIR)
+            .join(NODES_DIR);
+        for node in nodes {
+            let node_dir = node_db_path(local_repo, &node.hash);
+            let sub_dir = util::fs::path_relative_to_dir(&node_dir, &tree_dir)?;
+            MerkleNodeDB::append_to_tar(local_repo, &node.hash, &mut tar, sub_dir)?;
+        }
+        tar.finish()?;
+        let buffer: Vec<u8> = tar.into_inner()?.finish()?;
+        log::debug!(
+            "SyncClient::create_nodes uploading {}",
+            bytesize::ByteSize::b(buffer.len() as u64)
+        );
+
+        let chunks: Vec<proto::Chunk> = buffer
+            .chunks(CHUNK_SIZE)
+            .map(|data| proto::Chunk {
+                data: data.to_vec(),
+            })
+            .collect();
+        let request = self.request(tokio_stream::iter(chunks));
+        let response = self
+            .client
+            .upload_nodes(request)
+            .await
+            .map_err(status_error)?;
+        parse_hashes(response.into_inner().hashes)
+    }
+
+    /// Download the tree nodes of the commits from `base_commit_id` back to the first
+    /// commit, or back to `head_commit_id` if it is given, into the local repository
+    pub async fn download_nodes(
+        &mut self,
+        local_repo: &LocalRepository,
+        base_commit_id: impl AsRef<str>,
+        head_commit_id: Option<String>,
+    ) -> Result<(), OxenError> {
+        let request = self.request(proto::DownloadNodesRequest {
+            base_commit_id: base_commit_id.as_ref().to_string(),
+            head_commit_id,
+        });
+        let mut stream = self
+            .client
+            .download_nodes(request)
+            .await
+            .map_err(status_error)?
+            .into_inner();
+
+        let mut buffer: Vec<u8> = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            buffer.extend(chunk.map_err(status_error)?.data);
+        }
+        log::debug!(
+            "SyncClient::download_nodes unpacking {}",
+            bytesize::ByteSize::b(buffer.len() as u64)
+        );
+
+        // The remote packs the nodes in TREE_DIR/NODES_DIR
+        let hidden_dir = util::fs::oxen_hidden_dir(&local_repo.path);
+        util::fs::create_dir_all(&hidden_dir)?;
+        let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(&buffer[..]));
+        archive.unpack(&hidden_dir)?;
+        Ok(())
+    }
+
+    /// Upload the versions of the given file hashes from the local repository.
+    /// Returns the number of files and bytes sent.
+    pub async fn upload_entries(
+        &mut self,
+        local_repo: &LocalRepository,
+        hashes: HashSet<MerkleHash>,
+    ) -> Result<(u64, u64), OxenError> {
+        let (tx, rx) = tokio::sync::mpsc::channel(16);
+        let local_repo = local_repo.clone();
+        // Versions are read and decoded a chunk at a time while the upload streams them
+        let reader = tokio::task::spawn_blocking(move || {
+            for hash in hashes {
+                // The upload failed, the error comes back from the call below
+                if !grpc::read_version_chunks(&local_repo, &hash, |chunk| {
+                    tx.blocking_send(chunk).is_ok()
+                })? {
+                    return Ok(());
+                }
+            }
+            Ok::<(), OxenError>(())
+        });
+
+        let request = self.request(ReceiverStream::new(rx));
+        let response = self.client.upload_entries(request).await;
+        reader
+            .await
+            .map_err(|err| OxenError::basic_str(format!("Could not read versions: {err}")))??;
+        let response = response.map_err(status_error)?.into_inner();
+        Ok((response.num_entries, response.num_bytes))
+    }
+
+    /// Download the versions of the given file hashes into the local repository.
+    /// Returns the number of files written.
+    pub async fn download_entries(
+        &mut self,
+        local_repo: &LocalRepository,
+        hashes: HashSet<MerkleHash>,
+    ) -> Result<u64, OxenError> {
+        let requests: Vec<proto::EntryRequest> = hashes
+            .iter()
+            .map(|h| h.to_string())
+            .collect::<Vec<String>>()
+            .chunks(HASHES_PER_REQUEST)
+            .map(|hashes| proto::EntryRequest {
+                hashes: hashes.to_vec(),
+            })
+            .collect();
+        let request = self.request(tokio_stream::iter(requests));
+        let mut stream = self
+            .client
+            .download_entries(request)
+            .await
+            .map_err(status_error)?
+            .into_inner();
+
+        // Files come back one after the other, each in order. Write them next to the
+        // version path and store them once complete and checked against their hash.
+        let mut current: Option<(MerkleHash, PathBuf, tokio::fs::File)> = None;
+        let mut num_entries = 0;
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(status_error)?;
+            let hash = MerkleHash::from_str(&chunk.hash)?;
+            if current.as_ref().map(|(h, _, _)| h != &hash).unwrap_or(true) {
+                if chunk.offset != 0 {
+                    return Err(OxenError::basic_str(format!(
+                        "Received version {hash} out of order"
+                    )));
+                }
+                let version_path = util::fs::version_path_from_hash(local_repo, hash.to_string());
+                if let Some(parent) = version_path.parent() {
+                    util::fs::create_dir_all(parent)?;
+                }
+                let part_path = version_path.with_extension("part");
+                let file = tokio::fs::File::create(&part_path).await?;
+                current = Some((hash, part_path, file));
+            }
+
+            // Set above
+            let (_, _, file) = current.as_mut().unwrap();
+            file.write_all(&chunk.data).await?;
+            if chunk.last {
+                file.flush().await?;
+                let (hash, part_path, _) = current.take().unwrap();
+                let local_repo = local_repo.clone();
+                tokio::task::spawn_blocking(move || {
+                    // Only the hash is known here, report the version path
+                    let version_path =
+                        util::fs::version_path_from_hash(&local_repo, hash.to_string());
+                    let path = util::fs::path_relative_to_dir(&version_path, &local_repo.path)?;
+                    quarantine::verify_received(
+                        &local_repo.path,
+                        &path,
+                        &hash.to_string(),
+                        &part_path,
+                    )?;
+                    grpc::store_version(&local_repo, &hash, &part_path)
+                })
+                .await
+                .map_err(|err| OxenError::basic_str(format!("Could not store version: {err}")))??;
+                num_entries += 1;
+            }
+        }
+
+        if let Some((hash, part_path, _)) = current {
+            let _ = tokio::fs::remove_file(&part_path).await;
+            return Err(OxenError::basic_str(format!(
+                "Download of version {hash} ended early"
+            )));
+        }
+        Ok(num_entries)
+    }
+}
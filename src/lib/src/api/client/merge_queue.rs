@@ -0,0 +1,96 @@
+//! Queue branches to be merged on the remote, and check on their status
+//!
+
+use crate::api;
+use crate::api::client;
+use crate::api::client::SendWithRetry;
+use crate::error::OxenError;
+use crate::model::{MergeQueueEntry, NewMergeQueueEntry, RemoteRepository};
+use crate::view::merge::{ListMergeQueueResponse, MergeQueueEntryResponse};
+
+/// Queue a merge of head into base on the remote
+pub async fn enqueue(
+    remote_repo: &RemoteRepository,
+    new_entry: &NewMergeQueueEntry,
+) -> Result<MergeQueueEntry, OxenError> {
+    let url = api::endpoint::url_from_repo(remote_repo, "/merge_queue")?;
+    log::debug!("api::client::merge_queue::enqueue url: {url}");
+
+    let params = serde_json::to_string(new_entry)?;
+    let client = client::new_for_url(&url)?;
+    let res = client.post(&url).body(params).send_with_retry().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<MergeQueueEntryResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val.entry),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::client::merge_queue::enqueue error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
+
+/// List the merge queue on the remote, optionally only for one base branch
+pub async fn list(
+    remote_repo: &RemoteRepository,
+    base: Option<&str>,
+) -> Result<Vec<MergeQueueEntry>, OxenError> {
+    let uri = match base {
+        Some(base) => format!("/merge_queue?base={}", urlencoding::encode(base)),
+        None => String::from("/merge_queue"),
+    };
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!("api::client::merge_queue::list url: {url}");
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send_with_retry().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<ListMergeQueueResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val.entries),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::client::merge_queue::list error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
+
+/// Get the status of a queued merge on the remote
+pub async fn get(
+    remote_repo: &RemoteRepository,
+    id: impl AsRef<str>,
+) -> Result<MergeQueueEntry, OxenError> {
+    let uri = format!("/merge_queue/{}", id.as_ref());
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!("api::client::merge_queue::get url: {url}");
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send_with_retry().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<MergeQueueEntryResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val.entry),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::client::merge_queue::get error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
+
+/// Cancel a queued merge on the remote that has not started yet
+pub async fn cancel(
+    remote_repo: &RemoteRepository,
+    id: impl AsRef<str>,
+) -> Result<MergeQueueEntry, OxenError> {
+    let uri = format!("/merge_queue/{}", id.as_ref());
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!("api::client::merge_queue::cancel url: {url}");
+
+    let client = client::new_for_url(&url)?;
+    let res = client.delete(&url).send_with_retry().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<MergeQueueEntryResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val.entry),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::client::merge_queue::cancel error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
@@ -0,0 +1,133 @@
+//! Open, review and merge merge requests on the remote
+//!
+
+use crate::api;
+use crate::api::client;
+use crate::api::client::SendWithRetry;
+use crate::error::OxenError;
+use crate::model::{MergeRequest, MergeRequestStatus, NewMergeRequest, RemoteRepository};
+use crate::view::compare::CompareEntries;
+use crate::view::merge::{ListMergeRequestsResponse, MergeRequestResponse};
+use crate::view::CompareEntriesResponse;
+
+/// Open a merge request of head into base on the remote
+pub async fn create(
+    remote_repo: &RemoteRepository,
+    new_request: &NewMergeRequest,
+) -> Result<MergeRequest, OxenError> {
+    let url = api::endpoint::url_from_repo(remote_repo, "/merge_requests")?;
+    log::debug!("api::client::merge_requests::create url: {url}");
+
+    let params = serde_json::to_string(new_request)?;
+    let client = client::new_for_url(&url)?;
+    let res = client.post(&url).body(params).send_with_retry().await?;
+    parse_merge_request_response(&url, res, "create").await
+}
+
+/// List the merge requests on the remote, optionally filtered by status
+pub async fn list(
+    remote_repo: &RemoteRepository,
+    status: Option<MergeRequestStatus>,
+) -> Result<Vec<MergeRequest>, OxenError> {
+    let uri = match status {
+        Some(status) => format!("/merge_requests?status={status}"),
+        None => String::from("/merge_requests"),
+    };
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!("api::client::merge_requests::list url: {url}");
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send_with_retry().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<ListMergeRequestsResponse, serde_json::Error> =
+        serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val.merge_requests),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::client::merge_requests::list error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
+
+/// Get a merge request on the remote by id or number
+pub async fn get(
+    remote_repo: &RemoteRepository,
+    id: impl AsRef<str>,
+) -> Result<MergeRequest, OxenError> {
+    let uri = format!("/merge_requests/{}", id.as_ref());
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!("api::client::merge_requests::get url: {url}");
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send_with_retry().await?;
+    parse_merge_request_response(&url, res, "get").await
+}
+
+/// The file changes the merge request would bring into its base branch
+pub async fn diff(
+    remote_repo: &RemoteRepository,
+    id: impl AsRef<str>,
+    page: usize,
+    page_size: usize,
+) -> Result<CompareEntries, OxenError> {
+    let uri = format!(
+        "/merge_requests/{}/diff?page={page}&page_size={page_size}",
+        id.as_ref()
+    );
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!("api::client::merge_requests::diff url: {url}");
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send_with_retry().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<CompareEntriesResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val.compare),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::client::merge_requests::diff error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
+
+/// Merge the merge request on the remote
+pub async fn merge(
+    remote_repo: &RemoteRepository,
+    id: impl AsRef<str>,
+) -> Result<MergeRequest, OxenError> {
+    let uri = format!("/merge_requests/{}/merge", id.as_ref());
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!("api::client::merge_requests::merge url: {url}");
+
+    let client = client::new_for_url(&url)?;
+    let res = client.post(&url).send_with_retry().await?;
+    parse_merge_request_response(&url, res, "merge").await
+}
+
+/// Close the merge request on the remote without merging it
+pub async fn close(
+    remote_repo: &RemoteRepository,
+    id: impl AsRef<str>,
+) -> Result<MergeRequest, OxenError> {
+    let uri = format!("/merge_requests/{}/close", id.as_ref());
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!("api::client::merge_requests::close url: {url}");
+
+    let client = client::new_for_url(&url)?;
+    let res = client.post(&url).send_with_retry().await?;
+    parse_merge_request_response(&url, res, "close").await
+}
+
+async fn parse_merge_request_response(
+    url: &str,
+    res: reqwest::Response,
+    action: &str,
+) -> Result<MergeRequest, OxenError> {
+    let body = client::parse_json_body(url, res).await?;
+    let response: Result<MergeRequestResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val.merge_request),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::client::merge_requests::{action} error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
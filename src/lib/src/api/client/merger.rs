@@ -3,6 +3,7 @@
 
 use crate::api;
 use crate::api::client;
+use crate::api::client::SendWithRetry;
 use crate::error::OxenError;
 use crate::model::RemoteRepository;
 use crate::view::merge::{Mergeable, MergeableResponse};
@@ -19,7 +20,7 @@ pub async fn mergability(
     log::debug!("url: {url}");
 
     let client = client::new_for_url(&url)?;
-    match client.get(&url).send().await {
+    match client.get(&url).send_with_retry().await {
         Ok(res) => {
             let body = client::parse_json_body(&url, res).await?;
             log::debug!("got body: {}", body);
@@ -3,6 +3,7 @@
 
 use crate::api;
 use crate::api::client;
+use crate::api::client::SendWithRetry;
 use crate::error::OxenError;
 use crate::model::RemoteRepository;
 use crate::view::MetadataEntryResponse;
@@ -21,7 +22,7 @@ pub async fn get_file(
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
 
     let client = client::new_for_url(&url)?;
-    let response = client.get(&url).send().await?;
+    let response = client.get(&url).send_with_retry().await?;
     let body = client::parse_json_body(&url, response).await?;
     Ok(serde_json::from_str(&body)?)
 }
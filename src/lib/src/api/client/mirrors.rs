@@ -0,0 +1,81 @@
+//! Replicate a remote repository to other oxen-servers
+//!
+
+use crate::api;
+use crate::api::client;
+use crate::api::client::SendWithRetry;
+use crate::error::OxenError;
+use crate::model::{Mirror, NewMirror, RemoteRepository};
+use crate::view::mirror::{ListMirrorsResponse, MirrorResponse};
+
+/// Add a mirror to the remote repository, the server starts syncing it right away
+pub async fn create(
+    remote_repo: &RemoteRepository,
+    new_mirror: &NewMirror,
+) -> Result<Mirror, OxenError> {
+    let url = api::endpoint::url_from_repo(remote_repo, "/mirrors")?;
+    log::debug!("api::client::mirrors::create url: {url}");
+
+    let params = serde_json::to_string(new_mirror)?;
+    let client = client::new_for_url(&url)?;
+    let res = client.post(&url).body(params).send_with_retry().await?;
+    parse_mirror_response(&url, res, "create").await
+}
+
+pub async fn list(remote_repo: &RemoteRepository) -> Result<Vec<Mirror>, OxenError> {
+    let url = api::endpoint::url_from_repo(remote_repo, "/mirrors")?;
+    log::debug!("api::client::mirrors::list url: {url}");
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send_with_retry().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<ListMirrorsResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val.mirrors),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::client::mirrors::list error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
+
+pub async fn delete(
+    remote_repo: &RemoteRepository,
+    id: impl AsRef<str>,
+) -> Result<Mirror, OxenError> {
+    let uri = format!("/mirrors/{}", id.as_ref());
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!("api::client::mirrors::delete url: {url}");
+
+    let client = client::new_for_url(&url)?;
+    let res = client.delete(&url).send_with_retry().await?;
+    parse_mirror_response(&url, res, "delete").await
+}
+
+/// Ask the server to sync the mirror now
+pub async fn sync(
+    remote_repo: &RemoteRepository,
+    id: impl AsRef<str>,
+) -> Result<Mirror, OxenError> {
+    let uri = format!("/mirrors/{}/sync", id.as_ref());
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!("api::client::mirrors::sync url: {url}");
+
+    let client = client::new_for_url(&url)?;
+    let res = client.post(&url).send_with_retry().await?;
+    parse_mirror_response(&url, res, "sync").await
+}
+
+async fn parse_mirror_response(
+    url: &str,
+    res: reqwest::Response,
+    action: &str,
+) -> Result<Mirror, OxenError> {
+    let body = client::parse_json_body(url, res).await?;
+    let response: Result<MirrorResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val.mirror),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::client::mirrors::{action} error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
@@ -0,0 +1,99 @@
+//! Name dataset versions on the remote with releases
+//!
+
+use crate::api;
+use crate::api::client;
+use crate::api::client::SendWithRetry;
+use crate::error::OxenError;
+use crate::model::{NewRelease, Release, RemoteRepository};
+use crate::view::release::{ListReleasesResponse, ReleaseResponse, UpdateReleaseBody};
+
+/// Create a release on the remote pointing at the commit `new_release.revision` resolves to
+pub async fn create(
+    remote_repo: &RemoteRepository,
+    new_release: &NewRelease,
+) -> Result<Release, OxenError> {
+    let url = api::endpoint::url_from_repo(remote_repo, "/releases")?;
+    log::debug!("api::client::releases::create url: {url}");
+
+    let params = serde_json::to_string(new_release)?;
+    let client = client::new_for_url(&url)?;
+    let res = client.post(&url).body(params).send_with_retry().await?;
+    parse_release_response(&url, res, "create").await
+}
+
+/// List the releases on the remote, newest first
+pub async fn list(remote_repo: &RemoteRepository) -> Result<Vec<Release>, OxenError> {
+    let url = api::endpoint::url_from_repo(remote_repo, "/releases")?;
+    log::debug!("api::client::releases::list url: {url}");
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send_with_retry().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<ListReleasesResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val.releases),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::client::releases::list error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
+
+/// Get a release on the remote by name
+pub async fn get(
+    remote_repo: &RemoteRepository,
+    name: impl AsRef<str>,
+) -> Result<Release, OxenError> {
+    let uri = format!("/releases/{}", name.as_ref());
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!("api::client::releases::get url: {url}");
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send_with_retry().await?;
+    parse_release_response(&url, res, "get").await
+}
+
+/// Replace the description of a release on the remote
+pub async fn update_description(
+    remote_repo: &RemoteRepository,
+    name: impl AsRef<str>,
+    description: Option<String>,
+) -> Result<Release, OxenError> {
+    let uri = format!("/releases/{}", name.as_ref());
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!("api::client::releases::update_description url: {url}");
+
+    let params = serde_json::to_string(&UpdateReleaseBody { description })?;
+    let client = client::new_for_url(&url)?;
+    let res = client.put(&url).body(params).send_with_retry().await?;
+    parse_release_response(&url, res, "update_description").await
+}
+
+/// Delete a release on the remote, returning it
+pub async fn delete(
+    remote_repo: &RemoteRepository,
+    name: impl AsRef<str>,
+) -> Result<Release, OxenError> {
+    let uri = format!("/releases/{}", name.as_ref());
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!("api::client::releases::delete url: {url}");
+
+    let client = client::new_for_url(&url)?;
+    let res = client.delete(&url).send_with_retry().await?;
+    parse_release_response(&url, res, "delete").await
+}
+
+async fn parse_release_response(
+    url: &str,
+    res: reqwest::Response,
+    action: &str,
+) -> Result<Release, OxenError> {
+    let body = client::parse_json_body(url, res).await?;
+    let response: Result<ReleaseResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val.release),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::client::releases::{action} error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
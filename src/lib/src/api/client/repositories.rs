@@ -1,11 +1,13 @@
 use crate::api;
 use crate::api::client;
+use crate::api::client::SendWithRetry;
 use crate::constants::{DEFAULT_HOST, DEFAULT_REMOTE_NAME};
 use crate::error::OxenError;
-use crate::model::{Branch, LocalRepository, Remote, RemoteRepository, RepoNew};
+use crate::model::{Branch, LocalRepository, Remote, RemoteRepository, RepoNew, StorageStats};
 use crate::repositories;
 use crate::view::repository::{
     RepositoryCreationResponse, RepositoryDataTypesResponse, RepositoryDataTypesView,
+    StorageStatsResponse,
 };
 use crate::view::{NamespaceView, RepositoryResponse, StatusMessage};
 use serde_json::json;
@@ -89,7 +91,7 @@ pub async fn get_by_remote(remote: &Remote) -> Result<Option<RemoteRepository>,
     log::debug!("get_by_remote url: {}", url);
 
     let client = client::new_for_url(&url)?;
-    match client.get(&url).send().await {
+    match client.get(&url).send_with_retry().await {
         Ok(res) => {
             if 404 == res.status() {
                 return Ok(None);
@@ -133,7 +135,7 @@ pub async fn get_repo_data_by_remote(
     );
 
     let client = client::new_for_url(&url)?;
-    match client.get(&url).send().await {
+    match client.get(&url).send_with_retry().await {
         Ok(res) => {
             if 404 == res.status() {
                 return Ok(None);
@@ -163,6 +165,24 @@ pub async fn get_repo_data_by_remote(
     }
 }
 
+/// Total size, per branch unique size, file counts by data type and commit counts of
+/// the remote repository
+pub async fn stats(repository: &RemoteRepository) -> Result<StorageStats, OxenError> {
+    let url = api::endpoint::url_from_repo(repository, "/stats/storage")?;
+    log::debug!("api::client::repositories::stats url: {url}");
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send_with_retry().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<StorageStatsResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val.storage_stats),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::client::repositories::stats error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
+
 pub async fn create_empty(repo: RepoNew) -> Result<RemoteRepository, OxenError> {
     let namespace = repo.namespace.as_ref();
     let repo_name = repo.name.as_ref();
@@ -181,7 +201,7 @@ pub async fn create_empty(repo: RepoNew) -> Result<RemoteRepository, OxenError>
     // no user agent, otherwise the create will fail when going through the hub
     let client = client::new_for_url_no_user_agent(&url)?;
     log::debug!("client: {:?}", client);
-    match client.post(&url).json(&params).send().await {
+    match client.post(&url).json(&params).send_with_retry().await {
         Ok(res) => {
             let body = client::parse_json_body(&url, res).await?;
 
@@ -215,7 +235,7 @@ pub async fn create(repo_new: RepoNew) -> Result<RemoteRepository, OxenError> {
 
     // no user agent, otherwise the create will fail when going through the hub
     let client = client::new_for_url_no_user_agent(&url)?;
-    if let Ok(res) = client.post(&url).json(&repo_new).send().await {
+    if let Ok(res) = client.post(&url).json(&repo_new).send_with_retry().await {
         let body = client::parse_json_body(&url, res).await?;
 
         log::debug!("repositories::create response {}", body);
@@ -261,7 +281,7 @@ pub async fn create_from_local(
     log::debug!("repositories::create_from_local: {}\n{:?}", url, repo_new);
 
     let client = client::new_for_url(&url)?;
-    let res = client.post(&url).json(&repo_new).send().await?;
+    let res = client.post(&url).json(&repo_new).send_with_retry().await?;
     let body = client::parse_json_body(&url, res).await?;
 
     log::debug!("repositories::create_from_local response {}", body);
@@ -294,7 +314,7 @@ pub async fn delete(repository: &RemoteRepository) -> Result<StatusMessage, Oxen
     log::debug!("Deleting repository: {}", url);
 
     let client = client::new_for_url(&url)?;
-    if let Ok(res) = client.delete(&url).send().await {
+    if let Ok(res) = client.delete(&url).send_with_retry().await {
         let body = client::parse_json_body(&url, res).await?;
         let response: Result<StatusMessage, serde_json::Error> = serde_json::from_str(&body);
         match response {
@@ -321,7 +341,7 @@ pub async fn transfer_namespace(
 
     let client = client::new_for_url(&url)?;
 
-    if let Ok(res) = client.patch(&url).body(params).send().await {
+    if let Ok(res) = client.patch(&url).body(params).send_with_retry().await {
         let body = client::parse_json_body(&url, res).await?;
         let response: Result<RepositoryResponse, serde_json::Error> = serde_json::from_str(&body);
 
@@ -445,7 +465,7 @@ async fn action_hook(
         request = request.json(&body_data);
     }
 
-    match request.send().await {
+    match request.send_with_retry().await {
         Ok(_) => Ok(()),
         _ => {
             let err = "api::repositories::action_hook() Request failed";
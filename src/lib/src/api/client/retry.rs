@@ -0,0 +1,218 @@
+//! # Request Retries
+//!
+//! Shared retry layer for the requests in `api::client`. Transient failures are retried
+//! with capped exponential backoff and jitter, so a flaky connection doesn't abort a long
+//! push or pull.
+//!
+//! Requests are only retried when it is safe to send them again. Idempotent methods
+//! (GET, HEAD, PUT, DELETE, OPTIONS) are retried on connection errors, timeouts and
+//! 408, 429 and 5xx responses. Other methods are only retried when the connection could
+//! not be made, since then the server never saw the request. Use `send_idempotent` for
+//! a POST that is safe to repeat. Requests with streaming bodies are sent once.
+//!
+//! Tuned with `OXEN_HTTP_RETRIES`, `OXEN_HTTP_RETRY_BASE_MS` and `OXEN_HTTP_RETRY_MAX_MS`.
+//!
+
+use rand::Rng;
+use reqwest::{header, Method, RequestBuilder, Response, StatusCode};
+use std::future::Future;
+use std::time::Duration;
+
+use crate::constants;
+
+pub const RETRIES_ENV: &str = "OXEN_HTTP_RETRIES";
+pub const RETRY_BASE_MS_ENV: &str = "OXEN_HTTP_RETRY_BASE_MS";
+pub const RETRY_MAX_MS_ENV: &str = "OXEN_HTTP_RETRY_MAX_MS";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub max_retries: u64,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: constants::NUM_HTTP_RETRIES,
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The default policy, with any values set in the environment
+    pub fn from_env() -> RetryPolicy {
+        let defaults = RetryPolicy::default();
+        RetryPolicy {
+            max_retries: env_or(RETRIES_ENV, defaults.max_retries),
+            base_delay: Duration::from_millis(env_or(
+                RETRY_BASE_MS_ENV,
+                defaults.base_delay.as_millis() as u64,
+            )),
+            max_delay: Duration::from_millis(env_or(
+                RETRY_MAX_MS_ENV,
+                defaults.max_delay.as_millis() as u64,
+            )),
+        }
+    }
+
+    /// Backoff before the retry, doubling from the base delay up to the max, with up to
+    /// half of it randomized so parallel workers don't retry in lockstep
+    pub fn delay_for(&self, retry: u64) -> Duration {
+        let exp = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry.min(31) as u32))
+            .min(self.max_delay);
+        let jitter = rand::thread_rng().gen_range(0.0..=0.5);
+        exp.mul_f64(1.0 - jitter)
+    }
+}
+
+/// The number set in the environment variable, or the default if it is unset or invalid
+pub fn env_or(name: &str, default: u64) -> u64 {
+    match std::env::var(name) {
+        Ok(value) => value.parse().unwrap_or_else(|_| {
+            log::warn!("Ignoring invalid {}={:?}", name, value);
+            default
+        }),
+        Err(_) => default,
+    }
+}
+
+pub fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS
+    )
+}
+
+pub fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::REQUEST_TIMEOUT
+        || status == StatusCode::TOO_MANY_REQUESTS
+        || status.is_server_error()
+}
+
+fn is_retryable_error(err: &reqwest::Error, idempotent: bool) -> bool {
+    if idempotent {
+        err.is_connect() || err.is_timeout() || err.is_request()
+    } else {
+        err.is_connect()
+    }
+}
+
+// Seconds the server asked us to wait, capped by the policy
+fn retry_after(res: &Response, policy: &RetryPolicy) -> Option<Duration> {
+    let secs: u64 = res
+        .headers()
+        .get(header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()?;
+    Some(Duration::from_secs(secs).min(policy.max_delay))
+}
+
+pub trait SendWithRetry {
+    /// Send the request, retrying transient failures if the method is idempotent
+    fn send_with_retry(self) -> impl Future<Output = Result<Response, reqwest::Error>> + Send;
+
+    /// Send the request, retrying transient failures whatever the method
+    fn send_idempotent(self) -> impl Future<Output = Result<Response, reqwest::Error>> + Send;
+}
+
+impl SendWithRetry for RequestBuilder {
+    fn send_with_retry(self) -> impl Future<Output = Result<Response, reqwest::Error>> + Send {
+        send(self, None)
+    }
+
+    fn send_idempotent(self) -> impl Future<Output = Result<Response, reqwest::Error>> + Send {
+        send(self, Some(true))
+    }
+}
+
+async fn send(
+    builder: RequestBuilder,
+    idempotent: Option<bool>,
+) -> Result<Response, reqwest::Error> {
+    let (client, request) = builder.build_split();
+    let request = request?;
+    let idempotent = idempotent.unwrap_or_else(|| is_idempotent(request.method()));
+    let policy = RetryPolicy::from_env();
+
+    let mut retry = 0;
+    loop {
+        // Streaming bodies can't be replayed, so they only get one attempt
+        let Some(attempt) = request.try_clone() else {
+            return client.execute(request).await;
+        };
+        let can_retry = retry < policy.max_retries;
+        let delay = match client.execute(attempt).await {
+            Ok(res) if can_retry && idempotent && is_retryable_status(res.status()) => {
+                log::warn!(
+                    "{} {} returned {}, retry {}/{}",
+                    request.method(),
+                    request.url(),
+                    res.status(),
+                    retry + 1,
+                    policy.max_retries
+                );
+                retry_after(&res, &policy).unwrap_or_else(|| policy.delay_for(retry))
+            }
+            Err(err) if can_retry && is_retryable_error(&err, idempotent) => {
+                log::warn!(
+                    "{} {} failed: {}, retry {}/{}",
+                    request.method(),
+                    request.url(),
+                    err,
+                    retry + 1,
+                    policy.max_retries
+                );
+                policy.delay_for(retry)
+            }
+            result => return result,
+        };
+        tokio::time::sleep(delay).await;
+        retry += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::{Method, StatusCode};
+    use std::time::Duration;
+
+    use crate::api::client::retry::{is_idempotent, is_retryable_status, RetryPolicy};
+
+    #[test]
+    fn test_retry_policy_backoff_is_capped() {
+        let policy = RetryPolicy {
+            max_retries: 10,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+        };
+        for retry in 0..10 {
+            let delay = policy.delay_for(retry);
+            let exp = Duration::from_millis(100 * 2u64.pow(retry as u32)).min(policy.max_delay);
+            assert!(delay <= exp);
+            assert!(delay >= exp / 2);
+        }
+        assert!(policy.delay_for(u64::MAX) <= policy.max_delay);
+    }
+
+    #[test]
+    fn test_retry_only_safe_requests() {
+        assert!(is_idempotent(&Method::GET));
+        assert!(is_idempotent(&Method::PUT));
+        assert!(is_idempotent(&Method::DELETE));
+        assert!(!is_idempotent(&Method::POST));
+        assert!(!is_idempotent(&Method::PATCH));
+
+        assert!(is_retryable_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!is_retryable_status(StatusCode::NOT_FOUND));
+        assert!(!is_retryable_status(StatusCode::UNAUTHORIZED));
+    }
+}
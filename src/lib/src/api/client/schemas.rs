@@ -7,6 +7,7 @@ use std::path::Path;
 
 use crate::api;
 use crate::api::client;
+use crate::api::client::SendWithRetry;
 use crate::error::OxenError;
 use crate::model::RemoteRepository;
 use crate::view::schema::SchemaWithPath;
@@ -22,7 +23,7 @@ pub async fn list(
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
 
     let client = client::new_for_url(&url)?;
-    match client.get(&url).send().await {
+    match client.get(&url).send_with_retry().await {
         Ok(res) => {
             let body = client::parse_json_body(&url, res).await?;
             log::debug!("got body: {}", body);
@@ -57,7 +58,7 @@ pub async fn get(
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
 
     let client = client::new_for_url(&url)?;
-    match client.get(&url).send().await {
+    match client.get(&url).send_with_retry().await {
         Ok(res) => {
             let body = client::parse_json_body(&url, res).await?;
             log::debug!("got body: {}", body);
@@ -319,7 +320,14 @@ mod tests {
             let schema = api::client::schemas::get(&remote_repo, branch_name, schema_ref).await?;
 
             assert!(schema.is_some());
-            let schema = schema.unwrap().schema;
+            let schema_w_path = schema.unwrap();
+
+            // Changing the schema metadata keeps the column stats from when it was added
+            let column_stats = schema_w_path.column_stats.unwrap();
+            assert_eq!(column_stats.len(), 5);
+            assert_eq!(column_stats[4].name, "difficulty");
+            assert_eq!(column_stats[4].null_count, 0);
+            let schema = schema_w_path.schema;
 
             // prompt,response,is_correct,response_time,difficulty
             assert_eq!(schema.fields.len(), 5);
@@ -5,6 +5,7 @@
 
 use crate::api;
 use crate::api::client;
+use crate::api::client::SendWithRetry;
 use crate::error::OxenError;
 use crate::model::RemoteRepository;
 use crate::view::repository::{RepositoryStatsResponse, RepositoryStatsView};
@@ -14,7 +15,7 @@ pub async fn get(remote_repo: &RemoteRepository) -> Result<RepositoryStatsView,
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
 
     let client = client::new_for_url(&url)?;
-    match client.get(&url).send().await {
+    match client.get(&url).send_with_retry().await {
         Ok(res) => {
             let body = client::parse_json_body(&url, res).await?;
             log::debug!("got body: {}", body);
@@ -0,0 +1,27 @@
+//! Mint scoped, expiring tokens from the token the client is configured with
+//!
+
+use crate::api::client;
+use crate::api::client::SendWithRetry;
+use crate::api::endpoint;
+use crate::error::OxenError;
+use crate::view::token::{NewToken, TokenResponse};
+
+/// Create a new token on the host, limited to what `new_token` asks for
+pub async fn create(host: &str, new_token: &NewToken) -> Result<TokenResponse, OxenError> {
+    let scheme = endpoint::get_scheme(host);
+    let url = format!("{scheme}://{host}/api/tokens");
+    log::debug!("api::client::tokens::create url: {url}");
+
+    let params = serde_json::to_string(new_token)?;
+    let client = client::new_for_url(&url)?;
+    let res = client.post(&url).body(params).send_with_retry().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<TokenResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::client::tokens::create error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
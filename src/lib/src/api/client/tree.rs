@@ -7,9 +7,10 @@ use std::collections::HashSet;
 use std::time;
 
 use crate::api::client;
+use crate::api::client::SendWithRetry;
 use crate::constants::{NODES_DIR, OXEN_HIDDEN_DIR, TREE_DIR};
 use crate::core::v0_19_0::index::merkle_node_db::node_db_path;
-use crate::core::v0_19_0::index::CommitMerkleTree;
+use crate::core::v0_19_0::index::{CommitMerkleTree, MerkleNodeDB};
 use crate::error::OxenError;
 use crate::model::merkle_tree::node::MerkleTreeNode;
 use crate::model::{Commit, LocalRepository, MerkleHash, RemoteRepository};
@@ -27,7 +28,7 @@ pub async fn has_node(
     log::debug!("api::client::tree::has_node {}", url);
 
     let client = client::new_for_url(&url)?;
-    let res = client.get(&url).send().await?;
+    let res = client.get(&url).send_with_retry().await?;
     if res.status() == 404 {
         return Ok(false);
     }
@@ -65,7 +66,7 @@ pub async fn create_nodes(
             sub_dir,
             node_dir
         );
-        tar.append_dir_all(sub_dir, node_dir)?;
+        MerkleNodeDB::append_to_tar(local_repo, &node.hash, &mut tar, sub_dir)?;
     }
 
     tar.finish()?;
@@ -85,7 +86,11 @@ pub async fn create_nodes(
         bytesize::ByteSize::b(size),
         url
     );
-    let res = client.post(&url).body(buffer.to_owned()).send().await?;
+    let res = client
+        .post(&url)
+        .body(buffer.to_owned())
+        .send_idempotent()
+        .await?;
     let body = client::parse_json_body(&url, res).await?;
     log::debug!("upload node complete {}", body);
 
@@ -285,7 +290,7 @@ async fn node_download_request(
     let url = url.as_ref();
     let client = client::new_for_url(url)?;
     log::debug!("node_download_request about to send request {}", url);
-    let res = client.get(url).send().await?;
+    let res = client.get(url).send_with_retry().await?;
     let reader = res
         .bytes_stream()
         .map_err(|e| futures::io::Error::new(futures::io::ErrorKind::Other, e))
@@ -320,7 +325,11 @@ pub async fn list_missing_node_hashes(
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
     let client = client::new_for_url(&url)?;
     let node_hashes = MerkleHashes { hashes: node_ids };
-    let res = client.post(&url).json(&node_hashes).send().await?;
+    let res = client
+        .post(&url)
+        .json(&node_hashes)
+        .send_idempotent()
+        .await?;
     let body = client::parse_json_body(&url, res).await?;
     let response: Result<MerkleHashesResponse, serde_json::Error> = serde_json::from_str(&body);
     match response {
@@ -338,7 +347,7 @@ pub async fn list_missing_file_hashes(
     let uri = format!("/tree/nodes/{node_id}/missing_file_hashes");
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
     let client = client::new_for_url(&url)?;
-    let res = client.get(&url).send().await?;
+    let res = client.get(&url).send_with_retry().await?;
     let body = client::parse_json_body(&url, res).await?;
     let response: Result<MerkleHashesResponse, serde_json::Error> = serde_json::from_str(&body);
     match response {
@@ -357,7 +366,11 @@ pub async fn list_missing_file_hashes_from_commits(
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
     let client = client::new_for_url(&url)?;
     let commit_hashes = MerkleHashes { hashes: commit_ids };
-    let res = client.post(&url).json(&commit_hashes).send().await?;
+    let res = client
+        .post(&url)
+        .json(&commit_hashes)
+        .send_idempotent()
+        .await?;
     let body = client::parse_json_body(&url, res).await?;
     let response: Result<MerkleHashesResponse, serde_json::Error> = serde_json::from_str(&body);
     match response {
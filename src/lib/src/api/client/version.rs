@@ -1,4 +1,5 @@
 use crate::api::client;
+use crate::api::client::SendWithRetry;
 use crate::api::endpoint;
 use crate::error::OxenError;
 use crate::view::version::VersionResponse;
@@ -10,7 +11,7 @@ pub async fn get_remote_version(host: &str) -> Result<String, OxenError> {
     log::debug!("Checking version at url {}", url);
 
     let client = client::new_for_url(&url)?;
-    if let Ok(res) = client.get(&url).send().await {
+    if let Ok(res) = client.get(&url).send_with_retry().await {
         log::debug!("get_remote_version got status: {}", res.status());
         let body = client::parse_json_body(&url, res).await?;
         log::debug!("get_remote_version got body: {}", body);
@@ -33,7 +34,7 @@ pub async fn get_min_oxen_version(host: &str) -> Result<String, OxenError> {
     log::debug!("Checking min cli version at url {}", url);
 
     let client = client::new_for_url(&url)?;
-    if let Ok(res) = client.get(&url).send().await {
+    if let Ok(res) = client.get(&url).send_with_retry().await {
         log::debug!("get_remote_version got status: {}", res.status());
         let body = client::parse_json_body(&url, res).await?;
         log::debug!("get_remote_version got body: {}", body);
@@ -0,0 +1,174 @@
+//! # Batched version transfer
+//!
+//! Push and pull many small files in one request each way, as a tar archive compressed
+//! with zstd. Files are named by their hash in the archive, so the server never sees
+//! working directory paths. Pass the same client to every batch of a transfer, its
+//! connections are reused and multiplexed when the server speaks HTTP/2.
+//!
+
+use async_compression::futures::bufread::ZstdDecoder;
+use async_std::prelude::*;
+use async_tar::Archive;
+use futures_util::TryStreamExt;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Client;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::api;
+use crate::api::client;
+use crate::api::client::SendWithRetry;
+use crate::constants::TAR_ZSTD_CONTENT_TYPE;
+use crate::core::transfer;
+use crate::core::v0_19_0::quarantine;
+use crate::error::OxenError;
+use crate::model::entry::commit_entry::Entry;
+use crate::model::{LocalRepository, MerkleHash, RemoteRepository};
+use crate::util;
+use crate::view::tree::merkle_hashes::MerkleHashes;
+use crate::view::MerkleHashesResponse;
+
+/// Set to talk HTTP/2 to plain http remotes without upgrading, for servers started with h2c
+pub const HTTP2_PRIOR_KNOWLEDGE_ENV: &str = "OXEN_HTTP2_PRIOR_KNOWLEDGE";
+
+/// Client to share between the batches of one push or pull
+pub fn batch_client(remote_repo: &RemoteRepository) -> Result<Client, OxenError> {
+    let url = api::endpoint::url_from_repo(remote_repo, "/versions/batch")?;
+    let mut builder = client::builder_for_url(&url)?;
+    if url.starts_with("http://") && std::env::var(HTTP2_PRIOR_KNOWLEDGE_ENV).is_ok() {
+        builder = builder.http2_prior_knowledge();
+    }
+    Ok(builder.build()?)
+}
+
+/// Older servers don't have the batch routes, push and pull fall back to gzipped bundles
+pub async fn has_batch_transfer(
+    client: &Client,
+    remote_repo: &RemoteRepository,
+) -> Result<bool, OxenError> {
+    let url = api::endpoint::url_from_repo(remote_repo, "/versions/batch")?;
+    let body = MerkleHashes {
+        hashes: HashSet::new(),
+    };
+    let res = client.get(&url).json(&body).send_with_retry().await?;
+    match res.status() {
+        reqwest::StatusCode::NOT_FOUND => Ok(false),
+        status if status.is_success() => Ok(true),
+        _ => {
+            client::parse_json_body(&url, res).await?;
+            Ok(false)
+        }
+    }
+}
+
+/// Pack the version files of `entries` into one zstd compressed tar, named by hash
+fn pack_entries(local_repo: &LocalRepository, entries: &[Entry]) -> Result<Vec<u8>, OxenError> {
+    let encoder = zstd::stream::write::Encoder::new(Vec::new(), 0)?;
+    let mut tar = tar::Builder::new(encoder);
+    for entry in entries {
+        let version_path = util::fs::version_path_for_entry(local_repo, entry);
+        let plain = util::encryption::plain_path(local_repo, &version_path)?;
+        tar.append_path_with_name(plain.path(), entry.hash())?;
+    }
+    let buffer = tar.into_inner()?.finish()?;
+    Ok(buffer)
+}
+
+/// Upload the version files of `entries` in a single request.
+/// Returns the hashes the server stored.
+pub async fn upload_batch(
+    client: &Client,
+    local_repo: &LocalRepository,
+    remote_repo: &RemoteRepository,
+    entries: &[Entry],
+) -> Result<HashSet<MerkleHash>, OxenError> {
+    let buffer = pack_entries(local_repo, entries)?;
+    log::debug!(
+        "upload_batch {} files in {}",
+        entries.len(),
+        bytesize::ByteSize::b(buffer.len() as u64)
+    );
+    transfer::throttle(buffer.len() as u64).await;
+
+    let url = api::endpoint::url_from_repo(remote_repo, "/versions/batch")?;
+    let res = client
+        .post(&url)
+        .header(CONTENT_TYPE, TAR_ZSTD_CONTENT_TYPE)
+        .body(buffer)
+        .send_idempotent()
+        .await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<MerkleHashesResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(response) => Ok(response.hashes),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::client::versions::upload_batch() Could not deserialize response [{err}]\n{body}"
+        ))),
+    }
+}
+
+/// Download the version files of `entries` in a single request, unpacking them into the
+/// versions dir of the repository at `dst` as they stream in. Each file is checked
+/// against its hash before it is moved in place. Returns the bytes written.
+pub async fn download_batch(
+    client: &Client,
+    remote_repo: &RemoteRepository,
+    entries: &[Entry],
+    dst: impl AsRef<Path>,
+) -> Result<u64, OxenError> {
+    let dst = dst.as_ref();
+    let url = api::endpoint::url_from_repo(remote_repo, "/versions/batch")?;
+    let entries = entries
+        .iter()
+        .map(|entry| Ok((MerkleHash::from_str(&entry.hash())?, entry)))
+        .collect::<Result<HashMap<MerkleHash, &Entry>, OxenError>>()?;
+    let hashes: HashSet<MerkleHash> = entries.keys().copied().collect();
+    let body = MerkleHashes {
+        hashes: hashes.clone(),
+    };
+    let res = client.get(&url).json(&body).send_with_retry().await?;
+    if !res.status().is_success() {
+        client::parse_json_body(&url, res).await?;
+        return Err(OxenError::basic_str(format!(
+            "Could not download {} versions",
+            hashes.len()
+        )));
+    }
+
+    let reader = res
+        .bytes_stream()
+        .map_err(|e| futures::io::Error::new(futures::io::ErrorKind::Other, e))
+        .into_async_read();
+    let archive = Archive::new(ZstdDecoder::new(futures::io::BufReader::new(reader)));
+
+    let mut size: u64 = 0;
+    let mut entries = archive.entries()?;
+    while let Some(file) = entries.next().await {
+        let mut file = file?;
+        let name = file.path()?.to_string_lossy().to_string();
+        // Only unpack what we asked for, the name becomes a path
+        let hash = MerkleHash::from_str(&name)?;
+        let Some(entry) = entries.get(&hash) else {
+            return Err(OxenError::basic_str(format!(
+                "Received version {name} that was not requested"
+            )));
+        };
+
+        let version_path =
+            util::fs::version_path_from_hash_and_file(dst, hash.to_string(), PathBuf::new());
+        if let Some(parent) = version_path.parent() {
+            util::fs::create_dir_all(parent)?;
+        }
+        // Move in place once complete, so a failed pull doesn't leave partial versions
+        let part_path = version_path.with_extension("part");
+        file.unpack(&part_path).await?;
+        quarantine::verify_received(dst, &entry.path(), &entry.hash(), &part_path)?;
+        std::fs::rename(&part_path, &version_path)?;
+
+        let num_bytes = file.header().size()?;
+        transfer::throttle(num_bytes).await;
+        size += num_bytes;
+    }
+    Ok(size)
+}
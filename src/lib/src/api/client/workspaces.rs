@@ -9,16 +9,18 @@ pub use commits::commit;
 
 use crate::api;
 use crate::api::client;
+use crate::api::client::SendWithRetry;
 use crate::error::OxenError;
 use crate::model::RemoteRepository;
 use crate::view::workspaces::ListWorkspaceResponseView;
 use crate::view::workspaces::{NewWorkspace, WorkspaceResponse};
+use crate::view::workspaces::{WorkspaceDiff, WorkspaceDiffResponse};
 use crate::view::WorkspaceResponseView;
 
 pub async fn list(remote_repo: &RemoteRepository) -> Result<Vec<WorkspaceResponse>, OxenError> {
     let url = api::endpoint::url_from_repo(remote_repo, "/workspaces")?;
     let client = client::new_for_url(&url)?;
-    let res = client.get(&url).send().await?;
+    let res = client.get(&url).send_with_retry().await?;
     let body = client::parse_json_body(&url, res).await?;
     let response: Result<ListWorkspaceResponseView, serde_json::Error> =
         serde_json::from_str(&body);
@@ -59,7 +61,7 @@ pub async fn create_with_path(
     };
 
     let client = client::new_for_url(&url)?;
-    let res = client.put(&url).json(&body).send().await?;
+    let res = client.put(&url).json(&body).send_with_retry().await?;
 
     let body = client::parse_json_body(&url, res).await?;
     log::debug!("create workspace got body: {}", body);
@@ -81,7 +83,7 @@ pub async fn delete(
     log::debug!("delete workspace {}\n", url);
 
     let client = client::new_for_url(&url)?;
-    let res = client.delete(&url).send().await?;
+    let res = client.delete(&url).send_with_retry().await?;
 
     let body = client::parse_json_body(&url, res).await?;
     log::debug!("delete workspace got body: {}", body);
@@ -94,6 +96,40 @@ pub async fn delete(
     }
 }
 
+/// Preview the changes a workspace commit would make under a path, before committing.
+/// Files list their added, modified or removed status. If the path is a data frame
+/// indexed in the workspace, its changed rows are included as well.
+pub async fn diff(
+    remote_repo: &RemoteRepository,
+    workspace_id: impl AsRef<str>,
+    path: impl AsRef<Path>,
+) -> Result<WorkspaceDiff, OxenError> {
+    let workspace_id = workspace_id.as_ref();
+    let path = path.as_ref();
+    let Some(path_str) = path.to_str() else {
+        return Err(OxenError::basic_str(format!(
+            "Path must be a string: {:?}",
+            path
+        )));
+    };
+    let uri = format!("/workspaces/{workspace_id}/diff/{path_str}");
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!("diff workspace {}\n", url);
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send_with_retry().await?;
+
+    let body = client::parse_json_body(&url, res).await?;
+    log::debug!("diff workspace got body: {}", body);
+    let response: Result<WorkspaceDiffResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val.diff),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -104,7 +140,7 @@ mod tests {
     use crate::constants;
     use crate::constants::DEFAULT_BRANCH_NAME;
     use crate::error::OxenError;
-    use crate::model::NewCommitBody;
+    use crate::model::{NewCommitBody, StagedEntryStatus};
     use crate::opts::DFOpts;
     use crate::repositories;
     use crate::test;
@@ -166,6 +202,54 @@ mod tests {
         .await
     }
 
+    #[tokio::test]
+    async fn test_diff_workspace_before_commit() -> Result<(), OxenError> {
+        // Skip duckdb if on windows
+        if std::env::consts::OS == "windows" {
+            return Ok(());
+        }
+
+        test::run_remote_repo_test_bounding_box_csv_pushed(|remote_repo| async move {
+            let workspace_id = "diff_workspace";
+            create(&remote_repo, DEFAULT_BRANCH_NAME, workspace_id).await?;
+
+            let path = Path::new("annotations")
+                .join("train")
+                .join("bounding_box.csv");
+            let empty_diff = diff(&remote_repo, workspace_id, "").await?;
+            assert!(empty_diff.files.is_empty());
+            assert!(empty_diff.data_frame.is_none());
+
+            api::client::workspaces::data_frames::index(&remote_repo, workspace_id, &path).await?;
+            let data = "{\"file\":\"image1.jpg\", \"label\": \"dog\", \"min_x\":13, \"min_y\":14, \"width\": 100, \"height\": 100}";
+            api::client::workspaces::data_frames::rows::add(
+                &remote_repo,
+                workspace_id,
+                &path,
+                data.to_string(),
+            )
+            .await?;
+
+            let file_diff = diff(&remote_repo, workspace_id, &path).await?;
+            assert_eq!(file_diff.files.len(), 1);
+            assert_eq!(file_diff.files[0].path, path);
+            assert_eq!(file_diff.files[0].status, StagedEntryStatus::Modified);
+            let df_diff = file_diff.data_frame.unwrap();
+            assert_eq!(df_diff.counts.added, 1);
+            assert_eq!(df_diff.counts.modified, 0);
+            assert_eq!(df_diff.counts.removed, 0);
+            assert_eq!(df_diff.rows.source.size.height, 1);
+
+            // Diffing a directory lists the file without its rows
+            let dir_diff = diff(&remote_repo, workspace_id, "annotations").await?;
+            assert_eq!(dir_diff.files.len(), 1);
+            assert!(dir_diff.data_frame.is_none());
+
+            Ok(remote_repo)
+        })
+        .await
+    }
+
     #[tokio::test]
     async fn test_remote_commit_fails_if_schema_changed() -> Result<(), OxenError> {
         // Skip if on windows
@@ -1,6 +1,7 @@
 use crate::api;
 
 use crate::api::client;
+use crate::api::client::SendWithRetry;
 use crate::error::OxenError;
 use crate::model::RemoteRepository;
 use crate::view::{RemoteStagedStatus, RemoteStagedStatusResponse};
@@ -23,7 +24,7 @@ pub async fn list(
     log::debug!("status url: {url}");
 
     let client = client::new_for_url(&url)?;
-    match client.get(&url).send().await {
+    match client.get(&url).send_with_retry().await {
         Ok(res) => {
             let body = client::parse_json_body(&url, res).await?;
             log::debug!("status got body: {}", body);
@@ -53,7 +54,7 @@ pub async fn rm(
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
     log::debug!("rm_file {}", url);
     let client = client::new_for_url(&url)?;
-    match client.delete(&url).send().await {
+    match client.delete(&url).send_with_retry().await {
         Ok(res) => {
             let body = client::parse_json_body(&url, res).await?;
             log::debug!("rm_file got body: {}", body);
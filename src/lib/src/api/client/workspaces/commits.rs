@@ -1,5 +1,6 @@
 use crate::api;
 use crate::api::client;
+use crate::api::client::SendWithRetry;
 use crate::error::OxenError;
 use crate::model::{Branch, Commit, NewCommitBody, RemoteRepository};
 use crate::view::CommitResponse;
@@ -15,7 +16,7 @@ pub async fn commit(
     log::debug!("commit_staged {}\n{:?}", url, commit);
 
     let client = client::new_for_url(&url)?;
-    let res = client.post(&url).json(&commit).send().await?;
+    let res = client.post(&url).json(&commit).send_with_retry().await?;
 
     let body = client::parse_json_body(&url, res).await?;
     log::debug!("commit_staged got body: {}", body);
@@ -2,6 +2,7 @@ use serde::{Deserialize, Serialize};
 
 use crate::api;
 use crate::api::client;
+use crate::api::client::SendWithRetry;
 use crate::error::OxenError;
 use crate::opts::DFOpts;
 use crate::view::entries::PaginatedMetadataEntriesResponse;
@@ -12,8 +13,11 @@ use crate::model::RemoteRepository;
 use crate::view::{JsonDataFrameViewResponse, JsonDataFrameViews, StatusMessage};
 
 pub mod columns;
+pub mod reviews;
 pub mod rows;
 
+pub use rows::{delete_rows, update_rows};
+
 #[derive(Serialize, Deserialize)]
 struct PutParam {
     is_indexed: bool,
@@ -39,7 +43,7 @@ pub async fn get(
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
 
     let client = client::new_for_url(&url)?;
-    match client.get(&url).send().await {
+    match client.get(&url).send_with_retry().await {
         Ok(res) => {
             let body = client::parse_json_body(&url, res).await?;
             let response: Result<WorkspaceJsonDataFrameViewResponse, serde_json::Error> =
@@ -77,7 +81,7 @@ pub async fn list(
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
 
     let client = client::new_for_url(&url)?;
-    match client.get(&url).send().await {
+    match client.get(&url).send_with_retry().await {
         Ok(res) => {
             let body = client::parse_json_body(&url, res).await?;
             let response: Result<PaginatedMetadataEntriesResponse, serde_json::Error> =
@@ -146,7 +150,7 @@ pub async fn put(
     let params = serde_json::to_string(data)?;
 
     let client = client::new_for_url(&url)?;
-    match client.put(&url).body(params).send().await {
+    match client.put(&url).body(params).send_with_retry().await {
         Ok(res) => {
             let body = client::parse_json_body(&url, res).await?;
             let response: Result<StatusMessage, serde_json::Error> = serde_json::from_str(&body);
@@ -177,7 +181,7 @@ pub async fn restore(
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
     log::debug!("workspaces::data_frames::restore {}", url);
     let client = client::new_for_url(&url)?;
-    match client.delete(&url).send().await {
+    match client.delete(&url).send_with_retry().await {
         Ok(res) => {
             let body = client::parse_json_body(&url, res).await?;
             log::debug!("workspaces::data_frames::restore got body: {}", body);
@@ -204,7 +208,7 @@ pub async fn diff(
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
 
     let client = client::new_for_url(&url)?;
-    match client.get(&url).send().await {
+    match client.get(&url).send_with_retry().await {
         Ok(res) => {
             let body = client::parse_json_body(&url, res).await?;
             log::debug!("diff got body: {}", body);
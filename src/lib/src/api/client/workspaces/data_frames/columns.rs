@@ -5,6 +5,7 @@ use serde_json::json;
 
 use crate::api;
 use crate::api::client;
+use crate::api::client::SendWithRetry;
 use crate::error::OxenError;
 use crate::view::json_data_frame_view::JsonDataFrameColumnResponse;
 
@@ -32,7 +33,7 @@ pub async fn create(
         .post(&url)
         .header("Content-Type", "application/json")
         .body(data)
-        .send()
+        .send_with_retry()
         .await
     {
         Ok(res) => {
@@ -74,7 +75,7 @@ pub async fn delete(
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
 
     let client = client::new_for_url(&url)?;
-    match client.delete(&url).send().await {
+    match client.delete(&url).send_with_retry().await {
         Ok(res) => {
             let body: String = client::parse_json_body(&url, res).await?;
             log::debug!("rm_df_mod got body: {}", body);
@@ -120,7 +121,7 @@ pub async fn update(
         .put(&url)
         .header("Content-Type", "application/json")
         .body(data)
-        .send()
+        .send_with_retry()
         .await
     {
         Ok(res) => {
@@ -170,7 +171,7 @@ pub async fn add_column_metadata(
         .post(&url)
         .header("Content-Type", "application/json")
         .body(body.to_string())
-        .send()
+        .send_with_retry()
         .await
     {
         Ok(_) => Ok(()),
@@ -0,0 +1,187 @@
+use std::path::Path;
+
+use polars::frame::DataFrame;
+
+use crate::api;
+use crate::api::client;
+use crate::api::client::SendWithRetry;
+use crate::error::OxenError;
+use crate::model::RemoteRepository;
+use crate::view::data_frames::{RowReview, RowReviewStatus, RowReviewsBody, RowReviewsResponse};
+use crate::view::{JsonDataFrameViewResponse, StatusMessage};
+
+/// Review many rows in one request, the server saves all of them or none
+pub async fn set(
+    remote_repo: &RemoteRepository,
+    workspace_id: &str,
+    path: &Path,
+    reviews: &[RowReview],
+) -> Result<Vec<RowReview>, OxenError> {
+    let file_path_str = path_str(path)?;
+    let uri = format!("/workspaces/{workspace_id}/data_frames/reviews/resource/{file_path_str}");
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!("set {} reviews {url}", reviews.len());
+
+    let body = RowReviewsBody {
+        reviews: reviews.to_vec(),
+    };
+    let client = client::new_for_url(&url)?;
+    let res = client.put(&url).json(&body).send_with_retry().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<RowReviewsResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val.reviews),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::workspaces::data_frames::reviews::set error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
+
+/// A page of the rows with their review columns, only the rows with `status` if it is set
+pub async fn list(
+    remote_repo: &RemoteRepository,
+    workspace_id: &str,
+    path: &Path,
+    status: Option<RowReviewStatus>,
+    page: usize,
+    page_size: usize,
+) -> Result<DataFrame, OxenError> {
+    let file_path_str = path_str(path)?;
+    let mut uri = format!(
+        "/workspaces/{workspace_id}/data_frames/reviews/resource/{file_path_str}?page={page}&page_size={page_size}"
+    );
+    if let Some(status) = status {
+        uri.push_str(&format!("&status={status}"));
+    }
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+
+    let client = client::new_for_url(&url)?;
+    let res = client.get(&url).send_with_retry().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<JsonDataFrameViewResponse, serde_json::Error> =
+        serde_json::from_str(&body);
+    match response {
+        Ok(val) => Ok(val.data_frame.view.to_df()),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::workspaces::data_frames::reviews::list error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
+
+/// Clear the review of a row so it is pending again
+pub async fn delete(
+    remote_repo: &RemoteRepository,
+    workspace_id: &str,
+    path: &Path,
+    row_id: &str,
+) -> Result<(), OxenError> {
+    let file_path_str = path_str(path)?;
+    let uri =
+        format!("/workspaces/{workspace_id}/data_frames/reviews/{row_id}/resource/{file_path_str}");
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+
+    let client = client::new_for_url(&url)?;
+    let res = client.delete(&url).send_with_retry().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<StatusMessage, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(_) => Ok(()),
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::workspaces::data_frames::reviews::delete error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
+
+fn path_str(path: &Path) -> Result<&str, OxenError> {
+    path.to_str()
+        .ok_or_else(|| OxenError::basic_str(format!("Path must be a string: {:?}", path)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::api;
+    use crate::config::UserConfig;
+    use crate::constants::{DEFAULT_BRANCH_NAME, OXEN_ID_COL, REVIEW_STATUS_COL};
+    use crate::error::OxenError;
+    use crate::opts::DFOpts;
+    use crate::test;
+    use crate::view::data_frames::{RowReview, RowReviewStatus};
+
+    #[tokio::test]
+    async fn test_review_rows_in_remote_workspace() -> Result<(), OxenError> {
+        // Skip duckdb if on windows
+        if std::env::consts::OS == "windows" {
+            return Ok(());
+        }
+
+        test::run_remote_repo_test_bounding_box_csv_pushed(|remote_repo| async move {
+            let workspace_id = UserConfig::identifier()?;
+            api::client::workspaces::create(&remote_repo, DEFAULT_BRANCH_NAME, &workspace_id)
+                .await?;
+            let path = Path::new("annotations")
+                .join("train")
+                .join("bounding_box.csv");
+            api::client::workspaces::data_frames::index(&remote_repo, &workspace_id, &path).await?;
+
+            let response = api::client::workspaces::data_frames::get(
+                &remote_repo,
+                &workspace_id,
+                &path,
+                DFOpts::empty(),
+            )
+            .await?;
+            let df = response.data_frame.unwrap().view.to_df();
+            let row_id = df.column(OXEN_ID_COL)?.get(0)?.to_string().replace('"', "");
+
+            let review = RowReview {
+                row_id: row_id.clone(),
+                status: RowReviewStatus::Approved,
+                comment: Some("looks good".to_string()),
+            };
+            let reviews = api::client::workspaces::data_frames::reviews::set(
+                &remote_repo,
+                &workspace_id,
+                &path,
+                &[review.clone()],
+            )
+            .await?;
+            assert_eq!(reviews, vec![review]);
+
+            let approved = api::client::workspaces::data_frames::reviews::list(
+                &remote_repo,
+                &workspace_id,
+                &path,
+                Some(RowReviewStatus::Approved),
+                1,
+                10,
+            )
+            .await?;
+            assert_eq!(approved.height(), 1);
+            let status = approved.column(REVIEW_STATUS_COL)?.get(0)?.to_string();
+            assert_eq!(status.replace('"', ""), "approved");
+
+            api::client::workspaces::data_frames::reviews::delete(
+                &remote_repo,
+                &workspace_id,
+                &path,
+                &row_id,
+            )
+            .await?;
+            let approved = api::client::workspaces::data_frames::reviews::list(
+                &remote_repo,
+                &workspace_id,
+                &path,
+                Some(RowReviewStatus::Approved),
+                1,
+                10,
+            )
+            .await?;
+            assert_eq!(approved.height(), 0);
+
+            Ok(remote_repo)
+        })
+        .await
+    }
+}
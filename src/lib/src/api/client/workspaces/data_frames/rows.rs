@@ -4,8 +4,10 @@ use polars::frame::DataFrame;
 
 use crate::api;
 use crate::api::client;
+use crate::api::client::SendWithRetry;
 use crate::error::OxenError;
-use crate::view::json_data_frame_view::JsonDataFrameRowResponse;
+use crate::view::data_frames::{BatchDeleteRowsBody, BatchUpdateRowsBody, RowUpdate};
+use crate::view::json_data_frame_view::{JsonDataFrameRowResponse, JsonDataFrameRowsResponse};
 
 use crate::model::RemoteRepository;
 
@@ -27,7 +29,7 @@ pub async fn get(
     log::debug!("get_row {url}\n{row_id}");
 
     let client = client::new_for_url(&url)?;
-    match client.get(&url).send().await {
+    match client.get(&url).send_with_retry().await {
         Ok(res) => {
             let body = client::parse_json_body(&url, res).await?;
             let response: Result<JsonDataFrameRowResponse, serde_json::Error> =
@@ -71,7 +73,7 @@ pub async fn update(
         .put(&url)
         .header("Content-Type", "application/json")
         .body(data)
-        .send()
+        .send_with_retry()
         .await
     {
         Ok(res) => {
@@ -112,7 +114,7 @@ pub async fn delete(
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
 
     let client = client::new_for_url(&url)?;
-    match client.delete(&url).send().await {
+    match client.delete(&url).send_with_retry().await {
         Ok(res) => {
             let body = client::parse_json_body(&url, res).await?;
             log::debug!("rm_df_mod got body: {}", body);
@@ -155,7 +157,7 @@ pub async fn add(
         .post(&url)
         .header("Content-Type", "application/json")
         .body(data)
-        .send()
+        .send_with_retry()
         .await
     {
         Ok(res) => {
@@ -198,7 +200,7 @@ pub async fn restore_row(
     match client
         .post(&url)
         .header("Content-Type", "application/json")
-        .send()
+        .send_with_retry()
         .await
     {
         Ok(res) => {
@@ -220,6 +222,90 @@ pub async fn restore_row(
     }
 }
 
+/// Update many rows in one request. The server applies them in a single transaction,
+/// so if any row fails to update none of them are changed.
+pub async fn update_rows(
+    remote_repo: &RemoteRepository,
+    workspace_id: &str,
+    path: &Path,
+    updates: &[RowUpdate],
+) -> Result<DataFrame, OxenError> {
+    let body = BatchUpdateRowsBody {
+        rows: updates.to_vec(),
+    };
+    let body = serde_json::to_string(&body)?;
+    batch_request(remote_repo, workspace_id, path, reqwest::Method::PUT, body).await
+}
+
+/// Delete many rows in one request. The server removes them in a single transaction,
+/// so if any row fails to delete none of them are removed.
+pub async fn delete_rows(
+    remote_repo: &RemoteRepository,
+    workspace_id: &str,
+    path: &Path,
+    row_ids: &[String],
+) -> Result<DataFrame, OxenError> {
+    let body = BatchDeleteRowsBody {
+        row_ids: row_ids.to_vec(),
+    };
+    let body = serde_json::to_string(&body)?;
+    batch_request(
+        remote_repo,
+        workspace_id,
+        path,
+        reqwest::Method::DELETE,
+        body,
+    )
+    .await
+}
+
+async fn batch_request(
+    remote_repo: &RemoteRepository,
+    workspace_id: &str,
+    path: &Path,
+    method: reqwest::Method,
+    body: String,
+) -> Result<DataFrame, OxenError> {
+    let Some(file_path_str) = path.to_str() else {
+        return Err(OxenError::basic_str(format!(
+            "Path must be a string: {:?}",
+            path
+        )));
+    };
+
+    let uri = format!("/workspaces/{workspace_id}/data_frames/rows/batch/resource/{file_path_str}");
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!("batch {method} rows {url}");
+
+    let client = client::new_for_url(&url)?;
+    match client
+        .request(method, &url)
+        .header("Content-Type", "application/json")
+        .body(body)
+        .send_with_retry()
+        .await
+    {
+        Ok(res) => {
+            let body = client::parse_json_body(&url, res).await?;
+            let response: Result<JsonDataFrameRowsResponse, serde_json::Error> =
+                serde_json::from_str(&body);
+            match response {
+                Ok(val) => Ok(val.data_frame.view.to_df()),
+                Err(err) => {
+                    let err = format!("api::workspaces::data_frames::rows::batch_request error parsing response from {url}\n\nErr {err:?} \n\n{body}");
+                    Err(OxenError::basic_str(err))
+                }
+            }
+        }
+        Err(err) => {
+            let err = format!(
+                "api::workspaces::data_frames::rows::batch_request Request failed: {url}\n\nErr {err:?}"
+            );
+            Err(OxenError::basic_str(err))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -233,6 +319,7 @@ mod tests {
     use crate::opts::DFOpts;
     use crate::repositories;
     use crate::test;
+    use crate::view::data_frames::RowUpdate;
     use crate::view::json_data_frame_view::JsonDataFrameRowResponse;
     use polars::prelude::AnyValue;
 
@@ -582,6 +669,112 @@ mod tests {
         .await
     }
 
+    #[tokio::test]
+    async fn test_batch_update_and_delete_rows() -> Result<(), OxenError> {
+        // Skip duckdb if on windows
+        if std::env::consts::OS == "windows" {
+            return Ok(());
+        }
+
+        test::run_remote_repo_test_bounding_box_csv_pushed(|remote_repo| async move {
+            let workspace_id = UserConfig::identifier()?;
+            api::client::workspaces::create(&remote_repo, DEFAULT_BRANCH_NAME, &workspace_id)
+                .await?;
+
+            let path = Path::new("annotations")
+                .join("train")
+                .join("bounding_box.csv");
+            api::client::workspaces::data_frames::index(&remote_repo, &workspace_id, &path).await?;
+
+            let df = api::client::workspaces::data_frames::get(
+                &remote_repo,
+                &workspace_id,
+                &path,
+                DFOpts::empty(),
+            )
+            .await?;
+            let row_ids: Vec<String> = df
+                .data_frame
+                .unwrap()
+                .view
+                .data
+                .as_array()
+                .unwrap()
+                .iter()
+                .take(3)
+                .map(|row| row.get("_oxen_id").unwrap().as_str().unwrap().to_string())
+                .collect();
+
+            let updates: Vec<RowUpdate> = row_ids[..2]
+                .iter()
+                .map(|row_id| RowUpdate {
+                    row_id: row_id.to_owned(),
+                    value: serde_json::json!({"label": "batched"}),
+                })
+                .collect();
+            let updated = api::client::workspaces::data_frames::rows::update_rows(
+                &remote_repo,
+                &workspace_id,
+                &path,
+                &updates,
+            )
+            .await?;
+            assert_eq!(updated.height(), 2);
+
+            // One unknown row rolls back the whole batch
+            let result = api::client::workspaces::data_frames::rows::delete_rows(
+                &remote_repo,
+                &workspace_id,
+                &path,
+                &[row_ids[2].to_owned(), "not-a-row".to_string()],
+            )
+            .await;
+            assert!(result.is_err());
+            let row = api::client::workspaces::data_frames::rows::get(
+                &remote_repo,
+                &workspace_id,
+                &path,
+                &row_ids[2],
+            )
+            .await?;
+            let data: Value = serde_json::from_value(row.data_frame.view.data[0].clone()).unwrap();
+            assert_eq!(data.get("_oxen_diff_status").unwrap(), "unchanged");
+
+            let deleted = api::client::workspaces::data_frames::rows::delete_rows(
+                &remote_repo,
+                &workspace_id,
+                &path,
+                &row_ids[1..],
+            )
+            .await?;
+            assert_eq!(deleted.height(), 2);
+
+            let row = api::client::workspaces::data_frames::rows::get(
+                &remote_repo,
+                &workspace_id,
+                &path,
+                &row_ids[0],
+            )
+            .await?;
+            let data: Value = serde_json::from_value(row.data_frame.view.data[0].clone()).unwrap();
+            assert_eq!(data.get("label").unwrap(), "batched");
+            assert_eq!(data.get("_oxen_diff_status").unwrap(), "modified");
+
+            let row = api::client::workspaces::data_frames::rows::get(
+                &remote_repo,
+                &workspace_id,
+                &path,
+                &row_ids[2],
+            )
+            .await?;
+            let data: Value = serde_json::from_value(row.data_frame.view.data[0].clone()).unwrap();
+            assert_eq!(data.get("_oxen_diff_status").unwrap(), "removed");
+
+            Ok(remote_repo)
+        })
+        .await
+    }
+
     #[tokio::test]
     async fn test_remote_stage_delete_row_clears_remote_status() -> Result<(), OxenError> {
         if std::env::consts::OS == "windows" {
@@ -1,11 +1,15 @@
 use crate::api;
 use crate::api::client;
+use crate::api::client::SendWithRetry;
+use crate::constants;
 use crate::error::OxenError;
-use crate::model::RemoteRepository;
+use crate::model::{FailedUpload, RemoteRepository, WorkspaceUploadResult};
 
+use crate::view::workspaces::{CompleteUploadBody, MoveFileBody};
 use crate::view::FilePathsResponse;
 
 use bytesize::ByteSize;
+use futures::prelude::*;
 use pluralizer::pluralize;
 use std::path::{Path, PathBuf};
 
@@ -26,10 +30,8 @@ pub async fn add(
 
     // * make sure file is not in .oxenignore
     let ignore = oxenignore::create(local_repo);
-    if let Some(ignore) = ignore {
-        if ignore.matched(path, path.is_dir()).is_ignore() {
-            return Ok(());
-        }
+    if ignore.is_ignored(path, path.is_dir()) {
+        return Ok(());
     }
 
     let (remote_directory, resolved_path) = resolve_remote_add_file_path(local_repo, path, opts)?;
@@ -123,7 +125,7 @@ pub async fn post_file(
     let file_part = reqwest::multipart::Part::bytes(file).file_name(file_name);
     let form = reqwest::multipart::Form::new().part("file", file_part);
     let client = client::new_for_url(&url)?;
-    match client.post(&url).multipart(form).send().await {
+    match client.post(&url).multipart(form).send_with_retry().await {
         Ok(res) => {
             let body = client::parse_json_body(&url, res).await?;
             let response: Result<FilePathsResponse, serde_json::Error> =
@@ -150,62 +152,231 @@ pub async fn post_file(
     }
 }
 
+/// Upload files into a directory of a workspace.
+///
+/// Each file is uploaded on its own and retried with back off, so one bad file does not
+/// fail the whole batch. Files that cannot be read are skipped with a warning and are
+/// reported as failed along with the ones the server did not accept. Files larger than
+/// `WORKSPACE_UPLOAD_CHUNK_SIZE` are uploaded in parts with `upload_file_in_chunks`.
 pub async fn add_many(
     remote_repo: &RemoteRepository,
     workspace_id: &str,
     directory_name: &str,
     paths: Vec<PathBuf>,
-) -> Result<Vec<PathBuf>, OxenError> {
-    // Check if the total size of the files is too large (over 100mb for now)
-    let limit = 100_000_000;
-    let total_size: u64 = paths.iter().map(|p| p.metadata().unwrap().len()).sum();
-    if total_size > limit {
-        let error_msg = format!("Total size of files to upload is too large. {} > {} Consider using `oxen push` instead for now until upload supports bulk push.", ByteSize::b(total_size), ByteSize::b(limit));
-        return Err(OxenError::basic_str(error_msg));
+) -> Result<WorkspaceUploadResult, OxenError> {
+    let mut result = WorkspaceUploadResult::default();
+
+    let mut to_upload: Vec<PathBuf> = Vec::new();
+    let mut total_size: u64 = 0;
+    for path in paths {
+        match path.metadata() {
+            Ok(metadata) if metadata.is_file() => {
+                total_size += metadata.len();
+                to_upload.push(path);
+            }
+            Ok(_) => {
+                log::warn!("Skipping upload of {:?}, it is not a file", path);
+                result.failed.push(FailedUpload {
+                    path,
+                    reason: String::from("Not a file"),
+                });
+            }
+            Err(err) => {
+                log::warn!("Skipping upload of {:?}, could not read it: {}", path, err);
+                result.failed.push(FailedUpload {
+                    path,
+                    reason: format!("Could not read file: {err}"),
+                });
+            }
+        }
     }
 
     println!(
         "Uploading {} from {} {}",
         ByteSize(total_size),
-        paths.len(),
-        pluralize("file", paths.len() as isize, true)
+        to_upload.len(),
+        pluralize("file", to_upload.len() as isize, true)
     );
 
-    let uri = format!("/workspaces/{workspace_id}/files/{directory_name}");
-    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    let uploads: Vec<(PathBuf, Result<PathBuf, OxenError>)> = stream::iter(to_upload)
+        .map(|path| async move {
+            // Large files go up in parts, so there is no limit on their size
+            let is_large = path
+                .metadata()
+                .map(|metadata| metadata.len() > constants::WORKSPACE_UPLOAD_CHUNK_SIZE)
+                .unwrap_or(false);
+            let uploaded = if is_large {
+                upload_file_in_chunks(
+                    remote_repo,
+                    workspace_id,
+                    directory_name,
+                    &path,
+                    constants::WORKSPACE_UPLOAD_CHUNK_SIZE,
+                )
+                .await
+            } else {
+                post_file_with_retry(remote_repo, workspace_id, directory_name, &path).await
+            };
+            (path, uploaded)
+        })
+        .buffer_unordered(constants::DEFAULT_NUM_WORKERS)
+        .collect()
+        .await;
+
+    for (path, uploaded) in uploads {
+        match uploaded {
+            Ok(remote_path) => result.added.push(remote_path),
+            Err(err) => {
+                log::error!("Could not upload {:?}: {}", path, err);
+                result.failed.push(FailedUpload {
+                    path,
+                    reason: err.to_string(),
+                });
+            }
+        }
+    }
 
-    let mut form = reqwest::multipart::Form::new();
-    for path in paths {
-        let file_name = path
-            .file_name()
-            .unwrap()
-            .to_os_string()
-            .into_string()
-            .ok()
-            .unwrap();
-        let file = std::fs::read(&path).unwrap();
-        let file_part = reqwest::multipart::Part::bytes(file).file_name(file_name);
-        form = form.part("file[]", file_part);
+    Ok(result)
+}
+
+async fn post_file_with_retry(
+    remote_repo: &RemoteRepository,
+    workspace_id: &str,
+    directory_name: &str,
+    path: &Path,
+) -> Result<PathBuf, OxenError> {
+    let mut num_tries = 0;
+    loop {
+        match post_file(remote_repo, workspace_id, directory_name, path).await {
+            Ok(remote_path) => return Ok(remote_path),
+            Err(err) => {
+                num_tries += 1;
+                if num_tries >= constants::NUM_HTTP_RETRIES {
+                    return Err(err);
+                }
+                // Exponentially back off
+                let sleep_time = num_tries * num_tries;
+                log::debug!(
+                    "post_file_with_retry upload of {:?} failed sleeping {}: {}",
+                    path,
+                    sleep_time,
+                    err
+                );
+                tokio::time::sleep(std::time::Duration::from_secs(sleep_time)).await;
+            }
+        }
     }
+}
+
+/// Upload a large file to a directory of a workspace in parts of `chunk_size` bytes.
+///
+/// Parts are sent in parallel and each is retried with back off. The server checks the
+/// hash of every part, then combines them and checks the hash of the whole file before
+/// staging it, so a corrupt upload is never added to the workspace.
+pub async fn upload_file_in_chunks(
+    remote_repo: &RemoteRepository,
+    workspace_id: impl AsRef<str>,
+    directory: impl AsRef<Path>,
+    path: impl AsRef<Path>,
+    chunk_size: u64,
+) -> Result<PathBuf, OxenError> {
+    let workspace_id = workspace_id.as_ref();
+    let directory = directory.as_ref();
+    let path = path.as_ref();
+    let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+        return Err(OxenError::basic_str(format!(
+            "Invalid file name for upload: {path:?}"
+        )));
+    };
 
+    let total_size = path.metadata()?.len();
+    let chunk_size = chunk_size.max(1);
+    let total_chunks = total_size.div_ceil(chunk_size).max(1) as usize;
+    let hash = util::hasher::hash_file_contents(path)?;
+    let upload_id = uuid::Uuid::new_v4().to_string();
+    log::debug!(
+        "upload_file_in_chunks {:?} {} in {} chunks as upload {}",
+        path,
+        ByteSize::b(total_size),
+        total_chunks,
+        upload_id
+    );
+
+    let upload_id_ref = &upload_id;
+    let results: Vec<Result<(), OxenError>> = stream::iter(0..total_chunks)
+        .map(|chunk_num| async move {
+            let chunk = util::fs::read_chunk(path, chunk_num, chunk_size)?;
+            upload_chunk_with_retry(remote_repo, workspace_id, upload_id_ref, chunk_num, chunk)
+                .await
+        })
+        .buffer_unordered(constants::DEFAULT_NUM_WORKERS)
+        .collect()
+        .await;
+    for result in results {
+        result?;
+    }
+
+    let body = CompleteUploadBody {
+        file_name: file_name.to_string(),
+        total_chunks,
+        hash,
+    };
+    let directory_name = directory.to_string_lossy();
+    let uri = format!("/workspaces/{workspace_id}/uploads/{upload_id}/complete/{directory_name}");
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
     let client = client::new_for_url(&url)?;
-    match client.post(&url).multipart(form).send().await {
-        Ok(res) => {
-            let body = client::parse_json_body(&url, res).await?;
-            let response: Result<FilePathsResponse, serde_json::Error> =
-                serde_json::from_str(&body);
-            match response {
-                Ok(val) => Ok(val.paths),
-                Err(err) => {
-                    let err = format!("api::staging::add_files error parsing response from {url}\n\nErr {err:?} \n\n{body}");
-                    Err(OxenError::basic_str(err))
+    let res = client.post(&url).json(&body).send_with_retry().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<FilePathsResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(val) => match val.paths.first() {
+            Some(path) => Ok(path.clone()),
+            None => Err(OxenError::basic_str("No file path returned from server")),
+        },
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::workspaces::files::upload_file_in_chunks error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
+
+async fn upload_chunk_with_retry(
+    remote_repo: &RemoteRepository,
+    workspace_id: &str,
+    upload_id: &str,
+    chunk_num: usize,
+    chunk: Vec<u8>,
+) -> Result<(), OxenError> {
+    let hash = util::hasher::hash_buffer(&chunk);
+    let uri =
+        format!("/workspaces/{workspace_id}/uploads/{upload_id}/chunks/{chunk_num}?hash={hash}");
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    let client = client::new_for_url(&url)?;
+
+    let mut num_tries = 0;
+    loop {
+        let result = match client.put(&url).body(chunk.clone()).send_with_retry().await {
+            Ok(res) => client::parse_json_body(&url, res).await.map(|_| ()),
+            Err(err) => Err(OxenError::from(err)),
+        };
+        match result {
+            Ok(_) => return Ok(()),
+            Err(err) => {
+                num_tries += 1;
+                if num_tries >= constants::NUM_HTTP_RETRIES {
+                    return Err(err);
                 }
+                // Exponentially back off
+                let sleep_time = num_tries * num_tries;
+                log::debug!(
+                    "upload_chunk_with_retry chunk {} of {} failed sleeping {}: {}",
+                    chunk_num,
+                    upload_id,
+                    sleep_time,
+                    err
+                );
+                tokio::time::sleep(std::time::Duration::from_secs(sleep_time)).await;
             }
         }
-        Err(err) => {
-            let err = format!("api::staging::add_files Request failed: {url}\n\nErr {err:?}");
-            Err(OxenError::basic_str(err))
-        }
     }
 }
 
@@ -219,7 +390,7 @@ pub async fn rm(
     let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
     log::debug!("rm_file {}", url);
     let client = client::new_for_url(&url)?;
-    match client.delete(&url).send().await {
+    match client.delete(&url).send_with_retry().await {
         Ok(res) => {
             let body = client::parse_json_body(&url, res).await?;
             log::debug!("rm_file got body: {}", body);
@@ -232,6 +403,35 @@ pub async fn rm(
     }
 }
 
+/// Move a committed file to `new_path` in the workspace, without downloading it
+pub async fn mv(
+    remote_repo: &RemoteRepository,
+    workspace_id: &str,
+    path: impl AsRef<Path>,
+    new_path: impl AsRef<Path>,
+) -> Result<PathBuf, OxenError> {
+    let file_name = path.as_ref().to_string_lossy();
+    let uri = format!("/workspaces/{workspace_id}/mv/{file_name}");
+    let url = api::endpoint::url_from_repo(remote_repo, &uri)?;
+    log::debug!("mv_file {}", url);
+    let body = MoveFileBody {
+        new_path: new_path.as_ref().to_path_buf(),
+    };
+    let client = client::new_for_url(&url)?;
+    let res = client.post(&url).json(&body).send_with_retry().await?;
+    let body = client::parse_json_body(&url, res).await?;
+    let response: Result<FilePathsResponse, serde_json::Error> = serde_json::from_str(&body);
+    match response {
+        Ok(val) => match val.paths.first() {
+            Some(path) => Ok(path.clone()),
+            None => Err(OxenError::basic_str("No file path returned from server")),
+        },
+        Err(err) => Err(OxenError::basic_str(format!(
+            "api::workspaces::files::mv error parsing response from {url}\n\nErr {err:?} \n\n{body}"
+        ))),
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -342,6 +542,84 @@ mod tests {
         .await
     }
 
+    #[tokio::test]
+    async fn test_upload_file_in_chunks() -> Result<(), OxenError> {
+        test::run_remote_repo_test_bounding_box_csv_pushed(|remote_repo| async move {
+            let workspace_id = UserConfig::identifier()?;
+            api::client::workspaces::create(&remote_repo, DEFAULT_BRANCH_NAME, &workspace_id)
+                .await?;
+
+            // Small parts so the image is split into many of them
+            let path = test::test_img_file();
+            let remote_path = api::client::workspaces::files::upload_file_in_chunks(
+                &remote_repo,
+                &workspace_id,
+                "images",
+                &path,
+                1024,
+            )
+            .await?;
+            assert_eq!(
+                remote_path,
+                Path::new("images").join(path.file_name().unwrap())
+            );
+
+            let entries = api::client::workspaces::changes::list(
+                &remote_repo,
+                &workspace_id,
+                Path::new("images"),
+                constants::DEFAULT_PAGE_NUM,
+                constants::DEFAULT_PAGE_SIZE,
+            )
+            .await?;
+            assert_eq!(entries.added_files.total_entries, 1);
+
+            Ok(remote_repo)
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_stage_multiple_files_reports_unreadable_files() -> Result<(), OxenError> {
+        test::run_remote_repo_test_bounding_box_csv_pushed(|remote_repo| async move {
+            let workspace_id = UserConfig::identifier()?;
+            api::client::workspaces::create(&remote_repo, DEFAULT_BRANCH_NAME, &workspace_id)
+                .await?;
+
+            let directory_name = "data";
+            let missing = Path::new("does_not_exist.jpg").to_path_buf();
+            let paths = vec![
+                test::test_img_file(),
+                missing.clone(),
+                test::test_img_file_with_name("cole_anthony.jpeg"),
+            ];
+            let result = api::client::workspaces::files::add_many(
+                &remote_repo,
+                &workspace_id,
+                directory_name,
+                paths,
+            )
+            .await?;
+            assert!(!result.is_complete());
+            assert_eq!(result.added.len(), 2);
+            assert_eq!(result.failed.len(), 1);
+            assert_eq!(result.failed[0].path, missing);
+
+            let entries = api::client::workspaces::changes::list(
+                &remote_repo,
+                &workspace_id,
+                Path::new(directory_name),
+                constants::DEFAULT_PAGE_NUM,
+                constants::DEFAULT_PAGE_SIZE,
+            )
+            .await?;
+            assert_eq!(entries.added_files.total_entries, 2);
+
+            Ok(remote_repo)
+        })
+        .await
+    }
+
     #[tokio::test]
     async fn test_create_remote_readme_repo_and_commit_multiple_data_frames(
     ) -> Result<(), OxenError> {
@@ -848,4 +1126,53 @@ mod tests {
         })
         .await
     }
+
+    #[tokio::test]
+    async fn test_mv_committed_file() -> Result<(), OxenError> {
+        test::run_remote_repo_test_bounding_box_csv_pushed(|remote_repo| async move {
+            let workspace_id = UserConfig::identifier()?;
+            api::client::workspaces::create(&remote_repo, DEFAULT_BRANCH_NAME, &workspace_id)
+                .await?;
+
+            let path = Path::new("annotations")
+                .join("train")
+                .join("bounding_box.csv");
+            let new_path = Path::new("labels").join("bounding_box.csv");
+            let moved =
+                api::client::workspaces::files::mv(&remote_repo, &workspace_id, &path, &new_path)
+                    .await?;
+            assert_eq!(moved, new_path);
+
+            // Only committed files can be moved
+            let missing = Path::new("annotations").join("missing.csv");
+            let result =
+                api::client::workspaces::files::mv(&remote_repo, &workspace_id, missing, &path)
+                    .await;
+            assert!(result.is_err());
+
+            let body = NewCommitBody {
+                message: "Move the bounding boxes".to_string(),
+                author: "Test User".to_string(),
+                email: "test@oxen.ai".to_string(),
+            };
+            api::client::workspaces::commit(
+                &remote_repo,
+                DEFAULT_BRANCH_NAME,
+                &workspace_id,
+                &body,
+            )
+            .await?;
+
+            let entry =
+                api::client::entries::get_entry(&remote_repo, &new_path, DEFAULT_BRANCH_NAME)
+                    .await?;
+            assert_eq!(entry.filename, "bounding_box.csv");
+            let result =
+                api::client::entries::get_entry(&remote_repo, &path, DEFAULT_BRANCH_NAME).await;
+            assert!(result.is_err());
+
+            Ok(remote_repo)
+        })
+        .await
+    }
 }
@@ -0,0 +1,79 @@
+//! # gRPC sync api
+//!
+//! Types generated from `proto/sync.proto` and the metadata keys shared by the server and
+//! [crate::api::client::grpc]. Only built with the `grpc` feature.
+//!
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use crate::error::OxenError;
+use crate::model::{LocalRepository, MerkleHash};
+use crate::util;
+use crate::util::encryption::Encoding;
+
+/// Generated messages, client and server for the `oxen.sync.v1` package
+pub mod proto {
+    tonic::include_proto!("oxen.sync.v1");
+}
+
+/// Metadata key with the namespace of the repository a call is for
+pub const NAMESPACE_METADATA_KEY: &str = "x-oxen-namespace";
+/// Metadata key with the name of the repository a call is for
+pub const REPO_NAME_METADATA_KEY: &str = "x-oxen-repo";
+
+/// Bytes sent per message when streaming files and tree archives
+pub const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Read the plain contents of the version of `hash` in chunks and hand each one to `send`,
+/// decoding compressed and encrypted versions on the way. Empty files still make one
+/// chunk, so the other side knows about them. Returns false once `send` does.
+pub fn read_version_chunks(
+    repo: &LocalRepository,
+    hash: &MerkleHash,
+    mut send: impl FnMut(proto::EntryChunk) -> bool,
+) -> Result<bool, OxenError> {
+    let version_path = util::fs::version_path_from_hash(repo, hash.to_string());
+    let plain_path = util::encryption::plain_path(repo, &version_path)?;
+    let mut file = File::open(plain_path.path())?;
+    let num_bytes = file.metadata()?.len();
+    let mut offset = 0;
+    loop {
+        let mut data = Vec::with_capacity(CHUNK_SIZE);
+        (&mut file).take(CHUNK_SIZE as u64).read_to_end(&mut data)?;
+        let next_offset = offset + data.len() as u64;
+        let last = next_offset >= num_bytes;
+        if data.is_empty() && !last {
+            return Err(OxenError::basic_str(format!(
+                "Version {hash} ended after {offset} of {num_bytes} bytes"
+            )));
+        }
+        let chunk = proto::EntryChunk {
+            hash: hash.to_string(),
+            offset,
+            data,
+            last,
+        };
+        if !send(chunk) {
+            return Ok(false);
+        }
+        if last {
+            return Ok(true);
+        }
+        offset = next_offset;
+    }
+}
+
+/// Move a version that was received into `part_path` to its version path, and compress
+/// and encrypt it the way the repository stores its versions
+pub fn store_version(
+    repo: &LocalRepository,
+    hash: &MerkleHash,
+    part_path: impl AsRef<Path>,
+) -> Result<(), OxenError> {
+    let version_path = Encoding::strip(util::fs::version_path_from_hash(repo, hash.to_string()));
+    std::fs::rename(part_path, &version_path)?;
+    util::encryption::store_in_place(repo, &version_path)?;
+    Ok(())
+}
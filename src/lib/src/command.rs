@@ -7,7 +7,11 @@ pub mod commit_cache;
 pub mod config;
 pub mod db;
 pub mod df;
+pub mod fetch;
 pub mod migrate;
+pub mod mv;
 
 pub use crate::command::df::{df, schema};
+pub use crate::command::fetch::fetch;
+pub use crate::command::mv::mv;
 pub use crate::repositories::add::add;
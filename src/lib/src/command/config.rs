@@ -3,8 +3,10 @@
 //! Configuration commands for Oxen
 //!
 
+use crate::core;
 use crate::error::OxenError;
-use crate::model::{LocalRepository, Remote};
+use crate::model::{CheckoutMode, LocalRepository, Remote, RemoteBranch};
+use crate::util;
 
 /// # Set the remote for a repository
 /// Tells the CLI where to push the changes to
@@ -18,10 +20,120 @@ pub fn set_remote(repo: &mut LocalRepository, name: &str, url: &str) -> Result<R
     Ok(remote)
 }
 
+/// # Add a new remote to a repository
+/// Unlike `set_remote`, errors if the name is taken and does not change the current remote
+pub fn add_remote(repo: &mut LocalRepository, name: &str, url: &str) -> Result<Remote, OxenError> {
+    if repo.has_remote(name) {
+        return Err(OxenError::basic_str(format!(
+            "Remote '{name}' already exists"
+        )));
+    }
+    if url::Url::parse(url).is_err() {
+        return Err(OxenError::invalid_set_remote_url(url));
+    }
+
+    let current = repo.remote().map(|r| r.name);
+    let remote = repo.set_remote(name, url);
+    if let Some(current) = current {
+        // set_remote switches the current remote, keep the one we had
+        repo.set_remote_name(&current);
+    }
+    repo.save_default()?;
+    Ok(remote)
+}
+
+/// # Rename a remote
+/// Branches tracking the remote are updated to track it under the new name
+pub fn rename_remote(
+    repo: &mut LocalRepository,
+    old_name: &str,
+    new_name: &str,
+) -> Result<Remote, OxenError> {
+    let remote = repo.rename_remote(old_name, new_name)?;
+    repo.save_default()?;
+    Ok(remote)
+}
+
+/// # Set the upstream of a branch
+/// `oxen push` and `oxen pull` on the branch go to `remote`/`merge` when no remote is given
+pub fn set_upstream(
+    repo: &mut LocalRepository,
+    branch: &str,
+    remote: &str,
+    merge: &str,
+) -> Result<(), OxenError> {
+    if !repo.has_remote(remote) {
+        return Err(OxenError::remote_not_set(remote));
+    }
+    repo.set_upstream(branch, remote, merge);
+    repo.save_default()?;
+    Ok(())
+}
+
+/// # Stop a branch from tracking an upstream
+pub fn unset_upstream(repo: &mut LocalRepository, branch: &str) -> Result<(), OxenError> {
+    repo.unset_upstream(branch);
+    repo.save_default()?;
+    Ok(())
+}
+
 /// # Remove the remote for a repository
 /// If you added a remote you no longer want, can remove it by supplying the name
 pub fn delete_remote(repo: &mut LocalRepository, name: &str) -> Result<(), OxenError> {
     repo.delete_remote(name);
     repo.save_default()?;
+
+    // Forget where its branches were
+    let prefix = format!("{name}/");
+    for tracked in core::refs::remote_refs::list(repo, Some(name))? {
+        let remote_branch = RemoteBranch {
+            remote: name.to_string(),
+            branch: tracked.name.trim_start_matches(&prefix).to_string(),
+        };
+        core::refs::remote_refs::delete(repo, &remote_branch)?;
+    }
+    Ok(())
+}
+
+/// # Toggle perceptual hashing of images
+/// When enabled, images added to the repository get a pHash stored in their metadata
+pub fn set_perceptual_hash(repo: &mut LocalRepository, enabled: bool) -> Result<(), OxenError> {
+    repo.set_perceptual_hash(enabled);
+    repo.save_default()?;
+    Ok(())
+}
+
+/// # Toggle content addressed tree hashing
+/// When enabled, new commits hash their dirs from the paths and contents below them only,
+/// so identical trees get identical hashes. Existing commits keep their hashes.
+pub fn set_content_addressed_tree(
+    repo: &mut LocalRepository,
+    enabled: bool,
+) -> Result<(), OxenError> {
+    repo.set_content_addressed_tree(enabled);
+    repo.save_default()?;
+    Ok(())
+}
+
+/// # Set how checkout puts version files into the working dir
+/// Reflinks and hard links avoid copying the data on filesystems that support them
+pub fn set_checkout_mode(repo: &mut LocalRepository, mode: CheckoutMode) -> Result<(), OxenError> {
+    repo.set_checkout_mode(mode);
+    repo.save_default()?;
+    Ok(())
+}
+
+/// # Set the zstd level new version files are compressed with
+/// Versions added or pulled from now on are compressed, existing ones stay as they are.
+/// Pass None to store new versions as is.
+pub fn set_compression_level(
+    repo: &mut LocalRepository,
+    level: Option<i32>,
+) -> Result<(), OxenError> {
+    if let Some(level) = level {
+        util::compression::validate_level(level)?;
+    }
+    repo.set_compression_level(level);
+    repo.save_default()?;
     Ok(())
 }
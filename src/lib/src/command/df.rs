@@ -5,11 +5,11 @@
 
 use std::path::Path;
 
-use crate::core::df::tabular;
+use crate::core::df::{sql_export, tabular};
 use crate::core::v0_19_0::index::CommitMerkleTree;
 use crate::error::OxenError;
 use crate::model::LocalRepository;
-use crate::opts::DFOpts;
+use crate::opts::{DFExportOpts, DFOpts};
 use crate::{repositories, util};
 
 /// Interact with DataFrames
@@ -50,6 +50,63 @@ pub fn df_revision(
     Ok(())
 }
 
+/// Export a DataFrame into a table of an external database, returns the number of rows written
+pub fn export(
+    input: impl AsRef<Path>,
+    export_opts: &DFExportOpts,
+    opts: DFOpts,
+) -> Result<usize, OxenError> {
+    let input = input.as_ref();
+    let df = tabular::read_df(input, opts)?;
+    write_export(input, &df, export_opts)
+}
+
+/// Export the version of a DataFrame at `revision` into a table of an external database
+pub fn export_revision(
+    repo: &LocalRepository,
+    input: impl AsRef<Path>,
+    revision: impl AsRef<str>,
+    export_opts: &DFExportOpts,
+    opts: DFOpts,
+) -> Result<usize, OxenError> {
+    let commit = repositories::revisions::get(repo, &revision)?.ok_or(OxenError::basic_str(
+        format!("Revision {} not found", revision.as_ref()),
+    ))?;
+    let path = input.as_ref();
+    let tree = CommitMerkleTree::from_path(repo, &commit, path, false)?;
+    let df = tabular::read_node(repo.clone(), &tree.root, opts)?;
+    write_export(path, &df, export_opts)
+}
+
+fn write_export(
+    input: &Path,
+    df: &polars::prelude::DataFrame,
+    export_opts: &DFExportOpts,
+) -> Result<usize, OxenError> {
+    let table = match &export_opts.table {
+        Some(table) => table.to_owned(),
+        None => input
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .ok_or(OxenError::basic_str(format!(
+                "Could not name the table after {input:?}, pass a table name"
+            )))?,
+    };
+    println!(
+        "Exporting {} rows to table {} in {}",
+        df.height(),
+        table,
+        export_opts.target
+    );
+    sql_export::to_sql(
+        df,
+        &export_opts.target,
+        &table,
+        export_opts.if_exists,
+        export_opts.batch_size,
+    )
+}
+
 /// Get a human readable schema for a DataFrame
 pub fn schema<P: AsRef<Path>>(input: P, flatten: bool, opts: DFOpts) -> Result<String, OxenError> {
     tabular::schema_to_string(input, flatten, &opts)
@@ -1,61 +1,84 @@
 //! # oxen fetch
 //!
-//! Download objects and refs from the remote repository
+//! Download new commits, merkle tree nodes and version files from the remotes and
+//! update the remote-tracking refs, without moving local branches or touching the
+//! working dir. Afterwards `origin/main` can be logged, diffed or merged offline.
 //!
 
 use crate::api;
-use crate::core::index::EntryIndexer;
+use crate::core;
+use crate::core::versions::MinOxenVersion;
 use crate::error::OxenError;
 use crate::model::{Branch, LocalRepository, RemoteBranch};
 
-/// # Fetch the remote branches and objects
-pub async fn fetch(repo: &LocalRepository) -> Result<Vec<Branch>, OxenError> {
-    for remote in repo.remotes.iter() {
-        fetch_remote(repo, &remote.name).await?;
+/// # Fetch every remote
+/// Returns the remote-tracking branches that were updated
+pub async fn fetch(repo: &LocalRepository, all: bool) -> Result<Vec<Branch>, OxenError> {
+    let mut fetched = vec![];
+    for remote in repo.remotes().iter() {
+        fetched.extend(fetch_remote(repo, &remote.name, all).await?);
     }
-
-    Ok(vec![])
+    Ok(fetched)
 }
 
+/// # Fetch the branches of one remote
+/// Tracking refs of branches that were deleted on the remote are pruned.
+/// With `all`, the version files of the whole history are downloaded, not just the heads.
 pub async fn fetch_remote(
     repo: &LocalRepository,
     remote_name: &str,
+    all: bool,
 ) -> Result<Vec<Branch>, OxenError> {
+    if matches!(repo.min_version(), MinOxenVersion::V0_10_0) {
+        return Err(OxenError::basic_str(
+            "oxen fetch is not supported for this repository version, run `oxen migrate` first",
+        ));
+    }
+
     let remote = repo
         .get_remote(remote_name)
         .ok_or(OxenError::remote_not_set(remote_name))?;
-    let remote_repo = api::remote::repositories::get_by_remote(&remote)
+    let remote_repo = api::client::repositories::get_by_remote(&remote)
         .await?
         .ok_or(OxenError::remote_not_found(remote.clone()))?;
+    let remote_branches = api::client::branches::list(&remote_repo).await?;
 
-    let remote_branches = api::remote::branches::list(&remote_repo).await?;
-    let local_branches = api::local::branches::list(repo)?;
-
-    // Find branches that are on the remote but not on the local
-    let mut branches_to_create = vec![];
-    let mut branches_to_fetch = vec![];
-    for remote_branch in remote_branches {
-        if !local_branches.iter().any(|b| b.name == remote_branch.name) {
-            branches_to_create.push(remote_branch);
-        } else {
-            branches_to_fetch.push(remote_branch);
-        }
-    }
-
-    // Pull the new branches
-    let indexer = EntryIndexer::new(repo)?;
-    for branch in branches_to_create {
-        println!("Fetch remote branch: {}/{}", remote_name, branch.name);
+    let mut fetched = vec![];
+    for branch in remote_branches.iter() {
         let rb = RemoteBranch {
             remote: remote.name.to_owned(),
             branch: branch.name.to_owned(),
         };
-        indexer
-            .pull_most_recent_commit_object(&remote_repo, &rb, false)
-            .await?;
+        let previous = core::refs::remote_refs::get(repo, format!("{remote_name}/{}", rb.branch))?;
+        if previous.as_deref() == Some(branch.commit_id.as_str()) {
+            log::debug!("{remote_name}/{} is up to date", rb.branch);
+            continue;
+        }
+
+        core::v0_19_0::fetch::fetch_remote_tracking_branch(repo, &remote_repo, &rb, all).await?;
+        fetched.push(Branch {
+            name: format!("{remote_name}/{}", rb.branch),
+            commit_id: branch.commit_id.to_owned(),
+        });
     }
 
-    Ok(vec![])
+    // Prune the tracking refs of branches that no longer exist on the remote
+    let prefix = format!("{remote_name}/");
+    for tracked in core::refs::remote_refs::list(repo, Some(remote_name))? {
+        let branch = tracked.name.trim_start_matches(&prefix);
+        if !remote_branches.iter().any(|b| b.name == branch) {
+            log::debug!("Pruning remote-tracking ref {}", tracked.name);
+            core::refs::remote_refs::delete(
+                repo,
+                &RemoteBranch {
+                    remote: remote_name.to_string(),
+                    branch: branch.to_string(),
+                },
+            )?;
+        }
+    }
+
+    Ok(fetched)
 }
 
 #[cfg(test)]
@@ -64,48 +87,72 @@ mod tests {
     use crate::command;
     use crate::constants;
     use crate::constants::DEFAULT_BRANCH_NAME;
+    use crate::core;
     use crate::error::OxenError;
+    use crate::repositories;
     use crate::test;
 
     #[tokio::test]
-    async fn test_fetch_branches() -> Result<(), OxenError> {
-        test::run_empty_local_repo_test_async(|mut repo| async move {
+    async fn test_fetch_updates_remote_tracking_refs_only() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|mut repo| async move {
             // Set the proper remote
             let remote = test::repo_remote_url_from(&repo.dirname());
             command::config::set_remote(&mut repo, constants::DEFAULT_REMOTE_NAME, &remote)?;
 
             // Create Remote
             let remote_repo = test::create_remote_repo(&repo).await?;
+            repositories::push(&repo).await?;
 
-            // Create a few local branches and push them
-            let branches = ["test_moo", "test_moo_2"];
-            for branch in branches.iter() {
-                command::create_checkout(&repo, branch)?;
-                let filepath = repo.path.join(format!("file_{}.txt", branch));
-                test::write_txt_file_to_path(&filepath, format!("a file on {}", branch))?;
-                command::add(&repo, &filepath)?;
-                command::commit(&repo, &format!("Adding file on {}", branch))?;
-                command::push(&repo).await?;
-            }
-
-            // Clone the main branch, then fetch the others
             test::run_empty_dir_test_async(|new_repo_dir| async move {
-                let cloned_repo =
-                    command::clone_url(&remote_repo.remote.url, &new_repo_dir.join("new_repo"))
-                        .await?;
-                let branches = api::local::branches::list(&cloned_repo)?;
-
-                assert_eq!(1, branches.len());
-
-                command::fetch(&cloned_repo).await?;
-
-                let branches = api::local::branches::list(&cloned_repo)?;
-                assert_eq!(3, branches.len());
-
-                let current_branch = api::local::branches::current_branch(&cloned_repo)?.unwrap();
-                assert_eq!(current_branch.name, DEFAULT_BRANCH_NAME);
-
-                api::remote::repositories::delete(&remote_repo).await?;
+                let cloned_repo = repositories::clone_url(
+                    &remote_repo.remote.url,
+                    &new_repo_dir.join("new_repo"),
+                )
+                .await?;
+                let cloned_head = repositories::commits::head_commit(&cloned_repo)?;
+
+                // Commit and push more data from the original repo
+                let filepath = repo.path.join("fetched.txt");
+                test::write_txt_file_to_path(&filepath, "fetch me")?;
+                repositories::add(&repo, &filepath)?;
+                let new_commit = repositories::commit(&repo, "Adding a file to fetch")?;
+                repositories::push(&repo).await?;
+                repositories::branches::create_checkout(&repo, "labels")?;
+                repositories::push::push_remote_branch(
+                    &repo,
+                    constants::DEFAULT_REMOTE_NAME,
+                    "labels",
+                )
+                .await?;
+
+                let fetched = command::fetch(&cloned_repo, false).await?;
+                assert_eq!(fetched.len(), 2);
+
+                // The local branch and the working dir are untouched
+                let main = repositories::branches::get_by_name(&cloned_repo, DEFAULT_BRANCH_NAME)?
+                    .unwrap();
+                assert_eq!(main.commit_id, cloned_head.id);
+                assert!(!cloned_repo.path.join("fetched.txt").exists());
+                assert_eq!(repositories::branches::list(&cloned_repo)?.len(), 1);
+
+                // The remote-tracking refs resolve as revisions
+                let origin_main =
+                    repositories::revisions::get(&cloned_repo, "origin/main")?.unwrap();
+                assert_eq!(origin_main.id, new_commit.id);
+                let history = repositories::commits::list_from(&cloned_repo, "origin/labels")?;
+                assert_eq!(history.first().unwrap().id, new_commit.id);
+
+                // Nothing new to fetch the second time around
+                assert!(command::fetch(&cloned_repo, false).await?.is_empty());
+
+                // Deleted remote branches are pruned
+                api::client::branches::delete(&remote_repo, "labels").await?;
+                command::fetch(&cloned_repo, false).await?;
+                let tracked = core::refs::remote_refs::list(&cloned_repo, None)?;
+                assert_eq!(tracked.len(), 1);
+                assert_eq!(tracked[0].name, "origin/main");
+
+                api::client::repositories::delete(&remote_repo).await?;
 
                 Ok(new_repo_dir)
             })
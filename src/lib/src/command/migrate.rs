@@ -1,7 +1,19 @@
-use std::path::Path;
+use indicatif::ProgressBar;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::panic::AssertUnwindSafe;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
+use crate::core::events::{self, Event, Operation, OperationTimer};
+use crate::repositories;
+use crate::util::progress_bar::{oxen_progress_bar, ProgressBarType};
 use crate::{error::OxenError, model::LocalRepository};
 
+pub mod checkpoint;
+pub use checkpoint::MigrationCheckpoint;
+
 pub mod m00_update_version_files;
 pub use m00_update_version_files::UpdateVersionFilesMigration;
 
@@ -20,10 +32,404 @@ pub use m04_create_merkle_trees::CreateMerkleTreesMigration;
 pub mod m05_optimize_merkle_tree;
 pub use m05_optimize_merkle_tree::OptimizeMerkleTreesMigration;
 
-pub trait Migrate {
+pub trait Migrate: Sync {
     fn up(&self, path: &Path, all: bool) -> Result<(), OxenError>;
     fn down(&self, path: &Path, all: bool) -> Result<(), OxenError>;
     fn is_needed(&self, repo: &LocalRepository) -> Result<bool, OxenError>;
     fn name(&self) -> &'static str;
     fn description(&self) -> &'static str;
+
+    /// Number of steps the migration takes for the repo, reported by a dry run.
+    /// None if the migration does not checkpoint its steps.
+    fn num_steps(&self, _repo: &LocalRepository) -> Result<Option<usize>, OxenError> {
+        Ok(None)
+    }
+
+    /// Undo the partial work of an interrupted run, before it starts over without resume
+    fn reset(&self, _repo: &LocalRepository) -> Result<(), OxenError> {
+        Ok(())
+    }
+}
+
+/// Every registered migration, in the order they should be applied
+pub fn all_migrations() -> Vec<Box<dyn Migrate>> {
+    vec![
+        Box::new(UpdateVersionFilesMigration),
+        Box::new(PropagateSchemasMigration),
+        Box::new(CacheDataFrameSizeMigration),
+        Box::new(CreateMerkleTreesMigration),
+        Box::new(AddDirectoriesToCacheMigration),
+        Box::new(OptimizeMerkleTreesMigration),
+    ]
+}
+
+/// What running a migration up would do to a repository
+#[derive(Debug)]
+pub struct MigrationReport {
+    pub path: PathBuf,
+    pub is_needed: bool,
+    /// An earlier run was interrupted, resuming skips the steps it finished
+    pub in_progress: bool,
+    pub num_steps_done: usize,
+    pub num_steps: Option<usize>,
+}
+
+/// The repository at `path`, or with `all` every repository in the namespaces below it
+pub fn list_repos(path: &Path, all: bool) -> Result<Vec<LocalRepository>, OxenError> {
+    if !all {
+        return Ok(vec![LocalRepository::new(path)?]);
+    }
+    let mut repos = vec![];
+    for namespace in repositories::list_namespaces(path)? {
+        repos.extend(repositories::list_repos_in_namespace(&path.join(namespace)));
+    }
+    Ok(repos)
+}
+
+/// Report what running the migration up would do, without changing anything
+pub fn dry_run(
+    migration: &dyn Migrate,
+    path: &Path,
+    all: bool,
+) -> Result<Vec<MigrationReport>, OxenError> {
+    let mut reports = vec![];
+    for repo in list_repos(path, all)? {
+        let in_progress = MigrationCheckpoint::exists(&repo, migration.name());
+        let num_steps_done = if in_progress {
+            MigrationCheckpoint::open(&repo, migration.name())?.num_done()?
+        } else {
+            0
+        };
+        reports.push(MigrationReport {
+            is_needed: migration.is_needed(&repo)?,
+            in_progress,
+            num_steps_done,
+            num_steps: migration.num_steps(&repo)?,
+            path: repo.path,
+        });
+    }
+    Ok(reports)
+}
+
+/// Which registered migrations a repository still needs
+#[derive(Debug, Serialize)]
+pub struct RepoMigrationStatus {
+    pub path: PathBuf,
+    pub needed: Vec<&'static str>,
+    /// Migrations with an interrupted run, these are also in `needed`
+    pub in_progress: Vec<&'static str>,
+    /// The repository could not be checked, for example it failed to load
+    pub error: Option<String>,
+}
+
+impl RepoMigrationStatus {
+    pub fn is_up_to_date(&self) -> bool {
+        self.needed.is_empty() && self.error.is_none()
+    }
+}
+
+/// Check every registered migration against the repository at `path`, or with `all`
+/// every repository below it, without changing anything. A repository that can't be
+/// checked is reported with its error instead of stopping the others.
+pub fn verify(path: &Path, all: bool) -> Result<Vec<RepoMigrationStatus>, OxenError> {
+    let migrations = all_migrations();
+    let mut statuses = vec![];
+    for repo in list_repos(path, all)? {
+        let mut status = RepoMigrationStatus {
+            path: repo.path.clone(),
+            needed: vec![],
+            in_progress: vec![],
+            error: None,
+        };
+        for migration in migrations.iter() {
+            let in_progress = MigrationCheckpoint::exists(&repo, migration.name());
+            match migration.is_needed(&repo) {
+                Ok(is_needed) => {
+                    if is_needed || in_progress {
+                        status.needed.push(migration.name());
+                    }
+                    if in_progress {
+                        status.in_progress.push(migration.name());
+                    }
+                }
+                Err(err) => {
+                    status.error = Some(format!("{}: {}", migration.name(), err));
+                    break;
+                }
+            }
+        }
+        statuses.push(status);
+    }
+    Ok(statuses)
+}
+
+/// Apply every migration the repositories from [verify] still need, oldest first.
+/// Each migration is re-checked before it runs since an earlier one may have covered it,
+/// and a repository that fails is left out of the migrations after it.
+pub fn run_needed(
+    statuses: &[RepoMigrationStatus],
+    num_workers: usize,
+) -> Result<Vec<(&'static str, MigrationSummary)>, OxenError> {
+    let mut repos = vec![];
+    for status in statuses.iter().filter(|s| s.error.is_none()) {
+        repos.push(LocalRepository::new(&status.path)?);
+    }
+
+    let mut failed: HashSet<PathBuf> = HashSet::new();
+    let mut summaries = vec![];
+    for migration in all_migrations() {
+        let mut needed = vec![];
+        for repo in repos.iter().filter(|r| !failed.contains(&r.path)) {
+            if migration.is_needed(repo)? || MigrationCheckpoint::exists(repo, migration.name()) {
+                needed.push(repo.clone());
+            }
+        }
+        if needed.is_empty() {
+            continue;
+        }
+
+        let timer = OperationTimer::start(Operation::Migrate, migration.name());
+        let summary = migrate_repos(migration.name(), &needed, num_workers, |repo| {
+            // An interrupted run continues from its checkpoint
+            migration.up(&repo.path, false)
+        })?;
+        timer.finish();
+        failed.extend(summary.failed.iter().map(|(path, _)| path.clone()));
+        summaries.push((migration.name(), summary));
+    }
+    Ok(summaries)
+}
+
+/// Repositories of a server-wide migration that are migrated at the same time
+pub const DEFAULT_MIGRATION_WORKERS: usize = 4;
+
+/// How a migration over many repositories went
+#[derive(Debug, Default)]
+pub struct MigrationSummary {
+    pub num_repos: usize,
+    /// Repositories that failed and why, the other repositories were still migrated
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+impl MigrationSummary {
+    pub fn num_succeeded(&self) -> usize {
+        self.num_repos - self.failed.len()
+    }
+}
+
+/// Run the migration up, sending Started and Finished events to the event sink.
+/// Interrupted runs continue from their checkpoint with `resume`, otherwise they start over.
+/// With `all`, up to `num_workers` repositories are migrated at once and a failing
+/// repository is reported in the summary instead of stopping the others.
+pub fn run_up(
+    migration: &dyn Migrate,
+    path: &Path,
+    all: bool,
+    resume: bool,
+    num_workers: usize,
+) -> Result<MigrationSummary, OxenError> {
+    let timer = OperationTimer::start(Operation::Migrate, migration.name());
+    let summary = for_each_repo(migration.name(), path, all, num_workers, |repo| {
+        if !resume && MigrationCheckpoint::exists(repo, migration.name()) {
+            events::info(
+                Operation::Migrate,
+                format!(
+                    "Restarting the interrupted {} migration for {:?}, pass --resume to continue it instead",
+                    migration.name(),
+                    repo.path
+                ),
+            );
+            migration.reset(repo)?;
+            MigrationCheckpoint::delete(repo, migration.name())?;
+        }
+        migration.up(&repo.path, false)
+    })?;
+    timer.finish();
+    Ok(summary)
+}
+
+/// Run the migration down, sending Started and Finished events to the event sink
+pub fn run_down(
+    migration: &dyn Migrate,
+    path: &Path,
+    all: bool,
+    num_workers: usize,
+) -> Result<MigrationSummary, OxenError> {
+    let timer = OperationTimer::start(Operation::Migrate, migration.name());
+    let summary = for_each_repo(migration.name(), path, all, num_workers, |repo| {
+        migration.down(&repo.path, false)
+    })?;
+    timer.finish();
+    Ok(summary)
+}
+
+/// Run `f` on each repository from [list_repos] on a pool of `num_workers` threads.
+/// A single repository returns its error, with `all` errors and panics are collected.
+fn for_each_repo(
+    name: &str,
+    path: &Path,
+    all: bool,
+    num_workers: usize,
+    f: impl Fn(&LocalRepository) -> Result<(), OxenError> + Sync,
+) -> Result<MigrationSummary, OxenError> {
+    if !all {
+        let repo = LocalRepository::new(path)?;
+        f(&repo)?;
+        return Ok(MigrationSummary {
+            num_repos: 1,
+            failed: vec![],
+        });
+    }
+
+    events::info(
+        Operation::Migrate,
+        "🐂 Collecting repositories to migrate...",
+    );
+    let repos = list_repos(path, all)?;
+    migrate_repos(name, &repos, num_workers, f)
+}
+
+/// Run `f` on each of `repos` on a pool of `num_workers` threads, collecting errors and panics
+fn migrate_repos(
+    name: &str,
+    repos: &[LocalRepository],
+    num_workers: usize,
+    f: impl Fn(&LocalRepository) -> Result<(), OxenError> + Sync,
+) -> Result<MigrationSummary, OxenError> {
+    events::info(
+        Operation::Migrate,
+        format!(
+            "🐂 Migrating {} repositories with {} workers",
+            repos.len(),
+            num_workers
+        ),
+    );
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(num_workers.max(1))
+        .build()
+        .map_err(|e| OxenError::basic_str(format!("Could not start migration workers: {e}")))?;
+    let bar = oxen_progress_bar(repos.len() as u64, ProgressBarType::Counter);
+    let failed: Mutex<Vec<(PathBuf, String)>> = Mutex::new(vec![]);
+    pool.install(|| {
+        repos.par_iter().for_each(|repo| {
+            // Some migrations panic on data they don't expect, that should only fail this repo
+            let result = std::panic::catch_unwind(AssertUnwindSafe(|| f(repo)));
+            let error = match result {
+                Ok(Ok(())) => None,
+                Ok(Err(err)) => Some(err.to_string()),
+                Err(_) => Some("migration panicked".to_string()),
+            };
+            if let Some(error) = error {
+                log::error!("Could not migrate repo {:?}: {}", repo.path, error);
+                failed.lock().unwrap().push((repo.path.clone(), error));
+            }
+            inc_progress(name, &bar);
+        });
+    });
+    bar.finish_and_clear();
+
+    let mut failed = failed.into_inner().unwrap();
+    failed.sort();
+    Ok(MigrationSummary {
+        num_repos: repos.len(),
+        failed,
+    })
+}
+
+/// Advance the migration's progress bar and tell the event sink how far along it is
+pub fn inc_progress(name: &str, bar: &ProgressBar) {
+    bar.inc(1);
+    events::emit(Event::MigrationStep {
+        name: name.to_string(),
+        num_done: bar.position(),
+        total: bar.length().unwrap_or(0),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::command::migrate::{
+        self, Migrate, MigrationCheckpoint, OptimizeMerkleTreesMigration,
+    };
+    use crate::error::OxenError;
+    use crate::model::LocalRepository;
+    use crate::repositories;
+    use crate::test;
+
+    struct FailOnRepoB;
+    impl Migrate for FailOnRepoB {
+        fn up(&self, path: &Path, _all: bool) -> Result<(), OxenError> {
+            if path.ends_with("b") {
+                return Err(OxenError::basic_str("repo b is broken"));
+            }
+            crate::util::fs::write_to_path(path.join("migrated.txt"), "done")?;
+            Ok(())
+        }
+
+        fn down(&self, _path: &Path, _all: bool) -> Result<(), OxenError> {
+            Ok(())
+        }
+
+        fn is_needed(&self, _repo: &LocalRepository) -> Result<bool, OxenError> {
+            Ok(true)
+        }
+
+        fn name(&self) -> &'static str {
+            "fail_on_repo_b"
+        }
+
+        fn description(&self) -> &'static str {
+            "Fails on the repo named b"
+        }
+    }
+
+    #[test]
+    fn test_migrate_all_repos_isolates_failures() -> Result<(), OxenError> {
+        test::run_empty_dir_test(|sync_dir| {
+            for name in ["a", "b", "c"] {
+                repositories::init(sync_dir.join("ox").join(name))?;
+            }
+
+            let summary = migrate::run_up(&FailOnRepoB, sync_dir, true, false, 2)?;
+            assert_eq!(summary.num_repos, 3);
+            assert_eq!(summary.num_succeeded(), 2);
+            assert_eq!(summary.failed.len(), 1);
+            assert!(summary.failed[0].0.ends_with("b"));
+            assert!(sync_dir.join("ox").join("a").join("migrated.txt").exists());
+            assert!(sync_dir.join("ox").join("c").join("migrated.txt").exists());
+
+            // A single repo still returns its error
+            assert!(migrate::run_up(
+                &FailOnRepoB,
+                &sync_dir.join("ox").join("b"),
+                false,
+                false,
+                1
+            )
+            .is_err());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_verify_reports_needed_migrations() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let statuses = migrate::verify(&repo.path, false)?;
+            assert_eq!(statuses.len(), 1);
+            assert!(statuses[0].is_up_to_date());
+
+            // An interrupted run still needs to finish
+            let name = OptimizeMerkleTreesMigration.name();
+            MigrationCheckpoint::open(&repo, name)?.mark_done("first")?;
+            let statuses = migrate::verify(&repo.path, false)?;
+            assert_eq!(statuses[0].needed, vec![name]);
+            assert_eq!(statuses[0].in_progress, vec![name]);
+
+            Ok(())
+        })
+    }
 }
@@ -0,0 +1,108 @@
+//! # Migration Checkpoints
+//!
+//! Records which steps of a migration are done for a repository, in
+//! .oxen/migrations/{name}, so an interrupted run can pick up where it stopped with
+//! `oxen migrate up --resume`. The checkpoint only exists while a migration is running,
+//! finishing the migration removes it.
+//!
+
+use rocksdb::{DBWithThreadMode, MultiThreaded};
+use std::path::PathBuf;
+
+use crate::constants::MIGRATIONS_DIR;
+use crate::core::db;
+use crate::core::db::key_val::str_val_db;
+use crate::error::OxenError;
+use crate::model::LocalRepository;
+use crate::util;
+
+pub struct MigrationCheckpoint {
+    db: DBWithThreadMode<MultiThreaded>,
+    path: PathBuf,
+}
+
+fn checkpoint_path(repo: &LocalRepository, name: &str) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path)
+        .join(MIGRATIONS_DIR)
+        .join(name)
+}
+
+impl MigrationCheckpoint {
+    /// Open the checkpoint of the migration, starting a new one if there is none
+    pub fn open(repo: &LocalRepository, name: &str) -> Result<MigrationCheckpoint, OxenError> {
+        let path = checkpoint_path(repo, name);
+        util::fs::create_dir_all(&path)?;
+        let opts = db::key_val::opts::default();
+        let db = DBWithThreadMode::open(&opts, dunce::simplified(&path))?;
+        Ok(MigrationCheckpoint { db, path })
+    }
+
+    /// Whether a run of the migration started and did not finish
+    pub fn exists(repo: &LocalRepository, name: &str) -> bool {
+        checkpoint_path(repo, name).exists()
+    }
+
+    /// Forget the progress of an interrupted run
+    pub fn delete(repo: &LocalRepository, name: &str) -> Result<(), OxenError> {
+        let path = checkpoint_path(repo, name);
+        if path.exists() {
+            util::fs::remove_dir_all(path)?;
+        }
+        Ok(())
+    }
+
+    pub fn is_done(&self, step: impl AsRef<str>) -> bool {
+        str_val_db::has_key(&self.db, step)
+    }
+
+    /// Call once the step is fully written, it is skipped when the migration resumes
+    pub fn mark_done(&self, step: impl AsRef<str>) -> Result<(), OxenError> {
+        str_val_db::put(&self.db, step, &String::new())?;
+        // The whole point is to survive the process being killed
+        self.db.flush()?;
+        Ok(())
+    }
+
+    pub fn num_done(&self) -> Result<usize, OxenError> {
+        Ok(str_val_db::list_keys(&self.db)?.len())
+    }
+
+    /// The migration finished, remove the checkpoint
+    pub fn finish(self) -> Result<(), OxenError> {
+        let path = self.path.clone();
+        drop(self.db);
+        util::fs::remove_dir_all(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::command::migrate::MigrationCheckpoint;
+    use crate::error::OxenError;
+    use crate::test;
+
+    #[test]
+    fn test_migration_checkpoint_survives_reopen() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let name = "test_migration";
+            assert!(!MigrationCheckpoint::exists(&repo, name));
+
+            let checkpoint = MigrationCheckpoint::open(&repo, name)?;
+            checkpoint.mark_done("commit_1")?;
+            drop(checkpoint);
+            assert!(MigrationCheckpoint::exists(&repo, name));
+
+            // Resuming sees the finished steps
+            let checkpoint = MigrationCheckpoint::open(&repo, name)?;
+            assert!(checkpoint.is_done("commit_1"));
+            assert!(!checkpoint.is_done("commit_2"));
+            checkpoint.mark_done("commit_2")?;
+            assert_eq!(checkpoint.num_done()?, 2);
+
+            checkpoint.finish()?;
+            assert!(!MigrationCheckpoint::exists(&repo, name));
+
+            Ok(())
+        })
+    }
+}
@@ -1,11 +1,12 @@
 use rocksdb::{DBWithThreadMode, MultiThreaded};
 
-use super::Migrate;
+use super::{inc_progress, Migrate};
 
 use std::path::{Path, PathBuf};
 
 use crate::core::db;
 use crate::core::db::key_val::path_db;
+use crate::core::events::{self, Operation};
 use crate::core::v0_10_0::index::{CommitEntryWriter, CommitReader, CommitWriter};
 use crate::core::versions::MinOxenVersion;
 use crate::error::OxenError;
@@ -59,10 +60,13 @@ impl Migrate for CreateMerkleTreesMigration {
 }
 
 pub fn create_merkle_trees_for_all_repos_up(path: &Path) -> Result<(), OxenError> {
-    println!("🐂 Collecting namespaces to migrate...");
+    events::info(Operation::Migrate, "🐂 Collecting namespaces to migrate...");
     let namespaces = repositories::list_namespaces(path)?;
     let bar = oxen_progress_bar(namespaces.len() as u64, ProgressBarType::Counter);
-    println!("🐂 Migrating {} namespaces", namespaces.len());
+    events::info(
+        Operation::Migrate,
+        format!("🐂 Migrating {} namespaces", namespaces.len()),
+    );
     for namespace in namespaces {
         let namespace_path = path.join(namespace);
         // Show the canonical namespace path
@@ -127,7 +131,7 @@ pub fn create_merkle_trees_up(repo: &LocalRepository) -> Result<(), OxenError> {
 
         commit_writer.add_commit_to_db(&commit_to_update)?;
 
-        bar.inc(1);
+        inc_progress("create_merkle_trees", &bar);
     }
 
     // runtime check: commit root hash is properly updated for all commits
@@ -1,23 +1,25 @@
 use chrono::Local;
 use rocksdb::{DBWithThreadMode, MultiThreaded};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use time::OffsetDateTime;
 
-use super::Migrate;
+use super::{inc_progress, Migrate, MigrationCheckpoint};
 
 use crate::config::RepositoryConfig;
 use crate::core;
 use crate::core::db;
 use crate::core::db::key_val::str_val_db;
 use crate::core::db::key_val::tree_db::{TreeObject, TreeObjectChild};
+use crate::core::events::{self, Operation};
+use crate::core::refs::RefReader;
 use crate::core::v0_10_0::index::object_db_reader::get_object_reader;
 use crate::core::v0_10_0::index::{
     CommitDirEntryReader, CommitEntryReader, CommitReader, ObjectDBReader,
 };
-use crate::core::v0_19_0::index::MerkleNodeDB;
+use crate::core::v0_19_0::index::{tree_cache, MerkleNodeDB};
 use crate::core::versions::MinOxenVersion;
 // use crate::core::v2::index::file_chunker::{ChunkShardManager, FileChunker};
 use crate::error::OxenError;
@@ -33,10 +35,25 @@ use crate::{constants, repositories, util};
 
 use std::str::FromStr;
 
+const MIGRATION_NAME: &str = "optimize_merkle_trees";
+
+/// Opens the object db reader of a commit when it is needed, through the shared LRU,
+/// instead of holding a reader per commit open for the whole migration
+struct ObjectReaders<'a> {
+    repo: &'a LocalRepository,
+    commits: &'a [Commit],
+}
+
+impl ObjectReaders<'_> {
+    fn get(&self, commit_idx: usize) -> Result<Arc<ObjectDBReader>, OxenError> {
+        get_object_reader(self.repo, &self.commits[commit_idx].id)
+    }
+}
+
 pub struct OptimizeMerkleTreesMigration;
 impl Migrate for OptimizeMerkleTreesMigration {
     fn name(&self) -> &'static str {
-        "optimize_merkle_trees"
+        MIGRATION_NAME
     }
 
     fn description(&self) -> &'static str {
@@ -53,8 +70,13 @@ impl Migrate for OptimizeMerkleTreesMigration {
         Ok(())
     }
 
-    fn down(&self, _path: &Path, _all: bool) -> Result<(), OxenError> {
-        log::warn!("Optimize merkle trees migration is not reversible");
+    fn down(&self, path: &Path, all: bool) -> Result<(), OxenError> {
+        if all {
+            create_merkle_trees_for_all_repos_down(path)?;
+        } else {
+            let repo = LocalRepository::new(path)?;
+            create_merkle_trees_down(&repo)?;
+        }
         Ok(())
     }
 
@@ -63,18 +85,39 @@ impl Migrate for OptimizeMerkleTreesMigration {
             .path
             .join(constants::OXEN_HIDDEN_DIR)
             .join(constants::TREE_DIR);
-        if !tree_dir.exists() {
+        if !tree_dir.exists() || MigrationCheckpoint::exists(repo, MIGRATION_NAME) {
             return Ok(true);
         }
         Ok(false)
     }
+
+    /// One step per commit
+    fn num_steps(&self, repo: &LocalRepository) -> Result<Option<usize>, OxenError> {
+        let commit_reader = CommitReader::new(repo)?;
+        Ok(Some(commit_reader.list_all()?.len()))
+    }
+
+    fn reset(&self, repo: &LocalRepository) -> Result<(), OxenError> {
+        let tree_dir = repo
+            .path
+            .join(constants::OXEN_HIDDEN_DIR)
+            .join(constants::TREE_DIR);
+        if tree_dir.exists() {
+            util::fs::remove_dir_all(tree_dir)?;
+        }
+        Ok(())
+    }
 }
 
 pub fn create_merkle_trees_for_all_repos_up(path: &Path) -> Result<(), OxenError> {
-    println!("🐂 Collecting namespaces to migrate...");
+    events::info(Operation::Migrate, "🐂 Collecting namespaces to migrate...");
     let namespaces = repositories::list_namespaces(path)?;
     let bar = oxen_progress_bar(namespaces.len() as u64, ProgressBarType::Counter);
-    println!("🐂 Migrating {} namespaces", namespaces.len());
+    events::info(
+        Operation::Migrate,
+        format!("🐂 Migrating {} namespaces", namespaces.len()),
+    );
+    let mut failed = vec![];
     for namespace in namespaces {
         let namespace_path = path.join(namespace);
         // Show the canonical namespace path
@@ -84,57 +127,78 @@ pub fn create_merkle_trees_for_all_repos_up(path: &Path) -> Result<(), OxenError
         );
         let repos = repositories::list_repos_in_namespace(&namespace_path);
         for repo in repos {
-            match create_merkle_trees_up(&repo) {
-                Ok(_) => {}
-                Err(err) => {
-                    log::error!(
-                        "Could not migrate merkle trees for repo {:?}\nErr: {}",
-                        repo.path.canonicalize(),
-                        err
-                    )
-                }
+            if let Err(err) = create_merkle_trees_up(&repo) {
+                log::error!(
+                    "Could not migrate merkle trees for repo {:?}\nErr: {}",
+                    repo.path.canonicalize(),
+                    err
+                );
+                failed.push(repo.path);
             }
         }
         bar.inc(1);
     }
-    Ok(())
+    all_repos_result("migrate", failed)
+}
+
+// Keep going past repos that fail so one bad repo does not block the rest, then report them
+fn all_repos_result(action: &str, failed: Vec<PathBuf>) -> Result<(), OxenError> {
+    if failed.is_empty() {
+        return Ok(());
+    }
+    let paths: Vec<String> = failed
+        .iter()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    Err(OxenError::basic_str(format!(
+        "Could not {action} merkle trees for {} repos: {}",
+        failed.len(),
+        paths.join(", ")
+    )))
 }
 
 pub fn create_merkle_trees_up(repo: &LocalRepository) -> Result<(), OxenError> {
-    println!("👋 Starting to migrate merkle trees for {:?}", repo.path);
+    events::info(
+        Operation::Migrate,
+        format!("👋 Starting to migrate merkle trees for {:?}", repo.path),
+    );
 
     // Get all commits in repo, then construct merkle tree for each commit
     let commit_reader = CommitReader::new(repo)?;
     let all_commits = commit_reader.list_all_sorted_by_timestamp()?;
-    println!("Migrate {} commits for {:?}", all_commits.len(), repo.path);
-
-    // Setup these object readers and entry readers to help pre-compute of latest commit for each file
-    let mut object_readers: Vec<Arc<ObjectDBReader>> = Vec::new();
-    for commit in &all_commits {
-        log::debug!("Getting object reader for commit: {}", commit);
-        object_readers.push(get_object_reader(repo, &commit.id)?);
-    }
+    events::info(
+        Operation::Migrate,
+        format!("Migrate {} commits for {:?}", all_commits.len(), repo.path),
+    );
 
-    log::debug!("Got {} object readers", object_readers.len());
+    // Object readers help pre-compute the latest commit for each file
+    let object_readers = ObjectReaders {
+        repo,
+        commits: &all_commits,
+    };
 
-    // Clear tree dir if exists (in order to run migration many times)
+    // A tree dir without a checkpoint is from a finished run
     let tree_dir = repo
         .path
         .join(constants::OXEN_HIDDEN_DIR)
         .join(constants::TREE_DIR);
-
-    if tree_dir.exists() {
+    if tree_dir.exists() && !MigrationCheckpoint::exists(repo, MIGRATION_NAME) {
         println!("Tree dir already exists: {:?}", tree_dir);
         return Ok(());
-    } else {
-        // Create tree dir
-        util::fs::create_dir_all(&tree_dir)?;
     }
+    util::fs::create_dir_all(&tree_dir)?;
+    let checkpoint = MigrationCheckpoint::open(repo, MIGRATION_NAME)?;
 
     let bar = oxen_progress_bar(all_commits.len() as u64, ProgressBarType::Counter);
     // let commit_writer = CommitWriter::new(repo)?;
     log::debug!("Migrating {} commits", all_commits.len());
-    for (commit_idx, _) in all_commits.iter().enumerate() {
+    for (commit_idx, commit) in all_commits.iter().enumerate() {
+        if checkpoint.is_done(&commit.id) {
+            log::debug!("Skipping commit {} migrated by an earlier run", commit);
+            inc_progress(MIGRATION_NAME, &bar);
+            continue;
+        }
+
         // Populate the global merkle tree from the old objects dir
         migrate_merkle_tree(
             repo,
@@ -143,8 +207,9 @@ pub fn create_merkle_trees_up(repo: &LocalRepository) -> Result<(), OxenError> {
             commit_idx,
             &object_readers,
         )?;
+        checkpoint.mark_done(&commit.id)?;
 
-        bar.inc(1);
+        inc_progress(MIGRATION_NAME, &bar);
     }
 
     // Set the oxen version to 0.19.0
@@ -152,6 +217,7 @@ pub fn create_merkle_trees_up(repo: &LocalRepository) -> Result<(), OxenError> {
     config.min_version = Some(MinOxenVersion::V0_19_0.as_str().to_string());
     let path = util::fs::config_filepath(&repo.path);
     config.save(&path)?;
+    checkpoint.finish()?;
 
     Ok(())
 }
@@ -161,7 +227,7 @@ fn migrate_merkle_tree(
     commit_reader: &CommitReader,
     commits: &[Commit],
     commit_idx: usize,
-    object_readers: &Vec<Arc<ObjectDBReader>>,
+    object_readers: &ObjectReaders,
 ) -> Result<(), OxenError> {
     let commit = &commits[commit_idx];
     let current_time = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
@@ -169,7 +235,7 @@ fn migrate_merkle_tree(
         "[{}] == START Migrating merkle tree for commit ({}/{}) {} ==",
         current_time,
         commit_idx,
-        object_readers.len(),
+        commits.len(),
         commit
     );
     let commit_dir = repo
@@ -192,7 +258,7 @@ fn migrate_merkle_tree(
     let mut commit_entry_readers: Vec<(Commit, CommitDirEntryReader)> = Vec::new();
     for (i, c) in commits.iter().enumerate() {
         log::debug!("Getting commit entry reader for commit {}", c);
-        let reader = CommitDirEntryReader::new(repo, &c.id, dir_path, object_readers[i].clone())?;
+        let reader = CommitDirEntryReader::new(repo, &c.id, dir_path, object_readers.get(i)?)?;
         log::debug!("Got commit entry reader for commit {}", c);
         commit_entry_readers.push((c.clone(), reader));
         log::debug!("total commit entry readers: {}", commit_entry_readers.len());
@@ -295,7 +361,7 @@ fn migrate_dir(
     commit_idx: usize,
     commit_reader: &CommitReader,
     entry_reader: &CommitEntryReader,
-    object_readers: &Vec<Arc<ObjectDBReader>>,
+    object_readers: &ObjectReaders,
     dir_db: &mut MerkleNodeDB,
     dir_path: &Path, // full path to dir (path/to/dir)
     dir_hash: &MerkleHash,
@@ -342,12 +408,12 @@ fn migrate_dir(
             * 12,5000 Children Per VNode
     */
 
-    let obj_reader = &object_readers[commit_idx];
+    let obj_reader = object_readers.get(commit_idx)?;
     let dir_obj = obj_reader.get_dir(&dir_hash.to_string())?;
 
     let mut commit_entry_readers: Vec<(Commit, CommitDirEntryReader)> = Vec::new();
     for (i, c) in commits.iter().enumerate() {
-        let reader = CommitDirEntryReader::new(repo, &c.id, dir_path, object_readers[i].clone())?;
+        let reader = CommitDirEntryReader::new(repo, &c.id, dir_path, object_readers.get(i)?)?;
         commit_entry_readers.push((c.clone(), reader));
     }
 
@@ -571,7 +637,7 @@ fn write_dir_child(
     repo: &LocalRepository,
     commit_idx: usize,
     entry_reader: &CommitEntryReader,
-    object_readers: &[Arc<ObjectDBReader>],
+    object_readers: &ObjectReaders,
     commits: &[Commit],
     node_db: &mut MerkleNodeDB,
     path: &Path,
@@ -628,7 +694,7 @@ fn write_dir_child(
             if dir_readers.contains_key(&(dir.clone(), c.id.clone())) {
                 continue;
             }
-            let reader = CommitDirEntryReader::new(repo, &c.id, dir, object_readers[i].clone())?;
+            let reader = CommitDirEntryReader::new(repo, &c.id, dir, object_readers.get(i)?)?;
             let entry_paths: HashMap<PathBuf, CommitEntry> = reader
                 .list_entries_set()?
                 .iter()
@@ -643,7 +709,7 @@ fn write_dir_child(
     for dir in dirs {
         log::debug!("processing path [{:?}] sub dir: {:?}", path, dir);
         let dir_entry_reader =
-            CommitDirEntryReader::new(repo, &commit.id, &dir, object_readers[commit_idx].clone())?;
+            CommitDirEntryReader::new(repo, &commit.id, &dir, object_readers.get(commit_idx)?)?;
         log::debug!(
             "Got dir entry reader for path [{:?}] subdir [{:?}]",
             path,
@@ -651,7 +717,7 @@ fn write_dir_child(
         );
         // let mut readers: Vec<(Commit, HashMap<PathBuf, CommitEntry>)> = Vec::new();
         // for (i, (c, _)) in commit_entry_readers.iter().enumerate() {
-        //     let reader = CommitDirEntryReader::new(repo, &c.id, &dir, object_readers[i].clone())?;
+        //     let reader = CommitDirEntryReader::new(repo, &c.id, &dir, object_readers.get(i)?)?;
         //     let entry_paths: HashMap<PathBuf, CommitEntry> = reader
         //         .list_entries_set()?.iter().map(|e| (e.path.clone(), e.clone())).collect();
         //     readers.push((c.clone(), entry_paths));
@@ -733,6 +799,8 @@ fn write_dir_child(
         last_modified_nanoseconds,
         data_type_counts,
         data_type_sizes,
+        image_resolution_counts: HashMap::new(),
+        data_type_durations: HashMap::new(),
     };
     if last_commit_id == 0 {
         log::warn!("No last commit id found for path {:?}", path);
@@ -898,12 +966,215 @@ fn write_file_node(
     Ok(())
 }
 
-pub fn create_merkle_trees_for_all_repos_down(_path: &Path) -> Result<(), OxenError> {
-    println!("There are no operations to be run");
-    Ok(())
+pub fn create_merkle_trees_for_all_repos_down(path: &Path) -> Result<(), OxenError> {
+    events::info(Operation::Migrate, "🐂 Collecting namespaces to migrate...");
+    let namespaces = repositories::list_namespaces(path)?;
+    let bar = oxen_progress_bar(namespaces.len() as u64, ProgressBarType::Counter);
+    let mut failed = vec![];
+    for namespace in namespaces {
+        let namespace_path = path.join(namespace);
+        for repo in repositories::list_repos_in_namespace(&namespace_path) {
+            if let Err(err) = create_merkle_trees_down(&repo) {
+                log::error!(
+                    "Could not roll back merkle trees for repo {:?}\nErr: {}",
+                    repo.path,
+                    err
+                );
+                failed.push(repo.path);
+            }
+        }
+        bar.inc(1);
+    }
+    all_repos_result("roll back", failed)
 }
 
-pub fn create_merkle_trees_down(_repo: &LocalRepository) -> Result<(), OxenError> {
-    println!("There are no operations to be run");
+/// Move a repository upgraded by [create_merkle_trees_up] back to the v0.10.0 layout, for
+/// older readers or after a failed upgrade. The upgrade keeps the v0.10.0 commit and object
+/// dbs, so this only undoes what it changed: the version files lose their extension, the
+/// dir hashes lose their quotes, the merkle tree is added and the min version is bumped.
+/// Versions compressed or encrypted since the upgrade are decoded, v0.10.0 reads them as is.
+/// Commits made after the upgrade only exist in the merkle tree, a repo with any is refused.
+pub fn create_merkle_trees_down(repo: &LocalRepository) -> Result<(), OxenError> {
+    let hidden_dir = repo.path.join(constants::OXEN_HIDDEN_DIR);
+    let tree_dir = hidden_dir.join(constants::TREE_DIR);
+    if repo.min_version() == MinOxenVersion::V0_10_0
+        && !tree_dir.exists()
+        && !MigrationCheckpoint::exists(repo, MIGRATION_NAME)
+    {
+        events::info(
+            Operation::Migrate,
+            format!("Repo is already on the v0.10.0 layout: {:?}", repo.path),
+        );
+        return Ok(());
+    }
+    if !hidden_dir.join(constants::OBJECTS_DIR).exists() {
+        return Err(OxenError::basic_str(format!(
+            "Repo {:?} was created after v0.19.0, it has no v0.10.0 layout to go back to",
+            repo.path
+        )));
+    }
+
+    let mut lock_file = repositories::get_lock_file(repo)?;
+    let _mutex = repositories::get_exclusive_lock(&mut lock_file)?;
+
+    // Every branch has to point at a commit from before the upgrade
+    let commit_reader = CommitReader::new(repo)?;
+    let ref_reader = RefReader::new(repo)?;
+    let newer_branches: Vec<String> = ref_reader
+        .list_branches()?
+        .into_iter()
+        .filter(|branch| !commit_reader.commit_id_exists(&branch.commit_id))
+        .map(|branch| branch.name)
+        .collect();
+    if !newer_branches.is_empty() {
+        return Err(OxenError::basic_str(format!(
+            "Branches {} have commits made after the upgrade to v0.19.0, they can't be moved to the v0.10.0 layout",
+            newer_branches.join(", ")
+        )));
+    }
+
+    let all_commits = commit_reader.list_all_sorted_by_timestamp()?;
+    events::info(
+        Operation::Migrate,
+        format!(
+            "Rolling back {} commits for {:?}",
+            all_commits.len(),
+            repo.path
+        ),
+    );
+    let bar = oxen_progress_bar(all_commits.len() as u64, ProgressBarType::Counter);
+    // Version files the upgrade renamed, removed once every entry that shares them is restored
+    let mut upgraded_paths: HashSet<PathBuf> = HashSet::new();
+    for commit in all_commits.iter() {
+        let commit_dir = hidden_dir.join(constants::HISTORY_DIR).join(&commit.id);
+        if !commit_dir.exists() {
+            log::warn!("Skipping commit {:?}, not downloaded", commit.id);
+            inc_progress(MIGRATION_NAME, &bar);
+            continue;
+        }
+
+        let entry_reader = CommitEntryReader::new(repo, commit)?;
+        for entry in entry_reader.list_entries()? {
+            let version_path = util::fs::version_path_from_hash_and_file_v0_10_0(
+                &repo.path,
+                &entry.hash,
+                entry.filename(),
+            );
+            let upgraded_path = util::encryption::stored_path(version_path.with_extension(""));
+            if version_path.exists() || !upgraded_path.exists() {
+                continue;
+            }
+            // Entries with the same contents but different extensions share the upgraded file
+            if !util::encryption::is_plain(&upgraded_path) {
+                util::encryption::copy_decrypted(repo, &upgraded_path, &version_path)?;
+            } else if std::fs::hard_link(&upgraded_path, &version_path).is_err() {
+                util::fs::copy(&upgraded_path, &version_path)?;
+            }
+            upgraded_paths.insert(upgraded_path);
+        }
+
+        // Put back the quotes the upgrade removed from the dir hashes
+        let dir_hashes_dir = commit_dir.join(constants::DIR_HASHES_DIR);
+        if dir_hashes_dir.exists() {
+            let dir_hashes_db: DBWithThreadMode<MultiThreaded> =
+                DBWithThreadMode::open(&db::key_val::opts::default(), dir_hashes_dir)?;
+            let vals: Vec<(String, String)> = str_val_db::list(&dir_hashes_db)?;
+            for (key, val) in vals {
+                if !val.starts_with('"') {
+                    str_val_db::put(&dir_hashes_db, key, &format!("\"{val}\""))?;
+                }
+            }
+        }
+
+        inc_progress(MIGRATION_NAME, &bar);
+    }
+    for path in upgraded_paths {
+        util::fs::remove_file(path)?;
+    }
+
+    if tree_dir.exists() {
+        util::fs::remove_dir_all(&tree_dir)?;
+    }
+    MigrationCheckpoint::delete(repo, MIGRATION_NAME)?;
+    tree_cache::invalidate(&repo.path);
+
+    // Every version is plain again, and v0.10.0 never compresses or encrypts new ones
+    let mut config = RepositoryConfig::from_repo(repo)?;
+    config.min_version = Some(MinOxenVersion::V0_10_0.as_str().to_string());
+    config.compression_level = None;
+    config.encryption_key_id = None;
+    let path = util::fs::config_filepath(&repo.path);
+    config.save(&path)?;
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::command::migrate::m05_optimize_merkle_tree::{
+        create_merkle_trees_down, create_merkle_trees_up,
+    };
+    use crate::constants;
+    use crate::core::v0_10_0::index::{CommitEntryReader, CommitReader};
+    use crate::core::versions::MinOxenVersion;
+    use crate::error::OxenError;
+    use crate::model::LocalRepository;
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_optimize_merkle_trees_down_restores_v0_10_0_layout() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed_async_min_version(
+            MinOxenVersion::V0_10_0,
+            |repo| async move {
+                let commit = CommitReader::new(&repo)?.head_commit()?;
+                let entries = CommitEntryReader::new(&repo, &commit)?.list_entries()?;
+
+                create_merkle_trees_up(&repo)?;
+                let repo = LocalRepository::new(&repo.path)?;
+                assert!(repo.min_version() == MinOxenVersion::V0_19_0);
+
+                // Versions compressed on v0.19.0 are plain again on v0.10.0
+                let compressed = &entries[0];
+                let contents = std::fs::read(repo.path.join(&compressed.path))?;
+                let upgraded_path = util::fs::version_path_from_hash_and_file_v0_10_0(
+                    &repo.path,
+                    &compressed.hash,
+                    compressed.filename(),
+                )
+                .with_extension("");
+                assert!(util::compression::compress_in_place(3, &upgraded_path)?);
+
+                create_merkle_trees_down(&repo)?;
+                let repo = LocalRepository::new(&repo.path)?;
+                assert!(repo.min_version() == MinOxenVersion::V0_10_0);
+                let tree_dir = repo
+                    .path
+                    .join(constants::OXEN_HIDDEN_DIR)
+                    .join(constants::TREE_DIR);
+                assert!(!tree_dir.exists());
+                for entry in entries.iter() {
+                    let version_path = util::fs::version_path_from_hash_and_file_v0_10_0(
+                        &repo.path,
+                        &entry.hash,
+                        entry.filename(),
+                    );
+                    assert!(version_path.exists());
+                }
+                let version_path = util::fs::version_path_from_hash_and_file_v0_10_0(
+                    &repo.path,
+                    &compressed.hash,
+                    compressed.filename(),
+                );
+                assert_eq!(std::fs::read(version_path)?, contents);
+
+                // The upgrade can run again from here
+                create_merkle_trees_up(&repo)?;
+                assert!(tree_dir.exists());
+
+                Ok(())
+            },
+        )
+        .await
+    }
+}
@@ -0,0 +1,136 @@
+//! # oxen mv
+//!
+//! Move or rename a tracked file or directory and stage it as a rename
+//!
+
+use std::path::{Path, PathBuf};
+
+use crate::error::OxenError;
+use crate::model::LocalRepository;
+use crate::repositories;
+use crate::util;
+
+/// # Move a file or directory within the repository
+///
+/// Moves `src` to `dst` on disk and stages both sides of the move. The contents keep
+/// their hash, so status reports the pair as moved and the diff reports it as renamed
+/// instead of an add and a remove. Like `mv`, if `dst` is an existing directory `src`
+/// is moved inside of it. Relative paths are relative to the root of the repository.
+pub fn mv(
+    repo: &LocalRepository,
+    src: impl AsRef<Path>,
+    dst: impl AsRef<Path>,
+) -> Result<PathBuf, OxenError> {
+    repositories::freeze::ensure_not_frozen(repo)?;
+
+    let src = full_path(repo, src.as_ref());
+    let mut dst = full_path(repo, dst.as_ref());
+    if !src.exists() {
+        return Err(OxenError::path_does_not_exist(&src));
+    }
+    if dst.is_dir() {
+        if let Some(file_name) = src.file_name() {
+            dst = dst.join(file_name);
+        }
+    }
+    if dst.exists() {
+        return Err(OxenError::basic_str(format!(
+            "Cannot move {:?} to {:?}, destination already exists",
+            src, dst
+        )));
+    }
+    if dst.starts_with(&src) {
+        return Err(OxenError::basic_str(format!(
+            "Cannot move {:?} into itself",
+            src
+        )));
+    }
+
+    let relative_src = util::fs::path_relative_to_dir(&src, &repo.path)?;
+    let relative_dst = util::fs::path_relative_to_dir(&dst, &repo.path)?;
+    let head_commit = repositories::commits::head_commit(repo)?;
+    let is_tracked = repositories::entries::get_file(repo, &head_commit, &relative_src)?.is_some()
+        || repositories::entries::get_directory(repo, &head_commit, &relative_src)?.is_some();
+    if !is_tracked {
+        return Err(OxenError::basic_str(format!(
+            "Cannot move {:?}, it is not tracked in the current commit",
+            relative_src
+        )));
+    }
+
+    if let Some(parent) = dst.parent() {
+        util::fs::create_dir_all(parent)?;
+    }
+    util::fs::rename(&src, &dst)?;
+
+    // Adding the old path stages its removal, the new path has the same hash
+    repositories::add(repo, &src)?;
+    repositories::add(repo, &dst)?;
+
+    log::debug!("Moved {:?} to {:?}", relative_src, relative_dst);
+    Ok(relative_dst)
+}
+
+fn full_path(repo: &LocalRepository, path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        repo.path.join(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use crate::command;
+    use crate::constants::DEFAULT_PAGE_SIZE;
+    use crate::error::OxenError;
+    use crate::model::diff::diff_entry_status::DiffEntryStatus;
+    use crate::repositories;
+    use crate::test;
+
+    #[test]
+    fn test_mv_stages_rename_and_diff_reports_renamed() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let base_commit = repositories::commits::head_commit(&repo)?;
+
+            let dst = command::mv(&repo, "README.md", Path::new("docs").join("README.md"))?;
+            assert_eq!(dst, Path::new("docs").join("README.md"));
+            assert!(!repo.path.join("README.md").exists());
+            assert!(repo.path.join(&dst).exists());
+
+            let status = repositories::status(&repo)?;
+            assert_eq!(status.moved_files.len(), 1);
+            assert!(status.untracked_files.is_empty());
+
+            let head_commit = repositories::commit(&repo, "Move the README into docs")?;
+            let diff = repositories::diffs::list_diff_entries(
+                &repo,
+                &base_commit,
+                &head_commit,
+                PathBuf::from(""),
+                1,
+                DEFAULT_PAGE_SIZE,
+            )?;
+            let files: Vec<_> = diff.entries.iter().filter(|e| !e.is_dir).collect();
+            assert_eq!(files.len(), 1);
+            assert_eq!(files[0].status, DiffEntryStatus::Renamed.to_string());
+            assert_eq!(files[0].filename, dst.to_string_lossy());
+            let base_resource = files[0].base_resource.as_ref().unwrap();
+            assert_eq!(base_resource.path, PathBuf::from("README.md"));
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_mv_refuses_to_overwrite() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let result = command::mv(&repo, "README.md", "labels.txt");
+            assert!(result.is_err());
+            assert!(repo.path.join("README.md").exists());
+            Ok(())
+        })
+    }
+}
@@ -6,10 +6,11 @@ pub mod endpoint;
 pub mod repository_config;
 pub mod user_config;
 
-pub use crate::config::repository_config::RepositoryConfig;
+pub use crate::config::repository_config::{BranchConfig, RepositoryConfig};
 
 pub use crate::config::user_config::UserConfig;
 pub use crate::config::user_config::USER_CONFIG_FILENAME;
 
 pub use crate::config::auth_config::AuthConfig;
+pub use crate::config::auth_config::HostConfig;
 pub use crate::config::auth_config::AUTH_CONFIG_FILENAME;
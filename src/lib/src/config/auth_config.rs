@@ -13,6 +13,19 @@ pub const AUTH_CONFIG_FILENAME: &str = "auth_config.toml";
 pub struct HostConfig {
     pub host: String,
     pub auth_token: Option<String>,
+    // How long to wait for a connection to the host
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connect_timeout_secs: Option<u64>,
+    // How long to wait between reads of a response before giving up
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub read_timeout_secs: Option<u64>,
+    // Proxy for all requests to the host, ex: http://proxy.corp:8080
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    // PEM bundle of extra root certificates to trust, for self-signed certificates or
+    // proxies that intercept TLS
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_cert: Option<PathBuf>,
 }
 
 impl HostConfig {
@@ -20,6 +33,10 @@ impl HostConfig {
         HostConfig {
             host: String::from(host),
             auth_token: None,
+            connect_timeout_secs: None,
+            read_timeout_secs: None,
+            proxy: None,
+            ca_cert: None,
         }
     }
 }
@@ -41,6 +58,9 @@ impl Hash for HostConfig {
 pub struct AuthConfig {
     pub default_host: Option<String>,
     pub host_configs: HashSet<HostConfig>,
+    // Never make network calls from this machine, see util::offline
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub offline: bool,
 }
 
 impl AuthConfig {
@@ -53,6 +73,7 @@ impl AuthConfig {
         AuthConfig {
             default_host: DEFAULT_HOST.to_string().into(),
             host_configs: HashSet::new(),
+            offline: false,
         }
     }
 
@@ -105,11 +126,27 @@ impl AuthConfig {
     }
 
     pub fn add_host_auth_token<S: AsRef<str>>(&mut self, host: S, token: S) {
+        let token = String::from(token.as_ref());
+        self.update_host_config(host, |config| config.auth_token = Some(token));
+    }
+
+    pub fn host_config<S: AsRef<str>>(&self, host: S) -> Option<&HostConfig> {
+        self.host_configs.get(&HostConfig::from_host(host.as_ref()))
+    }
+
+    /// Change the config of the host, keeping the rest of its settings
+    pub fn update_host_config<S: AsRef<str>>(
+        &mut self,
+        host: S,
+        update: impl FnOnce(&mut HostConfig),
+    ) {
         let host = host.as_ref();
-        self.host_configs.replace(HostConfig {
-            host: String::from(host),
-            auth_token: Some(String::from(token.as_ref())),
-        });
+        let mut config = self
+            .host_config(host)
+            .cloned()
+            .unwrap_or_else(|| HostConfig::from_host(host));
+        update(&mut config);
+        self.host_configs.replace(config);
     }
 
     pub fn auth_token_for_host<S: AsRef<str>>(&self, host: S) -> Option<String> {
@@ -150,4 +187,35 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_auth_token_keeps_host_settings() -> Result<(), OxenError> {
+        let mut auth_config = AuthConfig::new(&test::auth_cfg_file());
+
+        let host = "oxen.corp.example";
+        auth_config.update_host_config(host, |config| {
+            config.connect_timeout_secs = Some(5);
+            config.proxy = Some(String::from("http://proxy.corp.example:8080"));
+        });
+        auth_config.add_host_auth_token(host, "1234");
+
+        let host_config = auth_config.host_config(host).unwrap();
+        assert_eq!(host_config.auth_token, Some(String::from("1234")));
+        assert_eq!(host_config.connect_timeout_secs, Some(5));
+        assert_eq!(
+            host_config.proxy,
+            Some(String::from("http://proxy.corp.example:8080"))
+        );
+
+        // Settings survive a save and load
+        let toml = toml::to_string(&auth_config)?;
+        let loaded: AuthConfig = toml::from_str(&toml)?;
+        assert_eq!(
+            loaded.host_config(host).unwrap().connect_timeout_secs,
+            Some(5)
+        );
+        assert_eq!(loaded.host_config(host).unwrap().ca_cert, None);
+
+        Ok(())
+    }
 }
@@ -1,11 +1,21 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::Path;
 
 use crate::constants::DEFAULT_VNODE_SIZE;
 use crate::error::OxenError;
-use crate::model::{LocalRepository, Remote};
+use crate::model::{CheckoutMode, LocalRepository, Remote};
 use crate::util;
 
+/// The upstream a local branch tracks, written as `[branch.<name>]` in the config
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BranchConfig {
+    // name of the remote to push to and pull from
+    pub remote: String,
+    // name of the branch on the remote
+    pub merge: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct RepositoryConfig {
     // this is the current remote name
@@ -14,6 +24,19 @@ pub struct RepositoryConfig {
     // write the version if it is past v0.18.4
     pub min_version: Option<String>,
     pub vnode_size: Option<u64>,
+    // compute perceptual hashes for images when they are added
+    pub perceptual_hash: Option<bool>,
+    // hash tree nodes from their paths and contents only, so identical trees get identical hashes
+    pub content_addressed_tree: Option<bool>,
+    // copy, reflink or hardlink version files into the working dir on checkout
+    pub checkout_mode: Option<CheckoutMode>,
+    // id of the key version files are encrypted with, see util::encryption
+    pub encryption_key_id: Option<String>,
+    // zstd level version files are compressed with, see util::compression
+    pub compression_level: Option<i32>,
+    // upstream each local branch tracks, keyed by local branch name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub branch: BTreeMap<String, BranchConfig>,
 }
 
 impl Default for RepositoryConfig {
@@ -29,6 +52,12 @@ impl RepositoryConfig {
             remotes: Vec::new(),
             min_version: None,
             vnode_size: None,
+            perceptual_hash: None,
+            content_addressed_tree: None,
+            checkout_mode: None,
+            encryption_key_id: None,
+            compression_level: None,
+            branch: BTreeMap::new(),
         }
     }
 
@@ -52,4 +81,12 @@ impl RepositoryConfig {
     pub fn vnode_size(&self) -> u64 {
         self.vnode_size.unwrap_or(DEFAULT_VNODE_SIZE)
     }
+
+    pub fn perceptual_hash(&self) -> bool {
+        self.perceptual_hash.unwrap_or(false)
+    }
+
+    pub fn checkout_mode(&self) -> CheckoutMode {
+        self.checkout_mode.unwrap_or_default()
+    }
 }
@@ -25,10 +25,36 @@ pub const REPO_CONFIG_FILENAME: &str = "config.toml";
 pub const HEAD_FILE: &str = "HEAD";
 /// refs/ is a key,val store of branch names to commit ids
 pub const REFS_DIR: &str = "refs";
+/// remote_refs/ is a key,val store of `<remote>/<branch>` to the commit id it had when last fetched
+pub const REMOTE_REFS_DIR: &str = "remote_refs";
 /// history/ dir is a list of directories named after commit ids
 pub const HISTORY_DIR: &str = "history";
 /// commits/ is a key-value database of commit ids to commit objects
 pub const COMMITS_DIR: &str = "commits";
+/// commit_states/ is a key-value database of commit ids to data quality states
+pub const COMMIT_STATES_DIR: &str = "commit_states";
+/// FROZEN marks a repository as archived and read only, holding when and how it was frozen
+pub const FROZEN_FILE: &str = "FROZEN";
+/// provenance/ records where copied files came from, staged until the next commit
+pub const PROVENANCE_DIR: &str = "provenance";
+/// quarantine/ holds downloaded files whose contents did not match their hash
+pub const QUARANTINE_DIR: &str = "quarantine";
+/// report.jsonl in quarantine/ records one line per quarantined file
+pub const QUARANTINE_REPORT_FILE: &str = "report.jsonl";
+/// webhooks.json lists the urls the server notifies about changes to the repository
+pub const WEBHOOKS_FILE: &str = "webhooks.json";
+/// mirrors.json lists the servers the repository is replicated to
+pub const MIRRORS_FILE: &str = "mirrors.json";
+/// branch_protection.json holds the rules the server enforces on updates to branches
+pub const BRANCH_PROTECTION_FILE: &str = "branch_protection.json";
+/// storage_usage.json tracks the bytes each branch uses, to enforce quotas on the server
+pub const STORAGE_USAGE_FILE: &str = "storage_usage.json";
+/// storage_stats.json caches the storage stats of the repository for its branch heads
+pub const STORAGE_STATS_FILE: &str = "storage_stats.json";
+/// pii_scan.json holds the mode and extra rules of the scan for secrets and personal data
+pub const PII_SCAN_FILE: &str = "pii_scan.json";
+/// files at the root of a revision that describe the dataset, in order of preference
+pub const DATASET_CARD_FILENAMES: [&str; 2] = ["DATASET_CARD.md", "README.md"];
 /// name of the schema db
 pub const SCHEMAS_DIR: &str = "schemas";
 /// schemas node in merkle tree
@@ -49,12 +75,20 @@ pub const DATA_FRAME_STATUS_DIR: &str = "data_frame_status";
 pub const DIRS_DIR: &str = "dirs";
 /// prefix for a commit dir => hash maping
 pub const DIR_HASHES_DIR: &str = "dir_hashes";
+/// prefix for a commit path => last commit id mapping of content addressed trees
+pub const LAST_COMMITS_DIR: &str = "last_commits";
 /// prefix for the commit merkle tree db
 pub const TREE_DIR: &str = "tree";
 /// prefix for the commit merkle tree node dbs
 pub const NODES_DIR: &str = "nodes";
+/// prefix for the packed merkle tree nodes written by `oxen repack`
+pub const NODE_PACKS_DIR: &str = "packs";
 /// prefix for the cached stats dirs
 pub const CACHE_DIR: &str = "cache";
+/// prefix for the cached hashes of working dir files, inside the cache dir
+pub const STAT_CACHE_DIR: &str = "stat_cache";
+/// prefix for the blocks `oxen mount` downloads on demand, inside the cache dir
+pub const MOUNT_CACHE_DIR: &str = "mount";
 /// prefix for cached compare dfs
 pub const COMPARES_DIR: &str = "compares";
 /// prefix for the left commit pointer in cached compares
@@ -90,6 +124,8 @@ pub const DUCKDB_DF_TABLE_NAME: &str = "df";
 pub const MAX_QUERYABLE_ROWS: usize = 5_000_000;
 /// prefix for the sync status dirs to tell if commits are synced locally
 pub const SYNC_STATUS_DIR: &str = "sync_status";
+/// dir for the status of the background processing of pushed commits, one json file per commit
+pub const POST_PROCESS_DIR: &str = "post_process";
 /// Flag for if the repository was cloned in a shallow fashion
 pub const SHALLOW_FLAG: &str = "SHALLOW";
 /// prefix for the commit indices
@@ -112,8 +148,21 @@ pub const OBJECT_VNODES_DIR: &str = "vnodes";
 pub const OBJECT_SCHEMAS_DIR: &str = "schemas";
 /// File name for files stored in versions directory (>0.8.4). (Was commit id <= 0.8.4)
 pub const VERSION_FILE_NAME: &str = "data";
+/// Suffixes of the names of version files and chunk shards stored compressed, encrypted,
+/// or both. How a stored file is read only ever depends on its name.
+pub const COMPRESSED_FILE_SUFFIX: &str = "-zstd";
+pub const ENCRYPTED_FILE_SUFFIX: &str = "-enc";
+pub const COMPRESSED_ENCRYPTED_FILE_SUFFIX: &str = "-zstd-enc";
 /// merge/ is where any merge conflicts are stored so that we can get rid of them
 pub const MERGE_DIR: &str = "merge";
+/// merge_queue/ is a key-value database of queued branch merges, processed one at a time on the server
+pub const MERGE_QUEUE_DIR: &str = "merge_queue";
+/// merge_requests/ is a key-value database of proposed branch merges waiting on review
+pub const MERGE_REQUESTS_DIR: &str = "merge_requests";
+/// comments/ is a key-value database of the comments on commits, files and rows
+pub const COMMENTS_DIR: &str = "comments";
+/// releases/ is a key-value database of named dataset versions, each pointing at a commit
+pub const RELEASES_DIR: &str = "releases";
 /// mods/ is where we can stage appends, modifications, deletions to files to be merged later
 pub const MODS_DIR: &str = "mods";
 /// workspaces/ is where we can make remote changes without having to clone locally
@@ -134,6 +183,8 @@ pub const HASH_FILE: &str = "HASH";
 pub const CONTENT_IS_VALID: &str = "CONTENT_IS_VALID";
 /// Key for if something is synced
 pub const IS_SYNCED: &str = "IS_SYNCED";
+/// Key for if a commit is known to be on a remote
+pub const IS_PUSHED: &str = "IS_PUSHED";
 
 /// Default branch name: main
 pub const DEFAULT_BRANCH_NAME: &str = "main";
@@ -147,6 +198,11 @@ pub const DEFAULT_NAMESPACE: &str = "ox";
 
 /// Initial Commit Message
 pub const INITIAL_COMMIT_MSG: &str = "Initialized Repo 🐂";
+/// Message trailer that credits another author of a commit, the same one git uses
+pub const CO_AUTHORED_BY_TRAILER: &str = "Co-authored-by:";
+
+/// The first 16 bytes of every SQLite database file
+pub const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
 
 /// Internal Name When Performing Computation
 pub const ROW_NUM_COL_NAME: &str = "_row_num";
@@ -170,10 +226,27 @@ pub const EVAL_ERROR_COL: &str = "_oxen_eval_error";
 // Internal Name For Evaluations Duration
 pub const EVAL_DURATION_COL: &str = "_oxen_eval_duration";
 
+/// Table in the workspace duckdb that holds the review of each row, joined on _oxen_id
+pub const REVIEW_TABLE_NAME: &str = "review";
+/// Internal Name For Row Review Status
+pub const REVIEW_STATUS_COL: &str = "_oxen_review_status";
+// Internal Name For Row Review Comment
+pub const REVIEW_COMMENT_COL: &str = "_oxen_comment";
+
 // Data transfer
 // Average chunk size of ~4mb
 /// Average chunk size of ~4mb when chunking and sending data
 // pub const AVG_CHUNK_SIZE: u64 = 1024 * 1024 * 4;
+/// Most bytes of small files packed into one batched transfer request
+pub const BATCH_TRANSFER_SIZE: u64 = 1024 * 1024 * 64;
+/// Most small files packed into one batched transfer request
+pub const BATCH_TRANSFER_MAX_FILES: usize = 50_000;
+/// Content type of the zstd compressed tar archives batches of small files are sent in
+pub const TAR_ZSTD_CONTENT_TYPE: &str = "application/x-tar+zstd";
+/// Files larger than this are uploaded to a workspace in parts of this size
+pub const WORKSPACE_UPLOAD_CHUNK_SIZE: u64 = 1024 * 1024 * 16;
+/// Directory in a workspace where the parts of chunked uploads are kept until assembled
+pub const UPLOADS_DIR: &str = "uploads";
 pub const AVG_CHUNK_SIZE: u64 = 1024 * 1024 * 4;
 // Retry and back off of requests N times
 /// Retry and back off of requests N times
@@ -192,6 +265,11 @@ pub const DEFAULT_PAGE_SIZE: usize = 100;
 /// Pagination page number of 1
 pub const DEFAULT_PAGE_NUM: usize = 1;
 
+/// Content type of data frames returned as an Arrow IPC stream
+pub const ARROW_STREAM_CONTENT_TYPE: &str = "application/vnd.apache.arrow.stream";
+/// Value of the format query param that asks for a data frame as an Arrow IPC stream
+pub const ARROW_FORMAT: &str = "arrow";
+
 /// Redis queue name for post commit actions
 pub const COMMIT_QUEUE_NAME: &str = "commit_queue";
 pub const DEFAULT_REDIS_URL: &str = "redis://localhost:6379";
@@ -210,6 +288,8 @@ pub const MIN_OXEN_VERSION: MinOxenVersion = MinOxenVersion::V0_19_0;
 
 /// Filepath used to track repo and server-level migration status
 pub const LAST_MIGRATION_FILE: &str = "last_migration.txt";
+/// prefix for the progress dbs of migrations that can be resumed
+pub const MIGRATIONS_DIR: &str = "migrations";
 
 /// Constraints for diff and compare size
 pub const MAX_DISPLAY_DIRS: usize = 10;
@@ -4,9 +4,12 @@
 pub mod commit_sync_status;
 pub mod db;
 pub mod df;
+pub mod events;
 pub mod merge;
 pub mod oxenignore;
 pub mod refs;
+pub mod transfer;
 pub mod v0_10_0;
 pub mod v0_19_0;
 pub mod versions;
+pub mod walker;
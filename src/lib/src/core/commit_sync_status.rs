@@ -42,12 +42,29 @@ pub fn mark_commit_as_synced(repo: &LocalRepository, commit: &Commit) -> Result<
     }
 }
 
-fn commit_is_synced_file_path(repo: &LocalRepository, commit: &Commit) -> PathBuf {
+/// Whether the commit was pushed to or fetched from a remote
+pub fn commit_is_pushed(repo: &LocalRepository, commit: &Commit) -> bool {
+    commit_sync_dir(repo, &commit.id)
+        .join(constants::IS_PUSHED)
+        .exists()
+}
+
+pub fn mark_commit_as_pushed(repo: &LocalRepository, commit_id: &str) -> Result<(), OxenError> {
+    let dir = commit_sync_dir(repo, commit_id);
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join(constants::IS_PUSHED), "true")?;
+    Ok(())
+}
+
+fn commit_sync_dir(repo: &LocalRepository, commit_id: &str) -> PathBuf {
     repo.path
         .join(constants::OXEN_HIDDEN_DIR)
         .join(constants::TREE_DIR)
         .join(constants::SYNC_STATUS_DIR)
         .join(constants::COMMITS_DIR)
-        .join(&commit.id)
-        .join(constants::IS_SYNCED)
+        .join(commit_id)
+}
+
+fn commit_is_synced_file_path(repo: &LocalRepository, commit: &Commit) -> PathBuf {
+    commit_sync_dir(repo, &commit.id).join(constants::IS_SYNCED)
 }
@@ -1,6 +1,7 @@
 pub mod column_changes_db;
 pub mod columns;
 pub mod df_db;
+pub mod reviews;
 pub mod row_changes_db;
 pub mod rows;
 pub mod workspace_df_db;
@@ -213,7 +213,8 @@ pub fn select_str(
 
     if opts.sort_by.is_some() {
         let sort_by: String = opts.sort_by.clone().unwrap_or_default();
-        sql.push_str(&format!(" ORDER BY \"{}\"", sort_by));
+        let direction = if opts.should_reverse { " DESC" } else { "" };
+        sql.push_str(&format!(" ORDER BY \"{}\"{}", sort_by, direction));
     }
 
     let pagination_clause = if let Some(page) = opts.page {
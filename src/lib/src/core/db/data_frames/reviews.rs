@@ -0,0 +1,115 @@
+//! Row reviews of a workspace data frame.
+//!
+//! Reviews are kept in their own table next to the staged df table, so they never end up in
+//! the schema or the exported file when the workspace is committed.
+
+use duckdb::params;
+use polars::frame::DataFrame;
+
+use crate::constants::{
+    OXEN_ID_COL, REVIEW_COMMENT_COL, REVIEW_STATUS_COL, REVIEW_TABLE_NAME, TABLE_NAME,
+};
+use crate::core::db::data_frames::{df_db, workspace_df_db};
+use crate::error::OxenError;
+use crate::model::data_frame::schema::{DataType, Field};
+use crate::opts::DFOpts;
+use crate::view::data_frames::{RowReview, RowReviewStatus};
+
+pub fn create_table_if_not_exists(conn: &duckdb::Connection) -> Result<(), OxenError> {
+    let sql = format!(
+        "CREATE TABLE IF NOT EXISTS {REVIEW_TABLE_NAME} (\"{OXEN_ID_COL}\" VARCHAR PRIMARY KEY, \"{REVIEW_STATUS_COL}\" VARCHAR NOT NULL, \"{REVIEW_COMMENT_COL}\" VARCHAR)"
+    );
+    conn.execute(&sql, [])?;
+    Ok(())
+}
+
+/// Set the review of a row, replacing the one it had
+pub fn upsert(conn: &duckdb::Connection, review: &RowReview) -> Result<(), OxenError> {
+    let sql = format!("SELECT count(*) FROM {TABLE_NAME} WHERE \"{OXEN_ID_COL}\" = ?");
+    let num_rows: usize = conn.query_row(&sql, params![review.row_id], |row| row.get(0))?;
+    if num_rows == 0 {
+        return Err(OxenError::resource_not_found(&review.row_id));
+    }
+
+    let sql = format!("INSERT OR REPLACE INTO {REVIEW_TABLE_NAME} VALUES (?, ?, ?)");
+    conn.execute(
+        &sql,
+        params![review.row_id, review.status.to_string(), review.comment],
+    )?;
+    Ok(())
+}
+
+pub fn delete(conn: &duckdb::Connection, row_id: &str) -> Result<(), OxenError> {
+    let sql = format!("DELETE FROM {REVIEW_TABLE_NAME} WHERE \"{OXEN_ID_COL}\" = ?");
+    let num_deleted = conn.execute(&sql, params![row_id])?;
+    if num_deleted == 0 {
+        return Err(OxenError::resource_not_found(row_id));
+    }
+    Ok(())
+}
+
+pub fn list(conn: &duckdb::Connection) -> Result<Vec<RowReview>, OxenError> {
+    let sql = format!(
+        "SELECT \"{OXEN_ID_COL}\", \"{REVIEW_STATUS_COL}\", \"{REVIEW_COMMENT_COL}\" FROM {REVIEW_TABLE_NAME} ORDER BY \"{OXEN_ID_COL}\""
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let mut rows = stmt.query([])?;
+    let mut reviews = vec![];
+    while let Some(row) = rows.next()? {
+        let status: String = row.get(1)?;
+        reviews.push(RowReview {
+            row_id: row.get(0)?,
+            status: RowReviewStatus::from_string(&status)?,
+            comment: row.get(2)?,
+        });
+    }
+    Ok(reviews)
+}
+
+/// The staged rows with their review status and comment, optionally only the rows in one state
+pub fn select_rows(
+    conn: &duckdb::Connection,
+    status: Option<RowReviewStatus>,
+    opts: &DFOpts,
+) -> Result<DataFrame, OxenError> {
+    let mut schema = workspace_df_db::full_staged_table_schema(conn)?;
+    for name in [REVIEW_STATUS_COL, REVIEW_COMMENT_COL] {
+        schema
+            .fields
+            .push(Field::new(name, DataType::String.as_str()));
+    }
+
+    let sql = format!(
+        "SELECT {TABLE_NAME}.*, {status_col} AS \"{REVIEW_STATUS_COL}\", {REVIEW_TABLE_NAME}.\"{REVIEW_COMMENT_COL}\" AS \"{REVIEW_COMMENT_COL}\" FROM {TABLE_NAME} LEFT JOIN {REVIEW_TABLE_NAME} ON {TABLE_NAME}.\"{OXEN_ID_COL}\" = {REVIEW_TABLE_NAME}.\"{OXEN_ID_COL}\"{where_clause}",
+        status_col = status_col(),
+        where_clause = where_clause(status),
+    );
+    df_db::select_str(conn, sql, true, Some(&schema), Some(opts))
+}
+
+pub fn count_rows(
+    conn: &duckdb::Connection,
+    status: Option<RowReviewStatus>,
+) -> Result<usize, OxenError> {
+    let sql = format!(
+        "SELECT count(*) FROM {TABLE_NAME} LEFT JOIN {REVIEW_TABLE_NAME} ON {TABLE_NAME}.\"{OXEN_ID_COL}\" = {REVIEW_TABLE_NAME}.\"{OXEN_ID_COL}\"{}",
+        where_clause(status)
+    );
+    let count: usize = conn.query_row(&sql, [], |row| row.get(0))?;
+    Ok(count)
+}
+
+// Rows that were never reviewed are pending
+fn status_col() -> String {
+    format!(
+        "COALESCE({REVIEW_TABLE_NAME}.\"{REVIEW_STATUS_COL}\", '{}')",
+        RowReviewStatus::Pending
+    )
+}
+
+fn where_clause(status: Option<RowReviewStatus>) -> String {
+    match status {
+        Some(status) => format!(" WHERE {} = '{status}'", status_col()),
+        None => String::new(),
+    }
+}
@@ -172,6 +172,39 @@ pub fn delete_row(conn: &duckdb::Connection, uuid: &str) -> Result<DataFrame, Ox
     Ok(row_to_delete)
 }
 
+/// Run the statements in `f` in a single transaction, rolling all of them back if one fails
+pub fn with_transaction<T>(
+    conn: &duckdb::Connection,
+    f: impl FnOnce(&duckdb::Connection) -> Result<T, OxenError>,
+) -> Result<T, OxenError> {
+    conn.execute_batch("BEGIN TRANSACTION")?;
+    match f(conn) {
+        Ok(result) => {
+            conn.execute_batch("COMMIT")?;
+            Ok(result)
+        }
+        Err(err) => {
+            if let Err(rollback_err) = conn.execute_batch("ROLLBACK") {
+                log::error!("with_transaction could not roll back: {}", rollback_err);
+            }
+            Err(err)
+        }
+    }
+}
+
+pub fn get_row(conn: &duckdb::Connection, uuid: &str) -> Result<DataFrame, OxenError> {
+    let schema = full_staged_table_schema(conn)?;
+    let select_stmt = sql::Select::new()
+        .select("*")
+        .from(TABLE_NAME)
+        .where_clause(&format!("\"{}\" = '{}'", OXEN_ID_COL, uuid));
+    let row = df_db::select(conn, &select_stmt, true, Some(&schema), None)?;
+    if row.height() == 0 {
+        return Err(OxenError::resource_not_found(uuid));
+    }
+    Ok(row)
+}
+
 fn get_hash_and_status_for_modification(
     conn: &duckdb::Connection,
     old_row: &DataFrame,
@@ -311,6 +344,20 @@ pub fn record_row_change(
     row_changes_db::write_data_frame_row_change(&change, &db)
 }
 
+/// Record many changes while only opening the changes db once
+pub fn record_row_changes(
+    row_changes_path: &Path,
+    changes: Vec<DataFrameRowChange>,
+) -> Result<(), OxenError> {
+    let opts = db::key_val::opts::default();
+    let db = DB::open(&opts, dunce::simplified(row_changes_path))?;
+    for change in changes {
+        maybe_revert_row_changes(&db, change.row_id.to_owned())?;
+        row_changes_db::write_data_frame_row_change(&change, &db)?;
+    }
+    Ok(())
+}
+
 pub fn maybe_revert_row_changes(db: &DB, row_id: String) -> Result<(), OxenError> {
     match row_changes_db::get_data_frame_row_change(db, &row_id) {
         Ok(None) => revert_row_changes(db, row_id),
@@ -2,6 +2,9 @@
 //!
 
 pub mod filter;
+pub mod geo;
 pub mod pretty_print;
 pub mod sql;
+pub mod sql_export;
+pub mod sqlite;
 pub mod tabular;
@@ -0,0 +1,551 @@
+//! Geospatial data frames: GeoJSON feature collections and GeoParquet files
+//!
+//! Geometry columns are found from the GeoParquet `geo` file metadata, or are
+//! the `geometry` member of each GeoJSON feature. Bounding box filters use the
+//! GeoParquet covering columns when they exist so polars can skip row groups,
+//! and fall back to computing the extent of each geometry.
+//!
+
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Cursor;
+use std::num::NonZeroUsize;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::error::OxenError;
+use crate::model::metadata::metadata_geospatial::GeometryColumn;
+
+/// Key of the GeoParquet metadata in the parquet footer
+pub const GEOPARQUET_METADATA_KEY: &str = "geo";
+/// Column we put GeoJSON feature geometries in
+pub const GEOJSON_GEOMETRY_COLUMN: &str = "geometry";
+
+/// The `geo` metadata of a GeoParquet file
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GeoParquetMetadata {
+    pub version: String,
+    pub primary_column: String,
+    pub columns: HashMap<String, GeoParquetColumn>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GeoParquetColumn {
+    pub encoding: String,
+    #[serde(default)]
+    pub geometry_types: Vec<String>,
+    // PROJJSON, missing means OGC:CRS84
+    pub crs: Option<Value>,
+    pub bbox: Option<Vec<f64>>,
+    pub covering: Option<GeoParquetCovering>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GeoParquetCovering {
+    pub bbox: GeoParquetBboxCovering,
+}
+
+/// Paths to the struct fields holding the bounding box of each row, ie: ["bbox", "xmin"]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct GeoParquetBboxCovering {
+    pub xmin: Vec<String>,
+    pub ymin: Vec<String>,
+    pub xmax: Vec<String>,
+    pub ymax: Vec<String>,
+}
+
+/// An axis aligned bounding box, parsed from "min_x,min_y,max_x,max_y"
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min_x: f64,
+    pub min_y: f64,
+    pub max_x: f64,
+    pub max_y: f64,
+}
+
+impl BoundingBox {
+    fn empty() -> Self {
+        Self {
+            min_x: f64::INFINITY,
+            min_y: f64::INFINITY,
+            max_x: f64::NEG_INFINITY,
+            max_y: f64::NEG_INFINITY,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.min_x > self.max_x || self.min_y > self.max_y
+    }
+
+    fn extend(&mut self, x: f64, y: f64) {
+        // Empty points are encoded as NaN in WKB
+        if x.is_nan() || y.is_nan() {
+            return;
+        }
+        self.min_x = self.min_x.min(x);
+        self.min_y = self.min_y.min(y);
+        self.max_x = self.max_x.max(x);
+        self.max_y = self.max_y.max(y);
+    }
+
+    fn merge(&mut self, other: &BoundingBox) {
+        if !other.is_empty() {
+            self.extend(other.min_x, other.min_y);
+            self.extend(other.max_x, other.max_y);
+        }
+    }
+
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        self.min_x <= other.max_x
+            && other.min_x <= self.max_x
+            && self.min_y <= other.max_y
+            && other.min_y <= self.max_y
+    }
+
+    pub fn to_vec(&self) -> Vec<f64> {
+        vec![self.min_x, self.min_y, self.max_x, self.max_y]
+    }
+}
+
+impl FromStr for BoundingBox {
+    type Err = OxenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let vals: Vec<f64> = s
+            .split(',')
+            .map(|v| v.trim().parse::<f64>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| OxenError::basic_str(format!("Invalid bbox '{s}'")))?;
+        let [min_x, min_y, max_x, max_y] = vals[..] else {
+            return Err(OxenError::basic_str(format!(
+                "Invalid bbox '{s}', expected 'min_x,min_y,max_x,max_y'"
+            )));
+        };
+        if min_x > max_x || min_y > max_y {
+            return Err(OxenError::basic_str(format!(
+                "Invalid bbox '{s}', min must be less than max"
+            )));
+        }
+        Ok(Self {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+        })
+    }
+}
+
+/// Read the `geo` metadata out of a parquet footer, None if it is plain parquet
+pub fn read_geoparquet_metadata(
+    path: impl AsRef<Path>,
+) -> Result<Option<GeoParquetMetadata>, OxenError> {
+    let file = File::open(path.as_ref())?;
+    let mut reader = ParquetReader::new(file);
+    let metadata = reader.get_metadata()?;
+    let Some(key_values) = metadata.key_value_metadata() else {
+        return Ok(None);
+    };
+    let Some(geo) = key_values
+        .iter()
+        .find(|kv| kv.key == GEOPARQUET_METADATA_KEY)
+        .and_then(|kv| kv.value.as_ref())
+    else {
+        return Ok(None);
+    };
+    Ok(Some(serde_json::from_str(geo)?))
+}
+
+pub fn is_geoparquet(path: impl AsRef<Path>) -> bool {
+    matches!(read_geoparquet_metadata(path), Ok(Some(_)))
+}
+
+/// Geometry columns declared in a GeoParquet file
+pub fn geoparquet_geometry_columns(
+    metadata: &GeoParquetMetadata,
+) -> Result<Vec<GeometryColumn>, OxenError> {
+    let mut columns = vec![];
+    for (name, column) in metadata.columns.iter() {
+        columns.push(GeometryColumn {
+            name: name.to_owned(),
+            encoding: column.encoding.to_lowercase(),
+            geometry_types: column.geometry_types.to_owned(),
+            crs: column.crs.as_ref().map(serde_json::to_string).transpose()?,
+            bbox: column.bbox.to_owned(),
+        });
+    }
+    columns.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(columns)
+}
+
+fn read_geojson(path: impl AsRef<Path>) -> Result<Value, OxenError> {
+    let file = File::open(path.as_ref())?;
+    let json: Value = serde_json::from_reader(std::io::BufReader::new(file))?;
+    Ok(json)
+}
+
+fn geojson_features(json: &Value) -> Vec<Value> {
+    match json.get("type").and_then(Value::as_str) {
+        Some("FeatureCollection") => json
+            .get("features")
+            .and_then(Value::as_array)
+            .cloned()
+            .unwrap_or_default(),
+        Some("Feature") => vec![json.to_owned()],
+        // A bare geometry
+        Some(_) => vec![serde_json::json!({ "geometry": json })],
+        None => vec![],
+    }
+}
+
+/// Read a GeoJSON file with one row per feature, the feature properties as columns,
+/// and the geometry as a GeoJSON string in the `geometry` column
+pub fn read_df_geojson(path: impl AsRef<Path>) -> Result<LazyFrame, OxenError> {
+    let path = path.as_ref();
+    let json = read_geojson(path)?;
+    let rows: Vec<Value> = geojson_features(&json)
+        .into_iter()
+        .map(|feature| {
+            let mut row = feature
+                .get("properties")
+                .and_then(Value::as_object)
+                .cloned()
+                .unwrap_or_default();
+            let geometry = feature.get("geometry").cloned().unwrap_or(Value::Null);
+            row.insert(
+                GEOJSON_GEOMETRY_COLUMN.to_string(),
+                Value::String(geometry.to_string()),
+            );
+            Value::Object(row)
+        })
+        .collect();
+
+    if rows.is_empty() {
+        let geometry = Series::new_empty(
+            PlSmallStr::from_str(GEOJSON_GEOMETRY_COLUMN),
+            &DataType::String,
+        );
+        return Ok(DataFrame::new(vec![Column::Series(geometry)])?.lazy());
+    }
+
+    let data = serde_json::to_vec(&rows)?;
+    let df = JsonReader::new(Cursor::new(data))
+        .infer_schema_len(Some(NonZeroUsize::new(10000).unwrap()))
+        .finish()
+        .map_err(|e| {
+            OxenError::basic_str(format!("Could not read geojson from path {path:?}: {e}"))
+        })?;
+    Ok(df.lazy())
+}
+
+/// The geometry column of a GeoJSON file, with the geometry types and extent of the features
+pub fn geojson_geometry_columns(path: impl AsRef<Path>) -> Result<Vec<GeometryColumn>, OxenError> {
+    let json = read_geojson(path)?;
+    let mut geometry_types: Vec<String> = vec![];
+    let mut bbox = BoundingBox::empty();
+    for feature in geojson_features(&json) {
+        let Some(geometry) = feature.get("geometry") else {
+            continue;
+        };
+        if let Some(geometry_type) = geometry.get("type").and_then(Value::as_str) {
+            if !geometry_types.iter().any(|t| t == geometry_type) {
+                geometry_types.push(geometry_type.to_string());
+            }
+        }
+        bbox.merge(&geojson_bbox(geometry));
+    }
+    geometry_types.sort();
+
+    // The 2008 GeoJSON spec allowed a named crs, RFC 7946 always means OGC:CRS84
+    let crs = json.get("crs").map(|crs| crs.to_string());
+
+    Ok(vec![GeometryColumn {
+        name: GEOJSON_GEOMETRY_COLUMN.to_string(),
+        encoding: String::from("geojson"),
+        geometry_types,
+        crs,
+        bbox: (!bbox.is_empty()).then(|| bbox.to_vec()),
+    }])
+}
+
+/// Keep the rows whose geometry intersects the bounding box.
+///
+/// GeoParquet files with a bbox covering column are filtered lazily on that column so the
+/// predicate is pushed down into the scan, everything else is filtered row by row.
+pub fn filter_bbox(
+    df: LazyFrame,
+    path: impl AsRef<Path>,
+    extension: &str,
+    bbox: &BoundingBox,
+) -> Result<LazyFrame, OxenError> {
+    let path = path.as_ref();
+    match extension {
+        "parquet" => {
+            let Some(metadata) = read_geoparquet_metadata(path)? else {
+                return Err(OxenError::basic_str(format!(
+                    "Cannot filter by bbox, {path:?} is not GeoParquet"
+                )));
+            };
+            let Some(column) = metadata.columns.get(&metadata.primary_column) else {
+                return Err(OxenError::basic_str(format!(
+                    "GeoParquet primary column '{}' is not described in the geo metadata",
+                    metadata.primary_column
+                )));
+            };
+            if let Some(covering) = &column.covering {
+                let field = |path: &[String]| -> Result<Expr, OxenError> {
+                    let Some((first, rest)) = path.split_first() else {
+                        return Err(OxenError::basic_str("Invalid GeoParquet covering"));
+                    };
+                    Ok(rest.iter().fold(col(first.as_str()), |expr, name| {
+                        expr.struct_().field_by_name(name)
+                    }))
+                };
+                let predicate = field(&covering.bbox.xmin)?
+                    .lt_eq(lit(bbox.max_x))
+                    .and(field(&covering.bbox.xmax)?.gt_eq(lit(bbox.min_x)))
+                    .and(field(&covering.bbox.ymin)?.lt_eq(lit(bbox.max_y)))
+                    .and(field(&covering.bbox.ymax)?.gt_eq(lit(bbox.min_y)));
+                return Ok(df.filter(predicate));
+            }
+
+            let encoding = column.encoding.to_lowercase();
+            if encoding != "wkb" {
+                return Err(OxenError::basic_str(format!(
+                    "Cannot filter {encoding} geometries by bbox without a bbox covering column"
+                )));
+            }
+            let df = df.collect()?;
+            let geometries = df.column(&metadata.primary_column)?.binary()?;
+            let mask: BooleanChunked = geometries
+                .into_iter()
+                .map(|wkb| wkb.and_then(wkb_bbox).is_some_and(|b| b.intersects(bbox)))
+                .collect();
+            Ok(df.filter(&mask)?.lazy())
+        }
+        "geojson" => {
+            let df = df.collect()?;
+            let geometries = df.column(GEOJSON_GEOMETRY_COLUMN)?.str()?;
+            let mask: BooleanChunked = geometries
+                .into_iter()
+                .map(|geometry| {
+                    geometry
+                        .and_then(|g| serde_json::from_str::<Value>(g).ok())
+                        .map(|g| geojson_bbox(&g))
+                        .is_some_and(|b| !b.is_empty() && b.intersects(bbox))
+                })
+                .collect();
+            Ok(df.filter(&mask)?.lazy())
+        }
+        _ => Err(OxenError::basic_str(format!(
+            "Cannot filter by bbox, {path:?} has no geometry column"
+        ))),
+    }
+}
+
+/// Extent of a GeoJSON geometry, empty if it has no coordinates
+pub fn geojson_bbox(geometry: &Value) -> BoundingBox {
+    fn walk(coords: &Value, bbox: &mut BoundingBox) {
+        let Some(coords) = coords.as_array() else {
+            return;
+        };
+        match (
+            coords.first().and_then(Value::as_f64),
+            coords.get(1).and_then(Value::as_f64),
+        ) {
+            (Some(x), Some(y)) => bbox.extend(x, y),
+            _ => coords.iter().for_each(|c| walk(c, bbox)),
+        }
+    }
+
+    let mut bbox = BoundingBox::empty();
+    if let Some(geometries) = geometry.get("geometries").and_then(Value::as_array) {
+        for geometry in geometries {
+            bbox.merge(&geojson_bbox(geometry));
+        }
+    } else if let Some(coords) = geometry.get("coordinates") {
+        walk(coords, &mut bbox);
+    }
+    bbox
+}
+
+/// Extent of a WKB (or EWKB) geometry, None if the bytes are not valid
+pub fn wkb_bbox(wkb: &[u8]) -> Option<BoundingBox> {
+    let mut reader = WkbReader { data: wkb, pos: 0 };
+    let mut bbox = BoundingBox::empty();
+    reader.geometry(&mut bbox)?;
+    Some(bbox)
+}
+
+struct WkbReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl WkbReader<'_> {
+    fn bytes<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let bytes = self.data.get(self.pos..self.pos + N)?.try_into().ok()?;
+        self.pos += N;
+        Some(bytes)
+    }
+
+    fn u32(&mut self, little_endian: bool) -> Option<u32> {
+        let bytes = self.bytes::<4>()?;
+        Some(if little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn f64(&mut self, little_endian: bool) -> Option<f64> {
+        let bytes = self.bytes::<8>()?;
+        Some(if little_endian {
+            f64::from_le_bytes(bytes)
+        } else {
+            f64::from_be_bytes(bytes)
+        })
+    }
+
+    fn points(&mut self, n: u32, dims: usize, le: bool, bbox: &mut BoundingBox) -> Option<()> {
+        for _ in 0..n {
+            let x = self.f64(le)?;
+            let y = self.f64(le)?;
+            for _ in 2..dims {
+                self.f64(le)?;
+            }
+            bbox.extend(x, y);
+        }
+        Some(())
+    }
+
+    fn geometry(&mut self, bbox: &mut BoundingBox) -> Option<()> {
+        let le = self.bytes::<1>()?[0] == 1;
+        let raw_type = self.u32(le)?;
+
+        // EWKB flags the extra dimensions and srid in the high bits
+        let mut dims = 2;
+        if raw_type & 0x8000_0000 != 0 {
+            dims += 1;
+        }
+        if raw_type & 0x4000_0000 != 0 {
+            dims += 1;
+        }
+        if raw_type & 0x2000_0000 != 0 {
+            self.u32(le)?;
+        }
+        // ISO WKB uses 1000s for Z, M and ZM
+        let iso_type = raw_type & 0x0fff_ffff;
+        dims += match iso_type / 1000 {
+            1 | 2 => 1,
+            3 => 2,
+            _ => 0,
+        };
+
+        match iso_type % 1000 {
+            1 => self.points(1, dims, le, bbox),
+            2 => {
+                let n = self.u32(le)?;
+                self.points(n, dims, le, bbox)
+            }
+            3 => {
+                let rings = self.u32(le)?;
+                for _ in 0..rings {
+                    let n = self.u32(le)?;
+                    self.points(n, dims, le, bbox)?;
+                }
+                Some(())
+            }
+            4..=7 => {
+                let n = self.u32(le)?;
+                for _ in 0..n {
+                    self.geometry(bbox)?;
+                }
+                Some(())
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::core::df::geo::{self, BoundingBox};
+    use crate::error::OxenError;
+    use crate::test;
+    use crate::util;
+
+    fn wkb_point(x: f64, y: f64) -> Vec<u8> {
+        let mut wkb = vec![1u8];
+        wkb.extend_from_slice(&1u32.to_le_bytes());
+        wkb.extend_from_slice(&x.to_le_bytes());
+        wkb.extend_from_slice(&y.to_le_bytes());
+        wkb
+    }
+
+    #[test]
+    fn test_wkb_bbox() {
+        let bbox = geo::wkb_bbox(&wkb_point(1.5, -2.0)).unwrap();
+        assert_eq!(bbox.to_vec(), vec![1.5, -2.0, 1.5, -2.0]);
+
+        // MultiPoint of two points
+        let mut wkb = vec![1u8];
+        wkb.extend_from_slice(&4u32.to_le_bytes());
+        wkb.extend_from_slice(&2u32.to_le_bytes());
+        wkb.extend(wkb_point(0.0, 0.0));
+        wkb.extend(wkb_point(10.0, 5.0));
+        let bbox = geo::wkb_bbox(&wkb).unwrap();
+        assert_eq!(bbox.to_vec(), vec![0.0, 0.0, 10.0, 5.0]);
+
+        assert!(geo::wkb_bbox(&wkb[..10]).is_none());
+    }
+
+    #[test]
+    fn test_parse_bbox() {
+        let bbox = BoundingBox::from_str("-10,-5.5,10,5.5").unwrap();
+        assert_eq!(bbox.min_y, -5.5);
+        assert!(BoundingBox::from_str("1,2,3").is_err());
+        assert!(BoundingBox::from_str("10,0,0,10").is_err());
+    }
+
+    #[test]
+    fn test_read_and_filter_geojson() -> Result<(), OxenError> {
+        test::run_empty_dir_test(|dir| {
+            let path = dir.join("parks.geojson");
+            util::fs::write_to_path(
+                &path,
+                r#"{
+                    "type": "FeatureCollection",
+                    "features": [
+                        {"type": "Feature", "properties": {"name": "Golden Gate"},
+                         "geometry": {"type": "Point", "coordinates": [-122.48, 37.77]}},
+                        {"type": "Feature", "properties": {"name": "Central"},
+                         "geometry": {"type": "Polygon", "coordinates": [[[-73.98, 40.76], [-73.95, 40.80], [-73.95, 40.76], [-73.98, 40.76]]]}}
+                    ]
+                }"#,
+            )?;
+
+            let df = geo::read_df_geojson(&path)?.collect()?;
+            assert_eq!(df.height(), 2);
+            assert!(df.column("name").is_ok());
+            assert!(df.column(geo::GEOJSON_GEOMETRY_COLUMN).is_ok());
+
+            let columns = geo::geojson_geometry_columns(&path)?;
+            assert_eq!(columns[0].geometry_types, vec!["Point", "Polygon"]);
+            assert_eq!(columns[0].bbox, Some(vec![-122.48, 37.77, -73.95, 40.80]));
+
+            // Only New York
+            let bbox = BoundingBox::from_str("-75,40,-73,41")?;
+            let df = geo::filter_bbox(geo::read_df_geojson(&path)?, &path, "geojson", &bbox)?
+                .collect()?;
+            assert_eq!(df.height(), 1);
+            assert_eq!(df.column("name")?.str()?.get(0), Some("Central"));
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,226 @@
+//! Read tables out of SQLite databases as DataFrames
+//!
+
+use polars::prelude::*;
+use rusqlite::types::Value;
+use rusqlite::{Connection, OpenFlags};
+use std::path::Path;
+
+use crate::constants;
+use crate::error::OxenError;
+use crate::model::{DataFrameSize, Schema};
+
+/// Extensions we treat as SQLite databases when reading data frames
+pub const SQLITE_EXTENSIONS: [&str; 3] = ["sqlite", "sqlite3", "db"];
+
+pub fn is_sqlite_extension(extension: &str) -> bool {
+    SQLITE_EXTENSIONS.contains(&extension)
+}
+
+fn open(path: impl AsRef<Path>) -> Result<Connection, OxenError> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Err(OxenError::entry_does_not_exist(path));
+    }
+    Ok(Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?)
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// List the user tables in the database, skipping SQLite's internal tables
+pub fn list_tables(path: impl AsRef<Path>) -> Result<Vec<String>, OxenError> {
+    let conn = open(path)?;
+    let mut stmt = conn.prepare(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+    )?;
+    let tables = stmt
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<String>, _>>()?;
+    Ok(tables)
+}
+
+/// Pick the table to read. If none is given, the database must only have one table.
+pub fn resolve_table(path: impl AsRef<Path>, table: Option<&str>) -> Result<String, OxenError> {
+    let path = path.as_ref();
+    let tables = list_tables(path)?;
+    match table {
+        Some(table) if tables.iter().any(|t| t == table) => Ok(table.to_string()),
+        Some(table) => Err(OxenError::basic_str(format!(
+            "Table '{}' not found in {:?}, available tables: {}",
+            table,
+            path,
+            tables.join(", ")
+        ))),
+        None if tables.len() == 1 => Ok(tables[0].to_owned()),
+        None if tables.is_empty() => Err(OxenError::basic_str(format!(
+            "No tables found in {:?}",
+            path
+        ))),
+        None => Err(OxenError::basic_str(format!(
+            "{:?} has {} tables, pick one with --table: {}",
+            path,
+            tables.len(),
+            tables.join(", ")
+        ))),
+    }
+}
+
+/// Read a whole table into a DataFrame.
+///
+/// SQLite values are dynamically typed, so the column type is picked from the values
+/// themselves: integers, then floats, then binary, falling back to strings for mixed columns.
+pub fn read_table(path: impl AsRef<Path>, table: &str) -> Result<DataFrame, OxenError> {
+    query_df(path, &format!("SELECT * FROM {}", quote_identifier(table)))
+}
+
+/// Get the schema of a table from the first page of rows
+pub fn table_schema(path: impl AsRef<Path>, table: &str) -> Result<Schema, OxenError> {
+    let df = query_df(
+        path,
+        &format!(
+            "SELECT * FROM {} LIMIT {}",
+            quote_identifier(table),
+            constants::DEFAULT_PAGE_SIZE
+        ),
+    )?;
+    Ok(Schema::from_polars(&df.schema()))
+}
+
+fn query_df(path: impl AsRef<Path>, sql: &str) -> Result<DataFrame, OxenError> {
+    let conn = open(path)?;
+    let mut stmt = conn.prepare(sql)?;
+    let names: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+
+    let mut values: Vec<Vec<Value>> = vec![Vec::new(); names.len()];
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        for (i, column) in values.iter_mut().enumerate() {
+            column.push(row.get::<_, Value>(i)?);
+        }
+    }
+
+    let columns: Vec<Column> = names
+        .iter()
+        .zip(values)
+        .map(|(name, values)| Column::Series(values_to_series(name, values)))
+        .collect();
+    Ok(DataFrame::new(columns)?)
+}
+
+/// Count the rows and columns of a table without reading it
+pub fn table_size(path: impl AsRef<Path>, table: &str) -> Result<DataFrameSize, OxenError> {
+    let conn = open(path)?;
+    let table = quote_identifier(table);
+    let height: i64 = conn.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+        row.get(0)
+    })?;
+    let stmt = conn.prepare(&format!("SELECT * FROM {table} LIMIT 0"))?;
+    Ok(DataFrameSize {
+        width: stmt.column_count(),
+        height: height as usize,
+    })
+}
+
+/// Read the table picked by `--table`, or the only table in the database
+pub fn read_df_sqlite(path: impl AsRef<Path>, table: Option<&str>) -> Result<LazyFrame, OxenError> {
+    let path = path.as_ref();
+    let table = resolve_table(path, table)?;
+    Ok(read_table(path, &table)?.lazy())
+}
+
+fn values_to_series(name: &str, values: Vec<Value>) -> Series {
+    let name = PlSmallStr::from_str(name);
+    let all = |f: fn(&Value) -> bool| values.iter().all(|v| matches!(v, Value::Null) || f(v));
+
+    if all(|v| matches!(v, Value::Integer(_))) {
+        let values: Vec<Option<i64>> = values
+            .into_iter()
+            .map(|v| match v {
+                Value::Integer(i) => Some(i),
+                _ => None,
+            })
+            .collect();
+        Series::new(name, values)
+    } else if all(|v| matches!(v, Value::Integer(_) | Value::Real(_))) {
+        let values: Vec<Option<f64>> = values
+            .into_iter()
+            .map(|v| match v {
+                Value::Integer(i) => Some(i as f64),
+                Value::Real(f) => Some(f),
+                _ => None,
+            })
+            .collect();
+        Series::new(name, values)
+    } else if all(|v| matches!(v, Value::Blob(_))) {
+        BinaryChunked::from_iter_options(
+            name,
+            values.into_iter().map(|v| match v {
+                Value::Blob(b) => Some(b),
+                _ => None,
+            }),
+        )
+        .into_series()
+    } else {
+        let values: Vec<Option<String>> = values
+            .into_iter()
+            .map(|v| match v {
+                Value::Null => None,
+                Value::Integer(i) => Some(i.to_string()),
+                Value::Real(f) => Some(f.to_string()),
+                Value::Text(s) => Some(s),
+                Value::Blob(b) => Some(String::from_utf8_lossy(&b).to_string()),
+            })
+            .collect();
+        Series::new(name, values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::*;
+    use rusqlite::Connection;
+
+    use crate::core::df::sqlite;
+    use crate::error::OxenError;
+    use crate::test;
+
+    #[test]
+    fn test_read_sqlite_tables() -> Result<(), OxenError> {
+        test::run_empty_dir_test(|dir| {
+            let path = dir.join("labels.db");
+            let conn = Connection::open(&path)?;
+            conn.execute_batch(
+                "CREATE TABLE labels (file TEXT, label TEXT, score REAL, width INTEGER);
+                 INSERT INTO labels VALUES ('a.jpg', 'cat', 0.9, 10), ('b.jpg', 'dog', 1, NULL);
+                 CREATE TABLE annotators (name TEXT);",
+            )?;
+            drop(conn);
+
+            assert_eq!(sqlite::list_tables(&path)?, vec!["annotators", "labels"]);
+            assert!(sqlite::resolve_table(&path, None).is_err());
+            assert!(sqlite::resolve_table(&path, Some("missing")).is_err());
+
+            let df = sqlite::read_table(&path, "labels")?;
+            assert_eq!(df.height(), 2);
+            assert_eq!(df.width(), 4);
+            assert_eq!(df.column("score")?.dtype(), &DataType::Float64);
+            assert_eq!(df.column("width")?.dtype(), &DataType::Int64);
+            assert_eq!(df.column("label")?.dtype(), &DataType::String);
+
+            let schema = sqlite::table_schema(&path, "labels")?;
+            assert_eq!(schema.fields.len(), 4);
+            assert_eq!(schema.fields[0].name, "file");
+
+            let size = sqlite::table_size(&path, "labels")?;
+            assert_eq!(size.width, 4);
+            assert_eq!(size.height, 2);
+
+            Ok(())
+        })
+    }
+}
@@ -6,12 +6,15 @@ use std::num::NonZeroUsize;
 
 use crate::constants;
 use crate::core::df::filter::DFLogicalOp;
+use crate::core::df::geo;
 use crate::core::df::pretty_print;
 use crate::core::df::sql;
+use crate::core::df::sqlite;
 use crate::error::OxenError;
 use crate::io::chunk_reader::ChunkReader;
 use crate::model::data_frame::schema::DataType;
 use crate::model::merkle_tree::node::MerkleTreeNode;
+use crate::model::metadata::ColumnStats;
 use crate::model::Commit;
 use crate::model::DataFrameSize;
 use crate::model::LocalRepository;
@@ -382,6 +385,31 @@ fn filter_df(mut df: LazyFrame, filter: &DFFilterExp) -> Result<LazyFrame, OxenE
     Ok(df.filter(expr))
 }
 
+/// Split the rows into the ones that match the filter and the ones that don't, keeping their order
+pub fn partition_by_filter(
+    df: DataFrame,
+    filter: &DFFilterExp,
+) -> Result<(DataFrame, DataFrame), OxenError> {
+    let df = df_add_row_num(df)?;
+    let matching = filter_df(df.clone().lazy(), filter)?.collect()?;
+    let matched: std::collections::HashSet<IdxSize> = matching
+        .column(constants::ROW_NUM_COL_NAME)?
+        .idx()?
+        .into_no_null_iter()
+        .collect();
+    let mask: BooleanChunked = df
+        .column(constants::ROW_NUM_COL_NAME)?
+        .idx()?
+        .into_no_null_iter()
+        .map(|row| !matched.contains(&row))
+        .collect();
+    let rest = df.filter(&mask)?;
+    Ok((
+        matching.drop(constants::ROW_NUM_COL_NAME)?,
+        rest.drop(constants::ROW_NUM_COL_NAME)?,
+    ))
+}
+
 fn unique_df(df: LazyFrame, columns: Vec<String>) -> Result<LazyFrame, OxenError> {
     log::debug!("Got unique: {:?}", columns);
     Ok(df.unique(Some(columns), UniqueKeepStrategy::First))
@@ -829,6 +857,132 @@ fn p_read_df_with_extension(
 ) -> Result<DataFrame, OxenError> {
     let path = path.as_ref();
     let extension = extension.as_ref();
+    let df = p_lazy_df_with_extension(path, extension, opts)?;
+
+    // log::debug!("Read finished");
+    if opts.has_transform() {
+        let df = transform_new(df, opts.clone())?;
+        Ok(df.collect()?)
+    } else {
+        Ok(df.collect()?)
+    }
+}
+
+/// Count the rows that pass the filter of `opts` without loading the file. The count is a
+/// lazy query, so only the columns the filter uses are read and parquet row groups that
+/// cannot match are skipped.
+pub fn count_df_with_extension(
+    path: impl AsRef<Path>,
+    extension: impl AsRef<str>,
+    opts: &DFOpts,
+) -> Result<usize, OxenError> {
+    let path = path.as_ref();
+    let extension = extension.as_ref();
+    std::panic::catch_unwind(|| p_count_df_with_extension(path, extension, opts)).map_err(|e| {
+        log::error!("Error Counting DataFrame {e:?} - {:?}", path);
+        OxenError::DataFrameError(format!("Error Counting DataFrame {e:?}").into())
+    })?
+}
+
+fn p_count_df_with_extension(
+    path: &Path,
+    extension: &str,
+    opts: &DFOpts,
+) -> Result<usize, OxenError> {
+    let mut df = p_lazy_df_with_extension(path, extension, opts)?;
+    if let Some(filter) = opts.get_filter()? {
+        df = filter_df(df, &filter)?;
+    }
+    if let Some(columns) = opts.unique_columns() {
+        df = unique_df(df, columns)?;
+    }
+    let counts = df.select([len()]).collect()?;
+    let count = counts
+        .column("len")?
+        .get(0)?
+        .extract::<usize>()
+        .unwrap_or_default();
+    Ok(count)
+}
+
+/// Min, max, null count and number of distinct values of every column, in one pass over the file
+pub fn get_column_stats_with_extension(
+    path: impl AsRef<Path>,
+    extension: impl AsRef<str>,
+) -> Result<Vec<ColumnStats>, OxenError> {
+    let path = path.as_ref();
+    let extension = extension.as_ref();
+    std::panic::catch_unwind(|| p_get_column_stats_with_extension(path, extension)).map_err(
+        |e| {
+            log::error!("Error Computing Column Stats {e:?} - {:?}", path);
+            OxenError::DataFrameError(format!("Error Computing Column Stats {e:?}").into())
+        },
+    )?
+}
+
+fn p_get_column_stats_with_extension(
+    path: &Path,
+    extension: &str,
+) -> Result<Vec<ColumnStats>, OxenError> {
+    let mut df = p_lazy_df_with_extension(path, extension, &DFOpts::empty())?;
+    let schema = df.collect_schema()?;
+
+    // Alias by index so the stats can never collide with a column name
+    let mut exprs = vec![];
+    for (i, (name, dtype)) in schema.iter().enumerate() {
+        let column = col(name.as_str());
+        exprs.push(column.clone().null_count().alias(format!("null_count_{i}")));
+        if is_orderable(dtype) {
+            exprs.push(column.clone().min().alias(format!("min_{i}")));
+            exprs.push(column.clone().max().alias(format!("max_{i}")));
+        }
+        if !dtype.is_nested() {
+            exprs.push(column.n_unique().alias(format!("distinct_count_{i}")));
+        }
+    }
+    let stats_df = df.select(exprs).collect()?;
+
+    let mut stats = vec![];
+    for (i, (name, _)) in schema.iter().enumerate() {
+        stats.push(ColumnStats {
+            name: name.to_string(),
+            min: stat_val(&stats_df, &format!("min_{i}")).and_then(any_val_to_stat),
+            max: stat_val(&stats_df, &format!("max_{i}")).and_then(any_val_to_stat),
+            null_count: stat_val(&stats_df, &format!("null_count_{i}"))
+                .and_then(|v| v.extract::<usize>())
+                .unwrap_or_default(),
+            distinct_count: stat_val(&stats_df, &format!("distinct_count_{i}"))
+                .and_then(|v| v.extract::<usize>()),
+        });
+    }
+    Ok(stats)
+}
+
+fn stat_val<'a>(df: &'a DataFrame, name: &str) -> Option<AnyValue<'a>> {
+    df.column(name).ok().and_then(|c| c.get(0).ok())
+}
+
+fn is_orderable(dtype: &polars::prelude::DataType) -> bool {
+    dtype.is_numeric()
+        || dtype.is_temporal()
+        || dtype.is_bool()
+        || *dtype == polars::prelude::DataType::String
+}
+
+fn any_val_to_stat(value: AnyValue) -> Option<String> {
+    match value {
+        AnyValue::Null => None,
+        AnyValue::String(s) => Some(s.to_string()),
+        AnyValue::StringOwned(s) => Some(s.to_string()),
+        value => Some(value.to_string()),
+    }
+}
+
+fn p_lazy_df_with_extension(
+    path: &Path,
+    extension: &str,
+    opts: &DFOpts,
+) -> Result<LazyFrame, OxenError> {
     if !path.exists() {
         return Err(OxenError::entry_does_not_exist(path));
     }
@@ -853,6 +1007,10 @@ fn p_read_df_with_extension(
             }
             read_df_arrow(path)
         }
+        "geojson" => geo::read_df_geojson(path),
+        ext if sqlite::is_sqlite_extension(ext) => {
+            sqlite::read_df_sqlite(path, opts.table.as_deref())
+        }
         _ => {
             let err = format!(
                 "Could not load data frame with path: {path:?} and extension: {extension:?}"
@@ -861,12 +1019,10 @@ fn p_read_df_with_extension(
         }
     }?;
 
-    // log::debug!("Read finished");
-    if opts.has_transform() {
-        let df = transform_new(df, opts.clone())?;
-        Ok(df.collect()?)
-    } else {
-        Ok(df.collect()?)
+    // Filter on the geometry before anything else so the bbox can be pushed down into the scan
+    match &opts.bbox {
+        Some(bbox) => geo::filter_bbox(df, path, extension, &bbox.parse()?),
+        None => Ok(df),
     }
 }
 
@@ -889,7 +1045,8 @@ pub fn maybe_read_df_with_extension(
     } else {
         let commit = repositories::commits::get_by_id(repo, commit_id)?;
         if let Some(commit) = commit {
-            try_to_read_extension_from_node(repo, version_path, path, &commit, opts)
+            let plain = crate::util::encryption::plain_path(repo, version_path)?;
+            try_to_read_extension_from_node(repo, plain.path(), path, &commit, opts)
         } else {
             let err = format!("Could not find commit: {commit_id}");
             Err(OxenError::basic_str(err))
@@ -964,6 +1121,10 @@ fn p_scan_df_with_extension(
             "tsv" => scan_df_csv(path, b'\t', total_rows),
             "parquet" => scan_df_parquet(path, total_rows),
             "arrow" => scan_df_arrow(path, total_rows),
+            "geojson" => geo::read_df_geojson(path),
+            ext if sqlite::is_sqlite_extension(ext) => {
+                sqlite::read_df_sqlite(path, opts.table.as_deref())
+            }
             _ => Err(OxenError::basic_str(err)),
         },
         None => Err(OxenError::basic_str(err)),
@@ -998,6 +1159,12 @@ fn p_get_size_with_extension(
         extension
     );
 
+    // Count the default table in SQL rather than reading it into memory
+    if let Some(extension) = extension.filter(|ext| sqlite::is_sqlite_extension(ext)) {
+        let table = sqlite::resolve_table(input_path, None)?;
+        return sqlite::table_size(input_path, &table);
+    }
+
     // Don't need that many rows to get the width
     let num_scan_rows = constants::DEFAULT_PAGE_SIZE;
     let mut lazy_df = scan_df_with_extension(&path, extension, &DFOpts::empty(), num_scan_rows)?;
@@ -1041,7 +1208,7 @@ fn p_get_size_with_extension(
                 let height = reader.finish().unwrap().height();
                 Ok(DataFrameSize { width, height })
             }
-            "json" => {
+            "json" | "geojson" => {
                 let df = lazy_df
                     .collect()
                     .map_err(|_| OxenError::basic_str("Could not collect json df"))?;
@@ -1120,6 +1287,21 @@ pub fn write_df_arrow<P: AsRef<Path>>(df: &mut DataFrame, output: P) -> Result<(
     Ok(())
 }
 
+/// Serialize a data frame as an Arrow IPC stream, which keeps the column types that json loses
+pub fn df_to_arrow_stream(df: &mut DataFrame) -> Result<Vec<u8>, OxenError> {
+    let mut buffer: Vec<u8> = vec![];
+    IpcStreamWriter::new(&mut buffer)
+        .finish(df)
+        .map_err(|e| OxenError::basic_str(format!("Could not write arrow stream: {e:?}")))?;
+    Ok(buffer)
+}
+
+pub fn df_from_arrow_stream(bytes: &[u8]) -> Result<DataFrame, OxenError> {
+    IpcStreamReader::new(Cursor::new(bytes))
+        .finish()
+        .map_err(|e| OxenError::basic_str(format!("Could not read arrow stream: {e:?}")))
+}
+
 pub fn write_df(df: &mut DataFrame, path: impl AsRef<Path>) -> Result<(), OxenError> {
     let path = path.as_ref();
     let extension = path.extension().and_then(OsStr::to_str);
@@ -1266,6 +1448,18 @@ pub fn show_node(
     repo: LocalRepository,
     node: &MerkleTreeNode,
     opts: DFOpts,
+) -> Result<DataFrame, OxenError> {
+    let df = read_node(repo, node, opts)?;
+    let pretty_df = pretty_print::df_to_str(&df);
+    println!("{pretty_df}");
+    Ok(df)
+}
+
+/// Read the version of a tabular file in `node` and apply the transforms in `opts`
+pub fn read_node(
+    repo: LocalRepository,
+    node: &MerkleTreeNode,
+    opts: DFOpts,
 ) -> Result<DataFrame, OxenError> {
     let file_node = node.file()?;
     log::debug!("Opening chunked reader");
@@ -1300,6 +1494,11 @@ pub fn show_node(
                 err
             ))),
         }?
+    } else if sqlite::is_sqlite_extension(&fs::file_extension(Path::new(&file_node.name))) {
+        // SQLite needs random access to the whole database, so read the version file directly
+        let version_path = fs::plain_version_path_from_hash(&repo, file_node.hash.to_string())?;
+        log::debug!("Reading sqlite table {:?}", opts.table);
+        sqlite::read_df_sqlite(version_path.path(), opts.table.as_deref())?.collect()?
     } else {
         let chunk_reader = ChunkReader::new(repo, file_node)?;
         let json_reader = JsonLineReader::new(chunk_reader);
@@ -1316,18 +1515,11 @@ pub fn show_node(
         }?
     };
 
-    let df: PolarsResult<DataFrame> = if opts.has_transform() {
-        let df = transform(df, opts)?;
-        let pretty_df = pretty_print::df_to_str(&df);
-        println!("{pretty_df}");
-        Ok(df)
+    if opts.has_transform() {
+        transform(df, opts)
     } else {
-        let pretty_df = pretty_print::df_to_str(&df);
-        println!("{pretty_df}");
         Ok(df)
-    };
-
-    Ok(df?)
+    }
 }
 
 #[cfg(test)]
@@ -1366,6 +1558,72 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_count_and_page_filtered_sorted_df() -> Result<(), OxenError> {
+        crate::test::run_empty_dir_test(|dir| {
+            let path = dir.join("labels.csv");
+            let mut df = df!(
+                "image" => &["0.jpg", "1.jpg", "2.jpg", "3.jpg", "4.jpg"],
+                "label" => &["dog", "cat", "dog", "dog", "cat"],
+                "confidence" => &[0.5, 0.9, 0.7, 0.1, 0.3],
+            )
+            .unwrap();
+            tabular::write_df_csv(&mut df, &path, b',')?;
+
+            let mut opts = DFOpts::empty();
+            opts.filter = Some("label == dog".to_string());
+            opts.sort_by = Some("confidence".to_string());
+            opts.slice = Some("1..3".to_string());
+
+            let count = tabular::count_df_with_extension(&path, "csv", &opts)?;
+            assert_eq!(count, 3);
+
+            // The second page of size two of the dogs, by confidence
+            let page = tabular::read_df_with_extension(&path, "csv", &opts)?;
+            assert_eq!(page.height(), 2);
+            let images: Vec<&str> = page
+                .column("image")
+                .unwrap()
+                .str()
+                .unwrap()
+                .into_no_null_iter()
+                .collect();
+            assert_eq!(images, vec!["0.jpg", "2.jpg"]);
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_get_column_stats() -> Result<(), OxenError> {
+        crate::test::run_empty_dir_test(|dir| {
+            let path = dir.join("labels.csv");
+            let mut df = df!(
+                "image" => &["0.jpg", "1.jpg", "2.jpg", "3.jpg"],
+                "label" => &[Some("dog"), None, Some("dog"), Some("cat")],
+                "confidence" => &[0.5, 0.9, 0.7, 0.1],
+            )
+            .unwrap();
+            tabular::write_df_csv(&mut df, &path, b',')?;
+
+            let stats = tabular::get_column_stats_with_extension(&path, "csv")?;
+            assert_eq!(stats.len(), 3);
+
+            let label = &stats[1];
+            assert_eq!(label.name, "label");
+            assert_eq!(label.null_count, 1);
+            // The null counts as a distinct value
+            assert_eq!(label.distinct_count, Some(3));
+            assert_eq!(label.min, Some("cat".to_string()));
+            assert_eq!(label.max, Some("dog".to_string()));
+
+            let confidence = &stats[2];
+            assert_eq!(confidence.null_count, 0);
+            assert_eq!(confidence.min, Some("0.1".to_string()));
+            assert_eq!(confidence.max, Some("0.9".to_string()));
+            Ok(())
+        })
+    }
+
     #[test]
     fn test_filter_multiple_or_expr() -> Result<(), OxenError> {
         let query = Some("label == dog || label == cat".to_string());
@@ -1623,4 +1881,17 @@ mod tests {
         assert_eq!(df.height(), 100);
         Ok(())
     }
+
+    #[test]
+    fn test_arrow_stream_round_trip_keeps_types() -> Result<(), OxenError> {
+        let mut opts = DFOpts::empty();
+        opts.slice = Some("0..10".to_string());
+        let mut df = tabular::read_df("data/test/parquet/wiki_1k.parquet", opts)?;
+
+        let bytes = tabular::df_to_arrow_stream(&mut df)?;
+        let read = tabular::df_from_arrow_stream(&bytes)?;
+        assert_eq!(read.schema(), df.schema());
+        assert!(read.equals_missing(&df));
+        Ok(())
+    }
 }
@@ -0,0 +1,208 @@
+//! # Events
+//!
+//! Structured progress and warnings for push, pull, fetch, commit and migrations.
+//!
+//! By default liboxen prints to the terminal and draws progress bars. Applications
+//! embedding liboxen, like the server or a GUI, can set an [`EventSink`] instead. While a
+//! sink is set the messages go to the sink rather than stdout and the progress bars are
+//! hidden.
+//!
+//! ```ignore
+//! events::set_sink(Some(Arc::new(|event: &Event| {
+//!     log::info!("{}", serde_json::to_string(event).unwrap());
+//! })));
+//! ```
+//!
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+
+use crate::core::transfer::TransferProgress;
+
+lazy_static! {
+    static ref SINK: RwLock<Option<Arc<dyn EventSink>>> = RwLock::new(None);
+}
+
+/// Receives the events of every operation in the process
+pub trait EventSink: Send + Sync {
+    fn on_event(&self, event: &Event);
+}
+
+impl<F> EventSink for F
+where
+    F: Fn(&Event) + Send + Sync,
+{
+    fn on_event(&self, event: &Event) {
+        self(event)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    Push,
+    Pull,
+    Fetch,
+    Commit,
+    Migrate,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EventLevel {
+    Info,
+    Warning,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Event {
+    Started {
+        operation: Operation,
+        /// What the operation is working on, ex: "origin main" or a repo path
+        detail: String,
+    },
+    Message {
+        operation: Operation,
+        level: EventLevel,
+        message: String,
+    },
+    Transfer(TransferProgress),
+    MigrationStep {
+        name: String,
+        num_done: u64,
+        total: u64,
+    },
+    Finished {
+        operation: Operation,
+        detail: String,
+        duration_ms: u64,
+    },
+}
+
+/// Send events to the sink instead of the terminal. Pass None to go back to printing.
+pub fn set_sink(sink: Option<Arc<dyn EventSink>>) {
+    *SINK.write().unwrap() = sink;
+}
+
+pub fn has_sink() -> bool {
+    SINK.read().unwrap().is_some()
+}
+
+/// Send the event to the sink, if there is one
+pub fn emit(event: Event) {
+    let sink = SINK.read().unwrap().clone();
+    if let Some(sink) = sink {
+        sink.on_event(&event);
+    }
+}
+
+/// Print the message, or send it to the sink if there is one
+pub fn info(operation: Operation, message: impl Into<String>) {
+    message_with_level(operation, EventLevel::Info, message.into());
+}
+
+/// Print the message to stderr, or send it to the sink if there is one
+pub fn warn(operation: Operation, message: impl Into<String>) {
+    message_with_level(operation, EventLevel::Warning, message.into());
+}
+
+fn message_with_level(operation: Operation, level: EventLevel, message: String) {
+    if !has_sink() {
+        match level {
+            EventLevel::Info => println!("{message}"),
+            EventLevel::Warning => eprintln!("{message}"),
+        }
+        return;
+    }
+    emit(Event::Message {
+        operation,
+        level,
+        message,
+    });
+}
+
+/// Emits Started when created and Finished with the elapsed time when `finish` is called
+pub struct OperationTimer {
+    operation: Operation,
+    detail: String,
+    start: Instant,
+}
+
+impl OperationTimer {
+    pub fn start(operation: Operation, detail: impl Into<String>) -> OperationTimer {
+        let detail = detail.into();
+        emit(Event::Started {
+            operation,
+            detail: detail.clone(),
+        });
+        OperationTimer {
+            operation,
+            detail,
+            start: Instant::now(),
+        }
+    }
+
+    pub fn finish(self) {
+        emit(Event::Finished {
+            operation: self.operation,
+            detail: self.detail,
+            duration_ms: self.start.elapsed().as_millis() as u64,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::core::events::{self, Event, EventLevel, Operation, OperationTimer};
+
+    #[test]
+    fn test_sink_receives_events() {
+        let received: Arc<Mutex<Vec<Event>>> = Arc::new(Mutex::new(vec![]));
+        let sink_received = received.clone();
+        events::set_sink(Some(Arc::new(move |event: &Event| {
+            sink_received.lock().unwrap().push(event.clone());
+        })));
+
+        let timer = OperationTimer::start(Operation::Push, "origin main");
+        events::warn(Operation::Push, "remote is behind");
+        timer.finish();
+        events::set_sink(None);
+
+        let received = received.lock().unwrap();
+        // Other tests may emit while the sink is set, only look at this one's events
+        let pushes: Vec<&Event> = received
+            .iter()
+            .filter(|event| {
+                matches!(
+                    event,
+                    Event::Started {
+                        operation: Operation::Push,
+                        ..
+                    } | Event::Message {
+                        operation: Operation::Push,
+                        ..
+                    } | Event::Finished {
+                        operation: Operation::Push,
+                        ..
+                    }
+                )
+            })
+            .collect();
+        assert_eq!(pushes.len(), 3);
+        assert_eq!(
+            pushes[1],
+            &Event::Message {
+                operation: Operation::Push,
+                level: EventLevel::Warning,
+                message: "remote is behind".to_string(),
+            }
+        );
+        assert!(matches!(pushes[2], Event::Finished { detail, .. } if detail == "origin main"));
+        assert!(!events::has_sink());
+    }
+}
@@ -1,19 +1,143 @@
+//! # Oxen Ignore
+//!
+//! Gitignore style rules for which paths in the working dir oxen skips.
+//!
+//! Any directory can have an .oxenignore. Its patterns are relative to the directory it
+//! is in, and the deepest file with a matching pattern wins, so a nested file can
+//! re-include with `!pattern` what a parent excluded. Like git, a file cannot be
+//! re-included if a directory above it is ignored.
+//!
+
 use ignore::gitignore::Gitignore;
+use ignore::Match;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 use crate::constants;
 use crate::model::LocalRepository;
+use crate::util;
+
+pub struct OxenIgnore {
+    root: PathBuf,
+    // Parsed .oxenignore files keyed by the directory they are in, loaded as needed
+    files: Mutex<HashMap<PathBuf, Option<Arc<Gitignore>>>>,
+}
+
+/// Create the ignore rules for the repository
+pub fn create(repo: &LocalRepository) -> OxenIgnore {
+    OxenIgnore {
+        root: repo.path.to_owned(),
+        files: Mutex::new(HashMap::new()),
+    }
+}
+
+impl OxenIgnore {
+    /// Check if a path is ignored. The path can be absolute or relative to the repository root.
+    pub fn is_ignored(&self, path: impl AsRef<Path>, is_dir: bool) -> bool {
+        let path = path.as_ref();
+        let relative_path = if path.is_absolute() || path.starts_with(&self.root) {
+            match util::fs::path_relative_to_dir(path, &self.root) {
+                Ok(relative_path) => relative_path,
+                Err(_) => return false,
+            }
+        } else {
+            path.to_path_buf()
+        };
 
-/// Create will load the .oxenignore if it exists. If it does not exist, it will return None.
-pub fn create(repo: &LocalRepository) -> Option<Gitignore> {
-    let path = repo.path.join(constants::OXEN_IGNORE_FILE);
-    match Gitignore::new(path) {
-        (gitignore, None) => {
-            // log::debug!("loaded .oxenignore file from {}", path.display());
-            Some(gitignore)
+        if relative_path.starts_with(constants::OXEN_HIDDEN_DIR) {
+            return true;
         }
-        (_, Some(err)) => {
-            log::debug!("Could not open .oxenignore file. Reason: {}", err);
-            None
+
+        // An ignored directory ignores everything below it
+        let mut dirs: Vec<&Path> = relative_path
+            .ancestors()
+            .skip(1)
+            .filter(|dir| *dir != Path::new(""))
+            .collect();
+        dirs.reverse();
+        if dirs.into_iter().any(|dir| self.matches(dir, true)) {
+            return true;
+        }
+        relative_path != Path::new("") && self.matches(&relative_path, is_dir)
+    }
+
+    fn matches(&self, relative_path: &Path, is_dir: bool) -> bool {
+        let full_path = self.root.join(relative_path);
+        // Deeper ignore files take precedence over the ones above them
+        for dir in relative_path.ancestors().skip(1) {
+            let Some(gitignore) = self.load(dir) else {
+                continue;
+            };
+            match gitignore.matched(&full_path, is_dir) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {}
+            }
         }
+        false
+    }
+
+    fn load(&self, dir: &Path) -> Option<Arc<Gitignore>> {
+        let mut files = self.files.lock().unwrap();
+        files
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| {
+                let path = self.root.join(dir).join(constants::OXEN_IGNORE_FILE);
+                if !path.exists() {
+                    return None;
+                }
+                let (gitignore, err) = Gitignore::new(&path);
+                if let Some(err) = err {
+                    log::warn!("Could not parse all of {:?}: {}", path, err);
+                }
+                Some(Arc::new(gitignore))
+            })
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::core::oxenignore;
+    use crate::error::OxenError;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_oxenignore_precedence() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            util::fs::write_to_path(
+                repo.path.join(".oxenignore"),
+                "*.log\n!keep.log\nbuild/\ntmp\n",
+            )?;
+            util::fs::create_dir_all(repo.path.join("data").join("raw"))?;
+            util::fs::write_to_path(repo.path.join("data").join(".oxenignore"), "!*.log\nraw/\n")?;
+            util::fs::create_dir_all(repo.path.join("build"))?;
+            util::fs::write_to_path(repo.path.join("build").join(".oxenignore"), "!*.bin\n")?;
+
+            let ignore = oxenignore::create(&repo);
+            // Negation in the same file
+            assert!(ignore.is_ignored("debug.log", false));
+            assert!(!ignore.is_ignored("keep.log", false));
+            // A nested file re-includes what the root excluded
+            assert!(!ignore.is_ignored(Path::new("data").join("debug.log"), false));
+            // Directory patterns only match directories, and everything below them
+            assert!(ignore.is_ignored(Path::new("data").join("raw"), true));
+            assert!(!ignore.is_ignored(Path::new("data").join("raw"), false));
+            assert!(ignore.is_ignored(Path::new("data").join("raw").join("image.png"), false));
+            // Can't re-include a file in an ignored directory
+            assert!(ignore.is_ignored(Path::new("build").join("model.bin"), false));
+            // Patterns without a slash match at any depth
+            assert!(ignore.is_ignored(Path::new("data").join("tmp"), true));
+            // Absolute paths and the hidden dir
+            assert!(ignore.is_ignored(repo.path.join("debug.log"), false));
+            assert!(ignore.is_ignored(".oxen", true));
+            assert!(!ignore.is_ignored("README.md", false));
+
+            Ok(())
+        })
     }
 }
@@ -1,5 +1,6 @@
 pub mod ref_reader;
 pub mod ref_writer;
+pub mod remote_refs;
 
 pub use ref_reader::RefReader;
 pub use ref_writer::RefWriter;
@@ -0,0 +1,72 @@
+//! Remote-tracking refs, the head commit of each remote branch as of the last fetch.
+//! Kept apart from the local refs so they never show up as local branches, and so
+//! fetching never moves a local branch or touches the working dir.
+
+use crate::constants::REMOTE_REFS_DIR;
+use crate::core::db;
+use crate::core::db::key_val::str_json_db;
+use crate::error::OxenError;
+use crate::model::{Branch, LocalRepository, RemoteBranch};
+use crate::util;
+
+use rocksdb::DB;
+
+fn open_db(repo: &LocalRepository) -> Result<DB, OxenError> {
+    let db_path = util::fs::oxen_hidden_dir(&repo.path).join(REMOTE_REFS_DIR);
+    let opts = db::key_val::opts::default();
+    Ok(DB::open(&opts, dunce::simplified(&db_path))?)
+}
+
+fn key(remote_branch: &RemoteBranch) -> String {
+    format!("{}/{}", remote_branch.remote, remote_branch.branch)
+}
+
+/// Whether `name` looks like `<remote>/<branch>` for one of the repository's remotes
+pub fn is_remote_ref(repo: &LocalRepository, name: &str) -> bool {
+    repo.remotes().iter().any(|remote| {
+        name.strip_prefix(&remote.name)
+            .is_some_and(|rest| rest.len() > 1 && rest.starts_with('/'))
+    })
+}
+
+/// The commit id of a remote-tracking ref by name, ex: `origin/main`
+pub fn get(repo: &LocalRepository, name: impl AsRef<str>) -> Result<Option<String>, OxenError> {
+    let name = name.as_ref();
+    let db_path = util::fs::oxen_hidden_dir(&repo.path).join(REMOTE_REFS_DIR);
+    if !is_remote_ref(repo, name) || !db_path.exists() {
+        return Ok(None);
+    }
+    let db = open_db(repo)?;
+    str_json_db::get(&db, name)
+}
+
+/// Point the remote-tracking ref of the branch at a commit
+pub fn set(
+    repo: &LocalRepository,
+    remote_branch: &RemoteBranch,
+    commit_id: impl AsRef<str>,
+) -> Result<(), OxenError> {
+    let db = open_db(repo)?;
+    str_json_db::put(&db, key(remote_branch), &commit_id.as_ref().to_string())
+}
+
+/// Drop the remote-tracking ref once the branch is gone from the remote
+pub fn delete(repo: &LocalRepository, remote_branch: &RemoteBranch) -> Result<(), OxenError> {
+    let db = open_db(repo)?;
+    str_json_db::delete(&db, key(remote_branch))
+}
+
+/// The remote-tracking refs, named `<remote>/<branch>`, optionally only those of one remote
+pub fn list(repo: &LocalRepository, remote: Option<&str>) -> Result<Vec<Branch>, OxenError> {
+    let db_path = util::fs::oxen_hidden_dir(&repo.path).join(REMOTE_REFS_DIR);
+    if !db_path.exists() {
+        return Ok(vec![]);
+    }
+    let db = open_db(repo)?;
+    let refs: Vec<(String, String)> = str_json_db::list(&db)?;
+    Ok(refs
+        .into_iter()
+        .filter(|(name, _)| remote.is_none_or(|remote| name.starts_with(&format!("{remote}/"))))
+        .map(|(name, commit_id)| Branch { name, commit_id })
+        .collect())
+}
@@ -0,0 +1,187 @@
+//! # Transfer Settings
+//!
+//! Process wide settings for push, pull and clone: a bandwidth limit, and a callback
+//! that receives structured progress so applications embedding liboxen can render
+//! their own progress instead of the terminal progress bars.
+//!
+//! ```ignore
+//! transfer::set_limit_rate(Some(transfer::parse_rate("10M")?));
+//! transfer::set_progress_callback(Some(Arc::new(|progress: &TransferProgress| {
+//!     println!("{} bytes at {:.0} B/s", progress.num_bytes, progress.bytes_per_sec);
+//! })));
+//! ```
+//!
+
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::core::events::{self, Event};
+use crate::error::OxenError;
+
+pub type ProgressCallback = Arc<dyn Fn(&TransferProgress) + Send + Sync>;
+
+lazy_static! {
+    static ref PROGRESS_CALLBACK: RwLock<Option<ProgressCallback>> = RwLock::new(None);
+    static ref LIMIT_RATE: RwLock<Option<u64>> = RwLock::new(None);
+    // When the bandwidth budget is free again, shared by every transfer in the process
+    static ref NEXT_FREE: Mutex<Option<Instant>> = Mutex::new(None);
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FileTransferState {
+    Started,
+    Finished,
+    Failed,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct FileProgress {
+    pub path: PathBuf,
+    pub state: FileTransferState,
+}
+
+/// A snapshot of a push or pull, passed to the progress callback
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TransferProgress {
+    /// "push" or "pull"
+    pub sync_type: String,
+    pub num_bytes: u64,
+    pub total_bytes: Option<u64>,
+    pub num_files: u64,
+    pub total_files: Option<u64>,
+    pub bytes_per_sec: f64,
+    pub eta: Option<Duration>,
+    /// Set when a single file changed state, None for byte and file count updates
+    pub file: Option<FileProgress>,
+}
+
+impl TransferProgress {
+    pub fn new(
+        sync_type: impl AsRef<str>,
+        num_bytes: u64,
+        total_bytes: Option<u64>,
+        elapsed: Duration,
+    ) -> TransferProgress {
+        let secs = elapsed.as_secs_f64();
+        let bytes_per_sec = if secs > 0.0 {
+            num_bytes as f64 / secs
+        } else {
+            0.0
+        };
+        let eta = match total_bytes {
+            Some(total) if bytes_per_sec > 0.0 => Some(Duration::from_secs_f64(
+                total.saturating_sub(num_bytes) as f64 / bytes_per_sec,
+            )),
+            _ => None,
+        };
+        TransferProgress {
+            sync_type: sync_type.as_ref().to_string(),
+            num_bytes,
+            total_bytes,
+            num_files: 0,
+            total_files: None,
+            bytes_per_sec,
+            eta,
+            file: None,
+        }
+    }
+}
+
+/// Receive progress for every push, pull and clone. Pass None to stop.
+pub fn set_progress_callback(callback: Option<ProgressCallback>) {
+    *PROGRESS_CALLBACK.write().unwrap() = callback;
+}
+
+pub fn report_progress(progress: &TransferProgress) {
+    let callback = PROGRESS_CALLBACK.read().unwrap().clone();
+    if let Some(callback) = callback {
+        callback(progress);
+    }
+    if events::has_sink() {
+        events::emit(Event::Transfer(progress.clone()));
+    }
+}
+
+/// Limit the combined upload and download rate, in bytes per second. Pass None for no limit.
+pub fn set_limit_rate(bytes_per_sec: Option<u64>) {
+    *LIMIT_RATE.write().unwrap() = bytes_per_sec.filter(|rate| *rate > 0);
+    *NEXT_FREE.lock().unwrap() = None;
+}
+
+pub fn limit_rate() -> Option<u64> {
+    *LIMIT_RATE.read().unwrap()
+}
+
+/// Wait until `num_bytes` fit in the rate limit. Returns right away without a limit.
+pub async fn throttle(num_bytes: u64) {
+    let Some(rate) = limit_rate() else {
+        return;
+    };
+    let delay = {
+        let mut next_free = NEXT_FREE.lock().unwrap();
+        let now = Instant::now();
+        let start = next_free.map_or(now, |next| next.max(now));
+        *next_free = Some(start + Duration::from_secs_f64(num_bytes as f64 / rate as f64));
+        start - now
+    };
+    if !delay.is_zero() {
+        log::debug!("throttle waiting {:?} to send {} bytes", delay, num_bytes);
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Parse a rate like `500K`, `10M` or `1G` into bytes per second. Like curl, the
+/// suffixes are powers of 1024 and a plain number is bytes.
+pub fn parse_rate(rate: impl AsRef<str>) -> Result<u64, OxenError> {
+    let rate = rate.as_ref().trim();
+    let (number, multiplier) = match rate.chars().last().map(|c| c.to_ascii_uppercase()) {
+        Some('K') => (&rate[..rate.len() - 1], 1024),
+        Some('M') => (&rate[..rate.len() - 1], 1024 * 1024),
+        Some('G') => (&rate[..rate.len() - 1], 1024 * 1024 * 1024),
+        _ => (rate, 1),
+    };
+    match number.trim().parse::<f64>() {
+        Ok(number) if number > 0.0 => Ok((number * multiplier as f64) as u64),
+        _ => Err(OxenError::basic_str(format!(
+            "Invalid rate {rate:?}, expected a number of bytes with an optional K, M or G suffix"
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::core::transfer::{parse_rate, TransferProgress};
+    use crate::error::OxenError;
+
+    #[test]
+    fn test_parse_rate() -> Result<(), OxenError> {
+        assert_eq!(parse_rate("2048")?, 2048);
+        assert_eq!(parse_rate("500k")?, 500 * 1024);
+        assert_eq!(parse_rate("10M")?, 10 * 1024 * 1024);
+        assert_eq!(parse_rate("1.5G")?, 3 * 512 * 1024 * 1024);
+        assert!(parse_rate("fast").is_err());
+        assert!(parse_rate("0").is_err());
+        assert!(parse_rate("").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_transfer_progress_rate_and_eta() {
+        let progress = TransferProgress::new("push", 1000, Some(3000), Duration::from_secs(2));
+        assert_eq!(progress.bytes_per_sec, 500.0);
+        assert_eq!(progress.eta, Some(Duration::from_secs(4)));
+
+        let progress = TransferProgress::new("pull", 1000, None, Duration::from_secs(2));
+        assert_eq!(progress.eta, None);
+
+        let progress = TransferProgress::new("pull", 0, Some(10), Duration::ZERO);
+        assert_eq!(progress.bytes_per_sec, 0.0);
+        assert_eq!(progress.eta, None);
+    }
+}
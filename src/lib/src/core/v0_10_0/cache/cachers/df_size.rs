@@ -55,7 +55,7 @@ pub fn get_cache_for_version(
     match get_from_cache(repo, commit, version_path) {
         Ok(result) => match result {
             Some(size) => Ok(size),
-            None => tabular::get_size(version_path),
+            None => tabular::get_size(util::encryption::plain_path(repo, version_path)?.path()),
         },
         Err(e) => Err(e),
     }
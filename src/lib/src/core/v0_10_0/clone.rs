@@ -8,6 +8,7 @@ use crate::opts::{CloneOpts, PullOpts};
 use crate::util::progress_bar::{oxen_progress_bar, ProgressBarType};
 use crate::{api, util};
 
+use std::collections::BTreeMap;
 use std::path::Path;
 
 pub async fn clone_repo(
@@ -42,6 +43,12 @@ pub async fn clone_repo(
         remotes: vec![remote_repo.remote.clone()],
         min_version: Some(remote_repo.min_version().to_string()),
         vnode_size: None,
+        perceptual_hash: None,
+        content_addressed_tree: None,
+        checkout_mode: None,
+        encryption_key_id: None,
+        compression_level: None,
+        branch: BTreeMap::new(),
     };
 
     let toml = toml::to_string(&remote_cfg)?;
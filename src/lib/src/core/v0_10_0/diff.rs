@@ -681,7 +681,10 @@ fn subset_dir_diffs_to_direct_children(
     for entry in entries {
         let status = DiffEntryStatus::from_str(&entry.status)?;
         let relevant_entry = match status {
-            DiffEntryStatus::Added | DiffEntryStatus::Modified => entry.head_entry.as_ref(),
+            DiffEntryStatus::Added
+            | DiffEntryStatus::Modified
+            | DiffEntryStatus::Renamed
+            | DiffEntryStatus::Copied => entry.head_entry.as_ref(),
             DiffEntryStatus::Removed => entry.base_entry.as_ref(),
         };
 
@@ -706,7 +709,10 @@ fn subset_file_diffs_to_direct_children(
 
     for entry in entries {
         let relevant_entry = match entry.status {
-            DiffEntryStatus::Added | DiffEntryStatus::Modified => entry.head_entry.as_ref(),
+            DiffEntryStatus::Added
+            | DiffEntryStatus::Modified
+            | DiffEntryStatus::Renamed
+            | DiffEntryStatus::Copied => entry.head_entry.as_ref(),
             DiffEntryStatus::Removed => entry.base_entry.as_ref(),
         };
 
@@ -63,6 +63,8 @@ pub fn get_directory(
         last_modified_nanoseconds: entry.last_modified_nanoseconds,
         data_type_counts: HashMap::new(),
         data_type_sizes: HashMap::new(),
+        image_resolution_counts: HashMap::new(),
+        data_type_durations: HashMap::new(),
     };
     Ok(Some(node))
 }
@@ -772,13 +772,13 @@ impl CommitEntryWriter {
             let parent = path.parent().unwrap_or(Path::new("")).to_path_buf();
             // Backup the schema to the versions dir as a part of the migration
             versioner::backup_schema(&self.repository, &schema)?;
-            let schema_with_path = SchemaWithPath {
-                path: PathBuf::from(SCHEMAS_TREE_PREFIX)
+            let schema_with_path = SchemaWithPath::new(
+                PathBuf::from(SCHEMAS_TREE_PREFIX)
                     .join(path.clone())
                     .to_string_lossy()
                     .to_string(),
                 schema,
-            };
+            );
             schema_map.entry(parent).or_default().push(schema_with_path);
         }
 
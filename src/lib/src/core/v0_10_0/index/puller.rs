@@ -217,6 +217,7 @@ async fn pull_large_entries(
                 let remote_path = &entry.path();
 
                 // Download to the tmp path, then copy over to the entries dir
+                progress_bar.file_started(remote_path);
                 match api::client::entries::download_large_entry(
                     &remote_repo,
                     &remote_path,
@@ -230,9 +231,11 @@ async fn pull_large_entries(
                         // log::debug!("Downloaded large entry {:?} to versions dir", remote_path);
                         progress_bar.add_bytes(entry.num_bytes());
                         progress_bar.add_files(1);
+                        progress_bar.file_finished(remote_path);
                     }
                     Err(err) => {
-                        log::error!("Could not download chunk... {}", err)
+                        log::error!("Could not download chunk... {}", err);
+                        progress_bar.file_failed(remote_path);
                     }
                 }
 
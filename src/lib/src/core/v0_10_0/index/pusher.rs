@@ -15,16 +15,18 @@ use futures::prelude::*;
 use indicatif::ProgressBar;
 use std::collections::{HashSet, VecDeque};
 
-use std::io::{BufReader, Read};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
+use tokio::sync::Semaphore;
 use tokio::time::Duration;
 
-use crate::constants::{self, AVG_CHUNK_SIZE, NUM_HTTP_RETRIES};
+use crate::constants::{AVG_CHUNK_SIZE, NUM_HTTP_RETRIES};
 
 use crate::core::v0_10_0::index::{CommitReader, Merger};
 use crate::error::OxenError;
 use crate::model::{Branch, Commit, LocalRepository, RemoteBranch, RemoteRepository};
+use crate::opts::PushOpts;
 
 use crate::core::v0_19_0::structs::push_progress::PushProgress;
 use crate::{api, util};
@@ -752,12 +754,25 @@ pub async fn push_entries(
     entries: &[Entry],
     commit: &Commit,
     progress: &Arc<PushProgress>,
+) -> Result<(), OxenError> {
+    let opts = PushOpts::from_env();
+    push_entries_with_opts(local_repo, remote_repo, entries, commit, &opts, progress).await
+}
+
+pub async fn push_entries_with_opts(
+    local_repo: &LocalRepository,
+    remote_repo: &RemoteRepository,
+    entries: &[Entry],
+    commit: &Commit,
+    opts: &PushOpts,
+    progress: &Arc<PushProgress>,
 ) -> Result<(), OxenError> {
     log::debug!(
-        "PUSH ENTRIES {} -> {} -> '{}'",
+        "PUSH ENTRIES {} -> {} -> '{}' with {:?}",
         entries.len(),
         commit.id,
-        commit.message
+        commit.message,
+        opts
     );
     // Some files may be much larger than others....so we can't just zip them up and send them
     // since bodies will be too big. Hence we chunk and send the big ones, and bundle and send the small ones
@@ -772,18 +787,12 @@ pub async fn push_entries(
     // For files larger than AVG_CHUNK_SIZE, we are going break them into chunks and send the chunks in parallel
     let larger_entries: Vec<Entry> = entries
         .iter()
-        .filter(|e| e.num_bytes() > AVG_CHUNK_SIZE)
+        .filter(|e| e.num_bytes() >= AVG_CHUNK_SIZE)
         .map(|e| e.to_owned())
         .collect();
 
-    let large_entries_sync = chunk_and_send_large_entries(
-        local_repo,
-        remote_repo,
-        larger_entries,
-        commit,
-        AVG_CHUNK_SIZE,
-        progress,
-    );
+    let large_entries_sync =
+        chunk_and_send_large_entries(local_repo, remote_repo, larger_entries, opts, progress);
     let small_entries_sync = bundle_and_send_small_entries(
         local_repo,
         remote_repo,
@@ -810,268 +819,194 @@ pub async fn push_entries(
     }
 }
 
+// How long we would like each chunk upload to take. Long enough that request overhead
+// is small, short enough that a retry does not throw away much work.
+const TARGET_CHUNK_SECS: f64 = 2.0;
+
+/// Picks the chunk size for the next large file from how fast recent chunks uploaded
+struct ChunkSizer {
+    chunk_size: AtomicU64,
+    min_chunk_size: u64,
+    max_chunk_size: u64,
+}
+
+impl ChunkSizer {
+    fn new(opts: &PushOpts) -> ChunkSizer {
+        ChunkSizer {
+            chunk_size: AtomicU64::new(
+                AVG_CHUNK_SIZE.clamp(opts.min_chunk_size, opts.max_chunk_size),
+            ),
+            min_chunk_size: opts.min_chunk_size,
+            max_chunk_size: opts.max_chunk_size,
+        }
+    }
+
+    fn chunk_size(&self) -> u64 {
+        self.chunk_size.load(Ordering::Relaxed)
+    }
+
+    /// Move halfway towards the size that would have taken TARGET_CHUNK_SECS to upload
+    fn record_success(&self, num_bytes: u64, elapsed: Duration) {
+        let secs = elapsed.as_secs_f64().max(0.001);
+        let ideal = (num_bytes as f64 / secs * TARGET_CHUNK_SECS) as u64;
+        let current = self.chunk_size();
+        let next = ((current + ideal) / 2).clamp(self.min_chunk_size, self.max_chunk_size);
+        self.chunk_size.store(next, Ordering::Relaxed);
+    }
+
+    /// Failures are often timeouts on slow links, so back off to smaller chunks
+    fn record_failure(&self) {
+        let next = (self.chunk_size() / 2).max(self.min_chunk_size);
+        self.chunk_size.store(next, Ordering::Relaxed);
+    }
+}
+
 async fn chunk_and_send_large_entries(
     local_repo: &LocalRepository,
     remote_repo: &RemoteRepository,
     entries: Vec<Entry>,
-    commit: &Commit,
-    chunk_size: u64,
+    opts: &PushOpts,
     progress: &Arc<PushProgress>,
 ) -> Result<(), OxenError> {
     if entries.is_empty() {
         return Ok(());
     }
 
-    use tokio::time::sleep;
-    type PieceOfWork = (Entry, LocalRepository, Commit, RemoteRepository);
-    type TaskQueue = deadqueue::limited::Queue<PieceOfWork>;
-    type FinishedTaskQueue = deadqueue::limited::Queue<bool>;
-
-    log::debug!("Chunking and sending {} larger files", entries.len());
-    let entries: Vec<PieceOfWork> = entries
-        .iter()
-        .map(|e| {
-            (
-                e.to_owned(),
-                local_repo.to_owned(),
-                commit.to_owned(),
-                remote_repo.to_owned(),
-            )
-        })
-        .collect();
-
-    let queue = Arc::new(TaskQueue::new(entries.len()));
-    let finished_queue = Arc::new(FinishedTaskQueue::new(entries.len()));
-    for entry in entries.iter() {
-        queue.try_push(entry.to_owned()).unwrap();
-        finished_queue.try_push(false).unwrap();
-    }
-
-    let worker_count = concurrency::num_threads_for_items(entries.len());
     log::debug!(
-        "worker_count {} entries len {}",
-        worker_count,
-        entries.len()
+        "Chunking and sending {} larger files with {} workers",
+        entries.len(),
+        opts.num_workers
     );
-    for worker in 0..worker_count {
-        let queue = queue.clone();
-        let finished_queue = finished_queue.clone();
-        let bar = Arc::clone(progress);
-        tokio::spawn(async move {
-            loop {
-                let (entry, repo, commit, remote_repo) = queue.pop().await;
-                log::debug!("worker[{}] processing task...", worker);
 
-                upload_large_file_chunks(entry, repo, commit, remote_repo, chunk_size, &bar).await;
-
-                finished_queue.pop().await;
-            }
-        });
-    }
+    // Every chunk of every file shares the same pool, so we keep the link busy when there
+    // are a few huge files or many medium sized ones
+    let sizer = ChunkSizer::new(opts);
+    let permits = Semaphore::new(opts.num_workers);
+    let results: Vec<Result<(), OxenError>> = stream::iter(entries.iter())
+        .map(|entry| {
+            upload_large_file_chunks(
+                local_repo,
+                remote_repo,
+                entry,
+                opts,
+                &sizer,
+                &permits,
+                progress,
+            )
+        })
+        .buffer_unordered(opts.num_workers)
+        .collect()
+        .await;
 
-    while finished_queue.len() > 0 {
-        // log::debug!("Before waiting for {} workers to finish...", queue.len());
-        sleep(Duration::from_secs(1)).await;
+    let errors: Vec<OxenError> = results.into_iter().filter_map(|r| r.err()).collect();
+    if let Some(err) = errors.into_iter().next() {
+        return Err(err);
     }
     log::debug!("All large file tasks done. :-)");
-
-    // Sleep again to let things sync...
-    sleep(Duration::from_millis(100)).await;
-
     Ok(())
 }
 
 /// Chunk and send large file in parallel
 async fn upload_large_file_chunks(
-    entry: Entry,
-    repo: LocalRepository,
-    commit: Commit,
-    remote_repo: RemoteRepository,
-    chunk_size: u64,
+    repo: &LocalRepository,
+    remote_repo: &RemoteRepository,
+    entry: &Entry,
+    opts: &PushOpts,
+    sizer: &ChunkSizer,
+    permits: &Semaphore,
     progress: &Arc<PushProgress>,
-) {
-    // Open versioned file
-    let version_path = util::fs::version_path_for_entry(&repo, &entry);
-    let f = std::fs::File::open(&version_path).unwrap();
-    let mut reader = BufReader::new(f);
-
-    // These variables are the same for every chunk
-    // let is_compressed = false;
+) -> Result<(), OxenError> {
+    let version_path = util::fs::version_path_for_entry(repo, entry);
     let hidden_dir = util::fs::oxen_hidden_dir(&repo.path);
-    let path = util::fs::path_relative_to_dir(&version_path, &hidden_dir).unwrap();
-    let file_name = Some(String::from(path.to_str().unwrap()));
+    let path = util::fs::path_relative_to_dir(&version_path, &hidden_dir)?;
+    let file_name = Some(path.to_string_lossy().to_string());
+    let entry_hash = entry.hash();
 
-    // Calculate chunk sizes
+    // The server combines chunks by number, so the size is fixed for the whole file
     let total_bytes = entry.num_bytes();
-    let total_chunks = ((total_bytes / chunk_size) + 1) as usize;
-    let mut total_bytes_read = 0;
-    let mut chunk_size = chunk_size;
-
-    // Create queues for sending data to workers
-    type PieceOfWork = (
-        Vec<u8>,
-        u64,   // chunk size
-        usize, // chunk num
-        usize, // total chunks
-        u64,   // total size
-        RemoteRepository,
-        String, // entry hash
-        Commit,
-        Option<String>, // filename
-    );
-
-    // In order to upload chunks in parallel
-    // We should only read N chunks at a time so that
-    // the whole file does not get read into memory
-    let sub_chunk_size = constants::DEFAULT_NUM_WORKERS;
-
-    let mut total_chunk_idx = 0;
-    let mut processed_chunk_idx = 0;
-    let num_sub_chunks = (total_chunks / sub_chunk_size) + 1;
+    let chunk_size = sizer.chunk_size();
+    let total_chunks = total_bytes.div_ceil(chunk_size).max(1) as usize;
     log::debug!(
-        "upload_large_file_chunks {:?} proccessing file in {} subchunks of size {} from total {} chunk size {} file size {}",
+        "upload_large_file_chunks {:?} sending {} chunks of size {} file size {}",
         entry.path(),
-        num_sub_chunks,
-        sub_chunk_size,
         total_chunks,
         chunk_size,
         total_bytes
     );
-    for i in 0..num_sub_chunks {
-        log::debug!(
-            "upload_large_file_chunks Start reading subchunk {i}/{num_sub_chunks} of size {sub_chunk_size} from total {total_chunks} chunk size {chunk_size} file size {total_bytes_read}/{total_bytes}"
-        );
-        // Read and send the subset of buffers sequentially
-        let mut sub_buffers: Vec<Vec<u8>> = Vec::new();
-        for _ in 0..sub_chunk_size {
-            // If we have read all the bytes, break
-            if total_bytes_read >= total_bytes {
-                break;
-            }
-
-            // Make sure we read the last size correctly
-            if (total_bytes_read + chunk_size) > total_bytes {
-                chunk_size = total_bytes % chunk_size;
-            }
 
-            let percent_read = (total_bytes_read as f64 / total_bytes as f64) * 100.0;
-            log::debug!("upload_large_file_chunks has read {total_bytes_read}/{total_bytes} = {percent_read}% about to read {chunk_size}");
+    progress.file_started(entry.path());
+    // Upload the plain bytes of encrypted version files, the hash is of the plain file
+    let plain = util::encryption::plain_path(repo, &version_path)?;
+    let version_path = plain.path();
+    let file_name = &file_name;
+    let entry_hash = &entry_hash;
+    let results: Vec<Result<(), OxenError>> = stream::iter(0..total_chunks)
+        .map(|chunk_num| async move {
+            // Only read the chunk once it can be sent, so memory stays bounded by the pool
+            let _permit = permits
+                .acquire()
+                .await
+                .map_err(|err| OxenError::basic_str(format!("Upload pool closed: {err}")))?;
+            let buffer = util::fs::read_chunk(version_path, chunk_num, chunk_size)?;
+            let params = ChunkParams {
+                chunk_num,
+                total_chunks,
+                total_size: total_bytes as usize,
+            };
+            upload_chunk(remote_repo, &buffer, entry_hash, &params, file_name, sizer).await?;
+            progress.add_bytes(buffer.len() as u64);
+            Ok(())
+        })
+        .buffer_unordered(opts.num_workers)
+        .collect()
+        .await;
 
-            // Only read as much as you need to send so we don't blow up memory on large files
-            let mut buffer = vec![0u8; chunk_size as usize];
-            match reader.read_exact(&mut buffer) {
-                Ok(_) => {}
-                Err(err) => {
-                    log::error!("upload_large_file_chunks Error reading file {:?} chunk {total_chunk_idx}/{total_chunks} chunk size {chunk_size} total_bytes_read: {total_bytes_read} total_bytes: {total_bytes} {:?}", entry.path(), err);
-                    return;
-                }
-            }
-            total_bytes_read += chunk_size;
-            total_chunk_idx += 1;
+    if let Some(err) = results.into_iter().find_map(|r| r.err()) {
+        progress.file_failed(entry.path());
+        return Err(err);
+    }
+    progress.add_files(1);
+    progress.file_finished(entry.path());
+    Ok(())
+}
 
-            sub_buffers.push(buffer);
+// Transient failures are retried by the request itself, see api::client::retry
+async fn upload_chunk(
+    remote_repo: &RemoteRepository,
+    buffer: &[u8],
+    hash: &str,
+    params: &ChunkParams,
+    file_name: &Option<String>,
+    sizer: &ChunkSizer,
+) -> Result<(), OxenError> {
+    let is_compressed = false;
+    let start = std::time::Instant::now();
+    match api::client::commits::upload_data_chunk_to_server(
+        remote_repo,
+        buffer,
+        hash,
+        params,
+        is_compressed,
+        file_name,
+    )
+    .await
+    {
+        Ok(_) => {
+            sizer.record_success(buffer.len() as u64, start.elapsed());
+            Ok(())
         }
-        log::debug!(
-            "upload_large_file_chunks Done, have read subchunk {}/{} subchunk {}/{} of size {}",
-            processed_chunk_idx,
-            total_chunks,
-            i,
-            num_sub_chunks,
-            sub_chunk_size
-        );
-
-        // Then send sub_buffers over network in parallel
-        // let queue = Arc::new(TaskQueue::new(sub_buffers.len()));
-        // let finished_queue = Arc::new(FinishedTaskQueue::new(sub_buffers.len()));
-        let mut tasks: Vec<PieceOfWork> = Vec::new();
-        for buffer in sub_buffers.iter() {
-            tasks.push((
-                buffer.to_owned(),
-                chunk_size,
-                processed_chunk_idx, // Needs to be the overall chunk num
-                total_chunks,
-                total_bytes,
-                remote_repo.to_owned(),
-                entry.hash().to_owned(),
-                commit.to_owned(),
-                file_name.to_owned(),
-            ));
-            // finished_queue.try_push(false).unwrap();
-            processed_chunk_idx += 1;
+        Err(err) => {
+            sizer.record_failure();
+            log::error!(
+                "Error uploading chunk {}/{} of {:?}: {}",
+                params.chunk_num,
+                params.total_chunks,
+                file_name,
+                err
+            );
+            Err(err)
         }
-
-        // Setup the stream chunks in parallel
-        let bodies = stream::iter(tasks)
-            .map(|item| async move {
-                let (
-                    buffer,
-                    chunk_size,
-                    chunk_num,
-                    total_chunks,
-                    total_size,
-                    remote_repo,
-                    entry_hash,
-                    _commit,
-                    file_name,
-                ) = item;
-                let size = buffer.len() as u64;
-                log::debug!(
-                    "upload_large_file_chunks Streaming entry buffer {}/{} of size {}",
-                    chunk_num,
-                    total_chunks,
-                    size
-                );
-
-                let params = ChunkParams {
-                    chunk_num,
-                    total_chunks,
-                    total_size: total_size as usize,
-                };
-
-                let is_compressed = false;
-                match api::client::commits::upload_data_chunk_to_server_with_retry(
-                    &remote_repo,
-                    &buffer,
-                    &entry_hash,
-                    &params,
-                    is_compressed,
-                    &file_name,
-                )
-                .await
-                {
-                    Ok(_) => {
-                        log::debug!(
-                            "upload_large_file_chunks Successfully uploaded subchunk overall chunk {}/{}",
-                            chunk_num,
-                            total_chunks
-                        );
-                        Ok(chunk_size)
-                    }
-                    Err(err) => {
-                        log::error!("Error uploading chunk: {err}");
-                        Err(err)
-                    }
-                }
-            })
-            .buffer_unordered(sub_chunk_size);
-
-        // Wait for all requests to finish
-        bodies
-            .for_each(|b| async {
-                match b {
-                    Ok(_) => {
-                        progress.add_bytes(chunk_size);
-                    }
-                    Err(err) => {
-                        log::error!("Error uploading chunk: {err}")
-                    }
-                }
-            })
-            .await;
-
-        log::debug!("upload_large_file_chunks Subchunk {i}/{num_sub_chunks} tasks done. :-)");
     }
-    progress.add_files(1);
 }
 
 /// Sends entries in tarballs of size ~chunk size
@@ -1154,7 +1089,15 @@ async fn bundle_and_send_small_entries(
                     let version_path = util::fs::version_path_for_entry(&repo, entry);
                     let name = util::fs::path_relative_to_dir(&version_path, &hidden_dir).unwrap();
 
-                    match tar.append_path_with_name(version_path, name) {
+                    let plain = match util::encryption::plain_path(&repo, &version_path) {
+                        Ok(plain) => plain,
+                        Err(e) => {
+                            log::error!("Failed to decrypt file: {}", e);
+                            continue;
+                        }
+                    };
+
+                    match tar.append_path_with_name(plain.path(), name) {
                         Ok(_) => {}
                         Err(e) => {
                             log::error!("Failed to add file to archive: {}", e);
@@ -1230,9 +1173,38 @@ mod tests {
     use crate::core::versions::MinOxenVersion;
     use crate::error::OxenError;
 
+    use crate::opts::PushOpts;
     use crate::repositories;
     use crate::test;
 
+    use tokio::time::Duration;
+
+    #[test]
+    fn test_chunk_sizer_adapts_to_throughput() {
+        let opts = PushOpts {
+            num_workers: 4,
+            min_chunk_size: 1024 * 1024,
+            max_chunk_size: 1024 * 1024 * 16,
+        };
+        let sizer = pusher::ChunkSizer::new(&opts);
+        let start = sizer.chunk_size();
+        assert_eq!(start, constants::AVG_CHUNK_SIZE);
+
+        // A fast link grows the chunks, but never past the max
+        for _ in 0..10 {
+            sizer.record_success(sizer.chunk_size(), Duration::from_millis(10));
+        }
+        assert_eq!(sizer.chunk_size(), opts.max_chunk_size);
+
+        // A slow link shrinks them, and failures back off further, but never below the min
+        sizer.record_success(sizer.chunk_size(), Duration::from_secs(20));
+        assert!(sizer.chunk_size() < opts.max_chunk_size);
+        for _ in 0..10 {
+            sizer.record_failure();
+        }
+        assert_eq!(sizer.chunk_size(), opts.min_chunk_size);
+    }
+
     #[tokio::test]
     async fn test_push_missing_commit_dbs() -> Result<(), OxenError> {
         test::run_training_data_repo_test_fully_committed_async_min_version(
@@ -10,7 +10,7 @@ use crate::core::db::key_val::path_db;
 use crate::core::db::key_val::str_json_db;
 use crate::core::df::tabular;
 use crate::core::merge::entry_merge_conflict_reader::EntryMergeConflictReader;
-use crate::core::oxenignore;
+use crate::core::oxenignore::{self, OxenIgnore};
 use crate::core::v0_10_0::index::object_db_reader::get_object_reader;
 use crate::core::v0_10_0::index::ObjectDBReader;
 use crate::core::v0_10_0::index::SchemaReader;
@@ -37,7 +37,6 @@ use crate::util;
 use crate::util::progress_bar::{oxen_progress_bar, oxen_progress_bar_with_msg, ProgressBarType};
 
 use filetime::FileTime;
-use ignore::gitignore::Gitignore;
 use indicatif::ProgressBar;
 use rayon::prelude::*;
 use rocksdb::SingleThreaded;
@@ -108,15 +107,9 @@ impl Stager {
         })
     }
 
-    fn should_ignore_path(&self, ignore: &Option<Gitignore>, path: &Path) -> bool {
+    fn should_ignore_path(&self, ignore: &OxenIgnore, path: &Path) -> bool {
         // If the path is the .oxen dir or is in the ignore file, ignore it
-        let should_ignore = if let Some(ignore) = ignore {
-            ignore.matched(path, path.is_dir()).is_ignore()
-        } else {
-            false
-        };
-
-        should_ignore || util::fs::is_in_oxen_hidden_dir(path)
+        ignore.is_ignored(path, path.is_dir()) || util::fs::is_in_oxen_hidden_dir(path)
     }
 
     pub fn add(
@@ -124,7 +117,7 @@ impl Stager {
         path: &Path,
         commit_reader: &CommitEntryReader,
         schema_reader: &SchemaReader,
-        ignore: &Option<Gitignore>,
+        ignore: &OxenIgnore,
     ) -> Result<(), OxenError> {
         if self.repository.is_shallow_clone() {
             return Err(OxenError::repo_is_shallow());
@@ -408,7 +401,7 @@ impl Stager {
         &self,
         full_dir: &Path,
         staged_data: &mut StagedData,
-        ignore: &Option<Gitignore>,
+        ignore: &OxenIgnore,
         _commit_reader: &CommitEntryReader,
         object_reader: Arc<ObjectDBReader>,
     ) -> Result<(), OxenError> {
@@ -480,7 +473,7 @@ impl Stager {
         &self,
         full_dir: &Path,
         staged_data: &mut StagedData,
-        ignore: &Option<Gitignore>,
+        ignore: &OxenIgnore,
         commit_reader: &CommitEntryReader,
         object_reader: Arc<ObjectDBReader>,
         bar: Arc<ProgressBar>,
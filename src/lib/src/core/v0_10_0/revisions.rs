@@ -1,7 +1,8 @@
 use crate::error::OxenError;
 use crate::model::LocalRepository;
 use crate::util;
-use std::path::{Path, PathBuf};
+use crate::util::encryption::PlainPath;
+use std::path::Path;
 
 use super::index::{object_db_reader::get_object_reader, CommitDirEntryReader};
 
@@ -10,7 +11,7 @@ pub fn get_version_file_from_commit_id(
     repo: &LocalRepository,
     commit_id: impl AsRef<str>,
     path: impl AsRef<Path>,
-) -> Result<PathBuf, OxenError> {
+) -> Result<PlainPath, OxenError> {
     let commit_id = commit_id.as_ref();
     let path = path.as_ref();
     let parent = match path.parent() {
@@ -34,5 +35,5 @@ pub fn get_version_file_from_commit_id(
         _ => return Err(OxenError::entry_does_not_exist_in_commit(path, commit_id)),
     };
 
-    Ok(util::fs::version_path(repo, &entry))
+    util::fs::plain_version_path(repo, &entry)
 }
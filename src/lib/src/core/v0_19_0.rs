@@ -2,6 +2,7 @@
 //!
 
 pub mod add;
+pub mod batch_transfer;
 pub mod branches;
 pub mod clone;
 pub mod commits;
@@ -14,12 +15,15 @@ pub mod index;
 pub mod init;
 pub mod merge;
 pub mod metadata;
+pub mod mount;
 pub mod pull;
 pub mod push;
+pub mod quarantine;
 pub mod restore;
 pub mod revisions;
 pub mod rm;
 pub mod status;
+pub mod storage_stats;
 pub mod structs;
 pub mod workspaces;
 
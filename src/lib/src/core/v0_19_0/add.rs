@@ -1,28 +1,32 @@
 use filetime::FileTime;
-use glob::glob;
-// use jwalk::WalkDirGeneric;
 use rayon::prelude::*;
 use rocksdb::{DBWithThreadMode, MultiThreaded};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use tokio::time::Duration;
-use walkdir::WalkDir;
 
 use indicatif::{ProgressBar, ProgressStyle};
 use rmp_serde::Serializer;
 use serde::Serialize;
 
-use crate::constants::{FILES_DIR, OXEN_HIDDEN_DIR, STAGED_DIR, VERSIONS_DIR};
+use crate::constants::{
+    CACHE_DIR, FILES_DIR, ROWS_DIR, STAGED_DIR, VERSIONS_DIR, VERSION_FILE_NAME,
+};
 use crate::core::db;
+use crate::core::df::filter::DFFilterExp;
+use crate::core::df::tabular;
+use crate::core::oxenignore;
 use crate::core::v0_19_0::structs::StagedMerkleTreeNode;
+use crate::core::walker::Walker;
 use crate::model::metadata::generic_metadata::GenericMetadata;
 use crate::model::{Commit, EntryDataType, MerkleHash, StagedEntryStatus};
-use crate::opts::RmOpts;
+use crate::opts::{DFOpts, RmOpts};
 use crate::{error::OxenError, model::LocalRepository};
 use crate::{repositories, util};
 use std::ops::AddAssign;
 
+use crate::core::v0_19_0::index::stat_cache::{self, StatCache};
 use crate::core::v0_19_0::index::CommitMerkleTree;
 use crate::model::merkle_tree::node::{EMerkleTreeNode, FileNode, MerkleTreeNode};
 
@@ -47,7 +51,17 @@ pub fn add(repo: &LocalRepository, path: impl AsRef<Path>) -> Result<(), OxenErr
     // Collect paths that match the glob pattern either:
     // 1. In the repo working directory (untracked or modified files)
     // 2. In the commit entry db (removed files)
+    let paths = repositories::add::expand_paths(repo, [path])?;
+    add_all(repo, &paths, false)
+}
 
+/// Stage the paths. Nested repositories found while walking directories are skipped
+/// unless they are already tracked, or `allow_nested` is set to vendor their files.
+pub fn add_all(
+    repo: &LocalRepository,
+    paths: &HashSet<PathBuf>,
+    allow_nested: bool,
+) -> Result<(), OxenError> {
     // Cannot add if shallow
     if repo.is_shallow_clone() {
         return Err(OxenError::basic_str(
@@ -57,32 +71,15 @@ pub fn add(repo: &LocalRepository, path: impl AsRef<Path>) -> Result<(), OxenErr
 
     // Start a timer
     let start = std::time::Instant::now();
-    let path = path.as_ref();
-    let mut paths: HashSet<PathBuf> = HashSet::new();
-    if let Some(path_str) = path.to_str() {
-        if util::fs::is_glob_path(path_str) {
-            log::debug!("glob path: {}", path_str);
-            // Match against any untracked entries in the current dir
-            for entry in glob(path_str)? {
-                paths.insert(entry?);
-            }
-
-            if let Some(commit) = repositories::commits::head_commit_maybe(repo)? {
-                let pattern_entries =
-                    repositories::commits::search_entries(repo, &commit, path_str)?;
-                log::debug!("pattern entries: {:?}", pattern_entries);
-                paths.extend(pattern_entries);
-            }
-        } else {
-            // Non-glob path
-            paths.insert(path.to_owned());
-        }
-    }
-    let stats = add_files(repo, &paths)?;
+    let stats = add_files(repo, paths, allow_nested)?;
 
     // Stop the timer, and round the duration to the nearest second
     let duration = Duration::from_millis(start.elapsed().as_millis() as u64);
-    log::debug!("---END--- oxen add: {:?} duration: {:?}", path, duration);
+    log::debug!(
+        "---END--- oxen add: {} paths duration: {:?}",
+        paths.len(),
+        duration
+    );
 
     // oxen staged?
     println!(
@@ -98,6 +95,7 @@ pub fn add(repo: &LocalRepository, path: impl AsRef<Path>) -> Result<(), OxenErr
 fn add_files(
     repo: &LocalRepository,
     paths: &HashSet<PathBuf>,
+    allow_nested: bool,
 ) -> Result<CumulativeStats, OxenError> {
     // To start, let's see how fast we can simply loop through all the paths
     // and and copy them into an index.
@@ -118,11 +116,17 @@ fn add_files(
         total_bytes: 0,
         data_type_counts: HashMap::new(),
     };
+    let ignore = oxenignore::create(repo);
     for path in paths {
         log::debug!("path is {path:?}");
 
+        if path.exists() && ignore.is_ignored(path, path.is_dir()) {
+            log::debug!("Skipping {path:?}, it is in .oxenignore");
+            continue;
+        }
+
         if path.is_dir() {
-            total += add_dir(repo, &maybe_head_commit, path.clone())?;
+            total += add_dir(repo, &maybe_head_commit, path.clone(), allow_nested)?;
         } else if path.is_file() {
             let entry = add_file(repo, &maybe_head_commit, path)?;
             if let Some(entry) = entry {
@@ -155,6 +159,7 @@ pub fn add_dir(
     repo: &LocalRepository,
     maybe_head_commit: &Option<Commit>,
     path: PathBuf,
+    allow_nested: bool,
 ) -> Result<CumulativeStats, OxenError> {
     let versions_path = util::fs::oxen_hidden_dir(&repo.path)
         .join(VERSIONS_DIR)
@@ -164,7 +169,17 @@ pub fn add_dir(
     let staged_db: DBWithThreadMode<MultiThreaded> =
         DBWithThreadMode::open(&opts, dunce::simplified(&db_path))?;
 
-    process_add_dir(repo, maybe_head_commit, &versions_path, &staged_db, path)
+    let stat_cache = StatCache::maybe_open(repo);
+
+    process_add_dir(
+        repo,
+        maybe_head_commit,
+        &versions_path,
+        &staged_db,
+        stat_cache.as_ref(),
+        path,
+        allow_nested,
+    )
 }
 
 pub fn process_add_dir(
@@ -172,7 +187,9 @@ pub fn process_add_dir(
     maybe_head_commit: &Option<Commit>,
     versions_path: &Path,
     staged_db: &DBWithThreadMode<MultiThreaded>,
+    stat_cache: Option<&StatCache>,
     path: PathBuf,
+    allow_nested: bool,
 ) -> Result<CumulativeStats, OxenError> {
     let start = std::time::Instant::now();
 
@@ -198,13 +215,18 @@ pub fn process_add_dir(
         data_type_counts: HashMap::new(),
     };
 
-    let walker = WalkDir::new(&path).into_iter();
-    walker
-        .filter_entry(|e| e.file_type().is_dir() && e.file_name() != OXEN_HIDDEN_DIR)
+    let ignore = oxenignore::create(&repo);
+    let walker_repo = repo.clone();
+    let walker_head_commit = maybe_head_commit.clone();
+    // Directories are streamed from the walker and staged as they are found
+    Walker::new(&repo, &path)
+        .filter_dirs(move |dir| {
+            allow_nested || !is_untracked_nested_repo(&walker_repo, &walker_head_commit, dir)
+        })
+        .dirs()
         .par_bridge()
-        .try_for_each(|entry| -> Result<(), OxenError> {
-            let entry = entry.unwrap();
-            let dir = entry.path();
+        .try_for_each(|dir| -> Result<(), OxenError> {
+            let dir = dir.as_path();
 
             log::debug!("Entry is: {dir:?}");
 
@@ -225,6 +247,9 @@ pub fn process_add_dir(
                 log::debug!("Dir Entry is: {dir_entry:?}");
                 let total_bytes = byte_counter_clone.load(Ordering::Relaxed);
                 let path = dir_entry.path();
+                if ignore.is_ignored(&path, path.is_dir()) {
+                    return;
+                }
                 let duration = start.elapsed().as_secs_f32();
                 let mbps = (total_bytes as f32 / duration) / 1_000_000.0;
 
@@ -242,6 +267,7 @@ pub fn process_add_dir(
                     &repo_path,
                     versions_path,
                     staged_db,
+                    stat_cache,
                     &dir_node,
                     &path,
                     &seen_dirs_clone,
@@ -269,6 +295,27 @@ pub fn process_add_dir(
     Ok(cumulative_stats)
 }
 
+// Another repository inside this one that has not been vendored into it
+fn is_untracked_nested_repo(
+    repo: &LocalRepository,
+    maybe_head_commit: &Option<Commit>,
+    path: &Path,
+) -> bool {
+    if !util::fs::is_nested_repo(&repo.path, path) {
+        return false;
+    }
+    let Ok(relative_path) = util::fs::path_relative_to_dir(path, &repo.path) else {
+        return false;
+    };
+    match maybe_load_directory(repo, maybe_head_commit, &relative_path) {
+        Ok(Some(_)) => false,
+        _ => {
+            log::debug!("Skipping nested repository {path:?}");
+            true
+        }
+    }
+}
+
 fn maybe_load_directory(
     repo: &LocalRepository,
     maybe_head_commit: &Option<Commit>,
@@ -323,12 +370,14 @@ pub fn add_file(
         maybe_dir_node = CommitMerkleTree::dir_with_children(repo, head_commit, parent_path)?;
     }
 
+    let stat_cache = StatCache::maybe_open(repo);
     let seen_dirs = Arc::new(Mutex::new(HashSet::new()));
     process_add_file(
         repo,
         &repo_path,
         &versions_path,
         &staged_db,
+        stat_cache.as_ref(),
         &maybe_dir_node,
         path,
         &seen_dirs,
@@ -340,6 +389,7 @@ pub fn process_add_file(
     repo_path: &Path,
     versions_path: &Path,
     staged_db: &DBWithThreadMode<MultiThreaded>,
+    stat_cache: Option<&StatCache>,
     maybe_dir_node: &Option<MerkleTreeNode>,
     path: &Path,
     seen_dirs: &Arc<Mutex<HashSet<PathBuf>>>,
@@ -370,7 +420,7 @@ pub fn process_add_file(
         let mtime = FileTime::from_last_modification_time(&metadata);
         oxen_metadata = file_node.metadata.clone();
         if has_different_modification_time(file_node, &mtime) {
-            let hash = util::hasher::get_hash_given_metadata(&full_path, &metadata)?;
+            let hash = stat_cache::hash_file(stat_cache, &relative_path, &full_path, &metadata)?;
             if file_node.hash.to_u128() != hash {
                 (
                     StagedEntryStatus::Modified,
@@ -397,7 +447,7 @@ pub fn process_add_file(
     } else {
         let metadata = std::fs::metadata(path)?;
         let mtime = FileTime::from_last_modification_time(&metadata);
-        let hash = util::hasher::get_hash_given_metadata(&full_path, &metadata)?;
+        let hash = stat_cache::hash_file(stat_cache, &relative_path, &full_path, &metadata)?;
         (
             StagedEntryStatus::Added,
             MerkleHash::new(hash),
@@ -425,20 +475,65 @@ pub fn process_add_file(
         return Ok(None);
     }
 
+    stage_file(
+        repo,
+        versions_path,
+        staged_db,
+        &relative_path,
+        &full_path,
+        StagedFileState {
+            status,
+            hash,
+            num_bytes,
+            mtime,
+            oxen_metadata,
+        },
+        seen_dirs,
+    )
+}
+
+// What we already know about a file that is about to be staged
+struct StagedFileState {
+    status: StagedEntryStatus,
+    hash: MerkleHash,
+    num_bytes: u64,
+    mtime: FileTime,
+    oxen_metadata: Option<GenericMetadata>,
+}
+
+// Copy the contents into the versions dir and stage them at the relative path
+fn stage_file(
+    repo: &LocalRepository,
+    versions_path: &Path,
+    staged_db: &DBWithThreadMode<MultiThreaded>,
+    relative_path: &Path,
+    contents_path: &Path,
+    state: StagedFileState,
+    seen_dirs: &Arc<Mutex<HashSet<PathBuf>>>,
+) -> Result<Option<StagedMerkleTreeNode>, OxenError> {
+    let StagedFileState {
+        status,
+        hash,
+        num_bytes,
+        mtime,
+        oxen_metadata,
+    } = state;
+    let full_path = contents_path;
+
     // Get the data type of the file
-    let mime_type = util::fs::file_mime_type(path);
-    let mut data_type = util::fs::datatype_from_mimetype(path, &mime_type);
+    let mime_type = util::fs::file_mime_type(full_path);
+    let mut data_type = util::fs::datatype_from_mimetype(full_path, &mime_type);
     let metadata = match &oxen_metadata {
         Some(oxen_metadata) => {
-            let df_metadata = repositories::metadata::get_file_metadata(&full_path, &data_type)?;
+            let df_metadata = repositories::metadata::get_file_metadata(full_path, &data_type)?;
             maybe_construct_generic_metadata_for_tabular(df_metadata, oxen_metadata.clone())
         }
-        None => repositories::metadata::get_file_metadata(&full_path, &data_type)?,
+        None => repositories::metadata::get_file_metadata_for_repo(repo, full_path, &data_type)?,
     };
 
     // If the metadata is None, but the data type is tabular, we need to set the data type to binary
     // because this means we failed to parse the metadata from the file
-    if metadata.is_none() && data_type == EntryDataType::Tabular {
+    if metadata.is_none() && data_type.is_tabular() {
         data_type = EntryDataType::Binary;
     }
 
@@ -454,8 +549,12 @@ pub fn process_add_file(
         util::fs::create_dir_all(&dst_dir).unwrap();
     }
 
-    let dst = dst_dir.join("data");
-    util::fs::copy(&full_path, &dst).unwrap();
+    // Version files are content addressed, so an existing one already has these bytes.
+    // It may also be hard linked into the working dir by checkout, so never rewrite it.
+    let dst = dst_dir.join(VERSION_FILE_NAME);
+    if !util::encryption::stored_path(&dst).exists() {
+        util::encryption::store(repo, full_path, &dst)?;
+    }
 
     let file_extension = relative_path
         .extension()
@@ -489,6 +588,113 @@ pub fn process_add_file(
     p_add_file_node_to_staged_db(staged_db, relative_path_str, status, &file_node, seen_dirs)
 }
 
+/// Stage only the rows of a tabular file that match the filter
+///
+/// The staged version is the committed rows that don't match the filter followed by the
+/// working rows that do, so changes to the other rows stay in the working file unstaged.
+pub fn add_rows(
+    repo: &LocalRepository,
+    path: &Path,
+    filter: &DFFilterExp,
+) -> Result<Option<StagedMerkleTreeNode>, OxenError> {
+    let relative_path = util::fs::path_relative_to_dir(path, &repo.path)?;
+    let full_path = repo.path.join(&relative_path);
+    if !full_path.is_file() {
+        return Err(OxenError::path_does_not_exist(&full_path));
+    }
+    if !util::fs::is_tabular(&full_path) {
+        return Err(OxenError::basic_str(format!(
+            "Can only stage rows of a tabular file, {:?} is not tabular",
+            relative_path
+        )));
+    }
+    let extension = util::fs::file_extension(&full_path);
+
+    let maybe_head_commit = repositories::commits::head_commit_maybe(repo)?;
+    let maybe_file_node = match &maybe_head_commit {
+        Some(commit) => repositories::entries::get_file(repo, commit, &relative_path)?,
+        None => None,
+    };
+
+    let working_df = tabular::read_df(&full_path, DFOpts::empty())?;
+    let (matching, _) = tabular::partition_by_filter(working_df, filter)?;
+    let mut staged_df = match &maybe_file_node {
+        Some(file_node) => {
+            let version_path =
+                util::fs::plain_version_path_from_hash(repo, file_node.hash.to_string())?;
+            let committed_df =
+                tabular::read_df_with_extension(version_path.path(), &extension, &DFOpts::empty())?;
+            let (_, unchanged) = tabular::partition_by_filter(committed_df, filter)?;
+            if unchanged.schema() != matching.schema() {
+                return Err(OxenError::basic_str(format!(
+                    "The schema of {:?} changed, stage the whole file with:\n\n  oxen add {}\n",
+                    relative_path,
+                    relative_path.to_string_lossy()
+                )));
+            }
+            unchanged.vstack(&matching)?
+        }
+        None => matching,
+    };
+
+    // Write the staged rows next to the other caches so they can be hashed and versioned
+    let tmp_dir = util::fs::oxen_hidden_dir(&repo.path)
+        .join(CACHE_DIR)
+        .join(ROWS_DIR);
+    util::fs::create_dir_all(&tmp_dir)?;
+    let tmp_path = tmp_dir.join(format!("{}.{}", uuid::Uuid::new_v4(), extension));
+    tabular::write_df(&mut staged_df, &tmp_path)?;
+
+    let result = stage_rows_file(repo, &relative_path, &tmp_path, maybe_file_node);
+    util::fs::remove_file(&tmp_path)?;
+    result
+}
+
+fn stage_rows_file(
+    repo: &LocalRepository,
+    relative_path: &Path,
+    contents_path: &Path,
+    maybe_file_node: Option<FileNode>,
+) -> Result<Option<StagedMerkleTreeNode>, OxenError> {
+    let metadata = std::fs::metadata(contents_path)?;
+    let hash = MerkleHash::new(util::hasher::get_hash_given_metadata(
+        contents_path,
+        &metadata,
+    )?);
+    let status = match &maybe_file_node {
+        Some(file_node) if file_node.hash == hash => {
+            log::debug!("add_rows no rows changed in {:?}", relative_path);
+            return Ok(None);
+        }
+        Some(_) => StagedEntryStatus::Modified,
+        None => StagedEntryStatus::Added,
+    };
+
+    let versions_path = util::fs::oxen_hidden_dir(&repo.path)
+        .join(VERSIONS_DIR)
+        .join(FILES_DIR);
+    let opts = db::key_val::opts::default();
+    let db_path = util::fs::oxen_hidden_dir(&repo.path).join(STAGED_DIR);
+    let staged_db: DBWithThreadMode<MultiThreaded> =
+        DBWithThreadMode::open(&opts, dunce::simplified(&db_path))?;
+    let seen_dirs = Arc::new(Mutex::new(HashSet::new()));
+    stage_file(
+        repo,
+        &versions_path,
+        &staged_db,
+        relative_path,
+        contents_path,
+        StagedFileState {
+            status,
+            hash,
+            num_bytes: metadata.len(),
+            mtime: FileTime::from_last_modification_time(&metadata),
+            oxen_metadata: maybe_file_node.and_then(|node| node.metadata),
+        },
+        &seen_dirs,
+    )
+}
+
 pub fn maybe_construct_generic_metadata_for_tabular(
     df_metadata: Option<GenericMetadata>,
     oxen_metadata: GenericMetadata,
@@ -509,6 +715,14 @@ pub fn maybe_construct_generic_metadata_for_tabular(
                     field.metadata = oxen_field.metadata.clone();
                 }
             }
+            // Schema level metadata like the primary key carries over to the new version
+            if df_metadata.tabular.schema.metadata.is_none() {
+                df_metadata
+                    .tabular
+                    .schema
+                    .metadata
+                    .clone_from(&oxen_metadata.tabular.schema.metadata);
+            }
             return Some(GenericMetadata::MetadataTabular(df_metadata));
         }
     }
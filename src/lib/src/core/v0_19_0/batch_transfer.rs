@@ -0,0 +1,181 @@
+//! # Batch transfer
+//!
+//! Push and pull small files as streamed tar batches over one shared client, so thousands
+//! of files take a handful of multiplexed requests instead of one gzipped bundle each.
+//! Files at or above [AVG_CHUNK_SIZE] and servers without the batch routes keep using
+//! the chunked transfer in [pusher] and [puller].
+//!
+
+use futures::prelude::*;
+use reqwest::Client;
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::api;
+use crate::constants::{AVG_CHUNK_SIZE, BATCH_TRANSFER_MAX_FILES, BATCH_TRANSFER_SIZE};
+use crate::core::v0_10_0::index::{puller, pusher};
+use crate::core::v0_19_0::structs::pull_progress::PullProgress;
+use crate::core::v0_19_0::structs::push_progress::PushProgress;
+use crate::error::OxenError;
+use crate::model::entry::commit_entry::Entry;
+use crate::model::{Commit, LocalRepository, MerkleHash, RemoteRepository};
+use crate::opts::PushOpts;
+
+/// Client for the batch routes, or None if the server doesn't have them
+pub async fn batch_client(remote_repo: &RemoteRepository) -> Result<Option<Client>, OxenError> {
+    let client = api::client::versions::batch_client(remote_repo)?;
+    if api::client::versions::has_batch_transfer(&client, remote_repo).await? {
+        Ok(Some(client))
+    } else {
+        log::debug!("Remote does not support batch transfer, falling back to bundles");
+        Ok(None)
+    }
+}
+
+/// Group entries into batches of at most [BATCH_TRANSFER_SIZE] bytes and
+/// [BATCH_TRANSFER_MAX_FILES] files
+pub fn split_batches(entries: &[Entry]) -> Vec<Vec<Entry>> {
+    let mut batches: Vec<Vec<Entry>> = vec![];
+    let mut batch: Vec<Entry> = vec![];
+    let mut batch_size: u64 = 0;
+    for entry in entries {
+        let is_full = batch_size + entry.num_bytes() > BATCH_TRANSFER_SIZE
+            || batch.len() >= BATCH_TRANSFER_MAX_FILES;
+        if !batch.is_empty() && is_full {
+            batches.push(std::mem::take(&mut batch));
+            batch_size = 0;
+        }
+        batch_size += entry.num_bytes();
+        batch.push(entry.to_owned());
+    }
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+    batches
+}
+
+fn partition_small(entries: &[Entry]) -> (Vec<Entry>, Vec<Entry>) {
+    entries
+        .iter()
+        .cloned()
+        .partition(|e| e.num_bytes() < AVG_CHUNK_SIZE)
+}
+
+/// Push the version files of `entries`, small files in batches if the server supports it
+pub async fn push_entries(
+    local_repo: &LocalRepository,
+    remote_repo: &RemoteRepository,
+    entries: &[Entry],
+    commit: &Commit,
+    progress: &Arc<PushProgress>,
+) -> Result<(), OxenError> {
+    let Some(client) = batch_client(remote_repo).await? else {
+        return pusher::push_entries(local_repo, remote_repo, entries, commit, progress).await;
+    };
+
+    let (small_entries, large_entries) = partition_small(entries);
+    let num_workers = PushOpts::from_env().num_workers;
+    let client = &client;
+    let results: Vec<Result<(), OxenError>> = stream::iter(split_batches(&small_entries))
+        .map(|batch| async move {
+            let stored =
+                api::client::versions::upload_batch(client, local_repo, remote_repo, &batch)
+                    .await?;
+            for entry in &batch {
+                let hash = MerkleHash::from_str(&entry.hash())?;
+                if !stored.contains(&hash) {
+                    return Err(OxenError::basic_str(format!(
+                        "Server did not store {:?}",
+                        entry.path()
+                    )));
+                }
+            }
+            progress.add_files(batch.len() as u64);
+            progress.add_bytes(batch.iter().map(|e| e.num_bytes()).sum());
+            Ok(())
+        })
+        .buffer_unordered(num_workers)
+        .collect()
+        .await;
+    results
+        .into_iter()
+        .collect::<Result<Vec<()>, OxenError>>()?;
+
+    pusher::push_entries(local_repo, remote_repo, &large_entries, commit, progress).await
+}
+
+/// Pull the version files of `entries` into the versions dir of the repository at `dst`.
+/// Pass the client from [batch_client] to pull small files in batches.
+pub async fn pull_entries(
+    remote_repo: &RemoteRepository,
+    client: Option<&Client>,
+    entries: &[Entry],
+    dst: &Path,
+    progress: &Arc<PullProgress>,
+) -> Result<(), OxenError> {
+    let Some(client) = client else {
+        return puller::pull_entries_to_versions_dir(remote_repo, entries, dst, progress).await;
+    };
+
+    let (small_entries, large_entries) = partition_small(entries);
+    let num_workers = PushOpts::from_env().num_workers;
+    let results: Vec<Result<(), OxenError>> = stream::iter(split_batches(&small_entries))
+        .map(|batch| async move {
+            let num_bytes =
+                api::client::versions::download_batch(client, remote_repo, &batch, dst).await?;
+            progress.add_files(batch.len() as u64);
+            progress.add_bytes(num_bytes);
+            Ok(())
+        })
+        .buffer_unordered(num_workers)
+        .collect()
+        .await;
+    results
+        .into_iter()
+        .collect::<Result<Vec<()>, OxenError>>()?;
+
+    puller::pull_entries_to_versions_dir(remote_repo, &large_entries, dst, progress).await
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::constants::{BATCH_TRANSFER_MAX_FILES, BATCH_TRANSFER_SIZE};
+    use crate::core::v0_19_0::batch_transfer;
+    use crate::model::entry::commit_entry::Entry;
+    use crate::model::CommitEntry;
+
+    fn entry(num_bytes: u64) -> Entry {
+        Entry::CommitEntry(CommitEntry {
+            commit_id: String::from("abc"),
+            path: PathBuf::from("file.txt"),
+            hash: String::from("1234"),
+            num_bytes,
+            last_modified_seconds: 0,
+            last_modified_nanoseconds: 0,
+        })
+    }
+
+    #[test]
+    fn test_split_batches_by_size_and_count() {
+        assert!(batch_transfer::split_batches(&[]).is_empty());
+
+        let half = BATCH_TRANSFER_SIZE / 2;
+        let entries = vec![
+            entry(half),
+            entry(half),
+            entry(1),
+            entry(BATCH_TRANSFER_SIZE * 2),
+        ];
+        let batches = batch_transfer::split_batches(&entries);
+        let sizes: Vec<usize> = batches.iter().map(|b| b.len()).collect();
+        assert_eq!(sizes, vec![2, 1, 1]);
+
+        let entries = vec![entry(1); BATCH_TRANSFER_MAX_FILES + 1];
+        let batches = batch_transfer::split_batches(&entries);
+        let sizes: Vec<usize> = batches.iter().map(|b| b.len()).collect();
+        assert_eq!(sizes, vec![BATCH_TRANSFER_MAX_FILES, 1]);
+    }
+}
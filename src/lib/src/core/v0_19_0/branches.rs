@@ -8,16 +8,23 @@ use crate::model::{Commit, CommitEntry, LocalRepository};
 use crate::repositories;
 use crate::util;
 
+use rayon::prelude::*;
 use std::collections::HashSet;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 
+// Only list this many failed paths in the checkout error, the rest go to the log
+const MAX_LISTED_FAILURES: usize = 10;
+
 struct CheckoutProgressBar {
     revision: String,
     progress: ProgressBar,
-    num_restored: usize,
-    num_modified: usize,
-    num_removed: usize,
+    num_to_check: AtomicUsize,
+    num_checked: AtomicUsize,
+    num_restored: AtomicUsize,
+    num_modified: AtomicUsize,
+    num_removed: AtomicUsize,
 }
 
 impl CheckoutProgressBar {
@@ -29,33 +36,54 @@ impl CheckoutProgressBar {
         Self {
             revision,
             progress,
-            num_restored: 0,
-            num_modified: 0,
-            num_removed: 0,
+            num_to_check: AtomicUsize::new(0),
+            num_checked: AtomicUsize::new(0),
+            num_restored: AtomicUsize::new(0),
+            num_modified: AtomicUsize::new(0),
+            num_removed: AtomicUsize::new(0),
         }
     }
 
-    pub fn increment_restored(&mut self) {
-        self.num_restored += 1;
+    pub fn set_num_to_check(&self, num_to_check: usize) {
+        self.num_to_check.store(num_to_check, Ordering::Relaxed);
+        self.update_message();
+    }
+
+    pub fn increment_checked(&self) {
+        self.num_checked.fetch_add(1, Ordering::Relaxed);
+        self.update_message();
+    }
+
+    pub fn increment_restored(&self) {
+        self.num_restored.fetch_add(1, Ordering::Relaxed);
         self.update_message();
     }
 
-    pub fn increment_modified(&mut self) {
-        self.num_modified += 1;
+    pub fn increment_modified(&self) {
+        self.num_modified.fetch_add(1, Ordering::Relaxed);
         self.update_message();
     }
 
-    pub fn increment_removed(&mut self) {
-        self.num_removed += 1;
+    pub fn increment_removed(&self) {
+        self.num_removed.fetch_add(1, Ordering::Relaxed);
         self.update_message();
     }
 
-    fn update_message(&mut self) {
+    fn update_message(&self) {
         self.progress.set_message(format!(
-            "🐂 checkout '{}' restored {}, modified {}, removed {}",
-            self.revision, self.num_restored, self.num_modified, self.num_removed
+            "🐂 checkout '{}' checked {}/{}, restored {}, modified {}, removed {}",
+            self.revision,
+            self.num_checked.load(Ordering::Relaxed),
+            self.num_to_check.load(Ordering::Relaxed),
+            self.num_restored.load(Ordering::Relaxed),
+            self.num_modified.load(Ordering::Relaxed),
+            self.num_removed.load(Ordering::Relaxed)
         ));
     }
+
+    fn finish(&self) {
+        self.progress.finish_and_clear();
+    }
 }
 
 pub fn list_entry_versions_for_commit(
@@ -153,7 +181,7 @@ pub async fn set_working_repo_to_commit(
     to_commit: &Commit,
     maybe_from_commit: &Option<Commit>,
 ) -> Result<(), OxenError> {
-    let mut progress = CheckoutProgressBar::new(to_commit.id.clone());
+    let progress = CheckoutProgressBar::new(to_commit.id.clone());
 
     log::debug!(
         "set_working_repo_to_commit to_commit {} from_commit {:?}",
@@ -172,7 +200,7 @@ pub async fn set_working_repo_to_commit(
 
         // Only cleanup removed files if we are checking out from an existing tree
         let from_tree = CommitMerkleTree::from_commit(repo, from_commit)?;
-        cleanup_removed_files(repo, &target_tree, &from_tree, &mut progress)?;
+        cleanup_removed_files(repo, &target_tree, &from_tree, &progress)?;
         Some(from_tree)
     } else {
         None
@@ -184,22 +212,18 @@ pub async fn set_working_repo_to_commit(
 
     // If we did it in one pass, we would not know if we should remove the file
     // or restore it.
-    r_restore_missing_or_modified_files(
-        repo,
-        &target_tree.root,
-        &from_tree,
-        Path::new(""),
-        &mut progress,
-    )?;
-
-    Ok(())
+    let mut files = Vec::new();
+    r_collect_missing_or_modified_files(&target_tree.root, &from_tree, Path::new(""), &mut files)?;
+    let result = restore_missing_or_modified_files(repo, &files, &progress);
+    progress.finish();
+    result
 }
 
 fn cleanup_removed_files(
     repo: &LocalRepository,
     target_tree: &CommitMerkleTree,
     from_tree: &CommitMerkleTree,
-    progress: &mut CheckoutProgressBar,
+    progress: &CheckoutProgressBar,
 ) -> Result<(), OxenError> {
     // Compare the nodes in the from tree to the nodes in the target tree
     // If the file node is in the from tree, but not in the target tree, remove it
@@ -224,7 +248,7 @@ fn r_remove_if_not_in_target(
     from_tree: &CommitMerkleTree,
     target_tree: &CommitMerkleTree,
     current_path: &Path,
-    progress: &mut CheckoutProgressBar,
+    progress: &CheckoutProgressBar,
 ) -> Result<(), OxenError> {
     log::debug!(
         "r_remove_if_not_in_target current_path: {:?} head_node: {}",
@@ -311,43 +335,25 @@ fn r_remove_if_not_in_target(
     Ok(())
 }
 
-fn r_restore_missing_or_modified_files(
-    repo: &LocalRepository,
+fn r_collect_missing_or_modified_files(
     node: &MerkleTreeNode,
     from_tree: &Option<CommitMerkleTree>,
     path: &Path, // relative path
-    progress: &mut CheckoutProgressBar,
+    files: &mut Vec<(PathBuf, FileNode)>,
 ) -> Result<(), OxenError> {
-    // Recursively iterate through the tree, checking each file against the working repo
-    // If the file is not in the working repo, restore it from the commit
-    // If the file is in the working repo, but the hash does not match, overwrite the file in the working repo with the file from the commit
-    // If the file is in the working repo, and the hash matches, do nothing
-
+    // Recursively iterate through the tree, collecting every file that may need to be
+    // restored. Comparing against the working repo is done later, in parallel, because
+    // hashing and copying is where the time goes on large commits.
     match &node.node {
         EMerkleTreeNode::File(file_node) => {
-            let rel_path = path.join(&file_node.name);
-            let full_path = repo.path.join(&rel_path);
-            if !full_path.exists() {
-                // File doesn't exist, restore it
-                log::debug!("Restoring missing file: {:?}", rel_path);
-                restore_file(repo, file_node, &full_path)?;
-                progress.increment_restored();
-            } else {
-                // File exists, check if it needs to be updated
-                let current_hash = util::hasher::hash_file_contents(&full_path)?;
-                if current_hash != file_node.hash.to_string() {
-                    log::debug!("Updating modified file: {:?}", rel_path);
-                    restore_file(repo, file_node, &full_path)?;
-                    progress.increment_modified();
-                }
-            }
+            files.push((path.join(&file_node.name), file_node.clone()));
         }
         EMerkleTreeNode::Directory(dir_node) => {
             // Early exit if the directory is the same in the from and target trees
             if let Some(from_tree) = from_tree {
                 if let Some(from_node) = from_tree.get_by_path(path)? {
                     if from_node.node.hash() == dir_node.hash {
-                        log::debug!("r_restore_missing_or_modified_files path {:?} is the same as from_tree", path);
+                        log::debug!("r_collect_missing_or_modified_files path {:?} is the same as from_tree", path);
                         return Ok(());
                     }
                 }
@@ -357,19 +363,13 @@ fn r_restore_missing_or_modified_files(
             let children = CommitMerkleTree::node_files_and_folders(node)?;
             let dir_path = path.join(&dir_node.name);
             for child_node in children {
-                r_restore_missing_or_modified_files(
-                    repo,
-                    &child_node,
-                    from_tree,
-                    &dir_path,
-                    progress,
-                )?;
+                r_collect_missing_or_modified_files(&child_node, from_tree, &dir_path, files)?;
             }
         }
         EMerkleTreeNode::Commit(_) => {
             // If we get a commit node, we need to skip to the root directory
             let root_dir = CommitMerkleTree::get_root_dir_from_commit(node)?;
-            r_restore_missing_or_modified_files(repo, root_dir, from_tree, path, progress)?;
+            r_collect_missing_or_modified_files(root_dir, from_tree, path, files)?;
         }
         _ => {
             return Err(OxenError::basic_str(
@@ -380,6 +380,80 @@ fn r_restore_missing_or_modified_files(
     Ok(())
 }
 
+fn restore_missing_or_modified_files(
+    repo: &LocalRepository,
+    files: &[(PathBuf, FileNode)],
+    progress: &CheckoutProgressBar,
+) -> Result<(), OxenError> {
+    // If the file is not in the working repo, restore it from the commit
+    // If the file is in the working repo, but the hash does not match, overwrite the file in the working repo with the file from the commit
+    // If the file is in the working repo, and the hash matches, do nothing
+    //
+    // Files are independent of each other, so spread them over the rayon pool and
+    // keep going when one fails, so a single bad version file does not leave the
+    // rest of the working directory half checked out.
+    progress.set_num_to_check(files.len());
+    let mut failures: Vec<(PathBuf, OxenError)> = files
+        .par_iter()
+        .filter_map(|(rel_path, file_node)| {
+            let result = restore_if_missing_or_modified(repo, rel_path, file_node, progress);
+            progress.increment_checked();
+            result.err().map(|err| (rel_path.to_owned(), err))
+        })
+        .collect();
+
+    if failures.is_empty() {
+        return Ok(());
+    }
+
+    failures.sort_by(|a, b| a.0.cmp(&b.0));
+    for (path, err) in failures.iter() {
+        log::error!("checkout could not restore {:?}: {}", path, err);
+    }
+    let listed: Vec<String> = failures
+        .iter()
+        .take(MAX_LISTED_FAILURES)
+        .map(|(path, err)| format!("  {}: {}", path.to_string_lossy(), err))
+        .collect();
+    let mut message = format!(
+        "Checkout could not restore {} of {} files:\n{}",
+        failures.len(),
+        files.len(),
+        listed.join("\n")
+    );
+    if failures.len() > MAX_LISTED_FAILURES {
+        message.push_str(&format!(
+            "\n  ...and {} more",
+            failures.len() - MAX_LISTED_FAILURES
+        ));
+    }
+    Err(OxenError::basic_str(message))
+}
+
+fn restore_if_missing_or_modified(
+    repo: &LocalRepository,
+    rel_path: &Path,
+    file_node: &FileNode,
+    progress: &CheckoutProgressBar,
+) -> Result<(), OxenError> {
+    let full_path = repo.path.join(rel_path);
+    if !full_path.exists() {
+        // File doesn't exist, restore it
+        log::debug!("Restoring missing file: {:?}", rel_path);
+        restore_file(repo, file_node, &full_path)?;
+        progress.increment_restored();
+    } else {
+        // File exists, check if it needs to be updated
+        let current_hash = util::hasher::hash_file_contents(&full_path)?;
+        if current_hash != file_node.hash.to_string() {
+            log::debug!("Updating modified file: {:?}", rel_path);
+            restore_file(repo, file_node, &full_path)?;
+            progress.increment_modified();
+        }
+    }
+    Ok(())
+}
+
 pub fn restore_file(
     repo: &LocalRepository,
     file_node: &FileNode,
@@ -399,7 +473,7 @@ pub fn restore_file(
         }
     }
 
-    util::fs::copy(version_path, dst_path)?;
+    util::fs::copy_version_to_working(repo, version_path, dst_path)?;
 
     let last_modified_seconds = file_node.last_modified_seconds;
     let last_modified_nanoseconds = file_node.last_modified_nanoseconds;
@@ -34,6 +34,7 @@ pub async fn clone_repo(
     repo_path.clone_into(&mut local_repo.path);
     local_repo.set_remote(DEFAULT_REMOTE_NAME, &remote_repo.remote.url);
     local_repo.set_min_version(remote_repo.min_version());
+    local_repo.set_upstream(&opts.branch, DEFAULT_REMOTE_NAME, &opts.branch);
 
     // Save remote config in .oxen/config.toml
     let remote_cfg = RepositoryConfig {
@@ -41,6 +42,12 @@ pub async fn clone_repo(
         remotes: vec![remote_repo.remote.clone()],
         min_version: Some(remote_repo.min_version().to_string()),
         vnode_size: Some(DEFAULT_VNODE_SIZE),
+        perceptual_hash: None,
+        content_addressed_tree: None,
+        checkout_mode: None,
+        encryption_key_id: None,
+        compression_level: None,
+        branch: local_repo.upstreams().clone(),
     };
 
     let toml = toml::to_string(&remote_cfg)?;
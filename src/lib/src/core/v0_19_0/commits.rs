@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::path::Path;
 
 use glob::Pattern;
@@ -8,7 +8,7 @@ use crate::core;
 use crate::core::refs::{RefReader, RefWriter};
 use crate::core::v0_10_0::cache::cacher_status::CacherStatusType;
 use crate::error::OxenError;
-use crate::model::merkle_tree::node::{CommitNode, EMerkleTreeNode};
+use crate::model::merkle_tree::node::{CommitNode, EMerkleTreeNode, MerkleTreeNode};
 use crate::model::{Commit, LocalRepository, MerkleHash, User};
 use crate::opts::PaginateOpts;
 use crate::view::{PaginatedCommits, StatusMessage};
@@ -18,6 +18,7 @@ use std::path::PathBuf;
 use std::str;
 use std::str::FromStr;
 
+use crate::core::v0_19_0::index::last_commits;
 use crate::core::v0_19_0::index::CommitMerkleTree;
 
 use super::index::MerkleNodeDB;
@@ -34,6 +35,17 @@ pub fn commit_with_user(
     super::index::commit_writer::commit_with_user(repo, message, user)
 }
 
+pub fn amend(repo: &LocalRepository, message: impl AsRef<str>) -> Result<Commit, OxenError> {
+    super::index::commit_writer::amend(repo, message)
+}
+
+pub fn commit_allow_empty(
+    repo: &LocalRepository,
+    message: impl AsRef<str>,
+) -> Result<Commit, OxenError> {
+    super::index::commit_writer::commit_allow_empty(repo, message)
+}
+
 pub fn get_commit_or_head<S: AsRef<str> + Clone>(
     repo: &LocalRepository,
     commit_id_or_branch_name: Option<S>,
@@ -56,6 +68,7 @@ fn get_commit_by_ref<S: AsRef<str> + Clone>(
 ) -> Result<Commit, OxenError> {
     get_by_id(repo, ref_name.clone())?
         .or_else(|| get_commit_by_branch(repo, ref_name.as_ref()))
+        .or_else(|| get_commit_by_remote_ref(repo, ref_name.as_ref()))
         .ok_or_else(|| OxenError::basic_str("Commit not found"))
 }
 
@@ -66,6 +79,14 @@ fn get_commit_by_branch(repo: &LocalRepository, branch_name: &str) -> Option<Com
         .and_then(|branch| get_by_id(repo, branch.commit_id).ok().flatten())
 }
 
+// Remote-tracking refs such as origin/main, written by oxen fetch
+fn get_commit_by_remote_ref(repo: &LocalRepository, ref_name: &str) -> Option<Commit> {
+    core::refs::remote_refs::get(repo, ref_name)
+        .ok()
+        .flatten()
+        .and_then(|commit_id| get_by_id(repo, commit_id).ok().flatten())
+}
+
 pub fn latest_commit(repo: &LocalRepository) -> Result<Commit, OxenError> {
     let ref_reader = RefReader::new(repo)?;
     let branches = ref_reader.list_branches()?;
@@ -411,25 +432,16 @@ pub fn list_by_path_from_paginated(
     path: &Path,
     pagination: PaginateOpts,
 ) -> Result<PaginatedCommits, OxenError> {
-    // Check if the path is a directory or file
-    let node = repositories::tree::get_node_by_path(repo, commit, path)?.ok_or(
-        OxenError::basic_str(format!("Merkle tree node not found for path: {:?}", path)),
-    )?;
-    let last_commit_id = match &node.node {
-        EMerkleTreeNode::File(file_node) => file_node.last_commit_id,
-        EMerkleTreeNode::Directory(dir_node) => dir_node.last_commit_id,
-        _ => {
-            return Err(OxenError::basic_str(format!(
-                "Merkle tree node not found for path: {:?}",
-                path
-            )));
-        }
-    };
-    let last_commit_id = last_commit_id.to_string();
-    let commits = list_from(repo, &last_commit_id)?;
+    if last_commit_id_for_path(repo, commit, path)?.is_none() {
+        return Err(OxenError::basic_str(format!(
+            "Merkle tree node not found for path: {:?}",
+            path
+        )));
+    }
+    let commits = list_by_path_from(repo, commit, path)?;
     log::info!(
         "list_by_path_from_paginated {} got {} commits before pagination",
-        last_commit_id,
+        commit.id,
         commits.len()
     );
     let (commits, pagination) = util::paginate(commits, pagination.page_num, pagination.page_size);
@@ -440,18 +452,127 @@ pub fn list_by_path_from_paginated(
     })
 }
 
+/// The commits that changed the file or directory at `path`, newest first, from `commit`
+/// back. Each change is found from the last_commit_id on the path's node in the parents of
+/// the change before it, so this reads a few nodes per change instead of walking the whole
+/// history. It stops at the commit that added the path, and is empty if `commit` doesn't
+/// have the path.
+pub fn list_by_path_from(
+    repo: &LocalRepository,
+    commit: &Commit,
+    path: &Path,
+) -> Result<Vec<Commit>, OxenError> {
+    let mut results = vec![];
+    let mut visited: HashSet<MerkleHash> = HashSet::new();
+    // Newest first when the history of the path goes through a merge
+    let mut queue: BinaryHeap<(OffsetDateTime, MerkleHash)> = BinaryHeap::new();
+    let mut pending: HashMap<MerkleHash, Commit> = HashMap::new();
+
+    if let Some(last_commit_id) = last_commit_id_for_path(repo, commit, path)? {
+        if let Some(change) = get_by_hash(repo, &last_commit_id)? {
+            queue.push((change.timestamp, last_commit_id));
+            pending.insert(last_commit_id, change);
+        }
+    }
+
+    while let Some((_, hash)) = queue.pop() {
+        let Some(change) = pending.remove(&hash) else {
+            continue;
+        };
+        if !visited.insert(hash) {
+            continue;
+        }
+        for parent_id in change.parent_ids.iter() {
+            let Some(parent) = get_by_id(repo, parent_id)? else {
+                continue;
+            };
+            let Some(last_commit_id) = last_commit_id_for_path(repo, &parent, path)? else {
+                continue;
+            };
+            if visited.contains(&last_commit_id) || pending.contains_key(&last_commit_id) {
+                continue;
+            }
+            if let Some(previous) = get_by_hash(repo, &last_commit_id)? {
+                queue.push((previous.timestamp, last_commit_id));
+                pending.insert(last_commit_id, previous);
+            }
+        }
+        results.push(change);
+    }
+    Ok(results)
+}
+
+/// Whether the commit changed the file or directory at `path`, using the id of the last
+/// commit that changed it stored on its merkle tree node. A path that is missing from the
+/// commit but in one of its parents was removed by it.
+pub fn commit_changed_path(
+    repo: &LocalRepository,
+    commit: &Commit,
+    path: &Path,
+) -> Result<bool, OxenError> {
+    if let Some(last_commit_id) = last_commit_id_for_path(repo, commit, path)? {
+        return Ok(last_commit_id.to_string() == commit.id);
+    }
+    for parent_id in commit.parent_ids.iter() {
+        if let Some(parent) = get_by_id(repo, parent_id)? {
+            if last_commit_id_for_path(repo, &parent, path)?.is_some() {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+fn last_commit_id_for_path(
+    repo: &LocalRepository,
+    commit: &Commit,
+    path: &Path,
+) -> Result<Option<MerkleHash>, OxenError> {
+    let Some(node) = repositories::tree::get_node_by_path(repo, commit, path)? else {
+        return Ok(None);
+    };
+    last_commit_id_for_node(repo, commit, path, &node)
+}
+
+/// The id of the last commit that changed the file or directory `node` at `path`, as of
+/// `commit`. Content addressed trees share their nodes between commits, so they leave it
+/// off the node and it is read from the index of the commit, see [last_commits].
+pub fn last_commit_id_for_node(
+    repo: &LocalRepository,
+    commit: &Commit,
+    path: &Path,
+    node: &MerkleTreeNode,
+) -> Result<Option<MerkleHash>, OxenError> {
+    let last_commit_id = match &node.node {
+        EMerkleTreeNode::File(file_node) => file_node.last_commit_id,
+        EMerkleTreeNode::Directory(dir_node) => dir_node.last_commit_id,
+        _ => return Ok(None),
+    };
+    if last_commit_id != MerkleHash::new(0) {
+        return Ok(Some(last_commit_id));
+    }
+    last_commits::get(repo, commit, path)
+}
+
 // TODO: Temporary function until after v0.19.0, see repositories::commits::get_commit_status_tmp
 pub fn get_commit_status_tmp(
     repo: &LocalRepository,
     commit: &Commit,
 ) -> Result<Option<CacherStatusType>, OxenError> {
-    match get_by_id(repo, &commit.id)? {
-        Some(_commit) => Ok(Some(CacherStatusType::Success)),
-        None => Ok(None),
+    if get_by_id(repo, &commit.id)?.is_none() {
+        return Ok(None);
+    }
+    // Commits that were never queued for post processing are done
+    match repositories::post_process::get_status(repo, commit)? {
+        Some(status) => Ok(Some(status.status)),
+        None => Ok(Some(CacherStatusType::Success)),
     }
 }
 
 // TODO: Temporary function until after v0.19.0, see repositories::commits::is_commit_valid_tmp
-pub fn is_commit_valid_tmp(_repo: &LocalRepository, _commit: &Commit) -> Result<bool, OxenError> {
-    Ok(true)
+pub fn is_commit_valid_tmp(repo: &LocalRepository, commit: &Commit) -> Result<bool, OxenError> {
+    match repositories::post_process::get_status(repo, commit)? {
+        Some(status) => Ok(status.status != CacherStatusType::Failed),
+        None => Ok(true),
+    }
 }
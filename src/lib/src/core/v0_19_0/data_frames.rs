@@ -52,11 +52,14 @@ pub fn get_slice(
     }
     // Read the data frame from the version path
     let version_path = util::fs::version_path_from_hash(repo, file_node.hash.to_string());
-    let df = tabular::read_df_with_extension(version_path, file_node.extension, opts)?;
+    let version_path = util::encryption::plain_path(repo, &version_path)?;
+    // The filter, sort, columns and page are pushed down into a lazy scan of the file, so a
+    // page of a large parquet file does not read the whole file
+    let df = tabular::read_df_with_extension(version_path.path(), &file_node.extension, opts)?;
 
-    // Check what the view height is
+    // Check what the view height is, the page only has some of the rows that matched
     let view_height = if opts.has_filter_transform() {
-        df.height()
+        tabular::count_df_with_extension(version_path.path(), &file_node.extension, opts)?
     } else {
         data_frame_size.height
     };
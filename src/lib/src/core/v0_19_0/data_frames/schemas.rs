@@ -22,7 +22,7 @@ use crate::error::OxenError;
 use crate::model::merkle_tree::node::EMerkleTreeNode;
 use crate::model::merkle_tree::node::MerkleTreeNode;
 use crate::model::metadata::generic_metadata::GenericMetadata;
-use crate::model::metadata::MetadataTabular;
+use crate::model::metadata::ColumnStats;
 use crate::model::MerkleHash;
 use crate::model::StagedEntryStatus;
 use crate::model::{Commit, LocalRepository, Schema};
@@ -87,6 +87,25 @@ pub fn get_by_path(
     Ok(Some(metadata.tabular.schema.clone()))
 }
 
+/// The column stats computed when the file was added, None if it was committed before stats existed
+pub fn get_column_stats_by_path(
+    repo: &LocalRepository,
+    commit: &Commit,
+    path: impl AsRef<Path>,
+) -> Result<Option<Vec<ColumnStats>>, OxenError> {
+    let path = path.as_ref();
+    let node = repositories::tree::get_file_by_path(repo, commit, path)?;
+    let Some(node) = node else {
+        return Err(OxenError::path_does_not_exist(path));
+    };
+
+    let Some(GenericMetadata::MetadataTabular(metadata)) = &node.metadata else {
+        return Err(OxenError::path_does_not_exist(path));
+    };
+
+    Ok(metadata.tabular.column_stats.clone())
+}
+
 /// Get a staged schema
 pub fn get_staged(
     repo: &LocalRepository,
@@ -154,11 +173,10 @@ pub fn restore_schema(
 
     let mut file_node = val.unwrap().node.file()?;
     if let Some(GenericMetadata::MetadataTabular(tabular_metadata)) = &file_node.metadata {
-        file_node.metadata = Some(GenericMetadata::MetadataTabular(MetadataTabular::new(
-            tabular_metadata.tabular.width,
-            tabular_metadata.tabular.height,
-            staged_schema,
-        )));
+        // Only the schema changed, the size and column stats still hold
+        let mut tabular_metadata = tabular_metadata.clone();
+        tabular_metadata.tabular.schema = staged_schema;
+        file_node.metadata = Some(GenericMetadata::MetadataTabular(tabular_metadata));
     } else {
         return Err(OxenError::basic_str("Expected tabular metadata"));
     }
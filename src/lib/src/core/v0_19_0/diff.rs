@@ -5,13 +5,17 @@ use crate::model::diff::diff_entry_status::DiffEntryStatus;
 use crate::model::diff::diff_file_node::DiffFileNode;
 use crate::model::diff::generic_diff_summary::GenericDiffSummary;
 use crate::model::diff::AddRemoveModifyCounts;
-use crate::model::merkle_tree::node::{DirNodeWithPath, FileNode, FileNodeWithDir};
+use crate::model::diff::DirSizeDiff;
+use crate::model::merkle_tree::node::{
+    DirNode, DirNodeWithPath, EMerkleTreeNode, FileNode, FileNodeWithDir,
+};
+use crate::model::MerkleHash;
 use crate::model::{Commit, DiffEntry, LocalRepository};
 use crate::opts::DFOpts;
 use crate::repositories;
 use crate::util;
 
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
@@ -78,12 +82,6 @@ pub fn list_diff_entries_in_dir_top_level(
         &dir,
     )?;
 
-    log::debug!("Collected {} dir_entries", dir_entries.len());
-    dir_entries = subset_dir_diffs_to_direct_children(dir_entries, dir.clone())?;
-    log::debug!("Filtered to {} dir_entries", dir_entries.len());
-
-    dir_entries.sort_by(|a, b| a.filename.cmp(&b.filename));
-
     let mut added_commit_entries: Vec<DiffFileNode> = vec![];
     collect_added_entries(&base_files, &head_files, &mut added_commit_entries, &dir)?;
 
@@ -93,16 +91,34 @@ pub fn list_diff_entries_in_dir_top_level(
     let mut modified_commit_entries: Vec<DiffFileNode> = vec![];
     collect_modified_entries(&base_files, &head_files, &mut modified_commit_entries, &dir)?;
 
+    let renamed_commit_entries =
+        collect_renamed_entries(&mut added_commit_entries, &mut removed_commit_entries);
+    collect_copied_entries(&base_files, &head_files, &mut added_commit_entries, &dir);
+    collect_renamed_directories(
+        &mut dir_entries,
+        &renamed_commit_entries,
+        &base_files,
+        &head_files,
+        &dir,
+    );
+
+    log::debug!("Collected {} dir_entries", dir_entries.len());
+    dir_entries = subset_dir_diffs_to_direct_children(dir_entries, dir.clone())?;
+    log::debug!("Filtered to {} dir_entries", dir_entries.len());
+
+    dir_entries.sort_by(|a, b| a.filename.cmp(&b.filename));
+
     let counts = AddRemoveModifyCounts {
         added: added_commit_entries.len(),
         removed: removed_commit_entries.len(),
-        modified: modified_commit_entries.len(),
+        modified: modified_commit_entries.len() + renamed_commit_entries.len(),
     };
 
     let mut combined: Vec<_> = added_commit_entries
         .into_iter()
         .chain(removed_commit_entries)
         .chain(modified_commit_entries)
+        .chain(renamed_commit_entries)
         .collect();
 
     // Filter out the entries that are not direct children of the provided dir
@@ -117,19 +133,7 @@ pub fn list_diff_entries_in_dir_top_level(
 
     let diff_entries: Vec<DiffEntry> = files
         .into_iter()
-        .map(|entry| {
-            DiffEntry::from_file_nodes(
-                repo,
-                entry.path,
-                entry.base_entry,
-                base_commit,
-                entry.head_entry,
-                head_commit,
-                entry.status,
-                false,
-                None,
-            )
-        })
+        .map(|entry| file_diff_entry(repo, entry, base_commit, head_commit))
         .collect::<Result<Vec<DiffEntry>, OxenError>>()?;
 
     let (dirs, _) =
@@ -257,15 +261,32 @@ pub fn list_diff_entries(
         dir,
         modified_commit_entries.len()
     );
+    let renamed_commit_entries =
+        collect_renamed_entries(&mut added_commit_entries, &mut removed_commit_entries);
+    collect_copied_entries(&base_files, &head_files, &mut added_commit_entries, &dir);
+    log::debug!(
+        "list_diff_entries dir: '{:?}' collected {} collect_renamed_entries",
+        dir,
+        renamed_commit_entries.len()
+    );
+    collect_renamed_directories(
+        &mut dir_entries,
+        &renamed_commit_entries,
+        &base_files,
+        &head_files,
+        &dir,
+    );
+    dir_entries.sort_by(|a, b| a.filename.cmp(&b.filename));
     let counts = AddRemoveModifyCounts {
         added: added_commit_entries.len(),
         removed: removed_commit_entries.len(),
-        modified: modified_commit_entries.len(),
+        modified: modified_commit_entries.len() + renamed_commit_entries.len(),
     };
     let mut combined: Vec<_> = added_commit_entries
         .into_iter()
         .chain(removed_commit_entries)
         .chain(modified_commit_entries)
+        .chain(renamed_commit_entries)
         .collect();
     combined.sort_by(|a, b| a.path.cmp(&b.path));
 
@@ -289,19 +310,7 @@ pub fn list_diff_entries(
     );
     let file_entries: Vec<DiffEntry> = files
         .into_iter()
-        .map(|entry| {
-            DiffEntry::from_file_nodes(
-                repo,
-                entry.path,
-                entry.base_entry,
-                base_commit,
-                entry.head_entry,
-                head_commit,
-                entry.status,
-                false,
-                None,
-            )
-        })
+        .map(|entry| file_diff_entry(repo, entry, base_commit, head_commit))
         .collect::<Result<Vec<DiffEntry>, OxenError>>()?;
 
     let (dirs, _) =
@@ -326,64 +335,69 @@ pub fn list_diff_entries(
     })
 }
 
+/// Directories that differ between the two commits. Both merkle trees are walked from
+/// the root one level at a time, skipping any subtree whose dir hash did not change.
 pub fn list_changed_dirs(
     repo: &LocalRepository,
     base_commit: &Commit,
     head_commit: &Commit,
 ) -> Result<Vec<(PathBuf, DiffEntryStatus)>, OxenError> {
-    let mut changed_dirs: Vec<(PathBuf, DiffEntryStatus)> = vec![];
-
-    let base_tree = CommitMerkleTree::from_commit(repo, base_commit)?;
-    let head_tree = CommitMerkleTree::from_commit(repo, head_commit)?;
+    let root = Path::new("");
+    let base_hash = CommitMerkleTree::dir_hashes(repo, base_commit)?
+        .get(root)
+        .cloned();
+    let head_hash = CommitMerkleTree::dir_hashes(repo, head_commit)?
+        .get(root)
+        .cloned();
 
-    let base_dirs = repositories::tree::list_all_dirs(&base_tree)?;
-    let head_dirs = repositories::tree::list_all_dirs(&head_tree)?;
+    let mut changed_dirs: Vec<(PathBuf, DiffEntryStatus)> = vec![];
+    r_list_changed_dirs(repo, root, base_hash, head_hash, &mut changed_dirs)?;
 
-    let added_dirs = head_dirs.difference(&base_dirs).collect::<HashSet<_>>();
-    let removed_dirs = base_dirs.difference(&head_dirs).collect::<HashSet<_>>();
-    let modified_or_unchanged_dirs = head_dirs.intersection(&base_dirs).collect::<HashSet<_>>();
+    // Sort by path for consistency
+    changed_dirs.sort_by(|a, b| a.0.cmp(&b.0));
 
-    for dir in added_dirs.iter() {
-        changed_dirs.push((dir.path.clone(), DiffEntryStatus::Added));
-    }
+    Ok(changed_dirs)
+}
 
-    for dir in removed_dirs.iter() {
-        changed_dirs.push((dir.path.clone(), DiffEntryStatus::Removed));
+fn r_list_changed_dirs(
+    repo: &LocalRepository,
+    path: &Path,
+    base_hash: Option<MerkleHash>,
+    head_hash: Option<MerkleHash>,
+    changed_dirs: &mut Vec<(PathBuf, DiffEntryStatus)>,
+) -> Result<(), OxenError> {
+    if base_hash == head_hash {
+        return Ok(());
     }
 
-    for dir in modified_or_unchanged_dirs.iter() {
-        let head_dir = head_tree.get_by_path(&dir.path)?;
-        let base_dir = base_tree.get_by_path(&dir.path)?;
-
-        let base_dir_hash = match base_dir {
-            Some(base_dir) => base_dir.hash,
-            None => {
-                return Err(OxenError::basic_str(
-                    format!("Could not calculate dir diff tree: base_dir_hash not found for dir {:?} in commit {}",
-                    dir, base_commit.id)
-                ))
-            }
-        };
+    let status = match (base_hash, head_hash) {
+        (None, Some(_)) => DiffEntryStatus::Added,
+        (Some(_), None) => DiffEntryStatus::Removed,
+        _ => DiffEntryStatus::Modified,
+    };
+    changed_dirs.push((path.to_path_buf(), status));
 
-        let head_dir_hash = match head_dir {
-            Some(head_dir) => head_dir.hash,
-            None => {
-                return Err(OxenError::basic_str(
-                    format!("Could not calculate dir diff tree: head_dir_hash not found for dir {:?} in commit {}",
-                    dir, head_commit.id)
-                ))
-            }
-        };
+    // Every dir under an added or removed dir is added or removed too
+    let base_dirs = match base_hash {
+        Some(hash) => read_dir_children(repo, &hash)?.2,
+        None => HashMap::new(),
+    };
+    let head_dirs = match head_hash {
+        Some(hash) => read_dir_children(repo, &hash)?.2,
+        None => HashMap::new(),
+    };
 
-        if base_dir_hash != head_dir_hash {
-            changed_dirs.push((dir.path.clone(), DiffEntryStatus::Modified));
-        }
+    let names: BTreeSet<&String> = base_dirs.keys().chain(head_dirs.keys()).collect();
+    for name in names {
+        r_list_changed_dirs(
+            repo,
+            &path.join(name),
+            base_dirs.get(name).cloned(),
+            head_dirs.get(name).cloned(),
+            changed_dirs,
+        )?;
     }
-
-    // Sort by path for consistency
-    changed_dirs.sort_by(|a, b| a.0.cmp(&b.0));
-
-    Ok(changed_dirs)
+    Ok(())
 }
 
 pub fn get_dir_diff_entry_with_summary(
@@ -442,6 +456,162 @@ pub fn get_dir_diff_entry_with_summary(
     }
 }
 
+/// Per directory file counts and byte deltas under `dir` between two commits, for quick
+/// release notes. Subtrees with matching hashes are skipped and sizes come from the dir
+/// nodes, so no file contents are read.
+pub fn list_dir_size_diffs(
+    repo: &LocalRepository,
+    dir: impl AsRef<Path>,
+    base_commit: &Commit,
+    head_commit: &Commit,
+) -> Result<Vec<DirSizeDiff>, OxenError> {
+    let dir = dir.as_ref();
+    let base_hash = CommitMerkleTree::dir_hashes(repo, base_commit)?
+        .get(dir)
+        .cloned();
+    let head_hash = CommitMerkleTree::dir_hashes(repo, head_commit)?
+        .get(dir)
+        .cloned();
+
+    if base_hash.is_none() && head_hash.is_none() {
+        return Err(OxenError::basic_str(format!(
+            "Could not calculate dir size diff: dir {:?} does not exist in either commit.",
+            dir
+        )));
+    }
+
+    let mut diffs: Vec<DirSizeDiff> = vec![];
+    r_list_dir_size_diffs(repo, dir, base_hash, head_hash, &mut diffs)?;
+    diffs.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(diffs)
+}
+
+// Returns the recursive file counts of the dir so the parent can roll them up
+fn r_list_dir_size_diffs(
+    repo: &LocalRepository,
+    path: &Path,
+    base_hash: Option<MerkleHash>,
+    head_hash: Option<MerkleHash>,
+    diffs: &mut Vec<DirSizeDiff>,
+) -> Result<AddRemoveModifyCounts, OxenError> {
+    let mut counts = AddRemoveModifyCounts {
+        added: 0,
+        removed: 0,
+        modified: 0,
+    };
+
+    if base_hash == head_hash {
+        return Ok(counts);
+    }
+
+    // A dir that only exists on one side is summarized by its own node
+    let (base_hash, head_hash) = match (base_hash, head_hash) {
+        (Some(base_hash), Some(head_hash)) => (base_hash, head_hash),
+        (Some(base_hash), None) => {
+            let base_dir = read_dir_node(repo, &base_hash)?;
+            counts.removed = base_dir.num_files() as usize;
+            diffs.push(DirSizeDiff::from_dir_nodes(
+                path,
+                Some(&base_dir),
+                None,
+                counts.clone(),
+            ));
+            return Ok(counts);
+        }
+        (None, Some(head_hash)) => {
+            let head_dir = read_dir_node(repo, &head_hash)?;
+            counts.added = head_dir.num_files() as usize;
+            diffs.push(DirSizeDiff::from_dir_nodes(
+                path,
+                None,
+                Some(&head_dir),
+                counts.clone(),
+            ));
+            return Ok(counts);
+        }
+        (None, None) => return Ok(counts),
+    };
+
+    let (base_dir, base_files, base_dirs) = read_dir_children(repo, &base_hash)?;
+    let (head_dir, head_files, head_dirs) = read_dir_children(repo, &head_hash)?;
+
+    for (name, base_file) in &base_files {
+        match head_files.get(name) {
+            Some(head_file) if head_file.hash != base_file.hash => counts.modified += 1,
+            Some(_) => {}
+            None => counts.removed += 1,
+        }
+    }
+    counts.added += head_files
+        .keys()
+        .filter(|name| !base_files.contains_key(*name))
+        .count();
+
+    let names: BTreeSet<&String> = base_dirs.keys().chain(head_dirs.keys()).collect();
+    for name in names {
+        let child_counts = r_list_dir_size_diffs(
+            repo,
+            &path.join(name),
+            base_dirs.get(name).cloned(),
+            head_dirs.get(name).cloned(),
+            diffs,
+        )?;
+        counts.added += child_counts.added;
+        counts.removed += child_counts.removed;
+        counts.modified += child_counts.modified;
+    }
+
+    diffs.push(DirSizeDiff::from_dir_nodes(
+        path,
+        Some(&base_dir),
+        Some(&head_dir),
+        counts.clone(),
+    ));
+    Ok(counts)
+}
+
+fn read_dir_node(repo: &LocalRepository, hash: &MerkleHash) -> Result<DirNode, OxenError> {
+    let Some(node) = CommitMerkleTree::read_node(repo, hash, false)? else {
+        return Err(OxenError::basic_str(format!(
+            "Merkle tree dir node not found: {}",
+            hash
+        )));
+    };
+    node.dir()
+}
+
+// The dir node, its files by name and its child dir hashes by name
+type DirChildren = (
+    DirNode,
+    HashMap<String, FileNode>,
+    HashMap<String, MerkleHash>,
+);
+
+fn read_dir_children(repo: &LocalRepository, hash: &MerkleHash) -> Result<DirChildren, OxenError> {
+    // Depth 2 to get the VNodes and the files and dirs under them
+    let Some(node) = CommitMerkleTree::read_depth(repo, hash, 2)? else {
+        return Err(OxenError::basic_str(format!(
+            "Merkle tree dir node not found: {}",
+            hash
+        )));
+    };
+
+    let mut files: HashMap<String, FileNode> = HashMap::new();
+    let mut dirs: HashMap<String, MerkleHash> = HashMap::new();
+    for child in CommitMerkleTree::node_files_and_folders(&node)? {
+        match &child.node {
+            EMerkleTreeNode::File(file_node) => {
+                files.insert(file_node.name.clone(), file_node.clone());
+            }
+            EMerkleTreeNode::Directory(dir_node) => {
+                dirs.insert(dir_node.name.clone(), child.hash);
+            }
+            _ => {}
+        }
+    }
+    Ok((node.dir()?, files, dirs))
+}
+
 pub fn diff_entries(
     repo: &LocalRepository,
     file_path: impl AsRef<Path>,
@@ -623,6 +793,7 @@ fn collect_added_entries(
             base_entry: None,
             head_entry: Some(head_entry.file_node.to_owned()),
             status: DiffEntryStatus::Added,
+            source_path: None,
         });
     }
     Ok(())
@@ -644,6 +815,7 @@ fn collect_removed_entries(
                 base_entry: Some(base_entry.file_node.to_owned()),
                 head_entry: None,
                 status: DiffEntryStatus::Removed,
+                source_path: None,
             });
         }
     }
@@ -678,6 +850,7 @@ fn collect_modified_entries(
                     base_entry: Some(base_entry.file_node.to_owned()),
                     head_entry: Some(head_entry.file_node.to_owned()),
                     status: DiffEntryStatus::Modified,
+                    source_path: None,
                 });
             }
         }
@@ -685,6 +858,200 @@ fn collect_modified_entries(
     Ok(())
 }
 
+// Pair up removed and added files with the same hash, they were renamed
+fn collect_renamed_entries(
+    added_entries: &mut Vec<DiffFileNode>,
+    removed_entries: &mut Vec<DiffFileNode>,
+) -> Vec<DiffFileNode> {
+    // Sort so that duplicate contents pair up the same way every time
+    added_entries.sort_by(|a, b| a.path.cmp(&b.path));
+    removed_entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let mut removed_by_hash: HashMap<MerkleHash, VecDeque<DiffFileNode>> = HashMap::new();
+    for entry in removed_entries.drain(..) {
+        let hash = entry.base_entry.as_ref().map(|node| node.hash);
+        match hash {
+            Some(hash) => removed_by_hash.entry(hash).or_default().push_back(entry),
+            None => removed_entries.push(entry),
+        }
+    }
+
+    let mut renamed = vec![];
+    let mut still_added = vec![];
+    for entry in added_entries.drain(..) {
+        let base = entry
+            .head_entry
+            .as_ref()
+            .and_then(|node| removed_by_hash.get_mut(&node.hash))
+            .and_then(|removed| removed.pop_front());
+        match base {
+            Some(base) => renamed.push(DiffFileNode {
+                status: DiffEntryStatus::Renamed,
+                path: entry.path,
+                head_entry: entry.head_entry,
+                base_entry: base.base_entry,
+                source_path: Some(base.path),
+            }),
+            None => still_added.push(entry),
+        }
+    }
+
+    *added_entries = still_added;
+    removed_entries.extend(removed_by_hash.into_values().flatten());
+    renamed
+}
+
+// Added files with the same contents as a file that is still in HEAD were copied
+fn collect_copied_entries(
+    base_entries: &HashSet<FileNodeWithDir>,
+    head_entries: &HashSet<FileNodeWithDir>,
+    added_entries: &mut [DiffFileNode],
+    base_path: impl AsRef<Path>,
+) {
+    let base_path = base_path.as_ref();
+    let mut sources: HashMap<MerkleHash, (PathBuf, &FileNode)> = HashMap::new();
+    for base_entry in base_entries {
+        if !head_entries.contains(base_entry) {
+            continue;
+        }
+        let path = base_path.join(base_entry.dir.join(&base_entry.file_node.name));
+        // Pick the same source every time if there are several
+        let source = sources
+            .entry(base_entry.file_node.hash)
+            .or_insert((path.clone(), &base_entry.file_node));
+        if path < source.0 {
+            *source = (path, &base_entry.file_node);
+        }
+    }
+
+    for entry in added_entries.iter_mut() {
+        let Some(hash) = entry.head_entry.as_ref().map(|node| node.hash) else {
+            continue;
+        };
+        if let Some((path, node)) = sources.get(&hash) {
+            entry.status = DiffEntryStatus::Copied;
+            entry.base_entry = Some((*node).to_owned());
+            entry.source_path = Some(path.to_owned());
+        }
+    }
+}
+
+// A removed directory was renamed if every file in it was renamed into the same
+// relative path under an added directory that has nothing else in it
+fn collect_renamed_directories(
+    dir_entries: &mut Vec<DiffEntry>,
+    renamed_entries: &[DiffFileNode],
+    base_entries: &HashSet<FileNodeWithDir>,
+    head_entries: &HashSet<FileNodeWithDir>,
+    base_path: impl AsRef<Path>,
+) {
+    let base_path = base_path.as_ref();
+
+    // Walk up from each renamed file while the names still match,
+    // every level is a directory that could have been renamed
+    let mut num_moved: HashMap<(PathBuf, PathBuf), usize> = HashMap::new();
+    for entry in renamed_entries {
+        let Some(source_path) = &entry.source_path else {
+            continue;
+        };
+        let (mut base, mut head) = (source_path.as_path(), entry.path.as_path());
+        while let (Some(base_dir), Some(head_dir)) = (base.parent(), head.parent()) {
+            if base.file_name() != head.file_name() {
+                break;
+            }
+            *num_moved
+                .entry((base_dir.to_path_buf(), head_dir.to_path_buf()))
+                .or_insert(0) += 1;
+            (base, head) = (base_dir, head_dir);
+        }
+    }
+
+    let base_counts = count_files_in_dirs(base_entries, base_path);
+    let head_counts = count_files_in_dirs(head_entries, base_path);
+    let renamed_dirs: HashMap<PathBuf, PathBuf> = num_moved
+        .into_iter()
+        .filter(|((base_dir, head_dir), count)| {
+            base_counts.get(base_dir) == Some(count) && head_counts.get(head_dir) == Some(count)
+        })
+        .map(|((base_dir, head_dir), _)| (head_dir, base_dir))
+        .collect();
+    if renamed_dirs.is_empty() {
+        return;
+    }
+
+    let added = DiffEntryStatus::Added.to_string();
+    let removed = DiffEntryStatus::Removed.to_string();
+    let mut removed_dirs: HashMap<PathBuf, DiffEntry> = HashMap::new();
+    let mut others = vec![];
+    for entry in dir_entries.drain(..) {
+        if entry.status == removed {
+            removed_dirs.insert(PathBuf::from(&entry.filename), entry);
+        } else {
+            others.push(entry);
+        }
+    }
+
+    for mut entry in others {
+        if entry.status == added {
+            let base = renamed_dirs
+                .get(Path::new(&entry.filename))
+                .and_then(|base_dir| removed_dirs.remove(base_dir));
+            if let Some(base) = base {
+                entry.status = DiffEntryStatus::Renamed.to_string();
+                entry.base_entry = base.base_entry;
+                entry.base_resource = base.base_resource;
+            }
+        }
+        dir_entries.push(entry);
+    }
+    dir_entries.extend(removed_dirs.into_values());
+}
+
+// Number of files in each directory, including its subdirectories
+fn count_files_in_dirs(
+    entries: &HashSet<FileNodeWithDir>,
+    base_path: &Path,
+) -> HashMap<PathBuf, usize> {
+    let mut counts: HashMap<PathBuf, usize> = HashMap::new();
+    for entry in entries {
+        for dir in base_path.join(&entry.dir).ancestors() {
+            *counts.entry(dir.to_path_buf()).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+fn file_diff_entry(
+    repo: &LocalRepository,
+    entry: DiffFileNode,
+    base_commit: &Commit,
+    head_commit: &Commit,
+) -> Result<DiffEntry, OxenError> {
+    let mut diff_entry = DiffEntry::from_file_nodes(
+        repo,
+        entry.path,
+        entry.base_entry,
+        base_commit,
+        entry.head_entry,
+        head_commit,
+        entry.status,
+        false,
+        None,
+    )?;
+
+    // Point the base resource at where the contents came from
+    if let Some(base_path) = entry.source_path {
+        if let Some(resource) = diff_entry.base_resource.as_mut() {
+            resource.resource = PathBuf::from(&base_commit.id).join(&base_path);
+            resource.path = base_path;
+        }
+        if let Some(base_entry) = diff_entry.base_entry.as_mut() {
+            base_entry.resource.clone_from(&diff_entry.base_resource);
+        }
+    }
+    Ok(diff_entry)
+}
+
 fn subset_dir_diffs_to_direct_children(
     entries: Vec<DiffEntry>,
     dir: PathBuf,
@@ -700,7 +1067,10 @@ fn subset_dir_diffs_to_direct_children(
 
         let status = DiffEntryStatus::from_str(&entry.status)?;
         let relevant_entry = match status {
-            DiffEntryStatus::Added | DiffEntryStatus::Modified => entry.head_entry.as_ref(),
+            DiffEntryStatus::Added
+            | DiffEntryStatus::Modified
+            | DiffEntryStatus::Renamed
+            | DiffEntryStatus::Copied => entry.head_entry.as_ref(),
             DiffEntryStatus::Removed => entry.base_entry.as_ref(),
         };
 
@@ -730,7 +1100,10 @@ fn subset_file_diffs_to_direct_children(
 
     for entry in entries {
         let relevant_entry = match entry.status {
-            DiffEntryStatus::Added | DiffEntryStatus::Modified => entry.head_entry.as_ref(),
+            DiffEntryStatus::Added
+            | DiffEntryStatus::Modified
+            | DiffEntryStatus::Renamed
+            | DiffEntryStatus::Copied => entry.head_entry.as_ref(),
             DiffEntryStatus::Removed => entry.base_entry.as_ref(),
         };
 
@@ -12,7 +12,9 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use crate::core;
+use crate::core::v0_19_0::quarantine;
 use crate::model::MerkleHash;
+use crate::util;
 use std::str::FromStr;
 
 pub async fn download_dir(
@@ -42,6 +44,7 @@ pub async fn download_dir(
     // Recursively pull entries
     r_download_entries(
         remote_repo,
+        &tmp_repo,
         &tmp_repo.path.join(&entry.filename),
         &dir_node,
         &directory,
@@ -54,6 +57,7 @@ pub async fn download_dir(
 
 async fn r_download_entries(
     remote_repo: &RemoteRepository,
+    repo: &LocalRepository,
     local_repo_path: &Path,
     node: &MerkleTreeNode,
     directory: &Path,
@@ -71,6 +75,7 @@ async fn r_download_entries(
         if child.has_children() {
             Box::pin(r_download_entries(
                 remote_repo,
+                repo,
                 local_repo_path,
                 child,
                 &new_directory,
@@ -95,16 +100,59 @@ async fn r_download_entries(
                 }
             }
 
+            // Files we already have were downloaded with another child of the same node
+            entries.retain(|entry| !local_repo_path.join(entry.path()).exists());
             log::debug!("downloading {} entries to working dir", entries.len());
-            core::v0_10_0::index::puller::pull_entries_to_working_dir(
+            if entries.is_empty() {
+                continue;
+            }
+
+            // Download next to the repository, and move each file in place once it
+            // matches its hash
+            let tmp_dir = util::fs::oxen_hidden_dir(&repo.path)
+                .join("tmp")
+                .join(uuid::Uuid::new_v4().to_string());
+            util::fs::create_dir_all(&tmp_dir)?;
+            let result = move_verified_entries(
                 remote_repo,
+                repo,
                 &entries,
+                &tmp_dir,
                 local_repo_path,
                 pull_progress,
             )
-            .await?;
+            .await;
+            util::fs::remove_dir_all(&tmp_dir)?;
+            result?;
         }
     }
 
     Ok(())
 }
+
+async fn move_verified_entries(
+    remote_repo: &RemoteRepository,
+    repo: &LocalRepository,
+    entries: &[Entry],
+    tmp_dir: &Path,
+    local_repo_path: &Path,
+    pull_progress: &Arc<PullProgress>,
+) -> Result<(), OxenError> {
+    core::v0_10_0::index::puller::pull_entries_to_working_dir(
+        remote_repo,
+        entries,
+        tmp_dir,
+        pull_progress,
+    )
+    .await?;
+    for entry in entries {
+        let received = tmp_dir.join(entry.path());
+        quarantine::verify_received(&repo.path, &entry.path(), &entry.hash(), &received)?;
+        let dst = local_repo_path.join(entry.path());
+        if let Some(parent) = dst.parent() {
+            util::fs::create_dir_all(parent)?;
+        }
+        util::fs::rename(&received, &dst)?;
+    }
+    Ok(())
+}
@@ -116,7 +116,7 @@ pub fn list_directory(
     log::debug!("list_directory got {} entries", entries.len());
 
     let (entries, pagination) = util::paginate(entries, page, page_size);
-    let metadata: Option<MetadataDir> = Some(MetadataDir::new(dir_node.data_types()));
+    let metadata: Option<MetadataDir> = Some(MetadataDir::from_dir_node(dir_node));
 
     Ok(PaginatedDirEntries {
         dir: dir_entry,
@@ -224,34 +224,31 @@ fn dir_node_to_metadata_entry(
         return Ok(None);
     };
 
-    if let std::collections::hash_map::Entry::Vacant(e) =
-        found_commits.entry(dir_node.last_commit_id)
-    {
-        let commit = repositories::commits::get_by_hash(repo, &dir_node.last_commit_id)?.ok_or(
-            OxenError::commit_id_does_not_exist(dir_node.last_commit_id.to_string()),
-        )?;
-        e.insert(commit);
-    }
-
-    let commit = found_commits.get(&dir_node.last_commit_id).unwrap();
     let mut parsed_resource = parsed_resource.clone();
     if should_append_resource {
         parsed_resource.resource = parsed_resource.resource.join(&dir_node.name);
         parsed_resource.path = parsed_resource.path.join(&dir_node.name);
     }
+    let commit = latest_commit(
+        repo,
+        node,
+        dir_node.last_commit_id,
+        &parsed_resource,
+        found_commits,
+    )?;
 
     Ok(Some(MetadataEntry {
         filename: dir_node.name.clone(),
         hash: dir_node.hash.to_string(),
         is_dir: true,
-        latest_commit: Some(commit.clone()),
+        latest_commit: Some(commit),
         resource: Some(parsed_resource.clone()),
         size: dir_node.num_bytes,
         data_type: EntryDataType::Dir,
         mime_type: "inode/directory".to_string(),
         extension: "".to_string(),
-        metadata: Some(GenericMetadata::MetadataDir(MetadataDir::new(
-            dir_node.data_types(),
+        metadata: Some(GenericMetadata::MetadataDir(MetadataDir::from_dir_node(
+            dir_node,
         ))),
         is_queryable: None,
     }))
@@ -267,16 +264,6 @@ fn file_node_to_metadata_entry(
         return Ok(None);
     };
 
-    if let std::collections::hash_map::Entry::Vacant(e) =
-        found_commits.entry(file_node.last_commit_id)
-    {
-        let commit = repositories::commits::get_by_hash(repo, &file_node.last_commit_id)?.ok_or(
-            OxenError::commit_id_does_not_exist(file_node.last_commit_id.to_string()),
-        )?;
-        e.insert(commit);
-    }
-
-    let commit = found_commits.get(&file_node.last_commit_id).unwrap();
     let data_type = &file_node.data_type;
 
     let mut parsed_resource = parsed_resource.clone();
@@ -288,6 +275,13 @@ fn file_node_to_metadata_entry(
         parsed_resource.resource = parsed_resource.resource.join(&file_node.name);
         parsed_resource.path = parsed_resource.path.join(&file_node.name);
     }
+    let commit = latest_commit(
+        repo,
+        node,
+        file_node.last_commit_id,
+        &parsed_resource,
+        found_commits,
+    )?;
 
     let is_indexed = if *data_type == EntryDataType::Tabular {
         Some(
@@ -303,7 +297,7 @@ fn file_node_to_metadata_entry(
         filename: file_node.name.clone(),
         hash: file_node.hash.to_string(),
         is_dir: false,
-        latest_commit: Some(commit.clone()),
+        latest_commit: Some(commit),
         resource: Some(parsed_resource.clone()),
         size: file_node.num_bytes,
         data_type: file_node.data_type.clone(),
@@ -314,6 +308,38 @@ fn file_node_to_metadata_entry(
     }))
 }
 
+// The last commit that changed the node at the resource path, looked up once per id.
+// Content addressed trees leave the id off their nodes, so it comes from the history
+// of the resource's commit.
+fn latest_commit(
+    repo: &LocalRepository,
+    node: &MerkleTreeNode,
+    last_commit_id: MerkleHash,
+    parsed_resource: &ParsedResource,
+    found_commits: &mut HashMap<MerkleHash, Commit>,
+) -> Result<Commit, OxenError> {
+    let last_commit_id = match &parsed_resource.commit {
+        Some(commit) if last_commit_id == MerkleHash::new(0) => {
+            core::v0_19_0::commits::last_commit_id_for_node(
+                repo,
+                commit,
+                &parsed_resource.path,
+                node,
+            )?
+            .unwrap_or(last_commit_id)
+        }
+        _ => last_commit_id,
+    };
+
+    if let std::collections::hash_map::Entry::Vacant(e) = found_commits.entry(last_commit_id) {
+        let commit = repositories::commits::get_by_hash(repo, &last_commit_id)?.ok_or(
+            OxenError::commit_id_does_not_exist(last_commit_id.to_string()),
+        )?;
+        e.insert(commit);
+    }
+    Ok(found_commits[&last_commit_id].clone())
+}
+
 fn p_dir_entries(
     repo: &LocalRepository,
     node: &MerkleTreeNode,
@@ -1,3 +1,4 @@
+use reqwest::Client;
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
@@ -6,6 +7,7 @@ use std::sync::Arc;
 use crate::api;
 use crate::constants::OXEN_HIDDEN_DIR;
 use crate::core;
+use crate::core::events::{self, Operation, OperationTimer};
 use crate::core::refs::RefWriter;
 use crate::error::OxenError;
 use crate::model::entry::commit_entry::Entry;
@@ -13,8 +15,11 @@ use crate::model::merkle_tree::node::{EMerkleTreeNode, FileNodeWithDir, MerkleTr
 use crate::model::{Branch, Commit, CommitEntry};
 use crate::model::{LocalRepository, MerkleHash, RemoteBranch, RemoteRepository};
 use crate::repositories;
+use crate::util;
 
+use crate::core::v0_19_0::batch_transfer;
 use crate::core::v0_19_0::index::commit_merkle_tree::CommitMerkleTree;
+use crate::core::v0_19_0::quarantine;
 use crate::core::v0_19_0::structs::pull_progress::PullProgress;
 
 pub async fn fetch_remote_branch(
@@ -23,14 +28,41 @@ pub async fn fetch_remote_branch(
     remote_branch: &RemoteBranch,
     all: bool,
 ) -> Result<(), OxenError> {
+    let branch = fetch_remote_tracking_branch(repo, remote_repo, remote_branch, all).await?;
+
+    // Write the new branch commit id to the local repo
+    log::debug!(
+        "Setting branch {} commit id to {}",
+        branch.name,
+        branch.commit_id
+    );
+    let ref_writer = RefWriter::new(repo)?;
+    ref_writer.set_branch_commit_id(&branch.name, &branch.commit_id)?;
+    Ok(())
+}
+
+/// Download the commits, merkle tree nodes and version files of a remote branch and
+/// point its remote-tracking ref at the remote head. Local branches and the working
+/// dir are left alone. Returns the branch as it is on the remote.
+pub async fn fetch_remote_tracking_branch(
+    repo: &LocalRepository,
+    remote_repo: &RemoteRepository,
+    remote_branch: &RemoteBranch,
+    all: bool,
+) -> Result<Branch, OxenError> {
     log::debug!(
         "fetching remote branch {} --all {}",
         remote_branch.branch,
         all
     );
+    let tracking_branch = remote_branch;
 
     // Start the timer
     let start = std::time::Instant::now();
+    let timer = OperationTimer::start(
+        Operation::Fetch,
+        format!("{} {}", remote_branch.remote, remote_branch.branch),
+    );
 
     // Keep track of how many bytes we have downloaded
     let pull_progress = Arc::new(PullProgress::new());
@@ -47,10 +79,11 @@ pub async fn fetch_remote_branch(
     let repo_hidden_dir = repo.path.join(OXEN_HIDDEN_DIR);
     if let Some(head_commit) = repositories::commits::head_commit_maybe(repo)? {
         if head_commit.id == remote_branch.commit_id {
-            println!("Repository is up to date.");
-            let ref_writer = RefWriter::new(repo)?;
-            ref_writer.set_branch_commit_id(&remote_branch.name, &remote_branch.commit_id)?;
-            return Ok(());
+            events::info(Operation::Fetch, "Repository is up to date.");
+            core::refs::remote_refs::set(repo, tracking_branch, &remote_branch.commit_id)?;
+            core::commit_sync_status::mark_commit_as_pushed(repo, &remote_branch.commit_id)?;
+            timer.finish();
+            return Ok(remote_branch);
         }
 
         // If head is not on the remote server, that means we are ahead of the remote branch
@@ -121,13 +154,17 @@ pub async fn fetch_remote_branch(
         missing_entries.len() as u64,
         total_bytes,
     ));
-    core::v0_10_0::index::puller::pull_entries_to_versions_dir(
+    let client = batch_transfer::batch_client(remote_repo).await?;
+    batch_transfer::pull_entries(
         remote_repo,
+        client.as_ref(),
         &missing_entries,
         &repo.path,
         &pull_progress,
     )
     .await?;
+    quarantine::verify_pulled_entries(repo, &missing_entries)?;
+    store_pulled_entries(repo, &missing_entries)?;
 
     // If we fetched the data, we're no longer shallow
     repo.write_is_shallow(false)?;
@@ -137,26 +174,31 @@ pub async fn fetch_remote_branch(
         core::commit_sync_status::mark_commit_as_synced(repo, &commit)?;
     }
 
-    // Write the new branch commit id to the local repo
+    // Remember where the remote branch is
     log::debug!(
-        "Setting branch {} commit id to {}",
-        remote_branch.name,
+        "Setting remote-tracking ref {}/{} to {}",
+        tracking_branch.remote,
+        tracking_branch.branch,
         remote_branch.commit_id
     );
-    let ref_writer = RefWriter::new(repo)?;
-    ref_writer.set_branch_commit_id(&remote_branch.name, &remote_branch.commit_id)?;
+    core::refs::remote_refs::set(repo, tracking_branch, &remote_branch.commit_id)?;
+    core::commit_sync_status::mark_commit_as_pushed(repo, &remote_branch.commit_id)?;
 
     pull_progress.finish();
     let duration = std::time::Duration::from_millis(start.elapsed().as_millis() as u64);
 
-    println!(
-        "🐂 oxen downloaded {} ({} files) in {}",
-        bytesize::ByteSize::b(pull_progress.get_num_bytes()),
-        pull_progress.get_num_files(),
-        humantime::format_duration(duration)
+    events::info(
+        Operation::Fetch,
+        format!(
+            "🐂 oxen downloaded {} ({} files) in {}",
+            bytesize::ByteSize::b(pull_progress.get_num_bytes()),
+            pull_progress.get_num_files(),
+            humantime::format_duration(duration)
+        ),
     );
+    timer.finish();
 
-    Ok(())
+    Ok(remote_branch)
 }
 
 fn collect_missing_entries(
@@ -293,11 +335,15 @@ pub async fn maybe_fetch_missing_entries(
     // Keep track of how many bytes we have downloaded
     let pull_progress = Arc::new(PullProgress::new());
 
+    // Check for batch support once, rather than for every vnode
+    let client = batch_transfer::batch_client(&remote_repo).await?;
+
     // Recursively download the entries
     let directory = PathBuf::from("");
     r_download_entries(
         repo,
         &remote_repo,
+        client.as_ref(),
         &commit_merkle_tree.root,
         &directory,
         &pull_progress,
@@ -310,6 +356,7 @@ pub async fn maybe_fetch_missing_entries(
 async fn r_download_entries(
     repo: &LocalRepository,
     remote_repo: &RemoteRepository,
+    client: Option<&Client>,
     node: &MerkleTreeNode,
     directory: &Path,
     pull_progress: &Arc<PullProgress>,
@@ -324,6 +371,7 @@ async fn r_download_entries(
             Box::pin(r_download_entries(
                 repo,
                 remote_repo,
+                client,
                 child,
                 &new_directory,
                 pull_progress,
@@ -354,13 +402,16 @@ async fn r_download_entries(
             }
         }
 
-        core::v0_10_0::index::puller::pull_entries_to_versions_dir(
+        batch_transfer::pull_entries(
             remote_repo,
+            client,
             &missing_entries,
             &repo.path,
             pull_progress,
         )
         .await?;
+        quarantine::verify_pulled_entries(repo, &missing_entries)?;
+        store_pulled_entries(repo, &missing_entries)?;
     }
 
     if let EMerkleTreeNode::Commit(commit_node) = &node.node {
@@ -372,3 +423,18 @@ async fn r_download_entries(
 
     Ok(())
 }
+
+// Downloads are plain, compress and encrypt them the way the repository stores its
+// version files
+fn store_pulled_entries(repo: &LocalRepository, entries: &[Entry]) -> Result<(), OxenError> {
+    if util::encryption::Encoding::for_repo(repo).is_plain() {
+        return Ok(());
+    }
+    for entry in entries {
+        let version_path = util::fs::version_path_for_entry(repo, entry);
+        if version_path.exists() {
+            util::encryption::store_in_place(repo, &version_path)?;
+        }
+    }
+    Ok(())
+}
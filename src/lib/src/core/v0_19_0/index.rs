@@ -1,7 +1,11 @@
 pub mod commit_merkle_tree;
 pub mod commit_writer;
 pub mod file_chunker;
+pub mod last_commits;
 pub mod merkle_node_db;
+pub mod node_pack;
 pub mod restore;
+pub mod stat_cache;
+pub mod tree_cache;
 pub use commit_merkle_tree::CommitMerkleTree;
 pub use merkle_node_db::MerkleNodeDB;
@@ -7,6 +7,7 @@ use rocksdb::{DBWithThreadMode, IteratorMode, MultiThreaded};
 use crate::constants::{DIR_HASHES_DIR, HISTORY_DIR};
 use crate::core::db;
 
+use crate::core::v0_19_0::index::tree_cache;
 use crate::core::v0_19_0::index::MerkleNodeDB;
 
 use crate::model::merkle_tree::node::EMerkleTreeNode;
@@ -20,6 +21,7 @@ use crate::model::{LocalRepository, MerkleHash, MerkleTreeNodeType};
 use crate::util;
 
 use std::str::FromStr;
+use std::sync::Arc;
 
 pub struct CommitMerkleTree {
     pub root: MerkleTreeNode,
@@ -164,6 +166,11 @@ impl CommitMerkleTree {
         recurse: bool,
     ) -> Result<Option<MerkleTreeNode>, OxenError> {
         // log::debug!("Read node hash [{}]", hash);
+        let cache_depth = if recurse { tree_cache::RECURSIVE } else { 0 };
+        if let Some(node) = tree_cache::get(repo, hash, cache_depth) {
+            return Ok(Some(Arc::unwrap_or_clone(node)));
+        }
+
         if !MerkleNodeDB::exists(repo, hash) {
             // log::debug!("read_node merkle node db does not exist for hash: {}", hash);
             return Ok(None);
@@ -173,6 +180,7 @@ impl CommitMerkleTree {
         let mut node_db = MerkleNodeDB::open_read_only(repo, hash)?;
         CommitMerkleTree::read_children_from_node(repo, &mut node_db, &mut node, recurse)?;
         // log::debug!("read_node done: {:?} recurse: {}", node.hash, recurse);
+        tree_cache::put(repo, cache_depth, &node);
         Ok(Some(node))
     }
 
@@ -182,6 +190,12 @@ impl CommitMerkleTree {
         depth: i32,
     ) -> Result<Option<MerkleTreeNode>, OxenError> {
         // log::debug!("Read depth {} node hash [{}]", depth, hash);
+        // Anything below 1 only loads the direct children
+        let cache_depth = depth.max(0);
+        if let Some(node) = tree_cache::get(repo, hash, cache_depth) {
+            return Ok(Some(Arc::unwrap_or_clone(node)));
+        }
+
         if !MerkleNodeDB::exists(repo, hash) {
             // log::debug!(
             //     "read_depth merkle node db does not exist for hash: {}",
@@ -195,6 +209,7 @@ impl CommitMerkleTree {
 
         CommitMerkleTree::read_children_until_depth(repo, &mut node_db, &mut node, depth)?;
         // log::debug!("Read depth {} node done: {:?}", depth, node.hash);
+        tree_cache::put(repo, cache_depth, &node);
         Ok(Some(node))
     }
 
@@ -203,6 +218,10 @@ impl CommitMerkleTree {
         repo: &LocalRepository,
         commit: &Commit,
     ) -> Result<HashMap<PathBuf, MerkleHash>, OxenError> {
+        if let Some(dir_hashes) = tree_cache::get_dir_hashes(repo, &commit.id) {
+            return Ok(dir_hashes.as_ref().clone());
+        }
+
         let node_db_dir = CommitMerkleTree::dir_hash_db_path(repo, commit);
         let opts = db::key_val::opts::default();
         let node_db: DBWithThreadMode<MultiThreaded> =
@@ -225,6 +244,7 @@ impl CommitMerkleTree {
             }
         }
         // log::debug!("read dir_hashes: {:?}", dir_hashes);
+        tree_cache::put_dir_hashes(repo, &commit.id, &dir_hashes);
         Ok(dir_hashes)
     }
 
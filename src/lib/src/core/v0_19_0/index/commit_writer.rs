@@ -17,7 +17,9 @@ use crate::constants::ORIG_HEAD_FILE;
 use crate::constants::{HEAD_FILE, STAGED_DIR};
 use crate::core::db;
 use crate::core::db::key_val::str_val_db;
+use crate::core::events::{self, Operation, OperationTimer};
 use crate::core::refs::RefWriter;
+use crate::core::v0_19_0::index::last_commits;
 use crate::core::v0_19_0::index::CommitMerkleTree;
 use crate::core::v0_19_0::index::MerkleNodeDB;
 use crate::core::v0_19_0::status;
@@ -25,10 +27,12 @@ use crate::core::v0_19_0::structs::StagedMerkleTreeNode;
 use crate::error::OxenError;
 use crate::model::merkle_tree::node::EMerkleTreeNode;
 use crate::model::merkle_tree::node::VNode;
+use crate::model::metadata::generic_metadata::GenericMetadata;
 use crate::model::MerkleHash;
 use crate::model::MerkleTreeNodeType;
 use crate::model::NewCommit;
 use crate::model::NewCommitBody;
+use crate::model::TMerkleTreeNode;
 use crate::model::User;
 use crate::model::{Commit, LocalRepository, StagedEntryStatus};
 
@@ -96,9 +100,11 @@ pub fn commit_with_cfg(
     let staged_db: DBWithThreadMode<SingleThreaded> =
         DBWithThreadMode::open(&opts, dunce::simplified(&staged_db_path))?;
 
+    let timer = OperationTimer::start(Operation::Commit, message);
     let commit_progress_bar = ProgressBar::new_spinner();
     commit_progress_bar.set_style(ProgressStyle::default_spinner());
     commit_progress_bar.enable_steady_tick(Duration::from_millis(100));
+    util::progress_bar::hide_if_sink(&commit_progress_bar);
 
     // Read all the staged entries
     let (dir_entries, total_changes) =
@@ -108,7 +114,7 @@ pub fn commit_with_cfg(
     log::debug!("got dir entries: {:?}", dir_entries.len());
 
     if dir_entries.is_empty() {
-        return Err(OxenError::basic_str("No changes to commit"));
+        return Err(OxenError::nothing_to_commit());
     }
 
     // let mut dir_tree = entries_to_dir_tree(&dir_entries)?;
@@ -158,18 +164,167 @@ pub fn commit_with_cfg(
     }
     ref_writer.set_head_commit_id(&commit_id)?;
 
+    // Content addressed nodes leave off their last commit, index it for this commit
+    if repo.content_addressed_tree() {
+        last_commits::write(repo, &commit)?;
+    }
+
     // Print that we finished
-    println!(
-        "🐂 commit {} in {}",
-        commit,
-        humantime::format_duration(Duration::from_millis(
-            start_time.elapsed().as_millis() as u64
-        ))
+    events::info(
+        Operation::Commit,
+        format!(
+            "🐂 commit {} in {}",
+            commit,
+            humantime::format_duration(Duration::from_millis(
+                start_time.elapsed().as_millis() as u64
+            ))
+        ),
     );
+    timer.finish();
 
     Ok(commit)
 }
 
+/// Replace the HEAD commit with one that has the new message and any staged changes.
+///
+/// The new commit keeps the author and parents of HEAD. If nothing is staged it reuses
+/// the tree of HEAD, otherwise the staged changes are committed on top of it.
+pub fn amend(repo: &LocalRepository, message: impl AsRef<str>) -> Result<Commit, OxenError> {
+    let message = message.as_ref();
+    if is_merge_commit(repo) {
+        return Err(OxenError::basic_str(
+            "Cannot amend while a merge is in progress",
+        ));
+    }
+    let Some(branch) = repositories::branches::current_branch(repo)? else {
+        return Err(OxenError::must_be_on_valid_branch());
+    };
+    let head_commit = repositories::commits::head_commit(repo)?;
+    let cfg = UserConfig {
+        name: head_commit.author.clone(),
+        email: head_commit.email.clone(),
+    };
+
+    match commit_with_cfg(repo, message, &cfg, Some(head_commit.parent_ids.clone())) {
+        Err(OxenError::NothingToCommit(_)) => {
+            let new_commit = NewCommitBody {
+                message: message.to_string(),
+                author: cfg.name,
+                email: cfg.email,
+            };
+            let commit = commit_reusing_tree(
+                repo,
+                &head_commit,
+                head_commit.parent_ids.clone(),
+                &branch.name,
+                &new_commit,
+            )?;
+            events::info(
+                Operation::Commit,
+                format!("🐂 amended {} -> {}", head_commit.id, commit.id),
+            );
+            Ok(commit)
+        }
+        result => result,
+    }
+}
+
+/// Commit the staged changes, or if nothing is staged create a commit on top of HEAD
+/// with the same tree, for example to record release notes for a dataset.
+pub fn commit_allow_empty(
+    repo: &LocalRepository,
+    message: impl AsRef<str>,
+) -> Result<Commit, OxenError> {
+    let message = message.as_ref();
+    let cfg = UserConfig::get()?;
+    match commit_with_cfg(repo, message, &cfg, None) {
+        Err(OxenError::NothingToCommit(_)) => {
+            let Some(branch) = repositories::branches::current_branch(repo)? else {
+                return Err(OxenError::must_be_on_valid_branch());
+            };
+            let Some(head_commit) = repositories::commits::head_commit_maybe(repo)? else {
+                return Err(OxenError::basic_str(
+                    "Cannot create an empty first commit, add a file first",
+                ));
+            };
+            let new_commit = NewCommitBody {
+                message: message.to_string(),
+                author: cfg.name,
+                email: cfg.email,
+            };
+            let commit = commit_reusing_tree(
+                repo,
+                &head_commit,
+                vec![head_commit.id.clone()],
+                &branch.name,
+                &new_commit,
+            )?;
+            events::info(Operation::Commit, format!("🐂 commit {} (empty)", commit));
+            Ok(commit)
+        }
+        result => result,
+    }
+}
+
+/// Write a commit with the given parents that shares the root directory of `tree_commit`,
+/// and point the branch at it
+fn commit_reusing_tree(
+    repo: &LocalRepository,
+    tree_commit: &Commit,
+    parent_ids: Vec<String>,
+    branch_name: &str,
+    new_commit: &NewCommitBody,
+) -> Result<Commit, OxenError> {
+    let tree_hash = tree_commit.hash()?;
+    let tree_node =
+        CommitMerkleTree::read_depth(repo, &tree_hash, 1)?.ok_or(OxenError::basic_str(format!(
+            "Merkle tree node not found for commit: '{}'",
+            tree_commit.id
+        )))?;
+
+    let timestamp = OffsetDateTime::now_utc();
+    let commit_data = NewCommit {
+        parent_ids,
+        message: new_commit.message.clone(),
+        author: new_commit.author.clone(),
+        email: new_commit.email.clone(),
+        timestamp,
+    };
+    let commit_id = compute_commit_id(&commit_data)?;
+    let parent_ids = commit_data
+        .parent_ids
+        .iter()
+        .map(|id| MerkleHash::from_str(id))
+        .collect::<Result<Vec<MerkleHash>, OxenError>>()?;
+    let node = CommitNode {
+        hash: commit_id,
+        parent_ids: parent_ids.clone(),
+        message: commit_data.message,
+        author: commit_data.author,
+        email: commit_data.email,
+        timestamp,
+        ..Default::default()
+    };
+
+    // The root directory node is shared with the tree commit
+    let mut commit_db = MerkleNodeDB::open_read_write(repo, &node, parent_ids.first().copied())?;
+    let Some(root) = tree_node.children.first() else {
+        return Err(OxenError::basic_str(format!(
+            "Commit '{}' has no root directory",
+            tree_commit.id
+        )));
+    };
+    commit_db.add_child(&root.dir()?)?;
+
+    let old_dir_hashes_path = CommitMerkleTree::dir_hash_db_path_from_commit_id(repo, tree_hash);
+    let new_dir_hashes_path = CommitMerkleTree::dir_hash_db_path_from_commit_id(repo, commit_id);
+    util::fs::copy_dir_all(old_dir_hashes_path, new_dir_hashes_path)?;
+
+    let ref_writer = RefWriter::new(repo)?;
+    ref_writer.set_branch_commit_id(branch_name, commit_id.to_string())?;
+    Ok(node.to_commit())
+}
+
 pub fn commit_dir_entries_with_parents(
     repo: &LocalRepository,
     parent_commits: Vec<String>,
@@ -557,8 +712,14 @@ fn split_into_vnodes(
         existing_nodes.keys()
     );
 
-    // Create the VNode buckets per directory
-    for (directory, new_children) in entries {
+    let content_addressed = repo.content_addressed_tree();
+
+    // Create the VNode buckets per directory, deepest first so the vnodes of modified
+    // sub dirs are known before their parents are split
+    let mut directories: Vec<&PathBuf> = entries.keys().collect();
+    directories.sort_by_key(|dir| std::cmp::Reverse(dir.components().count()));
+    for directory in directories {
+        let new_children = &entries[directory];
         let mut children = HashSet::new();
 
         // Lookup children in the existing merkle tree
@@ -624,6 +785,15 @@ fn split_into_vnodes(
             );
         }
 
+        // Modified sub dirs still have their staged placeholder hash, give them their
+        // content addressed hash before bucketing so the vnodes are deterministic too
+        if content_addressed {
+            children = children
+                .into_iter()
+                .map(|child| with_content_addressed_hash(child, &results))
+                .collect::<Result<_, OxenError>>()?;
+        }
+
         // Compute number of vnodes based on the repo's vnode size and number of children
         let total_children = children.len();
         let vnode_size = repo.vnode_size();
@@ -667,6 +837,13 @@ fn split_into_vnodes(
 
             let mut has_new_entries = false;
             for entry in vnode.entries.iter() {
+                if content_addressed {
+                    let path = entry.node.maybe_path()?;
+                    let path = path
+                        .to_str()
+                        .ok_or_else(|| OxenError::could_not_convert_path_to_str(&path))?;
+                    vnode_hasher.update(path.as_bytes());
+                }
                 if let EMerkleTreeNode::File(file_node) = &entry.node.node {
                     vnode_hasher.update(&file_node.combined_hash.to_le_bytes());
                 } else {
@@ -677,8 +854,9 @@ fn split_into_vnodes(
                 }
             }
 
-            // If the vnode has new entries, we need to update the uuid to make a new vnode
-            if existing_nodes.contains_key(directory) && has_new_entries {
+            // If the vnode has new entries, we need to update the uuid to make a new vnode.
+            // Content addressed vnodes with the same id are the same, so they are shared.
+            if !content_addressed && existing_nodes.contains_key(directory) && has_new_entries {
                 let uuid = uuid::Uuid::new_v4();
                 vnode_hasher.update(uuid.as_bytes());
             }
@@ -748,12 +926,12 @@ fn write_commit_entries(
         root_path.to_str().unwrap(),
         &dir_node.hash.to_string(),
     )?;
-    let dir_db = MerkleNodeDB::open_read_write(repo, &dir_node, Some(commit_id))?;
+    let mut dir_db = open_node_db(repo, &dir_node, Some(commit_id))?;
     r_create_dir_node(
         repo,
         maybe_head_commit,
         commit_id,
-        &mut Some(dir_db),
+        &mut dir_db,
         dir_hash_db,
         entries,
         root_path,
@@ -804,18 +982,10 @@ fn r_create_dir_node(
         //     vnode.entries.len()
         // );
 
-        // Maybe because we don't need to overwrite vnode dbs that already exist,
-        // but still need to recurse and create the children
-        // let mut maybe_vnode_db = MerkleNodeDB::open_read_write_if_not_exists(
-        //     repo,
-        //     &vnode_obj,
-        //     maybe_dir_db.as_ref().map(|db| db.node_id),
-        // )?;
-        let mut vnode_db = MerkleNodeDB::open_read_write(
-            repo,
-            &vnode_obj,
-            maybe_dir_db.as_ref().map(|db| db.node_id),
-        )?;
+        // Content addressed vnode dbs that already exist are not rewritten,
+        // but we still recurse to record the dir hashes of this commit
+        let mut maybe_vnode_db =
+            open_node_db(repo, &vnode_obj, maybe_dir_db.as_ref().map(|db| db.node_id))?;
         for entry in vnode.entries.iter() {
             // log::debug!("Processing entry {} in vnode {}", entry.node, vnode.id);
             match &entry.node.node {
@@ -832,26 +1002,12 @@ fn r_create_dir_node(
                             &dir_path,
                         )?;
 
-                        // if let Some(vnode_db) = &mut maybe_vnode_db {
-                        vnode_db.add_child(&dir_node)?;
-                        *total_written += 1;
-                        // }
+                        if let Some(vnode_db) = &mut maybe_vnode_db {
+                            vnode_db.add_child(&dir_node)?;
+                            *total_written += 1;
+                        }
 
-                        // if the vnode is new, we need a new dir db
-                        // let mut child_db = if maybe_vnode_db.is_some() {
-                        let mut child_db = Some(MerkleNodeDB::open_read_write(
-                            repo,
-                            &dir_node,
-                            Some(vnode.id),
-                        )?);
-                        // } else {
-                        //     // Otherwise, check if the dir is new before opening a new db
-                        //     MerkleNodeDB::open_read_write_if_not_exists(
-                        //         repo,
-                        //         &dir_node,
-                        //         Some(vnode.id),
-                        //     )?
-                        // };
+                        let mut child_db = open_node_db(repo, &dir_node, Some(vnode.id))?;
 
                         r_create_dir_node(
                             repo,
@@ -878,10 +1034,10 @@ fn r_create_dir_node(
                         };
                         let dir_node = old_dir_node.dir()?;
 
-                        // if let Some(vnode_db) = &mut maybe_vnode_db {
-                        vnode_db.add_child(&dir_node)?;
-                        *total_written += 1;
-                        // }
+                        if let Some(vnode_db) = &mut maybe_vnode_db {
+                            vnode_db.add_child(&dir_node)?;
+                            *total_written += 1;
+                        }
                         dir_node
                     };
 
@@ -916,23 +1072,21 @@ fn r_create_dir_node(
                     // Just single file chunk for now
                     let chunks = vec![file_node.hash.to_u128()];
                     file_node.chunk_hashes = chunks;
-                    file_node.last_commit_id = if entry.status == StagedEntryStatus::Unmodified {
-                        file_node.last_commit_id
-                    } else {
-                        commit_id
-                    };
+                    if repo.content_addressed_tree() {
+                        // Shared between commits, so nothing about this commit or the
+                        // working dir goes in, see index::last_commits
+                        file_node.last_commit_id = MerkleHash::new(0);
+                        file_node.last_modified_seconds = 0;
+                        file_node.last_modified_nanoseconds = 0;
+                    } else if entry.status != StagedEntryStatus::Unmodified {
+                        file_node.last_commit_id = commit_id;
+                    }
                     file_node.name = file_name.to_string();
 
-                    // if let Some(vnode_db) = &mut maybe_vnode_db {
-                    // log::debug!(
-                    //     "Adding file {} to vnode {} in commit {}",
-                    //     file_name,
-                    //     vnode.id,
-                    //     commit_id
-                    // );
-                    vnode_db.add_child(&file_node)?;
-                    *total_written += 1;
-                    // }
+                    if let Some(vnode_db) = &mut maybe_vnode_db {
+                        vnode_db.add_child(&file_node)?;
+                        *total_written += 1;
+                    }
                 }
                 _ => {
                     return Err(OxenError::basic_str(format!(
@@ -953,6 +1107,72 @@ fn r_create_dir_node(
     Ok(())
 }
 
+// Content addressed nodes with the same hash have the same contents, existing ones are kept as is
+fn open_node_db(
+    repo: &LocalRepository,
+    node: &impl TMerkleTreeNode,
+    parent_id: Option<MerkleHash>,
+) -> Result<Option<MerkleNodeDB>, OxenError> {
+    if repo.content_addressed_tree() {
+        MerkleNodeDB::open_read_write_if_not_exists(repo, node, parent_id)
+    } else {
+        Ok(Some(MerkleNodeDB::open_read_write(repo, node, parent_id)?))
+    }
+}
+
+// Hash of a dir from its path and the paths and hashes of its children, sorted so
+// the order they were staged in does not matter. Nothing about the commit goes in.
+fn content_addressed_dir_hash(path: &Path, vnodes: &[EntryVNode]) -> Result<MerkleHash, OxenError> {
+    let mut children: Vec<&StagedMerkleTreeNode> = vnodes
+        .iter()
+        .flat_map(|vnode| vnode.entries.iter())
+        .filter(|entry| entry.status != StagedEntryStatus::Removed)
+        .collect();
+    children.sort_by_key(|entry| entry.node.maybe_path().unwrap_or_default());
+
+    let mut hasher = xxhash_rust::xxh3::Xxh3::new();
+    hasher.update(b"dir");
+    let path_str = path
+        .to_str()
+        .ok_or_else(|| OxenError::could_not_convert_path_to_str(path))?;
+    hasher.update(path_str.as_bytes());
+    for child in children {
+        match &child.node.node {
+            EMerkleTreeNode::File(file_node) => {
+                hasher.update(b"file");
+                hasher.update(file_node.name.as_bytes());
+                hasher.update(&file_node.combined_hash.to_le_bytes());
+            }
+            EMerkleTreeNode::Directory(dir_node) => {
+                hasher.update(b"dir");
+                hasher.update(dir_node.name.as_bytes());
+                hasher.update(&dir_node.hash.to_le_bytes());
+            }
+            _ => {}
+        }
+    }
+    Ok(MerkleHash::new(hasher.digest128()))
+}
+
+// Swap the staged hash of a modified sub dir for its content addressed hash
+fn with_content_addressed_hash(
+    mut child: StagedMerkleTreeNode,
+    split_dirs: &HashMap<PathBuf, Vec<EntryVNode>>,
+) -> Result<StagedMerkleTreeNode, OxenError> {
+    let Ok(path) = child.node.maybe_path() else {
+        return Ok(child);
+    };
+    let Some(vnodes) = split_dirs.get(&path) else {
+        return Ok(child);
+    };
+    let hash = content_addressed_dir_hash(&path, vnodes)?;
+    if let EMerkleTreeNode::Directory(dir_node) = &mut child.node.node {
+        dir_node.hash = hash;
+        child.node.hash = hash;
+    }
+    Ok(child)
+}
+
 fn get_children(
     entries: &HashMap<PathBuf, Vec<EntryVNode>>,
     dir_path: impl AsRef<Path>,
@@ -984,6 +1204,8 @@ fn compute_dir_node(
     let mut num_bytes = 0;
     let mut data_type_counts: HashMap<String, u64> = HashMap::new();
     let mut data_type_sizes: HashMap<String, u64> = HashMap::new();
+    let mut image_resolution_counts: HashMap<String, u64> = HashMap::new();
+    let mut data_type_durations: HashMap<String, u64> = HashMap::new();
 
     // Collect the previous commit counts
     if let Some(head_commit) = maybe_head_commit {
@@ -994,6 +1216,8 @@ fn compute_dir_node(
             num_bytes = old_dir_node.num_bytes;
             data_type_counts = old_dir_node.data_type_counts;
             data_type_sizes = old_dir_node.data_type_sizes;
+            image_resolution_counts = old_dir_node.image_resolution_counts;
+            data_type_durations = old_dir_node.data_type_durations;
         };
     }
 
@@ -1028,6 +1252,18 @@ fn compute_dir_node(
                         hasher.update(file_node.name.as_bytes());
                         hasher.update(&file_node.combined_hash.to_le_bytes());
 
+                        let resolution_bucket = match &file_node.metadata {
+                            Some(GenericMetadata::MetadataImage(image)) => {
+                                Some(image.resolution_bucket())
+                            }
+                            _ => None,
+                        };
+                        let duration_ms = file_node
+                            .metadata
+                            .as_ref()
+                            .and_then(|m| m.num_seconds())
+                            .map(|seconds| (seconds * 1000.0).round() as u64);
+
                         match entry.status {
                             StagedEntryStatus::Added => {
                                 num_bytes += file_node.num_bytes;
@@ -1037,6 +1273,14 @@ fn compute_dir_node(
                                 *data_type_sizes
                                     .entry(file_node.data_type.to_string())
                                     .or_insert(0) += file_node.num_bytes;
+                                if let Some(bucket) = resolution_bucket {
+                                    *image_resolution_counts.entry(bucket).or_insert(0) += 1;
+                                }
+                                if let Some(duration_ms) = duration_ms {
+                                    *data_type_durations
+                                        .entry(file_node.data_type.to_string())
+                                        .or_insert(0) += duration_ms;
+                                }
                             }
                             StagedEntryStatus::Removed => {
                                 num_bytes -= file_node.num_bytes;
@@ -1046,6 +1290,17 @@ fn compute_dir_node(
                                 *data_type_sizes
                                     .entry(file_node.data_type.to_string())
                                     .or_insert(0) -= file_node.num_bytes;
+                                // Dirs committed before these were tracked have no counts
+                                if let Some(bucket) = resolution_bucket {
+                                    let count = image_resolution_counts.entry(bucket).or_insert(0);
+                                    *count = count.saturating_sub(1);
+                                }
+                                if let Some(duration_ms) = duration_ms {
+                                    let total = data_type_durations
+                                        .entry(file_node.data_type.to_string())
+                                        .or_insert(0);
+                                    *total = total.saturating_sub(duration_ms);
+                                }
                             }
                             _ => {
                                 // Do nothing
@@ -1063,7 +1318,10 @@ fn compute_dir_node(
         }
     }
 
-    let hash = MerkleHash::new(hasher.digest128());
+    let hash = match entries.get(&path) {
+        Some(vnodes) if repo.content_addressed_tree() => content_addressed_dir_hash(&path, vnodes)?,
+        _ => MerkleHash::new(hasher.digest128()),
+    };
     let file_name = path.file_name().unwrap_or_default().to_str().unwrap();
     log::debug!(
         "Aggregated dir {:?} [{}] num_bytes {:?} data_type_counts {:?}",
@@ -1073,16 +1331,25 @@ fn compute_dir_node(
         data_type_counts
     );
 
+    // Content addressed dirs are shared between commits, so the last commit is left off,
+    // see index::last_commits
+    let last_commit_id = if repo.content_addressed_tree() {
+        MerkleHash::new(0)
+    } else {
+        commit_id
+    };
     let node = DirNode {
         node_type: MerkleTreeNodeType::Dir,
         name: file_name.to_owned(),
         hash,
         num_bytes,
-        last_commit_id: commit_id,
+        last_commit_id,
         last_modified_seconds: 0,
         last_modified_nanoseconds: 0,
         data_type_counts,
         data_type_sizes,
+        image_resolution_counts,
+        data_type_durations,
     };
     Ok(node)
 }
@@ -1145,10 +1412,12 @@ mod tests {
     use std::collections::HashSet;
     use std::path::Path;
 
+    use crate::core;
     use crate::core::v0_19_0::index::CommitMerkleTree;
     use crate::core::versions::MinOxenVersion;
     use crate::error::OxenError;
     use crate::model::MerkleHash;
+    use crate::opts::PaginateOpts;
     use crate::repositories;
     use crate::test;
     use crate::test::add_n_files_m_dirs;
@@ -1682,4 +1951,121 @@ mod tests {
             Ok(())
         })
     }
+
+    #[test]
+    fn test_content_addressed_tree_hashes_only_depend_on_content() -> Result<(), OxenError> {
+        test::run_empty_dir_test(|dir| {
+            let mut root_hashes = vec![];
+            for (name, files) in [
+                ("repo_a", ["data/a.txt", "data/nested/b.txt", "c.txt"]),
+                ("repo_b", ["c.txt", "data/nested/b.txt", "data/a.txt"]),
+            ] {
+                let mut repo =
+                    repositories::init::init_with_version(dir.join(name), MinOxenVersion::V0_19_0)?;
+                repo.set_content_addressed_tree(true);
+                repo.save_default()?;
+
+                // Same contents, written in a different order and committed with a different message
+                for file in files {
+                    let path = repo.path.join(file);
+                    util::fs::write_to_path(&path, file)?;
+                    repositories::add(&repo, &path)?;
+                }
+                let commit = super::commit(&repo, &format!("Adding data to {name}"))?;
+                let root = CommitMerkleTree::dir_without_children(&repo, &commit, "")?.unwrap();
+                root_hashes.push(root.hash);
+
+                if name == "repo_a" {
+                    // Changing a file and changing it back gives the same tree again
+                    let path = repo.path.join("data/nested/b.txt");
+                    util::fs::write_to_path(&path, "changed")?;
+                    repositories::add(&repo, &path)?;
+                    let changed = super::commit(&repo, "Changing b")?;
+                    let changed_root =
+                        CommitMerkleTree::dir_without_children(&repo, &changed, "")?.unwrap();
+                    assert_ne!(changed_root.hash, root.hash);
+
+                    util::fs::write_to_path(&path, "data/nested/b.txt")?;
+                    repositories::add(&repo, &path)?;
+                    let reverted = super::commit(&repo, "Changing b back")?;
+                    let reverted_root =
+                        CommitMerkleTree::dir_without_children(&repo, &reverted, "")?.unwrap();
+                    assert_eq!(reverted_root.hash, root.hash);
+
+                    // The shared nodes still load as a full tree
+                    let tree = CommitMerkleTree::from_commit(&repo, &reverted)?;
+                    assert!(tree.get_by_path(Path::new("data/nested/b.txt"))?.is_some());
+                }
+            }
+            assert_eq!(root_hashes[0], root_hashes[1]);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_content_addressed_tree_keeps_history() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|mut repo| {
+            repo.set_content_addressed_tree(true);
+            repo.save_default()?;
+
+            let path = repo.path.join("data/b.txt");
+            util::fs::write_to_path(&path, "b")?;
+            util::fs::write_to_path(repo.path.join("c.txt"), "c")?;
+            repositories::add(&repo, &path)?;
+            repositories::add(&repo, repo.path.join("c.txt"))?;
+            let first = super::commit(&repo, "Adding b and c")?;
+
+            util::fs::write_to_path(&path, "changed")?;
+            repositories::add(&repo, &path)?;
+            let changed = super::commit(&repo, "Changing b")?;
+
+            // Back to the tree of the first commit, which shares its nodes
+            util::fs::write_to_path(&path, "b")?;
+            repositories::add(&repo, &path)?;
+            let reverted = super::commit(&repo, "Changing b back")?;
+
+            let history = core::v0_19_0::commits::list_by_path_from(
+                &repo,
+                &reverted,
+                Path::new("data/b.txt"),
+            )?;
+            let ids: Vec<&str> = history.iter().map(|c| c.id.as_str()).collect();
+            assert_eq!(ids, vec![&reverted.id, &changed.id, &first.id]);
+
+            // The last commit of each entry is per commit, not per shared node
+            let entries = repositories::entries::list_directory(
+                &repo,
+                Path::new(""),
+                &reverted.id,
+                &PaginateOpts::default(),
+            )?;
+            let last_commit = |name: &str| {
+                entries
+                    .entries
+                    .iter()
+                    .find(|entry| entry.filename == name)
+                    .and_then(|entry| entry.latest_commit.clone())
+                    .unwrap()
+                    .id
+            };
+            assert_eq!(last_commit("data"), reverted.id);
+            assert_eq!(last_commit("c.txt"), first.id);
+
+            let entries = repositories::entries::list_directory(
+                &repo,
+                Path::new(""),
+                &first.id,
+                &PaginateOpts::default(),
+            )?;
+            let data = entries
+                .entries
+                .iter()
+                .find(|e| e.filename == "data")
+                .unwrap();
+            assert_eq!(data.latest_commit.as_ref().unwrap().id, first.id);
+
+            Ok(())
+        })
+    }
 }
@@ -34,6 +34,7 @@ use crate::error::OxenError;
 use crate::model::CommitEntry;
 use crate::model::LocalRepository;
 use crate::util;
+use crate::util::encryption::{EncryptionKey, MaybeDecrypted};
 use crate::util::hasher;
 use crate::util::progress_bar::oxen_progress_bar;
 use crate::util::progress_bar::ProgressBarType;
@@ -102,7 +103,9 @@ impl ChunkShardIndex {
 
 pub struct ChunkShardFile {
     pub path: PathBuf,
-    pub file: File,
+    pub file: MaybeDecrypted,
+    // Shards are encrypted like version files when the repository has a key
+    key: Option<EncryptionKey>,
     pub index: ChunkShardIndex,
     pub data_start: usize,
     pub offset: usize,
@@ -123,6 +126,8 @@ impl ChunkShardFile {
 
     pub fn shard_idx(path: &Path) -> u32 {
         let file_stem = path.file_stem().unwrap();
+        // Encrypted shards are stored under their own name
+        let file_stem = util::encryption::Encoding::strip(file_stem);
         let file_stem_str = file_stem.to_str().unwrap();
         let idx_str = file_stem_str.split('_').nth(1).unwrap();
         idx_str.parse::<u32>().unwrap()
@@ -131,11 +136,12 @@ impl ChunkShardFile {
     pub fn open(repo: &LocalRepository, file_idx: u32) -> Result<ChunkShardFile, OxenError> {
         log::debug!("Opening shard file: {:?}", Self::shard_path(repo, file_idx));
         let path = Self::shard_path(repo, file_idx);
-        let file = File::open(&path)?;
+        let file = util::encryption::open(repo, util::encryption::stored_path(&path))?;
         // allocate the data buffer
         let shard_file = ChunkShardFile {
             path,
             file,
+            key: util::encryption::repo_key(repo)?,
             index: ChunkShardIndex::new(),
             data_start: 0,
             offset: 0,
@@ -150,11 +156,12 @@ impl ChunkShardFile {
             Self::shard_path(repo, file_idx)
         );
         let path = Self::shard_path(repo, file_idx);
-        let file = File::create(&path)?;
+        let file = MaybeDecrypted::Plain(File::create(&path)?);
         let index = ChunkShardIndex::new();
         Ok(ChunkShardFile {
             path,
             file,
+            key: util::encryption::repo_key(repo)?,
             index,
             data_start: 0,
             offset: 0,
@@ -243,22 +250,25 @@ impl ChunkShardFile {
     pub fn save(&mut self) -> Result<(), OxenError> {
         log::debug!("Saving shard file: {:?}", self.path);
         // Overwrite existing file
-        self.file = File::create(&self.path)?;
+        let mut file = File::create(&self.path)?;
         // write the index to the file
         let index_bytes = bincode::serialize(&self.index)?;
         log::debug!("Saving shard index: {:?}", index_bytes.len());
         // write index size to the file
-        self.file
-            .write_all(&(index_bytes.len() as u32).to_le_bytes())?;
+        file.write_all(&(index_bytes.len() as u32).to_le_bytes())?;
         // write index to the file
-        self.file.write_all(&index_bytes)?;
+        file.write_all(&index_bytes)?;
         // write the data size
-        self.file.write_all(&(self.offset as u32).to_le_bytes())?;
+        file.write_all(&(self.offset as u32).to_le_bytes())?;
         // write only the data that has been written
         let data = &self.data[..self.offset];
         log::debug!("Saving shard data: {:?}", data.len());
-        self.file.write_all(data)?;
-        self.file.sync_all()?;
+        file.write_all(data)?;
+        file.sync_all()?;
+        if let Some(key) = &self.key {
+            util::encryption::encrypt_in_place(key, &self.path)?;
+        }
+        self.file = MaybeDecrypted::Plain(file);
         log::debug!("Saved shard file: {:?}", self.path);
         Ok(())
     }
@@ -294,12 +304,13 @@ impl ChunkShardManager {
             .map(|x| x.unwrap().path())
             .collect::<Vec<PathBuf>>();
 
-        // sort the shard paths by the file index
+        // sort the shard paths by the file index, a shard being encrypted is there twice
         shard_paths.sort_by(|a, b| {
             let a_idx = ChunkShardFile::shard_idx(a);
             let b_idx = ChunkShardFile::shard_idx(b);
             a_idx.cmp(&b_idx)
         });
+        shard_paths.dedup_by_key(|path| ChunkShardFile::shard_idx(path));
 
         let mut current_idx = 0;
         let mut current_file: Option<ChunkShardFile> = None;
@@ -406,7 +417,7 @@ impl FileChunker {
         csm: &mut ChunkShardManager,
     ) -> Result<Vec<u128>, OxenError> {
         let version_file = util::fs::version_path(&self.repo, entry);
-        let mut read_file = File::open(&version_file)?;
+        let mut read_file = util::encryption::open(&self.repo, &version_file)?;
 
         // Create a progress bar for larger files
         let mut progress_bar: Option<Arc<ProgressBar>> =
@@ -0,0 +1,217 @@
+//! # Last Commits
+//!
+//! Content addressed trees share their nodes between commits, so the id of the last
+//! commit that changed a file or dir can't be stored on its node. Each commit gets an
+//! index from every path in its tree to that id instead, built from the indices of its
+//! parents. It is written when the commit is made, or the first time it is read for a
+//! commit that was pushed or pulled, so a lookup never walks the history.
+//!
+
+use rocksdb::{DBWithThreadMode, SingleThreaded};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::constants::{HISTORY_DIR, LAST_COMMITS_DIR};
+use crate::core::db;
+use crate::core::db::key_val::str_val_db;
+use crate::core::v0_19_0::index::{CommitMerkleTree, MerkleNodeDB};
+use crate::error::OxenError;
+use crate::model::merkle_tree::node::{EMerkleTreeNode, MerkleTreeNode};
+use crate::model::{Commit, LocalRepository, MerkleHash};
+use crate::repositories;
+use crate::util;
+
+pub fn db_path(repo: &LocalRepository, commit_id: impl AsRef<str>) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path)
+        .join(HISTORY_DIR)
+        .join(commit_id.as_ref())
+        .join(LAST_COMMITS_DIR)
+}
+
+/// The id of the last commit that changed the file or dir at `path`, as of `commit`
+pub fn get(
+    repo: &LocalRepository,
+    commit: &Commit,
+    path: impl AsRef<Path>,
+) -> Result<Option<MerkleHash>, OxenError> {
+    write(repo, commit)?;
+    let db_path = db_path(repo, &commit.id);
+    // Commits whose tree was never downloaded have no index
+    if !db_path.exists() {
+        return Ok(None);
+    }
+    let opts = db::key_val::opts::default();
+    let db: DBWithThreadMode<SingleThreaded> =
+        DBWithThreadMode::open_for_read_only(&opts, dunce::simplified(&db_path), false)?;
+    let last_commit_id: Option<String> = str_val_db::get(&db, util::fs::to_unix_str(path))?;
+    last_commit_id
+        .map(|id| MerkleHash::from_str(&id))
+        .transpose()
+}
+
+/// Write the index of `commit`, and first the indices of the parents it is built from
+/// if they don't have one yet
+pub fn write(repo: &LocalRepository, commit: &Commit) -> Result<(), OxenError> {
+    // Parents before children, without recursing down the whole history. Parents whose
+    // tree was never downloaded are treated like there were none.
+    let mut pending = vec![(commit.clone(), false)];
+    let mut seen = HashSet::new();
+    while let Some((commit, parents_written)) = pending.pop() {
+        if db_path(repo, &commit.id).exists() {
+            continue;
+        }
+        if parents_written {
+            write_commit(repo, &commit)?;
+            continue;
+        }
+        if !seen.insert(commit.id.clone()) {
+            continue;
+        }
+        pending.push((commit.clone(), true));
+        for parent_id in commit.parent_ids.iter() {
+            if !MerkleNodeDB::exists(repo, &MerkleHash::from_str(parent_id)?) {
+                continue;
+            }
+            if let Some(parent) = repositories::commits::get_by_id(repo, parent_id)? {
+                pending.push((parent, false));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_commit(repo: &LocalRepository, commit: &Commit) -> Result<(), OxenError> {
+    let Some(paths) = tree_paths(repo, &commit.id)? else {
+        return Ok(());
+    };
+
+    // A path keeps the last commit of a parent it has the same contents in
+    let opts = db::key_val::opts::default();
+    let mut parents = vec![];
+    for parent_id in commit.parent_ids.iter() {
+        let parent_db_path = db_path(repo, parent_id);
+        let Some(parent_paths) = tree_paths(repo, parent_id)? else {
+            continue;
+        };
+        if !parent_db_path.exists() {
+            continue;
+        }
+        let parent_db: DBWithThreadMode<SingleThreaded> =
+            DBWithThreadMode::open_for_read_only(&opts, dunce::simplified(&parent_db_path), false)?;
+        parents.push((parent_paths, parent_db));
+    }
+
+    // Written next to the index and moved in place once complete
+    let db_path = db_path(repo, &commit.id);
+    let tmp_path = db_path.with_extension(format!("tmp-{}", uuid::Uuid::new_v4()));
+    if let Some(parent) = db_path.parent() {
+        util::fs::create_dir_all(parent)?;
+    }
+    {
+        let db: DBWithThreadMode<SingleThreaded> =
+            DBWithThreadMode::open(&opts, dunce::simplified(&tmp_path))?;
+        for (path, contents) in paths.iter() {
+            let mut last_commit_id: Option<String> = None;
+            for (parent_paths, parent_db) in parents.iter() {
+                if parent_paths.get(path) == Some(contents) {
+                    last_commit_id = str_val_db::get(parent_db, path)?;
+                    if last_commit_id.is_some() {
+                        break;
+                    }
+                }
+            }
+            let last_commit_id = last_commit_id.unwrap_or_else(|| commit.id.clone());
+            str_val_db::put(&db, path, &last_commit_id)?;
+        }
+    }
+    std::fs::rename(&tmp_path, &db_path)?;
+    Ok(())
+}
+
+// The hash of the contents of every file and dir in the tree of the commit, by path.
+// None if the tree was never downloaded.
+fn tree_paths(
+    repo: &LocalRepository,
+    commit_id: &str,
+) -> Result<Option<HashMap<String, MerkleHash>>, OxenError> {
+    let hash = MerkleHash::from_str(commit_id)?;
+    let Some(root) = CommitMerkleTree::read_node(repo, &hash, true)? else {
+        return Ok(None);
+    };
+    let mut paths = HashMap::new();
+    for child in root.children.iter() {
+        r_tree_paths(child, Path::new(""), &mut paths);
+    }
+    Ok(Some(paths))
+}
+
+fn r_tree_paths(node: &MerkleTreeNode, dir: &Path, paths: &mut HashMap<String, MerkleHash>) {
+    match &node.node {
+        // Files also change when only their metadata does
+        EMerkleTreeNode::File(file_node) => {
+            let path = dir.join(&file_node.name);
+            paths.insert(util::fs::to_unix_str(path), file_node.combined_hash);
+        }
+        EMerkleTreeNode::Directory(dir_node) => {
+            let path = dir.join(&dir_node.name);
+            paths.insert(util::fs::to_unix_str(&path), dir_node.hash);
+            for child in node.children.iter() {
+                r_tree_paths(child, &path, paths);
+            }
+        }
+        EMerkleTreeNode::VNode(_) => {
+            for child in node.children.iter() {
+                r_tree_paths(child, dir, paths);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::core::v0_19_0::index::last_commits;
+    use crate::error::OxenError;
+    use crate::model::MerkleHash;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_last_commits_are_indexed_per_commit() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|mut repo| {
+            repo.set_content_addressed_tree(true);
+            repo.save_default()?;
+
+            let path = repo.path.join("data").join("b.txt");
+            util::fs::write_to_path(&path, "b")?;
+            util::fs::write_to_path(repo.path.join("c.txt"), "c")?;
+            repositories::add(&repo, &repo.path)?;
+            let first = repositories::commit(&repo, "Adding b and c")?;
+
+            util::fs::write_to_path(&path, "changed")?;
+            repositories::add(&repo, &path)?;
+            let second = repositories::commit(&repo, "Changing b")?;
+            assert!(last_commits::db_path(&repo, &second.id).exists());
+
+            let last_commit = |path: &str| -> Result<String, OxenError> {
+                let id = last_commits::get(&repo, &second, Path::new(path))?;
+                Ok(id.map(|id: MerkleHash| id.to_string()).unwrap())
+            };
+            assert_eq!(last_commit("data/b.txt")?, second.id);
+            assert_eq!(last_commit("data")?, second.id);
+            assert_eq!(last_commit("")?, second.id);
+            assert_eq!(last_commit("c.txt")?, first.id);
+
+            // Indices of pulled commits are built on the first read
+            util::fs::remove_dir_all(last_commits::db_path(&repo, &first.id))?;
+            util::fs::remove_dir_all(last_commits::db_path(&repo, &second.id))?;
+            assert_eq!(last_commit("c.txt")?, first.id);
+            assert!(last_commits::db_path(&repo, &first.id).exists());
+            Ok(())
+        })
+    }
+}
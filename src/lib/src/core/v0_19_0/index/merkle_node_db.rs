@@ -44,6 +44,8 @@ For example, data for a vnode of hash 1234 with two children:
 .oxen/tree/1234/children
     {file data node}
     {dir data node}
+
+After `oxen repack` the same two files live in a pack file instead, see node_pack.rs.
 */
 
 use rmp_serde::Serializer;
@@ -58,6 +60,7 @@ use std::io::Write;
 use std::path::{Path, PathBuf};
 
 use crate::constants;
+use crate::core::v0_19_0::index::node_pack;
 use crate::error::OxenError;
 use crate::model::LocalRepository;
 use crate::model::MerkleHash;
@@ -98,7 +101,7 @@ pub struct MerkleNodeLookup {
 }
 
 impl MerkleNodeLookup {
-    pub fn load(node_table_file: &mut File) -> Result<Self, OxenError> {
+    pub fn load(node_table_file: &mut impl Read) -> Result<Self, OxenError> {
         // log::debug!("MerkleNodeLookup.load() {:?}", node_table_file);
         // Read the whole node into memory
         let mut file_data = Vec::new();
@@ -200,6 +203,8 @@ pub struct MerkleNodeDB {
     node_file: Option<File>,
     children_file: Option<File>,
     lookup: Option<MerkleNodeLookup>,
+    // Children read from a pack instead of the children file
+    packed_children: Option<Vec<u8>>,
     data: Vec<u8>,
     num_children: u64,
     data_offset: u64,
@@ -245,15 +250,69 @@ impl MerkleNodeDB {
     }
 
     pub fn exists(repo: &LocalRepository, hash: &MerkleHash) -> bool {
+        Self::exists_loose(repo, hash) || node_pack::contains(repo, hash)
+    }
+
+    fn exists_loose(repo: &LocalRepository, hash: &MerkleHash) -> bool {
         let db_path = node_db_path(repo, hash);
         db_path.join(NODE_FILE).exists() && db_path.join(CHILDREN_FILE).exists()
     }
 
     pub fn open_read_only(repo: &LocalRepository, hash: &MerkleHash) -> Result<Self, OxenError> {
         let path = node_db_path(repo, hash);
+        if !Self::exists_loose(repo, hash) {
+            if let Some((node_data, children_data)) = node_pack::read(repo, hash)? {
+                return Self::open_packed(path, node_data, children_data);
+            }
+        }
         Self::open(path, true)
     }
 
+    fn open_packed(
+        path: PathBuf,
+        node_data: Vec<u8>,
+        children_data: Vec<u8>,
+    ) -> Result<Self, OxenError> {
+        let lookup = MerkleNodeLookup::load(&mut node_data.as_slice())?;
+        Ok(Self {
+            read_only: true,
+            path,
+            node_file: None,
+            children_file: None,
+            dtype: MerkleTreeNodeType::from_u8(lookup.data_type),
+            parent_id: Some(MerkleHash::new(lookup.parent_id)),
+            lookup: Some(lookup),
+            packed_children: Some(children_data),
+            data: vec![],
+            num_children: 0,
+            node_id: MerkleHash::new(0),
+            data_offset: 0,
+        })
+    }
+
+    /// Add the `node` and `children` files of a node to a tarball under `tar_dir`, from
+    /// the loose node dir or from a pack. The tarballs we send always hold loose nodes.
+    pub fn append_to_tar<W: Write>(
+        repo: &LocalRepository,
+        hash: &MerkleHash,
+        tar: &mut tar::Builder<W>,
+        tar_dir: impl AsRef<Path>,
+    ) -> Result<(), OxenError> {
+        let tar_dir = tar_dir.as_ref();
+        if Self::exists_loose(repo, hash) {
+            tar.append_dir_all(tar_dir, node_db_path(repo, hash))?;
+        } else if let Some((node_data, children_data)) = node_pack::read(repo, hash)? {
+            for (name, data) in [(NODE_FILE, node_data), (CHILDREN_FILE, children_data)] {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(data.len() as u64);
+                header.set_mode(0o644);
+                header.set_cksum();
+                tar.append_data(&mut header, tar_dir.join(name), data.as_slice())?;
+            }
+        }
+        Ok(())
+    }
+
     pub fn open_read_write_if_not_exists(
         repo: &LocalRepository,
         node: &impl TMerkleTreeNode,
@@ -333,6 +392,7 @@ impl MerkleNodeDB {
             node_file,
             children_file,
             lookup,
+            packed_children: None,
             data: vec![],
             num_children: 0,
             dtype,
@@ -493,16 +553,21 @@ impl MerkleNodeDB {
         let Some(lookup) = self.lookup.as_ref() else {
             return Err(OxenError::basic_str("Must call open before reading"));
         };
-        let Some(children_file) = self.children_file.as_mut() else {
-            return Err(OxenError::basic_str("Must call open before writing"));
-        };
 
         // Parse the node parent id
         let data_type = MerkleTreeNodeType::from_u8(lookup.data_type);
         let parent_id = MerkleTreeNode::deserialize_id(&lookup.data, data_type)?;
 
-        let mut file_data = Vec::new();
-        children_file.read_to_end(&mut file_data)?;
+        let mut loose_data = Vec::new();
+        let file_data: &[u8] = if let Some(packed_children) = &self.packed_children {
+            packed_children
+        } else {
+            let Some(children_file) = self.children_file.as_mut() else {
+                return Err(OxenError::basic_str("Must call open before writing"));
+            };
+            children_file.read_to_end(&mut loose_data)?;
+            &loose_data
+        };
         // log::debug!("Loading merkle node db map got {} bytes", file_data.len());
 
         let mut ret: Vec<(MerkleHash, MerkleTreeNode)> =
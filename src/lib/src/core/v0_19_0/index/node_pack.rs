@@ -0,0 +1,376 @@
+//! # Node Packs
+//!
+//! Every merkle node is written to its own dir under .oxen/tree/nodes holding a `node`
+//! and a `children` file, so large repos end up with millions of tiny files. `oxen repack`
+//! moves them into a single append-only pack file with a sorted index next to it, much
+//! like git packfiles. Readers look for the loose node dir first, then in the packs.
+//!
+//! Index file, little endian:
+//! - magic "OXNI" and a u32 version
+//! - u64 number of nodes
+//! - per node, sorted by hash: u128 hash, u64 pack offset, u64 node length, u64 children length
+//!
+//! The pack file holds the `node` file of each node followed by its `children` file.
+//!
+
+use lazy_static::lazy_static;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use crate::constants::{NODES_DIR, NODE_PACKS_DIR, TREE_DIR};
+use crate::error::OxenError;
+use crate::model::{LocalRepository, MerkleHash};
+use crate::util;
+
+const INDEX_MAGIC: &[u8; 4] = b"OXNI";
+const INDEX_VERSION: u32 = 1;
+const PACK_EXT: &str = "pack";
+const INDEX_EXT: &str = "idx";
+const NODE_FILE: &str = "node";
+const CHILDREN_FILE: &str = "children";
+
+struct PackEntry {
+    hash: u128,
+    offset: u64,
+    node_len: u64,
+    children_len: u64,
+}
+
+struct PackIndex {
+    pack_path: PathBuf,
+    entries: Vec<PackEntry>,
+}
+
+impl PackIndex {
+    fn load(index_path: &Path) -> Result<PackIndex, OxenError> {
+        let mut data = Vec::new();
+        util::fs::open_file(index_path)?.read_to_end(&mut data)?;
+        let mut cursor = std::io::Cursor::new(data);
+
+        let mut magic = [0u8; 4];
+        cursor.read_exact(&mut magic)?;
+        let mut version = [0u8; 4];
+        cursor.read_exact(&mut version)?;
+        if &magic != INDEX_MAGIC || u32::from_le_bytes(version) != INDEX_VERSION {
+            return Err(OxenError::basic_str(format!(
+                "Invalid node pack index {index_path:?}"
+            )));
+        }
+
+        let num_entries = read_u64(&mut cursor)?;
+        let mut entries = Vec::with_capacity(num_entries as usize);
+        let mut hash_buffer = [0u8; 16];
+        for _ in 0..num_entries {
+            cursor.read_exact(&mut hash_buffer)?;
+            entries.push(PackEntry {
+                hash: u128::from_le_bytes(hash_buffer),
+                offset: read_u64(&mut cursor)?,
+                node_len: read_u64(&mut cursor)?,
+                children_len: read_u64(&mut cursor)?,
+            });
+        }
+
+        Ok(PackIndex {
+            pack_path: index_path.with_extension(PACK_EXT),
+            entries,
+        })
+    }
+
+    fn find(&self, hash: u128) -> Option<&PackEntry> {
+        self.entries
+            .binary_search_by_key(&hash, |e| e.hash)
+            .ok()
+            .map(|i| &self.entries[i])
+    }
+
+    fn read(&self, entry: &PackEntry) -> Result<(Vec<u8>, Vec<u8>), OxenError> {
+        let mut pack = util::fs::open_file(&self.pack_path)?;
+        pack.seek(SeekFrom::Start(entry.offset))?;
+        let mut node_data = vec![0; entry.node_len as usize];
+        pack.read_exact(&mut node_data)?;
+        let mut children_data = vec![0; entry.children_len as usize];
+        pack.read_exact(&mut children_data)?;
+        Ok((node_data, children_data))
+    }
+}
+
+lazy_static! {
+    // Loaded indices per packs dir, reloaded when packs are added or removed
+    static ref PACK_INDICES: Mutex<HashMap<PathBuf, (SystemTime, Arc<Vec<PackIndex>>)>> =
+        Mutex::new(HashMap::new());
+}
+
+fn read_u64(reader: &mut impl Read) -> Result<u64, OxenError> {
+    let mut buffer = [0u8; 8];
+    reader.read_exact(&mut buffer)?;
+    Ok(u64::from_le_bytes(buffer))
+}
+
+pub fn packs_dir(repo: &LocalRepository) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path)
+        .join(TREE_DIR)
+        .join(NODE_PACKS_DIR)
+}
+
+fn nodes_dir(repo: &LocalRepository) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path)
+        .join(TREE_DIR)
+        .join(NODES_DIR)
+}
+
+fn load_indices(dir: &Path) -> Result<Vec<PackIndex>, OxenError> {
+    let mut indices = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == INDEX_EXT) {
+            indices.push(PackIndex::load(&path)?);
+        }
+    }
+    Ok(indices)
+}
+
+fn indices(repo: &LocalRepository) -> Result<Arc<Vec<PackIndex>>, OxenError> {
+    let dir = packs_dir(repo);
+    let Ok(modified) = std::fs::metadata(&dir).and_then(|m| m.modified()) else {
+        // Never repacked
+        return Ok(Arc::new(vec![]));
+    };
+
+    let mut cache = PACK_INDICES.lock().unwrap();
+    if let Some((loaded_at, indices)) = cache.get(&dir) {
+        if *loaded_at == modified {
+            return Ok(indices.clone());
+        }
+    }
+    let indices = Arc::new(load_indices(&dir)?);
+    cache.insert(dir, (modified, indices.clone()));
+    Ok(indices)
+}
+
+/// Whether the node is in one of the packs
+pub fn contains(repo: &LocalRepository, hash: &MerkleHash) -> bool {
+    match indices(repo) {
+        Ok(indices) => indices.iter().any(|i| i.find(hash.to_u128()).is_some()),
+        Err(err) => {
+            log::warn!("Could not read node pack indices: {err}");
+            false
+        }
+    }
+}
+
+/// The `node` and `children` file contents of a packed node
+pub fn read(
+    repo: &LocalRepository,
+    hash: &MerkleHash,
+) -> Result<Option<(Vec<u8>, Vec<u8>)>, OxenError> {
+    for index in indices(repo)?.iter() {
+        if let Some(entry) = index.find(hash.to_u128()) {
+            return index.read(entry).map(Some);
+        }
+    }
+    Ok(None)
+}
+
+/// The hashes of all packed nodes
+pub fn list(repo: &LocalRepository) -> Result<Vec<MerkleHash>, OxenError> {
+    Ok(indices(repo)?
+        .iter()
+        .flat_map(|i| i.entries.iter().map(|e| MerkleHash::new(e.hash)))
+        .collect())
+}
+
+#[derive(Debug, Default)]
+pub struct RepackSummary {
+    /// Nodes in the new pack
+    pub num_nodes: usize,
+    /// Loose node dirs that were moved into the pack
+    pub num_loose: usize,
+    /// Older packs that were merged into the new one
+    pub num_packs: usize,
+}
+
+/// Move every loose node and every existing pack into a single new pack. Loose nodes are
+/// only removed once the new pack and its index are on disk, so an interrupted repack
+/// leaves the repository readable.
+pub fn repack(repo: &LocalRepository) -> Result<RepackSummary, OxenError> {
+    let packs_dir = packs_dir(repo);
+    let old_indices = if packs_dir.exists() {
+        load_indices(&packs_dir)?
+    } else {
+        vec![]
+    };
+    let loose = list_loose(repo)?;
+    if loose.is_empty() && old_indices.len() <= 1 {
+        log::debug!("repack nothing to do");
+        return Ok(RepackSummary::default());
+    }
+    util::fs::create_dir_all(&packs_dir)?;
+
+    let name = format!("pack-{}", uuid::Uuid::new_v4());
+    let tmp_pack_path = packs_dir.join(format!("{name}.{PACK_EXT}.tmp"));
+    let mut pack = BufWriter::new(File::create(&tmp_pack_path)?);
+    let mut entries: Vec<PackEntry> = vec![];
+    let mut seen: HashSet<u128> = HashSet::new();
+    let mut offset = 0;
+    let mut write_entry =
+        |hash: u128, node_data: &[u8], children_data: &[u8]| -> Result<(), OxenError> {
+            pack.write_all(node_data)?;
+            pack.write_all(children_data)?;
+            entries.push(PackEntry {
+                hash,
+                offset,
+                node_len: node_data.len() as u64,
+                children_len: children_data.len() as u64,
+            });
+            offset += (node_data.len() + children_data.len()) as u64;
+            Ok(())
+        };
+
+    for index in old_indices.iter() {
+        for entry in index.entries.iter() {
+            if seen.insert(entry.hash) {
+                let (node_data, children_data) = index.read(entry)?;
+                write_entry(entry.hash, &node_data, &children_data)?;
+            }
+        }
+    }
+    for (hash, node_dir) in loose.iter() {
+        if seen.insert(hash.to_u128()) {
+            let node_data = std::fs::read(node_dir.join(NODE_FILE))?;
+            let children_data = std::fs::read(node_dir.join(CHILDREN_FILE))?;
+            write_entry(hash.to_u128(), &node_data, &children_data)?;
+        }
+    }
+    pack.into_inner()
+        .map_err(|e| OxenError::basic_str(format!("Could not write node pack: {e}")))?
+        .sync_all()?;
+
+    entries.sort_by_key(|e| e.hash);
+    let tmp_index_path = packs_dir.join(format!("{name}.{INDEX_EXT}.tmp"));
+    let mut index = BufWriter::new(File::create(&tmp_index_path)?);
+    index.write_all(INDEX_MAGIC)?;
+    index.write_all(&INDEX_VERSION.to_le_bytes())?;
+    index.write_all(&(entries.len() as u64).to_le_bytes())?;
+    for entry in entries.iter() {
+        index.write_all(&entry.hash.to_le_bytes())?;
+        index.write_all(&entry.offset.to_le_bytes())?;
+        index.write_all(&entry.node_len.to_le_bytes())?;
+        index.write_all(&entry.children_len.to_le_bytes())?;
+    }
+    index
+        .into_inner()
+        .map_err(|e| OxenError::basic_str(format!("Could not write node pack index: {e}")))?
+        .sync_all()?;
+
+    // The index goes in last, readers only look at packs that have one
+    util::fs::rename(&tmp_pack_path, packs_dir.join(format!("{name}.{PACK_EXT}")))?;
+    util::fs::rename(
+        &tmp_index_path,
+        packs_dir.join(format!("{name}.{INDEX_EXT}")),
+    )?;
+
+    for old in old_indices.iter() {
+        util::fs::remove_file(old.pack_path.with_extension(INDEX_EXT))?;
+        util::fs::remove_file(&old.pack_path)?;
+    }
+    for (_, node_dir) in loose.iter() {
+        util::fs::remove_dir_all(node_dir)?;
+        // Drop the prefix dir once it is empty
+        if let Some(prefix_dir) = node_dir.parent() {
+            let _ = std::fs::remove_dir(prefix_dir);
+        }
+    }
+
+    Ok(RepackSummary {
+        num_nodes: entries.len(),
+        num_loose: loose.len(),
+        num_packs: old_indices.len(),
+    })
+}
+
+/// The complete loose node dirs, .oxen/tree/nodes/{prefix}/{suffix}
+fn list_loose(repo: &LocalRepository) -> Result<Vec<(MerkleHash, PathBuf)>, OxenError> {
+    let nodes_dir = nodes_dir(repo);
+    let mut loose = vec![];
+    if !nodes_dir.exists() {
+        return Ok(loose);
+    }
+    for prefix_entry in std::fs::read_dir(&nodes_dir)? {
+        let prefix_dir = prefix_entry?.path();
+        if !prefix_dir.is_dir() {
+            continue;
+        }
+        for entry in std::fs::read_dir(&prefix_dir)? {
+            let node_dir = entry?.path();
+            // Skip nodes that are still being written
+            if !(node_dir.join(NODE_FILE).exists() && node_dir.join(CHILDREN_FILE).exists()) {
+                continue;
+            }
+            let (Some(prefix), Some(suffix)) = (prefix_dir.file_name(), node_dir.file_name())
+            else {
+                continue;
+            };
+            let hash_str = format!("{}{}", prefix.to_string_lossy(), suffix.to_string_lossy());
+            match hash_str.parse::<MerkleHash>() {
+                Ok(hash) => loose.push((hash, node_dir)),
+                Err(_) => log::warn!("Skipping unknown dir in nodes dir {node_dir:?}"),
+            }
+        }
+    }
+    Ok(loose)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::v0_19_0::index::merkle_node_db::node_db_path;
+    use crate::core::v0_19_0::index::node_pack;
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_repack_moves_loose_nodes_into_pack() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let commit = repositories::commits::head_commit(&repo)?;
+            let tree_before = repositories::tree::get_by_commit(&repo, &commit)?.root;
+            let num_files = repositories::entries::list_for_commit(&repo, &commit)?.len();
+
+            let summary = node_pack::repack(&repo)?;
+            assert!(summary.num_loose > 0);
+            assert_eq!(summary.num_packs, 0);
+            assert_eq!(summary.num_nodes, summary.num_loose);
+            assert!(!node_db_path(&repo, &tree_before.hash).exists());
+            assert!(node_pack::contains(&repo, &tree_before.hash));
+
+            // Everything still reads from the pack
+            let tree_after = repositories::tree::get_by_commit(&repo, &commit)?.root;
+            assert_eq!(tree_after.hash, tree_before.hash);
+            assert_eq!(
+                repositories::entries::list_for_commit(&repo, &commit)?.len(),
+                num_files
+            );
+
+            // New commits write loose nodes, a second repack merges them with the old pack
+            util::fs::write_to_path(repo.path.join("new.txt"), "new")?;
+            repositories::add(&repo, repo.path.join("new.txt"))?;
+            repositories::commit(&repo, "Adding new.txt")?;
+            let summary = node_pack::repack(&repo)?;
+            assert!(summary.num_loose > 0);
+            assert_eq!(summary.num_packs, 1);
+            assert_eq!(
+                std::fs::read_dir(node_pack::packs_dir(&repo))?.count(),
+                2 // one pack and its index
+            );
+
+            // Nothing left to do
+            assert_eq!(node_pack::repack(&repo)?.num_nodes, 0);
+
+            Ok(())
+        })
+    }
+}
@@ -77,8 +77,10 @@ fn restore_staged(repo: &LocalRepository, opts: RestoreOpts) -> Result<(), OxenE
             }
             log::debug!("restore::restore_staged: prepared to clear all staged entries");
         } else {
-            // Remove specific staged entry or entries under a directory
-            let prefix = opts.path.to_string_lossy().into_owned();
+            // Remove specific staged entry or entries under a directory, leaving the rest
+            // of the pending commit (including its staged schemas) alone
+            let relative_path = staged_key_path(repo, &opts.path);
+            let prefix = relative_path.to_string_lossy().into_owned();
             for result in db.iterator(rocksdb::IteratorMode::From(
                 prefix.as_bytes(),
                 rocksdb::Direction::Forward,
@@ -86,15 +88,17 @@ fn restore_staged(repo: &LocalRepository, opts: RestoreOpts) -> Result<(), OxenE
                 match result {
                     Ok((key, _)) => {
                         let key_str = String::from_utf8_lossy(&key);
-                        // if prefix is a file, it will also return true on starts_with
-                        if key_str.starts_with(&prefix) {
+                        if !key_str.starts_with(&prefix) {
+                            break; // Stop when we've passed all entries with the given prefix
+                        }
+                        // The prefix "data" also matches "data.csv" and "data2/", only remove
+                        // the path itself and what is below it
+                        if Path::new(key_str.as_ref()).starts_with(&relative_path) {
                             batch.delete(&key);
                             log::debug!(
                                 "restore::restore_staged: prepared to remove staged entry for path {:?}",
                                 key_str
                             );
-                        } else {
-                            break; // Stop when we've passed all entries with the given prefix
                         }
                     }
                     Err(e) => return Err(OxenError::basic_str(&e)),
@@ -149,6 +153,24 @@ fn restore_staged(repo: &LocalRepository, opts: RestoreOpts) -> Result<(), OxenE
     Ok(())
 }
 
+/// Staged db keys are relative to the repo root, but users may pass paths within the repo
+fn staged_key_path(repo: &LocalRepository, path: &Path) -> PathBuf {
+    let full_path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        match std::env::current_dir() {
+            Ok(cwd) if !path.starts_with(&repo.path) => cwd.join(path),
+            _ => path.to_path_buf(),
+        }
+    };
+    let full_path = dunce::canonicalize(&full_path).unwrap_or(full_path);
+    let repo_path = dunce::canonicalize(&repo.path).unwrap_or(repo.path.to_owned());
+    match full_path.strip_prefix(&repo_path) {
+        Ok(relative_path) => relative_path.to_path_buf(),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
 fn open_staged_db(db_path: &Path) -> Result<Option<DBWithThreadMode<SingleThreaded>>, OxenError> {
     if db_path.join("CURRENT").exists() {
         let opts = db::key_val::opts::default();
@@ -176,20 +198,14 @@ fn restore_dir(
     let bar =
         util::progress_bar::oxen_progress_bar_with_msg(file_nodes_with_paths.len() as u64, &msg);
 
-    let mut existing_files = HashSet::new();
-    if let Ok(entries) = fs::read_dir(path) {
-        existing_files.extend(
-            entries
-                .filter_map(Result::ok)
-                .filter(|e| e.path().is_file())
-                .map(|e| e.path()),
-        );
-    }
+    // Files tracked at HEAD but not in the revision being restored were added since,
+    // they get removed. Untracked files are never touched.
+    let mut added_files = head_files_in_dir(repo, path)?;
 
     file_nodes_with_paths
         .iter()
         .for_each(|(file_node, file_path)| {
-            existing_files.remove(file_path);
+            added_files.remove(file_path);
 
             match restore_file(repo, file_node, file_path) {
                 Ok(_) => log::debug!("restore::restore_dir: entry restored successfully"),
@@ -204,8 +220,11 @@ fn restore_dir(
             bar.inc(1);
         });
 
-    for file_to_remove in existing_files {
-        fs::remove_file(file_to_remove)?;
+    for file_to_remove in added_files {
+        let working_path = repo.path.join(&file_to_remove);
+        if working_path.is_file() {
+            fs::remove_file(working_path)?;
+        }
     }
 
     bar.finish_and_clear();
@@ -214,6 +233,22 @@ fn restore_dir(
     Ok(())
 }
 
+fn head_files_in_dir(
+    repo: &LocalRepository,
+    path: &PathBuf,
+) -> Result<HashSet<PathBuf>, OxenError> {
+    let Some(head) = repositories::commits::head_commit_maybe(repo)? else {
+        return Ok(HashSet::new());
+    };
+    let Some(dir) = CommitMerkleTree::dir_with_children_recursive(repo, &head, path)? else {
+        return Ok(HashSet::new());
+    };
+    Ok(CommitMerkleTree::dir_entries_with_paths(&dir, path)?
+        .into_iter()
+        .map(|(_, file_path)| file_path)
+        .collect())
+}
+
 pub fn restore_file(
     repo: &LocalRepository,
     file_node: &FileNode,
@@ -240,7 +275,7 @@ pub fn restore_file(
     log::debug!("restore::restore_regular: copying file");
     log::debug!("restore::restore_regular: version_path {:?}", version_path);
     log::debug!("restore::restore_regular: working_path {:?}", working_path);
-    util::fs::copy(version_path, working_path.clone())?;
+    util::fs::copy_version_to_working(repo, version_path, &working_path)?;
     let last_modified = std::time::SystemTime::UNIX_EPOCH
         + std::time::Duration::from_secs(last_modified_seconds as u64)
         + std::time::Duration::from_nanos(last_modified_nanoseconds as u64);
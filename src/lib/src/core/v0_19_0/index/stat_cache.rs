@@ -0,0 +1,163 @@
+//! # Stat Cache
+//!
+//! Remembers the content hash of each file in the working dir keyed by its path,
+//! modification time and size, so status and add only hash files that actually changed.
+//! It lives in the cache dir, so it is safe to delete at any time.
+//!
+
+use filetime::FileTime;
+use rocksdb::{DBWithThreadMode, MultiThreaded};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::constants::{CACHE_DIR, STAT_CACHE_DIR};
+use crate::core::db;
+use crate::core::db::key_val::str_json_db;
+use crate::error::OxenError;
+use crate::model::LocalRepository;
+use crate::util;
+
+// Files written this recently could change again without the mtime moving, so don't trust them
+const RACY_SECONDS: i64 = 2;
+
+#[derive(Serialize, Deserialize, Debug)]
+struct StatCacheEntry {
+    last_modified_seconds: i64,
+    last_modified_nanoseconds: u32,
+    num_bytes: u64,
+    hash: String,
+}
+
+pub struct StatCache {
+    db: DBWithThreadMode<MultiThreaded>,
+}
+
+impl StatCache {
+    pub fn open(repo: &LocalRepository) -> Result<StatCache, OxenError> {
+        let path = util::fs::oxen_hidden_dir(&repo.path)
+            .join(CACHE_DIR)
+            .join(STAT_CACHE_DIR);
+        if let Some(parent) = path.parent() {
+            util::fs::create_dir_all(parent)?;
+        }
+        let opts = db::key_val::opts::default();
+        let db = DBWithThreadMode::open(&opts, dunce::simplified(&path))?;
+        Ok(StatCache { db })
+    }
+
+    /// The cache only speeds things up, so if it can't be opened we hash everything
+    pub fn maybe_open(repo: &LocalRepository) -> Option<StatCache> {
+        match StatCache::open(repo) {
+            Ok(cache) => Some(cache),
+            Err(err) => {
+                log::warn!("Could not open stat cache, hashing all files: {}", err);
+                None
+            }
+        }
+    }
+
+    /// Get the cached hash if the file has the same mtime and size as when it was hashed
+    pub fn get(&self, relative_path: &Path, metadata: &std::fs::Metadata) -> Option<u128> {
+        let key = relative_path.to_string_lossy();
+        let entry: StatCacheEntry = str_json_db::get(&self.db, key).ok()??;
+        let mtime = FileTime::from_last_modification_time(metadata);
+        if entry.last_modified_seconds != mtime.unix_seconds()
+            || entry.last_modified_nanoseconds != mtime.nanoseconds()
+            || entry.num_bytes != metadata.len()
+        {
+            return None;
+        }
+        u128::from_str_radix(&entry.hash, 16).ok()
+    }
+
+    pub fn put(
+        &self,
+        relative_path: &Path,
+        metadata: &std::fs::Metadata,
+        hash: u128,
+    ) -> Result<(), OxenError> {
+        let mtime = FileTime::from_last_modification_time(metadata);
+        if is_racy(&mtime) {
+            return Ok(());
+        }
+        let entry = StatCacheEntry {
+            last_modified_seconds: mtime.unix_seconds(),
+            last_modified_nanoseconds: mtime.nanoseconds(),
+            num_bytes: metadata.len(),
+            hash: format!("{hash:x}"),
+        };
+        str_json_db::put(&self.db, relative_path.to_string_lossy(), &entry)
+    }
+}
+
+/// Hash the file unless the cache already knows the hash for its mtime and size
+pub fn hash_file(
+    cache: Option<&StatCache>,
+    relative_path: &Path,
+    full_path: &Path,
+    metadata: &std::fs::Metadata,
+) -> Result<u128, OxenError> {
+    if let Some(hash) = cache.and_then(|cache| cache.get(relative_path, metadata)) {
+        return Ok(hash);
+    }
+
+    let hash = util::hasher::get_hash_given_metadata(full_path, metadata)?;
+    if let Some(cache) = cache {
+        cache.put(relative_path, metadata, hash)?;
+    }
+    Ok(hash)
+}
+
+fn is_racy(mtime: &FileTime) -> bool {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(i64::MAX);
+    now - mtime.unix_seconds() < RACY_SECONDS
+}
+
+#[cfg(test)]
+mod tests {
+    use filetime::FileTime;
+    use std::path::Path;
+
+    use crate::core::v0_19_0::index::stat_cache::{self, StatCache};
+    use crate::error::OxenError;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_stat_cache_skips_unchanged_files() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let relative_path = Path::new("data.txt");
+            let full_path = repo.path.join(relative_path);
+            util::fs::write_to_path(&full_path, "hello")?;
+            // Old enough that the cache trusts it
+            let old = FileTime::from_unix_time(1_000_000_000, 0);
+            filetime::set_file_mtime(&full_path, old)?;
+
+            let cache = StatCache::open(&repo)?;
+            let metadata = std::fs::metadata(&full_path)?;
+            assert!(cache.get(relative_path, &metadata).is_none());
+            let hash = stat_cache::hash_file(Some(&cache), relative_path, &full_path, &metadata)?;
+            assert_eq!(cache.get(relative_path, &metadata), Some(hash));
+
+            // Same size and mtime, the cache is trusted
+            util::fs::write_to_path(&full_path, "world")?;
+            filetime::set_file_mtime(&full_path, old)?;
+            let metadata = std::fs::metadata(&full_path)?;
+            assert_eq!(cache.get(relative_path, &metadata), Some(hash));
+
+            // A new mtime means the file has to be hashed again
+            filetime::set_file_mtime(&full_path, FileTime::from_unix_time(1_000_000_001, 0))?;
+            let metadata = std::fs::metadata(&full_path)?;
+            assert!(cache.get(relative_path, &metadata).is_none());
+            let new_hash =
+                stat_cache::hash_file(Some(&cache), relative_path, &full_path, &metadata)?;
+            assert_ne!(hash, new_hash);
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,263 @@
+//! # Tree Cache
+//!
+//! Loaded merkle subtrees and dir hashes shared by every request in the process, so
+//! browsing the same dataset again does not reopen the node files. Nodes are content
+//! addressed and never change once written, but a subtree can be read while a push is
+//! still uploading it, so the server invalidates a repo's entries whenever new nodes
+//! or commits land.
+//!
+//! The cache is off until [set_max_nodes] is called, the CLI never needs it.
+//!
+
+use lazy_static::lazy_static;
+use lru::LruCache;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::model::merkle_tree::node::MerkleTreeNode;
+use crate::model::{LocalRepository, MerkleHash};
+
+/// Number of nodes kept in memory by the server unless configured otherwise, a few tens
+/// of MB for typical file nodes
+pub const DEFAULT_TREE_CACHE_NODES: usize = 50_000;
+
+/// Depth key for subtrees loaded all the way down
+pub const RECURSIVE: i32 = -1;
+
+type NodeKey = (PathBuf, MerkleHash, i32);
+type DirHashesKey = (PathBuf, String);
+
+struct Entries {
+    num_nodes: usize,
+    nodes: LruCache<NodeKey, (Arc<MerkleTreeNode>, usize)>,
+    dir_hashes: LruCache<DirHashesKey, Arc<HashMap<PathBuf, MerkleHash>>>,
+}
+
+/// LRU of loaded subtrees and dir hashes, bounded by the number of nodes in the subtrees.
+/// Hits hand out the shared subtree, so the lock is only held to look it up.
+pub struct TreeCache {
+    max_nodes: AtomicUsize,
+    entries: Mutex<Entries>,
+}
+
+lazy_static! {
+    static ref TREE_CACHE: TreeCache = TreeCache::new(0);
+}
+
+fn count_nodes(node: &MerkleTreeNode) -> usize {
+    1 + node.children.iter().map(count_nodes).sum::<usize>()
+}
+
+impl TreeCache {
+    pub fn new(max_nodes: usize) -> TreeCache {
+        TreeCache {
+            max_nodes: AtomicUsize::new(max_nodes),
+            entries: Mutex::new(Entries {
+                num_nodes: 0,
+                nodes: LruCache::unbounded(),
+                // Dir hashes are small, a few per hot commit is plenty
+                dir_hashes: LruCache::new(std::num::NonZeroUsize::new(1024).unwrap()),
+            }),
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.max_nodes.load(Ordering::Relaxed) > 0
+    }
+
+    /// Limit the cache to `max_nodes` merkle tree nodes across all subtrees, 0 turns it off
+    pub fn set_max_nodes(&self, max_nodes: usize) {
+        self.max_nodes.store(max_nodes, Ordering::Relaxed);
+        let mut entries = self.entries.lock().unwrap();
+        entries.evict(max_nodes);
+        if max_nodes == 0 {
+            entries.dir_hashes.clear();
+        }
+    }
+
+    /// The subtree under `hash` loaded to `depth`, or [RECURSIVE]
+    pub fn get(
+        &self,
+        repo: &LocalRepository,
+        hash: &MerkleHash,
+        depth: i32,
+    ) -> Option<Arc<MerkleTreeNode>> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let key = (repo.path.clone(), *hash, depth);
+        let mut entries = self.entries.lock().unwrap();
+        entries.nodes.get(&key).map(|(node, _)| node.clone())
+    }
+
+    pub fn put(&self, repo: &LocalRepository, depth: i32, node: &MerkleTreeNode) {
+        let max_nodes = self.max_nodes.load(Ordering::Relaxed);
+        if max_nodes == 0 {
+            return;
+        }
+        // Counted and copied before taking the lock, subtrees can be large
+        let size = count_nodes(node);
+        // A subtree bigger than the whole cache would just flush everything else
+        if size > max_nodes {
+            return;
+        }
+        let key = (repo.path.clone(), node.hash, depth);
+        let node = Arc::new(node.clone());
+        let mut entries = self.entries.lock().unwrap();
+        if let Some((_, old_size)) = entries.nodes.put(key, (node, size)) {
+            entries.num_nodes -= old_size;
+        }
+        entries.num_nodes += size;
+        entries.evict(max_nodes);
+    }
+
+    pub fn get_dir_hashes(
+        &self,
+        repo: &LocalRepository,
+        commit_id: &str,
+    ) -> Option<Arc<HashMap<PathBuf, MerkleHash>>> {
+        if !self.is_enabled() {
+            return None;
+        }
+        let key = (repo.path.clone(), commit_id.to_string());
+        let mut entries = self.entries.lock().unwrap();
+        entries.dir_hashes.get(&key).cloned()
+    }
+
+    pub fn put_dir_hashes(
+        &self,
+        repo: &LocalRepository,
+        commit_id: &str,
+        dir_hashes: &HashMap<PathBuf, MerkleHash>,
+    ) {
+        if !self.is_enabled() {
+            return;
+        }
+        let key = (repo.path.clone(), commit_id.to_string());
+        let dir_hashes = Arc::new(dir_hashes.clone());
+        let mut entries = self.entries.lock().unwrap();
+        entries.dir_hashes.put(key, dir_hashes);
+    }
+
+    /// Drop everything cached for the repository at `repo_path`
+    pub fn invalidate(&self, repo_path: impl AsRef<Path>) {
+        let repo_path = repo_path.as_ref();
+        let mut entries = self.entries.lock().unwrap();
+        let stale: Vec<NodeKey> = entries
+            .nodes
+            .iter()
+            .filter(|((path, _, _), _)| path == repo_path)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in stale {
+            if let Some((_, size)) = entries.nodes.pop(&key) {
+                entries.num_nodes -= size;
+            }
+        }
+        let stale: Vec<DirHashesKey> = entries
+            .dir_hashes
+            .iter()
+            .filter(|((path, _), _)| path == repo_path)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in stale {
+            entries.dir_hashes.pop(&key);
+        }
+    }
+}
+
+impl Entries {
+    fn evict(&mut self, max_nodes: usize) {
+        while self.num_nodes > max_nodes {
+            let Some((_, (_, size))) = self.nodes.pop_lru() else {
+                break;
+            };
+            self.num_nodes -= size;
+        }
+    }
+}
+
+/// Limit the cache of the process to `max_nodes` merkle tree nodes, 0 turns it off
+pub fn set_max_nodes(max_nodes: usize) {
+    TREE_CACHE.set_max_nodes(max_nodes)
+}
+
+pub fn get(repo: &LocalRepository, hash: &MerkleHash, depth: i32) -> Option<Arc<MerkleTreeNode>> {
+    TREE_CACHE.get(repo, hash, depth)
+}
+
+pub fn put(repo: &LocalRepository, depth: i32, node: &MerkleTreeNode) {
+    TREE_CACHE.put(repo, depth, node)
+}
+
+pub fn get_dir_hashes(
+    repo: &LocalRepository,
+    commit_id: &str,
+) -> Option<Arc<HashMap<PathBuf, MerkleHash>>> {
+    TREE_CACHE.get_dir_hashes(repo, commit_id)
+}
+
+pub fn put_dir_hashes(
+    repo: &LocalRepository,
+    commit_id: &str,
+    dir_hashes: &HashMap<PathBuf, MerkleHash>,
+) {
+    TREE_CACHE.put_dir_hashes(repo, commit_id, dir_hashes)
+}
+
+/// Drop everything the cache of the process holds for the repository at `repo_path`
+pub fn invalidate(repo_path: impl AsRef<Path>) {
+    TREE_CACHE.invalidate(repo_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::v0_19_0::index::tree_cache::{self, TreeCache};
+    use crate::core::v0_19_0::index::CommitMerkleTree;
+    use crate::error::OxenError;
+    use crate::model::MerkleHash;
+    use crate::repositories;
+    use crate::test;
+
+    use std::str::FromStr;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_tree_cache_serves_and_invalidates_subtrees() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let commit = repositories::commits::head_commit(&repo)?;
+            let hash = MerkleHash::from_str(&commit.id)?;
+            let tree = CommitMerkleTree::from_commit(&repo, &commit)?;
+            let dir_hashes = CommitMerkleTree::dir_hashes(&repo, &commit)?;
+
+            let cache = TreeCache::new(tree_cache::DEFAULT_TREE_CACHE_NODES);
+            cache.put(&repo, tree_cache::RECURSIVE, &tree.root);
+            cache.put_dir_hashes(&repo, &commit.id, &dir_hashes);
+
+            // Hits share the cached subtree instead of copying it
+            let cached = cache.get(&repo, &hash, tree_cache::RECURSIVE).unwrap();
+            assert_eq!(cached.children.len(), tree.root.children.len());
+            let again = cache.get(&repo, &hash, tree_cache::RECURSIVE).unwrap();
+            assert!(Arc::ptr_eq(&cached, &again));
+            assert!(cache.get(&repo, &hash, 0).is_none());
+            assert!(cache.get_dir_hashes(&repo, &commit.id).is_some());
+
+            cache.invalidate(&repo.path);
+            assert!(cache.get(&repo, &hash, tree_cache::RECURSIVE).is_none());
+            assert!(cache.get_dir_hashes(&repo, &commit.id).is_none());
+
+            // Subtrees over the limit are not kept
+            cache.set_max_nodes(1);
+            cache.put(&repo, tree_cache::RECURSIVE, &tree.root);
+            assert!(cache.get(&repo, &hash, tree_cache::RECURSIVE).is_none());
+
+            // Nor is anything once it is off
+            cache.set_max_nodes(0);
+            cache.put(&repo, 0, &tree.root);
+            assert!(cache.get(&repo, &hash, 0).is_none());
+            Ok(())
+        })
+    }
+}
@@ -10,7 +10,7 @@ use crate::core::v0_19_0::{add, rm};
 use crate::error::OxenError;
 use crate::model::merge_conflict::NodeMergeConflict;
 use crate::model::merkle_tree::node::{EMerkleTreeNode, FileNode, MerkleTreeNode};
-use crate::model::{Branch, Commit, LocalRepository};
+use crate::model::{Branch, Commit, LocalRepository, MerkleHash};
 use crate::opts::RmOpts;
 use crate::repositories;
 use crate::repositories::merge::MergeCommits;
@@ -20,6 +20,7 @@ use rocksdb::DB;
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::str;
+use std::str::FromStr;
 
 use super::index::{self, CommitMerkleTree};
 
@@ -583,7 +584,7 @@ fn create_merge_commit(
     // let stager = Stager::new(repo)?;
     // stager.add(&repo.path, &reader, &schema_reader, &ignore)?;
     let head_commit = repositories::commits::head_commit(repo)?;
-    add::add_dir(repo, &Some(head_commit), repo.path.clone())?;
+    add::add_dir(repo, &Some(head_commit), repo.path.clone(), false)?;
 
     let commit_msg = format!(
         "Merge commit {} into {}",
@@ -611,7 +612,7 @@ fn create_merge_commit_on_branch(
 ) -> Result<Commit, OxenError> {
     // Stage changes
     let head_commit = repositories::commits::head_commit(repo)?;
-    add::add_dir(repo, &Some(head_commit), repo.path.clone())?;
+    add::add_dir(repo, &Some(head_commit), repo.path.clone(), false)?;
 
     let commit_msg = format!(
         "Merge commit {} into {} on branch {}",
@@ -749,18 +750,18 @@ pub fn find_merge_conflicts(
                     && base_file_node.hash != merge_entry.0.hash
                 {
                     conflicts.push(NodeMergeConflict {
-                        lca_entry: lca_entry.to_owned(),
-                        base_entry: base_entry.to_owned(),
-                        merge_entry: merge_entry.to_owned(),
+                        lca_entry: conflict_entry(lca_entry, &merge_commits.lca)?,
+                        base_entry: conflict_entry(base_entry, &merge_commits.base)?,
+                        merge_entry: conflict_entry(merge_entry, &merge_commits.merge)?,
                     });
                 }
             } else {
                 // merge entry doesn't exist in LCA, so just check if it's different from base
                 if base_file_node.hash != merge_entry.0.hash {
                     conflicts.push(NodeMergeConflict {
-                        lca_entry: base_entry.to_owned(),
-                        base_entry: base_entry.to_owned(),
-                        merge_entry: merge_entry.to_owned(),
+                        lca_entry: conflict_entry(base_entry, &merge_commits.base)?,
+                        base_entry: conflict_entry(base_entry, &merge_commits.base)?,
+                        merge_entry: conflict_entry(merge_entry, &merge_commits.merge)?,
                     });
                 }
             }
@@ -775,6 +776,19 @@ pub fn find_merge_conflicts(
     Ok(conflicts)
 }
 
+// Content addressed trees leave the last commit off their nodes, so point the conflict at
+// the commit the entry was read from to still find its version
+fn conflict_entry(
+    entry: &(FileNode, PathBuf),
+    commit: &Commit,
+) -> Result<(FileNode, PathBuf), OxenError> {
+    let (mut file_node, path) = entry.to_owned();
+    if file_node.last_commit_id == MerkleHash::new(0) {
+        file_node.last_commit_id = MerkleHash::from_str(&commit.id)?;
+    }
+    Ok((file_node, path))
+}
+
 fn update_entry(
     repo: &LocalRepository,
     merge_entry: &(FileNode, PathBuf),
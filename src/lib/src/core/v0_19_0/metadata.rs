@@ -1,9 +1,9 @@
 //! Helper functions to get metadata from the local filesystem.
 //!
 
+use crate::core;
 use crate::error::OxenError;
 use crate::model::entry::metadata_entry::CLIMetadataEntry;
-use crate::model::merkle_tree::node::EMerkleTreeNode;
 use crate::model::{Commit, LocalRepository};
 use crate::repositories;
 use crate::util;
@@ -33,16 +33,14 @@ pub fn get_cli(
     if let Ok(head_commit) = repositories::commits::head_commit(repo) {
         let tree = CommitMerkleTree::from_commit(repo, &head_commit)?;
         if let Some(node) = tree.get_by_path(entry_path)? {
-            if let EMerkleTreeNode::File(file_node) = &node.node {
-                let last_commit_id = file_node.last_commit_id.to_string();
+            if let Some(last_commit_id) = core::v0_19_0::commits::last_commit_id_for_node(
+                repo,
+                &head_commit,
+                entry_path,
+                &node,
+            )? {
                 // this commit is guaranteed to exist because we are iterating through the tree
-                let commit = repositories::commits::get_by_id(repo, &last_commit_id)?.unwrap();
-                last_updated = Some(commit);
-            }
-            if let EMerkleTreeNode::Directory(dir_node) = &node.node {
-                let last_commit_id = dir_node.last_commit_id.to_string();
-                let commit = repositories::commits::get_by_id(repo, &last_commit_id)?.unwrap();
-                last_updated = Some(commit);
+                last_updated = repositories::commits::get_by_hash(repo, &last_commit_id)?;
             }
         }
     }
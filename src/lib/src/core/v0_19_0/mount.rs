@@ -0,0 +1,45 @@
+//! Read only view of a commit, served through FUSE by `oxen mount`.
+//!
+//! Directories are listed lazily from the merkle tree, and file contents are
+//! read from the local version store or fetched block by block from the remote.
+//!
+
+pub mod block_cache;
+pub use block_cache::BlockCache;
+
+pub mod inodes;
+pub use inodes::{InodeEntry, InodeKind, InodeTable, ROOT_INODE};
+
+#[cfg(feature = "mount")]
+pub mod filesystem;
+#[cfg(feature = "mount")]
+pub use filesystem::OxenFs;
+
+#[cfg(feature = "mount")]
+use std::path::Path;
+
+#[cfg(feature = "mount")]
+use crate::error::OxenError;
+#[cfg(feature = "mount")]
+use crate::model::{Commit, LocalRepository, RemoteRepository};
+
+/// Mount the commit read only at the mountpoint, blocks until it is unmounted
+#[cfg(feature = "mount")]
+pub fn mount(
+    repo: &LocalRepository,
+    commit: &Commit,
+    mountpoint: &Path,
+    remote_repo: Option<RemoteRepository>,
+) -> Result<(), OxenError> {
+    let inodes = InodeTable::new(repo, commit)?;
+    let cache = BlockCache::new(repo, commit, remote_repo)?;
+    let fs = OxenFs::new(inodes, cache);
+
+    let options = [
+        fuser::MountOption::RO,
+        fuser::MountOption::FSName(String::from("oxen")),
+        fuser::MountOption::DefaultPermissions,
+    ];
+    fuser::mount2(fs, mountpoint, &options)?;
+    Ok(())
+}
@@ -0,0 +1,239 @@
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use lru::LruCache;
+use tokio::runtime::Handle;
+
+use crate::api;
+use crate::constants::{AVG_CHUNK_SIZE, CACHE_DIR, MOUNT_CACHE_DIR};
+use crate::error::OxenError;
+use crate::model::{Commit, LocalRepository, RemoteRepository};
+use crate::util;
+
+/// Size of the blocks fetched from the remote, matches the chunked download size
+pub const BLOCK_SIZE: u64 = AVG_CHUNK_SIZE;
+const NUM_BLOCKS_IN_MEMORY: usize = 32;
+
+/// Serves byte ranges of versioned files.
+///
+/// Plain files in the local version store are read in place, compressed or encrypted
+/// ones are decoded into the in memory blocks. Anything else is fetched from the remote
+/// one block at a time and kept on disk under .oxen/cache/mount. The most recently used
+/// blocks are kept in memory.
+pub struct BlockCache {
+    repo: LocalRepository,
+    commit_id: String,
+    remote_repo: Option<RemoteRepository>,
+    runtime: Option<Handle>,
+    cache_dir: PathBuf,
+    blocks: LruCache<(String, u64), Arc<Vec<u8>>>,
+}
+
+impl BlockCache {
+    pub fn new(
+        repo: &LocalRepository,
+        commit: &Commit,
+        remote_repo: Option<RemoteRepository>,
+    ) -> Result<BlockCache, OxenError> {
+        let cache_dir = util::fs::oxen_hidden_dir(&repo.path)
+            .join(CACHE_DIR)
+            .join(MOUNT_CACHE_DIR);
+        util::fs::create_dir_all(&cache_dir)?;
+
+        Ok(BlockCache {
+            repo: repo.clone(),
+            commit_id: commit.id.to_owned(),
+            remote_repo,
+            runtime: Handle::try_current().ok(),
+            cache_dir,
+            blocks: LruCache::new(NonZeroUsize::new(NUM_BLOCKS_IN_MEMORY).unwrap()),
+        })
+    }
+
+    /// Read up to `size` bytes of the file starting at `offset`
+    pub fn read(
+        &mut self,
+        path: &Path,
+        hash: &str,
+        num_bytes: u64,
+        offset: u64,
+        size: u64,
+    ) -> Result<Vec<u8>, OxenError> {
+        if offset >= num_bytes || size == 0 {
+            return Ok(vec![]);
+        }
+        let end = std::cmp::min(offset + size, num_bytes);
+
+        let version_path = util::fs::version_path_from_hash(&self.repo, hash);
+        if version_path.exists() && util::encryption::is_plain(&version_path) {
+            let mut file = File::open(&version_path)?;
+            file.seek(SeekFrom::Start(offset))?;
+            let mut data = vec![0; (end - offset) as usize];
+            let num_read = file.read(&mut data)?;
+            data.truncate(num_read);
+            return Ok(data);
+        }
+
+        let mut data = Vec::with_capacity((end - offset) as usize);
+        let mut pos = offset;
+        while pos < end {
+            let block_idx = pos / BLOCK_SIZE;
+            let block_start = block_idx * BLOCK_SIZE;
+            let block = self.get_block(path, hash, num_bytes, block_idx)?;
+
+            let from = (pos - block_start) as usize;
+            let to = (std::cmp::min(end, block_start + block.len() as u64) - block_start) as usize;
+            if from >= to {
+                // Short block, do not loop forever on a truncated download
+                break;
+            }
+            data.extend_from_slice(&block[from..to]);
+            pos = block_start + to as u64;
+        }
+        Ok(data)
+    }
+
+    fn get_block(
+        &mut self,
+        path: &Path,
+        hash: &str,
+        num_bytes: u64,
+        block_idx: u64,
+    ) -> Result<Arc<Vec<u8>>, OxenError> {
+        let key = (hash.to_string(), block_idx);
+        if let Some(block) = self.blocks.get(&key) {
+            return Ok(block.clone());
+        }
+
+        let version_path = util::fs::version_path_from_hash(&self.repo, hash);
+        let block_path = self.cache_dir.join(hash).join(block_idx.to_string());
+        let block = if version_path.exists() {
+            // Compressed or encrypted versions are decoded a block at a time and the plain
+            // blocks are only ever kept in memory
+            util::encryption::read_plain_range(
+                &self.repo,
+                &version_path,
+                block_idx * BLOCK_SIZE,
+                BLOCK_SIZE,
+            )?
+            .bytes
+        } else if block_path.exists() {
+            std::fs::read(&block_path)?
+        } else {
+            let bytes = self.download_block(path, num_bytes, block_idx)?;
+            util::fs::create_dir_all(self.cache_dir.join(hash))?;
+            std::fs::write(&block_path, &bytes)?;
+            bytes
+        };
+
+        let block = Arc::new(block);
+        self.blocks.put(key, block.clone());
+        Ok(block)
+    }
+
+    fn download_block(
+        &self,
+        path: &Path,
+        num_bytes: u64,
+        block_idx: u64,
+    ) -> Result<Vec<u8>, OxenError> {
+        let (Some(remote_repo), Some(runtime)) = (&self.remote_repo, &self.runtime) else {
+            return Err(OxenError::basic_str(format!(
+                "{:?} is not downloaded and there is no remote to fetch it from",
+                path
+            )));
+        };
+
+        let start = block_idx * BLOCK_SIZE;
+        let size = std::cmp::min(BLOCK_SIZE, num_bytes - start);
+        log::debug!("Fetching block {block_idx} of {:?} from remote", path);
+        runtime.block_on(api::client::entries::download_chunk_bytes(
+            remote_repo,
+            path,
+            &self.commit_id,
+            start,
+            size,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::constants::{CACHE_DIR, MOUNT_CACHE_DIR};
+    use crate::core::v0_19_0::mount::BlockCache;
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_block_cache_reads_version_files_and_cached_blocks() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let hello_file = repo.path.join("hello.txt");
+            util::fs::write_to_path(&hello_file, "Hello World")?;
+            repositories::add(&repo, &hello_file)?;
+            let commit = repositories::commit(&repo, "Adding hello")?;
+            let entry =
+                repositories::entries::get_commit_entry(&repo, &commit, Path::new("hello.txt"))?
+                    .unwrap();
+
+            let mut cache = BlockCache::new(&repo, &commit, None)?;
+            let path = Path::new("hello.txt");
+            let data = cache.read(path, &entry.hash, entry.num_bytes, 6, 100)?;
+            assert_eq!(data, b"World");
+
+            // Once the version file is gone, blocks come from the cache dir
+            util::fs::remove_file(util::fs::version_path_from_hash(&repo, &entry.hash))?;
+            assert!(cache
+                .read(path, &entry.hash, entry.num_bytes, 0, 5)
+                .is_err());
+
+            let block_dir = util::fs::oxen_hidden_dir(&repo.path)
+                .join(CACHE_DIR)
+                .join(MOUNT_CACHE_DIR)
+                .join(&entry.hash);
+            util::fs::create_dir_all(&block_dir)?;
+            util::fs::write_to_path(block_dir.join("0"), "Hello World")?;
+            let data = cache.read(path, &entry.hash, entry.num_bytes, 0, 5)?;
+            assert_eq!(data, b"Hello");
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_block_cache_reads_encrypted_version_files() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|mut repo| {
+            let (_, key_path) = repositories::encryption::enable(&mut repo)?;
+            let hello_file = repo.path.join("hello.txt");
+            util::fs::write_to_path(&hello_file, "Hello World")?;
+            repositories::add(&repo, &hello_file)?;
+            let commit = repositories::commit(&repo, "Adding hello")?;
+            let entry =
+                repositories::entries::get_commit_entry(&repo, &commit, Path::new("hello.txt"))?
+                    .unwrap();
+
+            let mut cache = BlockCache::new(&repo, &commit, None)?;
+            let path = Path::new("hello.txt");
+            let data = cache.read(path, &entry.hash, entry.num_bytes, 6, 100)?;
+            assert_eq!(data, b"World");
+
+            // Nothing decoded is written to disk
+            let tmp_dir = util::fs::oxen_hidden_dir(&repo.path).join("tmp");
+            assert!(!tmp_dir.exists() || util::fs::rlist_files_in_dir(&tmp_dir).is_empty());
+            let block_dir = util::fs::oxen_hidden_dir(&repo.path)
+                .join(CACHE_DIR)
+                .join(MOUNT_CACHE_DIR)
+                .join(&entry.hash);
+            assert!(!block_dir.exists());
+
+            util::fs::remove_file(key_path)?;
+            Ok(())
+        })
+    }
+}
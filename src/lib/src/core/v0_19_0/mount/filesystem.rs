@@ -0,0 +1,187 @@
+use std::ffi::OsStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use fuser::{
+    FileAttr, FileType, Filesystem, ReplyAttr, ReplyData, ReplyDirectory, ReplyEntry, ReplyOpen,
+    Request,
+};
+
+use crate::core::v0_19_0::mount::{BlockCache, InodeEntry, InodeKind, InodeTable, ROOT_INODE};
+
+/// The tree of a commit never changes, so the kernel can hold on to attributes
+const TTL: Duration = Duration::from_secs(60);
+const BLOCK_SIZE: u32 = 512;
+
+/// Read only FUSE filesystem over a single commit
+pub struct OxenFs {
+    inodes: InodeTable,
+    cache: BlockCache,
+    uid: u32,
+    gid: u32,
+}
+
+impl OxenFs {
+    pub fn new(inodes: InodeTable, cache: BlockCache) -> OxenFs {
+        // Safety: getuid and getgid always succeed
+        let (uid, gid) = unsafe { (libc::getuid(), libc::getgid()) };
+        OxenFs {
+            inodes,
+            cache,
+            uid,
+            gid,
+        }
+    }
+
+    fn attr(&self, entry: &InodeEntry) -> FileAttr {
+        let mtime = UNIX_EPOCH
+            + Duration::new(
+                entry.last_modified_seconds.max(0) as u64,
+                entry.last_modified_nanoseconds,
+            );
+        let (kind, perm, nlink, size) = match entry.kind {
+            InodeKind::Dir => (FileType::Directory, 0o555, 2, 0),
+            InodeKind::File { .. } => (FileType::RegularFile, 0o444, 1, entry.num_bytes),
+        };
+        FileAttr {
+            ino: entry.ino,
+            size,
+            blocks: size.div_ceil(BLOCK_SIZE as u64),
+            atime: mtime,
+            mtime,
+            ctime: mtime,
+            crtime: mtime,
+            kind,
+            perm,
+            nlink,
+            uid: self.uid,
+            gid: self.gid,
+            rdev: 0,
+            blksize: BLOCK_SIZE,
+            flags: 0,
+        }
+    }
+}
+
+impl Filesystem for OxenFs {
+    fn lookup(&mut self, _req: &Request<'_>, parent: u64, name: &OsStr, reply: ReplyEntry) {
+        match self.inodes.lookup(parent, name) {
+            Ok(Some(ino)) => {
+                let attr = self.attr(self.inodes.get(ino).unwrap());
+                reply.entry(&TTL, &attr, 0);
+            }
+            Ok(None) => reply.error(libc::ENOENT),
+            Err(err) => {
+                log::error!("oxen mount lookup {:?} failed: {}", name, err);
+                reply.error(libc::EIO);
+            }
+        }
+    }
+
+    fn getattr(&mut self, _req: &Request<'_>, ino: u64, _fh: Option<u64>, reply: ReplyAttr) {
+        match self.inodes.get(ino) {
+            Some(entry) => reply.attr(&TTL, &self.attr(entry)),
+            None => reply.error(libc::ENOENT),
+        }
+    }
+
+    fn open(&mut self, _req: &Request<'_>, ino: u64, flags: i32, reply: ReplyOpen) {
+        if flags & libc::O_ACCMODE != libc::O_RDONLY {
+            reply.error(libc::EROFS);
+            return;
+        }
+        match self.inodes.get(ino) {
+            Some(entry) if matches!(entry.kind, InodeKind::File { .. }) => reply.opened(0, 0),
+            Some(_) => reply.error(libc::EISDIR),
+            None => reply.error(libc::ENOENT),
+        }
+    }
+
+    fn read(
+        &mut self,
+        _req: &Request<'_>,
+        ino: u64,
+        _fh: u64,
+        offset: i64,
+        size: u32,
+        _flags: i32,
+        _lock_owner: Option<u64>,
+        reply: ReplyData,
+    ) {
+        let Some(entry) = self.inodes.get(ino).cloned() else {
+            reply.error(libc::ENOENT);
+            return;
+        };
+        let InodeKind::File { hash } = &entry.kind else {
+            reply.error(libc::EISDIR);
+            return;
+        };
+
+        match self.cache.read(
+            &entry.path,
+            hash,
+            entry.num_bytes,
+            offset.max(0) as u64,
+            size as u64,
+        ) {
+            Ok(data) => reply.data(&data),
+            Err(err) => {
+                log::error!("oxen mount read {:?} failed: {}", entry.path, err);
+                reply.error(libc::EIO);
+            }
+        }
+    }
+
+    fn readdir(
+        &mut self,
+        _req: &Request<'_>,
+        ino: u64,
+        _fh: u64,
+        offset: i64,
+        mut reply: ReplyDirectory,
+    ) {
+        let children = match self.inodes.children(ino) {
+            Ok(children) => children,
+            Err(err) => {
+                log::error!("oxen mount readdir {} failed: {}", ino, err);
+                reply.error(libc::ENOTDIR);
+                return;
+            }
+        };
+
+        let parent = match self.inodes.get(ino).and_then(|e| e.path.parent()) {
+            Some(parent) => self.inodes.get_by_path(parent).unwrap_or(ROOT_INODE),
+            None => ROOT_INODE,
+        };
+        let mut entries = vec![
+            (ino, FileType::Directory, OsStr::new(".").to_os_string()),
+            (parent, FileType::Directory, OsStr::new("..").to_os_string()),
+        ];
+        for child in children {
+            let entry = self.inodes.get(child).unwrap();
+            let kind = match entry.kind {
+                InodeKind::Dir => FileType::Directory,
+                InodeKind::File { .. } => FileType::RegularFile,
+            };
+            let name = entry.path.file_name().unwrap_or_default().to_os_string();
+            entries.push((child, kind, name));
+        }
+
+        // The offset is the index of the next entry the kernel wants
+        for (i, (ino, kind, name)) in entries.into_iter().enumerate().skip(offset.max(0) as usize) {
+            if reply.add(ino, (i + 1) as i64, kind, name) {
+                break;
+            }
+        }
+        reply.ok();
+    }
+
+    fn access(&mut self, _req: &Request<'_>, ino: u64, mask: i32, reply: fuser::ReplyEmpty) {
+        if mask & libc::W_OK != 0 {
+            reply.error(libc::EROFS);
+        } else if self.inodes.get(ino).is_some() {
+            reply.ok();
+        } else {
+            reply.error(libc::ENOENT);
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::core::v0_19_0::index::CommitMerkleTree;
+use crate::error::OxenError;
+use crate::model::merkle_tree::node::EMerkleTreeNode;
+use crate::model::{Commit, LocalRepository};
+
+/// FUSE reserves inode 1 for the root of the filesystem
+pub const ROOT_INODE: u64 = 1;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum InodeKind {
+    Dir,
+    File { hash: String },
+}
+
+#[derive(Debug, Clone)]
+pub struct InodeEntry {
+    pub ino: u64,
+    pub path: PathBuf,
+    pub kind: InodeKind,
+    pub num_bytes: u64,
+    pub last_modified_seconds: i64,
+    pub last_modified_nanoseconds: u32,
+}
+
+/// Hands out inode numbers for the files and directories of a commit.
+///
+/// Directories are only read from the merkle tree the first time they are listed,
+/// so mounting a large repository does not load the whole tree.
+pub struct InodeTable {
+    repo: LocalRepository,
+    commit: Commit,
+    entries: Vec<InodeEntry>,
+    by_path: HashMap<PathBuf, u64>,
+    children: HashMap<u64, Vec<u64>>,
+}
+
+impl InodeTable {
+    pub fn new(repo: &LocalRepository, commit: &Commit) -> Result<InodeTable, OxenError> {
+        let Some(root) = CommitMerkleTree::dir_without_children(repo, commit, "")? else {
+            return Err(OxenError::basic_str(format!(
+                "Could not find the root directory of commit {}",
+                commit.id
+            )));
+        };
+        let EMerkleTreeNode::Directory(dir) = &root.node else {
+            return Err(OxenError::basic_str("Root node is not a directory"));
+        };
+
+        let mut table = InodeTable {
+            repo: repo.clone(),
+            commit: commit.clone(),
+            entries: vec![],
+            by_path: HashMap::new(),
+            children: HashMap::new(),
+        };
+        table.insert(
+            PathBuf::from(""),
+            InodeKind::Dir,
+            dir.num_bytes,
+            dir.last_modified_seconds,
+            dir.last_modified_nanoseconds,
+        );
+        Ok(table)
+    }
+
+    pub fn get(&self, ino: u64) -> Option<&InodeEntry> {
+        let idx = ino.checked_sub(ROOT_INODE)?;
+        self.entries.get(idx as usize)
+    }
+
+    pub fn get_by_path(&self, path: impl AsRef<Path>) -> Option<u64> {
+        self.by_path.get(path.as_ref()).copied()
+    }
+
+    /// List the inodes directly inside a directory, sorted by name
+    pub fn children(&mut self, ino: u64) -> Result<Vec<u64>, OxenError> {
+        if let Some(children) = self.children.get(&ino) {
+            return Ok(children.clone());
+        }
+
+        let Some(entry) = self.get(ino) else {
+            return Err(OxenError::basic_str(format!("Unknown inode {ino}")));
+        };
+        if entry.kind != InodeKind::Dir {
+            return Err(OxenError::basic_str(format!(
+                "{:?} is not a directory",
+                entry.path
+            )));
+        }
+        let dir_path = entry.path.clone();
+
+        let mut children = vec![];
+        if let Some(node) =
+            CommitMerkleTree::dir_with_children(&self.repo, &self.commit, &dir_path)?
+        {
+            for child in CommitMerkleTree::node_files_and_folders(&node)? {
+                let child_ino = match &child.node {
+                    EMerkleTreeNode::File(file) => self.insert(
+                        dir_path.join(&file.name),
+                        InodeKind::File {
+                            hash: file.hash.to_string(),
+                        },
+                        file.num_bytes,
+                        file.last_modified_seconds,
+                        file.last_modified_nanoseconds,
+                    ),
+                    EMerkleTreeNode::Directory(dir) => self.insert(
+                        dir_path.join(&dir.name),
+                        InodeKind::Dir,
+                        dir.num_bytes,
+                        dir.last_modified_seconds,
+                        dir.last_modified_nanoseconds,
+                    ),
+                    _ => continue,
+                };
+                children.push(child_ino);
+            }
+        }
+        children.sort_by(|a, b| self.get(*a).unwrap().path.cmp(&self.get(*b).unwrap().path));
+
+        self.children.insert(ino, children.clone());
+        Ok(children)
+    }
+
+    /// Find a file or directory by name inside a directory
+    pub fn lookup(
+        &mut self,
+        parent: u64,
+        name: impl AsRef<Path>,
+    ) -> Result<Option<u64>, OxenError> {
+        let name = name.as_ref();
+        for child in self.children(parent)? {
+            if self.get(child).and_then(|e| e.path.file_name()) == Some(name.as_os_str()) {
+                return Ok(Some(child));
+            }
+        }
+        Ok(None)
+    }
+
+    fn insert(
+        &mut self,
+        path: PathBuf,
+        kind: InodeKind,
+        num_bytes: u64,
+        last_modified_seconds: i64,
+        last_modified_nanoseconds: u32,
+    ) -> u64 {
+        if let Some(ino) = self.by_path.get(&path) {
+            return *ino;
+        }
+        let ino = self.entries.len() as u64 + ROOT_INODE;
+        self.by_path.insert(path.clone(), ino);
+        self.entries.push(InodeEntry {
+            ino,
+            path,
+            kind,
+            num_bytes,
+            last_modified_seconds,
+            last_modified_nanoseconds,
+        });
+        ino
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use crate::core::v0_19_0::mount::{InodeKind, InodeTable, ROOT_INODE};
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+
+    #[test]
+    fn test_inode_table_lists_dirs_lazily() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let commit = repositories::commits::head_commit(&repo)?;
+            let mut table = InodeTable::new(&repo, &commit)?;
+            assert_eq!(table.get(ROOT_INODE).unwrap().kind, InodeKind::Dir);
+
+            let annotations = table.lookup(ROOT_INODE, "annotations")?.unwrap();
+            let train = table.lookup(annotations, "train")?.unwrap();
+            let bbox = table.lookup(train, "bounding_box.csv")?.unwrap();
+            let entry = table.get(bbox).unwrap().clone();
+            assert_eq!(
+                entry.path,
+                PathBuf::from("annotations/train/bounding_box.csv")
+            );
+
+            let commit_entry =
+                repositories::entries::get_commit_entry(&repo, &commit, &entry.path)?.unwrap();
+            assert_eq!(
+                entry.kind,
+                InodeKind::File {
+                    hash: commit_entry.hash
+                }
+            );
+            assert_eq!(entry.num_bytes, commit_entry.num_bytes);
+
+            // Looking up the same path again hands back the same inode
+            assert_eq!(
+                table.lookup(train, Path::new("bounding_box.csv"))?,
+                Some(bbox)
+            );
+            assert_eq!(table.lookup(train, "does_not_exist.csv")?, None);
+
+            Ok(())
+        })
+    }
+}
@@ -1,5 +1,6 @@
 use crate::api;
 use crate::constants::{DEFAULT_BRANCH_NAME, DEFAULT_REMOTE_NAME};
+use crate::core::events::{self, Operation, OperationTimer};
 use crate::error::OxenError;
 use crate::model::{LocalRepository, RemoteBranch};
 use crate::repositories;
@@ -18,7 +19,11 @@ pub async fn pull_shallow(
 ) -> Result<(), OxenError> {
     let remote = remote.as_ref();
     let branch = branch.as_ref();
-    println!("🐂 oxen pull {} {}", remote, branch);
+    events::info(
+        Operation::Pull,
+        format!("🐂 oxen pull {} {}", remote, branch),
+    );
+    let timer = OperationTimer::start(Operation::Pull, format!("{remote} {branch}"));
 
     let remote = repo
         .get_remote(remote)
@@ -29,7 +34,7 @@ pub async fn pull_shallow(
         .ok_or(OxenError::remote_not_found(remote.clone()))?;
 
     let rb = RemoteBranch {
-        remote: remote.to_string(),
+        remote: remote.name.clone(),
         branch: branch.to_string(),
     };
 
@@ -43,6 +48,7 @@ pub async fn pull_shallow(
     // Mark the repo as shallow, because we only fetched the commit history
     repo.write_is_shallow(true)?;
 
+    timer.finish();
     Ok(())
 }
 
@@ -60,7 +66,11 @@ pub async fn pull_remote_branch(
 ) -> Result<(), OxenError> {
     let remote = remote.as_ref();
     let branch = branch.as_ref();
-    println!("🐂 oxen pull {} {}", remote, branch);
+    events::info(
+        Operation::Pull,
+        format!("🐂 oxen pull {} {}", remote, branch),
+    );
+    let timer = OperationTimer::start(Operation::Pull, format!("{remote} {branch}"));
 
     let remote = repo
         .get_remote(remote)
@@ -71,7 +81,7 @@ pub async fn pull_remote_branch(
         .ok_or(OxenError::remote_not_found(remote.clone()))?;
 
     let rb = RemoteBranch {
-        remote: remote.to_string(),
+        remote: remote.name.clone(),
         branch: branch.to_string(),
     };
 
@@ -106,5 +116,6 @@ pub async fn pull_remote_branch(
 
     repositories::branches::set_head(repo, branch)?;
 
+    timer.finish();
     Ok(())
 }
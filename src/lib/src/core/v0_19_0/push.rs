@@ -4,10 +4,13 @@ use std::sync::Arc;
 
 use crate::constants::DEFAULT_REMOTE_NAME;
 use crate::core;
+use crate::core::events::{self, Operation, OperationTimer};
 use crate::error::OxenError;
 use crate::model::entry::commit_entry::Entry;
 use crate::model::merkle_tree::node::EMerkleTreeNode;
-use crate::model::{Branch, Commit, CommitEntry, LocalRepository, MerkleHash, RemoteRepository};
+use crate::model::{
+    Branch, Commit, CommitEntry, LocalRepository, MerkleHash, RemoteBranch, RemoteRepository,
+};
 use crate::{api, repositories};
 
 use crate::core::v0_19_0::index::CommitMerkleTree;
@@ -26,6 +29,38 @@ pub async fn push_remote_branch(
     repo: &LocalRepository,
     remote: impl AsRef<str>,
     branch_name: impl AsRef<str>,
+) -> Result<Branch, OxenError> {
+    push_remote_branch_with_lease(repo, remote.as_ref(), branch_name.as_ref(), None).await
+}
+
+/// Push the branch even if the remote branch is not one of its ancestors, rewriting the
+/// remote history. The push only goes through if the remote branch is still at
+/// `expected_commit_id`, which defaults to the remote-tracking ref from the last fetch,
+/// so commits someone else pushed in the meantime are never silently dropped.
+pub async fn force_push_remote_branch(
+    repo: &LocalRepository,
+    remote: impl AsRef<str>,
+    branch_name: impl AsRef<str>,
+    expected_commit_id: Option<&str>,
+) -> Result<Branch, OxenError> {
+    let remote = remote.as_ref();
+    let branch_name = branch_name.as_ref();
+    let expected_commit_id = match expected_commit_id {
+        Some(commit_id) => commit_id.to_string(),
+        None => core::refs::remote_refs::get(repo, format!("{remote}/{branch_name}"))?.ok_or(
+            OxenError::basic_str(format!(
+                "No remote-tracking ref for {remote}/{branch_name} to lease against. Run `oxen fetch {remote}` first, or pass the commit you expect the remote branch to be at with --force-with-lease=<commit>"
+            )),
+        )?,
+    };
+    push_remote_branch_with_lease(repo, remote, branch_name, Some(&expected_commit_id)).await
+}
+
+async fn push_remote_branch_with_lease(
+    repo: &LocalRepository,
+    remote: &str,
+    branch_name: &str,
+    lease: Option<&str>,
 ) -> Result<Branch, OxenError> {
     // start a timer
     let start = std::time::Instant::now();
@@ -36,17 +71,18 @@ pub async fn push_remote_branch(
         ));
     }
 
-    let remote = remote.as_ref();
-    let branch_name = branch_name.as_ref();
-
     let Some(local_branch) = repositories::branches::get_by_name(repo, branch_name)? else {
         return Err(OxenError::local_branch_not_found(branch_name));
     };
 
-    println!(
-        "🐂 oxen push {} {} -> {}",
-        remote, local_branch.name, local_branch.commit_id
+    events::info(
+        Operation::Push,
+        format!(
+            "🐂 oxen push {} {} -> {}",
+            remote, local_branch.name, local_branch.commit_id
+        ),
     );
+    let timer = OperationTimer::start(Operation::Push, format!("{remote} {branch_name}"));
 
     let remote = repo
         .get_remote(remote)
@@ -58,12 +94,16 @@ pub async fn push_remote_branch(
         Err(err) => return Err(err),
     };
 
-    push_local_branch_to_remote_repo(repo, &remote_repo, &local_branch).await?;
+    push_local_branch_to_remote_repo(repo, &remote_repo, &local_branch, lease).await?;
     let duration = std::time::Duration::from_millis(start.elapsed().as_millis() as u64);
-    println!(
-        "🐂 push complete 🎉 took {}",
-        humantime::format_duration(duration)
+    events::info(
+        Operation::Push,
+        format!(
+            "🐂 push complete 🎉 took {}",
+            humantime::format_duration(duration)
+        ),
     );
+    timer.finish();
     Ok(local_branch)
 }
 
@@ -71,6 +111,7 @@ async fn push_local_branch_to_remote_repo(
     repo: &LocalRepository,
     remote_repo: &RemoteRepository,
     local_branch: &Branch,
+    lease: Option<&str>,
 ) -> Result<(), OxenError> {
     // Get the commit from the branch
     let Some(commit) = repositories::commits::get_by_id(repo, &local_branch.commit_id)? else {
@@ -83,16 +124,30 @@ async fn push_local_branch_to_remote_repo(
     api::client::repositories::pre_push(remote_repo, local_branch, &commit.id).await?;
 
     // Check if the remote branch exists, and either push to it or create a new one
-    match api::client::branches::get_by_name(remote_repo, &local_branch.name).await? {
-        Some(remote_branch) => {
+    match (
+        api::client::branches::get_by_name(remote_repo, &local_branch.name).await?,
+        lease,
+    ) {
+        (Some(remote_branch), Some(lease)) => {
+            force_push_to_existing_branch(repo, &commit, remote_repo, &remote_branch, lease).await?
+        }
+        (Some(remote_branch), None) => {
             push_to_existing_branch(repo, &commit, remote_repo, &remote_branch).await?
         }
-        None => push_to_new_branch(repo, remote_repo, local_branch, &commit).await?,
+        (None, _) => push_to_new_branch(repo, remote_repo, local_branch, &commit).await?,
     }
 
     // Notify the server that we are done pushing
     api::client::repositories::post_push(remote_repo, local_branch, &commit.id).await?;
 
+    // Remember the commit is on the remote so it is not rewritten locally
+    core::commit_sync_status::mark_commit_as_pushed(repo, &commit.id)?;
+    let remote_branch = RemoteBranch {
+        remote: remote_repo.remote.name.to_owned(),
+        branch: local_branch.name.to_owned(),
+    };
+    core::refs::remote_refs::set(repo, &remote_branch, &commit.id)?;
+
     Ok(())
 }
 
@@ -145,7 +200,7 @@ async fn push_to_existing_branch(
 ) -> Result<(), OxenError> {
     // Check if the latest commit on the remote is the same as the local branch
     if remote_branch.commit_id == commit.id {
-        println!("Everything is up to date");
+        events::info(Operation::Push, "Everything is up to date");
         return Ok(());
     }
 
@@ -174,6 +229,41 @@ async fn push_to_existing_branch(
     Ok(())
 }
 
+async fn force_push_to_existing_branch(
+    repo: &LocalRepository,
+    commit: &Commit,
+    remote_repo: &RemoteRepository,
+    remote_branch: &Branch,
+    lease: &str,
+) -> Result<(), OxenError> {
+    // Fail early if the lease is stale, the server checks again when the branch moves
+    if remote_branch.commit_id != lease {
+        return Err(OxenError::push_rejected(format!(
+            "stale info, {} is at {} not {lease}. Fetch and check the new commits before pushing again",
+            remote_branch.name, remote_branch.commit_id
+        )));
+    }
+    if remote_branch.commit_id == commit.id {
+        events::info(Operation::Push, "Everything is up to date");
+        return Ok(());
+    }
+
+    // The remote history is being replaced, push whatever part of ours it is missing
+    let history = repositories::commits::list_from(repo, &commit.id)?;
+    push_commits(repo, remote_repo, &history).await?;
+
+    api::client::branches::update_with_lease(remote_repo, &remote_branch.name, commit, lease)
+        .await?;
+    events::info(
+        Operation::Push,
+        format!(
+            "Forced {} from {} to {}",
+            remote_branch.name, remote_branch.commit_id, commit.id
+        ),
+    );
+    Ok(())
+}
+
 async fn push_commits(
     repo: &LocalRepository,
     remote_repo: &RemoteRepository,
@@ -253,7 +343,7 @@ async fn push_commits(
     ));
     log::debug!("pushing {} entries", missing_files.len());
     let commit = &history.last().unwrap();
-    core::v0_10_0::index::pusher::push_entries(
+    core::v0_19_0::batch_transfer::push_entries(
         repo,
         remote_repo,
         &missing_files,
@@ -263,5 +353,14 @@ async fn push_commits(
     .await?;
     progress.finish();
 
+    // Let the server validate the commits in the background. Older servers may not know
+    // how to for v0.19.0 repos, which shouldn't fail a push that already landed.
+    if !commits.is_empty() {
+        if let Err(err) = api::client::commits::bulk_post_push_complete(remote_repo, &commits).await
+        {
+            log::warn!("Could not queue post processing for pushed commits: {err}");
+        }
+    }
+
     Ok(())
 }
@@ -0,0 +1,275 @@
+//! # Quarantine
+//!
+//! Check downloaded files against the hashes in the tree before they are moved in place
+//! or checked out. Files that don't match are moved to `.oxen/quarantine` and recorded in
+//! its report, so a corrupted transfer never makes it into the versions dir or the
+//! working tree.
+//!
+
+use rayon::prelude::*;
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use time::OffsetDateTime;
+
+use crate::constants::{QUARANTINE_DIR, QUARANTINE_REPORT_FILE};
+use crate::error::OxenError;
+use crate::model::entry::commit_entry::Entry;
+use crate::model::{LocalRepository, MerkleHash, QuarantinedFile};
+use crate::util;
+
+pub fn quarantine_dir(repo: &LocalRepository) -> PathBuf {
+    quarantine_dir_at(&repo.path)
+}
+
+pub fn report_path(repo: &LocalRepository) -> PathBuf {
+    report_path_at(&repo.path)
+}
+
+fn quarantine_dir_at(repo_path: &Path) -> PathBuf {
+    util::fs::oxen_hidden_dir(repo_path).join(QUARANTINE_DIR)
+}
+
+fn report_path_at(repo_path: &Path) -> PathBuf {
+    quarantine_dir_at(repo_path).join(QUARANTINE_REPORT_FILE)
+}
+
+/// Hash the file that was just received into `received`, before it is moved in place.
+/// If it does not match `hash` it is moved to the quarantine dir of the repository at
+/// `repo_path` instead and recorded under `path`, where it belongs in the repository.
+pub fn verify_received(
+    repo_path: &Path,
+    path: &Path,
+    hash: &str,
+    received: &Path,
+) -> Result<(), OxenError> {
+    let actual_hash = MerkleHash::new(util::hasher::u128_hash_file_contents(received)?);
+    if actual_hash.to_string() == hash {
+        return Ok(());
+    }
+    let file = quarantine_file(repo_path, path, hash, &actual_hash, received)?;
+    log::error!(
+        "Quarantined {:?}, expected hash {} got {}",
+        file.path,
+        file.hash,
+        file.actual_hash
+    );
+    Err(OxenError::checksum_mismatch(1, report_path_at(repo_path)))
+}
+
+/// Hash the freshly downloaded version files of `entries` and quarantine the ones that
+/// don't match. Errors if anything was quarantined, before the caller moves on to update
+/// refs or the working tree.
+pub fn verify_pulled_entries(repo: &LocalRepository, entries: &[Entry]) -> Result<(), OxenError> {
+    let quarantined = quarantine_mismatches(repo, entries)?;
+    if quarantined.is_empty() {
+        return Ok(());
+    }
+    for file in &quarantined {
+        log::error!(
+            "Quarantined {:?}, expected hash {} got {}",
+            file.path,
+            file.hash,
+            file.actual_hash
+        );
+    }
+    Err(OxenError::checksum_mismatch(
+        quarantined.len(),
+        report_path(repo),
+    ))
+}
+
+/// Move the version files of `entries` whose contents don't match their hash to the
+/// quarantine dir, returning what was moved
+pub fn quarantine_mismatches(
+    repo: &LocalRepository,
+    entries: &[Entry],
+) -> Result<Vec<QuarantinedFile>, OxenError> {
+    let mismatches = entries
+        .par_iter()
+        .map(|entry| check_entry(repo, entry))
+        .collect::<Result<Vec<Option<(Entry, MerkleHash)>>, OxenError>>()?;
+
+    let mut quarantined = vec![];
+    for (entry, actual_hash) in mismatches.into_iter().flatten() {
+        quarantined.push(quarantine_entry(repo, &entry, &actual_hash)?);
+    }
+    Ok(quarantined)
+}
+
+/// Everything that was quarantined in the repository, oldest first
+pub fn list(repo: &LocalRepository) -> Result<Vec<QuarantinedFile>, OxenError> {
+    let path = report_path(repo);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let reader = BufReader::new(std::fs::File::open(&path)?);
+    let mut files = vec![];
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        files.push(serde_json::from_str(&line)?);
+    }
+    Ok(files)
+}
+
+// The node hash is the hash of the plain contents, so compressed and encrypted versions
+// are decoded before they are hashed
+fn check_entry(
+    repo: &LocalRepository,
+    entry: &Entry,
+) -> Result<Option<(Entry, MerkleHash)>, OxenError> {
+    let version_path = util::fs::version_path_for_entry(repo, entry);
+    if !version_path.exists() {
+        return Ok(None);
+    }
+    let plain_path = util::encryption::plain_path(repo, &version_path)?;
+    let actual_hash = MerkleHash::new(util::hasher::u128_hash_file_contents(plain_path.path())?);
+    if actual_hash.to_string() == entry.hash() {
+        Ok(None)
+    } else {
+        Ok(Some((entry.to_owned(), actual_hash)))
+    }
+}
+
+fn quarantine_entry(
+    repo: &LocalRepository,
+    entry: &Entry,
+    actual_hash: &MerkleHash,
+) -> Result<QuarantinedFile, OxenError> {
+    let version_path = util::fs::version_path_for_entry(repo, entry);
+    quarantine_file(
+        &repo.path,
+        &entry.path(),
+        &entry.hash(),
+        actual_hash,
+        &version_path,
+    )
+}
+
+fn quarantine_file(
+    repo_path: &Path,
+    path: &Path,
+    hash: &str,
+    actual_hash: &MerkleHash,
+    received: &Path,
+) -> Result<QuarantinedFile, OxenError> {
+    let num_bytes = std::fs::metadata(received)?.len();
+    let quarantined_at = OffsetDateTime::now_utc();
+
+    // The same version can go bad more than once, keep every copy
+    let quarantine_path = PathBuf::from(format!(
+        "{}-{}",
+        hash,
+        quarantined_at.unix_timestamp_nanos()
+    ));
+    let dir = quarantine_dir_at(repo_path);
+    util::fs::create_dir_all(&dir)?;
+    util::fs::rename(received, dir.join(&quarantine_path))?;
+
+    let file = QuarantinedFile {
+        path: path.to_path_buf(),
+        hash: hash.to_string(),
+        actual_hash: actual_hash.to_string(),
+        num_bytes,
+        quarantine_path,
+        quarantined_at,
+    };
+    append_report(&report_path_at(repo_path), &file)?;
+    Ok(file)
+}
+
+fn append_report(path: &Path, file: &QuarantinedFile) -> Result<(), OxenError> {
+    let mut report = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(report, "{}", serde_json::to_string(file)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::core::v0_19_0::quarantine;
+    use crate::error::OxenError;
+    use crate::model::entry::commit_entry::Entry;
+    use crate::model::CommitEntry;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_quarantine_mismatched_downloads() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let good = "what we asked for";
+            let good_path = repo.path.join("good.txt");
+            util::fs::write_to_path(&good_path, good)?;
+            let good_hash = util::hasher::hash_file_contents(&good_path)?;
+
+            let bad_hash = util::hasher::hash_str("something else");
+            let entries: Vec<Entry> = [("good.txt", &good_hash), ("bad.txt", &bad_hash)]
+                .iter()
+                .map(|(path, hash)| {
+                    Entry::CommitEntry(CommitEntry {
+                        commit_id: String::from("abc"),
+                        path: PathBuf::from(path),
+                        hash: hash.to_string(),
+                        num_bytes: good.len() as u64,
+                        last_modified_seconds: 0,
+                        last_modified_nanoseconds: 0,
+                    })
+                })
+                .collect();
+
+            // Both downloads have the same contents, only the first matches its hash
+            for entry in &entries {
+                let version_path = util::fs::version_path_for_entry(&repo, entry);
+                util::fs::create_dir_all(version_path.parent().unwrap())?;
+                util::fs::write_to_path(&version_path, good)?;
+            }
+
+            let result = quarantine::verify_pulled_entries(&repo, &entries);
+            assert!(matches!(result, Err(OxenError::ChecksumMismatch(_))));
+
+            assert!(util::fs::version_path_for_entry(&repo, &entries[0]).exists());
+            assert!(!util::fs::version_path_for_entry(&repo, &entries[1]).exists());
+
+            let quarantined = quarantine::list(&repo)?;
+            assert_eq!(quarantined.len(), 1);
+            assert_eq!(quarantined[0].path, PathBuf::from("bad.txt"));
+            assert_eq!(quarantined[0].actual_hash, good_hash);
+            assert!(quarantine::quarantine_dir(&repo)
+                .join(&quarantined[0].quarantine_path)
+                .exists());
+
+            // Nothing left to quarantine
+            quarantine::verify_pulled_entries(&repo, &entries[..1])?;
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_quarantine_received_file_before_it_is_moved_in_place() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let received = repo.path.join("received.part");
+            util::fs::write_to_path(&received, "what we asked for")?;
+            let hash = util::hasher::hash_file_contents(&received)?;
+            let path = PathBuf::from("data").join("file.txt");
+
+            quarantine::verify_received(&repo.path, &path, &hash, &received)?;
+            assert!(received.exists());
+
+            let other_hash = util::hasher::hash_str("something else");
+            let result = quarantine::verify_received(&repo.path, &path, &other_hash, &received);
+            assert!(matches!(result, Err(OxenError::ChecksumMismatch(_))));
+            assert!(!received.exists());
+
+            let quarantined = quarantine::list(&repo)?;
+            assert_eq!(quarantined.len(), 1);
+            assert_eq!(quarantined[0].path, path);
+            assert_eq!(quarantined[0].hash, other_hash);
+            assert_eq!(quarantined[0].actual_hash, hash);
+            Ok(())
+        })
+    }
+}
@@ -43,7 +43,8 @@ pub fn rm(
         return Err(OxenError::repo_is_shallow());
     }
 
-    if has_modified_files(repo, paths)? {
+    // Modifications are only lost if we delete the working copy
+    if !opts.cached && has_modified_files(repo, paths)? {
         let error = "There are modified files in the working directory.\n\tUse `oxen status` to see the modified files.".to_string();
         return Err(OxenError::basic_str(error));
     }
@@ -375,7 +376,7 @@ fn remove(
                 // Remove dir from working directory
                 let full_path = repo.path.join(path);
                 log::debug!("REMOVING DIR: {full_path:?}");
-                if full_path.exists() {
+                if full_path.exists() && !opts.cached {
                     // user might have removed dir manually before using `oxen rm`
                     util::fs::remove_dir_all(&full_path)?;
                 }
@@ -385,7 +386,7 @@ fn remove(
                 total += remove_file(repo, &path, file_node)?;
                 let full_path = repo.path.join(path);
                 log::debug!("REMOVING FILE: {full_path:?}");
-                if full_path.exists() {
+                if full_path.exists() && !opts.cached {
                     // user might have removed file manually before using `oxen rm`
                     util::fs::remove_file(&full_path)?;
                 }
@@ -1,7 +1,7 @@
-use crate::constants::OXEN_HIDDEN_DIR;
 use crate::constants::STAGED_DIR;
 use crate::core::db;
-use crate::core::oxenignore;
+use crate::core::oxenignore::{self, OxenIgnore};
+use crate::core::v0_19_0::index::stat_cache::{self, StatCache};
 use crate::core::v0_19_0::structs::StagedMerkleTreeNode;
 use crate::error::OxenError;
 use crate::model::merkle_tree::node::FileNode;
@@ -14,14 +14,15 @@ use crate::model::{
 use crate::{repositories, util};
 
 use filetime::FileTime;
-use ignore::gitignore::Gitignore;
 use indicatif::{ProgressBar, ProgressStyle};
+use rayon::prelude::*;
 use rocksdb::{DBWithThreadMode, IteratorMode, SingleThreaded};
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::path::Path;
 use std::path::PathBuf;
 use std::str;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 
 use crate::core::v0_19_0::index::CommitMerkleTree;
@@ -62,10 +63,12 @@ pub fn status_from_opts(
     read_progress.set_style(ProgressStyle::default_spinner());
     read_progress.enable_steady_tick(Duration::from_millis(100));
 
-    let mut total_entries = 0;
+    let total_entries = AtomicU64::new(0);
+    // Shared so each .oxenignore is only parsed once
+    let ignore = oxenignore::create(repo);
 
     let mut untracked = UntrackedData::new();
-    let mut modified = HashSet::new();
+    let mut maybe_modified = HashMap::new();
     let mut removed = HashSet::new();
 
     for dir in opts.paths.iter() {
@@ -76,13 +79,15 @@ pub fn status_from_opts(
             &relative_dir,
             &staged_db_maybe,
             &dir_hashes,
+            &ignore,
             &read_progress,
-            &mut total_entries,
+            &total_entries,
         )?;
         untracked.merge(sub_untracked);
-        modified.extend(sub_modified);
+        maybe_modified.extend(sub_modified);
         removed.extend(sub_removed);
     }
+    let modified = filter_changed_contents(repo, maybe_modified);
 
     log::debug!("find_changes untracked: {:?}", untracked);
     log::debug!("find_changes modified: {:?}", modified);
@@ -339,9 +344,17 @@ fn find_changes(
     relative_path: impl AsRef<Path>,
     staged_db: &Option<DBWithThreadMode<SingleThreaded>>,
     dir_hashes: &HashMap<PathBuf, MerkleHash>,
+    ignore: &OxenIgnore,
     progress: &ProgressBar,
-    total_entries: &mut u64,
-) -> Result<(UntrackedData, HashSet<PathBuf>, HashSet<PathBuf>), OxenError> {
+    total_entries: &AtomicU64,
+) -> Result<
+    (
+        UntrackedData,
+        HashMap<PathBuf, Option<MerkleHash>>,
+        HashSet<PathBuf>,
+    ),
+    OxenError,
+> {
     let relative_path = relative_path.as_ref();
     let full_path = repo.path.join(relative_path);
     log::debug!(
@@ -352,14 +365,14 @@ fn find_changes(
 
     if let Some(ignore) = &opts.ignore {
         if ignore.contains(relative_path) || ignore.contains(&full_path) {
-            return Ok((UntrackedData::new(), HashSet::new(), HashSet::new()));
+            return Ok((UntrackedData::new(), HashMap::new(), HashSet::new()));
         }
     }
 
     let mut untracked = UntrackedData::new();
-    let mut modified = HashSet::new();
+    // Files with a new mtime, mapped to their committed hash to check the contents against
+    let mut modified = HashMap::new();
     let mut removed = HashSet::new();
-    let gitignore = oxenignore::create(repo);
 
     let mut entries: Vec<PathBuf> = Vec::new();
     if full_path.is_dir() {
@@ -376,35 +389,37 @@ fn find_changes(
         entries.push(full_path.to_owned());
     }
     let mut untracked_count = 0;
+    let mut sub_dirs = vec![];
     let dir_node = maybe_get_dir_node(repo, dir_hashes, relative_path)?;
 
     for path in entries {
         log::debug!("find_changes entry path: {:?}", path);
+        let num_entries = total_entries.fetch_add(1, Ordering::Relaxed) + 1;
         progress.set_message(format!(
-            "🐂 checking ({total_entries} files) scanning {:?}",
+            "🐂 checking ({num_entries} files) scanning {:?}",
             relative_path
         ));
-        *total_entries += 1;
         let relative_path = util::fs::path_relative_to_dir(&path, &repo.path)?;
 
-        if is_ignored(&relative_path, &gitignore, path.is_dir()) {
+        if ignore.is_ignored(&relative_path, path.is_dir()) {
+            continue;
+        }
+
+        // Nested repositories are managed on their own unless they were vendored in
+        if util::fs::is_nested_repo(&repo.path, &path)
+            && !dir_hashes.contains_key(&relative_path)
+            && !is_staged(&relative_path, staged_db)?
+        {
+            log::debug!(
+                "find_changes skipping nested repository {:?}",
+                relative_path
+            );
             continue;
         }
 
         if path.is_dir() {
-            // If it's a directory, recursively find changes below it
-            let (sub_untracked, sub_modified, sub_removed) = find_changes(
-                repo,
-                opts,
-                &relative_path,
-                staged_db,
-                dir_hashes,
-                progress,
-                total_entries,
-            )?;
-            untracked.merge(sub_untracked);
-            modified.extend(sub_modified);
-            removed.extend(sub_removed);
+            // Walked in parallel once the files in this dir are done
+            sub_dirs.push(relative_path);
         } else if is_staged(&relative_path, staged_db)? {
             // check this after handling directories, because we still need to recurse into staged directories
             untracked.all_untracked = false;
@@ -418,14 +433,14 @@ fn find_changes(
             let is_modified = is_modified(&node, &path)?;
             log::debug!("is_modified {} {:?}", is_modified, relative_path);
             if is_modified {
-                modified.insert(relative_path.clone());
+                modified.insert(relative_path.clone(), file_hash(&node));
             }
         } else {
             // If it's none of the above conditions
             // then check if it's untracked or modified
             if let Some(node) = CommitMerkleTree::read_file(repo, dir_hashes, &relative_path)? {
                 if is_modified(&node, &path)? {
-                    modified.insert(relative_path.clone());
+                    modified.insert(relative_path.clone(), file_hash(&node));
                 }
             } else {
                 untracked.add_file(relative_path.clone());
@@ -434,6 +449,28 @@ fn find_changes(
         }
     }
 
+    // Recursively find changes below the sub directories, ignored ones were already pruned
+    let sub_changes = sub_dirs
+        .par_iter()
+        .map(|sub_dir| {
+            find_changes(
+                repo,
+                opts,
+                sub_dir,
+                staged_db,
+                dir_hashes,
+                ignore,
+                progress,
+                total_entries,
+            )
+        })
+        .collect::<Result<Vec<_>, OxenError>>()?;
+    for (sub_untracked, sub_modified, sub_removed) in sub_changes {
+        untracked.merge(sub_untracked);
+        modified.extend(sub_modified);
+        removed.extend(sub_removed);
+    }
+
     // Only add the untracked directory if it's not the root directory
     // and it's not staged
     if untracked.all_untracked
@@ -509,19 +546,6 @@ fn maybe_get_dir_node(
     }
 }
 
-fn is_ignored(path: &Path, gitignore: &Option<Gitignore>, is_dir: bool) -> bool {
-    // Skip hidden .oxen files
-    if path.starts_with(OXEN_HIDDEN_DIR) {
-        return true;
-    }
-    if let Some(gitignore) = gitignore {
-        if gitignore.matched(path, is_dir).is_ignore() {
-            return true;
-        }
-    }
-    false
-}
-
 fn is_staged(
     path: &Path,
     staged_db: &Option<DBWithThreadMode<SingleThreaded>>,
@@ -600,6 +624,42 @@ fn maybe_get_child_node(
     node.get_by_path(path)
 }
 
+fn file_hash(node: &MerkleTreeNode) -> Option<MerkleHash> {
+    match &node.node {
+        EMerkleTreeNode::File(file) => Some(file.hash),
+        _ => None,
+    }
+}
+
+/// A new mtime only means a file is modified if its contents changed too. Hash the
+/// candidates in parallel, skipping the ones the stat cache already knows.
+fn filter_changed_contents(
+    repo: &LocalRepository,
+    maybe_modified: HashMap<PathBuf, Option<MerkleHash>>,
+) -> HashSet<PathBuf> {
+    let stat_cache = StatCache::maybe_open(repo);
+    maybe_modified
+        .into_par_iter()
+        .filter(|(relative_path, committed_hash)| {
+            let Some(committed_hash) = committed_hash else {
+                return true;
+            };
+            let full_path = repo.path.join(relative_path);
+            let Ok(metadata) = std::fs::metadata(&full_path) else {
+                return true;
+            };
+            match stat_cache::hash_file(stat_cache.as_ref(), relative_path, &full_path, &metadata) {
+                Ok(hash) => hash != committed_hash.to_u128(),
+                Err(err) => {
+                    log::debug!("Could not hash {:?}: {}", full_path, err);
+                    true
+                }
+            }
+        })
+        .map(|(relative_path, _)| relative_path)
+        .collect()
+}
+
 fn is_modified(node: &MerkleTreeNode, full_path: impl AsRef<Path>) -> Result<bool, OxenError> {
     if !full_path.as_ref().exists() {
         return Ok(false);
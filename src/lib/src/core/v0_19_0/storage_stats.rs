@@ -0,0 +1,127 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+use crate::core::v0_19_0::index::CommitMerkleTree;
+use crate::error::OxenError;
+use crate::model::merkle_tree::node::EMerkleTreeNode;
+use crate::model::{
+    Branch, BranchStorage, DataTypeStorage, LocalRepository, MerkleHash, StorageStats,
+};
+use crate::repositories;
+
+// The files directly in a dir and the hashes of its sub dirs
+struct DirEntries {
+    files: Vec<(MerkleHash, u64, String)>,
+    dirs: Vec<MerkleHash>,
+}
+
+/// Walk the merkle tree of every branch head. Each dir is only read once no matter how
+/// many branches share it, so the cost grows with the unique data, not the branches.
+pub fn compute(repo: &LocalRepository) -> Result<StorageStats, OxenError> {
+    let branches: Vec<Branch> = repositories::branches::list(repo)?;
+
+    let mut dir_cache: HashMap<MerkleHash, DirEntries> = HashMap::new();
+    // Which branches reach each dir
+    let mut dir_branches: HashMap<MerkleHash, BTreeSet<usize>> = HashMap::new();
+    let mut branch_stats: Vec<BranchStorage> = vec![];
+    let mut commit_ids: HashSet<String> = HashSet::new();
+
+    for (i, branch) in branches.iter().enumerate() {
+        let commit = repositories::commits::get_by_id(repo, &branch.commit_id)?.ok_or(
+            OxenError::revision_not_found(branch.commit_id.as_str().into()),
+        )?;
+        let history = repositories::commits::list_from(repo, &commit.id)?;
+        let num_commits = history.len() as u64;
+        commit_ids.extend(history.into_iter().map(|c| c.id));
+
+        let mut num_bytes = 0;
+        if let Some(root_hash) = CommitMerkleTree::dir_hashes(repo, &commit)?
+            .get(Path::new(""))
+            .cloned()
+        {
+            if let Some(root) = CommitMerkleTree::read_node(repo, &root_hash, false)? {
+                num_bytes = root.dir()?.num_bytes;
+            }
+
+            let mut stack = vec![root_hash];
+            while let Some(hash) = stack.pop() {
+                // Already reached from this branch
+                if !dir_branches.entry(hash).or_default().insert(i) {
+                    continue;
+                }
+                if !dir_cache.contains_key(&hash) {
+                    dir_cache.insert(hash, read_dir_entries(repo, &hash)?);
+                }
+                stack.extend(dir_cache[&hash].dirs.iter().cloned());
+            }
+        }
+
+        branch_stats.push(BranchStorage {
+            name: branch.name.clone(),
+            commit_id: commit.id.clone(),
+            num_bytes,
+            unique_num_bytes: 0,
+            num_commits,
+        });
+    }
+
+    // Which branches reach each file, by content hash
+    let mut file_branches: HashMap<MerkleHash, (u64, &str, BTreeSet<usize>)> = HashMap::new();
+    for (hash, entries) in &dir_cache {
+        let reached_by = &dir_branches[hash];
+        for (file_hash, num_bytes, data_type) in &entries.files {
+            file_branches
+                .entry(*file_hash)
+                .or_insert_with(|| (*num_bytes, data_type.as_str(), BTreeSet::new()))
+                .2
+                .extend(reached_by);
+        }
+    }
+
+    let mut stats = StorageStats {
+        num_commits: commit_ids.len() as u64,
+        ..Default::default()
+    };
+    for (num_bytes, data_type, reached_by) in file_branches.values() {
+        stats.num_bytes += num_bytes;
+        stats.num_files += 1;
+        let data_type_stats: &mut DataTypeStorage =
+            stats.data_types.entry(data_type.to_string()).or_default();
+        data_type_stats.num_files += 1;
+        data_type_stats.num_bytes += num_bytes;
+
+        if reached_by.len() == 1 {
+            let i = *reached_by.first().unwrap();
+            branch_stats[i].unique_num_bytes += num_bytes;
+        }
+    }
+    stats.branches = branch_stats;
+    Ok(stats)
+}
+
+fn read_dir_entries(repo: &LocalRepository, hash: &MerkleHash) -> Result<DirEntries, OxenError> {
+    // Depth 2 to get the VNodes and the files and dirs under them
+    let Some(node) = CommitMerkleTree::read_depth(repo, hash, 2)? else {
+        return Err(OxenError::basic_str(format!(
+            "Merkle tree dir node not found: {}",
+            hash
+        )));
+    };
+
+    let mut entries = DirEntries {
+        files: vec![],
+        dirs: vec![],
+    };
+    for child in CommitMerkleTree::node_files_and_folders(&node)? {
+        match &child.node {
+            EMerkleTreeNode::File(file_node) => entries.files.push((
+                file_node.hash,
+                file_node.num_bytes,
+                file_node.data_type.to_string(),
+            )),
+            EMerkleTreeNode::Directory(_) => entries.dirs.push(child.hash),
+            _ => {}
+        }
+    }
+    Ok(entries)
+}
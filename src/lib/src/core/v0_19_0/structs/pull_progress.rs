@@ -1,5 +1,6 @@
 use crate::core::v0_19_0::structs::sync_progress::{SyncProgress, SyncType};
 use std::borrow::Cow;
+use std::ops::{Deref, DerefMut};
 
 pub struct PullProgress {
     sync_progress: SyncProgress,
@@ -52,3 +53,17 @@ impl PullProgress {
         self.sync_progress.finish();
     }
 }
+
+impl Deref for PullProgress {
+    type Target = SyncProgress;
+
+    fn deref(&self) -> &Self::Target {
+        &self.sync_progress
+    }
+}
+
+impl DerefMut for PullProgress {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.sync_progress
+    }
+}
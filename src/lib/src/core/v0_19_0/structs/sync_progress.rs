@@ -1,12 +1,17 @@
 use indicatif::{ProgressBar, ProgressStyle};
 use std::{
     borrow::Cow,
+    path::Path,
     sync::{
         atomic::{AtomicU64, Ordering},
         Arc,
     },
+    time::Instant,
 };
 
+use crate::core::transfer::{self, FileProgress, FileTransferState, TransferProgress};
+use crate::util::progress_bar;
+
 pub enum SyncType {
     Push,
     Pull,
@@ -28,6 +33,7 @@ pub struct SyncProgress {
     progress_bar: ProgressBar,
     total_files: Option<u64>,
     total_bytes: Option<u64>,
+    start: Instant,
 }
 
 impl SyncProgress {
@@ -35,6 +41,7 @@ impl SyncProgress {
         let progress_bar = ProgressBar::new_spinner();
         progress_bar.set_style(ProgressStyle::default_spinner());
         progress_bar.enable_steady_tick(std::time::Duration::from_millis(100));
+        progress_bar::hide_if_sink(&progress_bar);
 
         SyncProgress {
             sync_type,
@@ -43,6 +50,7 @@ impl SyncProgress {
             progress_bar,
             total_files: None,
             total_bytes: None,
+            start: Instant::now(),
         }
     }
 
@@ -51,11 +59,12 @@ impl SyncProgress {
         progress_bar.set_style(
             ProgressStyle::default_bar()
                 .template(
-                    "{spinner:.green} {msg} [{elapsed_precise}] [{wide_bar}] {bytes}/{total_bytes}",
+                    "{spinner:.green} {msg} [{elapsed_precise}] [{wide_bar}] {bytes}/{total_bytes} ({binary_bytes_per_sec}, {eta})",
                 )
                 .unwrap()
                 .progress_chars("🌾🐂➖"),
         );
+        progress_bar::hide_if_sink(&progress_bar);
 
         SyncProgress {
             sync_type,
@@ -64,6 +73,7 @@ impl SyncProgress {
             progress_bar,
             total_files: Some(total_files),
             total_bytes: Some(total_bytes),
+            start: Instant::now(),
         }
     }
 
@@ -100,15 +110,52 @@ impl SyncProgress {
                 self.progress_bar.set_position(bytes);
             }
             _ => {
+                let progress = self.progress(None);
                 let message = format!(
-                    "🐂 {} ({} files {})",
+                    "🐂 {} ({} files {}, {}/s)",
                     self.sync_type.as_str(),
                     files,
-                    bytesize::ByteSize::b(bytes)
+                    bytesize::ByteSize::b(bytes),
+                    bytesize::ByteSize::b(progress.bytes_per_sec as u64)
                 );
                 self.progress_bar.set_message(message);
             }
         };
+        transfer::report_progress(&self.progress(None));
+    }
+
+    /// Snapshot of the transfer so far, with the rate since it started
+    pub fn progress(&self, file: Option<FileProgress>) -> TransferProgress {
+        let mut progress = TransferProgress::new(
+            self.sync_type.as_str(),
+            self.get_num_bytes(),
+            self.total_bytes,
+            self.start.elapsed(),
+        );
+        progress.num_files = self.get_num_files();
+        progress.total_files = self.total_files;
+        progress.file = file;
+        progress
+    }
+
+    pub fn file_started(&self, path: impl AsRef<Path>) {
+        self.report_file(path, FileTransferState::Started);
+    }
+
+    pub fn file_finished(&self, path: impl AsRef<Path>) {
+        self.report_file(path, FileTransferState::Finished);
+    }
+
+    pub fn file_failed(&self, path: impl AsRef<Path>) {
+        self.report_file(path, FileTransferState::Failed);
+    }
+
+    fn report_file(&self, path: impl AsRef<Path>, state: FileTransferState) {
+        let file = FileProgress {
+            path: path.as_ref().to_path_buf(),
+            state,
+        };
+        transfer::report_progress(&self.progress(Some(file)));
     }
 
     pub fn add_files(&self, files: u64) {
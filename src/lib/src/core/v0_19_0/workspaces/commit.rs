@@ -119,7 +119,14 @@ fn export_tabular_data_frames(
                     {
                         node_path = dir_path.join(node_path);
                     }
-                    if file_node.data_type == EntryDataType::Tabular {
+                    // Removed and moved files are staged straight from the tree, there is
+                    // nothing in the workspace to export
+                    let is_exportable = file_node.data_type == EntryDataType::Tabular
+                        && dir_entry.status != StagedEntryStatus::Removed
+                        && (repositories::workspaces::data_frames::is_indexed(
+                            workspace, &node_path,
+                        )? || workspace.workspace_repo.path.join(&node_path).exists());
+                    if is_exportable {
                         log::debug!(
                             "Exporting tabular data frame: {:?} -> {:?}",
                             node_path,
@@ -181,7 +188,8 @@ fn compute_staged_merkle_tree_node(
     // Get the data type of the file
     let mime_type = util::fs::file_mime_type(path);
     let data_type = util::fs::datatype_from_mimetype(path, &mime_type);
-    let mut metadata = repositories::metadata::get_file_metadata(path, &data_type)?;
+    let mut metadata =
+        repositories::metadata::get_file_metadata_for_repo(&workspace.base_repo, path, &data_type)?;
 
     // Here we give priority to the staged schema, as it can contained metadata that was changed during the
     let staged_schema =
@@ -209,7 +217,8 @@ fn compute_staged_merkle_tree_node(
 
     log::debug!("Copying file to {:?}", dst);
 
-    util::fs::copy(path, &dst).unwrap();
+    // Store the version compressed and encrypted like the base repository stores them
+    util::encryption::store(&workspace.base_repo, path, &dst)?;
     let file_extension = path.extension().unwrap_or_default().to_string_lossy();
     let relative_path_str = relative_path.to_str().unwrap();
     let file_node = FileNode {
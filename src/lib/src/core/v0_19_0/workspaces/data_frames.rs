@@ -136,7 +136,10 @@ pub fn index(workspace: &Workspace, path: &Path) -> Result<(), OxenError> {
     if df_db::table_exists(&conn, TABLE_NAME)? {
         df_db::drop_table(&conn, TABLE_NAME)?;
     }
-    let version_path = util::fs::version_path_from_node(repo, file_hash.to_string(), path);
+    let version_path = util::encryption::plain_path(
+        repo,
+        util::fs::version_path_from_node(repo, file_hash.to_string(), path),
+    )?;
 
     log::debug!(
         "core::v0_19_0::index::workspaces::data_frames::index({:?}) got version path: {:?}",
@@ -151,7 +154,7 @@ pub fn index(workspace: &Workspace, path: &Path) -> Result<(), OxenError> {
         }
     };
 
-    df_db::index_file_with_id(&version_path, &conn, &extension)?;
+    df_db::index_file_with_id(version_path.path(), &conn, &extension)?;
     log::debug!(
         "core::v0_19_0::index::workspaces::data_frames::index({:?}) finished!",
         path
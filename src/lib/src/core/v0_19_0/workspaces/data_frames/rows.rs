@@ -22,6 +22,7 @@ use crate::model::staged_row_status::StagedRowStatus;
 use crate::model::{Commit, LocalRepository, Workspace};
 use crate::repositories;
 use crate::util;
+use crate::view::data_frames::{DataFrameRowChange, RowUpdate};
 use crate::view::JsonDataFrameView;
 
 use std::collections::HashSet;
@@ -225,6 +226,101 @@ pub fn batch_update(
     }
 }
 
+/// Apply many row updates in one transaction. If any row fails to update, none are changed.
+pub fn update_rows(
+    workspace: &Workspace,
+    path: impl AsRef<Path>,
+    updates: &[RowUpdate],
+) -> Result<DataFrame, OxenError> {
+    let path = path.as_ref();
+    let db_path = repositories::workspaces::data_frames::duckdb_path(workspace, path);
+    let row_changes_path = repositories::workspaces::data_frames::row_changes_path(workspace, path);
+    log::debug!("update_rows() {} rows in {:?}", updates.len(), path);
+
+    let (updated, changes) = {
+        let conn = df_db::get_connection(db_path)?;
+        rows::with_transaction(&conn, |conn| {
+            let mut updated: Option<DataFrame> = None;
+            let mut changes = Vec::with_capacity(updates.len());
+            for update in updates {
+                let mut df = tabular::parse_json_to_df(&update.value)?;
+                let mut row_before = rows::get_row(conn, &update.row_id)?;
+                let mut row_after = rows::modify_row(conn, &mut df, &update.row_id)?;
+                changes.push(DataFrameRowChange {
+                    row_id: update.row_id.to_owned(),
+                    operation: "updated".to_owned(),
+                    value: JsonDataFrameView::json_from_df(&mut row_before),
+                    new_value: Some(JsonDataFrameView::json_from_df(&mut row_after)),
+                });
+                updated = Some(match updated {
+                    Some(updated) => updated.vstack(&row_after)?,
+                    None => row_after,
+                });
+            }
+            Ok((updated.unwrap_or_default(), changes))
+        })?
+    };
+
+    rows::record_row_changes(&row_changes_path, changes)?;
+    track_changes(workspace, path)?;
+    Ok(updated)
+}
+
+/// Delete many rows in one transaction. If any row fails to delete, none are removed.
+pub fn delete_rows(
+    workspace: &Workspace,
+    path: impl AsRef<Path>,
+    row_ids: &[String],
+) -> Result<DataFrame, OxenError> {
+    let path = path.as_ref();
+    let db_path = repositories::workspaces::data_frames::duckdb_path(workspace, path);
+    let row_changes_path = repositories::workspaces::data_frames::row_changes_path(workspace, path);
+    log::debug!("delete_rows() {} rows in {:?}", row_ids.len(), path);
+
+    let (deleted, changes) = {
+        let conn = df_db::get_connection(db_path)?;
+        rows::with_transaction(&conn, |conn| {
+            let mut deleted: Option<DataFrame> = None;
+            let mut changes = Vec::with_capacity(row_ids.len());
+            for row_id in row_ids {
+                let mut deleted_row = rows::delete_row(conn, row_id)?;
+                changes.push(DataFrameRowChange {
+                    row_id: row_id.to_owned(),
+                    operation: "deleted".to_owned(),
+                    value: JsonDataFrameView::json_from_df(&mut deleted_row),
+                    new_value: None,
+                });
+                deleted = Some(match deleted {
+                    Some(deleted) => deleted.vstack(&deleted_row)?,
+                    None => deleted_row,
+                });
+            }
+            Ok((deleted.unwrap_or_default(), changes))
+        })?
+    };
+
+    rows::record_row_changes(&row_changes_path, changes)?;
+    track_changes(workspace, path)?;
+    Ok(deleted)
+}
+
+// Stage the data frame if it has changes, or unstage it if the edits restored it
+fn track_changes(workspace: &Workspace, path: &Path) -> Result<(), OxenError> {
+    let diff = repositories::workspaces::data_frames::full_diff(workspace, path)?;
+    if let DiffResult::Tabular(diff) = diff {
+        if !diff.has_changes() {
+            log::debug!("no changes, deleting file from staged db {:?}", path);
+            rm::remove_staged_recursively(
+                &workspace.workspace_repo,
+                &HashSet::from([path.to_path_buf()]),
+            )?;
+        } else {
+            workspaces::files::track_modified_data_frame(workspace, path)?;
+        }
+    }
+    Ok(())
+}
+
 pub fn prepare_modified_or_removed_row(
     repo: &LocalRepository,
     commit: &Commit,
@@ -247,11 +343,14 @@ pub fn prepare_modified_or_removed_row(
     );
 
     // let scan_rows = 10000 as usize;
-    let committed_df_path = util::fs::version_path_from_node(
+    let committed_df_path = util::encryption::plain_path(
         repo,
-        commit_merkle_tree.root.hash.to_string(),
-        path.as_ref(),
-    );
+        util::fs::version_path_from_node(
+            repo,
+            commit_merkle_tree.root.hash.to_string(),
+            path.as_ref(),
+        ),
+    )?;
 
     log::debug!(
         "prepare_modified_or_removed_row() committed_df_path: {:?}",
@@ -259,8 +358,11 @@ pub fn prepare_modified_or_removed_row(
     );
 
     // TODONOW should not be using all rows - just need to parse delim
-    let lazy_df =
-        tabular::read_df_with_extension(committed_df_path, file_node.extension, &DFOpts::empty())?;
+    let lazy_df = tabular::read_df_with_extension(
+        committed_df_path.path(),
+        file_node.extension,
+        &DFOpts::empty(),
+    )?;
 
     // Get the row by index
     let mut row = lazy_df.slice(row_idx_og, 1_usize);
@@ -9,7 +9,6 @@ use crate::error::OxenError;
 use crate::model::merkle_tree::node::FileNode;
 use crate::model::merkle_tree::node::MerkleTreeNode;
 use crate::model::metadata::generic_metadata::GenericMetadata;
-use crate::model::metadata::MetadataTabular;
 use crate::model::Schema;
 use crate::model::StagedEntryStatus;
 use crate::model::Workspace;
@@ -65,11 +64,10 @@ pub fn update_schema(
     }
 
     if let Some(GenericMetadata::MetadataTabular(tabular_metadata)) = &file_node.metadata {
-        file_node.metadata = Some(GenericMetadata::MetadataTabular(MetadataTabular::new(
-            tabular_metadata.tabular.width,
-            tabular_metadata.tabular.height,
-            schema,
-        )));
+        // Only the schema changed, the size and column stats still hold
+        let mut tabular_metadata = tabular_metadata.clone();
+        tabular_metadata.tabular.schema = schema;
+        file_node.metadata = Some(GenericMetadata::MetadataTabular(tabular_metadata));
     } else {
         return Err(OxenError::basic_str("Expected tabular metadata"));
     }
@@ -63,6 +63,59 @@ pub fn delete(workspace: &Workspace, path: impl AsRef<Path>) -> Result<(), OxenE
     Ok(())
 }
 
+/// Stage a committed file as removed at `path` and added at `new_path`. The version file is
+/// shared, so nothing is copied.
+pub fn mv(
+    workspace: &Workspace,
+    path: impl AsRef<Path>,
+    new_path: impl AsRef<Path>,
+) -> Result<PathBuf, OxenError> {
+    let path = path.as_ref();
+    let new_path = new_path.as_ref();
+    let base_repo = &workspace.base_repo;
+    let commit = &workspace.commit;
+
+    let dir_hashes = CommitMerkleTree::dir_hashes(base_repo, commit)?;
+    let file_node = match CommitMerkleTree::read_file(base_repo, &dir_hashes, path)? {
+        Some(node) => node.file()?,
+        None => return Err(OxenError::entry_does_not_exist_in_commit(path, &commit.id)),
+    };
+    if dir_hashes.contains_key(new_path)
+        || CommitMerkleTree::read_file(base_repo, &dir_hashes, new_path)?.is_some()
+    {
+        return Err(OxenError::basic_str(format!(
+            "Cannot move {path:?}, {new_path:?} already exists in commit {}",
+            commit.id
+        )));
+    }
+    // The edits of an indexed data frame live in the workspace under the old path
+    if repositories::workspaces::data_frames::is_indexed(workspace, path)? {
+        return Err(OxenError::basic_str(format!(
+            "Cannot move {path:?} while it is indexed as a data frame, commit or unindex it first"
+        )));
+    }
+
+    let opts = db::key_val::opts::default();
+    let db_path = util::fs::oxen_hidden_dir(&workspace.workspace_repo.path).join(STAGED_DIR);
+    let staged_db: DBWithThreadMode<MultiThreaded> =
+        DBWithThreadMode::open(&opts, dunce::simplified(&db_path))?;
+
+    let mut removed_node = file_node.clone();
+    removed_node.name = path.to_string_lossy().to_string();
+    add_file_node_to_staged_db(&staged_db, path, StagedEntryStatus::Removed, &removed_node)?;
+
+    let mut added_node = file_node;
+    added_node.name = new_path.to_string_lossy().to_string();
+    added_node.extension = new_path
+        .extension()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    add_file_node_to_staged_db(&staged_db, new_path, StagedEntryStatus::Added, &added_node)?;
+
+    Ok(new_path.to_path_buf())
+}
+
 pub fn exists(workspace: &Workspace, path: impl AsRef<Path>) -> Result<bool, OxenError> {
     let path = path.as_ref();
     let workspace_repo = &workspace.workspace_repo;
@@ -105,6 +158,7 @@ fn p_add_file(
         &workspace_repo.path,
         &versions_path,
         &staged_db,
+        None,
         &maybe_dir_node,
         path,
         &seen_dirs,
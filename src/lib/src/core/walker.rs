@@ -0,0 +1,132 @@
+//! # Walker
+//!
+//! Parallel walk of the working dir for `oxen add` and `oxen status`. The .oxen dir,
+//! paths in .oxenignore and any directory the caller rejects are pruned before they
+//! are read, and paths are streamed as they are found instead of collected up front,
+//! so a repo with millions of files never holds all of its paths in memory.
+//!
+
+use jwalk::{Parallelism, WalkDirGeneric};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::constants::OXEN_HIDDEN_DIR;
+use crate::core::oxenignore::{self, OxenIgnore};
+use crate::model::LocalRepository;
+
+type DirFilter = Arc<dyn Fn(&Path) -> bool + Send + Sync>;
+
+pub struct Walker {
+    root: PathBuf,
+    ignore: Arc<OxenIgnore>,
+    keep_dir: Option<DirFilter>,
+}
+
+impl Walker {
+    /// Walk `root`, which must be inside the repository
+    pub fn new(repo: &LocalRepository, root: impl AsRef<Path>) -> Walker {
+        Walker {
+            root: root.as_ref().to_path_buf(),
+            ignore: Arc::new(oxenignore::create(repo)),
+            keep_dir: None,
+        }
+    }
+
+    /// Skip the directories `keep_dir` returns false for, and everything below them
+    pub fn filter_dirs(
+        mut self,
+        keep_dir: impl Fn(&Path) -> bool + Send + Sync + 'static,
+    ) -> Walker {
+        self.keep_dir = Some(Arc::new(keep_dir));
+        self
+    }
+
+    /// Stream the directories, starting with the root
+    pub fn dirs(self) -> impl Iterator<Item = PathBuf> {
+        self.walk(true)
+    }
+
+    /// Stream the files
+    pub fn files(self) -> impl Iterator<Item = PathBuf> {
+        self.walk(false)
+    }
+
+    pub fn count_files(self) -> usize {
+        self.files().count()
+    }
+
+    fn walk(self, dirs_only: bool) -> impl Iterator<Item = PathBuf> {
+        let ignore = self.ignore;
+        let keep_dir = self.keep_dir;
+        WalkDirGeneric::<((), ())>::new(&self.root)
+            .skip_hidden(false)
+            // Its own pool, callers often consume the paths with rayon
+            .parallelism(Parallelism::RayonNewPool(0))
+            .process_read_dir(move |_, _, _, children| {
+                children.retain(|child| {
+                    // Keep errors so they are logged below
+                    let Ok(child) = child else {
+                        return true;
+                    };
+                    let is_dir = child.file_type.is_dir();
+                    if (dirs_only && !is_dir) || child.file_name == OXEN_HIDDEN_DIR {
+                        return false;
+                    }
+                    let path = child.path();
+                    if ignore.is_ignored(&path, is_dir) {
+                        return false;
+                    }
+                    !is_dir || keep_dir.as_ref().is_none_or(|keep_dir| keep_dir(&path))
+                });
+            })
+            .into_iter()
+            .filter_map(move |entry| match entry {
+                Ok(entry) => (entry.file_type.is_dir() == dirs_only).then(|| entry.path()),
+                Err(err) => {
+                    log::warn!("Could not read entry while walking: {err}");
+                    None
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::path::PathBuf;
+
+    use crate::core::walker::Walker;
+    use crate::error::OxenError;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_walker_prunes_ignored_and_filtered_dirs() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            util::fs::write_to_path(repo.path.join("a.txt"), "a")?;
+            util::fs::write_to_path(repo.path.join("data").join("b.txt"), "b")?;
+            util::fs::write_to_path(repo.path.join("data").join("c.tmp"), "c")?;
+            util::fs::write_to_path(repo.path.join("scratch").join("d.txt"), "d")?;
+            util::fs::write_to_path(repo.path.join("skip").join("e.txt"), "e")?;
+            util::fs::write_to_path(repo.path.join(".oxenignore"), "*.tmp\nscratch/\n")?;
+
+            let files: HashSet<PathBuf> = Walker::new(&repo, &repo.path)
+                .filter_dirs(|dir| !dir.ends_with("skip"))
+                .files()
+                .map(|path| util::fs::path_relative_to_dir(path, &repo.path).unwrap())
+                .collect();
+            let expected: HashSet<PathBuf> = [".oxenignore", "a.txt", "data/b.txt"]
+                .iter()
+                .map(PathBuf::from)
+                .collect();
+            assert_eq!(files, expected);
+
+            let dirs: Vec<PathBuf> = Walker::new(&repo, &repo.path).dirs().collect();
+            assert_eq!(dirs.len(), 3);
+            assert!(dirs.contains(&repo.path));
+            assert!(!dirs.iter().any(|dir| dir.ends_with("scratch")));
+
+            Ok(())
+        })
+    }
+}
@@ -16,10 +16,14 @@ use crate::model::Schema;
 use crate::model::{Commit, ParsedResource};
 use crate::model::{Remote, RepoNew};
 
+pub mod parse_error;
 pub mod path_buf_error;
+pub mod remote_rejected_error;
 pub mod string_error;
 
+pub use crate::error::parse_error::ParseError;
 pub use crate::error::path_buf_error::PathBufError;
+pub use crate::error::remote_rejected_error::RemoteRejectedError;
 pub use crate::error::string_error::StringError;
 
 use polars::prelude::PolarsError;
@@ -41,21 +45,31 @@ pub enum OxenError {
     UserConfigNotFound(Box<StringError>),
 
     // Repo
+    LocalRepoNotFound(StringError),
     RepoNotFound(Box<RepoNew>),
     RepoAlreadyExists(Box<RepoNew>),
+    RepoFrozen(StringError),
 
     // Remotes
     RemoteRepoNotFound(Box<Remote>),
     RemoteAheadOfLocal(StringError),
     IncompleteLocalHistory(StringError),
     RemoteBranchLocked(StringError),
+    BranchProtected(StringError),
+    QuotaExceeded(StringError),
+    PushRejected(StringError),
+    ChecksumMismatch(StringError),
     UpstreamMergeConflict(StringError),
+    NetworkError(StringError),
+    Offline(StringError),
+    RemoteRejected(Box<RemoteRejectedError>),
 
     // Branches/Commits
     BranchNotFound(Box<StringError>),
     RevisionNotFound(Box<StringError>),
     RootCommitDoesNotMatch(Box<Commit>),
     NothingToCommit(StringError),
+    MergeConflict(StringError),
     NoCommitsFound(StringError),
     HeadNotFound(StringError),
 
@@ -80,6 +94,7 @@ pub enum OxenError {
     // Schema
     InvalidSchema(Box<Schema>),
     IncompatibleSchemas(Box<Schema>),
+    SchemaMismatch(StringError),
     InvalidFileType(StringError),
     ColumnNameAlreadyExists(StringError),
     ColumnNameNotFound(StringError),
@@ -91,6 +106,9 @@ pub enum OxenError {
     // SQL
     SQLParseError(StringError),
 
+    // Files and values that could not be parsed
+    ParseError(Box<ParseError>),
+
     // CLI Interaction
     OperationCancelled(StringError),
 
@@ -114,6 +132,7 @@ pub enum OxenError {
     UTF8Error(std::str::Utf8Error),
     DB(rocksdb::Error),
     DUCKDB(duckdb::Error),
+    SQLite(rusqlite::Error),
     ENV(std::env::VarError),
     ImageError(image::ImageError),
     RedisError(redis::RedisError),
@@ -192,6 +211,47 @@ impl OxenError {
         ))
     }
 
+    pub fn branch_protected(reason: impl AsRef<str>) -> Self {
+        OxenError::BranchProtected(StringError::from(format!(
+            "\nRemote rejected the change, the branch is protected: {}\n",
+            reason.as_ref()
+        )))
+    }
+
+    pub fn quota_exceeded(reason: impl AsRef<str>) -> Self {
+        OxenError::QuotaExceeded(StringError::from(format!(
+            "\nRemote rejected the change, the storage quota is exceeded: {}\n",
+            reason.as_ref()
+        )))
+    }
+
+    pub fn push_rejected(reason: impl AsRef<str>) -> Self {
+        OxenError::PushRejected(StringError::from(format!(
+            "\nRemote rejected the push: {}\n",
+            reason.as_ref()
+        )))
+    }
+
+    pub fn checksum_mismatch(num_files: usize, report: impl AsRef<Path>) -> Self {
+        OxenError::ChecksumMismatch(StringError::from(format!(
+            "\n{num_files} downloaded file(s) did not match their hash and were moved to quarantine.\nSee the report in {:?}\n\nTo download them again run:\n\n  oxen pull\n",
+            report.as_ref()
+        )))
+    }
+
+    pub fn offline(url: impl AsRef<str>) -> Self {
+        OxenError::Offline(StringError::from(format!(
+            "\nOffline mode is on, not connecting to {}\n\nUnset OXEN_OFFLINE, drop --offline or run `oxen config --offline-mode false` to go back online.\n",
+            url.as_ref()
+        )))
+    }
+
+    pub fn repo_is_frozen() -> Self {
+        OxenError::RepoFrozen(StringError::from(
+            "\nRepository is frozen and does not accept changes. Thaw it first with:\n\n  oxen thaw\n",
+        ))
+    }
+
     pub fn operation_cancelled() -> Self {
         OxenError::OperationCancelled(StringError::from("\nOperation cancelled.\n"))
     }
@@ -241,7 +301,15 @@ impl OxenError {
     }
 
     pub fn local_repo_not_found() -> OxenError {
-        OxenError::basic_str(NO_REPO_FOUND)
+        OxenError::LocalRepoNotFound(StringError::from(NO_REPO_FOUND))
+    }
+
+    pub fn nothing_to_commit() -> OxenError {
+        OxenError::NothingToCommit(StringError::from("No changes to commit"))
+    }
+
+    pub fn merge_conflict(s: impl AsRef<str>) -> OxenError {
+        OxenError::MergeConflict(StringError::from(s.as_ref()))
     }
 
     pub fn email_and_name_not_set() -> OxenError {
@@ -249,7 +317,7 @@ impl OxenError {
     }
 
     pub fn auth_token_not_set() -> OxenError {
-        OxenError::basic_str(AUTH_TOKEN_NOT_FOUND)
+        OxenError::authentication(AUTH_TOKEN_NOT_FOUND)
     }
 
     pub fn remote_repo_not_found(url: impl AsRef<str>) -> OxenError {
@@ -269,6 +337,21 @@ impl OxenError {
         OxenError::basic_str("Repository is in a detached HEAD state, checkout a valid branch to continue.\n\n  oxen checkout <branch>\n")
     }
 
+    pub fn nested_repo(path: impl AsRef<Path>) -> OxenError {
+        OxenError::basic_str(format!(
+            "{:?} is part of a nested oxen repository.\n\nManage it from inside that repository, or vendor its files into this one with:\n\n  oxen add --allow-nested <path>\n",
+            path.as_ref()
+        ))
+    }
+
+    pub fn init_nested_repo(path: impl AsRef<Path>, other: impl AsRef<Path>) -> OxenError {
+        OxenError::basic_str(format!(
+            "Cannot initialize a repository at {:?}, it would be nested with the oxen repository at {:?}.\n\nTo create it anyway, run:\n\n  oxen init --allow-nested\n",
+            path.as_ref(),
+            other.as_ref()
+        ))
+    }
+
     pub fn no_schemas_staged() -> OxenError {
         OxenError::basic_str(
             "No schemas staged\n\nAuto detect schema on file with:\n\n  oxen add path/to/file.csv\n\nOr manually add a schema override with:\n\n  oxen schemas add path/to/file.csv 'name:str, age:i32'\n",
@@ -299,7 +382,11 @@ impl OxenError {
     pub fn schema_has_changed(old_schema: Schema, current_schema: Schema) -> OxenError {
         let err =
             format!("\nSchema has changed\n\nOld\n{old_schema}\n\nCurrent\n{current_schema}\n");
-        OxenError::basic_str(err)
+        OxenError::schema_mismatch(err)
+    }
+
+    pub fn schema_mismatch(s: impl AsRef<str>) -> OxenError {
+        OxenError::SchemaMismatch(StringError::from(s.as_ref()))
     }
 
     pub fn remote_branch_not_found(name: impl AsRef<str>) -> OxenError {
@@ -483,8 +570,45 @@ impl OxenError {
     }
 
     pub fn parse_error(value: impl AsRef<str>) -> OxenError {
-        let err = format!("Parse error: {:?}", value.as_ref());
-        OxenError::basic_str(err)
+        OxenError::ParseError(Box::new(ParseError {
+            path: None,
+            line: None,
+            message: format!("{:?}", value.as_ref()),
+        }))
+    }
+
+    pub fn parse_error_at(
+        path: impl AsRef<Path>,
+        line: Option<usize>,
+        message: impl AsRef<str>,
+    ) -> OxenError {
+        OxenError::ParseError(Box::new(ParseError {
+            path: Some(path.as_ref().to_path_buf()),
+            line,
+            message: message.as_ref().to_string(),
+        }))
+    }
+
+    /// A json file that did not parse, with the line serde stopped at
+    pub fn json_file_error(path: impl AsRef<Path>, error: serde_json::Error) -> OxenError {
+        let line = (error.line() > 0).then_some(error.line());
+        OxenError::parse_error_at(path, line, error.to_string())
+    }
+
+    pub fn network_error(s: impl AsRef<str>) -> OxenError {
+        OxenError::NetworkError(StringError::from(s.as_ref()))
+    }
+
+    pub fn remote_rejected(
+        status: u16,
+        code: impl AsRef<str>,
+        message: impl AsRef<str>,
+    ) -> OxenError {
+        OxenError::RemoteRejected(Box::new(RemoteRejectedError {
+            status,
+            code: code.as_ref().to_string(),
+            message: message.as_ref().to_string(),
+        }))
     }
 
     pub fn repo_is_shallow() -> OxenError {
@@ -505,6 +629,84 @@ Or you can interact with the remote directly with the `oxen workspace` subcomman
     }
 }
 
+impl OxenError {
+    /// Stable, machine readable code for the kind of error. These are returned by the
+    /// server in the `code` field and `x-oxen-error-code` header of error responses and
+    /// printed by the CLI with `--json`, so never change an existing one.
+    pub fn code(&self) -> &'static str {
+        match self {
+            OxenError::UserConfigNotFound(_) => "user_config_not_found",
+            OxenError::LocalRepoNotFound(_) => "local_repo_not_found",
+            OxenError::RepoNotFound(_) => "repo_not_found",
+            OxenError::RepoAlreadyExists(_) => "repo_already_exists",
+            OxenError::RepoFrozen(_) => "repo_frozen",
+            OxenError::RemoteRepoNotFound(_) => "remote_repo_not_found",
+            OxenError::RemoteAheadOfLocal(_) => "remote_ahead_of_local",
+            OxenError::IncompleteLocalHistory(_) => "incomplete_local_history",
+            OxenError::RemoteBranchLocked(_) => "remote_branch_locked",
+            OxenError::BranchProtected(_) => "branch_protected",
+            OxenError::QuotaExceeded(_) => "quota_exceeded",
+            OxenError::PushRejected(_) => "push_rejected",
+            OxenError::ChecksumMismatch(_) => "checksum_mismatch",
+            OxenError::UpstreamMergeConflict(_) => "upstream_merge_conflict",
+            OxenError::NetworkError(_) => "network_error",
+            OxenError::Offline(_) => "offline",
+            OxenError::RemoteRejected(_) => "remote_rejected",
+            OxenError::BranchNotFound(_) => "branch_not_found",
+            OxenError::RevisionNotFound(_) => "revision_not_found",
+            OxenError::RootCommitDoesNotMatch(_) => "root_commit_does_not_match",
+            OxenError::NothingToCommit(_) => "nothing_to_commit",
+            OxenError::MergeConflict(_) => "merge_conflict",
+            OxenError::NoCommitsFound(_) => "no_commits_found",
+            OxenError::HeadNotFound(_) => "head_not_found",
+            OxenError::WorkspaceNotFound(_) => "workspace_not_found",
+            OxenError::QueryableWorkspaceNotFound() => "queryable_workspace_not_found",
+            OxenError::WorkspaceBehind(_) => "workspace_behind",
+            OxenError::ResourceNotFound(_) => "resource_not_found",
+            OxenError::PathDoesNotExist(_) => "path_does_not_exist",
+            OxenError::ParsedResourceNotFound(_) => "parsed_resource_not_found",
+            OxenError::MigrationRequired(_) => "migration_required",
+            OxenError::OxenUpdateRequired(_) => "oxen_update_required",
+            OxenError::InvalidVersion(_) => "invalid_version",
+            OxenError::CommitEntryNotFound(_) => "commit_entry_not_found",
+            OxenError::InvalidSchema(_) => "invalid_schema",
+            OxenError::IncompatibleSchemas(_) => "incompatible_schemas",
+            OxenError::SchemaMismatch(_) => "schema_mismatch",
+            OxenError::InvalidFileType(_) => "invalid_file_type",
+            OxenError::ColumnNameAlreadyExists(_) => "column_name_already_exists",
+            OxenError::ColumnNameNotFound(_) => "column_name_not_found",
+            OxenError::UnsupportedOperation(_) => "unsupported_operation",
+            OxenError::ImageMetadataParseError(_) => "image_metadata_parse_error",
+            OxenError::SQLParseError(_) => "sql_parse_error",
+            OxenError::ParseError(_) => "parse_error",
+            OxenError::OperationCancelled(_) => "operation_cancelled",
+            OxenError::StripPrefixError(_) => "strip_prefix_error",
+            OxenError::DataFrameError(_) => "data_frame_error",
+            OxenError::IO(_) => "io_error",
+            OxenError::Authentication(_) => "authentication",
+            OxenError::ArrowError(_) => "arrow_error",
+            OxenError::BinCodeError(_) => "bincode_error",
+            OxenError::TomlSer(_) | OxenError::TomlDe(_) => "toml_error",
+            OxenError::URI(_) | OxenError::URL(_) => "invalid_url",
+            OxenError::JSON(_) => "json_error",
+            OxenError::HTTP(_) => "http_error",
+            OxenError::UTF8Error(_) => "utf8_error",
+            OxenError::DB(_) => "db_error",
+            OxenError::DUCKDB(_) => "duckdb_error",
+            OxenError::SQLite(_) => "sqlite_error",
+            OxenError::ENV(_) => "env_error",
+            OxenError::ImageError(_) => "image_error",
+            OxenError::RedisError(_) => "redis_error",
+            OxenError::R2D2Error(_) => "connection_pool_error",
+            OxenError::JwalkError(_) => "walk_error",
+            OxenError::PatternError(_) | OxenError::GlobError(_) => "glob_error",
+            OxenError::PolarsError(_) => "polars_error",
+            OxenError::ParseIntError(_) => "parse_int_error",
+            OxenError::Basic(_) => "error",
+        }
+    }
+}
+
 // if you do not want to call .map_err, implement the std::convert::From trait
 impl From<io::Error> for OxenError {
     fn from(error: io::Error) -> Self {
@@ -604,6 +806,10 @@ impl From<glob::GlobError> for OxenError {
 
 impl From<reqwest::Error> for OxenError {
     fn from(error: reqwest::Error) -> Self {
+        // Could not reach the remote at all, as opposed to a bad request or response
+        if error.is_connect() || error.is_timeout() {
+            return OxenError::network_error(error.to_string());
+        }
         OxenError::HTTP(error)
     }
 }
@@ -620,6 +826,12 @@ impl From<duckdb::Error> for OxenError {
     }
 }
 
+impl From<rusqlite::Error> for OxenError {
+    fn from(error: rusqlite::Error) -> Self {
+        OxenError::SQLite(error)
+    }
+}
+
 impl From<std::env::VarError> for OxenError {
     fn from(error: std::env::VarError) -> Self {
         OxenError::ENV(error)
@@ -648,3 +860,42 @@ impl From<image::ImageError> for OxenError {
         OxenError::ImageError(error)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::error::OxenError;
+
+    #[test]
+    fn test_json_file_error_has_path_and_line() {
+        let path = Path::new(".oxen/pii_scan.json");
+        let err = serde_json::from_str::<serde_json::Value>("{\n  \"mode\": \n}").unwrap_err();
+        let err = OxenError::json_file_error(path, err);
+        assert_eq!(err.code(), "parse_error");
+        let OxenError::ParseError(parse_error) = &err else {
+            panic!("expected a parse error, got {err:?}");
+        };
+        assert_eq!(parse_error.path.as_deref(), Some(path));
+        assert_eq!(parse_error.line, Some(3));
+        assert!(err
+            .to_string()
+            .starts_with("Parse error in .oxen/pii_scan.json:3:"));
+    }
+
+    #[test]
+    fn test_error_codes() {
+        assert_eq!(
+            OxenError::schema_mismatch("a != b").code(),
+            "schema_mismatch"
+        );
+        assert_eq!(
+            OxenError::network_error("timed out").code(),
+            "network_error"
+        );
+        let err = OxenError::remote_rejected(409, "branch_protected", "Branch is protected");
+        assert_eq!(err.code(), "remote_rejected");
+        assert_eq!(err.to_string(), "Branch is protected");
+        assert_eq!(OxenError::basic_str("oops").code(), "error");
+    }
+}
@@ -0,0 +1,38 @@
+//! # ParseError
+//!
+//! A file or value that could not be parsed, with where it went wrong when we know.
+//!
+
+use std::fmt;
+use std::path::PathBuf;
+
+#[derive(Debug)]
+pub struct ParseError {
+    pub path: Option<PathBuf>,
+    /// 1-based line of the path the parser stopped at
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (&self.path, self.line) {
+            (Some(path), Some(line)) => write!(
+                f,
+                "Parse error in {}:{}: {}",
+                path.to_string_lossy(),
+                line,
+                self.message
+            ),
+            (Some(path), None) => write!(
+                f,
+                "Parse error in {}: {}",
+                path.to_string_lossy(),
+                self.message
+            ),
+            _ => write!(f, "Parse error: {}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
@@ -0,0 +1,24 @@
+//! # RemoteRejectedError
+//!
+//! The remote answered but refused the request, with the HTTP status and the
+//! error code it sent back.
+//!
+
+use std::fmt;
+
+#[derive(Debug)]
+pub struct RemoteRejectedError {
+    /// HTTP status of the response
+    pub status: u16,
+    /// Error code from the response body, "unknown" for servers that don't send one
+    pub code: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for RemoteRejectedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for RemoteRejectedError {}
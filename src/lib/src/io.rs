@@ -1 +1,2 @@
+pub mod chunk_cache;
 pub mod chunk_reader;
@@ -0,0 +1,68 @@
+//! # Chunk Cache
+//!
+//! The most recently read file chunks, shared by every [`ChunkReader`](super::chunk_reader::ChunkReader)
+//! in the process. Chunks are content addressed, so a chunk read for one file or repository
+//! can be served to any other reader that needs the same hash.
+//!
+
+use lazy_static::lazy_static;
+use lru::LruCache;
+use std::num::NonZeroUsize;
+use std::sync::{Arc, Mutex};
+
+/// Number of chunks kept in memory by default, 16MB of 16KB chunks
+pub const DEFAULT_CHUNK_CACHE_SIZE: usize = 1024;
+
+lazy_static! {
+    static ref CHUNK_CACHE: Mutex<LruCache<u128, Arc<Vec<u8>>>> = Mutex::new(LruCache::new(
+        NonZeroUsize::new(DEFAULT_CHUNK_CACHE_SIZE).unwrap()
+    ));
+}
+
+pub fn get(hash: u128) -> Option<Arc<Vec<u8>>> {
+    CHUNK_CACHE.lock().unwrap().get(&hash).cloned()
+}
+
+/// Whether the chunk is cached, without counting as a use
+pub fn contains(hash: u128) -> bool {
+    CHUNK_CACHE.lock().unwrap().contains(&hash)
+}
+
+pub fn put(hash: u128, data: Arc<Vec<u8>>) {
+    CHUNK_CACHE.lock().unwrap().put(hash, data);
+}
+
+/// Change how many chunks are kept, dropping the least recently used ones if it shrinks
+pub fn set_capacity(num_chunks: usize) {
+    let num_chunks = NonZeroUsize::new(num_chunks).unwrap_or(NonZeroUsize::MIN);
+    CHUNK_CACHE.lock().unwrap().resize(num_chunks);
+}
+
+pub fn clear() {
+    CHUNK_CACHE.lock().unwrap().clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::io::chunk_cache;
+
+    #[test]
+    fn test_chunk_cache_evicts_least_recently_used() {
+        // Hashes that no real chunk will have, other tests may share the cache
+        let (a, b, c) = (u128::MAX - 1, u128::MAX - 2, u128::MAX - 3);
+        chunk_cache::put(a, Arc::new(vec![1]));
+        chunk_cache::put(b, Arc::new(vec![2]));
+        assert_eq!(chunk_cache::get(a).unwrap().as_slice(), &[1]);
+
+        // b is now the least recently used
+        chunk_cache::set_capacity(2);
+        chunk_cache::put(c, Arc::new(vec![3]));
+        assert!(chunk_cache::contains(a));
+        assert!(!chunk_cache::contains(b));
+        assert!(chunk_cache::contains(c));
+
+        chunk_cache::set_capacity(chunk_cache::DEFAULT_CHUNK_CACHE_SIZE);
+    }
+}
@@ -4,22 +4,56 @@ use polars::io::mmap::MmapBytesReader;
 use crate::core::v0_19_0::index::file_chunker::ChunkShardManager;
 use crate::core::v0_19_0::index::file_chunker::CHUNK_SIZE;
 use crate::error::OxenError;
+use crate::io::chunk_cache;
 use crate::model::merkle_tree::node::FileNode;
 use crate::model::LocalRepository;
 
 use std::io::Read;
 use std::io::Seek;
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Number of chunks read ahead of the current one by default, 512KB of 16KB chunks
+pub const DEFAULT_READ_AHEAD: usize = 32;
+
+#[derive(Clone, Debug)]
+pub struct ChunkReaderOpts {
+    /// How many chunks past the one being read to load into the chunk cache in the background.
+    /// 0 reads every chunk on demand.
+    pub read_ahead: usize,
+}
+
+impl Default for ChunkReaderOpts {
+    fn default() -> Self {
+        Self {
+            read_ahead: DEFAULT_READ_AHEAD,
+        }
+    }
+}
 
 pub struct ChunkReader {
     pub repo: LocalRepository,
     node: FileNode,
     offset: u64,
-    csm: ChunkShardManager,
+    csm: Arc<Mutex<ChunkShardManager>>,
+    opts: ChunkReaderOpts,
+    // Chunk indices below this have already been sent to the prefetcher
+    prefetched_to: usize,
+    prefetcher: Option<Sender<Vec<u128>>>,
     // data: Vec<u8>,
 }
 
 impl ChunkReader {
     pub fn new(repo: LocalRepository, node: FileNode) -> Result<Self, OxenError> {
+        Self::with_opts(repo, node, ChunkReaderOpts::default())
+    }
+
+    pub fn with_opts(
+        repo: LocalRepository,
+        node: FileNode,
+        opts: ChunkReaderOpts,
+    ) -> Result<Self, OxenError> {
         // let num_bytes = node.num_bytes as usize;
         // let mut data: Vec<u8> = vec![0; num_bytes];
 
@@ -40,15 +74,77 @@ impl ChunkReader {
         //     log::debug!("read data... {total_read}/{num_bytes}");
         // }
 
-        let csm = ChunkShardManager::new(&repo)?;
+        let csm = Arc::new(Mutex::new(ChunkShardManager::new(&repo)?));
+        let prefetcher = if opts.read_ahead > 0 && node.chunk_hashes.len() > 1 {
+            Some(Self::spawn_prefetcher(csm.clone()))
+        } else {
+            None
+        };
         Ok(Self {
             repo,
             node,
             offset: 0,
             csm,
+            opts,
+            prefetched_to: 0,
+            prefetcher,
             // data,
         })
     }
+
+    // Loads the requested chunks into the chunk cache until the reader is dropped
+    fn spawn_prefetcher(csm: Arc<Mutex<ChunkShardManager>>) -> Sender<Vec<u128>> {
+        let (sender, receiver) = mpsc::channel::<Vec<u128>>();
+        thread::spawn(move || {
+            for hashes in receiver {
+                for hash in hashes {
+                    if chunk_cache::contains(hash) {
+                        continue;
+                    }
+                    let chunk = csm.lock().unwrap().read_chunk(hash);
+                    match chunk {
+                        Ok(data) => chunk_cache::put(hash, Arc::new(data)),
+                        Err(err) => {
+                            log::warn!("Could not prefetch chunk {hash}: {err:?}");
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+        sender
+    }
+
+    fn prefetch(&mut self, chunk_index: usize) {
+        let Some(prefetcher) = &self.prefetcher else {
+            return;
+        };
+        let num_chunks = self.node.chunk_hashes.len();
+        let start = std::cmp::max(chunk_index + 1, self.prefetched_to);
+        let end = std::cmp::min(chunk_index + 1 + self.opts.read_ahead, num_chunks);
+        if start >= end {
+            return;
+        }
+        let hashes = self.node.chunk_hashes[start..end].to_vec();
+        if prefetcher.send(hashes).is_err() {
+            // The prefetch thread is gone, keep reading on demand
+            self.prefetcher = None;
+            return;
+        }
+        self.prefetched_to = end;
+    }
+
+    fn read_chunk(&mut self, chunk_index: usize) -> Result<Arc<Vec<u8>>, OxenError> {
+        self.prefetch(chunk_index);
+
+        let hash = self.node.chunk_hashes[chunk_index];
+        if let Some(data) = chunk_cache::get(hash) {
+            return Ok(data);
+        }
+        let data = Arc::new(self.csm.lock().unwrap().read_chunk(hash)?);
+        chunk_cache::put(hash, data.clone());
+        Ok(data)
+    }
 }
 
 impl Read for ChunkReader {
@@ -92,9 +188,10 @@ impl Read for ChunkReader {
                 chunk_offset
             );
 
-            // Find the hashed chunk file
-            let chunk_hash = self.node.chunk_hashes[chunk_index as usize];
-            let chunk_data = self.csm.read_chunk(chunk_hash).unwrap();
+            // Find the hashed chunk, it is already cached if it was prefetched
+            let chunk_data = self
+                .read_chunk(chunk_index as usize)
+                .map_err(|err| std::io::Error::other(err.to_string()))?;
             let chunk_data_len = chunk_data.len() as u64;
 
             log::debug!("Chunk file size {:?}", chunk_data_len);
@@ -140,6 +237,8 @@ impl Seek for ChunkReader {
             std::io::SeekFrom::End(offset) => (self.node.num_bytes as i64 + offset) as u64,
         };
         log::debug!("New offset {:?}", self.offset);
+        // Read ahead from the new position
+        self.prefetched_to = 0;
         Ok(self.offset)
     }
 }
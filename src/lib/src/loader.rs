@@ -0,0 +1,322 @@
+//! # Loader
+//!
+//! Stream rows or batches of a tabular file at any revision straight out of the
+//! version store, without checking it out. Meant to back training loops and the
+//! python bindings, so it only ever holds a batch or a shuffle buffer in memory.
+//!
+//! ```ignore
+//! use liboxen::loader::DataLoader;
+//! use liboxen::opts::LoaderOpts;
+//!
+//! let opts = LoaderOpts {
+//!     batch_size: 64,
+//!     shuffle_buffer: Some(10_000),
+//!     ..LoaderOpts::default()
+//! };
+//! for batch in DataLoader::new(&repo, "main", "train.parquet", opts)? {
+//!     let batch = batch?;
+//!     // train on batch
+//! }
+//! ```
+//!
+
+use std::path::Path;
+
+use polars::prelude::*;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use serde_json::Value;
+
+use crate::core::df::tabular;
+use crate::error::OxenError;
+use crate::model::LocalRepository;
+use crate::opts::{DFOpts, LoaderOpts};
+use crate::repositories;
+use crate::util;
+use crate::util::encryption::PlainPath;
+use crate::view::JsonDataFrameView;
+
+/// Iterator over batches of a tabular file in a commit
+pub struct DataLoader {
+    lazy: LazyFrame,
+    // The lazy frame scans this file, it has to outlive it
+    _version_path: PlainPath,
+    num_rows: usize,
+    offset: usize,
+    opts: LoaderOpts,
+    buffer: Option<DataFrame>,
+    rng: StdRng,
+}
+
+impl DataLoader {
+    pub fn new(
+        repo: &LocalRepository,
+        revision: impl AsRef<str>,
+        path: impl AsRef<Path>,
+        opts: LoaderOpts,
+    ) -> Result<DataLoader, OxenError> {
+        let revision = revision.as_ref();
+        let path = path.as_ref();
+        if !util::fs::is_tabular(path) {
+            return Err(OxenError::invalid_file_type(format!(
+                "{:?} is not a tabular file",
+                path
+            )));
+        }
+
+        let Some(commit) = repositories::revisions::get(repo, revision)? else {
+            return Err(OxenError::revision_not_found(revision.into()));
+        };
+        let Some(entry) = repositories::entries::get_commit_entry(repo, &commit, path)? else {
+            return Err(OxenError::entry_does_not_exist_in_commit(path, &commit.id));
+        };
+
+        let version_path = util::fs::plain_version_path(repo, &entry)?;
+        let extension = util::fs::file_extension(path);
+        let size = tabular::get_size_with_extension(version_path.path(), Some(&extension))?;
+        let mut lazy = tabular::scan_df_with_extension(
+            version_path.path(),
+            Some(&extension),
+            &DFOpts::empty(),
+            size.height,
+        )?;
+        if let Some(columns) = &opts.columns {
+            let columns: Vec<Expr> = columns.iter().map(|c| col(c.as_str())).collect();
+            lazy = lazy.select(columns);
+        }
+
+        let rng = match opts.seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+
+        Ok(DataLoader {
+            lazy,
+            _version_path: version_path,
+            num_rows: size.height,
+            offset: 0,
+            opts,
+            buffer: None,
+            rng,
+        })
+    }
+
+    /// Total number of rows in the file
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// Number of batches in one pass over the file
+    pub fn num_batches(&self) -> usize {
+        let batch_size = self.batch_size();
+        if self.opts.drop_last {
+            self.num_rows / batch_size
+        } else {
+            self.num_rows.div_ceil(batch_size)
+        }
+    }
+
+    /// Iterate over single rows as json objects instead of batches
+    pub fn rows(self) -> Rows {
+        Rows {
+            loader: self,
+            rows: vec![].into_iter(),
+        }
+    }
+
+    fn batch_size(&self) -> usize {
+        self.opts.batch_size.max(1)
+    }
+
+    fn next_batch(&mut self) -> Result<Option<DataFrame>, OxenError> {
+        let batch_size = self.batch_size();
+        let batch = match self.opts.shuffle_buffer {
+            None => {
+                if self.offset >= self.num_rows {
+                    return Ok(None);
+                }
+                self.read(batch_size)?
+            }
+            Some(buffer_size) => {
+                // Top the buffer up from the file, then draw the batch at random from it
+                let buffer_size = buffer_size.max(batch_size);
+                let buffered = self.buffer.as_ref().map(|df| df.height()).unwrap_or(0);
+                if buffered < buffer_size && self.offset < self.num_rows {
+                    let df = self.read(buffer_size - buffered)?;
+                    self.buffer = Some(match self.buffer.take() {
+                        Some(mut buffer) => {
+                            buffer.vstack_mut(&df)?;
+                            buffer
+                        }
+                        None => df,
+                    });
+                }
+
+                let Some(buffer) = self.buffer.take() else {
+                    return Ok(None);
+                };
+                let mut indices: Vec<IdxSize> = (0..buffer.height() as IdxSize).collect();
+                indices.shuffle(&mut self.rng);
+                let (batch_indices, rest_indices) = indices.split_at(batch_size.min(indices.len()));
+                let batch = buffer.take(&IdxCa::new("idx".into(), batch_indices))?;
+                self.buffer = Some(buffer.take(&IdxCa::new("idx".into(), rest_indices))?);
+                batch
+            }
+        };
+
+        if batch.height() == 0 || (self.opts.drop_last && batch.height() < batch_size) {
+            return Ok(None);
+        }
+        Ok(Some(batch))
+    }
+
+    fn read(&mut self, num_rows: usize) -> Result<DataFrame, OxenError> {
+        let df = self
+            .lazy
+            .clone()
+            .slice(self.offset as i64, num_rows as IdxSize)
+            .collect()?;
+        self.offset += num_rows;
+        Ok(df)
+    }
+}
+
+impl Iterator for DataLoader {
+    type Item = Result<DataFrame, OxenError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_batch().transpose()
+    }
+}
+
+/// Iterator over the rows of a DataLoader, each row is a json object keyed by column
+pub struct Rows {
+    loader: DataLoader,
+    rows: std::vec::IntoIter<Value>,
+}
+
+impl Iterator for Rows {
+    type Item = Result<Value, OxenError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(row) = self.rows.next() {
+                return Some(Ok(row));
+            }
+            match self.loader.next()? {
+                Ok(mut batch) => {
+                    self.rows = match JsonDataFrameView::json_from_df(&mut batch) {
+                        Value::Array(rows) => rows.into_iter(),
+                        _ => vec![].into_iter(),
+                    };
+                }
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::core::df::tabular;
+    use crate::error::OxenError;
+    use crate::loader::DataLoader;
+    use crate::opts::{DFOpts, LoaderOpts};
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    const BBOX_PATH: &str = "annotations/train/bounding_box.csv";
+
+    fn file_column(loader: DataLoader) -> Result<Vec<String>, OxenError> {
+        let mut files = vec![];
+        for row in loader.rows() {
+            let row = row?;
+            files.push(row["file"].as_str().unwrap().to_string());
+        }
+        Ok(files)
+    }
+
+    #[test]
+    fn test_loader_batches_in_order() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let df = tabular::read_df(repo.path.join(BBOX_PATH), DFOpts::empty())?;
+
+            let opts = LoaderOpts {
+                batch_size: 2,
+                ..LoaderOpts::default()
+            };
+            let loader = DataLoader::new(&repo, "main", Path::new(BBOX_PATH), opts)?;
+            assert_eq!(loader.num_rows(), df.height());
+            let num_batches = loader.num_batches();
+
+            let batches: Vec<_> = loader.collect::<Result<_, _>>()?;
+            assert_eq!(batches.len(), num_batches);
+            assert_eq!(batches[0], df.head(Some(2)));
+            let total: usize = batches.iter().map(|b| b.height()).sum();
+            assert_eq!(total, df.height());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_loader_reads_encrypted_versions() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|mut repo| {
+            let (_, key_path) = repositories::encryption::enable(&mut repo)?;
+            let path = repo.path.join("labels.csv");
+            util::fs::write_to_path(&path, "file,label\na.png,cat\nb.png,dog\nc.png,cat\n")?;
+            repositories::add(&repo, &path)?;
+            repositories::commit(&repo, "Adding labels")?;
+
+            let opts = LoaderOpts {
+                batch_size: 2,
+                ..LoaderOpts::default()
+            };
+            let loader = DataLoader::new(&repo, "main", Path::new("labels.csv"), opts)?;
+            assert_eq!(file_column(loader)?, vec!["a.png", "b.png", "c.png"]);
+
+            util::fs::remove_file(key_path)?;
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_loader_shuffle_is_seeded_and_covers_every_row() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let commit = repositories::commits::head_commit(&repo)?;
+            let opts = LoaderOpts {
+                batch_size: 3,
+                columns: Some(vec![String::from("file")]),
+                shuffle_buffer: Some(4),
+                seed: Some(42),
+                drop_last: false,
+            };
+
+            let in_order = file_column(DataLoader::new(
+                &repo,
+                &commit.id,
+                BBOX_PATH,
+                LoaderOpts {
+                    shuffle_buffer: None,
+                    ..opts.clone()
+                },
+            )?)?;
+            let shuffled =
+                file_column(DataLoader::new(&repo, &commit.id, BBOX_PATH, opts.clone())?)?;
+            let shuffled_again = file_column(DataLoader::new(&repo, &commit.id, BBOX_PATH, opts)?)?;
+
+            assert_eq!(shuffled, shuffled_again);
+            let mut sorted = shuffled.clone();
+            sorted.sort();
+            let mut expected = in_order.clone();
+            expected.sort();
+            assert_eq!(sorted, expected);
+
+            Ok(())
+        })
+    }
+}
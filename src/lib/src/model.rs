@@ -3,32 +3,54 @@
 
 pub mod base_head;
 pub mod branch;
+pub mod branch_protection;
+pub mod bundle;
+pub mod comment;
 pub mod commit;
+pub mod commit_state;
 pub mod content_type;
 pub mod data_frame;
+pub mod dataset_card;
 pub mod diff;
+pub mod duplicate_group;
 pub mod entry;
 pub mod file;
 pub mod merge_conflict;
+pub mod merge_queue;
+pub mod merge_request;
 pub mod merkle_tree;
 pub mod metadata;
+pub mod mirror;
 pub mod namespace;
 pub mod object_id;
+pub mod oxen_uri;
 pub mod parsed_resource;
+pub mod pii_scan;
+pub mod pin;
+pub mod provenance;
+pub mod quarantined_file;
+pub mod release;
 pub mod remote;
 pub mod remote_branch;
+pub mod repo_freeze;
 pub mod repository;
 pub mod staged_data;
 pub mod staged_dir_stats;
 pub mod staged_row_status;
+pub mod storage_stats;
+pub mod storage_usage;
 pub mod summarized_staged_dir_stats;
 pub mod user;
+pub mod webhook;
 pub mod workspace;
 
 // Namespace
 pub use crate::model::namespace::Namespace;
 
 // Repository
+pub use crate::model::quarantined_file::QuarantinedFile;
+pub use crate::model::repo_freeze::{FreezeOpts, RepoFreeze};
+pub use crate::model::repository::checkout_mode::CheckoutMode;
 pub use crate::model::repository::local_repository::LocalRepository;
 pub use crate::model::repository::remote_repository::RemoteRepository;
 pub use crate::model::repository::repo_new::RepoNew;
@@ -37,9 +59,11 @@ pub use crate::model::repository::repo_stats::{DataTypeStat, RepoStats};
 // Commit
 pub use crate::model::base_head::BaseHead;
 pub use crate::model::commit::{Commit, CommitStats, NewCommit, NewCommitBody};
+pub use crate::model::commit_state::{CommitState, CommitStateEntry, NewCommitState};
 
 // Branch
 pub use crate::model::branch::Branch;
+pub use crate::model::branch_protection::BranchProtection;
 pub use crate::model::remote_branch::RemoteBranch;
 
 // Entry (TODO: These should just be nodes in the tree)
@@ -53,9 +77,16 @@ pub use crate::model::entry::remote_entry::RemoteEntry;
 pub use crate::model::entry::staged_entry::{StagedEntry, StagedEntryStatus};
 pub use crate::model::entry::ContentHashable;
 
+// Dedup
+pub use crate::model::duplicate_group::{
+    DuplicateEntry, DuplicateGroup, NearDuplicateEntry, NearDuplicateGroup,
+};
+
 // Merge
 pub use crate::model::merge_conflict::EntryMergeConflict;
 pub use crate::model::merge_conflict::NodeMergeConflict;
+pub use crate::model::merge_queue::{MergeQueueEntry, MergeQueueStatus, NewMergeQueueEntry};
+pub use crate::model::merge_request::{MergeRequest, MergeRequestStatus, NewMergeRequest};
 
 // Metadata
 pub use crate::model::metadata::dir_metadata_item::DirMetadataItem;
@@ -64,8 +95,20 @@ pub use crate::model::data_frame::data_frame_size::DataFrameSize;
 
 pub use crate::model::user::User;
 
+pub use crate::model::bundle::{BundleManifest, BundleRef};
+pub use crate::model::comment::{Comment, CommentFilter, CommentThread, NewComment};
+pub use crate::model::dataset_card::{DatasetCard, DatasetCardSchema, DatasetCardStats};
+pub use crate::model::mirror::{Mirror, NewMirror};
 pub use crate::model::object_id::ObjectID;
+pub use crate::model::oxen_uri::OxenUri;
 pub use crate::model::parsed_resource::ParsedResource;
+pub use crate::model::pii_scan::{PiiFinding, PiiRule, PiiScanConfig, PiiScanMode, PiiScanReport};
+pub use crate::model::pin::{Pin, PinEntry, PinMismatch};
+pub use crate::model::provenance::Provenance;
+pub use crate::model::release::{NewRelease, Release};
+pub use crate::model::storage_stats::{BranchStorage, DataTypeStorage, StorageStats};
+pub use crate::model::storage_usage::StorageUsage;
+pub use crate::model::webhook::{NewWebhook, Webhook, WebhookEvent, WebhookPayload};
 
 pub use crate::model::staged_data::StagedData;
 pub use crate::model::staged_dir_stats::StagedDirStats;
@@ -80,7 +123,7 @@ pub use crate::model::data_frame::schema::staged_schema::StagedSchema;
 pub use crate::model::data_frame::schema::Schema;
 
 // Workspace
-pub use crate::model::workspace::Workspace;
+pub use crate::model::workspace::{FailedUpload, Workspace, WorkspaceUploadResult};
 
 // Merkle Tree Node
 pub use crate::model::merkle_tree::merkle_hash::MerkleHash;
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+/// Rules the server enforces on a branch, or on every branch matching a glob like `release/*`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BranchProtection {
+    pub pattern: String,
+    /// Allow moving the branch to a commit that does not contain its current head
+    #[serde(default)]
+    pub allow_force_push: bool,
+    #[serde(default)]
+    pub allow_deletion: bool,
+    /// Number of distinct `Reviewed-by:` trailers the new head commit needs
+    #[serde(default)]
+    pub required_reviewers: usize,
+    /// Emails or names of the users allowed to update the branch. Empty means anyone.
+    #[serde(default)]
+    pub allowed_pushers: Vec<String>,
+}
+
+impl BranchProtection {
+    pub fn new(pattern: impl AsRef<str>) -> BranchProtection {
+        BranchProtection {
+            pattern: pattern.as_ref().to_string(),
+            allow_force_push: false,
+            allow_deletion: false,
+            required_reviewers: 0,
+            allowed_pushers: vec![],
+        }
+    }
+
+    pub fn matches(&self, branch: &str) -> bool {
+        self.pattern == branch
+            || glob::Pattern::new(&self.pattern).is_ok_and(|pattern| pattern.matches(branch))
+    }
+}
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// A branch packed in a bundle and the commit it pointed to
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BundleRef {
+    pub name: String,
+    pub commit_id: String,
+}
+
+/// Describes the contents of a bundle, stored as the first file in the archive
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BundleManifest {
+    pub oxen_version: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    pub refs: Vec<BundleRef>,
+    pub commit_ids: Vec<String>,
+    pub num_files: u64,
+    pub num_bytes: u64,
+}
+
+impl BundleManifest {
+    pub fn get_ref(&self, name: &str) -> Option<&BundleRef> {
+        self.refs.iter().find(|r| r.name == name)
+    }
+}
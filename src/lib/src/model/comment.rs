@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use time::OffsetDateTime;
+
+/// A comment on a commit, on a file in the commit, or on a row of a tabular file in the
+/// commit. Rows are identified by the hash of their values so a comment follows the row
+/// across commits that do not change it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Comment {
+    pub id: String,
+    pub commit_id: String,
+    pub path: Option<PathBuf>,
+    pub row_hash: Option<String>,
+    // The comment this is a reply to, replies share the target of the thread
+    pub parent_id: Option<String>,
+    pub author: String,
+    pub email: String,
+    pub body: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+}
+
+/// A top level comment and every reply under it, oldest first
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommentThread {
+    pub comment: Comment,
+    pub replies: Vec<Comment>,
+}
+
+/// Body used to comment through the API. Replies only need the parent_id.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct NewComment {
+    #[serde(default)]
+    pub commit_id: String,
+    pub path: Option<PathBuf>,
+    pub row_hash: Option<String>,
+    pub parent_id: Option<String>,
+    pub author: String,
+    pub email: String,
+    pub body: String,
+}
+
+/// Which comments to list, any field left empty matches everything
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CommentFilter {
+    pub commit_id: Option<String>,
+    pub path: Option<PathBuf>,
+    pub row_hash: Option<String>,
+}
+
+impl CommentFilter {
+    pub fn matches(&self, comment: &Comment) -> bool {
+        self.commit_id
+            .as_ref()
+            .is_none_or(|id| *id == comment.commit_id)
+            && self
+                .path
+                .as_ref()
+                .is_none_or(|path| Some(path) == comment.path.as_ref())
+            && self
+                .row_hash
+                .as_ref()
+                .is_none_or(|hash| Some(hash) == comment.row_hash.as_ref())
+    }
+}
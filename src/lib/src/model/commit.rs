@@ -4,6 +4,7 @@ use std::hash::{Hash, Hasher};
 use time::OffsetDateTime;
 
 use super::{Branch, MerkleHash, User};
+use crate::constants::CO_AUTHORED_BY_TRAILER;
 use crate::core::v0_10_0::index::CommitReader;
 use crate::error::OxenError;
 use crate::view::workspaces::WorkspaceCommit;
@@ -52,6 +53,9 @@ pub struct Commit {
     pub root_hash: Option<String>, // Option for now to facilitate migration from older stored commits
     #[serde(with = "time::serde::rfc3339")]
     pub timestamp: OffsetDateTime,
+    /// From the Co-authored-by trailers of the message, so API clients don't have to parse it
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub co_authors: Vec<User>,
 }
 
 impl From<Commit> for WorkspaceCommit {
@@ -126,6 +130,7 @@ impl Commit {
             email: new_commit.email.to_owned(),
             timestamp: new_commit.timestamp.to_owned(),
             root_hash: None,
+            co_authors: Commit::parse_co_authors(&new_commit.message),
         }
     }
 
@@ -138,6 +143,7 @@ impl Commit {
             email: new_commit.email.to_owned(),
             timestamp: new_commit.timestamp.to_owned(),
             root_hash: None,
+            co_authors: Commit::parse_co_authors(&new_commit.message),
         }
     }
 
@@ -180,6 +186,7 @@ impl Commit {
             email: commit.email.to_owned(),
             timestamp: commit.timestamp.to_owned(),
             root_hash: commit.root_hash.to_owned(),
+            co_authors: Commit::parse_co_authors(&commit.message),
         }
     }
 
@@ -192,6 +199,7 @@ impl Commit {
             email: commit.email.to_owned(),
             timestamp: commit.timestamp.to_owned(),
             root_hash: commit.root_hash.to_owned(),
+            co_authors: Commit::parse_co_authors(&commit.message),
         }
     }
 
@@ -217,6 +225,65 @@ impl Commit {
             email: self.email.to_owned(),
         }
     }
+
+    /// The author followed by everyone credited with a Co-authored-by trailer
+    pub fn authors(&self) -> Vec<User> {
+        let mut authors = vec![self.get_user()];
+        authors.extend(Commit::parse_co_authors(&self.message));
+        authors
+    }
+
+    /// The message without its Co-authored-by trailers, for showing next to the authors
+    pub fn message_without_co_authors(&self) -> String {
+        self.message
+            .lines()
+            .filter(|line| co_author_from_line(line).is_none())
+            .collect::<Vec<&str>>()
+            .join("\n")
+            .trim_end()
+            .to_string()
+    }
+
+    /// The users credited by Co-authored-by trailers in a commit message
+    pub fn parse_co_authors(message: &str) -> Vec<User> {
+        message.lines().filter_map(co_author_from_line).collect()
+    }
+
+    /// Append a Co-authored-by trailer to `message` for each co-author it doesn't credit yet
+    pub fn message_with_co_authors(message: &str, co_authors: &[User]) -> String {
+        let mut credited = Commit::parse_co_authors(message);
+        let mut trailers = vec![];
+        for user in co_authors {
+            if credited
+                .iter()
+                .any(|c| c.email.eq_ignore_ascii_case(&user.email))
+            {
+                continue;
+            }
+            trailers.push(format!("{CO_AUTHORED_BY_TRAILER} {user}"));
+            credited.push(user.clone());
+        }
+        if trailers.is_empty() {
+            return message.to_string();
+        }
+
+        let message = message.trim_end();
+        // Trailers go in one block at the end, after a blank line
+        let separator = match message.lines().last() {
+            Some(line) if co_author_from_line(line).is_some() => "\n",
+            _ => "\n\n",
+        };
+        format!("{message}{separator}{}", trailers.join("\n"))
+    }
+}
+
+fn co_author_from_line(line: &str) -> Option<User> {
+    let line = line.trim();
+    let prefix = line.get(..CO_AUTHORED_BY_TRAILER.len())?;
+    if !prefix.eq_ignore_ascii_case(CO_AUTHORED_BY_TRAILER) {
+        return None;
+    }
+    User::from_str(&line[CO_AUTHORED_BY_TRAILER.len()..]).ok()
 }
 
 impl CommitWithSize {
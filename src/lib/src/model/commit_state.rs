@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use time::OffsetDateTime;
+
+use crate::error::OxenError;
+
+/// Data quality state that can be attached to a commit, so that downstream
+/// consumers can refuse to train on versions that have not been signed off.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum CommitState {
+    Approved,
+    Quarantined,
+}
+
+impl fmt::Display for CommitState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommitState::Approved => write!(f, "approved"),
+            CommitState::Quarantined => write!(f, "quarantined"),
+        }
+    }
+}
+
+impl std::str::FromStr for CommitState {
+    type Err = OxenError;
+
+    fn from_str(s: &str) -> Result<CommitState, OxenError> {
+        match s.to_lowercase().as_str() {
+            "approved" => Ok(CommitState::Approved),
+            "quarantined" => Ok(CommitState::Quarantined),
+            _ => Err(OxenError::basic_str(format!(
+                "Invalid commit state '{s}', must be one of: approved, quarantined"
+            ))),
+        }
+    }
+}
+
+/// Who set the state on a commit and when
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CommitStateEntry {
+    pub commit_id: String,
+    pub state: CommitState,
+    pub author: String,
+    pub email: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+}
+
+/// Body used to set the state of a commit through the API
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NewCommitState {
+    pub state: CommitState,
+    pub author: String,
+    pub email: String,
+}
@@ -7,6 +7,7 @@ pub use custom_data_type::CustomDataType;
 pub use data_type::DataType;
 pub use field::Field;
 
+use crate::error::OxenError;
 use crate::util::hasher;
 use itertools::Itertools;
 use polars::prelude::SchemaExt;
@@ -77,6 +78,45 @@ impl Schema {
         self.hash == schema_ref
     }
 
+    /// The columns that identify a row, from `_oxen.primary_key` in the schema metadata
+    pub fn primary_key(&self) -> Option<Vec<String>> {
+        let key = self.metadata.as_ref()?.get("_oxen")?.get("primary_key")?;
+        let columns: Vec<String> = key
+            .as_array()?
+            .iter()
+            .filter_map(|c| c.as_str().map(String::from))
+            .collect();
+        if columns.is_empty() {
+            None
+        } else {
+            Some(columns)
+        }
+    }
+
+    /// Set `_oxen.primary_key` in the schema metadata, keeping the rest of the metadata
+    pub fn set_primary_key(&mut self, columns: &[String]) -> Result<(), OxenError> {
+        for column in columns {
+            if !self.has_field_name(column) {
+                return Err(OxenError::basic_str(format!(
+                    "Cannot use '{column}' as a key, it is not a column in the schema"
+                )));
+            }
+        }
+
+        let mut metadata = match self.metadata.take() {
+            Some(Value::Object(map)) => map,
+            _ => serde_json::Map::new(),
+        };
+        let mut oxen = match metadata.remove("_oxen") {
+            Some(Value::Object(map)) => map,
+            _ => serde_json::Map::new(),
+        };
+        oxen.insert("primary_key".to_string(), serde_json::json!(columns));
+        metadata.insert("_oxen".to_string(), Value::Object(oxen));
+        self.metadata = Some(Value::Object(metadata));
+        Ok(())
+    }
+
     /// Add metadata to a column
     pub fn add_column_metadata(&mut self, name: &str, metadata: &Value) {
         log::debug!("add_column_metadata {} {}", name, metadata);
@@ -334,6 +374,24 @@ mod tests {
     use crate::model::data_frame::schema::Field;
     use crate::model::data_frame::schema::Schema;
 
+    #[test]
+    fn test_set_primary_key_keeps_other_metadata() {
+        let mut schema = Schema::new(vec![Field::new("id", "i64"), Field::new("label", "str")]);
+        schema.metadata = Some(serde_json::json!({
+            "task": "classification",
+            "_oxen": { "render": { "func": "image" } }
+        }));
+        assert_eq!(schema.primary_key(), None);
+
+        schema.set_primary_key(&["id".to_string()]).unwrap();
+        assert_eq!(schema.primary_key(), Some(vec!["id".to_string()]));
+        let metadata = schema.metadata.as_ref().unwrap();
+        assert_eq!(metadata["task"], "classification");
+        assert_eq!(metadata["_oxen"]["render"]["func"], "image");
+
+        assert!(schema.set_primary_key(&["missing".to_string()]).is_err());
+    }
+
     #[test]
     fn test_schemas_to_string_one_field() {
         let mut schemas = HashMap::new();
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::model::Schema;
+
+/// The card describing a dataset at a revision: the metadata from the front matter of
+/// the card file, its markdown body, and stats filled in from the merkle tree.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DatasetCard {
+    pub commit_id: String,
+    // None if the revision has no card file, the stats are still filled in
+    pub path: Option<PathBuf>,
+    pub metadata: BTreeMap<String, serde_json::Value>,
+    pub body: String,
+    pub stats: DatasetCardStats,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DatasetCardStats {
+    pub num_bytes: u64,
+    pub num_files: u64,
+    pub data_type_counts: BTreeMap<String, u64>,
+    pub data_type_sizes: BTreeMap<String, u64>,
+    pub schemas: Vec<DatasetCardSchema>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DatasetCardSchema {
+    pub path: PathBuf,
+    #[serde(flatten)]
+    pub schema: Schema,
+}
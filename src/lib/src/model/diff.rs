@@ -20,11 +20,23 @@ pub mod generic_diff_summary;
 pub mod dir_diff;
 pub mod dir_diff_summary;
 
+pub mod dir_size_diff;
+pub use dir_size_diff::DirSizeDiff;
+
+pub mod drift_report;
+pub use drift_report::DriftReport;
+
+pub mod image_diff;
+pub use image_diff::ImageDiff;
+
 pub mod schema_diff;
 
 pub mod tabular_diff;
 pub use tabular_diff::TabularDiff;
 
+pub mod tabular_change_preview;
+pub use tabular_change_preview::TabularChangePreview;
+
 pub mod tabular_diff_summary;
 
 pub mod text_diff;
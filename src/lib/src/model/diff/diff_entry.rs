@@ -10,7 +10,7 @@ use crate::error::OxenError;
 use crate::model::diff::dir_diff_summary::DirDiffSummaryImpl;
 use crate::model::diff::AddRemoveModifyCounts;
 use crate::model::merkle_tree::node::{DirNode, FileNode};
-use crate::model::{Commit, EntryDataType, MetadataEntry, ParsedResource};
+use crate::model::{Commit, EntryDataType, MerkleHash, MetadataEntry, ParsedResource};
 use crate::opts::DFOpts;
 use crate::view::TabularDiffView;
 use crate::{
@@ -182,8 +182,10 @@ impl DiffEntry {
         } else {
             base_dir.clone().unwrap()
         };
-        let base_resource = DiffEntry::resource_from_dir_node(base_dir.clone(), &dir_path);
-        let head_resource = DiffEntry::resource_from_dir_node(head_dir.clone(), &dir_path);
+        let base_resource =
+            DiffEntry::resource_from_dir_node(base_dir.clone(), &dir_path, base_commit);
+        let head_resource =
+            DiffEntry::resource_from_dir_node(head_dir.clone(), &dir_path, head_commit);
 
         let mut base_meta_entry = MetadataEntry::from_dir_node(repo, base_dir.clone(), base_commit);
         let mut head_meta_entry = MetadataEntry::from_dir_node(repo, head_dir.clone(), head_commit);
@@ -268,7 +270,7 @@ impl DiffEntry {
         }
 
         if let Some(df_opts) = df_opts {
-            if data_type == EntryDataType::Tabular && should_do_full_diff {
+            if data_type.is_tabular() && should_do_full_diff {
                 log::debug!("doing full diff for tabular");
                 let diff =
                     TabularDiffView::from_file_nodes(repo, &base_entry, &head_entry, df_opts);
@@ -290,6 +292,33 @@ impl DiffEntry {
             }
         }
 
+        if data_type == EntryDataType::Image && should_do_full_diff {
+            if let (Some(base), Some(head)) = (&base_entry, &head_entry) {
+                let base_path =
+                    util::fs::plain_version_path_from_hash(repo, base.hash.to_string())?;
+                let head_path =
+                    util::fs::plain_version_path_from_hash(repo, head.hash.to_string())?;
+                match util::image::diff(base_path.path(), head_path.path()) {
+                    Ok(diff) => {
+                        return Ok(DiffEntry {
+                            status: status.to_string(),
+                            data_type: data_type.clone(),
+                            filename: file_path.as_os_str().to_str().unwrap().to_string(),
+                            is_dir: false,
+                            size: current_entry.num_bytes,
+                            head_resource,
+                            base_resource,
+                            head_entry: head_meta_entry,
+                            base_entry: base_meta_entry,
+                            diff_summary: Some(GenericDiffSummary::ImageDiff(diff)),
+                            diff: None,
+                        });
+                    }
+                    Err(err) => log::warn!("Could not diff image {file_path:?}: {err}"),
+                }
+            }
+        }
+
         // log::debug!("fall through .... not doing full diff for tabular");
         Ok(DiffEntry {
             status: status.to_string(),
@@ -323,15 +352,15 @@ impl DiffEntry {
     ) -> Result<DiffEntry, OxenError> {
         // Need to check whether we have the head or base entry to check data about the file
         let (current_entry, version_path) = if let Some(entry) = &head_entry {
-            (entry.clone(), util::fs::version_path(repo, entry))
+            (entry.clone(), util::fs::plain_version_path(repo, entry)?)
         } else {
             (
                 base_entry.clone().unwrap(),
-                util::fs::version_path(repo, &base_entry.clone().unwrap()),
+                util::fs::plain_version_path(repo, &base_entry.clone().unwrap())?,
             )
         };
 
-        let data_type = util::fs::file_data_type(&version_path);
+        let data_type = util::fs::file_data_type(version_path.path());
 
         let base_resource = DiffEntry::resource_from_entry(base_entry.clone());
         let head_resource = DiffEntry::resource_from_entry(head_entry.clone());
@@ -368,7 +397,7 @@ impl DiffEntry {
         // );
 
         if let Some(df_opts) = df_opts {
-            if data_type == EntryDataType::Tabular && should_do_full_diff {
+            if data_type.is_tabular() && should_do_full_diff {
                 let diff =
                     TabularDiffView::from_commit_entries(repo, &base_entry, &head_entry, df_opts);
                 return Ok(DiffEntry {
@@ -434,17 +463,26 @@ impl DiffEntry {
         })
     }
 
+    // Content addressed dir nodes have no last commit, they point at the commit compared
     fn resource_from_dir_node(
         node: Option<DirNode>,
         dir_path: impl AsRef<Path>,
+        commit: &Commit,
     ) -> Option<ParsedResource> {
         let path = dir_path.as_ref().to_path_buf();
-        node.map(|node| ParsedResource {
-            commit: None,
-            branch: None,
-            version: PathBuf::from(node.last_commit_id.to_string()),
-            path: path.clone(),
-            resource: PathBuf::from(node.last_commit_id.to_string()).join(path),
+        node.map(|node| {
+            let version = if node.last_commit_id == MerkleHash::new(0) {
+                commit.id.clone()
+            } else {
+                node.last_commit_id.to_string()
+            };
+            ParsedResource {
+                commit: None,
+                branch: None,
+                version: PathBuf::from(&version),
+                path: path.clone(),
+                resource: PathBuf::from(&version).join(path),
+            }
         })
     }
 
@@ -690,9 +728,11 @@ impl DiffEntry {
     ) -> Result<Option<GenericDiffSummary>, OxenError> {
         // TODO match on type, and create the appropriate summary
         match data_type {
-            EntryDataType::Tabular => Ok(Some(GenericDiffSummary::TabularDiffWrapper(
-                TabularDiffWrapper::from_commit_entries(repo, base_entry, head_entry)?,
-            ))),
+            EntryDataType::Tabular | EntryDataType::Geospatial => {
+                Ok(Some(GenericDiffSummary::TabularDiffWrapper(
+                    TabularDiffWrapper::from_commit_entries(repo, base_entry, head_entry)?,
+                )))
+            }
             _ => Ok(None),
         }
     }
@@ -704,9 +744,11 @@ impl DiffEntry {
     ) -> Result<Option<GenericDiffSummary>, OxenError> {
         // TODO match on type, and create the appropriate summary
         match data_type {
-            EntryDataType::Tabular => Ok(Some(GenericDiffSummary::TabularDiffWrapper(
-                TabularDiffWrapper::from_file_nodes(base_entry, head_entry)?,
-            ))),
+            EntryDataType::Tabular | EntryDataType::Geospatial => {
+                Ok(Some(GenericDiffSummary::TabularDiffWrapper(
+                    TabularDiffWrapper::from_file_nodes(base_entry, head_entry)?,
+                )))
+            }
             _ => Ok(None),
         }
     }
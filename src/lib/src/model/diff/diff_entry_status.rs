@@ -6,6 +6,8 @@ pub enum DiffEntryStatus {
     Added,
     Modified,
     Removed,
+    Renamed,
+    Copied,
 }
 
 // Downcase the status
@@ -15,6 +17,8 @@ impl std::fmt::Display for DiffEntryStatus {
             DiffEntryStatus::Added => "added",
             DiffEntryStatus::Modified => "modified",
             DiffEntryStatus::Removed => "removed",
+            DiffEntryStatus::Renamed => "renamed",
+            DiffEntryStatus::Copied => "copied",
         };
         write!(f, "{}", status)
     }
@@ -29,6 +33,8 @@ impl std::str::FromStr for DiffEntryStatus {
             "added" => Ok(DiffEntryStatus::Added),
             "modified" => Ok(DiffEntryStatus::Modified),
             "removed" => Ok(DiffEntryStatus::Removed),
+            "renamed" => Ok(DiffEntryStatus::Renamed),
+            "copied" => Ok(DiffEntryStatus::Copied),
             _ => Err(format!("Could not parse {} as a DiffEntryStatus", s)),
         }
     }
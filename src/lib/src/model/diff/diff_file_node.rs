@@ -15,4 +15,8 @@ pub struct DiffFileNode {
     // FileNode(s)
     pub head_entry: Option<FileNode>,
     pub base_entry: Option<FileNode>,
+
+    // Where the contents lived in base if the file was renamed or copied
+    #[serde(default)]
+    pub source_path: Option<PathBuf>,
 }
@@ -1,4 +1,5 @@
 // use crate::model::diff::dir_diff::DirDiff;
+use crate::model::diff::image_diff::ImageDiff;
 use crate::model::diff::tabular_diff::TabularDiff;
 use crate::model::diff::text_diff::TextDiff;
 
@@ -6,4 +7,5 @@ use crate::model::diff::text_diff::TextDiff;
 pub enum DiffResult {
     Tabular(TabularDiff),
     Text(TextDiff),
+    Image(ImageDiff),
 }
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::model::diff::diff_entry_status::DiffEntryStatus;
+use crate::model::diff::AddRemoveModifyCounts;
+use crate::model::merkle_tree::node::DirNode;
+
+/// File counts and byte deltas of a directory that changed between two revisions.
+/// Counts are recursive, so a parent dir includes the changes of its children.
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct DirSizeDiff {
+    pub path: PathBuf,
+    pub status: DiffEntryStatus,
+    pub file_counts: AddRemoveModifyCounts,
+    pub base_num_bytes: u64,
+    pub head_num_bytes: u64,
+    pub byte_delta: i64,
+    // Keyed by data type, only the types whose total size changed
+    pub data_type_byte_deltas: BTreeMap<String, i64>,
+}
+
+impl DirSizeDiff {
+    pub fn from_dir_nodes(
+        path: impl Into<PathBuf>,
+        base_dir: Option<&DirNode>,
+        head_dir: Option<&DirNode>,
+        file_counts: AddRemoveModifyCounts,
+    ) -> DirSizeDiff {
+        let status = match (base_dir, head_dir) {
+            (None, Some(_)) => DiffEntryStatus::Added,
+            (Some(_), None) => DiffEntryStatus::Removed,
+            _ => DiffEntryStatus::Modified,
+        };
+
+        let base_num_bytes = base_dir.map(|d| d.num_bytes).unwrap_or(0);
+        let head_num_bytes = head_dir.map(|d| d.num_bytes).unwrap_or(0);
+
+        let mut data_type_byte_deltas: BTreeMap<String, i64> = BTreeMap::new();
+        if let Some(base_dir) = base_dir {
+            for (data_type, size) in &base_dir.data_type_sizes {
+                *data_type_byte_deltas.entry(data_type.clone()).or_default() -= *size as i64;
+            }
+        }
+        if let Some(head_dir) = head_dir {
+            for (data_type, size) in &head_dir.data_type_sizes {
+                *data_type_byte_deltas.entry(data_type.clone()).or_default() += *size as i64;
+            }
+        }
+        data_type_byte_deltas.retain(|_, delta| *delta != 0);
+
+        DirSizeDiff {
+            path: path.into(),
+            status,
+            file_counts,
+            base_num_bytes,
+            head_num_bytes,
+            byte_delta: head_num_bytes as i64 - base_num_bytes as i64,
+            data_type_byte_deltas,
+        }
+    }
+}
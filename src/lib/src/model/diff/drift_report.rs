@@ -0,0 +1,73 @@
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::error::OxenError;
+
+/// How the distribution of each column of a tabular file moved between two revisions
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct DriftReport {
+    pub path: String,
+    pub base_revision: String,
+    pub head_revision: String,
+    pub base_num_rows: usize,
+    pub head_num_rows: usize,
+    // Columns only in one of the versions have no distribution to compare
+    pub added_columns: Vec<String>,
+    pub removed_columns: Vec<String>,
+    pub columns: Vec<ColumnDrift>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ColumnDriftKind {
+    Numeric,
+    Categorical,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ColumnDrift {
+    pub name: String,
+    pub dtype: String,
+    pub kind: ColumnDriftKind,
+    pub base_null_rate: f64,
+    pub head_null_rate: f64,
+    pub null_rate_delta: f64,
+    // Only for numeric columns
+    pub base_mean: Option<f64>,
+    pub head_mean: Option<f64>,
+    pub mean_shift: Option<f64>,
+    pub base_std: Option<f64>,
+    pub head_std: Option<f64>,
+    pub std_shift: Option<f64>,
+    /// Population stability index, above 0.25 is usually treated as a significant shift
+    pub psi: f64,
+    /// KL divergence of the head distribution from the base distribution
+    pub kl_divergence: f64,
+    // Only for categorical columns, the largest changes first
+    pub category_deltas: Vec<CategoryDelta>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct CategoryDelta {
+    pub value: String,
+    pub base_frequency: f64,
+    pub head_frequency: f64,
+    pub delta: f64,
+}
+
+impl DriftReport {
+    /// One row per column, for printing the report as a table
+    pub fn to_df(&self) -> Result<DataFrame, OxenError> {
+        let columns = &self.columns;
+        let df = df!(
+            "column" => columns.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(),
+            "dtype" => columns.iter().map(|c| c.dtype.as_str()).collect::<Vec<_>>(),
+            "null_rate_delta" => columns.iter().map(|c| c.null_rate_delta).collect::<Vec<_>>(),
+            "mean_shift" => columns.iter().map(|c| c.mean_shift).collect::<Vec<_>>(),
+            "std_shift" => columns.iter().map(|c| c.std_shift).collect::<Vec<_>>(),
+            "psi" => columns.iter().map(|c| c.psi).collect::<Vec<_>>(),
+            "kl_divergence" => columns.iter().map(|c| c.kl_divergence).collect::<Vec<_>>(),
+        )?;
+        Ok(df)
+    }
+}
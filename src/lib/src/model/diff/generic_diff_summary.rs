@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::model::diff::dir_diff_summary::DirDiffSummary;
+use crate::model::diff::image_diff::ImageDiff;
 use crate::model::diff::tabular_diff_summary::TabularDiffWrapper;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -8,4 +9,5 @@ use crate::model::diff::tabular_diff_summary::TabularDiffWrapper;
 pub enum GenericDiffSummary {
     DirDiffSummary(DirDiffSummary),
     TabularDiffWrapper(TabularDiffWrapper),
+    ImageDiff(ImageDiff),
 }
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Pixel level comparison of two versions of an image
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ImageDiff {
+    pub image: ImageDiffImpl,
+}
+
+// Impl is so that we can wrap the json response in the "image" field to make summaries easier to distinguish
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ImageDiffImpl {
+    pub base_width: u32,
+    pub base_height: u32,
+    pub head_width: u32,
+    pub head_height: u32,
+    // When the dimensions differ the head is resized to the base before comparing pixels
+    pub num_pixels: u64,
+    pub num_changed_pixels: u64,
+    pub percent_changed: f64,
+    // Per channel differences on a 0-255 scale
+    pub mean_abs_delta: f64,
+    pub max_abs_delta: u8,
+    // Side by side render of base, head and the changed pixels, when one was written
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub composite_path: Option<PathBuf>,
+}
+
+impl ImageDiff {
+    pub fn dimensions_changed(&self) -> bool {
+        self.image.base_width != self.image.head_width
+            || self.image.base_height != self.image.head_height
+    }
+
+    pub fn has_changes(&self) -> bool {
+        self.dimensions_changed() || self.image.num_changed_pixels > 0
+    }
+}
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+
+/// Compact summary of how a modified tabular file differs from HEAD
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TabularChangePreview {
+    pub path: PathBuf,
+    pub rows_added: usize,
+    pub rows_removed: usize,
+    pub columns_added: Vec<String>,
+    pub columns_removed: Vec<String>,
+}
+
+impl fmt::Display for TabularChangePreview {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "+{} rows, -{} rows", self.rows_added, self.rows_removed)?;
+        if !self.columns_added.is_empty() {
+            write!(f, ", added columns: {}", self.columns_added.join(", "))?;
+        }
+        if !self.columns_removed.is_empty() {
+            write!(f, ", removed columns: {}", self.columns_removed.join(", "))?;
+        }
+        Ok(())
+    }
+}
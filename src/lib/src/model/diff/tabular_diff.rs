@@ -1,7 +1,9 @@
 use crate::{
+    constants::DIFF_STATUS_COL,
     error::OxenError,
     model::data_frame::schema::{Field, Schema},
 };
+use polars::datatypes::AnyValue;
 use polars::frame::DataFrame;
 use serde::{Deserialize, Serialize};
 
@@ -47,6 +49,16 @@ pub struct TabularDiffParameters {
     pub display: Vec<String>,
 }
 
+/// A value that changed in a row matched across both versions by its keys
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TabularCellChange {
+    // Values of the key columns, in the order of the diff parameters
+    pub keys: Vec<String>,
+    pub column: String,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
 // Need to serialize here because we directly write this to disk to cache compares
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TabularDiffDupes {
@@ -135,6 +147,62 @@ impl TabularDiff {
             contents: DataFrame::empty(),
         }
     }
+
+    /// The old and new value of every target column that changed in a modified row
+    pub fn cell_changes(&self) -> Result<Vec<TabularCellChange>, OxenError> {
+        let df = &self.contents;
+        let Ok(status) = df.column(DIFF_STATUS_COL) else {
+            return Ok(vec![]);
+        };
+        let status = status.str()?;
+
+        // Targets only on one side were added or removed as a whole column
+        let mut targets = vec![];
+        for target in &self.parameters.targets {
+            let left = df.column(&format!("{target}.left"));
+            let right = df.column(&format!("{target}.right"));
+            if let (Ok(left), Ok(right)) = (left, right) {
+                targets.push((target, left, right));
+            }
+        }
+        let mut keys = vec![];
+        for key in &self.parameters.keys {
+            keys.push(df.column(key)?);
+        }
+
+        let mut changes = vec![];
+        for i in 0..df.height() {
+            if status.get(i) != Some("modified") {
+                continue;
+            }
+            let key_values = keys
+                .iter()
+                .map(|k| Ok(cell_to_string(k.get(i)?).unwrap_or_default()))
+                .collect::<Result<Vec<String>, OxenError>>()?;
+            for (target, left, right) in &targets {
+                let old = cell_to_string(left.get(i)?);
+                let new = cell_to_string(right.get(i)?);
+                if old != new {
+                    changes.push(TabularCellChange {
+                        keys: key_values.clone(),
+                        column: target.to_string(),
+                        old,
+                        new,
+                    });
+                }
+            }
+        }
+        Ok(changes)
+    }
+}
+
+fn cell_to_string(value: AnyValue) -> Option<String> {
+    match value {
+        AnyValue::Null => None,
+        AnyValue::String(s) => Some(s.to_string()),
+        AnyValue::StringOwned(s) => Some(s.to_string()),
+        value => Some(value.to_string()),
+    }
 }
 
 impl TabularDiffSummary {
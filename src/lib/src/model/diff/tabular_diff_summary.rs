@@ -47,18 +47,26 @@ impl TabularDiffWrapper {
     ) -> Result<TabularDiffWrapper, OxenError> {
         match (base_entry, head_entry) {
             (Some(base_entry), Some(head_entry)) => {
-                let base_size = match &base_entry.metadata {
-                    Some(GenericMetadata::MetadataTabular(df_meta)) => DataFrameSize {
-                        height: df_meta.tabular.height,
-                        width: df_meta.tabular.width,
+                let base_size = match base_entry
+                    .metadata
+                    .as_ref()
+                    .and_then(GenericMetadata::tabular)
+                {
+                    Some(df_meta) => DataFrameSize {
+                        height: df_meta.height,
+                        width: df_meta.width,
                     },
                     _ => return Err(OxenError::basic_str("Invalid metadata type")),
                 };
 
-                let head_size = match &head_entry.metadata {
-                    Some(GenericMetadata::MetadataTabular(df_meta)) => DataFrameSize {
-                        height: df_meta.tabular.height,
-                        width: df_meta.tabular.width,
+                let head_size = match head_entry
+                    .metadata
+                    .as_ref()
+                    .and_then(GenericMetadata::tabular)
+                {
+                    Some(df_meta) => DataFrameSize {
+                        height: df_meta.height,
+                        width: df_meta.width,
                     },
                     _ => return Err(OxenError::basic_str("Invalid metadata type")),
                 };
@@ -101,10 +109,14 @@ impl TabularDiffWrapper {
                 })
             }
             (Some(base_entry), None) => {
-                let base_size = match &base_entry.metadata {
-                    Some(GenericMetadata::MetadataTabular(df_meta)) => DataFrameSize {
-                        height: df_meta.tabular.height,
-                        width: df_meta.tabular.width,
+                let base_size = match base_entry
+                    .metadata
+                    .as_ref()
+                    .and_then(GenericMetadata::tabular)
+                {
+                    Some(df_meta) => DataFrameSize {
+                        height: df_meta.height,
+                        width: df_meta.width,
                     },
                     _ => return Err(OxenError::basic_str("Invalid metadata type")),
                 };
@@ -121,10 +133,14 @@ impl TabularDiffWrapper {
             }
 
             (None, Some(head_entry)) => {
-                let head_size = match &head_entry.metadata {
-                    Some(GenericMetadata::MetadataTabular(df_meta)) => DataFrameSize {
-                        height: df_meta.tabular.height,
-                        width: df_meta.tabular.width,
+                let head_size = match head_entry
+                    .metadata
+                    .as_ref()
+                    .and_then(GenericMetadata::tabular)
+                {
+                    Some(df_meta) => DataFrameSize {
+                        height: df_meta.height,
+                        width: df_meta.width,
                     },
                     _ => return Err(OxenError::basic_str("Invalid metadata type")),
                 };
@@ -303,9 +319,10 @@ impl TabularDiffWrapper {
     ) -> Option<DataFrame> {
         match node {
             Some(node) => {
-                let version_path = util::fs::version_path_from_hash(repo, node.hash.to_string());
+                let version_path =
+                    util::fs::plain_version_path_from_hash(repo, node.hash.to_string()).ok()?;
                 match tabular::read_df_with_extension(
-                    version_path,
+                    version_path.path(),
                     node.extension.clone(),
                     &DFOpts::empty(),
                 ) {
@@ -323,8 +340,8 @@ impl TabularDiffWrapper {
     ) -> Option<DataFrame> {
         match entry {
             Some(entry) => {
-                let version_path = util::fs::version_path(repo, entry);
-                match tabular::read_df(version_path, DFOpts::empty()) {
+                let version_path = util::fs::plain_version_path(repo, entry).ok()?;
+                match tabular::read_df(version_path.path(), DFOpts::empty()) {
                     Ok(df) => Some(df),
                     Err(_) => None,
                 }
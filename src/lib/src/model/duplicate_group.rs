@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A file that shares its content with at least one other file
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateEntry {
+    pub path: PathBuf,
+    // The first commit (in the order searched) the path was found in with this content
+    pub commit_id: String,
+}
+
+/// A group of files that all have identical content
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DuplicateGroup {
+    pub hash: String,
+    pub num_bytes: u64,
+    pub entries: Vec<DuplicateEntry>,
+}
+
+impl DuplicateGroup {
+    /// Bytes that could be saved by keeping a single copy of the file
+    pub fn wasted_bytes(&self) -> u64 {
+        self.num_bytes * (self.entries.len().saturating_sub(1) as u64)
+    }
+}
+
+/// An image that looks like at least one other image, based on perceptual hashes
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct NearDuplicateEntry {
+    pub path: PathBuf,
+    pub commit_id: String,
+    pub hash: String,
+    pub phash: String,
+    // Hamming distance between this phash and the first entry in the group
+    pub distance: u32,
+}
+
+/// A cluster of images whose perceptual hashes are within a threshold of each other
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NearDuplicateGroup {
+    pub entries: Vec<NearDuplicateEntry>,
+}
@@ -12,6 +12,7 @@ pub enum EntryDataType {
     Video,
     Audio,
     Tabular,
+    Geospatial,
     Binary,
 }
 
@@ -24,9 +25,15 @@ impl EntryDataType {
             EntryDataType::Video => "🎥".to_string(),
             EntryDataType::Audio => "🎵".to_string(),
             EntryDataType::Tabular => "📊".to_string(),
+            EntryDataType::Geospatial => "🗺️".to_string(),
             EntryDataType::Binary => "📦".to_string(),
         }
     }
+
+    /// Whether the entry can be read as a data frame
+    pub fn is_tabular(&self) -> bool {
+        matches!(self, EntryDataType::Tabular | EntryDataType::Geospatial)
+    }
 }
 
 impl FromStr for EntryDataType {
@@ -40,6 +47,7 @@ impl FromStr for EntryDataType {
             "video" => Ok(EntryDataType::Video),
             "audio" => Ok(EntryDataType::Audio),
             "tabular" => Ok(EntryDataType::Tabular),
+            "geospatial" => Ok(EntryDataType::Geospatial),
             "binary" => Ok(EntryDataType::Binary),
             _ => Err(()),
         }
@@ -55,6 +63,7 @@ impl fmt::Display for EntryDataType {
             EntryDataType::Video => write!(f, "video"),
             EntryDataType::Audio => write!(f, "audio"),
             EntryDataType::Tabular => write!(f, "tabular"),
+            EntryDataType::Geospatial => write!(f, "geospatial"),
             EntryDataType::Binary => write!(f, "binary"),
         }
     }
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+use time::OffsetDateTime;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeQueueStatus {
+    Queued,
+    Merging,
+    Merged,
+    Conflict,
+    Failed,
+    Cancelled,
+}
+
+impl MergeQueueStatus {
+    /// Whether the entry is done being processed
+    pub fn is_finished(&self) -> bool {
+        !matches!(self, MergeQueueStatus::Queued | MergeQueueStatus::Merging)
+    }
+}
+
+impl fmt::Display for MergeQueueStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            MergeQueueStatus::Queued => "queued",
+            MergeQueueStatus::Merging => "merging",
+            MergeQueueStatus::Merged => "merged",
+            MergeQueueStatus::Conflict => "conflict",
+            MergeQueueStatus::Failed => "failed",
+            MergeQueueStatus::Cancelled => "cancelled",
+        };
+        write!(f, "{s}")
+    }
+}
+
+/// A request to merge a head branch into a base branch, processed in the order queued
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MergeQueueEntry {
+    pub id: String,
+    pub base: String,
+    pub head: String,
+    pub author: String,
+    pub email: String,
+    pub status: MergeQueueStatus,
+    // Set once the head has been merged
+    pub merge_commit_id: Option<String>,
+    // Paths that conflicted if the merge could not be completed
+    pub conflicts: Vec<PathBuf>,
+    pub error: Option<String>,
+    // Url to POST the entry to once it is finished
+    pub notify_url: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+}
+
+/// Body used to queue a merge through the API
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NewMergeQueueEntry {
+    pub base: String,
+    pub head: String,
+    pub author: String,
+    pub email: String,
+    pub notify_url: Option<String>,
+}
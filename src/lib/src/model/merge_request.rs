@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use time::OffsetDateTime;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MergeRequestStatus {
+    Open,
+    Merged,
+    Closed,
+}
+
+impl fmt::Display for MergeRequestStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            MergeRequestStatus::Open => "open",
+            MergeRequestStatus::Merged => "merged",
+            MergeRequestStatus::Closed => "closed",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl std::str::FromStr for MergeRequestStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "open" => Ok(MergeRequestStatus::Open),
+            "merged" => Ok(MergeRequestStatus::Merged),
+            "closed" => Ok(MergeRequestStatus::Closed),
+            _ => Err(format!("Could not parse {s} as a MergeRequestStatus")),
+        }
+    }
+}
+
+/// A proposal to merge a head branch into a base branch, reviewed before it lands
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MergeRequest {
+    pub id: String,
+    // Sequential per repository, easier to type than the id
+    pub number: u64,
+    pub title: String,
+    pub description: String,
+    pub base: String,
+    pub head: String,
+    pub author: String,
+    pub email: String,
+    pub status: MergeRequestStatus,
+    // Set once the head has been merged
+    pub merge_commit_id: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub updated_at: OffsetDateTime,
+}
+
+/// Body used to open a merge request through the API
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NewMergeRequest {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub base: String,
+    pub head: String,
+    pub author: String,
+    pub email: String,
+}
@@ -27,6 +27,7 @@ impl CommitNode {
             message: self.message.to_owned(),
             timestamp: self.timestamp.to_owned(),
             root_hash: None,
+            co_authors: Commit::parse_co_authors(&self.message),
         }
     }
 
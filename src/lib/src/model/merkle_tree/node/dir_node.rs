@@ -30,6 +30,12 @@ pub struct DirNode {
     // Recursive file counts in the directory
     pub data_type_counts: HashMap<String, u64>,
     pub data_type_sizes: HashMap<String, u64>,
+    // Recursive histogram of image resolutions in the directory, keyed by bucket
+    #[serde(default)]
+    pub image_resolution_counts: HashMap<String, u64>,
+    // Recursive total duration in milliseconds of audio and video, keyed by data type
+    #[serde(default)]
+    pub data_type_durations: HashMap<String, u64>,
 }
 
 impl DirNode {
@@ -66,6 +72,8 @@ impl Default for DirNode {
             last_modified_nanoseconds: 0,
             data_type_counts: HashMap::new(),
             data_type_sizes: HashMap::new(),
+            image_resolution_counts: HashMap::new(),
+            data_type_durations: HashMap::new(),
         }
     }
 }
@@ -91,6 +99,12 @@ impl fmt::Debug for DirNode {
         writeln!(f, "\tnum_bytes: {}", bytesize::ByteSize::b(self.num_bytes))?;
         writeln!(f, "\tdata_type_counts: {:?}", self.data_type_counts)?;
         writeln!(f, "\tdata_type_sizes: {:?}", self.data_type_sizes)?;
+        writeln!(
+            f,
+            "\timage_resolution_counts: {:?}",
+            self.image_resolution_counts
+        )?;
+        writeln!(f, "\tdata_type_durations: {:?}", self.data_type_durations)?;
         Ok(())
     }
 }
@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
 
+use crate::model::metadata::metadata_tabular::MetadataTabularImpl;
 use crate::model::metadata::{
-    MetadataAudio, MetadataDir, MetadataImage, MetadataTabular, MetadataText, MetadataVideo,
+    MetadataAudio, MetadataDir, MetadataGeospatial, MetadataImage, MetadataSqlite, MetadataTabular,
+    MetadataText, MetadataVideo,
 };
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -12,7 +14,30 @@ pub enum GenericMetadata {
     MetadataImage(MetadataImage),
     MetadataVideo(MetadataVideo),
     MetadataAudio(MetadataAudio),
+    // Must come before MetadataTabular, it has the same `tabular` key plus `geospatial`
+    MetadataGeospatial(MetadataGeospatial),
     MetadataTabular(MetadataTabular),
+    MetadataSqlite(MetadataSqlite),
+}
+
+impl GenericMetadata {
+    /// Duration in seconds for time based media (audio and video)
+    pub fn num_seconds(&self) -> Option<f64> {
+        match self {
+            GenericMetadata::MetadataVideo(metadata) => Some(metadata.video.num_seconds),
+            GenericMetadata::MetadataAudio(metadata) => Some(metadata.audio.num_seconds),
+            _ => None,
+        }
+    }
+
+    /// Size and schema for anything that can be read as a data frame
+    pub fn tabular(&self) -> Option<&MetadataTabularImpl> {
+        match self {
+            GenericMetadata::MetadataTabular(metadata) => Some(&metadata.tabular),
+            GenericMetadata::MetadataGeospatial(metadata) => Some(&metadata.tabular),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for GenericMetadata {
@@ -23,7 +48,9 @@ impl std::fmt::Display for GenericMetadata {
             GenericMetadata::MetadataImage(metadata) => write!(f, "{}", metadata),
             GenericMetadata::MetadataVideo(metadata) => write!(f, "{}", metadata),
             GenericMetadata::MetadataAudio(metadata) => write!(f, "{}", metadata),
+            GenericMetadata::MetadataGeospatial(metadata) => write!(f, "{}", metadata),
             GenericMetadata::MetadataTabular(metadata) => write!(f, "{}", metadata),
+            GenericMetadata::MetadataSqlite(metadata) => write!(f, "{}", metadata),
         }
     }
 }
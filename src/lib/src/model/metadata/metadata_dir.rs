@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
+use crate::model::merkle_tree::node::DirNode;
 use crate::view::DataTypeCount;
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -10,14 +12,41 @@ pub struct MetadataDir {
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct MetadataDirImpl {
     pub data_types: Vec<DataTypeCount>,
+    #[serde(default)]
+    pub image_resolutions: HashMap<String, u64>,
+    // Total milliseconds of audio and video by data type
+    #[serde(default)]
+    pub durations: HashMap<String, u64>,
 }
 
 impl MetadataDir {
     pub fn new(data_types: Vec<DataTypeCount>) -> Self {
         Self {
-            dir: MetadataDirImpl { data_types },
+            dir: MetadataDirImpl {
+                data_types,
+                image_resolutions: HashMap::new(),
+                durations: HashMap::new(),
+            },
         }
     }
+
+    /// Aggregated composition of a committed directory
+    pub fn from_dir_node(dir_node: &DirNode) -> Self {
+        let mut metadata = MetadataDir::new(dir_node.data_types());
+        metadata.dir.image_resolutions = dir_node
+            .image_resolution_counts
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .map(|(bucket, count)| (bucket.to_owned(), *count))
+            .collect();
+        metadata.dir.durations = dir_node
+            .data_type_durations
+            .iter()
+            .filter(|(_, ms)| **ms > 0)
+            .map(|(data_type, ms)| (data_type.to_owned(), *ms))
+            .collect();
+        metadata
+    }
 }
 
 impl std::fmt::Display for MetadataDir {
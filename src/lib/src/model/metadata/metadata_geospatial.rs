@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::metadata::metadata_tabular::MetadataTabularImpl;
+use crate::model::Schema;
+
+/// Tabular metadata plus the geometry columns, so anything that only cares
+/// about the size and schema can treat it like any other data frame
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MetadataGeospatial {
+    pub tabular: MetadataTabularImpl,
+    pub geospatial: MetadataGeospatialImpl,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MetadataGeospatialImpl {
+    pub primary_column: Option<String>,
+    pub geometry_columns: Vec<GeometryColumn>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct GeometryColumn {
+    pub name: String,
+    // wkb, geojson, point, polygon...
+    pub encoding: String,
+    pub geometry_types: Vec<String>,
+    // PROJJSON or a GeoJSON crs member, None means OGC:CRS84
+    pub crs: Option<String>,
+    // [min_x, min_y, max_x, max_y]
+    pub bbox: Option<Vec<f64>>,
+}
+
+impl MetadataGeospatial {
+    pub fn new(
+        width: usize,
+        height: usize,
+        schema: Schema,
+        primary_column: Option<String>,
+        geometry_columns: Vec<GeometryColumn>,
+    ) -> Self {
+        Self {
+            tabular: MetadataTabularImpl {
+                width,
+                height,
+                schema,
+                column_stats: None,
+            },
+            geospatial: MetadataGeospatialImpl {
+                primary_column,
+                geometry_columns,
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for MetadataGeospatial {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let columns: Vec<&str> = self
+            .geospatial
+            .geometry_columns
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        write!(
+            f,
+            "MetadataGeospatial({}x{}, geometry: {})",
+            self.tabular.width,
+            self.tabular.height,
+            columns.join(", ")
+        )
+    }
+}
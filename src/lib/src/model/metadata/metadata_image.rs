@@ -31,8 +31,29 @@ pub struct MetadataImageImpl {
     pub width: u32,
     pub height: u32,
     pub color_space: Option<ImgColorSpace>,
+    // Default these so nodes written before they existed still deserialize
+    #[serde(default)]
+    pub num_channels: Option<u8>,
+    #[serde(default)]
+    pub exif: Option<MetadataImageExif>,
+    /// Hex encoded perceptual hash, only computed when the repository opts in
+    #[serde(default)]
+    pub phash: Option<String>,
 }
 
+/// The subset of EXIF tags we pull out of the first IFD of an image
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetadataImageExif {
+    pub make: Option<String>,
+    pub model: Option<String>,
+    pub software: Option<String>,
+    pub date_time: Option<String>,
+    pub orientation: Option<u16>,
+}
+
+/// Upper bounds (inclusive) of the longest image side for each resolution histogram bucket
+pub const RESOLUTION_BUCKETS: [u32; 7] = [64, 128, 256, 512, 1024, 2048, 4096];
+
 #[derive(Deserialize, Debug)]
 pub struct ImgResize {
     pub width: Option<u32>,
@@ -46,9 +67,33 @@ impl MetadataImage {
                 width,
                 height,
                 color_space: None,
+                num_channels: None,
+                exif: None,
+                phash: None,
             },
         }
     }
+
+    /// The perceptual hash of the image, if one was computed when it was added
+    pub fn phash(&self) -> Option<u64> {
+        self.image
+            .phash
+            .as_ref()
+            .and_then(|phash| u64::from_str_radix(phash, 16).ok())
+    }
+
+    /// The resolution histogram bucket this image falls in, based on its longest side
+    pub fn resolution_bucket(&self) -> String {
+        let longest_side = self.image.width.max(self.image.height);
+        let mut lower = 0;
+        for upper in RESOLUTION_BUCKETS {
+            if longest_side <= upper {
+                return format!("{}-{}", lower, upper);
+            }
+            lower = upper + 1;
+        }
+        format!("{}+", lower)
+    }
 }
 
 impl std::fmt::Display for MetadataImage {
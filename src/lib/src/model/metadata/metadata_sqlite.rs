@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::Schema;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MetadataSqlite {
+    pub sqlite: MetadataSqliteImpl,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MetadataSqliteImpl {
+    pub tables: Vec<MetadataSqliteTable>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MetadataSqliteTable {
+    pub name: String,
+    pub width: usize,
+    pub height: usize,
+    pub schema: Schema,
+}
+
+impl MetadataSqlite {
+    pub fn new(tables: Vec<MetadataSqliteTable>) -> Self {
+        Self {
+            sqlite: MetadataSqliteImpl { tables },
+        }
+    }
+
+    pub fn get_table(&self, name: &str) -> Option<&MetadataSqliteTable> {
+        self.sqlite.tables.iter().find(|t| t.name == name)
+    }
+}
+
+impl std::fmt::Display for MetadataSqlite {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let tables: Vec<String> = self
+            .sqlite
+            .tables
+            .iter()
+            .map(|t| format!("{}({}x{})", t.name, t.width, t.height))
+            .collect();
+        write!(f, "MetadataSqlite({})", tables.join(", "))
+    }
+}
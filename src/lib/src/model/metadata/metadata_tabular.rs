@@ -12,6 +12,20 @@ pub struct MetadataTabularImpl {
     pub width: usize,
     pub height: usize,
     pub schema: Schema,
+    // Computed when the file is added, None for files committed before stats existed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column_stats: Option<Vec<ColumnStats>>,
+}
+
+/// Summary of the values in one column, so the file does not have to be scanned to plan a query
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ColumnStats {
+    pub name: String,
+    // Rendered as strings so every orderable dtype fits, None if the column is empty or not orderable
+    pub min: Option<String>,
+    pub max: Option<String>,
+    pub null_count: usize,
+    pub distinct_count: Option<usize>,
 }
 
 impl MetadataTabular {
@@ -21,6 +35,7 @@ impl MetadataTabular {
                 width,
                 height,
                 schema,
+                column_stats: None,
             },
         }
     }
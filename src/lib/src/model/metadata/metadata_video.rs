@@ -10,6 +10,11 @@ pub struct MetadataVideoImpl {
     pub num_seconds: f64,
     pub width: usize,
     pub height: usize,
+    // Default these so nodes written before they existed still deserialize
+    #[serde(default)]
+    pub fps: Option<f64>,
+    #[serde(default)]
+    pub codec: Option<String>,
 }
 
 impl MetadataVideo {
@@ -19,6 +24,8 @@ impl MetadataVideo {
                 num_seconds,
                 width,
                 height,
+                fps: None,
+                codec: None,
             },
         }
     }
@@ -28,8 +35,15 @@ impl std::fmt::Display for MetadataVideo {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(
             f,
-            "MetadataVideo({}x{} {}s)",
+            "MetadataVideo({}x{} {}s",
             self.video.width, self.video.height, self.video.num_seconds
-        )
+        )?;
+        if let Some(fps) = self.video.fps {
+            write!(f, " {}fps", fps)?;
+        }
+        if let Some(codec) = &self.video.codec {
+            write!(f, " {}", codec)?;
+        }
+        write!(f, ")")
     }
 }
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// A secondary oxen-server a repository is replicated to
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Mirror {
+    pub id: String,
+    /// Url of the repository on the other server, ex: https://hub.oxen.ai/ox/CatsVsDogs
+    pub url: String,
+    /// Sync every this many seconds. None syncs after every push instead.
+    #[serde(default)]
+    pub interval_seconds: Option<u64>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub last_synced_at: Option<OffsetDateTime>,
+    // Why the last sync failed, None if it succeeded
+    #[serde(default)]
+    pub last_error: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+impl Mirror {
+    pub fn syncs_on_push(&self) -> bool {
+        self.interval_seconds.is_none()
+    }
+
+    /// Whether a scheduled mirror is due for a sync at `now`
+    pub fn is_due(&self, now: OffsetDateTime) -> bool {
+        let Some(interval) = self.interval_seconds else {
+            return false;
+        };
+        match self.last_synced_at {
+            Some(last) => now - last >= time::Duration::seconds(interval as i64),
+            None => true,
+        }
+    }
+}
+
+/// Body for adding a mirror
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct NewMirror {
+    pub url: String,
+    #[serde(default)]
+    pub interval_seconds: Option<u64>,
+}
@@ -0,0 +1,120 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::error::OxenError;
+
+pub const OXEN_URI_SCHEME: &str = "oxen://";
+
+/// Points at a path within a revision of a repository, ex: oxen://ox/CatDog@main:images/cat.jpg
+///
+/// The revision is optional and defaults to the main branch, the path is optional and
+/// defaults to the root of the repository.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct OxenUri {
+    pub namespace: String,
+    pub repo_name: String,
+    pub revision: Option<String>,
+    pub path: PathBuf,
+}
+
+impl OxenUri {
+    pub fn is_oxen_uri(s: impl AsRef<str>) -> bool {
+        s.as_ref().starts_with(OXEN_URI_SCHEME)
+    }
+
+    /// The namespace/repo_name used to look the repository up on a host
+    pub fn repo_name_with_namespace(&self) -> String {
+        format!("{}/{}", self.namespace, self.repo_name)
+    }
+}
+
+impl FromStr for OxenUri {
+    type Err = OxenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            OxenError::basic_str(format!(
+                "Invalid oxen uri '{s}', expected {OXEN_URI_SCHEME}namespace/repo@revision:path"
+            ))
+        };
+
+        let rest = s.strip_prefix(OXEN_URI_SCHEME).ok_or_else(invalid)?;
+        let (repo_part, path) = match rest.split_once(':') {
+            Some((repo_part, path)) => (repo_part, PathBuf::from(path.trim_start_matches('/'))),
+            None => (rest, PathBuf::new()),
+        };
+        let (name, revision) = match repo_part.split_once('@') {
+            Some((name, revision)) if !revision.is_empty() => (name, Some(revision.to_string())),
+            Some(_) => return Err(invalid()),
+            None => (repo_part, None),
+        };
+        let Some((namespace, repo_name)) = name.split_once('/') else {
+            return Err(invalid());
+        };
+        if namespace.is_empty() || repo_name.is_empty() || repo_name.contains('/') {
+            return Err(invalid());
+        }
+
+        Ok(OxenUri {
+            namespace: namespace.to_string(),
+            repo_name: repo_name.to_string(),
+            revision,
+            path,
+        })
+    }
+}
+
+impl fmt::Display for OxenUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{OXEN_URI_SCHEME}{}", self.repo_name_with_namespace())?;
+        if let Some(revision) = &self.revision {
+            write!(f, "@{revision}")?;
+        }
+        if self.path != PathBuf::new() {
+            write!(f, ":{}", self.path.to_string_lossy())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::str::FromStr;
+
+    use crate::model::OxenUri;
+
+    #[test]
+    fn test_parse_oxen_uri_with_revision_and_path() {
+        let uri = OxenUri::from_str("oxen://ox/CatDog@feature/cats:images/cat.jpg").unwrap();
+        assert_eq!(uri.namespace, "ox");
+        assert_eq!(uri.repo_name, "CatDog");
+        assert_eq!(uri.revision, Some("feature/cats".to_string()));
+        assert_eq!(uri.path, PathBuf::from("images/cat.jpg"));
+        assert_eq!(
+            uri.to_string(),
+            "oxen://ox/CatDog@feature/cats:images/cat.jpg"
+        );
+    }
+
+    #[test]
+    fn test_parse_oxen_uri_defaults() {
+        let uri = OxenUri::from_str("oxen://ox/CatDog").unwrap();
+        assert_eq!(uri.revision, None);
+        assert_eq!(uri.path, PathBuf::new());
+
+        let uri = OxenUri::from_str("oxen://ox/CatDog:annotations").unwrap();
+        assert_eq!(uri.revision, None);
+        assert_eq!(uri.path, PathBuf::from("annotations"));
+    }
+
+    #[test]
+    fn test_parse_oxen_uri_invalid() {
+        assert!(OxenUri::from_str("ox/CatDog:images").is_err());
+        assert!(OxenUri::from_str("oxen://CatDog:images").is_err());
+        assert!(OxenUri::from_str("oxen://ox/CatDog@:images").is_err());
+        assert!(OxenUri::from_str("oxen://ox/Cat/Dog:images").is_err());
+    }
+}
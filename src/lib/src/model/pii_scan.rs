@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::error::OxenError;
+
+/// What to do when the scan finds something
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PiiScanMode {
+    #[default]
+    Off,
+    /// Print the report and carry on
+    Warn,
+    /// Print the report and refuse to commit or push
+    Block,
+}
+
+impl fmt::Display for PiiScanMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PiiScanMode::Off => write!(f, "off"),
+            PiiScanMode::Warn => write!(f, "warn"),
+            PiiScanMode::Block => write!(f, "block"),
+        }
+    }
+}
+
+impl FromStr for PiiScanMode {
+    type Err = OxenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(PiiScanMode::Off),
+            "warn" => Ok(PiiScanMode::Warn),
+            "block" => Ok(PiiScanMode::Block),
+            _ => Err(OxenError::basic_str(format!(
+                "Unknown scan mode {s:?}, expected off, warn or block"
+            ))),
+        }
+    }
+}
+
+/// A named regex, matched against each line of text files and each string cell of data frames
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PiiRule {
+    pub name: String,
+    pub pattern: String,
+}
+
+impl PiiRule {
+    pub fn new(name: impl AsRef<str>, pattern: impl AsRef<str>) -> PiiRule {
+        PiiRule {
+            name: name.as_ref().to_string(),
+            pattern: pattern.as_ref().to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct PiiScanConfig {
+    #[serde(default)]
+    pub mode: PiiScanMode,
+    /// Rules checked on top of the built in ones
+    #[serde(default)]
+    pub rules: Vec<PiiRule>,
+    /// Names of built in rules to skip, ex: `email` for a dataset of public contacts
+    #[serde(default)]
+    pub disabled_rules: Vec<String>,
+    /// Glob patterns of paths that are never scanned
+    #[serde(default)]
+    pub ignore_paths: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PiiFinding {
+    pub path: PathBuf,
+    pub rule: String,
+    /// 1 based line of text files
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    /// Column and 0 based row of data frames
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub row: Option<usize>,
+    /// The match with most of it masked, so the report does not leak it again
+    pub excerpt: String,
+}
+
+impl fmt::Display for PiiFinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.path.to_string_lossy())?;
+        if let Some(line) = self.line {
+            write!(f, ":{line}")?;
+        }
+        if let (Some(column), Some(row)) = (&self.column, self.row) {
+            write!(f, " [{column} row {row}]")?;
+        }
+        write!(f, "\t{}\t{}", self.rule, self.excerpt)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct PiiScanReport {
+    pub num_files_scanned: usize,
+    pub findings: Vec<PiiFinding>,
+}
+
+impl PiiScanReport {
+    pub fn is_clean(&self) -> bool {
+        self.findings.is_empty()
+    }
+}
+
+impl fmt::Display for PiiScanReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for finding in self.findings.iter() {
+            writeln!(f, "{finding}")?;
+        }
+        write!(
+            f,
+            "Found {} possible secrets or personal data in {} scanned files",
+            self.findings.len(),
+            self.num_files_scanned
+        )
+    }
+}
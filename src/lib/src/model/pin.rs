@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use time::OffsetDateTime;
+
+use crate::util;
+
+/// A file captured in a pin
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PinEntry {
+    pub path: PathBuf,
+    pub hash: String,
+    pub num_bytes: u64,
+}
+
+/// Records exactly which version of a set of files was used, ex) to train a model,
+/// so that a serving system can later prove it is using the same data.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Pin {
+    pub name: String,
+    // Url of the remote repository if the repo had one when the pin was created
+    pub remote: Option<String>,
+    pub revision: String,
+    pub commit_id: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+    pub entries: Vec<PinEntry>,
+    // Hash over the commit and entries so edits to the pin file can be detected
+    pub hash: String,
+}
+
+impl Pin {
+    pub fn compute_hash(commit_id: &str, entries: &[PinEntry]) -> String {
+        let mut contents = String::from(commit_id);
+        for entry in entries {
+            contents.push_str(&format!(
+                "\n{}\t{}\t{}",
+                entry.path.to_string_lossy(),
+                entry.hash,
+                entry.num_bytes
+            ));
+        }
+        util::hasher::hash_str(contents)
+    }
+
+    pub fn has_valid_hash(&self) -> bool {
+        self.hash == Pin::compute_hash(&self.commit_id, &self.entries)
+    }
+}
+
+/// A pinned file that does not match what was found when verifying
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PinMismatch {
+    pub path: PathBuf,
+    pub expected_hash: String,
+    // None if the file is missing
+    pub actual_hash: Option<String>,
+}
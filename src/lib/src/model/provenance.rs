@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Where a file or directory in a commit was copied from
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Provenance {
+    // Host the source repository lives on, None if it is the same host as the destination
+    pub host: Option<String>,
+    pub namespace: String,
+    pub repo_name: String,
+    pub commit_id: String,
+    pub source_path: PathBuf,
+    // Path relative to the root of the destination repository
+    pub dest_path: PathBuf,
+}
+
+impl Provenance {
+    /// The git style trailer line recorded in the commit message
+    pub fn trailer(&self) -> String {
+        let host = self
+            .host
+            .as_ref()
+            .map(|host| format!("{host}/"))
+            .unwrap_or_default();
+        format!(
+            "Copied-From: oxen://{host}{}/{}@{}:{} -> {}",
+            self.namespace,
+            self.repo_name,
+            self.commit_id,
+            self.source_path.to_string_lossy(),
+            self.dest_path.to_string_lossy()
+        )
+    }
+}
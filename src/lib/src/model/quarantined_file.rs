@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use time::OffsetDateTime;
+
+/// A downloaded file whose contents did not match the hash in the tree, kept out of the
+/// versions dir and the working tree
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct QuarantinedFile {
+    pub path: PathBuf,
+    // Hash from the file node
+    pub hash: String,
+    // Hash of what we actually received
+    pub actual_hash: String,
+    pub num_bytes: u64,
+    // Where the received file was moved, relative to the quarantine dir
+    pub quarantine_path: PathBuf,
+    #[serde(with = "time::serde::rfc3339")]
+    pub quarantined_at: OffsetDateTime,
+}
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// A named version of the dataset, like `train-v3`, pointing at a commit. Unlike a branch
+/// it never moves, so the name always means the same data.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Release {
+    pub name: String,
+    pub commit_id: String,
+    pub description: Option<String>,
+    pub author: String,
+    pub email: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+/// Body used to create a release through the API. The revision can be a branch or
+/// commit id, the release keeps the commit it resolves to.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct NewRelease {
+    pub name: String,
+    pub revision: String,
+    pub description: Option<String>,
+    pub author: String,
+    pub email: String,
+}
@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// Records that a repository was frozen, making it read only until it is thawed
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RepoFreeze {
+    #[serde(with = "time::serde::rfc3339")]
+    pub frozen_at: OffsetDateTime,
+    // Whether the version files were recompressed with zstd at the max level
+    pub recompressed: bool,
+    // Size of the .oxen dir before and after freezing
+    pub num_bytes_before: u64,
+    pub num_bytes_after: u64,
+}
+
+/// Options for freezing a repository
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct FreezeOpts {
+    #[serde(default)]
+    pub recompress: bool,
+}
@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::OxenError;
+
+/// How checkout puts version files into the working directory
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckoutMode {
+    /// Copy the version file, always works
+    #[default]
+    Copy,
+    /// Clone the version file on filesystems with copy on write support (btrfs, xfs, apfs),
+    /// falling back to a copy elsewhere
+    Reflink,
+    /// Hard link to the version file, which is made read only so in place edits fail
+    /// instead of rewriting history. Falls back to a copy across filesystems.
+    Hardlink,
+}
+
+impl FromStr for CheckoutMode {
+    type Err = OxenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "copy" => Ok(CheckoutMode::Copy),
+            "reflink" => Ok(CheckoutMode::Reflink),
+            "hardlink" => Ok(CheckoutMode::Hardlink),
+            _ => Err(OxenError::basic_str(format!(
+                "Invalid checkout mode '{s}', must be one of: copy, reflink, hardlink"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for CheckoutMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckoutMode::Copy => write!(f, "copy"),
+            CheckoutMode::Reflink => write!(f, "reflink"),
+            CheckoutMode::Hardlink => write!(f, "hardlink"),
+        }
+    }
+}
@@ -1,14 +1,14 @@
-use crate::config::RepositoryConfig;
+use crate::config::{BranchConfig, RepositoryConfig};
 use crate::constants::SHALLOW_FLAG;
 use crate::constants::{self, DEFAULT_VNODE_SIZE, MIN_OXEN_VERSION};
 use crate::core::versions::MinOxenVersion;
-use crate::error;
 use crate::error::OxenError;
-use crate::model::{Remote, RemoteRepository};
+use crate::model::{CheckoutMode, Remote, RemoteBranch, RemoteRepository};
 use crate::util;
 use crate::view::RepositoryView;
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -19,6 +19,13 @@ pub struct LocalRepository {
     min_version: Option<String>, // write the version if it is past v0.18.4
     remotes: Vec<Remote>,        // List of possible remotes
     vnode_size: Option<u64>,
+    perceptual_hash: Option<bool>,
+    content_addressed_tree: Option<bool>,
+    checkout_mode: Option<CheckoutMode>,
+    encryption_key_id: Option<String>,
+    compression_level: Option<i32>,
+    #[serde(default)]
+    branches: BTreeMap<String, BranchConfig>, // Upstream each local branch tracks
 }
 
 impl LocalRepository {
@@ -33,6 +40,12 @@ impl LocalRepository {
             // New with a path should default to our current MIN_OXEN_VERSION
             min_version: Some(MIN_OXEN_VERSION.to_string()),
             vnode_size: None,
+            perceptual_hash: None,
+            content_addressed_tree: None,
+            checkout_mode: None,
+            encryption_key_id: None,
+            compression_level: None,
+            branches: BTreeMap::new(),
         })
     }
 
@@ -47,6 +60,12 @@ impl LocalRepository {
             remote_name: None,
             min_version: Some(min_version.as_ref().to_string()),
             vnode_size: None,
+            perceptual_hash: None,
+            content_addressed_tree: None,
+            checkout_mode: None,
+            encryption_key_id: None,
+            compression_level: None,
+            branches: BTreeMap::new(),
         })
     }
 
@@ -57,6 +76,12 @@ impl LocalRepository {
             remote_name: None,
             min_version: None,
             vnode_size: None,
+            perceptual_hash: None,
+            content_addressed_tree: None,
+            checkout_mode: None,
+            encryption_key_id: None,
+            compression_level: None,
+            branches: BTreeMap::new(),
         })
     }
 
@@ -67,6 +92,12 @@ impl LocalRepository {
             remote_name: Some(String::from(constants::DEFAULT_REMOTE_NAME)),
             min_version: None,
             vnode_size: None,
+            perceptual_hash: None,
+            content_addressed_tree: None,
+            checkout_mode: None,
+            encryption_key_id: None,
+            compression_level: None,
+            branches: BTreeMap::new(),
         })
     }
 
@@ -83,13 +114,19 @@ impl LocalRepository {
             remote_name: cfg.remote_name,
             min_version: cfg.min_version,
             vnode_size: Some(vnode_size),
+            perceptual_hash: cfg.perceptual_hash,
+            content_addressed_tree: cfg.content_addressed_tree,
+            checkout_mode: cfg.checkout_mode,
+            encryption_key_id: cfg.encryption_key_id,
+            compression_level: cfg.compression_level,
+            branches: cfg.branch,
         };
         Ok(repo)
     }
 
     pub fn from_current_dir() -> Result<LocalRepository, OxenError> {
-        let repo_dir = util::fs::get_repo_root_from_current_dir()
-            .ok_or(OxenError::basic_str(error::NO_REPO_FOUND))?;
+        let repo_dir =
+            util::fs::get_repo_root_from_current_dir().ok_or(OxenError::local_repo_not_found())?;
 
         LocalRepository::from_dir(&repo_dir)
     }
@@ -123,12 +160,64 @@ impl LocalRepository {
         self.vnode_size = Some(size);
     }
 
+    /// Whether to compute perceptual hashes for images as they are added, off by default
+    pub fn perceptual_hash(&self) -> bool {
+        self.perceptual_hash.unwrap_or(false)
+    }
+
+    pub fn set_perceptual_hash(&mut self, enabled: bool) {
+        self.perceptual_hash = Some(enabled);
+    }
+
+    /// Whether dir and vnode hashes only depend on the paths and contents below them, off by default.
+    /// Commits with the same tree then share their nodes, within a repo and across forks.
+    pub fn content_addressed_tree(&self) -> bool {
+        self.content_addressed_tree.unwrap_or(false)
+    }
+
+    pub fn set_content_addressed_tree(&mut self, enabled: bool) {
+        self.content_addressed_tree = Some(enabled);
+    }
+
+    /// How checkout puts version files into the working dir, copies by default
+    pub fn checkout_mode(&self) -> CheckoutMode {
+        self.checkout_mode.unwrap_or_default()
+    }
+
+    pub fn set_checkout_mode(&mut self, mode: CheckoutMode) {
+        self.checkout_mode = Some(mode);
+    }
+
+    /// Id of the key the version files are encrypted with, None if they are not encrypted
+    pub fn encryption_key_id(&self) -> Option<&str> {
+        self.encryption_key_id.as_deref()
+    }
+
+    pub fn set_encryption_key_id(&mut self, key_id: Option<String>) {
+        self.encryption_key_id = key_id;
+    }
+
+    /// zstd level new version files are compressed with, None if they are stored as is
+    pub fn compression_level(&self) -> Option<i32> {
+        self.compression_level
+    }
+
+    pub fn set_compression_level(&mut self, level: Option<i32>) {
+        self.compression_level = level;
+    }
+
     pub fn save(&self, path: &Path) -> Result<(), OxenError> {
         let cfg = RepositoryConfig {
             remote_name: self.remote_name.clone(),
             remotes: self.remotes.clone(),
             min_version: self.min_version.clone(),
             vnode_size: Some(self.vnode_size.unwrap_or(DEFAULT_VNODE_SIZE)),
+            perceptual_hash: self.perceptual_hash,
+            content_addressed_tree: self.content_addressed_tree,
+            checkout_mode: self.checkout_mode,
+            encryption_key_id: self.encryption_key_id.clone(),
+            compression_level: self.compression_level,
+            branch: self.branches.clone(),
         };
         let toml = toml::to_string(&cfg)?;
         util::fs::write_to_path(path, toml)?;
@@ -169,6 +258,59 @@ impl LocalRepository {
             }
         }
         self.remotes = new_remotes;
+        // Branches can no longer track the removed remote
+        self.branches.retain(|_, upstream| upstream.remote != name);
+    }
+
+    /// Rename a remote, keeping the current remote and the branches that track it pointed at it
+    pub fn rename_remote(&mut self, old_name: &str, new_name: &str) -> Result<Remote, OxenError> {
+        if self.has_remote(new_name) {
+            return Err(OxenError::basic_str(format!(
+                "Remote '{new_name}' already exists"
+            )));
+        }
+        let Some(remote) = self.remotes.iter_mut().find(|r| r.name == old_name) else {
+            return Err(OxenError::remote_not_set(old_name));
+        };
+        remote.name = String::from(new_name);
+        let remote = remote.clone();
+
+        if self.remote_name.as_deref() == Some(old_name) {
+            self.remote_name = Some(String::from(new_name));
+        }
+        for upstream in self.branches.values_mut() {
+            if upstream.remote == old_name {
+                upstream.remote = String::from(new_name);
+            }
+        }
+        Ok(remote)
+    }
+
+    /// The remote branch a local branch pushes to and pulls from, if it tracks one
+    pub fn upstream(&self, branch: &str) -> Option<RemoteBranch> {
+        self.branches.get(branch).map(|upstream| RemoteBranch {
+            remote: upstream.remote.clone(),
+            branch: upstream.merge.clone(),
+        })
+    }
+
+    /// Upstreams keyed by local branch name
+    pub fn upstreams(&self) -> &BTreeMap<String, BranchConfig> {
+        &self.branches
+    }
+
+    pub fn set_upstream(&mut self, branch: &str, remote: &str, merge: &str) {
+        self.branches.insert(
+            String::from(branch),
+            BranchConfig {
+                remote: String::from(remote),
+                merge: String::from(merge),
+            },
+        );
+    }
+
+    pub fn unset_upstream(&mut self, branch: &str) {
+        self.branches.remove(branch);
     }
 
     pub fn has_remote(&self, name: &str) -> bool {
@@ -199,6 +341,11 @@ impl LocalRepository {
         }
     }
 
+    /// Switch the current remote, the remote must already be set
+    pub fn set_remote_name(&mut self, name: &str) {
+        self.remote_name = Some(String::from(name));
+    }
+
     pub fn write_is_shallow(&self, shallow: bool) -> Result<(), OxenError> {
         let shallow_flag_path = util::fs::oxen_hidden_dir(&self.path).join(SHALLOW_FLAG);
         log::debug!("Write is shallow [{shallow}] to path: {shallow_flag_path:?}");
@@ -219,7 +366,7 @@ impl LocalRepository {
 #[cfg(test)]
 mod tests {
     use crate::error::OxenError;
-    use crate::model::RepoNew;
+    use crate::model::{LocalRepository, RepoNew};
     use crate::test;
 
     #[test]
@@ -264,4 +411,37 @@ mod tests {
             Ok(())
         })
     }
+
+    #[test]
+    fn test_rename_remote_keeps_upstreams() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|mut local_repo| {
+            local_repo.set_remote("origin", "http://0.0.0.0:3000/ox/OxenData");
+            local_repo.set_remote("backup", "http://0.0.0.0:4000/ox/OxenData");
+            local_repo.set_upstream("main", "origin", "main");
+            local_repo.set_upstream("labels", "backup", "relabel");
+
+            // Cannot rename onto an existing remote
+            assert!(local_repo.rename_remote("origin", "backup").is_err());
+
+            local_repo.rename_remote("backup", "archive")?;
+            assert!(local_repo.get_remote("backup").is_none());
+            let upstream = local_repo.upstream("labels").unwrap();
+            assert_eq!(upstream.remote, "archive");
+            assert_eq!(upstream.branch, "relabel");
+            // set_remote made "backup" the current remote, so it follows the rename
+            assert_eq!(local_repo.remote().unwrap().name, "archive");
+
+            // Tracking survives a round trip through the config
+            local_repo.save_default()?;
+            let reloaded = LocalRepository::from_dir(&local_repo.path)?;
+            assert_eq!(reloaded.upstream("main").unwrap().remote, "origin");
+
+            // Deleting a remote stops the branches tracking it
+            local_repo.delete_remote("archive");
+            assert!(local_repo.upstream("labels").is_none());
+            assert!(local_repo.upstream("main").is_some());
+
+            Ok(())
+        })
+    }
 }
@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// What a repository stores across all of its branches. Files are counted once by
+/// content hash, so a file shared by many branches or commits only counts once.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct StorageStats {
+    pub num_bytes: u64,
+    pub num_files: u64,
+    pub num_commits: u64,
+    pub data_types: BTreeMap<String, DataTypeStorage>,
+    pub branches: Vec<BranchStorage>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct DataTypeStorage {
+    pub num_files: u64,
+    pub num_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BranchStorage {
+    pub name: String,
+    pub commit_id: String,
+    // Size of the head of the branch
+    pub num_bytes: u64,
+    // Bytes in the head of the branch that no other branch head has
+    pub unique_num_bytes: u64,
+    pub num_commits: u64,
+}
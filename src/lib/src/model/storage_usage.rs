@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// How many bytes a repository uses. Each branch uses the size of the root dir of its
+/// head, the repository uses as much as its largest branch since branches share most
+/// of their files.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct StorageUsage {
+    pub num_bytes: u64,
+    pub branches: BTreeMap<String, u64>,
+}
+
+impl StorageUsage {
+    pub fn from_branches(branches: BTreeMap<String, u64>) -> StorageUsage {
+        StorageUsage {
+            num_bytes: branches.values().copied().max().unwrap_or(0),
+            branches,
+        }
+    }
+}
@@ -1,7 +1,37 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::OxenError;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct User {
     pub email: String,
     pub name: String,
 }
+
+impl fmt::Display for User {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} <{}>", self.name, self.email)
+    }
+}
+
+/// Parses `Name <email>`, the way authors are written in commit trailers
+impl FromStr for User {
+    type Err = OxenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let invalid = || OxenError::basic_str(format!("Expected `Name <email>`, got {s:?}"));
+        let (name, rest) = s.split_once('<').ok_or_else(invalid)?;
+        let email = rest.strip_suffix('>').ok_or_else(invalid)?.trim();
+        let name = name.trim();
+        if name.is_empty() || email.is_empty() {
+            return Err(invalid());
+        }
+        Ok(User {
+            name: name.to_string(),
+            email: email.to_string(),
+        })
+    }
+}
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use time::OffsetDateTime;
+
+use crate::error::OxenError;
+use crate::model::Commit;
+
+/// What happened on the server that a webhook can be notified about
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEvent {
+    Push,
+    BranchCreate,
+    BranchDelete,
+    WorkspaceCommit,
+}
+
+impl WebhookEvent {
+    pub fn as_str(&self) -> &str {
+        match self {
+            WebhookEvent::Push => "push",
+            WebhookEvent::BranchCreate => "branch_create",
+            WebhookEvent::BranchDelete => "branch_delete",
+            WebhookEvent::WorkspaceCommit => "workspace_commit",
+        }
+    }
+}
+
+impl fmt::Display for WebhookEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for WebhookEvent {
+    type Err = OxenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "push" => Ok(WebhookEvent::Push),
+            "branch_create" => Ok(WebhookEvent::BranchCreate),
+            "branch_delete" => Ok(WebhookEvent::BranchDelete),
+            "workspace_commit" => Ok(WebhookEvent::WorkspaceCommit),
+            _ => Err(OxenError::basic_str(format!(
+                "Unknown webhook event {s:?}, expected push, branch_create, branch_delete or workspace_commit"
+            ))),
+        }
+    }
+}
+
+/// A url the server POSTs to when something happens in a repository
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Webhook {
+    pub id: String,
+    pub url: String,
+    // Signs the payloads, never sent back to clients
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    // Empty means every event
+    #[serde(default)]
+    pub events: Vec<WebhookEvent>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub created_at: OffsetDateTime,
+}
+
+impl Webhook {
+    pub fn is_subscribed(&self, event: WebhookEvent) -> bool {
+        self.events.is_empty() || self.events.contains(&event)
+    }
+
+    /// Without the secret, for returning to clients
+    pub fn redacted(mut self) -> Webhook {
+        self.secret = None;
+        self
+    }
+}
+
+/// Body for creating a webhook
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct NewWebhook {
+    pub url: String,
+    #[serde(default)]
+    pub secret: Option<String>,
+    #[serde(default)]
+    pub events: Vec<WebhookEvent>,
+}
+
+/// The json body POSTed to a webhook
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WebhookPayload {
+    /// Unique per delivery, also sent in the X-Oxen-Delivery header
+    pub id: String,
+    pub event: WebhookEvent,
+    pub namespace: String,
+    pub repo_name: String,
+    pub branch: Option<String>,
+    pub commit: Option<Commit>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub timestamp: OffsetDateTime,
+}
@@ -41,3 +41,24 @@ impl Workspace {
         Self::workspace_dir(&self.base_repo, &workspace_id_hash)
     }
 }
+
+/// A file that could not be uploaded to a workspace, and why
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FailedUpload {
+    pub path: PathBuf,
+    pub reason: String,
+}
+
+/// The outcome of uploading many files to a workspace, each file succeeds or fails on its own
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct WorkspaceUploadResult {
+    // Paths of the added files within the workspace
+    pub added: Vec<PathBuf>,
+    pub failed: Vec<FailedUpload>,
+}
+
+impl WorkspaceUploadResult {
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
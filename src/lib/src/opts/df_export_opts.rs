@@ -0,0 +1,24 @@
+use crate::core::df::sql_export::{ExportTarget, IfExists, DEFAULT_EXPORT_BATCH_SIZE};
+
+#[derive(Clone, Debug)]
+pub struct DFExportOpts {
+    /// Database to write into
+    pub target: ExportTarget,
+    /// Table to write into. Defaults to the file name without its extension.
+    pub table: Option<String>,
+    /// What to do if the table already exists
+    pub if_exists: IfExists,
+    /// Rows inserted per transaction
+    pub batch_size: usize,
+}
+
+impl DFExportOpts {
+    pub fn new(target: ExportTarget) -> DFExportOpts {
+        DFExportOpts {
+            target,
+            table: None,
+            if_exists: IfExists::default(),
+            batch_size: DEFAULT_EXPORT_BATCH_SIZE,
+        }
+    }
+}
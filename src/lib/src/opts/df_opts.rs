@@ -27,6 +27,7 @@ pub struct DFOpts {
     pub add_col: Option<String>,
     pub add_row: Option<String>,
     pub at: Option<usize>,
+    pub bbox: Option<String>,
     pub columns: Option<String>,
     pub delete_row: Option<String>,
     pub delimiter: Option<String>,
@@ -45,6 +46,7 @@ pub struct DFOpts {
     pub slice: Option<String>,
     pub sort_by: Option<String>,
     pub sql: Option<String>,
+    pub table: Option<String>,
     pub text2sql: Option<String>,
     pub tail: Option<usize>,
     pub take: Option<String>,
@@ -69,6 +71,7 @@ impl DFOpts {
             add_col: None,
             add_row: None,
             at: None,
+            bbox: None,
             item: None,
             columns: None,
             delete_row: None,
@@ -87,6 +90,7 @@ impl DFOpts {
             slice: None,
             sort_by: None,
             sql: None,
+            table: None,
             text2sql: None,
             tail: None,
             take: None,
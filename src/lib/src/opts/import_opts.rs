@@ -0,0 +1,11 @@
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, Default)]
+pub struct ImportOpts {
+    /// Directory in the repository to import into. Defaults to the name of the dataset.
+    pub path: Option<PathBuf>,
+    /// Commit message. Defaults to "Import <source>".
+    pub message: Option<String>,
+    /// Keep downloaded archives as is instead of extracting them
+    pub no_extract: bool,
+}
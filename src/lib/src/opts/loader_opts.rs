@@ -0,0 +1,26 @@
+#[derive(Clone, Debug)]
+pub struct LoaderOpts {
+    /// Number of rows in each batch
+    pub batch_size: usize,
+    /// Only load these columns, all columns if None
+    pub columns: Option<Vec<String>>,
+    /// Number of rows to shuffle between, no shuffling if None
+    pub shuffle_buffer: Option<usize>,
+    /// Seed for the shuffle so epochs can be reproduced
+    pub seed: Option<u64>,
+    /// Skip the last batch if it has fewer than batch_size rows
+    pub drop_last: bool,
+}
+
+// Add default values
+impl Default for LoaderOpts {
+    fn default() -> Self {
+        LoaderOpts {
+            batch_size: 32,
+            columns: None,
+            shuffle_buffer: None,
+            seed: None,
+            drop_last: false,
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use std::path::PathBuf;
+use time::OffsetDateTime;
+
+use crate::model::Commit;
+
+/// Filters for the history listed by `oxen log`
+#[derive(Clone, Debug, Default)]
+pub struct LogOpts {
+    /// Branch or commit to start from, defaults to HEAD
+    pub revision: Option<String>,
+    /// Case insensitive substring of the name or email of the author or a co-author
+    pub author: Option<String>,
+    pub since: Option<OffsetDateTime>,
+    pub until: Option<OffsetDateTime>,
+    /// Only commits that changed this file or directory
+    pub path: Option<PathBuf>,
+    /// Case insensitive substring of the commit message
+    pub grep: Option<String>,
+    pub max_count: Option<usize>,
+}
+
+impl LogOpts {
+    /// Whether the commit passes the filters that only need the commit itself
+    pub fn matches(&self, commit: &Commit) -> bool {
+        if let Some(author) = &self.author {
+            let author = author.to_lowercase();
+            // Co-authors count as authors
+            if !commit.authors().iter().any(|user| {
+                user.name.to_lowercase().contains(&author)
+                    || user.email.to_lowercase().contains(&author)
+            }) {
+                return false;
+            }
+        }
+        if let Some(grep) = &self.grep {
+            if !commit.message.to_lowercase().contains(&grep.to_lowercase()) {
+                return false;
+            }
+        }
+        if self.since.is_some_and(|since| commit.timestamp < since) {
+            return false;
+        }
+        if self.until.is_some_and(|until| commit.timestamp > until) {
+            return false;
+        }
+        true
+    }
+}
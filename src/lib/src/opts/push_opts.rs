@@ -0,0 +1,47 @@
+use crate::api::client::retry::env_or;
+use crate::constants;
+
+/// Most chunk uploads in flight at once, set with OXEN_PUSH_WORKERS
+pub const PUSH_WORKERS_ENV: &str = "OXEN_PUSH_WORKERS";
+/// Smallest chunk large files are split into, in bytes, set with OXEN_PUSH_MIN_CHUNK_SIZE
+pub const PUSH_MIN_CHUNK_SIZE_ENV: &str = "OXEN_PUSH_MIN_CHUNK_SIZE";
+/// Largest chunk large files are split into, in bytes, set with OXEN_PUSH_MAX_CHUNK_SIZE
+pub const PUSH_MAX_CHUNK_SIZE_ENV: &str = "OXEN_PUSH_MAX_CHUNK_SIZE";
+
+/// How push uploads the chunks of large files.
+///
+/// The chunk size starts at `AVG_CHUNK_SIZE` and adapts to the throughput of the
+/// connection between `min_chunk_size` and `max_chunk_size`, so at most
+/// `num_workers * max_chunk_size` bytes are held in memory at once.
+#[derive(Clone, Debug)]
+pub struct PushOpts {
+    pub num_workers: usize,
+    pub min_chunk_size: u64,
+    pub max_chunk_size: u64,
+}
+
+impl Default for PushOpts {
+    fn default() -> PushOpts {
+        PushOpts {
+            // High latency links need more requests in flight to stay saturated
+            num_workers: constants::DEFAULT_NUM_WORKERS * 2,
+            min_chunk_size: 1024 * 1024,
+            max_chunk_size: 1024 * 1024 * 16,
+        }
+    }
+}
+
+impl PushOpts {
+    /// The defaults, overridden by any of the OXEN_PUSH_* environment variables that are set
+    pub fn from_env() -> PushOpts {
+        let defaults = PushOpts::default();
+        let num_workers = env_or(PUSH_WORKERS_ENV, defaults.num_workers as u64).max(1) as usize;
+        let min_chunk_size = env_or(PUSH_MIN_CHUNK_SIZE_ENV, defaults.min_chunk_size).max(1);
+        let max_chunk_size = env_or(PUSH_MAX_CHUNK_SIZE_ENV, defaults.max_chunk_size);
+        PushOpts {
+            num_workers,
+            min_chunk_size,
+            max_chunk_size: max_chunk_size.max(min_chunk_size),
+        }
+    }
+}
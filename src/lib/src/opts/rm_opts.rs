@@ -5,6 +5,8 @@ pub struct RmOpts {
     pub path: PathBuf,
     pub staged: bool,
     pub recursive: bool,
+    // Stage the removal but keep the file in the working directory
+    pub cached: bool,
     // TODO: add `force` flag
 }
 
@@ -15,6 +17,7 @@ impl RmOpts {
             path: path.as_ref().to_owned(),
             staged: false,
             recursive: false,
+            cached: false,
         }
     }
 
@@ -24,6 +27,7 @@ impl RmOpts {
             path: path.as_ref().to_owned(),
             staged: true,
             recursive: false,
+            cached: false,
         }
     }
 
@@ -33,6 +37,17 @@ impl RmOpts {
             path: path.as_ref().to_owned(),
             staged: false,
             recursive: true,
+            cached: false,
+        }
+    }
+
+    /// Sets `cached = true` to stage the removal and keep the file on disk
+    pub fn from_cached_path<P: AsRef<Path>>(path: P) -> RmOpts {
+        RmOpts {
+            path: path.as_ref().to_owned(),
+            staged: false,
+            recursive: false,
+            cached: true,
         }
     }
 
@@ -42,6 +57,7 @@ impl RmOpts {
             path: path.as_ref().to_owned(),
             staged: opts.staged,
             recursive: opts.recursive,
+            cached: opts.cached,
         }
     }
 }
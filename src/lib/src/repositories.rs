@@ -25,27 +25,52 @@ use std::path::Path;
 use std::str::FromStr;
 
 pub mod add;
+pub mod branch_protection;
 pub mod branches;
+pub mod bundle;
 pub mod checkout;
+pub mod clean;
 pub mod clone;
+pub mod comments;
 pub mod commits;
+pub mod copy;
 pub mod data_frames;
+pub mod dataset_cards;
+pub mod dedup;
 pub mod diffs;
 pub mod download;
+pub mod encryption;
 pub mod entries;
+pub mod export;
 pub mod fetch;
+pub mod freeze;
+pub mod git;
+pub mod import;
 pub mod init;
 pub mod load;
 pub mod merge;
+pub mod merge_queue;
+pub mod merge_requests;
 pub mod metadata;
+pub mod mirrors;
+#[cfg(feature = "mount")]
+pub mod mount;
+pub mod pii_scan;
+pub mod pins;
+pub mod post_process;
+pub mod provenance;
 pub mod pull;
 pub mod push;
+pub mod quotas;
+pub mod releases;
 pub mod restore;
 pub mod revisions;
 pub mod rm;
 pub mod save;
 pub mod status;
+pub mod storage_stats;
 pub mod tree;
+pub mod webhooks;
 pub mod workspaces;
 
 pub use add::add;
@@ -281,6 +306,7 @@ pub fn transfer_namespace(
 
     util::fs::create_dir_all(&new_repo_dir)?;
     util::fs::rename(&repo_dir, &new_repo_dir)?;
+    core::v0_19_0::index::tree_cache::invalidate(&repo_dir);
 
     // Update path in config
     let config_path = util::fs::config_filepath(&new_repo_dir);
@@ -374,6 +400,7 @@ pub fn delete(repo: LocalRepository) -> Result<LocalRepository, OxenError> {
 
     log::debug!("Deleting repo directory: {:?}", repo);
     util::fs::remove_dir_all(&repo.path)?;
+    core::v0_19_0::index::tree_cache::invalidate(&repo.path);
     Ok(repo)
 }
 
@@ -434,6 +461,7 @@ mod tests {
                 email: String::from("ox@oxen.ai"),
                 timestamp,
                 root_hash: None,
+                co_authors: vec![],
             };
             let repo_new = RepoNew::from_root_commit(namespace, name, root_commit);
             let _repo = repositories::create(sync_dir, repo_new)?;
@@ -596,6 +624,7 @@ mod tests {
                 email: String::from("ox@oxen.ai"),
                 timestamp,
                 root_hash: None,
+                co_authors: vec![],
             };
             let repo_new = RepoNew::from_root_commit(old_namespace, name, root_commit);
             let _repo = repositories::create(sync_dir, repo_new)?;
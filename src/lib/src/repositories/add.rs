@@ -4,10 +4,15 @@
 //!
 
 use crate::core;
+use crate::core::df::filter;
+use crate::core::oxenignore;
 use crate::core::versions::MinOxenVersion;
 use crate::error::OxenError;
 use crate::model::LocalRepository;
-use std::path::Path;
+use crate::{repositories, util};
+use glob::{glob, Pattern};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
 /// # Stage files into repository
 ///
@@ -45,19 +50,203 @@ pub fn add_with_version(
     path: impl AsRef<Path>,
     version: MinOxenVersion,
 ) -> Result<(), OxenError> {
+    repositories::freeze::ensure_not_frozen(repo)?;
+    ensure_not_in_nested_repo(repo, path.as_ref())?;
     match version {
         MinOxenVersion::V0_10_0 => core::v0_10_0::add::add(repo, path),
         MinOxenVersion::V0_19_0 => core::v0_19_0::add::add(repo, path),
     }
 }
 
+/// # Stage many files or directories in one pass
+///
+/// The paths are added as is, run them through `expand_paths` first to match globs.
+/// Errors if a path is inside of a nested repository, and skips nested repositories
+/// found in directories unless they are already tracked.
+pub fn add_all(
+    repo: &LocalRepository,
+    paths: impl IntoIterator<Item = impl AsRef<Path>>,
+) -> Result<(), OxenError> {
+    p_add_all(repo, paths, false)
+}
+
+/// # Stage paths including any nested repositories in them
+///
+/// Vendors the files of nested repositories into this one. Their .oxen dirs are never added.
+pub fn add_all_allow_nested(
+    repo: &LocalRepository,
+    paths: impl IntoIterator<Item = impl AsRef<Path>>,
+) -> Result<(), OxenError> {
+    p_add_all(repo, paths, true)
+}
+
+fn p_add_all(
+    repo: &LocalRepository,
+    paths: impl IntoIterator<Item = impl AsRef<Path>>,
+    allow_nested: bool,
+) -> Result<(), OxenError> {
+    repositories::freeze::ensure_not_frozen(repo)?;
+    let paths: Vec<PathBuf> = paths
+        .into_iter()
+        .map(|p| p.as_ref().to_path_buf())
+        .collect();
+    if !allow_nested {
+        for path in &paths {
+            ensure_not_in_nested_repo(repo, path)?;
+        }
+    }
+    match repo.min_version() {
+        MinOxenVersion::V0_10_0 => {
+            for path in paths {
+                core::v0_10_0::add::add(repo, path)?;
+            }
+            Ok(())
+        }
+        MinOxenVersion::V0_19_0 => {
+            let paths: HashSet<PathBuf> = paths.into_iter().collect();
+            core::v0_19_0::add::add_all(repo, &paths, allow_nested)
+        }
+    }
+}
+
+/// # Stage only some rows of a tabular file
+///
+/// Stages the rows of the working file that match the filter, such as `split == train`,
+/// on top of the committed version. Changes to the rows that don't match stay unstaged.
+pub fn add_rows(
+    repo: &LocalRepository,
+    path: impl AsRef<Path>,
+    filter: impl AsRef<str>,
+) -> Result<(), OxenError> {
+    repositories::freeze::ensure_not_frozen(repo)?;
+    let path = path.as_ref();
+    ensure_not_in_nested_repo(repo, path)?;
+    let Some(filter) = filter::parse(Some(filter.as_ref().to_string()))? else {
+        return Err(OxenError::basic_str(format!(
+            "Invalid row filter: {:?}",
+            filter.as_ref()
+        )));
+    };
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        repo.path.join(path)
+    };
+    match repo.min_version() {
+        MinOxenVersion::V0_10_0 => Err(OxenError::basic_str(
+            "Staging rows is not supported for this repository version, run `oxen migrate` first",
+        )),
+        MinOxenVersion::V0_19_0 => {
+            core::v0_19_0::add::add_rows(repo, &path, &filter)?;
+            Ok(())
+        }
+    }
+}
+
+/// # Stage changes to tracked files only
+///
+/// Like `git add -u`, stages the modified and removed files that match the paths, or all
+/// of them if there are no paths, and leaves untracked files alone. Paths can be globs and
+/// are relative to the root of the repository unless they are absolute.
+pub fn add_update(
+    repo: &LocalRepository,
+    paths: impl IntoIterator<Item = impl AsRef<Path>>,
+) -> Result<HashSet<PathBuf>, OxenError> {
+    let mut patterns = vec![];
+    for path in paths {
+        let path = path.as_ref();
+        let relative_path = if path.is_absolute() {
+            util::fs::path_relative_to_dir(path, &repo.path)?
+        } else {
+            path.to_path_buf()
+        };
+        patterns.push(relative_path);
+    }
+    let globs = patterns
+        .iter()
+        .filter(|p| util::fs::is_glob_path(p))
+        .map(|p| Pattern::new(&p.to_string_lossy()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let status = repositories::status(repo)?;
+    let changed: HashSet<PathBuf> = status
+        .modified_files
+        .into_iter()
+        .chain(status.removed_files)
+        .filter(|path| {
+            patterns.is_empty()
+                || globs.iter().any(|glob| glob.matches_path(path))
+                || patterns
+                    .iter()
+                    .any(|p| !util::fs::is_glob_path(p) && path.starts_with(p))
+        })
+        .collect();
+    log::debug!("add_update staging {} changed files", changed.len());
+
+    if !changed.is_empty() {
+        add_all(repo, changed.iter().map(|path| repo.path.join(path)))?;
+    }
+    Ok(changed)
+}
+
+fn ensure_not_in_nested_repo(repo: &LocalRepository, path: &Path) -> Result<(), OxenError> {
+    match util::fs::find_nested_repo(&repo.path, path) {
+        Some(nested_repo) => {
+            let relative_path = util::fs::path_relative_to_dir(&nested_repo, &repo.path)?;
+            Err(OxenError::nested_repo(relative_path))
+        }
+        None => Ok(()),
+    }
+}
+
+/// Expand any globs in the paths to add.
+///
+/// Globs match files in the working dir, skipping the .oxen dir and anything in
+/// .oxenignore, as well as files in the head commit so that removed files get staged.
+/// Paths without a glob are returned as is.
+pub fn expand_paths(
+    repo: &LocalRepository,
+    paths: impl IntoIterator<Item = impl AsRef<Path>>,
+) -> Result<HashSet<PathBuf>, OxenError> {
+    let ignore = oxenignore::create(repo);
+    let head_commit = repositories::commits::head_commit_maybe(repo)?;
+
+    let mut expanded = HashSet::new();
+    for path in paths {
+        let path = path.as_ref();
+        let path_str = path.to_string_lossy();
+        if !util::fs::is_glob_path(path) {
+            expanded.insert(path.to_path_buf());
+            continue;
+        }
+
+        log::debug!("glob path: {}", path_str);
+        for entry in glob(&path_str)? {
+            let entry = entry?;
+            if !ignore.is_ignored(&entry, entry.is_dir()) {
+                expanded.insert(entry);
+            }
+        }
+
+        if let Some(commit) = &head_commit {
+            let pattern_entries = repositories::commits::search_entries(repo, commit, &path_str)?;
+            log::debug!("pattern entries: {:?}", pattern_entries);
+            expanded.extend(pattern_entries);
+        }
+    }
+    Ok(expanded)
+}
+
 #[cfg(test)]
 mod tests {
 
     use std::path::Path;
     use std::path::PathBuf;
 
+    use crate::command;
+    use crate::core::df::tabular;
     use crate::error::OxenError;
+    use crate::opts::{DFOpts, RestoreOpts};
     use crate::repositories;
     use crate::test;
     use crate::util;
@@ -480,4 +669,223 @@ mod tests {
         })
         .await
     }
+
+    #[test]
+    fn test_expand_glob_skips_oxenignore_and_adds_in_one_pass() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let images_dir = repo.path.join("images");
+            for dir in ["cats", "dogs", "tmp"] {
+                util::fs::create_dir_all(images_dir.join(dir))?;
+                util::fs::write_to_path(images_dir.join(dir).join("1.png"), dir)?;
+                util::fs::write_to_path(images_dir.join(dir).join("notes.txt"), dir)?;
+            }
+            util::fs::write_to_path(repo.path.join(".oxenignore"), "images/tmp/\n")?;
+
+            let pattern = images_dir.join("**").join("*.png");
+            let paths = repositories::add::expand_paths(&repo, [&pattern])?;
+            assert_eq!(paths.len(), 2);
+            assert!(paths.contains(&images_dir.join("cats").join("1.png")));
+            assert!(paths.contains(&images_dir.join("dogs").join("1.png")));
+
+            repositories::add::add_all(&repo, &paths)?;
+            let status = repositories::status(&repo)?;
+            assert_eq!(status.staged_files.len(), 2);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_add_and_status_respect_nested_oxenignore() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let logs_dir = repo.path.join("logs");
+            util::fs::create_dir_all(logs_dir.join("old"))?;
+            util::fs::write_to_path(repo.path.join("debug.log"), "root log")?;
+            util::fs::write_to_path(logs_dir.join("train.log"), "keep me")?;
+            util::fs::write_to_path(logs_dir.join("old").join("train.log"), "old")?;
+            util::fs::write_to_path(repo.path.join(".oxenignore"), "*.log\n")?;
+            util::fs::write_to_path(logs_dir.join(".oxenignore"), "!*.log\nold/\n")?;
+
+            let status = repositories::status(&repo)?;
+            assert!(!status.untracked_files.contains(&PathBuf::from("debug.log")));
+
+            repositories::add(&repo, &repo.path)?;
+            let status = repositories::status(&repo)?;
+            assert!(status
+                .staged_files
+                .contains_key(&PathBuf::from("logs").join("train.log")));
+            assert!(!status
+                .staged_files
+                .contains_key(&PathBuf::from("debug.log")));
+            assert!(!status
+                .staged_files
+                .contains_key(&PathBuf::from("logs").join("old").join("train.log")));
+
+            // Explicitly adding an ignored file is a no-op
+            repositories::add(&repo, repo.path.join("debug.log"))?;
+            let status = repositories::status(&repo)?;
+            assert!(!status
+                .staged_files
+                .contains_key(&PathBuf::from("debug.log")));
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_add_update_only_stages_tracked_changes() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let labels_path = repo.path.join("labels.txt");
+            util::fs::write_to_path(&labels_path, "cat\ndog\nbird")?;
+            util::fs::remove_file(repo.path.join("README.md"))?;
+            util::fs::write_to_path(repo.path.join("new.txt"), "untracked")?;
+
+            let staged = repositories::add::add_update(&repo, Vec::<PathBuf>::new())?;
+            assert_eq!(staged.len(), 2);
+
+            let status = repositories::status(&repo)?;
+            assert!(status
+                .staged_files
+                .contains_key(&PathBuf::from("labels.txt")));
+            assert!(status
+                .staged_files
+                .contains_key(&PathBuf::from("README.md")));
+            assert!(!status.staged_files.contains_key(&PathBuf::from("new.txt")));
+            assert!(status.untracked_files.contains(&PathBuf::from("new.txt")));
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_add_update_with_glob() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            util::fs::write_to_path(repo.path.join("labels.txt"), "cat\ndog\nbird")?;
+            util::fs::write_to_path(repo.path.join("README.md"), "# Changed")?;
+
+            let staged = repositories::add::add_update(&repo, ["*.md"])?;
+            assert_eq!(staged, [PathBuf::from("README.md")].into_iter().collect());
+
+            let status = repositories::status(&repo)?;
+            assert!(status
+                .staged_files
+                .contains_key(&PathBuf::from("README.md")));
+            assert!(status.modified_files.contains(&PathBuf::from("labels.txt")));
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_add_rows_stages_matching_rows() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let path = repo.path.join("data.csv");
+            util::fs::write_to_path(&path, "file,split\na.png,train\nb.png,test\n")?;
+            repositories::add(&repo, &path)?;
+            repositories::commit(&repo, "Adding data")?;
+
+            util::fs::write_to_path(
+                &path,
+                "file,split\na.png,train\nb.png,test\nc.png,train\nd.png,test\n",
+            )?;
+            repositories::add::add_rows(&repo, &path, "split == train")?;
+            let commit = repositories::commit(&repo, "Adding train rows")?;
+
+            let file_node = repositories::entries::get_file(&repo, &commit, "data.csv")?.unwrap();
+            let version_path = util::fs::version_path_from_hash(&repo, file_node.hash.to_string());
+            let df = tabular::read_df_with_extension(version_path, "csv", &DFOpts::empty())?;
+            assert_eq!(df.height(), 3);
+
+            // The test row is still a change in the working file
+            let status = repositories::status(&repo)?;
+            assert!(status.modified_files.contains(&PathBuf::from("data.csv")));
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_add_rows_on_compressed_repo() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|mut repo| {
+            command::config::set_compression_level(&mut repo, Some(3))?;
+            let path = repo.path.join("data.csv");
+            util::fs::write_to_path(&path, "file,split\na.png,train\nb.png,test\n")?;
+            repositories::add(&repo, &path)?;
+            repositories::commit(&repo, "Adding data")?;
+
+            // The committed rows are read back out of the compressed version
+            util::fs::write_to_path(&path, "file,split\na.png,train\nb.png,test\nc.png,train\n")?;
+            repositories::add::add_rows(&repo, &path, "split == train")?;
+            let commit = repositories::commit(&repo, "Adding train rows")?;
+
+            let version_path =
+                repositories::revisions::get_version_file(&repo, &commit.id, "data.csv")?;
+            let df = tabular::read_df_with_extension(version_path.path(), "csv", &DFOpts::empty())?;
+            assert_eq!(df.height(), 3);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_add_skips_nested_repo_unless_allowed() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let nested_dir = repo.path.join("vendor").join("other");
+            repositories::init::init_nested(&nested_dir, repo.min_version())?;
+            util::fs::write_to_path(nested_dir.join("data.txt"), "nested")?;
+            util::fs::write_to_path(repo.path.join("hello.txt"), "hello")?;
+            let nested_file = PathBuf::from("vendor").join("other").join("data.txt");
+
+            let status = repositories::status(&repo)?;
+            assert!(!status.untracked_files.contains(&nested_file));
+            assert!(!status
+                .untracked_dirs
+                .iter()
+                .any(|(dir, _)| dir.starts_with(Path::new("vendor").join("other"))));
+
+            repositories::add(&repo, &repo.path)?;
+            let status = repositories::status(&repo)?;
+            assert!(status
+                .staged_files
+                .contains_key(&PathBuf::from("hello.txt")));
+            assert!(!status.staged_files.contains_key(&nested_file));
+
+            // Explicitly adding inside of the nested repo needs to be allowed
+            assert!(repositories::add(&repo, repo.path.join(&nested_file)).is_err());
+
+            repositories::add::add_all_allow_nested(&repo, [&nested_dir])?;
+            let status = repositories::status(&repo)?;
+            assert!(status.staged_files.contains_key(&nested_file));
+            assert!(!status
+                .staged_files
+                .keys()
+                .any(|path| util::fs::is_in_oxen_hidden_dir(path)));
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_add_compresses_versions_when_configured() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|mut repo| {
+            command::config::set_compression_level(&mut repo, Some(3))?;
+            let path = repo.path.join("labels.csv");
+            let contents = "file,label\n".repeat(100);
+            util::fs::write_to_path(&path, &contents)?;
+            repositories::add(&repo, &path)?;
+            let commit = repositories::commit(&repo, "Add labels")?;
+
+            let file_node = repositories::entries::get_file(&repo, &commit, "labels.csv")?.unwrap();
+            let version_path = util::fs::version_path_from_hash(&repo, file_node.hash.to_string());
+            assert!(util::compression::is_compressed(&version_path));
+            assert!(util::fs::metadata(&version_path)?.len() < contents.len() as u64);
+
+            // Checking the file out again gives back the plain contents
+            util::fs::remove_file(&path)?;
+            repositories::restore::restore(&repo, RestoreOpts::from_path("labels.csv"))?;
+            assert_eq!(util::fs::read_from_path(&path)?, contents);
+
+            Ok(())
+        })
+    }
 }
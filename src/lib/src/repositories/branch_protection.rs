@@ -0,0 +1,256 @@
+//! # Branch Protection
+//!
+//! Rules the server checks before a branch is updated, deleted or committed to from a
+//! workspace: whether force pushes and deletion are allowed, how many reviewers the new
+//! head must name in `Reviewed-by:` trailers, and who may push at all. Rules live in
+//! `.oxen/branch_protection.json`, a branch follows the first rule that matches it.
+//!
+
+use std::collections::HashSet;
+
+use crate::constants::BRANCH_PROTECTION_FILE;
+use crate::error::OxenError;
+use crate::model::{BranchProtection, Commit, LocalRepository};
+use crate::repositories;
+use crate::util;
+
+const REVIEWED_BY_TRAILER: &str = "Reviewed-by:";
+
+pub fn list(repo: &LocalRepository) -> Result<Vec<BranchProtection>, OxenError> {
+    let path = util::fs::oxen_hidden_dir(&repo.path).join(BRANCH_PROTECTION_FILE);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let contents = util::fs::read_from_path(&path)?;
+    serde_json::from_str(&contents).map_err(|err| OxenError::json_file_error(&path, err))
+}
+
+/// The rule that applies to the branch, an exact match wins over a glob
+pub fn get_for_branch(
+    repo: &LocalRepository,
+    branch: impl AsRef<str>,
+) -> Result<Option<BranchProtection>, OxenError> {
+    let branch = branch.as_ref();
+    let rules = list(repo)?;
+    if let Some(rule) = rules.iter().find(|rule| rule.pattern == branch) {
+        return Ok(Some(rule.clone()));
+    }
+    Ok(rules.into_iter().find(|rule| rule.matches(branch)))
+}
+
+/// Add the rule, replacing any rule with the same pattern
+pub fn protect(repo: &LocalRepository, rule: &BranchProtection) -> Result<(), OxenError> {
+    if rule.pattern.is_empty() {
+        return Err(OxenError::basic_str(
+            "Branch protection pattern cannot be empty",
+        ));
+    }
+    glob::Pattern::new(&rule.pattern).map_err(|err| {
+        OxenError::basic_str(format!("Invalid branch pattern {:?}: {err}", rule.pattern))
+    })?;
+    let mut rules = list(repo)?;
+    match rules.iter_mut().find(|r| r.pattern == rule.pattern) {
+        Some(existing) => *existing = rule.clone(),
+        None => rules.push(rule.clone()),
+    }
+    write(repo, &rules)
+}
+
+/// Remove the rule with the pattern, returning it
+pub fn unprotect(
+    repo: &LocalRepository,
+    pattern: impl AsRef<str>,
+) -> Result<BranchProtection, OxenError> {
+    let pattern = pattern.as_ref();
+    let mut rules = list(repo)?;
+    let Some(index) = rules.iter().position(|rule| rule.pattern == pattern) else {
+        return Err(OxenError::resource_not_found(format!(
+            "branch protection {pattern}"
+        )));
+    };
+    let rule = rules.remove(index);
+    write(repo, &rules)?;
+    Ok(rule)
+}
+
+fn write(repo: &LocalRepository, rules: &[BranchProtection]) -> Result<(), OxenError> {
+    let path = util::fs::oxen_hidden_dir(&repo.path).join(BRANCH_PROTECTION_FILE);
+    util::fs::write_to_path(&path, serde_json::to_string_pretty(rules)?)
+}
+
+/// Check that `pusher` may move the branch to `commit`. `pusher` is the email of the
+/// authenticated user, None when the server does not require auth.
+pub fn check_update(
+    repo: &LocalRepository,
+    branch: impl AsRef<str>,
+    commit: &Commit,
+    pusher: Option<&str>,
+) -> Result<(), OxenError> {
+    let branch = branch.as_ref();
+    let Some(rule) = get_for_branch(repo, branch)? else {
+        return Ok(());
+    };
+    check_pusher(&rule, branch, pusher)?;
+
+    if let Some(current) = repositories::branches::get_by_name(repo, branch)? {
+        if current.commit_id != commit.id
+            && !rule.allow_force_push
+            && !repositories::commits::list_from(repo, &commit.id)?
+                .iter()
+                .any(|c| c.id == current.commit_id)
+        {
+            return Err(OxenError::branch_protected(format!(
+                "force pushes to {branch} are not allowed, pull and merge the latest changes first"
+            )));
+        }
+    }
+    check_reviewers(&rule, branch, &commit.message, Some(&commit.email))
+}
+
+/// Check that `pusher` may delete the branch
+pub fn check_delete(
+    repo: &LocalRepository,
+    branch: impl AsRef<str>,
+    pusher: Option<&str>,
+) -> Result<(), OxenError> {
+    let branch = branch.as_ref();
+    let Some(rule) = get_for_branch(repo, branch)? else {
+        return Ok(());
+    };
+    check_pusher(&rule, branch, pusher)?;
+    if !rule.allow_deletion {
+        return Err(OxenError::branch_protected(format!(
+            "{branch} cannot be deleted"
+        )));
+    }
+    Ok(())
+}
+
+/// Check that `pusher` may commit to the branch with this message, for commits the
+/// server creates such as workspace commits
+pub fn check_commit(
+    repo: &LocalRepository,
+    branch: impl AsRef<str>,
+    message: &str,
+    pusher: Option<&str>,
+) -> Result<(), OxenError> {
+    let branch = branch.as_ref();
+    let Some(rule) = get_for_branch(repo, branch)? else {
+        return Ok(());
+    };
+    check_pusher(&rule, branch, pusher)?;
+    check_reviewers(&rule, branch, message, pusher)
+}
+
+fn check_pusher(
+    rule: &BranchProtection,
+    branch: &str,
+    pusher: Option<&str>,
+) -> Result<(), OxenError> {
+    if rule.allowed_pushers.is_empty() {
+        return Ok(());
+    }
+    match pusher {
+        Some(pusher) if rule.allowed_pushers.iter().any(|p| p == pusher) => Ok(()),
+        Some(pusher) => Err(OxenError::branch_protected(format!(
+            "{pusher} is not allowed to push to {branch}"
+        ))),
+        None => Err(OxenError::branch_protected(format!(
+            "only specific users can push to {branch}, and this server does not know who you are"
+        ))),
+    }
+}
+
+// Reviews from the author themself do not count
+fn check_reviewers(
+    rule: &BranchProtection,
+    branch: &str,
+    message: &str,
+    author: Option<&str>,
+) -> Result<(), OxenError> {
+    if rule.required_reviewers == 0 {
+        return Ok(());
+    }
+    let reviewers: HashSet<String> = message
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix(REVIEWED_BY_TRAILER))
+        .map(|reviewer| reviewer.trim().to_lowercase())
+        .filter(|reviewer| {
+            !reviewer.is_empty()
+                && author.is_none_or(|author| !reviewer.contains(&author.to_lowercase()))
+        })
+        .collect();
+    if reviewers.len() < rule.required_reviewers {
+        return Err(OxenError::branch_protected(format!(
+            "{branch} needs {} reviewer(s) in \"{REVIEWED_BY_TRAILER}\" trailers, found {}",
+            rule.required_reviewers,
+            reviewers.len()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::OxenError;
+    use crate::model::BranchProtection;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_branch_protection_rules() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            util::fs::write_to_path(repo.path.join("labels.txt"), "cat\ndog")?;
+            repositories::add(&repo, repo.path.join("labels.txt"))?;
+            let base = repositories::commit(&repo, "Add labels")?;
+            let mut rule = BranchProtection::new("main");
+            rule.required_reviewers = 1;
+            rule.allowed_pushers = vec!["ox@oxen.ai".to_string()];
+            repositories::branch_protection::protect(&repo, &rule)?;
+            repositories::branch_protection::protect(&repo, &BranchProtection::new("release/*"))?;
+
+            let glob = repositories::branch_protection::get_for_branch(&repo, "release/v1")?;
+            assert_eq!(glob.unwrap().pattern, "release/*");
+            assert!(repositories::branch_protection::get_for_branch(&repo, "dev")?.is_none());
+
+            // A fast forward with a review from someone else
+            util::fs::write_to_path(repo.path.join("data.txt"), "more data")?;
+            repositories::add(&repo, repo.path.join("data.txt"))?;
+            let reviewed =
+                repositories::commit(&repo, "Add data\n\nReviewed-by: Bessie <bessie@oxen.ai>")?;
+            let allowed = Some("ox@oxen.ai");
+            repositories::branch_protection::check_update(&repo, "main", &reviewed, allowed)?;
+
+            let result = repositories::branch_protection::check_update(
+                &repo,
+                "main",
+                &reviewed,
+                Some("someone@else.com"),
+            );
+            assert!(matches!(result, Err(OxenError::BranchProtected(_))));
+            let result =
+                repositories::branch_protection::check_update(&repo, "main", &reviewed, None);
+            assert!(matches!(result, Err(OxenError::BranchProtected(_))));
+
+            let result = repositories::branch_protection::check_commit(
+                &repo,
+                "main",
+                "No review\n\nReviewed-by: ox@oxen.ai",
+                allowed,
+            );
+            assert!(matches!(result, Err(OxenError::BranchProtected(_))));
+
+            // Moving main back to where it was is a force push
+            let result =
+                repositories::branch_protection::check_update(&repo, "main", &base, allowed);
+            assert!(matches!(result, Err(OxenError::BranchProtected(_))));
+
+            let result = repositories::branch_protection::check_delete(&repo, "main", allowed);
+            assert!(matches!(result, Err(OxenError::BranchProtected(_))));
+            repositories::branch_protection::unprotect(&repo, "main")?;
+            repositories::branch_protection::check_delete(&repo, "main", None)?;
+            Ok(())
+        })
+    }
+}
@@ -3,6 +3,7 @@
 //! Interact with Oxen branches.
 //!
 
+use std::collections::{HashSet, VecDeque};
 use std::path::Path;
 
 use crate::constants::{BRANCH_LOCKS_DIR, OXEN_HIDDEN_DIR};
@@ -92,6 +93,7 @@ pub fn create(
 ) -> Result<Branch, OxenError> {
     let name = name.as_ref();
     let commit_id = commit_id.as_ref();
+    repositories::freeze::ensure_not_frozen(repo)?;
     let ref_writer = RefWriter::new(repo)?;
     if repositories::commits::commit_id_exists(repo, commit_id)? {
         ref_writer.create_branch(name, commit_id)
@@ -136,6 +138,74 @@ pub fn update(
     }
 }
 
+/// Move the branch to `commit` without losing history, creating it if it does not exist.
+/// Fast-forwards are always allowed. Anything else rewrites the branch, and is only
+/// allowed with a lease: `expected_commit_id` is where the pusher last saw the branch, and
+/// if the branch has moved since then, the commits pushed in between would be dropped, so
+/// it is rejected. The check and the write happen under the refs db lock, so the branch
+/// cannot move in between.
+pub fn update_with_lease(
+    repo: &LocalRepository,
+    name: impl AsRef<str>,
+    commit: &Commit,
+    expected_commit_id: Option<&str>,
+) -> Result<Branch, OxenError> {
+    let name = name.as_ref();
+    // Holds the lock on the refs db until it is dropped
+    let ref_writer = RefWriter::new(repo)?;
+    let Some(current) = ref_writer.get_branch_by_name(name)? else {
+        return ref_writer.create_branch(name, &commit.id);
+    };
+
+    if let Some(expected) = expected_commit_id {
+        if current.commit_id != expected {
+            return Err(OxenError::push_rejected(format!(
+                "stale info, {name} is at {} not {expected}. Fetch and check the new commits before pushing again",
+                current.commit_id
+            )));
+        }
+    }
+
+    let is_allowed = current.commit_id == commit.id
+        || expected_commit_id.is_some()
+        || is_ancestor(repo, &current.commit_id, commit)?;
+    if !is_allowed {
+        return Err(OxenError::push_rejected(format!(
+            "{name} is at {}, which is not an ancestor of {}. Pull and merge the latest changes, or push with --force-with-lease to rewrite the branch",
+            current.commit_id, commit.id
+        )));
+    }
+
+    ref_writer.set_branch_commit_id(name, &commit.id)?;
+    Ok(Branch {
+        name: name.to_string(),
+        commit_id: commit.id.clone(),
+    })
+}
+
+// Walk back from `commit` until we find `ancestor_id`, so a fast-forward only reads the
+// commits being pushed instead of the whole history
+fn is_ancestor(
+    repo: &LocalRepository,
+    ancestor_id: &str,
+    commit: &Commit,
+) -> Result<bool, OxenError> {
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::from(commit.parent_ids.clone());
+    while let Some(id) = queue.pop_front() {
+        if id == ancestor_id {
+            return Ok(true);
+        }
+        if !visited.insert(id.clone()) {
+            continue;
+        }
+        let parent = repositories::commits::get_by_id(repo, &id)?
+            .ok_or(OxenError::local_parent_link_broken(&id))?;
+        queue.extend(parent.parent_ids);
+    }
+    Ok(false)
+}
+
 /// Delete a local branch
 pub fn delete(repo: &LocalRepository, name: impl AsRef<str>) -> Result<Branch, OxenError> {
     let name = name.as_ref();
@@ -445,6 +515,44 @@ mod tests {
     use crate::test;
     use crate::util;
 
+    #[test]
+    fn test_update_with_lease() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let path = repo.path.join("labels.txt");
+            util::fs::write_to_path(&path, "cat")?;
+            repositories::add(&repo, &path)?;
+            let first = repositories::commit(&repo, "first")?;
+            util::fs::write_to_path(&path, "dog")?;
+            repositories::add(&repo, &path)?;
+            let second = repositories::commit(&repo, "second")?;
+
+            // Moving forward is fine without a lease
+            repositories::branches::create(&repo, "rewrite", &first.id)?;
+            let branch =
+                repositories::branches::update_with_lease(&repo, "rewrite", &second, None)?;
+            assert_eq!(branch.commit_id, second.id);
+
+            // Moving back rewrites history, it needs a lease that matches the branch
+            let result = repositories::branches::update_with_lease(&repo, "rewrite", &first, None);
+            assert!(matches!(result, Err(OxenError::PushRejected(_))));
+            let stale = repositories::branches::update_with_lease(
+                &repo,
+                "rewrite",
+                &first,
+                Some(&first.id),
+            );
+            assert!(matches!(stale, Err(OxenError::PushRejected(_))));
+            let branch = repositories::branches::get_by_name(&repo, "rewrite")?.unwrap();
+            assert_eq!(branch.commit_id, second.id);
+
+            repositories::branches::update_with_lease(&repo, "rewrite", &first, Some(&second.id))?;
+            let branch = repositories::branches::get_by_name(&repo, "rewrite")?.unwrap();
+            assert_eq!(branch.commit_id, first.id);
+
+            Ok(())
+        })
+    }
+
     #[test]
     fn test_list_branch_versions_main() -> Result<(), OxenError> {
         test::run_empty_local_repo_test(|repo| {
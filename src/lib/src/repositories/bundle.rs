@@ -0,0 +1,384 @@
+//! # Bundles
+//!
+//! Pack branches with their full history into a single archive, so a repository can be
+//! moved to a machine without network access. A bundle is a gzipped tarball with a
+//! `bundle.json` manifest followed by the merkle nodes, commit dir hashes and version
+//! files, laid out the same way they are in the `.oxen` dir.
+//!
+//! `unbundle` unpacks the archive into another repository and creates or fast-forwards
+//! its branches. `pull` does the same, then checks out one of the branches.
+//!
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Component, Path};
+use time::OffsetDateTime;
+
+use crate::constants::{self, DIRS_DIR, DIR_HASHES_DIR, HISTORY_DIR, TREE_DIR, VERSIONS_DIR};
+use crate::core::v0_19_0::index::merkle_node_db::node_db_path;
+use crate::core::v0_19_0::index::{CommitMerkleTree, MerkleNodeDB};
+use crate::core::versions::MinOxenVersion;
+use crate::error::OxenError;
+use crate::model::merkle_tree::node::EMerkleTreeNode;
+use crate::model::{BundleManifest, BundleRef, Commit, LocalRepository, MerkleHash};
+use crate::repositories;
+use crate::util;
+
+pub const MANIFEST_FILE: &str = "bundle.json";
+
+/// What unbundling did to a branch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BundleRefStatus {
+    Created,
+    FastForwarded,
+    UpToDate,
+    /// The branch is checked out, use `pull` to update it and the working dir
+    SkippedCheckedOut,
+}
+
+#[derive(Debug, Clone)]
+pub struct UnbundleResult {
+    pub manifest: BundleManifest,
+    pub refs: Vec<(BundleRef, BundleRefStatus)>,
+}
+
+/// Write the branches, their history and all of the files they reference to `dst`
+pub fn create(
+    repo: &LocalRepository,
+    dst: impl AsRef<Path>,
+    branches: &[String],
+) -> Result<BundleManifest, OxenError> {
+    ensure_supported(repo)?;
+    if branches.is_empty() {
+        return Err(OxenError::basic_str(
+            "Must supply at least one branch to bundle",
+        ));
+    }
+
+    let mut refs: Vec<BundleRef> = Vec::new();
+    let mut commits: Vec<Commit> = Vec::new();
+    let mut seen: HashSet<String> = HashSet::new();
+    for name in branches {
+        let Some(branch) = repositories::branches::get_by_name(repo, name)? else {
+            return Err(OxenError::local_branch_not_found(name));
+        };
+        for commit in repositories::commits::list_from(repo, &branch.commit_id)? {
+            if seen.insert(commit.id.clone()) {
+                commits.push(commit);
+            }
+        }
+        refs.push(BundleRef {
+            name: branch.name,
+            commit_id: branch.commit_id,
+        });
+    }
+
+    // Every node in every commit, and the version file for each file node
+    let mut node_hashes: HashSet<MerkleHash> = HashSet::new();
+    let mut files: HashMap<MerkleHash, u64> = HashMap::new();
+    for commit in &commits {
+        let tree = CommitMerkleTree::from_commit(repo, commit)?;
+        tree.walk_tree(|node| match &node.node {
+            EMerkleTreeNode::File(file) => {
+                files.insert(node.hash, file.num_bytes);
+            }
+            EMerkleTreeNode::Commit(_)
+            | EMerkleTreeNode::Directory(_)
+            | EMerkleTreeNode::VNode(_) => {
+                node_hashes.insert(node.hash);
+            }
+            _ => {}
+        });
+    }
+
+    let manifest = BundleManifest {
+        oxen_version: constants::OXEN_VERSION.to_string(),
+        created_at: OffsetDateTime::now_utc(),
+        refs,
+        commit_ids: commits.iter().map(|c| c.id.clone()).collect(),
+        num_files: files.len() as u64,
+        num_bytes: files.values().sum(),
+    };
+    log::debug!(
+        "bundle::create {} commits {} nodes {} files",
+        commits.len(),
+        node_hashes.len(),
+        files.len()
+    );
+
+    let hidden_dir = util::fs::oxen_hidden_dir(&repo.path);
+    let enc = GzEncoder::new(util::fs::file_create(dst.as_ref())?, Compression::default());
+    let mut tar = tar::Builder::new(enc);
+
+    // The manifest goes first so it can be checked before anything is unpacked
+    let json = serde_json::to_string_pretty(&manifest)?;
+    let mut header = tar::Header::new_gnu();
+    header.set_size(json.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    tar.append_data(&mut header, MANIFEST_FILE, json.as_bytes())?;
+
+    for hash in &node_hashes {
+        let tar_path = util::fs::path_relative_to_dir(node_db_path(repo, hash), &hidden_dir)?;
+        MerkleNodeDB::append_to_tar(repo, hash, &mut tar, tar_path)?;
+    }
+
+    for commit in &commits {
+        let tar_dir = Path::new(HISTORY_DIR).join(&commit.id);
+        for dir in [DIRS_DIR, DIR_HASHES_DIR] {
+            let full_path = hidden_dir.join(&tar_dir).join(dir);
+            if full_path.exists() {
+                tar.append_dir_all(tar_dir.join(dir), full_path)?;
+            }
+        }
+    }
+
+    for hash in files.keys() {
+        let version_dir = util::fs::version_dir_from_hash(&repo.path, hash.to_string());
+        if !version_dir.exists() {
+            return Err(OxenError::basic_str(format!(
+                "Missing version file for {hash}. Run `oxen pull --all` to download the full history before bundling."
+            )));
+        }
+        let tar_path = util::fs::path_relative_to_dir(&version_dir, &hidden_dir)?;
+        tar.append_dir_all(tar_path, &version_dir)?;
+    }
+
+    tar.into_inner()?.finish()?;
+    Ok(manifest)
+}
+
+/// Read the manifest without unpacking the bundle
+pub fn read_manifest(src: impl AsRef<Path>) -> Result<BundleManifest, OxenError> {
+    let mut archive = open(src.as_ref())?;
+    let mut entries = archive.entries()?;
+    match entries.next() {
+        Some(entry) => read_manifest_entry(&mut entry?),
+        None => Err(not_a_bundle(src.as_ref())),
+    }
+}
+
+/// Unpack the bundle into the repository and create or fast-forward its branches.
+/// The checked out branch is left alone, so the working dir stays consistent with it.
+pub fn unbundle(
+    repo: &LocalRepository,
+    src: impl AsRef<Path>,
+) -> Result<UnbundleResult, OxenError> {
+    unbundle_and_update(repo, src.as_ref(), None)
+}
+
+/// Unbundle, then update the branch and check it out, like pulling from a remote
+pub async fn pull(
+    repo: &LocalRepository,
+    src: impl AsRef<Path>,
+    branch: impl AsRef<str>,
+) -> Result<UnbundleResult, OxenError> {
+    let branch = branch.as_ref();
+    let manifest = read_manifest(src.as_ref())?;
+    if manifest.get_ref(branch).is_none() {
+        return Err(OxenError::basic_str(format!(
+            "Branch {branch} is not in the bundle"
+        )));
+    }
+
+    let previous_head_commit = repositories::commits::head_commit_maybe(repo)?;
+    let result = unbundle_and_update(repo, src.as_ref(), Some(branch))?;
+    repositories::branches::checkout_branch_from_commit(repo, branch, &previous_head_commit)
+        .await?;
+    repositories::branches::set_head(repo, branch)?;
+    Ok(result)
+}
+
+fn unbundle_and_update(
+    repo: &LocalRepository,
+    src: &Path,
+    checkout: Option<&str>,
+) -> Result<UnbundleResult, OxenError> {
+    ensure_supported(repo)?;
+    let hidden_dir = util::fs::oxen_hidden_dir(&repo.path);
+    let mut archive = open(src)?;
+    let mut entries = archive.entries()?;
+    let manifest = match entries.next() {
+        Some(entry) => read_manifest_entry(&mut entry?)?,
+        None => return Err(not_a_bundle(src)),
+    };
+
+    // Don't touch the dir hashes dbs of commits we already have
+    let mut existing_commits: HashSet<&str> = HashSet::new();
+    for commit_id in &manifest.commit_ids {
+        if repositories::commits::get_by_id(repo, commit_id)?.is_some() {
+            existing_commits.insert(commit_id);
+        }
+    }
+
+    for entry in entries {
+        let mut entry = entry?;
+        let path = entry.path()?.to_path_buf();
+        if !is_bundled_path(&path) {
+            return Err(OxenError::basic_str(format!(
+                "Unexpected path {path:?} in bundle {src:?}"
+            )));
+        }
+        if let Ok(commit_path) = path.strip_prefix(HISTORY_DIR) {
+            let commit_id = commit_path.components().next();
+            if commit_id
+                .is_some_and(|c| existing_commits.contains(&*c.as_os_str().to_string_lossy()))
+            {
+                continue;
+            }
+        } else if hidden_dir.join(&path).is_file() {
+            // Nodes and versions are content addressed, so existing files are the same
+            continue;
+        }
+        entry.unpack_in(&hidden_dir)?;
+    }
+
+    // Check every branch before moving any, so a failed unbundle leaves the refs alone
+    let mut refs = Vec::new();
+    let mut diverged = Vec::new();
+    for bundle_ref in &manifest.refs {
+        let status = match repositories::branches::get_by_name(repo, &bundle_ref.name)? {
+            None => BundleRefStatus::Created,
+            Some(branch) if branch.commit_id == bundle_ref.commit_id => BundleRefStatus::UpToDate,
+            Some(branch) => {
+                let history = repositories::commits::list_from(repo, &bundle_ref.commit_id)?;
+                if !history.iter().any(|c| c.id == branch.commit_id) {
+                    diverged.push(bundle_ref.name.clone());
+                    continue;
+                }
+                if checkout != Some(branch.name.as_str())
+                    && repositories::branches::is_checked_out(repo, &branch.name)
+                {
+                    BundleRefStatus::SkippedCheckedOut
+                } else {
+                    BundleRefStatus::FastForwarded
+                }
+            }
+        };
+        refs.push((bundle_ref.clone(), status));
+    }
+    if !diverged.is_empty() {
+        return Err(OxenError::basic_str(format!(
+            "Branches have diverged from the bundle and can't be fast-forwarded: {}",
+            diverged.join(", ")
+        )));
+    }
+
+    for (bundle_ref, status) in &refs {
+        if matches!(
+            status,
+            BundleRefStatus::Created | BundleRefStatus::FastForwarded
+        ) {
+            repositories::branches::update(repo, &bundle_ref.name, &bundle_ref.commit_id)?;
+        }
+    }
+
+    Ok(UnbundleResult { manifest, refs })
+}
+
+fn ensure_supported(repo: &LocalRepository) -> Result<(), OxenError> {
+    match repo.min_version() {
+        MinOxenVersion::V0_10_0 => Err(OxenError::basic_str(
+            "Bundles are not supported for repositories older than v0.19.0, run `oxen migrate` first",
+        )),
+        MinOxenVersion::V0_19_0 => Ok(()),
+    }
+}
+
+fn open(src: &Path) -> Result<tar::Archive<GzDecoder<File>>, OxenError> {
+    let file = File::open(src).map_err(|_| OxenError::path_does_not_exist(src))?;
+    Ok(tar::Archive::new(GzDecoder::new(file)))
+}
+
+fn read_manifest_entry(
+    entry: &mut tar::Entry<GzDecoder<File>>,
+) -> Result<BundleManifest, OxenError> {
+    if entry.path()? != Path::new(MANIFEST_FILE) {
+        return Err(OxenError::basic_str(format!(
+            "Not an oxen bundle, expected {MANIFEST_FILE} first"
+        )));
+    }
+    let mut json = String::new();
+    entry.read_to_string(&mut json)?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+fn not_a_bundle(src: &Path) -> OxenError {
+    OxenError::basic_str(format!("{src:?} is not an oxen bundle"))
+}
+
+// Only unpack into the dirs a bundle writes, and never outside of them
+fn is_bundled_path(path: &Path) -> bool {
+    let mut components = path.components();
+    let top_level = match components.next() {
+        Some(Component::Normal(dir)) => dir,
+        _ => return false,
+    };
+    [TREE_DIR, HISTORY_DIR, VERSIONS_DIR]
+        .iter()
+        .any(|dir| top_level == *dir)
+        && components.all(|c| matches!(c, Component::Normal(_)))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::constants::DEFAULT_BRANCH_NAME;
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::repositories::bundle::{self, is_bundled_path, BundleRefStatus};
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_is_bundled_path() {
+        assert!(is_bundled_path(Path::new("tree/nodes/abc/node")));
+        assert!(is_bundled_path(Path::new("versions/files/ab/cdef/data")));
+        assert!(!is_bundled_path(Path::new("refs/main")));
+        assert!(!is_bundled_path(Path::new("tree/../../etc/passwd")));
+        assert!(!is_bundled_path(Path::new("/tree/nodes")));
+    }
+
+    #[tokio::test]
+    async fn test_bundle_create_and_pull_into_empty_repo() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed_async(|repo| async move {
+            let head_commit = repositories::commits::head_commit(&repo)?;
+            let bundle_path = repo.path.join("main.oxenbundle");
+            let manifest = bundle::create(&repo, &bundle_path, &[DEFAULT_BRANCH_NAME.into()])?;
+            assert_eq!(manifest.refs.len(), 1);
+            assert_eq!(manifest.refs[0].commit_id, head_commit.id);
+            assert_eq!(
+                bundle::read_manifest(&bundle_path)?.commit_ids,
+                manifest.commit_ids
+            );
+
+            let dst_dir = test::create_repo_dir(test::test_run_dir())?;
+            let dst_repo = repositories::init(&dst_dir)?;
+            let result = bundle::pull(&dst_repo, &bundle_path, DEFAULT_BRANCH_NAME).await?;
+            assert_eq!(result.refs[0].1, BundleRefStatus::Created);
+
+            let dst_head = repositories::commits::head_commit(&dst_repo)?;
+            assert_eq!(dst_head.id, head_commit.id);
+            let history = repositories::commits::list(&dst_repo)?;
+            assert_eq!(history.len(), manifest.commit_ids.len());
+            assert!(dst_repo.path.join("README.md").exists());
+            assert_eq!(
+                util::fs::read_from_path(dst_repo.path.join("README.md"))?,
+                util::fs::read_from_path(repo.path.join("README.md"))?
+            );
+
+            // Unbundling again has nothing to do
+            let result = bundle::unbundle(&dst_repo, &bundle_path)?;
+            assert_eq!(result.refs[0].1, BundleRefStatus::UpToDate);
+
+            util::fs::remove_dir_all(dst_dir)?;
+            Ok(())
+        })
+        .await
+    }
+}
@@ -173,9 +173,12 @@ pub fn checkout_combine<P: AsRef<Path>>(repo: &LocalRepository, path: P) -> Resu
 
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
+
     use crate::api;
     use crate::constants::DEFAULT_BRANCH_NAME;
     use crate::error::OxenError;
+    use crate::model::CheckoutMode;
     use crate::repositories;
     use crate::test;
     use crate::util;
@@ -837,4 +840,143 @@ mod tests {
         })
         .await
     }
+
+    #[tokio::test]
+    async fn test_checkout_restores_many_files_across_dirs() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let orig_branch = repositories::branches::current_branch(&repo)?.unwrap();
+            let readme = repo.path.join("README.md");
+            util::fs::write_to_path(&readme, "# Many files")?;
+            repositories::add(&repo, &readme)?;
+            repositories::commit(&repo, "Adding README")?;
+
+            let branch_name = "add-many-files";
+            repositories::branches::create_checkout(&repo, branch_name)?;
+            for dir_idx in 0..8 {
+                for file_idx in 0..25 {
+                    let path = repo
+                        .path
+                        .join(format!("dir_{dir_idx}"))
+                        .join(format!("file_{file_idx}.txt"));
+                    util::fs::create_dir_all(path.parent().unwrap())?;
+                    util::fs::write_to_path(&path, format!("{dir_idx} {file_idx}"))?;
+                }
+            }
+            repositories::add(&repo, &repo.path)?;
+            repositories::commit(&repo, "Adding 200 files")?;
+
+            repositories::checkout(&repo, &orig_branch.name).await?;
+            assert!(!repo.path.join("dir_0").exists());
+
+            repositories::checkout(&repo, branch_name).await?;
+            for dir_idx in 0..8 {
+                let dir = repo.path.join(format!("dir_{dir_idx}"));
+                assert_eq!(util::fs::list_files_in_dir(&dir).len(), 25);
+            }
+            let contents = util::fs::read_from_path(repo.path.join("dir_7").join("file_24.txt"))?;
+            assert_eq!(contents, "7 24");
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_checkout_reports_all_files_it_could_not_restore() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let orig_branch = repositories::branches::current_branch(&repo)?.unwrap();
+            let readme = repo.path.join("README.md");
+            util::fs::write_to_path(&readme, "# Broken versions")?;
+            repositories::add(&repo, &readme)?;
+            repositories::commit(&repo, "Adding README")?;
+
+            let branch_name = "broken-versions";
+            repositories::branches::create_checkout(&repo, branch_name)?;
+            for name in ["a.txt", "b.txt", "c.txt"] {
+                util::fs::write_to_path(repo.path.join(name), name)?;
+            }
+            repositories::add(&repo, &repo.path)?;
+            let commit = repositories::commit(&repo, "Adding a, b and c")?;
+
+            repositories::checkout(&repo, &orig_branch.name).await?;
+
+            // Lose the version files for a and b
+            for name in ["a.txt", "b.txt"] {
+                let entry =
+                    repositories::entries::get_commit_entry(&repo, &commit, Path::new(name))?
+                        .unwrap();
+                util::fs::remove_file(util::fs::version_path_from_hash(&repo, &entry.hash))?;
+            }
+
+            let result = repositories::checkout(&repo, branch_name).await;
+            let Err(err) = result else {
+                panic!("checkout should fail when version files are missing");
+            };
+            let message = err.to_string();
+            assert!(message.contains("could not restore 2 of"));
+            assert!(message.contains("a.txt"));
+            assert!(message.contains("b.txt"));
+
+            // The files that could be restored still were
+            assert!(repo.path.join("c.txt").exists());
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_checkout_links_version_files_when_configured() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|mut repo| async move {
+            let orig_branch = repositories::branches::current_branch(&repo)?.unwrap();
+            let readme = repo.path.join("README.md");
+            util::fs::write_to_path(&readme, "# Linked checkout")?;
+            repositories::add(&repo, &readme)?;
+            repositories::commit(&repo, "Adding README")?;
+
+            let branch_name = "linked";
+            repositories::branches::create_checkout(&repo, branch_name)?;
+            let path = repo.path.join("data.txt");
+            util::fs::write_to_path(&path, "linked data")?;
+            repositories::add(&repo, &path)?;
+            let commit = repositories::commit(&repo, "Adding data")?;
+            let entry =
+                repositories::entries::get_commit_entry(&repo, &commit, Path::new("data.txt"))?
+                    .unwrap();
+            let version_path = util::fs::version_path_from_hash(&repo, &entry.hash);
+
+            for mode in [CheckoutMode::Reflink, CheckoutMode::Hardlink] {
+                repo.set_checkout_mode(mode);
+                repositories::checkout(&repo, &orig_branch.name).await?;
+                assert!(!path.exists());
+                repositories::checkout(&repo, branch_name).await?;
+                assert_eq!(util::fs::read_from_path(&path)?, "linked data");
+            }
+
+            // Hard links share the version file, which is read only so edits can't leak into it
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::MetadataExt;
+                let working = std::fs::metadata(&path)?;
+                let version = std::fs::metadata(&version_path)?;
+                assert_eq!(working.ino(), version.ino());
+                assert!(version.permissions().readonly());
+            }
+
+            // Replacing the working file shows up as a modification and leaves the version intact
+            util::fs::remove_file(&path)?;
+            util::fs::write_to_path(&path, "edited data")?;
+            let status = repositories::status(&repo)?;
+            assert_eq!(status.modified_files.len(), 1);
+            assert_eq!(util::fs::read_from_path(&version_path)?, "linked data");
+
+            // Adding the edit again must not touch the linked version file
+            repositories::add(&repo, &path)?;
+            repositories::commit(&repo, "Editing data")?;
+            assert_eq!(util::fs::read_from_path(&version_path)?, "linked data");
+
+            Ok(())
+        })
+        .await
+    }
 }
@@ -0,0 +1,132 @@
+//! # oxen clean
+//!
+//! Remove untracked files and directories from the working dir, for example the scratch
+//! output of a data generation run. Paths matched by .oxenignore, staged files and
+//! nested repositories are never removed.
+//!
+
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+use crate::core::oxenignore;
+use crate::error::OxenError;
+use crate::model::LocalRepository;
+use crate::repositories;
+use crate::util;
+
+/// List the untracked files and directories below `path`, relative to the repository root.
+/// A directory where nothing is tracked is listed once instead of file by file.
+pub fn list_untracked(
+    repo: &LocalRepository,
+    path: impl AsRef<Path>,
+) -> Result<Vec<PathBuf>, OxenError> {
+    let path = path.as_ref();
+    let full_path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        repo.path.join(path)
+    };
+    if !full_path.exists() {
+        return Err(OxenError::path_does_not_exist(path));
+    }
+
+    let status = repositories::status_from_dir(repo, &full_path)?;
+    let mut untracked: Vec<PathBuf> = status
+        .untracked_dirs
+        .into_iter()
+        .map(|(dir, _)| dir)
+        .chain(status.untracked_files)
+        .collect();
+    untracked.sort();
+    Ok(untracked)
+}
+
+/// Remove the untracked files and directories below `path`, returning what was removed
+pub fn clean(repo: &LocalRepository, path: impl AsRef<Path>) -> Result<Vec<PathBuf>, OxenError> {
+    let untracked = list_untracked(repo, path)?;
+    remove(repo, &untracked)?;
+    Ok(untracked)
+}
+
+/// Remove paths returned by [list_untracked]. Ignored files and nested repositories
+/// inside an untracked directory are kept, along with the directories that hold them.
+pub fn remove(repo: &LocalRepository, paths: &[PathBuf]) -> Result<(), OxenError> {
+    let ignore = oxenignore::create(repo);
+    for path in paths {
+        let full_path = repo.path.join(path);
+        if full_path.is_file() {
+            util::fs::remove_file(&full_path)?;
+            continue;
+        }
+
+        let walker = WalkDir::new(&full_path)
+            .contents_first(true)
+            .into_iter()
+            .filter_entry(|e| {
+                !util::fs::is_nested_repo(&repo.path, e.path())
+                    && !ignore.is_ignored(e.path(), e.file_type().is_dir())
+            });
+        for entry in walker {
+            let entry = entry.map_err(std::io::Error::from)?;
+            if entry.file_type().is_dir() {
+                // Fails if something we kept is still in it, which is what we want
+                if let Err(err) = std::fs::remove_dir(entry.path()) {
+                    log::debug!("clean keeping dir {:?}: {err}", entry.path());
+                }
+            } else {
+                util::fs::remove_file(entry.path())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_clean_removes_untracked_and_keeps_ignored() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let scratch_dir = repo.path.join("scratch");
+            util::fs::write_to_path(scratch_dir.join("out_1.txt"), "generated")?;
+            util::fs::write_to_path(scratch_dir.join("logs").join("run.log"), "log")?;
+            util::fs::write_to_path(repo.path.join("notes.txt"), "scratch notes")?;
+            util::fs::write_to_path(repo.path.join(".oxenignore"), "*.log\n")?;
+
+            // Staged files are not untracked
+            let staged_path = repo.path.join("staged.txt");
+            util::fs::write_to_path(&staged_path, "keep me")?;
+            repositories::add(&repo, &staged_path)?;
+
+            let untracked = repositories::clean::list_untracked(&repo, "")?;
+            assert!(untracked.contains(&PathBuf::from("scratch")));
+            assert!(untracked.contains(&PathBuf::from("notes.txt")));
+            assert!(!untracked.contains(&PathBuf::from("staged.txt")));
+
+            // Listing is a dry run
+            assert!(scratch_dir.join("out_1.txt").exists());
+
+            // Only clean below a path
+            let removed = repositories::clean::clean(&repo, "scratch")?;
+            assert_eq!(removed, vec![PathBuf::from("scratch")]);
+            assert!(!scratch_dir.join("out_1.txt").exists());
+            assert!(scratch_dir.join("logs").join("run.log").exists());
+            assert!(repo.path.join("notes.txt").exists());
+
+            repositories::clean::clean(&repo, "")?;
+            assert!(!repo.path.join("notes.txt").exists());
+            assert!(staged_path.exists());
+            // Tracked files are untouched
+            assert!(repo.path.join("README.md").exists());
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,297 @@
+//! # Comments
+//!
+//! Threaded discussions on a commit, a file in a commit, or a row of a tabular file,
+//! so dataset reviews can happen next to the versions they are about.
+//!
+
+use crate::constants::COMMENTS_DIR;
+use crate::core::db;
+use crate::core::db::key_val::str_json_db;
+use crate::error::OxenError;
+use crate::model::{Comment, CommentFilter, CommentThread, LocalRepository, NewComment};
+use crate::repositories;
+use crate::util;
+
+use rocksdb::DB;
+use std::collections::HashMap;
+use time::OffsetDateTime;
+
+fn open_db(repo: &LocalRepository) -> Result<DB, OxenError> {
+    let db_path = util::fs::oxen_hidden_dir(&repo.path).join(COMMENTS_DIR);
+    let opts = db::key_val::opts::default();
+    Ok(DB::open(&opts, dunce::simplified(&db_path))?)
+}
+
+fn list_all(repo: &LocalRepository) -> Result<Vec<Comment>, OxenError> {
+    let db = open_db(repo)?;
+    let comments: Vec<(String, Comment)> = str_json_db::list(&db)?;
+    let mut comments: Vec<Comment> = comments.into_iter().map(|(_, c)| c).collect();
+    comments.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    Ok(comments)
+}
+
+/// Add a comment, or a reply if `parent_id` is set. Replies are attached to the same
+/// commit, path and row as the comment they reply to.
+pub fn create(repo: &LocalRepository, new_comment: NewComment) -> Result<Comment, OxenError> {
+    if new_comment.body.trim().is_empty() {
+        return Err(OxenError::basic_str("Comment body cannot be empty"));
+    }
+
+    let (commit_id, path, row_hash) = match &new_comment.parent_id {
+        Some(parent_id) => {
+            let parent = get(repo, parent_id)?.ok_or(OxenError::resource_not_found(parent_id))?;
+            (parent.commit_id, parent.path, parent.row_hash)
+        }
+        None => {
+            validate_target(repo, &new_comment)?;
+            (
+                new_comment.commit_id,
+                new_comment.path,
+                new_comment.row_hash,
+            )
+        }
+    };
+
+    let now = OffsetDateTime::now_utc();
+    let comment = Comment {
+        id: uuid::Uuid::new_v4().to_string(),
+        commit_id,
+        path,
+        row_hash,
+        parent_id: new_comment.parent_id,
+        author: new_comment.author,
+        email: new_comment.email,
+        body: new_comment.body,
+        created_at: now,
+        updated_at: now,
+    };
+    let db = open_db(repo)?;
+    str_json_db::put(&db, &comment.id, &comment)?;
+    Ok(comment)
+}
+
+/// Get a comment by id
+pub fn get(repo: &LocalRepository, id: impl AsRef<str>) -> Result<Option<Comment>, OxenError> {
+    let db = open_db(repo)?;
+    str_json_db::get(&db, id)
+}
+
+/// The comments that match the filter, oldest first
+pub fn list(repo: &LocalRepository, filter: &CommentFilter) -> Result<Vec<Comment>, OxenError> {
+    Ok(list_all(repo)?
+        .into_iter()
+        .filter(|c| filter.matches(c))
+        .collect())
+}
+
+/// The threads that match the filter, each top level comment with all of its replies
+pub fn list_threads(
+    repo: &LocalRepository,
+    filter: &CommentFilter,
+) -> Result<Vec<CommentThread>, OxenError> {
+    let comments = list(repo, filter)?;
+
+    // Replies can be nested, file each one under the top level comment of its thread
+    let parents: HashMap<&str, &str> = comments
+        .iter()
+        .filter_map(|c| c.parent_id.as_deref().map(|p| (c.id.as_str(), p)))
+        .collect();
+    let root_of = |id: &str| {
+        let mut id = id;
+        while let Some(parent) = parents.get(id) {
+            id = parent;
+        }
+        id.to_string()
+    };
+
+    let mut replies: HashMap<String, Vec<Comment>> = HashMap::new();
+    for comment in comments.iter().filter(|c| c.parent_id.is_some()) {
+        replies
+            .entry(root_of(&comment.id))
+            .or_default()
+            .push(comment.clone());
+    }
+
+    Ok(comments
+        .iter()
+        .filter(|c| c.parent_id.is_none())
+        .map(|c| CommentThread {
+            comment: c.clone(),
+            replies: replies.remove(&c.id).unwrap_or_default(),
+        })
+        .collect())
+}
+
+/// Replace the body of a comment
+pub fn update(
+    repo: &LocalRepository,
+    id: impl AsRef<str>,
+    body: impl AsRef<str>,
+) -> Result<Comment, OxenError> {
+    let id = id.as_ref();
+    let body = body.as_ref();
+    if body.trim().is_empty() {
+        return Err(OxenError::basic_str("Comment body cannot be empty"));
+    }
+
+    let mut comment = get(repo, id)?.ok_or(OxenError::resource_not_found(id))?;
+    comment.body = body.to_string();
+    comment.updated_at = OffsetDateTime::now_utc();
+    let db = open_db(repo)?;
+    str_json_db::put(&db, &comment.id, &comment)?;
+    Ok(comment)
+}
+
+/// Delete a comment and every reply under it, returning the deleted comments
+pub fn delete(repo: &LocalRepository, id: impl AsRef<str>) -> Result<Vec<Comment>, OxenError> {
+    let id = id.as_ref();
+    let comments = list_all(repo)?;
+    let Some(comment) = comments.iter().find(|c| c.id == id) else {
+        return Err(OxenError::resource_not_found(id));
+    };
+
+    let mut deleted = vec![comment.clone()];
+    let mut i = 0;
+    while i < deleted.len() {
+        let parent_id = deleted[i].id.clone();
+        deleted.extend(
+            comments
+                .iter()
+                .filter(|c| c.parent_id.as_deref() == Some(parent_id.as_str()))
+                .cloned(),
+        );
+        i += 1;
+    }
+
+    let db = open_db(repo)?;
+    for comment in &deleted {
+        str_json_db::delete(&db, &comment.id)?;
+    }
+    Ok(deleted)
+}
+
+fn validate_target(repo: &LocalRepository, new_comment: &NewComment) -> Result<(), OxenError> {
+    let commit = repositories::commits::get_by_id(repo, &new_comment.commit_id)?.ok_or(
+        OxenError::revision_not_found(new_comment.commit_id.as_str().into()),
+    )?;
+
+    match (&new_comment.path, &new_comment.row_hash) {
+        (None, Some(_)) => Err(OxenError::basic_str(
+            "A comment on a row needs the path of the tabular file",
+        )),
+        (Some(path), row_hash) => {
+            if repositories::entries::get_file(repo, &commit, path)?.is_none() {
+                return Err(OxenError::path_does_not_exist(path));
+            }
+            if row_hash.is_some() && !util::fs::is_tabular(path) {
+                return Err(OxenError::basic_str(format!(
+                    "Cannot comment on a row of {path:?}, it is not a tabular file"
+                )));
+            }
+            Ok(())
+        }
+        (None, None) => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::error::OxenError;
+    use crate::model::{CommentFilter, NewComment};
+    use crate::repositories;
+    use crate::test;
+
+    fn new_comment(commit_id: &str, body: &str) -> NewComment {
+        NewComment {
+            commit_id: commit_id.to_string(),
+            author: String::from("Ox"),
+            email: String::from("ox@oxen.ai"),
+            body: body.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_comment_threads_on_commit_and_row() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let commit = repositories::commits::head_commit(&repo)?;
+            let path = PathBuf::from("annotations/train/bounding_box.csv");
+
+            let on_commit =
+                repositories::comments::create(&repo, new_comment(&commit.id, "Looks good"))?;
+            let on_row = repositories::comments::create(
+                &repo,
+                NewComment {
+                    path: Some(path.clone()),
+                    row_hash: Some(String::from("abc123")),
+                    ..new_comment(&commit.id, "This box is off")
+                },
+            )?;
+            let reply = repositories::comments::create(
+                &repo,
+                NewComment {
+                    parent_id: Some(on_row.id.clone()),
+                    ..new_comment("", "Fixed in the next commit")
+                },
+            )?;
+            let nested = repositories::comments::create(
+                &repo,
+                NewComment {
+                    parent_id: Some(reply.id.clone()),
+                    ..new_comment("", "Thanks")
+                },
+            )?;
+            // Replies inherit the target of the thread
+            assert_eq!(reply.row_hash, on_row.row_hash);
+            assert_eq!(nested.path, Some(path.clone()));
+
+            let filter = CommentFilter {
+                commit_id: Some(commit.id.clone()),
+                ..Default::default()
+            };
+            let threads = repositories::comments::list_threads(&repo, &filter)?;
+            assert_eq!(threads.len(), 2);
+            assert_eq!(threads[0].comment.id, on_commit.id);
+            assert!(threads[0].replies.is_empty());
+            assert_eq!(threads[1].replies.len(), 2);
+
+            let row_filter = CommentFilter {
+                path: Some(path),
+                row_hash: Some(String::from("abc123")),
+                ..Default::default()
+            };
+            assert_eq!(repositories::comments::list(&repo, &row_filter)?.len(), 3);
+
+            // Deleting the thread deletes the replies
+            let deleted = repositories::comments::delete(&repo, &on_row.id)?;
+            assert_eq!(deleted.len(), 3);
+            assert_eq!(repositories::comments::list(&repo, &filter)?.len(), 1);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_comment_rejects_invalid_targets() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let commit = repositories::commits::head_commit(&repo)?;
+
+            assert!(repositories::comments::create(&repo, new_comment("nope", "Hi")).is_err());
+            assert!(repositories::comments::create(&repo, new_comment(&commit.id, " ")).is_err());
+            let missing_path = NewComment {
+                path: Some(PathBuf::from("does/not/exist.csv")),
+                ..new_comment(&commit.id, "Hi")
+            };
+            assert!(repositories::comments::create(&repo, missing_path).is_err());
+            let row_without_path = NewComment {
+                row_hash: Some(String::from("abc123")),
+                ..new_comment(&commit.id, "Hi")
+            };
+            assert!(repositories::comments::create(&repo, row_without_path).is_err());
+
+            Ok(())
+        })
+    }
+}
@@ -6,14 +6,16 @@
 use crate::core::versions::MinOxenVersion;
 use crate::error::OxenError;
 use crate::model::{Commit, LocalRepository, MerkleHash};
-use crate::opts::PaginateOpts;
+use crate::opts::{LogOpts, PaginateOpts};
 use crate::util;
 use crate::view::{PaginatedCommits, StatusMessage};
-use crate::{core, resource};
+use crate::{core, repositories, resource};
 
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
+pub mod states;
+
 /// # Commit the staged files in the repo
 ///
 /// ```
@@ -44,10 +46,65 @@ use std::path::{Path, PathBuf};
 /// # }
 /// ```
 pub fn commit(repo: &LocalRepository, message: &str) -> Result<Commit, OxenError> {
-    match repo.min_version() {
-        MinOxenVersion::V0_10_0 => core::v0_10_0::commits::commit(repo, message),
-        MinOxenVersion::V0_19_0 => core::v0_19_0::commits::commit(repo, message),
+    repositories::freeze::ensure_not_frozen(repo)?;
+    repositories::pii_scan::check_before_commit(repo)?;
+    let commit = match repo.min_version() {
+        MinOxenVersion::V0_10_0 => core::v0_10_0::commits::commit(repo, message)?,
+        MinOxenVersion::V0_19_0 => core::v0_19_0::commits::commit(repo, message)?,
+    };
+    // Attach where any copied files came from
+    repositories::provenance::record_for_commit(repo, &commit)?;
+    Ok(commit)
+}
+
+/// # Commit the staged files, allowing nothing to be staged
+///
+/// With nothing staged the new commit has the same tree as HEAD, which is how release
+/// notes or a marker can be recorded without changing the data.
+pub fn commit_allow_empty(repo: &LocalRepository, message: &str) -> Result<Commit, OxenError> {
+    repositories::freeze::ensure_not_frozen(repo)?;
+    repositories::pii_scan::check_before_commit(repo)?;
+    let commit = match repo.min_version() {
+        MinOxenVersion::V0_10_0 => {
+            return Err(OxenError::basic_str(
+                "oxen commit --allow-empty is not supported for this repository version, run `oxen migrate` first",
+            ))
+        }
+        MinOxenVersion::V0_19_0 => core::v0_19_0::commits::commit_allow_empty(repo, message)?,
+    };
+    repositories::provenance::record_for_commit(repo, &commit)?;
+    Ok(commit)
+}
+
+/// # Amend the HEAD commit
+///
+/// Replaces HEAD with a commit that has the new message and any staged changes,
+/// keeping the tree of HEAD if nothing is staged. Refuses to rewrite a commit that is
+/// already on a remote unless `force` is set.
+pub fn amend(repo: &LocalRepository, message: &str, force: bool) -> Result<Commit, OxenError> {
+    repositories::freeze::ensure_not_frozen(repo)?;
+    let head_commit = head_commit(repo)?;
+    if !force && core::commit_sync_status::commit_is_pushed(repo, &head_commit) {
+        return Err(OxenError::basic_str(format!(
+            "Commit {} has already been pushed, use --force to amend it anyway",
+            head_commit.id
+        )));
+    }
+    let commit = match repo.min_version() {
+        MinOxenVersion::V0_10_0 => {
+            return Err(OxenError::basic_str(
+                "oxen commit --amend is not supported for this repository version, run `oxen migrate` first",
+            ))
+        }
+        MinOxenVersion::V0_19_0 => core::v0_19_0::commits::amend(repo, message)?,
+    };
+    // Keep where the files in the replaced commit were copied from
+    let mut provenance = repositories::provenance::get_for_commit(repo, &head_commit)?;
+    provenance.extend(repositories::provenance::record_for_commit(repo, &commit)?);
+    if !provenance.is_empty() {
+        repositories::provenance::save_for_commit(repo, &commit, &provenance)?;
     }
+    Ok(commit)
 }
 
 /// Iterate over all commits and get the one with the latest timestamp
@@ -198,6 +255,53 @@ pub fn list_from(repo: &LocalRepository, revision: &str) -> Result<Vec<Commit>,
         MinOxenVersion::V0_19_0 => core::v0_19_0::commits::list_from(repo, revision),
     }
 }
+
+/// List the history from `opts.revision`, or HEAD, keeping the commits that match the
+/// author, date range, message and path filters, up to `opts.max_count` commits
+pub fn list_with_opts(repo: &LocalRepository, opts: &LogOpts) -> Result<Vec<Commit>, OxenError> {
+    let revision = match &opts.revision {
+        Some(revision) => revision.to_owned(),
+        None => head_commit(repo)?.id,
+    };
+    if opts.path.is_some() && matches!(repo.min_version(), MinOxenVersion::V0_10_0) {
+        return Err(OxenError::basic_str(
+            "oxen log with a path is not supported for this repository version, run `oxen migrate` first",
+        ));
+    }
+
+    let max_count = opts.max_count.unwrap_or(usize::MAX);
+    if let Some(path) = &opts.path {
+        // A path removed before the revision is only found by checking every commit
+        let start = repositories::revisions::get(repo, &revision)?
+            .ok_or(OxenError::revision_not_found(revision.as_str().into()))?;
+        let changes = core::v0_19_0::commits::list_by_path_from(repo, &start, path)?;
+        if !changes.is_empty() {
+            return Ok(changes
+                .into_iter()
+                .filter(|commit| opts.matches(commit))
+                .take(max_count)
+                .collect());
+        }
+    }
+
+    let mut commits = vec![];
+    for commit in list_from(repo, &revision)? {
+        if commits.len() >= max_count {
+            break;
+        }
+        if !opts.matches(&commit) {
+            continue;
+        }
+        if let Some(path) = &opts.path {
+            if !core::v0_19_0::commits::commit_changed_path(repo, &commit, path)? {
+                continue;
+            }
+        }
+        commits.push(commit);
+    }
+    Ok(commits)
+}
+
 pub fn list_from_with_depth(
     repo: &LocalRepository,
     revision: &str,
@@ -222,6 +326,48 @@ pub fn list_between(
     }
 }
 
+/// The best common ancestor of two commits, found by walking their parent_ids. This is
+/// the base of a three way merge between them. If several candidates are not ancestors
+/// of each other (criss-cross merges) the most recent one is returned, and None is
+/// returned if the histories are unrelated.
+pub fn merge_base(
+    repo: &LocalRepository,
+    commit_a: &Commit,
+    commit_b: &Commit,
+) -> Result<Option<Commit>, OxenError> {
+    let history_a = list_from(repo, &commit_a.id)?;
+    let history_b = list_from(repo, &commit_b.id)?;
+
+    let ids_a: HashSet<&str> = history_a.iter().map(|c| c.id.as_str()).collect();
+    let common: HashMap<&str, &Commit> = history_b
+        .iter()
+        .filter(|c| ids_a.contains(c.id.as_str()))
+        .map(|c| (c.id.as_str(), c))
+        .collect();
+
+    // A common ancestor reachable from another common ancestor is not the best one.
+    // Every ancestor of a common ancestor is common too, so the walk stays in `common`.
+    let mut reachable: HashSet<&str> = HashSet::new();
+    let mut stack: Vec<&str> = common
+        .values()
+        .flat_map(|c| c.parent_ids.iter().map(|id| id.as_str()))
+        .collect();
+    while let Some(id) = stack.pop() {
+        if !reachable.insert(id) {
+            continue;
+        }
+        if let Some(commit) = common.get(id) {
+            stack.extend(commit.parent_ids.iter().map(|id| id.as_str()));
+        }
+    }
+
+    Ok(common
+        .into_values()
+        .filter(|c| !reachable.contains(c.id.as_str()))
+        .max_by_key(|c| c.timestamp)
+        .cloned())
+}
+
 /// Get a list commits by the commit message
 pub fn get_by_message(
     repo: &LocalRepository,
@@ -375,12 +521,219 @@ mod tests {
     use crate::model::EntryDataType;
     use crate::model::MerkleHash;
     use crate::model::StagedEntryStatus;
+    use crate::model::User;
     use crate::repositories;
     use crate::test;
     use crate::util;
 
     use super::*;
 
+    #[test]
+    fn test_amend_replaces_head_commit() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let head = repositories::commits::head_commit(&repo)?;
+            let num_commits = repositories::commits::list(&repo)?.len();
+
+            // Only the message changes, the tree is reused
+            let amended = repositories::commits::amend(&repo, "Better message", false)?;
+            assert_ne!(amended.id, head.id);
+            assert_eq!(amended.message, "Better message");
+            assert_eq!(amended.parent_ids, head.parent_ids);
+            assert_eq!(amended.author, head.author);
+            assert_eq!(repositories::commits::head_commit(&repo)?.id, amended.id);
+            assert_eq!(repositories::commits::list(&repo)?.len(), num_commits);
+            assert!(
+                repositories::entries::get_file(&repo, &amended, Path::new("README.md"))?.is_some()
+            );
+
+            // Staged changes are folded into the amended commit
+            let new_file = repo.path.join("new_file.txt");
+            util::fs::write_to_path(&new_file, "Forgot this one")?;
+            repositories::add(&repo, &new_file)?;
+            let amended_again = repositories::commits::amend(&repo, "With the file", false)?;
+            assert_eq!(amended_again.parent_ids, head.parent_ids);
+            assert_eq!(repositories::commits::list(&repo)?.len(), num_commits);
+            assert!(repositories::entries::get_file(
+                &repo,
+                &amended_again,
+                Path::new("new_file.txt")
+            )?
+            .is_some());
+            assert!(repositories::status(&repo)?.is_clean());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_list_by_path_follows_changes_to_the_path() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let first = repositories::commits::head_commit(&repo)?;
+            let readme = repo.path.join("README.md");
+            let other = repo.path.join("other.txt");
+
+            util::fs::write_to_path(&other, "unrelated")?;
+            repositories::add(&repo, &other)?;
+            repositories::commit(&repo, "Unrelated change")?;
+
+            util::fs::write_to_path(&readme, "A new readme")?;
+            repositories::add(&repo, &readme)?;
+            let changed = repositories::commit(&repo, "Update the readme")?;
+
+            util::fs::write_to_path(&other, "unrelated again")?;
+            repositories::add(&repo, &other)?;
+            let head = repositories::commit(&repo, "Another unrelated change")?;
+
+            let path = Path::new("README.md");
+            let commits = core::v0_19_0::commits::list_by_path_from(&repo, &head, path)?;
+            let ids: Vec<&str> = commits.iter().map(|c| c.id.as_str()).collect();
+            assert_eq!(ids, vec![changed.id.as_str(), first.id.as_str()]);
+
+            let page = repositories::commits::list_by_path_from_paginated(
+                &repo,
+                &head,
+                path,
+                PaginateOpts {
+                    page_num: 1,
+                    page_size: 1,
+                },
+            )?;
+            assert_eq!(page.commits.len(), 1);
+            assert_eq!(page.commits[0].id, changed.id);
+            assert_eq!(page.pagination.total_entries, 2);
+
+            // oxen log <path> uses the same history
+            let opts = LogOpts {
+                path: Some(path.to_path_buf()),
+                ..Default::default()
+            };
+            let logged = repositories::commits::list_with_opts(&repo, &opts)?;
+            assert_eq!(logged.len(), 2);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_commit_with_co_authors() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let co_authors = vec![
+                User::from_str("Ada Lovelace <ada@oxen.ai>")?,
+                User::from_str("Grace Hopper <grace@oxen.ai>")?,
+            ];
+            let message = Commit::message_with_co_authors("Relabel the cats", &co_authors);
+            // Credited once even if given again
+            let message = Commit::message_with_co_authors(&message, &co_authors[..1]);
+            assert_eq!(
+                message,
+                "Relabel the cats\n\nCo-authored-by: Ada Lovelace <ada@oxen.ai>\nCo-authored-by: Grace Hopper <grace@oxen.ai>"
+            );
+
+            let new_file = repo.path.join("labels.txt");
+            util::fs::write_to_path(&new_file, "cat")?;
+            repositories::add(&repo, &new_file)?;
+            repositories::commit(&repo, &message)?;
+
+            let commit = repositories::commits::head_commit(&repo)?;
+            assert_eq!(commit.co_authors.len(), 2);
+            assert_eq!(commit.co_authors[1].email, "grace@oxen.ai");
+            assert_eq!(commit.authors().len(), 3);
+            assert_eq!(commit.message_without_co_authors(), "Relabel the cats");
+
+            // Filtering the log by author finds co-authored commits
+            let opts = LogOpts {
+                author: Some("grace".to_string()),
+                ..Default::default()
+            };
+            let commits = repositories::commits::list_with_opts(&repo, &opts)?;
+            assert_eq!(commits.len(), 1);
+            assert_eq!(commits[0].id, commit.id);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_commit_allow_empty_reuses_head_tree() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let head = repositories::commits::head_commit(&repo)?;
+            assert!(repositories::commit(&repo, "Nothing staged").is_err());
+
+            let empty = repositories::commits::commit_allow_empty(&repo, "Release notes v1")?;
+            assert_eq!(empty.parent_ids, vec![head.id.clone()]);
+            assert_eq!(repositories::commits::head_commit(&repo)?.id, empty.id);
+            assert!(
+                repositories::entries::get_file(&repo, &empty, Path::new("README.md"))?.is_some()
+            );
+
+            // Staged changes are committed as usual
+            let new_file = repo.path.join("new_file.txt");
+            util::fs::write_to_path(&new_file, "More data")?;
+            repositories::add(&repo, &new_file)?;
+            let commit = repositories::commits::commit_allow_empty(&repo, "Add a file")?;
+            assert_eq!(commit.parent_ids, vec![empty.id]);
+            assert!(
+                repositories::entries::get_file(&repo, &commit, Path::new("new_file.txt"))?
+                    .is_some()
+            );
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_amend_refuses_pushed_commit_without_force() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let head = repositories::commits::head_commit(&repo)?;
+            core::commit_sync_status::mark_commit_as_pushed(&repo, &head.id)?;
+
+            let result = repositories::commits::amend(&repo, "Rewrite history", false);
+            assert!(result.is_err());
+            assert_eq!(repositories::commits::head_commit(&repo)?.id, head.id);
+
+            let amended = repositories::commits::amend(&repo, "Rewrite history", true)?;
+            assert_eq!(repositories::commits::head_commit(&repo)?.id, amended.id);
+
+            Ok(())
+        })
+    }
+
+    #[tokio::test]
+    async fn test_merge_base_of_diverged_branches() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|repo| async move {
+            // A - C - D
+            //  \
+            //   B
+            let main = repositories::branches::current_branch(&repo)?.unwrap();
+            let a = repositories::commits::head_commit(&repo)?;
+
+            repositories::branches::create_checkout(&repo, "feature")?;
+            util::fs::write_to_path(repo.path.join("b.txt"), "b")?;
+            repositories::add(&repo, repo.path.join("b.txt"))?;
+            let b = repositories::commit(&repo, "Add b")?;
+
+            repositories::checkout(&repo, &main.name).await?;
+            util::fs::write_to_path(repo.path.join("c.txt"), "c")?;
+            repositories::add(&repo, repo.path.join("c.txt"))?;
+            let c = repositories::commit(&repo, "Add c")?;
+            util::fs::write_to_path(repo.path.join("d.txt"), "d")?;
+            repositories::add(&repo, repo.path.join("d.txt"))?;
+            let d = repositories::commit(&repo, "Add d")?;
+
+            let base = repositories::commits::merge_base(&repo, &d, &b)?.unwrap();
+            assert_eq!(base.id, a.id);
+            let base = repositories::commits::merge_base(&repo, &b, &d)?.unwrap();
+            assert_eq!(base.id, a.id);
+
+            // An ancestor is its own merge base with a descendant
+            let base = repositories::commits::merge_base(&repo, &c, &d)?.unwrap();
+            assert_eq!(base.id, c.id);
+
+            Ok(())
+        })
+        .await
+    }
+
     #[test]
     fn test_command_commit_file() -> Result<(), OxenError> {
         test::run_empty_local_repo_test(|repo| {
@@ -947,4 +1300,60 @@ mod tests {
         })
         .await
     }
+
+    #[test]
+    fn test_list_with_opts_filters_history() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let a_path = repo.path.join("a.txt");
+            let b_path = repo.path.join("b.txt");
+            util::fs::write_to_path(&a_path, "a")?;
+            repositories::add(&repo, &a_path)?;
+            repositories::commit(&repo, "Add a")?;
+            util::fs::write_to_path(&b_path, "b")?;
+            repositories::add(&repo, &b_path)?;
+            repositories::commit(&repo, "Add b")?;
+            util::fs::write_to_path(&a_path, "a2")?;
+            repositories::add(&repo, &a_path)?;
+            repositories::commit(&repo, "Update a")?;
+
+            let messages = |opts: &LogOpts| -> Result<Vec<String>, OxenError> {
+                Ok(list_with_opts(&repo, opts)?
+                    .into_iter()
+                    .map(|c| c.message)
+                    .collect())
+            };
+
+            let opts = LogOpts {
+                path: Some(PathBuf::from("a.txt")),
+                ..LogOpts::default()
+            };
+            assert_eq!(messages(&opts)?, vec!["Update a", "Add a"]);
+
+            let opts = LogOpts {
+                grep: Some("ADD".to_string()),
+                ..LogOpts::default()
+            };
+            assert_eq!(messages(&opts)?, vec!["Add b", "Add a"]);
+
+            let opts = LogOpts {
+                grep: Some("add".to_string()),
+                max_count: Some(1),
+                ..LogOpts::default()
+            };
+            assert_eq!(messages(&opts)?, vec!["Add b"]);
+
+            let opts = LogOpts {
+                author: Some("nobody@else.com".to_string()),
+                ..LogOpts::default()
+            };
+            assert!(messages(&opts)?.is_empty());
+
+            let opts = LogOpts {
+                since: Some(time::OffsetDateTime::now_utc() + time::Duration::days(1)),
+                ..LogOpts::default()
+            };
+            assert!(messages(&opts)?.is_empty());
+            Ok(())
+        })
+    }
 }
@@ -0,0 +1,208 @@
+//! # Commit States
+//!
+//! Flag commits as `approved` or `quarantined` so that downstream jobs
+//! can check the data quality state of a version before using it.
+//!
+
+use crate::constants::COMMIT_STATES_DIR;
+use crate::core::db;
+use crate::core::db::key_val::str_json_db;
+use crate::error::OxenError;
+use crate::model::{Branch, Commit, CommitState, CommitStateEntry, LocalRepository, User};
+use crate::repositories;
+use crate::util;
+
+use rocksdb::DB;
+use std::collections::HashMap;
+use time::OffsetDateTime;
+
+fn open_db(repo: &LocalRepository) -> Result<DB, OxenError> {
+    let db_path = util::fs::oxen_hidden_dir(&repo.path).join(COMMIT_STATES_DIR);
+    let opts = db::key_val::opts::default();
+    Ok(DB::open(&opts, dunce::simplified(&db_path))?)
+}
+
+/// Set the state of the commit a revision points to, overwriting any previous state
+pub fn set(
+    repo: &LocalRepository,
+    revision: impl AsRef<str>,
+    state: CommitState,
+    user: &User,
+) -> Result<CommitStateEntry, OxenError> {
+    let revision = revision.as_ref();
+    let Some(commit) = repositories::revisions::get(repo, revision)? else {
+        return Err(OxenError::revision_not_found(revision.into()));
+    };
+
+    let entry = CommitStateEntry {
+        commit_id: commit.id.to_owned(),
+        state,
+        author: user.name.to_owned(),
+        email: user.email.to_owned(),
+        timestamp: OffsetDateTime::now_utc(),
+    };
+    let db = open_db(repo)?;
+    str_json_db::put(&db, &commit.id, &entry)?;
+    Ok(entry)
+}
+
+/// Get the state of the commit a revision points to, if one has been set
+pub fn get(
+    repo: &LocalRepository,
+    revision: impl AsRef<str>,
+) -> Result<Option<CommitStateEntry>, OxenError> {
+    let revision = revision.as_ref();
+    let Some(commit) = repositories::revisions::get(repo, revision)? else {
+        return Err(OxenError::revision_not_found(revision.into()));
+    };
+
+    let db = open_db(repo)?;
+    str_json_db::get(&db, &commit.id)
+}
+
+/// Remove the state from the commit a revision points to
+pub fn clear(repo: &LocalRepository, revision: impl AsRef<str>) -> Result<(), OxenError> {
+    let revision = revision.as_ref();
+    let Some(commit) = repositories::revisions::get(repo, revision)? else {
+        return Err(OxenError::revision_not_found(revision.into()));
+    };
+
+    let db = open_db(repo)?;
+    str_json_db::delete(&db, &commit.id)
+}
+
+/// List the states of all commits that have one, keyed by commit id
+pub fn list(repo: &LocalRepository) -> Result<HashMap<String, CommitStateEntry>, OxenError> {
+    let db = open_db(repo)?;
+    let entries: Vec<(String, CommitStateEntry)> = str_json_db::list(&db)?;
+    Ok(entries.into_iter().collect())
+}
+
+/// Only keep the commits that are in the given state
+pub fn filter_commits(
+    repo: &LocalRepository,
+    commits: Vec<Commit>,
+    state: CommitState,
+) -> Result<Vec<Commit>, OxenError> {
+    let states = list(repo)?;
+    Ok(commits
+        .into_iter()
+        .filter(|commit| states.get(&commit.id).map(|s| s.state) == Some(state))
+        .collect())
+}
+
+/// Only keep the branches whose head commit is in the given state
+pub fn filter_branches(
+    repo: &LocalRepository,
+    branches: Vec<Branch>,
+    state: CommitState,
+) -> Result<Vec<Branch>, OxenError> {
+    let states = list(repo)?;
+    Ok(branches
+        .into_iter()
+        .filter(|branch| states.get(&branch.commit_id).map(|s| s.state) == Some(state))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::OxenError;
+    use crate::model::{CommitState, User};
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    fn test_user() -> User {
+        User {
+            name: String::from("Ox"),
+            email: String::from("ox@oxen.ai"),
+        }
+    }
+
+    #[test]
+    fn test_commit_states_set_get_clear() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let head = repositories::commits::head_commit(&repo)?;
+            assert!(repositories::commits::states::get(&repo, &head.id)?.is_none());
+
+            let entry = repositories::commits::states::set(
+                &repo,
+                &head.id,
+                CommitState::Quarantined,
+                &test_user(),
+            )?;
+            assert_eq!(entry.commit_id, head.id);
+
+            // Setting again overwrites the state
+            repositories::commits::states::set(
+                &repo,
+                &head.id,
+                CommitState::Approved,
+                &test_user(),
+            )?;
+            let entry = repositories::commits::states::get(&repo, &head.id)?.unwrap();
+            assert_eq!(entry.state, CommitState::Approved);
+            assert_eq!(entry.email, "ox@oxen.ai");
+
+            repositories::commits::states::clear(&repo, &head.id)?;
+            assert!(repositories::commits::states::get(&repo, &head.id)?.is_none());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_commit_states_filter_log_and_branches() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let first_head = repositories::commits::head_commit(&repo)?;
+            repositories::commits::states::set(
+                &repo,
+                &first_head.id,
+                CommitState::Approved,
+                &test_user(),
+            )?;
+
+            // Branch off the approved commit, then add an unapproved commit on top
+            repositories::branches::create_from_head(&repo, "approved-data")?;
+            let new_file = repo.path.join("new_file.txt");
+            util::fs::write_to_path(&new_file, "unreviewed")?;
+            repositories::add(&repo, &new_file)?;
+            let new_commit = repositories::commit(&repo, "Adding unreviewed data")?;
+
+            let commits = repositories::commits::list(&repo)?;
+            let approved = repositories::commits::states::filter_commits(
+                &repo,
+                commits,
+                CommitState::Approved,
+            )?;
+            assert_eq!(approved.len(), 1);
+            assert_eq!(approved[0].id, first_head.id);
+            assert!(approved.iter().all(|c| c.id != new_commit.id));
+
+            let branches = repositories::branches::list(&repo)?;
+            let approved = repositories::commits::states::filter_branches(
+                &repo,
+                branches,
+                CommitState::Approved,
+            )?;
+            assert_eq!(approved.len(), 1);
+            assert_eq!(approved[0].name, "approved-data");
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_commit_states_unknown_revision() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let result = repositories::commits::states::set(
+                &repo,
+                "not-a-revision",
+                CommitState::Approved,
+                &test_user(),
+            );
+            assert!(result.is_err());
+            Ok(())
+        })
+    }
+}
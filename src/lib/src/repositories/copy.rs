@@ -0,0 +1,214 @@
+//! # Copy
+//!
+//! Copy files and directories out of one repository and into another,
+//! recording the source repository and commit as provenance.
+//!
+
+use std::path::{Path, PathBuf};
+
+use crate::api;
+use crate::constants::DEFAULT_BRANCH_NAME;
+use crate::error::OxenError;
+use crate::model::{Commit, LocalRepository, NewCommitBody, OxenUri, Provenance, RemoteRepository};
+use crate::opts::helpers::remote_commit_id;
+use crate::repositories;
+use crate::util;
+
+/// Copy a file or directory from a revision of one repository into a branch of another
+/// repository on the same host, and commit it.
+///
+/// File contents are read straight out of the source version store, so nothing is
+/// downloaded or uploaded and the destination ends up with the same content hashes.
+/// The source repository and commit are appended to the commit message as a trailer
+/// and saved as the provenance of the new commit.
+pub fn copy_between_repos(
+    src_repo: &LocalRepository,
+    src: &OxenUri,
+    dst_repo: &LocalRepository,
+    dst_branch: impl AsRef<str>,
+    dst_path: impl AsRef<Path>,
+    new_commit: &NewCommitBody,
+) -> Result<Commit, OxenError> {
+    let dst_branch = dst_branch.as_ref();
+    let revision = src.revision.as_deref().unwrap_or(DEFAULT_BRANCH_NAME);
+    let Some(src_commit) = repositories::revisions::get(src_repo, revision)? else {
+        return Err(OxenError::revision_not_found(revision.into()));
+    };
+
+    let files: Vec<(PathBuf, String)> =
+        repositories::entries::list_file_hashes(src_repo, &src_commit)?
+            .into_iter()
+            .filter(|(path, _, _)| path.starts_with(&src.path))
+            .map(|(path, hash, _)| (path, hash))
+            .collect();
+    if files.is_empty() {
+        return Err(OxenError::entry_does_not_exist_in_commit(
+            &src.path,
+            &src_commit.id,
+        ));
+    }
+
+    // Like cp, the destination is the new name of the file or directory
+    let dst_path = match (dst_path.as_ref(), src.path.file_name()) {
+        (dst_path, Some(file_name)) if dst_path == Path::new("") => PathBuf::from(file_name),
+        (dst_path, _) => dst_path.to_path_buf(),
+    };
+
+    let Some(branch) = repositories::branches::get_by_name(dst_repo, dst_branch)? else {
+        return Err(OxenError::local_branch_not_found(dst_branch));
+    };
+    let Some(dst_commit) = repositories::commits::get_by_id(dst_repo, &branch.commit_id)? else {
+        return Err(OxenError::revision_not_found(branch.commit_id.into()));
+    };
+
+    let provenance = Provenance {
+        host: None,
+        namespace: src.namespace.to_owned(),
+        repo_name: src.repo_name.to_owned(),
+        commit_id: src_commit.id.to_owned(),
+        source_path: src.path.to_owned(),
+        dest_path: dst_path.to_owned(),
+    };
+
+    let workspace_id = uuid::Uuid::new_v4().to_string();
+    let workspace = repositories::workspaces::create(dst_repo, &dst_commit, workspace_id, true)?;
+    let result = (|| {
+        for (path, hash) in files.iter() {
+            let relative = path.strip_prefix(&src.path).unwrap_or(path);
+            let dst_file = if relative == Path::new("") {
+                workspace.workspace_repo.path.join(&dst_path)
+            } else {
+                workspace.workspace_repo.path.join(&dst_path).join(relative)
+            };
+            if let Some(parent) = dst_file.parent() {
+                util::fs::create_dir_all(parent)?;
+            }
+            let version_path = util::fs::version_path_from_hash(src_repo, hash);
+            util::encryption::copy_decrypted(src_repo, &version_path, &dst_file)?;
+            repositories::workspaces::files::add(&workspace, &dst_file)?;
+        }
+
+        let commit_body = NewCommitBody {
+            message: format!("{}\n\n{}", new_commit.message, provenance.trailer()),
+            author: new_commit.author.to_owned(),
+            email: new_commit.email.to_owned(),
+        };
+        repositories::workspaces::commit(&workspace, &commit_body, dst_branch)
+    })();
+    repositories::workspaces::delete(&workspace)?;
+
+    let commit = result?;
+    repositories::provenance::save_for_commit(dst_repo, &commit, &[provenance])?;
+    Ok(commit)
+}
+
+/// Download a file or directory from a remote repository to a local path.
+///
+/// If the local path is inside an oxen repository, the provenance is staged so that it
+/// is recorded on the next commit that includes the copied data.
+pub async fn copy_to_local(
+    remote_repo: &RemoteRepository,
+    src: &OxenUri,
+    dst: impl AsRef<Path>,
+) -> Result<Provenance, OxenError> {
+    let dst = dst.as_ref();
+    let commit_id = remote_commit_id(remote_repo, src.revision.clone()).await?;
+
+    // Mirror where download_entry puts the file, so the provenance points at it
+    let entry = api::client::entries::get_entry(remote_repo, &src.path, &commit_id).await?;
+    let local_path = match src.path.file_name() {
+        Some(file_name) if dst.is_dir() && !entry.is_dir => dst.join(file_name),
+        _ => dst.to_path_buf(),
+    };
+
+    repositories::download(remote_repo, &src.path, dst, &commit_id).await?;
+
+    let mut provenance = Provenance {
+        host: Some(api::client::get_host_from_url(&remote_repo.remote.url)?),
+        namespace: src.namespace.to_owned(),
+        repo_name: src.repo_name.to_owned(),
+        commit_id,
+        source_path: src.path.to_owned(),
+        dest_path: local_path.to_owned(),
+    };
+
+    let local_path = std::fs::canonicalize(&local_path).unwrap_or(local_path);
+    if let Some(repo_root) = util::fs::get_repo_root(&local_path) {
+        let repo = LocalRepository::from_dir(&repo_root)?;
+        provenance.dest_path = util::fs::path_relative_to_dir(&local_path, &repo_root)?;
+        repositories::provenance::stage(&repo, std::slice::from_ref(&provenance))?;
+    }
+    Ok(provenance)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+    use std::str::FromStr;
+
+    use crate::error::OxenError;
+    use crate::model::{NewCommitBody, OxenUri};
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_copy_dir_between_repos() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|src_repo| {
+            test::run_empty_dir_test(|dir| {
+                let dst_repo = repositories::init(dir)?;
+                util::fs::write_to_path(dir.join("README.md"), "# Destination")?;
+                repositories::add(&dst_repo, dir.join("README.md"))?;
+                repositories::commit(&dst_repo, "Initial commit")?;
+
+                let src = OxenUri::from_str("oxen://ox/training_data@main:annotations/train")?;
+                let new_commit = NewCommitBody {
+                    message: String::from("Bring in the training annotations"),
+                    author: String::from("Ox"),
+                    email: String::from("ox@oxen.ai"),
+                };
+                let commit = repositories::copy::copy_between_repos(
+                    &src_repo,
+                    &src,
+                    &dst_repo,
+                    "main",
+                    Path::new("labels"),
+                    &new_commit,
+                )?;
+
+                assert!(commit
+                    .message
+                    .contains("Copied-From: oxen://ox/training_data@"));
+                let provenance = repositories::provenance::get_for_commit(&dst_repo, &commit)?;
+                assert_eq!(provenance.len(), 1);
+                assert_eq!(
+                    provenance[0].source_path,
+                    PathBuf::from("annotations/train")
+                );
+                assert_eq!(provenance[0].dest_path, PathBuf::from("labels"));
+
+                // The copied files keep the same content hashes
+                let src_commit = repositories::commits::head_commit(&src_repo)?;
+                let src_hashes: Vec<String> =
+                    repositories::entries::list_file_hashes(&src_repo, &src_commit)?
+                        .into_iter()
+                        .filter(|(path, _, _)| path.starts_with("annotations/train"))
+                        .map(|(_, hash, _)| hash)
+                        .collect();
+                let dst_hashes: Vec<String> =
+                    repositories::entries::list_file_hashes(&dst_repo, &commit)?
+                        .into_iter()
+                        .filter(|(path, _, _)| path.starts_with("labels"))
+                        .map(|(_, hash, _)| hash)
+                        .collect();
+                assert!(!src_hashes.is_empty());
+                assert_eq!(src_hashes.len(), dst_hashes.len());
+                for hash in src_hashes.iter() {
+                    assert!(dst_hashes.contains(hash));
+                }
+
+                Ok(())
+            })
+        })
+    }
+}
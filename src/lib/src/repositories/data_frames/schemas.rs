@@ -3,15 +3,20 @@
 //! Interact with schemas
 //!
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
+use glob::glob;
+
 use crate::core;
+use crate::core::df::tabular;
 use crate::core::versions::MinOxenVersion;
 
 use crate::error::OxenError;
+use crate::model::metadata::ColumnStats;
 use crate::model::{Commit, LocalRepository, Schema};
 use crate::repositories;
+use crate::util;
 
 use std::path::Path;
 
@@ -40,6 +45,21 @@ pub fn get_by_path(
     }
 }
 
+/// Min, max, null count and distinct count of each column of a committed data frame
+pub fn get_column_stats_by_path(
+    repo: &LocalRepository,
+    commit: &Commit,
+    path: impl AsRef<Path>,
+) -> Result<Option<Vec<ColumnStats>>, OxenError> {
+    match repo.min_version() {
+        // Older repositories never computed stats
+        MinOxenVersion::V0_10_0 => Ok(None),
+        MinOxenVersion::V0_19_0 => {
+            core::v0_19_0::data_frames::schemas::get_column_stats_by_path(repo, commit, path)
+        }
+    }
+}
+
 /// Get a staged schema
 pub fn get_staged(
     repo: &LocalRepository,
@@ -161,10 +181,147 @@ pub fn add_column_metadata(
     }
 }
 
+/// Set the columns that identify a row of the data frame, tabular diffs align rows on them
+pub fn set_key(
+    repo: &LocalRepository,
+    path: impl AsRef<Path>,
+    columns: &[String],
+) -> Result<HashMap<PathBuf, Schema>, OxenError> {
+    let path = path.as_ref();
+    let mut schema = match get_staged(repo, path)? {
+        Some(schema) => schema,
+        None => {
+            let commit = repositories::commits::head_commit(repo)?;
+            get_by_path(repo, &commit, path)?.ok_or(OxenError::path_does_not_exist(path))?
+        }
+    };
+    schema.set_primary_key(columns)?;
+    let metadata = schema.metadata.unwrap_or_default();
+    add_schema_metadata(repo, path, &metadata)
+}
+
+/// Validate every tabular file matching the glob pattern against the schema, then stage
+/// the schema and column metadata on each of them. Nothing is staged if any file fails
+/// validation, so a single bad shard does not leave the repo half updated.
+pub fn apply(
+    repo: &LocalRepository,
+    pattern: impl AsRef<str>,
+    schema: &Schema,
+) -> Result<HashMap<PathBuf, Schema>, OxenError> {
+    let pattern = pattern.as_ref();
+    let paths = list_tabular_matches(repo, pattern)?;
+    log::debug!("apply schema to {} paths matching {pattern}", paths.len());
+    if paths.is_empty() {
+        return Err(OxenError::basic_str(format!(
+            "No tabular files match pattern {pattern:?}"
+        )));
+    }
+
+    let head_commit = repositories::commits::head_commit_maybe(repo)?;
+    let mut invalid: Vec<String> = vec![];
+    for path in paths.iter() {
+        let full_path = repo.path.join(path);
+        let file_schema = if full_path.exists() {
+            tabular::get_schema(&full_path)?
+        } else if let Some(commit) = &head_commit {
+            match get_by_path(repo, commit, path)? {
+                Some(schema) => schema,
+                None => return Err(OxenError::schema_does_not_exist_for_file(path)),
+            }
+        } else {
+            return Err(OxenError::path_does_not_exist(path));
+        };
+
+        if !fields_match(&file_schema, schema) {
+            invalid.push(format!("  {}\t{}", path.to_string_lossy(), file_schema));
+        }
+    }
+
+    if !invalid.is_empty() {
+        return Err(OxenError::schema_mismatch(format!(
+            "{} file(s) do not match schema {}\n\n{}\n",
+            invalid.len(),
+            schema,
+            invalid.join("\n")
+        )));
+    }
+
+    let mut results = HashMap::new();
+    for path in paths {
+        // Stage the schema even if it only has field metadata or none at all, keeping the
+        // metadata the file already has if the schema does not set any
+        let metadata = match &schema.metadata {
+            Some(metadata) => metadata.to_owned(),
+            None => {
+                let current = match get_staged(repo, &path)? {
+                    Some(schema) => Some(schema),
+                    None => match &head_commit {
+                        Some(commit) => get_by_path(repo, commit, &path)?,
+                        None => None,
+                    },
+                };
+                current
+                    .and_then(|schema| schema.metadata)
+                    .unwrap_or_default()
+            }
+        };
+        add_schema_metadata(repo, &path, &metadata)?;
+        for field in schema.fields.iter() {
+            if let Some(metadata) = &field.metadata {
+                add_column_metadata(repo, &path, &field.name, metadata)?;
+            }
+        }
+
+        match get_staged(repo, &path)? {
+            Some(staged) => {
+                results.insert(path, staged);
+            }
+            None => log::warn!("apply schema did not stage {path:?}"),
+        }
+    }
+
+    Ok(results)
+}
+
+/// Find all the tabular files in the working directory or HEAD commit that match the pattern
+fn list_tabular_matches(
+    repo: &LocalRepository,
+    pattern: &str,
+) -> Result<HashSet<PathBuf>, OxenError> {
+    let mut paths: HashSet<PathBuf> = HashSet::new();
+
+    let full_pattern = repo.path.join(pattern);
+    for entry in glob(&full_pattern.to_string_lossy())? {
+        let path = entry?;
+        if path.is_file() && util::fs::is_tabular(&path) {
+            paths.insert(util::fs::path_relative_to_dir(&path, &repo.path)?);
+        }
+    }
+
+    if let Some(commit) = repositories::commits::head_commit_maybe(repo)? {
+        let committed = repositories::commits::search_entries(repo, &commit, pattern)?;
+        paths.extend(
+            committed
+                .into_iter()
+                .filter(|path| util::fs::is_tabular(path)),
+        );
+    }
+
+    Ok(paths)
+}
+
+/// A file matches the schema if it has exactly the same field names and data types
+fn fields_match(file_schema: &Schema, schema: &Schema) -> bool {
+    file_schema.fields.len() == schema.fields.len()
+        && schema.fields.iter().all(|f| file_schema.has_field(f))
+}
+
 // unit tests
 #[cfg(test)]
 mod tests {
     use crate::error::OxenError;
+    use crate::model::data_frame::schema::Field;
+    use crate::model::Schema;
     use crate::test;
     use crate::util;
     use crate::{command, repositories};
@@ -779,4 +936,84 @@ mod tests {
         })
         .await
     }
+
+    #[tokio::test]
+    async fn test_schemas_apply_to_glob() -> Result<(), OxenError> {
+        test::run_select_data_repo_test_no_commits_async("annotations", |repo| async move {
+            repositories::add(&repo, repo.path.join("annotations"))?;
+
+            let mut schema = Schema::new(vec![
+                Field::new("file", "str"),
+                Field::new("label", "str"),
+                Field::new("min_x", "f64"),
+                Field::new("min_y", "f64"),
+                Field::new("width", "i64"),
+                Field::new("height", "i64"),
+            ]);
+            let label_meta = json!({
+                "classes": ["cat", "dog"]
+            });
+            schema.add_column_metadata("label", &label_meta);
+
+            let applied =
+                repositories::data_frames::schemas::apply(&repo, "annotations/**/*.csv", &schema)?;
+            assert_eq!(applied.len(), 4);
+
+            let one_shot = Path::new("annotations").join("train").join("one_shot.csv");
+            let staged = repositories::data_frames::schemas::get_staged(&repo, &one_shot)?.unwrap();
+            assert_eq!(staged.fields[1].name, "label");
+            assert_eq!(staged.fields[1].metadata, Some(label_meta));
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_schemas_apply_stages_committed_files_without_metadata() -> Result<(), OxenError> {
+        test::run_select_data_repo_test_no_commits_async("annotations", |repo| async move {
+            repositories::add(&repo, repo.path.join("annotations"))?;
+            repositories::commit(&repo, "Adding annotations")?;
+            let one_shot = Path::new("annotations").join("train").join("one_shot.csv");
+            assert!(repositories::data_frames::schemas::get_staged(&repo, &one_shot)?.is_none());
+
+            let schema = Schema::new(vec![
+                Field::new("file", "str"),
+                Field::new("label", "str"),
+                Field::new("min_x", "f64"),
+                Field::new("min_y", "f64"),
+                Field::new("width", "i64"),
+                Field::new("height", "i64"),
+            ]);
+            let applied =
+                repositories::data_frames::schemas::apply(&repo, "annotations/**/*.csv", &schema)?;
+            assert_eq!(applied.len(), 4);
+            assert!(repositories::data_frames::schemas::get_staged(&repo, &one_shot)?.is_some());
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_schemas_apply_rejects_mismatched_files() -> Result<(), OxenError> {
+        test::run_select_data_repo_test_no_commits_async("annotations", |repo| async move {
+            repositories::add(&repo, repo.path.join("annotations"))?;
+
+            let mut schema = Schema::new(vec![Field::new("file", "str")]);
+            schema.add_column_metadata("file", &json!({"render": "image"}));
+
+            let result =
+                repositories::data_frames::schemas::apply(&repo, "annotations/**/*.csv", &schema);
+            assert!(result.is_err());
+
+            // Nothing should have been updated
+            let one_shot = Path::new("annotations").join("train").join("one_shot.csv");
+            let staged = repositories::data_frames::schemas::get_staged(&repo, &one_shot)?.unwrap();
+            assert_eq!(staged.fields[0].metadata, None);
+
+            Ok(())
+        })
+        .await
+    }
 }
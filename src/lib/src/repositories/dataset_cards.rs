@@ -0,0 +1,212 @@
+//! # Dataset Cards
+//!
+//! Read the DATASET_CARD.md or README.md at the root of a revision, along with stats
+//! about the data pulled from the merkle tree, so a hub can render a summary of the
+//! dataset without walking the files itself.
+//!
+
+use std::collections::BTreeMap;
+
+use crate::constants::DATASET_CARD_FILENAMES;
+use crate::error::OxenError;
+use crate::model::{Commit, DatasetCard, DatasetCardSchema, DatasetCardStats, LocalRepository};
+use crate::repositories;
+use crate::util;
+
+/// The card for the dataset at a commit. The metadata is parsed from the front matter
+/// of the card file, and the stats are filled in even if there is no card file.
+pub fn get(repo: &LocalRepository, commit: &Commit) -> Result<DatasetCard, OxenError> {
+    let mut path = None;
+    let mut metadata = BTreeMap::new();
+    let mut body = String::new();
+    for filename in DATASET_CARD_FILENAMES {
+        let Some(file_node) = repositories::entries::get_file(repo, commit, filename)? else {
+            continue;
+        };
+        let version_path =
+            util::fs::plain_version_path_from_hash(repo, file_node.hash.to_string())?;
+        let contents = util::fs::read_from_path(version_path.path())?;
+        (metadata, body) = parse_front_matter(&contents);
+        path = Some(filename.into());
+        break;
+    }
+
+    Ok(DatasetCard {
+        commit_id: commit.id.clone(),
+        path,
+        metadata,
+        body,
+        stats: stats(repo, commit)?,
+    })
+}
+
+fn stats(repo: &LocalRepository, commit: &Commit) -> Result<DatasetCardStats, OxenError> {
+    let mut stats = DatasetCardStats::default();
+    if let Some(root) = repositories::entries::get_directory(repo, commit, "")? {
+        stats.num_bytes = root.num_bytes;
+        stats.num_files = root.num_files();
+        stats.data_type_counts = root.data_type_counts.into_iter().collect();
+        stats.data_type_sizes = root.data_type_sizes.into_iter().collect();
+    }
+
+    let mut schemas: Vec<DatasetCardSchema> =
+        repositories::data_frames::schemas::list(repo, commit)?
+            .into_iter()
+            .map(|(path, schema)| DatasetCardSchema { path, schema })
+            .collect();
+    schemas.sort_by(|a, b| a.path.cmp(&b.path));
+    stats.schemas = schemas;
+    Ok(stats)
+}
+
+/// Split the `---` delimited front matter off the top of a card. Supports the flat
+/// subset of YAML cards use in practice: scalars, inline `[a, b]` lists and `- item`
+/// lists. Nested maps are skipped.
+fn parse_front_matter(contents: &str) -> (BTreeMap<String, serde_json::Value>, String) {
+    let mut metadata = BTreeMap::new();
+    let mut lines = contents.lines();
+    if lines.next().map(str::trim_end) != Some("---") {
+        return (metadata, contents.to_string());
+    }
+
+    let mut front_matter = vec![];
+    let mut closed = false;
+    for line in lines.by_ref() {
+        if line.trim_end() == "---" {
+            closed = true;
+            break;
+        }
+        front_matter.push(line);
+    }
+    if !closed {
+        return (metadata, contents.to_string());
+    }
+
+    let mut current_list: Option<String> = None;
+    for line in front_matter {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+
+        let indented = line.starts_with(' ') || line.starts_with('\t');
+        if indented {
+            let item = line.trim();
+            if let (Some(key), Some(item)) = (&current_list, item.strip_prefix("- ")) {
+                if let Some(serde_json::Value::Array(items)) = metadata.get_mut(key) {
+                    items.push(parse_scalar(item));
+                }
+            }
+            continue;
+        }
+
+        current_list = None;
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let key = key.trim().to_string();
+        let value = value.trim();
+        if value.is_empty() {
+            // Either a block list or a nested map follows, only lists are kept
+            metadata.insert(key.clone(), serde_json::Value::Array(vec![]));
+            current_list = Some(key);
+        } else if let Some(inner) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+            let items = inner
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(parse_scalar)
+                .collect();
+            metadata.insert(key, serde_json::Value::Array(items));
+        } else {
+            metadata.insert(key, parse_scalar(value));
+        }
+    }
+
+    let body = lines.collect::<Vec<_>>().join("\n");
+    (metadata, body.trim_start_matches('\n').to_string())
+}
+
+fn parse_scalar(value: &str) -> serde_json::Value {
+    let value = value.trim();
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|v| v.strip_suffix(quote))
+        {
+            return serde_json::Value::String(inner.to_string());
+        }
+    }
+    match value {
+        "true" => serde_json::Value::Bool(true),
+        "false" => serde_json::Value::Bool(false),
+        "null" | "~" => serde_json::Value::Null,
+        _ => {
+            if let Ok(n) = value.parse::<i64>() {
+                serde_json::Value::from(n)
+            } else if let Ok(n) = value.parse::<f64>() {
+                serde_json::Value::from(n)
+            } else {
+                serde_json::Value::String(value.to_string())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::command;
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_dataset_card_front_matter_and_stats() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|mut repo| {
+            // The card is read back out of a compressed version
+            command::config::set_compression_level(&mut repo, Some(3))?;
+            let card = "---\nlicense: mit\ntask_categories:\n  - object-detection\n  - classification\ntags: [images, \"boxes\"]\nsize: 42\n---\n\n# Bounding Boxes\n";
+            let card_path = repo.path.join("DATASET_CARD.md");
+            util::fs::write_to_path(&card_path, card)?;
+            repositories::add(&repo, &card_path)?;
+            let commit = repositories::commit(&repo, "Adding dataset card")?;
+
+            let card = repositories::dataset_cards::get(&repo, &commit)?;
+            assert_eq!(card.path, Some(PathBuf::from("DATASET_CARD.md")));
+            assert_eq!(card.metadata["license"], "mit");
+            assert_eq!(
+                card.metadata["task_categories"],
+                serde_json::json!(["object-detection", "classification"])
+            );
+            assert_eq!(
+                card.metadata["tags"],
+                serde_json::json!(["images", "boxes"])
+            );
+            assert_eq!(card.metadata["size"], 42);
+            assert_eq!(card.body, "# Bounding Boxes");
+
+            assert!(card.stats.num_files > 0);
+            assert!(card.stats.num_bytes > 0);
+            assert!(card
+                .stats
+                .schemas
+                .iter()
+                .any(|s| s.path == PathBuf::from("annotations/train/bounding_box.csv")));
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_dataset_card_falls_back_to_readme() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let commit = repositories::commits::head_commit(&repo)?;
+            let card = repositories::dataset_cards::get(&repo, &commit)?;
+            assert_eq!(card.path, Some(PathBuf::from("README.md")));
+            assert!(card.metadata.is_empty());
+            assert!(!card.body.is_empty());
+            Ok(())
+        })
+    }
+}
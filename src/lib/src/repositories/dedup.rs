@@ -0,0 +1,323 @@
+//! # Dedup
+//!
+//! Find groups of identical files using the content hashes stored in the tree,
+//! or near-identical images using their perceptual hashes
+//!
+
+use crate::core::versions::MinOxenVersion;
+use crate::error::OxenError;
+use crate::model::metadata::generic_metadata::GenericMetadata;
+use crate::model::{
+    Commit, DuplicateEntry, DuplicateGroup, EntryDataType, LocalRepository, NearDuplicateEntry,
+    NearDuplicateGroup,
+};
+use crate::repositories;
+use crate::util;
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+/// Find files with identical content within a single commit
+pub fn find_duplicates(
+    repo: &LocalRepository,
+    commit: &Commit,
+) -> Result<Vec<DuplicateGroup>, OxenError> {
+    find_duplicates_in_commits(repo, std::slice::from_ref(commit))
+}
+
+/// Find files with identical content across a set of commits.
+///
+/// The same path with the same content in multiple commits is not a duplicate,
+/// only distinct paths that share content are grouped. Groups are sorted by the
+/// number of bytes that could be saved by deduplicating them.
+pub fn find_duplicates_in_commits(
+    repo: &LocalRepository,
+    commits: &[Commit],
+) -> Result<Vec<DuplicateGroup>, OxenError> {
+    // hash -> (num_bytes, entries)
+    let mut groups: HashMap<String, (u64, Vec<DuplicateEntry>)> = HashMap::new();
+    let mut seen: HashSet<(String, PathBuf)> = HashSet::new();
+
+    for commit in commits {
+        for (path, hash, num_bytes) in repositories::entries::list_file_hashes(repo, commit)? {
+            if !seen.insert((hash.clone(), path.clone())) {
+                continue;
+            }
+
+            let (_, group) = groups.entry(hash).or_insert_with(|| (num_bytes, vec![]));
+            group.push(DuplicateEntry {
+                path,
+                commit_id: commit.id.to_owned(),
+            });
+        }
+    }
+
+    let mut duplicates: Vec<DuplicateGroup> = groups
+        .into_iter()
+        .filter(|(_, (_, entries))| entries.len() > 1)
+        .map(|(hash, (num_bytes, mut entries))| {
+            entries.sort_by(|a, b| a.path.cmp(&b.path));
+            DuplicateGroup {
+                hash,
+                num_bytes,
+                entries,
+            }
+        })
+        .collect();
+
+    duplicates.sort_by(|a, b| {
+        b.wasted_bytes()
+            .cmp(&a.wasted_bytes())
+            .then_with(|| a.hash.cmp(&b.hash))
+    });
+    Ok(duplicates)
+}
+
+/// Find clusters of images that look alike across a set of commits.
+///
+/// Images are linked when the hamming distance between their perceptual hashes is at most
+/// `threshold`, and clusters are the connected components of those links. Hashes computed
+/// when the images were added are used, otherwise they are computed from the version files.
+pub fn find_near_duplicate_images(
+    repo: &LocalRepository,
+    commits: &[Commit],
+    threshold: u32,
+) -> Result<Vec<NearDuplicateGroup>, OxenError> {
+    if let MinOxenVersion::V0_10_0 = repo.min_version() {
+        return Err(OxenError::basic_str(
+            "Fuzzy dedup requires a repository on oxen v0.19.0 or later",
+        ));
+    }
+
+    let mut images: Vec<(NearDuplicateEntry, u64)> = vec![];
+    let mut seen: HashSet<(String, PathBuf)> = HashSet::new();
+    for commit in commits {
+        let tree = repositories::tree::get_by_commit(repo, commit)?;
+        for file in repositories::tree::list_all_files(&tree)? {
+            let node = file.file_node;
+            if node.data_type != EntryDataType::Image {
+                continue;
+            }
+            let path = file.dir.join(&node.name);
+            let hash = node.hash.to_string();
+            if !seen.insert((hash.clone(), path.clone())) {
+                continue;
+            }
+
+            let stored = match &node.metadata {
+                Some(GenericMetadata::MetadataImage(metadata)) => metadata.phash(),
+                _ => None,
+            };
+            let phash = match stored {
+                Some(phash) => phash,
+                None => {
+                    let phash = util::fs::plain_version_path_from_hash(repo, &hash)
+                        .and_then(|version_path| util::image::perceptual_hash(version_path.path()));
+                    match phash {
+                        Ok(phash) => phash,
+                        Err(err) => {
+                            log::warn!("Could not compute perceptual hash for {path:?}: {err}");
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            let entry = NearDuplicateEntry {
+                path,
+                commit_id: commit.id.to_owned(),
+                hash,
+                phash: format!("{:016x}", phash),
+                distance: 0,
+            };
+            images.push((entry, phash));
+        }
+    }
+
+    // Union find over every pair within the threshold
+    let mut parents: Vec<usize> = (0..images.len()).collect();
+    fn find(parents: &mut [usize], i: usize) -> usize {
+        let mut root = i;
+        while parents[root] != root {
+            root = parents[root];
+        }
+        let mut i = i;
+        while parents[i] != root {
+            let next = parents[i];
+            parents[i] = root;
+            i = next;
+        }
+        root
+    }
+    for i in 0..images.len() {
+        for j in (i + 1)..images.len() {
+            if util::image::hamming_distance(images[i].1, images[j].1) <= threshold {
+                let (a, b) = (find(&mut parents, i), find(&mut parents, j));
+                if a != b {
+                    parents[b] = a;
+                }
+            }
+        }
+    }
+
+    let mut clusters: HashMap<usize, Vec<(NearDuplicateEntry, u64)>> = HashMap::new();
+    for (i, image) in images.into_iter().enumerate() {
+        let root = find(&mut parents, i);
+        clusters.entry(root).or_default().push(image);
+    }
+
+    let mut groups: Vec<NearDuplicateGroup> = clusters
+        .into_values()
+        .filter(|cluster| cluster.len() > 1)
+        .map(|mut cluster| {
+            cluster.sort_by(|a, b| a.0.path.cmp(&b.0.path));
+            let first = cluster[0].1;
+            let entries = cluster
+                .into_iter()
+                .map(|(mut entry, phash)| {
+                    entry.distance = util::image::hamming_distance(first, phash);
+                    entry
+                })
+                .collect();
+            NearDuplicateGroup { entries }
+        })
+        .collect();
+
+    groups.sort_by(|a, b| {
+        b.entries
+            .len()
+            .cmp(&a.entries.len())
+            .then_with(|| a.entries[0].path.cmp(&b.entries[0].path))
+    });
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::command;
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_find_duplicates_across_directories() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            util::fs::create_dir_all(repo.path.join("a"))?;
+            util::fs::create_dir_all(repo.path.join("b"))?;
+            util::fs::write_to_path(repo.path.join("a").join("cat.txt"), "meow")?;
+            util::fs::write_to_path(repo.path.join("b").join("cat.txt"), "meow")?;
+            util::fs::write_to_path(repo.path.join("cat_copy.txt"), "meow")?;
+            util::fs::write_to_path(repo.path.join("dog.txt"), "woof")?;
+            repositories::add(&repo, &repo.path)?;
+            let commit = repositories::commit(&repo, "Adding cats and dogs")?;
+
+            let groups = repositories::dedup::find_duplicates(&repo, &commit)?;
+            assert_eq!(groups.len(), 1);
+            let group = &groups[0];
+            assert_eq!(group.entries.len(), 3);
+            assert_eq!(group.wasted_bytes(), 8);
+            let paths: Vec<PathBuf> = group.entries.iter().map(|e| e.path.clone()).collect();
+            assert_eq!(
+                paths,
+                vec![
+                    PathBuf::from("a").join("cat.txt"),
+                    PathBuf::from("b").join("cat.txt"),
+                    PathBuf::from("cat_copy.txt"),
+                ]
+            );
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_find_duplicates_across_commits() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            util::fs::write_to_path(repo.path.join("cat.txt"), "meow")?;
+            util::fs::write_to_path(repo.path.join("dog.txt"), "woof")?;
+            repositories::add(&repo, &repo.path)?;
+            let first = repositories::commit(&repo, "Adding cat and dog")?;
+
+            // The cat content shows up at a new path in the next commit
+            util::fs::write_to_path(repo.path.join("cat.txt"), "purr")?;
+            util::fs::write_to_path(repo.path.join("kitty.txt"), "meow")?;
+            repositories::add(&repo, &repo.path)?;
+            let second = repositories::commit(&repo, "Cat purrs, kitty meows")?;
+
+            // Nothing is duplicated within either commit
+            assert!(repositories::dedup::find_duplicates(&repo, &first)?.is_empty());
+            assert!(repositories::dedup::find_duplicates(&repo, &second)?.is_empty());
+
+            // dog.txt is the same path in both commits, so only the meow is reported
+            let groups = repositories::dedup::find_duplicates_in_commits(
+                &repo,
+                &[first.clone(), second.clone()],
+            )?;
+            assert_eq!(groups.len(), 1);
+            assert_eq!(groups[0].entries.len(), 2);
+            assert_eq!(groups[0].entries[0].path, PathBuf::from("cat.txt"));
+            assert_eq!(groups[0].entries[0].commit_id, first.id);
+            assert_eq!(groups[0].entries[1].path, PathBuf::from("kitty.txt"));
+            assert_eq!(groups[0].entries[1].commit_id, second.id);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_find_near_duplicate_images() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|mut repo| {
+            repo.set_perceptual_hash(true);
+            let images_dir = repo.path.join("images");
+            util::fs::create_dir_all(&images_dir)?;
+            let cat = test::test_img_file_with_name("cat_1.jpg");
+            util::fs::copy(&cat, images_dir.join("cat.jpg"))?;
+            util::image::resize_and_save(&cat, images_dir.join("cat_small.png"), 128)?;
+            util::fs::copy(
+                test::test_img_file_with_name("mnist_7.png"),
+                images_dir.join("seven.png"),
+            )?;
+            repositories::add(&repo, &repo.path)?;
+            let commit = repositories::commit(&repo, "Adding images")?;
+
+            // Resizing changes the content hash, so it is not an exact duplicate
+            assert!(repositories::dedup::find_duplicates(&repo, &commit)?.is_empty());
+
+            let groups = repositories::dedup::find_near_duplicate_images(&repo, &[commit], 8)?;
+            assert_eq!(groups.len(), 1);
+            let paths: Vec<PathBuf> = groups[0].entries.iter().map(|e| e.path.clone()).collect();
+            assert_eq!(
+                paths,
+                vec![
+                    PathBuf::from("images").join("cat.jpg"),
+                    PathBuf::from("images").join("cat_small.png"),
+                ]
+            );
+            assert_eq!(groups[0].entries[0].distance, 0);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_find_near_duplicate_images_without_stored_phash() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|mut repo| {
+            // Nothing stored on the nodes, so the hashes come from the compressed versions
+            command::config::set_compression_level(&mut repo, Some(3))?;
+            let cat = test::test_img_file_with_name("cat_1.jpg");
+            util::fs::copy(&cat, repo.path.join("cat.jpg"))?;
+            util::image::resize_and_save(&cat, repo.path.join("cat_small.png"), 128)?;
+            repositories::add(&repo, &repo.path)?;
+            let commit = repositories::commit(&repo, "Adding images")?;
+
+            let groups = repositories::dedup::find_near_duplicate_images(&repo, &[commit], 8)?;
+            assert_eq!(groups.len(), 1);
+            assert_eq!(groups[0].entries.len(), 2);
+
+            Ok(())
+        })
+    }
+}
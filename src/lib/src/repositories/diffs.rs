@@ -28,14 +28,16 @@ use crate::model::diff::tabular_diff::{
     TabularDiffSummary, TabularSchemaDiff,
 };
 
-use crate::model::{Commit, CommitEntry, DataFrameDiff, DiffEntry, LocalRepository, Schema};
+use crate::model::{
+    Commit, CommitEntry, DataFrameDiff, DiffEntry, EntryDataType, LocalRepository, Schema,
+};
 
 use crate::{constants, repositories, util};
 
 use polars::prelude::DataFrame;
 use polars::prelude::IntoLazy;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
@@ -43,9 +45,11 @@ use crate::model::diff::diff_entries_counts::DiffEntriesCounts;
 use crate::model::diff::schema_diff::SchemaDiff;
 use crate::model::diff::AddRemoveModifyCounts;
 use crate::model::diff::DiffResult;
+use crate::model::diff::DirSizeDiff;
 
 use crate::opts::DFOpts;
 
+pub mod drift;
 pub mod join_diff;
 pub mod utf8_diff;
 
@@ -59,6 +63,10 @@ fn is_files_tabular(file_1: impl AsRef<Path>, file_2: impl AsRef<Path>) -> bool
 fn is_files_utf8(file_1: impl AsRef<Path>, file_2: impl AsRef<Path>) -> bool {
     util::fs::is_utf8(file_1.as_ref()) && util::fs::is_utf8(file_2.as_ref())
 }
+fn is_files_image(file_1: impl AsRef<Path>, file_2: impl AsRef<Path>) -> bool {
+    util::fs::file_data_type(file_1.as_ref()) == EntryDataType::Image
+        && util::fs::file_data_type(file_2.as_ref()) == EntryDataType::Image
+}
 
 pub fn diff(
     path_1: impl AsRef<Path>,
@@ -198,6 +206,10 @@ pub fn diff_commits(
     let node_1 = node_1.unwrap();
     let node_2 = node_2.unwrap();
 
+    if node_1.data_type == EntryDataType::Image && node_2.data_type == EntryDataType::Image {
+        return diff_image_file_nodes(repo, &node_1, &node_2);
+    }
+
     let compare_result = repositories::diffs::diff_tabular_file_nodes(
         repo, &node_1, &node_2, keys, targets, display,
     )?;
@@ -217,6 +229,8 @@ pub fn diff_files(
     if is_files_tabular(&file_1, &file_2) {
         let result = tabular(file_1, file_2, keys, targets, display)?;
         Ok(result)
+    } else if is_files_image(&file_1, &file_2) {
+        image_diff(file_1, file_2)
     } else if is_files_utf8(&file_1, &file_2) {
         let result = utf8_diff::diff(file_1, file_2)?;
         Ok(DiffResult::Text(result))
@@ -229,6 +243,29 @@ pub fn diff_files(
     }
 }
 
+pub fn diff_image_file_nodes(
+    repo: &LocalRepository,
+    file_1: &FileNode,
+    file_2: &FileNode,
+) -> Result<DiffResult, OxenError> {
+    let version_path_1 = util::fs::plain_version_path_from_hash(repo, file_1.hash.to_string())?;
+    let version_path_2 = util::fs::plain_version_path_from_hash(repo, file_2.hash.to_string())?;
+    image_diff(version_path_1.path(), version_path_2.path())
+}
+
+// Pixel stats plus a composite of the two images written to the temp dir
+fn image_diff(file_1: impl AsRef<Path>, file_2: impl AsRef<Path>) -> Result<DiffResult, OxenError> {
+    let mut result = util::image::diff(&file_1, &file_2)?;
+    let hash_1 = util::hasher::hash_file_contents(file_1.as_ref())?;
+    let hash_2 = util::hasher::hash_file_contents(file_2.as_ref())?;
+    let composite_dir = std::env::temp_dir().join("oxen").join("image_diffs");
+    util::fs::create_dir_all(&composite_dir)?;
+    let composite_path = composite_dir.join(format!("{hash_1}_{hash_2}.png"));
+    util::image::write_diff_composite(&file_1, &file_2, &composite_path)?;
+    result.image.composite_path = Some(composite_path);
+    Ok(DiffResult::Image(result))
+}
+
 pub fn diff_tabular_file_nodes(
     repo: &LocalRepository,
     file_1: &FileNode,
@@ -237,21 +274,41 @@ pub fn diff_tabular_file_nodes(
     targets: Vec<String>,
     display: Vec<String>,
 ) -> Result<DiffResult, OxenError> {
-    let version_path_1 = util::fs::version_path_from_hash(repo, file_1.hash.to_string());
-    let version_path_2 = util::fs::version_path_from_hash(repo, file_2.hash.to_string());
-    let df_1 =
-        tabular::read_df_with_extension(version_path_1, &file_1.extension, &DFOpts::empty())?;
-    let df_2 =
-        tabular::read_df_with_extension(version_path_2, &file_2.extension, &DFOpts::empty())?;
+    let version_path_1 = util::fs::plain_version_path_from_hash(repo, file_1.hash.to_string())?;
+    let version_path_2 = util::fs::plain_version_path_from_hash(repo, file_2.hash.to_string())?;
+    let df_1 = tabular::read_df_with_extension(
+        version_path_1.path(),
+        &file_1.extension,
+        &DFOpts::empty(),
+    )?;
+    let df_2 = tabular::read_df_with_extension(
+        version_path_2.path(),
+        &file_2.extension,
+        &DFOpts::empty(),
+    )?;
 
     let schema_1 = Schema::from_polars(&df_1.schema());
     let schema_2 = Schema::from_polars(&df_2.schema());
 
+    // Align rows on the key set with `oxen schemas set-key` unless the caller picked keys
+    let keys = if keys.is_empty() {
+        schema_primary_key(file_2)
+            .or_else(|| schema_primary_key(file_1))
+            .filter(|key| schema_1.has_field_names(key) && schema_2.has_field_names(key))
+            .unwrap_or_default()
+    } else {
+        keys
+    };
+
     validate_required_fields(schema_1, schema_2, keys.clone(), targets.clone())?;
 
     diff_dfs(&df_1, &df_2, keys, targets, display)
 }
 
+fn schema_primary_key(node: &FileNode) -> Option<Vec<String>> {
+    node.metadata.as_ref()?.tabular()?.schema.primary_key()
+}
+
 pub fn tabular(
     file_1: impl AsRef<Path>,
     file_2: impl AsRef<Path>,
@@ -764,6 +821,23 @@ pub fn list_changed_dirs(
     }
 }
 
+/// Per directory added/removed/modified file counts and byte deltas under `dir`
+pub fn list_dir_size_diffs(
+    repo: &LocalRepository,
+    dir: impl AsRef<Path>,
+    base_commit: &Commit,
+    head_commit: &Commit,
+) -> Result<Vec<DirSizeDiff>, OxenError> {
+    match repo.min_version() {
+        MinOxenVersion::V0_19_0 => {
+            core::v0_19_0::diff::list_dir_size_diffs(repo, dir, base_commit, head_commit)
+        }
+        MinOxenVersion::V0_10_0 => Err(OxenError::basic_str(
+            "oxen diff --summary is not supported for this repository version, run `oxen migrate` first",
+        )),
+    }
+}
+
 pub fn cache_tabular_diff(
     repo: &LocalRepository,
     compare_id: &str,
@@ -1021,6 +1095,65 @@ pub fn list_diff_entries(
     }
 }
 
+/// The files the commit changed compared to its first parent, sorted by path.
+/// Every file of the first commit is added.
+pub fn list_commit_changes(
+    repo: &LocalRepository,
+    commit: &Commit,
+) -> Result<Vec<(PathBuf, DiffEntryStatus)>, OxenError> {
+    let parent = match commit.parent_ids.first() {
+        Some(parent_id) => repositories::commits::get_by_id(repo, parent_id)?,
+        None => None,
+    };
+    let Some(parent) = parent else {
+        let mut changes: Vec<(PathBuf, DiffEntryStatus)> =
+            repositories::entries::list_file_hashes(repo, commit)?
+                .into_iter()
+                .map(|(path, _, _)| (path, DiffEntryStatus::Added))
+                .collect();
+        changes.sort_by(|a, b| a.0.cmp(&b.0));
+        return Ok(changes);
+    };
+
+    let page_size = constants::DEFAULT_PAGE_SIZE;
+    let mut page = 1;
+    let mut changes = vec![];
+    loop {
+        let diff = list_diff_entries(repo, &parent, commit, PathBuf::from(""), page, page_size)?;
+        for entry in diff.entries.into_iter().filter(|e| !e.is_dir) {
+            let status = DiffEntryStatus::from_str(&entry.status).map_err(OxenError::basic_str)?;
+            changes.push((PathBuf::from(entry.filename), status));
+        }
+        if page >= diff.pagination.total_pages {
+            break;
+        }
+        page += 1;
+    }
+    changes.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(changes)
+}
+
+/// Added, removed and modified file counts for each directory with changes
+pub fn counts_by_dir(
+    changes: &[(PathBuf, DiffEntryStatus)],
+) -> BTreeMap<PathBuf, AddRemoveModifyCounts> {
+    let mut counts: BTreeMap<PathBuf, AddRemoveModifyCounts> = BTreeMap::new();
+    for (path, status) in changes {
+        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let dir_counts = counts.entry(dir).or_insert(AddRemoveModifyCounts {
+            added: 0,
+            removed: 0,
+            modified: 0,
+        });
+        match status {
+            DiffEntryStatus::Added | DiffEntryStatus::Copied => dir_counts.added += 1,
+            DiffEntryStatus::Removed => dir_counts.removed += 1,
+            DiffEntryStatus::Modified | DiffEntryStatus::Renamed => dir_counts.modified += 1,
+        }
+    }
+    counts
+}
+
 fn write_diff_df_cache(
     repo: &LocalRepository,
     compare_id: &str,
@@ -1102,9 +1235,10 @@ pub fn get_add_remove_modify_counts(entries: &[DiffEntry]) -> AddRemoveModifyCou
         }
 
         match DiffEntryStatus::from_str(&entry.status).unwrap() {
-            DiffEntryStatus::Added => added += 1,
+            DiffEntryStatus::Added | DiffEntryStatus::Copied => added += 1,
             DiffEntryStatus::Removed => removed += 1,
-            DiffEntryStatus::Modified => modified += 1,
+            // A rename keeps the contents, so it counts as a modified path
+            DiffEntryStatus::Modified | DiffEntryStatus::Renamed => modified += 1,
         }
     }
     AddRemoveModifyCounts {
@@ -1131,9 +1265,11 @@ mod tests {
     use std::path::Path;
     use std::path::PathBuf;
 
+    use crate::command;
+    use crate::core::df::tabular;
     use crate::error::OxenError;
     use crate::model::diff::diff_entry_status::DiffEntryStatus;
-    use crate::opts::RmOpts;
+    use crate::opts::{DFOpts, RmOpts};
     use crate::repositories;
     use crate::test;
     use crate::util;
@@ -1178,6 +1314,194 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_list_commit_changes_counts_by_dir() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            util::fs::create_dir_all(repo.path.join("images"))?;
+            util::fs::write_to_path(repo.path.join("images").join("cat.txt"), "cat")?;
+            util::fs::write_to_path(repo.path.join("labels.txt"), "cat")?;
+            repositories::add(&repo, &repo.path)?;
+            let first = repositories::commit(&repo, "Add data")?;
+
+            let changes = repositories::diffs::list_commit_changes(&repo, &first)?;
+            assert_eq!(changes.len(), 2);
+            assert!(changes.iter().all(|(_, s)| *s == DiffEntryStatus::Added));
+
+            util::fs::write_to_path(repo.path.join("images").join("cat.txt"), "lion")?;
+            util::fs::write_to_path(repo.path.join("images").join("dog.txt"), "dog")?;
+            repositories::add(&repo, repo.path.join("images"))?;
+            util::fs::remove_file(repo.path.join("labels.txt"))?;
+            repositories::rm(&repo, &RmOpts::from_path("labels.txt"))?;
+            let second = repositories::commit(&repo, "Update images")?;
+
+            let changes = repositories::diffs::list_commit_changes(&repo, &second)?;
+            let counts = repositories::diffs::counts_by_dir(&changes);
+            let images = &counts[&PathBuf::from("images")];
+            assert_eq!((images.added, images.removed, images.modified), (1, 0, 1));
+            let root = &counts[&PathBuf::from("")];
+            assert_eq!((root.added, root.removed, root.modified), (0, 1, 0));
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_list_dir_size_diffs_rolls_up_counts_and_bytes() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            util::fs::create_dir_all(repo.path.join("images").join("train"))?;
+            util::fs::create_dir_all(repo.path.join("text"))?;
+            util::fs::write_to_path(
+                repo.path.join("images").join("train").join("cat.txt"),
+                "cat",
+            )?;
+            util::fs::write_to_path(repo.path.join("text").join("a.txt"), "a")?;
+            repositories::add(&repo, &repo.path)?;
+            let base = repositories::commit(&repo, "Add data")?;
+
+            util::fs::write_to_path(
+                repo.path.join("images").join("train").join("cat.txt"),
+                "lion",
+            )?;
+            util::fs::write_to_path(
+                repo.path.join("images").join("train").join("dog.txt"),
+                "dog",
+            )?;
+            util::fs::create_dir_all(repo.path.join("audio"))?;
+            util::fs::write_to_path(repo.path.join("audio").join("bark.txt"), "woof")?;
+            repositories::add(&repo, &repo.path)?;
+            let head = repositories::commit(&repo, "Update images")?;
+
+            let diffs = repositories::diffs::list_dir_size_diffs(&repo, "", &base, &head)?;
+            let paths: Vec<PathBuf> = diffs.iter().map(|d| d.path.clone()).collect();
+            // text is unchanged so it is pruned
+            assert_eq!(
+                paths,
+                vec![
+                    PathBuf::from(""),
+                    PathBuf::from("audio"),
+                    PathBuf::from("images"),
+                    PathBuf::from("images/train"),
+                ]
+            );
+
+            let root = &diffs[0];
+            assert_eq!(
+                (
+                    root.file_counts.added,
+                    root.file_counts.removed,
+                    root.file_counts.modified
+                ),
+                (2, 0, 1)
+            );
+            // "lion" + "dog" + "woof" - "cat"
+            assert_eq!(root.byte_delta, 8);
+
+            let audio = &diffs[1];
+            assert_eq!(audio.status, DiffEntryStatus::Added);
+            assert_eq!(audio.file_counts.added, 1);
+            assert_eq!(audio.byte_delta, 4);
+
+            let train = &diffs[3];
+            assert_eq!(train.status, DiffEntryStatus::Modified);
+            assert_eq!(
+                (train.file_counts.added, train.file_counts.modified),
+                (1, 1)
+            );
+            assert_eq!(train.byte_delta, 4);
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_list_changed_dirs_skips_unchanged_subtrees() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            util::fs::create_dir_all(repo.path.join("images").join("train"))?;
+            util::fs::create_dir_all(repo.path.join("text"))?;
+            util::fs::create_dir_all(repo.path.join("old").join("nested"))?;
+            util::fs::write_to_path(repo.path.join("images").join("train").join("a.txt"), "a")?;
+            util::fs::write_to_path(repo.path.join("text").join("b.txt"), "b")?;
+            util::fs::write_to_path(repo.path.join("old").join("nested").join("c.txt"), "c")?;
+            repositories::add(&repo, &repo.path)?;
+            let base = repositories::commit(&repo, "Add data")?;
+
+            util::fs::write_to_path(repo.path.join("images").join("train").join("a.txt"), "aa")?;
+            util::fs::create_dir_all(repo.path.join("new"))?;
+            util::fs::write_to_path(repo.path.join("new").join("d.txt"), "d")?;
+            util::fs::remove_dir_all(repo.path.join("old"))?;
+            repositories::rm(&repo, &RmOpts::from_path_recursive("old"))?;
+            repositories::add(&repo, repo.path.join("images"))?;
+            repositories::add(&repo, repo.path.join("new"))?;
+            let head = repositories::commit(&repo, "Update data")?;
+
+            let changed = repositories::diffs::list_changed_dirs(&repo, &base, &head)?;
+            assert_eq!(
+                changed,
+                vec![
+                    (PathBuf::from(""), DiffEntryStatus::Modified),
+                    (PathBuf::from("images"), DiffEntryStatus::Modified),
+                    (PathBuf::from("images/train"), DiffEntryStatus::Modified),
+                    (PathBuf::from("new"), DiffEntryStatus::Added),
+                    (PathBuf::from("old"), DiffEntryStatus::Removed),
+                    (PathBuf::from("old/nested"), DiffEntryStatus::Removed),
+                ]
+            );
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_diff_entries_detects_renamed_dir_and_copied_file() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let base_commit = repositories::commits::head_commit(&repo)?;
+
+            // Move a whole directory and copy one file
+            command::mv(&repo, "train", Path::new("images").join("train"))?;
+            util::fs::copy(
+                repo.path.join("labels.txt"),
+                repo.path.join("labels_copy.txt"),
+            )?;
+            repositories::add(&repo, repo.path.join("labels_copy.txt"))?;
+            let head_commit = repositories::commit(&repo, "Move train into images")?;
+
+            let diff = repositories::diffs::list_diff_entries(
+                &repo,
+                &base_commit,
+                &head_commit,
+                PathBuf::from(""),
+                0,
+                100,
+            )?;
+            assert_eq!(diff.counts.removed, 0);
+            assert_eq!(diff.counts.added, 1);
+
+            let renamed = DiffEntryStatus::Renamed.to_string();
+            let dirs: Vec<_> = diff.entries.iter().filter(|e| e.is_dir).collect();
+            let train = dirs
+                .iter()
+                .find(|e| Path::new(&e.filename) == Path::new("images").join("train"))
+                .unwrap();
+            assert_eq!(train.status, renamed);
+            assert_eq!(
+                train.base_resource.as_ref().unwrap().path,
+                PathBuf::from("train")
+            );
+            assert!(!dirs.iter().any(|e| e.filename == "train"));
+
+            for entry in diff.entries.iter().filter(|e| !e.is_dir) {
+                if entry.filename == "labels_copy.txt" {
+                    assert_eq!(entry.status, DiffEntryStatus::Copied.to_string());
+                    assert_eq!(
+                        entry.base_resource.as_ref().unwrap().path,
+                        PathBuf::from("labels.txt")
+                    );
+                } else {
+                    assert_eq!(entry.status, renamed);
+                }
+            }
+
+            Ok(())
+        })
+    }
+
     #[test]
     fn test_diff_entries_modify_one_tabular() -> Result<(), OxenError> {
         test::run_bounding_box_csv_repo_test_fully_committed(|repo| {
@@ -1866,6 +2190,150 @@ train/cat_2.jpg,cat,30.5,44.0,333,396
         .await
     }
 
+    #[tokio::test]
+    async fn test_compare_aligns_rows_on_schema_key() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let path = PathBuf::from("labels.csv");
+            let full_path = repo.path.join(&path);
+            tokio::fs::write(&full_path, "id,label\n1,cat\n2,cat\n3,dog\n").await?;
+            repositories::add(&repo, &full_path)?;
+            repositories::data_frames::schemas::set_key(&repo, &path, &["id".to_string()])?;
+            let base = repositories::commit(&repo, "add labels")?;
+
+            // Relabel one row, without the key it would be one removed and one added row
+            tokio::fs::write(&full_path, "id,label\n1,cat\n2,dog\n3,dog\n").await?;
+            repositories::add(&repo, &full_path)?;
+            let head = repositories::commit(&repo, "fix label")?;
+
+            let compare_result = repositories::diffs::diff_commits(
+                &repo,
+                CommitPath {
+                    commit: Some(base),
+                    path: path.clone(),
+                },
+                CommitPath {
+                    commit: Some(head),
+                    path: path.clone(),
+                },
+                vec![],
+                vec![],
+                vec![],
+            )?;
+
+            let DiffResult::Tabular(result) = compare_result else {
+                panic!("expected tabular result");
+            };
+            assert_eq!(result.parameters.keys, vec!["id"]);
+            let row_counts = &result.summary.modifications.row_counts;
+            assert_eq!(row_counts.modified, 1);
+            assert_eq!(row_counts.added, 0);
+            assert_eq!(row_counts.removed, 0);
+
+            let changes = result.cell_changes()?;
+            assert_eq!(changes.len(), 1);
+            assert_eq!(changes[0].keys, vec!["2"]);
+            assert_eq!(changes[0].column, "label");
+            assert_eq!(changes[0].old, Some("cat".to_string()));
+            assert_eq!(changes[0].new, Some("dog".to_string()));
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[test]
+    fn test_diff_commits_modified_image() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let path = PathBuf::from("cat.png");
+            let full_path = repo.path.join(&path);
+            util::fs::copy(test::test_img_file_with_name("cat_rgba.png"), &full_path)?;
+            repositories::add(&repo, &full_path)?;
+            let base = repositories::commit(&repo, "add cat")?;
+
+            // Black out the top row
+            let mut img = image::open(&full_path)?.to_rgba8();
+            for x in 0..img.width() {
+                img.put_pixel(x, 0, image::Rgba([0, 0, 0, 255]));
+            }
+            img.save(&full_path)?;
+            repositories::add(&repo, &full_path)?;
+            let head = repositories::commit(&repo, "edit cat")?;
+
+            let compare_result = repositories::diffs::diff_commits(
+                &repo,
+                CommitPath {
+                    commit: Some(base),
+                    path: path.clone(),
+                },
+                CommitPath {
+                    commit: Some(head),
+                    path: path.clone(),
+                },
+                vec![],
+                vec![],
+                vec![],
+            )?;
+
+            let DiffResult::Image(result) = compare_result else {
+                panic!("expected image result");
+            };
+            assert!(!result.dimensions_changed());
+            assert!(result.image.num_changed_pixels > 0);
+            assert!(result.image.num_changed_pixels <= result.image.base_width as u64);
+            assert!(result.image.composite_path.unwrap().exists());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_diff_commits_tabular_on_encrypted_repo() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|mut repo| {
+            let (_, key_path) = repositories::encryption::enable(&mut repo)?;
+
+            let path = PathBuf::from("labels.csv");
+            let full_path = repo.path.join(&path);
+            util::fs::write_to_path(&full_path, "file,label\na.jpg,cat\nb.jpg,dog\n")?;
+            repositories::add(&repo, &full_path)?;
+            let base = repositories::commit(&repo, "add labels")?;
+
+            util::fs::write_to_path(&full_path, "file,label\na.jpg,cat\nb.jpg,dog\nc.jpg,fish\n")?;
+            repositories::add(&repo, &full_path)?;
+            let head = repositories::commit(&repo, "add a label")?;
+            assert_eq!(repositories::encryption::status(&repo)?.num_plain, 0);
+
+            // The df at a revision is read from the decrypted version
+            let version_file =
+                repositories::revisions::get_version_file_from_commit_id(&repo, &base.id, &path)?;
+            let df = tabular::read_df_with_extension(&version_file, "csv", &DFOpts::empty())?;
+            assert_eq!(df.height(), 2);
+
+            let compare_result = repositories::diffs::diff_commits(
+                &repo,
+                CommitPath {
+                    commit: Some(base),
+                    path: path.clone(),
+                },
+                CommitPath {
+                    commit: Some(head),
+                    path: path.clone(),
+                },
+                vec![],
+                vec![],
+                vec![],
+            )?;
+
+            let DiffResult::Tabular(result) = compare_result else {
+                panic!("expected tabular result");
+            };
+            assert_eq!(result.summary.modifications.row_counts.added, 1);
+            assert_eq!(result.summary.modifications.row_counts.removed, 0);
+
+            util::fs::remove_file(key_path)?;
+            Ok(())
+        })
+    }
+
     #[tokio::test]
     async fn test_compare_keys_no_targets_implies_modified() -> Result<(), OxenError> {
         test::run_empty_local_repo_test_async(|repo| async move {
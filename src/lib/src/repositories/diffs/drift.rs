@@ -0,0 +1,348 @@
+//! # oxen diff --stats
+//!
+//! Compare the distribution of every column of a tabular file between two revisions
+//! to spot data drift without diffing the rows.
+//!
+//! ```shell
+//! oxen diff --stats data.csv -r v1..v2
+//! ```
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+
+use polars::prelude::*;
+
+use crate::core::df::tabular;
+use crate::error::OxenError;
+use crate::model::diff::drift_report::{CategoryDelta, ColumnDrift, ColumnDriftKind};
+use crate::model::diff::DriftReport;
+use crate::model::LocalRepository;
+use crate::opts::DFOpts;
+use crate::{repositories, util};
+
+/// Numeric columns are bucketed into this many equal width bins to compare their distributions
+const NUM_BINS: usize = 10;
+/// Keeps empty bins from blowing up PSI and KL divergence
+const EPSILON: f64 = 1e-4;
+/// Only the categories that moved the most are reported
+const MAX_CATEGORY_DELTAS: usize = 10;
+
+/// Compare the column distributions of `path` at `base_revision` and `head_revision`
+pub fn drift_report(
+    repo: &LocalRepository,
+    path: impl AsRef<Path>,
+    base_revision: impl AsRef<str>,
+    head_revision: impl AsRef<str>,
+) -> Result<DriftReport, OxenError> {
+    let path = path.as_ref();
+    let base_revision = base_revision.as_ref();
+    let head_revision = head_revision.as_ref();
+
+    let base_df = read_df_at_revision(repo, path, base_revision)?;
+    let head_df = read_df_at_revision(repo, path, head_revision)?;
+
+    let mut report = compare_dfs(&base_df, &head_df)?;
+    report.path = path.to_string_lossy().to_string();
+    report.base_revision = base_revision.to_string();
+    report.head_revision = head_revision.to_string();
+    Ok(report)
+}
+
+/// The drift of every column the two data frames share
+pub fn compare_dfs(base_df: &DataFrame, head_df: &DataFrame) -> Result<DriftReport, OxenError> {
+    let base_names: Vec<String> = base_df
+        .get_column_names()
+        .iter()
+        .map(|n| n.to_string())
+        .collect();
+    let head_names: Vec<String> = head_df
+        .get_column_names()
+        .iter()
+        .map(|n| n.to_string())
+        .collect();
+
+    let mut columns = vec![];
+    for name in base_names.iter().filter(|n| head_names.contains(n)) {
+        let base_col = base_df.column(name)?.as_materialized_series();
+        let head_col = head_df.column(name)?.as_materialized_series();
+        match column_drift(base_col, head_col)? {
+            Some(drift) => columns.push(drift),
+            None => log::debug!("drift_report skipping column {name} of unsupported type"),
+        }
+    }
+
+    Ok(DriftReport {
+        path: String::new(),
+        base_revision: String::new(),
+        head_revision: String::new(),
+        base_num_rows: base_df.height(),
+        head_num_rows: head_df.height(),
+        added_columns: head_names
+            .iter()
+            .filter(|n| !base_names.contains(n))
+            .cloned()
+            .collect(),
+        removed_columns: base_names
+            .iter()
+            .filter(|n| !head_names.contains(n))
+            .cloned()
+            .collect(),
+        columns,
+    })
+}
+
+fn read_df_at_revision(
+    repo: &LocalRepository,
+    path: &Path,
+    revision: &str,
+) -> Result<DataFrame, OxenError> {
+    let commit = repositories::revisions::get(repo, revision)?
+        .ok_or(OxenError::revision_not_found(revision.into()))?;
+    let node = repositories::entries::get_file(repo, &commit, path)?.ok_or_else(|| {
+        OxenError::ResourceNotFound(format!("{}@{}", path.display(), commit.id).into())
+    })?;
+    if !node.data_type.is_tabular() {
+        return Err(OxenError::invalid_file_type(format!(
+            "Drift reports are only supported for tabular files, found {path:?}"
+        )));
+    }
+    let version_path = util::fs::plain_version_path_from_hash(repo, node.hash.to_string())?;
+    tabular::read_df_with_extension(version_path.path(), &node.extension, &DFOpts::empty())
+}
+
+// None if the column is neither numeric nor castable to strings, like nested lists
+fn column_drift(base: &Series, head: &Series) -> Result<Option<ColumnDrift>, OxenError> {
+    let base_null_rate = null_rate(base);
+    let head_null_rate = null_rate(head);
+
+    let mut drift = ColumnDrift {
+        name: base.name().to_string(),
+        dtype: head.dtype().to_string(),
+        kind: ColumnDriftKind::Numeric,
+        base_null_rate,
+        head_null_rate,
+        null_rate_delta: head_null_rate - base_null_rate,
+        base_mean: None,
+        head_mean: None,
+        mean_shift: None,
+        base_std: None,
+        head_std: None,
+        std_shift: None,
+        psi: 0.0,
+        kl_divergence: 0.0,
+        category_deltas: vec![],
+    };
+
+    if base.dtype().is_numeric() && head.dtype().is_numeric() {
+        let base_values = numeric_values(base)?;
+        let head_values = numeric_values(head)?;
+        let (base_mean, base_std) = mean_std(&base_values);
+        let (head_mean, head_std) = mean_std(&head_values);
+        drift.base_mean = base_mean;
+        drift.head_mean = head_mean;
+        drift.mean_shift = base_mean.zip(head_mean).map(|(b, h)| h - b);
+        drift.base_std = base_std;
+        drift.head_std = head_std;
+        drift.std_shift = base_std.zip(head_std).map(|(b, h)| h - b);
+
+        let (base_dist, head_dist) = binned_distributions(&base_values, &head_values);
+        drift.psi = psi(&base_dist, &head_dist);
+        drift.kl_divergence = kl_divergence(&base_dist, &head_dist);
+        return Ok(Some(drift));
+    }
+
+    let (Some(base_counts), Some(head_counts)) = (category_counts(base), category_counts(head))
+    else {
+        return Ok(None);
+    };
+    let base_total = base_counts.values().sum::<usize>().max(1) as f64;
+    let head_total = head_counts.values().sum::<usize>().max(1) as f64;
+    let categories: BTreeSet<&String> = base_counts.keys().chain(head_counts.keys()).collect();
+
+    let mut base_dist = vec![];
+    let mut head_dist = vec![];
+    let mut deltas = vec![];
+    for value in categories {
+        let base_frequency = *base_counts.get(value).unwrap_or(&0) as f64 / base_total;
+        let head_frequency = *head_counts.get(value).unwrap_or(&0) as f64 / head_total;
+        base_dist.push(base_frequency);
+        head_dist.push(head_frequency);
+        deltas.push(CategoryDelta {
+            value: value.to_string(),
+            base_frequency,
+            head_frequency,
+            delta: head_frequency - base_frequency,
+        });
+    }
+    deltas.sort_by(|a, b| b.delta.abs().total_cmp(&a.delta.abs()));
+    deltas.truncate(MAX_CATEGORY_DELTAS);
+
+    drift.kind = ColumnDriftKind::Categorical;
+    drift.psi = psi(&base_dist, &head_dist);
+    drift.kl_divergence = kl_divergence(&base_dist, &head_dist);
+    drift.category_deltas = deltas;
+    Ok(Some(drift))
+}
+
+fn null_rate(series: &Series) -> f64 {
+    if series.is_empty() {
+        return 0.0;
+    }
+    series.null_count() as f64 / series.len() as f64
+}
+
+fn numeric_values(series: &Series) -> Result<Vec<f64>, OxenError> {
+    let values = series.cast(&DataType::Float64)?;
+    Ok(values
+        .f64()?
+        .into_iter()
+        .flatten()
+        .filter(|v| v.is_finite())
+        .collect())
+}
+
+fn category_counts(series: &Series) -> Option<HashMap<String, usize>> {
+    let values = series.cast(&DataType::String).ok()?;
+    let mut counts = HashMap::new();
+    for value in values.str().ok()?.into_iter().flatten() {
+        *counts.entry(value.to_string()).or_insert(0) += 1;
+    }
+    Some(counts)
+}
+
+// Sample standard deviation, None if there are not enough values
+fn mean_std(values: &[f64]) -> (Option<f64>, Option<f64>) {
+    if values.is_empty() {
+        return (None, None);
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    if values.len() < 2 {
+        return (Some(mean), None);
+    }
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    (Some(mean), Some(variance.sqrt()))
+}
+
+// Share of values in each equal width bin over the combined range of both versions
+fn binned_distributions(base: &[f64], head: &[f64]) -> (Vec<f64>, Vec<f64>) {
+    let all = base.iter().chain(head.iter());
+    let min = all.clone().cloned().fold(f64::INFINITY, f64::min);
+    let max = all.cloned().fold(f64::NEG_INFINITY, f64::max);
+    let width = (max - min) / NUM_BINS as f64;
+
+    let histogram = |values: &[f64]| -> Vec<f64> {
+        let mut bins = vec![0.0; NUM_BINS];
+        if values.is_empty() {
+            return bins;
+        }
+        for v in values {
+            let bin = if width > 0.0 {
+                (((v - min) / width) as usize).min(NUM_BINS - 1)
+            } else {
+                0
+            };
+            bins[bin] += 1.0;
+        }
+        bins.iter().map(|c| c / values.len() as f64).collect()
+    };
+    (histogram(base), histogram(head))
+}
+
+fn psi(base: &[f64], head: &[f64]) -> f64 {
+    base.iter()
+        .zip(head)
+        .map(|(b, h)| {
+            let (b, h) = (b.max(EPSILON), h.max(EPSILON));
+            (h - b) * (h / b).ln()
+        })
+        .sum()
+}
+
+fn kl_divergence(base: &[f64], head: &[f64]) -> f64 {
+    base.iter()
+        .zip(head)
+        .map(|(b, h)| {
+            let (b, h) = (b.max(EPSILON), h.max(EPSILON));
+            h * (h / b).ln()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::*;
+
+    use crate::error::OxenError;
+    use crate::model::diff::drift_report::ColumnDriftKind;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_compare_dfs_numeric_and_categorical_drift() -> Result<(), OxenError> {
+        let base = df!(
+            "label" => &["cat", "cat", "dog", "dog"],
+            "score" => &[Some(1.0), Some(2.0), Some(3.0), Some(4.0)],
+        )?;
+        let head = df!(
+            "label" => &["dog", "dog", "dog", "bird"],
+            "score" => &[Some(11.0), Some(12.5), None, Some(14.0)],
+            "extra" => &[1, 2, 3, 4],
+        )?;
+
+        let report = repositories::diffs::drift::compare_dfs(&base, &head)?;
+        assert_eq!(report.added_columns, vec!["extra"]);
+        assert!(report.removed_columns.is_empty());
+        assert_eq!(report.columns.len(), 2);
+
+        let label = &report.columns[0];
+        assert_eq!(label.kind, ColumnDriftKind::Categorical);
+        // cat went from half the rows to none
+        assert_eq!(label.category_deltas[0].value, "cat");
+        assert_eq!(label.category_deltas[0].delta, -0.5);
+        assert!(label.psi > 0.25);
+
+        let score = &report.columns[1];
+        assert_eq!(score.kind, ColumnDriftKind::Numeric);
+        assert_eq!(score.null_rate_delta, 0.25);
+        assert_eq!(score.base_mean, Some(2.5));
+        assert_eq!(score.mean_shift, Some(10.0));
+        assert!(score.psi > 0.25);
+        assert!(score.kl_divergence > 0.0);
+
+        // Nothing drifts against itself
+        let report = repositories::diffs::drift::compare_dfs(&base, &base)?;
+        assert!(report.columns.iter().all(|c| c.psi == 0.0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_drift_report_between_revisions() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|mut repo| {
+            // The head version is encrypted, the base version is not
+            let (_, key_path) = repositories::encryption::enable(&mut repo)?;
+            let path = std::path::Path::new("annotations")
+                .join("train")
+                .join("bounding_box.csv");
+            let base = repositories::commits::head_commit(&repo)?;
+
+            let full_path = repo.path.join(&path);
+            let contents = util::fs::read_from_path(&full_path)?;
+            let mut lines: Vec<&str> = contents.lines().collect();
+            lines.truncate(3);
+            util::fs::write_to_path(&full_path, lines.join("\n"))?;
+            repositories::add(&repo, &full_path)?;
+            let head = repositories::commit(&repo, "Keep two boxes")?;
+
+            let report =
+                repositories::diffs::drift::drift_report(&repo, &path, &base.id, &head.id)?;
+            assert_eq!(report.head_num_rows, 2);
+            assert!(report.base_num_rows > report.head_num_rows);
+            assert!(report.columns.iter().any(|c| c.name == "label"));
+
+            util::fs::remove_file(key_path)?;
+            Ok(())
+        })
+    }
+}
@@ -188,6 +188,41 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_download_one_file_at_older_revision() -> Result<(), OxenError> {
+        test::run_empty_remote_repo_test(|mut local_repo, remote_repo| async move {
+            let cloned_remote = remote_repo.clone();
+            let file_path = "hello.txt";
+            let local_path = &local_repo.path.join(file_path);
+
+            util::fs::write_to_path(local_path, "Hello World")?;
+            repositories::add(&local_repo, local_path)?;
+            let first_commit = repositories::commit(&local_repo, "Added hello.txt")?;
+
+            util::fs::write_to_path(local_path, "Goodbye World")?;
+            repositories::add(&local_repo, local_path)?;
+            repositories::commit(&local_repo, "Updated hello.txt")?;
+
+            command::config::set_remote(&mut local_repo, DEFAULT_REMOTE_NAME, cloned_remote.url())?;
+            repositories::push(&local_repo).await?;
+
+            test::run_empty_dir_test_async(|repo_dir| async move {
+                download(&remote_repo, file_path, &repo_dir, &first_commit.id).await?;
+
+                let downloaded = repo_dir.join(file_path);
+                assert_eq!(util::fs::read_from_path(&downloaded)?, "Hello World");
+
+                Ok(repo_dir)
+            })
+            .await?;
+
+            Ok(cloned_remote)
+        })
+        .await?;
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_download_dir() -> Result<(), OxenError> {
         test::run_training_data_fully_sync_remote(|local_repo, remote_repo| async move {
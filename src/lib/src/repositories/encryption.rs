@@ -0,0 +1,149 @@
+//! # Encryption
+//!
+//! Turn encryption at rest on or off for a repository. Enabling generates a key, saves
+//! it to the user config and encrypts the existing version files and chunk shards in
+//! place. Files added afterwards are encrypted as they are written, and decrypted when
+//! they are checked out or read. See `util::encryption` for the file format.
+//!
+
+use jwalk::WalkDir;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::constants::{TREE_DIR, VERSIONS_DIR};
+use crate::error::OxenError;
+use crate::model::LocalRepository;
+use crate::util;
+use crate::util::encryption::EncryptionKey;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EncryptionStatus {
+    pub key_id: Option<String>,
+    pub num_encrypted: usize,
+    pub num_plain: usize,
+}
+
+/// Generate a key for the repository, save it to the user config and encrypt all the
+/// version files and chunk shards with it. Returns the key id and where the key was saved.
+pub fn enable(repo: &mut LocalRepository) -> Result<(String, PathBuf), OxenError> {
+    if let Some(key_id) = repo.encryption_key_id() {
+        return Err(OxenError::basic_str(format!(
+            "Repository is already encrypted with key {key_id}"
+        )));
+    }
+    let key_id = uuid::Uuid::new_v4().to_string();
+    let key = EncryptionKey::generate();
+    let key_path = util::encryption::save_key(&key_id, &key)?;
+
+    // Save the key id first, so a failure part way leaves files we can still read
+    repo.set_encryption_key_id(Some(key_id.clone()));
+    repo.save_default()?;
+
+    let mut num_encrypted = 0;
+    for path in stored_files(repo) {
+        if util::encryption::encrypt_in_place(&key, &path)? {
+            num_encrypted += 1;
+        }
+    }
+    log::debug!("Encrypted {num_encrypted} files in {:?}", repo.path);
+    Ok((key_id, key_path))
+}
+
+/// Decrypt all the version files and chunk shards and stop encrypting new ones.
+/// The key is left in the user config, in case other clones still use it.
+pub fn disable(repo: &mut LocalRepository) -> Result<usize, OxenError> {
+    let Some(key) = util::encryption::repo_key(repo)? else {
+        return Err(OxenError::basic_str("Repository is not encrypted"));
+    };
+
+    let mut num_decrypted = 0;
+    for path in stored_files(repo) {
+        if util::encryption::decrypt_in_place(&key, &path)? {
+            num_decrypted += 1;
+        }
+    }
+
+    repo.set_encryption_key_id(None);
+    repo.save_default()?;
+    Ok(num_decrypted)
+}
+
+/// The key id of the repository and how many of its stored files are encrypted
+pub fn status(repo: &LocalRepository) -> Result<EncryptionStatus, OxenError> {
+    let mut status = EncryptionStatus {
+        key_id: repo.encryption_key_id().map(String::from),
+        num_encrypted: 0,
+        num_plain: 0,
+    };
+    for path in stored_files(repo) {
+        if util::encryption::is_encrypted(&path) {
+            status.num_encrypted += 1;
+        } else {
+            status.num_plain += 1;
+        }
+    }
+    Ok(status)
+}
+
+// The version files and chunk shards, the other files under .oxen are metadata
+fn stored_files(repo: &LocalRepository) -> Vec<PathBuf> {
+    let hidden_dir = util::fs::oxen_hidden_dir(&repo.path);
+    let mut files = list_files(&hidden_dir.join(VERSIONS_DIR));
+    files.extend(list_files(&hidden_dir.join(TREE_DIR).join("shards")));
+    files
+}
+
+fn list_files(dir: &Path) -> Vec<PathBuf> {
+    if !dir.exists() {
+        return vec![];
+    }
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.path())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::OxenError;
+    use crate::opts::RestoreOpts;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_enable_encrypts_versions_and_restore_decrypts() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|mut repo| {
+            let path = repo.path.join("labels.txt");
+            util::fs::write_to_path(&path, "cat\ndog")?;
+            repositories::add(&repo, &path)?;
+            repositories::commit(&repo, "Add labels")?;
+
+            let (key_id, key_path) = repositories::encryption::enable(&mut repo)?;
+            let status = repositories::encryption::status(&repo)?;
+            assert_eq!(status.key_id, Some(key_id));
+            assert!(status.num_encrypted > 0);
+            assert_eq!(status.num_plain, 0);
+
+            // New files are encrypted as they are added
+            let other = repo.path.join("other.txt");
+            util::fs::write_to_path(&other, "fish")?;
+            repositories::add(&repo, &other)?;
+            repositories::commit(&repo, "Add other")?;
+            assert_eq!(repositories::encryption::status(&repo)?.num_plain, 0);
+
+            util::fs::remove_file(&path)?;
+            repositories::restore::restore(&repo, RestoreOpts::from_path("labels.txt"))?;
+            assert_eq!(util::fs::read_from_path(&path)?, "cat\ndog");
+
+            let num_decrypted = repositories::encryption::disable(&mut repo)?;
+            assert!(num_decrypted > status.num_encrypted);
+            assert!(repo.encryption_key_id().is_none());
+
+            util::fs::remove_file(key_path)?;
+            Ok(())
+        })
+    }
+}
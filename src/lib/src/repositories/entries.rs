@@ -169,6 +169,33 @@ pub fn list_for_commit(
     }
 }
 
+/// List (path, content hash, num bytes) for every file in the commit
+pub fn list_file_hashes(
+    repo: &LocalRepository,
+    commit: &Commit,
+) -> Result<Vec<(PathBuf, String, u64)>, OxenError> {
+    match repo.min_version() {
+        MinOxenVersion::V0_10_0 => Ok(core::v0_10_0::entries::list_for_commit(repo, commit)?
+            .into_iter()
+            .map(|entry| (entry.path, entry.hash, entry.num_bytes))
+            .collect()),
+        MinOxenVersion::V0_19_0 => {
+            // The v0.19.0 commit entries only carry the file name, so walk the tree for full paths
+            let tree = repositories::tree::get_by_commit(repo, commit)?;
+            Ok(repositories::tree::list_all_files(&tree)?
+                .into_iter()
+                .map(|f| {
+                    (
+                        f.dir.join(&f.file_node.name),
+                        f.file_node.hash.to_string(),
+                        f.file_node.num_bytes,
+                    )
+                })
+                .collect())
+        }
+    }
+}
+
 pub fn count_for_commit(repo: &LocalRepository, commit: &Commit) -> Result<usize, OxenError> {
     match repo.min_version() {
         MinOxenVersion::V0_10_0 => core::v0_10_0::entries::count_for_commit(repo, commit),
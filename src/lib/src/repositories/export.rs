@@ -0,0 +1,277 @@
+//! # Export
+//!
+//! Write the files of a revision to a tar, tar.gz or zip archive, reading them straight
+//! from the version store so nothing has to be checked out.
+//!
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use crate::error::OxenError;
+use crate::model::{Commit, LocalRepository};
+use crate::repositories;
+use crate::util;
+use crate::util::encryption::MaybeDecrypted;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Tar,
+    TarGz,
+    Zip,
+}
+
+impl ExportFormat {
+    /// Pick the format from the extension of the output file
+    pub fn from_path(path: impl AsRef<Path>) -> Result<ExportFormat, OxenError> {
+        let name = path.as_ref().to_string_lossy().to_lowercase();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Ok(ExportFormat::TarGz)
+        } else if name.ends_with(".tar") {
+            Ok(ExportFormat::Tar)
+        } else if name.ends_with(".zip") {
+            Ok(ExportFormat::Zip)
+        } else {
+            Err(OxenError::basic_str(format!(
+                "Cannot tell the archive format of {name:?}, use .tar, .tar.gz, .tgz or .zip"
+            )))
+        }
+    }
+}
+
+impl std::str::FromStr for ExportFormat {
+    type Err = OxenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "tar" => Ok(ExportFormat::Tar),
+            "tar.gz" | "tgz" => Ok(ExportFormat::TarGz),
+            "zip" => Ok(ExportFormat::Zip),
+            _ => Err(OxenError::basic_str(format!(
+                "Unknown archive format {s:?}, expected tar, tar.gz or zip"
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ExportSummary {
+    pub commit: Commit,
+    pub num_files: u64,
+    pub num_bytes: u64,
+}
+
+/// Export the files at `revision` to `dst`. If `path` is set, only the files under it are
+/// exported. Paths in the archive are relative to the root of the repository.
+pub fn export(
+    repo: &LocalRepository,
+    revision: impl AsRef<str>,
+    dst: impl AsRef<Path>,
+    path: Option<&Path>,
+    format: ExportFormat,
+) -> Result<ExportSummary, OxenError> {
+    let revision = revision.as_ref();
+    let Some(commit) = repositories::revisions::get(repo, revision)? else {
+        return Err(OxenError::revision_not_found(revision.into()));
+    };
+
+    let mut files: Vec<(PathBuf, String, u64)> =
+        repositories::entries::list_file_hashes(repo, &commit)?
+            .into_iter()
+            .filter(|(file_path, _, _)| path.is_none_or(|p| file_path.starts_with(p)))
+            .collect();
+    if let Some(path) = path {
+        if files.is_empty() {
+            return Err(OxenError::path_does_not_exist(path));
+        }
+    }
+    // Sorted with the commit time as the mtime, so exports of a revision are reproducible
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    let mtime = commit.timestamp.unix_timestamp().max(0) as u64;
+
+    let dst = dst.as_ref();
+    let file = util::fs::file_create(dst)?;
+    let result = match format {
+        ExportFormat::Tar => write_tar(repo, &files, mtime, file).map(|_| ()),
+        ExportFormat::TarGz => {
+            let enc = GzEncoder::new(file, Compression::default());
+            write_tar(repo, &files, mtime, enc).and_then(|enc| {
+                enc.finish()?;
+                Ok(())
+            })
+        }
+        ExportFormat::Zip => write_zip(repo, &files, &commit, file),
+    };
+    if let Err(err) = result {
+        // Don't leave a partial archive behind
+        util::fs::remove_file(dst)?;
+        return Err(err);
+    }
+
+    log::debug!(
+        "Exported {} files at {} to {:?}",
+        files.len(),
+        commit.id,
+        dst
+    );
+    Ok(ExportSummary {
+        commit,
+        num_files: files.len() as u64,
+        num_bytes: files.iter().map(|(_, _, num_bytes)| num_bytes).sum(),
+    })
+}
+
+fn open_version(
+    repo: &LocalRepository,
+    path: &Path,
+    hash: &str,
+) -> Result<MaybeDecrypted, OxenError> {
+    let version_path = util::fs::version_path_from_node(repo, hash, path);
+    if !version_path.exists() {
+        return Err(OxenError::basic_str(format!(
+            "Missing version file for {path:?}. Run `oxen pull --all` to download it before exporting."
+        )));
+    }
+    util::encryption::open(repo, version_path)
+}
+
+fn write_tar<W: Write>(
+    repo: &LocalRepository,
+    files: &[(PathBuf, String, u64)],
+    mtime: u64,
+    writer: W,
+) -> Result<W, OxenError> {
+    let mut tar = tar::Builder::new(writer);
+    for (path, hash, _) in files {
+        let file = open_version(repo, path, hash)?;
+        let mut header = tar::Header::new_gnu();
+        header.set_size(file.len()?);
+        header.set_mode(0o644);
+        header.set_mtime(mtime);
+        header.set_cksum();
+        tar.append_data(&mut header, path, file)?;
+    }
+    Ok(tar.into_inner()?)
+}
+
+fn write_zip(
+    repo: &LocalRepository,
+    files: &[(PathBuf, String, u64)],
+    commit: &Commit,
+    writer: File,
+) -> Result<(), OxenError> {
+    let mut zip = zip::ZipWriter::new(writer);
+    let timestamp = zip::DateTime::try_from(commit.timestamp).unwrap_or_default();
+    for (path, hash, num_bytes) in files {
+        let options = zip::write::FileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .last_modified_time(timestamp)
+            .large_file(*num_bytes >= u32::MAX as u64);
+        // Zip paths always use forward slashes
+        let name = path
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        zip.start_file(name, options).map_err(zip_error)?;
+        let mut file = open_version(repo, path, hash)?;
+        io::copy(&mut file, &mut zip)?;
+    }
+    zip.finish().map_err(zip_error)?;
+    Ok(())
+}
+
+fn zip_error(err: zip::result::ZipError) -> OxenError {
+    OxenError::basic_str(format!("Could not write zip archive: {err}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use flate2::read::GzDecoder;
+    use std::fs::File;
+    use std::io::Read;
+    use std::path::{Path, PathBuf};
+
+    use crate::constants::DEFAULT_BRANCH_NAME;
+    use crate::error::OxenError;
+    use crate::repositories::export::{export, ExportFormat};
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_export_format_from_path() -> Result<(), OxenError> {
+        assert_eq!(ExportFormat::from_path("data.tar")?, ExportFormat::Tar);
+        assert_eq!(ExportFormat::from_path("data.TAR.GZ")?, ExportFormat::TarGz);
+        assert_eq!(ExportFormat::from_path("data.tgz")?, ExportFormat::TarGz);
+        assert_eq!(ExportFormat::from_path("data.zip")?, ExportFormat::Zip);
+        assert!(ExportFormat::from_path("data.rar").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_export_subdir_to_tar_gz_and_zip() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let train_dir = Path::new("train");
+            let export_dir = test::create_empty_dir(test::test_run_dir())?;
+
+            let tar_path = export_dir.join("train.tar.gz");
+            let summary = export(
+                &repo,
+                DEFAULT_BRANCH_NAME,
+                &tar_path,
+                Some(train_dir),
+                ExportFormat::TarGz,
+            )?;
+            let expected = util::fs::rcount_files_in_dir(&repo.path.join(train_dir)) as u64;
+            assert_eq!(summary.num_files, expected);
+
+            let mut archive = tar::Archive::new(GzDecoder::new(File::open(&tar_path)?));
+            let mut num_entries = 0;
+            for entry in archive.entries()? {
+                let mut entry = entry?;
+                let path: PathBuf = entry.path()?.to_path_buf();
+                assert!(path.starts_with(train_dir));
+                let mut contents = Vec::new();
+                entry.read_to_end(&mut contents)?;
+                assert_eq!(contents, std::fs::read(repo.path.join(&path))?);
+                num_entries += 1;
+            }
+            assert_eq!(num_entries, expected);
+
+            let zip_path = export_dir.join("all.zip");
+            let summary = export(
+                &repo,
+                DEFAULT_BRANCH_NAME,
+                &zip_path,
+                None,
+                ExportFormat::Zip,
+            )?;
+            let mut zip = zip::ZipArchive::new(File::open(&zip_path)?)
+                .map_err(|err| OxenError::basic_str(err.to_string()))?;
+            assert_eq!(zip.len() as u64, summary.num_files);
+            let mut readme = String::new();
+            zip.by_name("README.md")
+                .map_err(|err| OxenError::basic_str(err.to_string()))?
+                .read_to_string(&mut readme)?;
+            assert_eq!(
+                readme,
+                util::fs::read_from_path(repo.path.join("README.md"))?
+            );
+
+            // Missing paths are an error instead of an empty archive
+            let result = export(
+                &repo,
+                DEFAULT_BRANCH_NAME,
+                export_dir.join("none.tar"),
+                Some(Path::new("does-not-exist")),
+                ExportFormat::Tar,
+            );
+            assert!(result.is_err());
+
+            util::fs::remove_dir_all(export_dir)?;
+            Ok(())
+        })
+    }
+}
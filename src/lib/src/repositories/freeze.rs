@@ -0,0 +1,264 @@
+//! # Freeze
+//!
+//! Archive a repository we must keep but rarely touch. A frozen repository
+//! rejects writes, drops its hot caches, and can optionally recompress its
+//! version files with zstd at the max level. Recompressed versions are read
+//! the same way as any compressed version, so a frozen repository can still be
+//! read, diffed and checked out. Thawing undoes all of this.
+//!
+
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use jwalk::WalkDir;
+use rayon::prelude::*;
+use time::OffsetDateTime;
+
+use crate::constants::{CACHE_DIR, FILES_DIR, FROZEN_FILE, VERSIONS_DIR, VERSION_FILE_NAME};
+use crate::core::versions::MinOxenVersion;
+use crate::error::OxenError;
+use crate::model::{FreezeOpts, LocalRepository, RepoFreeze};
+use crate::repositories;
+use crate::util;
+use crate::util::compression;
+use crate::util::encryption::Encoding;
+
+// Each zstd stream at the max level holds a window of up to a few hundred MB, so only a
+// few versions are recompressed at a time
+const MAX_RECOMPRESS_THREADS: usize = 4;
+
+/// Get how the repository was frozen, None if it is not frozen
+pub fn get(repo: &LocalRepository) -> Result<Option<RepoFreeze>, OxenError> {
+    let path = util::fs::oxen_hidden_dir(&repo.path).join(FROZEN_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = util::fs::read_from_path(&path)?;
+    Ok(Some(serde_json::from_str(&contents)?))
+}
+
+pub fn is_frozen(repo: &LocalRepository) -> bool {
+    util::fs::oxen_hidden_dir(&repo.path)
+        .join(FROZEN_FILE)
+        .exists()
+}
+
+/// Errors if the repository is frozen, call before anything that writes to it
+pub fn ensure_not_frozen(repo: &LocalRepository) -> Result<(), OxenError> {
+    if is_frozen(repo) {
+        return Err(OxenError::repo_is_frozen());
+    }
+    Ok(())
+}
+
+/// Freeze the repository so it is read only and cheaper to store
+pub fn freeze(repo: &LocalRepository, opts: &FreezeOpts) -> Result<RepoFreeze, OxenError> {
+    ensure_not_frozen(repo)?;
+
+    // Open workspaces are writes in progress, do not throw them away
+    let workspaces = repositories::workspaces::list(repo)?;
+    if !workspaces.is_empty() {
+        return Err(OxenError::basic_str(format!(
+            "Cannot freeze a repository with {} open workspace(s), commit or delete them first",
+            workspaces.len()
+        )));
+    }
+
+    let oxen_dir = util::fs::oxen_hidden_dir(&repo.path);
+    let num_bytes_before = num_bytes(&oxen_dir);
+
+    // Caches are rebuilt on demand, so they are safe to drop
+    let cache_dir = oxen_dir.join(CACHE_DIR);
+    if cache_dir.exists() {
+        util::fs::remove_dir_all(&cache_dir)?;
+    }
+
+    let versions_dir = oxen_dir.join(VERSIONS_DIR);
+    let recompressed = opts.recompress && versions_dir.exists();
+    if recompressed {
+        // Only the v0.19 readers decode compressed versions
+        if repo.min_version() != MinOxenVersion::V0_19_0 {
+            return Err(OxenError::basic_str(
+                "Recompressing needs a repository on v0.19 or later, run `oxen migrate` first",
+            ));
+        }
+        let level = *zstd::compression_level_range().end();
+        let version_files = version_files(repo);
+        let num_threads = util::concurrency::num_threads_for_items(version_files.len())
+            .clamp(1, MAX_RECOMPRESS_THREADS);
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(num_threads)
+            .build()
+            .map_err(|e| OxenError::basic_str(format!("Could not start freeze workers: {e}")))?;
+        let num_recompressed = pool
+            .install(|| {
+                version_files
+                    .par_iter()
+                    .map(|path| recompress_version(repo, path, level))
+                    .collect::<Result<Vec<bool>, OxenError>>()
+            })?
+            .into_iter()
+            .filter(|recompressed| *recompressed)
+            .count();
+        log::debug!("freeze recompressed {num_recompressed} version files");
+    }
+
+    let frozen = RepoFreeze {
+        frozen_at: OffsetDateTime::now_utc(),
+        recompressed,
+        num_bytes_before,
+        num_bytes_after: num_bytes(&oxen_dir),
+    };
+    util::fs::write_to_path(oxen_dir.join(FROZEN_FILE), serde_json::to_string(&frozen)?)?;
+    Ok(frozen)
+}
+
+/// Thaw a frozen repository, decompressing its version files if they were recompressed
+pub fn thaw(repo: &LocalRepository) -> Result<RepoFreeze, OxenError> {
+    let Some(frozen) = get(repo)? else {
+        return Err(OxenError::basic_str("Repository is not frozen"));
+    };
+
+    let oxen_dir = util::fs::oxen_hidden_dir(&repo.path);
+    if frozen.recompressed && repo.compression_level().is_none() {
+        // Repositories that compress their versions keep them compressed
+        version_files(repo)
+            .par_iter()
+            .map(|path| decompress_version(path))
+            .collect::<Result<Vec<()>, OxenError>>()?;
+    }
+
+    util::fs::remove_file(oxen_dir.join(FROZEN_FILE))?;
+    Ok(frozen)
+}
+
+fn version_files(repo: &LocalRepository) -> Vec<PathBuf> {
+    let files_dir = util::fs::oxen_hidden_dir(&repo.path)
+        .join(VERSIONS_DIR)
+        .join(FILES_DIR);
+    WalkDir::new(files_dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            Encoding::strip(path)
+                .file_stem()
+                .is_some_and(|stem| stem == VERSION_FILE_NAME)
+        })
+        .collect()
+}
+
+// Compress the version again at `level`, keeping it only if it got smaller. Encrypted
+// versions do not get any smaller and are left alone.
+fn recompress_version(repo: &LocalRepository, path: &Path, level: i32) -> Result<bool, OxenError> {
+    let current = Encoding::of(path);
+    if current.encrypted || !Encoding::can_encode(path) {
+        return Ok(false);
+    }
+    let plain = util::encryption::plain_path(repo, path)?;
+    let compressed = Encoding {
+        compressed: true,
+        encrypted: false,
+    };
+    let dst = compressed.path(path);
+    let tmp_path = dst.with_extension(format!("tmp-{}", uuid::Uuid::new_v4()));
+    compression::compress(level, File::open(plain.path())?, File::create(&tmp_path)?)?;
+    if std::fs::metadata(&tmp_path)?.len() >= std::fs::metadata(path)?.len() {
+        util::fs::remove_file(&tmp_path)?;
+        return Ok(false);
+    }
+    // Version files may have been made read only by hard link checkouts
+    let permissions = std::fs::metadata(path)?.permissions();
+    std::fs::rename(&tmp_path, &dst)?;
+    std::fs::set_permissions(&dst, permissions)?;
+    if dst != path {
+        util::fs::remove_file(path)?;
+    }
+    Ok(true)
+}
+
+fn decompress_version(path: &Path) -> Result<(), OxenError> {
+    compression::decompress_in_place(path)?;
+    Ok(())
+}
+
+fn num_bytes(dir: &Path) -> u64 {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| metadata.is_file())
+        .map(|metadata| metadata.len())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::error::OxenError;
+    use crate::model::FreezeOpts;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_frozen_repo_rejects_writes_until_thawed() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            repositories::freeze::freeze(&repo, &FreezeOpts::default())?;
+            assert!(repositories::freeze::is_frozen(&repo));
+
+            let new_file = repo.path.join("new_file.txt");
+            util::fs::write_to_path(&new_file, "Can't touch this")?;
+            let result = repositories::add(&repo, &new_file);
+            assert!(matches!(result, Err(OxenError::RepoFrozen(_))));
+            let result = repositories::commit(&repo, "Should not commit");
+            assert!(matches!(result, Err(OxenError::RepoFrozen(_))));
+
+            // Reads still work
+            repositories::commits::head_commit(&repo)?;
+
+            repositories::freeze::thaw(&repo)?;
+            assert!(!repositories::freeze::is_frozen(&repo));
+            repositories::add(&repo, &new_file)?;
+            repositories::commit(&repo, "Back to work")?;
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_freeze_recompress_round_trip() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let commit = repositories::commits::head_commit(&repo)?;
+            let hashes = repositories::entries::list_file_hashes(&repo, &commit)?;
+            let path = Path::new("annotations")
+                .join("train")
+                .join("bounding_box.csv");
+            let contents = util::fs::read_from_path(repo.path.join(&path))?;
+
+            let opts = FreezeOpts { recompress: true };
+            let frozen = repositories::freeze::freeze(&repo, &opts)?;
+            assert!(frozen.recompressed);
+            assert!(frozen.num_bytes_after < frozen.num_bytes_before);
+            for (_, hash, _) in hashes.iter() {
+                assert!(util::fs::version_path_from_hash(&repo, hash).exists());
+            }
+
+            // Frozen versions still read back as they were committed
+            let version = repositories::revisions::get_version_file(&repo, &commit.id, &path)?;
+            assert_eq!(util::fs::read_from_path(version.path())?, contents);
+
+            repositories::freeze::thaw(&repo)?;
+            for (_, hash, _) in hashes.iter() {
+                let version_path = util::fs::version_path_from_hash(&repo, hash);
+                assert!(!util::compression::is_compressed(&version_path));
+            }
+            let version = repositories::revisions::get_version_file(&repo, &commit.id, &path)?;
+            assert_eq!(util::fs::read_from_path(version.path())?, contents);
+
+            Ok(())
+        })
+    }
+}
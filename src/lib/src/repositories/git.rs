@@ -0,0 +1,821 @@
+//! # Git
+//!
+//! Move the history of a git repository into Oxen.
+//!
+//! Each local branch is replayed commit by commit, following first parents, into a new
+//! Oxen repository. Files stored with git-LFS are resolved from `.git/lfs/objects` so the
+//! real data ends up in the version store instead of the pointer. The original commit id,
+//! date and any merged parents are kept as `Git-*` trailers on the commit message.
+//!
+//! Going the other way, `fast_export` writes the history of a revision as a
+//! `git fast-import` stream, optionally swapping large files for LFS pointers.
+//!
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::{Component, Path, PathBuf};
+
+use gix::ObjectId;
+use sha2::{Digest, Sha256};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use crate::constants::{DEFAULT_BRANCH_NAME, OXEN_HIDDEN_DIR};
+use crate::core;
+use crate::error::OxenError;
+use crate::model::{Commit, LocalRepository, User};
+use crate::repositories;
+use crate::util;
+
+const LFS_POINTER_PREFIX: &str = "version https://git-lfs.github.com/spec/";
+// Pointers are ~130 bytes, anything much bigger is a real file
+const MAX_LFS_POINTER_SIZE: usize = 1024;
+
+#[derive(Debug, Clone, Default)]
+pub struct GitImportSummary {
+    pub num_commits: usize,
+    /// Git commits that did not change any files
+    pub num_empty_commits: usize,
+    pub branches: Vec<String>,
+    /// Branches that do not share history with the checked out branch
+    pub skipped_branches: Vec<String>,
+    pub num_lfs_files: usize,
+    /// LFS files whose object was not in .git/lfs/objects, these keep their pointer
+    pub missing_lfs_files: Vec<PathBuf>,
+}
+
+struct GitCommit {
+    id: ObjectId,
+    parents: Vec<ObjectId>,
+    tree: ObjectId,
+    message: String,
+    author: User,
+    seconds: i64,
+}
+
+/// Replay the history of the git repository at `git_path` into a new Oxen repository at
+/// `dst`. The branch checked out in git is replayed first and is checked out in Oxen.
+pub fn import(
+    git_path: impl AsRef<Path>,
+    dst: impl AsRef<Path>,
+) -> Result<GitImportSummary, OxenError> {
+    let git_path = git_path.as_ref();
+    let dst = dst.as_ref();
+    let git = gix::open(git_path).map_err(|err| {
+        OxenError::basic_str(format!("{git_path:?} is not a git repository: {err}"))
+    })?;
+    if dst.exists() && std::fs::read_dir(dst)?.next().is_some() {
+        return Err(OxenError::basic_str(format!(
+            "Cannot import into {dst:?}, the directory is not empty"
+        )));
+    }
+
+    let (head_branch, branches) = list_branches(&git)?;
+    let Some(head_branch) = head_branch.or_else(|| branches.keys().next().cloned()) else {
+        return Err(OxenError::basic_str(format!(
+            "No branches to import in {git_path:?}"
+        )));
+    };
+
+    let repo = repositories::init(dst)?;
+    let mut importer = Importer {
+        git: &git,
+        repo: &repo,
+        lfs_dir: git.common_dir().join("lfs").join("objects"),
+        files: HashMap::new(),
+        commit_ids: HashMap::new(),
+        summary: GitImportSummary::default(),
+    };
+
+    importer.replay(&first_parent_chain(&git, branches[&head_branch])?)?;
+    if head_branch != DEFAULT_BRANCH_NAME {
+        repositories::branches::rename_current_branch(&repo, &head_branch)?;
+    }
+    importer.summary.branches.push(head_branch.clone());
+
+    for (name, tip) in &branches {
+        if *name == head_branch {
+            continue;
+        }
+        let chain = first_parent_chain(&git, *tip)?;
+        // Branch off from the newest commit that has already been replayed
+        let Some(fork) = chain
+            .iter()
+            .rposition(|id| importer.commit_ids.contains_key(id))
+        else {
+            log::warn!(
+                "Skipping git branch {name}, it has no history in common with {head_branch}"
+            );
+            importer.summary.skipped_branches.push(name.clone());
+            continue;
+        };
+        repositories::branches::create(&repo, name, &importer.commit_ids[&chain[fork]])?;
+        repositories::branches::set_head(&repo, name)?;
+        importer.checkout(read_commit(&git, chain[fork])?.tree)?;
+        importer.replay(&chain[fork + 1..])?;
+        importer.summary.branches.push(name.clone());
+    }
+
+    // Leave the working directory on the branch that was checked out in git
+    repositories::branches::set_head(&repo, &head_branch)?;
+    importer.checkout(read_commit(&git, branches[&head_branch])?.tree)?;
+    Ok(importer.summary)
+}
+
+struct Importer<'a> {
+    git: &'a gix::Repository,
+    repo: &'a LocalRepository,
+    lfs_dir: PathBuf,
+    // The files in the working directory and the blobs they were written from
+    files: HashMap<PathBuf, ObjectId>,
+    // Git commit id -> oxen commit id
+    commit_ids: HashMap<ObjectId, String>,
+    summary: GitImportSummary,
+}
+
+impl Importer<'_> {
+    fn replay(&mut self, chain: &[ObjectId]) -> Result<(), OxenError> {
+        for id in chain {
+            if self.commit_ids.contains_key(id) {
+                continue;
+            }
+            let commit = read_commit(self.git, *id)?;
+            let changed = self.checkout(commit.tree)?;
+            log::debug!("Replaying git commit {} with {} changes", id, changed.len());
+
+            let oxen_commit = if changed.is_empty() {
+                None
+            } else {
+                repositories::add_all(self.repo, &changed)?;
+                match core::v0_19_0::commits::commit_with_user(
+                    self.repo,
+                    commit_message(&commit),
+                    &commit.author,
+                ) {
+                    Ok(oxen_commit) => Some(oxen_commit),
+                    Err(OxenError::NothingToCommit(_)) => None,
+                    Err(err) => return Err(err),
+                }
+            };
+            match oxen_commit {
+                Some(oxen_commit) => {
+                    self.summary.num_commits += 1;
+                    self.commit_ids.insert(*id, oxen_commit.id);
+                }
+                None => {
+                    // Oxen commits need changes, so point this one at its parent instead
+                    self.summary.num_empty_commits += 1;
+                    if let Some(head) = repositories::commits::head_commit_maybe(self.repo)? {
+                        self.commit_ids.insert(*id, head.id);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Make the working directory match the git tree, returning the paths that changed
+    fn checkout(&mut self, tree: ObjectId) -> Result<Vec<PathBuf>, OxenError> {
+        let next = list_tree(self.git, tree)?;
+        let mut changed = Vec::new();
+        for path in self.files.keys() {
+            if !next.contains_key(path) {
+                let full_path = self.repo.path.join(path);
+                util::fs::remove_file(&full_path)?;
+                remove_empty_parents(&self.repo.path, &full_path)?;
+                changed.push(full_path);
+            }
+        }
+        for (path, blob) in &next {
+            if self.files.get(path) == Some(blob) {
+                continue;
+            }
+            let full_path = self.repo.path.join(path);
+            self.write_blob(path, *blob, &full_path)?;
+            changed.push(full_path);
+        }
+        self.files = next;
+        Ok(changed)
+    }
+
+    fn write_blob(&mut self, path: &Path, blob: ObjectId, dst: &Path) -> Result<(), OxenError> {
+        if let Some(parent) = dst.parent() {
+            util::fs::create_dir_all(parent)?;
+        }
+        let data = self.git.find_object(blob).map_err(git_error)?.detach().data;
+        if let Some(oid) = lfs_pointer_oid(&data) {
+            self.summary.num_lfs_files += 1;
+            let object = self.lfs_dir.join(&oid[0..2]).join(&oid[2..4]).join(&oid);
+            if object.exists() {
+                util::fs::copy(&object, dst)?;
+                return Ok(());
+            }
+            log::warn!("LFS object {oid} for {path:?} is missing, keeping the pointer file");
+            if !self.summary.missing_lfs_files.contains(&path.to_path_buf()) {
+                self.summary.missing_lfs_files.push(path.to_path_buf());
+            }
+        }
+        std::fs::write(dst, data).map_err(|err| OxenError::file_error(dst, err))
+    }
+}
+
+fn git_error(err: impl Display) -> OxenError {
+    OxenError::basic_str(format!("Could not read git repository: {err}"))
+}
+
+// The checked out branch, if any, and the tip of every local branch
+fn list_branches(
+    git: &gix::Repository,
+) -> Result<(Option<String>, BTreeMap<String, ObjectId>), OxenError> {
+    let head_branch = git
+        .head_name()
+        .map_err(git_error)?
+        .map(|name| name.shorten().to_string());
+    let mut branches = BTreeMap::new();
+    let references = git.references().map_err(git_error)?;
+    for reference in references.local_branches().map_err(git_error)? {
+        let mut reference = reference.map_err(git_error)?;
+        let name = reference.name().shorten().to_string();
+        let tip = reference.peel_to_id_in_place().map_err(git_error)?.detach();
+        branches.insert(name, tip);
+    }
+    // An unborn HEAD has no commits to import
+    let head_branch = head_branch.filter(|name| branches.contains_key(name));
+    Ok((head_branch, branches))
+}
+
+// Oldest first
+fn first_parent_chain(git: &gix::Repository, tip: ObjectId) -> Result<Vec<ObjectId>, OxenError> {
+    let mut chain = vec![tip];
+    let mut current = tip;
+    while let Some(parent) = read_commit(git, current)?.parents.first() {
+        chain.push(*parent);
+        current = *parent;
+    }
+    chain.reverse();
+    Ok(chain)
+}
+
+fn read_commit(git: &gix::Repository, id: ObjectId) -> Result<GitCommit, OxenError> {
+    let commit = git
+        .find_object(id)
+        .map_err(git_error)?
+        .try_into_commit()
+        .map_err(git_error)?;
+    let author = commit.author().map_err(git_error)?;
+    Ok(GitCommit {
+        id,
+        parents: commit.parent_ids().map(|id| id.detach()).collect(),
+        tree: commit.tree_id().map_err(git_error)?.detach(),
+        message: commit.message_raw_sloppy().to_string(),
+        author: User {
+            name: author.name.to_string(),
+            email: author.email.to_string(),
+        },
+        seconds: author.time.seconds,
+    })
+}
+
+fn commit_message(commit: &GitCommit) -> String {
+    let mut trailers = vec![format!("Git-Commit: {}", commit.id)];
+    if let Some(date) = OffsetDateTime::from_unix_timestamp(commit.seconds)
+        .ok()
+        .and_then(|date| date.format(&Rfc3339).ok())
+    {
+        trailers.push(format!("Git-Date: {date}"));
+    }
+    // Only first parents are replayed, note what else was merged in
+    for parent in commit.parents.iter().skip(1) {
+        trailers.push(format!("Git-Merged: {parent}"));
+    }
+    let message = commit.message.trim();
+    let message = if message.is_empty() {
+        "Imported from git"
+    } else {
+        message
+    };
+    format!("{message}\n\n{}", trailers.join("\n"))
+}
+
+// Every blob in the tree by path. Submodules and symlinks are skipped, as is anything
+// that would land in the .oxen dir.
+fn list_tree(
+    git: &gix::Repository,
+    tree: ObjectId,
+) -> Result<HashMap<PathBuf, ObjectId>, OxenError> {
+    let tree = git
+        .find_object(tree)
+        .map_err(git_error)?
+        .try_into_tree()
+        .map_err(git_error)?;
+    let mut recorder = gix::traverse::tree::Recorder::default();
+    tree.traverse()
+        .breadthfirst(&mut recorder)
+        .map_err(git_error)?;
+
+    let mut files = HashMap::new();
+    for entry in recorder.records {
+        if !entry.mode.is_blob() {
+            continue;
+        }
+        let path = PathBuf::from(entry.filepath.to_string());
+        let is_valid = path.components().all(|c| match c {
+            Component::Normal(name) => name != OXEN_HIDDEN_DIR,
+            _ => false,
+        });
+        if is_valid {
+            files.insert(path, entry.oid);
+        } else {
+            log::warn!("Skipping git path {:?}", path);
+        }
+    }
+    Ok(files)
+}
+
+fn lfs_pointer_oid(data: &[u8]) -> Option<String> {
+    if data.len() > MAX_LFS_POINTER_SIZE {
+        return None;
+    }
+    let text = std::str::from_utf8(data).ok()?;
+    if !text.starts_with(LFS_POINTER_PREFIX) {
+        return None;
+    }
+    text.lines()
+        .find_map(|line| line.strip_prefix("oid sha256:"))
+        .map(|oid| oid.trim().to_string())
+        .filter(|oid| oid.len() == 64 && oid.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+fn remove_empty_parents(root: &Path, path: &Path) -> Result<(), OxenError> {
+    let mut dir = path.parent();
+    while let Some(current) = dir {
+        if current == root || std::fs::read_dir(current)?.next().is_some() {
+            break;
+        }
+        util::fs::remove_dir_all(current)?;
+        dir = current.parent();
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FastExportOpts {
+    /// Files larger than this many bytes are written as LFS pointers
+    pub lfs_threshold: Option<u64>,
+    /// Where to write the LFS objects, laid out like `.git/lfs/objects`
+    pub lfs_objects_dir: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FastExportSummary {
+    pub num_commits: usize,
+    pub num_blobs: usize,
+    pub num_lfs_files: usize,
+}
+
+/// Write the history of `revision` to `writer` as a `git fast-import` stream.
+///
+/// Commits land on `refs/heads/<revision>` if the revision is a branch, otherwise on
+/// `refs/heads/oxen-export`. Each commit only lists the files that changed from its first
+/// parent, and file contents are written once no matter how many commits share them.
+pub fn fast_export(
+    repo: &LocalRepository,
+    revision: impl AsRef<str>,
+    writer: &mut impl Write,
+    opts: &FastExportOpts,
+) -> Result<FastExportSummary, OxenError> {
+    let revision = revision.as_ref();
+    if repositories::revisions::get(repo, revision)?.is_none() {
+        return Err(OxenError::revision_not_found(revision.into()));
+    }
+    let git_ref = match repositories::branches::get_by_name(repo, revision)? {
+        Some(branch) => format!("refs/heads/{}", branch.name),
+        None => "refs/heads/oxen-export".to_string(),
+    };
+    let commits = parents_first(repositories::commits::list_from(repo, revision)?);
+
+    let mut exporter = FastExporter {
+        repo,
+        opts,
+        writer,
+        blob_marks: HashMap::new(),
+        commit_marks: HashMap::new(),
+        next_mark: 1,
+        summary: FastExportSummary::default(),
+    };
+    writeln!(exporter.writer, "feature done")?;
+    let mut previous: Option<(String, HashMap<PathBuf, (String, u64)>)> = None;
+    for commit in &commits {
+        let files = list_files(repo, commit)?;
+        // In a linear history the parent was just written, so its files are already listed
+        let parent_files = match (commit.parent_ids.first(), previous) {
+            (Some(parent_id), Some((id, files))) if *parent_id == id => files,
+            (Some(parent_id), _) => match repositories::commits::get_by_id(repo, parent_id)? {
+                Some(parent) => list_files(repo, &parent)?,
+                None => HashMap::new(),
+            },
+            (None, _) => HashMap::new(),
+        };
+        exporter.write_commit(commit, &git_ref, &parent_files, &files)?;
+        previous = Some((commit.id.clone(), files));
+    }
+    writeln!(exporter.writer, "done")?;
+    exporter.writer.flush()?;
+    Ok(exporter.summary)
+}
+
+struct FastExporter<'a, W: Write> {
+    repo: &'a LocalRepository,
+    opts: &'a FastExportOpts,
+    writer: &'a mut W,
+    // Oxen file hash -> mark of the blob written for it
+    blob_marks: HashMap<String, usize>,
+    commit_marks: HashMap<String, usize>,
+    next_mark: usize,
+    summary: FastExportSummary,
+}
+
+impl<W: Write> FastExporter<'_, W> {
+    fn mark(&mut self) -> usize {
+        let mark = self.next_mark;
+        self.next_mark += 1;
+        mark
+    }
+
+    fn write_commit(
+        &mut self,
+        commit: &Commit,
+        git_ref: &str,
+        parent_files: &HashMap<PathBuf, (String, u64)>,
+        files: &HashMap<PathBuf, (String, u64)>,
+    ) -> Result<(), OxenError> {
+        let mut changed: Vec<(&PathBuf, usize)> = Vec::new();
+        let mut paths: Vec<&PathBuf> = files.keys().collect();
+        paths.sort();
+        for path in paths {
+            let (hash, num_bytes) = &files[path];
+            if parent_files.get(path).map(|(h, _)| h) == Some(hash) {
+                continue;
+            }
+            let mark = self.write_blob(path, hash, *num_bytes)?;
+            changed.push((path, mark));
+        }
+        let mut removed: Vec<&PathBuf> = parent_files
+            .keys()
+            .filter(|path| !files.contains_key(*path))
+            .collect();
+        removed.sort();
+
+        let mark = self.mark();
+        let signature = format!(
+            "{} <{}> {} {}",
+            sanitize_ident(&commit.author),
+            sanitize_ident(&commit.email),
+            commit.timestamp.unix_timestamp(),
+            format_offset(commit.timestamp.offset().whole_minutes())
+        );
+        writeln!(self.writer, "commit {git_ref}")?;
+        writeln!(self.writer, "mark :{mark}")?;
+        writeln!(self.writer, "author {signature}")?;
+        writeln!(self.writer, "committer {signature}")?;
+        write_data(&mut *self.writer, commit.message.as_bytes())?;
+        for (i, parent_id) in commit.parent_ids.iter().enumerate() {
+            if let Some(parent_mark) = self.commit_marks.get(parent_id) {
+                let command = if i == 0 { "from" } else { "merge" };
+                writeln!(self.writer, "{command} :{parent_mark}")?;
+            }
+        }
+        for path in removed {
+            writeln!(self.writer, "D {}", quote_path(path))?;
+        }
+        for (path, blob_mark) in changed {
+            writeln!(self.writer, "M 100644 :{blob_mark} {}", quote_path(path))?;
+        }
+        writeln!(self.writer)?;
+
+        self.commit_marks.insert(commit.id.clone(), mark);
+        self.summary.num_commits += 1;
+        Ok(())
+    }
+
+    fn write_blob(&mut self, path: &Path, hash: &str, num_bytes: u64) -> Result<usize, OxenError> {
+        if let Some(mark) = self.blob_marks.get(hash) {
+            return Ok(*mark);
+        }
+        let version_path = util::fs::version_path_from_node(self.repo, hash, path);
+        if !version_path.exists() {
+            return Err(OxenError::basic_str(format!(
+                "Missing version file for {path:?}. Run `oxen pull --all` to download it before exporting."
+            )));
+        }
+        let version_path = util::encryption::plain_path(self.repo, &version_path)?;
+
+        let mark = self.mark();
+        writeln!(self.writer, "blob")?;
+        writeln!(self.writer, "mark :{mark}")?;
+        if self
+            .opts
+            .lfs_threshold
+            .is_some_and(|threshold| num_bytes > threshold)
+        {
+            let oid = self.write_lfs_object(version_path.path())?;
+            let pointer = format!("{LFS_POINTER_PREFIX}v1\noid sha256:{oid}\nsize {num_bytes}\n");
+            write_data(&mut *self.writer, pointer.as_bytes())?;
+            self.summary.num_lfs_files += 1;
+        } else {
+            let mut file = File::open(version_path.path())?;
+            let len = file.metadata()?.len();
+            writeln!(self.writer, "data {len}")?;
+            io::copy(&mut file, &mut *self.writer)?;
+            writeln!(self.writer)?;
+        }
+        self.blob_marks.insert(hash.to_string(), mark);
+        self.summary.num_blobs += 1;
+        Ok(mark)
+    }
+
+    // Hash the file for its LFS oid, copying it to the objects dir if there is one
+    fn write_lfs_object(&self, version_path: &Path) -> Result<String, OxenError> {
+        let mut hasher = Sha256::new();
+        io::copy(&mut File::open(version_path)?, &mut hasher)?;
+        let oid = format!("{:x}", hasher.finalize());
+        if let Some(objects_dir) = &self.opts.lfs_objects_dir {
+            let dir = objects_dir.join(&oid[0..2]).join(&oid[2..4]);
+            if !dir.join(&oid).exists() {
+                util::fs::create_dir_all(&dir)?;
+                util::fs::copy(version_path, dir.join(&oid))?;
+            }
+        }
+        Ok(oid)
+    }
+}
+
+fn list_files(
+    repo: &LocalRepository,
+    commit: &Commit,
+) -> Result<HashMap<PathBuf, (String, u64)>, OxenError> {
+    Ok(repositories::entries::list_file_hashes(repo, commit)?
+        .into_iter()
+        .map(|(path, hash, num_bytes)| (path, (hash, num_bytes)))
+        .collect())
+}
+
+// fast-import needs every parent written before its children
+fn parents_first(commits: Vec<Commit>) -> Vec<Commit> {
+    let mut by_id: HashMap<String, Commit> = commits
+        .iter()
+        .map(|commit| (commit.id.clone(), commit.clone()))
+        .collect();
+    let mut visited = HashSet::new();
+    let mut ordered = Vec::with_capacity(commits.len());
+    // Oldest first so the order is stable, with an explicit stack to handle deep histories
+    for commit in commits.iter().rev() {
+        let mut stack = vec![(commit.id.clone(), false)];
+        while let Some((id, parents_done)) = stack.pop() {
+            if parents_done {
+                if let Some(commit) = by_id.remove(&id) {
+                    ordered.push(commit);
+                }
+                continue;
+            }
+            if !visited.insert(id.clone()) {
+                continue;
+            }
+            stack.push((id.clone(), true));
+            if let Some(commit) = by_id.get(&id) {
+                for parent_id in commit.parent_ids.iter().rev() {
+                    if !visited.contains(parent_id) {
+                        stack.push((parent_id.clone(), false));
+                    }
+                }
+            }
+        }
+    }
+    ordered
+}
+
+fn write_data(writer: &mut impl Write, data: &[u8]) -> Result<(), OxenError> {
+    writeln!(writer, "data {}", data.len())?;
+    writer.write_all(data)?;
+    writeln!(writer)?;
+    Ok(())
+}
+
+fn sanitize_ident(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| !matches!(c, '<' | '>' | '\n'))
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+fn format_offset(minutes: i16) -> String {
+    let sign = if minutes < 0 { '-' } else { '+' };
+    let minutes = minutes.unsigned_abs();
+    format!("{sign}{:02}{:02}", minutes / 60, minutes % 60)
+}
+
+// Paths with a newline, a leading quote or a backslash are written as C-style strings
+fn quote_path(path: &Path) -> String {
+    let path = path
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    if !path.starts_with('"') && !path.contains(['\n', '\\']) {
+        return path;
+    }
+    let mut quoted = String::from("\"");
+    for c in path.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::path::Path;
+    use std::process::{Command, Stdio};
+
+    use crate::error::OxenError;
+    use crate::model::LocalRepository;
+    use crate::repositories;
+    use crate::repositories::git::{lfs_pointer_oid, quote_path, FastExportOpts};
+    use crate::test;
+    use crate::util;
+
+    fn git(dir: &Path, args: &[&str]) -> Result<(), OxenError> {
+        let status = Command::new("git")
+            .current_dir(dir)
+            .args(["-c", "user.name=Ox", "-c", "user.email=ox@oxen.ai"])
+            .args(args)
+            .status()?;
+        assert!(status.success(), "git {:?} failed", args);
+        Ok(())
+    }
+
+    fn git_output(dir: &Path, args: &[&str]) -> Result<String, OxenError> {
+        let output = Command::new("git").current_dir(dir).args(args).output()?;
+        assert!(output.status.success(), "git {:?} failed", args);
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    #[test]
+    fn test_quote_path() {
+        assert_eq!(quote_path(Path::new("train/cat 1.jpg")), "train/cat 1.jpg");
+        assert_eq!(quote_path(Path::new("\"odd\"")), "\"\\\"odd\\\"\"");
+        assert_eq!(quote_path(Path::new("a\nb")), "\"a\\nb\"");
+    }
+
+    #[test]
+    fn test_lfs_pointer_oid() {
+        let oid = "4d7a214614ab2935c943f9e0ff69d22eadbb8f32b1258daaa5e2ca24d17e2393";
+        let pointer =
+            format!("version https://git-lfs.github.com/spec/v1\noid sha256:{oid}\nsize 12345\n");
+        assert_eq!(lfs_pointer_oid(pointer.as_bytes()), Some(oid.to_string()));
+        assert_eq!(lfs_pointer_oid(b"label,count\ncat,2\n"), None);
+    }
+
+    #[test]
+    fn test_import_git_history_with_branches_and_lfs() -> Result<(), OxenError> {
+        let git_dir = test::create_empty_dir(test::test_run_dir())?;
+        let oxen_dir = test::test_run_dir().join(format!("git-import-{}", uuid::Uuid::new_v4()));
+
+        git(&git_dir, &["init", "-q"])?;
+        git(&git_dir, &["symbolic-ref", "HEAD", "refs/heads/main"])?;
+        util::fs::write_to_path(git_dir.join("README.md"), "# Pets")?;
+        util::fs::create_dir_all(git_dir.join("data"))?;
+        util::fs::write_to_path(git_dir.join("data").join("labels.csv"), "label\ncat\n")?;
+        git(&git_dir, &["add", "."])?;
+        git(&git_dir, &["commit", "-q", "-m", "Add labels"])?;
+
+        // An LFS pointer with its object stored the way git-lfs does
+        let image = "not really a png";
+        let oid = util::hasher::hash_str_sha256(image);
+        let object = git_dir
+            .join(".git/lfs/objects")
+            .join(&oid[0..2])
+            .join(&oid[2..4]);
+        util::fs::create_dir_all(&object)?;
+        util::fs::write_to_path(object.join(&oid), image)?;
+        util::fs::write_to_path(
+            git_dir.join("data").join("cat.png"),
+            format!(
+                "version https://git-lfs.github.com/spec/v1\noid sha256:{oid}\nsize {}\n",
+                image.len()
+            ),
+        )?;
+        util::fs::remove_file(git_dir.join("README.md"))?;
+        git(&git_dir, &["add", "-A"])?;
+        git(&git_dir, &["commit", "-q", "-m", "Add cat, remove readme"])?;
+        git(
+            &git_dir,
+            &["commit", "-q", "--allow-empty", "-m", "Nothing"],
+        )?;
+
+        git(&git_dir, &["checkout", "-q", "-b", "dogs", "HEAD~1"])?;
+        util::fs::write_to_path(git_dir.join("data").join("labels.csv"), "label\ndog\n")?;
+        git(&git_dir, &["commit", "-q", "-am", "Dogs"])?;
+        git(&git_dir, &["checkout", "-q", "main"])?;
+
+        let summary = repositories::git::import(&git_dir, &oxen_dir)?;
+        assert_eq!(summary.num_commits, 3);
+        assert_eq!(summary.num_empty_commits, 1);
+        assert_eq!(summary.branches, vec!["main", "dogs"]);
+        assert_eq!(summary.num_lfs_files, 1);
+        assert!(summary.missing_lfs_files.is_empty());
+
+        let repo = LocalRepository::from_dir(&oxen_dir)?;
+        let history = repositories::commits::list_from(&repo, "main")?;
+        assert_eq!(history.len(), 2);
+        assert!(history[0].message.starts_with("Add cat, remove readme"));
+        assert!(history[0].message.contains("\nGit-Commit: "));
+        assert_eq!(history[0].author, "Ox");
+
+        // The working directory is back on main with the LFS file resolved
+        assert!(!oxen_dir.join("README.md").exists());
+        assert_eq!(
+            util::fs::read_from_path(oxen_dir.join("data").join("cat.png"))?,
+            image
+        );
+        assert!(repositories::status(&repo)?.is_clean());
+
+        let dogs = repositories::commits::list_from(&repo, "dogs")?;
+        assert_eq!(dogs.len(), 3);
+        assert_eq!(dogs[1].id, history[0].id);
+
+        util::fs::remove_dir_all(git_dir)?;
+        util::fs::remove_dir_all(oxen_dir)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_fast_export_imports_into_git() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            util::fs::write_to_path(repo.path.join("README.md"), "# Updated")?;
+            util::fs::remove_file(repo.path.join("labels.txt"))?;
+            repositories::add(&repo, repo.path.join("README.md"))?;
+            repositories::add(&repo, repo.path.join("labels.txt"))?;
+            repositories::commit(&repo, "Update readme, remove labels")?;
+            let history = repositories::commits::list_from(&repo, "main")?;
+
+            let mut stream = Vec::new();
+            let summary = repositories::git::fast_export(
+                &repo,
+                "main",
+                &mut stream,
+                &FastExportOpts::default(),
+            )?;
+            assert_eq!(summary.num_commits, history.len());
+            assert_eq!(summary.num_lfs_files, 0);
+
+            let git_dir = test::create_empty_dir(test::test_run_dir())?;
+            git(&git_dir, &["init", "-q"])?;
+            let mut child = Command::new("git")
+                .current_dir(&git_dir)
+                .args(["fast-import", "--quiet"])
+                .stdin(Stdio::piped())
+                .spawn()?;
+            child.stdin.take().unwrap().write_all(&stream)?;
+            assert!(child.wait()?.success());
+
+            let count = git_output(&git_dir, &["rev-list", "--count", "main"])?;
+            assert_eq!(count.trim(), history.len().to_string());
+            assert_eq!(
+                git_output(&git_dir, &["show", "main:README.md"])?,
+                "# Updated"
+            );
+            let files = git_output(&git_dir, &["ls-tree", "-r", "--name-only", "main"])?;
+            assert!(!files.lines().any(|line| line == "labels.txt"));
+            assert!(files.lines().any(|line| line.starts_with("train/")));
+
+            // Everything as LFS pointers, with the objects written out for git-lfs
+            let objects_dir = git_dir.join("lfs-objects");
+            let opts = FastExportOpts {
+                lfs_threshold: Some(0),
+                lfs_objects_dir: Some(objects_dir.clone()),
+            };
+            let mut stream = Vec::new();
+            let summary = repositories::git::fast_export(&repo, "main", &mut stream, &opts)?;
+            assert_eq!(summary.num_lfs_files, summary.num_blobs);
+            let oid = util::hasher::hash_str_sha256("# Updated");
+            let object = objects_dir.join(&oid[0..2]).join(&oid[2..4]).join(&oid);
+            assert_eq!(util::fs::read_from_path(object)?, "# Updated");
+            let stream = String::from_utf8_lossy(&stream);
+            assert!(stream.contains(&format!("oid sha256:{oid}\nsize 9\n")));
+
+            util::fs::remove_dir_all(git_dir)?;
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,581 @@
+//! # Import
+//!
+//! Download an external dataset into the repository, then stage and commit it in one step.
+//!
+//! Sources are a plain `http(s)://` url, `hf://datasets/<owner>/<name>[@revision]` for a
+//! HuggingFace dataset, or `kaggle://<owner>/<dataset>` for a Kaggle dataset. Downloads go
+//! to `.oxen/tmp/imports` first and continue where they left off if an import is
+//! interrupted and run again. Archives (.zip, .tar, .tar.gz, .tgz) are extracted.
+//!
+//! Where the data came from is recorded in `Imported-From` trailers on the commit message.
+//!
+
+use flate2::read::GzDecoder;
+use reqwest::{header, Client, RequestBuilder, StatusCode};
+use serde::Deserialize;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
+
+use crate::api::client::{SendWithRetry, Url};
+use crate::constants;
+use crate::error::OxenError;
+use crate::model::{Commit, LocalRepository};
+use crate::opts::ImportOpts;
+use crate::repositories;
+use crate::util;
+
+pub const HF_ENDPOINT_ENV: &str = "HF_ENDPOINT";
+pub const HF_TOKEN_ENV: &str = "HF_TOKEN";
+pub const KAGGLE_ENDPOINT_ENV: &str = "OXEN_KAGGLE_ENDPOINT";
+const DEFAULT_HF_ENDPOINT: &str = "https://huggingface.co";
+const DEFAULT_KAGGLE_ENDPOINT: &str = "https://www.kaggle.com";
+const IMPORTS_DIR: &str = "imports";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportSource {
+    Url(String),
+    HuggingFace { repo_id: String, revision: String },
+    Kaggle { owner: String, dataset: String },
+}
+
+impl FromStr for ImportSource {
+    type Err = OxenError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(rest) = s.strip_prefix("hf://") {
+            let rest = rest.strip_prefix("datasets/").unwrap_or(rest);
+            let (repo_id, revision) = rest.split_once('@').unwrap_or((rest, "main"));
+            let (owner, name) = owner_and_name(s, repo_id)?;
+            Ok(ImportSource::HuggingFace {
+                repo_id: format!("{owner}/{name}"),
+                revision: revision.to_string(),
+            })
+        } else if let Some(rest) = s.strip_prefix("kaggle://") {
+            let (owner, dataset) = owner_and_name(s, rest)?;
+            Ok(ImportSource::Kaggle {
+                owner: owner.to_string(),
+                dataset: dataset.to_string(),
+            })
+        } else if s.starts_with("http://") || s.starts_with("https://") {
+            Url::parse(s)?;
+            Ok(ImportSource::Url(s.to_string()))
+        } else {
+            Err(OxenError::basic_str(format!(
+                "Cannot import {s:?}, expected an http(s) url, hf://datasets/<owner>/<name> or kaggle://<owner>/<dataset>"
+            )))
+        }
+    }
+}
+
+// Both end up in urls and the name in the import path, so each has to be a single
+// plain path component
+fn owner_and_name<'a>(source: &str, path: &'a str) -> Result<(&'a str, &'a str), OxenError> {
+    let is_component = |part: &str| {
+        let mut components = Path::new(part).components();
+        matches!(
+            (components.next(), components.next()),
+            (Some(Component::Normal(_)), None)
+        )
+    };
+    match path.trim_end_matches('/').split_once('/') {
+        Some((owner, name)) if is_component(owner) && is_component(name) => Ok((owner, name)),
+        _ => Err(OxenError::basic_str(format!(
+            "Invalid dataset {source:?}, expected <owner>/<name>"
+        ))),
+    }
+}
+
+impl fmt::Display for ImportSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportSource::Url(url) => write!(f, "{url}"),
+            ImportSource::HuggingFace { repo_id, revision } => {
+                write!(f, "hf://datasets/{repo_id}@{revision}")
+            }
+            ImportSource::Kaggle { owner, dataset } => write!(f, "kaggle://{owner}/{dataset}"),
+        }
+    }
+}
+
+impl ImportSource {
+    /// The default directory to import into
+    pub fn name(&self) -> String {
+        match self {
+            ImportSource::Url(url) => {
+                let file_name = file_name_from_url(url);
+                archive_stem(&file_name).unwrap_or(&file_name).to_string()
+            }
+            ImportSource::HuggingFace { repo_id, .. } => {
+                repo_id.rsplit('/').next().unwrap_or(repo_id).to_string()
+            }
+            ImportSource::Kaggle { dataset, .. } => dataset.to_string(),
+        }
+    }
+}
+
+/// Download, extract, stage and commit the dataset at `source`
+pub async fn import(
+    repo: &LocalRepository,
+    source: impl AsRef<str>,
+    opts: &ImportOpts,
+) -> Result<Commit, OxenError> {
+    let source = ImportSource::from_str(source.as_ref())?;
+    if let Some(path) = &opts.path {
+        if !path.components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(OxenError::basic_str(format!(
+                "Import path {path:?} must be relative to the root of the repository"
+            )));
+        }
+    }
+    if repositories::status(repo)?.has_added_entries() {
+        return Err(OxenError::basic_str(
+            "Commit or unstage your changes before importing, so the import gets its own commit",
+        ));
+    }
+
+    util::offline::ensure_online(source.to_string())?;
+
+    // Keyed by the source, so running the same import again resumes the download
+    let tmp_dir = util::fs::oxen_hidden_dir(&repo.path)
+        .join("tmp")
+        .join(IMPORTS_DIR)
+        .join(util::hasher::hash_str(source.to_string()));
+    util::fs::create_dir_all(&tmp_dir)?;
+
+    let client = Client::builder()
+        .user_agent(format!("Oxen/{}", constants::OXEN_VERSION))
+        .build()?;
+    let mut trailers = vec![format!("Imported-From: {source}")];
+    let imported = match &source {
+        ImportSource::Url(url) => {
+            let file_name = file_name_from_url(url);
+            let download = tmp_dir.join(&file_name);
+            download_resumable(|| client.get(url), &download).await?;
+            trailers.push(format!(
+                "Imported-Hash: {}",
+                util::hasher::hash_file_contents(&download)?
+            ));
+            place_download(repo, &source, &download, &file_name, opts)?
+        }
+        ImportSource::HuggingFace { repo_id, revision } => {
+            let endpoint = endpoint(HF_ENDPOINT_ENV, DEFAULT_HF_ENDPOINT);
+            let files = list_hf_files(&client, &endpoint, repo_id, revision).await?;
+            let dst = import_dir(repo, &source, opts);
+            for file in &files {
+                let url = format!(
+                    "{endpoint}/datasets/{repo_id}/resolve/{revision}/{}",
+                    file.path
+                );
+                let download = tmp_dir.join(&file.path);
+                download_resumable(|| hf_request(client.get(&url)), &download).await?;
+            }
+            // Only move the files over once every one of them is downloaded
+            for file in &files {
+                move_file(&tmp_dir.join(&file.path), &dst.join(&file.path))?;
+            }
+            trailers.push(format!("Imported-Files: {}", files.len()));
+            dst
+        }
+        ImportSource::Kaggle { owner, dataset } => {
+            let endpoint = endpoint(KAGGLE_ENDPOINT_ENV, DEFAULT_KAGGLE_ENDPOINT);
+            let (username, key) = kaggle_credentials()?;
+            let url = format!("{endpoint}/api/v1/datasets/download/{owner}/{dataset}");
+            let file_name = format!("{dataset}.zip");
+            let download = tmp_dir.join(&file_name);
+            download_resumable(
+                || client.get(&url).basic_auth(&username, Some(&key)),
+                &download,
+            )
+            .await?;
+            trailers.push(format!(
+                "Imported-Hash: {}",
+                util::hasher::hash_file_contents(&download)?
+            ));
+            place_download(repo, &source, &download, &file_name, opts)?
+        }
+    };
+    util::fs::remove_dir_all(&tmp_dir)?;
+
+    repositories::add(repo, &imported)?;
+    let message = opts
+        .message
+        .clone()
+        .unwrap_or_else(|| format!("Import {source}"));
+    let message = format!("{message}\n\n{}", trailers.join("\n"));
+    repositories::commit(repo, &message)
+}
+
+fn import_dir(repo: &LocalRepository, source: &ImportSource, opts: &ImportOpts) -> PathBuf {
+    match &opts.path {
+        Some(path) => repo.path.join(path),
+        None => repo.path.join(source.name()),
+    }
+}
+
+// Archives are extracted into the import dir, other files are put in it as is.
+// Without an import path a single file goes in the root of the repository.
+fn place_download(
+    repo: &LocalRepository,
+    source: &ImportSource,
+    download: &Path,
+    file_name: &str,
+    opts: &ImportOpts,
+) -> Result<PathBuf, OxenError> {
+    if !opts.no_extract && archive_stem(file_name).is_some() {
+        let dst = import_dir(repo, source, opts);
+        extract(download, &dst)?;
+        return Ok(dst);
+    }
+    let dst = repo
+        .path
+        .join(opts.path.clone().unwrap_or_default())
+        .join(file_name);
+    move_file(download, &dst)?;
+    Ok(dst)
+}
+
+fn move_file(src: &Path, dst: &Path) -> Result<(), OxenError> {
+    if let Some(parent) = dst.parent() {
+        util::fs::create_dir_all(parent)?;
+    }
+    util::fs::rename(src, dst)
+}
+
+fn endpoint(env: &str, default: &str) -> String {
+    std::env::var(env)
+        .unwrap_or_else(|_| default.to_string())
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Download to `dst`, continuing from the bytes already in it if the server supports
+/// range requests. Interrupted transfers are resumed a few times before giving up.
+async fn download_resumable(
+    request: impl Fn() -> RequestBuilder,
+    dst: &Path,
+) -> Result<(), OxenError> {
+    if let Some(parent) = dst.parent() {
+        util::fs::create_dir_all(parent)?;
+    }
+
+    let mut attempt = 0;
+    loop {
+        let offset = if dst.exists() {
+            util::fs::metadata(dst)?.len()
+        } else {
+            0
+        };
+        let mut builder = request();
+        if offset > 0 {
+            builder = builder.header(header::RANGE, format!("bytes={offset}-"));
+        }
+        let mut res = builder.send_with_retry().await?;
+        let mut file = match res.status() {
+            StatusCode::PARTIAL_CONTENT => OpenOptions::new().append(true).open(dst)?,
+            // Nothing left past what we have
+            StatusCode::RANGE_NOT_SATISFIABLE if offset > 0 => return Ok(()),
+            // The server ignored the range, start over
+            status if status.is_success() => File::create(dst)?,
+            status => {
+                return Err(OxenError::basic_str(format!(
+                    "Could not download {}: {status}",
+                    res.url()
+                )))
+            }
+        };
+        log::debug!("download_resumable {:?} from byte {}", dst, offset);
+
+        let result: Result<(), OxenError> = async {
+            while let Some(chunk) = res.chunk().await? {
+                file.write_all(&chunk)?;
+            }
+            Ok(())
+        }
+        .await;
+        match result {
+            Ok(()) => return Ok(()),
+            Err(err) if attempt < constants::NUM_HTTP_RETRIES => {
+                log::warn!("Download of {:?} interrupted, resuming: {}", dst, err);
+                attempt += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+fn file_name_from_url(url: &str) -> String {
+    Url::parse(url)
+        .ok()
+        .and_then(|url| {
+            url.path_segments()
+                .and_then(|mut segments| segments.next_back().map(|s| s.to_string()))
+        })
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "download".to_string())
+}
+
+// The name without the archive extension, or None if it isn't an archive we extract
+fn archive_stem(file_name: &str) -> Option<&str> {
+    [".tar.gz", ".tgz", ".tar", ".zip"]
+        .iter()
+        .find_map(|ext| file_name.strip_suffix(ext))
+}
+
+fn extract(archive: &Path, dst: &Path) -> Result<(), OxenError> {
+    util::fs::create_dir_all(dst)?;
+    let name = archive.to_string_lossy();
+    let file = File::open(archive)?;
+    if name.ends_with(".zip") {
+        // Paths that would escape dst are rejected by the zip and tar readers
+        zip::ZipArchive::new(file)
+            .and_then(|mut zip| zip.extract(dst))
+            .map_err(|err| OxenError::basic_str(format!("Could not extract {name}: {err}")))?;
+    } else if name.ends_with(".tar") {
+        tar::Archive::new(file).unpack(dst)?;
+    } else {
+        tar::Archive::new(GzDecoder::new(file)).unpack(dst)?;
+    }
+    Ok(())
+}
+
+#[derive(Deserialize, Debug)]
+struct HfTreeEntry {
+    #[serde(rename = "type")]
+    entry_type: String,
+    path: String,
+}
+
+fn hf_request(builder: RequestBuilder) -> RequestBuilder {
+    match std::env::var(HF_TOKEN_ENV) {
+        Ok(token) => builder.bearer_auth(token),
+        Err(_) => builder,
+    }
+}
+
+async fn list_hf_files(
+    client: &Client,
+    endpoint: &str,
+    repo_id: &str,
+    revision: &str,
+) -> Result<Vec<HfTreeEntry>, OxenError> {
+    let mut files = Vec::new();
+    let mut next = Some(format!(
+        "{endpoint}/api/datasets/{repo_id}/tree/{revision}?recursive=true"
+    ));
+    while let Some(url) = next {
+        let res = hf_request(client.get(&url)).send_with_retry().await?;
+        let status = res.status();
+        if !status.is_success() {
+            return Err(OxenError::basic_str(format!(
+                "Could not list hf://datasets/{repo_id}@{revision}: {status}. Set {HF_TOKEN_ENV} for private or gated datasets."
+            )));
+        }
+        next = next_link(endpoint, res.headers());
+        let body = res.text().await?;
+        let entries: Vec<HfTreeEntry> = serde_json::from_str(&body)?;
+        for entry in entries {
+            if entry.entry_type != "file" {
+                continue;
+            }
+            if !Path::new(&entry.path)
+                .components()
+                .all(|c| matches!(c, Component::Normal(_)))
+            {
+                return Err(OxenError::basic_str(format!(
+                    "Refusing to import {:?} from {repo_id}",
+                    entry.path
+                )));
+            }
+            files.push(entry);
+        }
+    }
+    Ok(files)
+}
+
+// The url with rel="next" in a Link header, used to page through large listings. Only
+// links to the endpoint itself are followed, the requests carry the HF token.
+fn next_link(endpoint: &str, headers: &header::HeaderMap) -> Option<String> {
+    let link = headers.get(header::LINK)?.to_str().ok()?;
+    let link = link
+        .split(',')
+        .find(|part| part.contains("rel=\"next\""))
+        .and_then(|part| {
+            let start = part.find('<')? + 1;
+            let end = part.find('>')?;
+            Some(&part[start..end])
+        })?;
+    let endpoint = Url::parse(endpoint).ok()?;
+    let url = endpoint.join(link).ok()?;
+    if url.origin() != endpoint.origin() {
+        log::warn!("Not following next link {url} off of {endpoint}");
+        return None;
+    }
+    Some(url.to_string())
+}
+
+#[derive(Deserialize)]
+struct KaggleCredentials {
+    username: String,
+    key: String,
+}
+
+/// Kaggle credentials from KAGGLE_USERNAME and KAGGLE_KEY, or ~/.kaggle/kaggle.json
+fn kaggle_credentials() -> Result<(String, String), OxenError> {
+    if let (Ok(username), Ok(key)) = (
+        std::env::var("KAGGLE_USERNAME"),
+        std::env::var("KAGGLE_KEY"),
+    ) {
+        return Ok((username, key));
+    }
+    let path = dirs::home_dir().map(|home| home.join(".kaggle").join("kaggle.json"));
+    match path.filter(|path| path.exists()) {
+        Some(path) => {
+            let credentials: KaggleCredentials =
+                serde_json::from_str(&util::fs::read_from_path(&path)?)?;
+            Ok((credentials.username, credentials.key))
+        }
+        None => Err(OxenError::basic_str(
+            "Kaggle credentials not found. Set KAGGLE_USERNAME and KAGGLE_KEY or create ~/.kaggle/kaggle.json",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::str::FromStr;
+
+    use crate::error::OxenError;
+    use crate::opts::ImportOpts;
+    use crate::repositories;
+    use crate::repositories::import::{download_resumable, next_link, ImportSource};
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_parse_import_source() -> Result<(), OxenError> {
+        assert_eq!(
+            ImportSource::from_str("hf://datasets/ox/cats@v1")?,
+            ImportSource::HuggingFace {
+                repo_id: "ox/cats".to_string(),
+                revision: "v1".to_string()
+            }
+        );
+        let source = ImportSource::from_str("hf://ox/cats")?;
+        assert_eq!(source.to_string(), "hf://datasets/ox/cats@main");
+        assert_eq!(source.name(), "cats");
+
+        let source = ImportSource::from_str("kaggle://ox/dogs")?;
+        assert_eq!(source.name(), "dogs");
+
+        let source = ImportSource::from_str("https://example.com/data/images.tar.gz?x=1")?;
+        assert_eq!(source.name(), "images");
+
+        assert!(ImportSource::from_str("kaggle://ox").is_err());
+        assert!(ImportSource::from_str("hf://datasets/ox/cats/extra").is_err());
+        assert!(ImportSource::from_str("hf://datasets/ox/..").is_err());
+        assert!(ImportSource::from_str("kaggle://../dogs").is_err());
+        assert!(ImportSource::from_str("kaggle://ox/.").is_err());
+        assert!(ImportSource::from_str("s3://bucket/data").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_link_stays_on_endpoint() {
+        let headers = |link: &str| {
+            let mut headers = reqwest::header::HeaderMap::new();
+            headers.insert(reqwest::header::LINK, link.parse().unwrap());
+            headers
+        };
+        let endpoint = "https://huggingface.co";
+        assert_eq!(
+            next_link(
+                endpoint,
+                &headers("<https://huggingface.co/api/datasets/ox/cats/tree/main?cursor=a>; rel=\"next\"")
+            ),
+            Some("https://huggingface.co/api/datasets/ox/cats/tree/main?cursor=a".to_string())
+        );
+        assert_eq!(
+            next_link(
+                endpoint,
+                &headers("<https://example.com/steal>; rel=\"next\"")
+            ),
+            None
+        );
+        assert_eq!(
+            next_link(
+                endpoint,
+                &headers("<http://huggingface.co/api>; rel=\"next\"")
+            ),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn test_download_resumes_from_partial_file() -> Result<(), OxenError> {
+        test::run_empty_dir_test_async(|dir| async move {
+            let mut server = mockito::Server::new_async().await;
+            let mock = server
+                .mock("GET", "/data.csv")
+                .match_header("range", "bytes=5-")
+                .with_status(206)
+                .with_body("b\n1,2\n")
+                .create_async()
+                .await;
+
+            let dst = dir.join("data.csv");
+            util::fs::write_to_path(&dst, "a,b,c")?;
+            let url = format!("{}/data.csv", server.url());
+            let client = reqwest::Client::new();
+            download_resumable(|| client.get(&url), &dst).await?;
+
+            mock.assert_async().await;
+            assert_eq!(util::fs::read_from_path(&dst)?, "a,b,c\n1,2\n");
+            Ok(dir)
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_import_extracts_and_commits_with_provenance() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            // A tar.gz with a csv in a sub directory
+            let mut tar = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+            let contents = b"label,count\ncat,2\n";
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            tar.append_data(&mut header, "annotations/labels.csv", &contents[..])?;
+            let archive = tar.into_inner()?.finish()?;
+
+            let mut server = mockito::Server::new_async().await;
+            let mock = server
+                .mock("GET", "/pets.tar.gz")
+                .with_body(archive)
+                .create_async()
+                .await;
+
+            let source = format!("{}/pets.tar.gz", server.url());
+            let commit =
+                repositories::import::import(&repo, &source, &ImportOpts::default()).await?;
+            mock.assert_async().await;
+
+            let imported = repo
+                .path
+                .join("pets")
+                .join("annotations")
+                .join("labels.csv");
+            assert_eq!(util::fs::read_from_path(&imported)?, "label,count\ncat,2\n");
+            assert!(commit.message.contains(&format!("Imported-From: {source}")));
+            assert!(commit.message.contains("Imported-Hash: "));
+            assert!(repositories::status(&repo)?.is_clean());
+            Ok(())
+        })
+        .await
+    }
+}
@@ -3,13 +3,15 @@
 //! Initialize a local oxen repository
 //!
 
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
 
-use crate::constants::MIN_OXEN_VERSION;
+use crate::constants::{MIN_OXEN_VERSION, OXEN_HIDDEN_DIR};
 use crate::core;
 use crate::core::versions::MinOxenVersion;
 use crate::error::OxenError;
 use crate::model::LocalRepository;
+use crate::util;
 
 /// # Initialize an Empty Oxen Repository
 /// ```
@@ -30,9 +32,23 @@ pub fn init(path: impl AsRef<Path>) -> Result<LocalRepository, OxenError> {
     init_with_version(path, MIN_OXEN_VERSION)
 }
 
+/// Initialize a repository with a specific version. Errors if it would be nested inside
+/// of, or contain, another oxen repository.
 pub fn init_with_version(
     path: impl AsRef<Path>,
     version: MinOxenVersion,
+) -> Result<LocalRepository, OxenError> {
+    let path = path.as_ref();
+    if let Some(other) = find_nested_repo(path) {
+        return Err(OxenError::init_nested_repo(path, other));
+    }
+    init_nested(path, version)
+}
+
+/// Initialize a repository even if it is nested inside of, or contains, another oxen repository
+pub fn init_nested(
+    path: impl AsRef<Path>,
+    version: MinOxenVersion,
 ) -> Result<LocalRepository, OxenError> {
     let path = path.as_ref();
     match version {
@@ -41,8 +57,28 @@ pub fn init_with_version(
     }
 }
 
+// Look for a repository above the path, then for any below it
+fn find_nested_repo(path: &Path) -> Option<PathBuf> {
+    if let Some(parent) = path.parent() {
+        if let Some(root) = util::fs::get_repo_root(parent) {
+            return Some(root);
+        }
+    }
+    if !path.is_dir() {
+        return None;
+    }
+    WalkDir::new(path)
+        .min_depth(1)
+        .into_iter()
+        .filter_entry(|e| e.file_type().is_dir() && e.file_name() != OXEN_HIDDEN_DIR)
+        .filter_map(|e| e.ok())
+        .find(|e| e.path().join(OXEN_HIDDEN_DIR).is_dir())
+        .map(|e| e.path().to_path_buf())
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::constants::MIN_OXEN_VERSION;
     use crate::error::OxenError;
     use crate::repositories;
     use crate::test;
@@ -63,4 +99,29 @@ mod tests {
             Ok(())
         })
     }
+
+    #[test]
+    fn test_init_refuses_nested_repo() -> Result<(), OxenError> {
+        test::run_empty_dir_test(|dir| {
+            let outer_dir = dir.join("outer");
+            repositories::init(&outer_dir)?;
+
+            // Inside of another repo
+            let inner_dir = outer_dir.join("data").join("inner");
+            util::fs::create_dir_all(&inner_dir)?;
+            assert!(repositories::init(&inner_dir).is_err());
+            assert!(!inner_dir.join(".oxen").exists());
+
+            // Containing another repo
+            let parent_dir = dir.join("parent");
+            repositories::init(parent_dir.join("child"))?;
+            assert!(repositories::init(&parent_dir).is_err());
+
+            // Unless asked to
+            repositories::init::init_nested(&inner_dir, MIN_OXEN_VERSION)?;
+            assert!(inner_dir.join(".oxen").exists());
+
+            Ok(())
+        })
+    }
 }
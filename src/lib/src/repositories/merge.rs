@@ -7,6 +7,7 @@ use crate::error::OxenError;
 use crate::model::merge_conflict::MergeConflict;
 use crate::model::Commit;
 use crate::model::{Branch, LocalRepository};
+use crate::repositories;
 
 #[derive(Debug)]
 pub struct MergeCommits {
@@ -169,6 +170,7 @@ pub fn merge(
     repo: &LocalRepository,
     branch_name: impl AsRef<str>,
 ) -> Result<Option<Commit>, OxenError> {
+    repositories::freeze::ensure_not_frozen(repo)?;
     match repo.min_version() {
         MinOxenVersion::V0_10_0 => {
             let merger = core::v0_10_0::index::merger::Merger::new(repo)?;
@@ -0,0 +1,497 @@
+//! # Merge Queue
+//!
+//! Queue up branches to be merged into a base branch, and merge them one at a time
+//! so that many contributors targeting the same branch do not have to coordinate.
+//!
+//! Entries can have a url that is POSTed to once they are finished. Notifications only go
+//! to public addresses, hosts on the server's own network have to be listed in the
+//! `OXEN_NOTIFY_ALLOWED_HOSTS` env var (comma separated).
+//!
+
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+use reqwest::redirect::Policy;
+use url::{Host, Url};
+
+use crate::api;
+use crate::constants::{MERGE_QUEUE_DIR, OXEN_VERSION};
+use crate::core::db;
+use crate::core::db::key_val::str_json_db;
+use crate::error::OxenError;
+use crate::model::{LocalRepository, MergeQueueEntry, MergeQueueStatus, NewMergeQueueEntry};
+use crate::repositories;
+use crate::util;
+
+use rocksdb::DB;
+use time::OffsetDateTime;
+
+pub const NOTIFY_ALLOWED_HOSTS_ENV: &str = "OXEN_NOTIFY_ALLOWED_HOSTS";
+const NOTIFY_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn open_db(repo: &LocalRepository) -> Result<DB, OxenError> {
+    let db_path = util::fs::oxen_hidden_dir(&repo.path).join(MERGE_QUEUE_DIR);
+    let opts = db::key_val::opts::default();
+    Ok(DB::open(&opts, dunce::simplified(&db_path))?)
+}
+
+fn save(repo: &LocalRepository, entry: &MergeQueueEntry) -> Result<(), OxenError> {
+    let db = open_db(repo)?;
+    str_json_db::put(&db, &entry.id, entry)
+}
+
+/// Add a merge of `head` into `base` to the back of the queue
+pub fn enqueue(
+    repo: &LocalRepository,
+    new_entry: NewMergeQueueEntry,
+) -> Result<MergeQueueEntry, OxenError> {
+    for name in [&new_entry.base, &new_entry.head] {
+        if !repositories::branches::exists(repo, name)? {
+            return Err(OxenError::local_branch_not_found(name));
+        }
+    }
+    if let Some(url) = &new_entry.notify_url {
+        let url = parse_notify_url(url)?;
+        // Addresses are checked again when notifying, hostnames can resolve anywhere
+        if let Some(Host::Ipv4(_) | Host::Ipv6(_)) = url.host() {
+            check_notify_host(&url, &[])?;
+        }
+    }
+
+    let now = OffsetDateTime::now_utc();
+    let entry = MergeQueueEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        base: new_entry.base,
+        head: new_entry.head,
+        author: new_entry.author,
+        email: new_entry.email,
+        status: MergeQueueStatus::Queued,
+        merge_commit_id: None,
+        conflicts: vec![],
+        error: None,
+        notify_url: new_entry.notify_url,
+        created_at: now,
+        updated_at: now,
+    };
+    save(repo, &entry)?;
+    Ok(entry)
+}
+
+/// Get an entry by id
+pub fn get(
+    repo: &LocalRepository,
+    id: impl AsRef<str>,
+) -> Result<Option<MergeQueueEntry>, OxenError> {
+    let db = open_db(repo)?;
+    str_json_db::get(&db, id)
+}
+
+/// List the entries in the order they were queued, optionally only for one base branch
+pub fn list(repo: &LocalRepository, base: Option<&str>) -> Result<Vec<MergeQueueEntry>, OxenError> {
+    let db = open_db(repo)?;
+    let entries: Vec<(String, MergeQueueEntry)> = str_json_db::list(&db)?;
+    let mut entries: Vec<MergeQueueEntry> = entries
+        .into_iter()
+        .map(|(_, entry)| entry)
+        .filter(|entry| base.is_none_or(|base| entry.base == base))
+        .collect();
+    entries.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+    Ok(entries)
+}
+
+/// Remove an entry from the queue if it has not started merging yet
+pub fn cancel(repo: &LocalRepository, id: impl AsRef<str>) -> Result<MergeQueueEntry, OxenError> {
+    let id = id.as_ref();
+    let Some(mut entry) = get(repo, id)? else {
+        return Err(OxenError::resource_not_found(id));
+    };
+
+    if entry.status != MergeQueueStatus::Queued {
+        return Err(OxenError::basic_str(format!(
+            "Cannot cancel merge queue entry {id}, it is already {}",
+            entry.status
+        )));
+    }
+
+    entry.status = MergeQueueStatus::Cancelled;
+    entry.updated_at = OffsetDateTime::now_utc();
+    save(repo, &entry)?;
+    Ok(entry)
+}
+
+/// Merge the oldest queued entry for the base branch.
+///
+/// Returns None if nothing is queued, and errors if the base branch is locked
+/// (ex: by a push), in which case the entry stays queued and can be retried.
+pub fn process_next(
+    repo: &LocalRepository,
+    base: impl AsRef<str>,
+) -> Result<Option<MergeQueueEntry>, OxenError> {
+    let base = base.as_ref();
+    if !list(repo, Some(base))?
+        .iter()
+        .any(|e| e.status == MergeQueueStatus::Queued)
+    {
+        return Ok(None);
+    }
+
+    // Lock the base so no one pushes to it while we merge, and pick the entry after
+    // locking so two processes can never merge the same one
+    repositories::branches::lock(repo, base)?;
+    let result = merge_next(repo, base);
+    let unlocked = repositories::branches::unlock(repo, base);
+    let entry = result?;
+    unlocked?;
+    Ok(entry)
+}
+
+// Must be called with the base locked. The entry is saved as finished, or left queued,
+// before this returns so it is never stuck merging.
+fn merge_next(repo: &LocalRepository, base: &str) -> Result<Option<MergeQueueEntry>, OxenError> {
+    let Some(mut entry) = list(repo, Some(base))?
+        .into_iter()
+        .find(|e| e.status == MergeQueueStatus::Queued)
+    else {
+        return Ok(None);
+    };
+
+    entry.status = MergeQueueStatus::Merging;
+    entry.updated_at = OffsetDateTime::now_utc();
+    save(repo, &entry)?;
+
+    if let Err(err) = merge_entry(repo, &mut entry) {
+        log::error!(
+            "merge_queue::process_next failed to merge {}: {}",
+            entry.id,
+            err
+        );
+        entry.status = MergeQueueStatus::Failed;
+        entry.error = Some(err.to_string());
+    }
+    entry.updated_at = OffsetDateTime::now_utc();
+    save(repo, &entry)?;
+    Ok(Some(entry))
+}
+
+/// Merge every queued entry for the base branch in order, returning the processed entries
+pub fn process_all(
+    repo: &LocalRepository,
+    base: impl AsRef<str>,
+) -> Result<Vec<MergeQueueEntry>, OxenError> {
+    let base = base.as_ref();
+    let mut processed = vec![];
+    while let Some(entry) = process_next(repo, base)? {
+        processed.push(entry);
+    }
+    Ok(processed)
+}
+
+/// Mark any entries left merging as failed and release the base branch,
+/// ex) if the process merging them crashed
+pub fn fail_in_progress(
+    repo: &LocalRepository,
+    base: impl AsRef<str>,
+    error: impl AsRef<str>,
+) -> Result<Vec<MergeQueueEntry>, OxenError> {
+    let base = base.as_ref();
+    let mut failed = vec![];
+    for mut entry in list(repo, Some(base))? {
+        if entry.status == MergeQueueStatus::Merging {
+            entry.status = MergeQueueStatus::Failed;
+            entry.error = Some(error.as_ref().to_string());
+            entry.updated_at = OffsetDateTime::now_utc();
+            save(repo, &entry)?;
+            failed.push(entry);
+        }
+    }
+
+    if !failed.is_empty() {
+        repositories::branches::unlock(repo, base)?;
+    }
+    Ok(failed)
+}
+
+/// POST the entry to its notify_url if it has one
+pub async fn notify(entry: &MergeQueueEntry) -> Result<(), OxenError> {
+    let Some(url) = &entry.notify_url else {
+        return Ok(());
+    };
+    let url = parse_notify_url(url)?;
+    let allowed_hosts = std::env::var(NOTIFY_ALLOWED_HOSTS_ENV).unwrap_or_default();
+    let allowed_hosts: Vec<&str> = allowed_hosts
+        .split(',')
+        .map(|host| host.trim())
+        .filter(|host| !host.is_empty())
+        .collect();
+
+    // Not api::client::new_for_url, the oxen auth token must never go to a notify url
+    let mut builder = api::client::builder_for_url_without_auth(url.as_str())?
+        .user_agent(format!("Oxen-Merge-Queue/{OXEN_VERSION}"))
+        .timeout(NOTIFY_TIMEOUT)
+        .redirect(Policy::none());
+    if let Some(Host::Domain(domain)) = url.host() {
+        if !allowed_hosts.contains(&domain) {
+            // Connect to the addresses we checked, so the name cannot resolve somewhere else
+            let port = url.port_or_known_default().unwrap_or(80);
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((domain, port)).await?.collect();
+            for addr in &addrs {
+                check_notify_ip(&url, addr.ip())?;
+            }
+            builder = builder.resolve_to_addrs(domain, &addrs);
+        }
+    } else {
+        check_notify_host(&url, &allowed_hosts)?;
+    }
+
+    let client = builder.build()?;
+    let res = client.post(url.as_str()).json(entry).send().await?;
+    if !res.status().is_success() {
+        log::warn!(
+            "merge_queue::notify {} responded with status {}",
+            url,
+            res.status()
+        );
+    }
+    Ok(())
+}
+
+fn parse_notify_url(url: &str) -> Result<Url, OxenError> {
+    let parsed = Url::parse(url)
+        .map_err(|err| OxenError::basic_str(format!("Invalid notify url: {err}")))?;
+    if parsed.scheme() != "http" && parsed.scheme() != "https" {
+        return Err(OxenError::basic_str(format!(
+            "Notify url must be http or https, got {url}"
+        )));
+    }
+    if parsed.host().is_none() {
+        return Err(OxenError::basic_str(format!(
+            "Notify url must have a host, got {url}"
+        )));
+    }
+    Ok(parsed)
+}
+
+// Only for urls with an ip address as the host
+fn check_notify_host(url: &Url, allowed_hosts: &[&str]) -> Result<(), OxenError> {
+    let ip = match url.host() {
+        Some(Host::Ipv4(ip)) => IpAddr::V4(ip),
+        Some(Host::Ipv6(ip)) => IpAddr::V6(ip),
+        _ => return Ok(()),
+    };
+    let host = url.host_str().unwrap_or_default();
+    if allowed_hosts.contains(&host) || allowed_hosts.contains(&ip.to_string().as_str()) {
+        return Ok(());
+    }
+    check_notify_ip(url, ip)
+}
+
+fn check_notify_ip(url: &Url, ip: IpAddr) -> Result<(), OxenError> {
+    if is_public_ip(ip) {
+        return Ok(());
+    }
+    Err(OxenError::basic_str(format!(
+        "Notify url {url} is not a public address, add its host to {NOTIFY_ALLOWED_HOSTS_ENV} to allow it"
+    )))
+}
+
+fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            // 100.64.0.0/10 is carrier grade NAT
+            let is_shared = a == 100 && (b & 0xc0) == 64;
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || is_shared)
+        }
+        IpAddr::V6(ip) => {
+            if let Some(ip) = ip.to_ipv4_mapped() {
+                return is_public_ip(IpAddr::V4(ip));
+            }
+            let first = ip.segments()[0];
+            // fc00::/7 is unique local and fe80::/10 is link local
+            let is_unique_local = (first & 0xfe00) == 0xfc00;
+            let is_link_local = (first & 0xffc0) == 0xfe80;
+            !(ip.is_loopback() || ip.is_unspecified() || is_unique_local || is_link_local)
+        }
+    }
+}
+
+fn merge_entry(repo: &LocalRepository, entry: &mut MergeQueueEntry) -> Result<(), OxenError> {
+    let base = repositories::branches::get_by_name(repo, &entry.base)?
+        .ok_or(OxenError::local_branch_not_found(&entry.base))?;
+    let head = repositories::branches::get_by_name(repo, &entry.head)?
+        .ok_or(OxenError::local_branch_not_found(&entry.head))?;
+
+    let conflicts = repositories::merge::list_conflicts_between_branches(repo, &base, &head)?;
+    if !conflicts.is_empty() {
+        entry.status = MergeQueueStatus::Conflict;
+        entry.conflicts = conflicts;
+        return Ok(());
+    }
+
+    match repositories::merge::merge_into_base(repo, &head, &base)? {
+        Some(commit) => {
+            entry.status = MergeQueueStatus::Merged;
+            entry.merge_commit_id = Some(commit.id);
+        }
+        None => {
+            entry.status = MergeQueueStatus::Conflict;
+            entry.conflicts =
+                repositories::merge::list_conflicts_between_branches(repo, &base, &head)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constants::DEFAULT_BRANCH_NAME;
+    use crate::error::OxenError;
+    use crate::model::{LocalRepository, MergeQueueStatus, NewMergeQueueEntry};
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    fn new_entry(head: &str) -> NewMergeQueueEntry {
+        NewMergeQueueEntry {
+            base: DEFAULT_BRANCH_NAME.to_string(),
+            head: head.to_string(),
+            author: String::from("Ox"),
+            email: String::from("ox@oxen.ai"),
+            notify_url: None,
+        }
+    }
+
+    async fn commit_on_branch(
+        repo: &LocalRepository,
+        branch: &str,
+        file: &str,
+        contents: &str,
+    ) -> Result<(), OxenError> {
+        repositories::branches::create_checkout(repo, branch)?;
+        let path = repo.path.join(file);
+        util::fs::write_to_path(&path, contents)?;
+        repositories::add(repo, &path)?;
+        repositories::commit(repo, &format!("Writing {file} on {branch}"))?;
+        repositories::checkout(repo, DEFAULT_BRANCH_NAME).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_merge_queue_merges_in_order() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|repo| async move {
+            commit_on_branch(&repo, "annotator-1", "a.txt", "a").await?;
+            commit_on_branch(&repo, "annotator-2", "b.txt", "b").await?;
+
+            let first = repositories::merge_queue::enqueue(&repo, new_entry("annotator-1"))?;
+            let second = repositories::merge_queue::enqueue(&repo, new_entry("annotator-2"))?;
+
+            let queued = repositories::merge_queue::list(&repo, Some(DEFAULT_BRANCH_NAME))?;
+            assert_eq!(queued.len(), 2);
+            assert_eq!(queued[0].id, first.id);
+            assert_eq!(queued[1].id, second.id);
+
+            let processed = repositories::merge_queue::process_all(&repo, DEFAULT_BRANCH_NAME)?;
+            assert_eq!(processed.len(), 2);
+            assert!(processed
+                .iter()
+                .all(|e| e.status == MergeQueueStatus::Merged));
+
+            let head = repositories::commits::head_commit(&repo)?;
+            let last = repositories::merge_queue::get(&repo, &second.id)?.unwrap();
+            assert_eq!(last.merge_commit_id, Some(head.id));
+            assert!(repo.path.join("a.txt").exists());
+            assert!(repo.path.join("b.txt").exists());
+
+            // Nothing left to do
+            assert!(repositories::merge_queue::process_next(&repo, DEFAULT_BRANCH_NAME)?.is_none());
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_merge_queue_reports_conflicts() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|repo| async move {
+            commit_on_branch(&repo, "annotator-1", "labels.txt", "cat").await?;
+            commit_on_branch(&repo, "annotator-2", "labels.txt", "dog").await?;
+
+            repositories::merge_queue::enqueue(&repo, new_entry("annotator-1"))?;
+            let second = repositories::merge_queue::enqueue(&repo, new_entry("annotator-2"))?;
+            repositories::merge_queue::process_all(&repo, DEFAULT_BRANCH_NAME)?;
+
+            let entry = repositories::merge_queue::get(&repo, &second.id)?.unwrap();
+            assert_eq!(entry.status, MergeQueueStatus::Conflict);
+            assert_eq!(entry.conflicts.len(), 1);
+
+            // The base is unlocked so it can keep going
+            assert!(!repositories::branches::is_locked(
+                &repo,
+                DEFAULT_BRANCH_NAME
+            )?);
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[test]
+    fn test_merge_queue_cancel() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            repositories::branches::create_from_head(&repo, "annotator-1")?;
+            let entry = repositories::merge_queue::enqueue(&repo, new_entry("annotator-1"))?;
+            let cancelled = repositories::merge_queue::cancel(&repo, &entry.id)?;
+            assert_eq!(cancelled.status, MergeQueueStatus::Cancelled);
+
+            // Cancelled entries are skipped
+            assert!(repositories::merge_queue::process_next(&repo, DEFAULT_BRANCH_NAME)?.is_none());
+            // And cannot be cancelled twice
+            assert!(repositories::merge_queue::cancel(&repo, &entry.id).is_err());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_merge_queue_enqueue_unknown_branch() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let result = repositories::merge_queue::enqueue(&repo, new_entry("does-not-exist"));
+            assert!(result.is_err());
+            Ok(())
+        })
+    }
+
+    #[tokio::test]
+    async fn test_merge_queue_notify_only_public_urls() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|repo| async move {
+            repositories::branches::create_from_head(&repo, "annotator-1")?;
+            for url in [
+                "file:///etc/passwd",
+                "http://127.0.0.1:8080/done",
+                "http://[::1]/",
+            ] {
+                let mut entry = new_entry("annotator-1");
+                entry.notify_url = Some(url.to_string());
+                assert!(repositories::merge_queue::enqueue(&repo, entry).is_err());
+            }
+
+            // Names are checked once they resolve, and nothing is sent to private addresses
+            let mut server = mockito::Server::new_async().await;
+            let mock = server.mock("POST", "/done").expect(0).create_async().await;
+            let mut entry = new_entry("annotator-1");
+            entry.notify_url = Some(server.url().replace("127.0.0.1", "localhost") + "/done");
+            let entry = repositories::merge_queue::enqueue(&repo, entry)?;
+            assert!(repositories::merge_queue::notify(&entry).await.is_err());
+            mock.assert_async().await;
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,336 @@
+//! # Merge Requests
+//!
+//! Propose merging a head branch into a base branch so the changes can be reviewed
+//! on the server before they land.
+//!
+
+use crate::constants::MERGE_REQUESTS_DIR;
+use crate::core::db;
+use crate::core::db::key_val::str_json_db;
+use crate::error::OxenError;
+use crate::model::{
+    Branch, Commit, LocalRepository, MergeRequest, MergeRequestStatus, NewMergeRequest,
+};
+use crate::repositories;
+use crate::util;
+
+use rocksdb::DB;
+use time::OffsetDateTime;
+
+fn open_db(repo: &LocalRepository) -> Result<DB, OxenError> {
+    let db_path = util::fs::oxen_hidden_dir(&repo.path).join(MERGE_REQUESTS_DIR);
+    let opts = db::key_val::opts::default();
+    Ok(DB::open(&opts, dunce::simplified(&db_path))?)
+}
+
+fn save(repo: &LocalRepository, merge_request: &MergeRequest) -> Result<(), OxenError> {
+    let db = open_db(repo)?;
+    str_json_db::put(&db, &merge_request.id, merge_request)
+}
+
+/// Open a merge request of `head` into `base`
+pub fn create(
+    repo: &LocalRepository,
+    new_request: NewMergeRequest,
+) -> Result<MergeRequest, OxenError> {
+    if new_request.base == new_request.head {
+        return Err(OxenError::basic_str(
+            "The base and head of a merge request must be different branches",
+        ));
+    }
+    for name in [&new_request.base, &new_request.head] {
+        if !repositories::branches::exists(repo, name)? {
+            return Err(OxenError::local_branch_not_found(name));
+        }
+    }
+
+    let number = list(repo, None, None)?
+        .iter()
+        .map(|mr| mr.number)
+        .max()
+        .unwrap_or(0)
+        + 1;
+    let now = OffsetDateTime::now_utc();
+    let merge_request = MergeRequest {
+        id: uuid::Uuid::new_v4().to_string(),
+        number,
+        title: new_request.title,
+        description: new_request.description,
+        base: new_request.base,
+        head: new_request.head,
+        author: new_request.author,
+        email: new_request.email,
+        status: MergeRequestStatus::Open,
+        merge_commit_id: None,
+        created_at: now,
+        updated_at: now,
+    };
+    save(repo, &merge_request)?;
+    Ok(merge_request)
+}
+
+/// Get a merge request by id, or by its number
+pub fn get(
+    repo: &LocalRepository,
+    id_or_number: impl AsRef<str>,
+) -> Result<Option<MergeRequest>, OxenError> {
+    let id_or_number = id_or_number.as_ref();
+    // Scoped so the db is closed before listing reopens it
+    {
+        let db = open_db(repo)?;
+        if let Some(merge_request) = str_json_db::get(&db, id_or_number)? {
+            return Ok(Some(merge_request));
+        }
+    }
+
+    let Ok(number) = id_or_number.parse::<u64>() else {
+        return Ok(None);
+    };
+    Ok(list(repo, None, None)?
+        .into_iter()
+        .find(|mr| mr.number == number))
+}
+
+/// List the merge requests in the order they were opened, optionally filtered by
+/// status and base branch
+pub fn list(
+    repo: &LocalRepository,
+    status: Option<MergeRequestStatus>,
+    base: Option<&str>,
+) -> Result<Vec<MergeRequest>, OxenError> {
+    let db = open_db(repo)?;
+    let merge_requests: Vec<(String, MergeRequest)> = str_json_db::list(&db)?;
+    let mut merge_requests: Vec<MergeRequest> = merge_requests
+        .into_iter()
+        .map(|(_, mr)| mr)
+        .filter(|mr| status.is_none_or(|status| mr.status == status))
+        .filter(|mr| base.is_none_or(|base| mr.base == base))
+        .collect();
+    merge_requests.sort_by_key(|mr| mr.number);
+    Ok(merge_requests)
+}
+
+/// The commits to diff to review the merge request: the merge base of the two branches
+/// and the head branch, so changes that only landed on the base are not shown.
+/// Once merged, the merge commit and its first parent.
+pub fn base_and_head_commits(
+    repo: &LocalRepository,
+    merge_request: &MergeRequest,
+) -> Result<(Commit, Commit), OxenError> {
+    if let Some(merge_commit_id) = &merge_request.merge_commit_id {
+        let merge_commit = repositories::commits::get_by_id(repo, merge_commit_id)?.ok_or(
+            OxenError::revision_not_found(merge_commit_id.as_str().into()),
+        )?;
+        let parent_id = merge_commit
+            .parent_ids
+            .first()
+            .ok_or(OxenError::basic_str(format!(
+                "Merge commit {merge_commit_id} has no parents"
+            )))?;
+        let parent = repositories::commits::get_by_id(repo, parent_id)?
+            .ok_or(OxenError::revision_not_found(parent_id.as_str().into()))?;
+        return Ok((parent, merge_commit));
+    }
+
+    let (base, head) = branches(repo, merge_request)?;
+    let base_commit = repositories::commits::get_by_id(repo, &base.commit_id)?.ok_or(
+        OxenError::revision_not_found(base.commit_id.as_str().into()),
+    )?;
+    let head_commit = repositories::commits::get_by_id(repo, &head.commit_id)?.ok_or(
+        OxenError::revision_not_found(head.commit_id.as_str().into()),
+    )?;
+    let merge_base =
+        repositories::commits::merge_base(repo, &base_commit, &head_commit)?.unwrap_or(base_commit);
+    Ok((merge_base, head_commit))
+}
+
+/// Merge the head branch into the base branch and mark the request as merged.
+/// Errors with the conflicting paths if the branches cannot be merged cleanly.
+pub fn merge(
+    repo: &LocalRepository,
+    id_or_number: impl AsRef<str>,
+) -> Result<MergeRequest, OxenError> {
+    let mut merge_request = get_open(repo, id_or_number)?;
+    let (base, head) = branches(repo, &merge_request)?;
+
+    let conflicts = repositories::merge::list_conflicts_between_branches(repo, &base, &head)?;
+    if !conflicts.is_empty() {
+        return Err(OxenError::merge_conflict(format!(
+            "Merge request {} has conflicts in {}",
+            merge_request.number,
+            conflicts
+                .iter()
+                .map(|path| path.to_string_lossy())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )));
+    }
+
+    // Lock the base so no one pushes to it while we merge
+    repositories::branches::lock(repo, &base.name)?;
+    let result = repositories::merge::merge_into_base(repo, &head, &base);
+    repositories::branches::unlock(repo, &base.name)?;
+
+    let Some(commit) = result? else {
+        return Err(OxenError::merge_conflict(format!(
+            "Merge request {} has conflicts",
+            merge_request.number
+        )));
+    };
+
+    merge_request.status = MergeRequestStatus::Merged;
+    merge_request.merge_commit_id = Some(commit.id);
+    merge_request.updated_at = OffsetDateTime::now_utc();
+    save(repo, &merge_request)?;
+    Ok(merge_request)
+}
+
+/// Close a merge request without merging it
+pub fn close(
+    repo: &LocalRepository,
+    id_or_number: impl AsRef<str>,
+) -> Result<MergeRequest, OxenError> {
+    let mut merge_request = get_open(repo, id_or_number)?;
+    merge_request.status = MergeRequestStatus::Closed;
+    merge_request.updated_at = OffsetDateTime::now_utc();
+    save(repo, &merge_request)?;
+    Ok(merge_request)
+}
+
+fn get_open(
+    repo: &LocalRepository,
+    id_or_number: impl AsRef<str>,
+) -> Result<MergeRequest, OxenError> {
+    let id_or_number = id_or_number.as_ref();
+    let Some(merge_request) = get(repo, id_or_number)? else {
+        return Err(OxenError::resource_not_found(id_or_number));
+    };
+
+    if merge_request.status != MergeRequestStatus::Open {
+        return Err(OxenError::basic_str(format!(
+            "Merge request {} is already {}",
+            merge_request.number, merge_request.status
+        )));
+    }
+    Ok(merge_request)
+}
+
+fn branches(
+    repo: &LocalRepository,
+    merge_request: &MergeRequest,
+) -> Result<(Branch, Branch), OxenError> {
+    let base = repositories::branches::get_by_name(repo, &merge_request.base)?
+        .ok_or(OxenError::local_branch_not_found(&merge_request.base))?;
+    let head = repositories::branches::get_by_name(repo, &merge_request.head)?
+        .ok_or(OxenError::local_branch_not_found(&merge_request.head))?;
+    Ok((base, head))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constants::DEFAULT_BRANCH_NAME;
+    use crate::error::OxenError;
+    use crate::model::{LocalRepository, MergeRequestStatus, NewMergeRequest};
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    fn new_request(head: &str) -> NewMergeRequest {
+        NewMergeRequest {
+            title: format!("Merge {head}"),
+            description: String::new(),
+            base: DEFAULT_BRANCH_NAME.to_string(),
+            head: head.to_string(),
+            author: String::from("Ox"),
+            email: String::from("ox@oxen.ai"),
+        }
+    }
+
+    async fn commit_on_branch(
+        repo: &LocalRepository,
+        branch: &str,
+        file: &str,
+        contents: &str,
+    ) -> Result<(), OxenError> {
+        repositories::branches::create_checkout(repo, branch)?;
+        let path = repo.path.join(file);
+        util::fs::write_to_path(&path, contents)?;
+        repositories::add(repo, &path)?;
+        repositories::commit(repo, &format!("Writing {file} on {branch}"))?;
+        repositories::checkout(repo, DEFAULT_BRANCH_NAME).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_merge_request_create_diff_and_merge() -> Result<(), OxenError> {
+        test::run_one_commit_local_repo_test_async(|repo| async move {
+            commit_on_branch(&repo, "labels", "labels.txt", "cat").await?;
+
+            let mr = repositories::merge_requests::create(&repo, new_request("labels"))?;
+            assert_eq!(mr.number, 1);
+            assert_eq!(mr.status, MergeRequestStatus::Open);
+
+            // Can be looked up by number as well as id
+            let by_number = repositories::merge_requests::get(&repo, "1")?.unwrap();
+            assert_eq!(by_number.id, mr.id);
+
+            let (base, head) = repositories::merge_requests::base_and_head_commits(&repo, &mr)?;
+            assert_eq!(base.id, repositories::commits::head_commit(&repo)?.id);
+            let diff = repositories::diffs::list_diff_entries(
+                &repo,
+                &base,
+                &head,
+                std::path::PathBuf::from(""),
+                1,
+                10,
+            )?;
+            assert_eq!(diff.counts.added, 1);
+
+            let merged = repositories::merge_requests::merge(&repo, &mr.id)?;
+            assert_eq!(merged.status, MergeRequestStatus::Merged);
+            assert!(merged.merge_commit_id.is_some());
+
+            // Cannot be merged or closed again
+            assert!(repositories::merge_requests::merge(&repo, &mr.id).is_err());
+            assert!(repositories::merge_requests::close(&repo, &mr.id).is_err());
+
+            let open =
+                repositories::merge_requests::list(&repo, Some(MergeRequestStatus::Open), None)?;
+            assert!(open.is_empty());
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[test]
+    fn test_merge_request_close() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            repositories::branches::create_from_head(&repo, "labels")?;
+            let first = repositories::merge_requests::create(&repo, new_request("labels"))?;
+            let second = repositories::merge_requests::create(&repo, new_request("labels"))?;
+            assert_eq!(second.number, first.number + 1);
+
+            let closed = repositories::merge_requests::close(&repo, "1")?;
+            assert_eq!(closed.status, MergeRequestStatus::Closed);
+
+            let open = repositories::merge_requests::list(
+                &repo,
+                Some(MergeRequestStatus::Open),
+                Some(DEFAULT_BRANCH_NAME),
+            )?;
+            assert_eq!(open.len(), 1);
+            assert_eq!(open[0].id, second.id);
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_merge_request_unknown_branch() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let result = repositories::merge_requests::create(&repo, new_request("missing"));
+            assert!(result.is_err());
+            Ok(())
+        })
+    }
+}
@@ -15,7 +15,9 @@ use crate::util;
 use std::path::{Path, PathBuf};
 
 pub mod audio;
+pub mod geospatial;
 pub mod image;
+pub mod sqlite;
 pub mod tabular;
 pub mod text;
 pub mod video;
@@ -77,16 +79,17 @@ pub fn from_commit_entry(
     entry: &CommitEntry,
     commit: &Commit,
 ) -> Result<MetadataEntry, OxenError> {
-    let path = util::fs::version_path(repo, entry);
+    let version_path = util::fs::plain_version_path(repo, entry)?;
+    let path = version_path.path();
     let base_name = entry
         .path
         .file_name()
-        .ok_or(OxenError::file_has_no_name(&path))?;
-    let size = get_file_size(&path)?;
-    let mime_type = util::fs::file_mime_type(&path);
-    let data_type = util::fs::datatype_from_mimetype(&path, mime_type.as_str());
-    let extension = util::fs::file_extension(&path);
-    let metadata = get_file_metadata(&path, &data_type)?;
+        .ok_or(OxenError::file_has_no_name(path))?;
+    let size = get_file_size(path)?;
+    let mime_type = util::fs::file_mime_type(path);
+    let data_type = util::fs::datatype_from_mimetype(path, mime_type.as_str());
+    let extension = util::fs::file_extension(path);
+    let metadata = get_file_metadata(path, &data_type)?;
 
     Ok(MetadataEntry {
         filename: base_name.to_string_lossy().to_string(),
@@ -203,6 +206,24 @@ pub fn get_file_metadata_with_extension(
                 Ok(None)
             }
         },
+        EntryDataType::Geospatial => {
+            match geospatial::get_metadata_with_extension(path, extension) {
+                Ok(metadata) => Ok(Some(GenericMetadata::MetadataGeospatial(metadata))),
+                Err(err) => {
+                    log::warn!("could not compute geospatial metadata: {}", err);
+                    Ok(None)
+                }
+            }
+        }
+        EntryDataType::Tabular if util::fs::is_sqlite(path.as_ref()) => {
+            match sqlite::get_metadata(path) {
+                Ok(metadata) => Ok(Some(GenericMetadata::MetadataSqlite(metadata))),
+                Err(err) => {
+                    log::warn!("could not compute sqlite metadata: {}", err);
+                    Ok(None)
+                }
+            }
+        }
         EntryDataType::Tabular => match tabular::get_metadata_with_extension(path, extension) {
             Ok(metadata) => Ok(Some(GenericMetadata::MetadataTabular(metadata))),
             Err(err) => {
@@ -223,6 +244,23 @@ pub fn get_file_metadata(
     get_file_metadata_with_extension(path, data_type, &util::fs::file_extension(path))
 }
 
+/// Returns metadata based on data_type, including the perceptual hash of images
+/// if the repository has opted in to computing them
+pub fn get_file_metadata_for_repo(
+    repo: &LocalRepository,
+    path: impl AsRef<Path>,
+    data_type: &EntryDataType,
+) -> Result<Option<GenericMetadata>, OxenError> {
+    let path = path.as_ref();
+    let mut metadata = get_file_metadata(path, data_type)?;
+    if repo.perceptual_hash() {
+        if let Some(GenericMetadata::MetadataImage(image_metadata)) = &mut metadata {
+            image::add_perceptual_hash(path, image_metadata);
+        }
+    }
+    Ok(metadata)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::model::EntryDataType;
@@ -0,0 +1,90 @@
+//! Helper functions to get metadata from GeoJSON and GeoParquet files.
+//!
+
+use crate::core::df::{geo, tabular};
+use crate::error::OxenError;
+use crate::model::metadata::MetadataGeospatial;
+
+use std::path::Path;
+
+/// Detects the size, schema and geometry columns for the given file.
+pub fn get_metadata_with_extension(
+    path: impl AsRef<Path>,
+    extension: &str,
+) -> Result<MetadataGeospatial, OxenError> {
+    let path = path.as_ref();
+    let size = tabular::get_size_with_extension(path, Some(extension))?;
+    let schema = tabular::get_schema_with_extension(path, Some(extension))?;
+    let (primary_column, geometry_columns) = match extension {
+        "geojson" => (
+            Some(geo::GEOJSON_GEOMETRY_COLUMN.to_string()),
+            geo::geojson_geometry_columns(path)?,
+        ),
+        _ => {
+            let Some(metadata) = geo::read_geoparquet_metadata(path)? else {
+                return Err(OxenError::basic_str(format!(
+                    "{path:?} has no GeoParquet metadata"
+                )));
+            };
+            (
+                Some(metadata.primary_column.to_owned()),
+                geo::geoparquet_geometry_columns(&metadata)?,
+            )
+        }
+    };
+    Ok(MetadataGeospatial::new(
+        size.width,
+        size.height,
+        schema,
+        primary_column,
+        geometry_columns,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::OxenError;
+    use crate::model::metadata::generic_metadata::GenericMetadata;
+    use crate::model::EntryDataType;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_get_metadata_geojson() -> Result<(), OxenError> {
+        test::run_empty_dir_test(|dir| {
+            let path = dir.join("stations.geojson");
+            util::fs::write_to_path(
+                &path,
+                r#"{
+                    "type": "FeatureCollection",
+                    "crs": {"type": "name", "properties": {"name": "EPSG:4326"}},
+                    "features": [
+                        {"type": "Feature", "properties": {"id": 1, "elevation": 12.5},
+                         "geometry": {"type": "Point", "coordinates": [2.35, 48.85]}},
+                        {"type": "Feature", "properties": {"id": 2, "elevation": 30.0},
+                         "geometry": {"type": "Point", "coordinates": [13.40, 52.52]}}
+                    ]
+                }"#,
+            )?;
+
+            let metadata = repositories::metadata::get(&path)?;
+            assert_eq!(metadata.data_type, EntryDataType::Geospatial);
+            let Some(GenericMetadata::MetadataGeospatial(metadata)) = metadata.metadata else {
+                panic!("Wrong metadata type");
+            };
+            assert_eq!(metadata.tabular.height, 2);
+            assert_eq!(metadata.tabular.width, 3);
+            assert_eq!(
+                metadata.geospatial.primary_column,
+                Some(String::from("geometry"))
+            );
+            let geometry = &metadata.geospatial.geometry_columns[0];
+            assert_eq!(geometry.geometry_types, vec!["Point"]);
+            assert!(geometry.crs.as_ref().unwrap().contains("EPSG:4326"));
+            assert_eq!(geometry.bbox, Some(vec![2.35, 48.85, 13.40, 52.52]));
+
+            Ok(())
+        })
+    }
+}
@@ -2,34 +2,161 @@
 //!
 
 use crate::error::OxenError;
-use crate::model::metadata::metadata_image::MetadataImage;
+use crate::model::metadata::metadata_image::{ImgColorSpace, MetadataImage, MetadataImageExif};
+use crate::util;
 
 use std::fs::File;
 
-use image::ImageReader;
+use image::{ColorType, ImageDecoder, ImageReader};
 use std::io::BufReader;
 use std::path::Path;
 
+// EXIF tags we read from IFD0
+const EXIF_TAG_MAKE: u16 = 0x010F;
+const EXIF_TAG_MODEL: u16 = 0x0110;
+const EXIF_TAG_ORIENTATION: u16 = 0x0112;
+const EXIF_TAG_SOFTWARE: u16 = 0x0131;
+const EXIF_TAG_DATE_TIME: u16 = 0x0132;
+
+// EXIF value formats we know how to decode
+const EXIF_FORMAT_ASCII: u16 = 2;
+const EXIF_FORMAT_SHORT: u16 = 3;
+
 /// Detects the image metadata for the given file.
 pub fn get_metadata(path: impl AsRef<Path>) -> Result<MetadataImage, OxenError> {
     let file = File::open(path)?;
     let reader = BufReader::new(file);
     let reader = ImageReader::new(reader).with_guessed_format()?;
 
-    match reader.into_dimensions() {
-        Ok((width, height)) => Ok(MetadataImage::new(width, height)),
+    let mut decoder = match reader.into_decoder() {
+        Ok(decoder) => decoder,
         Err(e) => {
             log::debug!("Could not get image metadata {:?}", e);
-            Err(OxenError::basic_str("Could not get image metadata"))
+            return Err(OxenError::basic_str("Could not get image metadata"));
+        }
+    };
+
+    let (width, height) = decoder.dimensions();
+    let color_type = decoder.color_type();
+    let mut metadata = MetadataImage::new(width, height);
+    metadata.image.color_space = Some(color_space(color_type));
+    metadata.image.num_channels = Some(color_type.channel_count());
+
+    // EXIF is best effort, a bad chunk should not fail the whole entry
+    match decoder.exif_metadata() {
+        Ok(Some(chunk)) => metadata.image.exif = parse_exif(&chunk),
+        Ok(None) => {}
+        Err(e) => log::debug!("Could not read exif metadata {:?}", e),
+    }
+
+    Ok(metadata)
+}
+
+/// Computes and sets the perceptual hash on the metadata, skipping it if the image cannot be decoded
+pub fn add_perceptual_hash(path: impl AsRef<Path>, metadata: &mut MetadataImage) {
+    match util::image::perceptual_hash(path) {
+        Ok(phash) => metadata.image.phash = Some(format!("{:016x}", phash)),
+        Err(e) => log::debug!("Could not compute perceptual hash {:?}", e),
+    }
+}
+
+fn color_space(color_type: ColorType) -> ImgColorSpace {
+    match color_type {
+        ColorType::L8 => ImgColorSpace::Grayscale,
+        ColorType::La8 => ImgColorSpace::GrayscaleAlpha,
+        ColorType::Rgb8 => ImgColorSpace::RGB,
+        ColorType::Rgba8 => ImgColorSpace::RGBA,
+        ColorType::L16 => ImgColorSpace::Grayscale16,
+        ColorType::La16 => ImgColorSpace::GrayscaleAlpha16,
+        ColorType::Rgb16 => ImgColorSpace::Rgb16,
+        ColorType::Rgba16 => ImgColorSpace::Rgba16,
+        ColorType::Rgb32F => ImgColorSpace::Rgb32F,
+        ColorType::Rgba32F => ImgColorSpace::Rgba32F,
+        _ => ImgColorSpace::Unknown,
+    }
+}
+
+/// Parses the tags we care about out of the first IFD of a raw TIFF formatted EXIF chunk
+pub fn parse_exif(chunk: &[u8]) -> Option<MetadataImageExif> {
+    let little_endian = match chunk.get(0..4)? {
+        [0x49, 0x49, 42, 0] => true,
+        [0x4d, 0x4d, 0, 42] => false,
+        _ => return None,
+    };
+
+    let read_u16 = |offset: usize| -> Option<u16> {
+        let bytes: [u8; 2] = chunk.get(offset..offset + 2)?.try_into().ok()?;
+        Some(if little_endian {
+            u16::from_le_bytes(bytes)
+        } else {
+            u16::from_be_bytes(bytes)
+        })
+    };
+    let read_u32 = |offset: usize| -> Option<u32> {
+        let bytes: [u8; 4] = chunk.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    };
+
+    let ifd_offset = read_u32(4)? as usize;
+    let num_entries = read_u16(ifd_offset)? as usize;
+
+    let mut exif = MetadataImageExif::default();
+    for i in 0..num_entries {
+        // Each IFD entry is 12 bytes: tag, format, count, value or offset
+        let entry = ifd_offset + 2 + i * 12;
+        let tag = read_u16(entry)?;
+        let format = read_u16(entry + 2)?;
+        let count = read_u32(entry + 4)? as usize;
+
+        match (tag, format) {
+            (EXIF_TAG_ORIENTATION, EXIF_FORMAT_SHORT) => {
+                exif.orientation = read_u16(entry + 8);
+            }
+            (
+                EXIF_TAG_MAKE | EXIF_TAG_MODEL | EXIF_TAG_SOFTWARE | EXIF_TAG_DATE_TIME,
+                EXIF_FORMAT_ASCII,
+            ) => {
+                // Strings of 4 bytes or less are stored inline, otherwise at an offset
+                let start = if count <= 4 {
+                    entry + 8
+                } else {
+                    read_u32(entry + 8)? as usize
+                };
+                let Some(bytes) = chunk.get(start..start + count) else {
+                    continue;
+                };
+                let value = String::from_utf8_lossy(bytes)
+                    .trim_end_matches('\0')
+                    .trim()
+                    .to_string();
+                match tag {
+                    EXIF_TAG_MAKE => exif.make = Some(value),
+                    EXIF_TAG_MODEL => exif.model = Some(value),
+                    EXIF_TAG_SOFTWARE => exif.software = Some(value),
+                    _ => exif.date_time = Some(value),
+                }
+            }
+            _ => {}
         }
     }
+
+    Some(exif)
 }
 
 #[cfg(test)]
 mod tests {
 
+    use std::path::Path;
+
+    use crate::core::v0_19_0::index::CommitMerkleTree;
+    use crate::error::OxenError;
     use crate::model::entry::entry_data_type::EntryDataType;
     use crate::model::metadata::generic_metadata::GenericMetadata;
+    use crate::model::metadata::metadata_image::ImgColorSpace;
     use crate::model::metadata::MetadataImage;
     use crate::repositories;
     use crate::test;
@@ -51,6 +178,8 @@ mod tests {
 
         assert_eq!(metadata.image.width, 499);
         assert_eq!(metadata.image.height, 375);
+        assert_eq!(metadata.image.color_space, Some(ImgColorSpace::RGB));
+        assert_eq!(metadata.image.num_channels, Some(3));
     }
 
     #[test]
@@ -69,6 +198,8 @@ mod tests {
 
         assert_eq!(metadata.image.width, 499);
         assert_eq!(metadata.image.height, 375);
+        assert_eq!(metadata.image.color_space, Some(ImgColorSpace::RGBA));
+        assert_eq!(metadata.image.num_channels, Some(4));
     }
 
     #[test]
@@ -88,6 +219,7 @@ mod tests {
 
         assert_eq!(metadata.image.width, 499);
         assert_eq!(metadata.image.height, 375);
+        assert_eq!(metadata.image.num_channels, Some(1));
     }
 
     #[test]
@@ -107,4 +239,59 @@ mod tests {
         assert_eq!(metadata.image.width, 28);
         assert_eq!(metadata.image.height, 28);
     }
+
+    #[test]
+    fn test_parse_exif_little_endian() {
+        // TIFF header, one IFD at offset 8 with Make (inline "Ox\0") and Orientation
+        let mut chunk: Vec<u8> = vec![0x49, 0x49, 42, 0, 8, 0, 0, 0];
+        chunk.extend_from_slice(&2u16.to_le_bytes());
+        // Make: tag, ASCII, count 3, inline value
+        chunk.extend_from_slice(&0x010Fu16.to_le_bytes());
+        chunk.extend_from_slice(&2u16.to_le_bytes());
+        chunk.extend_from_slice(&3u32.to_le_bytes());
+        chunk.extend_from_slice(&[b'O', b'x', 0, 0]);
+        // Orientation: tag, SHORT, count 1, value 6
+        chunk.extend_from_slice(&0x0112u16.to_le_bytes());
+        chunk.extend_from_slice(&3u16.to_le_bytes());
+        chunk.extend_from_slice(&1u32.to_le_bytes());
+        chunk.extend_from_slice(&[6, 0, 0, 0]);
+
+        let exif = super::parse_exif(&chunk).unwrap();
+        assert_eq!(exif.make, Some("Ox".to_string()));
+        assert_eq!(exif.orientation, Some(6));
+        assert_eq!(exif.model, None);
+    }
+
+    #[test]
+    fn test_parse_exif_invalid_header() {
+        assert!(super::parse_exif(&[0, 1, 2, 3, 4, 5, 6, 7]).is_none());
+    }
+
+    #[test]
+    fn test_image_resolution_bucket() {
+        assert_eq!(MetadataImage::new(28, 28).resolution_bucket(), "0-64");
+        assert_eq!(MetadataImage::new(499, 375).resolution_bucket(), "257-512");
+        assert_eq!(
+            MetadataImage::new(1024, 2000).resolution_bucket(),
+            "1025-2048"
+        );
+        assert_eq!(MetadataImage::new(8000, 10).resolution_bucket(), "4097+");
+    }
+
+    #[test]
+    fn test_dir_node_image_resolution_counts() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let commit = repositories::commits::head_commit(&repo)?;
+            let root = CommitMerkleTree::dir_without_children(&repo, &commit, Path::new(""))?
+                .unwrap()
+                .dir()?;
+
+            let num_images = root.data_type_counts.get("image").copied().unwrap_or(0);
+            let num_bucketed: u64 = root.image_resolution_counts.values().sum();
+            assert!(num_images > 0);
+            assert_eq!(num_images, num_bucketed);
+
+            Ok(())
+        })
+    }
 }
@@ -0,0 +1,68 @@
+//! Helper functions to get metadata from SQLite databases.
+//!
+
+use crate::core::df::sqlite;
+use crate::error::OxenError;
+use crate::model::metadata::metadata_sqlite::MetadataSqliteTable;
+use crate::model::metadata::MetadataSqlite;
+
+use std::path::Path;
+
+/// Detects the size and schema of every table in the database.
+pub fn get_metadata(path: impl AsRef<Path>) -> Result<MetadataSqlite, OxenError> {
+    let path = path.as_ref();
+    let mut tables = vec![];
+    for name in sqlite::list_tables(path)? {
+        let size = sqlite::table_size(path, &name)?;
+        let schema = sqlite::table_schema(path, &name)?;
+        tables.push(MetadataSqliteTable {
+            name,
+            width: size.width,
+            height: size.height,
+            schema,
+        });
+    }
+    Ok(MetadataSqlite::new(tables))
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::Connection;
+
+    use crate::error::OxenError;
+    use crate::model::metadata::generic_metadata::GenericMetadata;
+    use crate::model::EntryDataType;
+    use crate::repositories;
+    use crate::test;
+
+    #[test]
+    fn test_get_metadata_sqlite() -> Result<(), OxenError> {
+        test::run_empty_dir_test(|dir| {
+            let path = dir.join("annotations.sqlite");
+            let conn = Connection::open(&path)?;
+            conn.execute_batch(
+                "CREATE TABLE labels (file TEXT, label TEXT);
+                 INSERT INTO labels VALUES ('a.jpg', 'cat'), ('b.jpg', 'dog'), ('c.jpg', 'cat');
+                 CREATE TABLE boxes (file TEXT, x REAL, y REAL, w REAL, h REAL);",
+            )?;
+            drop(conn);
+
+            let metadata = repositories::metadata::get(&path)?;
+            assert_eq!(metadata.data_type, EntryDataType::Tabular);
+
+            let Some(GenericMetadata::MetadataSqlite(metadata)) = metadata.metadata else {
+                panic!("Wrong metadata type");
+            };
+            assert_eq!(metadata.sqlite.tables.len(), 2);
+            let labels = metadata.get_table("labels").unwrap();
+            assert_eq!(labels.width, 2);
+            assert_eq!(labels.height, 3);
+            assert_eq!(labels.schema.fields[1].name, "label");
+            let boxes = metadata.get_table("boxes").unwrap();
+            assert_eq!(boxes.width, 5);
+            assert_eq!(boxes.height, 0);
+
+            Ok(())
+        })
+    }
+}
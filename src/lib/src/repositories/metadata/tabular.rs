@@ -4,6 +4,7 @@
 use crate::core::df::tabular;
 use crate::error::OxenError;
 use crate::model::metadata::MetadataTabular;
+use crate::util;
 
 use std::path::Path;
 
@@ -12,7 +13,12 @@ pub fn get_metadata(path: impl AsRef<Path>) -> Result<MetadataTabular, OxenError
     let path = path.as_ref();
     let size = tabular::get_size(path)?;
     let schema = tabular::get_schema(path)?;
-    Ok(MetadataTabular::new(size.width, size.height, schema))
+    let metadata = MetadataTabular::new(size.width, size.height, schema);
+    Ok(with_column_stats(
+        metadata,
+        path,
+        &util::fs::file_extension(path),
+    ))
 }
 
 pub fn get_metadata_with_extension(
@@ -22,7 +28,21 @@ pub fn get_metadata_with_extension(
     let path = path.as_ref();
     let size = tabular::get_size_with_extension(path, Some(extension))?;
     let schema = tabular::get_schema_with_extension(path, Some(extension))?;
-    Ok(MetadataTabular::new(size.width, size.height, schema))
+    let metadata = MetadataTabular::new(size.width, size.height, schema);
+    Ok(with_column_stats(metadata, path, extension))
+}
+
+// Stats are a nice to have, a file we can read but not summarize still gets added
+fn with_column_stats(
+    mut metadata: MetadataTabular,
+    path: &Path,
+    extension: &str,
+) -> MetadataTabular {
+    match tabular::get_column_stats_with_extension(path, extension) {
+        Ok(stats) => metadata.tabular.column_stats = Some(stats),
+        Err(err) => log::warn!("could not compute column stats for {path:?}: {err}"),
+    }
+    metadata
 }
 
 #[cfg(test)]
@@ -49,5 +69,9 @@ mod tests {
 
         assert_eq!(metadata.tabular.width, 11);
         assert_eq!(metadata.tabular.height, 200_000);
+
+        let stats = metadata.tabular.column_stats.unwrap();
+        assert_eq!(stats.len(), 11);
+        assert!(stats.iter().all(|s| s.null_count == 0));
     }
 }
@@ -33,11 +33,15 @@ pub fn get_metadata(path: impl AsRef<Path>) -> Result<MetadataVideo, OxenError>
                 .first()
                 .ok_or(OxenError::basic_str("Could not get video track"))?;
 
-            Ok(MetadataVideo::new(
-                duration,
-                video.width() as usize,
-                video.height() as usize,
-            ))
+            let mut metadata =
+                MetadataVideo::new(duration, video.width() as usize, video.height() as usize);
+            let fps = video.frame_rate();
+            if fps > 0.0 {
+                metadata.video.fps = Some(fps);
+            }
+            metadata.video.codec = video.media_type().ok().map(|t| t.to_string());
+
+            Ok(metadata)
         }
         Err(err) => {
             let err = format!("Could not get video metadata {:?}", err);
@@ -48,11 +52,16 @@ pub fn get_metadata(path: impl AsRef<Path>) -> Result<MetadataVideo, OxenError>
 
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
+
+    use crate::core::v0_19_0::index::CommitMerkleTree;
+    use crate::error::OxenError;
     use crate::model::metadata::generic_metadata::GenericMetadata;
     use crate::model::metadata::MetadataVideo;
     use crate::model::EntryDataType;
     use crate::repositories;
     use crate::test;
+    use crate::util;
 
     use approx::assert_relative_eq;
 
@@ -74,6 +83,8 @@ mod tests {
         assert_eq!(metadata.video.width, 128);
         assert_eq!(metadata.video.height, 176);
         assert_relative_eq!(metadata.video.num_seconds, 1.6);
+        assert!(metadata.video.fps.is_some());
+        assert!(metadata.video.codec.is_some());
     }
 
     #[test]
@@ -89,4 +100,33 @@ mod tests {
         // We do not know how to parse mov files yet
         assert!(metadata.metadata.is_none());
     }
+
+    #[test]
+    fn test_dir_node_rolls_up_media_durations() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let video_dir = repo.path.join("videos");
+            util::fs::create_dir_all(&video_dir)?;
+            let video_file = video_dir.join("basketball.mp4");
+            util::fs::copy(
+                test::test_video_file_with_name("basketball.mp4"),
+                &video_file,
+            )?;
+            let audio_file = video_dir.join("speech.wav");
+            util::fs::copy(
+                test::test_audio_file_with_name("121-121726-0005.wav"),
+                &audio_file,
+            )?;
+
+            repositories::add(&repo, &video_dir)?;
+            let commit = repositories::commit(&repo, "Adding media")?;
+
+            let dir = CommitMerkleTree::dir_without_children(&repo, &commit, Path::new("videos"))?
+                .unwrap()
+                .dir()?;
+            assert_eq!(dir.data_type_durations.get("video"), Some(&1600));
+            assert_eq!(dir.data_type_durations.get("audio"), Some(&3100));
+
+            Ok(())
+        })
+    }
 }
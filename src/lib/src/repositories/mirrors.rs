@@ -0,0 +1,223 @@
+//! # Mirrors
+//!
+//! Replicate a repository on this server to other oxen-servers, for availability and to
+//! serve datasets closer to the people using them. Every branch is pushed to the mirror
+//! and branches deleted here are deleted there, either after each push or on a schedule.
+//! Mirrors are configured per repository in `.oxen/mirrors.json`, pushes authenticate
+//! with the auth config of the user running the server.
+//!
+
+use http::Uri;
+use time::OffsetDateTime;
+
+use crate::api;
+use crate::constants::MIRRORS_FILE;
+use crate::error::OxenError;
+use crate::model::{LocalRepository, Mirror, NewMirror, RepoNew};
+use crate::repositories;
+use crate::util;
+
+pub fn list(repo: &LocalRepository) -> Result<Vec<Mirror>, OxenError> {
+    let path = util::fs::oxen_hidden_dir(&repo.path).join(MIRRORS_FILE);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let contents = util::fs::read_from_path(&path)?;
+    serde_json::from_str(&contents).map_err(|err| OxenError::json_file_error(&path, err))
+}
+
+pub fn get(repo: &LocalRepository, id: impl AsRef<str>) -> Result<Option<Mirror>, OxenError> {
+    let id = id.as_ref();
+    Ok(list(repo)?.into_iter().find(|mirror| mirror.id == id))
+}
+
+/// The mirrors to sync after a push
+pub fn list_on_push(repo: &LocalRepository) -> Result<Vec<Mirror>, OxenError> {
+    Ok(list(repo)?
+        .into_iter()
+        .filter(|mirror| mirror.syncs_on_push())
+        .collect())
+}
+
+/// The scheduled mirrors that are due for a sync
+pub fn list_due(repo: &LocalRepository) -> Result<Vec<Mirror>, OxenError> {
+    let now = OffsetDateTime::now_utc();
+    Ok(list(repo)?
+        .into_iter()
+        .filter(|mirror| mirror.is_due(now))
+        .collect())
+}
+
+pub fn create(repo: &LocalRepository, new_mirror: &NewMirror) -> Result<Mirror, OxenError> {
+    repo_new_for_url(&new_mirror.url)?;
+    if new_mirror.interval_seconds == Some(0) {
+        return Err(OxenError::basic_str(
+            "Mirror interval must be at least one second",
+        ));
+    }
+
+    let mut mirrors = list(repo)?;
+    if mirrors.iter().any(|mirror| mirror.url == new_mirror.url) {
+        return Err(OxenError::basic_str(format!(
+            "Repository is already mirrored to {}",
+            new_mirror.url
+        )));
+    }
+
+    let mirror = Mirror {
+        id: uuid::Uuid::new_v4().to_string(),
+        url: new_mirror.url.clone(),
+        interval_seconds: new_mirror.interval_seconds,
+        last_synced_at: None,
+        last_error: None,
+        created_at: OffsetDateTime::now_utc(),
+    };
+    mirrors.push(mirror.clone());
+    write(repo, &mirrors)?;
+    Ok(mirror)
+}
+
+pub fn delete(repo: &LocalRepository, id: impl AsRef<str>) -> Result<Mirror, OxenError> {
+    let id = id.as_ref();
+    let mut mirrors = list(repo)?;
+    let Some(index) = mirrors.iter().position(|mirror| mirror.id == id) else {
+        return Err(OxenError::resource_not_found(format!("mirror {id}")));
+    };
+    let mirror = mirrors.remove(index);
+    write(repo, &mirrors)?;
+    Ok(mirror)
+}
+
+/// Push every branch to the mirror and delete the branches it has that we don't,
+/// creating the repository on the mirror if needed. The outcome is recorded on the
+/// mirror, which is returned.
+pub async fn sync(repo: &LocalRepository, id: impl AsRef<str>) -> Result<Mirror, OxenError> {
+    let id = id.as_ref();
+    let mirror = get(repo, id)?.ok_or(OxenError::resource_not_found(format!("mirror {id}")))?;
+
+    let result = push_all(repo, &mirror).await;
+    let mut mirrors = list(repo)?;
+    let Some(mirror) = mirrors.iter_mut().find(|mirror| mirror.id == id) else {
+        // Deleted while syncing
+        return Err(OxenError::resource_not_found(format!("mirror {id}")));
+    };
+    match &result {
+        Ok(_) => {
+            mirror.last_synced_at = Some(OffsetDateTime::now_utc());
+            mirror.last_error = None;
+        }
+        Err(err) => mirror.last_error = Some(err.to_string().trim().to_string()),
+    }
+    let mirror = mirror.clone();
+    write(repo, &mirrors)?;
+    result.map(|_| mirror)
+}
+
+async fn push_all(repo: &LocalRepository, mirror: &Mirror) -> Result<(), OxenError> {
+    // Only in memory, the mirror never shows up as a remote in the repository config
+    let remote_name = format!("mirror-{}", mirror.id);
+    let mut repo = repo.clone();
+    let remote = repo.set_remote(&remote_name, &mirror.url);
+
+    let remote_repo = match api::client::repositories::get_by_remote(&remote).await? {
+        Some(remote_repo) => remote_repo,
+        None => api::client::repositories::create_empty(repo_new_for_url(&mirror.url)?).await?,
+    };
+
+    let branches = repositories::branches::list(&repo)?;
+    for branch in &branches {
+        log::debug!("mirrors::sync pushing {} to {}", branch.name, mirror.url);
+        repositories::push::push_remote_branch(&repo, &remote_name, &branch.name).await?;
+    }
+
+    for remote_branch in api::client::branches::list(&remote_repo).await? {
+        if !branches
+            .iter()
+            .any(|branch| branch.name == remote_branch.name)
+        {
+            log::debug!(
+                "mirrors::sync deleting {} from {}",
+                remote_branch.name,
+                mirror.url
+            );
+            api::client::branches::delete(&remote_repo, &remote_branch.name).await?;
+        }
+    }
+    Ok(())
+}
+
+// The repository to create on the mirror, the url must point at a repository,
+// ex: https://hub.oxen.ai/ox/CatsVsDogs
+fn repo_new_for_url(url: &str) -> Result<RepoNew, OxenError> {
+    let uri = url.parse::<Uri>()?;
+    if uri.scheme().is_none() || uri.authority().is_none() {
+        return Err(OxenError::basic_str(format!(
+            "Invalid mirror url {url:?}, expected a url like https://hub.oxen.ai/namespace/repo"
+        )));
+    }
+    let mut repo_new = RepoNew::from_url(url)?;
+    // from_url drops the port of the host
+    repo_new.host = uri.authority().map(|authority| authority.to_string());
+    repo_new.is_public = Some(false);
+    Ok(repo_new)
+}
+
+fn write(repo: &LocalRepository, mirrors: &[Mirror]) -> Result<(), OxenError> {
+    let path = util::fs::oxen_hidden_dir(&repo.path).join(MIRRORS_FILE);
+    util::fs::write_to_path(&path, serde_json::to_string_pretty(mirrors)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::OxenError;
+    use crate::model::NewMirror;
+    use crate::repositories;
+    use crate::test;
+
+    #[test]
+    fn test_mirror_create_schedule_and_delete() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let on_push = repositories::mirrors::create(
+                &repo,
+                &NewMirror {
+                    url: String::from("http://localhost:3001/ox/mirror"),
+                    interval_seconds: None,
+                },
+            )?;
+            let scheduled = repositories::mirrors::create(
+                &repo,
+                &NewMirror {
+                    url: String::from("http://localhost:3002/ox/mirror"),
+                    interval_seconds: Some(60),
+                },
+            )?;
+
+            // The same url can't be added twice, and it has to point at a repository
+            let duplicate = NewMirror {
+                url: on_push.url.clone(),
+                interval_seconds: None,
+            };
+            assert!(repositories::mirrors::create(&repo, &duplicate).is_err());
+            let invalid = NewMirror {
+                url: String::from("not a url"),
+                interval_seconds: None,
+            };
+            assert!(repositories::mirrors::create(&repo, &invalid).is_err());
+
+            let on_push_ids: Vec<String> = repositories::mirrors::list_on_push(&repo)?
+                .into_iter()
+                .map(|mirror| mirror.id)
+                .collect();
+            assert_eq!(on_push_ids, vec![on_push.id.clone()]);
+            // Never synced, so it is due
+            let due = repositories::mirrors::list_due(&repo)?;
+            assert_eq!(due.len(), 1);
+            assert_eq!(due[0].id, scheduled.id);
+
+            repositories::mirrors::delete(&repo, &on_push.id)?;
+            assert_eq!(repositories::mirrors::list(&repo)?.len(), 1);
+            assert!(repositories::mirrors::delete(&repo, &on_push.id).is_err());
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,47 @@
+//! # oxen mount
+//!
+//! Expose a revision as a read only filesystem without checking it out.
+//! Requires the `mount` feature and FUSE on the host.
+//!
+
+use std::path::Path;
+
+use crate::core;
+use crate::core::versions::MinOxenVersion;
+use crate::error::OxenError;
+use crate::model::{LocalRepository, RemoteRepository};
+use crate::repositories;
+
+/// Mount a revision read only at the mountpoint, blocking until it is unmounted.
+///
+/// Files missing from the local version store are fetched block by block from the
+/// remote repository if one is given. This calls into the async api client, so run
+/// it on a blocking thread of the tokio runtime.
+pub fn mount(
+    repo: &LocalRepository,
+    revision: impl AsRef<str>,
+    mountpoint: impl AsRef<Path>,
+    remote_repo: Option<RemoteRepository>,
+) -> Result<(), OxenError> {
+    let revision = revision.as_ref();
+    let mountpoint = mountpoint.as_ref();
+    if !mountpoint.is_dir() {
+        return Err(OxenError::basic_str(format!(
+            "Mountpoint {:?} must be an existing directory",
+            mountpoint
+        )));
+    }
+
+    let Some(commit) = repositories::revisions::get(repo, revision)? else {
+        return Err(OxenError::revision_not_found(revision.into()));
+    };
+
+    match repo.min_version() {
+        MinOxenVersion::V0_10_0 => Err(OxenError::basic_str(
+            "oxen mount requires a repository on v0.19.0 or later, run `oxen migrate` first",
+        )),
+        MinOxenVersion::V0_19_0 => {
+            core::v0_19_0::mount::mount(repo, &commit, mountpoint, remote_repo)
+        }
+    }
+}
@@ -0,0 +1,416 @@
+//! # PII Scan
+//!
+//! An opt-in scan for secrets and personal data, such as emails, social security numbers
+//! and API keys, that runs over text files and the string columns of data frames before
+//! they are committed or pushed. In `warn` mode the report is printed and the commit or
+//! push goes ahead, in `block` mode it is refused. The mode, extra rules, disabled built
+//! in rules and ignored paths live in `.oxen/pii_scan.json`.
+//!
+
+use polars::prelude::DataType;
+use regex::Regex;
+use std::collections::HashSet;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use crate::constants::PII_SCAN_FILE;
+use crate::core::commit_sync_status;
+use crate::core::df::tabular;
+use crate::error::OxenError;
+use crate::model::{
+    Commit, LocalRepository, PiiFinding, PiiRule, PiiScanConfig, PiiScanMode, PiiScanReport,
+    StagedEntryStatus,
+};
+use crate::opts::DFOpts;
+use crate::repositories;
+use crate::util;
+
+// Stop reporting a file after this many findings, a column of emails is one problem
+const MAX_FINDINGS_PER_FILE: usize = 20;
+
+/// The rules every scan starts with, by name
+pub fn builtin_rules() -> Vec<PiiRule> {
+    vec![
+        PiiRule::new("email", r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}"),
+        PiiRule::new("ssn", r"\b\d{3}-\d{2}-\d{4}\b"),
+        PiiRule::new("aws_access_key", r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b"),
+        PiiRule::new("github_token", r"\bgh[pousr]_[A-Za-z0-9]{36,}\b"),
+        PiiRule::new("slack_token", r"\bxox[abpr]-[A-Za-z0-9-]{10,}"),
+        PiiRule::new("private_key", r"-----BEGIN [A-Z ]*PRIVATE KEY-----"),
+        PiiRule::new(
+            "api_key",
+            r#"(?i)\b(?:api[_-]?key|secret|access[_-]?token|password)\b\s*[:=]\s*['"]?[A-Za-z0-9_\-/+]{16,}"#,
+        ),
+    ]
+}
+
+/// The scan config of the repository, the scan is off if it was never configured
+pub fn get_config(repo: &LocalRepository) -> Result<PiiScanConfig, OxenError> {
+    let path = util::fs::oxen_hidden_dir(&repo.path).join(PII_SCAN_FILE);
+    if !path.exists() {
+        return Ok(PiiScanConfig::default());
+    }
+    serde_json::from_str(&util::fs::read_from_path(&path)?)
+        .map_err(|err| OxenError::json_file_error(&path, err))
+}
+
+pub fn set_config(repo: &LocalRepository, config: &PiiScanConfig) -> Result<(), OxenError> {
+    // Fail on bad rules now rather than on the next commit
+    Scanner::new(config)?;
+    let path = util::fs::oxen_hidden_dir(&repo.path).join(PII_SCAN_FILE);
+    util::fs::write_to_path(&path, serde_json::to_string_pretty(config)?)
+}
+
+/// Scan the files that are staged to be added or modified
+pub fn scan_staged(repo: &LocalRepository) -> Result<PiiScanReport, OxenError> {
+    scan_staged_with(repo, &get_config(repo)?)
+}
+
+/// Scan every file in the commit
+pub fn scan_commit(repo: &LocalRepository, commit: &Commit) -> Result<PiiScanReport, OxenError> {
+    let files: Vec<(PathBuf, String)> = repositories::entries::list_file_hashes(repo, commit)?
+        .into_iter()
+        .map(|(path, hash, _)| (path, hash))
+        .collect();
+    scan_versions(repo, &get_config(repo)?, &files)
+}
+
+/// Scan the versions of files that the commits not on a remote yet would push
+pub fn scan_unpushed(repo: &LocalRepository) -> Result<PiiScanReport, OxenError> {
+    scan_unpushed_with(repo, &get_config(repo)?)
+}
+
+/// Run the scan before a commit, if it is turned on
+pub fn check_before_commit(repo: &LocalRepository) -> Result<(), OxenError> {
+    let config = get_config(repo)?;
+    if config.mode == PiiScanMode::Off {
+        return Ok(());
+    }
+    let report = scan_staged_with(repo, &config)?;
+    enforce(&config, &report, "commit")
+}
+
+/// Run the scan before a push, if it is turned on
+pub fn check_before_push(repo: &LocalRepository) -> Result<(), OxenError> {
+    let config = get_config(repo)?;
+    if config.mode == PiiScanMode::Off {
+        return Ok(());
+    }
+    let report = scan_unpushed_with(repo, &config)?;
+    enforce(&config, &report, "push")
+}
+
+fn enforce(config: &PiiScanConfig, report: &PiiScanReport, action: &str) -> Result<(), OxenError> {
+    if report.is_clean() {
+        return Ok(());
+    }
+    match config.mode {
+        PiiScanMode::Block => Err(OxenError::basic_str(format!(
+            "{report}\n\nRefusing to {action}. Remove the data, add the paths to ignore_paths in .oxen/{PII_SCAN_FILE}, or set the scan mode to warn."
+        ))),
+        _ => {
+            eprintln!("{report}");
+            Ok(())
+        }
+    }
+}
+
+fn scan_staged_with(
+    repo: &LocalRepository,
+    config: &PiiScanConfig,
+) -> Result<PiiScanReport, OxenError> {
+    let scanner = Scanner::new(config)?;
+    let status = repositories::status(repo)?;
+    let mut paths: Vec<&PathBuf> = status
+        .staged_files
+        .iter()
+        .filter(|(_, entry)| {
+            matches!(
+                entry.status,
+                StagedEntryStatus::Added | StagedEntryStatus::Modified
+            )
+        })
+        .map(|(path, _)| path)
+        .collect();
+    paths.sort();
+
+    let mut report = PiiScanReport::default();
+    for path in paths {
+        let full_path = repo.path.join(path);
+        if full_path.is_file() {
+            scanner.scan_file(path, &full_path, &mut report)?;
+        }
+    }
+    Ok(report)
+}
+
+fn scan_unpushed_with(
+    repo: &LocalRepository,
+    config: &PiiScanConfig,
+) -> Result<PiiScanReport, OxenError> {
+    let Some(head) = repositories::commits::head_commit_maybe(repo)? else {
+        return Ok(PiiScanReport::default());
+    };
+    let history = repositories::commits::list_from(repo, &head.id)?;
+
+    // Files the remote already has are not scanned again
+    let mut pushed: HashSet<(PathBuf, String)> = HashSet::new();
+    if let Some(base) = history
+        .iter()
+        .find(|commit| commit_sync_status::commit_is_pushed(repo, commit))
+    {
+        for (path, hash, _) in repositories::entries::list_file_hashes(repo, base)? {
+            pushed.insert((path, hash));
+        }
+    }
+
+    let mut files: Vec<(PathBuf, String)> = vec![];
+    let mut seen: HashSet<(PathBuf, String)> = HashSet::new();
+    for commit in history
+        .iter()
+        .take_while(|commit| !commit_sync_status::commit_is_pushed(repo, commit))
+    {
+        for (path, hash, _) in repositories::entries::list_file_hashes(repo, commit)? {
+            let key = (path, hash);
+            if !pushed.contains(&key) && seen.insert(key.clone()) {
+                files.push(key);
+            }
+        }
+    }
+    files.sort();
+    scan_versions(repo, config, &files)
+}
+
+fn scan_versions(
+    repo: &LocalRepository,
+    config: &PiiScanConfig,
+    files: &[(PathBuf, String)],
+) -> Result<PiiScanReport, OxenError> {
+    let scanner = Scanner::new(config)?;
+    let mut report = PiiScanReport::default();
+    for (path, hash) in files {
+        let version_path = util::fs::version_path_from_node(repo, hash, path);
+        if !version_path.exists() {
+            log::debug!("Skipping scan of {path:?}, version file is not local");
+            continue;
+        }
+        let plain = util::encryption::plain_path(repo, &version_path)?;
+        scanner.scan_file(path, plain.path(), &mut report)?;
+    }
+    Ok(report)
+}
+
+struct Scanner {
+    rules: Vec<(String, Regex)>,
+    ignore_paths: Vec<glob::Pattern>,
+}
+
+impl Scanner {
+    fn new(config: &PiiScanConfig) -> Result<Scanner, OxenError> {
+        let rules = builtin_rules()
+            .into_iter()
+            .filter(|rule| !config.disabled_rules.contains(&rule.name))
+            .chain(config.rules.iter().cloned())
+            .map(|rule| {
+                let regex = Regex::new(&rule.pattern).map_err(|err| {
+                    OxenError::basic_str(format!("Invalid scan rule {:?}: {err}", rule.name))
+                })?;
+                Ok((rule.name, regex))
+            })
+            .collect::<Result<Vec<_>, OxenError>>()?;
+        let ignore_paths = config
+            .ignore_paths
+            .iter()
+            .map(|pattern| {
+                glob::Pattern::new(pattern).map_err(|err| {
+                    OxenError::basic_str(format!("Invalid ignore path {pattern:?}: {err}"))
+                })
+            })
+            .collect::<Result<Vec<_>, OxenError>>()?;
+        Ok(Scanner {
+            rules,
+            ignore_paths,
+        })
+    }
+
+    /// `path` is where the file lives in the repository, `data_path` where to read it from
+    fn scan_file(
+        &self,
+        path: &Path,
+        data_path: &Path,
+        report: &mut PiiScanReport,
+    ) -> Result<(), OxenError> {
+        if self
+            .ignore_paths
+            .iter()
+            .any(|pattern| pattern.matches_path(path))
+        {
+            return Ok(());
+        }
+
+        if util::fs::is_tabular_from_extension(data_path, path) {
+            let extension = util::fs::file_extension(path);
+            match tabular::read_df_with_extension(data_path, extension, &DFOpts::empty()) {
+                Ok(df) => {
+                    report.num_files_scanned += 1;
+                    return self.scan_df(path, &df, report);
+                }
+                Err(err) => log::debug!("Scanning {path:?} as text, could not read it: {err}"),
+            }
+        }
+
+        if !util::fs::is_utf8(data_path) {
+            return Ok(());
+        }
+        report.num_files_scanned += 1;
+        let mut num_findings = 0;
+        let reader = BufReader::new(std::fs::File::open(data_path)?);
+        for (i, line) in reader.lines().enumerate() {
+            // Stop at the first invalid utf8, the file was binary after all
+            let Ok(line) = line else {
+                break;
+            };
+            for (rule, value) in self.matches(&line) {
+                report.findings.push(PiiFinding {
+                    path: path.to_path_buf(),
+                    rule,
+                    line: Some(i + 1),
+                    column: None,
+                    row: None,
+                    excerpt: mask(value),
+                });
+                num_findings += 1;
+                if num_findings >= MAX_FINDINGS_PER_FILE {
+                    return Ok(());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn scan_df(
+        &self,
+        path: &Path,
+        df: &polars::frame::DataFrame,
+        report: &mut PiiScanReport,
+    ) -> Result<(), OxenError> {
+        let mut num_findings = 0;
+        for column in df.get_columns() {
+            if column.dtype() != &DataType::String {
+                continue;
+            }
+            for (row, value) in column.str()?.into_iter().enumerate() {
+                let Some(value) = value else {
+                    continue;
+                };
+                for (rule, found) in self.matches(value) {
+                    report.findings.push(PiiFinding {
+                        path: path.to_path_buf(),
+                        rule,
+                        line: None,
+                        column: Some(column.name().to_string()),
+                        row: Some(row),
+                        excerpt: mask(found),
+                    });
+                    num_findings += 1;
+                    if num_findings >= MAX_FINDINGS_PER_FILE {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // The first match of each rule in the text
+    fn matches<'a>(&self, text: &'a str) -> Vec<(String, &'a str)> {
+        self.rules
+            .iter()
+            .filter_map(|(name, regex)| regex.find(text).map(|m| (name.clone(), m.as_str())))
+            .collect()
+    }
+}
+
+/// Keep the first and last two characters of longer values so the report is useful
+/// without repeating the secret
+fn mask(value: &str) -> String {
+    let chars: Vec<char> = value.chars().collect();
+    if chars.len() <= 6 {
+        return "*".repeat(chars.len());
+    }
+    let start: String = chars[..2].iter().collect();
+    let end: String = chars[chars.len() - 2..].iter().collect();
+    format!("{start}{}{end}", "*".repeat(chars.len() - 4))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::OxenError;
+    use crate::model::{PiiRule, PiiScanConfig, PiiScanMode};
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_pii_scan_blocks_commit_with_secrets() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            util::fs::write_to_path(
+                repo.path.join("users.csv"),
+                "name,contact\nox,ox@oxen.ai\nbessie,555-12-3456\n",
+            )?;
+            util::fs::write_to_path(
+                repo.path.join("notes.txt"),
+                "nothing to see\ninternal id EMP-000123\n",
+            )?;
+            repositories::add(&repo, &repo.path)?;
+
+            // Off by default
+            repositories::pii_scan::check_before_commit(&repo)?;
+
+            let mut config = PiiScanConfig {
+                mode: PiiScanMode::Block,
+                rules: vec![PiiRule::new("employee_id", r"EMP-\d{6}")],
+                ..PiiScanConfig::default()
+            };
+            repositories::pii_scan::set_config(&repo, &config)?;
+
+            let report = repositories::pii_scan::scan_staged(&repo)?;
+            let mut rules: Vec<&str> = report.findings.iter().map(|f| f.rule.as_str()).collect();
+            rules.sort();
+            assert_eq!(rules, vec!["email", "employee_id", "ssn"]);
+            let email = report.findings.iter().find(|f| f.rule == "email").unwrap();
+            assert_eq!(email.column.as_deref(), Some("contact"));
+            assert_eq!(email.row, Some(0));
+            assert!(!email.excerpt.contains("oxen"));
+            assert!(repositories::commit(&repo, "Add users").is_err());
+
+            config.mode = PiiScanMode::Warn;
+            config.ignore_paths = vec!["users.csv".to_string()];
+            repositories::pii_scan::set_config(&repo, &config)?;
+            assert_eq!(
+                repositories::pii_scan::scan_staged(&repo)?.findings.len(),
+                1
+            );
+            let commit = repositories::commit(&repo, "Add users")?;
+
+            // Scanning a commit reads the version files
+            let report = repositories::pii_scan::scan_commit(&repo, &commit)?;
+            assert_eq!(report.findings.len(), 1);
+            assert_eq!(report.findings[0].line, Some(2));
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_pii_scan_rejects_invalid_rule() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let config = PiiScanConfig {
+                mode: PiiScanMode::Warn,
+                rules: vec![PiiRule::new("broken", "(")],
+                ..PiiScanConfig::default()
+            };
+            assert!(repositories::pii_scan::set_config(&repo, &config).is_err());
+            Ok(())
+        })
+    }
+}
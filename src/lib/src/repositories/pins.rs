@@ -0,0 +1,195 @@
+//! # Pins
+//!
+//! Capture the exact hashes of a set of files at a revision in a pin file,
+//! then verify later that a local copy or the remote still matches it.
+//!
+
+use crate::api;
+use crate::error::OxenError;
+use crate::model::{LocalRepository, Pin, PinEntry, PinMismatch, RemoteRepository};
+use crate::repositories;
+use crate::util;
+
+use std::path::{Path, PathBuf};
+use time::OffsetDateTime;
+
+/// Create a pin of the files under `paths` at `revision`.
+/// An empty list of paths pins every file in the revision.
+pub fn create(
+    repo: &LocalRepository,
+    name: impl AsRef<str>,
+    revision: impl AsRef<str>,
+    paths: &[PathBuf],
+) -> Result<Pin, OxenError> {
+    let revision = revision.as_ref();
+    let Some(commit) = repositories::revisions::get(repo, revision)? else {
+        return Err(OxenError::revision_not_found(revision.into()));
+    };
+
+    let mut entries: Vec<PinEntry> = repositories::entries::list_file_hashes(repo, &commit)?
+        .into_iter()
+        .filter(|(path, _, _)| paths.is_empty() || paths.iter().any(|p| path.starts_with(p)))
+        .map(|(path, hash, num_bytes)| PinEntry {
+            path,
+            hash,
+            num_bytes,
+        })
+        .collect();
+
+    // Make sure every requested path exists so we do not silently pin nothing
+    for path in paths {
+        if !entries.iter().any(|e| e.path.starts_with(path)) {
+            return Err(OxenError::path_does_not_exist(path));
+        }
+    }
+
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    let hash = Pin::compute_hash(&commit.id, &entries);
+    Ok(Pin {
+        name: name.as_ref().to_string(),
+        remote: repo.remote().map(|r| r.url),
+        revision: revision.to_string(),
+        commit_id: commit.id,
+        created_at: OffsetDateTime::now_utc(),
+        entries,
+        hash,
+    })
+}
+
+/// Write the pin to a json file
+pub fn save(pin: &Pin, path: impl AsRef<Path>) -> Result<(), OxenError> {
+    let json = serde_json::to_string_pretty(pin)?;
+    util::fs::write_to_path(path, json)
+}
+
+/// Read a pin from a json file, making sure it has not been edited
+pub fn load(path: impl AsRef<Path>) -> Result<Pin, OxenError> {
+    let path = path.as_ref();
+    let contents = util::fs::read_from_path(path)?;
+    let pin: Pin = serde_json::from_str(&contents)?;
+    if !pin.has_valid_hash() {
+        return Err(OxenError::basic_str(format!(
+            "Pin file {:?} has been modified, its hash does not match its entries",
+            path
+        )));
+    }
+    Ok(pin)
+}
+
+/// Check the files in a local directory against the pin
+pub fn verify_local(pin: &Pin, dir: impl AsRef<Path>) -> Result<Vec<PinMismatch>, OxenError> {
+    let dir = dir.as_ref();
+    let mut mismatches = vec![];
+    for entry in pin.entries.iter() {
+        let full_path = dir.join(&entry.path);
+        let actual_hash = if full_path.is_file() {
+            Some(util::hasher::hash_file_contents(&full_path)?)
+        } else {
+            None
+        };
+
+        if actual_hash.as_ref() != Some(&entry.hash) {
+            mismatches.push(PinMismatch {
+                path: entry.path.to_owned(),
+                expected_hash: entry.hash.to_owned(),
+                actual_hash,
+            });
+        }
+    }
+    Ok(mismatches)
+}
+
+/// Check the files at a revision on the remote against the pin
+pub async fn verify_remote(
+    remote_repo: &RemoteRepository,
+    pin: &Pin,
+    revision: impl AsRef<str>,
+) -> Result<Vec<PinMismatch>, OxenError> {
+    let revision = revision.as_ref();
+    let mut mismatches = vec![];
+    for entry in pin.entries.iter() {
+        // Any failure to fetch the file counts as a mismatch, so verification fails closed
+        let actual_hash =
+            match api::client::metadata::get_file(remote_repo, revision, &entry.path).await {
+                Ok(response) => Some(response.entry.hash),
+                Err(err) => {
+                    log::debug!("verify_remote could not get {:?}: {}", entry.path, err);
+                    None
+                }
+            };
+
+        if actual_hash.as_ref() != Some(&entry.hash) {
+            mismatches.push(PinMismatch {
+                path: entry.path.to_owned(),
+                expected_hash: entry.hash.to_owned(),
+                actual_hash,
+            });
+        }
+    }
+    Ok(mismatches)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::constants::DEFAULT_BRANCH_NAME;
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_pin_create_save_load_verify() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let paths = vec![PathBuf::from("annotations").join("train")];
+            let pin = repositories::pins::create(&repo, "model-v1", DEFAULT_BRANCH_NAME, &paths)?;
+            assert!(!pin.entries.is_empty());
+            assert!(pin.entries.iter().all(|e| e.path.starts_with(&paths[0])));
+
+            let pin_file = repo.path.join("model-v1.pin.json");
+            repositories::pins::save(&pin, &pin_file)?;
+            let loaded = repositories::pins::load(&pin_file)?;
+            assert_eq!(loaded.commit_id, pin.commit_id);
+            assert_eq!(loaded.entries, pin.entries);
+
+            // Working dir matches the commit
+            let mismatches = repositories::pins::verify_local(&loaded, &repo.path)?;
+            assert!(mismatches.is_empty());
+
+            // Modify one of the pinned files
+            let changed = repo.path.join(&pin.entries[0].path);
+            util::fs::write_to_path(&changed, "changed")?;
+            let mismatches = repositories::pins::verify_local(&loaded, &repo.path)?;
+            assert_eq!(mismatches.len(), 1);
+            assert_eq!(mismatches[0].path, pin.entries[0].path);
+            assert!(mismatches[0].actual_hash.is_some());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_pin_load_detects_tampering() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let mut pin = repositories::pins::create(&repo, "model-v1", DEFAULT_BRANCH_NAME, &[])?;
+            pin.entries[0].hash = String::from("not-the-real-hash");
+
+            let pin_file = repo.path.join("model-v1.pin.json");
+            repositories::pins::save(&pin, &pin_file)?;
+            assert!(repositories::pins::load(&pin_file).is_err());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_pin_create_missing_path() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let paths = vec![PathBuf::from("does-not-exist")];
+            let result = repositories::pins::create(&repo, "model-v1", DEFAULT_BRANCH_NAME, &paths);
+            assert!(result.is_err());
+            Ok(())
+        })
+    }
+}
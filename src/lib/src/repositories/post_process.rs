@@ -0,0 +1,202 @@
+//! # Post Process
+//!
+//! Work the server does on pushed commits after the push returns: check that every
+//! version file arrived, summarize the files in the commit and load the merkle tree so
+//! the first reads of the commit are fast. The server queues `run` for each pushed
+//! commit, and the status written here backs the `is_synced` and `latest_synced`
+//! endpoints the client polls.
+//!
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::constants::POST_PROCESS_DIR;
+use crate::core::v0_10_0::cache::cacher_status::CacherStatusType;
+use crate::core::versions::MinOxenVersion;
+use crate::error::OxenError;
+use crate::model::{Commit, LocalRepository};
+use crate::repositories;
+use crate::util;
+
+/// Only report this many missing files, a commit missing more is failed either way
+const MAX_MISSING_FILES: usize = 100;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PostProcessStatus {
+    pub status: CacherStatusType,
+    pub status_message: String,
+    pub num_files: u64,
+    pub num_bytes: u64,
+    /// Number of files by data type, ex: {"tabular": 3, "image": 120}
+    pub data_types: HashMap<String, u64>,
+    /// Files in the commit whose version file is not on the server
+    pub missing_files: Vec<PathBuf>,
+}
+
+impl PostProcessStatus {
+    pub fn pending() -> PostProcessStatus {
+        PostProcessStatus {
+            status: CacherStatusType::Pending,
+            status_message: String::new(),
+            num_files: 0,
+            num_bytes: 0,
+            data_types: HashMap::new(),
+            missing_files: vec![],
+        }
+    }
+
+    pub fn failed(message: impl AsRef<str>) -> PostProcessStatus {
+        PostProcessStatus {
+            status: CacherStatusType::Failed,
+            status_message: message.as_ref().to_string(),
+            ..PostProcessStatus::pending()
+        }
+    }
+}
+
+fn status_path(repo: &LocalRepository, commit: &Commit) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path)
+        .join(POST_PROCESS_DIR)
+        .join(format!("{}.json", commit.id))
+}
+
+/// The processing status of the commit, None if it was never queued. Commits pushed
+/// before the queue existed have no status and are treated as processed.
+pub fn get_status(
+    repo: &LocalRepository,
+    commit: &Commit,
+) -> Result<Option<PostProcessStatus>, OxenError> {
+    let path = status_path(repo, commit);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let contents = util::fs::read_from_path(&path)?;
+    serde_json::from_str(&contents)
+        .map(Some)
+        .map_err(|err| OxenError::json_file_error(&path, err))
+}
+
+pub fn set_status(
+    repo: &LocalRepository,
+    commit: &Commit,
+    status: &PostProcessStatus,
+) -> Result<(), OxenError> {
+    let path = status_path(repo, commit);
+    if let Some(parent) = path.parent() {
+        util::fs::create_dir_all(parent)?;
+    }
+    util::fs::write_to_path(&path, serde_json::to_string(status)?)
+}
+
+/// Mark the commit as waiting in the queue, so clients polling see it as processing
+pub fn mark_pending(repo: &LocalRepository, commit: &Commit) -> Result<(), OxenError> {
+    set_status(repo, commit, &PostProcessStatus::pending())
+}
+
+/// Validate, summarize and warm the caches for a pushed commit, and save the result
+pub fn run(repo: &LocalRepository, commit: &Commit) -> Result<PostProcessStatus, OxenError> {
+    if matches!(repo.min_version(), MinOxenVersion::V0_10_0) {
+        return Err(OxenError::basic_str(
+            "Post processing is not supported for v0.10.0 repositories, use the commit cachers",
+        ));
+    }
+
+    let status = match process(repo, commit) {
+        Ok(status) => status,
+        Err(err) => PostProcessStatus::failed(err.to_string()),
+    };
+    log::debug!(
+        "post process commit {} is {:?} {}",
+        commit.id,
+        status.status,
+        status.status_message
+    );
+    set_status(repo, commit, &status)?;
+    Ok(status)
+}
+
+fn process(repo: &LocalRepository, commit: &Commit) -> Result<PostProcessStatus, OxenError> {
+    // Loading the tree reads every node of the commit, which also warms the node dbs for
+    // the first requests against it
+    let tree = repositories::tree::get_by_commit(repo, commit)?;
+    let files = repositories::tree::list_all_files(&tree)?;
+
+    let mut status = PostProcessStatus::pending();
+    let mut num_missing = 0;
+    for file in files.iter() {
+        let node = &file.file_node;
+        let path = file.dir.join(&node.name);
+        status.num_files += 1;
+        status.num_bytes += node.num_bytes;
+        *status
+            .data_types
+            .entry(node.data_type.to_string())
+            .or_default() += 1;
+
+        let version_path = util::fs::version_path_from_node(repo, node.hash.to_string(), &path);
+        // Chunked files are stored as shards rather than a single version file
+        if node.chunk_hashes.is_empty() && !version_path.exists() {
+            num_missing += 1;
+            if status.missing_files.len() < MAX_MISSING_FILES {
+                status.missing_files.push(path);
+            }
+        }
+    }
+
+    if num_missing > 0 {
+        status.status = CacherStatusType::Failed;
+        status.status_message = format!("{num_missing} file(s) are missing their version file");
+    } else {
+        status.status = CacherStatusType::Success;
+    }
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::v0_10_0::cache::cacher_status::CacherStatusType;
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_run_validates_and_summarizes_commit() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            util::fs::write_to_path(repo.path.join("data.csv"), "a,b\n1,2\n")?;
+            util::fs::write_to_path(repo.path.join("README.md"), "# Data\n")?;
+            repositories::add(&repo, &repo.path)?;
+            let commit = repositories::commit(&repo, "Adding data")?;
+
+            assert!(repositories::post_process::get_status(&repo, &commit)?.is_none());
+            repositories::post_process::mark_pending(&repo, &commit)?;
+            let status = repositories::post_process::get_status(&repo, &commit)?.unwrap();
+            assert_eq!(status.status, CacherStatusType::Pending);
+
+            let status = repositories::post_process::run(&repo, &commit)?;
+            assert_eq!(status.status, CacherStatusType::Success);
+            assert_eq!(status.num_files, 2);
+            assert_eq!(status.data_types.get("tabular"), Some(&1));
+            assert!(status.missing_files.is_empty());
+
+            // Lose a version file, like a push that never finished uploading
+            let (_, hash, _) = repositories::entries::list_file_hashes(&repo, &commit)?
+                .into_iter()
+                .find(|(path, _, _)| path.ends_with("data.csv"))
+                .unwrap();
+            let version_path =
+                util::fs::version_path_from_node(&repo, &hash, std::path::Path::new("data.csv"));
+            util::fs::remove_file(&version_path)?;
+
+            let status = repositories::post_process::run(&repo, &commit)?;
+            assert_eq!(status.status, CacherStatusType::Failed);
+            assert_eq!(status.missing_files.len(), 1);
+            assert_eq!(
+                repositories::post_process::get_status(&repo, &commit)?,
+                Some(status)
+            );
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,192 @@
+//! # Provenance
+//!
+//! Track where files copied in from other repositories came from. Copies into a
+//! working directory are staged, and attached to the commit that adds or changes them.
+//!
+
+use std::path::Path;
+
+use crate::constants::PROVENANCE_DIR;
+use crate::core;
+use crate::core::db;
+use crate::core::db::key_val::str_json_db;
+use crate::core::versions::MinOxenVersion;
+use crate::error::OxenError;
+use crate::model::{Commit, LocalRepository, Provenance};
+use crate::repositories;
+use crate::util;
+
+use rocksdb::DB;
+
+const STAGED_DIR: &str = "staged";
+const COMMITS_DIR: &str = "commits";
+
+fn open_db(repo: &LocalRepository, name: &str) -> Result<DB, OxenError> {
+    let db_path = util::fs::oxen_hidden_dir(&repo.path)
+        .join(PROVENANCE_DIR)
+        .join(name);
+    let opts = db::key_val::opts::default();
+    Ok(DB::open(&opts, dunce::simplified(&db_path))?)
+}
+
+/// Stage provenance for files copied into the working directory, keyed by their destination path
+pub fn stage(repo: &LocalRepository, provenance: &[Provenance]) -> Result<(), OxenError> {
+    let db = open_db(repo, STAGED_DIR)?;
+    for entry in provenance {
+        let key = entry.dest_path.to_string_lossy();
+        str_json_db::put(&db, key.as_ref(), entry)?;
+    }
+    Ok(())
+}
+
+/// List the provenance waiting to be attached to a commit
+pub fn list_staged(repo: &LocalRepository) -> Result<Vec<Provenance>, OxenError> {
+    let staged_path = util::fs::oxen_hidden_dir(&repo.path)
+        .join(PROVENANCE_DIR)
+        .join(STAGED_DIR);
+    if !staged_path.exists() {
+        return Ok(vec![]);
+    }
+
+    let db = open_db(repo, STAGED_DIR)?;
+    let entries: Vec<(String, Provenance)> = str_json_db::list(&db)?;
+    Ok(entries.into_iter().map(|(_, entry)| entry).collect())
+}
+
+/// Save the provenance of a commit, replacing any that was there
+pub fn save_for_commit(
+    repo: &LocalRepository,
+    commit: &Commit,
+    provenance: &[Provenance],
+) -> Result<(), OxenError> {
+    let db = open_db(repo, COMMITS_DIR)?;
+    str_json_db::put(&db, &commit.id, &provenance.to_vec())
+}
+
+/// Get the provenance recorded for a commit, empty if nothing in it was copied
+pub fn get_for_commit(
+    repo: &LocalRepository,
+    commit: &Commit,
+) -> Result<Vec<Provenance>, OxenError> {
+    let db = open_db(repo, COMMITS_DIR)?;
+    let provenance: Option<Vec<Provenance>> = str_json_db::get(&db, &commit.id)?;
+    Ok(provenance.unwrap_or_default())
+}
+
+/// Move the staged provenance for paths the commit added or changed over to the commit.
+/// Paths that were already tracked and not changed by the commit stay staged.
+pub fn record_for_commit(
+    repo: &LocalRepository,
+    commit: &Commit,
+) -> Result<Vec<Provenance>, OxenError> {
+    let staged = list_staged(repo)?;
+    if staged.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut recorded = vec![];
+    for entry in staged {
+        let in_commit = match repo.min_version() {
+            MinOxenVersion::V0_10_0 => true,
+            MinOxenVersion::V0_19_0 => is_changed_in_commit(repo, commit, &entry.dest_path)?,
+        };
+        if in_commit {
+            recorded.push(entry);
+        }
+    }
+
+    if !recorded.is_empty() {
+        save_for_commit(repo, commit, &recorded)?;
+        let db = open_db(repo, STAGED_DIR)?;
+        for entry in recorded.iter() {
+            let key = entry.dest_path.to_string_lossy();
+            str_json_db::delete(&db, key.as_ref())?;
+        }
+    }
+    Ok(recorded)
+}
+
+fn is_changed_in_commit(
+    repo: &LocalRepository,
+    commit: &Commit,
+    path: &Path,
+) -> Result<bool, OxenError> {
+    let Some(node) = repositories::tree::get_node_by_path(repo, commit, path)? else {
+        return Ok(false);
+    };
+    let last_commit_id =
+        core::v0_19_0::commits::last_commit_id_for_node(repo, commit, path, &node)?;
+    Ok(last_commit_id.is_some_and(|id| id.to_string() == commit.id))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::error::OxenError;
+    use crate::model::Provenance;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    fn copied_from(dest_path: &str) -> Provenance {
+        Provenance {
+            host: None,
+            namespace: String::from("ox"),
+            repo_name: String::from("CatDog"),
+            commit_id: String::from("abc123"),
+            source_path: PathBuf::from("images/cat.jpg"),
+            dest_path: PathBuf::from(dest_path),
+        }
+    }
+
+    #[test]
+    fn test_staged_provenance_is_recorded_on_commit() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            util::fs::write_to_path(repo.path.join("cat.jpg"), "not really a cat")?;
+            repositories::provenance::stage(
+                &repo,
+                &[copied_from("cat.jpg"), copied_from("dog.jpg")],
+            )?;
+
+            repositories::add(&repo, repo.path.join("cat.jpg"))?;
+            let commit = repositories::commit(&repo, "Adding the copied cat")?;
+
+            // Only the file that was committed has its provenance moved over
+            let recorded = repositories::provenance::get_for_commit(&repo, &commit)?;
+            assert_eq!(recorded, vec![copied_from("cat.jpg")]);
+            let staged = repositories::provenance::list_staged(&repo)?;
+            assert_eq!(staged, vec![copied_from("dog.jpg")]);
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_provenance_waits_for_the_commit_that_changes_the_path() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let cat = repo.path.join("cat.jpg");
+            util::fs::write_to_path(&cat, "the old cat")?;
+            repositories::add(&repo, &cat)?;
+            repositories::commit(&repo, "Adding a cat")?;
+
+            // Copied over the tracked cat, but only something else is committed
+            util::fs::write_to_path(&cat, "the copied cat")?;
+            repositories::provenance::stage(&repo, &[copied_from("cat.jpg")])?;
+            let other = repo.path.join("other.txt");
+            util::fs::write_to_path(&other, "unrelated")?;
+            repositories::add(&repo, &other)?;
+            let commit = repositories::commit(&repo, "Adding something else")?;
+            assert!(repositories::provenance::get_for_commit(&repo, &commit)?.is_empty());
+            assert_eq!(repositories::provenance::list_staged(&repo)?.len(), 1);
+
+            repositories::add(&repo, &cat)?;
+            let commit = repositories::commit(&repo, "Adding the copied cat")?;
+            let recorded = repositories::provenance::get_for_commit(&repo, &commit)?;
+            assert_eq!(recorded, vec![copied_from("cat.jpg")]);
+            assert!(repositories::provenance::list_staged(&repo)?.is_empty());
+
+            Ok(())
+        })
+    }
+}
@@ -7,6 +7,7 @@ use crate::core;
 use crate::core::versions::MinOxenVersion;
 use crate::error::OxenError;
 use crate::model::{Branch, LocalRepository};
+use crate::repositories;
 
 /// # Get a log of all the commits
 ///
@@ -48,6 +49,7 @@ use crate::model::{Branch, LocalRepository};
 /// # }
 /// ```
 pub async fn push(repo: &LocalRepository) -> Result<Branch, OxenError> {
+    repositories::pii_scan::check_before_push(repo)?;
     match repo.min_version() {
         MinOxenVersion::V0_10_0 => core::v0_10_0::push::push(repo).await,
         MinOxenVersion::V0_19_0 => core::v0_19_0::push::push(repo).await,
@@ -60,6 +62,7 @@ pub async fn push_remote_branch(
     remote: impl AsRef<str>,
     branch_name: impl AsRef<str>,
 ) -> Result<Branch, OxenError> {
+    repositories::pii_scan::check_before_push(repo)?;
     match repo.min_version() {
         MinOxenVersion::V0_10_0 => {
             core::v0_10_0::push::push_remote_branch(repo, remote, branch_name).await
@@ -70,6 +73,32 @@ pub async fn push_remote_branch(
     }
 }
 
+/// Push to a remote branch that is not an ancestor of the local branch, replacing its
+/// history, as long as the remote branch is still at `expected_commit_id`. Defaults to
+/// the remote-tracking ref, see `command::fetch`.
+pub async fn force_push_remote_branch(
+    repo: &LocalRepository,
+    remote: impl AsRef<str>,
+    branch_name: impl AsRef<str>,
+    expected_commit_id: Option<&str>,
+) -> Result<Branch, OxenError> {
+    repositories::pii_scan::check_before_push(repo)?;
+    match repo.min_version() {
+        MinOxenVersion::V0_10_0 => Err(OxenError::basic_str(
+            "Force pushing is not supported for this repository version, run `oxen migrate` first",
+        )),
+        MinOxenVersion::V0_19_0 => {
+            core::v0_19_0::push::force_push_remote_branch(
+                repo,
+                remote,
+                branch_name,
+                expected_commit_id,
+            )
+            .await
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::PathBuf;
@@ -165,7 +194,7 @@ mod tests {
             // Push it real good
             repositories::push(&repo).await?;
 
-            let is_synced = api::client::commits::commit_is_synced(&remote_repo, &commit.id)
+            let is_synced = test::wait_for_commit_synced(&remote_repo, &commit.id)
                 .await?
                 .unwrap();
             assert!(is_synced.is_valid);
@@ -217,7 +246,7 @@ mod tests {
             // Push again
             repositories::push(&repo).await?;
 
-            let is_synced = api::client::commits::commit_is_synced(&remote_repo, &commit.id)
+            let is_synced = test::wait_for_commit_synced(&remote_repo, &commit.id)
                 .await?
                 .unwrap();
             assert!(is_synced.is_valid);
@@ -0,0 +1,131 @@
+//! # Quotas
+//!
+//! Track how much storage a repository uses as branches move, so the server can reject
+//! pushes that would take a repository over its quota. Usage is recorded in
+//! `.oxen/storage_usage.json` each time a branch lands, and rebuilt from the branches
+//! if the file is missing.
+//!
+
+use std::collections::BTreeMap;
+
+use bytesize::ByteSize;
+
+use crate::constants::STORAGE_USAGE_FILE;
+use crate::error::OxenError;
+use crate::model::{Commit, LocalRepository, StorageUsage};
+use crate::repositories;
+use crate::util;
+
+/// The storage the repository uses with its branches where they are now
+pub fn usage(repo: &LocalRepository) -> Result<StorageUsage, OxenError> {
+    let path = util::fs::oxen_hidden_dir(&repo.path).join(STORAGE_USAGE_FILE);
+    if path.exists() {
+        let contents = util::fs::read_from_path(&path)?;
+        return serde_json::from_str(&contents)
+            .map_err(|err| OxenError::json_file_error(&path, err));
+    }
+
+    let mut branches = BTreeMap::new();
+    for branch in repositories::branches::list(repo)? {
+        if let Some(commit) = repositories::commits::get_by_id(repo, &branch.commit_id)? {
+            branches.insert(branch.name, commit_num_bytes(repo, &commit)?);
+        }
+    }
+    Ok(StorageUsage::from_branches(branches))
+}
+
+/// The storage the repository would use if the branch moved to the commit,
+/// or was deleted if the commit is None
+pub fn usage_with_branch_at(
+    repo: &LocalRepository,
+    branch: impl AsRef<str>,
+    commit: Option<&Commit>,
+) -> Result<StorageUsage, OxenError> {
+    let mut branches = usage(repo)?.branches;
+    match commit {
+        Some(commit) => {
+            branches.insert(branch.as_ref().to_string(), commit_num_bytes(repo, commit)?);
+        }
+        None => {
+            branches.remove(branch.as_ref());
+        }
+    }
+    Ok(StorageUsage::from_branches(branches))
+}
+
+/// Check that moving the branch to the commit keeps the repository within `quota` bytes.
+/// Returns the usage after the move.
+pub fn check(
+    repo: &LocalRepository,
+    branch: impl AsRef<str>,
+    commit: &Commit,
+    quota: u64,
+) -> Result<StorageUsage, OxenError> {
+    let usage = usage_with_branch_at(repo, branch, Some(commit))?;
+    if usage.num_bytes > quota {
+        return Err(OxenError::quota_exceeded(format!(
+            "the repository would use {} of its {} quota",
+            ByteSize::b(usage.num_bytes),
+            ByteSize::b(quota)
+        )));
+    }
+    Ok(usage)
+}
+
+/// Record where the branch landed, or that it was deleted if the commit is None
+pub fn record(
+    repo: &LocalRepository,
+    branch: impl AsRef<str>,
+    commit: Option<&Commit>,
+) -> Result<StorageUsage, OxenError> {
+    let usage = usage_with_branch_at(repo, branch, commit)?;
+    let path = util::fs::oxen_hidden_dir(&repo.path).join(STORAGE_USAGE_FILE);
+    util::fs::write_to_path(&path, serde_json::to_string_pretty(&usage)?)?;
+    Ok(usage)
+}
+
+fn commit_num_bytes(repo: &LocalRepository, commit: &Commit) -> Result<u64, OxenError> {
+    Ok(repositories::entries::get_directory(repo, commit, "")?
+        .map(|dir| dir.num_bytes)
+        .unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constants::DEFAULT_BRANCH_NAME;
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_quota_check_and_record() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let head = repositories::commits::head_commit(&repo)?;
+            let usage = repositories::quotas::usage(&repo)?;
+            assert!(usage.num_bytes > 0);
+            assert_eq!(usage.branches[DEFAULT_BRANCH_NAME], usage.num_bytes);
+
+            // Grow a branch past a quota that fits the current data
+            let quota = usage.num_bytes + 10;
+            repositories::branches::create_checkout(&repo, "more-data")?;
+            let path = repo.path.join("more.txt");
+            util::fs::write_to_path(&path, "x".repeat(100))?;
+            repositories::add(&repo, &path)?;
+            let commit = repositories::commit(&repo, "Adding more data")?;
+
+            assert!(repositories::quotas::check(&repo, "more-data", &head, quota).is_ok());
+            let result = repositories::quotas::check(&repo, "more-data", &commit, quota);
+            assert!(matches!(result, Err(OxenError::QuotaExceeded(_))));
+
+            let recorded = repositories::quotas::record(&repo, "more-data", Some(&commit))?;
+            assert!(recorded.num_bytes > usage.num_bytes);
+            assert_eq!(repositories::quotas::usage(&repo)?, recorded);
+
+            // Deleting the branch frees its bytes
+            let recorded = repositories::quotas::record(&repo, "more-data", None)?;
+            assert_eq!(recorded.num_bytes, usage.num_bytes);
+            Ok(())
+        })
+    }
+}
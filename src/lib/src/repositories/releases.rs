@@ -0,0 +1,183 @@
+//! # Releases
+//!
+//! Named dataset versions like `train-v3`, each pointing at a commit with an optional
+//! description. They live next to branches but never move, so a release name can be used
+//! anywhere a revision is expected and always means the same data.
+//!
+
+use crate::constants::RELEASES_DIR;
+use crate::core::db;
+use crate::core::db::key_val::str_json_db;
+use crate::error::OxenError;
+use crate::model::{LocalRepository, NewRelease, Release};
+use crate::repositories;
+use crate::util;
+
+use rocksdb::DB;
+use std::path::PathBuf;
+use time::OffsetDateTime;
+
+fn db_path(repo: &LocalRepository) -> PathBuf {
+    util::fs::oxen_hidden_dir(&repo.path).join(RELEASES_DIR)
+}
+
+fn open_db(repo: &LocalRepository) -> Result<DB, OxenError> {
+    let opts = db::key_val::opts::default();
+    Ok(DB::open(&opts, dunce::simplified(&db_path(repo)))?)
+}
+
+/// Release names end up in revisions and urls, keep them to one path segment
+fn validate_name(name: &str) -> Result<(), OxenError> {
+    let is_valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !is_valid {
+        return Err(OxenError::basic_str(format!(
+            "Invalid release name {name:?}, use letters, numbers, '-', '_' and '.'"
+        )));
+    }
+    Ok(())
+}
+
+/// Name the commit `new_release.revision` resolves to. Names are unique and can't
+/// shadow a branch, since both are accepted as revisions.
+pub fn create(repo: &LocalRepository, new_release: NewRelease) -> Result<Release, OxenError> {
+    validate_name(&new_release.name)?;
+    if get(repo, &new_release.name)?.is_some() {
+        return Err(OxenError::basic_str(format!(
+            "Release {} already exists",
+            new_release.name
+        )));
+    }
+    if repositories::branches::exists(repo, &new_release.name)? {
+        return Err(OxenError::basic_str(format!(
+            "There is already a branch named {}",
+            new_release.name
+        )));
+    }
+    let commit = repositories::revisions::get(repo, &new_release.revision)?.ok_or(
+        OxenError::revision_not_found(new_release.revision.as_str().into()),
+    )?;
+
+    let release = Release {
+        name: new_release.name,
+        commit_id: commit.id,
+        description: new_release.description.filter(|d| !d.trim().is_empty()),
+        author: new_release.author,
+        email: new_release.email,
+        created_at: OffsetDateTime::now_utc(),
+    };
+    let db = open_db(repo)?;
+    str_json_db::put(&db, &release.name, &release)?;
+    Ok(release)
+}
+
+/// Get a release by name. Opens the db read only, revisions are resolved through here
+/// from many requests at once.
+pub fn get(repo: &LocalRepository, name: impl AsRef<str>) -> Result<Option<Release>, OxenError> {
+    let path = db_path(repo);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let opts = db::key_val::opts::default();
+    let db = DB::open_for_read_only(&opts, dunce::simplified(&path), false)?;
+    str_json_db::get(&db, name)
+}
+
+/// All releases, newest first
+pub fn list(repo: &LocalRepository) -> Result<Vec<Release>, OxenError> {
+    if !db_path(repo).exists() {
+        return Ok(vec![]);
+    }
+    let db = open_db(repo)?;
+    let releases: Vec<(String, Release)> = str_json_db::list(&db)?;
+    let mut releases: Vec<Release> = releases.into_iter().map(|(_, r)| r).collect();
+    releases.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    Ok(releases)
+}
+
+/// Replace the description of a release, the commit it points at never changes
+pub fn update_description(
+    repo: &LocalRepository,
+    name: impl AsRef<str>,
+    description: Option<String>,
+) -> Result<Release, OxenError> {
+    let name = name.as_ref();
+    let mut release = get(repo, name)?.ok_or(OxenError::resource_not_found(name))?;
+    release.description = description.filter(|d| !d.trim().is_empty());
+    let db = open_db(repo)?;
+    str_json_db::put(&db, &release.name, &release)?;
+    Ok(release)
+}
+
+/// Delete a release, returning it. The commit it pointed at is kept.
+pub fn delete(repo: &LocalRepository, name: impl AsRef<str>) -> Result<Release, OxenError> {
+    let name = name.as_ref();
+    let release = get(repo, name)?.ok_or(OxenError::resource_not_found(name))?;
+    let db = open_db(repo)?;
+    str_json_db::delete(&db, name)?;
+    Ok(release)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::OxenError;
+    use crate::model::NewRelease;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    fn new_release(name: &str, revision: &str) -> NewRelease {
+        NewRelease {
+            name: name.to_string(),
+            revision: revision.to_string(),
+            description: Some(String::from("Relabeled cats")),
+            author: String::from("Ox"),
+            email: String::from("ox@oxen.ai"),
+        }
+    }
+
+    #[test]
+    fn test_release_names_a_commit() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let commit = repositories::commits::head_commit(&repo)?;
+            let branch = repositories::branches::current_branch(&repo)?.unwrap();
+
+            let release =
+                repositories::releases::create(&repo, new_release("train-v3", &branch.name))?;
+            assert_eq!(release.commit_id, commit.id);
+
+            // The release stays put when the branch moves
+            let path = repo.path.join("more.txt");
+            util::fs::write_to_path(&path, "more data")?;
+            repositories::add(&repo, &path)?;
+            repositories::commit(&repo, "More data")?;
+            let resolved = repositories::revisions::get(&repo, "train-v3")?.unwrap();
+            assert_eq!(resolved.id, commit.id);
+
+            // Names are unique, valid and don't shadow branches
+            assert!(
+                repositories::releases::create(&repo, new_release("train-v3", &commit.id)).is_err()
+            );
+            assert!(
+                repositories::releases::create(&repo, new_release("bad name", &commit.id)).is_err()
+            );
+            assert!(
+                repositories::releases::create(&repo, new_release(&branch.name, &commit.id))
+                    .is_err()
+            );
+            assert!(repositories::releases::create(&repo, new_release("v4", "nope")).is_err());
+
+            let updated = repositories::releases::update_description(&repo, "train-v3", None)?;
+            assert_eq!(updated.description, None);
+            assert_eq!(repositories::releases::list(&repo)?.len(), 1);
+
+            repositories::releases::delete(&repo, "train-v3")?;
+            assert!(repositories::releases::list(&repo)?.is_empty());
+            assert!(repositories::revisions::get(&repo, "train-v3")?.is_none());
+
+            Ok(())
+        })
+    }
+}
@@ -219,6 +219,38 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_restore_directory_keeps_untracked_files() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let last_commit = repositories::commits::head_commit(&repo)?;
+            let annotations_dir = Path::new("annotations");
+
+            // Commit a file that did not exist in the revision we restore
+            let added_path = repo
+                .path
+                .join(annotations_dir)
+                .join("train")
+                .join("added.txt");
+            util::fs::write_to_path(&added_path, "added after")?;
+            repositories::add(&repo, &added_path)?;
+            repositories::commit(&repo, "Adding a file to annotations")?;
+
+            // And leave one untracked
+            let untracked_path = repo.path.join(annotations_dir).join("untracked.txt");
+            util::fs::write_to_path(&untracked_path, "not tracked")?;
+
+            repositories::restore::restore(
+                &repo,
+                RestoreOpts::from_path_ref(annotations_dir, last_commit.id.clone()),
+            )?;
+
+            assert!(!added_path.exists());
+            assert!(untracked_path.exists());
+
+            Ok(())
+        })
+    }
+
     #[test]
     fn test_restore_removed_tabular_data() -> Result<(), OxenError> {
         test::run_training_data_repo_test_fully_committed(|repo| {
@@ -336,6 +368,37 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_restore_staged_file_keeps_rest_of_pending_commit() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            util::fs::write_to_path(repo.path.join("labels.csv"), "file,label\na.jpg,cat\n")?;
+            util::fs::write_to_path(repo.path.join("labels.csv.bak"), "backup")?;
+            util::fs::create_dir_all(repo.path.join("labels"))?;
+            util::fs::write_to_path(repo.path.join("labels").join("notes.txt"), "notes")?;
+            repositories::add(&repo, &repo.path)?;
+
+            let status = repositories::status(&repo)?;
+            assert_eq!(status.staged_files.len(), 3);
+            assert_eq!(status.staged_schemas.len(), 1);
+
+            // Only the file itself is unstaged, not the paths it is a prefix of
+            repositories::restore::restore(&repo, RestoreOpts::from_staged_path("labels.csv"))?;
+
+            let status = repositories::status(&repo)?;
+            assert_eq!(status.staged_files.len(), 2);
+            assert!(status
+                .staged_files
+                .contains_key(Path::new("labels.csv.bak")));
+            assert!(status
+                .staged_files
+                .contains_key(&Path::new("labels").join("notes.txt")));
+            assert_eq!(status.staged_schemas.len(), 0);
+            assert_eq!(status.untracked_files.len(), 1);
+
+            Ok(())
+        })
+    }
+
     #[test]
     fn test_restore_data_frame_with_duplicates() -> Result<(), OxenError> {
         // THIS ONE FAILS BECAUSE OF THE REPOSITOROIES::COMMIT, IT DOESN'T GET TO RESTORE
@@ -1,14 +1,16 @@
 //! Revisions can either be commits by id or head commits on branches by name
 
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
 use crate::core;
 use crate::core::versions::MinOxenVersion;
 use crate::error::OxenError;
 use crate::model::{Commit, LocalRepository};
 use crate::repositories;
+use crate::util::encryption::PlainPath;
 
-/// Get a commit object from a commit id or branch name
+/// Get a commit object from a commit id, branch name, remote-tracking branch like `origin/main`
+/// or release name
 /// Returns Ok(None) if the revision does not exist
 pub fn get(repo: &LocalRepository, revision: impl AsRef<str>) -> Result<Option<Commit>, OxenError> {
     let revision = revision.as_ref();
@@ -18,6 +20,12 @@ pub fn get(repo: &LocalRepository, revision: impl AsRef<str>) -> Result<Option<C
         let branch = branch.ok_or(OxenError::local_branch_not_found(revision))?;
         let commit = repositories::commits::get_by_id(repo, &branch.commit_id)?;
         Ok(commit)
+    } else if let Some(commit_id) = core::refs::remote_refs::get(repo, revision)? {
+        log::debug!("revision is a remote-tracking branch: {}", revision);
+        repositories::commits::get_by_id(repo, &commit_id)
+    } else if let Some(release) = repositories::releases::get(repo, revision)? {
+        log::debug!("revision is a release: {}", revision);
+        repositories::commits::get_by_id(repo, &release.commit_id)
     } else {
         log::debug!("revision is a commit id: {}", revision);
         let commit = repositories::commits::get_by_id(repo, revision)?;
@@ -25,12 +33,12 @@ pub fn get(repo: &LocalRepository, revision: impl AsRef<str>) -> Result<Option<C
     }
 }
 
-/// Get the version file path from a commit id
+/// Get the plain version file of a path at a revision
 pub fn get_version_file(
     repo: &LocalRepository,
     revision: impl AsRef<str>,
     path: impl AsRef<Path>,
-) -> Result<PathBuf, OxenError> {
+) -> Result<PlainPath, OxenError> {
     let commit_id = match get(repo, &revision)? {
         Some(commit) => commit.id,
         None => return Err(OxenError::commit_id_does_not_exist(revision.as_ref())),
@@ -39,12 +47,13 @@ pub fn get_version_file(
     get_version_file_from_commit_id(repo, commit_id, path)
 }
 
-/// Get the version file path from a commit id
+/// Get the plain version file of a path at a commit id, decoded to a temp file if the
+/// version is stored encrypted or compressed
 pub fn get_version_file_from_commit_id(
     repo: &LocalRepository,
     commit_id: impl AsRef<str>,
     path: impl AsRef<Path>,
-) -> Result<PathBuf, OxenError> {
+) -> Result<PlainPath, OxenError> {
     match repo.min_version() {
         MinOxenVersion::V0_19_0 => {
             core::v0_19_0::revisions::get_version_file_from_commit_id(repo, commit_id, path)
@@ -1,6 +1,7 @@
 //! # oxen rm
 //!
-//! Remove files from the index and working directory
+//! Remove files from the index and working directory,
+//! or with `--cached` from the index only
 //!
 
 use std::collections::HashSet;
@@ -19,6 +20,7 @@ use crate::util;
 /// Removes the path from the index
 pub fn rm(repo: &LocalRepository, opts: &RmOpts) -> Result<(), OxenError> {
     log::debug!("Rm with opts: {opts:?}");
+    repositories::freeze::ensure_not_frozen(repo)?;
     let path: &Path = opts.path.as_ref();
     let paths: HashSet<PathBuf> = parse_glob_path(path, repo)?;
 
@@ -32,6 +34,11 @@ fn p_rm(paths: &HashSet<PathBuf>, repo: &LocalRepository, opts: &RmOpts) -> Resu
     match repo.min_version() {
         MinOxenVersion::V0_10_0 => {
             log::debug!("Version found: V0_10_0");
+            if opts.cached {
+                return Err(OxenError::basic_str(
+                    "`oxen rm --cached` is not supported on this repository version, run `oxen migrate` first",
+                ));
+            }
             for path in paths {
                 let opts = RmOpts::from_path_opts(path, opts);
                 core::v0_10_0::index::rm(repo, &opts)?;
@@ -105,6 +112,7 @@ mod tests {
                 path: rm_dir.to_owned(),
                 recursive: true,
                 staged: false,
+                cached: false,
             };
             println!("Before rm");
             repositories::rm(&repo, &opts)?;
@@ -555,6 +563,7 @@ mod tests {
                 path: PathBuf::from("images/*"),
                 recursive: false,
                 staged: false,
+                cached: false,
             };
 
             repositories::rm(&repo, &rm_opts)?;
@@ -572,6 +581,7 @@ mod tests {
                 path: PathBuf::from("images/*"),
                 recursive: false,
                 staged: true,
+                cached: false,
             };
 
             repositories::rm(&repo, &rm_opts)?;
@@ -628,6 +638,7 @@ mod tests {
                 path: path.to_path_buf(),
                 staged: true,
                 recursive: false, // This should be an error
+                cached: false,
             };
             let result = repositories::rm(&repo, &opts);
             assert!(result.is_err());
@@ -653,6 +664,7 @@ mod tests {
                 path: path.to_path_buf(),
                 staged: true,
                 recursive: true, // make sure to pass in recursive
+                cached: false,
             };
             repositories::rm(&repo, &opts)?;
 
@@ -683,6 +695,7 @@ mod tests {
                 path: path.to_path_buf(),
                 staged: true,
                 recursive: true, // make sure to pass in recursive
+                cached: false,
             };
             repositories::rm(&repo, &opts)?;
 
@@ -712,6 +725,7 @@ mod tests {
                 path: path.to_path_buf(),
                 staged: true,
                 recursive: true, // make sure to pass in recursive
+                cached: false,
             };
             let result = repositories::rm(&repo, &opts);
             assert!(result.is_ok());
@@ -760,6 +774,7 @@ mod tests {
                 path: path.to_path_buf(),
                 staged: false,
                 recursive: false, // This should be an error
+                cached: false,
             };
 
             let result = repositories::rm(&repo, &opts);
@@ -780,6 +795,7 @@ mod tests {
                 path: train_dir.to_path_buf(),
                 staged: false,
                 recursive: true, // Need to specify recursive
+                cached: false,
             };
 
             let result = repositories::rm(&repo, &opts);
@@ -805,6 +821,7 @@ mod tests {
                 path: train_dir.to_path_buf(),
                 staged: false,
                 recursive: true, // Need to specify recursive
+                cached: false,
             };
 
             // copy a cat into the dog image
@@ -846,6 +863,7 @@ mod tests {
                 path: path.to_path_buf(),
                 staged: false,
                 recursive: true, // Must pass in recursive = true
+                cached: false,
             };
             repositories::rm(&repo, &opts)?;
 
@@ -885,6 +903,7 @@ mod tests {
                 path: path.to_path_buf(),
                 staged: false,
                 recursive: true, // Must pass in recursive = true
+                cached: false,
             };
             repositories::rm(&repo, &opts)?;
 
@@ -912,6 +931,7 @@ mod tests {
                 path,
                 staged: false,
                 recursive: true, // Must pass in recursive = true
+                cached: false,
             };
             repositories::rm(&repo, &opts)?;
 
@@ -927,4 +947,42 @@ mod tests {
         })
         .await
     }
+
+    /// $ oxen rm --cached -r train/
+    #[test]
+    fn test_rm_cached_keeps_working_files() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let rm_dir = PathBuf::from("train");
+            let full_path = repo.path.join(&rm_dir);
+            let num_files = util::fs::rcount_files_in_dir(&full_path);
+
+            // Modified files are fine, the working copy is kept
+            let labels_path = repo.path.join("labels.txt");
+            test::modify_txt_file(&labels_path, "cat\ndog\nfish")?;
+            repositories::rm(&repo, &RmOpts::from_cached_path("labels.txt"))?;
+
+            let mut opts = RmOpts::from_cached_path(&rm_dir);
+            opts.recursive = true;
+            repositories::rm(&repo, &opts)?;
+
+            let status = repositories::status(&repo)?;
+            let removed = status
+                .staged_files
+                .values()
+                .filter(|entry| entry.status == StagedEntryStatus::Removed)
+                .count();
+            assert_eq!(removed, num_files + 1);
+            assert!(labels_path.exists());
+            assert_eq!(util::fs::rcount_files_in_dir(&full_path), num_files);
+
+            let commit = repositories::commit(&repo, "Stop tracking train and labels")?;
+            let entry =
+                repositories::entries::get_commit_entry(&repo, &commit, Path::new("labels.txt"))?;
+            assert!(entry.is_none());
+            assert!(labels_path.exists());
+            assert!(full_path.exists());
+
+            Ok(())
+        })
+    }
 }
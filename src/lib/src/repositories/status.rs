@@ -4,13 +4,22 @@
 //! and which files are staged for commit.
 //!
 
+use std::collections::HashMap;
 use std::path::Path;
 
+use polars::prelude::DataFrame;
+
+use crate::constants::ROW_HASH_COL_NAME;
 use crate::core;
+use crate::core::df::tabular;
 use crate::core::versions::MinOxenVersion;
 use crate::error::OxenError;
+use crate::model::diff::TabularChangePreview;
 use crate::model::staged_data::StagedDataOpts;
 use crate::model::{LocalRepository, StagedData};
+use crate::opts::DFOpts;
+use crate::repositories;
+use crate::util;
 
 /// # oxen status
 ///
@@ -94,8 +103,109 @@ pub fn status_from_dir(
     }
 }
 
+/// Summarize the row and column changes of each modified tabular file against HEAD.
+///
+/// Rows are compared by hashing the columns both versions share, so a row that was
+/// edited shows up as one removed and one added row.
+pub fn preview_tabular_changes(
+    repo: &LocalRepository,
+    status: &StagedData,
+) -> Result<Vec<TabularChangePreview>, OxenError> {
+    let Some(head) = repositories::commits::head_commit_maybe(repo)? else {
+        return Ok(vec![]);
+    };
+
+    let mut paths: Vec<_> = status
+        .modified_files
+        .iter()
+        .filter(|path| util::fs::is_tabular(path))
+        .collect();
+    paths.sort();
+
+    let mut previews = vec![];
+    for path in paths {
+        let Some(entry) = repositories::entries::get_commit_entry(repo, &head, path)? else {
+            continue;
+        };
+        let version_path = util::fs::plain_version_path(repo, &entry)?;
+        let head_df = tabular::read_df_with_extension(
+            version_path.path(),
+            util::fs::file_extension(path),
+            &DFOpts::empty(),
+        )?;
+        let working_df = tabular::read_df(repo.path.join(path), DFOpts::empty())?;
+        previews.push(preview_df_changes(path, &head_df, &working_df)?);
+    }
+    Ok(previews)
+}
+
+fn preview_df_changes(
+    path: &Path,
+    head_df: &DataFrame,
+    working_df: &DataFrame,
+) -> Result<TabularChangePreview, OxenError> {
+    let head_cols: Vec<String> = head_df
+        .get_column_names()
+        .iter()
+        .map(|c| c.to_string())
+        .collect();
+    let working_cols: Vec<String> = working_df
+        .get_column_names()
+        .iter()
+        .map(|c| c.to_string())
+        .collect();
+    let shared_cols: Vec<String> = head_cols
+        .iter()
+        .filter(|c| working_cols.contains(c))
+        .cloned()
+        .collect();
+
+    let head_counts = row_counts(head_df, &shared_cols)?;
+    let working_counts = row_counts(working_df, &shared_cols)?;
+
+    Ok(TabularChangePreview {
+        path: path.to_path_buf(),
+        rows_added: count_missing(&working_counts, &head_counts),
+        rows_removed: count_missing(&head_counts, &working_counts),
+        columns_added: working_cols
+            .iter()
+            .filter(|c| !head_cols.contains(c))
+            .cloned()
+            .collect(),
+        columns_removed: head_cols
+            .iter()
+            .filter(|c| !working_cols.contains(c))
+            .cloned()
+            .collect(),
+    })
+}
+
+// Duplicate rows are counted, so adding a copy of an existing row is still an added row
+fn row_counts(df: &DataFrame, cols: &[String]) -> Result<HashMap<String, usize>, OxenError> {
+    let mut counts = HashMap::new();
+    if cols.is_empty() {
+        return Ok(counts);
+    }
+    // Select the columns in the same order for both versions so the hashes line up
+    let df = df.select(cols.to_vec())?;
+    let df = tabular::df_hash_rows_on_cols(df, cols, ROW_HASH_COL_NAME)?;
+    for hash in df.column(ROW_HASH_COL_NAME)?.str()?.into_iter().flatten() {
+        *counts.entry(hash.to_string()).or_insert(0) += 1;
+    }
+    Ok(counts)
+}
+
+// How many rows in `counts` are not matched by a row in `other`
+fn count_missing(counts: &HashMap<String, usize>, other: &HashMap<String, usize>) -> usize {
+    counts
+        .iter()
+        .map(|(hash, count)| count.saturating_sub(other.get(hash).copied().unwrap_or(0)))
+        .sum()
+}
+
 #[cfg(test)]
 mod tests {
+    use crate::command;
     use crate::error::OxenError;
     use crate::model::staged_data::StagedDataOpts;
     use crate::model::StagedEntryStatus;
@@ -242,6 +352,30 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_command_status_touched_file_is_not_modified() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            // Same contents with a new modification time
+            let labels_path = repo.path.join(Path::new("labels.txt"));
+            let contents = util::fs::read_from_path(&labels_path)?;
+            util::fs::write_to_path(&labels_path, &contents)?;
+            filetime::set_file_mtime(&labels_path, filetime::FileTime::from_unix_time(1, 0))?;
+
+            let repo_status = repositories::status(&repo)?;
+            assert_eq!(repo_status.modified_files.len(), 0);
+
+            // Asking again is answered from the stat cache
+            let repo_status = repositories::status(&repo)?;
+            assert_eq!(repo_status.modified_files.len(), 0);
+
+            test::modify_txt_file(&labels_path, "new labels coming in hot")?;
+            let repo_status = repositories::status(&repo)?;
+            assert_eq!(repo_status.modified_files.len(), 1);
+
+            Ok(())
+        })
+    }
+
     #[test]
     fn test_command_modified_files_status_with_file_search_paths() -> Result<(), OxenError> {
         test::run_training_data_repo_test_fully_committed(|repo| {
@@ -368,7 +502,7 @@ mod tests {
             let commits = repositories::commits::list(&repo)?;
             let initial_len = commits.len();
             let result = repositories::commit(&repo, "Should not work");
-            assert!(result.is_err());
+            assert!(matches!(result, Err(OxenError::NothingToCommit(_))));
             let commits = repositories::commits::list(&repo)?;
             // We should not have added any commits
             assert_eq!(commits.len(), initial_len);
@@ -808,6 +942,56 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_status_preview_tabular_changes() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let csv_path = repo.path.join("data.csv");
+            util::fs::write_to_path(&csv_path, "file,label\na.jpg,cat\nb.jpg,dog\nc.jpg,cat\n")?;
+            repositories::add(&repo, &csv_path)?;
+            repositories::commit(&repo, "add data.csv")?;
+
+            // Edit one row, add one row, and add a column
+            util::fs::write_to_path(
+                &csv_path,
+                "file,label,split\na.jpg,cat,train\nb.jpg,cat,train\nc.jpg,cat,test\nd.jpg,dog,test\n",
+            )?;
+
+            let status = repositories::status(&repo)?;
+            let previews = repositories::status::preview_tabular_changes(&repo, &status)?;
+            assert_eq!(previews.len(), 1);
+            let preview = &previews[0];
+            assert_eq!(preview.path, PathBuf::from("data.csv"));
+            assert_eq!(preview.rows_added, 2);
+            assert_eq!(preview.rows_removed, 1);
+            assert_eq!(preview.columns_added, vec!["split".to_string()]);
+            assert!(preview.columns_removed.is_empty());
+
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_status_preview_counts_duplicate_rows_on_compressed_repo() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|mut repo| {
+            command::config::set_compression_level(&mut repo, Some(3))?;
+            let csv_path = repo.path.join("data.csv");
+            util::fs::write_to_path(&csv_path, "file,label\na.jpg,cat\nb.jpg,dog\n")?;
+            repositories::add(&repo, &csv_path)?;
+            repositories::commit(&repo, "add data.csv")?;
+
+            // Two more copies of a row that is already there, and one of the dogs removed
+            util::fs::write_to_path(&csv_path, "file,label\na.jpg,cat\na.jpg,cat\na.jpg,cat\n")?;
+
+            let status = repositories::status(&repo)?;
+            let previews = repositories::status::preview_tabular_changes(&repo, &status)?;
+            assert_eq!(previews.len(), 1);
+            assert_eq!(previews[0].rows_added, 2);
+            assert_eq!(previews[0].rows_removed, 1);
+
+            Ok(())
+        })
+    }
+
     #[tokio::test]
     async fn test_command_status_modified_file_in_subdirectory() -> Result<(), OxenError> {
         test::run_select_data_repo_test_no_commits_async("annotations", |repo| async move {
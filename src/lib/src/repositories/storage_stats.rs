@@ -0,0 +1,112 @@
+//! # Storage Stats
+//!
+//! How much a repository stores, and which branches are responsible for it, computed
+//! from the merkle trees of the branch heads. The result is cached in
+//! `.oxen/storage_stats.json` until a branch moves.
+//!
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::constants::STORAGE_STATS_FILE;
+use crate::core;
+use crate::core::versions::MinOxenVersion;
+use crate::error::OxenError;
+use crate::model::{LocalRepository, StorageStats};
+use crate::repositories;
+use crate::util;
+
+#[derive(Serialize, Deserialize)]
+struct CachedStorageStats {
+    // Branch name to head commit id when the stats were computed
+    heads: BTreeMap<String, String>,
+    stats: StorageStats,
+}
+
+/// The storage stats of the repository, from the cache if no branch moved since they
+/// were computed
+pub fn get(repo: &LocalRepository) -> Result<StorageStats, OxenError> {
+    let heads: BTreeMap<String, String> = repositories::branches::list(repo)?
+        .into_iter()
+        .map(|branch| (branch.name, branch.commit_id))
+        .collect();
+
+    let path = util::fs::oxen_hidden_dir(&repo.path).join(STORAGE_STATS_FILE);
+    if path.exists() {
+        let contents = util::fs::read_from_path(&path)?;
+        match serde_json::from_str::<CachedStorageStats>(&contents) {
+            Ok(cached) if cached.heads == heads => return Ok(cached.stats),
+            Ok(_) => log::debug!("storage_stats::get branches moved, recomputing"),
+            Err(err) => log::warn!("storage_stats::get ignoring invalid cache {path:?}: {err}"),
+        }
+    }
+
+    let stats = compute(repo)?;
+    let cached = CachedStorageStats {
+        heads,
+        stats: stats.clone(),
+    };
+    util::fs::write_to_path(&path, serde_json::to_string(&cached)?)?;
+    Ok(stats)
+}
+
+/// Compute the storage stats from the merkle trees, skipping the cache
+pub fn compute(repo: &LocalRepository) -> Result<StorageStats, OxenError> {
+    match repo.min_version() {
+        MinOxenVersion::V0_19_0 => core::v0_19_0::storage_stats::compute(repo),
+        MinOxenVersion::V0_10_0 => Err(OxenError::basic_str(
+            "Storage stats are not supported for this repository version, run `oxen migrate` first",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constants::DEFAULT_BRANCH_NAME;
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_storage_stats_unique_bytes_per_branch() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let before = repositories::storage_stats::get(&repo)?;
+            assert_eq!(before.branches.len(), 1);
+            assert!(before.num_files > 0);
+            assert!(before.num_commits > 0);
+            let main = &before.branches[0];
+            assert_eq!(main.name, DEFAULT_BRANCH_NAME);
+            assert_eq!(main.unique_num_bytes, before.num_bytes);
+
+            // A branch with one extra file only owns the bytes of that file
+            repositories::branches::create_checkout(&repo, "more-data")?;
+            let path = repo.path.join("more.txt");
+            util::fs::write_to_path(&path, "x".repeat(100))?;
+            repositories::add(&repo, &path)?;
+            repositories::commit(&repo, "Adding more data")?;
+
+            let after = repositories::storage_stats::get(&repo)?;
+            assert_eq!(after.num_files, before.num_files + 1);
+            assert_eq!(after.num_bytes, before.num_bytes + 100);
+            assert_eq!(after.num_commits, before.num_commits + 1);
+            let branch = after
+                .branches
+                .iter()
+                .find(|b| b.name == "more-data")
+                .unwrap();
+            assert_eq!(branch.unique_num_bytes, 100);
+            assert_eq!(branch.num_commits, main.num_commits + 1);
+            let main = after
+                .branches
+                .iter()
+                .find(|b| b.name == DEFAULT_BRANCH_NAME)
+                .unwrap();
+            assert_eq!(main.unique_num_bytes, 0);
+
+            // Cached until a branch moves
+            assert_eq!(repositories::storage_stats::get(&repo)?, after);
+            Ok(())
+        })
+    }
+}
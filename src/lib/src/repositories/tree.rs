@@ -1,8 +1,9 @@
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
-use crate::core::v0_19_0::index::merkle_node_db::node_db_path;
-use crate::core::v0_19_0::index::CommitMerkleTree;
+use crate::core::v0_19_0::index::node_pack::{self, RepackSummary};
+use crate::core::v0_19_0::index::{CommitMerkleTree, MerkleNodeDB};
+use crate::core::versions::MinOxenVersion;
 use crate::error::OxenError;
 use crate::model::merkle_tree::node::{
     DirNodeWithPath, EMerkleTreeNode, FileNode, FileNodeWithDir, MerkleTreeNode,
@@ -148,14 +149,23 @@ pub fn list_missing_node_hashes(
 ) -> Result<HashSet<MerkleHash>, OxenError> {
     let mut results = HashSet::new();
     for hash in hashes {
-        let dir_prefix = node_db_path(repo, hash);
-        if !(dir_prefix.join("node").exists() && dir_prefix.join("children").exists()) {
+        if !MerkleNodeDB::exists(repo, hash) {
             results.insert(*hash);
         }
     }
     Ok(results)
 }
 
+/// Move the loose merkle tree nodes and any older packs into a single pack file
+pub fn repack(repo: &LocalRepository) -> Result<RepackSummary, OxenError> {
+    match repo.min_version() {
+        MinOxenVersion::V0_10_0 => Err(OxenError::basic_str(
+            "oxen repack is not supported for this repository version, run `oxen migrate` first",
+        )),
+        MinOxenVersion::V0_19_0 => node_pack::repack(repo),
+    }
+}
+
 fn list_missing_file_hashes_from_hashes(
     repo: &LocalRepository,
     hashes: &HashSet<MerkleHash>,
@@ -0,0 +1,212 @@
+//! # Webhooks
+//!
+//! Urls the server POSTs a json payload to when a branch is pushed, created or deleted,
+//! or a workspace is committed. They are configured per repository in `.oxen/webhooks.json`.
+//!
+//! Each delivery has a unique id in the `X-Oxen-Delivery` header. Failed deliveries are
+//! retried, so receivers should use it to ignore duplicates. If the webhook has a secret,
+//! `X-Oxen-Signature-256` holds `sha256=<hmac of the body>`.
+//!
+
+use reqwest::header::CONTENT_TYPE;
+use std::time::Duration;
+use time::OffsetDateTime;
+
+use crate::api::client::{SendWithRetry, Url};
+use crate::constants::{OXEN_VERSION, WEBHOOKS_FILE};
+use crate::error::OxenError;
+use crate::model::{Commit, LocalRepository, NewWebhook, Webhook, WebhookEvent, WebhookPayload};
+use crate::util;
+
+pub const EVENT_HEADER: &str = "X-Oxen-Event";
+pub const DELIVERY_HEADER: &str = "X-Oxen-Delivery";
+pub const SIGNATURE_HEADER: &str = "X-Oxen-Signature-256";
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub fn list(repo: &LocalRepository) -> Result<Vec<Webhook>, OxenError> {
+    let path = util::fs::oxen_hidden_dir(&repo.path).join(WEBHOOKS_FILE);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let contents = util::fs::read_from_path(&path)?;
+    Ok(serde_json::from_str(&contents)?)
+}
+
+/// The webhooks to notify about an event
+pub fn list_for_event(
+    repo: &LocalRepository,
+    event: WebhookEvent,
+) -> Result<Vec<Webhook>, OxenError> {
+    Ok(list(repo)?
+        .into_iter()
+        .filter(|webhook| webhook.is_subscribed(event))
+        .collect())
+}
+
+pub fn get(repo: &LocalRepository, id: impl AsRef<str>) -> Result<Option<Webhook>, OxenError> {
+    let id = id.as_ref();
+    Ok(list(repo)?.into_iter().find(|webhook| webhook.id == id))
+}
+
+pub fn create(repo: &LocalRepository, new_webhook: &NewWebhook) -> Result<Webhook, OxenError> {
+    let url = Url::parse(&new_webhook.url)
+        .map_err(|err| OxenError::basic_str(format!("Invalid webhook url: {err}")))?;
+    if url.scheme() != "http" && url.scheme() != "https" {
+        return Err(OxenError::basic_str(format!(
+            "Webhook url must be http or https, got {}",
+            new_webhook.url
+        )));
+    }
+
+    let webhook = Webhook {
+        id: uuid::Uuid::new_v4().to_string(),
+        url: new_webhook.url.clone(),
+        secret: new_webhook
+            .secret
+            .clone()
+            .filter(|secret| !secret.is_empty()),
+        events: new_webhook.events.clone(),
+        created_at: OffsetDateTime::now_utc(),
+    };
+    let mut webhooks = list(repo)?;
+    webhooks.push(webhook.clone());
+    write(repo, &webhooks)?;
+    Ok(webhook)
+}
+
+pub fn delete(repo: &LocalRepository, id: impl AsRef<str>) -> Result<Webhook, OxenError> {
+    let id = id.as_ref();
+    let mut webhooks = list(repo)?;
+    let Some(index) = webhooks.iter().position(|webhook| webhook.id == id) else {
+        return Err(OxenError::resource_not_found(format!("webhook {id}")));
+    };
+    let webhook = webhooks.remove(index);
+    write(repo, &webhooks)?;
+    Ok(webhook)
+}
+
+fn write(repo: &LocalRepository, webhooks: &[Webhook]) -> Result<(), OxenError> {
+    let path = util::fs::oxen_hidden_dir(&repo.path).join(WEBHOOKS_FILE);
+    util::fs::write_to_path(&path, serde_json::to_string_pretty(webhooks)?)
+}
+
+pub fn payload(
+    namespace: impl AsRef<str>,
+    repo_name: impl AsRef<str>,
+    event: WebhookEvent,
+    branch: Option<&str>,
+    commit: Option<&Commit>,
+) -> WebhookPayload {
+    WebhookPayload {
+        id: uuid::Uuid::new_v4().to_string(),
+        event,
+        namespace: namespace.as_ref().to_string(),
+        repo_name: repo_name.as_ref().to_string(),
+        branch: branch.map(|branch| branch.to_string()),
+        commit: commit.cloned(),
+        timestamp: OffsetDateTime::now_utc(),
+    }
+}
+
+/// POST the payload to the webhook, retrying transient failures
+pub async fn deliver(webhook: &Webhook, payload: &WebhookPayload) -> Result<(), OxenError> {
+    let body = serde_json::to_string(payload)?;
+    // Not api::client::new_for_url, the oxen auth token must never go to a webhook
+    let client = reqwest::Client::builder()
+        .user_agent(format!("Oxen-Webhook/{OXEN_VERSION}"))
+        .timeout(DELIVERY_TIMEOUT)
+        .build()?;
+    let mut request = client
+        .post(&webhook.url)
+        .header(CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, payload.event.as_str())
+        .header(DELIVERY_HEADER, &payload.id);
+    if let Some(secret) = &webhook.secret {
+        let signature = util::hasher::hmac_sha256(secret.as_bytes(), body.as_bytes());
+        request = request.header(SIGNATURE_HEADER, format!("sha256={signature}"));
+    }
+
+    // Safe to retry, receivers dedupe on the delivery id
+    let res = request.body(body).send_idempotent().await?;
+    let status = res.status();
+    if !status.is_success() {
+        return Err(OxenError::basic_str(format!(
+            "Webhook {} returned {status}",
+            webhook.url
+        )));
+    }
+    log::debug!("Delivered {} to webhook {}", payload.event, webhook.url);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::OxenError;
+    use crate::model::{NewWebhook, WebhookEvent};
+    use crate::repositories;
+    use crate::repositories::webhooks::{DELIVERY_HEADER, SIGNATURE_HEADER};
+    use crate::test;
+    use crate::util;
+
+    #[tokio::test]
+    async fn test_webhooks_create_filter_and_deliver() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test_async(|repo| async move {
+            let mut server = mockito::Server::new_async().await;
+            let url = format!("{}/hooks/oxen", server.url());
+            let pushes = repositories::webhooks::create(
+                &repo,
+                &NewWebhook {
+                    url: url.clone(),
+                    secret: Some("shh".to_string()),
+                    events: vec![WebhookEvent::Push],
+                },
+            )?;
+            let everything = repositories::webhooks::create(
+                &repo,
+                &NewWebhook {
+                    url: url.clone(),
+                    ..Default::default()
+                },
+            )?;
+            assert!(repositories::webhooks::create(
+                &repo,
+                &NewWebhook {
+                    url: "ftp://example.com".to_string(),
+                    ..Default::default()
+                }
+            )
+            .is_err());
+
+            let hooks = repositories::webhooks::list_for_event(&repo, WebhookEvent::BranchDelete)?;
+            assert_eq!(hooks, vec![everything.clone()]);
+            let hooks = repositories::webhooks::list_for_event(&repo, WebhookEvent::Push)?;
+            assert_eq!(hooks.len(), 2);
+
+            let payload = repositories::webhooks::payload(
+                "ox",
+                "pets",
+                WebhookEvent::Push,
+                Some("main"),
+                None,
+            );
+            let body = serde_json::to_string(&payload)?;
+            let signature = util::hasher::hmac_sha256(b"shh", body.as_bytes());
+            let mock = server
+                .mock("POST", "/hooks/oxen")
+                .match_header(DELIVERY_HEADER, payload.id.as_str())
+                .match_header(SIGNATURE_HEADER, format!("sha256={signature}").as_str())
+                .match_body(body.as_str())
+                .with_status(204)
+                .create_async()
+                .await;
+            repositories::webhooks::deliver(&pushes, &payload).await?;
+            mock.assert_async().await;
+
+            repositories::webhooks::delete(&repo, &pushes.id)?;
+            assert_eq!(repositories::webhooks::list(&repo)?, vec![everything]);
+            assert!(repositories::webhooks::delete(&repo, &pushes.id).is_err());
+            Ok(())
+        })
+        .await
+    }
+}
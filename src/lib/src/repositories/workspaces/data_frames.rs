@@ -22,6 +22,7 @@ use crate::model::diff::{AddRemoveModifyCounts, DiffResult, TabularDiff};
 use std::path::{Path, PathBuf};
 
 pub mod columns;
+pub mod reviews;
 pub mod rows;
 pub mod schemas;
 
@@ -534,7 +535,7 @@ mod tests {
                 &repo, &commit.id, &file_path,
             )?;
             // copy the file to the same path but with .csv as the extension
-            let file_1_csv = file_1.with_extension("csv");
+            let file_1_csv = file_1.path().with_extension("csv");
             util::fs::copy(&file_1, &file_1_csv)?;
             log::debug!("copied file 1 to {:?}", file_1_csv);
 
@@ -543,7 +544,7 @@ mod tests {
                 commit_2.id,
                 &file_path,
             )?;
-            let file_2_csv = file_2.with_extension("csv");
+            let file_2_csv = file_2.path().with_extension("csv");
             util::fs::copy(&file_2, &file_2_csv)?;
             log::debug!("copied file 2 to {:?}", file_2_csv);
             let diff_result =
@@ -997,10 +998,10 @@ mod tests {
 
             // Make sure version file is updated
             let entry = repositories::entries::get_commit_entry(&repo, &commit, &path)?.unwrap();
-            let version_file = util::fs::version_path(&repo, &entry);
+            let version_file = util::fs::plain_version_path(&repo, &entry)?;
             let extension = entry.path.extension().unwrap().to_str().unwrap();
             let data_frame =
-                df::tabular::read_df_with_extension(version_file, extension, &DFOpts::empty())?;
+                df::tabular::read_df_with_extension(&version_file, extension, &DFOpts::empty())?;
             println!("{data_frame}");
             assert_eq!(
                 format!("{data_frame}"),
@@ -0,0 +1,214 @@
+//! # Row Reviews
+//!
+//! Labeling teams can mark the rows of an indexed workspace data frame as approved or
+//! rejected with an optional comment, then filter the rows by their review state before
+//! committing. Reviews stay in the workspace, they are not part of the committed file.
+//!
+
+use std::path::Path;
+
+use polars::frame::DataFrame;
+
+use crate::core::db::data_frames::{df_db, reviews, rows};
+use crate::error::OxenError;
+use crate::model::Workspace;
+use crate::opts::DFOpts;
+use crate::repositories;
+use crate::view::data_frames::{RowReview, RowReviewStatus};
+
+/// Set the review of many rows in one transaction, none are changed if any of the rows is missing
+pub fn set(
+    workspace: &Workspace,
+    path: impl AsRef<Path>,
+    reviews: &[RowReview],
+) -> Result<Vec<RowReview>, OxenError> {
+    let path = path.as_ref();
+    let conn = connection(workspace, path)?;
+    log::debug!("reviews::set() {} rows in {:?}", reviews.len(), path);
+    reviews::create_table_if_not_exists(&conn)?;
+    rows::with_transaction(&conn, |conn| {
+        for review in reviews {
+            reviews::upsert(conn, review)?;
+        }
+        Ok(())
+    })?;
+    Ok(reviews.to_vec())
+}
+
+/// Clear the review of a row, it goes back to pending
+pub fn delete(
+    workspace: &Workspace,
+    path: impl AsRef<Path>,
+    row_id: &str,
+) -> Result<(), OxenError> {
+    let conn = connection(workspace, path.as_ref())?;
+    reviews::create_table_if_not_exists(&conn)?;
+    reviews::delete(&conn, row_id)
+}
+
+/// All the rows that have been reviewed
+pub fn list(workspace: &Workspace, path: impl AsRef<Path>) -> Result<Vec<RowReview>, OxenError> {
+    let conn = connection(workspace, path.as_ref())?;
+    reviews::create_table_if_not_exists(&conn)?;
+    reviews::list(&conn)
+}
+
+/// The rows with their `_oxen_review_status` and `_oxen_comment` columns, filtered by status
+pub fn query(
+    workspace: &Workspace,
+    path: impl AsRef<Path>,
+    status: Option<RowReviewStatus>,
+    opts: &DFOpts,
+) -> Result<DataFrame, OxenError> {
+    let conn = connection(workspace, path.as_ref())?;
+    reviews::create_table_if_not_exists(&conn)?;
+    reviews::select_rows(&conn, status, opts)
+}
+
+pub fn count(
+    workspace: &Workspace,
+    path: impl AsRef<Path>,
+    status: Option<RowReviewStatus>,
+) -> Result<usize, OxenError> {
+    let conn = connection(workspace, path.as_ref())?;
+    reviews::create_table_if_not_exists(&conn)?;
+    reviews::count_rows(&conn, status)
+}
+
+fn connection(workspace: &Workspace, path: &Path) -> Result<duckdb::Connection, OxenError> {
+    if !repositories::workspaces::data_frames::is_indexed(workspace, path)? {
+        return Err(OxenError::basic_str(format!(
+            "Data frame {path:?} must be indexed before its rows can be reviewed"
+        )));
+    }
+    let db_path = repositories::workspaces::data_frames::duckdb_path(workspace, path);
+    df_db::get_connection(db_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::config::UserConfig;
+    use crate::constants::{OXEN_ID_COL, REVIEW_COMMENT_COL, REVIEW_STATUS_COL};
+    use crate::core::df::tabular;
+    use crate::error::OxenError;
+    use crate::model::NewCommitBody;
+    use crate::opts::DFOpts;
+    use crate::repositories::workspaces;
+    use crate::test;
+    use crate::view::data_frames::{RowReview, RowReviewStatus};
+    use crate::{repositories, util};
+
+    #[test]
+    fn test_review_rows_and_filter_by_status() -> Result<(), OxenError> {
+        // Skip duckdb if on windows
+        if std::env::consts::OS == "windows" {
+            return Ok(());
+        }
+
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let branch_name = "test-review";
+            let branch = repositories::branches::create_checkout(&repo, branch_name)?;
+            let commit = repositories::commits::get_by_id(&repo, &branch.commit_id)?.unwrap();
+            let workspace_id = UserConfig::identifier()?;
+            let workspace = repositories::workspaces::create(&repo, &commit, workspace_id, true)?;
+            let file_path = Path::new("annotations")
+                .join("train")
+                .join("bounding_box.csv");
+            workspaces::data_frames::index(&repo, &workspace, &file_path)?;
+
+            let num_rows = workspaces::data_frames::count(&workspace, &file_path)?;
+            let staged_df =
+                workspaces::data_frames::query(&workspace, &file_path, &DFOpts::empty())?;
+            let row_id = |i: usize| -> Result<String, OxenError> {
+                Ok(staged_df
+                    .column(OXEN_ID_COL)?
+                    .get(i)?
+                    .to_string()
+                    .replace('"', ""))
+            };
+
+            let reviews = vec![
+                RowReview {
+                    row_id: row_id(0)?,
+                    status: RowReviewStatus::Approved,
+                    comment: None,
+                },
+                RowReview {
+                    row_id: row_id(1)?,
+                    status: RowReviewStatus::Rejected,
+                    comment: Some("box is too small".to_string()),
+                },
+            ];
+            workspaces::data_frames::reviews::set(&workspace, &file_path, &reviews)?;
+            assert_eq!(
+                workspaces::data_frames::reviews::list(&workspace, &file_path)?.len(),
+                2
+            );
+
+            // A missing row fails the whole batch
+            let bad = vec![
+                RowReview {
+                    row_id: row_id(2)?,
+                    status: RowReviewStatus::Approved,
+                    comment: None,
+                },
+                RowReview {
+                    row_id: "not-a-row".to_string(),
+                    status: RowReviewStatus::Approved,
+                    comment: None,
+                },
+            ];
+            assert!(workspaces::data_frames::reviews::set(&workspace, &file_path, &bad).is_err());
+
+            let rejected = workspaces::data_frames::reviews::query(
+                &workspace,
+                &file_path,
+                Some(RowReviewStatus::Rejected),
+                &DFOpts::empty(),
+            )?;
+            assert_eq!(rejected.height(), 1);
+            let comment = rejected.column(REVIEW_COMMENT_COL)?.get(0)?.to_string();
+            assert_eq!(comment.replace('"', ""), "box is too small");
+
+            let num_pending = workspaces::data_frames::reviews::count(
+                &workspace,
+                &file_path,
+                Some(RowReviewStatus::Pending),
+            )?;
+            assert_eq!(num_pending, num_rows - 2);
+            let pending = workspaces::data_frames::reviews::query(
+                &workspace,
+                &file_path,
+                Some(RowReviewStatus::Pending),
+                &DFOpts::empty(),
+            )?;
+            let status = pending.column(REVIEW_STATUS_COL)?.get(0)?.to_string();
+            assert_eq!(status.replace('"', ""), "pending");
+
+            // Reviewing does not change the data frame
+            let status = workspaces::status::status(&workspace)?;
+            assert_eq!(status.staged_files.len(), 0);
+
+            // The review columns are not committed
+            workspaces::data_frames::rows::delete(&repo, &workspace, &file_path, &row_id(3)?)?;
+            let new_commit = NewCommitBody {
+                author: "author".to_string(),
+                email: "email".to_string(),
+                message: "Remove a row".to_string(),
+            };
+            let commit = workspaces::commit(&workspace, &new_commit, branch_name)?;
+            let version_file = repositories::revisions::get_version_file_from_commit_id(
+                &repo, &commit.id, &file_path,
+            )?;
+            let version_csv = version_file.path().with_extension("csv");
+            util::fs::copy(&version_file, &version_csv)?;
+            let df = tabular::read_df(&version_csv, DFOpts::empty())?;
+            assert!(df.column(REVIEW_STATUS_COL).is_err());
+            assert!(df.column(REVIEW_COMMENT_COL).is_err());
+
+            Ok(())
+        })
+    }
+}
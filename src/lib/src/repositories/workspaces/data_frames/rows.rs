@@ -3,7 +3,7 @@ use crate::core::versions::MinOxenVersion;
 use crate::error::OxenError;
 use crate::model::data_frame::update_result::UpdateResult;
 use crate::model::Workspace;
-use crate::view::data_frames::DataFrameRowChange;
+use crate::view::data_frames::{DataFrameRowChange, RowUpdate};
 
 use polars::datatypes::AnyValue;
 
@@ -97,6 +97,44 @@ pub fn batch_update(
     }
 }
 
+/// Update many rows in one transaction, none are changed if any of them fail
+pub fn update_rows(
+    repo: &LocalRepository,
+    workspace: &Workspace,
+    path: impl AsRef<Path>,
+    updates: &[RowUpdate],
+) -> Result<DataFrame, OxenError> {
+    match repo.min_version() {
+        MinOxenVersion::V0_10_0 => Err(OxenError::basic_str(
+            "Batch row updates are not supported for this repository version, run `oxen migrate` first",
+        )),
+        MinOxenVersion::V0_19_0 => core::v0_19_0::workspaces::data_frames::rows::update_rows(
+            workspace,
+            path.as_ref(),
+            updates,
+        ),
+    }
+}
+
+/// Delete many rows in one transaction, none are removed if any of them fail
+pub fn delete_rows(
+    repo: &LocalRepository,
+    workspace: &Workspace,
+    path: impl AsRef<Path>,
+    row_ids: &[String],
+) -> Result<DataFrame, OxenError> {
+    match repo.min_version() {
+        MinOxenVersion::V0_10_0 => Err(OxenError::basic_str(
+            "Batch row deletes are not supported for this repository version, run `oxen migrate` first",
+        )),
+        MinOxenVersion::V0_19_0 => core::v0_19_0::workspaces::data_frames::rows::delete_rows(
+            workspace,
+            path.as_ref(),
+            row_ids,
+        ),
+    }
+}
+
 pub fn delete(
     repo: &LocalRepository,
     workspace: &Workspace,
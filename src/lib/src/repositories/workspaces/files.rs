@@ -1,8 +1,13 @@
+use crate::constants;
 use crate::core;
 use crate::core::versions::MinOxenVersion;
 use crate::error::OxenError;
 use crate::model::Workspace;
+use crate::util;
 
+use std::ffi::OsStr;
+use std::fs::File;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
 pub fn exists(workspace: &Workspace, path: impl AsRef<Path>) -> Result<bool, OxenError> {
@@ -29,3 +34,200 @@ pub fn delete(workspace: &Workspace, path: impl AsRef<Path>) -> Result<(), OxenE
         MinOxenVersion::V0_19_0 => core::v0_19_0::workspaces::files::delete(workspace, path),
     }
 }
+
+/// Stage a move of a committed file to a new path in the workspace
+pub fn mv(
+    workspace: &Workspace,
+    path: impl AsRef<Path>,
+    new_path: impl AsRef<Path>,
+) -> Result<PathBuf, OxenError> {
+    match workspace.base_repo.min_version() {
+        MinOxenVersion::V0_10_0 => Err(OxenError::basic_str(
+            "Moving files in a workspace is not supported for oxen repositories older than v0.19.0",
+        )),
+        MinOxenVersion::V0_19_0 => core::v0_19_0::workspaces::files::mv(workspace, path, new_path),
+    }
+}
+
+/// Where the parts of a chunked upload are kept until they are assembled
+pub fn upload_dir(workspace: &Workspace, upload_id: impl AsRef<str>) -> Result<PathBuf, OxenError> {
+    let upload_id = upload_id.as_ref();
+    let is_valid = !upload_id.is_empty()
+        && upload_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !is_valid {
+        return Err(OxenError::basic_str(format!(
+            "Invalid upload id: {upload_id:?}"
+        )));
+    }
+    Ok(util::fs::oxen_hidden_dir(&workspace.workspace_repo.path)
+        .join(constants::UPLOADS_DIR)
+        .join(upload_id))
+}
+
+/// Save one part of a chunked upload. The part is rejected if it does not match the hash
+/// the client computed, so the client can send it again.
+pub fn save_upload_chunk(
+    workspace: &Workspace,
+    upload_id: impl AsRef<str>,
+    chunk_num: usize,
+    data: &[u8],
+    hash: impl AsRef<str>,
+) -> Result<PathBuf, OxenError> {
+    let hash = hash.as_ref();
+    let data_hash = util::hasher::hash_buffer(data);
+    if data_hash != hash {
+        return Err(OxenError::basic_str(format!(
+            "Chunk {chunk_num} is corrupt, expected hash {hash} got {data_hash}"
+        )));
+    }
+
+    let dir = upload_dir(workspace, upload_id)?;
+    util::fs::create_dir_all(&dir)?;
+    let chunk_path = dir.join(format!("chunk_{chunk_num:016}"));
+    util::fs::write(&chunk_path, data)?;
+    Ok(chunk_path)
+}
+
+/// Combine the parts of a chunked upload into `directory/file_name` in the workspace and
+/// stage it. The file is only moved into place if the hash of the whole file matches.
+pub fn complete_upload(
+    workspace: &Workspace,
+    upload_id: impl AsRef<str>,
+    directory: impl AsRef<Path>,
+    file_name: impl AsRef<str>,
+    total_chunks: usize,
+    hash: impl AsRef<str>,
+) -> Result<PathBuf, OxenError> {
+    let file_name = file_name.as_ref();
+    let hash = hash.as_ref();
+    if Path::new(file_name).file_name() != Some(OsStr::new(file_name)) {
+        return Err(OxenError::basic_str(format!(
+            "Invalid file name for upload: {file_name:?}"
+        )));
+    }
+
+    let dir = upload_dir(workspace, upload_id)?;
+    let chunk_paths: Vec<PathBuf> = (0..total_chunks)
+        .map(|chunk_num| dir.join(format!("chunk_{chunk_num:016}")))
+        .collect();
+    let missing: Vec<usize> = chunk_paths
+        .iter()
+        .enumerate()
+        .filter(|(_, path)| !path.exists())
+        .map(|(chunk_num, _)| chunk_num)
+        .collect();
+    if !missing.is_empty() {
+        return Err(OxenError::basic_str(format!(
+            "Upload is missing chunks {missing:?} of {total_chunks}"
+        )));
+    }
+
+    let assembled_path = dir.join("assembled");
+    let mut assembled = File::create(&assembled_path)?;
+    for chunk_path in chunk_paths.iter() {
+        let mut chunk = File::open(chunk_path)?;
+        std::io::copy(&mut chunk, &mut assembled)?;
+    }
+    assembled.flush()?;
+    drop(assembled);
+
+    let assembled_hash = util::hasher::hash_file_contents(&assembled_path)?;
+    if assembled_hash != hash {
+        util::fs::remove_dir_all(&dir)?;
+        return Err(OxenError::basic_str(format!(
+            "Uploaded file {file_name} is corrupt, expected hash {hash} got {assembled_hash}"
+        )));
+    }
+
+    let full_dir = workspace.workspace_repo.path.join(directory.as_ref());
+    util::fs::create_dir_all(&full_dir)?;
+    let file_path = full_dir.join(file_name);
+    util::fs::rename(&assembled_path, &file_path)?;
+    util::fs::remove_dir_all(&dir)?;
+
+    add(workspace, &file_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::error::OxenError;
+    use crate::repositories;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_complete_chunked_upload() -> Result<(), OxenError> {
+        test::run_training_data_repo_test_fully_committed(|repo| {
+            let commit = repositories::commits::head_commit(&repo)?;
+            let workspace = repositories::workspaces::create(&repo, &commit, "uploads", true)?;
+
+            let contents = b"file,label\nimage_1.jpg,dog\nimage_2.jpg,cat\n";
+            let chunks: Vec<&[u8]> = contents.chunks(10).collect();
+            let hash = util::hasher::hash_buffer(contents);
+
+            // A corrupt part is rejected
+            let result = repositories::workspaces::files::save_upload_chunk(
+                &workspace,
+                "upload-1",
+                0,
+                chunks[1],
+                util::hasher::hash_buffer(chunks[0]),
+            );
+            assert!(result.is_err());
+
+            // Parts can arrive in any order, but all of them are needed
+            for (chunk_num, chunk) in chunks.iter().enumerate().rev().skip(1) {
+                repositories::workspaces::files::save_upload_chunk(
+                    &workspace,
+                    "upload-1",
+                    chunk_num,
+                    chunk,
+                    util::hasher::hash_buffer(chunk),
+                )?;
+            }
+            let result = repositories::workspaces::files::complete_upload(
+                &workspace,
+                "upload-1",
+                "data",
+                "labels.csv",
+                chunks.len(),
+                &hash,
+            );
+            assert!(result.is_err());
+
+            repositories::workspaces::files::save_upload_chunk(
+                &workspace,
+                "upload-1",
+                0,
+                chunks[0],
+                util::hasher::hash_buffer(chunks[0]),
+            )?;
+            let path = repositories::workspaces::files::complete_upload(
+                &workspace,
+                "upload-1",
+                "data",
+                "labels.csv",
+                chunks.len(),
+                &hash,
+            )?;
+            assert_eq!(path, Path::new("data").join("labels.csv"));
+            assert_eq!(
+                util::fs::read_from_path(workspace.workspace_repo.path.join(&path))?,
+                String::from_utf8_lossy(contents)
+            );
+            assert!(!repositories::workspaces::files::upload_dir(&workspace, "upload-1")?.exists());
+
+            let status = repositories::workspaces::status::status(&workspace)?;
+            assert_eq!(status.staged_files.len(), 1);
+
+            // Ids can't escape the uploads dir
+            assert!(repositories::workspaces::files::upload_dir(&workspace, "../..").is_err());
+
+            Ok(())
+        })
+    }
+}
@@ -19,6 +19,7 @@ use crate::model::{LocalRepository, RemoteRepository};
 use crate::opts::RmOpts;
 use crate::repositories;
 use crate::util;
+use crate::view::IsValidStatusMessage;
 
 use rand::distributions::Alphanumeric;
 use rand::Rng;
@@ -85,7 +86,7 @@ pub fn create_repo_dir(base_dir: impl AsRef<Path>) -> Result<PathBuf, OxenError>
     create_prefixed_dir(base_dir, "repo")
 }
 
-fn create_empty_dir(base_dir: impl AsRef<Path>) -> Result<PathBuf, OxenError> {
+pub fn create_empty_dir(base_dir: impl AsRef<Path>) -> Result<PathBuf, OxenError> {
     create_prefixed_dir(base_dir, "dir")
 }
 
@@ -98,6 +99,21 @@ pub async fn create_remote_repo(repo: &LocalRepository) -> Result<RemoteReposito
     api::client::repositories::create_from_local(repo, repo_new).await
 }
 
+/// Wait for the server to finish processing a pushed commit, and return its sync status
+pub async fn wait_for_commit_synced(
+    remote_repo: &RemoteRepository,
+    commit_id: &str,
+) -> Result<Option<IsValidStatusMessage>, OxenError> {
+    for _ in 0..50 {
+        let status = api::client::commits::commit_is_synced(remote_repo, commit_id).await?;
+        if !status.as_ref().is_some_and(|s| s.is_processing) {
+            return Ok(status);
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    api::client::commits::commit_is_synced(remote_repo, commit_id).await
+}
+
 pub fn add_n_files_m_dirs(
     repo: &LocalRepository,
     num_files: u64,
@@ -488,6 +504,7 @@ where
         path: PathBuf::from("test"),
         recursive: true,
         staged: false,
+        cached: false,
     };
 
     repositories::rm(&local_repo, &rm_opts)?;
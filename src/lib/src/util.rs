@@ -1,11 +1,15 @@
 //! Various utility functions
 //!
 
+pub mod commit_graph;
+pub mod compression;
 pub mod concurrency;
+pub mod encryption;
 pub mod fs;
 pub mod hasher;
 pub mod image;
 pub mod logging;
+pub mod offline;
 pub mod oxen_version;
 pub mod paginate;
 pub mod progress_bar;
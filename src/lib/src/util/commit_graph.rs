@@ -0,0 +1,221 @@
+//! # Commit Graph
+//!
+//! Draw the history as ASCII art for `oxen log --graph`, with one column per line of
+//! history and `|`, `/` and `\` edges between them, like `git log --graph`.
+//!
+
+use std::collections::{HashMap, HashSet};
+
+use crate::model::Commit;
+
+const SHORT_ID_LEN: usize = 8;
+
+/// Render the commits, newest first, as graph lines. `refs` maps commit ids to the branch
+/// names that point at them. Parents that are not in `commits` are left out, so a filtered
+/// or truncated history draws as the commits it has.
+pub fn render(commits: &[Commit], refs: &HashMap<String, Vec<String>>) -> Vec<String> {
+    let commits = topological_order(commits);
+    let known: HashSet<&str> = commits.iter().map(|c| c.id.as_str()).collect();
+
+    let mut lines = vec![];
+    // The commit id each column is waiting for
+    let mut columns: Vec<String> = vec![];
+    for commit in commits {
+        let idx = match columns.iter().position(|id| *id == commit.id) {
+            Some(idx) => idx,
+            None => {
+                columns.push(commit.id.clone());
+                columns.len() - 1
+            }
+        };
+
+        // Other lines that were waiting for this commit join it first
+        if columns.iter().filter(|id| **id == commit.id).count() > 1 {
+            let mut edges: Vec<(usize, usize)> = vec![];
+            let mut next: Vec<String> = vec![];
+            for (from, id) in columns.iter().enumerate() {
+                if *id == commit.id && from != idx {
+                    edges.push((from, idx));
+                } else {
+                    edges.push((from, next.len()));
+                    next.push(id.clone());
+                }
+            }
+            lines.push(edge_line(&edges));
+            columns = next;
+        }
+
+        lines.push(commit_line(commit, idx, columns.len(), refs));
+
+        let parents: Vec<&String> = commit
+            .parent_ids
+            .iter()
+            .filter(|id| known.contains(id.as_str()))
+            .collect();
+        let mut next = columns.clone();
+        match parents.first() {
+            Some(first) => next[idx] = first.to_string(),
+            None => {
+                next.remove(idx);
+            }
+        }
+        let mut insert_at = idx + 1;
+        for parent in parents.iter().skip(1) {
+            if !next.contains(parent) {
+                next.insert(insert_at, parent.to_string());
+                insert_at += 1;
+            }
+        }
+
+        // Columns right of the commit shift by the parents added or the line that ended
+        let shift = next.len() as isize - columns.len() as isize;
+        let mut edges: Vec<(usize, usize)> = vec![];
+        for from in 0..columns.len() {
+            if from == idx {
+                for parent in parents.iter() {
+                    if let Some(to) = next.iter().position(|n| n == *parent) {
+                        edges.push((from, to));
+                    }
+                }
+            } else if from < idx {
+                edges.push((from, from));
+            } else {
+                edges.push((from, (from as isize + shift) as usize));
+            }
+        }
+        let is_straight = next.len() == columns.len() && edges.iter().all(|(f, t)| f == t);
+        if !is_straight && !next.is_empty() {
+            lines.push(edge_line(&edges));
+        }
+        columns = next;
+    }
+    lines
+}
+
+fn commit_line(
+    commit: &Commit,
+    idx: usize,
+    num_columns: usize,
+    refs: &HashMap<String, Vec<String>>,
+) -> String {
+    let graph: Vec<&str> = (0..num_columns)
+        .map(|j| if j == idx { "*" } else { "|" })
+        .collect();
+    let short_id: String = commit.id.chars().take(SHORT_ID_LEN).collect();
+    let refs = match refs.get(&commit.id) {
+        Some(names) if !names.is_empty() => format!(" ({})", names.join(", ")),
+        _ => String::new(),
+    };
+    let message = commit.message.lines().next().unwrap_or_default();
+    format!("{} {short_id}{refs} {message}", graph.join(" "))
+}
+
+// Straight edges sit on the column, edges that move sit between the two columns
+fn edge_line(edges: &[(usize, usize)]) -> String {
+    let width = edges
+        .iter()
+        .map(|(from, to)| 2 * from.max(to) + 1)
+        .max()
+        .unwrap_or(0);
+    let mut chars = vec![' '; width];
+    for (from, to) in edges {
+        match from.cmp(to) {
+            std::cmp::Ordering::Equal => chars[2 * from] = '|',
+            std::cmp::Ordering::Less => chars[2 * from + 1] = '\\',
+            std::cmp::Ordering::Greater => chars[2 * from - 1] = '/',
+        }
+    }
+    chars.into_iter().collect::<String>().trim_end().to_string()
+}
+
+// Children before parents, newest first among the commits that are ready
+fn topological_order(commits: &[Commit]) -> Vec<&Commit> {
+    let ids: HashSet<&str> = commits.iter().map(|c| c.id.as_str()).collect();
+    let mut num_children: HashMap<&str, usize> = HashMap::new();
+    for commit in commits {
+        for parent in commit.parent_ids.iter() {
+            if ids.contains(parent.as_str()) {
+                *num_children.entry(parent.as_str()).or_default() += 1;
+            }
+        }
+    }
+    let by_id: HashMap<&str, &Commit> = commits.iter().map(|c| (c.id.as_str(), c)).collect();
+
+    let mut ready: Vec<&Commit> = commits
+        .iter()
+        .filter(|c| !num_children.contains_key(c.id.as_str()))
+        .collect();
+    let mut ordered = vec![];
+    while !ready.is_empty() {
+        // Take the newest ready commit
+        let (i, _) = ready
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, c)| c.timestamp)
+            .unwrap();
+        let commit = ready.remove(i);
+        ordered.push(commit);
+        for parent in commit.parent_ids.iter() {
+            if let Some(count) = num_children.get_mut(parent.as_str()) {
+                *count -= 1;
+                if *count == 0 {
+                    ready.push(by_id[parent.as_str()]);
+                }
+            }
+        }
+    }
+    ordered
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use time::OffsetDateTime;
+
+    use crate::model::Commit;
+    use crate::util::commit_graph;
+
+    fn commit(id: &str, parents: &[&str], minutes: i64) -> Commit {
+        Commit {
+            id: id.to_string(),
+            parent_ids: parents.iter().map(|p| p.to_string()).collect(),
+            message: format!("commit {id}"),
+            author: "ox".to_string(),
+            email: "ox@oxen.ai".to_string(),
+            root_hash: None,
+            co_authors: vec![],
+            timestamp: OffsetDateTime::UNIX_EPOCH + time::Duration::minutes(minutes),
+        }
+    }
+
+    #[test]
+    fn test_render_branch_and_merge() {
+        // a <- b <- d (merge of b and c), a <- c
+        let commits = vec![
+            commit("d", &["b", "c"], 3),
+            commit("c", &["a"], 2),
+            commit("b", &["a"], 1),
+            commit("a", &[], 0),
+        ];
+        let refs = HashMap::from([("d".to_string(), vec!["main".to_string()])]);
+        let lines = commit_graph::render(&commits, &refs);
+        assert_eq!(
+            lines,
+            vec![
+                "* d (main) commit d",
+                "|\\",
+                "| * c commit c",
+                "* | b commit b",
+                "|/",
+                "* a commit a",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_linear_history() {
+        let commits = vec![commit("b", &["a"], 1), commit("a", &[], 0)];
+        let lines = commit_graph::render(&commits, &HashMap::new());
+        assert_eq!(lines, vec!["* b commit b", "* a commit a"]);
+    }
+}
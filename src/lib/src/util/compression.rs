@@ -0,0 +1,138 @@
+//! # Compression
+//!
+//! Optional zstd compression at rest for the version files under `.oxen`, turned on per
+//! repository with `oxen config --compression-level`. Text heavy datasets like labels,
+//! jsonl and csv files often shrink several times over.
+//!
+//! Like encrypted files, a compressed version is stored under its own file name, so
+//! compressed and plain versions live side by side and a `.zst` file someone added is
+//! never decompressed by mistake. Versions are compressed before they are encrypted, and
+//! the readers in [util::encryption](crate::util::encryption) undo both.
+//!
+
+use std::io::{Read, Write};
+use std::path::Path;
+
+use crate::error::OxenError;
+use crate::util::encryption::{self, Encoding};
+
+/// Level used for responses when the repository does not set one
+pub const DEFAULT_COMPRESSION_LEVEL: i32 = 3;
+
+pub fn validate_level(level: i32) -> Result<(), OxenError> {
+    let range = zstd::compression_level_range();
+    if !range.contains(&level) {
+        return Err(OxenError::basic_str(format!(
+            "Invalid compression level {level}, expected {} to {}",
+            range.start(),
+            range.end()
+        )));
+    }
+    Ok(())
+}
+
+pub fn is_compressed(path: impl AsRef<Path>) -> bool {
+    Encoding::of(path).compressed
+}
+
+pub fn compress<R: Read, W: Write>(level: i32, mut reader: R, writer: W) -> Result<(), OxenError> {
+    let mut encoder = zstd::stream::write::Encoder::new(writer, level)?;
+    std::io::copy(&mut reader, &mut encoder)?;
+    encoder.finish()?.flush()?;
+    Ok(())
+}
+
+pub fn decompress<R: Read, W: Write>(reader: R, mut writer: W) -> Result<(), OxenError> {
+    zstd::stream::copy_decode(reader, &mut writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Compress the file where it is, leaving files that are already compressed or
+/// encrypted alone
+pub fn compress_in_place(level: i32, path: impl AsRef<Path>) -> Result<bool, OxenError> {
+    let path = path.as_ref();
+    let current = Encoding::of(path);
+    if current.compressed || current.encrypted {
+        return Ok(false);
+    }
+    let encoding = Encoding {
+        compressed: true,
+        ..current
+    };
+    Ok(encryption::reencode(path, encoding, Some(level), None)? != path)
+}
+
+/// Decompress the file where it is, leaving files that are plain or encrypted alone
+pub fn decompress_in_place(path: impl AsRef<Path>) -> Result<bool, OxenError> {
+    let path = path.as_ref();
+    let current = Encoding::of(path);
+    if !current.compressed || current.encrypted {
+        return Ok(false);
+    }
+    Ok(encryption::reencode(path, Encoding::PLAIN, None, None)? != path)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::constants::{COMPRESSED_FILE_SUFFIX, VERSION_FILE_NAME};
+    use crate::error::OxenError;
+    use crate::test;
+    use crate::util;
+    use crate::util::compression;
+
+    #[test]
+    fn test_compress_decompress_roundtrip() -> Result<(), OxenError> {
+        let text = "file,label\n".repeat(1000);
+        // Empty, short and text that compresses well
+        for data in [&b""[..], &b"abc"[..], text.as_bytes()] {
+            let mut compressed = Vec::new();
+            compression::compress(3, data, &mut compressed)?;
+            let mut decompressed = Vec::new();
+            compression::decompress(compressed.as_slice(), &mut decompressed)?;
+            assert_eq!(decompressed, data);
+        }
+
+        let mut compressed = Vec::new();
+        compression::compress(3, text.as_bytes(), &mut compressed)?;
+        assert!(compressed.len() < text.len() / 10);
+
+        assert!(compression::validate_level(3).is_ok());
+        assert!(compression::validate_level(100).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_compressed_is_told_by_file_name() -> Result<(), OxenError> {
+        test::run_empty_local_repo_test(|repo| {
+            let version_dir = util::fs::version_dir_from_hash(&repo.path, "abcdef");
+            util::fs::create_dir_all(&version_dir)?;
+
+            // A plain version whose contents look like a zstd frame is read as is
+            let zstd_bytes = zstd::encode_all("file,label\n".as_bytes(), 3)?;
+            let version_path = version_dir.join(VERSION_FILE_NAME);
+            std::fs::write(&version_path, &zstd_bytes)?;
+            assert!(!compression::is_compressed(&version_path));
+            let plain = util::encryption::plain_path(&repo, &version_path)?;
+            assert_eq!(std::fs::read(plain.path())?, zstd_bytes);
+            drop(plain);
+
+            // Compressing moves it to the compressed name, which reads back the same
+            assert!(compression::compress_in_place(3, &version_path)?);
+            assert!(!version_path.exists());
+            let stored = util::encryption::stored_path(&version_path);
+            assert_eq!(
+                stored,
+                version_dir.join(format!("{VERSION_FILE_NAME}{COMPRESSED_FILE_SUFFIX}"))
+            );
+            assert!(compression::is_compressed(&stored));
+            let plain = util::encryption::plain_path(&repo, &stored)?;
+            assert_eq!(std::fs::read(plain.path())?, zstd_bytes);
+            drop(plain);
+
+            assert!(compression::decompress_in_place(&stored)?);
+            assert_eq!(std::fs::read(&version_path)?, zstd_bytes);
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,791 @@
+//! # Encryption
+//!
+//! Optional AES-256-GCM encryption at rest for the version files and chunk shards under
+//! `.oxen`. Files are encrypted in fixed size segments (the STREAM construction), so
+//! large files never have to fit in memory and truncation is detected.
+//!
+//! Encoded files are stored under their own file name next to where the plain file would
+//! be, see [Encoding], so encrypted, compressed and plain files can live side by side
+//! while a repository is being converted, and what a file holds never decides how it is
+//! read. The repository config only stores the id of its key, the key itself is read
+//! from `OXEN_ENCRYPTION_KEY` or from `~/.config/oxen/keys/<key_id>`.
+//!
+
+use aes_gcm::aead::generic_array::GenericArray;
+use aes_gcm::aead::stream::{DecryptorBE32, EncryptorBE32};
+use aes_gcm::{Aes256Gcm, KeyInit};
+use rand::RngCore;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Cursor, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::constants::{
+    COMPRESSED_ENCRYPTED_FILE_SUFFIX, COMPRESSED_FILE_SUFFIX, ENCRYPTED_FILE_SUFFIX,
+};
+use crate::error::OxenError;
+use crate::model::LocalRepository;
+use crate::util;
+use crate::util::compression;
+
+/// Env var with the hex encoded key, overrides the key file
+pub const ENCRYPTION_KEY_ENV: &str = "OXEN_ENCRYPTION_KEY";
+pub const KEYS_DIR: &str = "keys";
+
+const MAGIC: &[u8; 8] = b"OXENENC1";
+// 12 byte GCM nonce minus the 4 byte counter and 1 byte last flag of the STREAM construction
+const NONCE_PREFIX_LEN: usize = 7;
+const SEGMENT_SIZE: usize = 64 * 1024;
+const TAG_SIZE: usize = 16;
+
+#[derive(Clone)]
+pub struct EncryptionKey([u8; 32]);
+
+impl EncryptionKey {
+    pub fn generate() -> EncryptionKey {
+        let mut key = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut key);
+        EncryptionKey(key)
+    }
+
+    pub fn from_hex(hex_key: &str) -> Result<EncryptionKey, OxenError> {
+        let bytes = hex::decode(hex_key.trim())
+            .map_err(|err| OxenError::basic_str(format!("Invalid encryption key: {err}")))?;
+        let key: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| OxenError::basic_str("Invalid encryption key: must be 32 bytes"))?;
+        Ok(EncryptionKey(key))
+    }
+
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+
+    fn cipher(&self) -> Aes256Gcm {
+        Aes256Gcm::new(GenericArray::from_slice(&self.0))
+    }
+}
+
+// Never print the key material
+impl std::fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EncryptionKey(..)")
+    }
+}
+
+pub fn key_path(key_id: impl AsRef<str>) -> Result<PathBuf, OxenError> {
+    Ok(util::fs::oxen_config_dir()?
+        .join(KEYS_DIR)
+        .join(key_id.as_ref()))
+}
+
+/// Save the key to the keys dir in the user config, readable only by the user
+pub fn save_key(key_id: impl AsRef<str>, key: &EncryptionKey) -> Result<PathBuf, OxenError> {
+    let path = key_path(key_id)?;
+    if let Some(parent) = path.parent() {
+        util::fs::create_dir_all(parent)?;
+    }
+    util::fs::write_to_path(&path, key.to_hex())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(path)
+}
+
+pub fn load_key(key_id: impl AsRef<str>) -> Result<EncryptionKey, OxenError> {
+    if let Ok(hex_key) = std::env::var(ENCRYPTION_KEY_ENV) {
+        return EncryptionKey::from_hex(&hex_key);
+    }
+    let key_id = key_id.as_ref();
+    let path = key_path(key_id)?;
+    if !path.exists() {
+        return Err(OxenError::basic_str(format!(
+            "Encryption key {key_id} not found. Set {ENCRYPTION_KEY_ENV} or copy the key to {path:?}"
+        )));
+    }
+    EncryptionKey::from_hex(&util::fs::read_from_path(&path)?)
+}
+
+/// The key of the repository, None if it is not encrypted
+pub fn repo_key(repo: &LocalRepository) -> Result<Option<EncryptionKey>, OxenError> {
+    repo.encryption_key_id().map(load_key).transpose()
+}
+
+pub fn is_encrypted(path: impl AsRef<Path>) -> bool {
+    Encoding::of(path).encrypted
+}
+
+pub fn encrypt<R: Read, W: Write>(
+    key: &EncryptionKey,
+    reader: R,
+    mut writer: W,
+) -> Result<(), OxenError> {
+    let mut nonce = [0u8; NONCE_PREFIX_LEN];
+    rand::thread_rng().fill_bytes(&mut nonce);
+    writer.write_all(MAGIC)?;
+    writer.write_all(&nonce)?;
+
+    let mut encryptor = EncryptorBE32::from_aead(key.cipher(), GenericArray::from_slice(&nonce));
+    let mut segments = Segments::new(reader, SEGMENT_SIZE);
+    loop {
+        let (segment, is_last) = segments.next()?;
+        if is_last {
+            let ciphertext = encryptor
+                .encrypt_last(segment.as_slice())
+                .map_err(|_| OxenError::basic_str("Could not encrypt file"))?;
+            writer.write_all(&ciphertext)?;
+            break;
+        }
+        let ciphertext = encryptor
+            .encrypt_next(segment.as_slice())
+            .map_err(|_| OxenError::basic_str("Could not encrypt file"))?;
+        writer.write_all(&ciphertext)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+pub fn decrypt<R: Read, W: Write>(
+    key: &EncryptionKey,
+    mut reader: R,
+    mut writer: W,
+) -> Result<(), OxenError> {
+    let mut magic = [0u8; MAGIC.len()];
+    reader.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+        return Err(OxenError::basic_str("File is not encrypted"));
+    }
+    let mut nonce = [0u8; NONCE_PREFIX_LEN];
+    reader.read_exact(&mut nonce)?;
+
+    let mut decryptor = DecryptorBE32::from_aead(key.cipher(), GenericArray::from_slice(&nonce));
+    let mut segments = Segments::new(reader, SEGMENT_SIZE + TAG_SIZE);
+    let bad_key =
+        || OxenError::basic_str("Could not decrypt file, the key is wrong or the file is corrupt");
+    loop {
+        let (segment, is_last) = segments.next()?;
+        if is_last {
+            let plaintext = decryptor
+                .decrypt_last(segment.as_slice())
+                .map_err(|_| bad_key())?;
+            writer.write_all(&plaintext)?;
+            break;
+        }
+        let plaintext = decryptor
+            .decrypt_next(segment.as_slice())
+            .map_err(|_| bad_key())?;
+        writer.write_all(&plaintext)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// How a stored file is encoded. It is told by the suffix of the file name and never by
+/// what the file holds, so a user file that happens to look like an encoded one is still
+/// read as is. Only names without an extension are ever encoded, like the `data` of a
+/// version or a `shard_<n>`, which leaves the `data.<ext>` versions of older
+/// repositories plain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Encoding {
+    pub compressed: bool,
+    pub encrypted: bool,
+}
+
+impl Encoding {
+    pub const PLAIN: Encoding = Encoding {
+        compressed: false,
+        encrypted: false,
+    };
+
+    // Longest suffix first, so `-zstd-enc` is not taken for `-enc`
+    const ENCODED: [Encoding; 3] = [
+        Encoding {
+            compressed: true,
+            encrypted: true,
+        },
+        Encoding {
+            compressed: false,
+            encrypted: true,
+        },
+        Encoding {
+            compressed: true,
+            encrypted: false,
+        },
+    ];
+
+    /// The encoding of the file at `path`, from its file name
+    pub fn of(path: impl AsRef<Path>) -> Encoding {
+        Encoding::split(path.as_ref()).1
+    }
+
+    /// How the repository stores its version files and chunk shards
+    pub fn for_repo(repo: &LocalRepository) -> Encoding {
+        Encoding {
+            compressed: repo.compression_level().is_some(),
+            encrypted: repo.encryption_key_id().is_some(),
+        }
+    }
+
+    pub fn is_plain(&self) -> bool {
+        *self == Encoding::PLAIN
+    }
+
+    pub fn suffix(&self) -> &'static str {
+        match (self.compressed, self.encrypted) {
+            (false, false) => "",
+            (true, false) => COMPRESSED_FILE_SUFFIX,
+            (false, true) => ENCRYPTED_FILE_SUFFIX,
+            (true, true) => COMPRESSED_ENCRYPTED_FILE_SUFFIX,
+        }
+    }
+
+    /// Where the file at `path` is stored with this encoding
+    pub fn path(&self, path: impl AsRef<Path>) -> PathBuf {
+        let path = path.as_ref();
+        let (name, _) = Encoding::split(path);
+        path.with_file_name(format!("{name}{}", self.suffix()))
+    }
+
+    /// The plain path of the stored file at `path`
+    pub fn strip(path: impl AsRef<Path>) -> PathBuf {
+        Encoding::PLAIN.path(path)
+    }
+
+    /// Whether the file at `path` can be stored encoded at all
+    pub fn can_encode(path: impl AsRef<Path>) -> bool {
+        let (name, _) = Encoding::split(path.as_ref());
+        !name.is_empty() && !name.contains('.')
+    }
+
+    // The plain file name and the encoding of the file at `path`
+    fn split(path: &Path) -> (String, Encoding) {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        for encoding in Encoding::ENCODED {
+            if let Some(plain_name) = name.strip_suffix(encoding.suffix()) {
+                if !plain_name.is_empty() && !plain_name.contains('.') {
+                    return (plain_name.to_string(), encoding);
+                }
+            }
+        }
+        (name, Encoding::PLAIN)
+    }
+}
+
+/// Where the version or chunk shard with the plain path `path` is stored. If there is no
+/// plain copy, this is the compressed or encrypted copy next to it.
+pub fn stored_path(path: impl AsRef<Path>) -> PathBuf {
+    let path = path.as_ref();
+    if path.exists() || !Encoding::can_encode(path) {
+        return path.to_path_buf();
+    }
+    Encoding::ENCODED
+        .into_iter()
+        .map(|encoding| encoding.path(path))
+        .find(|stored| stored.exists())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// Store the plain file `src` as the version or chunk shard at the plain path `dst`, the
+/// way the repository stores them. Returns where it was stored.
+pub fn store(
+    repo: &LocalRepository,
+    src: impl AsRef<Path>,
+    dst: impl AsRef<Path>,
+) -> Result<PathBuf, OxenError> {
+    let encoding = Encoding::for_repo(repo);
+    let key = repo_key(repo)?;
+    let dst = encoding.path(dst);
+    write_encoded(
+        src.as_ref(),
+        Encoding::PLAIN,
+        &dst,
+        encoding,
+        repo.compression_level(),
+        key.as_ref(),
+    )?;
+    Ok(dst)
+}
+
+/// Encode a plain version or chunk shard that was written in place, like a download,
+/// the way the repository stores them. Returns where it is stored now.
+pub fn store_in_place(
+    repo: &LocalRepository,
+    path: impl AsRef<Path>,
+) -> Result<PathBuf, OxenError> {
+    let key = repo_key(repo)?;
+    reencode(
+        path,
+        Encoding::for_repo(repo),
+        repo.compression_level(),
+        key.as_ref(),
+    )
+}
+
+/// Store the file at `path` with `encoding` instead, compressing at `level` and
+/// encrypting with `key` as needed. The new copy is written under the file name of its
+/// encoding before the old one is removed, so readers always find a whole copy. Files
+/// that can't be encoded, see [Encoding], are left as they are. Returns where the file
+/// is stored.
+pub fn reencode(
+    path: impl AsRef<Path>,
+    encoding: Encoding,
+    level: Option<i32>,
+    key: Option<&EncryptionKey>,
+) -> Result<PathBuf, OxenError> {
+    let path = path.as_ref();
+    let current = Encoding::of(path);
+    if current == encoding || !Encoding::can_encode(path) {
+        return Ok(path.to_path_buf());
+    }
+    let dst = encoding.path(path);
+    // Version files may have been made read only by hard link checkouts
+    let permissions = std::fs::metadata(path)?.permissions();
+    write_encoded(path, current, &dst, encoding, level, key)?;
+    std::fs::set_permissions(&dst, permissions)?;
+    std::fs::remove_file(path)?;
+    Ok(dst)
+}
+
+/// Encrypt the file where it is, leaving files that are already encrypted alone
+pub fn encrypt_in_place(key: &EncryptionKey, path: impl AsRef<Path>) -> Result<bool, OxenError> {
+    let path = path.as_ref();
+    let current = Encoding::of(path);
+    if current.encrypted {
+        return Ok(false);
+    }
+    let encoding = Encoding {
+        encrypted: true,
+        ..current
+    };
+    Ok(reencode(path, encoding, None, Some(key))? != path)
+}
+
+/// Decrypt the file where it is, leaving plain files alone
+pub fn decrypt_in_place(key: &EncryptionKey, path: impl AsRef<Path>) -> Result<bool, OxenError> {
+    let path = path.as_ref();
+    let current = Encoding::of(path);
+    if !current.encrypted {
+        return Ok(false);
+    }
+    let encoding = Encoding {
+        encrypted: false,
+        ..current
+    };
+    Ok(reencode(path, encoding, None, Some(key))? != path)
+}
+
+// Write `src`, stored with `from`, to `dst` with `to`. Written next to `dst` and renamed
+// into place, so a failure never leaves half a file.
+fn write_encoded(
+    src: &Path,
+    from: Encoding,
+    dst: &Path,
+    to: Encoding,
+    level: Option<i32>,
+    key: Option<&EncryptionKey>,
+) -> Result<(), OxenError> {
+    let tmp_path = dst.with_extension(format!("tmp-{}", uuid::Uuid::new_v4()));
+    let result = (|| {
+        let require_key = || {
+            key.ok_or_else(|| {
+                OxenError::basic_str(format!(
+                    "{src:?} needs the encryption key of the repository"
+                ))
+            })
+        };
+
+        // Decryption writes out what it decrypts, so decrypt to a temp file first. The
+        // rest is streamed from one file to the other.
+        let decrypted = if from.encrypted {
+            let decrypted_path = dst.with_extension(format!("tmp-{}", uuid::Uuid::new_v4()));
+            let writer = BufWriter::new(File::create(&decrypted_path)?);
+            let decrypted = PlainPath {
+                path: decrypted_path,
+                is_temp: true,
+            };
+            decrypt(require_key()?, BufReader::new(File::open(src)?), writer)?;
+            Some(decrypted)
+        } else {
+            None
+        };
+        let src = decrypted.as_ref().map(|path| path.path()).unwrap_or(src);
+
+        let file = BufReader::new(File::open(src)?);
+        let mut reader: Box<dyn Read> = match (from.compressed, to.compressed) {
+            (true, false) => Box::new(zstd::stream::read::Decoder::new(file)?),
+            (false, true) => {
+                let level = level.unwrap_or(compression::DEFAULT_COMPRESSION_LEVEL);
+                Box::new(zstd::stream::read::Encoder::new(file, level)?)
+            }
+            _ => Box::new(file),
+        };
+        let mut tmp = File::create(&tmp_path)?;
+        let mut writer = BufWriter::new(&mut tmp);
+        if to.encrypted {
+            encrypt(require_key()?, reader, &mut writer)?;
+        } else {
+            std::io::copy(&mut reader, &mut writer)?;
+            writer.flush()?;
+        }
+        drop(writer);
+        tmp.sync_all()?;
+        Ok(())
+    })();
+    if let Err(err) = result {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(err);
+    }
+    std::fs::rename(&tmp_path, dst)?;
+    Ok(())
+}
+
+/// Whether the file is stored as is, neither encrypted nor compressed
+pub fn is_plain(path: impl AsRef<Path>) -> bool {
+    Encoding::of(path).is_plain()
+}
+
+// Decrypt and decompress the file into `writer`
+fn write_plain<W: Write>(repo: &LocalRepository, path: &Path, writer: W) -> Result<(), OxenError> {
+    let encoding = Encoding::of(path);
+    if encoding.compressed {
+        let mut decoder = zstd::stream::write::Decoder::new(writer)?;
+        write_decrypted(repo, path, encoding, &mut decoder)?;
+        decoder.flush()?;
+        decoder.into_inner().flush()?;
+    } else {
+        write_decrypted(repo, path, encoding, writer)?;
+    }
+    Ok(())
+}
+
+fn write_decrypted<W: Write>(
+    repo: &LocalRepository,
+    path: &Path,
+    encoding: Encoding,
+    mut writer: W,
+) -> Result<(), OxenError> {
+    let mut reader = BufReader::new(File::open(path)?);
+    if encoding.encrypted {
+        let key = require_key(repo, path)?;
+        decrypt(&key, reader, writer)?;
+    } else {
+        std::io::copy(&mut reader, &mut writer)?;
+        writer.flush()?;
+    }
+    Ok(())
+}
+
+/// Copy `src` to `dst`, decrypting and decompressing it if needed
+pub fn copy_decrypted(
+    repo: &LocalRepository,
+    src: impl AsRef<Path>,
+    dst: impl AsRef<Path>,
+) -> Result<(), OxenError> {
+    let (src, dst) = (src.as_ref(), dst.as_ref());
+    if is_plain(src) {
+        util::fs::copy(src, dst)?;
+        return Ok(());
+    }
+    let writer = BufWriter::new(util::fs::file_create(dst)?);
+    write_plain(repo, src, writer)
+}
+
+/// Open the file for reading, decrypting and decompressing it into memory if needed.
+/// Meant for files that are read in full anyway, like chunk shards.
+pub fn open(repo: &LocalRepository, path: impl AsRef<Path>) -> Result<MaybeDecrypted, OxenError> {
+    let path = path.as_ref();
+    if is_plain(path) {
+        return Ok(MaybeDecrypted::Plain(File::open(path)?));
+    }
+    let mut buffer = Vec::new();
+    write_plain(repo, path, &mut buffer)?;
+    Ok(MaybeDecrypted::Decrypted(Cursor::new(buffer)))
+}
+
+/// A plain path to the contents of `path`. Encrypted and compressed files are decoded
+/// into a temp file under `.oxen/tmp` that is removed when the returned value is dropped,
+/// for readers that need a path, like polars or chunked uploads.
+pub fn plain_path(repo: &LocalRepository, path: impl AsRef<Path>) -> Result<PlainPath, OxenError> {
+    let path = path.as_ref();
+    if !path.exists() || is_plain(path) {
+        return Ok(PlainPath {
+            path: path.to_path_buf(),
+            is_temp: false,
+        });
+    }
+    let tmp_dir = util::fs::oxen_hidden_dir(&repo.path)
+        .join("tmp")
+        .join("decrypted");
+    util::fs::create_dir_all(&tmp_dir)?;
+    // Keep the extension, readers like polars go by it
+    let mut name = uuid::Uuid::new_v4().to_string();
+    if let Some(extension) = path.extension() {
+        name = format!("{name}.{}", extension.to_string_lossy());
+    }
+    let plain = PlainPath {
+        path: tmp_dir.join(name),
+        is_temp: true,
+    };
+    copy_decrypted(repo, path, &plain.path)?;
+    Ok(plain)
+}
+
+/// Read up to `len` plain bytes of `path` starting at `start`. Encrypted and compressed
+/// files are decoded as a stream and only the range is kept, so no plain copy of the file
+/// is ever written to disk.
+pub fn read_plain_range(
+    repo: &LocalRepository,
+    path: impl AsRef<Path>,
+    start: u64,
+    len: u64,
+) -> Result<PlainRange, OxenError> {
+    let path = path.as_ref();
+    if is_plain(path) {
+        let mut file = File::open(path)?;
+        let file_len = file.metadata()?.len();
+        file.seek(SeekFrom::Start(start.min(file_len)))?;
+        let mut bytes = Vec::new();
+        file.take(len).read_to_end(&mut bytes)?;
+        return Ok(PlainRange {
+            bytes,
+            file_len: Some(file_len),
+        });
+    }
+
+    let mut range = RangeWriter {
+        skip: start,
+        remaining: len,
+        bytes: Vec::new(),
+        seen: 0,
+    };
+    match write_plain(repo, path, &mut range) {
+        Ok(()) => Ok(PlainRange {
+            bytes: range.bytes,
+            file_len: Some(range.seen),
+        }),
+        // Stopped decoding once the range was filled
+        Err(_) if range.remaining == 0 => Ok(PlainRange {
+            bytes: range.bytes,
+            file_len: None,
+        }),
+        Err(err) => Err(err),
+    }
+}
+
+fn require_key(repo: &LocalRepository, path: &Path) -> Result<EncryptionKey, OxenError> {
+    match repo_key(repo)? {
+        Some(key) => Ok(key),
+        None => Err(OxenError::basic_str(format!(
+            "{path:?} is encrypted but the repository has no encryption key configured"
+        ))),
+    }
+}
+
+#[derive(Debug)]
+pub struct PlainPath {
+    path: PathBuf,
+    is_temp: bool,
+}
+
+impl PlainPath {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl AsRef<Path> for PlainPath {
+    fn as_ref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for PlainPath {
+    fn drop(&mut self) {
+        if self.is_temp {
+            if let Err(err) = std::fs::remove_file(&self.path) {
+                log::error!("Could not remove decrypted file {:?}: {}", self.path, err);
+            }
+        }
+    }
+}
+
+/// Plain bytes read by [read_plain_range]
+#[derive(Debug)]
+pub struct PlainRange {
+    pub bytes: Vec<u8>,
+    /// Plain length of the whole file, None if decoding stopped before the end
+    pub file_len: Option<u64>,
+}
+
+// Keeps the bytes of a range out of everything written to it, and fails the writes that
+// come after it so the decoder stops early
+struct RangeWriter {
+    skip: u64,
+    remaining: u64,
+    bytes: Vec<u8>,
+    seen: u64,
+}
+
+impl Write for RangeWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.remaining == 0 && !buf.is_empty() {
+            return Err(std::io::Error::other("Read the whole range"));
+        }
+        self.seen += buf.len() as u64;
+        let skipped = self.skip.min(buf.len() as u64);
+        self.skip -= skipped;
+        let rest = &buf[skipped as usize..];
+        let kept = self.remaining.min(rest.len() as u64) as usize;
+        self.bytes.extend_from_slice(&rest[..kept]);
+        self.remaining -= kept as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+pub enum MaybeDecrypted {
+    Plain(File),
+    Decrypted(Cursor<Vec<u8>>),
+}
+
+impl MaybeDecrypted {
+    /// Length of the plain contents
+    pub fn len(&self) -> std::io::Result<u64> {
+        match self {
+            MaybeDecrypted::Plain(file) => Ok(file.metadata()?.len()),
+            MaybeDecrypted::Decrypted(cursor) => Ok(cursor.get_ref().len() as u64),
+        }
+    }
+
+    pub fn is_empty(&self) -> std::io::Result<bool> {
+        Ok(self.len()? == 0)
+    }
+}
+
+impl Read for MaybeDecrypted {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            MaybeDecrypted::Plain(file) => file.read(buf),
+            MaybeDecrypted::Decrypted(cursor) => cursor.read(buf),
+        }
+    }
+}
+
+impl Seek for MaybeDecrypted {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        match self {
+            MaybeDecrypted::Plain(file) => file.seek(pos),
+            MaybeDecrypted::Decrypted(cursor) => cursor.seek(pos),
+        }
+    }
+}
+
+/// Reads fixed size segments, telling whether each one is the last
+struct Segments<R: Read> {
+    reader: R,
+    size: usize,
+    next: Option<Vec<u8>>,
+}
+
+impl<R: Read> Segments<R> {
+    fn new(reader: R, size: usize) -> Self {
+        Segments {
+            reader,
+            size,
+            next: None,
+        }
+    }
+
+    fn read_segment(&mut self) -> Result<Vec<u8>, OxenError> {
+        let mut buffer = Vec::with_capacity(self.size);
+        (&mut self.reader)
+            .take(self.size as u64)
+            .read_to_end(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    fn next(&mut self) -> Result<(Vec<u8>, bool), OxenError> {
+        let current = match self.next.take() {
+            Some(segment) => segment,
+            None => self.read_segment()?,
+        };
+        if current.len() < self.size {
+            return Ok((current, true));
+        }
+        // A full segment is only the last one if nothing follows it
+        let next = self.read_segment()?;
+        if next.is_empty() {
+            return Ok((current, true));
+        }
+        self.next = Some(next);
+        Ok((current, false))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::OxenError;
+    use std::path::Path;
+
+    use crate::util::encryption::{self, Encoding, EncryptionKey, SEGMENT_SIZE};
+
+    #[test]
+    fn test_encrypt_decrypt_roundtrip() -> Result<(), OxenError> {
+        let key = EncryptionKey::generate();
+        // Empty, smaller than a segment, exactly one segment and a few segments
+        for size in [0, 10, SEGMENT_SIZE, SEGMENT_SIZE * 3 + 7] {
+            let data: Vec<u8> = (0..size).map(|i| (i % 251) as u8).collect();
+            let mut encrypted = Vec::new();
+            encryption::encrypt(&key, data.as_slice(), &mut encrypted)?;
+            assert_ne!(encrypted, data);
+
+            let mut decrypted = Vec::new();
+            encryption::decrypt(&key, encrypted.as_slice(), &mut decrypted)?;
+            assert_eq!(decrypted, data);
+
+            // Truncated files and the wrong key are both rejected
+            let mut truncated = Vec::new();
+            let cut = encrypted.len() - 1;
+            assert!(encryption::decrypt(&key, &encrypted[..cut], &mut truncated).is_err());
+            let other = EncryptionKey::generate();
+            assert!(encryption::decrypt(&other, encrypted.as_slice(), &mut Vec::new()).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_key_hex_roundtrip() -> Result<(), OxenError> {
+        let key = EncryptionKey::generate();
+        let parsed = EncryptionKey::from_hex(&key.to_hex())?;
+        assert_eq!(parsed.to_hex(), key.to_hex());
+        assert!(EncryptionKey::from_hex("abcd").is_err());
+        assert!(!format!("{key:?}").contains(&key.to_hex()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_encoding_is_told_by_file_name() {
+        let dir = Path::new("versions").join("ab").join("cdef");
+        let plain = dir.join("data");
+        let both = Encoding {
+            compressed: true,
+            encrypted: true,
+        };
+        assert_eq!(both.path(&plain), dir.join("data-zstd-enc"));
+        assert_eq!(Encoding::of(dir.join("data-zstd-enc")), both);
+        assert_eq!(Encoding::strip(dir.join("data-zstd-enc")), plain);
+        assert!(encryption::is_encrypted(dir.join("shard_3-enc")));
+
+        // Names with an extension are never encoded, whatever they end with
+        assert!(!Encoding::can_encode(dir.join("data.csv")));
+        assert!(encryption::is_plain(dir.join("labels.txt-enc")));
+        assert!(encryption::is_plain(dir.join("-enc")));
+    }
+}
@@ -22,8 +22,10 @@ use crate::constants::CONTENT_IS_VALID;
 use crate::constants::DATA_ARROW_FILE;
 use crate::constants::HISTORY_DIR;
 use crate::constants::OXEN_HIDDEN_DIR;
+use crate::constants::SQLITE_HEADER;
 use crate::constants::TREE_DIR;
 use crate::constants::VERSION_FILE_NAME;
+use crate::core::df::geo;
 use crate::core::versions::MinOxenVersion;
 use crate::error::OxenError;
 use crate::model::entry::commit_entry::Entry;
@@ -31,13 +33,14 @@ use crate::model::merkle_tree::node::FileNode;
 use crate::model::metadata::metadata_image::ImgResize;
 use crate::model::Commit;
 use crate::model::Schema;
-use crate::model::{CommitEntry, EntryDataType, LocalRepository};
+use crate::model::{CheckoutMode, CommitEntry, EntryDataType, LocalRepository};
 use crate::opts::CountLinesOpts;
 use crate::view::health::DiskUsage;
 use image::ImageFormat;
 
 use crate::repositories;
 use crate::util;
+use crate::util::encryption::PlainPath;
 
 // Deprecated
 pub fn oxen_hidden_dir(repo_path: impl AsRef<Path>) -> PathBuf {
@@ -120,6 +123,18 @@ pub fn resized_path_for_file_node(
     Ok(resized_path)
 }
 
+/// Where the side by side composite of two versions of an image is cached, next to the head version
+pub fn image_diff_path_for_file_nodes(
+    repo: &LocalRepository,
+    base_node: &FileNode,
+    head_node: &FileNode,
+) -> PathBuf {
+    let path = version_path_from_hash(repo, head_node.hash.to_string());
+    path.parent()
+        .unwrap()
+        .join(format!("diff_{}.png", base_node.hash))
+}
+
 pub fn resized_path_for_staged_entry(
     branch_repo: LocalRepository,
     img_path: &Path,
@@ -163,11 +178,12 @@ pub fn version_file_size(repo: &LocalRepository, entry: &CommitEntry) -> Result<
 }
 
 pub fn chunk_path(repo: &LocalRepository, hash: impl AsRef<str>) -> PathBuf {
-    oxen_hidden_dir(&repo.path)
+    let path = oxen_hidden_dir(&repo.path)
         .join(TREE_DIR)
         .join(CHUNKS_DIR)
         .join(hash.as_ref())
-        .join("data")
+        .join(VERSION_FILE_NAME);
+    util::encryption::stored_path(path)
 }
 
 pub fn version_path(repo: &LocalRepository, entry: &CommitEntry) -> PathBuf {
@@ -220,6 +236,24 @@ pub fn version_path_from_hash(repo: &LocalRepository, hash: impl AsRef<str>) ->
     }
 }
 
+/// Path to the plain contents of the version with `hash`. Use this rather than
+/// [version_path_from_hash] to read a version, encrypted and compressed versions are
+/// decoded to a temp file that lives as long as the returned value.
+pub fn plain_version_path_from_hash(
+    repo: &LocalRepository,
+    hash: impl AsRef<str>,
+) -> Result<PlainPath, OxenError> {
+    util::encryption::plain_path(repo, version_path_from_hash(repo, hash))
+}
+
+/// Path to the plain contents of the version of `entry`, see [plain_version_path_from_hash]
+pub fn plain_version_path(
+    repo: &LocalRepository,
+    entry: &CommitEntry,
+) -> Result<PlainPath, OxenError> {
+    util::encryption::plain_path(repo, version_path(repo, entry))
+}
+
 pub fn version_path_for_entry(repo: &LocalRepository, entry: &Entry) -> PathBuf {
     match entry {
         Entry::CommitEntry(commit_entry) => version_path(repo, commit_entry),
@@ -277,7 +311,7 @@ pub fn version_path_from_hash_and_file(
     // );
     let extension = extension_from_path(filename);
     if extension.is_empty() {
-        version_dir.join(VERSION_FILE_NAME)
+        util::encryption::stored_path(version_dir.join(VERSION_FILE_NAME))
     } else {
         // backwards compatibility
         let path = version_dir.join(format!("{}.{}", VERSION_FILE_NAME, extension));
@@ -286,7 +320,7 @@ pub fn version_path_from_hash_and_file(
             path
         } else {
             // Newer files do not have the extension in the filename
-            version_dir.join(VERSION_FILE_NAME)
+            util::encryption::stored_path(version_dir.join(VERSION_FILE_NAME))
         }
     }
 }
@@ -299,7 +333,7 @@ pub fn version_path_from_schema(dst: impl AsRef<Path>, schema: &Schema) -> PathB
 pub fn version_path_from_schema_hash(dst: impl AsRef<Path>, hash: String) -> PathBuf {
     // Save schemas as path with no extension
     let version_dir = version_dir_from_hash(dst, hash);
-    version_dir.join(VERSION_FILE_NAME)
+    util::encryption::stored_path(version_dir.join(VERSION_FILE_NAME))
 }
 
 pub fn extension_from_path(path: &Path) -> String {
@@ -624,6 +658,24 @@ pub fn get_repo_root(path: &Path) -> Option<PathBuf> {
     }
 }
 
+/// A directory below the root of a repository that has its own .oxen dir
+pub fn is_nested_repo(repo_path: &Path, path: &Path) -> bool {
+    path != repo_path && path.join(OXEN_HIDDEN_DIR).is_dir()
+}
+
+/// Find the root of a nested repository that contains `path`, if there is one
+pub fn find_nested_repo(repo_path: &Path, path: &Path) -> Option<PathBuf> {
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        repo_path.join(path)
+    };
+    path.ancestors()
+        .take_while(|dir| dir.starts_with(repo_path) && *dir != repo_path)
+        .find(|dir| is_nested_repo(repo_path, dir))
+        .map(|dir| dir.to_path_buf())
+}
+
 pub fn get_repo_root_from_current_dir() -> Option<PathBuf> {
     let Ok(path) = std::env::current_dir() else {
         log::error!("Could not get current directory");
@@ -704,6 +756,81 @@ pub fn copy(src: impl AsRef<Path>, dst: impl AsRef<Path>) -> Result<(), OxenErro
     }
 }
 
+/// Put a version file into the working directory using the repository's checkout mode.
+///
+/// Any existing file at `dst` is removed first rather than truncated, so a file that is
+/// hard linked to another version is never written through.
+pub fn copy_version_to_working(
+    repo: &LocalRepository,
+    version_path: impl AsRef<Path>,
+    dst: impl AsRef<Path>,
+) -> Result<(), OxenError> {
+    let version_path = version_path.as_ref();
+    let dst = dst.as_ref();
+    if dst.exists() {
+        remove_file(dst)?;
+    }
+
+    // Encrypted and compressed version files can only be decoded into a copy, never linked
+    if !util::encryption::is_plain(version_path) {
+        util::encryption::copy_decrypted(repo, version_path, dst)?;
+        return make_writable(dst);
+    }
+
+    let mode = repo.checkout_mode();
+
+    if mode == CheckoutMode::Hardlink {
+        // Version files never change, make that explicit so editors cannot write
+        // through the link into history
+        let mut permissions = std::fs::metadata(version_path)
+            .map_err(|err| OxenError::file_error(version_path, err))?
+            .permissions();
+        if !permissions.readonly() {
+            permissions.set_readonly(true);
+            std::fs::set_permissions(version_path, permissions)?;
+        }
+        match std::fs::hard_link(version_path, dst) {
+            Ok(_) => return Ok(()),
+            // Different filesystem or no hard link support, copy is always safe
+            Err(err) => log::debug!("hard link failed, copying {:?}: {}", dst, err),
+        }
+    }
+
+    if mode == CheckoutMode::Reflink {
+        if let Err(err) = reflink_copy::reflink_or_copy(version_path, dst) {
+            return Err(OxenError::file_copy_error(version_path, dst, err));
+        }
+    } else {
+        copy(version_path, dst)?;
+    }
+
+    // Copies keep the permissions of the version file, which is read only if the
+    // repository ever used hard links
+    make_writable(dst)
+}
+
+#[cfg(unix)]
+fn make_writable(path: &Path) -> Result<(), OxenError> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut permissions = std::fs::metadata(path)?.permissions();
+    if permissions.mode() & 0o200 == 0 {
+        permissions.set_mode(permissions.mode() | 0o200);
+        std::fs::set_permissions(path, permissions)?;
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+#[allow(clippy::permissions_set_readonly_false)]
+fn make_writable(path: &Path) -> Result<(), OxenError> {
+    let mut permissions = std::fs::metadata(path)?.permissions();
+    if permissions.readonly() {
+        permissions.set_readonly(false);
+        std::fs::set_permissions(path, permissions)?;
+    }
+    Ok(())
+}
+
 /// Wrapper around the std::fs::rename command to tell us which file failed to copy
 pub fn rename(src: impl AsRef<Path>, dst: impl AsRef<Path>) -> Result<(), OxenError> {
     let src = src.as_ref();
@@ -806,6 +933,26 @@ pub fn write(src: impl AsRef<Path>, data: impl AsRef<[u8]>) -> Result<(), OxenEr
     }
 }
 
+/// Read the `chunk_num`th chunk of `chunk_size` bytes from a file, the last chunk may be shorter
+pub fn read_chunk(
+    path: impl AsRef<Path>,
+    chunk_num: usize,
+    chunk_size: u64,
+) -> Result<Vec<u8>, OxenError> {
+    let path = path.as_ref();
+    let read = || -> Result<Vec<u8>, std::io::Error> {
+        let mut file = File::open(path)?;
+        file.seek(std::io::SeekFrom::Start(chunk_num as u64 * chunk_size))?;
+        let mut chunk = Vec::with_capacity(chunk_size as usize);
+        file.take(chunk_size).read_to_end(&mut chunk)?;
+        Ok(chunk)
+    };
+    read().map_err(|err| {
+        log::error!("read_chunk {:?} chunk {} {}", path, chunk_num, err);
+        OxenError::file_error(path, err)
+    })
+}
+
 /// Wrapper around the util::fs::remove_file command to tell us which file it failed on
 pub fn remove_file(src: impl AsRef<Path>) -> Result<(), OxenError> {
     let src = src.as_ref();
@@ -854,13 +1001,35 @@ pub fn is_tabular_from_extension(data_path: &Path, file_path: &Path) -> bool {
         }
     }
 
-    let exts: HashSet<String> = vec!["csv", "tsv", "parquet", "arrow", "ndjson", "jsonl"]
-        .into_iter()
-        .map(String::from)
-        .collect();
+    // .db is used for plenty of things that are not SQLite, so check the header
+    if has_ext(file_path, "db") && is_sqlite(data_path) {
+        return true;
+    }
+
+    let exts: HashSet<String> = vec![
+        "csv", "tsv", "parquet", "arrow", "ndjson", "jsonl", "sqlite", "sqlite3", "geojson",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect();
     contains_ext(file_path, &exts)
 }
 
+/// Checks for the "SQLite format 3" header at the start of the file
+pub fn is_sqlite(path: &Path) -> bool {
+    let Ok(mut file) = File::open(path) else {
+        return false;
+    };
+    let mut header = [0u8; 16];
+    file.read_exact(&mut header).is_ok() && header == *SQLITE_HEADER
+}
+
+/// GeoJSON, or parquet files with GeoParquet metadata in the footer
+pub fn is_geospatial_from_extension(data_path: &Path, file_path: &Path) -> bool {
+    has_ext(file_path, "geojson")
+        || (has_ext(file_path, "parquet") && geo::is_geoparquet(data_path))
+}
+
 pub fn is_tabular(path: &Path) -> bool {
     is_tabular_from_extension(path, path)
 }
@@ -903,6 +1072,9 @@ pub fn data_type_from_extension(path: &Path) -> EntryDataType {
         "arrow" => EntryDataType::Tabular,
         "ndjson" => EntryDataType::Tabular,
         "jsonl" => EntryDataType::Tabular,
+        "sqlite" => EntryDataType::Tabular,
+        "sqlite3" => EntryDataType::Tabular,
+        "geojson" => EntryDataType::Geospatial,
 
         "md" => EntryDataType::Text,
         "txt" => EntryDataType::Text,
@@ -1004,6 +1176,9 @@ pub fn datatype_from_mimetype_from_extension(
         "video/x-flv" => EntryDataType::Video,
         "video/x-ms-wmv" => EntryDataType::Video,
 
+        // Tabular
+        "application/vnd.sqlite3" => EntryDataType::Tabular,
+
         // Audio
         "audio/midi" => EntryDataType::Audio,
         "audio/mpeg" => EntryDataType::Audio,
@@ -1024,7 +1199,9 @@ pub fn datatype_from_mimetype_from_extension(
             //     mime_type
             // );
             // Catch text and dataframe types from file extension
-            if is_tabular_from_extension(data_path, file_path) {
+            if is_geospatial_from_extension(data_path, file_path) {
+                EntryDataType::Geospatial
+            } else if is_tabular_from_extension(data_path, file_path) {
                 EntryDataType::Tabular
             } else if "text/plain" == mime_type || "text/markdown" == mime_type {
                 EntryDataType::Text
@@ -1268,13 +1445,19 @@ pub fn rcount_files_in_dir(dir: &Path) -> usize {
         return count;
     }
 
-    for entry in WalkDir::new(dir) {
+    // Prune the hidden oxen dirs instead of walking them and filtering every entry
+    let walker = WalkDir::new(dir).process_read_dir(|_, _, _, children| {
+        children.retain(|child| {
+            child
+                .as_ref()
+                .map(|child| child.file_name != OXEN_HIDDEN_DIR)
+                .unwrap_or(true)
+        });
+    });
+    for entry in walker {
         match entry {
             Ok(val) => {
-                let path = val.path();
-                // if it's not the hidden oxen dir and is not a directory
-                if !is_in_oxen_hidden_dir(&path) && !path.is_dir() {
-                    // log::debug!("Found file {count}: {:?}", path);
+                if !val.file_type.is_dir() {
                     count += 1;
                 }
             }
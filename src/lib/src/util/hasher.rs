@@ -2,6 +2,7 @@ use crate::core::db::key_val::tree_db::TreeObjectChild;
 use crate::error::OxenError;
 use crate::model::metadata::generic_metadata::GenericMetadata;
 use crate::model::{ContentHashable, NewCommit};
+use hmac::{Hmac, Mac};
 use sha2::{Digest, Sha256};
 use std::fs::File;
 use std::io::prelude::*;
@@ -26,6 +27,13 @@ pub fn hash_str_sha256<S: AsRef<str>>(str: S) -> String {
     format!("{result:x}")
 }
 
+/// HMAC-SHA256 of the message as lowercase hex, used to sign webhook payloads
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any size");
+    mac.update(message);
+    format!("{:x}", mac.finalize().into_bytes())
+}
+
 pub fn hash_buffer_128bit(buffer: &[u8]) -> u128 {
     xxh3_128(buffer)
 }
@@ -227,3 +235,17 @@ fn hash_large_file_contents(path: &Path) -> Result<u128, OxenError> {
 pub fn hash_path_name(path: impl AsRef<Path>) -> String {
     hash_str(path.as_ref().to_str().unwrap())
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::util::hasher;
+
+    #[test]
+    fn test_hmac_sha256() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            hasher::hmac_sha256(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use crate::error::OxenError;
-use image::imageops;
+use crate::model::diff::image_diff::{ImageDiff, ImageDiffImpl};
+use image::{imageops, DynamicImage, ImageReader, Rgba, RgbaImage};
 use std::path::Path;
 
 pub fn resize_and_save(
@@ -29,3 +30,247 @@ pub fn resize_and_save(
 
     Ok(())
 }
+
+/// Channel differences at or below this are treated as compression noise, not a change
+const PIXEL_DIFF_THRESHOLD: u8 = 8;
+
+/// Compare the pixels of two images, resizing the head to the base if their dimensions differ
+pub fn diff(base: impl AsRef<Path>, head: impl AsRef<Path>) -> Result<ImageDiff, OxenError> {
+    let base = open_any(base)?;
+    let head = open_any(head)?;
+    let (base_width, base_height) = (base.width(), base.height());
+    let (head_width, head_height) = (head.width(), head.height());
+    let base = base.to_rgba8();
+    let head = resized_to(&head, base_width, base_height);
+
+    let mut num_changed_pixels = 0;
+    let mut total_delta: u64 = 0;
+    let mut max_abs_delta = 0;
+    for (b, h) in base.pixels().zip(head.pixels()) {
+        let delta = pixel_delta(b, h);
+        total_delta +=
+            b.0.iter()
+                .zip(h.0.iter())
+                .map(|(x, y)| x.abs_diff(*y) as u64)
+                .sum::<u64>();
+        max_abs_delta = max_abs_delta.max(delta);
+        if delta > PIXEL_DIFF_THRESHOLD {
+            num_changed_pixels += 1;
+        }
+    }
+
+    let num_pixels = base_width as u64 * base_height as u64;
+    let (percent_changed, mean_abs_delta) = if num_pixels == 0 {
+        (0.0, 0.0)
+    } else {
+        (
+            num_changed_pixels as f64 / num_pixels as f64 * 100.0,
+            total_delta as f64 / (num_pixels * 4) as f64,
+        )
+    };
+    Ok(ImageDiff {
+        image: ImageDiffImpl {
+            base_width,
+            base_height,
+            head_width,
+            head_height,
+            num_pixels,
+            num_changed_pixels,
+            percent_changed,
+            mean_abs_delta,
+            max_abs_delta,
+            composite_path: None,
+        },
+    })
+}
+
+/// Write the base, the head and the changed pixels in red over a faded base side by side
+pub fn write_diff_composite(
+    base: impl AsRef<Path>,
+    head: impl AsRef<Path>,
+    dst: impl AsRef<Path>,
+) -> Result<(), OxenError> {
+    let base = open_any(base)?;
+    let head = open_any(head)?;
+    let (width, height) = (base.width(), base.height());
+    let base = base.to_rgba8();
+    let head_resized = resized_to(&head, width, height);
+    let head = head.to_rgba8();
+
+    let mut delta = RgbaImage::new(width, height);
+    for (x, y, b) in base.enumerate_pixels() {
+        let h = head_resized.get_pixel(x, y);
+        let pixel = if pixel_delta(b, h) > PIXEL_DIFF_THRESHOLD {
+            Rgba([255, 0, 0, 255])
+        } else {
+            let gray = (b.0[0] as u32 + b.0[1] as u32 + b.0[2] as u32) / 3;
+            let faded = (gray / 3 + 170) as u8;
+            Rgba([faded, faded, faded, 255])
+        };
+        delta.put_pixel(x, y, pixel);
+    }
+
+    let composite_height = height.max(head.height());
+    let mut composite = RgbaImage::from_pixel(
+        width * 2 + head.width(),
+        composite_height,
+        Rgba([255, 255, 255, 255]),
+    );
+    imageops::replace(&mut composite, &base, 0, 0);
+    imageops::replace(&mut composite, &head, width as i64, 0);
+    imageops::replace(&mut composite, &delta, (width + head.width()) as i64, 0);
+    composite.save_with_format(dst, image::ImageFormat::Png)?;
+    Ok(())
+}
+
+// Version files have no extension, so detect the format from the contents
+fn open_any(path: impl AsRef<Path>) -> Result<DynamicImage, OxenError> {
+    Ok(ImageReader::open(path)?.with_guessed_format()?.decode()?)
+}
+
+fn resized_to(img: &DynamicImage, width: u32, height: u32) -> RgbaImage {
+    if img.width() == width && img.height() == height {
+        img.to_rgba8()
+    } else {
+        imageops::resize(&img.to_rgba8(), width, height, imageops::Triangle)
+    }
+}
+
+// Largest difference of any channel
+fn pixel_delta(a: &Rgba<u8>, b: &Rgba<u8>) -> u8 {
+    a.0.iter()
+        .zip(b.0.iter())
+        .map(|(x, y)| x.abs_diff(*y))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Side of the grayscale thumbnail the DCT is computed over
+const PHASH_SIZE: usize = 32;
+/// Side of the block of low frequency DCT coefficients that make up the hash
+const PHASH_LOW_FREQ: usize = 8;
+
+/// Computes a 64 bit DCT perceptual hash (pHash) of an image.
+///
+/// Images that look alike, even after resizing, re-encoding or small edits,
+/// hash to values that are a small hamming distance apart.
+/// The format is read from the contents, so version files without an extension work too.
+pub fn perceptual_hash(path: impl AsRef<Path>) -> Result<u64, OxenError> {
+    let img = ImageReader::open(path)?.with_guessed_format()?.decode()?;
+    let size = PHASH_SIZE as u32;
+    let gray = imageops::resize(&img.to_luma8(), size, size, imageops::Triangle);
+    let pixels: Vec<f64> = gray.pixels().map(|p| p.0[0] as f64).collect();
+
+    let coefficients = low_frequency_dct(&pixels);
+
+    // Compare against the median, skipping the DC term which only tracks overall brightness
+    let mut sorted = coefficients[1..].to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let median = sorted[sorted.len() / 2];
+
+    let mut hash: u64 = 0;
+    for (i, value) in coefficients.iter().enumerate() {
+        if *value > median {
+            hash |= 1 << i;
+        }
+    }
+    Ok(hash)
+}
+
+/// Number of bits that differ between two perceptual hashes
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// The top left PHASH_LOW_FREQ x PHASH_LOW_FREQ block of the 2D DCT-II of a PHASH_SIZE square image
+fn low_frequency_dct(pixels: &[f64]) -> Vec<f64> {
+    let n = PHASH_SIZE;
+    let k = PHASH_LOW_FREQ;
+    let cosines: Vec<f64> = (0..k)
+        .flat_map(|u| {
+            (0..n).map(move |x| {
+                (((2 * x + 1) * u) as f64 * std::f64::consts::PI / (2 * n) as f64).cos()
+            })
+        })
+        .collect();
+
+    // Transform the rows, then the columns of the result
+    let mut rows = vec![0.0; n * k];
+    for y in 0..n {
+        for u in 0..k {
+            rows[y * k + u] = (0..n).map(|x| pixels[y * n + x] * cosines[u * n + x]).sum();
+        }
+    }
+
+    let mut coefficients = vec![0.0; k * k];
+    for v in 0..k {
+        for u in 0..k {
+            coefficients[v * k + u] = (0..n).map(|y| rows[y * k + u] * cosines[v * n + y]).sum();
+        }
+    }
+    coefficients
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::OxenError;
+    use crate::test;
+    use crate::util;
+
+    #[test]
+    fn test_perceptual_hash_resized_image_is_close() -> Result<(), OxenError> {
+        test::run_empty_dir_test(|dir| {
+            let original = test::test_img_file_with_name("cat_1.jpg");
+            let resized = dir.join("cat_small.png");
+            util::image::resize_and_save(&original, &resized, 128)?;
+
+            let a = util::image::perceptual_hash(&original)?;
+            let b = util::image::perceptual_hash(&resized)?;
+            assert!(util::image::hamming_distance(a, b) <= 8);
+
+            let other = util::image::perceptual_hash(test::test_img_file_with_name("mnist_7.png"))?;
+            assert!(util::image::hamming_distance(a, other) > 8);
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_image_diff_and_composite() -> Result<(), OxenError> {
+        test::run_empty_dir_test(|dir| {
+            let base = test::test_img_file_with_name("cat_rgba.png");
+            let img = image::open(&base)?;
+            let mut edited = img.to_rgba8();
+            // Paint a 10x10 black square in the corner
+            for x in 0..10 {
+                for y in 0..10 {
+                    edited.put_pixel(x, y, image::Rgba([0, 0, 0, 255]));
+                }
+            }
+            let head = dir.join("edited.png");
+            edited.save(&head)?;
+
+            let same = util::image::diff(&base, &base)?;
+            assert!(!same.has_changes());
+
+            let diff = util::image::diff(&base, &head)?;
+            assert!(!diff.dimensions_changed());
+            assert!(diff.image.num_changed_pixels > 0);
+            assert!(diff.image.num_changed_pixels <= 100);
+            assert!(diff.image.mean_abs_delta > 0.0);
+
+            let composite = dir.join("composite.png");
+            util::image::write_diff_composite(&base, &head, &composite)?;
+            let composite = image::open(&composite)?;
+            assert_eq!(composite.width(), img.width() * 3);
+            assert_eq!(composite.height(), img.height());
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_hamming_distance() {
+        assert_eq!(util::image::hamming_distance(0, 0), 0);
+        assert_eq!(util::image::hamming_distance(0b1011, 0b0001), 2);
+        assert_eq!(util::image::hamming_distance(u64::MAX, 0), 64);
+    }
+}
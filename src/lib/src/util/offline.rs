@@ -0,0 +1,76 @@
+//! # Offline mode
+//!
+//! Guarantees no network calls are made, for machines like training clusters that can't
+//! reach a remote. Turned on for one command with `oxen --offline`, for the process with
+//! the `OXEN_OFFLINE` env var, or for the machine with `oxen config --offline-mode true`.
+//!
+//! Every client from [api::client](crate::api::client) checks it, so anything that would
+//! talk to a remote fails right away with [OxenError::Offline] instead of waiting on a
+//! connection that never comes. Local commands like add, commit, df and diff work as usual.
+//!
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::config::AuthConfig;
+use crate::error::OxenError;
+
+pub const OFFLINE_ENV: &str = "OXEN_OFFLINE";
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Turn offline mode on or off for the rest of the process, on top of the env var and
+/// the config
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+pub fn is_offline() -> bool {
+    if OFFLINE.load(Ordering::Relaxed) {
+        return true;
+    }
+    if let Ok(value) = std::env::var(OFFLINE_ENV) {
+        return is_set(&value);
+    }
+    AuthConfig::get()
+        .map(|config| config.offline)
+        .unwrap_or(false)
+}
+
+/// Errors if offline mode is on, call before anything that goes over the network
+pub fn ensure_online(url: impl AsRef<str>) -> Result<(), OxenError> {
+    if is_offline() {
+        return Err(OxenError::offline(url));
+    }
+    Ok(())
+}
+
+// The env var turns offline mode off again with 0 or false
+fn is_set(value: &str) -> bool {
+    !matches!(
+        value.trim().to_lowercase().as_str(),
+        "" | "0" | "false" | "no" | "off"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::AuthConfig;
+    use crate::error::OxenError;
+    use crate::test;
+    use crate::util::offline;
+
+    #[test]
+    fn test_offline_env_values_and_config_default() -> Result<(), OxenError> {
+        for value in ["1", "true", "YES", "on"] {
+            assert!(offline::is_set(value));
+        }
+        for value in ["", "0", "false", "Off"] {
+            assert!(!offline::is_set(value));
+        }
+
+        // Configs written before offline mode existed stay online
+        let config = AuthConfig::new(&test::auth_cfg_file());
+        assert!(!config.offline);
+        Ok(())
+    }
+}
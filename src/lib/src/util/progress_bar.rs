@@ -1,7 +1,9 @@
 use std::sync::Arc;
 use tokio::time::Duration;
 
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+
+use crate::core::events;
 
 pub enum ProgressBarType {
     Counter,
@@ -14,6 +16,7 @@ pub fn spinner_with_msg(msg: impl AsRef<str>) -> ProgressBar {
     spinner.set_message(msg.as_ref().to_owned());
     spinner.set_style(ProgressStyle::default_spinner());
     spinner.enable_steady_tick(Duration::from_millis(100));
+    hide_if_sink(&spinner);
     spinner
 }
 
@@ -25,6 +28,7 @@ pub fn oxen_progress_bar(size: u64, progress_type: ProgressBarType) -> Arc<Progr
             .unwrap()
             .progress_chars("🌾🐂➖"),
     );
+    hide_if_sink(&bar);
     bar
 }
 
@@ -39,6 +43,7 @@ pub fn oxen_progress_bar_indeterminate(
             .unwrap()
             .progress_chars("🌾🐂➖"),
     );
+    hide_if_sink(&bar);
     bar
 }
 
@@ -51,6 +56,7 @@ pub fn oxen_progress_bar_with_msg(size: u64, msg: impl AsRef<str>) -> Arc<Progre
             .unwrap()
             .progress_chars("🌾🐂➖"),
     );
+    hide_if_sink(&bar);
     bar
 }
 
@@ -62,9 +68,17 @@ pub fn oxify_bar(bar: Arc<ProgressBar>, progress_type: ProgressBarType) -> Arc<P
             .unwrap()
             .progress_chars("🌾🐂➖"),
     );
+    hide_if_sink(&bar);
     bar
 }
 
+/// Embedders with an event sink render their own progress, so don't draw to the terminal
+pub fn hide_if_sink(bar: &ProgressBar) {
+    if events::has_sink() {
+        bar.set_draw_target(ProgressDrawTarget::hidden());
+    }
+}
+
 pub fn progress_type_to_template(progress_type: ProgressBarType) -> String {
     match progress_type {
         ProgressBarType::Counter => {
@@ -2,10 +2,14 @@
 //!
 
 pub mod branch;
+pub mod branch_protection;
+pub mod comment;
 pub mod commit;
 pub mod compare;
+pub mod copy;
 pub mod data_frames;
 pub mod data_type_count;
+pub mod dataset_card;
 pub mod diff;
 pub mod entries;
 pub mod entry_metadata;
@@ -17,18 +21,23 @@ pub mod json_data_frame_view;
 pub mod merge;
 pub mod message;
 pub mod mime_type_count;
+pub mod mirror;
 pub mod namespace;
 pub mod oxen_response;
 pub mod pagination;
+pub mod release;
 pub mod remote_staged_status;
+pub mod repo_freeze;
 pub mod repository;
 pub mod revision;
 pub mod schema;
 pub mod sql_parse_error;
 pub mod status_message;
 pub mod tabular_diff_view;
+pub mod token;
 pub mod tree;
 pub mod version;
+pub mod webhook;
 pub mod workspaces;
 
 pub use crate::view::compare::CompareEntriesResponse;
@@ -51,6 +51,9 @@ pub struct BranchNewFromCommitId {
 #[derive(Deserialize, Serialize, Debug)]
 pub struct BranchUpdate {
     pub commit_id: String,
+    // Lease for a force push: the commit the pusher expects the branch to be at
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_commit_id: Option<String>,
 }
 
 #[derive(Deserialize, Serialize, Debug)]
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+use super::StatusMessage;
+use crate::model::BranchProtection;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BranchProtectionResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub rule: BranchProtection,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ListBranchProtectionResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub rules: Vec<BranchProtection>,
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+use super::StatusMessage;
+use crate::model::{Comment, CommentThread};
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CommentResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub comment: Comment,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ListCommentsResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub comments: Vec<Comment>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ListCommentThreadsResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub threads: Vec<CommentThread>,
+}
+
+/// Body used to edit a comment
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdateCommentBody {
+    pub body: String,
+}
@@ -1,4 +1,4 @@
-use crate::model::{Commit, CommitStats};
+use crate::model::{Commit, CommitStateEntry, CommitStats};
 use serde::{Deserialize, Serialize};
 
 use super::{Pagination, StatusMessage};
@@ -38,6 +38,13 @@ pub struct ListCommitResponse {
     pub commits: Vec<Commit>,
 }
 
+#[derive(Deserialize, Serialize, Debug)]
+pub struct CommitStateResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub state: Option<CommitStateEntry>,
+}
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct CommitSyncStatusResponse {
     #[serde(flatten)]
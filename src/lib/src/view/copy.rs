@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::model::OxenUri;
+
+/// Body of a request to copy a file or directory from another repository on the same host
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CopyRequest {
+    pub source: OxenUri,
+    // Branch in the destination repository to commit the copy to
+    pub branch: String,
+    // Path in the destination repository, defaults to the name of the source path
+    #[serde(default)]
+    pub path: PathBuf,
+    pub message: String,
+    pub author: String,
+    pub email: String,
+}
@@ -1,5 +1,9 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::fmt::Display;
+
+use crate::error::OxenError;
+use crate::view::StatusMessage;
 
 pub mod columns;
 
@@ -28,3 +32,71 @@ pub struct DataFrameRowChange {
     pub value: Value,
     pub new_value: Option<Value>,
 }
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RowUpdate {
+    pub row_id: String,
+    pub value: Value,
+}
+
+/// Body to update many rows in one transaction
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct BatchUpdateRowsBody {
+    pub rows: Vec<RowUpdate>,
+}
+
+/// Body to delete many rows in one transaction
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct BatchDeleteRowsBody {
+    pub row_ids: Vec<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RowReviewStatus {
+    Pending,
+    Approved,
+    Rejected,
+}
+
+impl RowReviewStatus {
+    pub fn from_string(s: &str) -> Result<RowReviewStatus, OxenError> {
+        match s {
+            "pending" => Ok(RowReviewStatus::Pending),
+            "approved" => Ok(RowReviewStatus::Approved),
+            "rejected" => Ok(RowReviewStatus::Rejected),
+            _ => Err(OxenError::basic_str(format!("Invalid review status: {s}"))),
+        }
+    }
+}
+
+impl Display for RowReviewStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RowReviewStatus::Pending => write!(f, "pending"),
+            RowReviewStatus::Approved => write!(f, "approved"),
+            RowReviewStatus::Rejected => write!(f, "rejected"),
+        }
+    }
+}
+
+/// The review of a row in a workspace data frame, rows without one are pending
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct RowReview {
+    pub row_id: String,
+    pub status: RowReviewStatus,
+    pub comment: Option<String>,
+}
+
+/// Body to review many rows in one transaction
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RowReviewsBody {
+    pub reviews: Vec<RowReview>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RowReviewsResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub reviews: Vec<RowReview>,
+}
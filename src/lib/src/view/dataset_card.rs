@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+use super::StatusMessage;
+use crate::model::DatasetCard;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DatasetCardResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub dataset_card: DatasetCard,
+}
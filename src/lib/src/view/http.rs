@@ -9,6 +9,11 @@ pub const MSG_RESOURCE_NOT_FOUND: &str = "resource_not_found";
 pub const MSG_CONFLICT: &str = "conflict";
 pub const MSG_CONTENT_IS_INVALID: &str = "content_is_invalid";
 pub const MSG_BAD_REQUEST: &str = "bad_request";
+pub const MSG_FORBIDDEN: &str = "forbidden";
+pub const MSG_REPO_FROZEN: &str = "repo_frozen";
+pub const MSG_BRANCH_PROTECTED: &str = "branch_protected";
+pub const MSG_QUOTA_EXCEEDED: &str = "quota_exceeded";
+pub const MSG_PUSH_REJECTED: &str = "push_rejected";
 pub const MSG_RESOURCE_ALREADY_EXISTS: &str = "resource_already_exists";
 pub const MSG_RESOURCE_IS_PROCESSING: &str = "resource_is_processing";
 pub const MSG_FAILED_PROCESS: &str = "failed_process";
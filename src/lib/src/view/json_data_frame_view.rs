@@ -72,6 +72,14 @@ pub struct JsonDataFrameRowResponse {
     pub row_index: Option<usize>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct JsonDataFrameRowsResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub diff: Option<Vec<DataFrameRowChange>>,
+    pub data_frame: JsonDataFrameViews,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct BatchUpdateResponse {
     pub row_id: String,
@@ -275,7 +283,98 @@ impl JsonDataFrameView {
     }
 }
 
+#[cfg(feature = "html")]
+impl JsonDataFrameView {
+    /// Render the page of data as an HTML table for notebooks and the hub.
+    ///
+    /// Like the CLI pretty printer, the header has the column names and dtypes and the
+    /// caption has the shape of the full data frame. The pagination is written as data
+    /// attributes on a trailing `<nav>` so the caller can wire up its own controls.
+    pub fn to_html(&self) -> String {
+        let mut html = String::from("<div class=\"oxen-df\">\n<table>\n");
+        html.push_str(&format!(
+            "<caption>shape: ({}, {})</caption>\n<thead>\n<tr>",
+            self.size.height, self.size.width
+        ));
+        for field in self.schema.fields.iter() {
+            html.push_str(&format!("<th>{}</th>", escape_html(&field.name)));
+        }
+        html.push_str("</tr>\n<tr class=\"oxen-df-dtypes\">");
+        for field in self.schema.fields.iter() {
+            html.push_str(&format!("<th>{}</th>", escape_html(&field.dtype)));
+        }
+        html.push_str("</tr>\n</thead>\n<tbody>\n");
+
+        if let serde_json::Value::Array(rows) = &self.data {
+            for row in rows.iter() {
+                html.push_str("<tr>");
+                for field in self.schema.fields.iter() {
+                    let value = row.get(&field.name).unwrap_or(&serde_json::Value::Null);
+                    html.push_str(&format!("<td>{}</td>", escape_html(&html_value(value))));
+                }
+                html.push_str("</tr>\n");
+            }
+        }
+        html.push_str("</tbody>\n</table>\n");
+
+        let pagination = &self.pagination;
+        let prev_page = if pagination.page_number > 1 {
+            format!(" data-prev-page=\"{}\"", pagination.page_number - 1)
+        } else {
+            String::new()
+        };
+        let next_page = if pagination.page_number < pagination.total_pages {
+            format!(" data-next-page=\"{}\"", pagination.page_number + 1)
+        } else {
+            String::new()
+        };
+        html.push_str(&format!(
+            "<nav class=\"oxen-df-pagination\" data-page-number=\"{}\" data-page-size=\"{}\" data-total-pages=\"{}\" data-total-entries=\"{}\"{}{}>page {} of {} ({} rows)</nav>\n</div>",
+            pagination.page_number,
+            pagination.page_size,
+            pagination.total_pages,
+            pagination.total_entries,
+            prev_page,
+            next_page,
+            pagination.page_number,
+            pagination.total_pages,
+            pagination.total_entries
+        ));
+        html
+    }
+}
+
+#[cfg(feature = "html")]
+fn html_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => String::from("null"),
+        serde_json::Value::String(s) => s.to_owned(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(feature = "html")]
+fn escape_html(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 impl JsonDataFrameViews {
+    #[cfg(feature = "html")]
+    pub fn to_html(&self) -> String {
+        self.view.to_html()
+    }
+
     pub fn from_df_and_opts(df: DataFrame, og_schema: Schema, opts: &DFOpts) -> JsonDataFrameViews {
         let source = DataFrameSchemaSize::from_df(&df, &og_schema);
         let view = JsonDataFrameView::from_df_opts(df, og_schema, opts);
@@ -344,3 +443,35 @@ fn cast_binary_to_string_with_fallback(series: &Column, out: &str) -> Column {
         Column::new(series.name().clone(), vec)
     }
 }
+
+#[cfg(all(test, feature = "html"))]
+mod tests {
+    use polars::prelude::*;
+
+    use crate::model::Schema;
+    use crate::opts::DFOpts;
+    use crate::view::JsonDataFrameView;
+
+    #[test]
+    fn test_json_data_frame_view_to_html() {
+        let df = df!(
+            "file" => &["a.jpg", "<b>.jpg", "c.jpg"],
+            "label" => &[Some("cat"), None, Some("dog")]
+        )
+        .unwrap();
+        let schema = Schema::from_polars(&df.schema());
+        let mut opts = DFOpts::empty();
+        opts.page_size = Some(2);
+        opts.page = Some(1);
+        let view = JsonDataFrameView::from_df_opts(df, schema, &opts);
+
+        let html = view.to_html();
+        assert!(html.contains("<caption>shape: (3, 2)</caption>"));
+        assert!(html.contains("<th>file</th><th>label</th>"));
+        assert!(html.contains("<td>&lt;b&gt;.jpg</td><td>null</td>"));
+        assert!(!html.contains("c.jpg"));
+        assert!(html.contains("data-total-pages=\"2\""));
+        assert!(html.contains("data-next-page=\"2\""));
+        assert!(!html.contains("data-prev-page"));
+    }
+}
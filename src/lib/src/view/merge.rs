@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-use crate::model::Commit;
+use crate::model::{Commit, MergeQueueEntry, MergeRequest};
 
 use super::StatusMessage;
 
@@ -31,3 +31,31 @@ pub struct MergeSuccessResponse {
     pub head_commit: String,
     pub base_commit: String,
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MergeQueueEntryResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub entry: MergeQueueEntry,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ListMergeQueueResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub entries: Vec<MergeQueueEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MergeRequestResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub merge_request: MergeRequest,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ListMergeRequestsResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub merge_requests: Vec<MergeRequest>,
+}
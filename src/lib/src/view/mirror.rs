@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+use super::StatusMessage;
+use crate::model::Mirror;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MirrorResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub mirror: Mirror,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ListMirrorsResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub mirrors: Vec<Mirror>,
+}
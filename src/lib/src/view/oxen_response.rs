@@ -7,6 +7,8 @@ pub struct OxenResponse {
     pub status_message: String,
     pub status_description: Option<String>,
     pub error: Option<ErrorResponse>,
+    /// Stable error code, see OxenError::code. Older servers do not send it.
+    pub code: Option<String>,
 }
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ErrorResponse {
@@ -31,6 +33,19 @@ impl OxenResponse {
         }
     }
 
+    pub fn error_detail(&self) -> Option<String> {
+        self.error.as_ref().and_then(|err| err.detail.clone())
+    }
+
+    /// The error code, falling back to the error type for servers that don't send codes
+    pub fn error_code(&self) -> String {
+        match (&self.code, &self.error) {
+            (Some(code), _) => code.to_owned(),
+            (None, Some(err)) => err.error_type.to_owned(),
+            (None, None) => String::from("unknown"),
+        }
+    }
+
     pub fn error_or_msg(&self) -> String {
         match self.error.to_owned() {
             Some(err) => err.title,
@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+use super::StatusMessage;
+use crate::model::Release;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReleaseResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub release: Release,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ListReleasesResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub releases: Vec<Release>,
+}
+
+/// Body used to edit the description of a release
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdateReleaseBody {
+    pub description: Option<String>,
+}
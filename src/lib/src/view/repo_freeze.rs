@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+use super::StatusMessage;
+use crate::model::RepoFreeze;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RepoFreezeResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    // None if the repository is not frozen
+    pub freeze: Option<RepoFreeze>,
+}
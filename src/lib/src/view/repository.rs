@@ -1,4 +1,4 @@
-use crate::model::{Commit, EntryDataType, RemoteRepository};
+use crate::model::{Commit, EntryDataType, RemoteRepository, StorageStats};
 use serde::{Deserialize, Serialize};
 
 use super::{DataTypeCount, StatusMessage};
@@ -79,6 +79,13 @@ pub struct RepositoryStatsResponse {
     pub repository: RepositoryStatsView,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StorageStatsResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub storage_stats: StorageStats,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct DataTypeView {
     pub data_type: EntryDataType,
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use super::entries::ResourceVersion;
+use crate::model::metadata::ColumnStats;
 use crate::model::{Commit, Schema};
 
 use super::StatusMessage;
@@ -10,11 +11,17 @@ pub struct SchemaWithPath {
     pub path: String,
     #[serde(flatten)]
     pub schema: Schema,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column_stats: Option<Vec<ColumnStats>>,
 }
 
 impl SchemaWithPath {
     pub fn new(path: String, schema: Schema) -> SchemaWithPath {
-        SchemaWithPath { path, schema }
+        SchemaWithPath {
+            path,
+            schema,
+            column_stats: None,
+        }
     }
 }
 
@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+use super::StatusMessage;
+
+/// Ask the server for a new token derived from the one on the request. The new token
+/// can only do less than the one it was minted with.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct NewToken {
+    /// ex: `read-only`, `workspace-only` or scopes the current token has
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    /// Namespaces or `namespace/repo_name`s the token is limited to
+    #[serde(default)]
+    pub resources: Vec<String>,
+    /// Unix timestamp in seconds after which the token is rejected
+    pub expires_at: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TokenResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub token: String,
+    pub scopes: Vec<String>,
+    pub resources: Vec<String>,
+    pub expires_at: Option<i64>,
+}
@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+use super::StatusMessage;
+use crate::model::Webhook;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct WebhookResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub webhook: Webhook,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ListWebhooksResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub webhooks: Vec<Webhook>,
+}
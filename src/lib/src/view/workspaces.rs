@@ -1,9 +1,11 @@
 use serde::{Deserialize, Serialize};
 
+use std::path::PathBuf;
 use time::OffsetDateTime;
 
-use super::StatusMessage;
-use crate::model::Commit;
+use super::{JsonDataFrameViews, StatusMessage};
+use crate::model::diff::AddRemoveModifyCounts;
+use crate::model::{Commit, StagedEntryStatus};
 
 #[derive(Deserialize, Serialize, Debug)]
 pub struct NewWorkspace {
@@ -29,6 +31,7 @@ impl From<WorkspaceCommit> for Commit {
     fn from(val: WorkspaceCommit) -> Self {
         Commit {
             id: val.id,
+            co_authors: Commit::parse_co_authors(&val.message),
             message: val.message,
             author: val.author,
             email: val.email,
@@ -58,3 +61,47 @@ pub struct ListWorkspaceResponseView {
     pub status: StatusMessage,
     pub workspaces: Vec<WorkspaceResponse>,
 }
+
+/// A file that differs between a workspace and the commit it was created from
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct WorkspaceFileChange {
+    pub path: PathBuf,
+    pub status: StagedEntryStatus,
+}
+
+/// Rows that were added, modified or removed in a workspace data frame
+#[derive(Deserialize, Serialize, Debug)]
+pub struct WorkspaceDataFrameDiff {
+    pub counts: AddRemoveModifyCounts,
+    pub rows: JsonDataFrameViews,
+}
+
+/// What committing the workspace would change under a path
+#[derive(Deserialize, Serialize, Debug)]
+pub struct WorkspaceDiff {
+    pub path: PathBuf,
+    pub files: Vec<WorkspaceFileChange>,
+    pub data_frame: Option<WorkspaceDataFrameDiff>,
+}
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct WorkspaceDiffResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub diff: WorkspaceDiff,
+}
+
+/// Sent once every part of a chunked upload is on the server, to assemble the file
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct CompleteUploadBody {
+    pub file_name: String,
+    pub total_chunks: usize,
+    // Hash of the whole file, checked after the parts are combined
+    pub hash: String,
+}
+
+/// Where to move a committed file in the workspace
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct MoveFileBody {
+    pub new_path: PathBuf,
+}
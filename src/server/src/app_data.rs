@@ -1,15 +1,25 @@
 use std::path::PathBuf;
 
+use crate::auth::access_keys::AccessKeysHandle;
+use crate::config::ServerConfigHandle;
 use crate::queues::TaskQueue;
 
 pub struct OxenAppData {
     pub path: PathBuf,
     pub queue: TaskQueue,
+    pub config: ServerConfigHandle,
+    pub keys: AccessKeysHandle,
 }
 
 impl OxenAppData {
     pub fn new(path: PathBuf, queue: TaskQueue) -> OxenAppData {
-        OxenAppData { path, queue }
+        let config = ServerConfigHandle::load(&path);
+        OxenAppData {
+            path,
+            queue,
+            config,
+            keys: AccessKeysHandle::default(),
+        }
     }
 }
 
@@ -18,6 +28,8 @@ impl Clone for OxenAppData {
         OxenAppData {
             path: self.path.clone(),
             queue: self.queue.clone(),
+            config: self.config.clone(),
+            keys: self.keys.clone(),
         }
     }
 }
@@ -1,2 +1,4 @@
 pub mod access_keys;
+pub mod roles;
+pub mod scopes;
 pub mod validator;
@@ -2,19 +2,71 @@ use liboxen::error::OxenError;
 use liboxen::model::User;
 use liboxen::util;
 
+use crate::auth::scopes;
+
 use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use rocksdb::{DBWithThreadMode, LogLevel, MultiThreaded, Options};
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::str;
+use std::sync::{Arc, Mutex};
 
 pub const SECRET_KEY_FILENAME: &str = "SECRET_KEY_BASE";
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct JWTClaim {
     id: String,
     name: String,
     email: String,
+    // Tokens created before scopes existed do not have any
+    #[serde(default)]
+    scopes: Vec<String>,
+    // Namespaces or namespace/repo pairs the token is limited to, empty means all of them
+    #[serde(default)]
+    resources: Vec<String>,
+    // Unix timestamp the token expires at, tokens without one never expire
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    exp: Option<i64>,
+}
+
+impl JWTClaim {
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+
+    pub fn scopes(&self) -> &[String] {
+        &self.scopes
+    }
+
+    pub fn resources(&self) -> &[String] {
+        &self.resources
+    }
+
+    pub fn expires_at(&self) -> Option<i64> {
+        self.exp
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.exp
+            .is_some_and(|exp| exp <= time::OffsetDateTime::now_utc().unix_timestamp())
+    }
+
+    /// Whether the token may be used on the namespace, or on the repo within it
+    pub fn can_access(&self, namespace: &str, repo_name: Option<&str>) -> bool {
+        if self.resources.is_empty() {
+            return true;
+        }
+        self.resources
+            .iter()
+            .any(|resource| match resource.split_once('/') {
+                Some((ns, name)) => ns == namespace && repo_name == Some(name),
+                None => resource == namespace,
+            })
+    }
 }
 
 pub struct AccessKeyManager {
@@ -22,6 +74,25 @@ pub struct AccessKeyManager {
     db: DBWithThreadMode<MultiThreaded>,
 }
 
+/// The keys db opened for writing once and shared by every worker, instead of opening it
+/// on each request that writes a token. It is opened the first time it is needed.
+#[derive(Clone, Default)]
+pub struct AccessKeysHandle {
+    manager: Arc<Mutex<Option<Arc<AccessKeyManager>>>>,
+}
+
+impl AccessKeysHandle {
+    pub fn get(&self, sync_dir: &Path) -> Result<Arc<AccessKeyManager>, OxenError> {
+        let mut manager = self.manager.lock().unwrap();
+        if let Some(manager) = manager.as_ref() {
+            return Ok(manager.clone());
+        }
+        let opened = Arc::new(AccessKeyManager::new(sync_dir)?);
+        *manager = Some(opened.clone());
+        Ok(opened)
+    }
+}
+
 impl AccessKeyManager {
     fn secret_key_path(sync_dir: &Path) -> PathBuf {
         let hidden_dir = util::fs::oxen_hidden_dir(sync_dir);
@@ -71,12 +142,104 @@ impl AccessKeyManager {
     }
 
     pub fn create(&self, user: &User) -> Result<(User, String), OxenError> {
+        self.create_with_scopes(user, &[], &[])
+    }
+
+    /// Create a token granted `scopes`, and limited to `resources` if there are any.
+    /// A resource is a namespace or a `namespace/repo_name`.
+    pub fn create_with_scopes(
+        &self,
+        user: &User,
+        scopes: &[String],
+        resources: &[String],
+    ) -> Result<(User, String), OxenError> {
         let user_claims = JWTClaim {
             id: format!("{}", uuid::Uuid::new_v4()),
             name: user.name.to_owned(),
             email: user.email.to_owned(),
+            scopes: scopes.to_vec(),
+            resources: resources.to_vec(),
+            exp: None,
         };
+        let (claim, token) = self.create_for_claim(user_claims)?;
+        Ok((
+            User {
+                name: claim.name,
+                email: claim.email,
+            },
+            token,
+        ))
+    }
 
+    /// Mint a token from the `parent` token on a request. The new token keeps the
+    /// restrictions of the parent, and can only ask for scopes the parent has, resources
+    /// the parent can access and an expiry no later than the parent's.
+    pub fn mint(
+        &self,
+        parent: &JWTClaim,
+        scopes: &[String],
+        resources: &[String],
+        expires_at: Option<i64>,
+    ) -> Result<(JWTClaim, String), OxenError> {
+        let mut new_scopes: Vec<String> = parent
+            .scopes
+            .iter()
+            .filter(|scope| scopes::RESTRICTIONS.contains(&scope.as_str()))
+            .cloned()
+            .collect();
+        for scope in scopes {
+            if !scopes::RESTRICTIONS.contains(&scope.as_str()) && !parent.has_scope(scope) {
+                return Err(OxenError::basic_str(format!(
+                    "Cannot grant scope '{scope}' the current token does not have"
+                )));
+            }
+            if !new_scopes.contains(scope) {
+                new_scopes.push(scope.to_owned());
+            }
+        }
+
+        let new_resources = if resources.is_empty() {
+            parent.resources.clone()
+        } else {
+            for resource in resources {
+                let (namespace, repo_name) = match resource.split_once('/') {
+                    Some((namespace, repo_name)) => (namespace, Some(repo_name)),
+                    None => (resource.as_str(), None),
+                };
+                if !parent.can_access(namespace, repo_name) {
+                    return Err(OxenError::basic_str(format!(
+                        "The current token cannot be used on {resource}"
+                    )));
+                }
+            }
+            resources.to_vec()
+        };
+
+        let now = time::OffsetDateTime::now_utc().unix_timestamp();
+        let exp = match (expires_at, parent.exp) {
+            (Some(exp), _) if exp <= now => {
+                return Err(OxenError::basic_str("Token expiry must be in the future"));
+            }
+            (Some(exp), Some(parent_exp)) if exp > parent_exp => {
+                return Err(OxenError::basic_str(
+                    "Token cannot outlive the token it was minted with",
+                ));
+            }
+            (Some(exp), _) => Some(exp),
+            (None, parent_exp) => parent_exp,
+        };
+
+        self.create_for_claim(JWTClaim {
+            id: format!("{}", uuid::Uuid::new_v4()),
+            name: parent.name.to_owned(),
+            email: parent.email.to_owned(),
+            scopes: new_scopes,
+            resources: new_resources,
+            exp,
+        })
+    }
+
+    fn create_for_claim(&self, user_claims: JWTClaim) -> Result<(JWTClaim, String), OxenError> {
         let secret_key = self.read_secret_key()?;
         match encode(
             &Header::default(),
@@ -91,13 +254,7 @@ impl AccessKeyManager {
                 // if they have someone elses token, we can block also (but how likely is this...? maybe sniffing traffic?)
                 let encoded_claim = serde_json::to_string(&user_claims)?;
                 self.db.put(&token, encoded_claim)?;
-                Ok((
-                    User {
-                        name: user_claims.name.to_owned(),
-                        email: user_claims.email.to_owned(),
-                    },
-                    token,
-                ))
+                Ok((user_claims, token))
             }
             Err(_) => {
                 let err = format!("Could not create access key for: {user_claims:?}");
@@ -123,32 +280,38 @@ impl AccessKeyManager {
     }
 
     pub fn token_is_valid(&self, token: &str) -> bool {
+        self.get_valid_claim(token).is_some()
+    }
+
+    /// Returns the claim for the token if it is valid, so that callers can check its scopes
+    pub fn get_valid_claim(&self, token: &str) -> Option<JWTClaim> {
         match self.get_claim(token) {
+            Ok(Some(claim)) if claim.is_expired() => {
+                log::info!("auth token is expired: {}", token);
+                None
+            }
             Ok(Some(claim)) => {
-                let secret = self.read_secret_key();
-                if secret.is_err() {
-                    return false;
-                }
+                let Ok(secret) = self.read_secret_key() else {
+                    return None;
+                };
 
                 let mut validator = Validation::new(Algorithm::HS256);
                 validator.set_required_spec_claims(&["email"]);
                 match decode::<JWTClaim>(
                     token,
-                    &DecodingKey::from_secret(secret.unwrap().as_ref()),
+                    &DecodingKey::from_secret(secret.as_ref()),
                     &validator,
                 ) {
-                    Ok(token_data) => {
-                        // Make sure we decoded the email is the one in our db
-                        token_data.claims == claim
-                    }
+                    // Make sure we decoded the email is the one in our db
+                    Ok(token_data) if token_data.claims == claim => Some(claim),
                     _ => {
                         log::info!("auth token is not valid: {}", token);
-                        false
+                        None
                     }
                 }
             }
-            Ok(None) => false,
-            Err(_) => false,
+            Ok(None) => None,
+            Err(_) => None,
         }
     }
 
@@ -160,8 +323,9 @@ impl AccessKeyManager {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
 
-    use crate::auth::access_keys::AccessKeyManager;
+    use crate::auth::access_keys::{AccessKeyManager, AccessKeysHandle};
     use crate::test;
     use liboxen::error::OxenError;
     use liboxen::model::User;
@@ -175,6 +339,24 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_keys_handle_opens_the_db_once() -> Result<(), OxenError> {
+        test::run_empty_sync_dir_test(|sync_dir| {
+            let handle = AccessKeysHandle::default();
+            let keygen = handle.get(sync_dir)?;
+            assert!(Arc::ptr_eq(&keygen, &handle.clone().get(sync_dir)?));
+
+            let new_user = User {
+                name: String::from("Ox"),
+                email: String::from("ox@oxen.ai"),
+            };
+            let (_user, token) = keygen.create(&new_user)?;
+            assert!(handle.get(sync_dir)?.token_is_valid(&token));
+
+            Ok(())
+        })
+    }
+
     #[test]
     fn test_generate_key() -> Result<(), OxenError> {
         test::run_empty_sync_dir_test(|sync_dir| {
@@ -235,4 +417,111 @@ mod tests {
             Ok(())
         })
     }
+
+    #[test]
+    fn test_generate_key_with_scopes() -> Result<(), OxenError> {
+        test::run_empty_sync_dir_test(|sync_dir| {
+            let keygen = AccessKeyManager::new(sync_dir)?;
+            let new_user = User {
+                name: String::from("Ox"),
+                email: String::from("ox@oxen.ai"),
+            };
+            let scopes = vec![String::from("commits:state")];
+            let (_user, token) = keygen.create_with_scopes(&new_user, &scopes, &[])?;
+            let claim = keygen.get_valid_claim(&token).unwrap();
+            assert!(claim.has_scope("commits:state"));
+            assert!(!claim.has_scope("repos:admin"));
+
+            let (_user, token) = keygen.create(&new_user)?;
+            let claim = keygen.get_valid_claim(&token).unwrap();
+            assert!(!claim.has_scope("commits:state"));
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_generate_key_with_resources() -> Result<(), OxenError> {
+        test::run_empty_sync_dir_test(|sync_dir| {
+            let keygen = AccessKeyManager::new(sync_dir)?;
+            let new_user = User {
+                name: String::from("Ox"),
+                email: String::from("ox@oxen.ai"),
+            };
+            let resources = vec![String::from("ox"), String::from("beta/datasets")];
+            let (_user, token) = keygen.create_with_scopes(&new_user, &[], &resources)?;
+            let claim = keygen.get_valid_claim(&token).unwrap();
+            assert!(claim.can_access("ox", None));
+            assert!(claim.can_access("ox", Some("anything")));
+            assert!(claim.can_access("beta", Some("datasets")));
+            assert!(!claim.can_access("beta", Some("models")));
+            assert!(!claim.can_access("beta", None));
+
+            // No resources means the token works everywhere
+            let (_user, token) = keygen.create(&new_user)?;
+            let claim = keygen.get_valid_claim(&token).unwrap();
+            assert!(claim.can_access("beta", Some("models")));
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_mint_narrower_token() -> Result<(), OxenError> {
+        test::run_empty_sync_dir_test(|sync_dir| {
+            let keygen = AccessKeyManager::new(sync_dir)?;
+            let new_user = User {
+                name: String::from("Ox"),
+                email: String::from("ox@oxen.ai"),
+            };
+            let scopes = vec![String::from("commits:state")];
+            let (_user, token) = keygen.create_with_scopes(&new_user, &scopes, &[])?;
+            let parent = keygen.get_valid_claim(&token).unwrap();
+
+            let an_hour = time::OffsetDateTime::now_utc().unix_timestamp() + 60 * 60;
+            let (claim, token) = keygen.mint(
+                &parent,
+                &[String::from("read-only")],
+                &[String::from("ox/datasets")],
+                Some(an_hour),
+            )?;
+            let fetched = keygen.get_valid_claim(&token).unwrap();
+            assert_eq!(claim, fetched);
+            assert!(fetched.has_scope("read-only"));
+            assert!(!fetched.has_scope("commits:state"));
+            assert!(fetched.can_access("ox", Some("datasets")));
+            assert!(!fetched.can_access("ox", Some("models")));
+            assert_eq!(fetched.expires_at(), Some(an_hour));
+
+            // A minted token cannot get more than its parent
+            let result = keygen.mint(&fetched, &[String::from("commits:state")], &[], None);
+            assert!(result.is_err());
+            let result = keygen.mint(&fetched, &[], &[String::from("ox")], None);
+            assert!(result.is_err());
+            let result = keygen.mint(&fetched, &[], &[], Some(an_hour + 1));
+            assert!(result.is_err());
+            // Restrictions are kept
+            let (claim, _token) = keygen.mint(&fetched, &[], &[], None)?;
+            assert!(claim.has_scope("read-only"));
+            assert_eq!(claim.expires_at(), Some(an_hour));
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_expired_token_is_invalid() -> Result<(), OxenError> {
+        test::run_empty_sync_dir_test(|sync_dir| {
+            let keygen = AccessKeyManager::new(sync_dir)?;
+            let new_user = User {
+                name: String::from("Ox"),
+                email: String::from("ox@oxen.ai"),
+            };
+            let (_user, token) = keygen.create(&new_user)?;
+            let parent = keygen.get_valid_claim(&token).unwrap();
+            let (mut claim, _token) = keygen.mint(&parent, &[], &[], None)?;
+            // Expire the claim by hand, minting refuses expiries in the past
+            claim.exp = Some(time::OffsetDateTime::now_utc().unix_timestamp() - 1);
+            let (_claim, token) = keygen.create_for_claim(claim)?;
+            assert!(!keygen.token_is_valid(&token));
+            Ok(())
+        })
+    }
 }
@@ -0,0 +1,392 @@
+//! Role based access control for namespaces and repositories.
+//!
+//! Grants live in `.oxen/roles.json` in the sync dir. A grant gives a user (by the email
+//! on their token) a read, write or admin role on a whole namespace, or on one repository
+//! in it. A user gets the highest role of the grants that apply.
+//!
+//! As long as there are no grants at all every valid token can do everything, like before
+//! roles existed, except freezing and thawing repositories which always need an admin.
+//! Tokens with the `roles:admin` scope always have admin access.
+
+use actix_web::http::Method;
+use actix_web::{HttpMessage, HttpRequest};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use liboxen::error::OxenError;
+use liboxen::util;
+
+use crate::app_data::OxenAppData;
+use crate::auth::access_keys::JWTClaim;
+use crate::auth::scopes;
+use crate::errors::OxenHttpError;
+
+pub const ROLES_FILENAME: &str = "roles.json";
+
+/// Roles are ordered, each one can do everything the ones before it can
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Read,
+    Write,
+    Admin,
+}
+
+impl std::fmt::Display for Role {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Role::Read => write!(f, "read"),
+            Role::Write => write!(f, "write"),
+            Role::Admin => write!(f, "admin"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RoleGrant {
+    pub email: String,
+    pub namespace: String,
+    /// The grant covers the whole namespace when there is no repo
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo_name: Option<String>,
+    pub role: Role,
+}
+
+impl RoleGrant {
+    fn is_same_target(&self, other: &RoleGrant) -> bool {
+        self.email == other.email
+            && self.namespace == other.namespace
+            && self.repo_name == other.repo_name
+    }
+
+    fn applies_to(&self, email: &str, namespace: &str, repo_name: Option<&str>) -> bool {
+        self.email == email
+            && self.namespace == namespace
+            && (self.repo_name.is_none() || self.repo_name.as_deref() == repo_name)
+    }
+}
+
+fn roles_path(sync_dir: &Path) -> PathBuf {
+    util::fs::oxen_hidden_dir(sync_dir).join(ROLES_FILENAME)
+}
+
+pub fn list(sync_dir: &Path) -> Result<Vec<RoleGrant>, OxenError> {
+    let path = roles_path(sync_dir);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    Ok(serde_json::from_str(&util::fs::read_from_path(&path)?)?)
+}
+
+/// Add the grant, replacing the role of an existing grant for the same user and target
+pub fn grant(sync_dir: &Path, grant: &RoleGrant) -> Result<(), OxenError> {
+    if grant.email.is_empty() || grant.namespace.is_empty() {
+        return Err(OxenError::basic_str(
+            "A role grant needs an email and a namespace",
+        ));
+    }
+    let mut grants = list(sync_dir)?;
+    match grants.iter_mut().find(|g| g.is_same_target(grant)) {
+        Some(existing) => existing.role = grant.role,
+        None => grants.push(grant.clone()),
+    }
+    write(sync_dir, &grants)
+}
+
+/// Remove the grant for the user and target, returning it if there was one
+pub fn revoke(
+    sync_dir: &Path,
+    email: &str,
+    namespace: &str,
+    repo_name: Option<&str>,
+) -> Result<Option<RoleGrant>, OxenError> {
+    let mut grants = list(sync_dir)?;
+    let Some(index) = grants.iter().position(|g| {
+        g.email == email && g.namespace == namespace && g.repo_name.as_deref() == repo_name
+    }) else {
+        return Ok(None);
+    };
+    let grant = grants.remove(index);
+    write(sync_dir, &grants)?;
+    Ok(Some(grant))
+}
+
+fn write(sync_dir: &Path, grants: &[RoleGrant]) -> Result<(), OxenError> {
+    let path = roles_path(sync_dir);
+    util::fs::create_dir_all(util::fs::oxen_hidden_dir(sync_dir))?;
+    util::fs::write_to_path(&path, serde_json::to_string_pretty(grants)?)
+}
+
+/// The highest role the user has on the namespace or repository
+pub fn role_for(
+    grants: &[RoleGrant],
+    email: &str,
+    namespace: &str,
+    repo_name: Option<&str>,
+) -> Option<Role> {
+    grants
+        .iter()
+        .filter(|g| g.applies_to(email, namespace, repo_name))
+        .map(|g| g.role)
+        .max()
+}
+
+/// The role a request to a repository endpoint needs, `path` is relative to the repository.
+/// Reads need read, changes need write, and deleting the repository or changing settings
+/// like webhooks, branch protection and freezing need admin.
+pub fn required_role(method: &Method, path: &str) -> Role {
+    let path = path.trim_end_matches('/');
+    if path.is_empty() && *method == Method::DELETE {
+        return Role::Admin;
+    }
+    let is_settings = ["/webhooks", "/branch_protection", "/freeze"]
+        .iter()
+        .any(|settings| path == *settings || path.starts_with(&format!("{settings}/")));
+    if is_settings {
+        return Role::Admin;
+    }
+    match *method {
+        Method::GET | Method::HEAD | Method::OPTIONS => Role::Read,
+        _ => Role::Write,
+    }
+}
+
+/// Whether the endpoint needs an admin even on servers without any grants. Freezing and
+/// thawing archive or restore the whole repository, so any token should not be able to.
+pub fn always_needs_admin(method: &Method, path: &str) -> bool {
+    let path = path.trim_end_matches('/');
+    let is_read = matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS);
+    path == "/freeze" && !is_read
+}
+
+/// Make sure the user on the request has at least the `required` role on the namespace
+/// or repository, and that their token is not limited to other resources.
+/// If there is no claim on the request, auth is disabled on this server.
+pub fn authorize(
+    req: &HttpRequest,
+    namespace: &str,
+    repo_name: Option<&str>,
+    required: Role,
+) -> Result<(), OxenHttpError> {
+    let app_data = req
+        .app_data::<OxenAppData>()
+        .ok_or(OxenHttpError::AppDataDoesNotExist)?;
+    let claim = req.extensions().get::<JWTClaim>().cloned();
+    authorize_claim(
+        &app_data.path,
+        claim.as_ref(),
+        namespace,
+        repo_name,
+        required,
+    )
+}
+
+/// Make sure the user on the request is an admin of the repository, see [always_needs_admin].
+/// On servers without grants only tokens with the `roles:admin` scope are.
+pub fn authorize_admin(
+    req: &HttpRequest,
+    namespace: &str,
+    repo_name: Option<&str>,
+) -> Result<(), OxenHttpError> {
+    let app_data = req
+        .app_data::<OxenAppData>()
+        .ok_or(OxenHttpError::AppDataDoesNotExist)?;
+    let claim = req.extensions().get::<JWTClaim>().cloned();
+    let open_without_grants = false;
+    check_claim(
+        &app_data.path,
+        claim.as_ref(),
+        namespace,
+        repo_name,
+        Role::Admin,
+        open_without_grants,
+    )
+}
+
+/// Same as [authorize] for the claim of a token that did not come with an HTTP request
+pub fn authorize_claim(
+    sync_dir: &Path,
+    claim: Option<&JWTClaim>,
+    namespace: &str,
+    repo_name: Option<&str>,
+    required: Role,
+) -> Result<(), OxenHttpError> {
+    let open_without_grants = true;
+    check_claim(
+        sync_dir,
+        claim,
+        namespace,
+        repo_name,
+        required,
+        open_without_grants,
+    )
+}
+
+fn check_claim(
+    sync_dir: &Path,
+    claim: Option<&JWTClaim>,
+    namespace: &str,
+    repo_name: Option<&str>,
+    required: Role,
+    open_without_grants: bool,
+) -> Result<(), OxenHttpError> {
+    let Some(claim) = claim else {
+        return Ok(());
+    };
+    let target = match repo_name {
+        Some(name) => format!("{namespace}/{name}"),
+        None => namespace.to_string(),
+    };
+    if !claim.can_access(namespace, repo_name) {
+        return Err(OxenHttpError::Forbidden(
+            format!("Token cannot be used on {target}").into(),
+        ));
+    }
+    if claim.has_scope(scopes::ROLES_ADMIN) {
+        return Ok(());
+    }
+
+    let grants = list(sync_dir)?;
+    if grants.is_empty() && open_without_grants {
+        return Ok(());
+    }
+    match role_for(&grants, claim.email(), namespace, repo_name) {
+        Some(role) if role >= required => Ok(()),
+        _ => Err(OxenHttpError::Forbidden(
+            format!("{} needs the {required} role on {target}", claim.email()).into(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::http::Method;
+
+    use liboxen::error::OxenError;
+    use liboxen::model::User;
+
+    use crate::auth::access_keys::AccessKeyManager;
+    use crate::auth::roles::{self, Role, RoleGrant};
+    use crate::auth::scopes;
+    use crate::errors::OxenHttpError;
+    use crate::test;
+
+    fn grant(email: &str, namespace: &str, repo_name: Option<&str>, role: Role) -> RoleGrant {
+        RoleGrant {
+            email: email.to_string(),
+            namespace: namespace.to_string(),
+            repo_name: repo_name.map(String::from),
+            role,
+        }
+    }
+
+    #[test]
+    fn test_role_for_takes_highest_grant() -> Result<(), OxenError> {
+        test::run_empty_sync_dir_test(|sync_dir| {
+            roles::grant(sync_dir, &grant("ox@oxen.ai", "ox", None, Role::Admin))?;
+            roles::grant(
+                sync_dir,
+                &grant("ox@oxen.ai", "ox", Some("datasets"), Role::Write),
+            )?;
+            // Replaces the role of the existing grant
+            roles::grant(sync_dir, &grant("ox@oxen.ai", "ox", None, Role::Read))?;
+            let grants = roles::list(sync_dir)?;
+            assert_eq!(grants.len(), 2);
+
+            let email = "ox@oxen.ai";
+            assert_eq!(
+                roles::role_for(&grants, email, "ox", Some("datasets")),
+                Some(Role::Write)
+            );
+            assert_eq!(
+                roles::role_for(&grants, email, "ox", Some("models")),
+                Some(Role::Read)
+            );
+            assert_eq!(roles::role_for(&grants, email, "beta", None), None);
+            assert_eq!(roles::role_for(&grants, "bessie@oxen.ai", "ox", None), None);
+
+            let revoked = roles::revoke(sync_dir, email, "ox", Some("datasets"))?;
+            assert!(revoked.is_some());
+            assert!(roles::revoke(sync_dir, email, "ox", Some("datasets"))?.is_none());
+            assert_eq!(roles::list(sync_dir)?.len(), 1);
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_required_role() {
+        assert_eq!(roles::required_role(&Method::GET, "/branches"), Role::Read);
+        assert_eq!(
+            roles::required_role(&Method::PUT, "/branches/main"),
+            Role::Write
+        );
+        assert_eq!(roles::required_role(&Method::GET, "/webhooks"), Role::Admin);
+        assert_eq!(roles::required_role(&Method::GET, ""), Role::Read);
+        assert_eq!(roles::required_role(&Method::DELETE, ""), Role::Admin);
+        assert_eq!(
+            roles::required_role(&Method::DELETE, "/branch_protection/main"),
+            Role::Admin
+        );
+        // Only the first segment counts, files can be named anything
+        assert_eq!(
+            roles::required_role(&Method::GET, "/file/main/freeze"),
+            Role::Read
+        );
+    }
+
+    #[test]
+    fn test_freeze_needs_admin_without_grants() -> Result<(), OxenError> {
+        test::run_empty_sync_dir_test(|sync_dir| {
+            assert!(roles::always_needs_admin(&Method::PUT, "/freeze"));
+            assert!(roles::always_needs_admin(&Method::DELETE, "/freeze/"));
+            assert!(!roles::always_needs_admin(&Method::GET, "/freeze"));
+            assert!(!roles::always_needs_admin(&Method::DELETE, "/webhooks"));
+
+            let keygen = AccessKeyManager::new(sync_dir)?;
+            let user = User {
+                name: String::from("Ox"),
+                email: String::from("ox@oxen.ai"),
+            };
+            let (_, token) = keygen.create(&user)?;
+            let claim = keygen.get_claim(&token)?.unwrap();
+            let admin_scopes = vec![scopes::ROLES_ADMIN.to_string()];
+            let (_, admin_token) = keygen.create_with_scopes(&user, &admin_scopes, &[])?;
+            let admin_claim = keygen.get_claim(&admin_token)?.unwrap();
+
+            // Other admin endpoints stay open until there are grants, freezing does not
+            roles::authorize_claim(sync_dir, Some(&claim), "ox", Some("data"), Role::Admin)
+                .unwrap();
+            let result = roles::check_claim(
+                sync_dir,
+                Some(&claim),
+                "ox",
+                Some("data"),
+                Role::Admin,
+                false,
+            );
+            assert!(matches!(result, Err(OxenHttpError::Forbidden(_))));
+            roles::check_claim(
+                sync_dir,
+                Some(&admin_claim),
+                "ox",
+                Some("data"),
+                Role::Admin,
+                false,
+            )
+            .unwrap();
+
+            // A granted admin can freeze too
+            roles::grant(sync_dir, &grant("ox@oxen.ai", "ox", None, Role::Admin))?;
+            roles::check_claim(
+                sync_dir,
+                Some(&claim),
+                "ox",
+                Some("data"),
+                Role::Admin,
+                false,
+            )
+            .unwrap();
+            Ok(())
+        })
+    }
+}
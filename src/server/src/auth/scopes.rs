@@ -0,0 +1,72 @@
+use crate::auth::access_keys::JWTClaim;
+use crate::errors::OxenHttpError;
+
+use actix_web::http::Method;
+use actix_web::{HttpMessage, HttpRequest};
+
+/// Allows setting the data quality state (approved / quarantined) of commits
+pub const COMMITS_STATE: &str = "commits:state";
+
+/// Allows changing the branch protection rules of repositories
+pub const BRANCHES_PROTECT: &str = "branches:protect";
+
+/// Allows managing the roles of users, and gives admin access to every repository
+pub const ROLES_ADMIN: &str = "roles:admin";
+
+/// Limits the token to reads
+pub const READ_ONLY: &str = "read-only";
+
+/// Limits the token to changing repositories through workspaces
+pub const WORKSPACE_ONLY: &str = "workspace-only";
+
+/// Scopes that take away from what a token can do, instead of adding to it
+pub const RESTRICTIONS: [&str; 2] = [READ_ONLY, WORKSPACE_ONLY];
+
+/// All the scopes a token can be granted
+pub const ALL: [&str; 5] = [
+    COMMITS_STATE,
+    BRANCHES_PROTECT,
+    ROLES_ADMIN,
+    READ_ONLY,
+    WORKSPACE_ONLY,
+];
+
+/// Make sure the token on the request was granted the scope.
+/// If there is no claim on the request, auth is disabled on this server.
+pub fn require(req: &HttpRequest, scope: &str) -> Result<(), OxenHttpError> {
+    match req.extensions().get::<JWTClaim>() {
+        Some(claim) if !claim.has_scope(scope) => Err(OxenHttpError::Forbidden(
+            format!("Token is missing required scope '{scope}'").into(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Make sure the request fits the restrictions of the token, `path` is relative to the
+/// repository. Read only tokens can only read, and workspace only tokens can only make
+/// changes through workspaces.
+pub fn check_restrictions(req: &HttpRequest, path: &str) -> Result<(), OxenHttpError> {
+    let Some(claim) = req.extensions().get::<JWTClaim>().cloned() else {
+        return Ok(());
+    };
+    if matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+        return Ok(());
+    }
+    if claim.has_scope(READ_ONLY) {
+        return Err(OxenHttpError::Forbidden("Token is read only".into()));
+    }
+    let is_workspace = path == "/workspaces" || path.starts_with("/workspaces/");
+    if claim.has_scope(WORKSPACE_ONLY) && !is_workspace {
+        return Err(OxenHttpError::Forbidden(
+            "Token can only make changes through workspaces".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// Email of the user the token on the request belongs to, None if auth is disabled
+pub fn user_email(req: &HttpRequest) -> Option<String> {
+    req.extensions()
+        .get::<JWTClaim>()
+        .map(|claim| claim.email().to_string())
+}
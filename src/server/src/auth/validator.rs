@@ -2,6 +2,7 @@ use crate::app_data::OxenAppData;
 use crate::auth;
 
 use actix_web::dev::ServiceRequest;
+use actix_web::HttpMessage;
 use actix_web_httpauth::extractors::bearer::BearerAuth;
 
 pub async fn validate(
@@ -12,10 +13,13 @@ pub async fn validate(
     match auth::access_keys::AccessKeyManager::new_read_only(&app_data.path) {
         Ok(keygen) => {
             let token = credentials.token();
-            if keygen.token_is_valid(token) {
-                Ok(req)
-            } else {
-                Err((actix_web::error::ErrorUnauthorized("unauthorized"), req))
+            match keygen.get_valid_claim(token) {
+                Some(claim) => {
+                    // Stash the claim so controllers can check scopes
+                    req.extensions_mut().insert(claim);
+                    Ok(req)
+                }
+                None => Err((actix_web::error::ErrorUnauthorized("unauthorized"), req)),
             }
         }
         Err(err) => Err((
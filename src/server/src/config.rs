@@ -0,0 +1,320 @@
+//! Server configuration that can change while the server is running.
+//!
+//! Read from `server_config.toml` in the sync dir, or the path in `OXEN_SERVER_CONFIG`.
+//! A background task reloads it when the file changes. A file that fails to parse is
+//! logged and ignored, so a bad edit never takes down a running server.
+//!
+//! ```toml
+//! [features.workspace_diff]
+//! enabled = false
+//! namespaces = ["ox", "beta-testers"]
+//!
+//! # Storage quotas in bytes, for a whole namespace or a single repo
+//! [quotas.namespaces]
+//! ox = 10737418240
+//!
+//! [quotas.repos]
+//! "ox/images" = 1073741824
+//! ```
+
+use actix_web::guard::{Guard, GuardContext};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime};
+
+use liboxen::error::OxenError;
+use liboxen::util;
+
+use crate::app_data::OxenAppData;
+
+pub const SERVER_CONFIG_FILENAME: &str = "server_config.toml";
+pub const SERVER_CONFIG_ENV: &str = "OXEN_SERVER_CONFIG";
+pub const RELOAD_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Previewing the staged changes of a workspace before committing them
+pub const WORKSPACE_DIFF_FEATURE: &str = "workspace_diff";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ServerConfig {
+    #[serde(default)]
+    pub features: HashMap<String, FeatureFlag>,
+    #[serde(default)]
+    pub quotas: QuotaConfig,
+}
+
+/// A feature is on everywhere when enabled, otherwise only for the listed namespaces
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct FeatureFlag {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub namespaces: Vec<String>,
+}
+
+/// Storage quotas in bytes. Repos are keyed by `namespace/repo_name`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct QuotaConfig {
+    #[serde(default)]
+    pub namespaces: HashMap<String, u64>,
+    #[serde(default)]
+    pub repos: HashMap<String, u64>,
+}
+
+impl ServerConfig {
+    pub fn parse(contents: &str) -> Result<ServerConfig, OxenError> {
+        toml::from_str(contents)
+            .map_err(|err| OxenError::basic_str(format!("Could not parse server config: {err}")))
+    }
+
+    pub fn is_enabled(&self, feature: &str, namespace: Option<&str>) -> bool {
+        match self.features.get(feature) {
+            Some(flag) => {
+                flag.enabled || namespace.is_some_and(|ns| flag.namespaces.iter().any(|n| n == ns))
+            }
+            None => false,
+        }
+    }
+
+    pub fn namespace_quota(&self, namespace: &str) -> Option<u64> {
+        self.quotas.namespaces.get(namespace).copied()
+    }
+
+    pub fn repo_quota(&self, namespace: &str, repo_name: &str) -> Option<u64> {
+        self.quotas
+            .repos
+            .get(&format!("{namespace}/{repo_name}"))
+            .copied()
+    }
+}
+
+/// Shared handle to the current config, cheap to clone into every worker
+#[derive(Clone)]
+pub struct ServerConfigHandle {
+    path: PathBuf,
+    current: Arc<RwLock<Arc<ServerConfig>>>,
+    last_modified: Arc<Mutex<Option<SystemTime>>>,
+}
+
+impl ServerConfigHandle {
+    /// Load the config for the sync dir, falling back to the defaults
+    pub fn load(sync_dir: &Path) -> ServerConfigHandle {
+        let path = match std::env::var(SERVER_CONFIG_ENV) {
+            Ok(path) => PathBuf::from(path),
+            Err(_) => sync_dir.join(SERVER_CONFIG_FILENAME),
+        };
+        let handle = ServerConfigHandle {
+            path,
+            current: Arc::new(RwLock::new(Arc::new(ServerConfig::default()))),
+            last_modified: Arc::new(Mutex::new(None)),
+        };
+        if let Err(err) = handle.reload_if_changed() {
+            log::error!("Using the default server config: {}", err);
+        }
+        handle
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn get(&self) -> Arc<ServerConfig> {
+        self.current.read().unwrap().clone()
+    }
+
+    pub fn is_enabled(&self, feature: &str, namespace: Option<&str>) -> bool {
+        self.get().is_enabled(feature, namespace)
+    }
+
+    /// Reload the config if the file changed since it was last read. Returns whether it
+    /// was reloaded. The current config is kept if the file can't be read or parsed.
+    pub fn reload_if_changed(&self) -> Result<bool, OxenError> {
+        let modified = match std::fs::metadata(&self.path) {
+            Ok(metadata) => Some(metadata.modified()?),
+            Err(_) => None,
+        };
+        let mut last_modified = self.last_modified.lock().unwrap();
+        if *last_modified == modified {
+            return Ok(false);
+        }
+        // Remember the attempt, so a broken file is only reported once
+        *last_modified = modified;
+
+        let config = match modified {
+            Some(_) => ServerConfig::parse(&util::fs::read_from_path(&self.path)?)?,
+            None => ServerConfig::default(),
+        };
+        log::info!("Loaded server config from {:?}", self.path);
+        *self.current.write().unwrap() = Arc::new(config);
+        Ok(true)
+    }
+}
+
+/// Poll the config file and reload it when it changes
+pub async fn watch(handle: ServerConfigHandle, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        if let Err(err) = handle.reload_if_changed() {
+            log::error!("Keeping the current server config: {}", err);
+        }
+    }
+}
+
+/// Only route to a scope or resource when the feature is on for the namespace in the path.
+/// Requests to it 404 otherwise, like the endpoint does not exist.
+///
+/// ```ignore
+/// web::resource("/diff/{path:.*}").guard(FeatureGuard::new(WORKSPACE_DIFF_FEATURE))
+/// ```
+pub struct FeatureGuard {
+    feature: String,
+}
+
+impl FeatureGuard {
+    pub fn new(feature: impl AsRef<str>) -> FeatureGuard {
+        FeatureGuard {
+            feature: feature.as_ref().to_string(),
+        }
+    }
+}
+
+impl Guard for FeatureGuard {
+    fn check(&self, ctx: &GuardContext<'_>) -> bool {
+        let Some(app_data) = ctx.app_data::<OxenAppData>() else {
+            return false;
+        };
+        let namespace = namespace_from_path(ctx.head().uri.path());
+        app_data.config.is_enabled(&self.feature, namespace)
+    }
+}
+
+// Paths to repository routes look like /api/repos/{namespace}/{repo_name}/...
+fn namespace_from_path(path: &str) -> Option<&str> {
+    path.strip_prefix("/api/repos/")?
+        .split('/')
+        .next()
+        .filter(|namespace| !namespace.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{http, web, App};
+
+    use liboxen::error::OxenError;
+    use liboxen::repositories;
+    use liboxen::util;
+
+    use crate::app_data::OxenAppData;
+    use crate::config::{namespace_from_path, ServerConfig, ServerConfigHandle};
+    use crate::routes;
+    use crate::test;
+
+    #[test]
+    fn test_feature_flags_by_namespace() -> Result<(), OxenError> {
+        let config = ServerConfig::parse(
+            "[features.beta]\nnamespaces = [\"ox\"]\n\n[features.stable]\nenabled = true\n",
+        )?;
+        assert!(config.is_enabled("beta", Some("ox")));
+        assert!(!config.is_enabled("beta", Some("other")));
+        assert!(!config.is_enabled("beta", None));
+        assert!(config.is_enabled("stable", None));
+        assert!(!config.is_enabled("missing", Some("ox")));
+        assert_eq!(config.namespace_quota("ox"), None);
+
+        assert_eq!(
+            namespace_from_path("/api/repos/ox/data/branches"),
+            Some("ox")
+        );
+        assert_eq!(namespace_from_path("/api/version"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_quotas() -> Result<(), OxenError> {
+        let config = ServerConfig::parse(
+            "[quotas.namespaces]\nox = 100\n\n[quotas.repos]\n\"ox/images\" = 10\n",
+        )?;
+        assert_eq!(config.namespace_quota("ox"), Some(100));
+        assert_eq!(config.namespace_quota("other"), None);
+        assert_eq!(config.repo_quota("ox", "images"), Some(10));
+        assert_eq!(config.repo_quota("ox", "text"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_reload_keeps_config_when_file_is_invalid() -> Result<(), OxenError> {
+        test::run_empty_sync_dir_test(|sync_dir| {
+            let handle = ServerConfigHandle::load(sync_dir);
+            assert!(!handle.is_enabled("beta", Some("ox")));
+
+            util::fs::write_to_path(handle.path(), "[features.beta]\nenabled = true\n")?;
+            assert!(handle.reload_if_changed()?);
+            assert!(handle.is_enabled("beta", Some("ox")));
+
+            // Make sure the modification time moves on coarse filesystems
+            std::thread::sleep(std::time::Duration::from_millis(1100));
+            util::fs::write_to_path(handle.path(), "[features.beta\nenabled = ")?;
+            assert!(handle.reload_if_changed().is_err());
+            assert!(handle.is_enabled("beta", Some("ox")));
+
+            Ok(())
+        })
+    }
+
+    #[actix_web::test]
+    async fn test_feature_guard_routes_workspace_diff_by_namespace() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let queue = test::init_queue();
+        let namespace = "Testing-Namespace";
+        let name = "Testing-Feature-Guard";
+        let repo = test::create_local_repo(&sync_dir, namespace, name)?;
+        let hello_file = repo.path.join("hello.txt");
+        util::fs::write_to_path(&hello_file, "Hello")?;
+        repositories::add(&repo, &hello_file)?;
+        let commit = repositories::commit(&repo, "First commit")?;
+        let workspace_id = "feature-guard";
+        repositories::workspaces::create(&repo, &commit, workspace_id, true)?;
+
+        let app_data = OxenAppData::new(sync_dir.clone(), queue);
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(app_data.clone())
+                .service(web::scope("/api/repos").configure(routes::config)),
+        )
+        .await;
+        let uri = format!("/api/repos/{namespace}/{name}/workspaces/{workspace_id}/diff/hello.txt");
+
+        // Off by default, the endpoint does not exist
+        let req = actix_web::test::TestRequest::get().uri(&uri).to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        // On for another namespace only
+        util::fs::write_to_path(
+            app_data.config.path(),
+            "[features.workspace_diff]\nnamespaces = [\"other\"]\n",
+        )?;
+        app_data.config.reload_if_changed()?;
+        let req = actix_web::test::TestRequest::get().uri(&uri).to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::NOT_FOUND);
+
+        // Make sure the modification time moves on coarse filesystems
+        std::thread::sleep(std::time::Duration::from_millis(1100));
+        util::fs::write_to_path(
+            app_data.config.path(),
+            format!("[features.workspace_diff]\nnamespaces = [\"{namespace}\"]\n"),
+        )?;
+        app_data.config.reload_if_changed()?;
+        let req = actix_web::test::TestRequest::get().uri(&uri).to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        // cleanup
+        util::fs::remove_dir_all(sync_dir)?;
+
+        Ok(())
+    }
+}
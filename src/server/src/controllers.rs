@@ -1,20 +1,33 @@
 pub mod action;
+pub mod branch_protection;
 pub mod branches;
+pub mod comments;
 pub mod commits;
+pub mod copy;
 pub mod data_frames;
+pub mod dataset_cards;
 pub mod diff;
 pub mod dir;
 pub mod entries;
 pub mod file;
+pub mod freeze;
 pub mod health;
+pub mod merge_queue;
+pub mod merge_requests;
 pub mod merger;
 pub mod metadata;
 pub mod migrations;
+pub mod mirrors;
 pub mod namespaces;
 pub mod not_found;
+pub mod releases;
 pub mod repositories;
 pub mod revisions;
+pub mod roles;
 pub mod schemas;
+pub mod tokens;
 pub mod tree;
 pub mod version;
+pub mod versions;
+pub mod webhooks;
 pub mod workspaces;
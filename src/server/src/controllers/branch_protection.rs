@@ -0,0 +1,137 @@
+use crate::auth::scopes;
+use crate::errors::OxenHttpError;
+use crate::helpers::get_repo;
+use crate::params::{app_data, path_param};
+
+use actix_web::{HttpRequest, HttpResponse};
+
+use liboxen::model::BranchProtection;
+use liboxen::repositories;
+use liboxen::view::branch_protection::{BranchProtectionResponse, ListBranchProtectionResponse};
+use liboxen::view::StatusMessage;
+
+pub async fn index(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let rules = repositories::branch_protection::list(&repository)?;
+    Ok(HttpResponse::Ok().json(ListBranchProtectionResponse {
+        status: StatusMessage::resource_found(),
+        rules,
+    }))
+}
+
+/// Add or replace the rule for a branch pattern, the body is a BranchProtection
+pub async fn update(req: HttpRequest, body: String) -> Result<HttpResponse, OxenHttpError> {
+    scopes::require(&req, scopes::BRANCHES_PROTECT)?;
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let rule: BranchProtection = serde_json::from_str(&body)
+        .map_err(|err| OxenHttpError::BadRequest(format!("{:?}", err).into()))?;
+    repositories::branch_protection::protect(&repository, &rule)
+        .map_err(|err| OxenHttpError::BadRequest(err.to_string().into()))?;
+    Ok(HttpResponse::Ok().json(BranchProtectionResponse {
+        status: StatusMessage::resource_updated(),
+        rule,
+    }))
+}
+
+pub async fn delete(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    scopes::require(&req, scopes::BRANCHES_PROTECT)?;
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let pattern = path_param(&req, "pattern")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let rule = repositories::branch_protection::unprotect(&repository, &pattern)?;
+    Ok(HttpResponse::Ok().json(BranchProtectionResponse {
+        status: StatusMessage::resource_deleted(),
+        rule,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::body::to_bytes;
+    use actix_web::http;
+
+    use liboxen::constants::DEFAULT_BRANCH_NAME;
+    use liboxen::error::OxenError;
+    use liboxen::repositories;
+    use liboxen::util;
+    use liboxen::view::branch_protection::ListBranchProtectionResponse;
+
+    use crate::controllers;
+    use crate::test;
+
+    #[actix_web::test]
+    async fn test_controllers_branch_protection_blocks_delete() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let queue = test::init_queue();
+        let namespace = "Testing-Namespace";
+        let name = "Testing-Branch-Protection";
+        let repo = test::create_local_repo(&sync_dir, namespace, name)?;
+        util::fs::write_to_path(repo.path.join("README.md"), "Hello")?;
+        repositories::add(&repo, repo.path.join("README.md"))?;
+        repositories::commit(&repo, "first commit")?;
+
+        let uri = format!("/oxen/{namespace}/{name}/branch_protection");
+        let req = test::repo_request(&sync_dir, queue.clone(), &uri, namespace, name);
+        let body = format!(r#"{{"pattern": "{DEFAULT_BRANCH_NAME}"}}"#);
+        let resp = controllers::branch_protection::update(req, body)
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let req = test::repo_request(&sync_dir, queue.clone(), &uri, namespace, name);
+        let resp = controllers::branch_protection::index(req).await.unwrap();
+        let body = to_bytes(resp.into_body()).await.unwrap();
+        let list: ListBranchProtectionResponse =
+            serde_json::from_str(std::str::from_utf8(&body).unwrap())?;
+        assert_eq!(list.rules.len(), 1);
+        assert!(!list.rules[0].allow_deletion);
+
+        // Deleting the protected branch is rejected with a 403
+        let uri = format!("/oxen/{namespace}/{name}/branches/{DEFAULT_BRANCH_NAME}");
+        let req = test::repo_request_with_param(
+            &sync_dir,
+            queue.clone(),
+            &uri,
+            namespace,
+            name,
+            "branch_name",
+            DEFAULT_BRANCH_NAME,
+        );
+        let err = controllers::branches::delete(req).await.unwrap_err();
+        assert_eq!(
+            actix_web::ResponseError::status_code(&err),
+            http::StatusCode::FORBIDDEN
+        );
+        assert!(repositories::branches::get_by_name(&repo, DEFAULT_BRANCH_NAME)?.is_some());
+
+        let uri = format!("/oxen/{namespace}/{name}/branch_protection/{DEFAULT_BRANCH_NAME}");
+        let req = test::repo_request_with_param(
+            &sync_dir,
+            queue,
+            &uri,
+            namespace,
+            name,
+            "pattern",
+            DEFAULT_BRANCH_NAME,
+        );
+        let resp = controllers::branch_protection::delete(req).await.unwrap();
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert!(repositories::branch_protection::list(&repo)?.is_empty());
+
+        // cleanup
+        util::fs::remove_dir_all(sync_dir)?;
+
+        Ok(())
+    }
+}
@@ -1,13 +1,18 @@
 use std::path::PathBuf;
 
+use crate::app_data::OxenAppData;
+use crate::auth::scopes;
 use crate::errors::OxenHttpError;
 use crate::helpers::get_repo;
-use crate::params::{app_data, path_param, PageNumQuery};
+use crate::mirrors;
+use crate::params::{app_data, path_param, CommitStateQuery, PageNumQuery};
+use crate::quotas;
+use crate::webhooks;
 
 use actix_web::{web, HttpRequest, HttpResponse};
 
 use liboxen::error::OxenError;
-use liboxen::model::LocalRepository;
+use liboxen::model::{LocalRepository, WebhookEvent};
 use liboxen::util::{self, paginate};
 use liboxen::view::entries::ResourceVersion;
 use liboxen::view::{
@@ -17,13 +22,19 @@ use liboxen::view::{
 };
 use liboxen::{constants, repositories};
 
-pub async fn index(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+pub async fn index(
+    req: HttpRequest,
+    query: web::Query<CommitStateQuery>,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
     let app_data = app_data(&req)?;
     let namespace = path_param(&req, "namespace")?;
     let name = path_param(&req, "repo_name")?;
     let repo = get_repo(&app_data.path, namespace, name)?;
 
-    let branches = repositories::branches::list(&repo)?;
+    let mut branches = repositories::branches::list(&repo)?;
+    if let Some(state) = query.state {
+        branches = repositories::commits::states::filter_branches(&repo, branches, state)?;
+    }
 
     let view = ListBranchesResponse {
         status: StatusMessage::resource_found(),
@@ -55,26 +66,45 @@ pub async fn create(req: HttpRequest, body: String) -> Result<HttpResponse, Oxen
     let namespace = path_param(&req, "namespace")?;
     let repo_name = path_param(&req, "repo_name")?;
 
-    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+    let repo = get_repo(&app_data.path, &namespace, &repo_name)?;
+    let pusher = scopes::user_email(&req);
 
     // Try to deserialize the body into a BranchNewFromBranchName
     let data: Result<BranchNewFromBranchName, serde_json::Error> = serde_json::from_str(&body);
     if let Ok(data) = data {
-        return create_from_branch(&repo, &data);
+        return create_from_branch(
+            app_data,
+            &repo,
+            &namespace,
+            &repo_name,
+            &data,
+            pusher.as_deref(),
+        );
     }
 
     // Try to deserialize the body into a BranchNewFromCommitId
     let data: Result<BranchNewFromCommitId, serde_json::Error> = serde_json::from_str(&body);
     if let Ok(data) = data {
-        return create_from_commit(&repo, &data);
+        return create_from_commit(
+            app_data,
+            &repo,
+            &namespace,
+            &repo_name,
+            &data,
+            pusher.as_deref(),
+        );
     }
 
     Ok(HttpResponse::BadRequest().json(StatusMessage::error("Invalid request body")))
 }
 
 fn create_from_branch(
+    app_data: &OxenAppData,
     repo: &LocalRepository,
+    namespace: &str,
+    repo_name: &str,
     data: &BranchNewFromBranchName,
+    pusher: Option<&str>,
 ) -> Result<HttpResponse, OxenHttpError> {
     let maybe_new_branch: Option<liboxen::model::Branch> =
         repositories::branches::get_by_name(repo, &data.new_name)?;
@@ -88,8 +118,23 @@ fn create_from_branch(
 
     let from_branch = repositories::branches::get_by_name(repo, &data.from_name)?
         .ok_or(OxenHttpError::NotFound)?;
+    let from_commit = repositories::commits::get_by_id(repo, &from_branch.commit_id)?.ok_or(
+        OxenError::revision_not_found(from_branch.commit_id.clone().into()),
+    )?;
+    repositories::branch_protection::check_update(repo, &data.new_name, &from_commit, pusher)?;
 
     let new_branch = repositories::branches::create(repo, &data.new_name, from_branch.commit_id)?;
+    quotas::record(repo, &new_branch.name, Some(&from_commit));
+    mirrors::sync_on_push(app_data, repo);
+    let commit = repositories::commits::get_by_id(repo, &new_branch.commit_id)?;
+    webhooks::notify(
+        repo,
+        namespace,
+        repo_name,
+        WebhookEvent::BranchCreate,
+        Some(&new_branch.name),
+        commit.as_ref(),
+    );
 
     Ok(HttpResponse::Ok().json(BranchResponse {
         status: StatusMessage::resource_created(),
@@ -98,10 +143,40 @@ fn create_from_branch(
 }
 
 fn create_from_commit(
+    app_data: &OxenAppData,
     repo: &LocalRepository,
+    namespace: &str,
+    repo_name: &str,
     data: &BranchNewFromCommitId,
+    pusher: Option<&str>,
 ) -> Result<HttpResponse, OxenHttpError> {
+    let from_commit = repositories::commits::get_by_id(repo, &data.commit_id)?
+        .ok_or(OxenError::revision_not_found(data.commit_id.clone().into()))?;
+    repositories::branch_protection::check_update(repo, &data.new_name, &from_commit, pusher)?;
+    quotas::check(
+        app_data,
+        namespace,
+        repo_name,
+        repo,
+        &data.new_name,
+        &from_commit,
+    )?;
+
     let new_branch = repositories::branches::create(repo, &data.new_name, &data.commit_id)?;
+    quotas::record(repo, &new_branch.name, Some(&from_commit));
+    mirrors::sync_on_push(app_data, repo);
+    // This is also how a new branch gets pushed, so it is a push as well
+    let commit = repositories::commits::get_by_id(repo, &new_branch.commit_id)?;
+    for event in [WebhookEvent::BranchCreate, WebhookEvent::Push] {
+        webhooks::notify(
+            repo,
+            namespace,
+            repo_name,
+            event,
+            Some(&new_branch.name),
+            commit.as_ref(),
+        );
+    }
 
     Ok(HttpResponse::Ok().json(BranchResponse {
         status: StatusMessage::resource_created(),
@@ -114,12 +189,25 @@ pub async fn delete(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHtt
     let namespace = path_param(&req, "namespace")?;
     let name = path_param(&req, "repo_name")?;
     let branch_name = path_param(&req, "branch_name")?;
-    let repository = get_repo(&app_data.path, namespace, name)?;
+    let repository = get_repo(&app_data.path, &namespace, &name)?;
 
     let branch = repositories::branches::get_by_name(&repository, &branch_name)?
         .ok_or(OxenError::remote_branch_not_found(&branch_name))?;
+    let pusher = scopes::user_email(&req);
+    repositories::branch_protection::check_delete(&repository, &branch.name, pusher.as_deref())?;
 
     repositories::branches::force_delete(&repository, &branch.name)?;
+    quotas::record(&repository, &branch.name, None);
+    mirrors::sync_on_push(app_data, &repository);
+    let commit = repositories::commits::get_by_id(&repository, &branch.commit_id)?;
+    webhooks::notify(
+        &repository,
+        namespace,
+        name,
+        WebhookEvent::BranchDelete,
+        Some(&branch.name),
+        commit.as_ref(),
+    );
     Ok(HttpResponse::Ok().json(BranchResponse {
         status: StatusMessage::resource_deleted(),
         branch,
@@ -134,12 +222,46 @@ pub async fn update(
     let namespace = path_param(&req, "namespace")?;
     let name = path_param(&req, "repo_name")?;
     let branch_name = path_param(&req, "branch_name")?;
-    let repository = get_repo(&app_data.path, namespace, name)?;
+    let repository = get_repo(&app_data.path, &namespace, &name)?;
 
     let data: Result<BranchUpdate, serde_json::Error> = serde_json::from_str(&body);
     let data = data.map_err(|err| OxenHttpError::BadRequest(format!("{:?}", err).into()))?;
 
-    let branch = repositories::branches::update(&repository, branch_name, data.commit_id)?;
+    let new_commit = repositories::commits::get_by_id(&repository, &data.commit_id)?
+        .ok_or(OxenError::revision_not_found(data.commit_id.clone().into()))?;
+    let pusher = scopes::user_email(&req);
+    repositories::branch_protection::check_update(
+        &repository,
+        &branch_name,
+        &new_commit,
+        pusher.as_deref(),
+    )?;
+    quotas::check(
+        app_data,
+        &namespace,
+        &name,
+        &repository,
+        &branch_name,
+        &new_commit,
+    )?;
+
+    let branch = repositories::branches::update_with_lease(
+        &repository,
+        &branch_name,
+        &new_commit,
+        data.expected_commit_id.as_deref(),
+    )?;
+    quotas::record(&repository, &branch.name, Some(&new_commit));
+    mirrors::sync_on_push(app_data, &repository);
+    let commit = repositories::commits::get_by_id(&repository, &branch.commit_id)?;
+    webhooks::notify(
+        &repository,
+        namespace,
+        name,
+        WebhookEvent::Push,
+        Some(&branch.name),
+        commit.as_ref(),
+    );
 
     Ok(HttpResponse::Ok().json(BranchResponse {
         status: StatusMessage::resource_updated(),
@@ -345,6 +467,7 @@ mod tests {
     use actix_web::http::{self};
 
     use actix_web::body::to_bytes;
+    use actix_web::web;
 
     use liboxen::constants::DEFAULT_BRANCH_NAME;
     use liboxen::error::OxenError;
@@ -356,6 +479,7 @@ mod tests {
     };
 
     use crate::controllers;
+    use crate::params::CommitStateQuery;
     use crate::test;
 
     #[actix_web::test]
@@ -372,7 +496,8 @@ mod tests {
         let uri = format!("/oxen/{namespace}/{name}/branches");
         let req = test::repo_request(&sync_dir, queue, &uri, namespace, name);
 
-        let resp = controllers::branches::index(req).await.unwrap();
+        let query: web::Query<CommitStateQuery> = web::Query::from_query("").unwrap();
+        let resp = controllers::branches::index(req, query).await.unwrap();
         assert_eq!(resp.status(), http::StatusCode::OK);
         let body = to_bytes(resp.into_body()).await.unwrap();
         let text = std::str::from_utf8(&body).unwrap();
@@ -405,7 +530,8 @@ mod tests {
         let uri = format!("/oxen/{namespace}/{name}/branches");
         let req = test::repo_request(&sync_dir, queue, &uri, namespace, name);
 
-        let resp = controllers::branches::index(req).await.unwrap();
+        let query: web::Query<CommitStateQuery> = web::Query::from_query("").unwrap();
+        let resp = controllers::branches::index(req, query).await.unwrap();
         assert_eq!(resp.status(), http::StatusCode::OK);
         let body = to_bytes(resp.into_body()).await.unwrap();
         let text = std::str::from_utf8(&body).unwrap();
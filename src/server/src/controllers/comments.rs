@@ -0,0 +1,103 @@
+use crate::errors::OxenHttpError;
+use crate::helpers::get_repo;
+use crate::params::{app_data, path_param, CommentQuery};
+
+use actix_web::{web, HttpRequest, HttpResponse};
+
+use liboxen::error::OxenError;
+use liboxen::model::{CommentFilter, NewComment};
+use liboxen::repositories;
+use liboxen::view::comment::{
+    CommentResponse, ListCommentThreadsResponse, ListCommentsResponse, UpdateCommentBody,
+};
+use liboxen::view::StatusMessage;
+
+use std::path::PathBuf;
+
+/// The comment threads on a commit, file or row, filtered by the query
+pub async fn index(
+    req: HttpRequest,
+    query: web::Query<CommentQuery>,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let filter = CommentFilter {
+        commit_id: query.commit_id.clone(),
+        path: query.path.as_ref().map(PathBuf::from),
+        row_hash: query.row_hash.clone(),
+    };
+    let threads = repositories::comments::list_threads(&repository, &filter)?;
+    Ok(HttpResponse::Ok().json(ListCommentThreadsResponse {
+        status: StatusMessage::resource_found(),
+        threads,
+    }))
+}
+
+pub async fn show(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let id = path_param(&req, "id")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let comment =
+        repositories::comments::get(&repository, &id)?.ok_or(OxenError::resource_not_found(&id))?;
+    Ok(HttpResponse::Ok().json(CommentResponse {
+        status: StatusMessage::resource_found(),
+        comment,
+    }))
+}
+
+pub async fn create(
+    req: HttpRequest,
+    body: String,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let data: NewComment = serde_json::from_str(&body)?;
+    let comment = repositories::comments::create(&repository, data)
+        .map_err(|err| OxenHttpError::BadRequest(err.to_string().into()))?;
+    Ok(HttpResponse::Ok().json(CommentResponse {
+        status: StatusMessage::resource_created(),
+        comment,
+    }))
+}
+
+pub async fn update(
+    req: HttpRequest,
+    body: String,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let id = path_param(&req, "id")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let data: UpdateCommentBody = serde_json::from_str(&body)?;
+    let comment = repositories::comments::update(&repository, &id, &data.body)?;
+    Ok(HttpResponse::Ok().json(CommentResponse {
+        status: StatusMessage::resource_updated(),
+        comment,
+    }))
+}
+
+/// Delete a comment and its replies
+pub async fn delete(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let id = path_param(&req, "id")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let comments = repositories::comments::delete(&repository, &id)?;
+    Ok(HttpResponse::Ok().json(ListCommentsResponse {
+        status: StatusMessage::resource_deleted(),
+        comments,
+    }))
+}
@@ -18,17 +18,19 @@ use liboxen::core::v0_10_0::commits::list_with_missing_dbs;
 use liboxen::core::v0_10_0::commits::merge_objects_dbs;
 use liboxen::core::v0_10_0::index::CommitReader;
 use liboxen::core::v0_10_0::index::CommitWriter;
+use liboxen::core::v0_19_0::index::tree_cache;
 use liboxen::core::versions::MinOxenVersion;
 
 use liboxen::core::refs::RefWriter;
 use liboxen::error::OxenError;
 use liboxen::model::commit::CommitWithBranchName;
 use liboxen::model::RepoNew;
-use liboxen::model::{Commit, LocalRepository};
+use liboxen::model::{Commit, LocalRepository, NewCommitState, User};
 use liboxen::opts::PaginateOpts;
 use liboxen::repositories;
 use liboxen::util;
 use liboxen::view::branch::BranchName;
+use liboxen::view::commit::CommitStateResponse;
 use liboxen::view::commit::CommitSyncStatusResponse;
 use liboxen::view::commit::CommitTreeValidationResponse;
 use liboxen::view::http::MSG_CONTENT_IS_INVALID;
@@ -46,12 +48,15 @@ use liboxen::view::{
 use os_path::OsPath;
 
 use crate::app_data::OxenAppData;
+use crate::auth;
 use crate::errors::OxenHttpError;
 use crate::helpers::get_repo;
 use crate::params::parse_resource;
+use crate::params::CommitStateQuery;
 use crate::params::PageNumQuery;
 use crate::params::{app_data, path_param};
 use crate::tasks;
+use crate::tasks::post_process_commit::PostProcessCommitTask;
 use crate::tasks::post_push_complete::PostPushComplete;
 
 use actix_web::{web, Error, HttpRequest, HttpResponse};
@@ -80,13 +85,19 @@ pub struct ChunkedDataUploadQuery {
 }
 
 // List commits for a repository
-pub async fn index(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+pub async fn index(
+    req: HttpRequest,
+    query: web::Query<CommitStateQuery>,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
     let app_data = app_data(&req)?;
     let namespace = path_param(&req, "namespace")?;
     let repo_name = path_param(&req, "repo_name")?;
     let repo = get_repo(&app_data.path, namespace, repo_name)?;
 
-    let commits = repositories::commits::list(&repo).unwrap_or_default();
+    let mut commits = repositories::commits::list(&repo).unwrap_or_default();
+    if let Some(state) = query.state {
+        commits = repositories::commits::states::filter_commits(&repo, commits, state)?;
+    }
     Ok(HttpResponse::Ok().json(ListCommitResponse::success(commits)))
 }
 
@@ -218,6 +229,61 @@ pub async fn show(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpE
     }))
 }
 
+pub async fn show_state(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let revision = path_param(&req, "commit_id")?;
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+    let state = repositories::commits::states::get(&repo, &revision)?;
+
+    Ok(HttpResponse::Ok().json(CommitStateResponse {
+        status: StatusMessage::resource_found(),
+        state,
+    }))
+}
+
+pub async fn update_state(
+    req: HttpRequest,
+    body: String,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    auth::scopes::require(&req, auth::scopes::COMMITS_STATE)?;
+
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let revision = path_param(&req, "commit_id")?;
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+
+    let data: NewCommitState = serde_json::from_str(&body)?;
+    let user = User {
+        name: data.author,
+        email: data.email,
+    };
+    let state = repositories::commits::states::set(&repo, &revision, data.state, &user)?;
+
+    Ok(HttpResponse::Ok().json(CommitStateResponse {
+        status: StatusMessage::resource_updated(),
+        state: Some(state),
+    }))
+}
+
+pub async fn delete_state(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    auth::scopes::require(&req, auth::scopes::COMMITS_STATE)?;
+
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let revision = path_param(&req, "commit_id")?;
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+    repositories::commits::states::clear(&repo, &revision)?;
+
+    Ok(HttpResponse::Ok().json(CommitStateResponse {
+        status: StatusMessage::resource_deleted(),
+        state: None,
+    }))
+}
+
 /// TODO: Depreciate this API - not good to send the full commit list separately from objects in the tree
 ///       We should just have commits be an object, and send them all last
 pub async fn commits_db_status(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
@@ -269,13 +335,29 @@ pub async fn latest_synced(req: HttpRequest) -> actix_web::Result<HttpResponse,
     //     log::debug!("latest_synced has commit.... {}", commit);
     // }
 
-    // If the repo is v0.19.0 we don't use this API anymore outside of tests,
-    // so we can just assume everything is synced
+    // v0.19.0 commits are synced once post processing is done, and commits that were
+    // never queued are synced as soon as they are pushed
     if repository.min_version() == MinOxenVersion::V0_19_0 {
+        let mut latest_synced: Option<Commit> = None;
+        let mut num_unsynced = 0;
+        for commit in commits {
+            match repositories::post_process::get_status(&repository, &commit)? {
+                None => latest_synced = Some(commit),
+                Some(status) => match status.status {
+                    CacherStatusType::Success => latest_synced = Some(commit),
+                    CacherStatusType::Pending => num_unsynced += 1,
+                    CacherStatusType::Failed => log::error!(
+                        "latest_synced post processing failed for commit {} {}",
+                        commit.id,
+                        status.status_message
+                    ),
+                },
+            }
+        }
         return Ok(HttpResponse::Ok().json(CommitSyncStatusResponse {
             status: StatusMessage::resource_found(),
-            latest_synced: commits.last().cloned(),
-            num_unsynced: 0,
+            latest_synced,
+            num_unsynced,
         }));
     }
 
@@ -410,12 +492,18 @@ pub async fn is_synced(req: HttpRequest) -> actix_web::Result<HttpResponse, Oxen
             is_valid: false,
         }),
         Ok(Some(CacherStatusType::Failed)) => {
-            let errors = commit_cacher::get_failures(&repository, &commit).unwrap();
-            let error_str = errors
-                .into_iter()
-                .map(|e| e.status_message)
-                .collect::<Vec<String>>()
-                .join(", ");
+            let error_str = if repository.min_version() == MinOxenVersion::V0_19_0 {
+                repositories::post_process::get_status(&repository, &commit)?
+                    .map(|status| status.status_message)
+                    .unwrap_or_default()
+            } else {
+                commit_cacher::get_failures(&repository, &commit)
+                    .unwrap()
+                    .into_iter()
+                    .map(|e| e.status_message)
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            };
             log::error!("CacherStatusType::Failed for commit {error_str}");
             HttpResponse::InternalServerError().json(IsValidStatusMessage {
                 status: String::from(STATUS_ERROR),
@@ -953,6 +1041,10 @@ fn check_if_upload_complete_and_unpack(
             }
         }
 
+        if let Some(repo_path) = hidden_dir.parent() {
+            tree_cache::invalidate(repo_path);
+        }
+
         // Cleanup tmp files
         match util::fs::remove_dir_all(&tmp_dir) {
             Ok(_) => {
@@ -1005,6 +1097,7 @@ pub async fn upload_tree(
     let mut archive = Archive::new(GzDecoder::new(&bytes[..]));
 
     unpack_tree_tarball(&tmp_dir, &mut archive);
+    tree_cache::invalidate(&repo.path);
 
     Ok(HttpResponse::Ok().json(CommitResponse {
         status: StatusMessage::resource_found(),
@@ -1170,6 +1263,7 @@ pub async fn upload(
     let mut archive = Archive::new(GzDecoder::new(&bytes[..]));
     unpack_entry_tarball(&hidden_dir, &mut archive);
     // });
+    tree_cache::invalidate(&repo.path);
 
     Ok(HttpResponse::Ok().json(StatusMessage::resource_created()))
 }
@@ -1235,6 +1329,20 @@ pub async fn complete_bulk(req: HttpRequest, body: String) -> Result<HttpRespons
             namespace, repo_name,
         )))?;
 
+    // v0.19.0 commits are validated off the critical path of the push
+    if repo.min_version() == MinOxenVersion::V0_19_0 {
+        for req_commit in commits {
+            let commit = repositories::commits::get_by_id(&repo, &req_commit.id)?
+                .ok_or(OxenError::revision_not_found(req_commit.id.clone().into()))?;
+            repositories::post_process::mark_pending(&repo, &commit)?;
+            queue.push(tasks::Task::PostProcessCommit(PostProcessCommitTask {
+                commit,
+                repo: repo.clone(),
+            }));
+        }
+        return Ok(HttpResponse::Ok().json(StatusMessage::resource_created()));
+    }
+
     // List commits for this repo
     let all_commits = repositories::commits::list(&repo)?;
 
@@ -1412,13 +1520,14 @@ mod tests {
 
     use liboxen::constants::OXEN_HIDDEN_DIR;
     use liboxen::error::OxenError;
+    use liboxen::model::{CommitState, User};
     use liboxen::repositories;
     use liboxen::util;
     use liboxen::view::{ListCommitResponse, StatusMessage};
 
     use crate::app_data::OxenAppData;
     use crate::controllers;
-    use crate::params::PageNumQuery;
+    use crate::params::{CommitStateQuery, PageNumQuery};
     use crate::test::{self, init_test_env};
 
     #[actix_web::test]
@@ -1433,7 +1542,8 @@ mod tests {
         let uri = format!("/oxen/{namespace}/{name}/commits");
         let req = test::repo_request(&sync_dir, queue, &uri, namespace, name);
 
-        let resp = controllers::commits::index(req).await.unwrap();
+        let query: web::Query<CommitStateQuery> = web::Query::from_query("").unwrap();
+        let resp = controllers::commits::index(req, query).await.unwrap();
 
         let body = to_bytes(resp.into_body()).await.unwrap();
         let text = std::str::from_utf8(&body).unwrap();
@@ -1464,7 +1574,8 @@ mod tests {
         let uri = format!("/oxen/{namespace}/{name}/commits");
         let req = test::repo_request(&sync_dir, queue, &uri, namespace, name);
 
-        let resp = controllers::commits::index(req).await.unwrap();
+        let query: web::Query<CommitStateQuery> = web::Query::from_query("").unwrap();
+        let resp = controllers::commits::index(req, query).await.unwrap();
         let body = to_bytes(resp.into_body()).await.unwrap();
         let text = std::str::from_utf8(&body).unwrap();
         let list: ListCommitResponse = serde_json::from_str(text)?;
@@ -1476,6 +1587,44 @@ mod tests {
         Ok(())
     }
 
+    #[actix_web::test]
+    async fn test_controllers_commits_index_filter_by_state() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let queue = test::init_queue();
+        let namespace = "Testing-Namespace";
+        let name = "Testing-Name";
+        let repo = test::create_local_repo(&sync_dir, namespace, name)?;
+
+        let path = liboxen::test::add_txt_file_to_dir(&repo.path, "hello")?;
+        repositories::add(&repo, path)?;
+        let first = repositories::commit(&repo, "first commit")?;
+        let path = liboxen::test::add_txt_file_to_dir(&repo.path, "world")?;
+        repositories::add(&repo, path)?;
+        repositories::commit(&repo, "second commit")?;
+
+        let user = User {
+            name: String::from("Ox"),
+            email: String::from("ox@oxen.ai"),
+        };
+        repositories::commits::states::set(&repo, &first.id, CommitState::Approved, &user)?;
+
+        let uri = format!("/oxen/{namespace}/{name}/commits?state=approved");
+        let req = test::repo_request(&sync_dir, queue, &uri, namespace, name);
+
+        let query: web::Query<CommitStateQuery> = web::Query::from_query("state=approved").unwrap();
+        let resp = controllers::commits::index(req, query).await.unwrap();
+        let body = to_bytes(resp.into_body()).await.unwrap();
+        let text = std::str::from_utf8(&body).unwrap();
+        let list: ListCommitResponse = serde_json::from_str(text)?;
+        assert_eq!(list.commits.len(), 1);
+        assert_eq!(list.commits[0].id, first.id);
+
+        // cleanup
+        util::fs::remove_dir_all(sync_dir)?;
+
+        Ok(())
+    }
+
     #[actix_web::test]
     async fn test_controllers_commits_list_commits_on_branch() -> Result<(), OxenError> {
         let sync_dir = test::get_sync_dir()?;
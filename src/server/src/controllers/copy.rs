@@ -0,0 +1,112 @@
+use crate::errors::OxenHttpError;
+use crate::helpers::get_repo;
+use crate::params::{app_data, path_param};
+
+use actix_web::{HttpRequest, HttpResponse};
+
+use liboxen::model::NewCommitBody;
+use liboxen::repositories;
+use liboxen::view::copy::CopyRequest;
+use liboxen::view::{CommitResponse, StatusMessage};
+
+/// Copy a file or directory from another repository on this server into this one,
+/// without the client having to download and upload it
+pub async fn create(req: HttpRequest, body: String) -> Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let dst_repo = get_repo(&app_data.path, namespace, repo_name)?;
+
+    let data: CopyRequest = serde_json::from_str(&body)?;
+    let src_repo = get_repo(
+        &app_data.path,
+        &data.source.namespace,
+        &data.source.repo_name,
+    )?;
+
+    let new_commit = NewCommitBody {
+        message: data.message,
+        author: data.author,
+        email: data.email,
+    };
+    let commit = repositories::copy::copy_between_repos(
+        &src_repo,
+        &data.source,
+        &dst_repo,
+        &data.branch,
+        &data.path,
+        &new_commit,
+    )?;
+
+    Ok(HttpResponse::Ok().json(CommitResponse {
+        status: StatusMessage::resource_created(),
+        commit,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::str::FromStr;
+
+    use actix_web::body::to_bytes;
+    use actix_web::http;
+
+    use liboxen::constants::DEFAULT_BRANCH_NAME;
+    use liboxen::error::OxenError;
+    use liboxen::model::OxenUri;
+    use liboxen::repositories;
+    use liboxen::util;
+    use liboxen::view::copy::CopyRequest;
+    use liboxen::view::CommitResponse;
+
+    use crate::controllers;
+    use crate::test;
+
+    #[actix_web::test]
+    async fn test_controllers_copy_file_between_repos() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let queue = test::init_queue();
+        let namespace = "Testing-Namespace";
+
+        let src_repo = test::create_local_repo(&sync_dir, namespace, "Source")?;
+        util::fs::create_dir_all(src_repo.path.join("assets"))?;
+        util::fs::write_to_path(src_repo.path.join("assets").join("logo.svg"), "<svg/>")?;
+        repositories::add(&src_repo, &src_repo.path)?;
+        let src_commit = repositories::commit(&src_repo, "Adding the logo")?;
+
+        let dst_repo = test::create_local_repo(&sync_dir, namespace, "Destination")?;
+        util::fs::write_to_path(dst_repo.path.join("README.md"), "# Destination")?;
+        repositories::add(&dst_repo, &dst_repo.path)?;
+        repositories::commit(&dst_repo, "First commit")?;
+
+        let request = CopyRequest {
+            source: OxenUri::from_str("oxen://Testing-Namespace/Source@main:assets/logo.svg")?,
+            branch: DEFAULT_BRANCH_NAME.to_string(),
+            path: PathBuf::from("shared/logo.svg"),
+            message: String::from("Share the logo"),
+            author: String::from("Ox"),
+            email: String::from("ox@oxen.ai"),
+        };
+        let uri = format!("/oxen/{namespace}/Destination/copy");
+        let req = test::repo_request(&sync_dir, queue, &uri, namespace, "Destination");
+        let body = serde_json::to_string(&request)?;
+
+        let resp = controllers::copy::create(req, body).await.unwrap();
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        let body = to_bytes(resp.into_body()).await.unwrap();
+        let text = std::str::from_utf8(&body).unwrap();
+        let response: CommitResponse = serde_json::from_str(text)?;
+        assert!(response.commit.message.contains(&src_commit.id));
+
+        let head = repositories::commits::head_commit(&dst_repo)?;
+        assert_eq!(head.id, response.commit.id);
+        let provenance = repositories::provenance::get_for_commit(&dst_repo, &head)?;
+        assert_eq!(provenance[0].commit_id, src_commit.id);
+
+        // cleanup
+        util::fs::remove_dir_all(sync_dir)?;
+
+        Ok(())
+    }
+}
@@ -4,8 +4,9 @@ use crate::params::df_opts_query::{self, DFOptsQuery};
 use crate::params::{app_data, parse_resource, path_param};
 
 use liboxen::constants;
+use liboxen::core::df::tabular;
 use liboxen::error::PathBufError;
-use liboxen::model::DataFrameSize;
+use liboxen::model::{Commit, DataFrameSize, LocalRepository};
 use liboxen::opts::df_opts::DFOptsView;
 use liboxen::repositories;
 use liboxen::view::entries::ResourceVersion;
@@ -16,6 +17,7 @@ use liboxen::view::{
     JsonDataFrameView, JsonDataFrameViewResponse, JsonDataFrameViews, Pagination, StatusMessage,
 };
 
+use std::path::Path;
 use uuid::Uuid;
 
 pub async fn get(
@@ -32,6 +34,10 @@ pub async fn get(
     let mut opts = DFOpts::empty();
     opts = df_opts_query::parse_opts(&query, &mut opts);
 
+    if query.format.as_deref() == Some(constants::ARROW_FORMAT) {
+        return get_arrow(&repo, &commit, &resource.path, &query, opts);
+    }
+
     let mut page_opts = PaginateOpts {
         page_num: constants::DEFAULT_PAGE_NUM,
         page_size: constants::DEFAULT_PAGE_SIZE,
@@ -100,6 +106,35 @@ pub async fn get(
     Ok(HttpResponse::Ok().json(response))
 }
 
+/// The result of the query as an Arrow IPC stream. Unlike the json view it is only
+/// paginated when a page or slice is asked for, so clients can pull large slices typed.
+fn get_arrow(
+    repo: &LocalRepository,
+    commit: &Commit,
+    path: &Path,
+    query: &DFOptsQuery,
+    mut opts: DFOpts,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    if opts.slice_indices().is_none() && (query.page.is_some() || query.page_size.is_some()) {
+        let page = query.page.unwrap_or(constants::DEFAULT_PAGE_NUM);
+        let page_size = query.page_size.unwrap_or(constants::DEFAULT_PAGE_SIZE);
+        let start = if page == 0 { 0 } else { page_size * (page - 1) };
+        opts.slice = Some(format!("{}..{}", start, page_size * page));
+    }
+
+    let data_frame_slice = repositories::data_frames::get_slice(repo, commit, path, &opts)?;
+    let mut df = data_frame_slice.slice;
+    log::debug!(
+        "controllers::data_frames::get_arrow {} of {} rows",
+        df.height(),
+        data_frame_slice.total_entries
+    );
+    let buffer = tabular::df_to_arrow_stream(&mut df)?;
+    Ok(HttpResponse::Ok()
+        .content_type(constants::ARROW_STREAM_CONTENT_TYPE)
+        .body(buffer))
+}
+
 pub async fn index(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
     let app_data = app_data(&req)?;
     let namespace = path_param(&req, "namespace")?;
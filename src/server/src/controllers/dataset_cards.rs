@@ -0,0 +1,32 @@
+use crate::errors::OxenHttpError;
+use crate::helpers::get_repo;
+use crate::params::{app_data, path_param};
+
+use actix_web::{HttpRequest, HttpResponse};
+
+use liboxen::constants::DEFAULT_BRANCH_NAME;
+use liboxen::error::OxenError;
+use liboxen::repositories;
+use liboxen::view::dataset_card::DatasetCardResponse;
+use liboxen::view::StatusMessage;
+
+/// The dataset card at a branch or commit, the default branch if none is given
+pub async fn show(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let revision = req
+        .match_info()
+        .get("revision")
+        .unwrap_or(DEFAULT_BRANCH_NAME);
+    let commit = repositories::revisions::get(&repository, revision)?
+        .ok_or(OxenError::revision_not_found(revision.into()))?;
+
+    let dataset_card = repositories::dataset_cards::get(&repository, &commit)?;
+    Ok(HttpResponse::Ok().json(DatasetCardResponse {
+        status: StatusMessage::resource_found(),
+        dataset_card,
+    }))
+}
@@ -3,6 +3,7 @@ use std::path::{Path, PathBuf};
 
 use crate::errors::OxenHttpError;
 
+use actix_files::NamedFile;
 use actix_web::{web, HttpRequest, HttpResponse};
 use liboxen::core::df::tabular;
 use liboxen::error::OxenError;
@@ -11,7 +12,7 @@ use liboxen::model::diff::diff_entry_status::DiffEntryStatus;
 use liboxen::model::diff::dir_diff_summary::{DirDiffSummary, DirDiffSummaryImpl};
 use liboxen::model::diff::generic_diff_summary::GenericDiffSummary;
 use liboxen::model::diff::DiffResult;
-use liboxen::model::{Commit, CommitEntry, DataFrameSize, LocalRepository, Schema};
+use liboxen::model::{Commit, CommitEntry, DataFrameSize, EntryDataType, LocalRepository, Schema};
 use liboxen::opts::df_opts::DFOptsView;
 use liboxen::opts::DFOpts;
 use liboxen::view::compare::{
@@ -278,6 +279,43 @@ pub async fn file(
     Ok(HttpResponse::Ok().json(view))
 }
 
+/// Side by side composite of the base and head versions of an image, with the changed pixels highlighted
+pub async fn image(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let base_head = path_param(&req, "base_head")?;
+
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    // For Example)
+    //   main..feature/add-data/path/to/image.png
+    let (base_commit, head_commit, resource) = parse_base_head_resource(&repository, &base_head)?;
+    let base_node = repositories::entries::get_file(&repository, &base_commit, &resource)?
+        .ok_or(OxenError::path_does_not_exist(&resource))?;
+    let head_node = repositories::entries::get_file(&repository, &head_commit, &resource)?
+        .ok_or(OxenError::path_does_not_exist(&resource))?;
+
+    if base_node.data_type != EntryDataType::Image || head_node.data_type != EntryDataType::Image {
+        return Err(OxenHttpError::BadRequest(
+            format!("{resource:?} is not an image").into(),
+        ));
+    }
+
+    // Versions are immutable, so the composite only has to be rendered once
+    let composite_path =
+        util::fs::image_diff_path_for_file_nodes(&repository, &base_node, &head_node);
+    if !composite_path.exists() {
+        let base_path =
+            util::fs::plain_version_path_from_hash(&repository, base_node.hash.to_string())?;
+        let head_path =
+            util::fs::plain_version_path_from_hash(&repository, head_node.hash.to_string())?;
+        util::image::write_diff_composite(base_path.path(), head_path.path(), &composite_path)?;
+    }
+
+    Ok(NamedFile::open(composite_path)?.into_response(&req))
+}
+
 pub async fn create_df_diff(
     req: HttpRequest,
     _query: web::Query<DFOptsQuery>,
@@ -1,15 +1,20 @@
+use crate::controllers;
 use crate::errors::OxenHttpError;
 use crate::helpers::get_repo;
 use crate::params::{app_data, parse_resource, path_param, PageNumQuery};
 
 use liboxen::constants::AVG_CHUNK_SIZE;
 use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::util::compression;
 use liboxen::util::fs::replace_file_name_keep_extension;
 use liboxen::util::paginate;
 use liboxen::view::entries::{PaginatedMetadataEntries, PaginatedMetadataEntriesResponse};
 use liboxen::view::StatusMessage;
 use liboxen::{constants, current_function, repositories, util};
 
+use actix_files::NamedFile;
+use actix_web::http::header;
 use actix_web::{web, HttpRequest, HttpResponse};
 use flate2::read::GzDecoder;
 use flate2::write::GzEncoder;
@@ -17,8 +22,8 @@ use flate2::Compression;
 use futures_util::stream::StreamExt as _;
 use serde::Deserialize;
 
-use std::fs::File;
 use std::io::prelude::*;
+use std::path::Path;
 
 #[derive(Deserialize, Debug)]
 pub struct ChunkQuery {
@@ -76,13 +81,15 @@ pub async fn download_data_from_version_paths(
 
         // In an ideal world we would just pass in the hash and not the full path to save on bandwidth as well
         let mut path_to_read = repo.path.join(content_file);
-        path_to_read = replace_file_name_keep_extension(
+        path_to_read = util::encryption::stored_path(replace_file_name_keep_extension(
             &path_to_read,
             constants::VERSION_FILE_NAME.to_string(),
-        );
+        ));
 
         if path_to_read.exists() {
-            tar.append_path_with_name(path_to_read, content_file)
+            // Send the plain contents, the client stores versions its own way
+            let plain_path = util::encryption::plain_path(&repo, &path_to_read)?;
+            tar.append_path_with_name(plain_path.path(), content_file)
                 .unwrap();
         } else {
             log::error!(
@@ -121,12 +128,83 @@ pub async fn download_chunk(
     let chunk_start: u64 = query.chunk_start.unwrap_or(0);
     let chunk_size: u64 = query.chunk_size.unwrap_or(AVG_CHUNK_SIZE);
 
-    let mut f = File::open(version_path).unwrap();
-    f.seek(std::io::SeekFrom::Start(chunk_start)).unwrap();
-    let mut buffer = vec![0u8; chunk_size as usize];
-    f.read_exact(&mut buffer).unwrap();
+    let chunk_end = chunk_start.checked_add(chunk_size).ok_or_else(|| {
+        OxenHttpError::BadRequest(format!("Invalid chunk {chunk_start} + {chunk_size}").into())
+    })?;
+    // Compressed or encrypted versions are decoded as a stream, only the chunk is kept
+    let range = util::encryption::read_plain_range(&repo, &version_path, chunk_start, chunk_size)?;
+    if (range.bytes.len() as u64) < chunk_size {
+        let num_bytes = range
+            .file_len
+            .unwrap_or(chunk_start + range.bytes.len() as u64);
+        return Ok(HttpResponse::RangeNotSatisfiable()
+            .insert_header((header::CONTENT_RANGE, format!("bytes */{num_bytes}")))
+            .json(StatusMessage::error(format!(
+                "Chunk {chunk_start}..{chunk_end} is past the end of the {num_bytes} byte file"
+            ))));
+    }
+    let buffer = range.bytes;
 
-    Ok(HttpResponse::Ok().body(buffer))
+    encoded_response(&req, &repo, buffer)
+}
+
+/// Whether the client sent `zstd` in its Accept-Encoding
+pub fn accepts_zstd(req: &HttpRequest) -> bool {
+    req.headers()
+        .get(header::ACCEPT_ENCODING)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            value
+                .split(',')
+                .any(|encoding| encoding.split(';').next().map(str::trim) == Some("zstd"))
+        })
+}
+
+// Responses are compressed at the level the repository compresses its version files with
+fn response_level(repo: &LocalRepository) -> i32 {
+    repo.compression_level()
+        .unwrap_or(compression::DEFAULT_COMPRESSION_LEVEL)
+}
+
+/// Compress the body with zstd if the client accepts it
+fn encoded_response(
+    req: &HttpRequest,
+    repo: &LocalRepository,
+    buffer: Vec<u8>,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    if !accepts_zstd(req) {
+        return Ok(HttpResponse::Ok().body(buffer));
+    }
+
+    let compressed = zstd::encode_all(buffer.as_slice(), response_level(repo))?;
+    Ok(HttpResponse::Ok()
+        .insert_header((header::CONTENT_ENCODING, "zstd"))
+        .insert_header((header::VARY, "Accept-Encoding"))
+        .body(compressed))
+}
+
+/// Serve a plain file, compressed with zstd as it streams out if the client accepts it
+pub fn encoded_file_response(
+    req: &HttpRequest,
+    repo: &LocalRepository,
+    path: &Path,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    if !accepts_zstd(req) {
+        return Ok(NamedFile::open(path)?.into_response(req));
+    }
+
+    let level = response_level(repo);
+    let file = std::fs::File::open(path)?;
+    let stream = controllers::versions::stream_blocking(move |writer| {
+        let mut encoder = zstd::stream::write::Encoder::new(writer, level)?;
+        std::io::copy(&mut std::io::BufReader::new(file), &mut encoder)?;
+        encoder.finish()?.flush()?;
+        Ok(())
+    });
+    Ok(HttpResponse::Ok()
+        .insert_header((header::CONTENT_ENCODING, "zstd"))
+        .insert_header((header::VARY, "Accept-Encoding"))
+        .streaming(stream))
 }
 
 pub async fn list_tabular(
@@ -163,3 +241,116 @@ pub async fn list_tabular(
         },
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use actix_web::body::to_bytes;
+    use actix_web::http::header;
+    use actix_web::{http, web};
+
+    use liboxen::command;
+    use liboxen::error::OxenError;
+    use liboxen::repositories;
+    use liboxen::util;
+
+    use crate::app_data::OxenAppData;
+    use crate::controllers;
+    use crate::test;
+
+    #[actix_web::test]
+    async fn test_controllers_entries_download_chunk_negotiates_zstd() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let queue = test::init_queue();
+        let namespace = "Testing-Namespace";
+        let repo_name = "Testing-Name";
+        let repo = test::create_local_repo(&sync_dir, namespace, repo_name)?;
+        let contents = "file,label\n".repeat(100);
+        let path = repo.path.join("labels.csv");
+        util::fs::write_to_path(&path, &contents)?;
+        repositories::add(&repo, &path)?;
+        repositories::commit(&repo, "Add labels")?;
+
+        let uri = format!("/oxen/{namespace}/{repo_name}/chunk/main/labels.csv");
+        let query = format!("chunk_start=0&chunk_size={}", contents.len());
+        for accept_encoding in ["gzip", "gzip, zstd"] {
+            let req = actix_web::test::TestRequest::with_uri(&uri)
+                .app_data(OxenAppData::new(sync_dir.clone(), queue.clone()))
+                .param("namespace", namespace)
+                .param("repo_name", repo_name)
+                .param("resource", "main/labels.csv")
+                .insert_header((header::ACCEPT_ENCODING, accept_encoding))
+                .to_http_request();
+            let query = web::Query::from_query(&query).unwrap();
+            let resp = controllers::entries::download_chunk(req, query)
+                .await
+                .unwrap();
+            assert_eq!(resp.status(), http::StatusCode::OK);
+            let is_zstd = resp.headers().get(header::CONTENT_ENCODING).is_some();
+            let body = to_bytes(resp.into_body()).await.unwrap();
+            let body = if is_zstd {
+                zstd::decode_all(&body[..])?
+            } else {
+                body.to_vec()
+            };
+            assert_eq!(is_zstd, accept_encoding.contains("zstd"));
+            assert_eq!(body, contents.as_bytes());
+        }
+
+        util::fs::remove_dir_all(sync_dir)?;
+        Ok(())
+    }
+
+    #[actix_web::test]
+    async fn test_controllers_entries_download_chunk_of_compressed_version() -> Result<(), OxenError>
+    {
+        let sync_dir = test::get_sync_dir()?;
+        let queue = test::init_queue();
+        let namespace = "Testing-Namespace";
+        let repo_name = "Testing-Name";
+        let mut repo = test::create_local_repo(&sync_dir, namespace, repo_name)?;
+        command::config::set_compression_level(&mut repo, Some(3))?;
+        let contents = "file,label\n".repeat(100);
+        let path = repo.path.join("labels.csv");
+        util::fs::write_to_path(&path, &contents)?;
+        repositories::add(&repo, &path)?;
+        repositories::commit(&repo, "Add labels")?;
+
+        let uri = format!("/oxen/{namespace}/{repo_name}/chunk/main/labels.csv");
+        let half = contents.len() / 2;
+        let chunks = [
+            (0, half, http::StatusCode::OK),
+            (half, contents.len() - half, http::StatusCode::OK),
+            (
+                half,
+                contents.len(),
+                http::StatusCode::RANGE_NOT_SATISFIABLE,
+            ),
+        ];
+        let mut body = vec![];
+        for (chunk_start, chunk_size, status) in chunks {
+            let req = actix_web::test::TestRequest::with_uri(&uri)
+                .app_data(OxenAppData::new(sync_dir.clone(), queue.clone()))
+                .param("namespace", namespace)
+                .param("repo_name", repo_name)
+                .param("resource", "main/labels.csv")
+                .to_http_request();
+            let query = format!("chunk_start={chunk_start}&chunk_size={chunk_size}");
+            let query = web::Query::from_query(&query).unwrap();
+            let resp = controllers::entries::download_chunk(req, query)
+                .await
+                .unwrap();
+            assert_eq!(resp.status(), status);
+            if status == http::StatusCode::OK {
+                body.extend_from_slice(&to_bytes(resp.into_body()).await.unwrap());
+            }
+        }
+        // Chunks come out of the decoded version, not the compressed file
+        assert_eq!(body, contents.as_bytes());
+        // and no decoded copy is left behind on disk
+        let tmp_dir = util::fs::oxen_hidden_dir(&repo.path).join("tmp");
+        assert!(!tmp_dir.exists() || util::fs::rlist_files_in_dir(&tmp_dir).is_empty());
+
+        util::fs::remove_dir_all(sync_dir)?;
+        Ok(())
+    }
+}
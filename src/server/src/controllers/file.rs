@@ -1,3 +1,4 @@
+use crate::controllers;
 use crate::errors::OxenHttpError;
 use crate::helpers::get_repo;
 use crate::params::{app_data, parse_resource, path_param};
@@ -32,7 +33,8 @@ pub async fn get(
     let entry = repositories::entries::get_file(&repo, &commit, &path)?;
     let entry = entry.ok_or(OxenError::path_does_not_exist(path.clone()))?;
 
-    let version_path = util::fs::version_path_from_hash(&repo, entry.hash.to_string());
+    // Decoded to a temp file if the version is stored encrypted or compressed
+    let version_path = util::fs::plain_version_path_from_hash(&repo, entry.hash.to_string())?;
 
     log::debug!("version path {version_path:?}",);
 
@@ -49,7 +51,7 @@ pub async fn get(
             img_resize.height,
         )?;
 
-        util::fs::resize_cache_image(&version_path, &resized_path, img_resize)?;
+        util::fs::resize_cache_image(version_path.path(), &resized_path, img_resize)?;
 
         log::debug!("In the resize cache! {:?}", resized_path);
         return Ok(NamedFile::open(resized_path)?.into_response(&req));
@@ -63,8 +65,8 @@ pub async fn get(
         version_path
     );
 
-    let file = NamedFile::open(version_path)?;
-    let mut response = file.into_response(&req);
+    let mut response =
+        controllers::entries::encoded_file_response(&req, &repo, version_path.path())?;
 
     let last_commit_id = entry.last_commit_id.to_string();
     response.headers_mut().insert(
@@ -0,0 +1,107 @@
+use crate::errors::OxenHttpError;
+use crate::helpers::get_repo;
+use crate::params::{app_data, path_param};
+
+use actix_web::{HttpRequest, HttpResponse};
+
+use liboxen::model::FreezeOpts;
+use liboxen::repositories;
+use liboxen::view::repo_freeze::RepoFreezeResponse;
+use liboxen::view::StatusMessage;
+
+pub async fn show(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let freeze = repositories::freeze::get(&repository)?;
+    Ok(HttpResponse::Ok().json(RepoFreezeResponse {
+        status: StatusMessage::resource_found(),
+        freeze,
+    }))
+}
+
+/// Freeze the repository, the body is an optional FreezeOpts
+pub async fn update(
+    req: HttpRequest,
+    body: String,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let opts: FreezeOpts = if body.is_empty() {
+        FreezeOpts::default()
+    } else {
+        serde_json::from_str(&body)?
+    };
+    let freeze = repositories::freeze::freeze(&repository, &opts)?;
+    Ok(HttpResponse::Ok().json(RepoFreezeResponse {
+        status: StatusMessage::resource_updated(),
+        freeze: Some(freeze),
+    }))
+}
+
+pub async fn delete(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let freeze = repositories::freeze::thaw(&repository)?;
+    Ok(HttpResponse::Ok().json(RepoFreezeResponse {
+        status: StatusMessage::resource_deleted(),
+        freeze: Some(freeze),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::body::to_bytes;
+    use actix_web::http;
+
+    use liboxen::error::OxenError;
+    use liboxen::repositories;
+    use liboxen::util;
+    use liboxen::view::repo_freeze::RepoFreezeResponse;
+
+    use crate::controllers;
+    use crate::test;
+
+    #[actix_web::test]
+    async fn test_controllers_freeze_update_and_delete() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let queue = test::init_queue();
+        let namespace = "Testing-Namespace";
+        let name = "Testing-Freeze";
+        let repo = test::create_local_repo(&sync_dir, namespace, name)?;
+        let hello_file = repo.path.join("hello.txt");
+        util::fs::write_to_path(&hello_file, "Hello")?;
+        repositories::add(&repo, &hello_file)?;
+        repositories::commit(&repo, "First commit")?;
+
+        let uri = format!("/oxen/{namespace}/{name}/freeze");
+        let req = test::repo_request(&sync_dir, queue.clone(), &uri, namespace, name);
+        let resp = controllers::freeze::update(req, String::from(r#"{"recompress": true}"#))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        let body = to_bytes(resp.into_body()).await.unwrap();
+        let text = std::str::from_utf8(&body).unwrap();
+        let resp: RepoFreezeResponse = serde_json::from_str(text)?;
+        assert!(resp.freeze.unwrap().recompressed);
+        assert!(repositories::freeze::is_frozen(&repo));
+
+        let req = test::repo_request(&sync_dir, queue, &uri, namespace, name);
+        let resp = controllers::freeze::delete(req).await.unwrap();
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert!(!repositories::freeze::is_frozen(&repo));
+
+        // cleanup
+        util::fs::remove_dir_all(sync_dir)?;
+
+        Ok(())
+    }
+}
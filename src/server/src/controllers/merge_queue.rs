@@ -0,0 +1,83 @@
+use crate::errors::OxenHttpError;
+use crate::helpers::get_repo;
+use crate::params::{app_data, path_param, MergeQueueQuery};
+use crate::tasks;
+use crate::tasks::merge_queue::MergeQueueTask;
+
+use actix_web::{web, HttpRequest, HttpResponse};
+
+use liboxen::error::OxenError;
+use liboxen::model::NewMergeQueueEntry;
+use liboxen::repositories;
+use liboxen::view::merge::{ListMergeQueueResponse, MergeQueueEntryResponse};
+use liboxen::view::StatusMessage;
+
+pub async fn index(
+    req: HttpRequest,
+    query: web::Query<MergeQueueQuery>,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let entries = repositories::merge_queue::list(&repository, query.base.as_deref())?;
+    Ok(HttpResponse::Ok().json(ListMergeQueueResponse {
+        status: StatusMessage::resource_found(),
+        entries,
+    }))
+}
+
+pub async fn show(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let id = path_param(&req, "id")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let entry = repositories::merge_queue::get(&repository, &id)?
+        .ok_or(OxenError::resource_not_found(&id))?;
+    Ok(HttpResponse::Ok().json(MergeQueueEntryResponse {
+        status: StatusMessage::resource_found(),
+        entry,
+    }))
+}
+
+/// Queue a merge and kick off a task to work through the queue for the base branch
+pub async fn create(
+    req: HttpRequest,
+    body: String,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let data: NewMergeQueueEntry = serde_json::from_str(&body)?;
+    let entry = repositories::merge_queue::enqueue(&repository, data)?;
+
+    let mut queue = app_data.queue.clone();
+    queue.push(tasks::Task::MergeQueue(MergeQueueTask {
+        repo: repository,
+        base: entry.base.to_owned(),
+    }));
+
+    Ok(HttpResponse::Ok().json(MergeQueueEntryResponse {
+        status: StatusMessage::resource_created(),
+        entry,
+    }))
+}
+
+pub async fn delete(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let id = path_param(&req, "id")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let entry = repositories::merge_queue::cancel(&repository, &id)?;
+    Ok(HttpResponse::Ok().json(MergeQueueEntryResponse {
+        status: StatusMessage::resource_deleted(),
+        entry,
+    }))
+}
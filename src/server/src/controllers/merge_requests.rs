@@ -0,0 +1,173 @@
+use crate::auth::scopes;
+use crate::errors::OxenHttpError;
+use crate::helpers::get_repo;
+use crate::mirrors;
+use crate::params::{app_data, path_param, MergeRequestQuery, PageNumQuery};
+use crate::quotas;
+use crate::webhooks;
+
+use actix_web::{web, HttpRequest, HttpResponse};
+
+use liboxen::constants;
+use liboxen::error::OxenError;
+use liboxen::model::{MergeRequestStatus, NewMergeRequest, WebhookEvent};
+use liboxen::repositories;
+use liboxen::view::compare::CompareEntries;
+use liboxen::view::merge::{ListMergeRequestsResponse, MergeRequestResponse};
+use liboxen::view::{CompareEntriesResponse, StatusMessage};
+
+use std::path::PathBuf;
+use std::str::FromStr;
+
+pub async fn index(
+    req: HttpRequest,
+    query: web::Query<MergeRequestQuery>,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let status = match &query.status {
+        Some(status) => Some(
+            MergeRequestStatus::from_str(status)
+                .map_err(|err| OxenHttpError::BadRequest(err.into()))?,
+        ),
+        None => None,
+    };
+    let merge_requests =
+        repositories::merge_requests::list(&repository, status, query.base.as_deref())?;
+    Ok(HttpResponse::Ok().json(ListMergeRequestsResponse {
+        status: StatusMessage::resource_found(),
+        merge_requests,
+    }))
+}
+
+pub async fn show(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let id = path_param(&req, "id")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let merge_request = repositories::merge_requests::get(&repository, &id)?
+        .ok_or(OxenError::resource_not_found(&id))?;
+    Ok(HttpResponse::Ok().json(MergeRequestResponse {
+        status: StatusMessage::resource_found(),
+        merge_request,
+    }))
+}
+
+pub async fn create(
+    req: HttpRequest,
+    body: String,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let data: NewMergeRequest = serde_json::from_str(&body)?;
+    let merge_request = repositories::merge_requests::create(&repository, data)?;
+    Ok(HttpResponse::Ok().json(MergeRequestResponse {
+        status: StatusMessage::resource_created(),
+        merge_request,
+    }))
+}
+
+/// The file changes the head branch would bring into the base branch
+pub async fn diff(
+    req: HttpRequest,
+    query: web::Query<PageNumQuery>,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let id = path_param(&req, "id")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let page = query.page.unwrap_or(constants::DEFAULT_PAGE_NUM);
+    let page_size = query.page_size.unwrap_or(constants::DEFAULT_PAGE_SIZE);
+
+    let merge_request = repositories::merge_requests::get(&repository, &id)?
+        .ok_or(OxenError::resource_not_found(&id))?;
+    let (base_commit, head_commit) =
+        repositories::merge_requests::base_and_head_commits(&repository, &merge_request)?;
+
+    let entries_diff = repositories::diffs::list_diff_entries(
+        &repository,
+        &base_commit,
+        &head_commit,
+        PathBuf::from(""),
+        page,
+        page_size,
+    )?;
+
+    Ok(HttpResponse::Ok().json(CompareEntriesResponse {
+        status: StatusMessage::resource_found(),
+        pagination: entries_diff.pagination,
+        compare: CompareEntries {
+            base_commit,
+            head_commit,
+            counts: entries_diff.counts,
+            entries: entries_diff.entries,
+            self_diff: None,
+        },
+    }))
+}
+
+/// Merge the head branch into the base branch, subject to the base's branch protection
+pub async fn merge(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let id = path_param(&req, "id")?;
+    let repository = get_repo(&app_data.path, &namespace, &name)?;
+
+    let merge_request = repositories::merge_requests::get(&repository, &id)?
+        .ok_or(OxenError::resource_not_found(&id))?;
+    let pusher = scopes::user_email(&req);
+    repositories::branch_protection::check_commit(
+        &repository,
+        &merge_request.base,
+        &merge_request.description,
+        pusher.as_deref(),
+    )?;
+
+    let merge_request = repositories::merge_requests::merge(&repository, &id)?;
+    let commit = match &merge_request.merge_commit_id {
+        Some(commit_id) => repositories::commits::get_by_id(&repository, commit_id)?,
+        None => None,
+    };
+    if let Some(commit) = &commit {
+        quotas::record(&repository, &merge_request.base, Some(commit));
+    }
+    mirrors::sync_on_push(app_data, &repository);
+    webhooks::notify(
+        &repository,
+        &namespace,
+        &name,
+        WebhookEvent::Push,
+        Some(&merge_request.base),
+        commit.as_ref(),
+    );
+
+    Ok(HttpResponse::Ok().json(MergeRequestResponse {
+        status: StatusMessage::resource_updated(),
+        merge_request,
+    }))
+}
+
+pub async fn close(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let id = path_param(&req, "id")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let merge_request = repositories::merge_requests::close(&repository, &id)?;
+    Ok(HttpResponse::Ok().json(MergeRequestResponse {
+        status: StatusMessage::resource_updated(),
+        merge_request,
+    }))
+}
@@ -0,0 +1,77 @@
+use crate::errors::OxenHttpError;
+use crate::helpers::get_repo;
+use crate::mirrors;
+use crate::params::{app_data, path_param};
+
+use actix_web::{HttpRequest, HttpResponse};
+
+use liboxen::model::NewMirror;
+use liboxen::repositories;
+use liboxen::view::mirror::{ListMirrorsResponse, MirrorResponse};
+use liboxen::view::StatusMessage;
+
+pub async fn index(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let mirrors = repositories::mirrors::list(&repository)?;
+    Ok(HttpResponse::Ok().json(ListMirrorsResponse {
+        status: StatusMessage::resource_found(),
+        mirrors,
+    }))
+}
+
+/// Add a mirror, the body is a NewMirror. The first sync is queued right away.
+pub async fn create(req: HttpRequest, body: String) -> Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let data: NewMirror = serde_json::from_str(&body)
+        .map_err(|err| OxenHttpError::BadRequest(format!("{:?}", err).into()))?;
+    let mirror = repositories::mirrors::create(&repository, &data)
+        .map_err(|err| OxenHttpError::BadRequest(err.to_string().into()))?;
+    mirrors::queue_sync(app_data, &repository, vec![mirror.id.clone()]);
+
+    Ok(HttpResponse::Ok().json(MirrorResponse {
+        status: StatusMessage::resource_created(),
+        mirror,
+    }))
+}
+
+pub async fn delete(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let mirror_id = path_param(&req, "mirror_id")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    if repositories::mirrors::get(&repository, &mirror_id)?.is_none() {
+        return Err(OxenHttpError::NotFound);
+    }
+    let mirror = repositories::mirrors::delete(&repository, &mirror_id)?;
+    Ok(HttpResponse::Ok().json(MirrorResponse {
+        status: StatusMessage::resource_deleted(),
+        mirror,
+    }))
+}
+
+/// Queue a sync of the mirror now, whatever its schedule
+pub async fn sync(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let mirror_id = path_param(&req, "mirror_id")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let mirror =
+        repositories::mirrors::get(&repository, &mirror_id)?.ok_or(OxenHttpError::NotFound)?;
+    mirrors::queue_sync(app_data, &repository, vec![mirror.id.clone()]);
+    Ok(HttpResponse::Ok().json(MirrorResponse {
+        status: StatusMessage::resource_found(),
+        mirror,
+    }))
+}
@@ -0,0 +1,92 @@
+use crate::errors::OxenHttpError;
+use crate::helpers::get_repo;
+use crate::params::{app_data, path_param};
+
+use actix_web::{HttpRequest, HttpResponse};
+
+use liboxen::error::OxenError;
+use liboxen::model::NewRelease;
+use liboxen::repositories;
+use liboxen::view::release::{ListReleasesResponse, ReleaseResponse, UpdateReleaseBody};
+use liboxen::view::StatusMessage;
+
+/// All releases of the repository, newest first
+pub async fn index(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let releases = repositories::releases::list(&repository)?;
+    Ok(HttpResponse::Ok().json(ListReleasesResponse {
+        status: StatusMessage::resource_found(),
+        releases,
+    }))
+}
+
+pub async fn show(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let release_name = path_param(&req, "release_name")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let release = repositories::releases::get(&repository, &release_name)?
+        .ok_or(OxenError::resource_not_found(&release_name))?;
+    Ok(HttpResponse::Ok().json(ReleaseResponse {
+        status: StatusMessage::resource_found(),
+        release,
+    }))
+}
+
+pub async fn create(
+    req: HttpRequest,
+    body: String,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let data: NewRelease = serde_json::from_str(&body)?;
+    let release = repositories::releases::create(&repository, data)
+        .map_err(|err| OxenHttpError::BadRequest(err.to_string().into()))?;
+    Ok(HttpResponse::Ok().json(ReleaseResponse {
+        status: StatusMessage::resource_created(),
+        release,
+    }))
+}
+
+/// Edit the description, the commit a release points at can't be changed
+pub async fn update(
+    req: HttpRequest,
+    body: String,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let release_name = path_param(&req, "release_name")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let data: UpdateReleaseBody = serde_json::from_str(&body)?;
+    let release =
+        repositories::releases::update_description(&repository, &release_name, data.description)?;
+    Ok(HttpResponse::Ok().json(ReleaseResponse {
+        status: StatusMessage::resource_updated(),
+        release,
+    }))
+}
+
+pub async fn delete(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let release_name = path_param(&req, "release_name")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let release = repositories::releases::delete(&repository, &release_name)?;
+    Ok(HttpResponse::Ok().json(ReleaseResponse {
+        status: StatusMessage::resource_deleted(),
+        release,
+    }))
+}
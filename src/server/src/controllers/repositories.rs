@@ -1,3 +1,6 @@
+use crate::auth::roles::{self, Role};
+use crate::auth::scopes;
+use crate::controllers;
 use crate::errors::OxenHttpError;
 use crate::helpers::get_repo;
 use crate::params::{app_data, parse_resource, path_param};
@@ -10,6 +13,7 @@ use liboxen::view::http::{MSG_RESOURCE_FOUND, MSG_RESOURCE_UPDATED, STATUS_SUCCE
 use liboxen::view::repository::{
     DataTypeView, RepositoryCreationResponse, RepositoryCreationView, RepositoryDataTypesResponse,
     RepositoryDataTypesView, RepositoryListView, RepositoryStatsResponse, RepositoryStatsView,
+    StorageStatsResponse,
 };
 use liboxen::view::{
     DataTypeCount, ListRepositoryResponse, NamespaceView, RepositoryResponse, RepositoryView,
@@ -18,7 +22,6 @@ use liboxen::view::{
 
 use liboxen::model::RepoNew;
 
-use actix_files::NamedFile;
 use actix_web::{HttpRequest, HttpResponse};
 use std::path::PathBuf;
 
@@ -28,8 +31,12 @@ pub async fn index(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttp
 
     let namespace_path = &app_data.path.join(&namespace);
 
+    // Only list the repositories the user can read
     let repos: Vec<RepositoryListView> = repositories::list_repos_in_namespace(namespace_path)
         .iter()
+        .filter(|repo| {
+            roles::authorize(&req, &namespace, Some(&repo.dirname()), Role::Read).is_ok()
+        })
         .map(|repo| RepositoryListView {
             name: repo.dirname(),
             namespace: namespace.to_string(),
@@ -130,6 +137,20 @@ pub async fn stats(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttp
     }
 }
 
+/// Total and per branch storage of the repository, so admins can see what uses the disk
+pub async fn storage_stats(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let storage_stats = repositories::storage_stats::get(&repository)?;
+    Ok(HttpResponse::Ok().json(StorageStatsResponse {
+        status: StatusMessage::resource_found(),
+        storage_stats,
+    }))
+}
+
 pub async fn create(
     req: HttpRequest,
     body: String,
@@ -137,6 +158,10 @@ pub async fn create(
     let app_data = app_data(&req)?;
     println!("controllers::repositories::create body:\n{}", body);
     let data: Result<RepoNew, serde_json::Error> = serde_json::from_str(&body);
+    if let Ok(data) = &data {
+        scopes::check_restrictions(&req, "")?;
+        roles::authorize(&req, &data.namespace, None, Role::Write)?;
+    }
     match data {
         Ok(data) => match repositories::create(&app_data.path, data.to_owned()) {
             Ok(repo) => match repositories::commits::latest_commit(&repo) {
@@ -237,7 +262,7 @@ pub async fn transfer_namespace(
     }))
 }
 
-pub async fn get_file_for_branch(req: HttpRequest) -> Result<NamedFile, OxenHttpError> {
+pub async fn get_file_for_branch(req: HttpRequest) -> Result<HttpResponse, OxenHttpError> {
     let app_data = app_data(&req)?;
     let namespace = path_param(&req, "namespace")?;
     let repo_name = path_param(&req, "repo_name")?;
@@ -253,10 +278,11 @@ pub async fn get_file_for_branch(req: HttpRequest) -> Result<NamedFile, OxenHttp
         filepath,
         version_path
     );
-    Ok(NamedFile::open(version_path)?)
+    let plain_path = util::encryption::plain_path(&repo, &version_path)?;
+    controllers::entries::encoded_file_response(&req, &repo, plain_path.path())
 }
 
-pub async fn get_file_for_commit_id(req: HttpRequest) -> Result<NamedFile, OxenHttpError> {
+pub async fn get_file_for_commit_id(req: HttpRequest) -> Result<HttpResponse, OxenHttpError> {
     let app_data = app_data(&req)?;
     let namespace = path_param(&req, "namespace")?;
     let repo_name = path_param(&req, "repo_name")?;
@@ -275,7 +301,8 @@ pub async fn get_file_for_commit_id(req: HttpRequest) -> Result<NamedFile, OxenH
         resource.path,
         version_path
     );
-    Ok(NamedFile::open(version_path)?)
+    let plain_path = util::encryption::plain_path(&repo, &version_path)?;
+    controllers::entries::encoded_file_response(&req, &repo, plain_path.path())
 }
 
 #[cfg(test)]
@@ -384,6 +411,7 @@ mod tests {
             email: String::from("ox@oxen.ai"),
             timestamp,
             root_hash: None,
+            co_authors: vec![],
         };
         let repo_new = RepoNew::from_root_commit("Testing-Name", "Testing-Namespace", root_commit);
         let data = serde_json::to_string(&repo_new)?;
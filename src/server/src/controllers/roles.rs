@@ -0,0 +1,125 @@
+use crate::auth::roles::{self, RoleGrant};
+use crate::auth::scopes;
+use crate::errors::OxenHttpError;
+use crate::params::{app_data, RoleQuery};
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde::{Deserialize, Serialize};
+
+use liboxen::view::StatusMessage;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RoleGrantResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub grant: RoleGrant,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ListRoleGrantsResponse {
+    #[serde(flatten)]
+    pub status: StatusMessage,
+    pub grants: Vec<RoleGrant>,
+}
+
+pub async fn index(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    scopes::require(&req, scopes::ROLES_ADMIN)?;
+    let app_data = app_data(&req)?;
+
+    let grants = roles::list(&app_data.path)?;
+    Ok(HttpResponse::Ok().json(ListRoleGrantsResponse {
+        status: StatusMessage::resource_found(),
+        grants,
+    }))
+}
+
+/// Grant a user a role, the body is a RoleGrant
+pub async fn update(req: HttpRequest, body: String) -> Result<HttpResponse, OxenHttpError> {
+    scopes::require(&req, scopes::ROLES_ADMIN)?;
+    let app_data = app_data(&req)?;
+
+    let grant: RoleGrant = serde_json::from_str(&body)
+        .map_err(|err| OxenHttpError::BadRequest(format!("{:?}", err).into()))?;
+    roles::grant(&app_data.path, &grant)
+        .map_err(|err| OxenHttpError::BadRequest(err.to_string().into()))?;
+    Ok(HttpResponse::Ok().json(RoleGrantResponse {
+        status: StatusMessage::resource_updated(),
+        grant,
+    }))
+}
+
+pub async fn delete(
+    req: HttpRequest,
+    query: web::Query<RoleQuery>,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    scopes::require(&req, scopes::ROLES_ADMIN)?;
+    let app_data = app_data(&req)?;
+
+    let grant = roles::revoke(
+        &app_data.path,
+        &query.email,
+        &query.namespace,
+        query.repo_name.as_deref(),
+    )?
+    .ok_or(OxenHttpError::NotFound)?;
+    Ok(HttpResponse::Ok().json(RoleGrantResponse {
+        status: StatusMessage::resource_deleted(),
+        grant,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::body::to_bytes;
+    use actix_web::{http, web};
+
+    use liboxen::error::OxenError;
+    use liboxen::util;
+
+    use crate::auth::roles::{self, Role};
+    use crate::controllers;
+    use crate::controllers::roles::ListRoleGrantsResponse;
+    use crate::params::RoleQuery;
+    use crate::test;
+
+    #[actix_web::test]
+    async fn test_controllers_roles_grant_list_revoke() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let queue = test::init_queue();
+
+        let uri = "/api/roles";
+        let req = test::request(&sync_dir, queue.clone(), uri);
+        let body = r#"{"email": "ox@oxen.ai", "namespace": "ox", "role": "write"}"#;
+        let resp = controllers::roles::update(req, body.to_string())
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), http::StatusCode::OK);
+
+        let req = test::request(&sync_dir, queue.clone(), uri);
+        let resp = controllers::roles::update(req, r#"{"email": "ox@oxen.ai"}"#.to_string()).await;
+        assert!(resp.is_err());
+
+        let req = test::request(&sync_dir, queue.clone(), uri);
+        let resp = controllers::roles::index(req).await.unwrap();
+        let body = to_bytes(resp.into_body()).await.unwrap();
+        let list: ListRoleGrantsResponse =
+            serde_json::from_str(std::str::from_utf8(&body).unwrap())?;
+        assert_eq!(list.grants.len(), 1);
+        assert_eq!(list.grants[0].role, Role::Write);
+
+        let req = test::request(&sync_dir, queue, uri);
+        let query = web::Query(RoleQuery {
+            email: "ox@oxen.ai".to_string(),
+            namespace: "ox".to_string(),
+            repo_name: None,
+        });
+        let resp = controllers::roles::delete(req, query).await.unwrap();
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert!(roles::list(&sync_dir)?.is_empty());
+
+        // cleanup
+        util::fs::remove_dir_all(sync_dir)?;
+
+        Ok(())
+    }
+}
@@ -35,10 +35,16 @@ pub async fn list_or_get(req: HttpRequest) -> actix_web::Result<HttpResponse, Ox
 
             let mut schema_w_paths: Vec<SchemaWithPath> = vec![];
             if let Some(schema) = schema {
-                schema_w_paths.push(SchemaWithPath::new(
-                    resource.path.to_string_lossy().into(),
-                    schema,
-                ));
+                let mut schema_w_path =
+                    SchemaWithPath::new(resource.path.to_string_lossy().into(), schema);
+                // Only for a single file, listing every schema would make the response huge
+                schema_w_path.column_stats =
+                    repositories::data_frames::schemas::get_column_stats_by_path(
+                        &repo,
+                        commit,
+                        &resource.path,
+                    )?;
+                schema_w_paths.push(schema_w_path);
             }
 
             let resource = ResourceVersion {
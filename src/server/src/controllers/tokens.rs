@@ -0,0 +1,89 @@
+use crate::auth::access_keys::JWTClaim;
+use crate::errors::OxenHttpError;
+use crate::params::app_data;
+
+use actix_web::{HttpMessage, HttpRequest, HttpResponse};
+
+use liboxen::view::token::{NewToken, TokenResponse};
+use liboxen::view::StatusMessage;
+
+/// Mint a token that can do at most what the token on the request can, the body is a NewToken
+pub async fn create(req: HttpRequest, body: String) -> Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let Some(parent) = req.extensions().get::<JWTClaim>().cloned() else {
+        return Err(OxenHttpError::BadRequest(
+            "This server does not require auth, there is no token to mint from".into(),
+        ));
+    };
+
+    let data: NewToken = serde_json::from_str(&body)
+        .map_err(|err| OxenHttpError::BadRequest(format!("{:?}", err).into()))?;
+    let keygen = app_data.keys.get(&app_data.path)?;
+    let (claim, token) = keygen
+        .mint(&parent, &data.scopes, &data.resources, data.expires_at)
+        .map_err(|err| OxenHttpError::Forbidden(err.to_string().into()))?;
+
+    Ok(HttpResponse::Ok().json(TokenResponse {
+        status: StatusMessage::resource_created(),
+        token,
+        scopes: claim.scopes().to_vec(),
+        resources: claim.resources().to_vec(),
+        expires_at: claim.expires_at(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::body::to_bytes;
+    use actix_web::{http, HttpMessage};
+
+    use liboxen::error::OxenError;
+    use liboxen::model::User;
+    use liboxen::util;
+    use liboxen::view::token::TokenResponse;
+
+    use crate::auth::access_keys::AccessKeyManager;
+    use crate::controllers;
+    use crate::test;
+
+    #[actix_web::test]
+    async fn test_controllers_tokens_mint_read_only() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let queue = test::init_queue();
+
+        let parent = {
+            let keygen = AccessKeyManager::new(&sync_dir)?;
+            let user = User {
+                name: String::from("Ox"),
+                email: String::from("ox@oxen.ai"),
+            };
+            let (_user, token) = keygen.create(&user)?;
+            keygen.get_valid_claim(&token).unwrap()
+        };
+
+        // Without a token on the request there is nothing to mint from
+        let req = test::request(&sync_dir, queue.clone(), "/api/tokens");
+        let resp = controllers::tokens::create(req, "{}".to_string()).await;
+        assert!(resp.is_err());
+
+        let req = test::request(&sync_dir, queue, "/api/tokens");
+        req.extensions_mut().insert(parent);
+        let body = r#"{"scopes": ["read-only"], "resources": ["ox/datasets"]}"#;
+        let resp = controllers::tokens::create(req, body.to_string())
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        let body = to_bytes(resp.into_body()).await.unwrap();
+        let resp: TokenResponse = serde_json::from_str(std::str::from_utf8(&body).unwrap())?;
+        assert_eq!(resp.scopes, vec!["read-only"]);
+        assert_eq!(resp.resources, vec!["ox/datasets"]);
+
+        let keygen = AccessKeyManager::new_read_only(&sync_dir)?;
+        assert!(keygen.token_is_valid(&resp.token));
+
+        // cleanup
+        util::fs::remove_dir_all(sync_dir)?;
+
+        Ok(())
+    }
+}
@@ -5,8 +5,10 @@ use futures_util::stream::StreamExt as _;
 use liboxen::constants::NODES_DIR;
 use liboxen::constants::OXEN_HIDDEN_DIR;
 use liboxen::constants::TREE_DIR;
-use liboxen::core::v0_19_0::index::merkle_node_db::node_db_path;
 use liboxen::core::v0_19_0::index::merkle_node_db::node_db_prefix;
+use liboxen::core::v0_19_0::index::node_pack;
+use liboxen::core::v0_19_0::index::tree_cache;
+use liboxen::core::v0_19_0::index::MerkleNodeDB;
 use liboxen::error::OxenError;
 use liboxen::model::LocalRepository;
 use liboxen::view::tree::merkle_hashes::MerkleHashes;
@@ -143,50 +145,7 @@ pub async fn create_nodes(
         bytes.extend_from_slice(&item.unwrap());
     }
 
-    log::debug!(
-        "create_node decompressing {} bytes",
-        ByteSize::b(bytes.len() as u64)
-    );
-
-    let mut hashes: HashSet<MerkleHash> = HashSet::new();
-    let mut archive = Archive::new(GzDecoder::new(&bytes[..]));
-    let Ok(entries) = archive.entries() else {
-        return Err(OxenHttpError::BadRequest(
-            "Could not unpack tree database from archive".into(),
-        ));
-    };
-
-    for file in entries {
-        let Ok(mut file) = file else {
-            log::error!("Could not unpack file in archive...");
-            continue;
-        };
-        let path = file.path().unwrap();
-        let oxen_hidden_path = repository.path.join(OXEN_HIDDEN_DIR);
-        let dst_path = oxen_hidden_path.join(TREE_DIR).join(NODES_DIR).join(path);
-
-        if let Some(parent) = dst_path.parent() {
-            if !parent.exists() {
-                std::fs::create_dir_all(parent).expect("Could not create parent dir");
-            }
-        }
-        log::debug!("create_node writing {:?}", dst_path);
-        file.unpack(&dst_path).unwrap();
-
-        // the hash is the last two path components combined
-        if !dst_path.ends_with("node") && !dst_path.ends_with("children") {
-            let id = dst_path
-                .components()
-                .rev()
-                .take(2)
-                .map(|c| c.as_os_str().to_str().unwrap())
-                .collect::<Vec<&str>>()
-                .into_iter()
-                .rev()
-                .collect::<String>();
-            hashes.insert(MerkleHash::from_str(&id)?);
-        }
-    }
+    let hashes = unpack_nodes(&repository, &bytes)?;
 
     Ok(HttpResponse::Ok().json(MerkleHashesResponse {
         status: StatusMessage::resource_found(),
@@ -214,6 +173,33 @@ pub async fn download_tree_nodes(
     let base_head_str = path_param(&req, "base_head")?;
     let (base_commit_id, maybe_head_commit_id) = maybe_parse_base_head(base_head_str)?;
 
+    let buffer = compress_tree_nodes(&repository, &base_commit_id, maybe_head_commit_id)?;
+
+    Ok(HttpResponse::Ok().body(buffer))
+}
+
+pub async fn download_node(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let hash_str = path_param(&req, "hash")?;
+    let hash = MerkleHash::from_str(&hash_str)?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let buffer = compress_node(&repository, &hash)?;
+
+    Ok(HttpResponse::Ok().body(buffer))
+}
+
+pub async fn download_commits(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let base_head = path_param(&req, "base_head")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let (base_commit_id, maybe_head_commit_id) = maybe_parse_base_head(base_head)?;
+
     let base_commit = repositories::commits::get_by_id(&repository, &base_commit_id)?
         .ok_or(OxenError::resource_not_found(&base_commit_id))?;
 
@@ -229,33 +215,20 @@ pub async fn download_tree_nodes(
         repositories::commits::list_from(&repository, &base_commit_id)?
     };
 
-    // zip up the node directories for each commit tree
+    // zip up the node directory
     let enc = GzEncoder::new(Vec::new(), Compression::default());
     let mut tar = tar::Builder::new(enc);
 
-    // Collect the unique node hashes for all the commits
-    // There could be duplicate nodes across commits, hence the need to dedup
-    let mut unique_node_hashes: HashSet<MerkleHash> = HashSet::new();
     for commit in &commits {
-        let tree = repositories::tree::get_by_commit(&repository, commit)?;
-
-        tree.walk_tree_without_leaves(|node| {
-            unique_node_hashes.insert(node.hash);
-        });
-    }
-
-    for hash in unique_node_hashes {
+        let hash = commit.hash()?;
         // This will be the subdir within the tarball
         // so when we untar it, all the subdirs will be extracted to
         // tree/nodes/...
         let dir_prefix = node_db_prefix(&hash);
         let tar_subdir = Path::new(TREE_DIR).join(NODES_DIR).join(dir_prefix);
 
-        let node_dir = node_db_path(&repository, &hash);
-        log::debug!("Compressing commit from dir {:?}", node_dir);
-        if node_dir.exists() {
-            tar.append_dir_all(&tar_subdir, node_dir)?;
-        }
+        log::debug!("Compressing node {}", hash);
+        MerkleNodeDB::append_to_tar(&repository, &hash, &mut tar, &tar_subdir)?;
     }
     tar.finish()?;
 
@@ -270,60 +243,108 @@ pub async fn download_tree_nodes(
     Ok(HttpResponse::Ok().body(buffer))
 }
 
-pub async fn download_node(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
-    let app_data = app_data(&req)?;
-    let namespace = path_param(&req, "namespace")?;
-    let name = path_param(&req, "repo_name")?;
-    let hash_str = path_param(&req, "hash")?;
-    let hash = MerkleHash::from_str(&hash_str)?;
-    let repository = get_repo(&app_data.path, namespace, name)?;
+/// Unpack a gzipped tar of tree nodes into the repository, returning the hashes of the
+/// nodes that were written. Shared by the HTTP and gRPC uploads.
+pub fn unpack_nodes(
+    repository: &LocalRepository,
+    bytes: &[u8],
+) -> Result<HashSet<MerkleHash>, OxenError> {
+    log::debug!(
+        "create_node decompressing {} bytes",
+        ByteSize::b(bytes.len() as u64)
+    );
 
-    let buffer = compress_node(&repository, &hash)?;
+    let mut hashes: HashSet<MerkleHash> = HashSet::new();
+    let mut archive = Archive::new(GzDecoder::new(bytes));
+    let Ok(entries) = archive.entries() else {
+        return Err(OxenError::basic_str(
+            "Could not unpack tree database from archive",
+        ));
+    };
 
-    Ok(HttpResponse::Ok().body(buffer))
-}
+    for file in entries {
+        let Ok(mut file) = file else {
+            log::error!("Could not unpack file in archive...");
+            continue;
+        };
+        let path = file.path()?.to_path_buf();
+        let oxen_hidden_path = repository.path.join(OXEN_HIDDEN_DIR);
+        let dst_path = oxen_hidden_path.join(TREE_DIR).join(NODES_DIR).join(path);
 
-pub async fn download_commits(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
-    let app_data = app_data(&req)?;
-    let namespace = path_param(&req, "namespace")?;
-    let name = path_param(&req, "repo_name")?;
-    let base_head = path_param(&req, "base_head")?;
-    let repository = get_repo(&app_data.path, namespace, name)?;
+        if let Some(parent) = dst_path.parent() {
+            if !parent.exists() {
+                std::fs::create_dir_all(parent)?;
+            }
+        }
+        log::debug!("create_node writing {:?}", dst_path);
+        file.unpack(&dst_path)?;
 
-    let (base_commit_id, maybe_head_commit_id) = maybe_parse_base_head(base_head)?;
+        // the hash is the last two path components combined
+        if !dst_path.ends_with("node") && !dst_path.ends_with("children") {
+            let id = dst_path
+                .components()
+                .rev()
+                .take(2)
+                .map(|c| c.as_os_str().to_str().unwrap())
+                .collect::<Vec<&str>>()
+                .into_iter()
+                .rev()
+                .collect::<String>();
+            hashes.insert(MerkleHash::from_str(&id)?);
+        }
+    }
+    // Subtrees read while the push was uploading could be missing these nodes
+    tree_cache::invalidate(&repository.path);
 
-    let base_commit = repositories::commits::get_by_id(&repository, &base_commit_id)?
-        .ok_or(OxenError::resource_not_found(&base_commit_id))?;
+    Ok(hashes)
+}
+
+/// Gzipped tar of the unique tree nodes of the commits from `base_commit_id` back to the
+/// first commit, or back to `head_commit_id` if it is given
+pub fn compress_tree_nodes(
+    repository: &LocalRepository,
+    base_commit_id: &str,
+    maybe_head_commit_id: Option<String>,
+) -> Result<Vec<u8>, OxenError> {
+    let base_commit = repositories::commits::get_by_id(repository, base_commit_id)?
+        .ok_or(OxenError::resource_not_found(base_commit_id))?;
 
     // If we have a head commit, then we are downloading a range of commits
     // Otherwise, we are downloading all commits from the base commit back to the first commit
     // This is the difference between the first pull and subsequent pulls
     // The first pull doesn't have a head commit, but subsequent pulls do
     let commits = if let Some(head_commit_id) = maybe_head_commit_id {
-        let head_commit = repositories::commits::get_by_id(&repository, &head_commit_id)?
+        let head_commit = repositories::commits::get_by_id(repository, &head_commit_id)?
             .ok_or(OxenError::resource_not_found(&head_commit_id))?;
-        repositories::commits::list_between(&repository, &head_commit, &base_commit)?
+        repositories::commits::list_between(repository, &head_commit, &base_commit)?
     } else {
-        repositories::commits::list_from(&repository, &base_commit_id)?
+        repositories::commits::list_from(repository, base_commit_id)?
     };
 
-    // zip up the node directory
+    // zip up the node directories for each commit tree
     let enc = GzEncoder::new(Vec::new(), Compression::default());
     let mut tar = tar::Builder::new(enc);
 
+    // Collect the unique node hashes for all the commits
+    // There could be duplicate nodes across commits, hence the need to dedup
+    let mut unique_node_hashes: HashSet<MerkleHash> = HashSet::new();
     for commit in &commits {
-        let hash = commit.hash()?;
+        let tree = repositories::tree::get_by_commit(repository, commit)?;
+
+        tree.walk_tree_without_leaves(|node| {
+            unique_node_hashes.insert(node.hash);
+        });
+    }
+
+    for hash in unique_node_hashes {
         // This will be the subdir within the tarball
         // so when we untar it, all the subdirs will be extracted to
         // tree/nodes/...
         let dir_prefix = node_db_prefix(&hash);
         let tar_subdir = Path::new(TREE_DIR).join(NODES_DIR).join(dir_prefix);
 
-        let node_dir = node_db_path(&repository, &hash);
-        log::debug!("Compressing commit from dir {:?}", node_dir);
-        if node_dir.exists() {
-            tar.append_dir_all(&tar_subdir, node_dir)?;
-        }
+        log::debug!("Compressing node {}", hash);
+        MerkleNodeDB::append_to_tar(repository, &hash, &mut tar, &tar_subdir)?;
     }
     tar.finish()?;
 
@@ -335,7 +356,7 @@ pub async fn download_commits(req: HttpRequest) -> actix_web::Result<HttpRespons
         ByteSize::b(total_size)
     );
 
-    Ok(HttpResponse::Ok().body(buffer))
+    Ok(buffer)
 }
 
 fn compress_node(repository: &LocalRepository, hash: &MerkleHash) -> Result<Vec<u8>, OxenError> {
@@ -348,12 +369,9 @@ fn compress_node(repository: &LocalRepository, hash: &MerkleHash) -> Result<Vec<
     // zip up the node directory
     let enc = GzEncoder::new(Vec::new(), Compression::default());
     let mut tar = tar::Builder::new(enc);
-    let node_dir = node_db_path(repository, hash);
 
-    log::debug!("Compressing node {} from dir {:?}", hash, node_dir);
-    if node_dir.exists() {
-        tar.append_dir_all(&tar_subdir, node_dir)?;
-    }
+    log::debug!("Compressing node {}", hash);
+    MerkleNodeDB::append_to_tar(repository, hash, &mut tar, &tar_subdir)?;
     tar.finish()?;
 
     let buffer: Vec<u8> = tar.into_inner()?.finish()?;
@@ -401,6 +419,12 @@ fn compress_full_tree(
         tar.append_dir_all(&tar_subdir, nodes_dir)?;
     }
 
+    // Packed nodes go in as loose nodes, clients don't read packs
+    for hash in node_pack::list(repository)? {
+        let node_subdir = tar_subdir.join(node_db_prefix(&hash));
+        MerkleNodeDB::append_to_tar(repository, &hash, tar, node_subdir)?;
+    }
+
     Ok(())
 }
 
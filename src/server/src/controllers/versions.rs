@@ -0,0 +1,287 @@
+use crate::errors::OxenHttpError;
+use crate::helpers::get_repo;
+use crate::params::{app_data, path_param};
+
+use liboxen::constants::TAR_ZSTD_CONTENT_TYPE;
+use liboxen::error::OxenError;
+use liboxen::model::{LocalRepository, MerkleHash};
+use liboxen::util;
+use liboxen::view::tree::merkle_hashes::MerkleHashes;
+use liboxen::view::{MerkleHashesResponse, StatusMessage};
+
+use actix_web::web::{self, Bytes};
+use actix_web::{HttpRequest, HttpResponse};
+use futures_util::stream::StreamExt as _;
+use std::collections::HashSet;
+use std::io::{BufWriter, Read, Write};
+use std::str::FromStr;
+use tokio::sync::mpsc;
+
+// Size of the pieces the download is streamed in
+const STREAM_BUFFER_SIZE: usize = 256 * 1024;
+
+async fn read_body(mut body: web::Payload) -> Result<Bytes, OxenHttpError> {
+    let mut bytes = web::BytesMut::new();
+    while let Some(item) = body.next().await {
+        bytes.extend_from_slice(&item.map_err(actix_web::Error::from)?);
+    }
+    Ok(bytes.freeze())
+}
+
+/// Store a zstd compressed tar of version files named by their hash, see
+/// `api::client::versions::upload_batch`
+pub async fn upload_batch(
+    req: HttpRequest,
+    body: web::Payload,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+
+    let bytes = read_body(body).await?;
+    log::debug!("upload_batch got {} bytes", bytes.len());
+    let hashes = web::block(move || unpack_versions(&repo, &bytes[..]))
+        .await
+        .map_err(actix_web::Error::from)??;
+    log::debug!("upload_batch stored {} versions", hashes.len());
+
+    Ok(HttpResponse::Ok().json(MerkleHashesResponse {
+        status: StatusMessage::resource_created(),
+        hashes,
+    }))
+}
+
+/// Stream the requested version files back as a zstd compressed tar, see
+/// `api::client::versions::download_batch`
+pub async fn download_batch(
+    req: HttpRequest,
+    body: web::Payload,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+
+    let bytes = read_body(body).await?;
+    let request: MerkleHashes = serde_json::from_slice(&bytes)?;
+    log::debug!("download_batch {} versions", request.hashes.len());
+
+    // Fail before we start streaming, a broken tar is harder to make sense of
+    let num_missing = request
+        .hashes
+        .iter()
+        .filter(|hash| !util::fs::version_path_from_hash(&repo, hash.to_string()).exists())
+        .count();
+    if num_missing > 0 {
+        return Err(OxenHttpError::BadRequest(
+            format!("{num_missing} of the requested versions do not exist").into(),
+        ));
+    }
+
+    let stream = stream_blocking(move |writer| pack_versions(&repo, &request.hashes, writer));
+
+    Ok(HttpResponse::Ok()
+        .content_type(TAR_ZSTD_CONTENT_TYPE)
+        .streaming(stream))
+}
+
+/// Run `write` on a blocking thread and stream what it writes as a response body. An
+/// error after the first bytes went out ends the stream with that error.
+pub(crate) fn stream_blocking<F>(
+    write: F,
+) -> impl futures_util::Stream<Item = Result<Bytes, std::io::Error>>
+where
+    F: FnOnce(BufWriter<ChannelWriter>) -> Result<(), OxenError> + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel::<Result<Bytes, std::io::Error>>(16);
+    tokio::task::spawn_blocking(move || {
+        let writer = BufWriter::with_capacity(STREAM_BUFFER_SIZE, ChannelWriter { tx: tx.clone() });
+        if let Err(err) = write(writer) {
+            log::error!("Could not stream response: {err}");
+            let _ = tx.blocking_send(Err(std::io::Error::other(err.to_string())));
+        }
+    });
+    futures_util::stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|item| (item, rx))
+    })
+}
+
+/// Unpack each version to a `.part` file, and move it in place once its contents
+/// match the hash it was named by. Versions we already have are skipped.
+fn unpack_versions(
+    repo: &LocalRepository,
+    data: impl Read,
+) -> Result<HashSet<MerkleHash>, OxenError> {
+    let decoder = zstd::stream::read::Decoder::new(data)?;
+    let mut archive = tar::Archive::new(decoder);
+    let mut hashes = HashSet::new();
+    for file in archive.entries()? {
+        let mut file = file?;
+        // The name becomes a path, only accept hashes
+        let name = file.path()?.to_string_lossy().to_string();
+        let hash = MerkleHash::from_str(&name)?;
+
+        let version_path = util::fs::version_path_from_hash(repo, hash.to_string());
+        if !version_path.exists() {
+            if let Some(parent) = version_path.parent() {
+                util::fs::create_dir_all(parent)?;
+            }
+            let part_path = version_path.with_extension("part");
+            file.unpack(&part_path)?;
+            let contents_hash = util::hasher::u128_hash_file_contents(&part_path)?;
+            if MerkleHash::new(contents_hash) != hash {
+                util::fs::remove_file(&part_path)?;
+                return Err(OxenError::basic_str(format!(
+                    "Contents of version {hash} do not match its hash"
+                )));
+            }
+            std::fs::rename(&part_path, &version_path)?;
+        }
+        hashes.insert(hash);
+    }
+    Ok(hashes)
+}
+
+fn pack_versions(
+    repo: &LocalRepository,
+    hashes: &HashSet<MerkleHash>,
+    writer: impl Write,
+) -> Result<(), OxenError> {
+    let encoder = zstd::stream::write::Encoder::new(writer, 0)?;
+    let mut tar = tar::Builder::new(encoder);
+    for hash in hashes {
+        // Versions are sent plain, the client checks them against their hash
+        let version_path = util::fs::version_path_from_hash(repo, hash.to_string());
+        let plain_path = util::encryption::plain_path(repo, &version_path)?;
+        tar.append_path_with_name(plain_path.path(), hash.to_string())?;
+    }
+    tar.into_inner()?.finish()?.flush()?;
+    Ok(())
+}
+
+// Hands what is written to the response stream, fails once the client went away
+pub(crate) struct ChannelWriter {
+    tx: mpsc::Sender<Result<Bytes, std::io::Error>>,
+}
+
+impl Write for ChannelWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.tx
+            .blocking_send(Ok(Bytes::copy_from_slice(buf)))
+            .map_err(|_| std::io::Error::from(std::io::ErrorKind::BrokenPipe))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::body::to_bytes;
+    use actix_web::{http, web, App};
+    use std::collections::HashSet;
+    use std::str::FromStr;
+
+    use liboxen::error::OxenError;
+    use liboxen::model::MerkleHash;
+    use liboxen::util;
+    use liboxen::view::tree::merkle_hashes::MerkleHashes;
+    use liboxen::view::MerkleHashesResponse;
+
+    use crate::app_data::OxenAppData;
+    use crate::controllers;
+    use crate::test;
+
+    #[actix_web::test]
+    async fn test_controllers_versions_batch_upload_and_download() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let queue = test::init_queue();
+        let namespace = "Testing-Namespace";
+        let repo_name = "Testing-Name";
+        let repo = test::create_local_repo(&sync_dir, namespace, repo_name)?;
+
+        // Pack two files the way the client does
+        let contents = ["first label", "second label"];
+        let encoder = zstd::stream::write::Encoder::new(Vec::new(), 0)?;
+        let mut tar = tar::Builder::new(encoder);
+        let mut hashes = HashSet::new();
+        for content in contents {
+            let path = repo.path.join(format!("{}.txt", hashes.len()));
+            util::fs::write_to_path(&path, content)?;
+            let hash = MerkleHash::from_str(&util::hasher::hash_file_contents(&path)?)?;
+            tar.append_path_with_name(&path, hash.to_string())?;
+            hashes.insert(hash);
+        }
+        let body = tar.into_inner()?.finish()?;
+
+        let uri = format!("/oxen/{namespace}/{repo_name}/versions/batch");
+        let app = actix_web::test::init_service(
+            App::new()
+                .app_data(OxenAppData::new(sync_dir.clone(), queue))
+                .route(
+                    "/oxen/{namespace}/{repo_name}/versions/batch",
+                    web::post().to(controllers::versions::upload_batch),
+                )
+                .route(
+                    "/oxen/{namespace}/{repo_name}/versions/batch",
+                    web::get().to(controllers::versions::download_batch),
+                ),
+        )
+        .await;
+
+        let req = actix_web::test::TestRequest::post()
+            .uri(&uri)
+            .set_payload(body)
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        let bytes = to_bytes(resp.into_body()).await.unwrap();
+        let response: MerkleHashesResponse = serde_json::from_slice(&bytes)?;
+        assert_eq!(response.hashes, hashes);
+        for hash in &hashes {
+            assert!(util::fs::version_path_from_hash(&repo, hash.to_string()).exists());
+        }
+
+        // Download them again
+        let req = actix_web::test::TestRequest::get()
+            .uri(&uri)
+            .set_json(MerkleHashes {
+                hashes: hashes.clone(),
+            })
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        let bytes = to_bytes(resp.into_body()).await.unwrap();
+        let decoder = zstd::stream::read::Decoder::new(&bytes[..])?;
+        let mut archive = tar::Archive::new(decoder);
+        let mut received = HashSet::new();
+        for file in archive.entries()? {
+            let file = file?;
+            received.insert(MerkleHash::from_str(&file.path()?.to_string_lossy())?);
+        }
+        assert_eq!(received, hashes);
+
+        // Files whose contents don't match their name are rejected
+        let encoder = zstd::stream::write::Encoder::new(Vec::new(), 0)?;
+        let mut tar = tar::Builder::new(encoder);
+        let path = repo.path.join("bad.txt");
+        util::fs::write_to_path(&path, "not what the hash says")?;
+        tar.append_path_with_name(&path, MerkleHash::new(u128::MAX).to_string())?;
+        let req = actix_web::test::TestRequest::post()
+            .uri(&uri)
+            .set_payload(tar.into_inner()?.finish()?)
+            .to_request();
+        let resp = actix_web::test::call_service(&app, req).await;
+        assert!(!resp.status().is_success());
+        assert!(
+            !util::fs::version_path_from_hash(&repo, MerkleHash::new(u128::MAX).to_string())
+                .exists()
+        );
+
+        util::fs::remove_dir_all(sync_dir)?;
+        Ok(())
+    }
+}
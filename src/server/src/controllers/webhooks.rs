@@ -0,0 +1,144 @@
+use crate::errors::OxenHttpError;
+use crate::helpers::get_repo;
+use crate::params::{app_data, path_param};
+
+use actix_web::{HttpRequest, HttpResponse};
+
+use liboxen::model::NewWebhook;
+use liboxen::repositories;
+use liboxen::view::webhook::{ListWebhooksResponse, WebhookResponse};
+use liboxen::view::StatusMessage;
+
+pub async fn index(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let webhooks = repositories::webhooks::list(&repository)?
+        .into_iter()
+        .map(|webhook| webhook.redacted())
+        .collect();
+    Ok(HttpResponse::Ok().json(ListWebhooksResponse {
+        status: StatusMessage::resource_found(),
+        webhooks,
+    }))
+}
+
+pub async fn show(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let webhook_id = path_param(&req, "webhook_id")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let webhook =
+        repositories::webhooks::get(&repository, &webhook_id)?.ok_or(OxenHttpError::NotFound)?;
+    Ok(HttpResponse::Ok().json(WebhookResponse {
+        status: StatusMessage::resource_found(),
+        webhook: webhook.redacted(),
+    }))
+}
+
+/// Add a webhook, the body is a NewWebhook
+pub async fn create(req: HttpRequest, body: String) -> Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    let data: NewWebhook = serde_json::from_str(&body)
+        .map_err(|err| OxenHttpError::BadRequest(format!("{:?}", err).into()))?;
+    let webhook = repositories::webhooks::create(&repository, &data)
+        .map_err(|err| OxenHttpError::BadRequest(err.to_string().into()))?;
+    Ok(HttpResponse::Ok().json(WebhookResponse {
+        status: StatusMessage::resource_created(),
+        webhook: webhook.redacted(),
+    }))
+}
+
+pub async fn delete(req: HttpRequest) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let name = path_param(&req, "repo_name")?;
+    let webhook_id = path_param(&req, "webhook_id")?;
+    let repository = get_repo(&app_data.path, namespace, name)?;
+
+    if repositories::webhooks::get(&repository, &webhook_id)?.is_none() {
+        return Err(OxenHttpError::NotFound);
+    }
+    let webhook = repositories::webhooks::delete(&repository, &webhook_id)?;
+    Ok(HttpResponse::Ok().json(WebhookResponse {
+        status: StatusMessage::resource_deleted(),
+        webhook: webhook.redacted(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::body::to_bytes;
+    use actix_web::http;
+
+    use liboxen::error::OxenError;
+    use liboxen::model::WebhookEvent;
+    use liboxen::repositories;
+    use liboxen::util;
+    use liboxen::view::webhook::{ListWebhooksResponse, WebhookResponse};
+
+    use crate::controllers;
+    use crate::test;
+
+    #[actix_web::test]
+    async fn test_controllers_webhooks_create_list_delete() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let queue = test::init_queue();
+        let namespace = "Testing-Namespace";
+        let name = "Testing-Webhooks";
+        let repo = test::create_local_repo(&sync_dir, namespace, name)?;
+
+        let uri = format!("/oxen/{namespace}/{name}/webhooks");
+        let req = test::repo_request(&sync_dir, queue.clone(), &uri, namespace, name);
+        let body = r#"{"url": "https://ci.example.com/oxen", "secret": "shh", "events": ["push"]}"#;
+        let resp = controllers::webhooks::create(req, body.to_string())
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        let body = to_bytes(resp.into_body()).await.unwrap();
+        let resp: WebhookResponse = serde_json::from_str(std::str::from_utf8(&body).unwrap())?;
+        assert_eq!(resp.webhook.events, vec![WebhookEvent::Push]);
+        // The secret is stored but never returned
+        assert!(resp.webhook.secret.is_none());
+        let stored = repositories::webhooks::get(&repo, &resp.webhook.id)?.unwrap();
+        assert_eq!(stored.secret, Some("shh".to_string()));
+
+        let req = test::repo_request(&sync_dir, queue.clone(), &uri, namespace, name);
+        let resp = controllers::webhooks::create(req, r#"{"url": "not a url"}"#.to_string()).await;
+        assert!(resp.is_err());
+
+        let req = test::repo_request(&sync_dir, queue.clone(), &uri, namespace, name);
+        let resp = controllers::webhooks::index(req).await.unwrap();
+        let body = to_bytes(resp.into_body()).await.unwrap();
+        let list: ListWebhooksResponse = serde_json::from_str(std::str::from_utf8(&body).unwrap())?;
+        assert_eq!(list.webhooks.len(), 1);
+
+        let id = stored.id.clone();
+        let uri = format!("/oxen/{namespace}/{name}/webhooks/{id}");
+        let req = test::repo_request_with_param(
+            &sync_dir,
+            queue,
+            &uri,
+            namespace,
+            name,
+            "webhook_id",
+            id,
+        );
+        let resp = controllers::webhooks::delete(req).await.unwrap();
+        assert_eq!(resp.status(), http::StatusCode::OK);
+        assert!(repositories::webhooks::list(&repo)?.is_empty());
+
+        // cleanup
+        util::fs::remove_dir_all(sync_dir)?;
+
+        Ok(())
+    }
+}
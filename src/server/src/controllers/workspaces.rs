@@ -1,14 +1,26 @@
+use crate::auth::scopes;
 use crate::errors::OxenHttpError;
 use crate::helpers::get_repo;
-use crate::params::{app_data, path_param};
+use crate::mirrors;
+use crate::params::{app_data, path_param, PageNumQuery};
+use crate::quotas;
+use crate::webhooks;
 
+use liboxen::constants;
 use liboxen::error::OxenError;
-use liboxen::model::NewCommitBody;
+use liboxen::model::diff::AddRemoveModifyCounts;
+use liboxen::model::{NewCommitBody, Schema, StagedEntryStatus, WebhookEvent};
+use liboxen::opts::DFOpts;
 use liboxen::repositories;
-use liboxen::view::workspaces::{ListWorkspaceResponseView, NewWorkspace, WorkspaceResponse};
-use liboxen::view::{CommitResponse, StatusMessage, WorkspaceResponseView};
+use liboxen::util;
+use liboxen::view::workspaces::{
+    ListWorkspaceResponseView, NewWorkspace, WorkspaceDataFrameDiff, WorkspaceDiff,
+    WorkspaceDiffResponse, WorkspaceFileChange, WorkspaceResponse,
+};
+use liboxen::view::{CommitResponse, JsonDataFrameViews, StatusMessage, WorkspaceResponseView};
 
-use actix_web::{HttpRequest, HttpResponse};
+use actix_web::{web, HttpRequest, HttpResponse};
+use std::path::PathBuf;
 
 pub mod changes;
 pub mod data_frames;
@@ -132,10 +144,27 @@ pub async fn commit(req: HttpRequest, body: String) -> Result<HttpResponse, Oxen
     };
 
     let workspace = repositories::workspaces::get(&repo, &workspace_id)?;
+    let pusher = scopes::user_email(&req);
+    repositories::branch_protection::check_commit(
+        &repo,
+        &branch_name,
+        &data.message,
+        pusher.as_deref(),
+    )?;
 
     match repositories::workspaces::commit(&workspace, &data, &branch_name) {
         Ok(commit) => {
             log::debug!("workspace::commit ✅ success! commit {:?}", commit);
+            quotas::record(&repo, &branch_name, Some(&commit));
+            mirrors::sync_on_push(app_data, &repo);
+            webhooks::notify(
+                &repo,
+                &namespace,
+                &repo_name,
+                WebhookEvent::WorkspaceCommit,
+                Some(&branch_name),
+                Some(&commit),
+            );
             Ok(HttpResponse::Ok().json(CommitResponse {
                 status: StatusMessage::resource_created(),
                 commit,
@@ -148,3 +177,67 @@ pub async fn commit(req: HttpRequest, body: String) -> Result<HttpResponse, Oxen
         }
     }
 }
+
+/// Preview what committing the workspace would change under a path. Data frames that are
+/// indexed in the workspace also list their added, modified and removed rows.
+pub async fn diff(
+    req: HttpRequest,
+    query: web::Query<PageNumQuery>,
+) -> actix_web::Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let workspace_id = path_param(&req, "workspace_id")?;
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+    let path = PathBuf::from(path_param(&req, "path")?);
+
+    let workspace = repositories::workspaces::get(&repo, workspace_id)?;
+    let staged = repositories::workspaces::status::status_from_dir(&workspace, &path)?;
+    let mut files: Vec<WorkspaceFileChange> = staged
+        .staged_files
+        .into_iter()
+        .filter(|(file, entry)| {
+            file.starts_with(&path) && entry.status != StagedEntryStatus::Unmodified
+        })
+        .map(|(path, entry)| WorkspaceFileChange {
+            path,
+            status: entry.status,
+        })
+        .collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let data_frame = if util::fs::is_tabular(&path)
+        && repositories::workspaces::data_frames::is_indexed(&workspace, &path)?
+    {
+        let diff_df = repositories::workspaces::data_frames::diff(&workspace, &path)?;
+        let counts = if diff_df.is_empty() {
+            AddRemoveModifyCounts {
+                added: 0,
+                removed: 0,
+                modified: 0,
+            }
+        } else {
+            AddRemoveModifyCounts::from_diff_df(&diff_df)?
+        };
+
+        let mut opts = DFOpts::empty();
+        opts.page = Some(query.page.unwrap_or(constants::DEFAULT_PAGE_NUM));
+        opts.page_size = Some(query.page_size.unwrap_or(constants::DEFAULT_PAGE_SIZE));
+        let schema = Schema::from_polars(&diff_df.schema());
+        Some(WorkspaceDataFrameDiff {
+            counts,
+            rows: JsonDataFrameViews::from_df_and_opts(diff_df, schema, &opts),
+        })
+    } else {
+        None
+    };
+
+    Ok(HttpResponse::Ok().json(WorkspaceDiffResponse {
+        status: StatusMessage::resource_found(),
+        diff: WorkspaceDiff {
+            path,
+            files,
+            data_frame,
+        },
+    }))
+}
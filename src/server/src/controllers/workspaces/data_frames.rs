@@ -19,6 +19,7 @@ use liboxen::view::json_data_frame_view::WorkspaceJsonDataFrameViewResponse;
 use liboxen::view::{JsonDataFrameViewResponse, JsonDataFrameViews, StatusMessage};
 
 pub mod columns;
+pub mod reviews;
 pub mod rows;
 
 pub async fn get_by_resource(
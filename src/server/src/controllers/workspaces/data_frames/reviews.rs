@@ -0,0 +1,103 @@
+use std::path::PathBuf;
+
+use crate::errors::OxenHttpError;
+use crate::helpers::get_repo;
+use crate::params::{app_data, path_param, ReviewQuery};
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use liboxen::constants;
+use liboxen::model::Schema;
+use liboxen::opts::DFOpts;
+use liboxen::repositories;
+use liboxen::view::data_frames::{RowReviewStatus, RowReviewsBody, RowReviewsResponse};
+use liboxen::view::entries::ResourceVersion;
+use liboxen::view::{JsonDataFrameViewResponse, JsonDataFrameViews, StatusMessage};
+
+/// The rows of a workspace data frame with their review columns, optionally filtered by status
+pub async fn list(
+    req: HttpRequest,
+    query: web::Query<ReviewQuery>,
+) -> Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let workspace_id = path_param(&req, "workspace_id")?;
+
+    let repo = get_repo(&app_data.path, &namespace, &repo_name)?;
+    let file_path = PathBuf::from(path_param(&req, "path")?);
+    let workspace = repositories::workspaces::get(&repo, &workspace_id)?;
+
+    let status = match &query.status {
+        Some(status) => Some(RowReviewStatus::from_string(status)?),
+        None => None,
+    };
+    let mut opts = DFOpts::empty();
+    opts.page = Some(query.page.unwrap_or(constants::DEFAULT_PAGE_NUM));
+    opts.page_size = Some(query.page_size.unwrap_or(constants::DEFAULT_PAGE_SIZE));
+
+    let count =
+        repositories::workspaces::data_frames::reviews::count(&workspace, &file_path, status)?;
+    let df = repositories::workspaces::data_frames::reviews::query(
+        &workspace, &file_path, status, &opts,
+    )?;
+    let schema = Schema::from_polars(&df.schema());
+
+    Ok(HttpResponse::Ok().json(JsonDataFrameViewResponse {
+        status: StatusMessage::resource_found(),
+        data_frame: JsonDataFrameViews::from_df_and_opts_unpaginated(df, schema, count, &opts),
+        commit: None, // Not at a committed state
+        resource: Some(ResourceVersion {
+            path: file_path.to_string_lossy().to_string(),
+            version: workspace.commit.id.to_string(),
+        }),
+        derived_resource: None,
+    }))
+}
+
+/// Set the review of many rows, either all of the reviews are saved or none are
+pub async fn update(req: HttpRequest, body: String) -> Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let workspace_id = path_param(&req, "workspace_id")?;
+
+    let repo = get_repo(&app_data.path, &namespace, &repo_name)?;
+    let file_path = PathBuf::from(path_param(&req, "path")?);
+
+    let body: RowReviewsBody = serde_json::from_str(&body)?;
+    let workspace = repositories::workspaces::get(&repo, &workspace_id)?;
+    log::debug!(
+        "review {} rows {}/{} -> {}/{:?}",
+        body.reviews.len(),
+        namespace,
+        repo_name,
+        workspace_id,
+        file_path
+    );
+
+    let reviews =
+        repositories::workspaces::data_frames::reviews::set(&workspace, &file_path, &body.reviews)?;
+    Ok(HttpResponse::Ok().json(RowReviewsResponse {
+        status: StatusMessage::resource_updated(),
+        reviews,
+    }))
+}
+
+/// Clear the review of a row so it is pending again
+pub async fn delete(req: HttpRequest) -> Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let workspace_id = path_param(&req, "workspace_id")?;
+    let row_id = path_param(&req, "row_id")?;
+
+    let repo = get_repo(&app_data.path, &namespace, &repo_name)?;
+    let file_path = PathBuf::from(path_param(&req, "path")?);
+    let workspace = repositories::workspaces::get(&repo, &workspace_id)?;
+
+    repositories::workspaces::data_frames::reviews::delete(&workspace, &file_path, &row_id)?;
+    Ok(HttpResponse::Ok().json(StatusMessage::resource_deleted()))
+}
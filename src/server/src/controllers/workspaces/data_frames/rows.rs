@@ -10,7 +10,10 @@ use liboxen::model::data_frame::DataFrameSchemaSize;
 use liboxen::model::Schema;
 use liboxen::opts::DFOpts;
 use liboxen::repositories;
-use liboxen::view::json_data_frame_view::{BatchUpdateResponse, JsonDataFrameRowResponse};
+use liboxen::view::data_frames::{BatchDeleteRowsBody, BatchUpdateRowsBody};
+use liboxen::view::json_data_frame_view::{
+    BatchUpdateResponse, JsonDataFrameRowResponse, JsonDataFrameRowsResponse,
+};
 use liboxen::view::{JsonDataFrameView, JsonDataFrameViews, StatusMessage};
 
 pub async fn create(req: HttpRequest, bytes: Bytes) -> Result<HttpResponse, OxenHttpError> {
@@ -311,3 +314,88 @@ pub async fn batch_update(req: HttpRequest, bytes: Bytes) -> Result<HttpResponse
 
     Ok(HttpResponse::Ok().json(responses))
 }
+
+/// Update many rows in a single transaction, either all of the updates apply or none do
+pub async fn batch_update_rows(
+    req: HttpRequest,
+    body: String,
+) -> Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let workspace_id = path_param(&req, "workspace_id")?;
+
+    let repo = get_repo(&app_data.path, &namespace, &repo_name)?;
+    let file_path = PathBuf::from(path_param(&req, "path")?);
+
+    let body: BatchUpdateRowsBody = serde_json::from_str(&body)?;
+    let workspace = repositories::workspaces::get(&repo, &workspace_id)?;
+    log::debug!(
+        "batch update {} rows {}/{} -> {}/{:?}",
+        body.rows.len(),
+        namespace,
+        repo_name,
+        workspace_id,
+        file_path
+    );
+
+    let df = repositories::workspaces::data_frames::rows::update_rows(
+        &repo, &workspace, &file_path, &body.rows,
+    )?;
+    let diff = repositories::workspaces::data_frames::rows::get_row_diff(&workspace, &file_path)?;
+
+    let schema = Schema::from_polars(&df.schema());
+    Ok(HttpResponse::Ok().json(JsonDataFrameRowsResponse {
+        status: StatusMessage::resource_updated(),
+        diff: Some(diff),
+        data_frame: JsonDataFrameViews {
+            source: DataFrameSchemaSize::from_df(&df, &schema),
+            view: JsonDataFrameView::from_df_opts(df, schema, &DFOpts::empty()),
+        },
+    }))
+}
+
+/// Delete many rows in a single transaction, either all of the rows are removed or none are
+pub async fn batch_delete_rows(
+    req: HttpRequest,
+    body: String,
+) -> Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let workspace_id = path_param(&req, "workspace_id")?;
+
+    let repo = get_repo(&app_data.path, &namespace, &repo_name)?;
+    let file_path = PathBuf::from(path_param(&req, "path")?);
+
+    let body: BatchDeleteRowsBody = serde_json::from_str(&body)?;
+    let workspace = repositories::workspaces::get(&repo, &workspace_id)?;
+    log::debug!(
+        "batch delete {} rows {}/{} -> {}/{:?}",
+        body.row_ids.len(),
+        namespace,
+        repo_name,
+        workspace_id,
+        file_path
+    );
+
+    let df = repositories::workspaces::data_frames::rows::delete_rows(
+        &repo,
+        &workspace,
+        &file_path,
+        &body.row_ids,
+    )?;
+    let diff = repositories::workspaces::data_frames::rows::get_row_diff(&workspace, &file_path)?;
+
+    let schema = Schema::from_polars(&df.schema());
+    Ok(HttpResponse::Ok().json(JsonDataFrameRowsResponse {
+        status: StatusMessage::resource_deleted(),
+        diff: Some(diff),
+        data_frame: JsonDataFrameViews {
+            source: DataFrameSchemaSize::from_df(&df, &schema),
+            view: JsonDataFrameView::from_df_opts(df, schema, &DFOpts::empty()),
+        },
+    }))
+}
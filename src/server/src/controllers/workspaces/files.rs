@@ -8,13 +8,15 @@ use liboxen::model::metadata::metadata_image::ImgResize;
 use liboxen::model::Workspace;
 use liboxen::repositories;
 use liboxen::util;
+use liboxen::view::workspaces::{CompleteUploadBody, MoveFileBody};
 use liboxen::view::{FilePathsResponse, StatusMessage};
 
 use actix_web::{web, HttpRequest, HttpResponse};
 
 use actix_multipart::Multipart;
 use actix_web::Error;
-use futures_util::TryStreamExt as _;
+use futures_util::{StreamExt as _, TryStreamExt as _};
+use serde::Deserialize;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
@@ -79,6 +81,96 @@ pub async fn add(req: HttpRequest, payload: Multipart) -> Result<HttpResponse, O
     }))
 }
 
+#[derive(Deserialize, Debug)]
+pub struct UploadChunkQuery {
+    hash: String, // hash of the chunk, so corrupt parts are rejected and sent again
+}
+
+/// Save one part of a large file, the parts are combined when the upload is completed
+pub async fn upload_chunk(
+    req: HttpRequest,
+    mut chunk: web::Payload,
+    query: web::Query<UploadChunkQuery>,
+) -> Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let workspace_id = path_param(&req, "workspace_id")?;
+    let upload_id = path_param(&req, "upload_id")?;
+    let chunk_num: usize = path_param(&req, "chunk_num")?
+        .parse()
+        .map_err(|_| OxenHttpError::BadRequest("Invalid chunk number".to_string().into()))?;
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+    let workspace = repositories::workspaces::get(&repo, workspace_id)?;
+
+    let mut bytes = web::BytesMut::new();
+    while let Some(item) = chunk.next().await {
+        bytes.extend_from_slice(&item.map_err(actix_web::Error::from)?);
+    }
+    log::debug!(
+        "upload_chunk got chunk {} of upload {} with {} bytes",
+        chunk_num,
+        upload_id,
+        bytes.len()
+    );
+
+    match repositories::workspaces::files::save_upload_chunk(
+        &workspace,
+        &upload_id,
+        chunk_num,
+        &bytes,
+        &query.hash,
+    ) {
+        Ok(_) => Ok(HttpResponse::Ok().json(StatusMessage::resource_created())),
+        Err(err) => {
+            log::error!("upload_chunk could not save chunk {}: {}", chunk_num, err);
+            Ok(HttpResponse::BadRequest().json(StatusMessage::error(err.to_string())))
+        }
+    }
+}
+
+/// Combine the parts of a large file, verify its hash and stage it in the workspace
+pub async fn complete_upload(
+    req: HttpRequest,
+    body: String,
+) -> Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let workspace_id = path_param(&req, "workspace_id")?;
+    let upload_id = path_param(&req, "upload_id")?;
+    let directory = PathBuf::from(path_param(&req, "path")?);
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+    let workspace = repositories::workspaces::get(&repo, workspace_id)?;
+
+    let body: CompleteUploadBody = serde_json::from_str(&body)?;
+    let file_name = sanitize_filename::sanitize(&body.file_name);
+    log::debug!(
+        "complete_upload {} assembling {} chunks into {:?}",
+        upload_id,
+        body.total_chunks,
+        directory.join(&file_name)
+    );
+
+    match repositories::workspaces::files::complete_upload(
+        &workspace,
+        &upload_id,
+        &directory,
+        &file_name,
+        body.total_chunks,
+        &body.hash,
+    ) {
+        Ok(path) => Ok(HttpResponse::Ok().json(FilePathsResponse {
+            status: StatusMessage::resource_created(),
+            paths: vec![path],
+        })),
+        Err(err) => {
+            log::error!("complete_upload could not assemble {}: {}", upload_id, err);
+            Ok(HttpResponse::BadRequest().json(StatusMessage::error(err.to_string())))
+        }
+    }
+}
+
 pub async fn delete(req: HttpRequest) -> Result<HttpResponse, OxenHttpError> {
     let app_data = app_data(&req)?;
     let namespace = path_param(&req, "namespace")?;
@@ -101,6 +193,31 @@ pub async fn delete(req: HttpRequest) -> Result<HttpResponse, OxenHttpError> {
     }
 }
 
+/// Stage a move of a committed file, without downloading or re-uploading it
+pub async fn mv(req: HttpRequest, body: String) -> Result<HttpResponse, OxenHttpError> {
+    let app_data = app_data(&req)?;
+    let namespace = path_param(&req, "namespace")?;
+    let repo_name = path_param(&req, "repo_name")?;
+    let workspace_id = path_param(&req, "workspace_id")?;
+    let repo = get_repo(&app_data.path, namespace, repo_name)?;
+    let path = PathBuf::from(path_param(&req, "path")?);
+    let workspace = repositories::workspaces::get(&repo, workspace_id)?;
+
+    let body: MoveFileBody = serde_json::from_str(&body)?;
+    log::debug!("mv {:?} -> {:?}", path, body.new_path);
+
+    match repositories::workspaces::files::mv(&workspace, &path, &body.new_path) {
+        Ok(new_path) => Ok(HttpResponse::Ok().json(FilePathsResponse {
+            status: StatusMessage::resource_updated(),
+            paths: vec![new_path],
+        })),
+        Err(err) => {
+            log::error!("mv could not move {:?}: {}", path, err);
+            Ok(HttpResponse::BadRequest().json(StatusMessage::error(err.to_string())))
+        }
+    }
+}
+
 async fn save_parts(
     workspace: &Workspace,
     directory: &Path,
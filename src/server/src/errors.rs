@@ -1,10 +1,12 @@
+use actix_web::http::header::{HeaderName, HeaderValue};
 use actix_web::{error, http::StatusCode, HttpResponse};
 use derive_more::{Display, Error};
 use liboxen::constants;
 use liboxen::error::{OxenError, PathBufError, StringError};
 use liboxen::model::Branch;
 use liboxen::view::http::{
-    MSG_BAD_REQUEST, MSG_CONFLICT, MSG_INTERNAL_SERVER_ERROR, MSG_RESOURCE_ALREADY_EXISTS,
+    MSG_BAD_REQUEST, MSG_BRANCH_PROTECTED, MSG_CONFLICT, MSG_FORBIDDEN, MSG_INTERNAL_SERVER_ERROR,
+    MSG_PUSH_REJECTED, MSG_QUOTA_EXCEEDED, MSG_REPO_FROZEN, MSG_RESOURCE_ALREADY_EXISTS,
     MSG_RESOURCE_NOT_FOUND, MSG_UPDATE_REQUIRED, STATUS_ERROR,
 };
 use liboxen::view::{SQLParseError, StatusMessage, StatusMessageDescription};
@@ -12,10 +14,14 @@ use liboxen::view::{SQLParseError, StatusMessage, StatusMessageDescription};
 use serde_json::json;
 use std::io;
 
+/// Response header with the stable code of the OxenError behind an error response
+pub const ERROR_CODE_HEADER: &str = "x-oxen-error-code";
+
 #[derive(Debug, Display, Error)]
 pub enum OxenHttpError {
     InternalServerError,
     BadRequest(StringError),
+    Forbidden(StringError),
     NotFound,
     AppDataDoesNotExist,
     PathParamDoesNotExist(StringError),
@@ -81,6 +87,18 @@ impl error::ResponseError for OxenHttpError {
             }
             OxenHttpError::BadRequest(desc) => HttpResponse::BadRequest()
                 .json(StatusMessageDescription::bad_request(desc.to_string())),
+            OxenHttpError::Forbidden(desc) => {
+                let error_json = json!({
+                    "error": {
+                        "type": MSG_FORBIDDEN,
+                        "title": "Forbidden",
+                        "detail": format!("{}", desc)
+                    },
+                    "status": STATUS_ERROR,
+                    "status_message": MSG_FORBIDDEN,
+                });
+                HttpResponse::Forbidden().json(error_json)
+            }
             OxenHttpError::SQLParseError(query) => {
                 HttpResponse::BadRequest().json(SQLParseError::new(query.to_string()))
             }
@@ -186,7 +204,7 @@ impl error::ResponseError for OxenHttpError {
             }
             OxenHttpError::InternalOxenError(error) => {
                 // Catch specific OxenError's and return the appropriate response
-                match error {
+                let mut response = match error {
                     OxenError::RepoNotFound(repo) => {
                         log::debug!("Repo not found: {}", repo);
 
@@ -195,6 +213,66 @@ impl error::ResponseError for OxenHttpError {
                             repo
                         )))
                     }
+                    OxenError::BranchProtected(msg) => {
+                        log::debug!("Branch protected: {}", msg);
+
+                        let error_json = json!({
+                            "error": {
+                                "type": MSG_BRANCH_PROTECTED,
+                                "title": "Branch is protected",
+                                "detail": msg.to_string().trim()
+                            },
+                            "status": STATUS_ERROR,
+                            "status_message": MSG_BRANCH_PROTECTED,
+                        });
+
+                        HttpResponse::Forbidden().json(error_json)
+                    }
+                    OxenError::QuotaExceeded(msg) => {
+                        log::debug!("Quota exceeded: {}", msg);
+
+                        let error_json = json!({
+                            "error": {
+                                "type": MSG_QUOTA_EXCEEDED,
+                                "title": "Storage quota exceeded",
+                                "detail": msg.to_string().trim()
+                            },
+                            "status": STATUS_ERROR,
+                            "status_message": MSG_QUOTA_EXCEEDED,
+                        });
+
+                        HttpResponse::InsufficientStorage().json(error_json)
+                    }
+                    OxenError::PushRejected(msg) => {
+                        log::debug!("Push rejected: {}", msg);
+
+                        let error_json = json!({
+                            "error": {
+                                "type": MSG_PUSH_REJECTED,
+                                "title": "Push rejected",
+                                "detail": msg.to_string().trim()
+                            },
+                            "status": STATUS_ERROR,
+                            "status_message": MSG_PUSH_REJECTED,
+                        });
+
+                        HttpResponse::Conflict().json(error_json)
+                    }
+                    OxenError::RepoFrozen(msg) => {
+                        log::debug!("Repo frozen: {}", msg);
+
+                        let error_json = json!({
+                            "error": {
+                                "type": MSG_REPO_FROZEN,
+                                "title": "Repository is frozen",
+                                "detail": msg.to_string()
+                            },
+                            "status": STATUS_ERROR,
+                            "status_message": MSG_REPO_FROZEN,
+                        });
+
+                        HttpResponse::Locked().json(error_json)
+                    }
                     OxenError::ResourceNotFound(resource) => {
                         log::debug!("Resource not found: {}", resource);
 
@@ -413,12 +491,62 @@ impl error::ResponseError for OxenHttpError {
                         });
                         HttpResponse::InternalServerError().json(error_json)
                     }
+                    OxenError::SchemaMismatch(msg) => {
+                        log::debug!("Schema mismatch: {}", msg);
+                        let error_json = json!({
+                            "error": {
+                                "type": "schema_error",
+                                "title": "Schema does not match",
+                                "detail": msg.to_string().trim()
+                            },
+                            "code": error.code(),
+                            "status": STATUS_ERROR,
+                            "status_message": MSG_BAD_REQUEST,
+                        });
+                        HttpResponse::BadRequest().json(error_json)
+                    }
+                    OxenError::ParseError(parse_error) => {
+                        log::debug!("Parse error: {}", parse_error);
+                        let error_json = json!({
+                            "error": {
+                                "type": "parse_error",
+                                "title": "Could not parse input",
+                                "detail": parse_error.message,
+                                "path": parse_error.path,
+                                "line": parse_error.line,
+                            },
+                            "code": error.code(),
+                            "status": STATUS_ERROR,
+                            "status_message": MSG_BAD_REQUEST,
+                        });
+                        HttpResponse::BadRequest().json(error_json)
+                    }
+                    OxenError::NetworkError(_) | OxenError::RemoteRejected(_) => {
+                        log::error!("Upstream error: {}", error);
+                        let error_json = json!({
+                            "error": {
+                                "type": "upstream_error",
+                                "title": "Could not reach upstream remote",
+                                "detail": error.to_string()
+                            },
+                            "code": error.code(),
+                            "status": STATUS_ERROR,
+                            "status_message": MSG_INTERNAL_SERVER_ERROR,
+                        });
+                        HttpResponse::BadGateway().json(error_json)
+                    }
                     err => {
                         log::error!("Internal server error: {:?}", err);
                         HttpResponse::InternalServerError()
                             .json(StatusMessage::internal_server_error())
                     }
-                }
+                };
+                // Every error response carries its stable code, see OxenError::code
+                response.headers_mut().insert(
+                    HeaderName::from_static(ERROR_CODE_HEADER),
+                    HeaderValue::from_static(error.code()),
+                );
+                response
             }
         }
     }
@@ -429,6 +557,7 @@ impl error::ResponseError for OxenHttpError {
             OxenHttpError::AppDataDoesNotExist => StatusCode::BAD_REQUEST,
             OxenHttpError::PathParamDoesNotExist(_) => StatusCode::BAD_REQUEST,
             OxenHttpError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            OxenHttpError::Forbidden(_) => StatusCode::FORBIDDEN,
             OxenHttpError::SQLParseError(_) => StatusCode::BAD_REQUEST,
             OxenHttpError::NotFound => StatusCode::NOT_FOUND,
             OxenHttpError::NotQueryable => StatusCode::BAD_REQUEST,
@@ -444,6 +573,14 @@ impl error::ResponseError for OxenHttpError {
                 OxenError::RepoNotFound(_) => StatusCode::NOT_FOUND,
                 OxenError::RevisionNotFound(_) => StatusCode::NOT_FOUND,
                 OxenError::InvalidSchema(_) => StatusCode::BAD_REQUEST,
+                OxenError::RepoFrozen(_) => StatusCode::LOCKED,
+                OxenError::BranchProtected(_) => StatusCode::FORBIDDEN,
+                OxenError::QuotaExceeded(_) => StatusCode::INSUFFICIENT_STORAGE,
+                OxenError::PushRejected(_) => StatusCode::CONFLICT,
+                OxenError::SchemaMismatch(_) => StatusCode::BAD_REQUEST,
+                OxenError::ParseError(_) => StatusCode::BAD_REQUEST,
+                OxenError::NetworkError(_) => StatusCode::BAD_GATEWAY,
+                OxenError::RemoteRejected(_) => StatusCode::BAD_GATEWAY,
                 _ => StatusCode::INTERNAL_SERVER_ERROR,
             },
         }
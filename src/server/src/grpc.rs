@@ -0,0 +1,571 @@
+//! gRPC sync service, started next to the HTTP server with `--grpc-port`.
+//!
+//! Serves the tree and version transfers of the /tree and /versions routes over one
+//! streaming connection, see `proto/sync.proto` in liboxen. Tokens, roles, token
+//! restrictions and frozen repositories are checked the same way as for HTTP requests.
+
+use liboxen::api::grpc::proto::sync_service_server::{SyncService, SyncServiceServer};
+use liboxen::api::grpc::proto::{
+    Chunk, DownloadNodesRequest, EntryChunk, EntryRequest, MerkleHashes, UploadEntriesResponse,
+};
+use liboxen::api::grpc::{
+    read_version_chunks, store_version, CHUNK_SIZE, NAMESPACE_METADATA_KEY, REPO_NAME_METADATA_KEY,
+};
+use liboxen::core::versions::MinOxenVersion;
+use liboxen::error::OxenError;
+use liboxen::model::{LocalRepository, MerkleHash};
+use liboxen::repositories;
+use liboxen::util;
+
+use std::collections::HashSet;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::str::FromStr;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::{Stream, StreamExt};
+use tonic::metadata::MetadataMap;
+use tonic::{Request, Response, Status, Streaming};
+
+use crate::auth::access_keys::AccessKeyManager;
+use crate::auth::roles::{self, Role};
+use crate::auth::scopes;
+use crate::controllers;
+use crate::errors::OxenHttpError;
+use crate::helpers::get_repo;
+
+// Largest message we accept, hash lists for big repositories don't fit the 4MB default
+const MAX_MESSAGE_SIZE: usize = 256 * 1024 * 1024;
+
+type ChunkStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send>>;
+
+/// Start the sync service on `addr`, runs until the server shuts down
+pub async fn serve(
+    addr: SocketAddr,
+    sync_dir: PathBuf,
+    enable_auth: bool,
+) -> Result<(), OxenError> {
+    log::info!("gRPC sync service listening on {addr}");
+    tonic::transport::Server::builder()
+        .add_service(service(sync_dir, enable_auth))
+        .serve(addr)
+        .await
+        .map_err(|err| OxenError::basic_str(format!("gRPC server error: {err}")))
+}
+
+pub fn service(sync_dir: PathBuf, enable_auth: bool) -> SyncServiceServer<OxenSyncService> {
+    SyncServiceServer::new(OxenSyncService {
+        sync_dir,
+        enable_auth,
+    })
+    .max_decoding_message_size(MAX_MESSAGE_SIZE)
+    .max_encoding_message_size(MAX_MESSAGE_SIZE)
+}
+
+pub struct OxenSyncService {
+    sync_dir: PathBuf,
+    enable_auth: bool,
+}
+
+fn oxen_status(err: OxenError) -> Status {
+    match err {
+        OxenError::RepoNotFound(_)
+        | OxenError::ResourceNotFound(_)
+        | OxenError::RevisionNotFound(_) => Status::not_found(err.to_string()),
+        OxenError::ParseIntError(_) => Status::invalid_argument(err.to_string()),
+        _ => {
+            log::error!("gRPC sync error: {err:?}");
+            Status::internal(err.to_string())
+        }
+    }
+}
+
+fn http_status(err: OxenHttpError) -> Status {
+    match err {
+        OxenHttpError::Forbidden(desc) => Status::permission_denied(desc.to_string()),
+        OxenHttpError::NotFound => Status::not_found("Not found"),
+        OxenHttpError::InternalOxenError(err) => oxen_status(err),
+        err => Status::internal(format!("{err:?}")),
+    }
+}
+
+fn metadata_str<'a>(metadata: &'a MetadataMap, key: &str) -> Result<&'a str, Status> {
+    metadata
+        .get(key)
+        .and_then(|value| value.to_str().ok())
+        .ok_or_else(|| Status::invalid_argument(format!("Missing {key} metadata")))
+}
+
+fn parse_hashes(hashes: &[String]) -> Result<HashSet<MerkleHash>, Status> {
+    hashes
+        .iter()
+        .map(|h| {
+            MerkleHash::from_str(h)
+                .map_err(|_| Status::invalid_argument(format!("Invalid hash {h}")))
+        })
+        .collect()
+}
+
+fn to_strings(hashes: HashSet<MerkleHash>) -> Vec<String> {
+    hashes.into_iter().map(|h| h.to_string()).collect()
+}
+
+async fn blocking<T, F>(f: F) -> Result<T, Status>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, OxenError> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|err| Status::internal(err.to_string()))?
+        .map_err(oxen_status)
+}
+
+impl OxenSyncService {
+    /// Repository the call is for, after checking the token has the `required` role on
+    /// it. Writes also need a token without restrictions and a repository that is not frozen.
+    fn authorize(&self, metadata: &MetadataMap, required: Role) -> Result<LocalRepository, Status> {
+        let namespace = metadata_str(metadata, NAMESPACE_METADATA_KEY)?;
+        let repo_name = metadata_str(metadata, REPO_NAME_METADATA_KEY)?;
+
+        let claim = if self.enable_auth {
+            let token = metadata_str(metadata, "authorization")?
+                .strip_prefix("Bearer ")
+                .ok_or_else(|| Status::unauthenticated("Expected a bearer token"))?;
+            let keygen = AccessKeyManager::new_read_only(&self.sync_dir).map_err(oxen_status)?;
+            Some(
+                keygen
+                    .get_valid_claim(token)
+                    .ok_or_else(|| Status::unauthenticated("unauthorized"))?,
+            )
+        } else {
+            None
+        };
+        let is_write = required > Role::Read;
+        if let Some(claim) = claim.as_ref().filter(|_| is_write) {
+            if let Some(restriction) = scopes::RESTRICTIONS.iter().find(|s| claim.has_scope(s)) {
+                return Err(Status::permission_denied(format!(
+                    "Token has the {restriction} scope and cannot push"
+                )));
+            }
+        }
+        roles::authorize_claim(
+            &self.sync_dir,
+            claim.as_ref(),
+            namespace,
+            Some(repo_name),
+            required,
+        )
+        .map_err(http_status)?;
+
+        let repo = get_repo(&self.sync_dir, namespace, repo_name).map_err(http_status)?;
+        if matches!(repo.min_version(), MinOxenVersion::V0_10_0) {
+            return Err(Status::failed_precondition(
+                "gRPC sync needs a repository on v0.19.0 or later, run `oxen migrate` first",
+            ));
+        }
+        if is_write && repositories::freeze::is_frozen(&repo) {
+            return Err(Status::failed_precondition(
+                "This repository is frozen and does not accept changes until it is thawed",
+            ));
+        }
+        Ok(repo)
+    }
+}
+
+/// Write one uploaded version to a `.part` file next to its version path, and store it
+/// once the last chunk arrived and the contents match the hash
+struct VersionWriter {
+    repo: LocalRepository,
+    hash: MerkleHash,
+    part_path: PathBuf,
+    // None when we already have the version and skip the upload
+    file: Option<tokio::fs::File>,
+    offset: u64,
+}
+
+impl VersionWriter {
+    async fn create(repo: &LocalRepository, hash: MerkleHash) -> Result<VersionWriter, OxenError> {
+        let version_path = util::fs::version_path_from_hash(repo, hash.to_string());
+        let part_path = version_path.with_extension("part");
+        let file = if version_path.exists() {
+            None
+        } else {
+            if let Some(parent) = version_path.parent() {
+                util::fs::create_dir_all(parent)?;
+            }
+            Some(tokio::fs::File::create(&part_path).await?)
+        };
+        Ok(VersionWriter {
+            repo: repo.clone(),
+            hash,
+            part_path,
+            file,
+            offset: 0,
+        })
+    }
+
+    async fn write(&mut self, chunk: &EntryChunk) -> Result<(), Status> {
+        if chunk.offset != self.offset {
+            return Err(Status::invalid_argument(format!(
+                "Expected offset {} of version {}, got {}",
+                self.offset, self.hash, chunk.offset
+            )));
+        }
+        if let Some(file) = self.file.as_mut() {
+            file.write_all(&chunk.data).await?;
+        }
+        self.offset += chunk.data.len() as u64;
+        Ok(())
+    }
+
+    async fn finish(mut self) -> Result<(), Status> {
+        let Some(mut file) = self.file.take() else {
+            return Ok(());
+        };
+        file.flush().await?;
+        drop(file);
+
+        let part_path = self.part_path.clone();
+        let hash = blocking(move || util::hasher::hash_file_contents(&part_path)).await?;
+        if MerkleHash::from_str(&hash).ok() != Some(self.hash) {
+            let _ = tokio::fs::remove_file(&self.part_path).await;
+            return Err(Status::data_loss(format!(
+                "Contents of version {} do not match its hash",
+                self.hash
+            )));
+        }
+        // Compressed and encrypted the same way as versions pulled over HTTP
+        let VersionWriter {
+            repo,
+            hash,
+            part_path,
+            ..
+        } = self;
+        blocking(move || store_version(&repo, &hash, &part_path)).await
+    }
+
+    async fn abort(mut self) {
+        if self.file.take().is_some() {
+            let _ = tokio::fs::remove_file(&self.part_path).await;
+        }
+    }
+}
+
+async fn abort_upload(writer: &mut Option<VersionWriter>) {
+    if let Some(writer) = writer.take() {
+        writer.abort().await;
+    }
+}
+
+/// Send the plain contents of a version in chunks as they are read, returns false once
+/// the client went away. Blocks on reading and decoding the version.
+fn send_version(
+    repo: &LocalRepository,
+    hash: &MerkleHash,
+    tx: &mpsc::Sender<Result<EntryChunk, Status>>,
+) -> bool {
+    let path = util::fs::version_path_from_hash(repo, hash.to_string());
+    if !path.exists() {
+        let status = Status::not_found(format!("Version {hash} not found"));
+        let _ = tx.blocking_send(Err(status));
+        return false;
+    }
+    match read_version_chunks(repo, hash, |chunk| tx.blocking_send(Ok(chunk)).is_ok()) {
+        Ok(sent) => sent,
+        Err(err) => {
+            let _ = tx.blocking_send(Err(oxen_status(err)));
+            false
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl SyncService for OxenSyncService {
+    async fn list_missing_nodes(
+        &self,
+        request: Request<MerkleHashes>,
+    ) -> Result<Response<MerkleHashes>, Status> {
+        let repo = self.authorize(request.metadata(), Role::Read)?;
+        let hashes = parse_hashes(&request.get_ref().hashes)?;
+        log::debug!("grpc list_missing_nodes checking {} node ids", hashes.len());
+        let missing =
+            blocking(move || repositories::tree::list_missing_node_hashes(&repo, &hashes)).await?;
+        Ok(Response::new(MerkleHashes {
+            hashes: to_strings(missing),
+        }))
+    }
+
+    async fn list_missing_files(
+        &self,
+        request: Request<MerkleHashes>,
+    ) -> Result<Response<MerkleHashes>, Status> {
+        let repo = self.authorize(request.metadata(), Role::Read)?;
+        let commit_ids = parse_hashes(&request.get_ref().hashes)?;
+        log::debug!(
+            "grpc list_missing_files checking {} commit ids",
+            commit_ids.len()
+        );
+        let missing = blocking(move || {
+            repositories::tree::list_missing_file_hashes_from_commits(&repo, &commit_ids)
+        })
+        .await?;
+        Ok(Response::new(MerkleHashes {
+            hashes: to_strings(missing),
+        }))
+    }
+
+    async fn upload_nodes(
+        &self,
+        request: Request<Streaming<Chunk>>,
+    ) -> Result<Response<MerkleHashes>, Status> {
+        let repo = self.authorize(request.metadata(), Role::Write)?;
+        let mut stream = request.into_inner();
+        let mut bytes: Vec<u8> = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            bytes.extend(chunk?.data);
+        }
+        let hashes = blocking(move || controllers::tree::unpack_nodes(&repo, &bytes)).await?;
+        Ok(Response::new(MerkleHashes {
+            hashes: to_strings(hashes),
+        }))
+    }
+
+    type DownloadNodesStream = ChunkStream<Chunk>;
+
+    async fn download_nodes(
+        &self,
+        request: Request<DownloadNodesRequest>,
+    ) -> Result<Response<Self::DownloadNodesStream>, Status> {
+        let repo = self.authorize(request.metadata(), Role::Read)?;
+        let DownloadNodesRequest {
+            base_commit_id,
+            head_commit_id,
+        } = request.into_inner();
+        let buffer = blocking(move || {
+            controllers::tree::compress_tree_nodes(&repo, &base_commit_id, head_commit_id)
+        })
+        .await?;
+
+        let chunks: Vec<Result<Chunk, Status>> = buffer
+            .chunks(CHUNK_SIZE)
+            .map(|data| {
+                Ok(Chunk {
+                    data: data.to_vec(),
+                })
+            })
+            .collect();
+        Ok(Response::new(Box::pin(tokio_stream::iter(chunks))))
+    }
+
+    async fn upload_entries(
+        &self,
+        request: Request<Streaming<EntryChunk>>,
+    ) -> Result<Response<UploadEntriesResponse>, Status> {
+        let repo = self.authorize(request.metadata(), Role::Write)?;
+        let mut stream = request.into_inner();
+        let mut writer: Option<VersionWriter> = None;
+        let mut response = UploadEntriesResponse {
+            num_entries: 0,
+            num_bytes: 0,
+        };
+
+        while let Some(chunk) = stream.next().await {
+            let chunk = match chunk {
+                Ok(chunk) => chunk,
+                Err(status) => {
+                    abort_upload(&mut writer).await;
+                    return Err(status);
+                }
+            };
+            let hash = MerkleHash::from_str(&chunk.hash)
+                .map_err(|_| Status::invalid_argument(format!("Invalid hash {}", chunk.hash)))?;
+            if writer.as_ref().map(|w| w.hash != hash).unwrap_or(true) {
+                if let Some(unfinished) = writer.take() {
+                    unfinished.abort().await;
+                    return Err(Status::invalid_argument(format!(
+                        "Version {hash} started before the last chunk of the one before it"
+                    )));
+                }
+                writer = Some(
+                    VersionWriter::create(&repo, hash)
+                        .await
+                        .map_err(oxen_status)?,
+                );
+            }
+
+            // Set above
+            let current = writer.as_mut().unwrap();
+            if let Err(status) = current.write(&chunk).await {
+                abort_upload(&mut writer).await;
+                return Err(status);
+            }
+            response.num_bytes += chunk.data.len() as u64;
+            if chunk.last {
+                writer.take().unwrap().finish().await?;
+                response.num_entries += 1;
+            }
+        }
+
+        if let Some(unfinished) = writer.take() {
+            let hash = unfinished.hash;
+            unfinished.abort().await;
+            return Err(Status::invalid_argument(format!(
+                "Upload ended before the last chunk of version {hash}"
+            )));
+        }
+        log::debug!(
+            "grpc upload_entries wrote {} versions, {} bytes",
+            response.num_entries,
+            response.num_bytes
+        );
+        Ok(Response::new(response))
+    }
+
+    type DownloadEntriesStream = ChunkStream<EntryChunk>;
+
+    async fn download_entries(
+        &self,
+        request: Request<Streaming<EntryRequest>>,
+    ) -> Result<Response<Self::DownloadEntriesStream>, Status> {
+        let repo = self.authorize(request.metadata(), Role::Read)?;
+        let mut requests = request.into_inner();
+        let (tx, rx) = mpsc::channel(16);
+
+        // Keep reading requests while the files of the ones before them stream back
+        tokio::spawn(async move {
+            while let Some(request) = requests.next().await {
+                let hashes = match request.map(|r| parse_hashes(&r.hashes)) {
+                    Ok(Ok(hashes)) => hashes,
+                    Ok(Err(status)) | Err(status) => {
+                        let _ = tx.send(Err(status)).await;
+                        return;
+                    }
+                };
+                for hash in hashes {
+                    let (repo, tx) = (repo.clone(), tx.clone());
+                    let sent = tokio::task::spawn_blocking(move || send_version(&repo, &hash, &tx))
+                        .await
+                        .unwrap_or(false);
+                    if !sent {
+                        return;
+                    }
+                }
+            }
+        });
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::str::FromStr;
+
+    use liboxen::api::client::grpc::SyncClient;
+    use liboxen::command;
+    use liboxen::error::OxenError;
+    use liboxen::model::{FreezeOpts, MerkleHash, Remote, RemoteRepository};
+    use liboxen::repositories;
+    use liboxen::util;
+    use tokio_stream::wrappers::TcpListenerStream;
+
+    use crate::grpc;
+    use crate::test;
+
+    #[tokio::test]
+    async fn test_grpc_push_and_pull_nodes_and_entries() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let namespace = "Testing-Namespace";
+        let name = "Testing-Grpc";
+        let mut remote = test::create_local_repo(&sync_dir, namespace, name)?;
+        let mut local = test::create_local_repo(&sync_dir, "local", name)?;
+        // Versions go over the wire plain, each side stores them its own way
+        command::config::set_compression_level(&mut local, Some(3))?;
+        command::config::set_compression_level(&mut remote, Some(19))?;
+        let path = local.path.join("README.md");
+        util::fs::write_to_path(&path, "Hello over gRPC")?;
+        repositories::add(&local, &path)?;
+        let commit = repositories::commit(&local, "first commit")?;
+        let commit_hash = MerkleHash::from_str(&commit.id)?;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let addr = listener.local_addr()?;
+        let service = grpc::service(sync_dir.clone(), false);
+        tokio::spawn(async move {
+            tonic::transport::Server::builder()
+                .add_service(service)
+                .serve_with_incoming(TcpListenerStream::new(listener))
+                .await
+        });
+
+        let remote_repo = RemoteRepository {
+            namespace: namespace.to_string(),
+            name: name.to_string(),
+            remote: Remote {
+                name: String::from("origin"),
+                url: format!("http://{addr}/{namespace}/{name}"),
+            },
+            min_version: None,
+            is_empty: true,
+        };
+        let mut client = SyncClient::connect(format!("http://{addr}"), &remote_repo).await?;
+
+        // Push the tree, then the file it is missing
+        let missing = client
+            .list_missing_node_hashes(HashSet::from([commit_hash]))
+            .await?;
+        assert!(missing.contains(&commit_hash));
+        let tree = repositories::tree::get_by_commit(&local, &commit)?;
+        let mut nodes = HashSet::new();
+        tree.walk_tree_without_leaves(|node| {
+            nodes.insert(node.clone());
+        });
+        let created = client.create_nodes(&local, nodes).await?;
+        assert!(created.contains(&commit_hash));
+
+        let missing_files = client
+            .list_missing_file_hashes_from_commits(HashSet::from([commit_hash]))
+            .await?;
+        assert_eq!(missing_files.len(), 1);
+        let (num_entries, _) = client.upload_entries(&local, missing_files.clone()).await?;
+        assert_eq!(num_entries, 1);
+        assert!(client
+            .list_missing_file_hashes_from_commits(HashSet::from([commit_hash]))
+            .await?
+            .is_empty());
+        let file_hash = missing_files.iter().next().unwrap().to_string();
+        let version_path = util::fs::version_path_from_hash(&remote, &file_hash);
+        assert!(util::compression::is_compressed(&version_path));
+        let plain_path = util::encryption::plain_path(&remote, &version_path)?;
+        assert_eq!(
+            util::fs::read_from_path(plain_path.path())?,
+            "Hello over gRPC"
+        );
+        drop(plain_path);
+
+        // Pull it all back into an empty repository
+        let clone = test::create_local_repo(&sync_dir, "clone", name)?;
+        client.download_nodes(&clone, &commit.id, None).await?;
+        assert!(repositories::tree::get_node_by_id(&clone, &commit_hash)?.is_some());
+        assert_eq!(
+            client
+                .download_entries(&clone, missing_files.clone())
+                .await?,
+            1
+        );
+        let version_path = util::fs::version_path_from_hash(&clone, file_hash);
+        assert!(util::encryption::is_plain(&version_path));
+        assert_eq!(util::fs::read_from_path(&version_path)?, "Hello over gRPC");
+
+        // Writes are rejected once the repository is frozen
+        repositories::freeze::freeze(&remote, &FreezeOpts::default())?;
+        assert!(client.upload_entries(&local, missing_files).await.is_err());
+
+        util::fs::remove_dir_all(sync_dir)?;
+        Ok(())
+    }
+}
@@ -1,22 +1,29 @@
 use dotenv::dotenv;
 use dotenv::from_filename;
 use liboxen::config::UserConfig;
+use liboxen::core::v0_19_0::index::tree_cache;
 use liboxen::model::User;
 use liboxen::util;
 
 pub mod app_data;
 pub mod auth;
+pub mod config;
 pub mod controllers;
 pub mod errors;
+#[cfg(feature = "grpc")]
+pub mod grpc;
 pub mod helpers;
 pub mod middleware;
+pub mod mirrors;
 pub mod params;
 pub mod queue_poller;
 pub mod queues;
+pub mod quotas;
 pub mod routes;
 pub mod services;
 pub mod tasks;
 pub mod test;
+pub mod webhooks;
 
 extern crate log;
 extern crate lru;
@@ -88,6 +95,12 @@ async fn main() -> std::io::Result<()> {
                         .short('a')
                         .help("Start the server with token-based authentication enforced")
                         .action(clap::ArgAction::SetTrue),
+                )
+                .arg(
+                    Arg::new("grpc-port")
+                        .long("grpc-port")
+                        .help("Also serve the gRPC sync api on this port, needs the grpc feature")
+                        .action(clap::ArgAction::Set),
                 ),
         )
         .subcommand(
@@ -117,6 +130,23 @@ async fn main() -> std::io::Result<()> {
                         .default_missing_value("always")
                         .help("Where to write the output config file to give to the user")
                         .action(clap::ArgAction::Set),
+                )
+                .arg(
+                    Arg::new("scope")
+                        .long("scope")
+                        .short('s')
+                        .help(
+                            "Scope to grant the access token, ex: commits:state. Can be repeated.",
+                        )
+                        .value_parser(auth::scopes::ALL)
+                        .action(clap::ArgAction::Append),
+                )
+                .arg(
+                    Arg::new("resource")
+                        .long("resource")
+                        .short('r')
+                        .help("Only allow the token to be used on this namespace or namespace/repo. Can be repeated.")
+                        .action(clap::ArgAction::Append),
                 ),
         );
     let matches = command.get_matches();
@@ -134,13 +164,38 @@ async fn main() -> std::io::Result<()> {
                     println!("Syncing to directory: {sync_dir}");
                     let enable_auth = sub_matches.get_flag("auth");
 
+                    // Keep hot merkle subtrees in memory, OXEN_TREE_CACHE_NODES=0 turns it off
+                    let tree_cache_nodes = env::var("OXEN_TREE_CACHE_NODES")
+                        .ok()
+                        .and_then(|n| n.parse::<usize>().ok())
+                        .unwrap_or(tree_cache::DEFAULT_TREE_CACHE_NODES);
+                    tree_cache::set_max_nodes(tree_cache_nodes);
+                    println!("Tree cache size: {tree_cache_nodes} nodes");
+
                     log::debug!("initializing queue");
-                    let queue = queue_poller::init_queue();
+                    let queue = queue_poller::init_queue(Path::new(&sync_dir));
                     log::debug!("initialized queue");
                     let data = app_data::OxenAppData::new(PathBuf::from(sync_dir), queue.clone());
                     // Poll for post-commit tasks in background
                     log::debug!("initialized app data, spawning polling worker");
                     tokio::spawn(async move { queue_poller::poll_queue(queue.clone()).await });
+                    // Reload the server config when it changes, without a restart
+                    let server_config = data.config.clone();
+                    println!("Server config: {:?}", server_config.path());
+                    tokio::spawn(async move {
+                        config::watch(server_config, config::RELOAD_INTERVAL).await
+                    });
+                    // Sync the mirrors that are on a schedule
+                    let mirror_data = data.clone();
+                    tokio::spawn(async move {
+                        mirrors::schedule(mirror_data, mirrors::SCHEDULE_INTERVAL).await
+                    });
+
+                    // Serve the gRPC sync api next to the http api
+                    if let Some(grpc_port) = sub_matches.get_one::<String>("grpc-port") {
+                        let grpc_port: u16 = grpc_port.parse::<u16>().expect(INVALID_PORT_MSG);
+                        start_grpc(host, grpc_port, data.path.clone(), enable_auth)?;
+                    }
 
                     HttpServer::new(move || {
                         App::new()
@@ -159,6 +214,13 @@ async fn main() -> std::io::Result<()> {
                                 "/api/namespaces/{namespace}",
                                 web::get().to(controllers::namespaces::show),
                             )
+                            .service(
+                                web::resource("/api/roles")
+                                    .route(web::get().to(controllers::roles::index))
+                                    .route(web::put().to(controllers::roles::update))
+                                    .route(web::delete().to(controllers::roles::delete)),
+                            )
+                            .route("/api/tokens", web::post().to(controllers::tokens::create))
                             .route(
                                 "/api/migrations/{migration_tstamp}",
                                 web::get().to(controllers::migrations::list_unmigrated),
@@ -172,7 +234,8 @@ async fn main() -> std::io::Result<()> {
                             .wrap(Logger::default())
                             .wrap(Logger::new("user agent is %a %{User-Agent}i"))
                     })
-                    .bind((host.to_owned(), port))?
+                    // Serve HTTP/1.1 and HTTP/2 over plain http, so batch transfers multiplex
+                    .bind_auto_h2c((host.to_owned(), port))?
                     .run()
                     .await
                 }
@@ -196,7 +259,17 @@ async fn main() -> std::io::Result<()> {
                             name: name.to_string(),
                             email: email.to_string(),
                         };
-                        match keygen.create(&new_user) {
+                        let scopes: Vec<String> = sub_matches
+                            .get_many::<String>("scope")
+                            .unwrap_or_default()
+                            .cloned()
+                            .collect();
+                        let resources: Vec<String> = sub_matches
+                            .get_many::<String>("resource")
+                            .unwrap_or_default()
+                            .cloned()
+                            .collect();
+                        match keygen.create_with_scopes(&new_user, &scopes, &resources) {
                             Ok((user, token)) => {
                                 let cfg = UserConfig::from_user(&user);
                                 match cfg.save(Path::new(output)) {
@@ -224,3 +297,30 @@ async fn main() -> std::io::Result<()> {
         _ => unreachable!(), // If all subcommands are defined above, anything else is unreachabe!()
     }
 }
+
+#[cfg(feature = "grpc")]
+fn start_grpc(host: &str, port: u16, sync_dir: PathBuf, enable_auth: bool) -> std::io::Result<()> {
+    let addr = format!("{host}:{port}")
+        .parse::<std::net::SocketAddr>()
+        .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidInput, err))?;
+    println!("gRPC sync api running on {addr}");
+    tokio::spawn(async move {
+        if let Err(err) = grpc::serve(addr, sync_dir, enable_auth).await {
+            log::error!("{err}");
+        }
+    });
+    Ok(())
+}
+
+#[cfg(not(feature = "grpc"))]
+fn start_grpc(
+    _host: &str,
+    _port: u16,
+    _sync_dir: PathBuf,
+    _enable_auth: bool,
+) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "--grpc-port needs oxen-server built with the grpc feature",
+    ))
+}
@@ -1 +1,81 @@
+use actix_web::body::{BoxBody, MessageBody};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::Method;
+use actix_web::middleware::Next;
+use actix_web::HttpResponse;
 
+use liboxen::repositories;
+use liboxen::view::http::{MSG_REPO_FROZEN, STATUS_ERROR};
+use serde_json::json;
+
+use crate::app_data::OxenAppData;
+use crate::auth::{roles, scopes};
+
+/// Reject requests that write to a frozen repository with 423 Locked.
+/// Reads, and the freeze endpoints used to thaw the repository, go through.
+pub async fn reject_writes_to_frozen_repos(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, actix_web::Error> {
+    let is_read = matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+    if is_read || req.path().trim_end_matches('/').ends_with("/freeze") {
+        return Ok(next.call(req).await?.map_into_boxed_body());
+    }
+
+    let frozen = match (
+        req.app_data::<OxenAppData>(),
+        req.match_info().get("namespace"),
+        req.match_info().get("repo_name"),
+    ) {
+        (Some(app_data), Some(namespace), Some(repo_name)) => {
+            matches!(
+                repositories::get_by_namespace_and_name(&app_data.path, namespace, repo_name),
+                Ok(Some(repo)) if repositories::freeze::is_frozen(&repo)
+            )
+        }
+        _ => false,
+    };
+
+    if frozen {
+        let error_json = json!({
+            "error": {
+                "type": MSG_REPO_FROZEN,
+                "title": "Repository is frozen",
+                "detail": "This repository is frozen and does not accept changes until it is thawed"
+            },
+            "status": STATUS_ERROR,
+            "status_message": MSG_REPO_FROZEN,
+        });
+        return Ok(req.into_response(HttpResponse::Locked().json(error_json)));
+    }
+
+    Ok(next.call(req).await?.map_into_boxed_body())
+}
+
+/// Reject requests the token is restricted from making, or from users that do not have
+/// the role the endpoint needs on the repository, see `auth::roles`.
+pub async fn enforce_roles(
+    req: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<BoxBody>, actix_web::Error> {
+    if let (Some(namespace), Some(repo_name)) = (
+        req.match_info().get("namespace"),
+        req.match_info().get("repo_name"),
+    ) {
+        let repo_root = format!("/{namespace}/{repo_name}");
+        let path = req
+            .path()
+            .split_once(&repo_root)
+            .map(|(_, path)| path)
+            .unwrap_or_default();
+        scopes::check_restrictions(req.request(), path)?;
+        if roles::always_needs_admin(req.method(), path) {
+            roles::authorize_admin(req.request(), namespace, Some(repo_name))?;
+        } else {
+            let required = roles::required_role(req.method(), path);
+            roles::authorize(req.request(), namespace, Some(repo_name), required)?;
+        }
+    }
+
+    Ok(next.call(req).await?.map_into_boxed_body())
+}
@@ -0,0 +1,60 @@
+//! Keep the mirrors of repositories in sync.
+//!
+//! Mirrors without an interval are synced by a task queued after every push. Scheduled
+//! mirrors are checked every `SCHEDULE_INTERVAL` and a task is queued for the ones that
+//! are due.
+
+use std::time::Duration;
+
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+
+use crate::app_data::OxenAppData;
+use crate::tasks::mirror::MirrorTask;
+use crate::tasks::Task;
+
+pub const SCHEDULE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Queue a sync of the mirrors that follow every push
+pub fn sync_on_push(app_data: &OxenAppData, repo: &LocalRepository) {
+    match repositories::mirrors::list_on_push(repo) {
+        Ok(mirrors) => queue_sync(app_data, repo, mirrors.into_iter().map(|m| m.id).collect()),
+        Err(err) => log::error!("Could not read mirrors for {:?}: {}", repo.path, err),
+    }
+}
+
+pub fn queue_sync(app_data: &OxenAppData, repo: &LocalRepository, mirror_ids: Vec<String>) {
+    if mirror_ids.is_empty() {
+        return;
+    }
+    let mut queue = app_data.queue.clone();
+    queue.push(Task::Mirror(MirrorTask {
+        repo: repo.clone(),
+        mirror_ids,
+    }));
+}
+
+/// Check every repository for scheduled mirrors that are due
+pub async fn schedule(app_data: OxenAppData, interval: Duration) {
+    let mut ticker = tokio::time::interval(interval);
+    loop {
+        ticker.tick().await;
+        let namespaces = match repositories::list_namespaces(&app_data.path) {
+            Ok(namespaces) => namespaces,
+            Err(err) => {
+                log::error!("Could not list namespaces to sync mirrors: {}", err);
+                continue;
+            }
+        };
+        for namespace in namespaces {
+            for repo in repositories::list_repos_in_namespace(&app_data.path.join(namespace)) {
+                match repositories::mirrors::list_due(&repo) {
+                    Ok(due) => {
+                        queue_sync(&app_data, &repo, due.into_iter().map(|m| m.id).collect())
+                    }
+                    Err(err) => log::error!("Could not read mirrors for {:?}: {}", repo.path, err),
+                }
+            }
+        }
+    }
+}
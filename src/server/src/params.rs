@@ -22,6 +22,24 @@ pub use page_num_query::PageNumVersionQuery;
 pub mod df_opts_query;
 pub use df_opts_query::DFOptsQuery;
 
+pub mod commit_state_query;
+pub use commit_state_query::CommitStateQuery;
+
+pub mod merge_queue_query;
+pub use merge_queue_query::MergeQueueQuery;
+
+pub mod comment_query;
+pub use comment_query::CommentQuery;
+
+pub mod merge_request_query;
+pub use merge_request_query::MergeRequestQuery;
+
+pub mod role_query;
+pub use role_query::RoleQuery;
+
+pub mod review_query;
+pub use review_query::ReviewQuery;
+
 pub fn app_data(req: &HttpRequest) -> Result<&OxenAppData, OxenHttpError> {
     log::debug!(
         "Get user agent from app data (app_data) {:?}",
@@ -0,0 +1,8 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct CommentQuery {
+    pub commit_id: Option<String>,
+    pub path: Option<String>,
+    pub row_hash: Option<String>,
+}
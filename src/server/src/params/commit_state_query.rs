@@ -0,0 +1,7 @@
+use liboxen::model::CommitState;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct CommitStateQuery {
+    pub state: Option<CommitState>,
+}
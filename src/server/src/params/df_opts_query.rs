@@ -4,17 +4,22 @@ use serde::Deserialize;
 
 #[derive(Deserialize, Debug)]
 pub struct DFOptsQuery {
+    pub bbox: Option<String>,
     pub columns: Option<String>,
     pub delimiter: Option<String>,
     pub filter: Option<String>,
+    /// Set to "arrow" to get the data frame as an Arrow IPC stream instead of json
+    pub format: Option<String>,
     pub page_size: Option<usize>,
     pub page: Option<usize>,
     pub row: Option<usize>,
     pub randomize: Option<bool>,
     pub reverse: Option<bool>,
     pub slice: Option<String>,
+    #[serde(alias = "sort")]
     pub sort_by: Option<String>,
     pub sql: Option<String>,
+    pub table: Option<String>,
     pub take: Option<String>,
 }
 
@@ -37,6 +42,7 @@ pub fn parse_opts(query: &web::Query<DFOptsQuery>, filter_ops: &mut DFOpts) -> D
         filter_ops.columns = Some(columns);
     }
 
+    filter_ops.bbox.clone_from(&query.bbox);
     filter_ops.delimiter.clone_from(&query.delimiter);
     filter_ops.page = query.page;
     filter_ops.page_size = query.page_size;
@@ -44,8 +50,16 @@ pub fn parse_opts(query: &web::Query<DFOptsQuery>, filter_ops: &mut DFOpts) -> D
     filter_ops.filter.clone_from(&query.filter);
     filter_ops.should_randomize = query.randomize.unwrap_or(false);
     filter_ops.should_reverse = query.reverse.unwrap_or(false);
-    filter_ops.sort_by.clone_from(&query.sort_by);
+    // A leading - sorts descending, ex: sort=-confidence
+    match query.sort_by.as_deref().map(|s| s.strip_prefix('-')) {
+        Some(Some(sort_by)) => {
+            filter_ops.sort_by = Some(sort_by.to_string());
+            filter_ops.should_reverse = !filter_ops.should_reverse;
+        }
+        _ => filter_ops.sort_by.clone_from(&query.sort_by),
+    }
     filter_ops.sql.clone_from(&query.sql);
+    filter_ops.table.clone_from(&query.table);
     filter_ops.take.clone_from(&query.take);
 
     filter_ops.clone()
@@ -0,0 +1,7 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct MergeRequestQuery {
+    pub status: Option<String>,
+    pub base: Option<String>,
+}
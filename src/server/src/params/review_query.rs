@@ -0,0 +1,9 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct ReviewQuery {
+    /// pending, approved or rejected, all rows if not set
+    pub status: Option<String>,
+    pub page: Option<usize>,
+    pub page_size: Option<usize>,
+}
@@ -0,0 +1,8 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub struct RoleQuery {
+    pub email: String,
+    pub namespace: String,
+    pub repo_name: Option<String>,
+}
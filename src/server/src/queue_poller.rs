@@ -1,20 +1,26 @@
 use liboxen::core::v0_10_0::cache::cacher_status::CacherStatus;
 use liboxen::core::v0_10_0::cache::commit_cacher;
+use liboxen::repositories;
+use liboxen::repositories::post_process::PostProcessStatus;
+use std::path::Path;
 use std::time::Duration;
 use tokio::time::sleep;
 
 use crate::helpers::get_redis_connection;
-use crate::queues::{InMemoryTaskQueue, RedisTaskQueue, TaskQueue};
+use crate::queues::{
+    InMemoryTaskQueue, RedisTaskQueue, RocksDbTaskQueue, TaskQueue, TASK_QUEUE_DIR,
+};
 use crate::tasks::{Runnable, Task};
 
 pub async fn poll_queue(mut queue: TaskQueue) {
     log::debug!("Starting queue poller");
     loop {
         match queue.pop() {
-            Some(task) => {
+            Some((task, ack)) => {
                 log::debug!("Got queue item: {:?}", task);
 
                 // to ensure we don't block the poller, we run the task in an OS thread.
+                let mut task_queue = queue.clone();
                 tokio::task::spawn_blocking(move || {
                     let result = std::panic::catch_unwind(|| {
                         task.run();
@@ -40,8 +46,42 @@ pub async fn poll_queue(mut queue: TaskQueue) {
                                     ),
                                 }
                             }
+                            Task::MergeQueue(merge_queue) => {
+                                match repositories::merge_queue::fail_in_progress(
+                                    &merge_queue.repo,
+                                    &merge_queue.base,
+                                    "Panic in task execution",
+                                ) {
+                                    Ok(_) => log::debug!("Set merging entries to failed status"),
+                                    Err(e) => log::error!(
+                                        "Error setting merging entries to failed status: {:?}",
+                                        e
+                                    ),
+                                }
+                            }
+                            Task::PostProcessCommit(task) => {
+                                match repositories::post_process::set_status(
+                                    &task.repo,
+                                    &task.commit,
+                                    &PostProcessStatus::failed("Panic in task execution"),
+                                ) {
+                                    Ok(_) => log::debug!("Set post process to failed status"),
+                                    Err(e) => log::error!(
+                                        "Error setting post process to failed status: {:?}",
+                                        e
+                                    ),
+                                }
+                            }
+                            Task::Mirror(task) => {
+                                log::error!(
+                                    "Mirror task for repo {:?} panicked, the next push or schedule retries it",
+                                    task.repo.path
+                                );
+                            }
                         }
                     }
+                    // Only done once the task ran, so a restart in between runs it again
+                    task_queue.ack(ack);
                 });
             }
             None => {
@@ -52,16 +92,22 @@ pub async fn poll_queue(mut queue: TaskQueue) {
     }
 }
 
-// If redis connection is available, use redis queue, else in-memory
-pub fn init_queue() -> TaskQueue {
+// If redis connection is available, use redis queue, else a rocksdb queue in the sync dir
+pub fn init_queue(sync_dir: &Path) -> TaskQueue {
     match get_redis_connection() {
         Ok(pool) => {
             println!("connecting to redis established, initializing queue");
             TaskQueue::Redis(RedisTaskQueue { pool })
         }
-        Err(_) => {
-            println!("Failed to connect to Redis. Falling back to in-memory queue.");
-            TaskQueue::InMemory(InMemoryTaskQueue::new())
-        }
+        Err(_) => match RocksDbTaskQueue::new(sync_dir.join(TASK_QUEUE_DIR)) {
+            Ok(queue) => {
+                println!("Failed to connect to Redis. Falling back to rocksdb queue.");
+                TaskQueue::RocksDb(queue)
+            }
+            Err(err) => {
+                println!("Failed to connect to Redis or open rocksdb queue ({err}). Falling back to in-memory queue.");
+                TaskQueue::InMemory(InMemoryTaskQueue::new())
+            }
+        },
     }
 }
@@ -1,14 +1,22 @@
+use rocksdb::{DBWithThreadMode, Direction, IteratorMode, MultiThreaded, WriteBatch};
+use std::path::Path;
 use std::sync::Mutex;
 use std::{collections::VecDeque, sync::Arc};
 
 use crate::tasks::post_push_complete::PostPushComplete;
 use crate::tasks::Task;
 use liboxen::constants::COMMIT_QUEUE_NAME;
+use liboxen::core::db;
+use liboxen::error::OxenError;
+
+/// Dir in the sync dir for the rocksdb queue, used when there is no redis
+pub const TASK_QUEUE_DIR: &str = ".task_queue";
 
 #[derive(Clone)]
 pub enum TaskQueue {
     InMemory(InMemoryTaskQueue),
     Redis(RedisTaskQueue),
+    RocksDb(RocksDbTaskQueue),
 }
 
 impl TaskQueue {
@@ -16,17 +24,30 @@ impl TaskQueue {
         match self {
             TaskQueue::InMemory(queue) => queue.push(task),
             TaskQueue::Redis(queue) => queue.push(task),
+            TaskQueue::RocksDb(queue) => queue.push(task),
         }
     }
 
-    pub fn pop(&mut self) -> Option<Task> {
+    /// Pop the next task, ack it once it has run
+    pub fn pop(&mut self) -> Option<(Task, TaskAck)> {
         match self {
-            TaskQueue::InMemory(queue) => queue.pop(),
-            TaskQueue::Redis(queue) => queue.pop(),
+            TaskQueue::InMemory(queue) => queue.pop().map(|task| (task, TaskAck(None))),
+            TaskQueue::Redis(queue) => queue.pop().map(|task| (task, TaskAck(None))),
+            TaskQueue::RocksDb(queue) => queue.pop().map(|(key, task)| (task, TaskAck(Some(key)))),
+        }
+    }
+
+    pub fn ack(&mut self, ack: TaskAck) {
+        if let (TaskQueue::RocksDb(queue), TaskAck(Some(key))) = (self, ack) {
+            queue.ack(key);
         }
     }
 }
 
+/// Marks a popped task as done, queues that persist tasks keep it until then
+#[derive(Debug)]
+pub struct TaskAck(Option<u64>);
+
 #[derive(Clone)]
 pub struct RedisTaskQueue {
     pub pool: r2d2::Pool<redis::Client>,
@@ -40,9 +61,7 @@ impl RedisTaskQueue {
     fn push(&mut self, task: Task) {
         let mut conn = self.pool.get().unwrap();
 
-        let data: Vec<u8> = match task {
-            Task::PostPushComplete(task) => bincode::serialize(&task).unwrap(),
-        };
+        let data: Vec<u8> = bincode::serialize(&task).unwrap();
 
         let _: isize = redis::cmd("LPUSH")
             .arg(COMMIT_QUEUE_NAME)
@@ -59,16 +78,142 @@ impl RedisTaskQueue {
             .unwrap();
 
         match outcome {
-            Some(data) => {
-                // TODO: Support multiple task types
-                let task: PostPushComplete = bincode::deserialize(&data).unwrap();
-                Some(Task::PostPushComplete(task))
-            }
+            Some(data) => match bincode::deserialize::<Task>(&data) {
+                Ok(task) => Some(task),
+                Err(_) => {
+                    // Items queued before tasks were tagged are all post push tasks
+                    let task: PostPushComplete = bincode::deserialize(&data).unwrap();
+                    Some(Task::PostPushComplete(task))
+                }
+            },
             None => None,
         }
     }
 }
 
+/// Persists the tasks on disk, so queued work survives a restart of the server. Popped
+/// tasks are kept in their own key space until they are acked, and tasks that were still
+/// running when the server stopped are queued again when it starts.
+#[derive(Clone)]
+pub struct RocksDbTaskQueue {
+    db: Arc<DBWithThreadMode<MultiThreaded>>,
+    // Key of the next task, keys are big endian so rocksdb iterates them in push order
+    next_key: Arc<Mutex<u64>>,
+}
+
+// Prefixes of the keys of queued and running tasks
+const QUEUED: u8 = 0;
+const IN_PROGRESS: u8 = 1;
+
+impl RocksDbTaskQueue {
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, OxenError> {
+        let opts = db::key_val::opts::default();
+        let db: DBWithThreadMode<MultiThreaded> =
+            DBWithThreadMode::open(&opts, dunce::simplified(path.as_ref()))?;
+
+        // Tasks that never got acked go back on the queue under the key they had, in
+        // front of the tasks queued after them
+        let mut batch = WriteBatch::default();
+        let mut requeued = 0;
+        for item in db.iterator(IteratorMode::From(&[IN_PROGRESS], Direction::Forward)) {
+            let (key, data) = item?;
+            if key.first() != Some(&IN_PROGRESS) {
+                break;
+            }
+            batch.delete(&key);
+            batch.put(task_key(QUEUED, key_to_u64(&key)), data);
+            requeued += 1;
+        }
+        db.write(batch)?;
+
+        let next_key = match db
+            .iterator(IteratorMode::From(&[IN_PROGRESS], Direction::Reverse))
+            .next()
+        {
+            Some(Ok((key, _))) if key.first() == Some(&QUEUED) => key_to_u64(&key) + 1,
+            _ => 0,
+        };
+        log::debug!(
+            "RocksDbTaskQueue opened {:?} next key {} requeued {}",
+            path.as_ref(),
+            next_key,
+            requeued
+        );
+        Ok(RocksDbTaskQueue {
+            db: Arc::new(db),
+            next_key: Arc::new(Mutex::new(next_key)),
+        })
+    }
+
+    fn push(&mut self, task: Task) {
+        let mut next_key = self.next_key.lock().unwrap();
+        let data: Vec<u8> = bincode::serialize(&task).unwrap();
+        match self.db.put(task_key(QUEUED, *next_key), data) {
+            Ok(_) => *next_key += 1,
+            Err(err) => log::error!("RocksDbTaskQueue could not push {:?}: {:?}", task, err),
+        }
+    }
+
+    fn pop(&mut self) -> Option<(u64, Task)> {
+        // Hold the lock so a push can't reuse the key we are moving
+        let _next_key = self.next_key.lock().unwrap();
+        let (key, data) = match self
+            .db
+            .iterator(IteratorMode::From(&[QUEUED], Direction::Forward))
+            .next()?
+        {
+            Ok(item) => item,
+            Err(err) => {
+                log::error!("RocksDbTaskQueue could not read: {:?}", err);
+                return None;
+            }
+        };
+        if key.first() != Some(&QUEUED) {
+            return None;
+        }
+        let key = key_to_u64(&key);
+        let task = match bincode::deserialize::<Task>(&data) {
+            Ok(task) => task,
+            Err(err) => {
+                log::error!("RocksDbTaskQueue dropping unreadable task: {:?}", err);
+                if let Err(err) = self.db.delete(task_key(QUEUED, key)) {
+                    log::error!("RocksDbTaskQueue could not delete {}: {:?}", key, err);
+                }
+                return None;
+            }
+        };
+
+        // Kept until the task is acked, in case the server stops while it runs
+        let mut batch = WriteBatch::default();
+        batch.delete(task_key(QUEUED, key));
+        batch.put(task_key(IN_PROGRESS, key), data);
+        if let Err(err) = self.db.write(batch) {
+            log::error!("RocksDbTaskQueue could not pop {}: {:?}", key, err);
+            return None;
+        }
+        Some((key, task))
+    }
+
+    fn ack(&mut self, key: u64) {
+        if let Err(err) = self.db.delete(task_key(IN_PROGRESS, key)) {
+            log::error!("RocksDbTaskQueue could not ack {}: {:?}", key, err);
+        }
+    }
+}
+
+fn task_key(prefix: u8, key: u64) -> Vec<u8> {
+    let mut bytes = vec![prefix];
+    bytes.extend_from_slice(&key.to_be_bytes());
+    bytes
+}
+
+fn key_to_u64(key: &[u8]) -> u64 {
+    let mut bytes = [0u8; 8];
+    let len = key.len().min(8);
+    bytes[8 - len..].copy_from_slice(&key[key.len() - len..]);
+    u64::from_be_bytes(bytes)
+}
+
 #[derive(Clone)]
 pub struct InMemoryTaskQueue {
     queue: Arc<Mutex<VecDeque<Task>>>,
@@ -96,3 +241,57 @@ impl Default for InMemoryTaskQueue {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use liboxen::error::OxenError;
+    use liboxen::model::LocalRepository;
+    use liboxen::util;
+
+    use crate::queues::RocksDbTaskQueue;
+    use crate::tasks::merge_queue::MergeQueueTask;
+    use crate::tasks::Task;
+    use crate::test;
+
+    fn task(base: &str) -> Task {
+        Task::MergeQueue(MergeQueueTask {
+            repo: LocalRepository::new("data/test/repo").unwrap(),
+            base: base.to_string(),
+        })
+    }
+
+    fn base(task: Option<(u64, Task)>) -> Option<String> {
+        match task {
+            Some((_, Task::MergeQueue(task))) => Some(task.base),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_rocksdb_queue_is_fifo_and_requeues_unacked_tasks() -> Result<(), OxenError> {
+        let sync_dir = test::get_sync_dir()?;
+        let path = sync_dir.join(crate::queues::TASK_QUEUE_DIR);
+        {
+            let mut queue = RocksDbTaskQueue::new(&path)?;
+            queue.push(task("main"));
+            queue.push(task("dev"));
+            queue.push(task("release"));
+            let (key, popped) = queue.pop().unwrap();
+            assert_eq!(base(Some((key, popped))), Some("main".to_string()));
+            queue.ack(key);
+            // Stops before dev is acked
+            assert_eq!(base(queue.pop()), Some("dev".to_string()));
+        }
+
+        // Reopening runs dev again and picks up where the last queue left off
+        let mut queue = RocksDbTaskQueue::new(&path)?;
+        queue.push(task("hotfix"));
+        assert_eq!(base(queue.pop()), Some("dev".to_string()));
+        assert_eq!(base(queue.pop()), Some("release".to_string()));
+        assert_eq!(base(queue.pop()), Some("hotfix".to_string()));
+        assert!(queue.pop().is_none());
+
+        util::fs::remove_dir_all(sync_dir)?;
+        Ok(())
+    }
+}
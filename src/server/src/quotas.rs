@@ -0,0 +1,60 @@
+//! Enforce the storage quotas in the server config on pushes.
+//!
+//! A branch may only move if the repository stays within its own quota, and the
+//! namespace stays within its quota counting every other repository in it. Usage is
+//! recorded after the branch lands, failures to record are logged.
+
+use bytesize::ByteSize;
+
+use liboxen::error::OxenError;
+use liboxen::model::{Commit, LocalRepository};
+use liboxen::repositories;
+
+use crate::app_data::OxenAppData;
+
+pub fn check(
+    app_data: &OxenAppData,
+    namespace: &str,
+    repo_name: &str,
+    repo: &LocalRepository,
+    branch: &str,
+    commit: &Commit,
+) -> Result<(), OxenError> {
+    let config = app_data.config.get();
+    let repo_quota = config.repo_quota(namespace, repo_name);
+    let namespace_quota = config.namespace_quota(namespace);
+    if repo_quota.is_none() && namespace_quota.is_none() {
+        return Ok(());
+    }
+
+    let usage = repositories::quotas::check(repo, branch, commit, repo_quota.unwrap_or(u64::MAX))?;
+
+    if let Some(quota) = namespace_quota {
+        let mut num_bytes = usage.num_bytes;
+        for other in repositories::list_repos_in_namespace(&app_data.path.join(namespace)) {
+            if other.path != repo.path {
+                num_bytes += repositories::quotas::usage(&other)?.num_bytes;
+            }
+        }
+        if num_bytes > quota {
+            return Err(OxenError::quota_exceeded(format!(
+                "namespace {namespace} would use {} of its {} quota",
+                ByteSize::b(num_bytes),
+                ByteSize::b(quota)
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Record where the branch landed, or that it was deleted if the commit is None
+pub fn record(repo: &LocalRepository, branch: &str, commit: Option<&Commit>) {
+    if let Err(err) = repositories::quotas::record(repo, branch, commit) {
+        log::error!(
+            "Could not record storage usage of {:?} for branch {}: {}",
+            repo.path,
+            branch,
+            err
+        );
+    }
+}
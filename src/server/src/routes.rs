@@ -1,7 +1,9 @@
 use super::controllers;
 
+use actix_web::middleware::from_fn;
 use actix_web::web;
 
+use crate::middleware;
 use crate::services;
 
 pub fn config(cfg: &mut web::ServiceConfig) {
@@ -18,23 +20,33 @@ pub fn config(cfg: &mut web::ServiceConfig) {
                 // we give the resource a name here so it can be used with HttpRequest.url_for
                 .name("repo_root")
                 .route(web::get().to(controllers::repositories::show))
-                .route(web::delete().to(controllers::repositories::delete)),
+                .route(web::delete().to(controllers::repositories::delete))
+                .wrap(from_fn(middleware::enforce_roles)),
         )
         // Repository Services
         .service(
             web::scope("/{namespace}/{repo_name}")
                 .service(services::action())
+                .service(services::branch_protection())
                 .service(services::branches())
                 .service(services::chunk())
+                .service(services::comments())
                 .service(services::commits())
                 .service(services::commits_db())
                 .service(services::compare())
+                .service(services::copy())
                 .service(services::data_frames())
+                .service(services::dataset_cards())
                 .service(services::dir())
                 .service(services::file())
+                .service(services::freeze())
                 .service(services::merge())
+                .service(services::merge_queue())
+                .service(services::merge_requests())
                 .service(services::meta())
+                .service(services::mirrors())
                 .service(services::objects_db())
+                .service(services::releases())
                 .service(services::revisions())
                 .service(services::schemas())
                 .service(services::stats())
@@ -42,6 +54,9 @@ pub fn config(cfg: &mut web::ServiceConfig) {
                 .service(services::transfer())
                 .service(services::tree())
                 .service(services::versions())
-                .service(services::workspace()),
+                .service(services::webhooks())
+                .service(services::workspace())
+                .wrap(from_fn(middleware::reject_writes_to_frozen_repos))
+                .wrap(from_fn(middleware::enforce_roles)),
         );
 }
@@ -1,15 +1,24 @@
 pub mod action;
+pub mod branch_protection;
 pub mod branches;
 pub mod chunk;
+pub mod comments;
 pub mod commits;
 pub mod commits_db;
 pub mod compare;
+pub mod copy;
 pub mod data_frames;
+pub mod dataset_cards;
 pub mod dir;
 pub mod file;
+pub mod freeze;
 pub mod merge;
+pub mod merge_queue;
+pub mod merge_requests;
 pub mod meta;
+pub mod mirrors;
 pub mod objects_db;
+pub mod releases;
 pub mod revisions;
 pub mod schemas;
 pub mod stats;
@@ -17,20 +26,30 @@ pub mod tabular;
 pub mod transfer;
 pub mod tree;
 pub mod versions;
+pub mod webhooks;
 pub mod workspaces;
 
 pub use action::action;
+pub use branch_protection::branch_protection;
 pub use branches::branches;
 pub use chunk::chunk;
+pub use comments::comments;
 pub use commits::commits;
 pub use commits_db::commits_db;
 pub use compare::compare;
+pub use copy::copy;
 pub use data_frames::data_frames;
+pub use dataset_cards::dataset_cards;
 pub use dir::dir;
 pub use file::file;
+pub use freeze::freeze;
 pub use merge::merge;
+pub use merge_queue::merge_queue;
+pub use merge_requests::merge_requests;
 pub use meta::meta;
+pub use mirrors::mirrors;
 pub use objects_db::objects_db;
+pub use releases::releases;
 pub use revisions::revisions;
 pub use schemas::schemas;
 pub use stats::stats;
@@ -38,4 +57,5 @@ pub use tabular::tabular;
 pub use transfer::transfer;
 pub use tree::tree;
 pub use versions::versions;
+pub use webhooks::webhooks;
 pub use workspaces::workspace;
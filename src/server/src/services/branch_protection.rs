@@ -0,0 +1,14 @@
+use actix_web::web;
+use actix_web::Scope;
+
+use crate::controllers;
+
+pub fn branch_protection() -> Scope {
+    web::scope("/branch_protection")
+        .route("", web::get().to(controllers::branch_protection::index))
+        .route("", web::put().to(controllers::branch_protection::update))
+        .route(
+            "/{pattern:.*}",
+            web::delete().to(controllers::branch_protection::delete),
+        )
+}
@@ -0,0 +1,13 @@
+use actix_web::web;
+use actix_web::Scope;
+
+use crate::controllers;
+
+pub fn comments() -> Scope {
+    web::scope("/comments")
+        .route("", web::get().to(controllers::comments::index))
+        .route("", web::post().to(controllers::comments::create))
+        .route("/{id}", web::get().to(controllers::comments::show))
+        .route("/{id}", web::put().to(controllers::comments::update))
+        .route("/{id}", web::delete().to(controllers::comments::delete))
+}
@@ -17,6 +17,18 @@ pub fn commits() -> Scope {
             "/{commit_id}/db_status",
             web::get().to(controllers::commits::commits_db_status),
         )
+        .route(
+            "/{commit_id}/state",
+            web::get().to(controllers::commits::show_state),
+        )
+        .route(
+            "/{commit_id}/state",
+            web::put().to(controllers::commits::update_state),
+        )
+        .route(
+            "/{commit_id}/state",
+            web::delete().to(controllers::commits::delete_state),
+        )
         .route(
             "/{commit_id}/entries_status",
             web::get().to(controllers::commits::entries_status),
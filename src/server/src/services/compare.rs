@@ -25,6 +25,10 @@ pub fn compare() -> Scope {
             "/file/{base_head:.*}",
             web::get().to(controllers::diff::file),
         )
+        .route(
+            "/image/{base_head:.*}",
+            web::get().to(controllers::diff::image),
+        )
         .route(
             "/data_frames/{compare_id}/{path}/{base_head:.*}",
             web::get().to(controllers::diff::get_derived_df),
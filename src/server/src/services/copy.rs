@@ -0,0 +1,8 @@
+use actix_web::web;
+use actix_web::Scope;
+
+use crate::controllers;
+
+pub fn copy() -> Scope {
+    web::scope("/copy").route("", web::post().to(controllers::copy::create))
+}
@@ -0,0 +1,13 @@
+use actix_web::web;
+use actix_web::Scope;
+
+use crate::controllers;
+
+pub fn dataset_cards() -> Scope {
+    web::scope("/dataset_card")
+        .route("", web::get().to(controllers::dataset_cards::show))
+        .route(
+            "/{revision:.*}",
+            web::get().to(controllers::dataset_cards::show),
+        )
+}
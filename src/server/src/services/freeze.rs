@@ -0,0 +1,11 @@
+use actix_web::web;
+use actix_web::Scope;
+
+use crate::controllers;
+
+pub fn freeze() -> Scope {
+    web::scope("/freeze")
+        .route("", web::get().to(controllers::freeze::show))
+        .route("", web::put().to(controllers::freeze::update))
+        .route("", web::delete().to(controllers::freeze::delete))
+}
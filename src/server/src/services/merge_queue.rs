@@ -0,0 +1,12 @@
+use actix_web::web;
+use actix_web::Scope;
+
+use crate::controllers;
+
+pub fn merge_queue() -> Scope {
+    web::scope("/merge_queue")
+        .route("", web::get().to(controllers::merge_queue::index))
+        .route("", web::post().to(controllers::merge_queue::create))
+        .route("/{id}", web::get().to(controllers::merge_queue::show))
+        .route("/{id}", web::delete().to(controllers::merge_queue::delete))
+}
@@ -0,0 +1,23 @@
+use actix_web::web;
+use actix_web::Scope;
+
+use crate::controllers;
+
+pub fn merge_requests() -> Scope {
+    web::scope("/merge_requests")
+        .route("", web::get().to(controllers::merge_requests::index))
+        .route("", web::post().to(controllers::merge_requests::create))
+        .route("/{id}", web::get().to(controllers::merge_requests::show))
+        .route(
+            "/{id}/diff",
+            web::get().to(controllers::merge_requests::diff),
+        )
+        .route(
+            "/{id}/merge",
+            web::post().to(controllers::merge_requests::merge),
+        )
+        .route(
+            "/{id}/close",
+            web::post().to(controllers::merge_requests::close),
+        )
+}
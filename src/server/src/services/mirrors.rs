@@ -0,0 +1,18 @@
+use actix_web::web;
+use actix_web::Scope;
+
+use crate::controllers;
+
+pub fn mirrors() -> Scope {
+    web::scope("/mirrors")
+        .route("", web::get().to(controllers::mirrors::index))
+        .route("", web::post().to(controllers::mirrors::create))
+        .route(
+            "/{mirror_id}",
+            web::delete().to(controllers::mirrors::delete),
+        )
+        .route(
+            "/{mirror_id}/sync",
+            web::post().to(controllers::mirrors::sync),
+        )
+}
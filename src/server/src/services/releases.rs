@@ -0,0 +1,22 @@
+use actix_web::web;
+use actix_web::Scope;
+
+use crate::controllers;
+
+pub fn releases() -> Scope {
+    web::scope("/releases")
+        .route("", web::get().to(controllers::releases::index))
+        .route("", web::post().to(controllers::releases::create))
+        .route(
+            "/{release_name}",
+            web::get().to(controllers::releases::show),
+        )
+        .route(
+            "/{release_name}",
+            web::put().to(controllers::releases::update),
+        )
+        .route(
+            "/{release_name}",
+            web::delete().to(controllers::releases::delete),
+        )
+}
@@ -4,5 +4,10 @@ use actix_web::Scope;
 use crate::controllers;
 
 pub fn stats() -> Scope {
-    web::scope("/stats").route("", web::get().to(controllers::repositories::stats))
+    web::scope("/stats")
+        .route("", web::get().to(controllers::repositories::stats))
+        .route(
+            "/storage",
+            web::get().to(controllers::repositories::storage_stats),
+        )
 }
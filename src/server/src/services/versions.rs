@@ -4,8 +4,17 @@ use actix_web::Scope;
 use crate::controllers;
 
 pub fn versions() -> Scope {
-    web::scope("/versions").route(
-        "",
-        web::get().to(controllers::entries::download_data_from_version_paths),
-    )
+    web::scope("/versions")
+        .route(
+            "",
+            web::get().to(controllers::entries::download_data_from_version_paths),
+        )
+        .route(
+            "/batch",
+            web::get().to(controllers::versions::download_batch),
+        )
+        .route(
+            "/batch",
+            web::post().to(controllers::versions::upload_batch),
+        )
 }
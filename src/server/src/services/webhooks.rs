@@ -0,0 +1,15 @@
+use actix_web::web;
+use actix_web::Scope;
+
+use crate::controllers;
+
+pub fn webhooks() -> Scope {
+    web::scope("/webhooks")
+        .route("", web::get().to(controllers::webhooks::index))
+        .route("", web::post().to(controllers::webhooks::create))
+        .route("/{webhook_id}", web::get().to(controllers::webhooks::show))
+        .route(
+            "/{webhook_id}",
+            web::delete().to(controllers::webhooks::delete),
+        )
+}
@@ -1,6 +1,7 @@
 use actix_web::web;
 use actix_web::Scope;
 
+use crate::config::{self, FeatureGuard};
 use crate::controllers;
 
 pub mod data_frames;
@@ -20,6 +21,11 @@ pub fn workspace() -> Scope {
                     "/changes/{path:.*}",
                     web::delete().to(controllers::workspaces::files::delete),
                 )
+                .service(
+                    web::resource("/diff/{path:.*}")
+                        .guard(FeatureGuard::new(config::WORKSPACE_DIFF_FEATURE))
+                        .route(web::get().to(controllers::workspaces::diff)),
+                )
                 .route(
                     "/files/{path:.*}",
                     web::get().to(controllers::workspaces::files::get),
@@ -32,6 +38,18 @@ pub fn workspace() -> Scope {
                     "/files/{path:.*}",
                     web::delete().to(controllers::workspaces::files::delete),
                 )
+                .route(
+                    "/mv/{path:.*}",
+                    web::post().to(controllers::workspaces::files::mv),
+                )
+                .route(
+                    "/uploads/{upload_id}/chunks/{chunk_num}",
+                    web::put().to(controllers::workspaces::files::upload_chunk),
+                )
+                .route(
+                    "/uploads/{upload_id}/complete/{path:.*}",
+                    web::post().to(controllers::workspaces::files::complete_upload),
+                )
                 .route(
                     "/commit/{branch:.*}",
                     web::post().to(controllers::workspaces::commit),
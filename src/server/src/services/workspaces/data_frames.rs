@@ -4,6 +4,7 @@ use actix_web::Scope;
 use crate::controllers;
 
 pub mod columns;
+pub mod reviews;
 pub mod rows;
 
 pub fn data_frames() -> Scope {
@@ -28,6 +29,7 @@ pub fn data_frames() -> Scope {
             "/resource/{path:.*}",
             web::delete().to(controllers::workspaces::data_frames::delete),
         )
+        .service(reviews::reviews())
         .service(rows::rows())
         .service(columns::columns())
 }
@@ -0,0 +1,20 @@
+use actix_web::web;
+use actix_web::Scope;
+
+use crate::controllers;
+
+pub fn reviews() -> Scope {
+    web::scope("/reviews")
+        .route(
+            "/resource/{path:.*}",
+            web::get().to(controllers::workspaces::data_frames::reviews::list),
+        )
+        .route(
+            "/resource/{path:.*}",
+            web::put().to(controllers::workspaces::data_frames::reviews::update),
+        )
+        .route(
+            "/{row_id}/resource/{path:.*}",
+            web::delete().to(controllers::workspaces::data_frames::reviews::delete),
+        )
+}
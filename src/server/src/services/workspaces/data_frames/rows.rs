@@ -5,6 +5,15 @@ use crate::controllers;
 
 pub fn rows() -> Scope {
     web::scope("/rows")
+        // Before the /{row_id} routes so "batch" is not taken as a row id
+        .route(
+            "/batch/resource/{path:.*}",
+            web::put().to(controllers::workspaces::data_frames::rows::batch_update_rows),
+        )
+        .route(
+            "/batch/resource/{path:.*}",
+            web::delete().to(controllers::workspaces::data_frames::rows::batch_delete_rows),
+        )
         .route(
             "/{row_id}/restore/{path:.*}",
             web::post().to(controllers::workspaces::data_frames::rows::restore),
@@ -1,18 +1,29 @@
+pub mod merge_queue;
+pub mod mirror;
+pub mod post_process_commit;
 pub mod post_push_complete;
 
+use serde::{Deserialize, Serialize};
+
 pub trait Runnable {
     fn run(&self);
 }
 
-#[derive(Debug)]
+#[derive(Serialize, Deserialize, Debug)]
 pub enum Task {
     PostPushComplete(post_push_complete::PostPushComplete),
+    MergeQueue(merge_queue::MergeQueueTask),
+    PostProcessCommit(post_process_commit::PostProcessCommitTask),
+    Mirror(mirror::MirrorTask),
 }
 
 impl Runnable for Task {
     fn run(&self) {
         match self {
             Task::PostPushComplete(task) => task.run(),
+            Task::MergeQueue(task) => task.run(),
+            Task::PostProcessCommit(task) => task.run(),
+            Task::Mirror(task) => task.run(),
         }
     }
 }
@@ -0,0 +1,77 @@
+use liboxen::error::OxenError;
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+use serde::{Deserialize, Serialize};
+use std::thread;
+use std::time::Duration;
+
+use super::Runnable;
+
+// How many times to wait for a locked base branch (ex: during a push) before giving up
+const MAX_LOCKED_RETRIES: usize = 60;
+
+/// Merges everything queued for a base branch, one entry at a time
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MergeQueueTask {
+    pub repo: LocalRepository,
+    pub base: String,
+}
+
+impl Runnable for MergeQueueTask {
+    fn run(&self) {
+        log::debug!(
+            "Processing merge queue for branch {} on repo {:?}",
+            self.base,
+            &self.repo.path
+        );
+
+        let mut retries = 0;
+        loop {
+            match repositories::merge_queue::process_next(&self.repo, &self.base) {
+                Ok(Some(entry)) => {
+                    log::info!(
+                        "Merge queue entry {} ({} -> {}) is {}",
+                        entry.id,
+                        entry.head,
+                        entry.base,
+                        entry.status
+                    );
+                    self.notify(&entry);
+                }
+                Ok(None) => break,
+                Err(OxenError::RemoteBranchLocked(_)) if retries < MAX_LOCKED_RETRIES => {
+                    // Another task is merging into this branch or someone is pushing to it
+                    retries += 1;
+                    thread::sleep(Duration::from_secs(1));
+                }
+                Err(err) => {
+                    log::error!(
+                        "Merge queue for branch {} on repo {:?} stopped: {:?}",
+                        self.base,
+                        &self.repo.path,
+                        err
+                    );
+                    break;
+                }
+            }
+        }
+    }
+}
+
+impl MergeQueueTask {
+    fn notify(&self, entry: &liboxen::model::MergeQueueEntry) {
+        if entry.notify_url.is_none() {
+            return;
+        }
+
+        let result =
+            tokio::runtime::Handle::current().block_on(repositories::merge_queue::notify(entry));
+        if let Err(err) = result {
+            log::error!(
+                "Could not notify for merge queue entry {}: {}",
+                entry.id,
+                err
+            );
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use liboxen::model::LocalRepository;
+use liboxen::repositories;
+use serde::{Deserialize, Serialize};
+
+use super::Runnable;
+
+/// Pushes the repository to each of the mirrors
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MirrorTask {
+    pub repo: LocalRepository,
+    pub mirror_ids: Vec<String>,
+}
+
+impl Runnable for MirrorTask {
+    fn run(&self) {
+        for id in &self.mirror_ids {
+            log::debug!("Syncing mirror {} of repo {:?}", id, &self.repo.path);
+            let result = tokio::runtime::Handle::current()
+                .block_on(repositories::mirrors::sync(&self.repo, id));
+            match result {
+                Ok(mirror) => log::info!("Synced repo {:?} to {}", &self.repo.path, mirror.url),
+                // The error is recorded on the mirror as well
+                Err(err) => log::error!(
+                    "Could not sync mirror {} of repo {:?}: {}",
+                    id,
+                    &self.repo.path,
+                    err
+                ),
+            }
+        }
+    }
+}
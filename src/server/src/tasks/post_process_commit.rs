@@ -0,0 +1,37 @@
+use liboxen::model::{Commit, LocalRepository};
+use liboxen::repositories;
+use serde::{Deserialize, Serialize};
+
+use super::Runnable;
+
+/// Validates and summarizes a pushed v0.19.0 commit after the push has returned
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PostProcessCommitTask {
+    pub commit: Commit,
+    pub repo: LocalRepository,
+}
+
+impl Runnable for PostProcessCommitTask {
+    fn run(&self) {
+        log::debug!(
+            "Post processing commit {} on repo {:?}",
+            self.commit.id,
+            &self.repo.path
+        );
+        match repositories::post_process::run(&self.repo, &self.commit) {
+            Ok(status) => log::debug!(
+                "Post processed commit {} on repo {:?}: {:?} {}",
+                self.commit.id,
+                &self.repo.path,
+                status.status,
+                status.status_message
+            ),
+            Err(err) => log::error!(
+                "Could not post process commit {} on repo {:?}: {:?}",
+                self.commit.id,
+                &self.repo.path,
+                err
+            ),
+        }
+    }
+}
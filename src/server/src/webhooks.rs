@@ -0,0 +1,42 @@
+//! Notify a repository's webhooks about changes made through the server.
+//!
+//! Deliveries run in the background so a slow or broken webhook never holds up the
+//! request that triggered it. Failures are logged.
+
+use liboxen::model::{Commit, LocalRepository, WebhookEvent};
+use liboxen::repositories;
+
+pub fn notify(
+    repo: &LocalRepository,
+    namespace: impl AsRef<str>,
+    repo_name: impl AsRef<str>,
+    event: WebhookEvent,
+    branch: Option<&str>,
+    commit: Option<&Commit>,
+) {
+    let webhooks = match repositories::webhooks::list_for_event(repo, event) {
+        Ok(webhooks) => webhooks,
+        Err(err) => {
+            log::error!("Could not read webhooks for {:?}: {}", repo.path, err);
+            return;
+        }
+    };
+    if webhooks.is_empty() {
+        return;
+    }
+
+    let payload = repositories::webhooks::payload(namespace, repo_name, event, branch, commit);
+    tokio::spawn(async move {
+        for webhook in webhooks {
+            if let Err(err) = repositories::webhooks::deliver(&webhook, &payload).await {
+                log::error!(
+                    "Webhook {} failed for {} delivery {}: {}",
+                    webhook.id,
+                    payload.event,
+                    payload.id,
+                    err
+                );
+            }
+        }
+    });
+}